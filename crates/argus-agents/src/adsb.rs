@@ -1,13 +1,16 @@
 use async_trait::async_trait;
 use chrono::Utc;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument};
-use argus_core::entity::EntityType;
+use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument, SchemaDrift};
+use argus_core::entity::{identifier_scheme, EntityType};
 use argus_core::error::{ArgusError, Result};
 
+use crate::drift::{DriftBaseline, DriftTracker};
+use crate::http::TracedSend;
+
 const OPENSKY_API_URL: &str = "https://opensky-network.org/api/states/all";
 
 /// Internal mutable state for the ADS-B agent.
@@ -16,10 +19,14 @@ struct AdsbState {
     last_run: Option<chrono::DateTime<Utc>>,
     documents_collected: u64,
     last_error: Option<String>,
+    /// OpenSky's time for the latest state vectors.
+    source_data_timestamp: Option<chrono::DateTime<Utc>>,
+    drift_baseline: DriftBaseline,
+    schema_drift: Option<SchemaDrift>,
 }
 
 /// Raw response from the OpenSky Network REST API.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct OpenSkyResponse {
     time: i64,
     states: Option<Vec<Vec<serde_json::Value>>>,
@@ -34,6 +41,12 @@ pub struct AdsbAgent {
     state: RwLock<AdsbState>,
 }
 
+impl Default for AdsbAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AdsbAgent {
     pub fn new() -> Self {
         Self {
@@ -46,6 +59,9 @@ impl AdsbAgent {
                 last_run: None,
                 documents_collected: 0,
                 last_error: None,
+                source_data_timestamp: None,
+                drift_baseline: DriftBaseline::new(),
+                schema_drift: None,
             }),
         }
     }
@@ -88,6 +104,11 @@ impl AdsbAgent {
             .unwrap_or("unknown")
             .to_string();
 
+        // Unix seconds of the last position report, null when stale
+        let time_position = sv
+            .get(3)
+            .and_then(|v| v.as_i64())
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0));
         let longitude = sv.get(5).and_then(|v| v.as_f64());
         let latitude = sv.get(6).and_then(|v| v.as_f64());
         let baro_altitude = sv.get(7).and_then(|v| v.as_f64());
@@ -96,10 +117,7 @@ impl AdsbAgent {
         let true_track = sv.get(10).and_then(|v| v.as_f64());
         let vertical_rate = sv.get(11).and_then(|v| v.as_f64());
         let geo_altitude = sv.get(13).and_then(|v| v.as_f64());
-        let squawk = sv
-            .get(14)
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+        let squawk = sv.get(14).and_then(|v| v.as_str()).map(|s| s.to_string());
 
         // Build a human-readable content summary
         let alt_str = baro_altitude
@@ -141,15 +159,21 @@ impl AdsbAgent {
 
         Some(RawDocument {
             source: "adsb".into(),
-            source_id: icao24,
+            source_id: icao24.clone(),
             title: Some(title),
             content,
             url: Some(format!(
                 "https://opensky-network.org/network/explorer?icao24={}",
-                sv.first().and_then(|v| v.as_str()).unwrap_or_default().trim()
+                sv.first()
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .trim()
             )),
             collected_at: Utc::now(),
+            observed_at: time_position,
+            identifiers: [(identifier_scheme::ICAO24.to_string(), icao24)].into(),
             metadata,
+            structured: None,
         })
     }
 }
@@ -170,7 +194,7 @@ impl Agent for AdsbAgent {
         let response = self
             .client
             .get(OPENSKY_API_URL)
-            .send()
+            .send_traced()
             .await
             .map_err(|e| ArgusError::Agent {
                 agent: "adsb".into(),
@@ -191,8 +215,13 @@ impl Agent for AdsbAgent {
             });
         }
 
-        let opensky: OpenSkyResponse =
-            response.json().await.map_err(|e| ArgusError::Agent {
+        let mut drift = DriftTracker::default();
+        let opensky: OpenSkyResponse = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|body| drift.decode(body).map_err(|e| e.to_string()))
+            .map_err(|e| ArgusError::Agent {
                 agent: "adsb".into(),
                 message: format!("failed to parse OpenSky response: {}", e),
             })?;
@@ -216,6 +245,13 @@ impl Agent for AdsbAgent {
         state.last_run = Some(Utc::now());
         state.documents_collected += count;
         state.last_error = None;
+        if let Some(time) = chrono::DateTime::from_timestamp(opensky.time, 0) {
+            state.source_data_timestamp = Some(time);
+        }
+        state.schema_drift = drift.finish(&mut state.drift_baseline);
+        if let Some(drift) = &state.schema_drift {
+            warn!(unknown = ?drift.unknown_fields, missing = ?drift.missing_fields, "ADS-B agent: OpenSky response schema drifted");
+        }
 
         Ok(documents)
     }
@@ -228,9 +264,16 @@ impl Agent for AdsbAgent {
             last_run: state.last_run,
             documents_collected: state.documents_collected,
             error: state.last_error.clone(),
+            source_data_timestamp: state.source_data_timestamp,
+            schema_drift: state.schema_drift.clone(),
+            capabilities: None,
         }
     }
 
+    fn as_lookup(&self) -> Option<&dyn AgentLookup> {
+        Some(self)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -252,22 +295,23 @@ impl AgentLookup for AdsbAgent {
 
         debug!(name = %name, "ADS-B aircraft lookup");
 
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            ArgusError::Agent {
-                agent: "adsb".into(),
-                message: format!("Lookup HTTP request failed: {}", e),
-            }
-        })?;
+        let response =
+            self.client
+                .get(&url)
+                .send_traced()
+                .await
+                .map_err(|e| ArgusError::Agent {
+                    agent: "adsb".into(),
+                    message: format!("Lookup HTTP request failed: {}", e),
+                })?;
 
         if !response.status().is_success() {
             return Ok(Vec::new());
         }
 
-        let opensky: OpenSkyResponse = response.json().await.map_err(|e| {
-            ArgusError::Agent {
-                agent: "adsb".into(),
-                message: format!("Lookup parse failed: {}", e),
-            }
+        let opensky: OpenSkyResponse = response.json().await.map_err(|e| ArgusError::Agent {
+            agent: "adsb".into(),
+            message: format!("Lookup parse failed: {}", e),
         })?;
 
         let states = opensky.states.unwrap_or_default();
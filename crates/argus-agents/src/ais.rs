@@ -1,18 +1,21 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
-use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument};
-use argus_core::entity::EntityType;
+use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument, SchemaDrift};
+use argus_core::entity::{identifier_scheme, parse_source_date, EntityType};
 use argus_core::error::{ArgusError, Result};
 
+use crate::drift::{DriftBaseline, DriftTracker};
+use crate::http::TracedSend;
+
 const AISHUB_API_URL: &str = "https://data.aishub.net/ws.php";
 
 /// AIS vessel position record from the AISHub API response.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct AisVesselRecord {
     #[serde(rename = "MMSI")]
     mmsi: i64,
@@ -42,24 +45,16 @@ struct AisVesselRecord {
     timestamp: Option<String>,
 }
 
-/// AISHub API response envelope.
-///
-/// The API returns a JSON array where the first element is a metadata array
-/// (containing error codes / record counts) and the second element is the
-/// actual data array of vessel records.
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
-enum AisHubResponse {
-    /// Successful response: `[ [{"ERROR": false, ...}], [ {vessel}, ... ] ]`
-    Success(Vec<serde_json::Value>),
-}
-
 #[derive(Debug)]
 struct AisAgentState {
     enabled: bool,
     last_run: Option<chrono::DateTime<Utc>>,
     documents_collected: u64,
     last_error: Option<String>,
+    /// Newest position report time seen.
+    source_data_timestamp: Option<chrono::DateTime<Utc>>,
+    drift_baseline: DriftBaseline,
+    schema_drift: Option<SchemaDrift>,
 }
 
 impl Default for AisAgentState {
@@ -69,6 +64,9 @@ impl Default for AisAgentState {
             last_run: None,
             documents_collected: 0,
             last_error: None,
+            source_data_timestamp: None,
+            drift_baseline: DriftBaseline::new(),
+            schema_drift: None,
         }
     }
 }
@@ -83,6 +81,12 @@ pub struct AisAgent {
     api_key: Option<String>,
 }
 
+impl Default for AisAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AisAgent {
     pub fn new() -> Self {
         let api_key = std::env::var("AISHUB_API_KEY").ok();
@@ -112,7 +116,7 @@ impl AisAgent {
     }
 
     /// Parse the raw API JSON into a vec of vessel records.
-    fn parse_response(&self, body: &str) -> Result<Vec<AisVesselRecord>> {
+    fn parse_response(&self, body: &str, drift: &mut DriftTracker) -> Result<Vec<AisVesselRecord>> {
         let envelope: Vec<serde_json::Value> =
             serde_json::from_str(body).map_err(|e| ArgusError::Agent {
                 agent: self.name().into(),
@@ -144,10 +148,12 @@ impl AisAgent {
         })?;
 
         let vessels: Vec<AisVesselRecord> =
-            serde_json::from_value(data_value.clone()).map_err(|e| ArgusError::Agent {
-                agent: self.name().into(),
-                message: format!("failed to parse AISHub vessel data: {e}"),
-            })?;
+            drift
+                .decode(data_value.clone())
+                .map_err(|e| ArgusError::Agent {
+                    agent: self.name().into(),
+                    message: format!("failed to parse AISHub vessel data: {e}"),
+                })?;
 
         Ok(vessels)
     }
@@ -171,11 +177,7 @@ impl AisAgent {
             vessel.longitude.unwrap_or(0.0),
             vessel.speed_over_ground.unwrap_or(0.0),
             vessel.course_over_ground.unwrap_or(0.0),
-            vessel
-                .destination
-                .as_deref()
-                .unwrap_or("N/A")
-                .trim(),
+            vessel.destination.as_deref().unwrap_or("N/A").trim(),
         );
 
         let metadata = serde_json::json!({
@@ -194,6 +196,12 @@ impl AisAgent {
             "timestamp": vessel.timestamp,
         });
 
+        let mut identifiers =
+            std::collections::BTreeMap::from([(identifier_scheme::MMSI.to_string(), mmsi.clone())]);
+        if let Some(imo) = vessel.imo.filter(|imo| *imo > 0) {
+            identifiers.insert(identifier_scheme::IMO.to_string(), imo.to_string());
+        }
+
         RawDocument {
             source: "ais".into(),
             source_id: mmsi,
@@ -201,7 +209,10 @@ impl AisAgent {
             content,
             url: None,
             collected_at: Utc::now(),
+            observed_at: vessel.timestamp.as_deref().and_then(parse_source_date),
+            identifiers,
             metadata,
+            structured: None,
         }
     }
 }
@@ -235,18 +246,13 @@ impl Agent for AisAgent {
         let url = self.build_url(&api_key);
         info!("AIS agent collecting vessel positions from AISHub");
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| {
-                error!(error = %e, "AIS HTTP request failed");
-                ArgusError::Agent {
-                    agent: self.name().into(),
-                    message: format!("HTTP request failed: {e}"),
-                }
-            })?;
+        let response = self.client.get(&url).send_traced().await.map_err(|e| {
+            error!(error = %e, "AIS HTTP request failed");
+            ArgusError::Agent {
+                agent: self.name().into(),
+                message: format!("HTTP request failed: {e}"),
+            }
+        })?;
 
         let status = response.status();
         if !status.is_success() {
@@ -269,18 +275,14 @@ impl Agent for AisAgent {
             }
         })?;
 
-        debug!(
-            body_length = body.len(),
-            "received AISHub response"
-        );
+        debug!(body_length = body.len(), "received AISHub response");
 
-        let vessels = self.parse_response(&body)?;
+        let mut drift = DriftTracker::default();
+        let vessels = self.parse_response(&body, &mut drift)?;
         info!(count = vessels.len(), "parsed AIS vessel records");
 
-        let documents: Vec<RawDocument> = vessels
-            .iter()
-            .map(|v| self.vessel_to_document(v))
-            .collect();
+        let documents: Vec<RawDocument> =
+            vessels.iter().map(|v| self.vessel_to_document(v)).collect();
 
         let doc_count = documents.len() as u64;
 
@@ -288,7 +290,13 @@ impl Agent for AisAgent {
         let mut state = self.state.write().await;
         state.last_run = Some(Utc::now());
         state.documents_collected += doc_count;
+        let newest = documents.iter().filter_map(|d| d.observed_at).max();
+        state.source_data_timestamp = newest.or(state.source_data_timestamp);
         state.last_error = None;
+        state.schema_drift = drift.finish(&mut state.drift_baseline);
+        if let Some(drift) = &state.schema_drift {
+            warn!(unknown = ?drift.unknown_fields, missing = ?drift.missing_fields, "AISHub response schema drifted");
+        }
 
         info!(
             documents = doc_count,
@@ -307,9 +315,20 @@ impl Agent for AisAgent {
             last_run: state.last_run,
             documents_collected: state.documents_collected,
             error: state.last_error.clone(),
+            source_data_timestamp: state.source_data_timestamp,
+            schema_drift: state.schema_drift.clone(),
+            capabilities: None,
         }
     }
 
+    fn required_env(&self) -> &[&'static str] {
+        &["AISHUB_API_KEY"]
+    }
+
+    fn as_lookup(&self) -> Option<&dyn AgentLookup> {
+        Some(self)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -398,7 +417,9 @@ mod tests {
             ]
         ]"#;
 
-        let vessels = agent.parse_response(body).unwrap();
+        let vessels = agent
+            .parse_response(body, &mut DriftTracker::default())
+            .unwrap();
         assert_eq!(vessels.len(), 2);
         assert_eq!(vessels[0].mmsi, 211234567);
         assert_eq!(vessels[0].name.as_deref(), Some("TESTSHIP ONE"));
@@ -413,7 +434,7 @@ mod tests {
             []
         ]"#;
 
-        let result = agent.parse_response(body);
+        let result = agent.parse_response(body, &mut DriftTracker::default());
         assert!(result.is_err());
         let err = result.unwrap_err();
         match err {
@@ -0,0 +1,167 @@
+//! Schema drift detection for JSON APIs.
+//!
+//! serde defaults make a response decode fine after upstream renames or
+//! drops a field; the agent just sees `None`. [`DriftTracker`] decodes
+//! through `serde_ignored` to catch fields the agent ignores, and compares
+//! the raw JSON with the decoded value re-serialized to catch fields that
+//! only decoded because they have a default. A field counts as missing once
+//! an earlier run received it and no response in this run did, so fields a
+//! source simply never sends are not reported.
+
+use std::collections::BTreeSet;
+
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use argus_core::agent::SchemaDrift;
+
+/// Reported fields per kind; a rewritten API should not flood the status.
+const MAX_REPORTED_FIELDS: usize = 50;
+
+/// Schema differences across the responses decoded in one run.
+#[derive(Debug, Default)]
+pub(crate) struct DriftTracker {
+    unknown: BTreeSet<String>,
+    /// Paths present in the raw responses.
+    seen: BTreeSet<String>,
+    /// Paths the decoded types would write back.
+    expected: BTreeSet<String>,
+}
+
+/// Paths received in any earlier run, kept in the agent's state.
+pub(crate) type DriftBaseline = BTreeSet<String>;
+
+impl DriftTracker {
+    /// Decode `value` as `T`, noting fields `T` ignores or defaulted.
+    pub(crate) fn decode<T: DeserializeOwned + Serialize>(
+        &mut self,
+        value: Value,
+    ) -> serde_json::Result<T> {
+        let mut unknown = Vec::new();
+        let decoded: T =
+            serde_ignored::deserialize(&value, |path| unknown.push(normalize(&path.to_string())))?;
+        self.unknown.extend(unknown);
+        collect_paths(&value, "", false, &mut self.seen);
+        if let Ok(back) = serde_json::to_value(&decoded) {
+            collect_paths(&back, "", true, &mut self.expected);
+        }
+        Ok(decoded)
+    }
+
+    /// Finish the run: what drifted, if anything, against `baseline`, which
+    /// then takes in this run's paths.
+    pub(crate) fn finish(self, baseline: &mut DriftBaseline) -> Option<SchemaDrift> {
+        // A run that decoded nothing says nothing about the schema
+        if self.seen.is_empty() {
+            return None;
+        }
+        let missing: Vec<String> = baseline
+            .iter()
+            .filter(|path| self.expected.contains(*path) && !self.seen.contains(*path))
+            .take(MAX_REPORTED_FIELDS)
+            .cloned()
+            .collect();
+        baseline.extend(self.seen);
+        let unknown: Vec<String> = self.unknown.into_iter().take(MAX_REPORTED_FIELDS).collect();
+        (!unknown.is_empty() || !missing.is_empty()).then(|| SchemaDrift {
+            unknown_fields: unknown,
+            missing_fields: missing,
+            detected_at: Utc::now(),
+        })
+    }
+}
+
+/// `results.companies.3.company.name` → `results.companies.*.company.name`.
+fn normalize(path: &str) -> String {
+    path.split('.')
+        .map(|segment| {
+            if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+                "*"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Every object key under `value`, as normalized dotted paths. Keys whose
+/// value is null count as absent unless `keep_null`: a raw null says the
+/// source sent nothing, a re-serialized one that the type has the field.
+fn collect_paths(value: &Value, prefix: &str, keep_null: bool, out: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if child.is_null() && !keep_null {
+                    continue;
+                }
+                let path = normalize(&if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                });
+                out.insert(path.clone());
+                collect_paths(child, &path, keep_null, out);
+            }
+        }
+        Value::Array(items) => {
+            let path = if prefix.is_empty() {
+                "*".to_string()
+            } else {
+                format!("{prefix}.*")
+            };
+            for item in items {
+                collect_paths(item, &path, keep_null, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Response {
+        items: Vec<Item>,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Item {
+        name: Option<String>,
+        #[serde(default)]
+        country: Option<String>,
+    }
+
+    fn run(baseline: &mut DriftBaseline, body: Value) -> Option<SchemaDrift> {
+        let mut tracker = DriftTracker::default();
+        tracker.decode::<Response>(body).unwrap();
+        tracker.finish(baseline)
+    }
+
+    #[test]
+    fn reports_ignored_and_vanished_fields() {
+        let mut baseline = DriftBaseline::new();
+        let first = serde_json::json!({ "items": [{ "name": "Acme", "country": "GB" }, { "name": "Borealis" }] });
+        assert_eq!(run(&mut baseline, first), None);
+
+        // Upstream renamed `country` and added a field
+        let renamed = serde_json::json!({ "items": [{ "name": "Acme", "country_code": "GB" }, { "name": "Borealis", "lei": null }] });
+        let drift = run(&mut baseline, renamed).unwrap();
+        assert_eq!(
+            drift.unknown_fields,
+            ["items.*.country_code", "items.*.lei"]
+        );
+        assert_eq!(drift.missing_fields, ["items.*.country"]);
+    }
+
+    #[test]
+    fn empty_runs_report_nothing() {
+        let mut baseline = DriftBaseline::from(["items.*.country".to_string()]);
+        assert_eq!(run(&mut baseline, serde_json::json!({ "items": [] })), None);
+    }
+}
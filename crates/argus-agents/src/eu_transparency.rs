@@ -1,13 +1,16 @@
 use async_trait::async_trait;
 use chrono::Utc;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument};
-use argus_core::entity::EntityType;
+use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument, SchemaDrift};
+use argus_core::entity::{identifier_scheme, parse_source_date, EntityType};
 use argus_core::error::{ArgusError, Result};
 
+use crate::drift::{DriftBaseline, DriftTracker};
+use crate::http::TracedSend;
+
 const EU_TRANSPARENCY_API_URL: &str =
     "https://ec.europa.eu/transparencyregister/public/consultation/statistics.do?action=getLobbyistsJson";
 
@@ -17,10 +20,12 @@ struct EuTransparencyState {
     last_run: Option<chrono::DateTime<Utc>>,
     documents_collected: u64,
     last_error: Option<String>,
+    drift_baseline: DriftBaseline,
+    schema_drift: Option<SchemaDrift>,
 }
 
 /// A single lobbyist organization entry from the EU Transparency Register API.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct LobbyistEntry {
     /// Unique registration identifier in the transparency register.
@@ -65,7 +70,7 @@ struct LobbyistEntry {
 }
 
 /// Wrapper for the API response which may be a direct array or nested under a key.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 enum ApiResponse {
     Array(Vec<LobbyistEntry>),
@@ -92,6 +97,12 @@ pub struct EuTransparencyAgent {
     state: RwLock<EuTransparencyState>,
 }
 
+impl Default for EuTransparencyAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EuTransparencyAgent {
     pub fn new() -> Self {
         Self {
@@ -104,6 +115,8 @@ impl EuTransparencyAgent {
                 last_run: None,
                 documents_collected: 0,
                 last_error: None,
+                drift_baseline: DriftBaseline::new(),
+                schema_drift: None,
             }),
         }
     }
@@ -204,12 +217,19 @@ impl EuTransparencyAgent {
 
         Some(RawDocument {
             source: "eu_transparency".into(),
-            source_id: registration_id,
+            source_id: registration_id.clone(),
             title: Some(name),
             content,
             url: Some(url),
             collected_at: Utc::now(),
+            observed_at: parse_source_date(&registration_date),
+            identifiers: [(
+                identifier_scheme::EU_TRANSPARENCY.to_string(),
+                registration_id,
+            )]
+            .into(),
             metadata,
+            structured: None,
         })
     }
 }
@@ -231,7 +251,7 @@ impl Agent for EuTransparencyAgent {
             .client
             .get(EU_TRANSPARENCY_API_URL)
             .header("Accept", "application/json")
-            .send()
+            .send_traced()
             .await
             .map_err(|e| ArgusError::Agent {
                 agent: "eu_transparency".into(),
@@ -265,8 +285,10 @@ impl Agent for EuTransparencyAgent {
             body.len()
         );
 
-        let api_response: ApiResponse =
-            serde_json::from_str(&body).map_err(|e| ArgusError::Agent {
+        let mut drift = DriftTracker::default();
+        let api_response: ApiResponse = serde_json::from_str(&body)
+            .and_then(|body| drift.decode(body))
+            .map_err(|e| ArgusError::Agent {
                 agent: "eu_transparency".into(),
                 message: format!("failed to parse EU Transparency Register response: {}", e),
             })?;
@@ -278,10 +300,7 @@ impl Agent for EuTransparencyAgent {
             entries.len()
         );
 
-        let documents: Vec<RawDocument> = entries
-            .iter()
-            .filter_map(Self::parse_entry)
-            .collect();
+        let documents: Vec<RawDocument> = entries.iter().filter_map(Self::parse_entry).collect();
 
         let count = documents.len() as u64;
         info!(
@@ -294,6 +313,10 @@ impl Agent for EuTransparencyAgent {
         state.last_run = Some(Utc::now());
         state.documents_collected += count;
         state.last_error = None;
+        state.schema_drift = drift.finish(&mut state.drift_baseline);
+        if let Some(drift) = &state.schema_drift {
+            warn!(unknown = ?drift.unknown_fields, missing = ?drift.missing_fields, "EU Transparency agent: response schema drifted");
+        }
 
         Ok(documents)
     }
@@ -306,9 +329,16 @@ impl Agent for EuTransparencyAgent {
             last_run: state.last_run,
             documents_collected: state.documents_collected,
             error: state.last_error.clone(),
+            source_data_timestamp: None,
+            schema_drift: state.schema_drift.clone(),
+            capabilities: None,
         }
     }
 
+    fn as_lookup(&self) -> Option<&dyn AgentLookup> {
+        Some(self)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
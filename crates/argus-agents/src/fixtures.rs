@@ -0,0 +1,346 @@
+//! Golden-file fixtures of upstream responses.
+//!
+//! With `AGENT_FIXTURES=record` every response an agent receives is also
+//! written to a file under `AGENT_FIXTURE_DIR` (default `fixtures/agents`);
+//! with `AGENT_FIXTURES=replay` agents read those files instead of the
+//! network, so a run is repeatable offline. Files are named after the
+//! request, e.g. `data.gdeltproject.org/gdeltv2/lastupdate.txt.get.json`;
+//! requests with a query or body get a hash of them in the name. API keys
+//! in the query are left out of both.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+/// Default directory fixtures are recorded to and replayed from.
+pub const DEFAULT_FIXTURE_DIR: &str = "fixtures/agents";
+
+/// Query parameters that carry credentials.
+const SECRET_PARAMS: &[&str] = &["api_key", "api_token", "apikey", "key", "token"];
+
+/// Response headers not worth keeping.
+const SKIPPED_HEADERS: &[&str] = &[
+    "date",
+    "set-cookie",
+    "content-length",
+    "transfer-encoding",
+    "connection",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FixtureMode {
+    /// Talk to the network.
+    #[default]
+    Off,
+    /// Talk to the network and write every response to a fixture.
+    Record,
+    /// Answer from fixtures only.
+    Replay,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    pub mode: FixtureMode,
+    pub dir: PathBuf,
+}
+
+/// One recorded response.
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    url: String,
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    /// The body, when it is text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// The body, base64-encoded, when it is not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_base64: Option<String>,
+}
+
+static ACTIVE: RwLock<Option<Arc<Fixtures>>> = RwLock::new(None);
+
+impl Fixtures {
+    /// From `AGENT_FIXTURES` (`record` or `replay`; off otherwise) and
+    /// `AGENT_FIXTURE_DIR`.
+    pub fn from_env() -> Self {
+        let mode = match std::env::var("AGENT_FIXTURES")
+            .ok()
+            .as_deref()
+            .map(str::trim)
+        {
+            Some(mode) if mode.eq_ignore_ascii_case("record") => FixtureMode::Record,
+            Some(mode) if mode.eq_ignore_ascii_case("replay") => FixtureMode::Replay,
+            _ => FixtureMode::Off,
+        };
+        let dir = std::env::var("AGENT_FIXTURE_DIR")
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_FIXTURE_DIR.to_string());
+        Self {
+            mode,
+            dir: dir.into(),
+        }
+    }
+
+    /// Where the response to `request` is recorded.
+    fn path(&self, request: &Request) -> PathBuf {
+        let url = request.url();
+        let mut path = self
+            .dir
+            .join(sanitize(url.host_str().unwrap_or("localhost")));
+        let mut segments: Vec<String> = url
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).map(sanitize).collect())
+            .unwrap_or_default();
+        let file = segments.pop().unwrap_or_else(|| "index".to_string());
+        path.extend(segments);
+
+        let query = public_query(url);
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .unwrap_or_default();
+        let method = request.method().as_str().to_lowercase();
+        let name = if query.is_empty() && body.is_empty() {
+            format!("{file}.{method}.json")
+        } else {
+            let mut hasher = Sha256::new();
+            hasher.update(query.as_bytes());
+            hasher.update(b"\n");
+            hasher.update(body);
+            let hash = hasher.finalize();
+            let hash: String = hash[..6].iter().map(|b| format!("{b:02x}")).collect();
+            format!("{file}.{method}.{hash}.json")
+        };
+        path.join(name)
+    }
+
+    /// The recorded response to `request`, or a 404 saying which fixture
+    /// is missing.
+    pub(crate) fn replay(&self, request: &Request) -> Response {
+        let path = self.path(request);
+        match read_fixture(&path) {
+            Ok(response) => response,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "No fixture to replay");
+                let message = format!(
+                    "no fixture at {} for {} {}",
+                    path.display(),
+                    request.method(),
+                    redacted(request.url())
+                );
+                Response::from(
+                    http::Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .body(message)
+                        .expect("valid response"),
+                )
+            }
+        }
+    }
+
+    /// Write `response` to the fixture for `request` and hand back an
+    /// equivalent response. A fixture that cannot be written is only
+    /// logged.
+    pub(crate) async fn record(
+        &self,
+        request: &Request,
+        response: Response,
+    ) -> reqwest::Result<Response> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await?;
+
+        let path = self.path(request);
+        let fixture = Fixture {
+            method: request.method().to_string(),
+            url: redacted(request.url()),
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body: std::str::from_utf8(&bytes).ok().map(str::to_string),
+            body_base64: std::str::from_utf8(&bytes)
+                .is_err()
+                .then(|| base64::engine::general_purpose::STANDARD.encode(&bytes)),
+        };
+        if let Err(e) = write_fixture(&path, &fixture) {
+            warn!(path = %path.display(), error = %e, "Failed to record fixture");
+        }
+
+        let mut rebuilt = http::Response::builder().status(status);
+        if let Some(map) = rebuilt.headers_mut() {
+            *map = headers;
+        }
+        Ok(Response::from(rebuilt.body(bytes).expect("valid response")))
+    }
+}
+
+/// The fixture settings agents use: set by [`configure`], else read from
+/// the environment on first use.
+pub fn active() -> Arc<Fixtures> {
+    if let Some(fixtures) = ACTIVE.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Arc::clone(fixtures);
+    }
+    let mut active = ACTIVE.write().unwrap_or_else(|e| e.into_inner());
+    Arc::clone(active.get_or_insert_with(|| {
+        let fixtures = Fixtures::from_env();
+        if fixtures.mode != FixtureMode::Off {
+            info!(mode = ?fixtures.mode, dir = %fixtures.dir.display(), "Agent HTTP fixtures enabled");
+        }
+        Arc::new(fixtures)
+    }))
+}
+
+/// Use `fixtures` for all agent requests from now on.
+pub fn configure(fixtures: Fixtures) {
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(fixtures));
+}
+
+fn read_fixture(path: &Path) -> Result<Response, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let fixture: Fixture = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let body = match (fixture.body, fixture.body_base64) {
+        (_, Some(encoded)) => base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| e.to_string())?,
+        (Some(body), None) => body.into_bytes(),
+        (None, None) => Vec::new(),
+    };
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in &fixture.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::try_from(value.as_str()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    let mut response = http::Response::builder().status(fixture.status);
+    if let Some(map) = response.headers_mut() {
+        *map = headers;
+    }
+    let response = response.body(body).map_err(|e| e.to_string())?;
+    Ok(Response::from(response))
+}
+
+fn write_fixture(path: &Path, fixture: &Fixture) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(fixture).map_err(std::io::Error::other)?;
+    std::fs::write(path, json + "\n")
+}
+
+/// The query without credential parameters, `&`-joined.
+fn public_query(url: &url::Url) -> String {
+    url.query_pairs()
+        .filter(|(name, _)| !SECRET_PARAMS.contains(&name.to_lowercase().as_str()))
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn redacted(url: &url::Url) -> String {
+    let mut url = url.clone();
+    let query = public_query(&url);
+    url.set_query(Some(&query).filter(|q| !q.is_empty()).map(String::as_str));
+    url.to_string()
+}
+
+fn sanitize(segment: &str) -> String {
+    segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures(dir: &Path) -> Fixtures {
+        Fixtures {
+            mode: FixtureMode::Replay,
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn get(url: &str) -> Request {
+        reqwest::Client::new().get(url).build().unwrap()
+    }
+
+    #[test]
+    fn fixture_paths_follow_the_url_without_credentials() {
+        let fixtures = fixtures(Path::new("fx"));
+        assert_eq!(
+            fixtures.path(&get("http://data.gdeltproject.org/gdeltv2/lastupdate.txt")),
+            Path::new("fx/data.gdeltproject.org/gdeltv2/lastupdate.txt.get.json")
+        );
+
+        let with_token = fixtures.path(&get(
+            "https://api.opencorporates.com/v0.4/companies/search?q=acme&api_token=secret",
+        ));
+        let without = fixtures.path(&get(
+            "https://api.opencorporates.com/v0.4/companies/search?q=acme",
+        ));
+        assert_eq!(with_token, without);
+        assert!(with_token.starts_with("fx/api.opencorporates.com/v0.4/companies"));
+        assert_ne!(
+            without,
+            fixtures.path(&get(
+                "https://api.opencorporates.com/v0.4/companies/search?q=other"
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_replay() {
+        let dir = std::env::temp_dir().join(format!("argus-fixtures-{}", uuid::Uuid::new_v4()));
+        let fixtures = fixtures(&dir);
+        let request = get("https://example.org/data.bin?page=2&key=secret");
+
+        let response = http::Response::builder()
+            .status(203)
+            .header("etag", "\"v1\"")
+            .body(vec![0xff_u8, 0x00, 0x7f])
+            .unwrap();
+        let recorded = fixtures
+            .record(&request, Response::from(response))
+            .await
+            .unwrap();
+        assert_eq!(recorded.bytes().await.unwrap().as_ref(), [0xff, 0x00, 0x7f]);
+
+        let written = std::fs::read_to_string(fixtures.path(&request)).unwrap();
+        assert!(!written.contains("secret"));
+
+        let replayed = fixtures.replay(&request);
+        assert_eq!(replayed.status(), StatusCode::NON_AUTHORITATIVE_INFORMATION);
+        assert_eq!(replayed.headers()["etag"], "\"v1\"");
+        assert_eq!(replayed.bytes().await.unwrap().as_ref(), [0xff, 0x00, 0x7f]);
+
+        let missing = fixtures.replay(&get("https://example.org/other"));
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
@@ -8,16 +9,45 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
 use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument};
-use argus_core::entity::EntityType;
+use argus_core::entity::{
+    parse_source_date, Entity, EntityType, ExtractionResult, RelationType, Relationship,
+    EVENT_KEY_PREFIX,
+};
 use argus_core::error::{ArgusError, Result};
+use argus_extraction::countries;
+use argus_extraction::formats::{self, DocumentFormat};
+use uuid::Uuid;
 
-/// URL that returns pointers to the latest GDELT 2.0 export files.
+use crate::http::TracedSend;
+
+/// Where GDELT 2.0 publishes its "lastupdate" manifests.
+const GDELT_BASE_URL: &str = "http://data.gdeltproject.org/gdeltv2";
+
+/// Manifest pointing at the latest GDELT 2.0 export files.
 /// Each line has: `<size> <md5> <url>`.  The first line is the events export zip.
-const GDELT_LAST_UPDATE_URL: &str = "http://data.gdeltproject.org/gdeltv2/lastupdate.txt";
+const GDELT_LAST_UPDATE_FILE: &str = "lastupdate.txt";
+
+/// The same manifest for the translingual stream: events from non-English
+/// media, machine-translated by GDELT. Its exports end in
+/// `.translation.export.CSV.zip` and `.translation.mentions.CSV.zip`.
+const GDELT_TRANSLATION_LAST_UPDATE_FILE: &str = "lastupdate-translation.txt";
 
 /// Maximum number of events to parse from a single export (safety limit).
 const MAX_EVENTS: usize = 5000;
 
+/// Linked articles fetched per run in structured mode, unless
+/// `GDELT_MAX_ARTICLES` says otherwise.
+const DEFAULT_MAX_ARTICLES: usize = 25;
+
+/// Articles larger than this are skipped rather than converted.
+const MAX_ARTICLE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Timeout for fetching one linked article.
+const ARTICLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Namespace for the ids of entities built from event records.
+const ID_NAMESPACE: Uuid = Uuid::from_u128(0x4f1e_8a27_b3c6_4d95_a0e2_6c7b_9d3f_2904);
+
 /// GDELT 2.0 Events export column count (58 fields per the GDELT codebook).
 const GDELT_EVENT_COLUMNS: usize = 58;
 
@@ -25,10 +55,16 @@ const GDELT_EVENT_COLUMNS: usize = 58;
 mod col {
     pub const GLOBAL_EVENT_ID: usize = 0;
     pub const DAY: usize = 1;
-    pub const ACTOR1_NAME: usize = 5;
+    pub const ACTOR1_CODE: usize = 5;
+    pub const ACTOR1_NAME: usize = 6;
     pub const ACTOR1_COUNTRY_CODE: usize = 7;
-    pub const ACTOR2_NAME: usize = 15;
+    pub const ACTOR1_KNOWN_GROUP_CODE: usize = 8;
+    pub const ACTOR1_TYPE_CODES: [usize; 3] = [12, 13, 14];
+    pub const ACTOR2_CODE: usize = 15;
+    pub const ACTOR2_NAME: usize = 16;
     pub const ACTOR2_COUNTRY_CODE: usize = 17;
+    pub const ACTOR2_KNOWN_GROUP_CODE: usize = 18;
+    pub const ACTOR2_TYPE_CODES: [usize; 3] = [22, 23, 24];
     pub const EVENT_CODE: usize = 26;
     pub const EVENT_BASE_CODE: usize = 27;
     pub const EVENT_ROOT_CODE: usize = 28;
@@ -49,19 +85,128 @@ mod col {
     pub const SOURCE_URL: usize = 57;
 }
 
+/// Column indices for the GDELT 2.0 Mentions export.
+mod mention_col {
+    pub const GLOBAL_EVENT_ID: usize = 0;
+    /// `srclc:<ISO 639-2 code>;eng:<translation engine>`, empty for English.
+    pub const DOC_TRANSLATION_INFO: usize = 14;
+}
+
+/// Language recorded for events from the English stream.
+const ENGLISH: &str = "eng";
+
+/// How GDELT event records become graph data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GdeltMode {
+    /// Each event is written up as a short text document for LLM extraction.
+    Prose,
+    /// Actors, the event and its location are mapped to entities and
+    /// relationships directly; only linked articles go through the LLM.
+    Structured,
+}
+
+impl GdeltMode {
+    /// From `GDELT_MODE` (`prose` or `structured`); prose when unset.
+    fn from_env() -> Self {
+        match std::env::var("GDELT_MODE").ok().as_deref().map(str::trim) {
+            Some(mode) if mode.eq_ignore_ascii_case("structured") => Self::Structured,
+            Some(mode) if !mode.is_empty() && !mode.eq_ignore_ascii_case("prose") => {
+                warn!(mode, "Unknown GDELT_MODE, using prose");
+                Self::Prose
+            }
+            _ => Self::Prose,
+        }
+    }
+}
+
+/// A GDELT 2.0 export stream. Event ids are unique across streams, so
+/// both keep the `gdelt-event-<id>` source ids and share dedup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GdeltStream {
+    /// English-language media.
+    English,
+    /// Non-English media, translated by GDELT; documents carry the
+    /// original language from the mentions export.
+    Translingual,
+}
+
+impl GdeltStream {
+    fn name(self) -> &'static str {
+        match self {
+            Self::English => "english",
+            Self::Translingual => "translingual",
+        }
+    }
+
+    fn manifest_file(self) -> &'static str {
+        match self {
+            Self::English => GDELT_LAST_UPDATE_FILE,
+            Self::Translingual => GDELT_TRANSLATION_LAST_UPDATE_FILE,
+        }
+    }
+
+    /// From `GDELT_STREAMS`, a comma-separated list of `english` and
+    /// `translingual`; English only when unset or nothing is recognised.
+    fn from_env() -> Vec<Self> {
+        let raw = std::env::var("GDELT_STREAMS").unwrap_or_default();
+        let mut streams = Vec::new();
+        for name in raw.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let stream = if name.eq_ignore_ascii_case("english") {
+                Self::English
+            } else if name.eq_ignore_ascii_case("translingual") {
+                Self::Translingual
+            } else {
+                warn!(stream = name, "Unknown GDELT_STREAMS entry, ignoring it");
+                continue;
+            };
+            if !streams.contains(&stream) {
+                streams.push(stream);
+            }
+        }
+        if streams.is_empty() {
+            streams.push(Self::English);
+        }
+        streams
+    }
+}
+
 pub struct GdeltAgent {
     client: reqwest::Client,
     state: Arc<GdeltState>,
+    mode: GdeltMode,
+    /// Export streams collected each run, in order.
+    streams: Vec<GdeltStream>,
+    /// Linked articles fetched per run in structured mode.
+    max_articles: usize,
+    /// Where the manifests are fetched from; the exports are wherever they
+    /// point.
+    base_url: String,
 }
 
 struct GdeltState {
     last_run: RwLock<Option<DateTime<Utc>>>,
     documents_collected: AtomicU64,
     last_error: RwLock<Option<String>>,
+    /// Time of the newest export collected.
+    source_data_timestamp: RwLock<Option<DateTime<Utc>>>,
+}
+
+impl Default for GdeltAgent {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GdeltAgent {
     pub fn new() -> Self {
+        let max_articles = std::env::var("GDELT_MAX_ARTICLES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_ARTICLES);
+        Self::with_mode(GdeltMode::from_env(), max_articles).with_streams(GdeltStream::from_env())
+    }
+
+    pub fn with_mode(mode: GdeltMode, max_articles: usize) -> Self {
         let client = reqwest::Client::builder()
             .user_agent("argus-gdelt-agent/0.1")
             .timeout(std::time::Duration::from_secs(120))
@@ -74,56 +219,54 @@ impl GdeltAgent {
                 last_run: RwLock::new(None),
                 documents_collected: AtomicU64::new(0),
                 last_error: RwLock::new(None),
+                source_data_timestamp: RwLock::new(None),
             }),
+            mode,
+            streams: vec![GdeltStream::English],
+            max_articles,
+            base_url: GDELT_BASE_URL.to_string(),
         }
     }
 
-    /// Fetch the GDELT "lastupdate.txt" manifest and extract the URL of the latest
-    /// events export zip file.  The manifest contains three lines (export, mentions,
-    /// gkg); each formatted as `<byte_size> <md5_hash> <url>`.
-    async fn fetch_latest_export_url(&self) -> Result<String> {
-        info!("Fetching GDELT last-update manifest");
-        let body = self
-            .client
-            .get(GDELT_LAST_UPDATE_URL)
-            .send()
+    /// Fetch the manifests from `base_url` instead of GDELT, e.g. a mirror
+    /// or a test server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Collect these streams instead of the English one alone.
+    pub fn with_streams(mut self, streams: Vec<GdeltStream>) -> Self {
+        if !streams.is_empty() {
+            self.streams = streams;
+        }
+        self
+    }
+
+    /// Fetch a stream's "lastupdate" manifest. It has three lines (export,
+    /// mentions, gkg), each formatted as `<byte_size> <md5_hash> <url>`.
+    async fn fetch_manifest(&self, stream: GdeltStream) -> Result<String> {
+        info!(
+            stream = stream.name(),
+            "Fetching GDELT last-update manifest"
+        );
+        self.client
+            .get(format!("{}/{}", self.base_url, stream.manifest_file()))
+            .send_traced()
             .await
             .map_err(|e| ArgusError::Agent {
                 agent: "gdelt".into(),
-                message: format!("failed to fetch last-update manifest: {e}"),
+                message: format!(
+                    "failed to fetch {} last-update manifest: {e}",
+                    stream.name()
+                ),
             })?
             .text()
             .await
             .map_err(|e| ArgusError::Agent {
                 agent: "gdelt".into(),
                 message: format!("failed to read last-update body: {e}"),
-            })?;
-
-        // Find the events export line (ends with `.export.CSV.zip`).
-        let first_line = body
-            .lines()
-            .find(|line| {
-                let trimmed = line.trim();
-                !trimmed.is_empty() && trimmed.ends_with(".export.CSV.zip")
             })
-            .or_else(|| body.lines().find(|l| !l.trim().is_empty()))
-            .ok_or_else(|| ArgusError::Agent {
-                agent: "gdelt".into(),
-                message: "last-update manifest was empty".into(),
-            })?;
-
-        // URL is the third whitespace-delimited token.
-        let url = first_line
-            .split_whitespace()
-            .nth(2)
-            .ok_or_else(|| ArgusError::Agent {
-                agent: "gdelt".into(),
-                message: format!("unexpected manifest line format: {first_line}"),
-            })?
-            .to_string();
-
-        debug!(url = %url, "Resolved latest GDELT export URL");
-        Ok(url)
     }
 
     /// Download a GDELT `.CSV.zip` archive, decompress in memory via a blocking
@@ -138,7 +281,7 @@ impl GdeltAgent {
         let bytes = self
             .client
             .get(zip_url)
-            .send()
+            .send_traced()
             .await
             .map_err(|e| ArgusError::Agent {
                 agent: "gdelt".into(),
@@ -169,7 +312,14 @@ impl GdeltAgent {
     }
 
     /// Parse tab-separated GDELT 2.0 events CSV into `RawDocument` records.
-    fn parse_events(&self, csv: &str) -> Vec<RawDocument> {
+    /// `languages` maps event ids to their original language, for the
+    /// translingual stream.
+    fn parse_events(
+        &self,
+        csv: &str,
+        stream: GdeltStream,
+        languages: &HashMap<String, String>,
+    ) -> Vec<RawDocument> {
         let now = Utc::now();
         let mut documents = Vec::new();
 
@@ -205,25 +355,6 @@ impl GdeltAgent {
             let action_geo = fields[col::ACTION_GEO_FULL_NAME].trim();
             let action_country = fields[col::ACTION_GEO_COUNTRY_CODE].trim();
 
-            let title = build_event_title(actor1, actor2, event_code, action_geo);
-
-            let content = build_event_content(
-                global_event_id,
-                day,
-                actor1,
-                fields[col::ACTOR1_COUNTRY_CODE].trim(),
-                actor2,
-                fields[col::ACTOR2_COUNTRY_CODE].trim(),
-                event_code,
-                event_root_code,
-                quad_class,
-                goldstein,
-                avg_tone,
-                action_geo,
-                action_country,
-                source_url,
-            );
-
             // Parse optional geo coordinates.
             let action_lat = parse_f64(fields[col::ACTION_GEO_LAT].trim());
             let action_lon = parse_f64(fields[col::ACTION_GEO_LONG].trim());
@@ -232,13 +363,34 @@ impl GdeltAgent {
             let actor2_lat = parse_f64(fields[col::ACTOR2_GEO_LAT].trim());
             let actor2_lon = parse_f64(fields[col::ACTOR2_GEO_LONG].trim());
 
-            let metadata = json!({
+            let type_codes = |columns: [usize; 3]| -> Vec<&str> {
+                columns
+                    .iter()
+                    .map(|&i| fields[i].trim())
+                    .filter(|c| !c.is_empty())
+                    .collect()
+            };
+
+            let language = match stream {
+                GdeltStream::English => Some(ENGLISH),
+                GdeltStream::Translingual => languages.get(global_event_id).map(String::as_str),
+            };
+
+            let mut metadata = json!({
                 "global_event_id": global_event_id,
+                "stream": stream.name(),
+                "source_language": language,
                 "day": day,
                 "actor1_name": actor1,
+                "actor1_code": fields[col::ACTOR1_CODE].trim(),
                 "actor1_country_code": fields[col::ACTOR1_COUNTRY_CODE].trim(),
+                "actor1_known_group_code": fields[col::ACTOR1_KNOWN_GROUP_CODE].trim(),
+                "actor1_type_codes": type_codes(col::ACTOR1_TYPE_CODES),
                 "actor2_name": actor2,
+                "actor2_code": fields[col::ACTOR2_CODE].trim(),
                 "actor2_country_code": fields[col::ACTOR2_COUNTRY_CODE].trim(),
+                "actor2_known_group_code": fields[col::ACTOR2_KNOWN_GROUP_CODE].trim(),
+                "actor2_type_codes": type_codes(col::ACTOR2_TYPE_CODES),
                 "event_code": event_code,
                 "event_base_code": event_base_code,
                 "event_root_code": event_root_code,
@@ -264,14 +416,59 @@ impl GdeltAgent {
                 Some(source_url.to_string())
             };
 
+            let source_id = format!("gdelt-event-{global_event_id}");
+            let observed_at = parse_source_date(day);
+            let (title, content, structured) = match self.mode {
+                GdeltMode::Prose => {
+                    // The extraction pipeline keys the event the model
+                    // reads out of the text on this, as structured mode does
+                    metadata["event_key"] = json!(event_key(&metadata));
+                    let title = build_event_title(actor1, actor2, event_code, action_geo);
+                    let mut content = build_event_content(
+                        global_event_id,
+                        day,
+                        actor1,
+                        fields[col::ACTOR1_COUNTRY_CODE].trim(),
+                        actor2,
+                        fields[col::ACTOR2_COUNTRY_CODE].trim(),
+                        event_code,
+                        event_root_code,
+                        quad_class,
+                        goldstein,
+                        avg_tone,
+                        action_geo,
+                        action_country,
+                        source_url,
+                    );
+                    if stream == GdeltStream::Translingual {
+                        content.push_str(&format!(
+                            "\nOriginal language: {}",
+                            language.unwrap_or("unknown")
+                        ));
+                    }
+                    (Some(title).filter(|t| !t.is_empty()), content, None)
+                }
+                GdeltMode::Structured => {
+                    let at = observed_at.unwrap_or(now);
+                    (
+                        None,
+                        String::new(),
+                        Some(event_extraction(&source_id, &metadata, at, now)),
+                    )
+                }
+            };
+
             documents.push(RawDocument {
                 source: "gdelt".into(),
-                source_id: format!("gdelt-event-{global_event_id}"),
-                title: if title.is_empty() { None } else { Some(title) },
+                source_id,
+                title,
                 content,
                 url,
                 collected_at: now,
+                observed_at,
+                identifiers: Default::default(),
                 metadata,
+                structured,
             });
         }
 
@@ -323,9 +520,17 @@ impl Agent for GdeltAgent {
             last_run: *self.state.last_run.read().await,
             documents_collected: self.state.documents_collected.load(Ordering::Relaxed),
             error: self.state.last_error.read().await.clone(),
+            source_data_timestamp: *self.state.source_data_timestamp.read().await,
+            // Exports are CSV, which has no field names to drift
+            schema_drift: None,
+            capabilities: None,
         }
     }
 
+    fn as_lookup(&self) -> Option<&dyn AgentLookup> {
+        Some(self)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -347,16 +552,587 @@ impl GdeltAgent {
     /// Inner collection logic, separated so `collect()` can handle state updates
     /// uniformly for both success and failure paths.
     async fn collect_inner(&self) -> Result<Vec<RawDocument>> {
-        let export_url = self.fetch_latest_export_url().await?;
-        let csv = self.download_and_decompress(&export_url).await?;
-        let documents = self.parse_events(&csv);
+        let mut documents = Vec::new();
+        let mut seen = HashSet::new();
+        let mut failures = Vec::new();
+        for &stream in &self.streams {
+            match self.collect_stream(stream).await {
+                Ok((exported_at, events)) => {
+                    let mut newest = self.state.source_data_timestamp.write().await;
+                    *newest = (*newest).max(exported_at);
+                    drop(newest);
+                    info!(
+                        stream = stream.name(),
+                        events = events.len(),
+                        "Parsed GDELT stream"
+                    );
+                    // An event already taken from an earlier stream is the same record
+                    documents.extend(
+                        events
+                            .into_iter()
+                            .filter(|d| seen.insert(d.source_id.clone())),
+                    );
+                }
+                // One stream being down shouldn't lose the other
+                Err(e) if self.streams.len() > 1 => {
+                    warn!(stream = stream.name(), error = %e, "GDELT stream failed");
+                    failures.push(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if failures.len() == self.streams.len() {
+            return Err(failures.remove(0));
+        }
 
         if documents.is_empty() {
             warn!("GDELT export yielded zero parsed events");
         }
 
+        if self.mode == GdeltMode::Structured && self.max_articles > 0 {
+            let articles = self.fetch_articles(&documents).await;
+            info!(articles = articles.len(), "Fetched GDELT linked articles");
+            documents.extend(articles);
+        }
+
         Ok(documents)
     }
+
+    /// Events from the latest export of one stream, and when it was
+    /// exported.
+    async fn collect_stream(
+        &self,
+        stream: GdeltStream,
+    ) -> Result<(Option<DateTime<Utc>>, Vec<RawDocument>)> {
+        let manifest = self.fetch_manifest(stream).await?;
+        let export_url = manifest_export_url(&manifest)?;
+        let exported_at = export_timestamp(&export_url);
+        let csv = self.download_and_decompress(&export_url).await?;
+
+        let mut languages = HashMap::new();
+        if stream == GdeltStream::Translingual {
+            // Without the mentions the events are still usable, just untagged
+            match manifest_entry(&manifest, ".mentions.CSV.zip") {
+                Some(url) => match self.download_and_decompress(&url).await {
+                    Ok(mentions) => languages = parse_mention_languages(&mentions),
+                    Err(e) => warn!(error = %e, "Failed to fetch GDELT translingual mentions"),
+                },
+                None => warn!("GDELT translingual manifest lists no mentions export"),
+            }
+        }
+        Ok((exported_at, self.parse_events(&csv, stream, &languages)))
+    }
+
+    /// Fetch the pages events link to, most reported events first, and
+    /// convert those in a text format (HTML, PDF, plain text) for LLM
+    /// extraction. Pages that fail or have no text are skipped.
+    async fn fetch_articles(&self, events: &[RawDocument]) -> Vec<RawDocument> {
+        let mut ranked: Vec<&RawDocument> = events.iter().filter(|d| d.url.is_some()).collect();
+        ranked.sort_by_key(|d| {
+            std::cmp::Reverse(
+                d.metadata["num_mentions"]
+                    .as_str()
+                    .and_then(|n| n.parse::<u64>().ok())
+                    .unwrap_or(0),
+            )
+        });
+
+        let mut seen = std::collections::HashSet::new();
+        let mut tasks = tokio::task::JoinSet::new();
+        for event in ranked {
+            let url = event.url.clone().unwrap_or_default();
+            if !seen.insert(url.clone()) {
+                continue;
+            }
+            if seen.len() > self.max_articles {
+                break;
+            }
+            let client = self.client.clone();
+            let event_id = event.metadata["global_event_id"].clone();
+            let language = event.metadata["source_language"].clone();
+            let observed_at = event.observed_at;
+            tasks.spawn(async move {
+                let article = fetch_article(&client, &url).await;
+                if let Err(e) = &article {
+                    debug!(url = %url, error = %e, "Skipping GDELT linked article");
+                }
+                let (title, content) = article.ok()?;
+                Some(RawDocument {
+                    source: "gdelt".into(),
+                    source_id: format!("gdelt-article-{}", Uuid::new_v5(&Uuid::NAMESPACE_URL, url.as_bytes()).simple()),
+                    title,
+                    content,
+                    url: Some(url),
+                    collected_at: Utc::now(),
+                    observed_at,
+                    identifiers: Default::default(),
+                    metadata: json!({ "kind": "article", "global_event_id": event_id, "source_language": language }),
+                    structured: None,
+                })
+            });
+        }
+
+        let mut articles = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok(Some(article)) = joined {
+                articles.push(article);
+            }
+        }
+        articles
+    }
+}
+
+/// URL of the manifest line whose file name ends in `suffix`.
+fn manifest_entry(manifest: &str, suffix: &str) -> Option<String> {
+    manifest
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(2))
+        .find(|url| url.ends_with(suffix))
+        .map(str::to_string)
+}
+
+/// URL of the events export in a manifest, falling back to the first line.
+fn manifest_export_url(manifest: &str) -> Result<String> {
+    if let Some(url) = manifest_entry(manifest, ".export.CSV.zip") {
+        debug!(url = %url, "Resolved latest GDELT export URL");
+        return Ok(url);
+    }
+    let first_line = manifest
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .ok_or_else(|| ArgusError::Agent {
+            agent: "gdelt".into(),
+            message: "last-update manifest was empty".into(),
+        })?;
+    // URL is the third whitespace-delimited token.
+    first_line
+        .split_whitespace()
+        .nth(2)
+        .map(str::to_string)
+        .ok_or_else(|| ArgusError::Agent {
+            agent: "gdelt".into(),
+            message: format!("unexpected manifest line format: {first_line}"),
+        })
+}
+
+/// When an export was published, from its `YYYYMMDDHHMMSS.…` file name.
+fn export_timestamp(url: &str) -> Option<DateTime<Utc>> {
+    let file = url.rsplit('/').next()?;
+    let stamp = file.get(..14)?;
+    chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d%H%M%S")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Original language of each event in a translingual mentions export, from
+/// the first mention that names one (`srclc:fra;eng:GT-FRA 1.0` → `fra`).
+fn parse_mention_languages(csv: &str) -> HashMap<String, String> {
+    let mut languages = HashMap::new();
+    for line in csv.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (Some(id), Some(info)) = (
+            fields.get(mention_col::GLOBAL_EVENT_ID),
+            fields.get(mention_col::DOC_TRANSLATION_INFO),
+        ) else {
+            continue;
+        };
+        let language = info
+            .split(';')
+            .find_map(|part| part.trim().strip_prefix("srclc:"))
+            .map(|code| code.trim().to_lowercase())
+            .filter(|code| !code.is_empty());
+        if let (false, Some(language)) = (id.trim().is_empty(), language) {
+            languages.entry(id.trim().to_string()).or_insert(language);
+        }
+    }
+    languages
+}
+
+/// Title and text of a linked article, by its content type.
+async fn fetch_article(client: &reqwest::Client, url: &str) -> Result<(Option<String>, String)> {
+    let fail = |message: String| ArgusError::Agent {
+        agent: "gdelt".into(),
+        message,
+    };
+    let response = client
+        .get(url)
+        .timeout(ARTICLE_TIMEOUT)
+        .send_traced()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| fail(format!("failed to fetch article: {e}")))?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_lowercase())
+        .unwrap_or_else(|| "text/html".to_string());
+    let format = DocumentFormat::from_content_type(&content_type)
+        .ok_or_else(|| fail(format!("article is {content_type}, not text")))?;
+    if response
+        .content_length()
+        .is_some_and(|len| len as usize > MAX_ARTICLE_BYTES)
+    {
+        return Err(fail("article too large".into()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| fail(format!("failed to read article: {e}")))?;
+    if bytes.len() > MAX_ARTICLE_BYTES {
+        return Err(fail("article too large".into()));
+    }
+    let converted = tokio::task::spawn_blocking(move || formats::convert(&bytes, format))
+        .await
+        .map_err(|e| fail(format!("article conversion panicked: {e}")))??;
+    if converted.is_empty() {
+        return Err(fail("article has no text".into()));
+    }
+    Ok((converted.title, converted.text))
+}
+
+// ---------------------------------------------------------------------------
+// Structured mode: entities straight from the event record
+// ---------------------------------------------------------------------------
+
+/// Entity for a GDELT actor or place name. Country names map to the
+/// canonical country location so they line up with other sources.
+fn named_entity(name: &str, entity_type: EntityType, kind: &str, at: DateTime<Utc>) -> Entity {
+    if let Some(country) = countries::by_name(name) {
+        let mut entity = country.to_entity("gdelt");
+        entity.observed_at = Some(at);
+        return entity;
+    }
+    let key = format!("gdelt-{kind}:{}", name.to_lowercase());
+    let mut entity = Entity::new(entity_type, name.to_string(), "gdelt".into());
+    entity.id = Uuid::new_v5(&ID_NAMESPACE, key.as_bytes());
+    entity.source_id = Some(key);
+    entity.observed_at = Some(at);
+    entity
+}
+
+/// Entity for an event actor. GDELT names an actor by what its CAMEO code
+/// matched: a country or city for a geographic match, an organization's or
+/// leader's name, or a class such as "POLICE" for a type match. The codes
+/// decide the entity type:
+/// - a known group (NATO, the UN) is an Organization
+/// - a code that is only the country, with a name that isn't one, is a
+///   city or region Location
+/// - a typed actor whose name reads as a personal name is a Person
+/// - anything else stays an Organization
+///
+/// Countries map to their canonical location as in [`named_entity`]; other
+/// actors carry their country and CAMEO codes as properties.
+fn actor_entity(meta: &serde_json::Value, actor: &str, at: DateTime<Utc>) -> Entity {
+    let text = |key: &str| {
+        meta[format!("{actor}_{key}")]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    let name = text("name");
+    let code = text("code");
+    let country_code = text("country_code");
+    let known_group = text("known_group_code");
+    let type_codes: Vec<&str> = meta[format!("{actor}_type_codes")]
+        .as_array()
+        .map(|codes| codes.iter().filter_map(|c| c.as_str()).collect())
+        .unwrap_or_default();
+
+    let (entity_type, kind) = if !known_group.is_empty() {
+        (EntityType::Organization, "actor")
+    } else if type_codes.is_empty() && !country_code.is_empty() && code == country_code {
+        (EntityType::Location, "place")
+    } else if !type_codes.is_empty() && reads_as_personal_name(&name) {
+        (EntityType::Person, "person")
+    } else {
+        (EntityType::Organization, "actor")
+    };
+
+    let mut entity = named_entity(&name, entity_type, kind, at);
+    if entity.source_id.is_some() {
+        let country = countries::by_iso3(&country_code);
+        let mut properties = json!({
+            "country_code": country_code,
+            "country": country.map(|c| c.name),
+            "cameo_code": Some(code).filter(|c| !c.is_empty()),
+            "cameo_types": type_codes.iter().map(|c| cameo_actor_type(c).unwrap_or(c)).collect::<Vec<_>>(),
+            "known_group_code": Some(known_group).filter(|g| !g.is_empty()),
+        });
+        if entity.entity_type == EntityType::Location {
+            properties["location_type"] = json!("place");
+        }
+        entity.properties = properties;
+    }
+    entity
+}
+
+/// Words of CAMEO class names and titles; a name containing one is a group
+/// or role ("POLICE OFFICER", "PRIME MINISTER"), not a person.
+const ROLE_WORDS: &[&str] = &[
+    "AGENCY",
+    "AMBASSADOR",
+    "ARMY",
+    "AUTHORITY",
+    "AUTHORITIES",
+    "BANK",
+    "CABINET",
+    "CITIZEN",
+    "COMMITTEE",
+    "COMMUNITY",
+    "COMPANY",
+    "CONGRESS",
+    "COUNCIL",
+    "COURT",
+    "DEPARTMENT",
+    "DIPLOMAT",
+    "EMBASSY",
+    "EMPLOYEE",
+    "FORCE",
+    "FORCES",
+    "GOVERNMENT",
+    "GROUP",
+    "HOSPITAL",
+    "JUDGE",
+    "LAWMAKER",
+    "LEADER",
+    "LEGISLATOR",
+    "MAYOR",
+    "MEDIA",
+    "MILITANT",
+    "MILITARY",
+    "MINISTER",
+    "MINISTRY",
+    "NATIONAL",
+    "OFFICER",
+    "OFFICIAL",
+    "OPPOSITION",
+    "PARLIAMENT",
+    "PARTY",
+    "PEOPLE",
+    "POLICE",
+    "PRESIDENT",
+    "PRIME",
+    "PROTESTER",
+    "REBEL",
+    "SCHOOL",
+    "SECRETARY",
+    "SENATE",
+    "SOLDIER",
+    "SPOKESMAN",
+    "SPOKESPERSON",
+    "STATE",
+    "STUDENT",
+    "UNION",
+    "UNIVERSITY",
+    "WORKER",
+];
+
+/// Two to four words of letters, none of them a [`ROLE_WORDS`] entry.
+fn reads_as_personal_name(name: &str) -> bool {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    (2..=4).contains(&words.len())
+        && words.iter().all(|word| {
+            let upper = word.to_uppercase();
+            let singular = upper.strip_suffix('S').unwrap_or(&upper);
+            word.chars()
+                .all(|c| c.is_alphabetic() || matches!(c, '-' | '\'' | '.'))
+                && !ROLE_WORDS.contains(&upper.as_str())
+                && !ROLE_WORDS.contains(&singular)
+        })
+}
+
+/// CAMEO actor type code to what it stands for.
+fn cameo_actor_type(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "GOV" => "government",
+        "MIL" => "military",
+        "COP" => "police",
+        "JUD" => "judiciary",
+        "LEG" => "legislature",
+        "SPY" => "intelligence",
+        "PTY" => "political party",
+        "OPP" => "opposition",
+        "ELI" => "elite",
+        "BUS" => "business",
+        "MNC" => "multinational corporation",
+        "NGO" => "non-governmental organization",
+        "IGO" => "intergovernmental organization",
+        "NGM" => "non-governmental movement",
+        "INS" => "insurgents",
+        "REB" => "rebels",
+        "SEP" => "separatists",
+        "UAF" => "unaligned armed forces",
+        "CRM" => "criminal",
+        "MED" => "media",
+        "EDU" => "education",
+        "HLH" => "health",
+        "LAB" => "labor",
+        "AGR" => "agriculture",
+        "ENV" => "environmental",
+        "HRI" => "human rights",
+        "REF" => "refugees",
+        "CVL" => "civilian",
+        "RAD" => "radical",
+        "MOD" => "moderate",
+        "UIS" => "unidentified state actor",
+        _ => return None,
+    })
+}
+
+fn event_relationship(
+    source: Uuid,
+    target: Uuid,
+    relation_type: RelationType,
+    role: &str,
+    at: DateTime<Utc>,
+) -> Relationship {
+    let mut relationship = Relationship::new(source, target, relation_type, "gdelt".into());
+    relationship.id = Uuid::new_v5(
+        &ID_NAMESPACE,
+        format!("{source}:{target}:{role}").as_bytes(),
+    );
+    relationship.properties = json!({ "role": role });
+    relationship.timestamp = Some(at);
+    relationship
+}
+
+/// The canonical id of the real-world event a record reports. GDELT files
+/// each later report of an event under a new GlobalEventID, so records
+/// with the same day, CAMEO code, actors and place are one event: the
+/// graph keeps one Event for them and counts the reports in its
+/// `mention_count`.
+fn event_key(meta: &serde_json::Value) -> String {
+    let text = |key: &str| meta[key].as_str().unwrap_or_default().trim().to_lowercase();
+    // Actor codes are stable across reports; names vary ("PRESIDENT",
+    // "BARACK OBAMA") and only stand in for records without a code
+    let actor = |actor: &str| {
+        let code = text(&format!("{actor}_code"));
+        if code.is_empty() {
+            text(&format!("{actor}_name"))
+        } else {
+            code
+        }
+    };
+    let mut place = text("action_geo_full_name");
+    if place.is_empty() {
+        if let (Some(lat), Some(lon)) = (
+            meta["action_geo_lat"].as_f64(),
+            meta["action_geo_long"].as_f64(),
+        ) {
+            place = format!("{lat:.2},{lon:.2}");
+        }
+    }
+    format!(
+        "{EVENT_KEY_PREFIX}{}",
+        [
+            text("day"),
+            text("event_code"),
+            actor("actor1"),
+            actor("actor2"),
+            place
+        ]
+        .join("|")
+    )
+}
+
+/// The entities an event record states outright: the event, its actors
+/// (RELATED_TO the event as `actor1`/`actor2`) and where it happened
+/// (LOCATED_AT). `at` is when the event happened.
+fn event_extraction(
+    source_id: &str,
+    meta: &serde_json::Value,
+    at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> ExtractionResult {
+    let text = |key: &str| meta[key].as_str().unwrap_or_default().trim();
+    let geo = text("action_geo_full_name");
+
+    let mut event = Entity::new(
+        EntityType::Event,
+        build_event_title(
+            text("actor1_name"),
+            text("actor2_name"),
+            text("event_code"),
+            geo,
+        ),
+        "gdelt".into(),
+    );
+    let key = event_key(meta);
+    event.id = Uuid::new_v5(&ID_NAMESPACE, key.as_bytes());
+    event.source_id = Some(key);
+    event.observed_at = Some(at);
+    event.properties = json!({
+        "global_event_id": text("global_event_id"),
+        "day": text("day"),
+        "source_language": meta["source_language"],
+        "event_code": text("event_code"),
+        "event_root_code": text("event_root_code"),
+        "event_description": cameo_event_description(text("event_code")),
+        "quad_class": text("quad_class"),
+        "goldstein_scale": parse_f64(text("goldstein_scale")),
+        "avg_tone": parse_f64(text("avg_tone")),
+        "num_mentions": text("num_mentions").parse::<u64>().ok(),
+        "num_sources": text("num_sources").parse::<u64>().ok(),
+        "num_articles": text("num_articles").parse::<u64>().ok(),
+        "latitude": meta["action_geo_lat"],
+        "longitude": meta["action_geo_long"],
+    });
+
+    let mut entities = Vec::new();
+    let mut relationships = Vec::new();
+    for actor in ["actor1", "actor2"] {
+        let name = text(&format!("{actor}_name"));
+        if name.is_empty() {
+            continue;
+        }
+        let entity = actor_entity(meta, actor, at);
+        relationships.push(event_relationship(
+            entity.id,
+            event.id,
+            RelationType::RelatedTo,
+            actor,
+            at,
+        ));
+        if !entities.iter().any(|e: &Entity| e.id == entity.id) {
+            entities.push(entity);
+        }
+    }
+    if !geo.is_empty() {
+        let mut place = named_entity(geo, EntityType::Location, "place", at);
+        // Canonical countries keep their own properties
+        if place.source_id.is_some() {
+            place.properties = json!({
+                "location_type": "place",
+                "country_code": text("action_geo_country_code"),
+                "latitude": meta["action_geo_lat"],
+                "longitude": meta["action_geo_long"],
+            });
+        }
+        relationships.push(event_relationship(
+            event.id,
+            place.id,
+            RelationType::LocatedAt,
+            "action_geo",
+            at,
+        ));
+        if !entities.iter().any(|e| e.id == place.id) {
+            entities.push(place);
+        }
+    }
+    entities.insert(0, event);
+    for entity in &mut entities {
+        entity.last_seen = now;
+    }
+
+    ExtractionResult {
+        entities,
+        relationships,
+        raw_source: source_id.to_string(),
+        extracted_at: now,
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -623,18 +1399,10 @@ fn decode_fixed_huffman_block(
 ) -> std::result::Result<(), String> {
     // Build fixed literal/length code lengths.
     let mut lit_lengths = [0u8; 288];
-    for i in 0..=143 {
-        lit_lengths[i] = 8;
-    }
-    for i in 144..=255 {
-        lit_lengths[i] = 9;
-    }
-    for i in 256..=279 {
-        lit_lengths[i] = 7;
-    }
-    for i in 280..=287 {
-        lit_lengths[i] = 8;
-    }
+    lit_lengths[0..=143].fill(8);
+    lit_lengths[144..=255].fill(9);
+    lit_lengths[256..=279].fill(7);
+    lit_lengths[280..=287].fill(8);
     let lit_tree = build_huffman_tree(&lit_lengths)?;
 
     // Fixed distance codes: all 5 bits.
@@ -679,15 +1447,11 @@ fn decode_dynamic_huffman_block(
             }
             17 => {
                 let repeat = reader.read_bits(3)? as usize + 3;
-                for _ in 0..repeat {
-                    code_lengths.push(0);
-                }
+                code_lengths.resize(code_lengths.len() + repeat, 0);
             }
             18 => {
                 let repeat = reader.read_bits(7)? as usize + 11;
-                for _ in 0..repeat {
-                    code_lengths.push(0);
-                }
+                code_lengths.resize(code_lengths.len() + repeat, 0);
             }
             _ => return Err(format!("invalid code length symbol {sym}")),
         }
@@ -751,10 +1515,7 @@ fn build_huffman_tree(lengths: &[u8]) -> std::result::Result<HuffmanTree, String
     Ok(HuffmanTree { table, max_bits })
 }
 
-fn decode_symbol(
-    reader: &mut BitReader,
-    tree: &HuffmanTree,
-) -> std::result::Result<u16, String> {
+fn decode_symbol(reader: &mut BitReader, tree: &HuffmanTree) -> std::result::Result<u16, String> {
     let mut code: u32 = 0;
     for bits in 1..=tree.max_bits {
         let bit = reader.read_bits_msb(1)?;
@@ -770,8 +1531,8 @@ fn decode_symbol(
 
 // Length and distance extra-bits tables per RFC 1951.
 static LENGTH_BASE: [u16; 29] = [
-    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
-    131, 163, 195, 227, 258,
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
 ];
 
 static LENGTH_EXTRA: [u8; 29] = [
@@ -784,8 +1545,8 @@ static DIST_BASE: [u16; 30] = [
 ];
 
 static DIST_EXTRA: [u8; 30] = [
-    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12,
-    13, 13,
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
 ];
 
 fn decode_huffman_stream(
@@ -816,8 +1577,8 @@ fn decode_huffman_stream(
                 if dist_sym >= DIST_BASE.len() {
                     return Err(format!("invalid distance symbol {dist_sym}"));
                 }
-                let distance = DIST_BASE[dist_sym] as usize
-                    + reader.read_bits(DIST_EXTRA[dist_sym])? as usize;
+                let distance =
+                    DIST_BASE[dist_sym] as usize + reader.read_bits(DIST_EXTRA[dist_sym])? as usize;
 
                 if distance > output.len() {
                     return Err(format!(
@@ -876,11 +1637,7 @@ fn find_signature_reverse(data: &[u8], sig: &[u8; 4]) -> Option<usize> {
 fn build_event_title(actor1: &str, actor2: &str, event_code: &str, geo: &str) -> String {
     let mut parts = Vec::new();
 
-    let a1 = if actor1.is_empty() {
-        "Unknown"
-    } else {
-        actor1
-    };
+    let a1 = if actor1.is_empty() { "Unknown" } else { actor1 };
     parts.push(a1.to_string());
 
     let action = cameo_event_description(event_code);
@@ -898,6 +1655,7 @@ fn build_event_title(actor1: &str, actor2: &str, event_code: &str, geo: &str) ->
 }
 
 /// Build structured content text for an event record.
+#[allow(clippy::too_many_arguments)]
 fn build_event_content(
     id: &str,
     day: &str,
@@ -1039,13 +1797,13 @@ mod tests {
     fn test_parse_events_skips_short_lines() {
         let agent = GdeltAgent::new();
         let csv = "too\tfew\tcolumns\n";
-        let docs = agent.parse_events(csv);
+        let docs = agent.parse_events(csv, GdeltStream::English, &HashMap::new());
         assert!(docs.is_empty());
     }
 
     #[test]
     fn test_parse_events_valid_line() {
-        let agent = GdeltAgent::new();
+        let agent = GdeltAgent::with_mode(GdeltMode::Prose, 0);
 
         // Build a line with exactly 58 tab-separated fields.
         let mut fields = vec![""; GDELT_EVENT_COLUMNS];
@@ -1071,7 +1829,7 @@ mod tests {
         fields[col::SOURCE_URL] = "https://example.com/article";
 
         let line = fields.join("\t");
-        let docs = agent.parse_events(&line);
+        let docs = agent.parse_events(&line, GdeltStream::English, &HashMap::new());
 
         assert_eq!(docs.len(), 1);
         let doc = &docs[0];
@@ -1083,6 +1841,195 @@ mod tests {
         assert_eq!(doc.url.as_deref(), Some("https://example.com/article"));
         assert_eq!(doc.metadata["quad_class"], "1");
         assert_eq!(doc.metadata["action_geo_lat"], 39.9042);
+        assert_eq!(doc.metadata["event_key"], event_key(&doc.metadata));
+        assert_eq!(
+            doc.observed_at.map(|at| at.to_rfc3339()).as_deref(),
+            Some("2026-02-26T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_translingual_events_carry_their_language() {
+        let manifest = "151 9f1e http://data.gdeltproject.org/gdeltv2/20260226101500.translation.export.CSV.zip\n\
+                        240 ab12 http://data.gdeltproject.org/gdeltv2/20260226101500.translation.mentions.CSV.zip\n\
+                        980 cd34 http://data.gdeltproject.org/gdeltv2/20260226101500.translation.gkg.csv.zip\n";
+        let export_url = manifest_export_url(manifest).unwrap();
+        assert!(export_url.ends_with(".translation.export.CSV.zip"));
+        assert_eq!(
+            export_timestamp(&export_url)
+                .map(|t| t.to_rfc3339())
+                .as_deref(),
+            Some("2026-02-26T10:15:00+00:00")
+        );
+        assert!(manifest_entry(manifest, ".mentions.CSV.zip")
+            .unwrap()
+            .ends_with(".translation.mentions.CSV.zip"));
+
+        let mention = |id: &str, info: &str| {
+            let mut fields = vec![""; 16];
+            fields[mention_col::GLOBAL_EVENT_ID] = id;
+            fields[mention_col::DOC_TRANSLATION_INFO] = info;
+            fields.join("\t")
+        };
+        let mentions = [
+            mention("111", "srclc:fra;eng:GT-FRA 1.0"),
+            mention("111", "srclc:deu;eng:GT-DEU 1.0"),
+            mention("222", ""),
+        ]
+        .join("\n");
+        let languages = parse_mention_languages(&mentions);
+        assert_eq!(languages.get("111").map(String::as_str), Some("fra"));
+        assert!(!languages.contains_key("222"));
+
+        let agent = GdeltAgent::with_mode(GdeltMode::Prose, 0);
+        let mut fields = vec![""; GDELT_EVENT_COLUMNS];
+        fields[col::GLOBAL_EVENT_ID] = "111";
+        fields[col::ACTOR1_NAME] = "FRANCE";
+        fields[col::EVENT_CODE] = "04";
+        let docs = agent.parse_events(&fields.join("\t"), GdeltStream::Translingual, &languages);
+        assert_eq!(docs[0].source_id, "gdelt-event-111");
+        assert_eq!(docs[0].metadata["stream"], "translingual");
+        assert_eq!(docs[0].metadata["source_language"], "fra");
+        assert!(docs[0].content.ends_with("Original language: fra"));
+
+        let english = agent.parse_events(&fields.join("\t"), GdeltStream::English, &HashMap::new());
+        assert_eq!(english[0].metadata["source_language"], "eng");
+        assert!(!english[0].content.contains("Original language"));
+    }
+
+    #[test]
+    fn test_parse_events_structured_mode() {
+        let agent = GdeltAgent::with_mode(GdeltMode::Structured, 0);
+
+        let mut fields = vec![""; GDELT_EVENT_COLUMNS];
+        fields[col::GLOBAL_EVENT_ID] = "1234567890";
+        fields[col::DAY] = "20260226";
+        fields[col::ACTOR1_NAME] = "UNITED STATES";
+        fields[col::ACTOR1_COUNTRY_CODE] = "USA";
+        fields[col::ACTOR2_NAME] = "ACME SHIPPING";
+        fields[col::EVENT_CODE] = "04";
+        fields[col::NUM_MENTIONS] = "5";
+        fields[col::ACTION_GEO_FULL_NAME] = "Beijing, Beijing, China";
+        fields[col::ACTION_GEO_COUNTRY_CODE] = "CH";
+        fields[col::ACTION_GEO_LAT] = "39.9042";
+        fields[col::ACTION_GEO_LONG] = "116.4074";
+        fields[col::SOURCE_URL] = "https://example.com/article";
+
+        let docs = agent.parse_events(&fields.join("\t"), GdeltStream::English, &HashMap::new());
+        let doc = &docs[0];
+        assert!(doc.title.is_none());
+        assert!(doc.content.is_empty());
+        assert_eq!(doc.metadata["actor2_name"], "ACME SHIPPING");
+
+        let result = doc.structured.as_ref().unwrap();
+        let event = &result.entities[0];
+        assert_eq!(event.entity_type, EntityType::Event);
+        assert_eq!(
+            event.source_id.as_deref(),
+            Some("gdelt-event:20260226|04|united states|acme shipping|beijing, beijing, china")
+        );
+        assert_eq!(result.raw_source, "gdelt-event-1234567890");
+        assert_eq!(event.properties["num_mentions"], 5);
+        let types: Vec<&EntityType> = result.entities.iter().map(|e| &e.entity_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                &EntityType::Event,
+                &EntityType::Location,
+                &EntityType::Organization,
+                &EntityType::Location
+            ]
+        );
+        // The country actor is the canonical country, not a GDELT-only node
+        assert_eq!(
+            result.entities[1].id,
+            countries::by_iso3("USA").unwrap().entity_id()
+        );
+        assert_eq!(result.entities[3].properties["latitude"], 39.9042);
+
+        let relations: Vec<(&RelationType, &str)> = result
+            .relationships
+            .iter()
+            .map(|r| (&r.relation_type, r.properties["role"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            relations,
+            vec![
+                (&RelationType::RelatedTo, "actor1"),
+                (&RelationType::RelatedTo, "actor2"),
+                (&RelationType::LocatedAt, "action_geo"),
+            ]
+        );
+        assert!(result
+            .relationships
+            .iter()
+            .all(|r| r.timestamp == doc.observed_at));
+
+        // Same record, same ids: re-collected events update rather than duplicate
+        let again = agent.parse_events(&fields.join("\t"), GdeltStream::English, &HashMap::new());
+        assert_eq!(
+            again[0].structured.as_ref().unwrap().entities[0].id,
+            event.id
+        );
+
+        // A later report of the same event under a new GlobalEventID is the
+        // same Event, counted as another mention by its raw source
+        fields[col::GLOBAL_EVENT_ID] = "1234567999";
+        fields[col::NUM_MENTIONS] = "2";
+        let report = agent.parse_events(&fields.join("\t"), GdeltStream::English, &HashMap::new());
+        let report = report[0].structured.as_ref().unwrap();
+        assert_eq!(report.entities[0].id, event.id);
+        assert_eq!(report.raw_source, "gdelt-event-1234567999");
+        assert_eq!(report.relationships[0].id, result.relationships[0].id);
+
+        // The same actors doing something else that day are another event
+        fields[col::EVENT_CODE] = "19";
+        let other = agent.parse_events(&fields.join("\t"), GdeltStream::English, &HashMap::new());
+        assert_ne!(
+            other[0].structured.as_ref().unwrap().entities[0].id,
+            event.id
+        );
+    }
+
+    #[test]
+    fn test_actor_types_from_cameo_codes() {
+        let at = Utc::now();
+        let meta = |name: &str, code: &str, country: &str, group: &str, types: &[&str]| {
+            json!({
+                "actor1_name": name,
+                "actor1_code": code,
+                "actor1_country_code": country,
+                "actor1_known_group_code": group,
+                "actor1_type_codes": types,
+            })
+        };
+
+        let leader = actor_entity(
+            &meta("VLADIMIR PUTIN", "RUSGOV", "RUS", "", &["GOV"]),
+            "actor1",
+            at,
+        );
+        assert_eq!(leader.entity_type, EntityType::Person);
+        assert_eq!(leader.properties["country"], "Russia");
+        assert_eq!(leader.properties["cameo_types"][0], "government");
+
+        let police = actor_entity(
+            &meta("POLICE OFFICERS", "RUSCOP", "RUS", "", &["COP"]),
+            "actor1",
+            at,
+        );
+        assert_eq!(police.entity_type, EntityType::Organization);
+
+        let city = actor_entity(&meta("MOSCOW", "RUS", "RUS", "", &[]), "actor1", at);
+        assert_eq!(city.entity_type, EntityType::Location);
+        assert_eq!(city.properties["location_type"], "place");
+
+        let nato = actor_entity(&meta("NATO", "NAT", "", "NAT", &[]), "actor1", at);
+        assert_eq!(nato.entity_type, EntityType::Organization);
+        assert_eq!(nato.properties["known_group_code"], "NAT");
+
+        let country = actor_entity(&meta("RUSSIA", "RUS", "RUS", "", &[]), "actor1", at);
+        assert_eq!(country.id, countries::by_iso3("RUS").unwrap().entity_id());
     }
 
     #[test]
@@ -1091,7 +2038,7 @@ mod tests {
         let mut fields = vec![""; GDELT_EVENT_COLUMNS];
         fields[col::GLOBAL_EVENT_ID] = "";
         let line = fields.join("\t");
-        let docs = agent.parse_events(&line);
+        let docs = agent.parse_events(&line, GdeltStream::English, &HashMap::new());
         assert!(docs.is_empty());
     }
 
@@ -1102,11 +2049,10 @@ mod tests {
         fields[col::GLOBAL_EVENT_ID] = "1";
         let line = fields.join("\t");
         // Create more lines than MAX_EVENTS.
-        let csv: String = std::iter::repeat(line.as_str())
-            .take(MAX_EVENTS + 100)
+        let csv: String = std::iter::repeat_n(line.as_str(), MAX_EVENTS + 100)
             .collect::<Vec<_>>()
             .join("\n");
-        let docs = agent.parse_events(&csv);
+        let docs = agent.parse_events(&csv, GdeltStream::English, &HashMap::new());
         assert_eq!(docs.len(), MAX_EVENTS);
     }
 
@@ -0,0 +1,107 @@
+//! Traced HTTP requests to upstream sources. Every agent request goes
+//! through here, which is also where fixtures are recorded and replayed.
+
+use std::future::Future;
+
+use reqwest::{RequestBuilder, Response};
+use tracing::field::Empty;
+use tracing::{info_span, Instrument, Span};
+
+use crate::fixtures::{self, FixtureMode};
+
+pub(crate) trait TracedSend {
+    /// `send`, inside an `http_request` span with the method, the URL
+    /// without its query (some sources take API keys there) and the
+    /// response status. Answered from fixtures in replay mode.
+    fn send_traced(self) -> impl Future<Output = reqwest::Result<Response>> + Send;
+}
+
+impl TracedSend for RequestBuilder {
+    fn send_traced(self) -> impl Future<Output = reqwest::Result<Response>> + Send {
+        let (client, request) = self.build_split();
+        let span = info_span!(
+            "http_request",
+            otel.kind = "client",
+            http.request.method = Empty,
+            url.full = Empty,
+            http.response.status_code = Empty,
+        );
+        if let Ok(request) = &request {
+            let mut url = request.url().clone();
+            url.set_query(None);
+            span.record("http.request.method", request.method().as_str());
+            span.record("url.full", url.as_str());
+        }
+        async move {
+            let request = request?;
+            let fixtures = fixtures::active();
+            let response = match fixtures.mode {
+                FixtureMode::Off => client.execute(request).await?,
+                FixtureMode::Replay => fixtures.replay(&request),
+                FixtureMode::Record => match request.try_clone() {
+                    Some(recorded) => {
+                        fixtures
+                            .record(&recorded, client.execute(request).await?)
+                            .await?
+                    }
+                    // Streamed bodies can't be replayed anyway
+                    None => client.execute(request).await?,
+                },
+            };
+            Span::current().record("http.response.status_code", response.status().as_u16());
+            Ok(response)
+        }
+        .instrument(span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Id, Record};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, Layer};
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+
+    /// Every `url.full` recorded on a span.
+    #[derive(Clone, Default)]
+    struct RecordedUrls(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for RecordedUrls {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "url.full" {
+                self.0.lock().unwrap().push(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl<S: Subscriber> Layer<S> for RecordedUrls {
+        fn on_record(&self, _span: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn spans_record_the_url_without_its_query() {
+        let urls = RecordedUrls::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(urls.clone()));
+
+        // Nothing listens on the discard port; only the span matters
+        let _ = reqwest::Client::new()
+            .get("http://127.0.0.1:9/v1/vessels?api_key=secret&page=2")
+            .send_traced()
+            .await;
+
+        assert_eq!(
+            *urls.0.lock().unwrap(),
+            vec!["http://127.0.0.1:9/v1/vessels".to_string()]
+        );
+    }
+}
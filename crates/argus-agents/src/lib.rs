@@ -5,22 +5,31 @@ use argus_core::Agent;
 
 mod adsb;
 mod ais;
+mod drift;
 mod eu_transparency;
+pub mod fixtures;
 mod gdelt;
+mod http;
 mod opencorporates;
 mod opensanctions;
+mod politeness;
+mod synthetic;
 
 pub use adsb::AdsbAgent;
 pub use ais::AisAgent;
 pub use eu_transparency::EuTransparencyAgent;
-pub use gdelt::GdeltAgent;
+pub use gdelt::{GdeltAgent, GdeltMode};
 pub use opencorporates::OpenCorporatesAgent;
-pub use opensanctions::OpenSanctionsAgent;
+pub use opensanctions::{OpenSanctionsAgent, OpenSanctionsMode};
+pub use synthetic::SyntheticAgent;
 
 pub fn agent_registry() -> HashMap<String, Arc<dyn Agent>> {
     let mut registry: HashMap<String, Arc<dyn Agent>> = HashMap::new();
     registry.insert("gdelt".into(), Arc::new(GdeltAgent::new()));
-    registry.insert("opencorporates".into(), Arc::new(OpenCorporatesAgent::new()));
+    registry.insert(
+        "opencorporates".into(),
+        Arc::new(OpenCorporatesAgent::new()),
+    );
     registry.insert("ais".into(), Arc::new(AisAgent::new()));
     registry.insert("adsb".into(), Arc::new(AdsbAgent::new()));
     registry.insert("opensanctions".into(), Arc::new(OpenSanctionsAgent::new()));
@@ -28,5 +37,6 @@ pub fn agent_registry() -> HashMap<String, Arc<dyn Agent>> {
         "eu_transparency".into(),
         Arc::new(EuTransparencyAgent::new()),
     );
+    registry.insert("synthetic".into(), Arc::new(SyntheticAgent::new()));
     registry
 }
@@ -1,30 +1,75 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
-use reqwest::Client;
-use serde::Deserialize;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
+use uuid::Uuid;
 
-use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument};
-use argus_core::entity::EntityType;
+use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument, SchemaDrift};
+use argus_core::entity::{
+    identifier_scheme, parse_source_date, Entity, EntityType, ExtractionResult, RelationType,
+    Relationship,
+};
 use argus_core::error::{ArgusError, Result};
+use argus_extraction::countries;
+
+use crate::drift::{DriftBaseline, DriftTracker};
+use crate::http::TracedSend;
 
 const OPENCORPORATES_API_BASE: &str = "https://api.opencorporates.com/v0.4";
 
-#[derive(Debug, Deserialize)]
+/// Wait after the first rate-limited response that doesn't say how long to
+/// wait; doubled for each one after, up to [`MAX_BACKOFF_SECONDS`].
+const BASE_BACKOFF_SECONDS: i64 = 60;
+const MAX_BACKOFF_SECONDS: i64 = 24 * 3600;
+
+/// Search hits a lookup fetches the full record (with officers) for.
+const LOOKUP_DETAILS: usize = 3;
+
+/// Namespace for ids of companies and officers taken from the register.
+const ID_NAMESPACE: Uuid = Uuid::from_u128(0x3c8e_52d1_7a4f_4b9e_a1d6_0f2b_94e7_c35a);
+
+/// Legal forms ignored when comparing a looked-up name to a register name.
+const LEGAL_FORMS: &[&str] = &[
+    "ltd",
+    "limited",
+    "llc",
+    "inc",
+    "incorporated",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "plc",
+    "gmbh",
+    "ag",
+    "sa",
+    "sas",
+    "srl",
+    "spa",
+    "bv",
+    "nv",
+    "oy",
+    "ab",
+    "as",
+    "llp",
+    "lp",
+];
+
+#[derive(Debug, Deserialize, Serialize)]
 struct ApiResponse {
     results: ApiResults,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct ApiResults {
     companies: Vec<CompanyWrapper>,
     total_count: Option<u64>,
-    page: Option<u64>,
-    per_page: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct CompanyWrapper {
     company: Company,
 }
@@ -62,6 +107,16 @@ struct Company {
     industry_codes: Vec<serde_json::Value>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CompanyResponse {
+    results: CompanyResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompanyResult {
+    company: Company,
+}
+
 #[derive(Debug, Deserialize, serde::Serialize)]
 struct CompanySource {
     publisher: Option<String>,
@@ -69,10 +124,97 @@ struct CompanySource {
     retrieved_at: Option<String>,
 }
 
+/// Rate-limit state of one API token, or of the public tier when there is
+/// no token.
+#[derive(Debug, Clone, Default)]
+struct TokenLimit {
+    token: Option<String>,
+    /// Calls left, from the last response's `X-RateLimit-Remaining`.
+    remaining: Option<u64>,
+    limited_until: Option<DateTime<Utc>>,
+    /// Rate-limited responses in a row.
+    strikes: u32,
+}
+
+impl TokenLimit {
+    fn available(&self, now: DateTime<Utc>) -> bool {
+        self.limited_until.is_none_or(|until| until <= now)
+    }
+
+    /// Track a response's rate-limit headers. 403 and 429 both mean the
+    /// quota is spent; the public tier answers with 403. Without a reset
+    /// time the token backs off exponentially.
+    fn observe(&mut self, status: StatusCode, headers: &HeaderMap, now: DateTime<Utc>) {
+        let (remaining, reset) = rate_limit_headers(headers, now);
+        self.remaining = remaining.or(self.remaining);
+        if matches!(
+            status,
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+        ) {
+            let backoff = (BASE_BACKOFF_SECONDS << self.strikes.min(20)).min(MAX_BACKOFF_SECONDS);
+            self.limited_until = Some(reset.unwrap_or(now + Duration::seconds(backoff)));
+            self.strikes += 1;
+        } else {
+            self.strikes = 0;
+            self.limited_until = match remaining {
+                Some(0) => Some(reset.unwrap_or(now + Duration::seconds(BASE_BACKOFF_SECONDS))),
+                _ => None,
+            };
+        }
+    }
+}
+
+/// `X-RateLimit-Remaining`, and when the limit resets from
+/// `X-RateLimit-Reset` (epoch seconds, or seconds from now) or
+/// `Retry-After` (seconds or an HTTP date).
+fn rate_limit_headers(
+    headers: &HeaderMap,
+    now: DateTime<Utc>,
+) -> (Option<u64>, Option<DateTime<Utc>>) {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+    };
+    let remaining = header("x-ratelimit-remaining").and_then(|v| v.parse().ok());
+    let reset = header("x-ratelimit-reset")
+        .and_then(|v| v.parse::<i64>().ok())
+        .and_then(|secs| {
+            if secs > 1_000_000_000 {
+                Utc.timestamp_opt(secs, 0).single()
+            } else {
+                Some(now + Duration::seconds(secs))
+            }
+        })
+        .or_else(|| {
+            let retry_after = header(RETRY_AFTER.as_str())?;
+            match retry_after.parse::<i64>() {
+                Ok(secs) => Some(now + Duration::seconds(secs)),
+                Err(_) => DateTime::parse_from_rfc2822(retry_after)
+                    .ok()
+                    .map(|at| at.with_timezone(&Utc)),
+            }
+        });
+    (remaining, reset)
+}
+
+fn rate_limited_message(until: DateTime<Utc>) -> String {
+    format!(
+        "rate limited until {}",
+        until.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    )
+}
+
 struct InternalState {
     last_run: Option<DateTime<Utc>>,
     documents_collected: u64,
     last_error: Option<String>,
+    /// Newest `updated_at` of the companies collected.
+    source_data_timestamp: Option<DateTime<Utc>>,
+    drift_baseline: DriftBaseline,
+    schema_drift: Option<SchemaDrift>,
+    tokens: Vec<TokenLimit>,
 }
 
 pub struct OpenCorporatesAgent {
@@ -80,8 +222,39 @@ pub struct OpenCorporatesAgent {
     state: RwLock<InternalState>,
 }
 
+impl Default for OpenCorporatesAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OpenCorporatesAgent {
     pub fn new() -> Self {
+        let tokens = std::env::var("OPENCORPORATES_API_TOKEN").unwrap_or_default();
+        Self::with_tokens(
+            tokens
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    /// An agent calling the API with `tokens` in turn, moving to the next
+    /// when one is rate limited; the public tier when there are none.
+    pub fn with_tokens(tokens: Vec<String>) -> Self {
+        let mut tokens: Vec<TokenLimit> = tokens
+            .into_iter()
+            .map(|token| TokenLimit {
+                token: Some(token),
+                ..TokenLimit::default()
+            })
+            .collect();
+        if tokens.is_empty() {
+            tokens.push(TokenLimit::default());
+        }
+
         let client = Client::builder()
             .user_agent("argus-intelligence-platform/0.1")
             .timeout(std::time::Duration::from_secs(30))
@@ -94,6 +267,10 @@ impl OpenCorporatesAgent {
                 last_run: None,
                 documents_collected: 0,
                 last_error: None,
+                source_data_timestamp: None,
+                drift_baseline: DriftBaseline::new(),
+                schema_drift: None,
+                tokens,
             }),
         }
     }
@@ -102,7 +279,153 @@ impl OpenCorporatesAgent {
         format!("{}/companies/search", OPENCORPORATES_API_BASE)
     }
 
-    fn company_to_raw_document(&self, company: &Company, collected_at: DateTime<Utc>) -> RawDocument {
+    /// Call the API with the first token that isn't rate limited, trying
+    /// the next when a response says the quota is spent. Fails with "rate
+    /// limited until T" when every token is waiting.
+    async fn get(&self, url: &str, query: &[(&str, &str)]) -> Result<Response> {
+        loop {
+            let (index, token) = {
+                let state = self.state.read().await;
+                let now = Utc::now();
+                match state.tokens.iter().position(|t| t.available(now)) {
+                    Some(index) => (index, state.tokens[index].token.clone()),
+                    None => {
+                        let until = state
+                            .tokens
+                            .iter()
+                            .filter_map(|t| t.limited_until)
+                            .min()
+                            .unwrap_or(now);
+                        return Err(ArgusError::Agent {
+                            agent: "opencorporates".to_string(),
+                            message: rate_limited_message(until),
+                        });
+                    }
+                }
+            };
+
+            let mut request = self.client.get(url).query(query);
+            if let Some(token) = &token {
+                request = request.query(&[("api_token", token)]);
+            }
+            let response = request.send_traced().await.map_err(|e| ArgusError::Agent {
+                agent: "opencorporates".to_string(),
+                message: format!("HTTP request to OpenCorporates failed: {}", e),
+            })?;
+
+            let limit = {
+                let mut state = self.state.write().await;
+                let limit = &mut state.tokens[index];
+                limit.observe(response.status(), response.headers(), Utc::now());
+                limit.clone()
+            };
+            match limit.limited_until {
+                Some(until) if !response.status().is_success() => {
+                    warn!(token = index, until = %until, remaining = ?limit.remaining, "OpenCorporates rate limited");
+                }
+                _ => return Ok(response),
+            }
+        }
+    }
+
+    async fn search(&self, query: &[(&str, &str)]) -> Result<Response> {
+        self.get(&self.build_search_url(), query).await
+    }
+
+    /// A company's full register record, officers included.
+    async fn fetch_company(&self, jurisdiction: &str, number: &str) -> Result<Option<Company>> {
+        let url = format!(
+            "{}/companies/{}/{}",
+            OPENCORPORATES_API_BASE,
+            urlencoding::encode(jurisdiction),
+            urlencoding::encode(number)
+        );
+        let response = self.get(&url, &[]).await?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+        let detail: CompanyResponse = response.json().await.map_err(|e| ArgusError::Agent {
+            agent: "opencorporates".to_string(),
+            message: format!("Company parse failed: {}", e),
+        })?;
+        Ok(Some(detail.results.company))
+    }
+
+    /// Register records for companies named `name`, in `jurisdiction` when
+    /// given, as structured documents. Search hits whose name differs from
+    /// `name` beyond case, punctuation and legal form are dropped; the rest
+    /// are fetched in full for their officers.
+    async fn lookup_companies(
+        &self,
+        name: &str,
+        jurisdiction: Option<&str>,
+    ) -> Result<Vec<RawDocument>> {
+        let collected_at = Utc::now();
+
+        debug!(name = %name, jurisdiction = ?jurisdiction, "OpenCorporates lookup");
+
+        let mut query = vec![("q", name), ("per_page", "5")];
+        if let Some(jurisdiction) = jurisdiction {
+            query.push(("jurisdiction_code", jurisdiction));
+        }
+        let response = self.search(&query).await?;
+
+        if !response.status().is_success() {
+            return Ok(Vec::new());
+        }
+
+        let api_response: ApiResponse = response.json().await.map_err(|e| ArgusError::Agent {
+            agent: "opencorporates".to_string(),
+            message: format!("Lookup parse failed: {}", e),
+        })?;
+
+        let wanted = normalize_company_name(name);
+        let mut docs = Vec::new();
+        for wrapper in api_response
+            .results
+            .companies
+            .into_iter()
+            .take(LOOKUP_DETAILS)
+        {
+            let company = wrapper.company;
+            if company.name.as_deref().map(normalize_company_name) != Some(wanted.clone()) {
+                continue;
+            }
+            let company = match (&company.jurisdiction_code, &company.company_number) {
+                (Some(jurisdiction), Some(number)) => {
+                    match self.fetch_company(jurisdiction, number).await {
+                        Ok(Some(detail)) => detail,
+                        Ok(None) => company,
+                        Err(e) => {
+                            warn!(error = %e, company = ?company.name, "Failed to fetch OpenCorporates company; using the search result");
+                            company
+                        }
+                    }
+                }
+                _ => company,
+            };
+            let mut doc = self.company_to_raw_document(&company, collected_at);
+            let mut result = company_result(&company, &doc.source_id, collected_at);
+            // Under the name it was looked up by, so it lands on the entity that
+            // prompted the lookup; the register's spelling becomes an alias
+            if let Some(organization) = result.entities.first_mut().filter(|o| o.name != name) {
+                let register_name = std::mem::replace(&mut organization.name, name.to_string());
+                organization.aliases.insert(0, register_name);
+            }
+            countries::link_countries(&doc, &mut result);
+            doc.structured = Some(result);
+            docs.push(doc);
+        }
+
+        info!(name = %name, results = docs.len(), "OpenCorporates lookup complete");
+        Ok(docs)
+    }
+
+    fn company_to_raw_document(
+        &self,
+        company: &Company,
+        collected_at: DateTime<Utc>,
+    ) -> RawDocument {
         let source_id = format!(
             "opencorporates:{}:{}",
             company.jurisdiction_code.as_deref().unwrap_or("unknown"),
@@ -137,11 +460,136 @@ impl OpenCorporatesAgent {
             content,
             url,
             collected_at,
+            observed_at: company
+                .incorporation_date
+                .as_deref()
+                .and_then(parse_source_date),
+            identifiers: company
+                .opencorporates_url
+                .iter()
+                .map(|url| (identifier_scheme::OPENCORPORATES.to_string(), url.clone()))
+                .collect(),
             metadata,
+            structured: None,
         }
     }
 }
 
+/// Lowercase words of a company name without punctuation or trailing legal
+/// forms: "ACME Shipping Co., Ltd." and "Acme Shipping" compare equal.
+fn normalize_company_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    while words.len() > 1 && words.last().is_some_and(|w| LEGAL_FORMS.contains(w)) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// A register record as entities: the company, and each officer linked to
+/// it (`DIRECTOR_OF` for directors, `EMPLOYEE_OF` for other positions).
+fn company_result(company: &Company, source_id: &str, now: DateTime<Utc>) -> ExtractionResult {
+    let name = company
+        .name
+        .clone()
+        .unwrap_or_else(|| source_id.to_string());
+    let mut organization =
+        Entity::new(EntityType::Organization, name, "opencorporates".to_string());
+    organization.id = Uuid::new_v5(&ID_NAMESPACE, source_id.as_bytes());
+    organization.source_id = Some(source_id.to_string());
+    organization.aliases = company
+        .previous_names
+        .iter()
+        .chain(&company.alternative_names)
+        .filter_map(|n| n["company_name"].as_str().map(String::from))
+        .collect();
+    organization.properties = serde_json::json!({
+        "jurisdiction_code": company.jurisdiction_code,
+        "company_number": company.company_number,
+        "company_type": company.company_type,
+        "incorporation_date": company.incorporation_date,
+        "dissolution_date": company.dissolution_date,
+        "current_status": company.current_status,
+        "registered_address": company.registered_address_in_full,
+        "registry_url": company.registry_url,
+    });
+    organization.observed_at = company
+        .incorporation_date
+        .as_deref()
+        .and_then(parse_source_date);
+    if let Some(url) = &company.opencorporates_url {
+        organization
+            .identifiers
+            .insert(identifier_scheme::OPENCORPORATES.to_string(), url.clone());
+    }
+
+    let mut entities = Vec::new();
+    let mut relationships = Vec::new();
+    for officer in company
+        .officers
+        .iter()
+        .map(|o| o.get("officer").unwrap_or(o))
+    {
+        let Some(officer_name) = officer["name"].as_str().filter(|n| !n.trim().is_empty()) else {
+            continue;
+        };
+        let officer_id = match &officer["id"] {
+            serde_json::Value::Number(id) => format!("opencorporates:officer:{id}"),
+            _ => format!("{source_id}:officer:{}", officer_name.to_lowercase()),
+        };
+        let mut person = Entity::new(
+            EntityType::Person,
+            officer_name.trim().to_string(),
+            "opencorporates".to_string(),
+        );
+        person.id = Uuid::new_v5(&ID_NAMESPACE, officer_id.as_bytes());
+        person.source_id = Some(officer_id);
+        person.properties = serde_json::json!({
+            "nationality": officer["nationality"],
+            "occupation": officer["occupation"],
+        });
+
+        let position = officer["position"].as_str().unwrap_or_default();
+        let relation_type = if position.to_lowercase().contains("director") {
+            RelationType::DirectorOf
+        } else {
+            RelationType::EmployeeOf
+        };
+        let mut relationship = Relationship::new(
+            person.id,
+            organization.id,
+            relation_type,
+            "opencorporates".to_string(),
+        );
+        relationship.properties = serde_json::json!({
+            "position": position,
+            "start_date": officer["start_date"],
+            "end_date": officer["end_date"],
+            "inactive": officer["inactive"],
+        });
+        relationship.timestamp = officer["start_date"].as_str().and_then(parse_source_date);
+        relationships.push(relationship);
+        entities.push(person);
+    }
+    entities.insert(0, organization);
+
+    ExtractionResult {
+        entities,
+        relationships,
+        raw_source: source_id.to_string(),
+        extracted_at: now,
+    }
+}
+
 #[async_trait]
 impl Agent for OpenCorporatesAgent {
     fn name(&self) -> &str {
@@ -171,25 +619,24 @@ impl Agent for OpenCorporatesAgent {
             "Fetching companies from OpenCorporates API"
         );
 
-        let response = self
-            .client
-            .get(&url)
-            .query(&[
+        let response = match self
+            .search(&[
                 ("q", "*"),
                 ("order", "updated_at"),
                 ("updated_since", &since),
                 ("per_page", "100"),
             ])
-            .send()
             .await
-            .map_err(|e| {
-                let msg = format!("HTTP request to OpenCorporates failed: {}", e);
-                error!(%msg);
-                ArgusError::Agent {
-                    agent: "opencorporates".to_string(),
-                    message: msg,
-                }
-            })?;
+        {
+            Ok(response) => response,
+            Err(e) => {
+                error!(error = %e, "OpenCorporates request failed");
+                let mut state = self.state.write().await;
+                state.last_run = Some(Utc::now());
+                state.last_error = Some(e.to_string());
+                return Err(e);
+            }
+        };
 
         let status = response.status();
         if !status.is_success() {
@@ -211,14 +658,20 @@ impl Agent for OpenCorporatesAgent {
             });
         }
 
-        let api_response: ApiResponse = response.json().await.map_err(|e| {
-            let msg = format!("Failed to parse OpenCorporates response: {}", e);
-            error!(%msg);
-            ArgusError::Agent {
-                agent: "opencorporates".to_string(),
-                message: msg,
-            }
-        })?;
+        let mut drift = DriftTracker::default();
+        let api_response: ApiResponse = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|body| drift.decode(body).map_err(|e| e.to_string()))
+            .map_err(|e| {
+                let msg = format!("Failed to parse OpenCorporates response: {}", e);
+                error!(%msg);
+                ArgusError::Agent {
+                    agent: "opencorporates".to_string(),
+                    message: msg,
+                }
+            })?;
 
         let total_count = api_response.results.total_count.unwrap_or(0);
         let companies = api_response.results.companies;
@@ -246,11 +699,27 @@ impl Agent for OpenCorporatesAgent {
             "Converted companies to RawDocuments"
         );
 
+        let newest = companies
+            .iter()
+            .filter_map(|wrapper| {
+                wrapper
+                    .company
+                    .updated_at
+                    .as_deref()
+                    .and_then(parse_source_date)
+            })
+            .max();
+
         // Update internal state
         let mut state = self.state.write().await;
         state.last_run = Some(Utc::now());
         state.documents_collected += documents.len() as u64;
+        state.source_data_timestamp = newest.or(state.source_data_timestamp);
         state.last_error = None;
+        state.schema_drift = drift.finish(&mut state.drift_baseline);
+        if let Some(drift) = &state.schema_drift {
+            warn!(unknown = ?drift.unknown_fields, missing = ?drift.missing_fields, "OpenCorporates response schema drifted");
+        }
 
         info!(
             documents_collected = documents.len(),
@@ -269,9 +738,16 @@ impl Agent for OpenCorporatesAgent {
             last_run: state.last_run,
             documents_collected: state.documents_collected,
             error: state.last_error.clone(),
+            source_data_timestamp: state.source_data_timestamp,
+            schema_drift: state.schema_drift.clone(),
+            capabilities: None,
         }
     }
 
+    fn as_lookup(&self) -> Option<&dyn AgentLookup> {
+        Some(self)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -284,42 +760,25 @@ impl AgentLookup for OpenCorporatesAgent {
     }
 
     async fn lookup(&self, name: &str, _entity_type: &EntityType) -> Result<Vec<RawDocument>> {
-        let url = self.build_search_url();
-        let collected_at = Utc::now();
-
-        debug!(name = %name, "OpenCorporates lookup");
-
-        let response = self
-            .client
-            .get(&url)
-            .query(&[("q", name), ("per_page", "5")])
-            .send()
-            .await
-            .map_err(|e| ArgusError::Agent {
-                agent: "opencorporates".to_string(),
-                message: format!("Lookup HTTP request failed: {}", e),
-            })?;
-
-        if !response.status().is_success() {
-            return Ok(Vec::new());
-        }
-
-        let api_response: ApiResponse = response.json().await.map_err(|e| {
-            ArgusError::Agent {
-                agent: "opencorporates".to_string(),
-                message: format!("Lookup parse failed: {}", e),
-            }
-        })?;
+        self.lookup_companies(name, None).await
+    }
 
-        let docs: Vec<RawDocument> = api_response
-            .results
-            .companies
+    /// Search within the entity's jurisdiction when its properties name one
+    /// (`jurisdiction_code`, `jurisdiction` or `country`).
+    async fn lookup_entity(&self, entity: &Entity) -> Result<Vec<RawDocument>> {
+        let jurisdiction = ["jurisdiction_code", "jurisdiction", "country"]
             .iter()
-            .map(|wrapper| self.company_to_raw_document(&wrapper.company, collected_at))
-            .collect();
-
-        info!(name = %name, results = docs.len(), "OpenCorporates lookup complete");
-        Ok(docs)
+            .filter_map(|key| entity.properties[*key].as_str())
+            .find_map(|value| {
+                let value = value.trim();
+                if countries::by_jurisdiction(value).is_some() {
+                    Some(value.to_lowercase())
+                } else {
+                    countries::by_name(value).map(|c| c.iso2.to_lowercase())
+                }
+            });
+        self.lookup_companies(&entity.name, jurisdiction.as_deref())
+            .await
     }
 }
 
@@ -370,7 +829,9 @@ mod tests {
             created_at: Some("2020-01-01T00:00:00+00:00".to_string()),
             updated_at: Some("2025-01-01T00:00:00+00:00".to_string()),
             retrieved_at: Some("2025-06-01T00:00:00+00:00".to_string()),
-            opencorporates_url: Some("https://opencorporates.com/companies/us_de/12345".to_string()),
+            opencorporates_url: Some(
+                "https://opencorporates.com/companies/us_de/12345".to_string(),
+            ),
             registered_address_in_full: Some("123 Main St, Dover, DE".to_string()),
             source: None,
             previous_names: vec![],
@@ -397,4 +858,100 @@ mod tests {
         assert_eq!(doc.metadata["current_status"], "Active");
         assert_eq!(doc.metadata["inactive"], false);
     }
+
+    #[test]
+    fn rate_limit_headers_set_the_reset_time() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut limit = TokenLimit::default();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1740834000".parse().unwrap());
+        limit.observe(StatusCode::OK, &headers, now);
+        assert_eq!(limit.remaining, Some(0));
+        assert_eq!(
+            limit.limited_until,
+            Some(Utc.with_ymd_and_hms(2025, 3, 1, 13, 0, 0).unwrap())
+        );
+        assert!(!limit.available(now));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "120".parse().unwrap());
+        limit.observe(StatusCode::TOO_MANY_REQUESTS, &headers, now);
+        assert_eq!(limit.limited_until, Some(now + Duration::seconds(120)));
+        assert_eq!(
+            rate_limited_message(limit.limited_until.unwrap()),
+            "rate limited until 2025-03-01T12:02:00Z"
+        );
+    }
+
+    #[test]
+    fn rate_limits_without_a_reset_back_off_exponentially() {
+        let now = Utc::now();
+        let mut limit = TokenLimit::default();
+        limit.observe(StatusCode::FORBIDDEN, &HeaderMap::new(), now);
+        assert_eq!(limit.limited_until, Some(now + Duration::seconds(60)));
+        limit.observe(StatusCode::FORBIDDEN, &HeaderMap::new(), now);
+        assert_eq!(limit.limited_until, Some(now + Duration::seconds(120)));
+
+        limit.observe(StatusCode::OK, &HeaderMap::new(), now);
+        assert_eq!((limit.strikes, limit.limited_until), (0, None));
+    }
+
+    #[test]
+    fn company_names_compare_without_legal_form() {
+        assert_eq!(
+            normalize_company_name("ACME Shipping Co., Ltd."),
+            "acme shipping"
+        );
+        assert_eq!(normalize_company_name("Acme Shipping"), "acme shipping");
+        assert_ne!(
+            normalize_company_name("Acme Shipping Holdings"),
+            "acme shipping"
+        );
+        assert_eq!(normalize_company_name("Limited"), "limited");
+    }
+
+    #[test]
+    fn company_result_links_officers() {
+        let company: Company = serde_json::from_value(serde_json::json!({
+            "name": "ACME SHIPPING LTD",
+            "company_number": "01234567",
+            "jurisdiction_code": "gb",
+            "incorporation_date": "2001-05-04",
+            "opencorporates_url": "https://opencorporates.com/companies/gb/01234567",
+            "previous_names": [{"company_name": "ACME FREIGHT LTD"}],
+            "officers": [
+                {"officer": {"id": 42, "name": "JANE ROE", "position": "director", "start_date": "2010-01-01"}},
+                {"officer": {"id": 43, "name": "JOHN DOE", "position": "secretary"}}
+            ]
+        }))
+        .unwrap();
+
+        let result = company_result(&company, "opencorporates:gb:01234567", Utc::now());
+        assert_eq!(result.entities.len(), 3);
+        let organization = &result.entities[0];
+        assert_eq!(organization.entity_type, EntityType::Organization);
+        assert_eq!(organization.aliases, vec!["ACME FREIGHT LTD".to_string()]);
+        assert_eq!(
+            organization.identifiers[identifier_scheme::OPENCORPORATES],
+            "https://opencorporates.com/companies/gb/01234567"
+        );
+
+        let director = &result.relationships[0];
+        assert_eq!(director.relation_type, RelationType::DirectorOf);
+        assert_eq!(
+            (director.source_entity_id, director.target_entity_id),
+            (result.entities[1].id, organization.id)
+        );
+        assert!(director.timestamp.is_some());
+        assert_eq!(
+            result.relationships[1].relation_type,
+            RelationType::EmployeeOf
+        );
+        assert_eq!(
+            result.entities[2].source_id.as_deref(),
+            Some("opencorporates:officer:43")
+        );
+    }
 }
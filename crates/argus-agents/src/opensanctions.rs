@@ -1,30 +1,100 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use reqwest::Client;
-use serde::Deserialize;
+use futures::{stream, StreamExt};
+use reqwest::header::{ETAG, IF_RANGE, RANGE};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
-use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument};
-use argus_core::entity::EntityType;
+use argus_core::agent::{Agent, AgentLookup, AgentStatus, RawDocument, SchemaDrift};
+use argus_core::entity::{
+    identifier_scheme, parse_source_date, Entity, EntityType, ExtractionResult, RelationType,
+    Relationship,
+};
 use argus_core::error::{ArgusError, Result};
+use argus_core::export::ftm::{self, FtmEntity};
+
+use crate::drift::{DriftBaseline, DriftTracker};
+use crate::http::TracedSend;
+use crate::politeness::{Pacer, Politeness};
 
-const OPENSANCTIONS_API_URL: &str = "https://api.opensanctions.org/entities";
+const OPENSANCTIONS_API_URL: &str = "https://api.opensanctions.org";
 const DEFAULT_DATASET: &str = "default";
 const PAGE_LIMIT: u32 = 100;
+/// Last offset the search API serves.
+const MAX_OFFSET: u32 = 10_000;
+/// Pages in flight and spacing between requests unless
+/// `AGENT_MAX_CONCURRENT_REQUESTS` / `AGENT_REQUEST_INTERVAL_MS` say otherwise.
+const DEFAULT_POLITENESS: Politeness = Politeness {
+    max_concurrent: 4,
+    min_interval: Duration::from_millis(100),
+};
+/// Collection lookups are matched against; unlike `default` it holds only
+/// sanctioned entities, not PEPs and their relatives.
+const DEFAULT_MATCH_DATASET: &str = "sanctions";
+const DEFAULT_MATCH_THRESHOLD: f64 = 0.7;
+const MATCH_LIMIT: u32 = 5;
+const DEFAULT_BULK_URL: &str =
+    "https://data.opensanctions.org/datasets/latest/default/entities.ftm.json";
+const DEFAULT_BULK_MAX_ENTITIES: usize = 20_000;
+/// The bulk export runs to gigabytes; one run may spend this long reading it.
+const BULK_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 
-#[derive(Debug, Deserialize)]
+/// Non-link schemata kept from the bulk export. Addresses, identifications,
+/// positions and the like are skipped; their values are already on the
+/// entities they describe.
+const BULK_SCHEMATA: &[&str] = &[
+    "Person",
+    "Company",
+    "Organization",
+    "LegalEntity",
+    "PublicBody",
+    "Vessel",
+    "Airplane",
+    "Sanction",
+];
+
+/// How OpenSanctions records are collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenSanctionsMode {
+    /// Pages of the search API, written up for LLM extraction. The API
+    /// stops at 10,000 offsets, so most of the dataset is never reached.
+    Api,
+    /// The dataset's FtM bulk export, streamed and mapped to entities and
+    /// relationships directly.
+    Bulk,
+}
+
+impl OpenSanctionsMode {
+    /// From `OPENSANCTIONS_MODE` (`api` or `bulk`); api when unset.
+    fn from_env() -> Self {
+        match std::env::var("OPENSANCTIONS_MODE")
+            .ok()
+            .as_deref()
+            .map(str::trim)
+        {
+            Some(mode) if mode.eq_ignore_ascii_case("bulk") => Self::Bulk,
+            Some(mode) if !mode.is_empty() && !mode.eq_ignore_ascii_case("api") => {
+                warn!(mode, "Unknown OPENSANCTIONS_MODE, using api");
+                Self::Api
+            }
+            _ => Self::Api,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 struct OpenSanctionsResponse {
     results: Vec<SanctionEntity>,
     #[serde(default)]
     total: Option<u64>,
-    #[serde(default)]
-    limit: Option<u32>,
-    #[serde(default)]
-    offset: Option<u32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct SanctionEntity {
     id: String,
     #[serde(default)]
@@ -49,21 +119,174 @@ struct SanctionEntity {
     target: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+struct MatchResponse {
+    #[serde(default)]
+    responses: HashMap<String, MatchQueryResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MatchQueryResponse {
+    #[serde(default)]
+    results: Vec<MatchResult>,
+}
+
+/// One candidate from the match API, scored against the query.
+#[derive(Debug, Deserialize)]
+struct MatchResult {
+    #[serde(flatten)]
+    entity: FtmEntity,
+    score: f64,
+}
+
+/// One line of the bulk export.
+#[derive(Debug, Deserialize)]
+struct BulkRecord {
+    #[serde(flatten)]
+    entity: FtmEntity,
+    #[serde(default)]
+    last_change: Option<String>,
+}
+
+/// Progress through the bulk export, kept between runs. A pass reads the
+/// export front to back over as many runs as the per-run cap needs, and
+/// only emits records changed since the previous pass.
+#[derive(Debug, Default)]
+struct BulkCursor {
+    /// Bytes of the export consumed in the current pass.
+    offset: u64,
+    /// ETag of the export the current pass is reading.
+    etag: Option<String>,
+    /// Newest `last_change` of the last complete pass.
+    watermark: Option<String>,
+    /// Newest `last_change` seen so far in the current pass.
+    pass_newest: Option<String>,
+    /// Whether the current pass has reached the end of the export.
+    read: bool,
+    /// Links and sanctions, held back until the export is read so the
+    /// entities they point at are written first.
+    deferred: Vec<BulkRecord>,
+}
+
+impl BulkCursor {
+    /// The record on `line` if it should be emitted now. Unchanged records
+    /// and skipped schemata give `None`, as do links and sanctions, which
+    /// are deferred.
+    fn record(&mut self, line: &[u8]) -> Option<BulkRecord> {
+        let line = line.trim_ascii();
+        if line.is_empty() {
+            return None;
+        }
+        let record: BulkRecord = match serde_json::from_slice(line) {
+            Ok(record) => record,
+            Err(e) => {
+                debug!(error = %e, offset = self.offset, "Skipping unparseable OpenSanctions export line");
+                return None;
+            }
+        };
+        if let Some(change) = &record.last_change {
+            if self
+                .pass_newest
+                .as_ref()
+                .is_none_or(|newest| change > newest)
+            {
+                self.pass_newest = Some(change.clone());
+            }
+            if self
+                .watermark
+                .as_ref()
+                .is_some_and(|watermark| change <= watermark)
+            {
+                return None;
+            }
+        }
+        let schema = record.entity.schema.as_str();
+        if ftm::is_link_schema(schema) || schema == "Sanction" {
+            self.deferred.push(record);
+            None
+        } else if BULK_SCHEMATA.contains(&schema) {
+            Some(record)
+        } else {
+            None
+        }
+    }
+
+    /// Start the next pass, emitting only what changes after this one.
+    fn finish_pass(&mut self) {
+        self.watermark = self.pass_newest.take().max(self.watermark.take());
+        self.offset = 0;
+        self.etag = None;
+        self.read = false;
+    }
+}
+
 #[derive(Debug)]
 struct InternalState {
     enabled: bool,
     last_run: Option<DateTime<Utc>>,
     documents_collected: u64,
     last_error: Option<String>,
+    /// Newest `last_change` of the records read.
+    source_data_timestamp: Option<DateTime<Utc>>,
+    drift_baseline: DriftBaseline,
+    schema_drift: Option<SchemaDrift>,
+    bulk: BulkCursor,
 }
 
 pub struct OpenSanctionsAgent {
     client: Client,
     state: RwLock<InternalState>,
+    mode: OpenSanctionsMode,
+    bulk_url: String,
+    /// Entities emitted per run in bulk mode; 0 for no cap.
+    bulk_max_entities: usize,
+    api_key: Option<String>,
+    match_dataset: String,
+    /// Lowest match score that counts as a hit.
+    match_threshold: f64,
+    /// Base of the search and match endpoints.
+    api_url: String,
+    /// Limits on concurrent search API page requests.
+    politeness: Politeness,
+}
+
+impl Default for OpenSanctionsAgent {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl OpenSanctionsAgent {
     pub fn new() -> Self {
+        let bulk_url = std::env::var("OPENSANCTIONS_BULK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_BULK_URL.to_string());
+        let bulk_max_entities = std::env::var("OPENSANCTIONS_BULK_MAX_ENTITIES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_BULK_MAX_ENTITIES);
+        let mut agent = Self::with_mode(OpenSanctionsMode::from_env(), bulk_url, bulk_max_entities);
+        agent.api_key = std::env::var("OPENSANCTIONS_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+        if let Some(dataset) = std::env::var("OPENSANCTIONS_MATCH_DATASET")
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+        {
+            agent.match_dataset = dataset;
+        }
+        if let Some(threshold) = std::env::var("OPENSANCTIONS_MATCH_THRESHOLD")
+            .ok()
+            .and_then(|s| s.parse().ok())
+        {
+            agent.match_threshold = threshold;
+        }
+        agent.politeness = Politeness::from_env("opensanctions", DEFAULT_POLITENESS);
+        agent
+    }
+
+    pub fn with_mode(mode: OpenSanctionsMode, bulk_url: String, bulk_max_entities: usize) -> Self {
         let client = Client::builder()
             .user_agent("argus-osint/0.1")
             .timeout(std::time::Duration::from_secs(60))
@@ -77,7 +300,33 @@ impl OpenSanctionsAgent {
                 last_run: None,
                 documents_collected: 0,
                 last_error: None,
+                source_data_timestamp: None,
+                drift_baseline: DriftBaseline::new(),
+                schema_drift: None,
+                bulk: BulkCursor::default(),
             }),
+            mode,
+            bulk_url,
+            bulk_max_entities,
+            api_key: None,
+            match_dataset: DEFAULT_MATCH_DATASET.to_string(),
+            match_threshold: DEFAULT_MATCH_THRESHOLD,
+            api_url: OPENSANCTIONS_API_URL.to_string(),
+            politeness: DEFAULT_POLITENESS,
+        }
+    }
+
+    /// Send API requests to `api_url` instead of api.opensanctions.org,
+    /// e.g. a self-hosted yente instance or a test server.
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.header(reqwest::header::AUTHORIZATION, format!("ApiKey {key}")),
+            None => request,
         }
     }
 
@@ -120,10 +369,28 @@ impl OpenSanctionsAgent {
             "caption": entity.caption,
         });
 
-        let url = format!(
-            "https://api.opensanctions.org/entities/{}",
-            entity.id
+        let url = format!("https://api.opensanctions.org/entities/{}", entity.id);
+
+        let mut identifiers = std::collections::BTreeMap::new();
+        identifiers.insert(
+            identifier_scheme::OPENSANCTIONS.to_string(),
+            entity.id.clone(),
         );
+        for (property, scheme) in [
+            ("leiCode", identifier_scheme::LEI),
+            ("wikidataId", identifier_scheme::WIKIDATA),
+        ] {
+            let value = entity
+                .properties
+                .as_ref()
+                .and_then(|p| p.get(property))
+                .and_then(|v| v.as_array())
+                .and_then(|values| values.first())
+                .and_then(|v| v.as_str());
+            if let Some(value) = value {
+                identifiers.insert(scheme.to_string(), value.to_string());
+            }
+        }
 
         RawDocument {
             source: "opensanctions".to_string(),
@@ -132,24 +399,42 @@ impl OpenSanctionsAgent {
             content,
             url: Some(url),
             collected_at: Utc::now(),
+            observed_at: entity.first_seen.as_deref().and_then(parse_source_date),
+            identifiers,
             metadata,
+            structured: None,
         }
     }
 
-    async fn fetch_page(&self, offset: u32, limit: u32) -> Result<OpenSanctionsResponse> {
+    /// Allow `max_concurrent` page requests in flight, started at least
+    /// `min_interval` apart.
+    pub fn with_page_concurrency(mut self, max_concurrent: usize, min_interval: Duration) -> Self {
+        self.politeness = Politeness {
+            max_concurrent: max_concurrent.max(1),
+            min_interval,
+        };
+        self
+    }
+
+    /// One page of the search API as sent; decoded in page order by
+    /// [`decode_page`], so drift tracking sees pages as a serial run would.
+    async fn fetch_page(&self, offset: u32, pacer: &Pacer) -> Result<serde_json::Value> {
         let url = format!(
-            "{}?dataset={}&limit={}&offset={}",
-            OPENSANCTIONS_API_URL, DEFAULT_DATASET, limit, offset
+            "{}/entities?dataset={}&limit={}&offset={}",
+            self.api_url, DEFAULT_DATASET, PAGE_LIMIT, offset
         );
 
+        pacer.wait().await;
         debug!(url = %url, "Fetching OpenSanctions page");
 
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            ArgusError::Agent {
+        let response = self
+            .authorized(self.client.get(&url))
+            .send_traced()
+            .await
+            .map_err(|e| ArgusError::Agent {
                 agent: "opensanctions".to_string(),
                 message: format!("HTTP request failed: {}", e),
-            }
-        })?;
+            })?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -164,14 +449,395 @@ impl OpenSanctionsAgent {
             });
         }
 
-        let data: OpenSanctionsResponse = response.json().await.map_err(|e| {
-            ArgusError::Agent {
+        response.json().await.map_err(|e| ArgusError::Agent {
+            agent: "opensanctions".to_string(),
+            message: format!("Failed to parse response JSON: {}", e),
+        })
+    }
+
+    /// Every page of the search API, in order. The first page gives the
+    /// total; the rest are fetched `max_concurrent` at a time and taken in
+    /// offset order until a short page or the API's offset limit.
+    async fn fetch_pages(&self, drift: &mut DriftTracker) -> Result<Vec<SanctionEntity>> {
+        let pacer = self.politeness.pacer();
+        let first = decode_page(drift, self.fetch_page(0, &pacer).await?)?;
+        debug!(results = first.results.len(), total = ?first.total, "Fetched first OpenSanctions page");
+        let mut entities = first.results;
+        if entities.len() < PAGE_LIMIT as usize {
+            return Ok(entities);
+        }
+
+        let last_offset = first.total.map_or(MAX_OFFSET, |total| {
+            total.saturating_sub(1).min(MAX_OFFSET as u64) as u32
+        });
+        let mut pages = stream::iter((PAGE_LIMIT..=last_offset).step_by(PAGE_LIMIT as usize))
+            .map(|offset| {
+                let pacer = &pacer;
+                async move { (offset, self.fetch_page(offset, pacer).await) }
+            })
+            .buffered(self.politeness.max_concurrent);
+
+        while let Some((offset, page)) = pages.next().await {
+            let page = page
+                .and_then(|body| decode_page(drift, body))
+                .inspect_err(|e| {
+                    error!(error = %e, offset = offset, "Failed to fetch OpenSanctions page");
+                })?;
+            let result_count = page.results.len();
+            debug!(
+                offset = offset,
+                results = result_count,
+                "Fetched OpenSanctions page"
+            );
+            entities.extend(page.results);
+            if result_count < PAGE_LIMIT as usize {
+                break;
+            }
+            if offset == MAX_OFFSET {
+                warn!(
+                    offset = offset,
+                    "Reached the search API's offset limit, stopping collection"
+                );
+            }
+        }
+        Ok(entities)
+    }
+
+    /// Candidates for `names` from the match API that score at least the
+    /// threshold, best first.
+    async fn match_candidates(
+        &self,
+        names: &[String],
+        entity_type: &EntityType,
+    ) -> Result<Vec<MatchResult>> {
+        let url = format!(
+            "{}/match/{}?limit={}",
+            self.api_url, self.match_dataset, MATCH_LIMIT
+        );
+        let body = serde_json::json!({
+            "queries": {
+                "q": {
+                    "schema": ftm::schema_for(entity_type),
+                    "properties": {"name": names},
+                }
+            }
+        });
+
+        debug!(url = %url, names = ?names, "OpenSanctions match");
+
+        let response = self
+            .authorized(self.client.post(&url))
+            .json(&body)
+            .send_traced()
+            .await
+            .map_err(|e| ArgusError::Agent {
                 agent: "opensanctions".to_string(),
-                message: format!("Failed to parse response JSON: {}", e),
+                message: format!("Match HTTP request failed: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(ArgusError::Agent {
+                agent: "opensanctions".to_string(),
+                message: format!("Match API returned HTTP {}", response.status()),
+            });
+        }
+
+        let data: MatchResponse = response.json().await.map_err(|e| ArgusError::Agent {
+            agent: "opensanctions".to_string(),
+            message: format!("Match parse failed: {}", e),
+        })?;
+
+        let mut candidates: Vec<MatchResult> = data
+            .responses
+            .into_values()
+            .flat_map(|response| response.results)
+            .filter(|candidate| candidate.score >= self.match_threshold)
+            .collect();
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        Ok(candidates)
+    }
+
+    /// One bulk-mode run: read on from where the last run stopped, and once
+    /// the export is read, emit the deferred links and sanctions.
+    async fn collect_bulk(&self, cursor: &mut BulkCursor) -> Result<Vec<RawDocument>> {
+        let now = Utc::now();
+        let cap = if self.bulk_max_entities == 0 {
+            usize::MAX
+        } else {
+            self.bulk_max_entities
+        };
+        let mut documents = Vec::new();
+        if !cursor.read {
+            self.read_bulk(cursor, cap, &mut documents, now).await?;
+        }
+        if cursor.read {
+            let take = cap
+                .saturating_sub(documents.len())
+                .min(cursor.deferred.len());
+            documents.extend(
+                cursor
+                    .deferred
+                    .drain(..take)
+                    .map(|record| bulk_document(&record, now)),
+            );
+            if cursor.deferred.is_empty() {
+                cursor.finish_pass();
+            }
+        }
+        Ok(documents)
+    }
+
+    /// Stream the export from `cursor.offset` until `cap` documents are
+    /// collected or the export ends. A download that breaks off keeps what
+    /// was read; the next run resumes there.
+    async fn read_bulk(
+        &self,
+        cursor: &mut BulkCursor,
+        cap: usize,
+        documents: &mut Vec<RawDocument>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut request = self.client.get(&self.bulk_url).timeout(BULK_TIMEOUT);
+        if cursor.offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", cursor.offset));
+            if let Some(etag) = &cursor.etag {
+                request = request.header(IF_RANGE, etag);
             }
+        }
+        debug!(url = %self.bulk_url, offset = cursor.offset, "Fetching OpenSanctions bulk export");
+
+        let mut response = request.send_traced().await.map_err(|e| ArgusError::Agent {
+            agent: "opensanctions".to_string(),
+            message: format!("Bulk export request failed: {}", e),
         })?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            cursor.read = true;
+            return Ok(());
+        }
+        if !response.status().is_success() {
+            return Err(ArgusError::Agent {
+                agent: "opensanctions".to_string(),
+                message: format!("Bulk export returned HTTP {}", response.status()),
+            });
+        }
 
-        Ok(data)
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let mut skip = 0;
+        if cursor.offset > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+            if etag == cursor.etag {
+                // Same export, but the server ignored the range
+                skip = cursor.offset;
+            } else {
+                info!(
+                    offset = cursor.offset,
+                    "OpenSanctions export changed mid-pass, reading it from the start"
+                );
+                cursor.offset = 0;
+            }
+        }
+        cursor.etag = etag;
+
+        let mut buffer: Vec<u8> = Vec::new();
+        loop {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!(error = %e, offset = cursor.offset, "OpenSanctions export download broke off");
+                    return Ok(());
+                }
+            };
+            let mut chunk = &chunk[..];
+            if skip > 0 {
+                let skipped = skip.min(chunk.len() as u64);
+                chunk = &chunk[skipped as usize..];
+                skip -= skipped;
+            }
+            buffer.extend_from_slice(chunk);
+
+            let mut start = 0;
+            while let Some(end) = buffer[start..].iter().position(|b| *b == b'\n') {
+                let line = &buffer[start..start + end];
+                start += end + 1;
+                cursor.offset += end as u64 + 1;
+                if let Some(record) = cursor.record(line) {
+                    documents.push(bulk_document(&record, now));
+                    if documents.len() >= cap {
+                        return Ok(());
+                    }
+                }
+            }
+            buffer.drain(..start);
+        }
+        cursor.offset += buffer.len() as u64;
+        if let Some(record) = cursor.record(&buffer) {
+            documents.push(bulk_document(&record, now));
+        }
+        cursor.read = true;
+        Ok(())
+    }
+}
+
+/// A match as a structured document: a Sanction for the listing, linked
+/// `SANCTIONED_BY` from the screened entity, or from the listed entity
+/// itself when nothing was screened.
+///
+/// The screened entity is written again unchanged so the edge follows it
+/// onto whatever node it was merged into.
+fn decode_page(drift: &mut DriftTracker, body: serde_json::Value) -> Result<OpenSanctionsResponse> {
+    drift.decode(body).map_err(|e| ArgusError::Agent {
+        agent: "opensanctions".to_string(),
+        message: format!("Failed to parse response JSON: {}", e),
+    })
+}
+
+fn match_document(
+    candidate: &MatchResult,
+    screened: Option<&Entity>,
+    now: DateTime<Utc>,
+) -> RawDocument {
+    let listed = &candidate.entity;
+    let (imported, _) = ftm::import(std::slice::from_ref(listed), "opensanctions", now);
+    let caption = imported
+        .entities
+        .first()
+        .map(|entity| entity.name.clone())
+        .unwrap_or_else(|| listed.id.clone());
+
+    let listing_id = format!("{}:listing", listed.id);
+    let mut sanction = Entity::new(
+        EntityType::Sanction,
+        format!("OpenSanctions listing: {caption}"),
+        "opensanctions".to_string(),
+    );
+    sanction.id = ftm::entity_uuid(&listing_id);
+    sanction.source_id = Some(listing_id);
+    sanction.properties = serde_json::json!({
+        "opensanctions_id": listed.id,
+        "schema": listed.schema,
+        "caption": caption,
+        "datasets": listed.datasets,
+        "topics": listed.values("topics"),
+    });
+    sanction.identifiers.insert(
+        identifier_scheme::OPENSANCTIONS.to_string(),
+        listed.id.clone(),
+    );
+
+    let subject = match screened {
+        Some(entity) => entity.clone(),
+        None => match imported.entities.into_iter().next() {
+            Some(entity) => entity,
+            None => Entity::new(
+                ftm::entity_type(&listed.schema),
+                caption.clone(),
+                "opensanctions".to_string(),
+            ),
+        },
+    };
+    let mut edge = Relationship::new(
+        subject.id,
+        sanction.id,
+        RelationType::SanctionedBy,
+        "opensanctions".to_string(),
+    );
+    edge.confidence = candidate.score;
+    edge.properties = serde_json::json!({
+        "match_score": candidate.score,
+        "matched_name": caption,
+        "opensanctions_id": listed.id,
+    });
+
+    let source_id = match screened {
+        Some(entity) => format!("match:{}:{}", entity.id, listed.id),
+        None => listed.id.clone(),
+    };
+    let content = format!(
+        "OpenSanctions match: {} matched {} ({}, score {:.2}). ID: {}",
+        subject.name, caption, listed.schema, candidate.score, listed.id
+    );
+
+    RawDocument {
+        source: "opensanctions".to_string(),
+        source_id,
+        title: Some(caption),
+        content,
+        url: Some(format!(
+            "https://www.opensanctions.org/entities/{}/",
+            listed.id
+        )),
+        collected_at: now,
+        observed_at: listed.first_seen.as_deref().and_then(parse_source_date),
+        identifiers: [(
+            identifier_scheme::OPENSANCTIONS.to_string(),
+            listed.id.clone(),
+        )]
+        .into(),
+        metadata: serde_json::json!({
+            "schema": listed.schema,
+            "datasets": listed.datasets,
+            "match_score": candidate.score,
+            "screened_entity_id": screened.map(|entity| entity.id),
+        }),
+        structured: Some(ExtractionResult {
+            entities: vec![subject, sanction],
+            relationships: vec![edge],
+            raw_source: "opensanctions".to_string(),
+            extracted_at: now,
+        }),
+    }
+}
+
+/// A bulk export record as a structured document. The content carries
+/// `last_change`, so a record that changed is not deduplicated away.
+fn bulk_document(record: &BulkRecord, now: DateTime<Utc>) -> RawDocument {
+    let ftm = &record.entity;
+    let (structured, _) = ftm::import(std::slice::from_ref(ftm), "opensanctions", now);
+    let title = structured
+        .entities
+        .first()
+        .map(|entity| entity.name.clone())
+        .or_else(|| ftm.caption.clone())
+        .unwrap_or_else(|| ftm.id.clone());
+
+    let mut identifiers = structured
+        .entities
+        .first()
+        .map(|entity| entity.identifiers.clone())
+        .unwrap_or_default();
+    identifiers.insert(identifier_scheme::OPENSANCTIONS.to_string(), ftm.id.clone());
+
+    RawDocument {
+        source: "opensanctions".to_string(),
+        source_id: ftm.id.clone(),
+        title: Some(title.clone()),
+        content: format!(
+            "OpenSanctions {}: {}. ID: {}. Last change: {}",
+            ftm.schema,
+            title,
+            ftm.id,
+            record.last_change.as_deref().unwrap_or("unknown")
+        ),
+        url: Some(format!(
+            "https://www.opensanctions.org/entities/{}/",
+            ftm.id
+        )),
+        collected_at: now,
+        observed_at: ftm.first_seen.as_deref().and_then(parse_source_date),
+        identifiers,
+        metadata: serde_json::json!({
+            "schema": ftm.schema,
+            "datasets": ftm.datasets,
+            "first_seen": ftm.first_seen,
+            "last_seen": ftm.last_seen,
+            "last_change": record.last_change,
+            "mode": "bulk",
+        }),
+        structured: Some(structured),
     }
 }
 
@@ -196,56 +862,53 @@ impl Agent for OpenSanctionsAgent {
             }
         }
 
-        let mut all_documents = Vec::new();
-        let mut offset: u32 = 0;
-
-        loop {
-            let page = match self.fetch_page(offset, PAGE_LIMIT).await {
-                Ok(page) => page,
+        if self.mode == OpenSanctionsMode::Bulk {
+            let mut cursor = std::mem::take(&mut self.state.write().await.bulk);
+            let result = self.collect_bulk(&mut cursor).await;
+            let mut state = self.state.write().await;
+            state.last_run = Some(Utc::now());
+            match &result {
+                Ok(documents) => {
+                    state.documents_collected += documents.len() as u64;
+                    state.last_error = None;
+                    let newest = cursor.pass_newest.clone().max(cursor.watermark.clone());
+                    if let Some(newest) = newest.as_deref().and_then(parse_source_date) {
+                        state.source_data_timestamp = Some(newest);
+                    }
+                    info!(
+                        documents = documents.len(),
+                        offset = cursor.offset,
+                        deferred = cursor.deferred.len(),
+                        watermark = ?cursor.watermark,
+                        "OpenSanctions bulk collection complete"
+                    );
+                }
                 Err(e) => {
-                    error!(error = %e, offset = offset, "Failed to fetch OpenSanctions page");
-                    let mut state = self.state.write().await;
+                    error!(error = %e, "Failed to read OpenSanctions bulk export");
                     state.last_error = Some(e.to_string());
-                    state.last_run = Some(Utc::now());
-                    return Err(e);
                 }
-            };
-
-            let result_count = page.results.len();
-            debug!(
-                offset = offset,
-                results = result_count,
-                total = ?page.total,
-                "Fetched OpenSanctions page"
-            );
-
-            for entity in &page.results {
-                let doc = self.entity_to_document(entity);
-                all_documents.push(doc);
-            }
-
-            // If we got fewer results than the limit, we've reached the end
-            if (result_count as u32) < PAGE_LIMIT {
-                break;
             }
+            state.bulk = cursor;
+            return result;
+        }
 
-            // If the API reports a total and we've reached it, stop
-            if let Some(total) = page.total {
-                if (offset + result_count as u32) as u64 >= total {
-                    break;
-                }
+        let mut drift = DriftTracker::default();
+        let entities = match self.fetch_pages(&mut drift).await {
+            Ok(entities) => entities,
+            Err(e) => {
+                let mut state = self.state.write().await;
+                state.last_error = Some(e.to_string());
+                state.last_run = Some(Utc::now());
+                return Err(e);
             }
+        };
 
-            offset += PAGE_LIMIT;
-
-            // Safety limit to avoid runaway pagination
-            if offset > 10_000 {
-                warn!(
-                    offset = offset,
-                    "Reached safety pagination limit, stopping collection"
-                );
-                break;
-            }
+        let mut all_documents = Vec::with_capacity(entities.len());
+        let mut newest_change = None;
+        for entity in &entities {
+            newest_change =
+                newest_change.max(entity.last_change.as_deref().and_then(parse_source_date));
+            all_documents.push(self.entity_to_document(entity));
         }
 
         let doc_count = all_documents.len() as u64;
@@ -255,12 +918,14 @@ impl Agent for OpenSanctionsAgent {
             state.last_run = Some(Utc::now());
             state.documents_collected += doc_count;
             state.last_error = None;
+            state.source_data_timestamp = newest_change.or(state.source_data_timestamp);
+            state.schema_drift = drift.finish(&mut state.drift_baseline);
+            if let Some(drift) = &state.schema_drift {
+                warn!(unknown = ?drift.unknown_fields, missing = ?drift.missing_fields, "OpenSanctions response schema drifted");
+            }
         }
 
-        info!(
-            documents = doc_count,
-            "OpenSanctions collection complete"
-        );
+        info!(documents = doc_count, "OpenSanctions collection complete");
 
         Ok(all_documents)
     }
@@ -273,9 +938,16 @@ impl Agent for OpenSanctionsAgent {
             last_run: state.last_run,
             documents_collected: state.documents_collected,
             error: state.last_error.clone(),
+            source_data_timestamp: state.source_data_timestamp,
+            schema_drift: state.schema_drift.clone(),
+            capabilities: None,
         }
     }
 
+    fn as_lookup(&self) -> Option<&dyn AgentLookup> {
+        Some(self)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -284,44 +956,37 @@ impl Agent for OpenSanctionsAgent {
 #[async_trait]
 impl AgentLookup for OpenSanctionsAgent {
     fn can_lookup(&self, entity_type: &EntityType) -> bool {
-        matches!(entity_type, EntityType::Person | EntityType::Organization | EntityType::Vessel)
+        matches!(
+            entity_type,
+            EntityType::Person | EntityType::Organization | EntityType::Vessel
+        )
     }
 
-    async fn lookup(&self, name: &str, _entity_type: &EntityType) -> Result<Vec<RawDocument>> {
-        let url = format!(
-            "{}?q={}&dataset={}&limit=5",
-            OPENSANCTIONS_API_URL,
-            urlencoding::encode(name),
-            DEFAULT_DATASET
-        );
-
-        debug!(url = %url, name = %name, "OpenSanctions lookup");
-
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            ArgusError::Agent {
-                agent: "opensanctions".to_string(),
-                message: format!("Lookup HTTP request failed: {}", e),
-            }
-        })?;
-
-        if !response.status().is_success() {
-            return Ok(Vec::new());
-        }
-
-        let data: OpenSanctionsResponse = response.json().await.map_err(|e| {
-            ArgusError::Agent {
-                agent: "opensanctions".to_string(),
-                message: format!("Lookup parse failed: {}", e),
-            }
-        })?;
-
-        let docs: Vec<RawDocument> = data
-            .results
+    async fn lookup(&self, name: &str, entity_type: &EntityType) -> Result<Vec<RawDocument>> {
+        let candidates = self
+            .match_candidates(&[name.to_string()], entity_type)
+            .await?;
+        let now = Utc::now();
+        let docs: Vec<RawDocument> = candidates
             .iter()
-            .map(|entity| self.entity_to_document(entity))
+            .map(|c| match_document(c, None, now))
             .collect();
+        info!(name = %name, matches = docs.len(), "OpenSanctions lookup complete");
+        Ok(docs)
+    }
 
-        info!(name = %name, results = docs.len(), "OpenSanctions lookup complete");
+    /// Screen an entity: each match at or above the threshold links it
+    /// `SANCTIONED_BY` to the listing, with the score as confidence.
+    async fn lookup_entity(&self, entity: &Entity) -> Result<Vec<RawDocument>> {
+        let mut names = vec![entity.name.clone()];
+        names.extend(entity.aliases.iter().cloned());
+        let candidates = self.match_candidates(&names, &entity.entity_type).await?;
+        let now = Utc::now();
+        let docs: Vec<RawDocument> = candidates
+            .iter()
+            .map(|c| match_document(c, Some(entity), now))
+            .collect();
+        info!(entity = %entity.name, matches = docs.len(), "OpenSanctions screening complete");
         Ok(docs)
     }
 }
@@ -438,4 +1103,85 @@ mod tests {
         // schema_ is used as fallback when schema_name is None
         assert!(doc.content.contains("LegalEntity"));
     }
+
+    #[test]
+    fn bulk_cursor_defers_links_and_skips_unchanged_records() {
+        let person = r#"{"id": "NK-1", "schema": "Person", "caption": "Jane Roe", "properties": {"name": ["Jane Roe"]}, "last_change": "2024-03-01T00:00:00"}"#;
+        let address = r#"{"id": "addr-1", "schema": "Address", "properties": {"full": ["1 Main St"]}, "last_change": "2024-03-02T00:00:00"}"#;
+        let ownership = r#"{"id": "own-1", "schema": "Ownership", "properties": {"owner": ["NK-1"], "asset": ["NK-2"]}, "last_change": "2024-02-01T00:00:00"}"#;
+
+        let mut cursor = BulkCursor::default();
+        let record = cursor.record(person.as_bytes()).unwrap();
+        assert_eq!(record.entity.id, "NK-1");
+        assert!(cursor.record(address.as_bytes()).is_none());
+        assert!(cursor.record(ownership.as_bytes()).is_none());
+        assert!(cursor.record(b"not json\n").is_none());
+        assert_eq!(cursor.deferred.len(), 1);
+
+        cursor.deferred.clear();
+        cursor.finish_pass();
+        assert_eq!(cursor.watermark.as_deref(), Some("2024-03-02T00:00:00"));
+        assert!(cursor.record(person.as_bytes()).is_none());
+        let changed = person.replace("2024-03-01", "2024-04-01");
+        assert!(cursor.record(changed.as_bytes()).is_some());
+    }
+
+    #[test]
+    fn bulk_document_is_structured() {
+        let line = r#"{"id": "NK-1", "schema": "Company", "properties": {"name": ["Acme Shipping"], "leiCode": ["5299000ABCDEF1234567"]}, "datasets": ["us_ofac_sdn"], "last_change": "2024-03-01T00:00:00"}"#;
+        let record: BulkRecord = serde_json::from_str(line).unwrap();
+        let doc = bulk_document(&record, Utc::now());
+
+        assert_eq!(doc.source_id, "NK-1");
+        assert_eq!(doc.title.as_deref(), Some("Acme Shipping"));
+        assert!(doc.content.contains("2024-03-01T00:00:00"));
+        assert_eq!(doc.identifiers[identifier_scheme::OPENSANCTIONS], "NK-1");
+        assert_eq!(
+            doc.identifiers[identifier_scheme::LEI],
+            "5299000ABCDEF1234567"
+        );
+        let structured = doc.structured.unwrap();
+        assert_eq!(structured.entities.len(), 1);
+        assert_eq!(structured.entities[0].entity_type, EntityType::Organization);
+    }
+
+    #[test]
+    fn match_attaches_the_listing_to_the_screened_entity() {
+        let response = r#"{"responses": {"q": {"results": [
+            {"id": "NK-abc", "schema": "Person", "caption": "Ivan Petrov", "score": 0.86,
+             "properties": {"name": ["Ivan Petrov"], "topics": ["sanction"]}, "datasets": ["us_ofac_sdn"]}
+        ]}}}"#;
+        let response: MatchResponse = serde_json::from_str(response).unwrap();
+        let candidate = &response.responses["q"].results[0];
+        let screened = Entity::new(
+            EntityType::Person,
+            "Ivan Petroff".to_string(),
+            "gdelt".to_string(),
+        );
+
+        let doc = match_document(candidate, Some(&screened), Utc::now());
+        let result = doc.structured.unwrap();
+        assert_eq!(result.entities[0].id, screened.id);
+        let sanction = &result.entities[1];
+        assert_eq!(sanction.entity_type, EntityType::Sanction);
+        assert_eq!(sanction.properties["datasets"][0], "us_ofac_sdn");
+
+        let edge = &result.relationships[0];
+        assert_eq!(edge.relation_type, RelationType::SanctionedBy);
+        assert_eq!(
+            (edge.source_entity_id, edge.target_entity_id),
+            (screened.id, sanction.id)
+        );
+        assert_eq!(edge.confidence, 0.86);
+
+        // Without a screened entity the listed record itself is linked
+        let doc = match_document(candidate, None, Utc::now());
+        let result = doc.structured.unwrap();
+        assert_eq!(result.entities[0].id, ftm::entity_uuid("NK-abc"));
+        assert_eq!(
+            result.relationships[0].source_entity_id,
+            result.entities[0].id
+        );
+        assert_eq!(result.entities[1].id, sanction.id);
+    }
 }
@@ -0,0 +1,106 @@
+//! Politeness limits per source: how many requests an agent keeps in
+//! flight, and how far apart it starts them.
+
+use std::time::Duration;
+
+use argus_core::config::parse_agent_numbers;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Limits for one source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Politeness {
+    /// Requests in flight at once, at least 1.
+    pub max_concurrent: usize,
+    /// Least time between the starts of two requests.
+    pub min_interval: Duration,
+}
+
+impl Politeness {
+    /// `agent`'s entries in `AGENT_MAX_CONCURRENT_REQUESTS` and
+    /// `AGENT_REQUEST_INTERVAL_MS` (`agent=number` pairs, like
+    /// `AGENT_INTERVALS`), each falling back to `default`.
+    pub(crate) fn from_env(agent: &str, default: Politeness) -> Self {
+        Self::from_lookup(agent, default, |key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(agent: &str, default: Politeness, var: impl Fn(&str) -> Option<String>) -> Self {
+        let entry = |key: &str| {
+            var(key).and_then(|spec| parse_agent_numbers(key, &spec).get(agent).copied())
+        };
+        Self {
+            max_concurrent: entry("AGENT_MAX_CONCURRENT_REQUESTS")
+                .map_or(default.max_concurrent, |n| n as usize)
+                .max(1),
+            min_interval: entry("AGENT_REQUEST_INTERVAL_MS")
+                .map_or(default.min_interval, Duration::from_millis),
+        }
+    }
+
+    /// A pacer spacing requests by `min_interval`.
+    pub(crate) fn pacer(&self) -> Pacer {
+        Pacer {
+            interval: self.min_interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+/// Hands out request start times at least `interval` apart, to however
+/// many concurrent callers.
+pub(crate) struct Pacer {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl Pacer {
+    /// Wait for this request's turn.
+    pub(crate) async fn wait(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+        let start = {
+            let mut next = self.next.lock().await;
+            let start = (*next).max(Instant::now());
+            *next = start + self.interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: Politeness = Politeness {
+        max_concurrent: 4,
+        min_interval: Duration::from_millis(100),
+    };
+
+    #[test]
+    fn limits_are_set_per_agent() {
+        let var = |key: &str| match key {
+            "AGENT_MAX_CONCURRENT_REQUESTS" => Some("opensanctions=8, gdelt=0".to_string()),
+            "AGENT_REQUEST_INTERVAL_MS" => Some("gdelt=1000".to_string()),
+            _ => None,
+        };
+        let opensanctions = Politeness::from_lookup("opensanctions", DEFAULT, var);
+        assert_eq!(opensanctions.max_concurrent, 8);
+        assert_eq!(opensanctions.min_interval, DEFAULT.min_interval);
+
+        let gdelt = Politeness::from_lookup("gdelt", DEFAULT, var);
+        assert_eq!(
+            (gdelt.max_concurrent, gdelt.min_interval),
+            (1, Duration::from_secs(1))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pacer_spaces_concurrent_requests() {
+        let pacer = DEFAULT.pacer();
+        let started = Instant::now();
+        futures::future::join_all((0..3).map(|_| pacer.wait())).await;
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,541 @@
+//! Synthetic documents for load testing: GDELT-like events and
+//! OpenSanctions-like listings, generated at a configured volume so
+//! extraction, storage and the API can be exercised without the real
+//! upstreams.
+//!
+//! Disabled unless `SYNTHETIC_DOCUMENTS_PER_RUN` is set. Names are drawn
+//! from a fixed pool of `SYNTHETIC_ENTITY_POOL` organizations and people,
+//! so documents keep mentioning the same entities, as real sources do.
+//! Everything is tagged with source `synthetic` and can be removed by it.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+use tokio::sync::RwLock;
+use tracing::info;
+use uuid::Uuid;
+
+use argus_core::agent::{Agent, AgentStatus, RawDocument};
+use argus_core::entity::{
+    identifier_scheme, Entity, EntityType, ExtractionResult, RelationType, Relationship,
+};
+use argus_core::error::{ArgusError, Result};
+
+const SOURCE: &str = "synthetic";
+const DEFAULT_ENTITY_POOL: u64 = 500;
+const DEFAULT_SANCTIONS_SHARE: f64 = 0.2;
+/// Event days are spread over this many days before now.
+const EVENT_SPREAD_DAYS: i64 = 30;
+const ID_NAMESPACE: Uuid = Uuid::from_u128(0x7d2a_91c4_e58b_4f06_b3a9_1e6c_f042_8d75);
+
+const ORG_PREFIXES: &[&str] = &[
+    "Baltic",
+    "Nordic",
+    "Atlas",
+    "Meridian",
+    "Caspian",
+    "Aurora",
+    "Harbor",
+    "Summit",
+    "Orion",
+    "Vanguard",
+    "Silk Road",
+    "Polar",
+    "Adriatic",
+    "Cobalt",
+    "Sterling",
+    "Pacific",
+];
+const ORG_SUFFIXES: &[&str] = &[
+    "Holdings",
+    "Trading",
+    "Shipping",
+    "Capital",
+    "Logistics",
+    "Resources",
+    "Energy",
+    "Maritime",
+    "Industries",
+    "Group",
+];
+const LEGAL_FORMS: &[&str] = &["Ltd", "LLC", "AG", "SA", "OOO", "GmbH", "FZE"];
+const GIVEN_NAMES: &[&str] = &[
+    "Alexei", "Maria", "Viktor", "Elena", "Omar", "Sofia", "Dmitri", "Leila", "Hans", "Irina",
+    "Karim", "Anna",
+];
+const FAMILY_NAMES: &[&str] = &[
+    "Petrov",
+    "Kovacs",
+    "Haddad",
+    "Lindqvist",
+    "Morozov",
+    "Rahimi",
+    "Novak",
+    "Ivanova",
+    "Schulz",
+    "Demir",
+    "Costa",
+];
+/// Place, country code, latitude, longitude.
+const PLACES: &[(&str, &str, f64, f64)] = &[
+    ("Limassol, Cyprus", "CY", 34.68, 33.04),
+    ("Dubai, United Arab Emirates", "AE", 25.20, 55.27),
+    ("Istanbul, Turkey", "TR", 41.01, 28.98),
+    ("Rotterdam, Netherlands", "NL", 51.92, 4.48),
+    ("Singapore", "SG", 1.35, 103.82),
+    ("Riga, Latvia", "LV", 56.95, 24.11),
+    ("Oslo, Norway", "NO", 59.91, 10.75),
+    ("Hong Kong", "HK", 22.32, 114.17),
+];
+/// CAMEO code and how an event with it reads.
+const EVENT_CODES: &[(&str, &str)] = &[
+    ("040", "consulted with"),
+    ("036", "expressed intent to meet"),
+    ("061", "cooperated economically with"),
+    ("0874", "agreed to a shipping deal with"),
+    ("112", "accused"),
+    ("163", "imposed an embargo on"),
+];
+const SANCTION_DATASETS: &[&str] = &[
+    "us_ofac_sdn",
+    "eu_fsf",
+    "gb_hmt_sanctions",
+    "ch_seco_sanctions",
+];
+
+/// Agent generating synthetic documents.
+pub struct SyntheticAgent {
+    documents_per_run: u64,
+    entity_pool: u64,
+    sanctions_share: f64,
+    /// Attach what each document states as a structured extraction, so
+    /// runs load storage without LLM calls.
+    structured: bool,
+    seed: u64,
+    runs: AtomicU64,
+    state: RwLock<SyntheticState>,
+}
+
+struct SyntheticState {
+    last_run: Option<DateTime<Utc>>,
+    documents_collected: u64,
+}
+
+impl Default for SyntheticAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyntheticAgent {
+    pub fn new() -> Self {
+        let var = |key: &str| std::env::var(key).ok();
+        let seed = var("SYNTHETIC_SEED")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64);
+        let mut agent = Self::with_volume(
+            var("SYNTHETIC_DOCUMENTS_PER_RUN")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            seed,
+        );
+        if let Some(pool) = var("SYNTHETIC_ENTITY_POOL").and_then(|s| s.parse().ok()) {
+            agent.entity_pool = pool;
+        }
+        if let Some(share) = var("SYNTHETIC_SANCTIONS_SHARE").and_then(|s| s.parse::<f64>().ok()) {
+            agent.sanctions_share = share.clamp(0.0, 1.0);
+        }
+        agent.structured = var("SYNTHETIC_STRUCTURED")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(false);
+        agent
+    }
+
+    /// `documents_per_run` documents a run, reproducible from `seed`; 0
+    /// disables the agent.
+    pub fn with_volume(documents_per_run: u64, seed: u64) -> Self {
+        Self {
+            documents_per_run,
+            entity_pool: DEFAULT_ENTITY_POOL,
+            sanctions_share: DEFAULT_SANCTIONS_SHARE,
+            structured: false,
+            seed,
+            runs: AtomicU64::new(0),
+            state: RwLock::new(SyntheticState {
+                last_run: None,
+                documents_collected: 0,
+            }),
+        }
+    }
+
+    /// Attach structured extractions, skipping the LLM.
+    pub fn with_structured(mut self, structured: bool) -> Self {
+        self.structured = structured;
+        self
+    }
+
+    /// Share of documents that are sanctions listings rather than events.
+    pub fn with_sanctions_share(mut self, share: f64) -> Self {
+        self.sanctions_share = share.clamp(0.0, 1.0);
+        self
+    }
+
+    fn generate(&self, run: u64, now: DateTime<Utc>) -> Vec<RawDocument> {
+        let mut rng = SplitMix64(self.seed ^ run.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        (0..self.documents_per_run)
+            .map(|n| {
+                let source_id = format!("{:x}-{run}-{n}", self.seed);
+                if rng.chance(self.sanctions_share) {
+                    self.sanction_document(&mut rng, &source_id, now)
+                } else {
+                    self.event_document(&mut rng, &source_id, now)
+                }
+            })
+            .collect()
+    }
+
+    /// An organization or person from the pool.
+    fn pool_member(&self, rng: &mut SplitMix64) -> (EntityType, String) {
+        let index = rng.below(self.entity_pool.max(1));
+        let mut names = SplitMix64(index);
+        // A quarter of the pool are people
+        if index % 4 == 3 {
+            let name = format!("{} {}", names.pick(GIVEN_NAMES), names.pick(FAMILY_NAMES));
+            (EntityType::Person, name)
+        } else {
+            let name = format!(
+                "{} {} {}",
+                names.pick(ORG_PREFIXES),
+                names.pick(ORG_SUFFIXES),
+                names.pick(LEGAL_FORMS)
+            );
+            (EntityType::Organization, name)
+        }
+    }
+
+    fn event_document(&self, rng: &mut SplitMix64, id: &str, now: DateTime<Utc>) -> RawDocument {
+        let (actor1_type, actor1) = self.pool_member(rng);
+        let (mut actor2_type, mut actor2) = self.pool_member(rng);
+        for _ in 0..8 {
+            if actor2 != actor1 {
+                break;
+            }
+            (actor2_type, actor2) = self.pool_member(rng);
+        }
+        let (place, country, lat, lon) = *rng.pick(PLACES);
+        let (code, verb) = *rng.pick(EVENT_CODES);
+        let observed_at =
+            now - Duration::minutes(rng.below(EVENT_SPREAD_DAYS as u64 * 24 * 60) as i64);
+        let tone = rng.below(200) as f64 / 10.0 - 10.0;
+        let source_id = format!("synthetic-event-{id}");
+
+        let title = format!("{actor1} {verb} {actor2} in {place}");
+        let content = format!(
+            "{title}.\n\nOn {day}, {actor1} {verb} {actor2} in {place}. The event was reported by {sources} \
+             sources across {articles} articles, with an average tone of {tone:.1}. Observers noted that {actor1} \
+             has previously been active in {country}.",
+            day = observed_at.format("%Y-%m-%d"),
+            sources = 1 + rng.below(12),
+            articles = 1 + rng.below(40),
+        );
+        let metadata = json!({
+            "kind": "event",
+            "actor1_name": actor1,
+            "actor2_name": actor2,
+            "event_code": code,
+            "avg_tone": tone,
+            "action_geo_full_name": place,
+            "action_geo_country_code": country,
+            "action_geo_lat": lat,
+            "action_geo_long": lon,
+        });
+
+        let structured = self.structured.then(|| {
+            let mut event = Entity::new(EntityType::Event, title.clone(), SOURCE.into());
+            event.id = Uuid::new_v5(&ID_NAMESPACE, source_id.as_bytes());
+            event.source_id = Some(source_id.clone());
+            event.observed_at = Some(observed_at);
+            event.properties =
+                json!({ "event_code": code, "avg_tone": tone, "latitude": lat, "longitude": lon });
+            let actor1 = pool_entity(actor1_type, &actor1, observed_at);
+            let actor2 = pool_entity(actor2_type, &actor2, observed_at);
+            let mut location = pool_entity(EntityType::Location, place, observed_at);
+            location.properties =
+                json!({ "country_code": country, "latitude": lat, "longitude": lon });
+            let relationships = vec![
+                relationship(actor1.id, event.id, RelationType::RelatedTo, observed_at),
+                relationship(actor2.id, event.id, RelationType::RelatedTo, observed_at),
+                relationship(actor1.id, actor2.id, RelationType::MeetingWith, observed_at),
+                relationship(event.id, location.id, RelationType::LocatedAt, observed_at),
+            ];
+            ExtractionResult {
+                entities: vec![event, actor1, actor2, location],
+                relationships,
+                raw_source: source_id.clone(),
+                extracted_at: now,
+            }
+        });
+
+        RawDocument {
+            source: SOURCE.to_string(),
+            source_id,
+            title: Some(title),
+            content,
+            url: None,
+            collected_at: now,
+            observed_at: Some(observed_at),
+            identifiers: BTreeMap::new(),
+            metadata,
+            structured,
+        }
+    }
+
+    fn sanction_document(&self, rng: &mut SplitMix64, id: &str, now: DateTime<Utc>) -> RawDocument {
+        let (entity_type, name) = self.pool_member(rng);
+        let dataset = *rng.pick(SANCTION_DATASETS);
+        let listed_at = now - Duration::days(rng.below(3 * 365) as i64);
+        let source_id = format!("synthetic-sanction-{id}");
+        let schema = if entity_type == EntityType::Person {
+            "Person"
+        } else {
+            "Organization"
+        };
+        let lei =
+            (entity_type == EntityType::Organization).then(|| format!("SYN{:017X}", fnv(&name)));
+
+        let content = format!(
+            "Sanctioned entity: {name} (Schema: {schema}). Listed in {dataset} on {}.",
+            listed_at.format("%Y-%m-%d")
+        );
+        let mut identifiers = BTreeMap::new();
+        if let Some(lei) = &lei {
+            identifiers.insert(identifier_scheme::LEI.to_string(), lei.clone());
+        }
+        let metadata = json!({ "kind": "sanction", "schema": schema, "datasets": [dataset] });
+
+        let structured = self.structured.then(|| {
+            let mut subject = pool_entity(entity_type, &name, listed_at);
+            subject.identifiers = identifiers.clone();
+            subject.sanctions_exposure = Some(1.0);
+            let mut sanction = Entity::new(
+                EntityType::Sanction,
+                format!("{dataset}: {name}"),
+                SOURCE.into(),
+            );
+            sanction.id = Uuid::new_v5(&ID_NAMESPACE, source_id.as_bytes());
+            sanction.source_id = Some(source_id.clone());
+            sanction.properties = json!({ "dataset": dataset });
+            sanction.observed_at = Some(listed_at);
+            let sanctioned_by = relationship(
+                subject.id,
+                sanction.id,
+                RelationType::SanctionedBy,
+                listed_at,
+            );
+            ExtractionResult {
+                entities: vec![subject, sanction],
+                relationships: vec![sanctioned_by],
+                raw_source: source_id.clone(),
+                extracted_at: now,
+            }
+        });
+
+        RawDocument {
+            source: SOURCE.to_string(),
+            source_id,
+            title: Some(name),
+            content,
+            url: None,
+            collected_at: now,
+            observed_at: Some(listed_at),
+            identifiers,
+            metadata,
+            structured,
+        }
+    }
+}
+
+/// A pool entity, with the same id in every document naming it.
+fn pool_entity(entity_type: EntityType, name: &str, at: DateTime<Utc>) -> Entity {
+    let key = format!("synthetic-{}:{}", entity_type, name.to_lowercase());
+    let mut entity = Entity::new(entity_type, name.to_string(), SOURCE.into());
+    entity.id = Uuid::new_v5(&ID_NAMESPACE, key.as_bytes());
+    entity.source_id = Some(key);
+    entity.observed_at = Some(at);
+    entity
+}
+
+fn relationship(
+    source: Uuid,
+    target: Uuid,
+    relation_type: RelationType,
+    at: DateTime<Utc>,
+) -> Relationship {
+    let mut relationship = Relationship::new(source, target, relation_type.clone(), SOURCE.into());
+    relationship.id = Uuid::new_v5(
+        &ID_NAMESPACE,
+        format!("{source}:{target}:{relation_type}").as_bytes(),
+    );
+    relationship.timestamp = Some(at);
+    relationship
+}
+
+/// FNV-1a, for identifiers derived from names.
+fn fnv(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Small seeded generator; the documents need to vary, not to be random.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+#[async_trait]
+impl Agent for SyntheticAgent {
+    fn name(&self) -> &str {
+        SOURCE
+    }
+
+    fn source_type(&self) -> &str {
+        "synthetic"
+    }
+
+    async fn collect(&self) -> Result<Vec<RawDocument>> {
+        if self.documents_per_run == 0 {
+            return Err(ArgusError::Agent {
+                agent: SOURCE.to_string(),
+                message: "disabled; set SYNTHETIC_DOCUMENTS_PER_RUN to generate documents"
+                    .to_string(),
+            });
+        }
+        let run = self.runs.fetch_add(1, Ordering::Relaxed);
+        let now = Utc::now();
+        let documents = self.generate(run, now);
+        info!(
+            run,
+            documents = documents.len(),
+            structured = self.structured,
+            "Generated synthetic documents"
+        );
+
+        let mut state = self.state.write().await;
+        state.last_run = Some(now);
+        state.documents_collected += documents.len() as u64;
+        Ok(documents)
+    }
+
+    async fn status(&self) -> AgentStatus {
+        let state = self.state.read().await;
+        AgentStatus {
+            name: SOURCE.to_string(),
+            enabled: self.documents_per_run > 0,
+            last_run: state.last_run,
+            documents_collected: state.documents_collected,
+            error: None,
+            source_data_timestamp: None,
+            schema_drift: None,
+            capabilities: None,
+        }
+    }
+
+    fn required_env(&self) -> &[&'static str] {
+        &["SYNTHETIC_DOCUMENTS_PER_RUN"]
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_are_reproducible_from_the_seed() {
+        let now = Utc::now();
+        let agent = SyntheticAgent::with_volume(50, 7).with_sanctions_share(0.5);
+        let first = agent.generate(0, now);
+        let again = SyntheticAgent::with_volume(50, 7)
+            .with_sanctions_share(0.5)
+            .generate(0, now);
+
+        assert_eq!(first.len(), 50);
+        assert_eq!(
+            first.iter().map(|d| &d.content).collect::<Vec<_>>(),
+            again.iter().map(|d| &d.content).collect::<Vec<_>>()
+        );
+        let sanctions = first
+            .iter()
+            .filter(|d| d.source_id.starts_with("synthetic-sanction-"))
+            .count();
+        assert!(
+            (10..=40).contains(&sanctions),
+            "{sanctions} sanctions of 50"
+        );
+        // A later run names new documents
+        assert!(agent
+            .generate(1, now)
+            .iter()
+            .all(|d| first.iter().all(|f| f.source_id != d.source_id)));
+
+        assert!(SyntheticAgent::with_volume(0, 7).collect().await.is_err());
+    }
+
+    #[test]
+    fn structured_documents_reuse_pool_entities() {
+        let mut agent = SyntheticAgent::with_volume(200, 1).with_structured(true);
+        agent.entity_pool = 5;
+        let documents = agent.generate(0, Utc::now());
+
+        let mut ids = std::collections::HashSet::new();
+        for document in &documents {
+            let extraction = document.structured.as_ref().expect("structured extraction");
+            for relationship in &extraction.relationships {
+                assert!(extraction
+                    .entities
+                    .iter()
+                    .any(|e| e.id == relationship.source_entity_id));
+                assert!(extraction
+                    .entities
+                    .iter()
+                    .any(|e| e.id == relationship.target_entity_id));
+            }
+            ids.extend(
+                extraction
+                    .entities
+                    .iter()
+                    .filter(|e| {
+                        matches!(e.entity_type, EntityType::Person | EntityType::Organization)
+                    })
+                    .map(|e| e.id),
+            );
+        }
+        assert!(ids.len() <= 5, "{} pool entities", ids.len());
+    }
+}
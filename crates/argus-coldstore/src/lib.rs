@@ -0,0 +1,633 @@
+//! Cold storage: raw observations appended to date-partitioned Parquet on
+//! a local disk or S3, so the Neo4j graph only has to keep the entities and
+//! relationships that summarize them.
+//!
+//! Records are buffered per table and written as one file per table and
+//! day, Hive style, so query engines read `date` as a partition column:
+//!
+//! ```text
+//! {root}/positions/date=2026-03-14/part-1773446400000-<uuid>.parquet
+//! {root}/events/date=2026-03-14/…
+//! {root}/documents/date=2026-03-14/…
+//! ```
+//!
+//! Files are never rewritten; a flush only adds files. [`ColdStorage::positions`]
+//! reads an asset's track back for historical analysis, and
+//! [`ColdStorage::sql`] runs read-only SQL over all three tables.
+
+mod records;
+mod sql;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arrow::array::RecordBatch;
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::TryStreamExt;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{ObjectStore, ObjectStoreExt, PutPayload};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use argus_core::{
+    ArgusError, ColdPartition, ColdTable, EntityType, ExtractionResult, PositionReport,
+    RawDocument, Result,
+};
+
+use records::{DocumentRecord, EventRecord};
+
+pub use sql::{SqlError, SqlRows};
+
+/// Rows kept per table while writes fail, as a multiple of the batch size;
+/// the oldest are dropped beyond it.
+const MAX_BUFFERED_BATCHES: usize = 4;
+
+fn storage_error(e: impl std::fmt::Display) -> ArgusError {
+    ArgusError::ColdStorage(e.to_string())
+}
+
+#[derive(Default)]
+struct Buffers {
+    positions: Vec<PositionReport>,
+    events: Vec<EventRecord>,
+    documents: Vec<DocumentRecord>,
+}
+
+impl Buffers {
+    fn len(&self, table: ColdTable) -> usize {
+        match table {
+            ColdTable::Positions => self.positions.len(),
+            ColdTable::Events => self.events.len(),
+            ColdTable::Documents => self.documents.len(),
+        }
+    }
+}
+
+pub struct ColdStorage {
+    store: Arc<dyn ObjectStore>,
+    root: Path,
+    batch_rows: usize,
+    buffers: Mutex<Buffers>,
+}
+
+impl ColdStorage {
+    /// Archive under `location`: a directory (created if missing), or a
+    /// URL such as `file:///var/lib/argus/cold` or `s3://bucket/prefix`.
+    /// S3 credentials and region come from the usual `AWS_*` variables.
+    pub fn open(location: &str, batch_rows: usize) -> Result<Self> {
+        let (store, root): (Arc<dyn ObjectStore>, Path) = if location.contains("://") {
+            let url = url::Url::parse(location)
+                .map_err(|e| ArgusError::Config(format!("COLD_STORAGE_URL: {e}")))?;
+            let options = std::env::vars().filter(|(key, _)| key.starts_with("AWS_"));
+            let (store, root) =
+                object_store::parse_url_opts(&url, options).map_err(storage_error)?;
+            (Arc::from(store), root)
+        } else {
+            std::fs::create_dir_all(location).map_err(storage_error)?;
+            (
+                Arc::new(LocalFileSystem::new_with_prefix(location).map_err(storage_error)?),
+                Path::default(),
+            )
+        };
+        Ok(Self::new(store, root, batch_rows))
+    }
+
+    pub fn new(store: Arc<dyn ObjectStore>, root: Path, batch_rows: usize) -> Self {
+        Self {
+            store,
+            root,
+            batch_rows: batch_rows.max(1),
+            buffers: Mutex::new(Buffers::default()),
+        }
+    }
+
+    fn buffers(&self) -> std::sync::MutexGuard<'_, Buffers> {
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Rows waiting for the next flush, across tables.
+    pub fn buffered(&self) -> u64 {
+        let buffers = self.buffers();
+        ColdTable::ALL
+            .iter()
+            .map(|table| buffers.len(*table) as u64)
+            .sum()
+    }
+
+    pub async fn record_positions(&self, positions: &[PositionReport]) {
+        if positions.is_empty() {
+            return;
+        }
+        let full = {
+            let mut buffers = self.buffers();
+            buffers.positions.extend_from_slice(positions);
+            buffers.positions.len() >= self.batch_rows
+        };
+        if full {
+            self.flush_table(ColdTable::Positions).await;
+        }
+    }
+
+    /// Archive the metadata of collected documents; their content stays in
+    /// the document archive.
+    pub async fn record_documents(&self, documents: &[RawDocument]) {
+        if documents.is_empty() {
+            return;
+        }
+        let full = {
+            let mut buffers = self.buffers();
+            buffers
+                .documents
+                .extend(documents.iter().map(DocumentRecord::from));
+            buffers.documents.len() >= self.batch_rows
+        };
+        if full {
+            self.flush_table(ColdTable::Documents).await;
+        }
+    }
+
+    /// Archive the Event entities of stored extraction results as each
+    /// source reported them, before deduplication folds re-reports into
+    /// one node.
+    pub async fn record_events(&self, results: &[ExtractionResult]) {
+        let events: Vec<EventRecord> = results
+            .iter()
+            .flat_map(|result| {
+                result
+                    .entities
+                    .iter()
+                    .filter(|e| e.entity_type == EntityType::Event)
+                    .map(|event| EventRecord {
+                        event: event.clone(),
+                        raw_source: result.raw_source.clone(),
+                        extracted_at: result.extracted_at,
+                    })
+            })
+            .collect();
+        if events.is_empty() {
+            return;
+        }
+        let full = {
+            let mut buffers = self.buffers();
+            buffers.events.extend(events);
+            buffers.events.len() >= self.batch_rows
+        };
+        if full {
+            self.flush_table(ColdTable::Events).await;
+        }
+    }
+
+    /// Write everything buffered. Returns how many rows were written; rows
+    /// whose file could not be written stay buffered for the next flush.
+    pub async fn flush(&self) -> usize {
+        let mut written = 0;
+        for table in ColdTable::ALL {
+            written += self.flush_table(table).await;
+        }
+        written
+    }
+
+    async fn flush_table(&self, table: ColdTable) -> usize {
+        match table {
+            ColdTable::Positions => {
+                let rows = std::mem::take(&mut self.buffers().positions);
+                let (written, failed) = self
+                    .write_rows(
+                        table,
+                        rows,
+                        records::position_date,
+                        records::positions_batch,
+                    )
+                    .await;
+                self.requeue(failed, |buffers| &mut buffers.positions);
+                written
+            }
+            ColdTable::Events => {
+                let rows = std::mem::take(&mut self.buffers().events);
+                let (written, failed) = self
+                    .write_rows(table, rows, records::event_date, records::events_batch)
+                    .await;
+                self.requeue(failed, |buffers| &mut buffers.events);
+                written
+            }
+            ColdTable::Documents => {
+                let rows = std::mem::take(&mut self.buffers().documents);
+                let (written, failed) = self
+                    .write_rows(
+                        table,
+                        rows,
+                        records::document_date,
+                        records::documents_batch,
+                    )
+                    .await;
+                self.requeue(failed, |buffers| &mut buffers.documents);
+                written
+            }
+        }
+    }
+
+    /// Put `failed` back ahead of what arrived during the flush, keeping at
+    /// most [`MAX_BUFFERED_BATCHES`] batches.
+    fn requeue<R>(&self, mut failed: Vec<R>, buffer: impl FnOnce(&mut Buffers) -> &mut Vec<R>) {
+        if failed.is_empty() {
+            return;
+        }
+        let mut buffers = self.buffers();
+        let buffer = buffer(&mut buffers);
+        failed.append(buffer);
+        let limit = self.batch_rows.saturating_mul(MAX_BUFFERED_BATCHES);
+        if failed.len() > limit {
+            let dropped = failed.len() - limit;
+            failed.drain(..dropped);
+            warn!(
+                dropped,
+                "Cold storage is not accepting writes, dropped the oldest buffered rows"
+            );
+        }
+        *buffer = failed;
+    }
+
+    /// Write `rows` as one file per day. Returns how many were written and
+    /// the rows of files that failed.
+    async fn write_rows<R>(
+        &self,
+        table: ColdTable,
+        rows: Vec<R>,
+        date: fn(&R) -> NaiveDate,
+        encode: fn(&[R]) -> Result<RecordBatch>,
+    ) -> (usize, Vec<R>) {
+        let mut days: BTreeMap<NaiveDate, Vec<R>> = BTreeMap::new();
+        for row in rows {
+            days.entry(date(&row)).or_default().push(row);
+        }
+
+        let (mut written, mut failed) = (0, Vec::new());
+        for (day, rows) in days {
+            match self.write_file(table, day, encode(&rows)).await {
+                Ok(path) => {
+                    info!(%table, %day, rows = rows.len(), %path, "Archived rows to cold storage");
+                    written += rows.len();
+                }
+                Err(e) => {
+                    warn!(%table, %day, rows = rows.len(), error = %e, "Failed to write cold storage file");
+                    failed.extend(rows);
+                }
+            }
+        }
+        (written, failed)
+    }
+
+    async fn write_file(
+        &self,
+        table: ColdTable,
+        day: NaiveDate,
+        batch: Result<RecordBatch>,
+    ) -> Result<Path> {
+        let batch = batch?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(properties))
+            .map_err(storage_error)?;
+        writer.write(&batch).map_err(storage_error)?;
+        let bytes = writer.into_inner().map_err(storage_error)?;
+
+        let name = format!(
+            "part-{}-{}.parquet",
+            Utc::now().timestamp_millis(),
+            Uuid::new_v4().simple()
+        );
+        let path = self.partition_path(table, day).join(name);
+        self.store
+            .put(&path, PutPayload::from(bytes))
+            .await
+            .map_err(storage_error)?;
+        Ok(path)
+    }
+
+    fn partition_path(&self, table: ColdTable, day: NaiveDate) -> Path {
+        self.root
+            .clone()
+            .join(table.as_str())
+            .join(format!("date={day}"))
+    }
+
+    /// Every partition written so far, by table and day.
+    pub async fn partitions(&self) -> Result<Vec<ColdPartition>> {
+        let files: Vec<_> = self
+            .store
+            .list(Some(&self.root))
+            .try_collect()
+            .await
+            .map_err(storage_error)?;
+        let mut partitions: BTreeMap<(ColdTable, NaiveDate), ColdPartition> = BTreeMap::new();
+        for file in files {
+            let Some(parts) = file.location.prefix_match(&self.root) else {
+                continue;
+            };
+            let parts: Vec<String> = parts.map(|part| part.as_ref().to_string()).collect();
+            let [table, date, name] = parts.as_slice() else {
+                continue;
+            };
+            let Some(table) = ColdTable::ALL.into_iter().find(|t| t.as_str() == table) else {
+                continue;
+            };
+            let Some(date) = date.strip_prefix("date=").and_then(|d| d.parse().ok()) else {
+                continue;
+            };
+            if !name.ends_with(".parquet") {
+                continue;
+            }
+            let partition = partitions.entry((table, date)).or_insert(ColdPartition {
+                table,
+                date,
+                files: 0,
+                bytes: 0,
+            });
+            partition.files += 1;
+            partition.bytes += file.size;
+        }
+        Ok(partitions.into_values().collect())
+    }
+
+    /// Archived positions of `asset` (`scheme:value`) observed in
+    /// `[since, until)`, oldest first, including those not yet flushed, at
+    /// most `limit`. Days are read in order from their own partitions, and
+    /// reading stops after the day that reaches `limit`.
+    pub async fn positions(
+        &self,
+        asset: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<PositionReport>> {
+        let wanted = |p: &PositionReport| {
+            p.asset == asset && p.observed_at >= since && p.observed_at < until
+        };
+        let mut buffered: BTreeMap<NaiveDate, Vec<PositionReport>> = BTreeMap::new();
+        for position in self.buffers().positions.iter().filter(|p| wanted(p)) {
+            buffered
+                .entry(records::position_date(position))
+                .or_default()
+                .push(position.clone());
+        }
+
+        let mut positions = Vec::new();
+        for day in since
+            .date_naive()
+            .iter_days()
+            .take_while(|day| *day <= until.date_naive())
+        {
+            let mut rows = buffered.remove(&day).unwrap_or_default();
+            let prefix = self.partition_path(ColdTable::Positions, day);
+            let files: Vec<_> = self
+                .store
+                .list(Some(&prefix))
+                .try_collect()
+                .await
+                .map_err(storage_error)?;
+            for file in files
+                .iter()
+                .filter(|f| f.location.extension() == Some("parquet"))
+            {
+                let bytes = self
+                    .store
+                    .get(&file.location)
+                    .await
+                    .map_err(storage_error)?
+                    .bytes()
+                    .await
+                    .map_err(storage_error)?;
+                for batch in read_batches(bytes)? {
+                    rows.extend(
+                        records::positions_from_batch(&batch)?
+                            .into_iter()
+                            .filter(|p| wanted(p)),
+                    );
+                }
+            }
+            rows.sort_by_key(|p| p.observed_at);
+            positions.extend(rows);
+            if positions.len() >= limit {
+                break;
+            }
+        }
+        positions.truncate(limit);
+        Ok(positions)
+    }
+}
+
+fn read_batches(bytes: Bytes) -> Result<Vec<RecordBatch>> {
+    ParquetRecordBatchReaderBuilder::try_new(bytes)
+        .map_err(storage_error)?
+        .build()
+        .map_err(storage_error)?
+        .map(|batch| batch.map_err(storage_error))
+        .collect()
+}
+
+/// Write what is buffered every `every`, so quiet tables reach disk too.
+pub async fn run_flusher(storage: Arc<ColdStorage>, every: Duration) {
+    let mut ticker = tokio::time::interval(every.max(Duration::from_secs(1)));
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        storage.flush().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::Entity;
+    use chrono::TimeZone;
+    use object_store::memory::InMemory;
+
+    fn report(asset: &str, observed_at: DateTime<Utc>) -> PositionReport {
+        PositionReport {
+            asset: asset.to_string(),
+            entity_type: EntityType::Vessel,
+            source: "ais".to_string(),
+            latitude: 51.95,
+            longitude: 4.05,
+            observed_at,
+            speed_mps: Some(6.2),
+            course: None,
+            altitude_m: None,
+            on_ground: None,
+            destination: Some("NLRTM".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn positions_are_partitioned_by_day_and_read_back() {
+        let storage = ColdStorage::new(Arc::new(InMemory::new()), Path::from("argus"), 3);
+        let day = |d: u32, h: u32| Utc.with_ymd_and_hms(2026, 3, d, h, 0, 0).unwrap();
+
+        storage
+            .record_positions(&[
+                report("mmsi:244660000", day(14, 9)),
+                report("mmsi:211000000", day(14, 10)),
+            ])
+            .await;
+        assert_eq!(storage.buffered(), 2);
+        // The third row fills the batch and writes both days
+        storage
+            .record_positions(&[report("mmsi:244660000", day(15, 8))])
+            .await;
+        assert_eq!(storage.buffered(), 0);
+        storage
+            .record_positions(&[report("mmsi:244660000", day(15, 20))])
+            .await;
+
+        let partitions = storage.partitions().await.unwrap();
+        assert_eq!(partitions.len(), 2);
+        assert_eq!(
+            partitions[0].date,
+            NaiveDate::from_ymd_opt(2026, 3, 14).unwrap()
+        );
+        assert_eq!(
+            (partitions[0].table, partitions[0].files),
+            (ColdTable::Positions, 1)
+        );
+
+        // Unflushed rows are read along with the files
+        let track = storage
+            .positions("mmsi:244660000", day(14, 0), day(16, 0), 10)
+            .await
+            .unwrap();
+        assert_eq!(track.len(), 3);
+        assert_eq!(track[0], report("mmsi:244660000", day(14, 9)));
+        assert_eq!(track[2].observed_at, day(15, 20));
+        let later = storage
+            .positions("mmsi:244660000", day(15, 0), day(15, 12), 10)
+            .await
+            .unwrap();
+        assert_eq!(later.len(), 1);
+
+        assert_eq!(storage.flush().await, 1);
+        assert_eq!(storage.partitions().await.unwrap()[1].files, 2);
+    }
+
+    #[tokio::test]
+    async fn track_reads_stop_at_the_limit_in_time_order() {
+        let storage = ColdStorage::new(Arc::new(InMemory::new()), Path::from("argus"), 100);
+        let day = |d: u32, h: u32| Utc.with_ymd_and_hms(2026, 3, d, h, 0, 0).unwrap();
+        storage
+            .record_positions(&[
+                report("mmsi:244660000", day(16, 9)),
+                report("mmsi:244660000", day(14, 18)),
+                report("mmsi:244660000", day(15, 7)),
+            ])
+            .await;
+        storage.flush().await;
+        // Unflushed rows of a day sort in among its files' rows
+        storage
+            .record_positions(&[report("mmsi:244660000", day(14, 6))])
+            .await;
+
+        let track = storage
+            .positions("mmsi:244660000", day(14, 0), day(17, 0), 2)
+            .await
+            .unwrap();
+        let times: Vec<_> = track.iter().map(|p| p.observed_at).collect();
+        assert_eq!(times, [day(14, 6), day(14, 18)]);
+        let all = storage
+            .positions("mmsi:244660000", day(14, 0), day(17, 0), 10)
+            .await
+            .unwrap();
+        assert_eq!(all.last().unwrap().observed_at, day(16, 9));
+        assert!(storage
+            .positions("mmsi:211000000", day(14, 0), day(17, 0), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_writes_stay_buffered_up_to_a_cap() {
+        // A file where the archive directory should be makes every put fail
+        let blocked = std::env::temp_dir().join(format!("argus-cold-{}", Uuid::new_v4()));
+        std::fs::write(&blocked, "").unwrap();
+        let store = LocalFileSystem::new_with_prefix(&blocked).unwrap();
+        let storage = ColdStorage::new(Arc::new(store), Path::default(), 2);
+        let at = Utc.with_ymd_and_hms(2026, 3, 14, 9, 0, 0).unwrap();
+
+        storage
+            .record_positions(&vec![report("mmsi:244660000", at); 3])
+            .await;
+        assert_eq!(storage.buffered(), 3);
+        storage
+            .record_positions(&vec![report("mmsi:244660000", at); 10])
+            .await;
+        assert_eq!(storage.buffered(), (2 * MAX_BUFFERED_BATCHES) as u64);
+        assert_eq!(storage.flush().await, 0);
+        std::fs::remove_file(&blocked).unwrap();
+    }
+
+    #[tokio::test]
+    async fn events_and_documents_are_filed_by_their_own_dates() {
+        let storage = ColdStorage::new(Arc::new(InMemory::new()), Path::from("argus"), 100);
+        let happened = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let collected = Utc.with_ymd_and_hms(2026, 3, 14, 8, 0, 0).unwrap();
+
+        let mut event = Entity::new(
+            EntityType::Event,
+            "Port strike in Rotterdam".to_string(),
+            "gdelt".to_string(),
+        );
+        event.observed_at = Some(happened);
+        let actor = Entity::new(
+            EntityType::Organization,
+            "FNV Havens".to_string(),
+            "gdelt".to_string(),
+        );
+        storage
+            .record_events(&[ExtractionResult {
+                entities: vec![event, actor],
+                relationships: Vec::new(),
+                raw_source: "gdelt-event-1".to_string(),
+                extracted_at: collected,
+            }])
+            .await;
+        storage
+            .record_documents(&[RawDocument {
+                source: "gdelt".to_string(),
+                source_id: "gdelt-event-1".to_string(),
+                title: None,
+                content: "full text stays out of the archive".to_string(),
+                url: None,
+                collected_at: collected,
+                observed_at: Some(happened),
+                identifiers: Default::default(),
+                metadata: serde_json::json!({}),
+                structured: None,
+            }])
+            .await;
+        // Only the Event entity is archived
+        assert_eq!(storage.buffered(), 2);
+        assert_eq!(storage.flush().await, 2);
+
+        let partitions: Vec<(ColdTable, NaiveDate)> = storage
+            .partitions()
+            .await
+            .unwrap()
+            .iter()
+            .map(|p| (p.table, p.date))
+            .collect();
+        assert_eq!(
+            partitions,
+            [
+                (ColdTable::Events, happened.date_naive()),
+                (ColdTable::Documents, collected.date_naive()),
+            ]
+        );
+    }
+}
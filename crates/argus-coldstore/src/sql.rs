@@ -0,0 +1,234 @@
+//! Read-only SQL over the archive, run by an embedded DataFusion.
+//!
+//! Each table is registered as a listing table over its directory, with
+//! the Hive `date` directories as a `Date32` partition column, so filters
+//! on `date` skip whole days. Files are listed per query, so a flush is
+//! visible to the next one; rows still buffered are not.
+
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::DataType;
+use arrow::json::writer::{JsonArray, WriterBuilder};
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::listing::{
+    ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+};
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::SQLOptions;
+use datafusion::execution::runtime_env::RuntimeEnvBuilder;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use serde_json::Value;
+
+use argus_core::{ColdTable, SqlColumn};
+
+use crate::{records, ColdStorage};
+
+/// The archive's object store is registered under this URL in each
+/// session.
+const ARCHIVE_URL: &str = "argus-cold://archive";
+/// Memory one query may use for sorts, joins and aggregations before it
+/// fails with resources exhausted.
+const SQL_MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+
+/// Why a query did not run.
+#[derive(Debug)]
+pub enum SqlError {
+    /// The query does not parse or plan, names unknown tables or columns,
+    /// or is not a plain query (DDL, DML, `SET` and the like).
+    Invalid(String),
+    Failed(String),
+}
+
+impl fmt::Display for SqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlError::Invalid(message) | SqlError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<DataFusionError> for SqlError {
+    fn from(e: DataFusionError) -> Self {
+        match e.find_root() {
+            DataFusionError::SQL(..)
+            | DataFusionError::Plan(_)
+            | DataFusionError::SchemaError(..)
+            | DataFusionError::NotImplemented(_) => SqlError::Invalid(e.strip_backtrace()),
+            _ => SqlError::Failed(e.strip_backtrace()),
+        }
+    }
+}
+
+/// The rows a query returned, as JSON objects keyed by column name.
+#[derive(Debug)]
+pub struct SqlRows {
+    pub columns: Vec<SqlColumn>,
+    pub rows: Vec<Value>,
+    /// Whether there were more than the `limit` asked for.
+    pub truncated: bool,
+}
+
+impl ColdStorage {
+    /// Run one read-only SQL query over the `positions`, `events` and
+    /// `documents` tables, returning at most `limit` rows.
+    pub async fn sql(&self, query: &str, limit: usize) -> Result<SqlRows, SqlError> {
+        let ctx = self.session()?;
+        let read_only = SQLOptions::new()
+            .with_allow_ddl(false)
+            .with_allow_dml(false)
+            .with_allow_statements(false);
+        let frame = ctx.sql_with_options(query, read_only).await?;
+        let columns = frame
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| SqlColumn {
+                name: field.name().clone(),
+                data_type: field.data_type().to_string(),
+            })
+            .collect();
+
+        // One row past the limit tells whether there were more
+        let batches = frame
+            .limit(0, Some(limit.saturating_add(1)))?
+            .collect()
+            .await?;
+        let mut rows = to_json(&batches)?;
+        let truncated = rows.len() > limit;
+        rows.truncate(limit);
+        Ok(SqlRows {
+            columns,
+            rows,
+            truncated,
+        })
+    }
+
+    fn session(&self) -> Result<SessionContext, SqlError> {
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(SQL_MEMORY_LIMIT, 1.0)
+            .build_arc()?;
+        let ctx = SessionContext::new_with_config_rt(
+            SessionConfig::new().with_information_schema(true),
+            runtime,
+        );
+        let url = url::Url::parse(ARCHIVE_URL).map_err(|e| SqlError::Failed(e.to_string()))?;
+        ctx.register_object_store(&url, self.store.clone());
+
+        for table in ColdTable::ALL {
+            let directory = self.root.clone().join(table.as_str());
+            let options = ListingOptions::new(Arc::new(ParquetFormat::default()))
+                .with_file_extension(".parquet")
+                .with_table_partition_cols(vec![("date".to_string(), DataType::Date32)]);
+            let config = ListingTableConfig::new(ListingTableUrl::parse(format!(
+                "{ARCHIVE_URL}/{directory}/"
+            ))?)
+            .with_listing_options(options)
+            .with_schema(records::schema(table));
+            ctx.register_table(table.as_str(), Arc::new(ListingTable::try_new(config)?))?;
+        }
+        Ok(ctx)
+    }
+}
+
+fn to_json(batches: &[RecordBatch]) -> Result<Vec<Value>, SqlError> {
+    let failed =
+        |e: &dyn fmt::Display| SqlError::Failed(format!("Failed to encode query result: {e}"));
+    let mut writer = WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, JsonArray>(Vec::new());
+    for batch in batches {
+        writer.write(batch).map_err(|e| failed(&e))?;
+    }
+    writer.finish().map_err(|e| failed(&e))?;
+    let bytes = writer.into_inner();
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_slice(&bytes).map_err(|e| failed(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+
+    use super::*;
+    use argus_core::{EntityType, PositionReport};
+
+    fn report(asset: &str, day: u32, speed_mps: f64) -> PositionReport {
+        PositionReport {
+            asset: asset.to_string(),
+            entity_type: EntityType::Vessel,
+            source: "ais".to_string(),
+            latitude: 51.95,
+            longitude: 4.05,
+            observed_at: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            speed_mps: Some(speed_mps),
+            course: None,
+            altitude_m: None,
+            on_ground: None,
+            destination: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn queries_aggregate_over_partitions_and_stay_read_only() {
+        let storage = ColdStorage::new(Arc::new(InMemory::new()), Path::from("argus"), 100);
+        storage
+            .record_positions(&[
+                report("mmsi:244660000", 14, 4.0),
+                report("mmsi:244660000", 15, 6.0),
+                report("mmsi:211000000", 15, 1.0),
+            ])
+            .await;
+        storage.flush().await;
+
+        let result = storage
+            .sql(
+                "SELECT asset, count(*) AS reports, avg(speed_mps) AS mean_speed FROM positions \
+                 WHERE date >= DATE '2026-03-14' GROUP BY asset ORDER BY reports DESC",
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.columns[0].name, "asset");
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0]["asset"], "mmsi:244660000");
+        assert_eq!(result.rows[0]["reports"], 2);
+        assert_eq!(result.rows[0]["mean_speed"], 5.0);
+        assert!(!result.truncated);
+
+        let one_day = storage
+            .sql(
+                "SELECT asset FROM positions WHERE date = DATE '2026-03-15'",
+                1,
+            )
+            .await
+            .unwrap();
+        assert_eq!(one_day.rows.len(), 1);
+        assert!(one_day.truncated);
+        // Tables without files yet are empty, not missing
+        assert!(storage
+            .sql("SELECT * FROM events", 10)
+            .await
+            .unwrap()
+            .rows
+            .is_empty());
+
+        for rejected in [
+            "DROP TABLE positions",
+            "CREATE TABLE copy AS SELECT * FROM positions",
+            "INSERT INTO positions SELECT * FROM positions",
+            "SET datafusion.execution.batch_size = 1",
+            "SELECT * FROM vessels",
+        ] {
+            assert!(
+                matches!(storage.sql(rejected, 10).await, Err(SqlError::Invalid(_))),
+                "{rejected}"
+            );
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use std::any::Any;
 
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::entity::EntityType;
+use crate::entity::{Entity, EntityType, ExtractionResult};
 use crate::error::Result;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,17 +16,104 @@ pub struct RawDocument {
     pub title: Option<String>,
     pub content: String,
     pub url: Option<String>,
+    /// When the agent fetched the document.
     pub collected_at: DateTime<Utc>,
+    /// When the source says the reported fact happened or began (event
+    /// day, incorporation date, listing date). Carried onto extracted
+    /// entities and relationships.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_at: Option<DateTime<Utc>>,
+    /// External identifiers of the document's subject, the entity named by
+    /// `title`. Carried onto that entity when it is extracted.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub identifiers: BTreeMap<String, String>,
     pub metadata: serde_json::Value,
+    /// Entities and relationships the source states outright. Documents
+    /// carrying one are stored as-is instead of going through LLM
+    /// extraction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<ExtractionResult>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct AgentStatus {
     pub name: String,
     pub enabled: bool,
     pub last_run: Option<DateTime<Utc>>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub documents_collected: u64,
     pub error: Option<String>,
+    /// Newest time the upstream put on the data collected, such as a
+    /// GDELT export's timestamp or an OpenSanctions `last_change`; how far
+    /// it trails now is how stale the source is. Unset for agents whose
+    /// source carries no such time, or before a run returned data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_data_timestamp: Option<DateTime<Utc>>,
+    /// Set when the latest run's responses no longer matched what the agent
+    /// decodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_drift: Option<SchemaDrift>,
+    /// What the agent can do here. Filled in by `GET /api/agents`, not by
+    /// the agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<AgentCapabilities>,
+}
+
+/// What an agent offers beyond collecting, so clients know which agents
+/// can enrich which entities.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AgentCapabilities {
+    pub source_type: String,
+    /// Built-in entity types the agent's [`AgentLookup`] accepts; empty for
+    /// agents without one.
+    pub lookup_entity_types: Vec<EntityType>,
+    /// Seconds between scheduled runs; unset for agents that only run when
+    /// triggered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub schedule_interval_seconds: Option<u64>,
+    /// Environment variables the agent needs to collect.
+    pub required_env: Vec<String>,
+    /// Those of `required_env` unset on this instance; the scheduler skips
+    /// the agent while any are.
+    pub missing_env: Vec<String>,
+}
+
+impl AgentCapabilities {
+    /// Everything but the schedule, which the server owns.
+    pub fn of(agent: &dyn Agent, env: impl Fn(&str) -> bool) -> Self {
+        let lookup_entity_types = match agent.as_lookup() {
+            Some(lookup) => EntityType::BUILT_IN
+                .into_iter()
+                .filter(|t| lookup.can_lookup(t))
+                .collect(),
+            None => Vec::new(),
+        };
+        let required_env: Vec<String> =
+            agent.required_env().iter().map(|v| v.to_string()).collect();
+        Self {
+            source_type: agent.source_type().to_string(),
+            lookup_entity_types,
+            schedule_interval_seconds: None,
+            missing_env: required_env.iter().filter(|v| !env(v)).cloned().collect(),
+            required_env,
+        }
+    }
+}
+
+/// How an upstream API's responses differed from what an agent decodes in
+/// one run. Paths are dotted, with `*` for array positions and map keys
+/// that are numbers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct SchemaDrift {
+    /// Fields the source sent that the agent ignores.
+    pub unknown_fields: Vec<String>,
+    /// Fields earlier runs received that no response carried this run.
+    pub missing_fields: Vec<String>,
+    pub detected_at: DateTime<Utc>,
 }
 
 #[async_trait]
@@ -34,7 +123,17 @@ pub trait Agent: Send + Sync {
     async fn collect(&self) -> Result<Vec<RawDocument>>;
     async fn status(&self) -> AgentStatus;
 
-    /// Downcast support for cross-referencing between agents.
+    /// Environment variables without which the agent collects nothing.
+    fn required_env(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// The agent's entity lookup, for agents that have one.
+    fn as_lookup(&self) -> Option<&dyn AgentLookup> {
+        None
+    }
+
+    /// Downcast support for agent-specific APIs.
     fn as_any(&self) -> &dyn Any;
 }
 
@@ -48,4 +147,10 @@ pub trait AgentLookup: Send + Sync {
 
     /// Search for a specific entity by name and type at this data source.
     async fn lookup(&self, name: &str, entity_type: &EntityType) -> Result<Vec<RawDocument>>;
+
+    /// Look up an entity already in the graph. Agents whose results attach
+    /// to the entity itself override this; the default searches by name.
+    async fn lookup_entity(&self, entity: &Entity) -> Result<Vec<RawDocument>> {
+        self.lookup(&entity.name, &entity.entity_type).await
+    }
 }
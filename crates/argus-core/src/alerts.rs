@@ -0,0 +1,238 @@
+//! Alert rules evaluated on new graph writes, and the alerts they raise.
+//!
+//! A rule's `condition` is a small expression language, parsed and
+//! evaluated by the server (`argus-server/src/alerts`); this module only
+//! holds the records shared by the API and the stores.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::EntityType;
+
+/// Most alerts kept; the oldest are dropped beyond this.
+pub const MAX_ALERTS: usize = 10_000;
+
+/// Sends to one channel given up on after this many failures.
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Info,
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+/// A user-defined condition checked against every stored extraction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AlertRule {
+    pub id: Uuid,
+    pub name: String,
+    /// e.g. `entity.type == organization AND new_relationship.type == sanctioned_by`
+    /// or `vessel enters bbox(-6.0, 35.5, -5.0, 36.5)`.
+    pub condition: String,
+    pub severity: AlertSeverity,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    /// Where its alerts are sent; none keeps them in the API only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<NotificationChannel>,
+}
+
+/// Where a notification goes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelTarget {
+    Email {
+        to: Vec<String>,
+    },
+    /// Posts to `webhook_url`, or to `SLACK_WEBHOOK_URL` if unset.
+    Slack {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        webhook_url: Option<String>,
+    },
+}
+
+/// A delivery channel on a rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct NotificationChannel {
+    #[serde(flatten)]
+    pub target: ChannelTarget,
+    /// Collect alerts and send one message per digest interval instead of
+    /// one per alert.
+    #[serde(default)]
+    pub digest: bool,
+    /// Message per alert, with `{rule}`, `{severity}`, `{message}`,
+    /// `{entities}`, `{source}`, `{created_at}` and `{id}` filled in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl NotificationChannel {
+    /// Names the channel in an alert's deliveries, e.g. `email:a@x.org,b@x.org`
+    /// or `slack`. Webhook URLs are secrets, so only a hash of one is shown.
+    pub fn label(&self) -> String {
+        match &self.target {
+            ChannelTarget::Email { to } => format!("email:{}", to.join(",")),
+            ChannelTarget::Slack { webhook_url: None } => "slack".to_string(),
+            ChannelTarget::Slack {
+                webhook_url: Some(url),
+            } => {
+                let hash = Uuid::new_v5(&Uuid::NAMESPACE_URL, url.as_bytes())
+                    .simple()
+                    .to_string();
+                format!("slack:{}", &hash[..8])
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for the next digest, or for a retry after a failure.
+    Pending,
+    Sent,
+    /// Gave up after [`MAX_DELIVERY_ATTEMPTS`].
+    Failed,
+}
+
+/// Delivery of an alert to one of its rule's channels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Delivery {
+    /// [`NotificationChannel::label`] of the channel.
+    pub channel: String,
+    pub status: DeliveryStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_at: Option<DateTime<Utc>>,
+    /// When the latest attempt started, so one still in flight is not
+    /// retried alongside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempted_at: Option<DateTime<Utc>>,
+}
+
+impl Delivery {
+    pub fn pending(channel: String) -> Self {
+        Self {
+            channel,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            error: None,
+            sent_at: None,
+            attempted_at: None,
+        }
+    }
+
+    /// Record a send attempt.
+    pub fn record(&mut self, outcome: std::result::Result<(), String>) {
+        self.attempts += 1;
+        match outcome {
+            Ok(()) => {
+                self.status = DeliveryStatus::Sent;
+                self.error = None;
+                self.sent_at = Some(Utc::now());
+            }
+            Err(e) => {
+                self.error = Some(e);
+                if self.attempts >= MAX_DELIVERY_ATTEMPTS {
+                    self.status = DeliveryStatus::Failed;
+                }
+            }
+        }
+    }
+}
+
+/// An entity an alert is about.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AlertEntity {
+    /// As extracted; writes resolving onto an existing node keep that
+    /// node's id instead.
+    pub id: Uuid,
+    pub name: String,
+    pub entity_type: EntityType,
+}
+
+/// A rule match on a graph write, open until acknowledged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Alert {
+    pub id: Uuid,
+    pub rule_id: Uuid,
+    pub rule_name: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub entities: Vec<AlertEntity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationship_id: Option<Uuid>,
+    /// Source of the write that matched.
+    pub source: String,
+    /// Same rule and subject; while one alert with a key is open, later
+    /// matches for it are not raised again.
+    pub dedup_key: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledged_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// One per channel of the rule at the time it was raised.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deliveries: Vec<Delivery>,
+}
+
+impl Alert {
+    pub fn is_open(&self) -> bool {
+        self.acknowledged_at.is_none()
+    }
+
+    pub fn has_pending_deliveries(&self) -> bool {
+        self.deliveries
+            .iter()
+            .any(|d| d.status == DeliveryStatus::Pending)
+    }
+
+    pub fn acknowledge(&mut self, by: Option<String>, note: Option<String>) {
+        self.acknowledged_at = Some(Utc::now());
+        self.acknowledged_by = by;
+        self.note = note;
+    }
+}
+
+/// Which alerts a listing returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    #[default]
+    Open,
+    Acknowledged,
+    All,
+}
+
+impl AlertStatus {
+    pub fn matches(&self, alert: &Alert) -> bool {
+        match self {
+            AlertStatus::Open => alert.is_open(),
+            AlertStatus::Acknowledged => !alert.is_open(),
+            AlertStatus::All => true,
+        }
+    }
+}
@@ -1,36 +1,435 @@
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::agent::AgentStatus;
-use crate::entity::{Entity, EntityType, Relationship};
-use crate::reasoning::{ReasoningResponse, ReasoningStep};
+use crate::agent::{AgentStatus, SchemaDrift};
+use crate::alerts::{Alert, AlertRule, AlertSeverity, AlertStatus, NotificationChannel};
+use crate::backup::{SnapshotCounts, SnapshotManifest};
+use crate::briefing::{parse_window, DEFAULT_BRIEFING_WINDOW};
+use crate::calibration::{
+    CalibrationGrouping, FeedbackLabel, FeedbackTarget, DEFAULT_CALIBRATION_BINS,
+    DEFAULT_TARGET_PRECISION, MAX_CALIBRATION_BINS,
+};
+use crate::cold_storage::{ColdPartition, SqlColumn};
+use crate::density::{
+    parse_interval, BoundingBox, DensityCell, DensityQuery, DEFAULT_DENSITY_INTERVAL,
+    DEFAULT_DENSITY_WINDOW_HOURS, MAX_DENSITY_BUCKETS, MAX_GEOHASH_PRECISION,
+};
+use crate::deny_list::{DenyEntry, DenyMatch};
+use crate::document::{ArchivedDocument, RunExtractionStats};
+use crate::duplicates::DuplicateCandidate;
+use crate::entity::{Direction, Entity, EntityType, RelationType, Relationship};
+use crate::export::ExportFormat;
+use crate::extraction::{CacheStats, ExtractionFailure, ValidationReport, MAX_EXTRACTION_FAILURES};
+use crate::graph::{
+    EntityBatchItem, EntitySort, GraphNeighbors, GraphPath, NeighborFilter, RelationshipSearchHit,
+};
+use crate::history::{EntityChange, Tombstone};
+use crate::integrity::IntegrityFinding;
+use crate::investigation::{CaseEvent, Investigation};
+use crate::natural_search::SearchInterpretation;
+use crate::ontology::Ontology;
+use crate::presentation::{presentations, Presentation};
+use crate::prompts::{
+    PromptBundle, PromptDeployment, DEFAULT_EXAMPLES_PER_SOURCE, MAX_EXAMPLES_PER_SOURCE,
+};
+use crate::quota::Spillover;
+use crate::reasoning::{ReasoningResponse, ReasoningStep, ReasoningUsage};
+use crate::retention::RetentionPolicy;
+use crate::shared::FailedDocument;
+use crate::subscriptions::{Notification, Subscription, SubscriptionEvent};
+use crate::tracks::PositionReport;
+use crate::usage::{UsageCounts, USAGE_RETENTION_DAYS};
 
 // --- Health ---
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct HealthResponse {
     pub status: String,
     pub version: String,
     pub neo4j_connected: bool,
     pub qdrant_connected: bool,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub entity_count: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub relationship_count: u64,
 }
 
+// --- Admin ---
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractionCacheStatsResponse {
+    pub enabled: bool,
+    /// `None` when EXTRACTION_CACHE is `none`.
+    pub stats: Option<CacheStats>,
+    pub hit_rate: f64,
+}
+
+/// Query-string options for `GET /api/admin/extraction-failures`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExtractionFailureListParams {
+    pub limit: Option<usize>,
+    /// Only failures from this agent.
+    pub source: Option<String>,
+}
+
+impl ExtractionFailureListParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(50).clamp(1, MAX_EXTRACTION_FAILURES)
+    }
+}
+
+/// A cataloged failure with a reference to its document rather than the
+/// document itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractionFailureEntry {
+    pub id: Uuid,
+    pub run_id: String,
+    pub source: String,
+    pub source_id: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub error: String,
+    pub raw_output: String,
+    pub model: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl From<&ExtractionFailure> for ExtractionFailureEntry {
+    fn from(failure: &ExtractionFailure) -> Self {
+        Self {
+            id: failure.id,
+            run_id: failure.run_id.clone(),
+            source: failure.agent_name.clone(),
+            source_id: failure.document.source_id.clone(),
+            title: failure.document.title.clone(),
+            url: failure.document.url.clone(),
+            error: failure.error.clone(),
+            raw_output: failure.raw_output.clone(),
+            model: failure.model.clone(),
+            failed_at: failure.failed_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractionFailureListResponse {
+    /// Most recent first.
+    pub failures: Vec<ExtractionFailureEntry>,
+}
+
+/// Query-string options for `GET /api/admin/usage`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UsageParams {
+    /// Days back to count, today included (default 7).
+    pub days: Option<u32>,
+}
+
+impl UsageParams {
+    pub fn days(&self) -> u32 {
+        self.days.unwrap_or(7).clamp(1, USAGE_RETENTION_DAYS)
+    }
+}
+
+/// One consumer's usage over the requested days.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConsumerUsage {
+    /// `token:` and a hash of the bearer token, or `anonymous`.
+    pub consumer: String,
+    #[serde(flatten)]
+    pub usage: UsageCounts,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageResponse {
+    pub since: NaiveDate,
+    pub until: NaiveDate,
+    /// Most requests first.
+    pub consumers: Vec<ConsumerUsage>,
+}
+
+/// What one retention pass removed, or with `dry_run` would remove.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub ran_at: DateTime<Utc>,
+    pub policies: Vec<RetentionPolicyReport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetentionPolicyReport {
+    pub policy: RetentionPolicy,
+    /// Nodes last observed before this are expired.
+    pub cutoff: DateTime<Utc>,
+    /// Expired nodes when the pass started.
+    pub expired: u64,
+    pub archived: u64,
+    pub deleted: u64,
+    /// JSON Lines file archived nodes were appended to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_file: Option<String>,
+    /// Why the policy stopped early; nodes not yet removed stay for the next pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Query parameters for `POST /api/admin/llm-log/purge`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LlmLogPurgeParams {
+    /// Purge logged calls older than this instead of `LLM_LOG_RETENTION_DAYS`;
+    /// 0 purges them all.
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+}
+
+/// Findings of one integrity pass, one per check.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// Whether safe fixes were applied.
+    pub repair: bool,
+    pub checked_at: DateTime<Utc>,
+    pub findings: Vec<IntegrityFinding>,
+}
+
+/// Upper bound on tombstones returned by one request.
+pub const MAX_TOMBSTONE_LIMIT: usize = 1000;
+
+/// Query-string options for `GET /api/admin/tombstones`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TombstoneListParams {
+    pub limit: Option<usize>,
+}
+
+impl TombstoneListParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(100).clamp(1, MAX_TOMBSTONE_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TombstoneListResponse {
+    /// Most recent first.
+    pub tombstones: Vec<Tombstone>,
+}
+
+/// Upper bound on candidates returned by one duplicate queue request.
+pub const MAX_DUPLICATE_LIMIT: usize = 1000;
+
+/// Query-string options for `GET /api/admin/duplicates`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DuplicateQueueParams {
+    pub limit: Option<usize>,
+    pub min_score: Option<f64>,
+}
+
+impl DuplicateQueueParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(100).clamp(1, MAX_DUPLICATE_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateQueueResponse {
+    /// Highest score first.
+    pub candidates: Vec<DuplicateCandidate>,
+    /// Candidates queued above `min_score`, before `limit`.
+    pub total: usize,
+}
+
+/// Body of `POST /api/admin/duplicates/{id}/merge`. Without `keep` the
+/// candidate's suggested survivor is kept.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DuplicateMergeRequest {
+    #[serde(default)]
+    pub keep: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateMergeResponse {
+    /// The surviving entity after the merge.
+    pub entity: Entity,
+    /// Entities folded into it and removed.
+    pub merged: Vec<Uuid>,
+}
+
+// --- Alerts ---
+
+/// Upper bound on alerts returned by one listing.
+pub const MAX_ALERT_LIMIT: usize = 1000;
+
+/// Query-string options for `GET /api/alerts`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AlertListParams {
+    #[serde(default)]
+    pub status: AlertStatus,
+    /// Only alerts at least this severe.
+    pub min_severity: Option<AlertSeverity>,
+    pub limit: Option<usize>,
+}
+
+impl AlertListParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(100).clamp(1, MAX_ALERT_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AlertListResponse {
+    /// Newest first.
+    pub alerts: Vec<Alert>,
+    pub total: usize,
+}
+
+/// Body of `POST /api/alerts/{id}/acknowledge`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AlertAcknowledgeRequest {
+    /// Who acknowledged it, e.g. an analyst's name.
+    #[serde(default)]
+    pub by: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Body of `POST /api/alerts/rules`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AlertRuleRequest {
+    pub name: String,
+    pub condition: String,
+    #[serde(default)]
+    pub severity: AlertSeverity,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub channels: Vec<NotificationChannel>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AlertRuleListResponse {
+    pub rules: Vec<AlertRule>,
+}
+
+// --- Subscriptions ---
+
+/// Upper bound on notifications returned by one feed request.
+pub const MAX_NOTIFICATION_LIMIT: usize = 1000;
+
+/// Body of `POST /api/entities/{id}/follow`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct FollowRequest {
+    /// What to be told about (default everything).
+    #[serde(default)]
+    pub events: Vec<SubscriptionEvent>,
+    #[serde(default)]
+    pub subscriber: Option<String>,
+    /// An https URL each notification is POSTed to.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+/// Query-string options for `GET /api/subscriptions`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct SubscriptionListParams {
+    pub subscriber: Option<String>,
+    pub entity_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct SubscriptionListResponse {
+    /// Oldest first.
+    pub subscriptions: Vec<Subscription>,
+}
+
+/// Query-string options for `GET /api/notifications`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct NotificationFeedParams {
+    pub subscriber: Option<String>,
+    pub subscription_id: Option<Uuid>,
+    /// Only notifications created after this.
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl NotificationFeedParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(100).clamp(1, MAX_NOTIFICATION_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct NotificationFeedResponse {
+    /// Newest first.
+    pub notifications: Vec<Notification>,
+    pub total: usize,
+}
+
+// --- Deny list ---
+
+/// Body of `POST /api/admin/deny-list`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DenyEntryRequest {
+    pub pattern: String,
+    #[serde(default, rename = "match")]
+    pub match_kind: DenyMatch,
+    #[serde(default)]
+    pub entity_type: Option<EntityType>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DenyListResponse {
+    pub entries: Vec<DenyEntry>,
+}
+
+// --- Backups ---
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotListResponse {
+    pub snapshots: Vec<SnapshotManifest>,
+}
+
+/// Body of `POST /api/admin/restore`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotRestoreRequest {
+    /// Snapshot file name, as listed by `GET /api/admin/backups`.
+    pub file: String,
+}
+
 // --- Agents ---
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct AgentListResponse {
     pub agents: Vec<AgentStatus>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct AgentTriggerRequest {
     pub agent_name: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct AgentTriggerResponse {
     pub run_id: String,
     pub agent_name: String,
@@ -38,19 +437,114 @@ pub struct AgentTriggerResponse {
     pub message: String,
 }
 
+/// Result of pausing or resuming an agent.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AgentPauseResponse {
+    pub agent_name: String,
+    /// Whether the scheduler will run the agent.
+    pub enabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct AgentRunStatus {
     pub run_id: String,
     pub agent_name: String,
     pub status: AgentRunState,
     pub started_at: DateTime<Utc>,
     pub finished_at: Option<DateTime<Utc>>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub documents_collected: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub entities_extracted: u64,
+    /// Documents whose extraction failed and were queued for retry.
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub documents_failed: u64,
+    /// Extracted items dropped by validation before storage.
+    #[serde(default)]
+    pub validation: ValidationReport,
+    /// Documents beyond the agent's daily extraction quota, dropped or
+    /// queued for off-peak extraction.
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub documents_over_quota: u64,
+    /// Upstream schema changes the agent noticed while collecting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_drift: Option<SchemaDrift>,
+    /// Records written so far by a workspace backup run, or restored by a
+    /// restore run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<SnapshotCounts>,
     pub error: Option<String>,
 }
 
+impl AgentRunStatus {
+    /// A freshly started run with no progress yet.
+    pub fn started(run_id: impl Into<String>, agent_name: impl Into<String>) -> Self {
+        Self {
+            run_id: run_id.into(),
+            agent_name: agent_name.into(),
+            status: AgentRunState::Running,
+            started_at: Utc::now(),
+            finished_at: None,
+            documents_collected: 0,
+            entities_extracted: 0,
+            documents_failed: 0,
+            validation: ValidationReport::default(),
+            documents_over_quota: 0,
+            schema_drift: None,
+            snapshot: None,
+            error: None,
+        }
+    }
+}
+
+/// Today's extraction consumption for one source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct SourceBudget {
+    pub source: String,
+    /// `None` when the source has no quota.
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub daily_limit: Option<u64>,
+    /// Documents sent for extraction today within the quota.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub used: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub remaining: Option<u64>,
+    /// Documents over quota dropped today.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub dropped: u64,
+    /// Documents waiting for off-peak extraction.
+    pub queued: usize,
+    /// Queued documents extracted off-peak today, outside the quota.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub off_peak_processed: u64,
+}
+
+/// Response of `GET /api/agents/budget`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct BudgetStatusResponse {
+    /// UTC day the counts are for.
+    pub day: NaiveDate,
+    pub spillover: Spillover,
+    pub off_peak_hours: String,
+    pub sources: Vec<SourceBudget>,
+}
+
+/// Query parameters for `POST /api/agents/runs/{id}/retry-failures`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RetryFailuresParams {
+    /// Retry documents still in backoff as well as those that are due.
+    #[serde(default)]
+    pub force: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 #[serde(rename_all = "snake_case")]
 pub enum AgentRunState {
     Running,
@@ -58,98 +552,1362 @@ pub enum AgentRunState {
     Failed,
 }
 
+impl AgentRunState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgentRunState::Running => "running",
+            AgentRunState::Completed => "completed",
+            AgentRunState::Failed => "failed",
+        }
+    }
+}
+
+/// Largest page `GET /api/agents/runs` returns.
+pub const MAX_RUN_PAGE: usize = 100;
+
+/// Query-string options for `GET /api/agents/runs`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunListParams {
+    /// Only runs of this agent.
+    pub agent: Option<String>,
+    pub status: Option<AgentRunState>,
+    /// Only runs started at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only runs started before this time.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+}
+
+impl RunListParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(MAX_RUN_PAGE).clamp(1, MAX_RUN_PAGE)
+    }
+
+    fn matches(&self, run: &AgentRunStatus) -> bool {
+        self.agent
+            .as_ref()
+            .is_none_or(|agent| &run.agent_name == agent)
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|status| &run.status == status)
+            && self.since.is_none_or(|since| run.started_at >= since)
+            && self.until.is_none_or(|until| run.started_at < until)
+    }
+
+    /// One page of `runs`, newest first, continuing after `cursor`. A
+    /// cursor is the start time and id of the last run on the previous
+    /// page, so it stays valid as runs are added or trimmed. Fails on a
+    /// malformed cursor.
+    pub fn page(&self, mut runs: Vec<AgentRunStatus>) -> Result<AgentRunsResponse, String> {
+        let after = self.cursor.as_deref().map(parse_run_cursor).transpose()?;
+        runs.retain(|run| self.matches(run));
+        runs.sort_by(|a, b| (b.started_at, &b.run_id).cmp(&(a.started_at, &a.run_id)));
+        if let Some((at, id)) = &after {
+            runs.retain(|run| (run.started_at, &run.run_id) < (*at, id));
+        }
+        let limit = self.limit();
+        let next_cursor = (runs.len() > limit).then(|| {
+            let last = &runs[limit - 1];
+            format!("{}_{}", last.started_at.timestamp_millis(), last.run_id)
+        });
+        runs.truncate(limit);
+        Ok(AgentRunsResponse { runs, next_cursor })
+    }
+}
+
+fn parse_run_cursor(cursor: &str) -> Result<(DateTime<Utc>, String), String> {
+    let invalid = || format!("Invalid cursor '{cursor}'");
+    let (millis, run_id) = cursor.split_once('_').ok_or_else(invalid)?;
+    let at = millis
+        .parse()
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .ok_or_else(invalid)?;
+    Ok((at, run_id.to_string()))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct AgentRunsResponse {
     pub runs: Vec<AgentRunStatus>,
+    /// Pass as `cursor` for the next page; absent on the last one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// A document whose extraction failed in a run.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunExtractionError {
+    pub source_id: String,
+    pub title: Option<String>,
+    pub error: String,
+    pub attempts: u32,
+    pub last_failed_at: DateTime<Utc>,
+    /// When a retry may run; `exhausted` documents are not retried.
+    pub next_attempt_at: DateTime<Utc>,
+    pub exhausted: bool,
+}
+
+impl From<&FailedDocument> for RunExtractionError {
+    fn from(failed: &FailedDocument) -> Self {
+        Self {
+            source_id: failed.document.source_id.clone(),
+            title: failed.document.title.clone(),
+            error: failed.error.clone(),
+            attempts: failed.attempts,
+            last_failed_at: failed.last_failed_at,
+            next_attempt_at: failed.next_attempt_at,
+            exhausted: failed.exhausted(),
+        }
+    }
+}
+
+/// Response of `GET /api/agents/runs/{id}`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AgentRunDetailResponse {
+    #[serde(flatten)]
+    pub run: AgentRunStatus,
+    /// Documents of the run still in the retry queue, oldest failure first.
+    pub extraction_errors: Vec<RunExtractionError>,
 }
 
 // --- Entities ---
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct EntitySearchRequest {
     pub query: String,
     #[serde(default = "default_limit")]
     pub limit: usize,
     pub entity_type: Option<EntityType>,
+    #[serde(default)]
+    pub min_sanctions_exposure: Option<f64>,
+    #[serde(default)]
+    pub sort: EntitySort,
+    /// What to search; `limit` applies to entities and relationships each.
+    #[serde(default)]
+    pub target: SearchTarget,
 }
 
 fn default_limit() -> usize {
     20
 }
 
+/// What an entity search looks through. Entity filters and sorting only
+/// apply to entities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum SearchTarget {
+    /// Entity names.
+    #[default]
+    Entities,
+    /// Relationship evidence and property values.
+    Relationships,
+    All,
+}
+
+impl SearchTarget {
+    pub fn includes_entities(self) -> bool {
+        matches!(self, SearchTarget::Entities | SearchTarget::All)
+    }
+
+    pub fn includes_relationships(self) -> bool {
+        matches!(self, SearchTarget::Relationships | SearchTarget::All)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct EntitySearchResponse {
     pub entities: Vec<Entity>,
+    /// Present when the search targeted relationships.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relationships: Vec<RelationshipSearchHit>,
+    /// Entities plus relationships returned.
     pub total: usize,
+    /// Per-entity category, color and icon, with `?include=presentation`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presentation: BTreeMap<Uuid, Presentation>,
 }
 
+/// A search in plain language, e.g. "Russian-flagged tankers seen near
+/// Ceuta in January".
 #[derive(Debug, Serialize, Deserialize)]
-pub struct EntityDetailResponse {
-    pub entity: Entity,
-    pub relationships: Vec<Relationship>,
-    pub neighbors: Vec<Entity>,
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct NaturalSearchRequest {
+    pub question: String,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
 }
 
-// --- Graph ---
-
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GraphQueryRequest {
-    pub cypher: String,
-    #[serde(default)]
-    pub params: serde_json::Value,
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct NaturalSearchResponse {
+    /// How the question was read; this is the search that ran.
+    pub interpretation: SearchInterpretation,
+    pub entities: Vec<Entity>,
+    pub total: usize,
+    /// Cost of translating the question.
+    pub usage: ReasoningUsage,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GraphQueryResponse {
-    pub result: serde_json::Value,
+pub struct EntityBatchRequest {
+    pub ids: Vec<Uuid>,
+    #[serde(default)]
+    pub include_relationships: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GraphStatsResponse {
-    pub entity_count: u64,
-    pub relationship_count: u64,
-    pub entity_types: Vec<EntityTypeStat>,
+pub struct EntityBatchResponse {
+    pub entities: Vec<EntityBatchItem>,
+    /// Requested ids with no matching entity.
+    pub missing: Vec<Uuid>,
+    /// Per-entity category, color and icon, with `?include=presentation`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presentation: BTreeMap<Uuid, Presentation>,
 }
 
+/// Entities found by an external identifier lookup.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct EntityTypeStat {
-    pub entity_type: EntityType,
-    pub count: u64,
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct EntityIdentifierResponse {
+    /// Canonical scheme the lookup used (e.g. `qid` is reported as `wikidata`).
+    pub scheme: String,
+    pub value: String,
+    pub entities: Vec<Entity>,
+    /// Per-entity category, color and icon, with `?include=presentation`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presentation: BTreeMap<Uuid, Presentation>,
 }
 
-// --- Reasoning ---
-
 #[derive(Debug, Serialize, Deserialize)]
-pub struct ReasoningRequest {
-    pub question: String,
-    pub context: Option<String>,
-    pub max_hops: Option<u32>,
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct EntityDetailResponse {
+    pub entity: Entity,
+    pub relationships: Vec<Relationship>,
+    pub neighbors: Vec<Entity>,
+    /// Edges pointing at the entity (e.g. "owned by").
+    #[serde(default)]
+    pub incoming: Vec<Relationship>,
+    /// Edges starting at the entity (e.g. "owns").
+    #[serde(default)]
+    pub outgoing: Vec<Relationship>,
+    /// Set when the neighborhood was capped; see `GraphNeighbors::truncated`.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Supporting quotes for the entity's relationships, for checking what
+    /// extraction concluded against the source text.
+    #[serde(default)]
+    pub evidence: Vec<RelationshipEvidence>,
+    /// Per-entity category, color and icon, with `?include=presentation`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presentation: BTreeMap<Uuid, Presentation>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ReasoningApiResponse {
-    pub answer: String,
+/// The source sentence a relationship was extracted from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RelationshipEvidence {
+    pub relationship_id: Uuid,
+    pub relation_type: RelationType,
+    /// As seen from the entity the detail is for.
+    pub direction: Direction,
+    /// The entity at the other end.
+    pub entity_id: Uuid,
+    pub quote: String,
+    pub source: String,
     pub confidence: f64,
-    pub steps: Vec<ReasoningStep>,
-    pub entities_referenced: Vec<Entity>,
-    pub sources: Vec<String>,
+    /// Where the quote sits in an ingested file, when known (1-based).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub page: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub paragraph: Option<u64>,
 }
 
-impl From<ReasoningResponse> for ReasoningApiResponse {
-    fn from(r: ReasoningResponse) -> Self {
-        Self {
-            answer: r.answer,
-            confidence: r.confidence,
-            steps: r.steps,
-            entities_referenced: r.entities_referenced,
-            sources: r.sources,
-        }
+impl RelationshipEvidence {
+    /// Evidence for `rel` as seen from `entity_id`, if it has a quote and
+    /// touches the entity.
+    pub fn from_relationship(rel: &Relationship, entity_id: Uuid) -> Option<Self> {
+        let quote = rel.evidence()?;
+        let direction = rel.direction_from(entity_id)?;
+        let entity_id = match direction {
+            Direction::Incoming => rel.source_entity_id,
+            _ => rel.target_entity_id,
+        };
+        Some(Self {
+            relationship_id: rel.id,
+            relation_type: rel.relation_type.clone(),
+            direction,
+            entity_id,
+            quote: quote.to_string(),
+            source: rel.source.clone(),
+            confidence: rel.confidence,
+            page: rel.properties.get("evidence_page").and_then(|p| p.as_u64()),
+            paragraph: rel
+                .properties
+                .get("evidence_paragraph")
+                .and_then(|p| p.as_u64()),
+        })
     }
 }
 
+impl EntityDetailResponse {
+    /// Build a detail response, splitting edges that touch the entity by direction.
+    pub fn new(entity: Entity, relationships: Vec<Relationship>, neighbors: Vec<Entity>) -> Self {
+        let mut incoming = Vec::new();
+        let mut outgoing = Vec::new();
+        let evidence = relationships
+            .iter()
+            .filter_map(|rel| RelationshipEvidence::from_relationship(rel, entity.id))
+            .collect();
+        for rel in &relationships {
+            match rel.direction_from(entity.id) {
+                Some(Direction::Outgoing) => outgoing.push(rel.clone()),
+                Some(Direction::Incoming) => incoming.push(rel.clone()),
+                _ => {}
+            }
+        }
+        Self {
+            entity,
+            relationships,
+            neighbors,
+            incoming,
+            outgoing,
+            truncated: false,
+            evidence,
+            presentation: BTreeMap::new(),
+        }
+    }
+
+    /// Fill in [`Self::presentation`] for the entity and its neighbors.
+    pub fn add_presentation(&mut self) {
+        self.presentation = presentations(std::iter::once(&self.entity).chain(&self.neighbors));
+    }
+}
+
+impl From<GraphNeighbors> for EntityDetailResponse {
+    fn from(n: GraphNeighbors) -> Self {
+        Self {
+            truncated: n.truncated,
+            ..Self::new(n.entity, n.relationships, n.neighbors)
+        }
+    }
+}
+
+/// Optional extras a response carries, as `?include=presentation`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IncludeParams {
+    /// Comma-separated extras; unknown names are ignored.
+    pub include: Option<String>,
+}
+
+impl IncludeParams {
+    fn includes(&self, extra: &str) -> bool {
+        self.include
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .any(|name| name.trim().eq_ignore_ascii_case(extra))
+    }
+
+    /// Whether entities should come with their [`Presentation`].
+    pub fn presentation(&self) -> bool {
+        self.includes("presentation")
+    }
+}
+
+/// Query-string options for neighbor traversal.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NeighborQueryParams {
+    pub depth: Option<u32>,
+    #[serde(default)]
+    pub direction: Direction,
+    /// Comma-separated relationship types, e.g. `owner_of,director_of`.
+    pub relation_types: Option<String>,
+    /// Answer from the graph as it stood at this instant (RFC 3339).
+    pub as_of: Option<DateTime<Utc>>,
+    /// Leave out relationships scored weaker than this (0.0–1.0).
+    pub min_strength: Option<f64>,
+}
+
+impl NeighborQueryParams {
+    /// The traversal filter, rejecting relationship types `ontology`
+    /// does not know.
+    pub fn filter(&self, ontology: &Ontology) -> Result<NeighborFilter, String> {
+        let relation_types = self
+            .relation_types
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|name| {
+                let relation_type = RelationType::from_name(name);
+                ontology
+                    .check_relation_type(&relation_type)
+                    .map(|()| relation_type)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(NeighborFilter {
+            direction: self.direction,
+            relation_types,
+            as_of: self.as_of,
+            min_strength: self.min_strength,
+        })
+    }
+}
+
+/// Upper bound on paths returned by one path request.
+pub const MAX_PATHS: usize = 20;
+
+/// Query-string options for `GET /api/graph/paths`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct PathQueryParams {
+    pub from: Uuid,
+    pub to: Uuid,
+    /// Hops, 1–6 (default 4).
+    pub max_depth: Option<u32>,
+    pub limit: Option<usize>,
+    /// Only paths through entities and relationships that existed at this
+    /// instant (RFC 3339).
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+impl PathQueryParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(5).clamp(1, MAX_PATHS)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct PathResponse {
+    pub paths: Vec<GraphPath>,
+}
+
+/// Upper bound on change-log entries returned by one history request.
+pub const MAX_HISTORY_LIMIT: usize = 1000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EntityHistoryParams {
+    pub limit: Option<usize>,
+}
+
+impl EntityHistoryParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(100).clamp(1, MAX_HISTORY_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct EntityHistoryResponse {
+    pub entity_id: Uuid,
+    /// Newest first.
+    pub changes: Vec<EntityChange>,
+}
+
+/// Query-string options for `POST /api/entities/{id}/enrich`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EnrichParams {
+    /// Comma-separated agent names to ask; every agent that can look the
+    /// entity up when unset.
+    pub agents: Option<String>,
+}
+
+impl EnrichParams {
+    /// The agents asked for, or `None` for all of them.
+    pub fn agent_names(&self) -> Option<Vec<String>> {
+        let names: Vec<String> = self
+            .agents
+            .as_deref()?
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+        (!names.is_empty()).then_some(names)
+    }
+}
+
+/// What one on-demand enrichment added to the graph.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct EnrichResponse {
+    pub entity_id: Uuid,
+    /// Run the looked-up documents were processed under; its documents and
+    /// extractions are at `/api/agents/runs/{run_id}/documents`.
+    pub run_id: String,
+    /// One per agent asked, in name order.
+    pub agents: Vec<AgentEnrichment>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub documents: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub entities: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub relationships: u64,
+}
+
+/// One agent's part of an enrichment.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AgentEnrichment {
+    pub agent: String,
+    /// Documents the lookup returned.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub documents: u64,
+    /// Entities and relationships stored from them, after validation.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub entities: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub relationships: u64,
+    /// Documents whose extraction failed; they are queued for retry.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub failed: u64,
+    /// Why the lookup itself failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// --- Documents ---
+
+pub const MAX_RUN_DOCUMENT_SAMPLE: usize = 100;
+
+/// Query-string options for `GET /api/agents/runs/{id}/documents`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RunDocumentsParams {
+    pub sample: Option<usize>,
+}
+
+impl RunDocumentsParams {
+    pub fn sample(&self) -> usize {
+        self.sample.unwrap_or(10).clamp(1, MAX_RUN_DOCUMENT_SAMPLE)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunDocumentsResponse {
+    pub run_id: String,
+    /// Documents the run archived; `documents` is a random sample of them.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub total: u64,
+    pub documents: Vec<ArchivedDocument>,
+}
+
+// --- Run logs ---
+
+/// One log event recorded while a run was in progress.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunLogLine {
+    pub timestamp: DateTime<Utc>,
+    /// `ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`.
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Spans the event happened in, outermost first, e.g. `["agent_run", "extract"]`.
+    #[serde(default)]
+    pub spans: Vec<String>,
+    /// The event's structured fields other than the message.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, serde_json::Value>,
+}
+
+/// Query-string options for `GET /api/agents/runs/{id}/logs`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RunLogParams {
+    /// Only lines at least this severe: `error`, `warn`, `info`, `debug` or `trace`.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunLogsResponse {
+    pub run_id: String,
+    /// Oldest first.
+    pub lines: Vec<RunLogLine>,
+    /// Earlier lines of the run that were no longer kept.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub dropped: u64,
+}
+
+/// Query parameters for `GET /api/agents/{name}/runs/compare`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunCompareParams {
+    pub base: String,
+    pub head: String,
+}
+
+/// A relative drop at least this large between runs is flagged.
+pub const REGRESSION_DROP: f64 = 0.5;
+
+/// A rise in error rate of at least this many percentage points is flagged.
+pub const REGRESSION_ERROR_RATE_POINTS: f64 = 10.0;
+
+/// Entity types with fewer entities than this in the base run are too
+/// noisy to flag.
+pub const REGRESSION_MIN_ENTITIES: u64 = 5;
+
+/// One side of a run comparison.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunSnapshot {
+    pub run: AgentRunStatus,
+    #[serde(flatten)]
+    pub stats: RunExtractionStats,
+    /// Percentage of collected documents whose extraction failed.
+    pub error_rate: f64,
+    /// Distinct entities per archived document.
+    pub entities_per_document: f64,
+}
+
+impl RunSnapshot {
+    pub fn new(run: AgentRunStatus, stats: RunExtractionStats) -> Self {
+        let ratio = |n: u64, d: u64| if d == 0 { 0.0 } else { n as f64 / d as f64 };
+        let entities = stats.new_entities + stats.repeated_entities;
+        Self {
+            error_rate: 100.0 * ratio(run.documents_failed, run.documents_collected),
+            entities_per_document: ratio(entities, stats.documents),
+            run,
+            stats,
+        }
+    }
+}
+
+/// Entity counts for one type in both runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct EntityTypeDelta {
+    pub entity_type: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub base: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub head: u64,
+}
+
+/// Response of `GET /api/agents/{name}/runs/compare`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunComparisonResponse {
+    pub agent_name: String,
+    pub base: RunSnapshot,
+    pub head: RunSnapshot,
+    /// Every entity type seen in either run.
+    pub entity_types: Vec<EntityTypeDelta>,
+    /// Drops large enough to suggest the source changed underneath the
+    /// agent; empty when `head` looks like `base`.
+    pub regressions: Vec<String>,
+}
+
+impl RunComparisonResponse {
+    pub fn new(agent_name: String, base: RunSnapshot, head: RunSnapshot) -> Self {
+        let mut types: Vec<&String> = base
+            .stats
+            .entities_by_type
+            .keys()
+            .chain(head.stats.entities_by_type.keys())
+            .collect();
+        types.sort();
+        types.dedup();
+        let count =
+            |side: &RunSnapshot, t: &str| side.stats.entities_by_type.get(t).copied().unwrap_or(0);
+        let entity_types: Vec<EntityTypeDelta> = types
+            .into_iter()
+            .map(|t| EntityTypeDelta {
+                entity_type: t.clone(),
+                base: count(&base, t),
+                head: count(&head, t),
+            })
+            .collect();
+
+        let dropped =
+            |before: f64, after: f64| before > 0.0 && (before - after) / before >= REGRESSION_DROP;
+        let mut regressions = Vec::new();
+        if dropped(
+            base.run.documents_collected as f64,
+            head.run.documents_collected as f64,
+        ) {
+            regressions.push(format!(
+                "documents collected fell from {} to {}",
+                base.run.documents_collected, head.run.documents_collected
+            ));
+        }
+        if dropped(base.entities_per_document, head.entities_per_document) {
+            regressions.push(format!(
+                "entities per document fell from {:.2} to {:.2}",
+                base.entities_per_document, head.entities_per_document
+            ));
+        }
+        if head.error_rate - base.error_rate >= REGRESSION_ERROR_RATE_POINTS {
+            regressions.push(format!(
+                "extraction error rate rose from {:.1}% to {:.1}%",
+                base.error_rate, head.error_rate
+            ));
+        }
+        for delta in &entity_types {
+            if delta.base >= REGRESSION_MIN_ENTITIES
+                && dropped(delta.base as f64, delta.head as f64)
+            {
+                regressions.push(format!(
+                    "{} entities fell from {} to {}",
+                    delta.entity_type, delta.base, delta.head
+                ));
+            }
+        }
+
+        Self {
+            agent_name,
+            base,
+            head,
+            entity_types,
+            regressions,
+        }
+    }
+}
+
+/// A file attached to an ingestion request, base64-encoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct IngestFile {
+    #[serde(default)]
+    pub filename: Option<String>,
+    /// MIME type; guessed from `filename` when absent.
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Standard base64 of the file's bytes.
+    pub data: String,
+}
+
+/// Body of `POST /api/documents/ingest`: raw text, a file, or both (the
+/// file's text follows `content`).
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DocumentIngestRequest {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub file: Option<IngestFile>,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Stable id for the document; re-ingesting under the same id updates
+    /// what it produced. Derived from the text when absent.
+    #[serde(default)]
+    pub source_id: Option<String>,
+    /// When the document was written, if known.
+    #[serde(default)]
+    pub observed_at: Option<DateTime<Utc>>,
+    /// Free-form metadata kept with the archived document.
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DocumentIngestResponse {
+    pub run_id: String,
+    pub source: String,
+    pub source_id: String,
+    /// Characters of text handed to extraction.
+    pub characters: usize,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DocumentSearchRequest {
+    pub query: String,
+    pub source: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DocumentSearchResult {
+    pub id: Uuid,
+    pub source: String,
+    pub source_id: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub collected_at: DateTime<Utc>,
+    pub score: f64,
+    /// Excerpt with matches wrapped in `<mark>` tags; other text is HTML-escaped.
+    pub snippet: String,
+    /// Entities extracted from this document (hydrate via /api/entities/batch).
+    pub entity_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DocumentSearchResponse {
+    pub results: Vec<DocumentSearchResult>,
+    pub total: usize,
+}
+
+// --- Export ---
+
+/// Query-string options for `GET /api/entities/{id}/export`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExportParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Neighbor depth to include around the entity (default 1).
+    pub depth: Option<u32>,
+    /// Leave out relationships scored weaker than this (0.0–1.0).
+    pub min_strength: Option<f64>,
+}
+
+/// Export of an investigation: a set of seed entities and their surroundings.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct InvestigationExportRequest {
+    pub entity_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Neighbor depth to include around each seed (default 1).
+    pub depth: Option<u32>,
+    /// Leave out relationships scored weaker than this (0.0–1.0).
+    #[serde(default)]
+    pub min_strength: Option<f64>,
+}
+
+// --- Investigations ---
+
+/// Body of `POST /api/investigations`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct InvestigationRequest {
+    pub name: String,
+    pub entity_ids: Vec<Uuid>,
+}
+
+/// Body of `POST /api/investigations/{id}/notes`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct InvestigationNoteRequest {
+    pub text: String,
+    /// One of the investigation's entities the note is about.
+    #[serde(default)]
+    pub entity_id: Option<Uuid>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// When what the note describes happened (default now).
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct InvestigationListResponse {
+    /// Newest first.
+    pub investigations: Vec<Investigation>,
+}
+
+/// How `GET /api/investigations/{id}/timeline` is returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Query-string options for `GET /api/investigations/{id}/timeline`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CaseTimelineParams {
+    #[serde(default)]
+    pub format: TimelineFormat,
+    /// Only entries from this time on.
+    pub start: Option<DateTime<Utc>>,
+    /// Only entries up to this time.
+    pub end: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct CaseTimelineResponse {
+    pub investigation_id: Uuid,
+    /// Oldest first.
+    pub events: Vec<CaseEvent>,
+    /// Some entity neighborhoods or position histories were capped, so
+    /// older entries may be missing.
+    pub truncated: bool,
+}
+
+// --- Import ---
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FtmImportParams {
+    /// Source name imported entities are attributed to (default `ftm`).
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct FtmImportResponse {
+    pub entities: usize,
+    pub relationships: usize,
+    /// FtM ids of link entities missing an endpoint.
+    pub skipped: Vec<String>,
+    /// 1-based line numbers that were not valid FtM JSON.
+    pub invalid_lines: Vec<usize>,
+}
+
+// --- Graph ---
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct GraphQueryRequest {
+    pub cypher: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+/// Query-string options for `POST /api/graph/query`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GraphQueryParams {
+    /// Send rows as newline-delimited JSON as they are read, instead of
+    /// one JSON document.
+    #[serde(default)]
+    pub stream: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct GraphQueryResponse {
+    pub result: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct GraphStatsResponse {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub entity_count: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub relationship_count: u64,
+    pub entity_types: Vec<EntityTypeStat>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct EntityTypeStat {
+    pub entity_type: EntityType,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub count: u64,
+}
+
+// --- Relationships ---
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelationshipListResponse {
+    pub relationships: Vec<Relationship>,
+    pub limit: usize,
+    pub offset: usize,
+    pub has_more: bool,
+}
+
+// --- Reasoning ---
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ReasoningRequest {
+    /// Free-form question; may be left out when `template` is set.
+    #[serde(default)]
+    pub question: String,
+    pub context: Option<String>,
+    pub max_hops: Option<u32>,
+    /// Record the answer in the graph; defaults to `REASONING_PERSIST_ANSWERS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persist: Option<bool>,
+    /// Named question template, as listed by `GET /api/reasoning/templates`;
+    /// replaces `question` and `max_hops`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// The template's parameters.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReasoningApiResponse {
+    pub answer: String,
+    pub confidence: f64,
+    pub steps: Vec<ReasoningStep>,
+    pub entities_referenced: Vec<Entity>,
+    pub sources: Vec<String>,
+    #[serde(default)]
+    pub usage: ReasoningUsage,
+    /// Document node the answer was recorded as, when it was persisted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<Uuid>,
+}
+
+/// A parameter of a [`ReasoningTemplate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ReasoningTemplateParam {
+    pub name: String,
+    pub description: String,
+    pub required: bool,
+}
+
+/// A named question usable as `template` in a reasoning request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ReasoningTemplate {
+    pub name: String,
+    pub description: String,
+    pub params: Vec<ReasoningTemplateParam>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ReasoningTemplateListResponse {
+    pub templates: Vec<ReasoningTemplate>,
+}
+
+impl From<ReasoningResponse> for ReasoningApiResponse {
+    fn from(r: ReasoningResponse) -> Self {
+        Self {
+            answer: r.answer,
+            confidence: r.confidence,
+            steps: r.steps,
+            entities_referenced: r.entities_referenced,
+            sources: r.sources,
+            usage: r.usage,
+            document_id: None,
+        }
+    }
+}
+
+// --- Briefing ---
+
+/// Query-string options for `GET /api/briefing`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct BriefingParams {
+    /// How far back to look, e.g. `90m`, `24h` or `7d`; defaults to 24 hours.
+    pub since: Option<String>,
+}
+
+impl BriefingParams {
+    pub fn window(&self) -> Result<chrono::Duration, String> {
+        parse_window(self.since.as_deref().unwrap_or(DEFAULT_BRIEFING_WINDOW))
+    }
+}
+
+// --- Analytics ---
+
+/// Query-string options for `GET /api/analytics/event-density`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EventDensityParams {
+    /// `west,south,east,north` in degrees; defaults to the whole world.
+    pub bbox: Option<String>,
+    /// Bucket length, e.g. `15m`, `1h` or `1d`; defaults to an hour.
+    pub interval: Option<String>,
+    /// Defaults to 24 hours before `until`.
+    pub since: Option<DateTime<Utc>>,
+    /// Defaults to now.
+    pub until: Option<DateTime<Utc>>,
+    /// Geohash length, 1–7; defaults to the finest that keeps the box
+    /// within about a thousand cells.
+    pub precision: Option<u8>,
+}
+
+impl EventDensityParams {
+    pub fn query(&self, now: DateTime<Utc>) -> Result<DensityQuery, String> {
+        let bbox = match &self.bbox {
+            Some(bbox) => BoundingBox::parse(bbox)?,
+            None => BoundingBox::WORLD,
+        };
+        let interval =
+            parse_interval(self.interval.as_deref().unwrap_or(DEFAULT_DENSITY_INTERVAL))?;
+        let until = self.until.unwrap_or(now);
+        let since = self
+            .since
+            .unwrap_or(until - chrono::Duration::hours(DEFAULT_DENSITY_WINDOW_HOURS));
+        if since >= until {
+            return Err("since must be before until".to_string());
+        }
+        if (until - since).num_seconds() / interval.num_seconds() >= MAX_DENSITY_BUCKETS {
+            return Err(format!(
+                "The window spans more than {MAX_DENSITY_BUCKETS} intervals; use a longer interval"
+            ));
+        }
+        let precision = match self.precision {
+            Some(precision) if !(1..=MAX_GEOHASH_PRECISION).contains(&precision) => {
+                return Err(format!(
+                    "precision must be between 1 and {MAX_GEOHASH_PRECISION}"
+                ));
+            }
+            Some(precision) => precision,
+            None => bbox.auto_precision(),
+        };
+        Ok(DensityQuery {
+            bbox,
+            since,
+            until,
+            interval,
+            precision,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventDensityResponse {
+    pub bbox: BoundingBox,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub interval_seconds: i64,
+    pub precision: u8,
+    /// Events and position reports counted across all cells.
+    pub total: u64,
+    /// Whether the least active cells were left out.
+    pub truncated: bool,
+    pub cells: Vec<DensityCell>,
+}
+
+/// Upper bound on archived positions returned by one request.
+pub const MAX_ARCHIVED_POSITIONS: usize = 10_000;
+
+/// Longest span one `GET /api/analytics/positions` reads, in days.
+pub const MAX_ARCHIVED_POSITION_DAYS: i64 = 31;
+
+/// `GET /api/analytics/cold-storage`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ColdStorageResponse {
+    /// By table, then day.
+    pub partitions: Vec<ColdPartition>,
+    /// Rows waiting for the next flush.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub buffered: u64,
+}
+
+/// Query-string options for `GET /api/analytics/positions`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ArchivedPositionsParams {
+    /// `scheme:value`, e.g. `mmsi:244660000`.
+    pub asset: String,
+    /// Defaults to 7 days before `until`; at most
+    /// [`MAX_ARCHIVED_POSITION_DAYS`] before it.
+    pub since: Option<DateTime<Utc>>,
+    /// Defaults to now.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl ArchivedPositionsParams {
+    pub fn window(&self, now: DateTime<Utc>) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let until = self.until.unwrap_or(now);
+        let since = self.since.unwrap_or(until - chrono::Duration::days(7));
+        if since >= until {
+            return Err("since must be before until".to_string());
+        }
+        if until - since > chrono::Duration::days(MAX_ARCHIVED_POSITION_DAYS) {
+            return Err(format!(
+                "since must be at most {MAX_ARCHIVED_POSITION_DAYS} days before until"
+            ));
+        }
+        Ok((since, until))
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(1000).clamp(1, MAX_ARCHIVED_POSITIONS)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedPositionsResponse {
+    pub asset: String,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// Oldest first.
+    pub positions: Vec<PositionReport>,
+}
+
+/// Upper bound on rows returned by one SQL query.
+pub const MAX_SQL_ROWS: usize = 10_000;
+
+/// Body of `POST /api/analytics/sql`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AnalyticsSqlRequest {
+    /// One read-only query over `positions`, `events` and `documents`.
+    pub sql: String,
+    /// Rows returned; defaults to 1000.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl AnalyticsSqlRequest {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(1000).clamp(1, MAX_SQL_ROWS)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AnalyticsSqlResponse {
+    pub columns: Vec<SqlColumn>,
+    /// One object per row, keyed by column name.
+    pub rows: Vec<serde_json::Value>,
+    /// Whether the query returned more rows than `limit`.
+    pub truncated: bool,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub elapsed_ms: u64,
+}
+
+// --- Feedback ---
+
+/// Upper bound on labels returned by one feedback list request.
+pub const MAX_FEEDBACK_LIMIT: usize = 1000;
+
+/// Body of `POST /api/feedback`. Entities and relationships are looked up
+/// by `target_id` for their confidence, source and type; answers take
+/// `target_id` when persisted (its `document_id`) and otherwise need the
+/// `confidence` the answer was given.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct FeedbackRequest {
+    pub target: FeedbackTarget,
+    #[serde(default)]
+    pub target_id: Option<Uuid>,
+    pub correct: bool,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct FeedbackListParams {
+    pub target: Option<FeedbackTarget>,
+    pub limit: Option<usize>,
+}
+
+impl FeedbackListParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(100).clamp(1, MAX_FEEDBACK_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct FeedbackListResponse {
+    /// Newest first.
+    pub labels: Vec<FeedbackLabel>,
+    /// Labels matching `target`, before `limit`.
+    pub total: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct CalibrationParams {
+    /// Only labels on this kind of item.
+    pub target: Option<FeedbackTarget>,
+    #[serde(default)]
+    pub by: CalibrationGrouping,
+    pub bins: Option<usize>,
+    /// Precision the suggested thresholds must reach, from 0 to 1.
+    pub precision: Option<f64>,
+}
+
+impl CalibrationParams {
+    pub fn bins(&self) -> usize {
+        self.bins
+            .unwrap_or(DEFAULT_CALIBRATION_BINS)
+            .clamp(1, MAX_CALIBRATION_BINS)
+    }
+
+    pub fn precision(&self) -> f64 {
+        self.precision
+            .filter(|p| p.is_finite())
+            .unwrap_or(DEFAULT_TARGET_PRECISION)
+            .clamp(0.0, 1.0)
+    }
+}
+
+// --- Prompt bundles ---
+
+/// Body of `POST /api/admin/prompt-bundles`. Examples are chosen from the
+/// most recent feedback labels.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PromptBundleRequest {
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub per_source: Option<usize>,
+}
+
+impl PromptBundleRequest {
+    pub fn per_source(&self) -> usize {
+        self.per_source
+            .unwrap_or(DEFAULT_EXAMPLES_PER_SOURCE)
+            .clamp(1, MAX_EXAMPLES_PER_SOURCE)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromptBundleListResponse {
+    /// Newest first.
+    pub bundles: Vec<PromptBundle>,
+    pub deployment: PromptDeployment,
+}
+
+/// Body of `POST /api/admin/prompt-deployment`. Version 0 is the prompt
+/// without examples.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromptDeploymentRequest {
+    pub control: u32,
+    #[serde(default)]
+    pub candidate: Option<u32>,
+    /// Share of documents extracted with `candidate`, from 0 to 1.
+    #[serde(default)]
+    pub candidate_share: Option<f64>,
+}
+
+/// Versions to compare; each defaults to the deployed one.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PromptCompareParams {
+    pub control: Option<u32>,
+    pub candidate: Option<u32>,
+}
+
+// --- Configuration ---
+
+/// Outcome of re-reading the configuration. Settings are named by their
+/// `AppConfig` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigReloadResponse {
+    /// Changed settings now in effect.
+    pub applied: Vec<String>,
+    /// Changed settings ignored until the server restarts.
+    pub requires_restart: Vec<String>,
+    pub reloaded_at: DateTime<Utc>,
+}
+
 // --- Timeline ---
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct TimelineRequest {
     pub entity_id: Option<Uuid>,
     pub start: Option<DateTime<Utc>>,
@@ -159,6 +1917,7 @@ pub struct TimelineRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct TimelineEvent {
     pub timestamp: DateTime<Utc>,
     pub entity: Entity,
@@ -168,6 +1927,7 @@ pub struct TimelineEvent {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct TimelineResponse {
     pub events: Vec<TimelineEvent>,
 }
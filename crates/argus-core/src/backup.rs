@@ -0,0 +1,163 @@
+//! Workspace snapshots: every graph node and relationship plus the shared
+//! state that lives outside the graph, in one gzip-compressed JSON Lines
+//! file that a restore can rebuild the workspace from.
+//!
+//! A snapshot opens with a [`SnapshotRecord::Meta`] line and closes with a
+//! [`SnapshotRecord::End`] line carrying the record counts, so a truncated
+//! file is caught before anything is restored from it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::AlertRule;
+use crate::deny_list::DenyEntry;
+
+/// Bumped whenever a record changes shape. Restores refuse snapshots from a
+/// newer format than they know.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Nodes or relationships restored per graph round-trip, and read by a
+/// backup between progress updates.
+pub const SNAPSHOT_BATCH_SIZE: usize = 1000;
+
+/// File extension of snapshot files.
+pub const SNAPSHOT_EXTENSION: &str = "jsonl.gz";
+
+/// First line of every snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotMeta {
+    pub format_version: u32,
+    /// Version of the Argus build that wrote the snapshot.
+    pub argus_version: String,
+    pub created_at: DateTime<Utc>,
+    /// Replica that wrote it.
+    pub instance_id: String,
+}
+
+/// Records in a snapshot, for checking it is complete.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct SnapshotCounts {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub nodes: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub relationships: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub alert_rules: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub deny_entries: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub paused_agents: u64,
+}
+
+/// A graph node as stored: labels and properties, plus a reference that is
+/// unique within the snapshot for relationships to point at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeDump {
+    #[serde(rename = "ref")]
+    pub node_ref: String,
+    pub labels: Vec<String>,
+    pub properties: serde_json::Value,
+}
+
+/// A relationship between two [`NodeDump`] references.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationshipDump {
+    #[serde(rename = "ref")]
+    pub rel_ref: String,
+    #[serde(rename = "type")]
+    pub rel_type: String,
+    pub start: String,
+    pub end: String,
+    pub properties: serde_json::Value,
+}
+
+/// One line of a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotRecord {
+    Meta(SnapshotMeta),
+    /// The writer's configuration with secrets removed, for reference. A
+    /// restore does not apply it; configuration comes from the environment.
+    Config {
+        config: serde_json::Value,
+    },
+    Node(NodeDump),
+    Relationship(RelationshipDump),
+    AlertRule(AlertRule),
+    DenyEntry(DenyEntry),
+    PausedAgent {
+        name: String,
+    },
+    End(SnapshotCounts),
+}
+
+/// A finished snapshot, as listed by the backup API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// File name within the backup directory.
+    pub file: String,
+    pub meta: SnapshotMeta,
+    pub counts: SnapshotCounts,
+    pub bytes: u64,
+}
+
+/// Whether `name` can be a snapshot file name: no directories, no hidden
+/// files, and the snapshot extension.
+pub fn is_snapshot_name(name: &str) -> bool {
+    name.strip_suffix(&format!(".{SNAPSHOT_EXTENSION}"))
+        .is_some_and(|stem| {
+            !stem.is_empty()
+                && !stem.starts_with('.')
+                && stem
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+}
+
+/// Whether `label` (a node label or relationship type) can be written back
+/// into Cypher safely.
+pub fn is_restorable_label(label: &str) -> bool {
+    label.starts_with(|c: char| c.is_ascii_alphabetic())
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_are_tagged_by_kind() {
+        let record = SnapshotRecord::Node(NodeDump {
+            node_ref: "4:abc:0".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: serde_json::json!({ "name": "Jane Doe" }),
+        });
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["kind"], "node");
+        assert_eq!(json["ref"], "4:abc:0");
+        assert_eq!(
+            serde_json::from_value::<SnapshotRecord>(json).unwrap(),
+            record
+        );
+
+        let json = serde_json::to_value(SnapshotRecord::PausedAgent {
+            name: "gdelt".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "paused_agent", "name": "gdelt" })
+        );
+    }
+
+    #[test]
+    fn snapshot_names_stay_inside_the_backup_directory() {
+        assert!(is_snapshot_name("argus-20260114T030000Z.jsonl.gz"));
+        assert!(!is_snapshot_name("../argus.jsonl.gz"));
+        assert!(!is_snapshot_name(".jsonl.gz"));
+        assert!(!is_snapshot_name("argus.json"));
+        assert!(is_restorable_label("SourceDocument"));
+        assert!(!is_restorable_label("Person`) DETACH DELETE (n"));
+    }
+}
@@ -0,0 +1,310 @@
+//! Daily intelligence briefings: what entered the graph and which alerts
+//! fired over a recent window, summarized by the reasoning engine.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::alerts::{Alert, AlertSeverity};
+use crate::entity::{Entity, EntityType, RelationType, Relationship};
+use crate::reasoning::ReasoningUsage;
+
+/// Window used when a briefing request names none.
+pub const DEFAULT_BRIEFING_WINDOW: &str = "24h";
+
+/// Longest window a briefing covers.
+pub const MAX_BRIEFING_WINDOW_HOURS: i64 = 7 * 24;
+
+/// New entities, new relationships and alerts each listed in one briefing,
+/// newest first. Anything beyond is counted but not listed.
+pub const MAX_BRIEFING_ITEMS: usize = 200;
+
+/// Items of each kind written into the summarization prompt.
+const MAX_PROMPT_ITEMS: usize = 60;
+
+/// Parse a window like `90m`, `24h` or `7d` into a duration of at most
+/// [`MAX_BRIEFING_WINDOW_HOURS`].
+pub fn parse_window(window: &str) -> Result<Duration, String> {
+    let window = window.trim();
+    let invalid =
+        || format!("Invalid window '{window}': expected a number followed by m, h or d, e.g. 24h");
+    let (split, _) = window.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = window.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let minutes = match unit {
+        "m" | "M" => Some(amount),
+        "h" | "H" => amount.checked_mul(60),
+        "d" | "D" => amount.checked_mul(24 * 60),
+        _ => return Err(invalid()),
+    };
+    match minutes {
+        Some(minutes) if minutes > MAX_BRIEFING_WINDOW_HOURS * 60 => Err(format!(
+            "Window '{window}' is longer than {MAX_BRIEFING_WINDOW_HOURS}h"
+        )),
+        Some(minutes) if minutes > 0 => Ok(Duration::minutes(minutes)),
+        None if amount > 0 => Err(format!(
+            "Window '{window}' is longer than {MAX_BRIEFING_WINDOW_HOURS}h"
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+/// An entity a briefing links to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct BriefingEntity {
+    pub id: Uuid,
+    pub name: String,
+    pub entity_type: EntityType,
+    pub source: String,
+    /// API path of the entity's detail.
+    pub link: String,
+}
+
+impl From<&Entity> for BriefingEntity {
+    fn from(entity: &Entity) -> Self {
+        Self {
+            id: entity.id,
+            name: entity.name.clone(),
+            entity_type: entity.entity_type.clone(),
+            source: entity.source.clone(),
+            link: entity_link(entity.id),
+        }
+    }
+}
+
+/// A new relationship, with the names of the entities it joins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct BriefingRelationship {
+    pub id: Uuid,
+    pub relation_type: RelationType,
+    pub source_entity_id: Uuid,
+    pub source_name: String,
+    pub target_entity_id: Uuid,
+    pub target_name: String,
+    pub source: String,
+    pub confidence: f64,
+}
+
+impl BriefingRelationship {
+    /// `names` maps entity ids to names; endpoints missing from it are shown
+    /// by id.
+    pub fn new(rel: &Relationship, names: &HashMap<Uuid, String>) -> Self {
+        let name = |id: &Uuid| names.get(id).cloned().unwrap_or_else(|| id.to_string());
+        Self {
+            id: rel.id,
+            relation_type: rel.relation_type.clone(),
+            source_entity_id: rel.source_entity_id,
+            source_name: name(&rel.source_entity_id),
+            target_entity_id: rel.target_entity_id,
+            target_name: name(&rel.target_entity_id),
+            source: rel.source.clone(),
+            confidence: rel.confidence,
+        }
+    }
+}
+
+/// An alert raised in the window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct BriefingAlert {
+    pub id: Uuid,
+    pub rule_name: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub entities: Vec<BriefingEntity>,
+}
+
+impl From<&Alert> for BriefingAlert {
+    fn from(alert: &Alert) -> Self {
+        Self {
+            id: alert.id,
+            rule_name: alert.rule_name.clone(),
+            severity: alert.severity,
+            message: alert.message.clone(),
+            created_at: alert.created_at,
+            entities: alert
+                .entities
+                .iter()
+                .map(|e| BriefingEntity {
+                    id: e.id,
+                    name: e.name.clone(),
+                    entity_type: e.entity_type.clone(),
+                    source: alert.source.clone(),
+                    link: entity_link(e.id),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// How much changed in the window, including what was not listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct BriefingCounts {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub new_entities: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub new_relationships: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub alerts: u64,
+    /// Some items were left out of the lists.
+    pub truncated: bool,
+}
+
+/// The answer to `GET /api/briefing`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Briefing {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// The engine's summary of the window; a fixed note when nothing changed.
+    pub summary: String,
+    pub confidence: f64,
+    /// Entities the summary names.
+    pub key_entities: Vec<BriefingEntity>,
+    pub new_entities: Vec<BriefingEntity>,
+    pub new_relationships: Vec<BriefingRelationship>,
+    pub alerts: Vec<BriefingAlert>,
+    pub counts: BriefingCounts,
+    #[serde(default)]
+    pub usage: ReasoningUsage,
+}
+
+fn entity_link(id: Uuid) -> String {
+    format!("/api/entities/{id}")
+}
+
+/// Instructions given to the reasoning engine with [`briefing_material`].
+pub fn briefing_instructions(since: DateTime<Utc>, until: DateTime<Utc>) -> String {
+    format!(
+        "Write a daily intelligence briefing covering {} to {}. Lead with the most significant \
+         developments: alerts first, then new relationships that connect notable entities, then \
+         notable new entities. Group related items, note patterns across sources, and say plainly \
+         when the window was quiet. Refer to entities by their exact names.",
+        since.to_rfc3339(),
+        until.to_rfc3339()
+    )
+}
+
+/// The window's changes as prompt text, most significant alerts first.
+pub fn briefing_material(
+    entities: &[BriefingEntity],
+    relationships: &[BriefingRelationship],
+    alerts: &[BriefingAlert],
+    counts: &BriefingCounts,
+) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("ALERTS ({}):\n", counts.alerts));
+    let mut by_severity: Vec<&BriefingAlert> = alerts.iter().collect();
+    by_severity.sort_by_key(|a| std::cmp::Reverse(a.severity));
+    for alert in by_severity.iter().take(MAX_PROMPT_ITEMS) {
+        out.push_str(&format!(
+            "- [{:?}] {}: {}\n",
+            alert.severity, alert.rule_name, alert.message
+        ));
+    }
+    if alerts.is_empty() {
+        out.push_str("- none\n");
+    }
+
+    out.push_str(&format!(
+        "\nNEW RELATIONSHIPS ({}):\n",
+        counts.new_relationships
+    ));
+    for rel in relationships.iter().take(MAX_PROMPT_ITEMS) {
+        out.push_str(&format!(
+            "- {} -[{}]-> {} (source: {}, confidence {:.2})\n",
+            rel.source_name, rel.relation_type, rel.target_name, rel.source, rel.confidence
+        ));
+    }
+    if relationships.is_empty() {
+        out.push_str("- none\n");
+    }
+
+    out.push_str(&format!("\nNEW ENTITIES ({}):\n", counts.new_entities));
+    for entity in entities.iter().take(MAX_PROMPT_ITEMS) {
+        out.push_str(&format!(
+            "- {} ({}, source: {})\n",
+            entity.name, entity.entity_type, entity.source
+        ));
+    }
+    if entities.is_empty() {
+        out.push_str("- none\n");
+    }
+
+    let listed = relationships.len().max(entities.len()).max(alerts.len());
+    if counts.truncated || listed > MAX_PROMPT_ITEMS {
+        out.push_str("\nOnly the most recent items are listed; the counts above are complete.\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_parse_with_units_and_a_ceiling() {
+        assert_eq!(parse_window("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_window("90m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_window("7d").unwrap(), Duration::days(7));
+        assert!(parse_window("8d").is_err());
+        assert!(parse_window("999999999999d").is_err());
+        assert!(parse_window("0h").is_err());
+        assert!(parse_window("h").is_err());
+        assert!(parse_window("24").is_err());
+        assert!(parse_window("").is_err());
+        assert!(parse_window("24é").is_err());
+    }
+
+    #[test]
+    fn material_lists_alerts_by_severity_and_names_endpoints() {
+        let acme = Uuid::new_v4();
+        let names = HashMap::from([(acme, "Acme Holdings".to_string())]);
+        let rel = Relationship {
+            id: Uuid::new_v4(),
+            source_entity_id: acme,
+            target_entity_id: Uuid::new_v4(),
+            relation_type: RelationType::SanctionedBy,
+            properties: serde_json::json!({}),
+            confidence: 0.9,
+            source: "opensanctions".to_string(),
+            timestamp: None,
+            strength: None,
+        };
+        let relationship = BriefingRelationship::new(&rel, &names);
+        assert_eq!(relationship.source_name, "Acme Holdings");
+        assert_eq!(relationship.target_name, rel.target_entity_id.to_string());
+
+        let alert = |severity, rule_name: &str| BriefingAlert {
+            id: Uuid::new_v4(),
+            rule_name: rule_name.to_string(),
+            severity,
+            message: "matched".to_string(),
+            created_at: Utc::now(),
+            entities: Vec::new(),
+        };
+        let alerts = vec![
+            alert(AlertSeverity::Low, "watchlist"),
+            alert(AlertSeverity::Critical, "sanctions"),
+        ];
+        let counts = BriefingCounts {
+            new_entities: 0,
+            new_relationships: 1,
+            alerts: 2,
+            truncated: false,
+        };
+
+        let material = briefing_material(&[], &[relationship], &alerts, &counts);
+        assert!(material.find("sanctions").unwrap() < material.find("watchlist").unwrap());
+        assert!(material.contains("Acme Holdings -[sanctioned_by]->"));
+        assert!(material.contains("NEW ENTITIES (0):\n- none"));
+        assert!(!material.contains("Only the most recent"));
+    }
+}
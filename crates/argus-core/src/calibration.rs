@@ -0,0 +1,411 @@
+//! Confidence calibration: analysts label entities, relationships and
+//! reasoning answers correct or incorrect, and the labels are compared with
+//! the confidence the system gave each item.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Reliability bins used when a report request names none.
+pub const DEFAULT_CALIBRATION_BINS: usize = 10;
+
+/// Most reliability bins one report may use.
+pub const MAX_CALIBRATION_BINS: usize = 50;
+
+/// Share of labels at or above a suggested threshold that must be correct,
+/// unless a report request names another.
+pub const DEFAULT_TARGET_PRECISION: f64 = 0.9;
+
+/// Labels needed at or above a confidence before it is suggested as a
+/// threshold.
+pub const MIN_LABELS_FOR_THRESHOLD: u64 = 5;
+
+/// What kind of item a label judges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackTarget {
+    Entity,
+    Relationship,
+    Answer,
+}
+
+impl fmt::Display for FeedbackTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FeedbackTarget::Entity => "entity",
+            FeedbackTarget::Relationship => "relationship",
+            FeedbackTarget::Answer => "answer",
+        })
+    }
+}
+
+/// An analyst's verdict on one item, with the confidence the system gave it
+/// at the time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct FeedbackLabel {
+    pub id: Uuid,
+    pub target: FeedbackTarget,
+    /// The entity, relationship or persisted answer judged; unset for
+    /// answers that were not persisted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<Uuid>,
+    pub correct: bool,
+    pub confidence: f64,
+    /// Data source that produced the item; `reasoning` for answers.
+    pub source: String,
+    /// Entity type or relationship type; `answer` for answers.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Extraction prompt bundle the item was extracted with, when one was
+    /// deployed; see [`crate::prompts`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_version: Option<u32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FeedbackLabel {
+    /// Identifies the item judged, so a new label replaces an earlier one
+    /// on the same item. Unpersisted answers are each their own item.
+    pub fn item_key(&self) -> String {
+        match self.target_id {
+            Some(id) => format!("{}:{id}", self.target),
+            None => format!("{}:label:{}", self.target, self.id),
+        }
+    }
+}
+
+/// How a calibration report groups labels within each target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationGrouping {
+    Source,
+    Type,
+    #[default]
+    SourceType,
+}
+
+/// One bar of a reliability diagram: labels whose confidence fell in
+/// `[lower, upper)` (the last bin includes 1.0).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ReliabilityBin {
+    pub lower: f64,
+    pub upper: f64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub count: u64,
+    /// Unset for empty bins.
+    pub mean_confidence: Option<f64>,
+    /// Share of the bin's labels that were correct; unset for empty bins.
+    pub accuracy: Option<f64>,
+}
+
+/// Calibration of one group of labels. `target`, `source` and `kind` are
+/// unset where the group spans several.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct CalibrationGroup {
+    pub target: Option<FeedbackTarget>,
+    pub source: Option<String>,
+    pub kind: Option<String>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub labels: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub correct: u64,
+    pub accuracy: f64,
+    pub mean_confidence: f64,
+    /// Label-weighted mean gap between each bin's confidence and accuracy.
+    pub expected_calibration_error: f64,
+    /// Mean squared gap between confidence and outcome (1 correct, 0 not).
+    pub brier_score: f64,
+    /// Lowest confidence at which items at or above it were correct at
+    /// least the report's `target_precision` of the time, given at least
+    /// [`MIN_LABELS_FOR_THRESHOLD`] labels; unset when none qualifies.
+    pub suggested_threshold: Option<f64>,
+    pub bins: Vec<ReliabilityBin>,
+}
+
+/// The answer to `GET /api/feedback/calibration`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct CalibrationReport {
+    pub grouping: CalibrationGrouping,
+    pub target_precision: f64,
+    /// Every label considered, in one group.
+    pub overall: CalibrationGroup,
+    /// Most labelled first.
+    pub groups: Vec<CalibrationGroup>,
+}
+
+/// Target, source and kind a group is split on; unset where not split.
+type GroupKey<'a> = (FeedbackTarget, Option<&'a str>, Option<&'a str>);
+
+/// Build a report over `labels` with `bins` equal-width reliability bins.
+pub fn calibration_report(
+    labels: &[FeedbackLabel],
+    grouping: CalibrationGrouping,
+    bins: usize,
+    target_precision: f64,
+) -> CalibrationReport {
+    let bins = bins.clamp(1, MAX_CALIBRATION_BINS);
+    let all: Vec<&FeedbackLabel> = labels.iter().collect();
+    let mut grouped: BTreeMap<GroupKey, Vec<&FeedbackLabel>> = BTreeMap::new();
+    for label in labels {
+        let source = matches!(
+            grouping,
+            CalibrationGrouping::Source | CalibrationGrouping::SourceType
+        )
+        .then_some(label.source.as_str());
+        let kind = matches!(
+            grouping,
+            CalibrationGrouping::Type | CalibrationGrouping::SourceType
+        )
+        .then_some(label.kind.as_str());
+        grouped
+            .entry((label.target, source, kind))
+            .or_default()
+            .push(label);
+    }
+
+    let mut groups: Vec<CalibrationGroup> = grouped
+        .into_iter()
+        .map(|((target, source, kind), members)| {
+            let mut group = calibrate(&members, bins, target_precision);
+            group.target = Some(target);
+            group.source = source.map(str::to_string);
+            group.kind = kind.map(str::to_string);
+            group
+        })
+        .collect();
+    groups.sort_by_key(|g| std::cmp::Reverse(g.labels));
+
+    let mut overall = calibrate(&all, bins, target_precision);
+    let mut targets = labels.iter().map(|l| l.target);
+    if let Some(first) = targets.next() {
+        if targets.all(|t| t == first) {
+            overall.target = Some(first);
+        }
+    }
+
+    CalibrationReport {
+        grouping,
+        target_precision,
+        overall,
+        groups,
+    }
+}
+
+fn calibrate(labels: &[&FeedbackLabel], bins: usize, target_precision: f64) -> CalibrationGroup {
+    let n = labels.len() as f64;
+    let confidence = |l: &FeedbackLabel| l.confidence.clamp(0.0, 1.0);
+    let outcome = |l: &FeedbackLabel| if l.correct { 1.0 } else { 0.0 };
+
+    let mut sums = vec![(0u64, 0.0f64, 0.0f64); bins];
+    for label in labels {
+        let c = confidence(label);
+        let bin = ((c * bins as f64) as usize).min(bins - 1);
+        sums[bin].0 += 1;
+        sums[bin].1 += c;
+        sums[bin].2 += outcome(label);
+    }
+
+    let mut ece = 0.0;
+    let reliability = sums
+        .iter()
+        .enumerate()
+        .map(|(i, &(count, conf_sum, correct_sum))| {
+            let (mean_confidence, accuracy) = if count == 0 {
+                (None, None)
+            } else {
+                let (mean, acc) = (conf_sum / count as f64, correct_sum / count as f64);
+                ece += count as f64 / n * (acc - mean).abs();
+                (Some(mean), Some(acc))
+            };
+            ReliabilityBin {
+                lower: i as f64 / bins as f64,
+                upper: (i + 1) as f64 / bins as f64,
+                count,
+                mean_confidence,
+                accuracy,
+            }
+        })
+        .collect();
+
+    let correct = labels.iter().filter(|l| l.correct).count() as u64;
+    let mean = |f: &dyn Fn(&FeedbackLabel) -> f64| {
+        if labels.is_empty() {
+            0.0
+        } else {
+            labels.iter().map(|l| f(l)).sum::<f64>() / n
+        }
+    };
+
+    CalibrationGroup {
+        target: None,
+        source: None,
+        kind: None,
+        labels: labels.len() as u64,
+        correct,
+        accuracy: mean(&outcome),
+        mean_confidence: mean(&confidence),
+        expected_calibration_error: ece,
+        brier_score: mean(&|l| (confidence(l) - outcome(l)).powi(2)),
+        suggested_threshold: suggested_threshold(labels, target_precision),
+        bins: reliability,
+    }
+}
+
+fn suggested_threshold(labels: &[&FeedbackLabel], target_precision: f64) -> Option<f64> {
+    let mut sorted: Vec<(f64, bool)> = labels
+        .iter()
+        .map(|l| (l.confidence.clamp(0.0, 1.0), l.correct))
+        .collect();
+    sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut threshold = None;
+    let mut correct = 0u64;
+    for (i, &(confidence, is_correct)) in sorted.iter().enumerate() {
+        correct += u64::from(is_correct);
+        let seen = i as u64 + 1;
+        // Only between distinct confidences, so ties fall on one side
+        let boundary = sorted.get(i + 1).is_none_or(|next| next.0 < confidence);
+        if boundary
+            && seen >= MIN_LABELS_FOR_THRESHOLD
+            && correct as f64 / seen as f64 >= target_precision
+        {
+            threshold = Some(confidence);
+        }
+    }
+    threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(
+        target: FeedbackTarget,
+        source: &str,
+        kind: &str,
+        confidence: f64,
+        correct: bool,
+    ) -> FeedbackLabel {
+        FeedbackLabel {
+            id: Uuid::new_v4(),
+            target,
+            target_id: Some(Uuid::new_v4()),
+            correct,
+            confidence,
+            source: source.to_string(),
+            kind: kind.to_string(),
+            note: None,
+            prompt_version: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn bins_and_scores_follow_the_labels() {
+        let labels = vec![
+            label(FeedbackTarget::Entity, "gdelt", "person", 0.95, true),
+            label(FeedbackTarget::Entity, "gdelt", "person", 0.91, false),
+            label(FeedbackTarget::Entity, "gdelt", "person", 0.15, false),
+            label(FeedbackTarget::Entity, "gdelt", "person", 1.0, true),
+        ];
+        let report = calibration_report(&labels, CalibrationGrouping::SourceType, 10, 0.9);
+        let group = &report.overall;
+        assert_eq!(group.target, Some(FeedbackTarget::Entity));
+        assert_eq!((group.labels, group.correct), (4, 2));
+        assert_eq!(group.bins.len(), 10);
+        assert_eq!(group.bins[1].count, 1);
+        assert_eq!(group.bins[1].accuracy, Some(0.0));
+        assert_eq!(group.bins[9].count, 3);
+        assert!((group.bins[9].accuracy.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert!(group.bins[5].accuracy.is_none());
+        // |0.15 - 0| * 1/4 + |0.62 - 0.667| * 3/4
+        assert!(
+            (group.expected_calibration_error - (0.15 / 4.0 + (2.86 / 3.0 - 2.0 / 3.0) * 0.75))
+                .abs()
+                < 1e-9
+        );
+        assert!((group.brier_score - (0.0025 + 0.8281 + 0.0225 + 0.0) / 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn groups_split_by_target_and_grouping() {
+        let labels = vec![
+            label(FeedbackTarget::Entity, "gdelt", "person", 0.9, true),
+            label(FeedbackTarget::Entity, "gdelt", "organization", 0.9, true),
+            label(FeedbackTarget::Entity, "opensanctions", "person", 0.9, true),
+            label(
+                FeedbackTarget::Relationship,
+                "gdelt",
+                "owner_of",
+                0.9,
+                false,
+            ),
+        ];
+        let by_source = calibration_report(&labels, CalibrationGrouping::Source, 10, 0.9);
+        assert_eq!(by_source.overall.target, None);
+        assert_eq!(by_source.groups.len(), 3);
+        assert_eq!(by_source.groups[0].labels, 2);
+        assert_eq!(by_source.groups[0].source.as_deref(), Some("gdelt"));
+        assert_eq!(by_source.groups[0].kind, None);
+
+        let by_both = calibration_report(&labels, CalibrationGrouping::SourceType, 10, 0.9);
+        assert_eq!(by_both.groups.len(), 4);
+    }
+
+    #[test]
+    fn threshold_is_the_lowest_confidence_meeting_the_precision() {
+        let mut labels: Vec<FeedbackLabel> = [0.99, 0.97, 0.95, 0.93, 0.9, 0.88, 0.86]
+            .into_iter()
+            .map(|c| label(FeedbackTarget::Relationship, "gdelt", "owner_of", c, true))
+            .collect();
+        labels.extend(
+            [0.85, 0.7, 0.6]
+                .into_iter()
+                .map(|c| label(FeedbackTarget::Relationship, "gdelt", "owner_of", c, false)),
+        );
+        let report = calibration_report(&labels, CalibrationGrouping::Source, 10, 0.9);
+        assert_eq!(report.overall.suggested_threshold, Some(0.86));
+
+        let few = &labels[..3];
+        assert_eq!(
+            calibration_report(few, CalibrationGrouping::Source, 10, 0.9)
+                .overall
+                .suggested_threshold,
+            None
+        );
+        assert_eq!(
+            calibration_report(&[], CalibrationGrouping::Source, 10, 0.9)
+                .overall
+                .labels,
+            0
+        );
+    }
+
+    #[test]
+    fn relabelling_an_item_keeps_its_key() {
+        let first = label(FeedbackTarget::Entity, "gdelt", "person", 0.8, true);
+        let second = FeedbackLabel {
+            id: Uuid::new_v4(),
+            correct: false,
+            ..first.clone()
+        };
+        assert_eq!(first.item_key(), second.item_key());
+
+        let answer = FeedbackLabel {
+            target: FeedbackTarget::Answer,
+            target_id: None,
+            ..first
+        };
+        assert!(answer.item_key().contains(&answer.id.to_string()));
+    }
+}
@@ -0,0 +1,68 @@
+//! The cold tier: raw observations archived as Parquet next to the graph.
+//!
+//! Position reports, extracted events and the metadata of collected
+//! documents are appended to date-partitioned Parquet files under
+//! `COLD_STORAGE_URL` (`argus-coldstore`), so the graph can keep only the
+//! entities and relationships summarizing them and let retention drop the
+//! rest.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// One archived record type; each is a directory of partitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ColdTable {
+    /// AIS and ADS-B position reports.
+    Positions,
+    /// Event entities as extracted, before deduplication merges them.
+    Events,
+    /// Collected documents without their content.
+    Documents,
+}
+
+impl ColdTable {
+    pub const ALL: [ColdTable; 3] = [
+        ColdTable::Positions,
+        ColdTable::Events,
+        ColdTable::Documents,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColdTable::Positions => "positions",
+            ColdTable::Events => "events",
+            ColdTable::Documents => "documents",
+        }
+    }
+}
+
+impl fmt::Display for ColdTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One day of one table: the files under `{table}/date=YYYY-MM-DD/`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ColdPartition {
+    pub table: ColdTable,
+    pub date: NaiveDate,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub files: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub bytes: u64,
+}
+
+/// A column of an analytical query's result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct SqlColumn {
+    pub name: String,
+    /// Arrow type name, e.g. `Utf8`, `Float64` or `Timestamp(ms, "UTC")`.
+    pub data_type: String,
+}
@@ -0,0 +1,419 @@
+//! Event density: geolocated events and position reports counted per
+//! geohash cell and time bucket, so activity heatmaps need no raw data.
+//!
+//! Cells are geohash cells; buckets start at multiples of the interval
+//! since the Unix epoch, so the same query always yields the same buckets.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Bucket length used when a request names none.
+pub const DEFAULT_DENSITY_INTERVAL: &str = "1h";
+
+/// Window counted when a request gives no `since`.
+pub const DEFAULT_DENSITY_WINDOW_HOURS: i64 = 24;
+
+/// Longest bucket accepted.
+pub const MAX_DENSITY_INTERVAL_DAYS: i64 = 7;
+
+/// Most time buckets one request may span.
+pub const MAX_DENSITY_BUCKETS: i64 = 1000;
+
+/// Finest geohash precision accepted (cells of about 150 m).
+pub const MAX_GEOHASH_PRECISION: u8 = 7;
+
+/// Cells across a bounding box that the automatic precision aims to stay under.
+const TARGET_CELLS: f64 = 1024.0;
+
+/// Most cells returned; the least active beyond it are dropped.
+pub const MAX_DENSITY_CELLS: usize = 10_000;
+
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// A longitude/latitude rectangle. `west` greater than `east` wraps across
+/// the antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub west: f64,
+    pub south: f64,
+    pub east: f64,
+    pub north: f64,
+}
+
+impl BoundingBox {
+    pub const WORLD: Self = Self {
+        west: -180.0,
+        south: -90.0,
+        east: 180.0,
+        north: 90.0,
+    };
+
+    /// Parse `west,south,east,north` in degrees.
+    pub fn parse(bbox: &str) -> Result<Self, String> {
+        let invalid =
+            || format!("Invalid bbox '{bbox}': expected west,south,east,north in degrees");
+        let values: Vec<f64> = bbox
+            .split(',')
+            .map(|v| v.trim().parse::<f64>().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [west, south, east, north] = values[..] else {
+            return Err(invalid());
+        };
+        let longitude = -180.0..=180.0;
+        let latitude = -90.0..=90.0;
+        if !longitude.contains(&west)
+            || !longitude.contains(&east)
+            || !latitude.contains(&south)
+            || !latitude.contains(&north)
+        {
+            return Err(format!("Invalid bbox '{bbox}': coordinates out of range"));
+        }
+        if south > north {
+            return Err(format!("Invalid bbox '{bbox}': south is above north"));
+        }
+        Ok(Self {
+            west,
+            south,
+            east,
+            north,
+        })
+    }
+
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        let within_longitude = if self.west <= self.east {
+            (self.west..=self.east).contains(&longitude)
+        } else {
+            longitude >= self.west || longitude <= self.east
+        };
+        within_longitude && (self.south..=self.north).contains(&latitude)
+    }
+
+    /// Width in degrees of longitude, counting a wrap across the antimeridian.
+    fn width(&self) -> f64 {
+        if self.west <= self.east {
+            self.east - self.west
+        } else {
+            360.0 - (self.west - self.east)
+        }
+    }
+
+    /// The finest geohash precision that keeps the box within about
+    /// [`TARGET_CELLS`] cells.
+    pub fn auto_precision(&self) -> u8 {
+        (1..=MAX_GEOHASH_PRECISION)
+            .rev()
+            .find(|&precision| {
+                let (height, width) = geohash_cell_size(precision);
+                (self.width() / width).ceil().max(1.0)
+                    * ((self.north - self.south) / height).ceil().max(1.0)
+                    <= TARGET_CELLS
+            })
+            .unwrap_or(1)
+    }
+}
+
+/// Parse a bucket length like `15m`, `1h` or `1d`, at most
+/// [`MAX_DENSITY_INTERVAL_DAYS`].
+pub fn parse_interval(interval: &str) -> Result<Duration, String> {
+    let interval = interval.trim();
+    let invalid = || {
+        format!("Invalid interval '{interval}': expected a number followed by m, h or d, e.g. 1h")
+    };
+    let (split, _) = interval.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = interval.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let minutes = match unit {
+        "m" | "M" => Some(amount),
+        "h" | "H" => amount.checked_mul(60),
+        "d" | "D" => amount.checked_mul(24 * 60),
+        _ => return Err(invalid()),
+    };
+    match minutes {
+        Some(minutes) if minutes > MAX_DENSITY_INTERVAL_DAYS * 24 * 60 => Err(format!(
+            "Interval '{interval}' is longer than {MAX_DENSITY_INTERVAL_DAYS}d"
+        )),
+        Some(minutes) if minutes > 0 => Ok(Duration::minutes(minutes)),
+        None if amount > 0 => Err(format!(
+            "Interval '{interval}' is longer than {MAX_DENSITY_INTERVAL_DAYS}d"
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+/// Bits of longitude and latitude in a geohash of `precision` characters.
+fn geohash_bits(precision: u8) -> (u32, u32) {
+    let bits = 5 * u32::from(precision);
+    (bits.div_ceil(2), bits / 2)
+}
+
+/// Height and width in degrees of a geohash cell of `precision` characters.
+pub fn geohash_cell_size(precision: u8) -> (f64, f64) {
+    let (lon_bits, lat_bits) = geohash_bits(precision);
+    (
+        180.0 / f64::from(1u32 << lat_bits),
+        360.0 / f64::from(1u32 << lon_bits),
+    )
+}
+
+/// Column and row of the geohash cell holding a point, counted from the
+/// south-west corner of the world.
+pub fn geohash_cell(latitude: f64, longitude: f64, precision: u8) -> (u64, u64) {
+    let (height, width) = geohash_cell_size(precision);
+    let (lon_bits, lat_bits) = geohash_bits(precision);
+    let x = ((longitude + 180.0) / width).floor().max(0.0) as u64;
+    let y = ((latitude + 90.0) / height).floor().max(0.0) as u64;
+    (x.min((1 << lon_bits) - 1), y.min((1 << lat_bits) - 1))
+}
+
+/// The geohash of the cell at column `x` and row `y`.
+pub fn geohash_of_cell(x: u64, y: u64, precision: u8) -> String {
+    let (mut lon_bits, mut lat_bits) = geohash_bits(precision);
+    let mut hash = String::with_capacity(usize::from(precision));
+    let mut value = 0usize;
+    for i in 0..5 * u32::from(precision) {
+        // Bits alternate, longitude first
+        let bit = if i % 2 == 0 {
+            lon_bits -= 1;
+            (x >> lon_bits) & 1
+        } else {
+            lat_bits -= 1;
+            (y >> lat_bits) & 1
+        };
+        value = (value << 1) | bit as usize;
+        if i % 5 == 4 {
+            hash.push(GEOHASH_ALPHABET[value] as char);
+            value = 0;
+        }
+    }
+    hash
+}
+
+/// The geohash of `precision` characters for a point.
+pub fn geohash(latitude: f64, longitude: f64, precision: u8) -> String {
+    let (x, y) = geohash_cell(latitude, longitude, precision);
+    geohash_of_cell(x, y, precision)
+}
+
+/// What to count, already validated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityQuery {
+    pub bbox: BoundingBox,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub interval: Duration,
+    pub precision: u8,
+}
+
+impl DensityQuery {
+    /// Bucket index of a time: whole intervals since the Unix epoch.
+    pub fn bucket_of(&self, at: DateTime<Utc>) -> i64 {
+        at.timestamp().div_euclid(self.interval.num_seconds())
+    }
+}
+
+/// Events and position reports in one cell and time bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DensityCell {
+    pub geohash: String,
+    /// Center of the cell.
+    pub latitude: f64,
+    pub longitude: f64,
+    pub bucket_start: DateTime<Utc>,
+    pub count: u64,
+    /// Counts per source, e.g. `gdelt`, `ais`, `adsb`.
+    pub by_source: BTreeMap<String, u64>,
+}
+
+/// The cells counted for a query, oldest bucket first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventDensity {
+    pub cells: Vec<DensityCell>,
+    /// Observations counted, including any in dropped cells.
+    pub total: u64,
+    /// Whether cells were dropped for exceeding [`MAX_DENSITY_CELLS`].
+    pub truncated: bool,
+}
+
+/// Accumulates counts for a [`DensityQuery`].
+#[derive(Debug)]
+pub struct DensityCounter {
+    query: DensityQuery,
+    /// By bucket, column and row.
+    counts: BTreeMap<(i64, u64, u64), BTreeMap<String, u64>>,
+}
+
+impl DensityCounter {
+    pub fn new(query: DensityQuery) -> Self {
+        Self {
+            query,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn query(&self) -> &DensityQuery {
+        &self.query
+    }
+
+    /// Count one observation, unless it falls outside the query.
+    pub fn add(&mut self, latitude: f64, longitude: f64, at: DateTime<Utc>, source: &str) {
+        if !self.query.bbox.contains(latitude, longitude)
+            || at < self.query.since
+            || at >= self.query.until
+        {
+            return;
+        }
+        let (x, y) = geohash_cell(latitude, longitude, self.query.precision);
+        self.add_cell(x, y, self.query.bucket_of(at), source, 1);
+    }
+
+    /// Count `count` observations already binned, e.g. by the database.
+    /// Columns and rows past the edge of the world are folded onto it.
+    pub fn add_cell(&mut self, x: u64, y: u64, bucket: i64, source: &str, count: u64) {
+        let (lon_bits, lat_bits) = geohash_bits(self.query.precision);
+        let cell = (
+            bucket,
+            x.min((1 << lon_bits) - 1),
+            y.min((1 << lat_bits) - 1),
+        );
+        *self
+            .counts
+            .entry(cell)
+            .or_default()
+            .entry(source.to_string())
+            .or_default() += count;
+    }
+
+    /// The cells counted, keeping the most active when there are more
+    /// than [`MAX_DENSITY_CELLS`].
+    pub fn finish(self) -> EventDensity {
+        let (height, width) = geohash_cell_size(self.query.precision);
+        let interval = self.query.interval.num_seconds();
+        let precision = self.query.precision;
+        let mut cells: Vec<DensityCell> = self
+            .counts
+            .into_iter()
+            .filter_map(|((bucket, x, y), by_source)| {
+                Some(DensityCell {
+                    geohash: geohash_of_cell(x, y, precision),
+                    latitude: -90.0 + (y as f64 + 0.5) * height,
+                    longitude: -180.0 + (x as f64 + 0.5) * width,
+                    bucket_start: DateTime::from_timestamp(bucket.checked_mul(interval)?, 0)?,
+                    count: by_source.values().sum(),
+                    by_source,
+                })
+            })
+            .collect();
+
+        let total = cells.iter().map(|c| c.count).sum();
+        let truncated = cells.len() > MAX_DENSITY_CELLS;
+        if truncated {
+            cells.sort_by_key(|c| std::cmp::Reverse(c.count));
+            cells.truncate(MAX_DENSITY_CELLS);
+            cells.sort_by(|a, b| {
+                a.bucket_start
+                    .cmp(&b.bucket_start)
+                    .then_with(|| a.geohash.cmp(&b.geohash))
+            });
+        }
+        EventDensity {
+            cells,
+            total,
+            truncated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn geohashes_match_the_reference_encoding() {
+        assert_eq!(geohash(57.64911, 10.40744, 7), "u4pruyd");
+        assert_eq!(geohash(42.6, -5.6, 5), "ezs42");
+        assert_eq!(geohash(-90.0, -180.0, 3), "000");
+        assert_eq!(geohash(90.0, 180.0, 3), "zzz");
+    }
+
+    #[test]
+    fn bboxes_parse_and_wrap() {
+        let europe = BoundingBox::parse("-10, 35, 40, 60").unwrap();
+        assert!(europe.contains(50.45, 30.52) && !europe.contains(50.45, 41.0));
+        assert!(BoundingBox::parse("-10,35,30").is_err());
+        assert!(BoundingBox::parse("-10,60,30,35").is_err());
+        assert!(BoundingBox::parse("-190,35,30,60").is_err());
+
+        let pacific = BoundingBox::parse("170,-20,-170,20").unwrap();
+        assert!(
+            pacific.contains(0.0, 179.0)
+                && pacific.contains(0.0, -175.0)
+                && !pacific.contains(0.0, 0.0)
+        );
+        assert!(pacific.auto_precision() > BoundingBox::WORLD.auto_precision());
+    }
+
+    #[test]
+    fn intervals_parse() {
+        assert_eq!(parse_interval("15m").unwrap(), Duration::minutes(15));
+        assert_eq!(parse_interval("1d").unwrap(), Duration::days(1));
+        assert!(parse_interval("8d").is_err());
+        assert!(parse_interval("0h").is_err());
+        assert!(parse_interval("1w").is_err());
+    }
+
+    #[test]
+    fn observations_are_counted_per_cell_and_bucket() {
+        let mut counter = DensityCounter::new(DensityQuery {
+            bbox: BoundingBox::parse("20,40,40,60").unwrap(),
+            since: at("2024-01-15T00:00:00Z"),
+            until: at("2024-01-16T00:00:00Z"),
+            interval: Duration::hours(1),
+            precision: 4,
+        });
+        counter.add(50.4333, 30.5167, at("2024-01-15T12:05:00Z"), "gdelt");
+        counter.add(50.4334, 30.5168, at("2024-01-15T12:55:00Z"), "gdelt");
+        counter.add(50.4333, 30.5167, at("2024-01-15T13:00:00Z"), "gdelt");
+        // Outside the box and the window
+        counter.add(59.33, 18.07, at("2024-01-15T12:00:00Z"), "gdelt");
+        counter.add(50.4333, 30.5167, at("2024-01-16T00:00:00Z"), "gdelt");
+        let (x, y) = geohash_cell(50.4333, 30.5167, 4);
+        counter.add_cell(
+            x,
+            y,
+            counter.query().bucket_of(at("2024-01-15T12:30:00Z")),
+            "ais",
+            3,
+        );
+
+        let EventDensity {
+            cells,
+            total,
+            truncated,
+        } = counter.finish();
+        assert!(!truncated);
+        assert_eq!(total, 6);
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].geohash, geohash(50.4333, 30.5167, 4));
+        assert_eq!(cells[0].bucket_start, at("2024-01-15T12:00:00Z"));
+        assert_eq!(cells[0].count, 5);
+        assert_eq!(
+            cells[0].by_source,
+            BTreeMap::from([("ais".to_string(), 3), ("gdelt".to_string(), 2)])
+        );
+        assert!(
+            (cells[0].latitude - 50.4333).abs() < 0.1 && (cells[0].longitude - 30.5167).abs() < 0.2
+        );
+        assert_eq!(
+            (cells[1].bucket_start, cells[1].count),
+            (at("2024-01-15T13:00:00Z"), 1)
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! Managed deny-list entries: names suppressed during validation because a
+//! source keeps reporting them as entities, such as GDELT's generic actors
+//! ("GOVERNMENT", "POLICE").
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::EntityType;
+
+/// How a deny-list pattern is compared with an entity name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenyMatch {
+    /// The whole name, ignoring case and surrounding whitespace.
+    #[default]
+    Exact,
+    /// A regular expression that must match the whole name, ignoring case.
+    Regex,
+}
+
+/// One suppressed name or name pattern, optionally limited to one entity
+/// type and one source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DenyEntry {
+    pub id: Uuid,
+    pub pattern: String,
+    #[serde(default, rename = "match")]
+    pub match_kind: DenyMatch,
+    /// Only entities of this type; any type when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_type: Option<EntityType>,
+    /// Only entities from this source, e.g. `gdelt`; any source when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::agent::RawDocument;
+use crate::entity::ExtractionResult;
+
+/// Node label archived documents are stored under, as named in retention
+/// policies.
+pub const DOCUMENT_LABEL: &str = "SourceDocument";
+
+/// A collected document kept alongside the graph so it can be searched and
+/// traced back to the entities extracted from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ArchivedDocument {
+    pub id: Uuid,
+    pub source: String,
+    pub source_id: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub url: Option<String>,
+    pub collected_at: DateTime<Utc>,
+    pub run_id: Option<String>,
+    pub entity_ids: Vec<Uuid>,
+    /// What extraction returned for this document, before entity merging.
+    /// Only loaded when sampling a run's documents, not by search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<ExtractionResult>,
+    /// Why extraction failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction_error: Option<String>,
+}
+
+impl ArchivedDocument {
+    pub fn from_raw(doc: &RawDocument, run_id: Option<String>, entity_ids: Vec<Uuid>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            source: doc.source.clone(),
+            source_id: doc.source_id.clone(),
+            title: doc.title.clone(),
+            content: doc.content.clone(),
+            url: doc.url.clone(),
+            collected_at: doc.collected_at,
+            run_id,
+            entity_ids,
+            extraction: None,
+            extraction_error: None,
+        }
+    }
+}
+
+/// A random sample of the documents archived by one run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunDocumentSample {
+    /// Documents the run archived in total.
+    pub total: u64,
+    pub documents: Vec<ArchivedDocument>,
+}
+
+/// What one run's archived documents extracted into the graph.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RunExtractionStats {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub documents: u64,
+    /// Documents archived with an extraction error.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub extraction_errors: u64,
+    /// Distinct stored entities the documents resolved to, by type.
+    #[cfg_attr(feature = "ts", ts(type = "Record<string, number>"))]
+    pub entities_by_type: BTreeMap<String, u64>,
+    /// Of those, entities first seen since the run started.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub new_entities: u64,
+    /// Of those, entities already in the graph before the run.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub repeated_entities: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentSearchQuery {
+    pub query: String,
+    pub source: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSearchHit {
+    pub document: ArchivedDocument,
+    pub score: f64,
+}
+
+/// Cut a window of roughly `max_chars` characters around the first match of
+/// any query term and wrap every match in `<mark>` tags. Everything else is
+/// HTML-escaped so the result can be rendered directly.
+pub fn highlight_snippet(text: &str, query: &str, max_chars: usize) -> String {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() > 1)
+        .map(str::to_lowercase)
+        .collect();
+
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+    // Lowercasing can change length for a few scripts; fall back to no highlighting
+    let lower = if lower.len() == chars.len() {
+        lower
+    } else {
+        chars.clone()
+    };
+
+    let mut matches: Vec<(usize, usize)> = Vec::new();
+    for term in &terms {
+        let term: Vec<char> = term.chars().collect();
+        let mut i = 0;
+        while i + term.len() <= lower.len() {
+            if lower[i..i + term.len()] == term[..] {
+                matches.push((i, i + term.len()));
+                i += term.len();
+            } else {
+                i += 1;
+            }
+        }
+    }
+    matches.sort_unstable();
+
+    let first = matches.first().map(|m| m.0).unwrap_or(0);
+    let start = first.saturating_sub(max_chars / 4);
+    let end = (start + max_chars).min(chars.len());
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    let mut pos = start;
+    for &(m_start, m_end) in &matches {
+        if m_start < pos || m_start >= end {
+            continue;
+        }
+        push_escaped(&mut out, &chars[pos..m_start]);
+        out.push_str("<mark>");
+        push_escaped(&mut out, &chars[m_start..m_end.min(end)]);
+        out.push_str("</mark>");
+        pos = m_end.min(end);
+    }
+    push_escaped(&mut out, &chars[pos..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+fn push_escaped(out: &mut String, chars: &[char]) {
+    for &c in chars {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_all_terms_case_insensitively() {
+        let snippet = highlight_snippet("Acme Corp paid ACME Holdings", "acme holdings", 200);
+        assert_eq!(
+            snippet,
+            "<mark>Acme</mark> Corp paid <mark>ACME</mark> <mark>Holdings</mark>"
+        );
+    }
+
+    #[test]
+    fn windows_long_text_around_first_match() {
+        let text = format!("{}needle{}", "a ".repeat(200), " b".repeat(200));
+        let snippet = highlight_snippet(&text, "needle", 40);
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>needle</mark>"));
+    }
+
+    #[test]
+    fn escapes_html_outside_marks() {
+        let snippet = highlight_snippet("<b>tanker</b> seized", "tanker", 100);
+        assert_eq!(snippet, "&lt;b&gt;<mark>tanker</mark>&lt;/b&gt; seized");
+    }
+
+    #[test]
+    fn no_match_returns_leading_text() {
+        assert_eq!(highlight_snippet("short text", "zzz", 100), "short text");
+    }
+}
@@ -0,0 +1,135 @@
+//! Probable duplicate entities found by the duplicate scan, waiting for an
+//! operator to merge or dismiss them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::EntityType;
+
+/// Why two entities look like the same real-world thing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DuplicateReason {
+    /// Same type and normalized name, reported by different sources.
+    SameName,
+    /// Both carry the same external identifier.
+    SharedIdentifier { scheme: String, value: String },
+    /// Same type and names whose similarity (0.0–1.0) is above the threshold.
+    SimilarName { similarity: f64 },
+}
+
+impl DuplicateReason {
+    /// How strongly this reason alone suggests a duplicate.
+    pub fn score(&self) -> f64 {
+        match self {
+            DuplicateReason::SharedIdentifier { .. } => 1.0,
+            DuplicateReason::SameName => 0.9,
+            DuplicateReason::SimilarName { similarity } => similarity * 0.8,
+        }
+    }
+}
+
+/// One side of a duplicate pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateMember {
+    pub id: Uuid,
+    pub name: String,
+    pub entity_type: EntityType,
+    pub source: String,
+}
+
+/// A pair of entities that are probably the same, with the one a merge
+/// should keep.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateCandidate {
+    /// Stable for the pair, so rescans and dismissals line up.
+    pub id: String,
+    pub entities: Vec<DuplicateMember>,
+    /// Suggested survivor of a merge; the other entity is folded into it.
+    pub keep: Uuid,
+    pub reasons: Vec<DuplicateReason>,
+    /// Strongest reason's score, 0.0–1.0.
+    pub score: f64,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl DuplicateCandidate {
+    pub fn new(keep: DuplicateMember, other: DuplicateMember, reason: DuplicateReason) -> Self {
+        Self {
+            id: candidate_id(keep.id, other.id),
+            keep: keep.id,
+            score: reason.score(),
+            reasons: vec![reason],
+            entities: vec![keep, other],
+            detected_at: Utc::now(),
+        }
+    }
+
+    /// Add another reason for the same pair, keeping the best score.
+    pub fn add_reason(&mut self, reason: DuplicateReason) {
+        if self.reasons.contains(&reason) {
+            return;
+        }
+        self.score = self.score.max(reason.score());
+        self.reasons.push(reason);
+    }
+
+    pub fn involves(&self, id: Uuid) -> bool {
+        self.entities.iter().any(|e| e.id == id)
+    }
+
+    /// Entities a merge keeping `keep` would fold away.
+    pub fn merged_away(&self, keep: Uuid) -> Vec<Uuid> {
+        self.entities
+            .iter()
+            .map(|e| e.id)
+            .filter(|id| *id != keep)
+            .collect()
+    }
+}
+
+/// Order-independent id for the pair `(a, b)`.
+pub fn candidate_id(a: Uuid, b: Uuid) -> String {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    format!("{}:{}", low.simple(), high.simple())
+}
+
+/// Highest score first, ties by id so listings are stable.
+pub fn sort_candidates(candidates: &mut [DuplicateCandidate]) {
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(name: &str, source: &str) -> DuplicateMember {
+        DuplicateMember {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            entity_type: EntityType::Organization,
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn candidate_ids_ignore_order_and_reasons_keep_best_score() {
+        let a = member("Acme Ltd", "opensanctions");
+        let b = member("ACME Limited", "opencorporates");
+        assert_eq!(candidate_id(a.id, b.id), candidate_id(b.id, a.id));
+
+        let mut candidate = DuplicateCandidate::new(
+            a.clone(),
+            b.clone(),
+            DuplicateReason::SimilarName { similarity: 0.9 },
+        );
+        assert!((candidate.score - 0.72).abs() < 1e-9);
+        candidate.add_reason(DuplicateReason::SameName);
+        candidate.add_reason(DuplicateReason::SameName);
+        assert_eq!(candidate.reasons.len(), 2);
+        assert_eq!(candidate.score, 0.9);
+        assert_eq!(candidate.merged_away(a.id), vec![b.id]);
+        assert!(candidate.involves(b.id));
+    }
+}
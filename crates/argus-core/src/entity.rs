@@ -1,9 +1,16 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "snake_case")]
+/// Entity types. The built-in variants cover the core ontology; `Custom`
+/// carries deployment-specific types (see [`crate::ontology::Ontology`]).
+///
+/// Serialized as a plain snake_case string, e.g. `"person"` or `"bank_account"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub enum EntityType {
     Person,
     Organization,
@@ -14,9 +21,103 @@ pub enum EntityType {
     Document,
     Transaction,
     Sanction,
+    Custom(String),
+}
+
+impl EntityType {
+    /// Every variant but `Custom`.
+    pub const BUILT_IN: [EntityType; 9] = [
+        EntityType::Person,
+        EntityType::Organization,
+        EntityType::Vessel,
+        EntityType::Aircraft,
+        EntityType::Location,
+        EntityType::Event,
+        EntityType::Document,
+        EntityType::Transaction,
+        EntityType::Sanction,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            EntityType::Person => "person",
+            EntityType::Organization => "organization",
+            EntityType::Vessel => "vessel",
+            EntityType::Aircraft => "aircraft",
+            EntityType::Location => "location",
+            EntityType::Event => "event",
+            EntityType::Document => "document",
+            EntityType::Transaction => "transaction",
+            EntityType::Sanction => "sanction",
+            EntityType::Custom(name) => name,
+        }
+    }
+
+    /// Resolve a type name to a built-in variant, or `Custom` with the
+    /// normalized snake_case name.
+    pub fn from_name(name: &str) -> Self {
+        match normalize_type_name(name).as_str() {
+            "person" => EntityType::Person,
+            "organization" => EntityType::Organization,
+            "vessel" => EntityType::Vessel,
+            "aircraft" => EntityType::Aircraft,
+            "location" => EntityType::Location,
+            "event" => EntityType::Event,
+            "document" => EntityType::Document,
+            "transaction" => EntityType::Transaction,
+            "sanction" => EntityType::Sanction,
+            other => EntityType::Custom(other.to_string()),
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        matches!(self, EntityType::Custom(_))
+    }
+}
+
+impl fmt::Display for EntityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for EntityType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(EntityType::from_name(&name))
+    }
+}
+
+/// Normalize a free-form type name to snake_case ASCII:
+/// `"Bank Account"`, `"BankAccount"` and `"bank-account"` all become `"bank_account"`.
+pub fn normalize_type_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut prev_lower_or_digit = false;
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && prev_lower_or_digit {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower_or_digit = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !out.ends_with('_') {
+                out.push('_');
+            }
+            prev_lower_or_digit = false;
+        }
+    }
+    out.trim_matches('_').to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct Entity {
     pub id: Uuid,
     pub entity_type: EntityType,
@@ -26,8 +127,38 @@ pub struct Entity {
     pub source: String,
     pub source_id: Option<String>,
     pub confidence: f64,
+    /// When Argus first collected the entity.
     pub first_seen: DateTime<Utc>,
+    /// When Argus last collected the entity.
     pub last_seen: DateTime<Utc>,
+    /// Earliest time the source data itself places the entity (event day,
+    /// incorporation or listing date), as opposed to when it was collected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_at: Option<DateTime<Utc>>,
+    /// Proximity to sanctioned entities in [0.0, 1.0], computed periodically
+    /// for people and organizations. `None` until the first scoring pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sanctions_exposure: Option<f64>,
+    /// How filled-in the profile is in [0.0, 1.0]: identifiers, links,
+    /// a place on the map and recent observations. `None` until the first
+    /// scoring pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completeness: Option<f64>,
+    /// Distinct source records describing this entity, for events a source
+    /// reports again under new ids (GDELT re-exports, source ids starting
+    /// with [`EVENT_KEY_PREFIX`]). `None` for entities not counted this way.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub mention_count: Option<u64>,
+    /// GeoJSON geometry of a reference Location (country boundary, port
+    /// outline, airport point). Stored apart from `properties`, which every
+    /// source write replaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<serde_json::Value>,
+    /// Identifiers in external knowledge bases, keyed by scheme (see
+    /// [`identifier_scheme`]), e.g. `{"lei": "5493001KJTIIGC8Y1R12"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub identifiers: BTreeMap<String, String>,
 }
 
 impl Entity {
@@ -44,12 +175,116 @@ impl Entity {
             confidence: 1.0,
             first_seen: now,
             last_seen: now,
+            observed_at: None,
+            sanctions_exposure: None,
+            completeness: None,
+            mention_count: None,
+            geometry: None,
+            identifiers: BTreeMap::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "snake_case")]
+/// Well-known schemes for [`Entity::identifiers`]. Other schemes are
+/// allowed; these are the ones agents populate.
+pub mod identifier_scheme {
+    pub const WIKIDATA: &str = "wikidata";
+    pub const LEI: &str = "lei";
+    pub const OPENSANCTIONS: &str = "opensanctions";
+    pub const OPENCORPORATES: &str = "opencorporates";
+    pub const EU_TRANSPARENCY: &str = "eu_transparency";
+    pub const ICAO24: &str = "icao24";
+    pub const MMSI: &str = "mmsi";
+    pub const IMO: &str = "imo";
+}
+
+/// Canonical spelling of an identifier scheme: lowercase with `_`
+/// separators, and common variants folded onto [`identifier_scheme`]
+/// (`"QID"` and `"wikidataId"` are both `wikidata`).
+pub fn normalize_identifier_scheme(scheme: &str) -> String {
+    let mut out = String::with_capacity(scheme.len());
+    for c in scheme.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    let out = out.trim_matches('_');
+    match out {
+        "qid" | "wikidataid" | "wikidata_id" => identifier_scheme::WIKIDATA,
+        "leicode" | "lei_code" => identifier_scheme::LEI,
+        "open_sanctions" | "opensanctions_id" => identifier_scheme::OPENSANCTIONS,
+        "open_corporates" | "opencorporates_url" => identifier_scheme::OPENCORPORATES,
+        "imo_number" | "imonumber" => identifier_scheme::IMO,
+        "icao" | "icao24_address" => identifier_scheme::ICAO24,
+        other => other,
+    }
+    .to_string()
+}
+
+/// Source id prefix of GDELT Events keyed on day, code, actors and place
+/// rather than on the GlobalEventID, so re-reports land on one Event; only
+/// these count reports in [`Entity::mention_count`].
+pub const EVENT_KEY_PREFIX: &str = "gdelt-event:";
+
+/// Property keys that hold a source-reported date for an entity, in order
+/// of preference. Used to backfill `observed_at` on entities stored before
+/// it existed.
+pub const OBSERVED_AT_PROPERTIES: &[&str] = &[
+    "observed_at",
+    "incorporation_date",
+    "registration_date",
+    "listing_date",
+    "first_seen",
+    "date",
+    "day",
+];
+
+/// Parse a date as sources write them: RFC 3339, `YYYY-MM-DD HH:MM:SS`,
+/// `YYYY-MM-DD`, GDELT's `YYYYMMDD`, or truncated `YYYY` / `YYYY-MM`
+/// (taken as the first day). Times without a zone are UTC.
+pub fn parse_source_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"] {
+        if let Some(dt) = value
+            .get(..19)
+            .and_then(|v| NaiveDateTime::parse_from_str(v, format).ok())
+        {
+            return Some(dt.and_utc());
+        }
+    }
+    let date = match value.len() {
+        4 => NaiveDate::parse_from_str(&format!("{value}-01-01"), "%Y-%m-%d").ok(),
+        7 => NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d").ok(),
+        8 if value.bytes().all(|b| b.is_ascii_digit()) => {
+            NaiveDate::parse_from_str(value, "%Y%m%d").ok()
+        }
+        _ => value
+            .get(..10)
+            .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok()),
+    }?;
+    date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc())
+}
+
+/// The first [`OBSERVED_AT_PROPERTIES`] value that parses as a date.
+pub fn observed_at_from_properties(properties: &serde_json::Value) -> Option<DateTime<Utc>> {
+    OBSERVED_AT_PROPERTIES
+        .iter()
+        .find_map(|key| match properties.get(key)? {
+            serde_json::Value::String(s) => parse_source_date(s),
+            serde_json::Value::Number(n) => parse_source_date(&n.to_string()),
+            _ => None,
+        })
+}
+
+/// Relationship types. Like [`EntityType`], `Custom` carries
+/// deployment-specific types and everything serializes as a snake_case string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub enum RelationType {
     OwnerOf,
     DirectorOf,
@@ -63,9 +298,74 @@ pub enum RelationType {
     MeetingWith,
     TraveledTo,
     PartOf,
+    Custom(String),
+}
+
+impl RelationType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RelationType::OwnerOf => "owner_of",
+            RelationType::DirectorOf => "director_of",
+            RelationType::EmployeeOf => "employee_of",
+            RelationType::RelatedTo => "related_to",
+            RelationType::LocatedAt => "located_at",
+            RelationType::TransactedWith => "transacted_with",
+            RelationType::SanctionedBy => "sanctioned_by",
+            RelationType::RegisteredIn => "registered_in",
+            RelationType::FlaggedAs => "flagged_as",
+            RelationType::MeetingWith => "meeting_with",
+            RelationType::TraveledTo => "traveled_to",
+            RelationType::PartOf => "part_of",
+            RelationType::Custom(name) => name,
+        }
+    }
+
+    /// Resolve a type name to a built-in variant, or `Custom` with the
+    /// normalized snake_case name.
+    pub fn from_name(name: &str) -> Self {
+        match normalize_type_name(name).as_str() {
+            "owner_of" => RelationType::OwnerOf,
+            "director_of" => RelationType::DirectorOf,
+            "employee_of" => RelationType::EmployeeOf,
+            "related_to" => RelationType::RelatedTo,
+            "located_at" => RelationType::LocatedAt,
+            "transacted_with" => RelationType::TransactedWith,
+            "sanctioned_by" => RelationType::SanctionedBy,
+            "registered_in" => RelationType::RegisteredIn,
+            "flagged_as" => RelationType::FlaggedAs,
+            "meeting_with" => RelationType::MeetingWith,
+            "traveled_to" => RelationType::TraveledTo,
+            "part_of" => RelationType::PartOf,
+            other => RelationType::Custom(other.to_string()),
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        matches!(self, RelationType::Custom(_))
+    }
+}
+
+impl fmt::Display for RelationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for RelationType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RelationType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(RelationType::from_name(&name))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct Relationship {
     pub id: Uuid,
     pub source_entity_id: Uuid,
@@ -75,9 +375,46 @@ pub struct Relationship {
     pub confidence: f64,
     pub source: String,
     pub timestamp: Option<DateTime<Utc>>,
+    /// How strongly the endpoints are tied in [0.0, 1.0], from how often,
+    /// how recently and by how many sources they were linked. Computed
+    /// periodically; `None` until the first scoring pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strength: Option<f64>,
+}
+
+/// Direction of a relationship relative to a given entity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+    #[default]
+    Both,
 }
 
 impl Relationship {
+    /// The supporting quote extraction recorded for this relationship.
+    pub fn evidence(&self) -> Option<&str> {
+        self.properties
+            .get("evidence")
+            .and_then(|e| e.as_str())
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+    }
+
+    /// Direction of this relationship as seen from `entity_id`, or `None`
+    /// if the entity is not one of its endpoints. Self-loops count as outgoing.
+    pub fn direction_from(&self, entity_id: Uuid) -> Option<Direction> {
+        if self.source_entity_id == entity_id {
+            Some(Direction::Outgoing)
+        } else if self.target_entity_id == entity_id {
+            Some(Direction::Incoming)
+        } else {
+            None
+        }
+    }
+
     pub fn new(
         source_entity_id: Uuid,
         target_entity_id: Uuid,
@@ -93,14 +430,118 @@ impl Relationship {
             confidence: 1.0,
             source,
             timestamp: None,
+            strength: None,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct ExtractionResult {
     pub entities: Vec<Entity>,
     pub relationships: Vec<Relationship>,
     pub raw_source: String,
     pub extracted_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifier_schemes_fold_common_spellings() {
+        assert_eq!(normalize_identifier_scheme("QID"), "wikidata");
+        assert_eq!(normalize_identifier_scheme("wikidataId"), "wikidata");
+        assert_eq!(normalize_identifier_scheme(" LEI "), "lei");
+        assert_eq!(normalize_identifier_scheme("leiCode"), "lei");
+        assert_eq!(
+            normalize_identifier_scheme("Open Corporates"),
+            "opencorporates"
+        );
+        assert_eq!(normalize_identifier_scheme("duns-number"), "duns_number");
+    }
+
+    #[test]
+    fn source_dates_parse_in_common_formats() {
+        let day = |s: &str| parse_source_date(s).map(|dt| dt.to_rfc3339());
+        assert_eq!(
+            day("20260226").as_deref(),
+            Some("2026-02-26T00:00:00+00:00")
+        );
+        assert_eq!(
+            day("2019-03-04").as_deref(),
+            Some("2019-03-04T00:00:00+00:00")
+        );
+        assert_eq!(day("2019-03").as_deref(), Some("2019-03-01T00:00:00+00:00"));
+        assert_eq!(
+            day("2026-01-15 10:00:00 GMT").as_deref(),
+            Some("2026-01-15T10:00:00+00:00")
+        );
+        assert_eq!(
+            day("2026-01-15T10:00:00+02:00").as_deref(),
+            Some("2026-01-15T08:00:00+00:00")
+        );
+        assert_eq!(day(""), None);
+        assert_eq!(day("unknown"), None);
+
+        let properties =
+            serde_json::json!({ "industry": "shipping", "incorporation_date": "2004-07-01" });
+        assert_eq!(
+            observed_at_from_properties(&properties)
+                .map(|dt| dt.to_rfc3339())
+                .as_deref(),
+            Some("2004-07-01T00:00:00+00:00")
+        );
+        assert_eq!(
+            observed_at_from_properties(&serde_json::json!({ "date": "soon" })),
+            None
+        );
+    }
+
+    #[test]
+    fn normalize_type_name_variants() {
+        assert_eq!(normalize_type_name("Bank Account"), "bank_account");
+        assert_eq!(normalize_type_name("BankAccount"), "bank_account");
+        assert_eq!(normalize_type_name("bank-account"), "bank_account");
+        assert_eq!(normalize_type_name("FUNDED_BY"), "funded_by");
+        assert_eq!(normalize_type_name("  funded by  "), "funded_by");
+        assert_eq!(normalize_type_name("x'); DROP"), "x_drop");
+    }
+
+    #[test]
+    fn builtin_types_serialize_as_plain_strings() {
+        assert_eq!(
+            serde_json::to_string(&EntityType::Person).unwrap(),
+            "\"person\""
+        );
+        assert_eq!(
+            serde_json::to_string(&RelationType::OwnerOf).unwrap(),
+            "\"owner_of\""
+        );
+        let et: EntityType = serde_json::from_str("\"sanction\"").unwrap();
+        assert_eq!(et, EntityType::Sanction);
+    }
+
+    #[test]
+    fn direction_from_endpoints() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let rel = Relationship::new(a, b, RelationType::OwnerOf, "test".into());
+        assert_eq!(rel.direction_from(a), Some(Direction::Outgoing));
+        assert_eq!(rel.direction_from(b), Some(Direction::Incoming));
+        assert_eq!(rel.direction_from(Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn custom_types_roundtrip() {
+        let et = EntityType::from_name("Bank Account");
+        assert_eq!(et, EntityType::Custom("bank_account".into()));
+        let json = serde_json::to_string(&et).unwrap();
+        assert_eq!(json, "\"bank_account\"");
+        assert_eq!(serde_json::from_str::<EntityType>(&json).unwrap(), et);
+
+        let rt: RelationType = serde_json::from_str("\"FUNDED_BY\"").unwrap();
+        assert_eq!(rt, RelationType::Custom("funded_by".into()));
+        assert!(rt.is_custom());
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::llm::LlmError;
+
 #[derive(Error, Debug)]
 pub enum ArgusError {
     #[error("HTTP error: {0}")]
@@ -14,9 +16,33 @@ pub enum ArgusError {
     #[error("Extraction error: {0}")]
     Extraction(String),
 
+    /// The model answered, but not with anything extraction could use.
+    #[error("Extraction error: {message}")]
+    ExtractionOutput {
+        model: String,
+        raw_output: String,
+        message: String,
+    },
+
+    /// The Anthropic API did not answer an LLM call.
+    #[error("{0}")]
+    Llm(LlmError),
+
     #[error("Reasoning error: {0}")]
     Reasoning(String),
 
+    #[error("Vector store error: {0}")]
+    Vector(String),
+
+    #[error("Reference data error: {0}")]
+    RefData(String),
+
+    #[error("Cold storage error: {0}")]
+    ColdStorage(String),
+
+    #[error("Cache error: {0}")]
+    Cache(String),
+
     #[error("Configuration error: {0}")]
     Config(String),
 
@@ -0,0 +1,639 @@
+//! FollowTheMoney (FtM) entities, the format used by OpenSanctions and Aleph.
+//!
+//! FtM models everything as an entity with a schema and multi-valued string
+//! properties; links such as `Ownership` are entities too, with properties
+//! pointing at their endpoints. Import turns "thing" schemata into Argus
+//! entities and "interval" schemata into relationships. The original schema
+//! and property map are kept on the Argus side so an export reproduces them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::entity::{
+    identifier_scheme, parse_source_date, Entity, EntityType, ExtractionResult, RelationType,
+    Relationship,
+};
+use crate::export::Subgraph;
+
+/// Media type for line-delimited FtM entities.
+pub const MEDIA_TYPE: &str = "application/x-ndjson";
+
+/// Property holding the original FtM schema on imported entities and relationships.
+pub const SCHEMA_KEY: &str = "ftm_schema";
+/// Property holding the original FtM id on imported relationships.
+pub const ID_KEY: &str = "ftm_id";
+/// Property holding the FtM datasets an imported entity appeared in.
+pub const DATASETS_KEY: &str = "ftm_datasets";
+
+/// FtM properties that hold external identifiers, and the
+/// `Entity::identifiers` scheme each maps to.
+const IDENTIFIER_PROPERTIES: &[(&str, &str)] = &[
+    ("leiCode", identifier_scheme::LEI),
+    ("wikidataId", identifier_scheme::WIKIDATA),
+    ("imoNumber", identifier_scheme::IMO),
+    ("mmsi", identifier_scheme::MMSI),
+];
+
+/// Namespace for deterministic Argus ids, so re-importing the same FtM
+/// entity updates one node instead of creating another.
+const ID_NAMESPACE: Uuid = Uuid::from_u128(0x6a1e_4f0e_9b7c_4d0a_8e2f_3c5b_7d9e_1f20);
+
+/// Link schemata: (schema, source property, target property, relation type).
+const EDGE_SCHEMATA: &[(&str, &str, &str, RelationType)] = &[
+    ("Ownership", "owner", "asset", RelationType::OwnerOf),
+    (
+        "Directorship",
+        "director",
+        "organization",
+        RelationType::DirectorOf,
+    ),
+    (
+        "Employment",
+        "employee",
+        "employer",
+        RelationType::EmployeeOf,
+    ),
+    ("Membership", "member", "organization", RelationType::PartOf),
+    (
+        "Payment",
+        "payer",
+        "beneficiary",
+        RelationType::TransactedWith,
+    ),
+    ("Family", "person", "relative", RelationType::RelatedTo),
+    ("Associate", "person", "associate", RelationType::RelatedTo),
+    ("Representation", "agent", "client", RelationType::RelatedTo),
+    (
+        "Succession",
+        "predecessor",
+        "successor",
+        RelationType::RelatedTo,
+    ),
+    ("UnknownLink", "subject", "object", RelationType::RelatedTo),
+];
+
+/// One FtM entity as found in `.ftm.json` / OpenSanctions bulk files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FtmEntity {
+    pub id: String,
+    pub schema: String,
+    #[serde(default)]
+    pub properties: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub datasets: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<String>,
+}
+
+impl FtmEntity {
+    /// String values of a property; FtM properties are always lists.
+    pub fn values(&self, property: &str) -> Vec<String> {
+        match self.properties.get(property) {
+            Some(Value::Array(values)) => values.iter().filter_map(value_string).collect(),
+            Some(value) => value_string(value).into_iter().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+fn value_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Deterministic Argus id for an FtM entity id.
+pub fn entity_uuid(ftm_id: &str) -> Uuid {
+    Uuid::new_v5(&ID_NAMESPACE, ftm_id.as_bytes())
+}
+
+/// Whether `schema` is a link schema, imported as relationships.
+pub fn is_link_schema(schema: &str) -> bool {
+    EDGE_SCHEMATA.iter().any(|(name, ..)| *name == schema)
+}
+
+/// Argus entity type for a non-link FtM schema.
+pub fn entity_type(schema: &str) -> EntityType {
+    match schema {
+        "Person" => EntityType::Person,
+        "Organization" | "Company" | "LegalEntity" | "PublicBody" => EntityType::Organization,
+        "Vessel" => EntityType::Vessel,
+        "Airplane" => EntityType::Aircraft,
+        "Address" => EntityType::Location,
+        "Event" => EntityType::Event,
+        "Document" => EntityType::Document,
+        "Sanction" => EntityType::Sanction,
+        other => EntityType::from_name(other),
+    }
+}
+
+/// FtM schema for an Argus entity type.
+pub fn schema_for(entity_type: &EntityType) -> String {
+    match entity_type {
+        EntityType::Person => "Person".to_string(),
+        EntityType::Organization => "Organization".to_string(),
+        EntityType::Vessel => "Vessel".to_string(),
+        EntityType::Aircraft => "Airplane".to_string(),
+        EntityType::Location => "Address".to_string(),
+        EntityType::Event => "Event".to_string(),
+        EntityType::Document => "Document".to_string(),
+        EntityType::Transaction => "Payment".to_string(),
+        EntityType::Sanction => "Sanction".to_string(),
+        EntityType::Custom(name) => name
+            .split('_')
+            .map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect(),
+    }
+}
+
+/// Map FtM entities to an [`ExtractionResult`] attributed to `source`.
+/// Also returns the ids of link entities skipped for missing an endpoint.
+pub fn import(
+    entities: &[FtmEntity],
+    source: &str,
+    now: DateTime<Utc>,
+) -> (ExtractionResult, Vec<String>) {
+    let mut result = ExtractionResult {
+        entities: Vec::new(),
+        relationships: Vec::new(),
+        raw_source: format!("ftm:{source}"),
+        extracted_at: now,
+    };
+    let mut skipped = Vec::new();
+
+    for ftm in entities {
+        if let Some((_, from, to, relation_type)) = EDGE_SCHEMATA
+            .iter()
+            .find(|(schema, ..)| *schema == ftm.schema)
+        {
+            let (sources, targets) = (ftm.values(from), ftm.values(to));
+            if sources.is_empty() || targets.is_empty() {
+                skipped.push(ftm.id.clone());
+                continue;
+            }
+            for source_ref in &sources {
+                for target_ref in &targets {
+                    result.relationships.push(edge(
+                        ftm,
+                        source_ref,
+                        target_ref,
+                        relation_type.clone(),
+                        source,
+                        &[from, to],
+                    ));
+                }
+            }
+            continue;
+        }
+
+        let entity = thing(ftm, source, now);
+        if ftm.schema == "Sanction" {
+            // Sanctions point at what they sanction; Argus models that as an edge
+            for target in ftm.values("entity") {
+                result.relationships.push(Relationship {
+                    id: entity_uuid(&format!("{}:entity:{target}", ftm.id)),
+                    source_entity_id: entity_uuid(&target),
+                    target_entity_id: entity.id,
+                    relation_type: RelationType::SanctionedBy,
+                    properties: Value::Object(Map::new()),
+                    confidence: 1.0,
+                    source: source.to_string(),
+                    timestamp: None,
+                    strength: None,
+                });
+            }
+        }
+        result.entities.push(entity);
+    }
+
+    (result, skipped)
+}
+
+fn thing(ftm: &FtmEntity, source: &str, now: DateTime<Utc>) -> Entity {
+    let names = ftm.values("name");
+    let name = ftm
+        .caption
+        .clone()
+        .or_else(|| names.first().cloned())
+        .unwrap_or_else(|| ftm.id.clone());
+    let mut aliases: Vec<String> = names.into_iter().filter(|n| *n != name).collect();
+    aliases.extend(ftm.values("alias"));
+    aliases.dedup();
+
+    let mut properties = ftm.properties.clone();
+    properties.insert(SCHEMA_KEY.into(), Value::String(ftm.schema.clone()));
+    if !ftm.datasets.is_empty() {
+        properties.insert(DATASETS_KEY.into(), serde_json::json!(ftm.datasets));
+    }
+
+    Entity {
+        id: entity_uuid(&ftm.id),
+        entity_type: entity_type(&ftm.schema),
+        name,
+        aliases,
+        properties: Value::Object(properties),
+        source: source.to_string(),
+        source_id: Some(ftm.id.clone()),
+        confidence: 1.0,
+        first_seen: ftm
+            .first_seen
+            .as_deref()
+            .and_then(parse_source_date)
+            .unwrap_or(now),
+        last_seen: ftm
+            .last_seen
+            .as_deref()
+            .and_then(parse_source_date)
+            .unwrap_or(now),
+        observed_at: ["incorporationDate", "startDate", "date"]
+            .iter()
+            .find_map(|prop| ftm.values(prop).first().and_then(|d| parse_source_date(d))),
+        sanctions_exposure: None,
+        completeness: None,
+        mention_count: None,
+        geometry: None,
+        identifiers: IDENTIFIER_PROPERTIES
+            .iter()
+            .filter_map(|(prop, scheme)| {
+                Some((scheme.to_string(), ftm.values(prop).into_iter().next()?))
+            })
+            .collect(),
+    }
+}
+
+fn edge(
+    ftm: &FtmEntity,
+    source_ref: &str,
+    target_ref: &str,
+    relation_type: RelationType,
+    source: &str,
+    endpoint_props: &[&str],
+) -> Relationship {
+    let mut properties = ftm.properties.clone();
+    for prop in endpoint_props {
+        properties.remove(*prop);
+    }
+    properties.insert(SCHEMA_KEY.into(), Value::String(ftm.schema.clone()));
+    properties.insert(ID_KEY.into(), Value::String(ftm.id.clone()));
+
+    let timestamp = ["startDate", "date"]
+        .iter()
+        .find_map(|prop| ftm.values(prop).first().and_then(|d| parse_source_date(d)));
+
+    Relationship {
+        id: entity_uuid(&format!("{}:{source_ref}:{target_ref}", ftm.id)),
+        source_entity_id: entity_uuid(source_ref),
+        target_entity_id: entity_uuid(target_ref),
+        relation_type,
+        properties: Value::Object(properties),
+        confidence: 1.0,
+        source: source.to_string(),
+        timestamp,
+        strength: None,
+    }
+}
+
+/// Parse FtM entities from either a JSON array or line-delimited JSON, the
+/// layout of bulk exports. Returns the entities and the 1-based line numbers
+/// that failed to parse.
+pub fn parse(body: &str) -> (Vec<FtmEntity>, Vec<usize>) {
+    if body.trim_start().starts_with('[') {
+        return match serde_json::from_str(body) {
+            Ok(entities) => (entities, Vec::new()),
+            Err(_) => (Vec::new(), vec![1]),
+        };
+    }
+
+    let mut entities = Vec::new();
+    let mut failed = Vec::new();
+    for (index, line) in body.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entity) => entities.push(entity),
+            Err(_) => failed.push(index + 1),
+        }
+    }
+    (entities, failed)
+}
+
+fn default_edge_schema(relation_type: &RelationType) -> &'static str {
+    match relation_type {
+        RelationType::OwnerOf => "Ownership",
+        RelationType::DirectorOf => "Directorship",
+        RelationType::EmployeeOf => "Employment",
+        RelationType::PartOf => "Membership",
+        RelationType::TransactedWith => "Payment",
+        _ => "UnknownLink",
+    }
+}
+
+/// Argus properties as FtM string lists, minus the import bookkeeping keys.
+fn ftm_properties(properties: &Value) -> Map<String, Value> {
+    let mut out = Map::new();
+    let Some(properties) = properties.as_object() else {
+        return out;
+    };
+    for (key, value) in properties {
+        if [SCHEMA_KEY, ID_KEY, DATASETS_KEY].contains(&key.as_str()) {
+            continue;
+        }
+        let values: Vec<Value> = match value {
+            Value::Array(values) => values
+                .iter()
+                .filter_map(value_string)
+                .map(Value::String)
+                .collect(),
+            other => value_string(other).map(Value::String).into_iter().collect(),
+        };
+        if !values.is_empty() {
+            out.insert(key.clone(), Value::Array(values));
+        }
+    }
+    out
+}
+
+fn push_value(properties: &mut Map<String, Value>, key: &str, value: &str) {
+    let values = properties
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if let Value::Array(values) = values {
+        if !values.iter().any(|v| v.as_str() == Some(value)) {
+            values.push(Value::String(value.to_string()));
+        }
+    }
+}
+
+/// Map a subgraph to FtM entities. Entities that came from an FtM import
+/// keep their original id, schema and properties. Relationships whose
+/// endpoints are not both in the subgraph are left out.
+pub fn export(subgraph: &Subgraph) -> Vec<FtmEntity> {
+    let mut out: Vec<FtmEntity> = Vec::new();
+    let mut ids = std::collections::HashMap::new();
+
+    for entity in &subgraph.entities {
+        let imported = entity.properties.get(SCHEMA_KEY).and_then(Value::as_str);
+        let id = match (imported, &entity.source_id) {
+            (Some(_), Some(source_id)) => source_id.clone(),
+            _ => entity.id.to_string(),
+        };
+        let mut properties = ftm_properties(&entity.properties);
+        push_value(&mut properties, "name", &entity.name);
+        for alias in &entity.aliases {
+            let is_name = properties["name"]
+                .as_array()
+                .is_some_and(|names| names.iter().any(|n| n.as_str() == Some(alias)));
+            if !is_name {
+                push_value(&mut properties, "alias", alias);
+            }
+        }
+        for (prop, scheme) in IDENTIFIER_PROPERTIES {
+            if let Some(value) = entity.identifiers.get(*scheme) {
+                push_value(&mut properties, prop, value);
+            }
+        }
+
+        ids.insert(entity.id, (id.clone(), out.len()));
+        out.push(FtmEntity {
+            id,
+            schema: imported
+                .map(str::to_string)
+                .unwrap_or_else(|| schema_for(&entity.entity_type)),
+            properties,
+            caption: Some(entity.name.clone()),
+            datasets: entity
+                .properties
+                .get(DATASETS_KEY)
+                .and_then(|d| serde_json::from_value(d.clone()).ok())
+                .unwrap_or_default(),
+            first_seen: Some(entity.first_seen.to_rfc3339()),
+            last_seen: Some(entity.last_seen.to_rfc3339()),
+        });
+    }
+
+    let mut links = std::collections::HashMap::new();
+    for rel in &subgraph.relationships {
+        let (Some((source_ref, _)), Some((target_ref, target_index))) = (
+            ids.get(&rel.source_entity_id),
+            ids.get(&rel.target_entity_id),
+        ) else {
+            continue;
+        };
+
+        if rel.relation_type == RelationType::SanctionedBy
+            && out[*target_index].schema == "Sanction"
+        {
+            push_value(&mut out[*target_index].properties, "entity", source_ref);
+            continue;
+        }
+
+        let schema = rel
+            .properties
+            .get(SCHEMA_KEY)
+            .and_then(Value::as_str)
+            .filter(|s| EDGE_SCHEMATA.iter().any(|(schema, ..)| schema == s))
+            .unwrap_or_else(|| default_edge_schema(&rel.relation_type));
+        let (_, from, to, _) = EDGE_SCHEMATA
+            .iter()
+            .find(|(s, ..)| *s == schema)
+            .expect("edge schema is listed");
+        let id = rel
+            .properties
+            .get(ID_KEY)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| rel.id.to_string());
+
+        // Multi-endpoint links were split on import; fold them back together
+        let index = *links.entry(id.clone()).or_insert_with(|| {
+            let mut properties = ftm_properties(&rel.properties);
+            if schema == "UnknownLink" && rel.relation_type != RelationType::RelatedTo {
+                push_value(&mut properties, "role", rel.relation_type.as_str());
+            }
+            out.push(FtmEntity {
+                id,
+                schema: schema.to_string(),
+                properties,
+                caption: None,
+                datasets: Vec::new(),
+                first_seen: None,
+                last_seen: None,
+            });
+            out.len() - 1
+        });
+        push_value(&mut out[index].properties, from, source_ref);
+        push_value(&mut out[index].properties, to, target_ref);
+    }
+
+    out
+}
+
+/// Serialize entities one per line.
+pub fn to_ndjson(entities: &[FtmEntity]) -> String {
+    entities
+        .iter()
+        .filter_map(|entity| serde_json::to_string(entity).ok())
+        .map(|line| line + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ftm(value: Value) -> FtmEntity {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn sample() -> Vec<FtmEntity> {
+        vec![
+            ftm(json!({
+                "id": "NK-abc", "schema": "Person", "datasets": ["us_ofac_sdn"],
+                "first_seen": "2021-04-01T00:00:00",
+                "properties": {"name": ["Ivan Petrov"], "alias": ["I. Petrov"], "nationality": ["ru"]}
+            })),
+            ftm(json!({
+                "id": "NK-co", "schema": "Company",
+                "properties": {"name": ["Petrov Holdings"], "jurisdiction": ["cy"]}
+            })),
+            ftm(json!({
+                "id": "own-1", "schema": "Ownership",
+                "properties": {"owner": ["NK-abc"], "asset": ["NK-co"], "percentage": ["51"], "startDate": ["2019-03"]}
+            })),
+            ftm(json!({
+                "id": "san-1", "schema": "Sanction",
+                "properties": {"entity": ["NK-abc"], "authority": ["OFAC"]}
+            })),
+            ftm(
+                json!({"id": "broken", "schema": "Directorship", "properties": {"director": ["NK-abc"]}}),
+            ),
+        ]
+    }
+
+    #[test]
+    fn import_maps_things_and_links() {
+        let (result, skipped) = import(&sample(), "opensanctions", Utc::now());
+        assert_eq!(skipped, vec!["broken".to_string()]);
+        assert_eq!(result.entities.len(), 3);
+
+        let person = &result.entities[0];
+        assert_eq!(person.entity_type, EntityType::Person);
+        assert_eq!(person.id, entity_uuid("NK-abc"));
+        assert_eq!(person.source_id.as_deref(), Some("NK-abc"));
+        assert_eq!(person.aliases, vec!["I. Petrov".to_string()]);
+        assert_eq!(person.properties[SCHEMA_KEY], "Person");
+        assert_eq!(person.first_seen.to_rfc3339(), "2021-04-01T00:00:00+00:00");
+        assert_eq!(result.entities[1].entity_type, EntityType::Organization);
+
+        let ownership = result
+            .relationships
+            .iter()
+            .find(|r| r.relation_type == RelationType::OwnerOf)
+            .unwrap();
+        assert_eq!(ownership.source_entity_id, entity_uuid("NK-abc"));
+        assert_eq!(ownership.target_entity_id, entity_uuid("NK-co"));
+        assert_eq!(ownership.properties["percentage"], json!(["51"]));
+        assert!(ownership.properties.get("owner").is_none());
+        assert_eq!(
+            ownership.timestamp.unwrap().to_rfc3339(),
+            "2019-03-01T00:00:00+00:00"
+        );
+
+        let sanctioned = result
+            .relationships
+            .iter()
+            .find(|r| r.relation_type == RelationType::SanctionedBy)
+            .unwrap();
+        assert_eq!(sanctioned.source_entity_id, person.id);
+        assert_eq!(sanctioned.target_entity_id, entity_uuid("san-1"));
+    }
+
+    #[test]
+    fn import_then_export_roundtrips() {
+        let input = sample();
+        let (result, _) = import(&input, "opensanctions", Utc::now());
+        let mut subgraph = Subgraph::default();
+        result
+            .entities
+            .into_iter()
+            .for_each(|e| subgraph.add_entity(e));
+        result
+            .relationships
+            .into_iter()
+            .for_each(|r| subgraph.add_relationship(r));
+
+        let exported = export(&subgraph);
+        let by_id = |id: &str| exported.iter().find(|e| e.id == id).unwrap();
+
+        for original in &input[..4] {
+            let back = by_id(&original.id);
+            assert_eq!(back.schema, original.schema);
+            for (key, values) in &original.properties {
+                assert_eq!(&back.properties[key], values, "{} {}", original.id, key);
+            }
+        }
+        assert_eq!(by_id("NK-abc").datasets, vec!["us_ofac_sdn".to_string()]);
+        assert!(exported.iter().all(|e| e.id != "broken"));
+    }
+
+    #[test]
+    fn native_entities_export_with_generated_ids() {
+        let mut alice = Entity::new(EntityType::Person, "Alice".to_string(), "gdelt".to_string());
+        alice.properties = json!({"birth_year": 1970});
+        let account = Entity::new(
+            EntityType::Custom("bank_account".into()),
+            "DE89".to_string(),
+            "gdelt".to_string(),
+        );
+        let mut subgraph = Subgraph::default();
+        subgraph.add_relationship(Relationship::new(
+            alice.id,
+            account.id,
+            RelationType::Custom("holds".into()),
+            "gdelt".to_string(),
+        ));
+        subgraph.add_entity(alice.clone());
+        subgraph.add_entity(account.clone());
+
+        let exported = export(&subgraph);
+        assert_eq!(exported.len(), 3);
+        assert_eq!(exported[0].id, alice.id.to_string());
+        assert_eq!(exported[0].properties["birth_year"], json!(["1970"]));
+        assert_eq!(exported[1].schema, "BankAccount");
+        assert_eq!(exported[2].schema, "UnknownLink");
+        assert_eq!(exported[2].values("role"), vec!["holds".to_string()]);
+        assert_eq!(exported[2].values("subject"), vec![alice.id.to_string()]);
+
+        let ndjson = to_ndjson(&exported);
+        let (parsed, failed) = parse(&ndjson);
+        assert!(failed.is_empty());
+        assert_eq!(parsed, exported);
+    }
+
+    #[test]
+    fn parse_reports_bad_lines() {
+        let body = "{\"id\": \"a\", \"schema\": \"Person\"}\n\nnot json\n";
+        let (entities, failed) = parse(body);
+        assert_eq!(entities.len(), 1);
+        assert_eq!(failed, vec![3]);
+
+        let (entities, _) = parse(r#"[{"id": "a", "schema": "Person"}]"#);
+        assert_eq!(entities[0].id, "a");
+    }
+}
@@ -0,0 +1,98 @@
+//! Conversions between Argus subgraphs and external interchange formats.
+
+pub mod ftm;
+pub mod stix;
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::{Entity, Relationship};
+use crate::graph::GraphNeighbors;
+
+/// Formats a subgraph can be exported as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// STIX 2.1 bundle, for threat-intel platforms.
+    #[default]
+    Stix,
+    /// Line-delimited FollowTheMoney entities, for Aleph and OpenSanctions.
+    Ftm,
+}
+
+/// A set of entities and the relationships between them, deduplicated by id.
+#[derive(Debug, Clone, Default)]
+pub struct Subgraph {
+    pub entities: Vec<Entity>,
+    pub relationships: Vec<Relationship>,
+    entity_ids: HashSet<Uuid>,
+    relationship_ids: HashSet<Uuid>,
+}
+
+impl Subgraph {
+    pub fn add_entity(&mut self, entity: Entity) {
+        if self.entity_ids.insert(entity.id) {
+            self.entities.push(entity);
+        }
+    }
+
+    pub fn add_relationship(&mut self, relationship: Relationship) {
+        if self.relationship_ids.insert(relationship.id) {
+            self.relationships.push(relationship);
+        }
+    }
+
+    /// Merge a neighbor traversal: the root, its neighbors and the edges.
+    pub fn add_neighbors(&mut self, neighbors: GraphNeighbors) {
+        self.add_entity(neighbors.entity);
+        for entity in neighbors.neighbors {
+            self.add_entity(entity);
+        }
+        for relationship in neighbors.relationships {
+            self.add_relationship(relationship);
+        }
+    }
+
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.entity_ids.contains(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityType, RelationType};
+
+    #[test]
+    fn subgraph_deduplicates_entities_and_relationships() {
+        let alice = Entity::new(EntityType::Person, "Alice".to_string(), "test".to_string());
+        let acme = Entity::new(
+            EntityType::Organization,
+            "Acme".to_string(),
+            "test".to_string(),
+        );
+        let rel = Relationship::new(
+            alice.id,
+            acme.id,
+            RelationType::DirectorOf,
+            "test".to_string(),
+        );
+
+        let mut subgraph = Subgraph::default();
+        for _ in 0..2 {
+            subgraph.add_neighbors(GraphNeighbors {
+                entity: alice.clone(),
+                relationships: vec![rel.clone()],
+                neighbors: vec![acme.clone()],
+                truncated: false,
+            });
+        }
+
+        assert_eq!(subgraph.entities.len(), 2);
+        assert_eq!(subgraph.relationships.len(), 1);
+        assert!(subgraph.contains(acme.id));
+    }
+}
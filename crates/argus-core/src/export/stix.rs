@@ -0,0 +1,280 @@
+//! STIX 2.1 bundles.
+//!
+//! People and organizations become `identity` objects, locations become
+//! `location` objects, vessels and aircraft become `infrastructure`, and
+//! everything else is carried as a custom `x-argus-entity` object so no
+//! entity is dropped. Relationships become `relationship` SROs. Argus
+//! fields without a STIX equivalent are kept as `x_argus_*` properties.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::entity::{Entity, EntityType, Relationship};
+use crate::export::Subgraph;
+
+pub const SPEC_VERSION: &str = "2.1";
+
+/// Media type for STIX 2.1 content.
+pub const MEDIA_TYPE: &str = "application/stix+json;version=2.1";
+
+/// STIX object type an entity maps to.
+pub fn object_type(entity_type: &EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Person | EntityType::Organization => "identity",
+        EntityType::Location => "location",
+        EntityType::Vessel | EntityType::Aircraft => "infrastructure",
+        _ => "x-argus-entity",
+    }
+}
+
+/// STIX identifier for an entity, reusing its Argus UUID.
+pub fn stix_id(entity: &Entity) -> String {
+    format!("{}--{}", object_type(&entity.entity_type), entity.id)
+}
+
+fn timestamp(ts: DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Argus confidence in [0.0, 1.0] to the STIX 0–100 scale.
+fn confidence(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 100.0).round() as u8
+}
+
+fn property<'a>(entity: &'a Entity, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter()
+        .find_map(|key| entity.properties.get(*key))
+        .filter(|value| !value.is_null())
+}
+
+/// Map one entity to a STIX domain object.
+pub fn entity_object(entity: &Entity) -> Value {
+    let object_type = object_type(&entity.entity_type);
+    let mut object = Map::new();
+    object.insert("type".into(), json!(object_type));
+    object.insert("spec_version".into(), json!(SPEC_VERSION));
+    object.insert("id".into(), json!(stix_id(entity)));
+    object.insert("created".into(), json!(timestamp(entity.first_seen)));
+    object.insert("modified".into(), json!(timestamp(entity.last_seen)));
+    object.insert("name".into(), json!(entity.name));
+    object.insert("confidence".into(), json!(confidence(entity.confidence)));
+
+    match &entity.entity_type {
+        EntityType::Person => {
+            object.insert("identity_class".into(), json!("individual"));
+        }
+        EntityType::Organization => {
+            object.insert("identity_class".into(), json!("organization"));
+        }
+        EntityType::Location => {
+            if let Some(country) = property(entity, &["country", "country_code"]) {
+                object.insert("country".into(), country.clone());
+            }
+            if let Some(lat) = property(entity, &["latitude", "lat"]) {
+                object.insert("latitude".into(), lat.clone());
+            }
+            if let Some(lon) = property(entity, &["longitude", "lon", "lng"]) {
+                object.insert("longitude".into(), lon.clone());
+            }
+        }
+        EntityType::Vessel | EntityType::Aircraft => {
+            object.insert("infrastructure_types".into(), json!(["unknown"]));
+        }
+        other => {
+            object.insert("x_argus_entity_type".into(), json!(other.as_str()));
+        }
+    }
+
+    if !entity.aliases.is_empty() {
+        // Only infrastructure defines `aliases`; elsewhere it is a custom property
+        let key = if object_type == "infrastructure" {
+            "aliases"
+        } else {
+            "x_argus_aliases"
+        };
+        object.insert(key.into(), json!(entity.aliases));
+    }
+
+    let mut reference = Map::new();
+    reference.insert("source_name".into(), json!(entity.source));
+    if let Some(source_id) = &entity.source_id {
+        reference.insert("external_id".into(), json!(source_id));
+    }
+    object.insert(
+        "external_references".into(),
+        Value::Array(vec![Value::Object(reference)]),
+    );
+
+    if entity.properties.as_object().is_some_and(|p| !p.is_empty()) {
+        object.insert("x_argus_properties".into(), entity.properties.clone());
+    }
+    if let Some(exposure) = entity.sanctions_exposure {
+        object.insert("x_argus_sanctions_exposure".into(), json!(exposure));
+    }
+
+    Value::Object(object)
+}
+
+/// Map a relationship to a STIX relationship object, given the STIX ids of
+/// its endpoints. Undated relationships are stamped with `now`, since
+/// `created`/`modified` are required.
+pub fn relationship_object(
+    relationship: &Relationship,
+    source_ref: &str,
+    target_ref: &str,
+    now: DateTime<Utc>,
+) -> Value {
+    let created = timestamp(relationship.timestamp.unwrap_or(now));
+    let mut object = Map::new();
+    object.insert("type".into(), json!("relationship"));
+    object.insert("spec_version".into(), json!(SPEC_VERSION));
+    object.insert(
+        "id".into(),
+        json!(format!("relationship--{}", relationship.id)),
+    );
+    object.insert("created".into(), json!(created));
+    object.insert("modified".into(), json!(created));
+    // STIX relationship types are lowercase and hyphenated
+    object.insert(
+        "relationship_type".into(),
+        json!(relationship.relation_type.as_str().replace('_', "-")),
+    );
+    object.insert("source_ref".into(), json!(source_ref));
+    object.insert("target_ref".into(), json!(target_ref));
+    object.insert(
+        "confidence".into(),
+        json!(confidence(relationship.confidence)),
+    );
+    object.insert(
+        "external_references".into(),
+        json!([{ "source_name": relationship.source }]),
+    );
+    if relationship
+        .properties
+        .as_object()
+        .is_some_and(|p| !p.is_empty())
+    {
+        object.insert("x_argus_properties".into(), relationship.properties.clone());
+    }
+    Value::Object(object)
+}
+
+/// Build a STIX bundle from a subgraph. Relationships whose endpoints are
+/// not both in the subgraph are left out so every reference resolves.
+pub fn bundle(subgraph: &Subgraph, now: DateTime<Utc>) -> Value {
+    let ids: HashMap<Uuid, String> = subgraph
+        .entities
+        .iter()
+        .map(|entity| (entity.id, stix_id(entity)))
+        .collect();
+
+    let mut objects: Vec<Value> = subgraph.entities.iter().map(entity_object).collect();
+    for relationship in &subgraph.relationships {
+        let (Some(source_ref), Some(target_ref)) = (
+            ids.get(&relationship.source_entity_id),
+            ids.get(&relationship.target_entity_id),
+        ) else {
+            continue;
+        };
+        objects.push(relationship_object(
+            relationship,
+            source_ref,
+            target_ref,
+            now,
+        ));
+    }
+
+    json!({
+        "type": "bundle",
+        "id": format!("bundle--{}", Uuid::new_v4()),
+        "objects": objects,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::RelationType;
+
+    #[test]
+    fn entity_types_map_to_stix_objects() {
+        let mut person = Entity::new(
+            EntityType::Person,
+            "Alice".to_string(),
+            "opensanctions".to_string(),
+        );
+        person.source_id = Some("Q42".to_string());
+        person.aliases = vec!["A.".to_string()];
+        person.confidence = 0.874;
+
+        let object = entity_object(&person);
+        assert_eq!(object["type"], "identity");
+        assert_eq!(object["identity_class"], "individual");
+        assert_eq!(object["id"], format!("identity--{}", person.id));
+        assert_eq!(object["confidence"], 87);
+        assert_eq!(object["x_argus_aliases"], json!(["A."]));
+        assert_eq!(object["external_references"][0]["external_id"], "Q42");
+
+        let mut city = Entity::new(
+            EntityType::Location,
+            "Berlin".to_string(),
+            "gdelt".to_string(),
+        );
+        city.properties = json!({"country": "DE", "latitude": 52.52, "longitude": 13.40});
+        let object = entity_object(&city);
+        assert_eq!(object["type"], "location");
+        assert_eq!(object["country"], "DE");
+        assert_eq!(object["latitude"], 52.52);
+
+        let event = Entity::new(EntityType::Event, "Summit".to_string(), "gdelt".to_string());
+        let object = entity_object(&event);
+        assert_eq!(object["type"], "x-argus-entity");
+        assert_eq!(object["x_argus_entity_type"], "event");
+    }
+
+    #[test]
+    fn bundle_skips_dangling_relationships() {
+        let alice = Entity::new(EntityType::Person, "Alice".to_string(), "test".to_string());
+        let acme = Entity::new(
+            EntityType::Organization,
+            "Acme".to_string(),
+            "test".to_string(),
+        );
+        let vessel = Entity::new(
+            EntityType::Vessel,
+            "Ever Given".to_string(),
+            "test".to_string(),
+        );
+
+        let mut subgraph = Subgraph::default();
+        subgraph.add_entity(alice.clone());
+        subgraph.add_entity(acme.clone());
+        subgraph.add_relationship(Relationship::new(
+            alice.id,
+            acme.id,
+            RelationType::OwnerOf,
+            "test".to_string(),
+        ));
+        subgraph.add_relationship(Relationship::new(
+            acme.id,
+            vessel.id,
+            RelationType::OwnerOf,
+            "test".to_string(),
+        ));
+
+        let bundle = bundle(&subgraph, Utc::now());
+        assert_eq!(bundle["type"], "bundle");
+        let objects = bundle["objects"].as_array().unwrap();
+        assert_eq!(objects.len(), 3);
+
+        let rel = &objects[2];
+        assert_eq!(rel["type"], "relationship");
+        assert_eq!(rel["relationship_type"], "owner-of");
+        assert_eq!(rel["source_ref"], format!("identity--{}", alice.id));
+        assert_eq!(rel["target_ref"], format!("identity--{}", acme.id));
+        assert!(rel["created"].is_string());
+    }
+}
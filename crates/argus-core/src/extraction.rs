@@ -1,11 +1,212 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::agent::RawDocument;
 use crate::entity::ExtractionResult;
-use crate::error::Result;
+use crate::error::{ArgusError, Result};
+
+/// Most recent failures kept in the extraction failure catalog.
+pub const MAX_EXTRACTION_FAILURES: usize = 500;
+
+/// Characters of model output kept with a cataloged failure.
+pub const RAW_OUTPUT_SNIPPET_CHARS: usize = 4000;
+
+/// A document the pipeline could not extract, with the reason.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedExtraction {
+    pub document: RawDocument,
+    pub error: String,
+    /// What the model answered, when it answered with something unusable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl FailedExtraction {
+    pub fn new(document: RawDocument, error: &ArgusError) -> Self {
+        let (raw_output, model) = match error {
+            ArgusError::ExtractionOutput {
+                model, raw_output, ..
+            } => (Some(raw_output.clone()), Some(model.clone())),
+            _ => (None, None),
+        };
+        Self {
+            document,
+            error: error.to_string(),
+            raw_output,
+            model,
+        }
+    }
+}
+
+/// A failure kept for prompt debugging: the model's output and why it
+/// could not be parsed, with the document so it can be retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionFailure {
+    pub id: Uuid,
+    pub run_id: String,
+    pub agent_name: String,
+    pub document: RawDocument,
+    pub error: String,
+    /// The start of the model's output, up to [`RAW_OUTPUT_SNIPPET_CHARS`].
+    pub raw_output: String,
+    pub model: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl ExtractionFailure {
+    /// Catalog `failed` if the model's output is what failed; failures
+    /// before any output (HTTP errors, rate limits) are left to the retry
+    /// queue.
+    pub fn from_failed(run_id: &str, agent_name: &str, failed: &FailedExtraction) -> Option<Self> {
+        let raw_output = failed.raw_output.as_deref()?;
+        Some(Self {
+            id: Uuid::new_v4(),
+            run_id: run_id.to_string(),
+            agent_name: agent_name.to_string(),
+            document: failed.document.clone(),
+            error: failed.error.clone(),
+            raw_output: raw_output.chars().take(RAW_OUTPUT_SNIPPET_CHARS).collect(),
+            model: failed.model.clone().unwrap_or_default(),
+            failed_at: Utc::now(),
+        })
+    }
+}
+
+/// Per-document outcome of a batch: what succeeded and what did not.
+#[derive(Debug, Clone, Default)]
+pub struct BatchExtraction {
+    pub results: Vec<ExtractionResult>,
+    pub failures: Vec<FailedExtraction>,
+}
 
 #[async_trait]
 pub trait ExtractionPipeline: Send + Sync {
     async fn extract(&self, document: &RawDocument) -> Result<ExtractionResult>;
+    /// Extract a batch, failing only if every document failed.
     async fn extract_batch(&self, documents: &[RawDocument]) -> Result<Vec<ExtractionResult>>;
+    /// Extract a batch, reporting failed documents instead of dropping them.
+    async fn extract_batch_detailed(&self, documents: &[RawDocument]) -> BatchExtraction;
+}
+
+/// Which extraction pipeline handles a source's documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineKind {
+    /// Only the agent's own structured results; documents without one fail.
+    Structured,
+    /// Every document goes to the LLM, structured results ignored.
+    Llm,
+    /// The agent's structured result where there is one, the LLM otherwise.
+    #[default]
+    Hybrid,
+}
+
+impl PipelineKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelineKind::Structured => "structured",
+            PipelineKind::Llm => "llm",
+            PipelineKind::Hybrid => "hybrid",
+        }
+    }
+}
+
+impl std::str::FromStr for PipelineKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "structured" => Ok(PipelineKind::Structured),
+            "llm" => Ok(PipelineKind::Llm),
+            "hybrid" => Ok(PipelineKind::Hybrid),
+            other => Err(format!("Unknown extraction pipeline: {other}")),
+        }
+    }
+}
+
+/// Items dropped by post-extraction validation, counted per rule.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ValidationReport {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub entities_rejected: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub relationships_rejected: u64,
+    /// Rejections keyed by rule name, entities and relationships together.
+    #[cfg_attr(feature = "ts", ts(type = "Record<string, number>"))]
+    pub by_rule: BTreeMap<String, u64>,
+}
+
+impl ValidationReport {
+    pub fn is_empty(&self) -> bool {
+        self.entities_rejected == 0 && self.relationships_rejected == 0
+    }
+
+    pub fn merge(&mut self, other: &ValidationReport) {
+        self.entities_rejected += other.entities_rejected;
+        self.relationships_rejected += other.relationships_rejected;
+        for (rule, count) in &other.by_rule {
+            *self.by_rule.entry(rule.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Counters describing how an extraction cache has been used since startup.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub backend: String,
+    pub hits: u64,
+    pub misses: u64,
+    pub writes: u64,
+    /// Entries found but discarded because they outlived the TTL.
+    pub expired: u64,
+    pub errors: u64,
+    pub ttl_seconds: u64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups served from the cache, or 0.0 before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Stores parsed extraction results keyed by a hash of the exact LLM input,
+/// so reprocessing identical content does not pay for another API call.
+#[async_trait]
+pub trait ExtractionCache: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<ExtractionResult>>;
+    async fn put(&self, key: &str, result: &ExtractionResult) -> Result<()>;
+    fn stats(&self) -> CacheStats;
+}
+
+/// Where a geocoder placed a name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeocodedPlace {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// ISO 3166-1 alpha-2, uppercase.
+    pub country: Option<String>,
+    /// The geocoder's full name for the place.
+    pub display_name: Option<String>,
+}
+
+/// Resolves place names to coordinates.
+#[async_trait]
+pub trait Geocoder: Send + Sync {
+    /// Short name recorded on geocoded entities, e.g. `nominatim`.
+    fn name(&self) -> &str;
+    /// The best match for `query`, or `None` if nothing matched.
+    async fn geocode(&self, query: &str) -> Result<Option<GeocodedPlace>>;
 }
@@ -1,29 +1,612 @@
+use std::collections::{BTreeMap, HashMap};
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::entity::{Entity, ExtractionResult, Relationship};
+use crate::backup::{NodeDump, RelationshipDump};
+use crate::density::{DensityQuery, EventDensity};
+use crate::document::{
+    ArchivedDocument, DocumentSearchHit, DocumentSearchQuery, RunDocumentSample, RunExtractionStats,
+};
+use crate::entity::{Direction, Entity, EntityType, ExtractionResult, RelationType, Relationship};
 use crate::error::Result;
+use crate::history::{EntityChange, Tombstone};
+use crate::inference::{InferenceReport, InferenceRule};
+use crate::integrity::{IntegrityCheck, IntegrityFinding};
+use crate::retention::RetentionPolicy;
+use crate::tracks::PositionReport;
+
+/// Query result rows as JSON objects, read from the database as they are
+/// consumed.
+pub type RowStream = BoxStream<'static, Result<serde_json::Value>>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphQuery {
     pub cypher: String,
     pub params: serde_json::Value,
 }
 
+/// Clauses that modify the graph or schema.
+const WRITE_CLAUSES: &[&str] = &[
+    "CREATE", "MERGE", "SET", "DELETE", "DETACH", "REMOVE", "DROP", "FOREACH", "LOAD",
+];
+
+/// Read-only procedures allowed after `CALL`; everything else is rejected
+/// because procedures can write.
+const READ_PROCEDURES: &[&str] = &[
+    "db.labels",
+    "db.relationshiptypes",
+    "db.propertykeys",
+    "db.schema.visualization",
+    "db.index.fulltext.querynodes",
+    "db.index.fulltext.queryrelationships",
+];
+
+impl GraphQuery {
+    /// The first write clause or non-allowlisted procedure call, if any.
+    /// String literals, backtick-quoted names and comments are ignored.
+    ///
+    /// This is a conservative screen for a clear error message; callers
+    /// that must not write should also run the query in a transaction they
+    /// roll back.
+    pub fn write_clause(&self) -> Option<String> {
+        let code = strip_literals(&self.cypher);
+        let tokens: Vec<&str> = code
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .filter(|t| !t.is_empty())
+            .collect();
+        for (i, token) in tokens.iter().enumerate() {
+            let upper = token.to_ascii_uppercase();
+            if WRITE_CLAUSES.contains(&upper.as_str()) {
+                return Some(upper);
+            }
+            if upper == "CALL" {
+                let procedure = tokens
+                    .get(i + 1)
+                    .map(|p| p.to_ascii_lowercase())
+                    .unwrap_or_default();
+                if !READ_PROCEDURES.contains(&procedure.as_str()) {
+                    return Some(format!("CALL {procedure}").trim_end().to_string());
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Blank out string literals, quoted identifiers and comments.
+fn strip_literals(cypher: &str) -> String {
+    let mut out = String::with_capacity(cypher.len());
+    let mut chars = cypher.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                let mut escaped = false;
+                for inner in chars.by_ref() {
+                    if escaped {
+                        escaped = false;
+                    } else if inner == '\\' && c != '`' {
+                        escaped = true;
+                    } else if inner == c {
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for inner in chars.by_ref() {
+                    if inner == '\n' {
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for inner in chars.by_ref() {
+                    if prev == '*' && inner == '/' {
+                        break;
+                    }
+                    prev = inner;
+                }
+                out.push(' ');
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// One path between two entities, as alternating entities and edges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct GraphPath {
+    /// Entities along the path, starting at the source.
+    pub entities: Vec<Entity>,
+    /// Edges between consecutive entities; `relationships[i]` joins
+    /// `entities[i]` and `entities[i + 1]` in either direction.
+    pub relationships: Vec<Relationship>,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GraphNeighbors {
     pub entity: Entity,
     pub relationships: Vec<Relationship>,
     pub neighbors: Vec<Entity>,
+    /// The traversal hit a node, relationship or per-hop cap, so this is a
+    /// sample of the neighborhood rather than all of it.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Restricts which edges a neighbor traversal follows.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NeighborFilter {
+    #[serde(default)]
+    pub direction: Direction,
+    /// Only traverse these relationship types; empty means all.
+    #[serde(default)]
+    pub relation_types: Vec<RelationType>,
+    /// Only traverse entities and relationships that existed at this
+    /// instant: first seen by then and not yet invalidated.
+    #[serde(default)]
+    pub as_of: Option<DateTime<Utc>>,
+    /// Skip relationships scored weaker than this. Relationships not yet
+    /// scored are kept.
+    #[serde(default)]
+    pub min_strength: Option<f64>,
+}
+
+/// Ordering applied to entity search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum EntitySort {
+    #[default]
+    Relevance,
+    Name,
+    LastSeen,
+    SanctionsExposure,
+    /// Least complete profiles first.
+    Completeness,
+}
+
+/// Structured filters for entity search, applied inside the graph query.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntitySearchFilter {
+    pub entity_type: Option<EntityType>,
+    pub min_sanctions_exposure: Option<f64>,
+    pub sort: EntitySort,
+}
+
+/// Relationship counts touching an entity, in either direction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelationshipSummary {
+    pub total: u64,
+    pub by_type: HashMap<RelationType, u64>,
+}
+
+/// One entity from a batch lookup, optionally with its relationship summary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityBatchItem {
+    pub entity: Entity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationships: Option<RelationshipSummary>,
+}
+
+/// A relationship whose evidence or properties matched a search, with the
+/// names of the entities it joins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RelationshipSearchHit {
+    pub relationship: Relationship,
+    pub source_name: String,
+    pub target_name: String,
+}
+
+/// Filters and pagination for listing relationships.
+///
+/// `created_after` / `created_before` apply to when the relationship was first
+/// written to the graph, not to the event time in `Relationship::timestamp`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationshipFilter {
+    pub relation_type: Option<RelationType>,
+    pub source: Option<String>,
+    pub min_confidence: Option<f64>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    #[serde(default = "default_relationship_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+fn default_relationship_limit() -> usize {
+    100
+}
+
+impl Default for RelationshipFilter {
+    fn default() -> Self {
+        Self {
+            relation_type: None,
+            source: None,
+            min_confidence: None,
+            created_after: None,
+            created_before: None,
+            limit: default_relationship_limit(),
+            offset: 0,
+        }
+    }
+}
+
+/// Graph write transactions since startup and how their transient
+/// failures went.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphWriteStats {
+    pub transactions: u64,
+    /// Attempts repeated after a transient error.
+    pub retries: u64,
+    /// Transactions that committed after at least one retry.
+    pub recovered: u64,
+    /// Transactions still failing transiently when retries ran out.
+    pub exhausted: u64,
+    /// Transactions that failed otherwise; these are not retried.
+    pub failed: u64,
+    /// Transient errors seen, per Neo4j status code.
+    pub transient_errors: BTreeMap<String, u64>,
+}
+
+/// How repeated observations of one relationship (same endpoints, type and
+/// source) are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RelationIdentity {
+    /// One edge; repeats only extend its `timestamp`..`last_seen` window
+    /// and bump `observations`. Suits standing facts like LOCATED_AT.
+    #[default]
+    Singleton,
+    /// One edge per window of `seconds`, keyed on the window start, so
+    /// recurring events such as meetings stay separate occurrences.
+    Bucketed { seconds: u64 },
+}
+
+impl RelationIdentity {
+    /// Start of the window `at` falls in, or `None` for singletons.
+    pub fn bucket(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            RelationIdentity::Singleton => None,
+            RelationIdentity::Bucketed { seconds } => {
+                let seconds = (*seconds).clamp(1, i64::MAX as u64) as i64;
+                DateTime::from_timestamp(at.timestamp().div_euclid(seconds) * seconds, 0)
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for RelationIdentity {
+    type Err = String;
+
+    /// `singleton`, or a window such as `90s`, `15m`, `6h` or `1d`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        if s == "singleton" {
+            return Ok(RelationIdentity::Singleton);
+        }
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (count, unit) = s.split_at(split);
+        let unit_seconds = match unit {
+            "s" | "" => 1,
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86400,
+            _ => return Err(format!("Unknown relationship identity: {s}")),
+        };
+        match count.parse::<u64>() {
+            Ok(count) if count > 0 => Ok(RelationIdentity::Bucketed {
+                seconds: count.saturating_mul(unit_seconds),
+            }),
+            _ => Err(format!("Unknown relationship identity: {s}")),
+        }
+    }
+}
+
+impl TryFrom<String> for RelationIdentity {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RelationIdentity> for String {
+    fn from(identity: RelationIdentity) -> Self {
+        match identity {
+            RelationIdentity::Singleton => "singleton".to_string(),
+            RelationIdentity::Bucketed { seconds } if seconds % 86400 == 0 => {
+                format!("{}d", seconds / 86400)
+            }
+            RelationIdentity::Bucketed { seconds } if seconds % 3600 == 0 => {
+                format!("{}h", seconds / 3600)
+            }
+            RelationIdentity::Bucketed { seconds } if seconds % 60 == 0 => {
+                format!("{}m", seconds / 60)
+            }
+            RelationIdentity::Bucketed { seconds } => format!("{seconds}s"),
+        }
+    }
 }
 
 #[async_trait]
 pub trait GraphStore: Send + Sync {
     async fn store_extraction(&self, result: &ExtractionResult) -> Result<()>;
+    /// Store several results in a single transaction; all or nothing.
+    async fn store_extractions(&self, results: &[ExtractionResult]) -> Result<()>;
     async fn get_entity(&self, id: Uuid) -> Result<Option<Entity>>;
+    /// Change log for an entity, newest first.
+    async fn get_entity_history(&self, id: Uuid, limit: usize) -> Result<Vec<EntityChange>>;
+    /// Fetch many entities in one round-trip. Unknown ids are omitted and
+    /// results follow the order of `ids`.
+    async fn get_entities_batch(
+        &self,
+        ids: &[Uuid],
+        include_relationships: bool,
+    ) -> Result<Vec<EntityBatchItem>>;
     async fn search_entities(&self, query: &str, limit: usize) -> Result<Vec<Entity>>;
+    /// Page through all entities ordered by id, starting after `after`.
+    async fn list_entities_after(&self, after: Option<Uuid>, limit: usize) -> Result<Vec<Entity>>;
+    /// Entities first seen at or after `since`, newest first.
+    async fn entities_first_seen_since(
+        &self,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Entity>>;
+    /// How many entities were first seen, and relationships first written,
+    /// at or after `since`.
+    async fn count_new_since(&self, since: DateTime<Utc>) -> Result<(u64, u64)>;
+    /// Record source-reported dates on stored entities, keeping any earlier
+    /// `observed_at` already there.
+    async fn set_observed_at(&self, observed: &[(Uuid, DateTime<Utc>)]) -> Result<()>;
+    async fn search_entities_filtered(
+        &self,
+        query: &str,
+        filter: &EntitySearchFilter,
+        limit: usize,
+    ) -> Result<Vec<Entity>>;
+    /// Entities carrying `value` under the external identifier `scheme`
+    /// (compared case-insensitively).
+    async fn find_by_identifier(&self, scheme: &str, value: &str) -> Result<Vec<Entity>>;
+    /// Fold `duplicates` into `keep`: their relationships, aliases, sources,
+    /// identifiers and change history move over and the nodes are deleted.
+    /// Returns the merged entity.
+    async fn merge_entities(&self, keep: Uuid, duplicates: &[Uuid]) -> Result<Entity>;
+    /// Hide an entity from search and traversal and tombstone it against
+    /// re-ingestion; its node, relationships and history stay.
+    async fn soft_delete_entity(&self, id: Uuid) -> Result<Tombstone>;
+    /// Undo a soft delete, lifting the tombstone.
+    async fn restore_entity(&self, id: Uuid) -> Result<Entity>;
+    /// Delete an entity with its relationships and history for good,
+    /// soft-deleted or not. The tombstone stays.
+    async fn purge_entity(&self, id: Uuid) -> Result<Tombstone>;
+    /// Tombstones, most recent first.
+    async fn list_tombstones(&self, limit: usize) -> Result<Vec<Tombstone>>;
     async fn get_neighbors(&self, entity_id: Uuid, depth: u32) -> Result<GraphNeighbors>;
+    async fn get_neighbors_filtered(
+        &self,
+        entity_id: Uuid,
+        depth: u32,
+        filter: &NeighborFilter,
+    ) -> Result<GraphNeighbors>;
+    /// List relationships matching `filter`, newest first.
+    async fn list_relationships(&self, filter: &RelationshipFilter) -> Result<Vec<Relationship>>;
+    /// Relationships whose evidence or property values contain `query`
+    /// (case-insensitive), most confident first.
+    async fn search_relationships(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<RelationshipSearchHit>>;
+    /// The relationship with `id`, unless either endpoint is deleted.
+    async fn get_relationship(&self, id: Uuid) -> Result<Option<Relationship>>;
     async fn execute_cypher(&self, query: &GraphQuery) -> Result<serde_json::Value>;
+    /// [`execute_cypher`](Self::execute_cypher) without collecting the rows,
+    /// for results too large to hold in memory.
+    async fn stream_cypher(&self, query: &GraphQuery) -> Result<RowStream>;
+    /// Run a query that must not modify the graph: rejected if it contains a
+    /// write clause, and executed in a transaction that is always rolled back.
+    async fn execute_readonly_cypher(&self, query: &GraphQuery) -> Result<serde_json::Value>;
+    /// Shortest paths between two entities, up to `max_depth` hops. With
+    /// `as_of`, only through entities and relationships that existed then.
+    async fn find_paths(
+        &self,
+        from: Uuid,
+        to: Uuid,
+        max_depth: u32,
+        limit: usize,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<GraphPath>>;
+    /// Persist collected documents, upserting on (source, source_id).
+    async fn archive_documents(&self, documents: &[ArchivedDocument]) -> Result<()>;
+    /// Full-text search over archived documents, best match first.
+    async fn search_documents(&self, query: &DocumentSearchQuery)
+        -> Result<Vec<DocumentSearchHit>>;
+    /// Up to `sample` documents archived by `run_id`, chosen at random, with
+    /// their extraction output. A document re-collected by a later run
+    /// belongs to that run.
+    async fn sample_run_documents(&self, run_id: &str, sample: usize) -> Result<RunDocumentSample>;
+    /// Totals over every document archived by `run_id`, with entities first
+    /// seen at or after `started_at` counted as new.
+    async fn run_extraction_stats(
+        &self,
+        run_id: &str,
+        started_at: DateTime<Utc>,
+    ) -> Result<RunExtractionStats>;
     async fn entity_count(&self) -> Result<u64>;
     async fn relationship_count(&self) -> Result<u64>;
+    /// Recompute `sanctions_exposure` for every person and organization.
+    /// Returns the number of entities scored.
+    async fn update_sanctions_exposure(&self) -> Result<u64>;
+    /// Recompute `strength` for every relationship as of `now`. Returns the
+    /// number of linked entity pairs scored.
+    async fn update_relationship_strength(&self, now: DateTime<Utc>) -> Result<u64>;
+    /// Recompute `completeness` for every entity as of `now`. Returns the
+    /// number of entities scored.
+    async fn update_completeness(&self, now: DateTime<Utc>) -> Result<u64>;
+    /// Derive `rule`'s relationships from the asserted ones as of `run_at`,
+    /// and retract those earlier passes derived that no longer hold.
+    async fn apply_inference_rule(
+        &self,
+        rule: InferenceRule,
+        run_at: DateTime<Utc>,
+    ) -> Result<InferenceReport>;
+    /// Count violations of one graph invariant and, with `repair`, apply
+    /// its safe fix if it has one. Repairs that need a time use `now`.
+    async fn check_integrity(
+        &self,
+        check: IntegrityCheck,
+        repair: bool,
+        now: DateTime<Utc>,
+    ) -> Result<IntegrityFinding>;
+    /// Set the GeoJSON `geometry` of existing entities by id. Returns the
+    /// number of entities updated.
+    async fn set_geometries(&self, geometries: &[(Uuid, serde_json::Value)]) -> Result<u64>;
+    /// Store position reports. A report for an asset and time already
+    /// stored is kept as it is.
+    async fn store_positions(&self, positions: &[PositionReport]) -> Result<()>;
+    /// Reports observed from `since` to `until`, oldest first, at most `limit`.
+    async fn positions_between(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<PositionReport>>;
+    /// The latest `limit` reports for any of the assets (`scheme:value`),
+    /// oldest first.
+    async fn asset_positions(&self, assets: &[String], limit: usize)
+        -> Result<Vec<PositionReport>>;
+    /// Geolocated `Event` entities and position reports within the query's
+    /// box and window, counted per geohash cell and time bucket.
+    async fn event_density(&self, query: &DensityQuery) -> Result<EventDensity>;
+    /// Number of nodes `policy` covers that were last observed before `before`.
+    async fn count_expired(&self, policy: &RetentionPolicy, before: DateTime<Utc>) -> Result<u64>;
+    /// Up to `limit` of those nodes, oldest first, as their properties.
+    async fn expired_nodes(
+        &self,
+        policy: &RetentionPolicy,
+        before: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<serde_json::Value>>;
+    /// Delete nodes by `id` with their relationships and change history.
+    /// Returns the number of nodes deleted.
+    async fn delete_nodes(&self, ids: &[String]) -> Result<u64>;
+    /// Every node of any label, streamed from one query. For workspace
+    /// snapshots.
+    async fn snapshot_nodes(&self) -> Result<BoxStream<'static, Result<NodeDump>>>;
+    /// Every relationship the same way.
+    async fn snapshot_relationships(&self) -> Result<BoxStream<'static, Result<RelationshipDump>>>;
+    /// Delete the whole graph before restoring a snapshot into it.
+    async fn begin_restore(&self) -> Result<()>;
+    async fn restore_nodes(&self, nodes: &[NodeDump]) -> Result<()>;
+    /// Returns the number restored; ones whose endpoints were not restored
+    /// are skipped.
+    async fn restore_relationships(&self, relationships: &[RelationshipDump]) -> Result<u64>;
+    /// Clear what the restore kept on nodes to link relationships.
+    async fn finish_restore(&self) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_clause(cypher: &str) -> Option<String> {
+        GraphQuery {
+            cypher: cypher.to_string(),
+            params: serde_json::Value::Null,
+        }
+        .write_clause()
+    }
+
+    #[test]
+    fn read_queries_pass() {
+        assert_eq!(
+            write_clause("MATCH (n:Person) WHERE n.name CONTAINS 'a' RETURN n LIMIT 5"),
+            None
+        );
+        assert_eq!(
+            write_clause("MATCH (n) RETURN n.created_at, n.offset ORDER BY n.updated_at"),
+            None
+        );
+        assert_eq!(
+            write_clause("CALL db.labels() YIELD label RETURN label"),
+            None
+        );
+    }
+
+    #[test]
+    fn write_clauses_are_found() {
+        assert_eq!(
+            write_clause("MATCH (n) DETACH DELETE n"),
+            Some("DETACH".into())
+        );
+        assert_eq!(write_clause("match (n) set n.x = 1"), Some("SET".into()));
+        assert_eq!(write_clause("MERGE (n:X {id: 1})"), Some("MERGE".into()));
+        assert_eq!(
+            write_clause("CALL apoc.periodic.iterate('MATCH (n) RETURN n', 'DELETE n', {})"),
+            Some("CALL apoc.periodic.iterate".into())
+        );
+    }
+
+    #[test]
+    fn literals_and_comments_are_ignored() {
+        assert_eq!(
+            write_clause("MATCH (n) WHERE n.name = 'CREATE TABLE' RETURN n"),
+            None
+        );
+        assert_eq!(
+            write_clause("MATCH (n:`DELETE`) RETURN n // SET later"),
+            None
+        );
+        assert_eq!(write_clause("MATCH (n) /* MERGE */ RETURN n"), None);
+        assert_eq!(write_clause(r#"RETURN "it\"s SET" AS s"#), None);
+    }
+
+    #[test]
+    fn relation_identity_parses_windows_and_buckets_timestamps() {
+        assert_eq!("singleton".parse(), Ok(RelationIdentity::Singleton));
+        assert_eq!(
+            "6h".parse(),
+            Ok(RelationIdentity::Bucketed { seconds: 21600 })
+        );
+        assert_eq!(
+            " 1D ".parse(),
+            Ok(RelationIdentity::Bucketed { seconds: 86400 })
+        );
+        assert!("0d".parse::<RelationIdentity>().is_err());
+        assert!("weekly".parse::<RelationIdentity>().is_err());
+        assert_eq!(
+            String::from(RelationIdentity::Bucketed { seconds: 86400 }),
+            "1d"
+        );
+
+        let at = DateTime::parse_from_rfc3339("2026-03-04T17:45:12Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let daily = RelationIdentity::Bucketed { seconds: 86400 };
+        assert_eq!(
+            daily.bucket(at).unwrap().to_rfc3339(),
+            "2026-03-04T00:00:00+00:00"
+        );
+        assert_eq!(
+            daily.bucket(at),
+            daily.bucket(at - chrono::Duration::hours(17))
+        );
+        assert_ne!(
+            daily.bucket(at),
+            daily.bucket(at + chrono::Duration::hours(7))
+        );
+        assert_eq!(RelationIdentity::Singleton.bucket(at), None);
+    }
 }
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::EntityType;
+
+/// What changed on an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    NameChanged,
+    AliasesChanged,
+    PropertyChanged,
+    ConfidenceChanged,
+    SourceAdded,
+    /// Another node was merged into this one; `new_value` is its id.
+    Merged,
+    /// Hidden from reads and tombstoned against re-ingestion.
+    Deleted,
+    /// A soft delete was undone.
+    Restored,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::NameChanged => "name_changed",
+            ChangeKind::AliasesChanged => "aliases_changed",
+            ChangeKind::PropertyChanged => "property_changed",
+            ChangeKind::ConfidenceChanged => "confidence_changed",
+            ChangeKind::SourceAdded => "source_added",
+            ChangeKind::Merged => "merged",
+            ChangeKind::Deleted => "deleted",
+            ChangeKind::Restored => "restored",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        Some(match s {
+            "created" => ChangeKind::Created,
+            "name_changed" => ChangeKind::NameChanged,
+            "aliases_changed" => ChangeKind::AliasesChanged,
+            "property_changed" => ChangeKind::PropertyChanged,
+            "confidence_changed" => ChangeKind::ConfidenceChanged,
+            "source_added" => ChangeKind::SourceAdded,
+            "merged" => ChangeKind::Merged,
+            "deleted" => ChangeKind::Deleted,
+            "restored" => ChangeKind::Restored,
+            _ => return None,
+        })
+    }
+}
+
+/// One entry in an entity's append-only change log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct EntityChange {
+    pub id: Uuid,
+    pub entity_id: Uuid,
+    pub kind: ChangeKind,
+    /// Property key for `property_changed`, otherwise the changed field.
+    pub field: Option<String>,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+    /// Agent source whose extraction caused the change.
+    pub source: String,
+    /// Source id of the document the extraction came from.
+    pub document_id: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Left behind by a deleted entity. Agents reporting the same id, or the
+/// same source record, are not stored again while it exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Tombstone {
+    pub entity_id: Uuid,
+    pub name: String,
+    pub entity_type: EntityType,
+    pub source: String,
+    pub source_id: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    /// The node is gone for good, rather than hidden.
+    pub purged: bool,
+}
@@ -0,0 +1,388 @@
+//! Relationships derived from others by rule rather than extracted: indirect
+//! ownership and membership through chains, and organizations linked by a
+//! shared registered address or a shared director.
+//!
+//! Each pass derives the full set again from asserted relationships only,
+//! so an inferred edge whose premises are gone is retracted and inferred
+//! edges never feed further inference. Inferred edges are stored with
+//! source [`INFERENCE_SOURCE`], `inferred: true` and the rule and premises
+//! that produced them.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::RelationType;
+
+/// Source of every inferred relationship.
+pub const INFERENCE_SOURCE: &str = "inference";
+
+/// Longest chain of asserted edges an ownership or membership is inferred
+/// through.
+pub const MAX_CHAIN_LENGTH: usize = 4;
+
+/// Organizations sharing one address beyond which it is taken for a
+/// registered agent's or formation agent's and no links are inferred.
+pub const MAX_ORGANIZATIONS_PER_ADDRESS: usize = 10;
+
+/// Directorships beyond which a person is taken for a nominee director and
+/// no links are inferred through them.
+pub const MAX_DIRECTORSHIPS: usize = 20;
+
+/// Properties holding an organization's registered address, by preference.
+pub const ADDRESS_PROPERTIES: &[&str] = &["registered_address", "address"];
+
+/// Normalized addresses shorter than this are too vague to link on.
+const MIN_ADDRESS_CHARS: usize = 10;
+
+/// Namespace for inferred relationship ids, so a rule re-deriving the same
+/// link keeps its id.
+const INFERENCE_NAMESPACE: Uuid = Uuid::from_u128(0x5d0e_8a31_c7b4_4f62_91e8_3b27_a6f4_d019);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InferenceRule {
+    /// A owns B owns C, so A owns C.
+    OwnershipChain,
+    /// A is part of B is part of C, so A is part of C.
+    PartOfChain,
+    /// Organizations registered at the same address are related.
+    SameAddress,
+    /// Organizations with a director in common are related.
+    DirectorOverlap,
+}
+
+impl InferenceRule {
+    pub const ALL: [InferenceRule; 4] = [
+        InferenceRule::OwnershipChain,
+        InferenceRule::PartOfChain,
+        InferenceRule::SameAddress,
+        InferenceRule::DirectorOverlap,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InferenceRule::OwnershipChain => "ownership_chain",
+            InferenceRule::PartOfChain => "part_of_chain",
+            InferenceRule::SameAddress => "same_address",
+            InferenceRule::DirectorOverlap => "director_overlap",
+        }
+    }
+
+    /// The type of the relationships the rule infers.
+    pub fn relation_type(self) -> RelationType {
+        match self {
+            InferenceRule::OwnershipChain => RelationType::OwnerOf,
+            InferenceRule::PartOfChain => RelationType::PartOf,
+            InferenceRule::SameAddress | InferenceRule::DirectorOverlap => RelationType::RelatedTo,
+        }
+    }
+}
+
+/// An asserted relationship used as a premise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PremiseEdge {
+    pub id: Uuid,
+    pub from: Uuid,
+    pub to: Uuid,
+    pub confidence: f64,
+}
+
+/// One derived relationship with what it was derived from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inference {
+    pub rule: InferenceRule,
+    pub source: Uuid,
+    pub target: Uuid,
+    /// Ids of the asserted relationships the link rests on.
+    pub premises: Vec<Uuid>,
+    pub confidence: f64,
+    pub explanation: String,
+}
+
+impl Inference {
+    /// Stable id for the link, the same on every pass that derives it.
+    pub fn id(&self) -> Uuid {
+        let key = format!("{}:{}:{}", self.rule.as_str(), self.source, self.target);
+        Uuid::new_v5(&INFERENCE_NAMESPACE, key.as_bytes())
+    }
+
+    /// Stored as the relationship's `properties`.
+    pub fn properties(&self) -> serde_json::Value {
+        serde_json::json!({
+            "inferred": true,
+            "rule": self.rule.as_str(),
+            "premises": self.premises,
+            "explanation": self.explanation,
+        })
+    }
+}
+
+/// What one pass of a rule changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InferenceReport {
+    pub rule: InferenceRule,
+    /// Links the rule holds now, new or kept.
+    pub inferred: u64,
+    /// Links from earlier passes whose premises no longer hold.
+    pub retracted: u64,
+}
+
+/// Links implied by chains of two to [`MAX_CHAIN_LENGTH`] edges, along the
+/// shortest chain. Pairs already linked directly, and cycles back to the
+/// start, are left out. Confidence is the product along the chain.
+pub fn transitive_closure(rule: InferenceRule, edges: &[PremiseEdge]) -> Vec<Inference> {
+    let mut outgoing: HashMap<Uuid, Vec<&PremiseEdge>> = HashMap::new();
+    let mut direct = HashSet::new();
+    for edge in edges.iter().filter(|e| e.from != e.to) {
+        outgoing.entry(edge.from).or_default().push(edge);
+        direct.insert((edge.from, edge.to));
+    }
+    let label = rule.relation_type().as_str().to_uppercase();
+
+    let mut starts: Vec<Uuid> = outgoing.keys().copied().collect();
+    starts.sort();
+    let mut inferences = Vec::new();
+    for start in starts {
+        // Breadth first, so the first chain reaching a node is a shortest one
+        let mut reached: HashMap<Uuid, (Vec<Uuid>, f64)> =
+            HashMap::from([(start, (Vec::new(), 1.0))]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let (chain, confidence) = reached[&node].clone();
+            if chain.len() == MAX_CHAIN_LENGTH {
+                continue;
+            }
+            for edge in outgoing.get(&node).into_iter().flatten() {
+                if reached.contains_key(&edge.to) {
+                    continue;
+                }
+                let mut longer = chain.clone();
+                longer.push(edge.id);
+                reached.insert(
+                    edge.to,
+                    (longer, confidence * edge.confidence.clamp(0.0, 1.0)),
+                );
+                queue.push_back(edge.to);
+            }
+        }
+
+        let mut targets: Vec<(Uuid, (Vec<Uuid>, f64))> = reached
+            .into_iter()
+            .filter(|(target, (chain, _))| chain.len() >= 2 && !direct.contains(&(start, *target)))
+            .collect();
+        targets.sort_by_key(|(target, _)| *target);
+        for (target, (chain, confidence)) in targets {
+            inferences.push(Inference {
+                rule,
+                source: start,
+                target,
+                explanation: format!("Chain of {} {label} relationships", chain.len()),
+                premises: chain,
+                confidence: round(confidence),
+            });
+        }
+    }
+    inferences
+}
+
+/// Links between organizations whose normalized addresses match, for
+/// addresses shared by at most [`MAX_ORGANIZATIONS_PER_ADDRESS`].
+pub fn same_address(addresses: &[(Uuid, String)]) -> Vec<Inference> {
+    let mut by_address: BTreeMap<String, Vec<Uuid>> = BTreeMap::new();
+    for (id, address) in addresses {
+        let normalized = normalize_address(address);
+        if normalized.len() >= MIN_ADDRESS_CHARS {
+            by_address.entry(normalized).or_default().push(*id);
+        }
+    }
+
+    let mut inferences = Vec::new();
+    for (address, mut ids) in by_address {
+        ids.sort();
+        ids.dedup();
+        if ids.len() > MAX_ORGANIZATIONS_PER_ADDRESS {
+            continue;
+        }
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                inferences.push(Inference {
+                    rule: InferenceRule::SameAddress,
+                    source: *a,
+                    target: *b,
+                    premises: Vec::new(),
+                    confidence: 0.6,
+                    explanation: format!("Registered at the same address: {address}"),
+                });
+            }
+        }
+    }
+    inferences
+}
+
+/// Links between organizations sharing directors, from `DIRECTOR_OF`
+/// edges. People with more than [`MAX_DIRECTORSHIPS`] are left out.
+/// Confidence starts at 0.5 and rises with each further shared director.
+pub fn director_overlap(directorships: &[PremiseEdge]) -> Vec<Inference> {
+    let mut by_director: HashMap<Uuid, Vec<&PremiseEdge>> = HashMap::new();
+    for edge in directorships {
+        by_director.entry(edge.from).or_default().push(edge);
+    }
+
+    // Organization pair -> directorship edges on both sides
+    let mut shared: BTreeMap<(Uuid, Uuid), Vec<Uuid>> = BTreeMap::new();
+    for edges in by_director.values() {
+        let mut edges = edges.clone();
+        edges.sort_by_key(|e| e.to);
+        edges.dedup_by_key(|e| e.to);
+        if edges.len() > MAX_DIRECTORSHIPS {
+            continue;
+        }
+        for (i, a) in edges.iter().enumerate() {
+            for b in &edges[i + 1..] {
+                shared.entry((a.to, b.to)).or_default().extend([a.id, b.id]);
+            }
+        }
+    }
+
+    shared
+        .into_iter()
+        .map(|((a, b), mut premises)| {
+            premises.sort();
+            let directors = premises.len() / 2;
+            Inference {
+                rule: InferenceRule::DirectorOverlap,
+                source: a,
+                target: b,
+                premises,
+                confidence: round((0.5 + 0.1 * (directors - 1) as f64).min(0.9)),
+                explanation: format!("{directors} director(s) in common"),
+            }
+        })
+        .collect()
+}
+
+/// The address of an organization from its properties, if it has one.
+pub fn address_of(properties: &serde_json::Value) -> Option<&str> {
+    ADDRESS_PROPERTIES
+        .iter()
+        .find_map(|key| properties.get(*key).and_then(|v| v.as_str()))
+        .filter(|address| !address.trim().is_empty())
+}
+
+/// Lowercase alphanumeric words separated by single spaces, so punctuation
+/// and spacing differences between sources don't matter.
+fn normalize_address(address: &str) -> String {
+    address
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn round(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: Uuid, to: Uuid, confidence: f64) -> PremiseEdge {
+        PremiseEdge {
+            id: Uuid::new_v4(),
+            from,
+            to,
+            confidence,
+        }
+    }
+
+    #[test]
+    fn ownership_follows_chains_but_not_direct_links_or_cycles() {
+        let [a, b, c, d] = [
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        ];
+        let edges = [
+            edge(a, b, 0.9),
+            edge(b, c, 0.5),
+            edge(c, d, 1.0),
+            edge(a, d, 1.0),
+            edge(c, a, 1.0),
+        ];
+
+        let inferred = transitive_closure(InferenceRule::OwnershipChain, &edges);
+        let find = |from, to| inferred.iter().find(|i| i.source == from && i.target == to);
+
+        let a_c = find(a, c).expect("a owns c through b");
+        assert_eq!(a_c.premises, vec![edges[0].id, edges[1].id]);
+        assert_eq!(a_c.confidence, 0.45);
+        assert!(find(a, d).is_none(), "already owned directly");
+        assert!(
+            find(a, a).is_none() && find(b, b).is_none(),
+            "cycles infer no self-ownership"
+        );
+        assert!(find(b, d).is_some());
+    }
+
+    #[test]
+    fn chains_stop_at_the_length_limit() {
+        let nodes: Vec<Uuid> = (0..=MAX_CHAIN_LENGTH + 1).map(|_| Uuid::new_v4()).collect();
+        let edges: Vec<PremiseEdge> = nodes.windows(2).map(|w| edge(w[0], w[1], 1.0)).collect();
+
+        let inferred = transitive_closure(InferenceRule::PartOfChain, &edges);
+        let first = |to| {
+            inferred
+                .iter()
+                .any(|i| i.source == nodes[0] && i.target == to)
+        };
+        assert!(first(nodes[MAX_CHAIN_LENGTH]));
+        assert!(!first(nodes[MAX_CHAIN_LENGTH + 1]));
+    }
+
+    #[test]
+    fn shared_addresses_link_organizations_unless_crowded() {
+        let [a, b, c] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let mut addresses = vec![
+            (a, "12 Harbour Road, Limassol".to_string()),
+            (b, "12 HARBOUR ROAD  LIMASSOL".to_string()),
+            (c, "n/a".to_string()),
+        ];
+        assert_eq!(same_address(&addresses).len(), 1);
+
+        let agent = "1209 Orange Street, Wilmington, DE";
+        addresses.extend(
+            (0..=MAX_ORGANIZATIONS_PER_ADDRESS).map(|_| (Uuid::new_v4(), agent.to_string())),
+        );
+        assert_eq!(same_address(&addresses).len(), 1);
+    }
+
+    #[test]
+    fn shared_directors_link_organizations() {
+        let [p, q, x, y] = [
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        ];
+        let inferred = director_overlap(&[
+            edge(p, x, 1.0),
+            edge(p, y, 1.0),
+            edge(q, x, 1.0),
+            edge(q, y, 1.0),
+        ]);
+        assert_eq!(inferred.len(), 1);
+        assert_eq!(inferred[0].premises.len(), 4);
+        assert_eq!(inferred[0].confidence, 0.6);
+
+        let nominee = Uuid::new_v4();
+        let many: Vec<PremiseEdge> = (0..=MAX_DIRECTORSHIPS)
+            .map(|_| edge(nominee, Uuid::new_v4(), 1.0))
+            .collect();
+        assert!(director_overlap(&many).is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+//! Graph integrity: invariants the rest of the code takes for granted,
+//! checked on a schedule because imports, restores and manual Cypher can
+//! break them.
+//!
+//! Each [`IntegrityCheck`] counts its violations and keeps a few samples.
+//! Some have a fix that loses nothing (see [`IntegrityCheck::repairable`]);
+//! the rest are only reported.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Violations named in a finding, for a first look.
+pub const MAX_SAMPLES: usize = 10;
+
+/// One graph invariant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityCheck {
+    /// Relationships with an endpoint that has no `id` or is not an entity
+    /// (a document, position, change-log entry or tombstone). Repaired by
+    /// deleting the relationship, which no read can reach.
+    DanglingRelationships,
+    /// Entities whose `id` is not a UUID, which reads fail on. Not repaired:
+    /// documents and exports may hold the old id.
+    InvalidEntityIds,
+    /// Entities without `id`, `name`, `first_seen` or `last_seen`. Repaired
+    /// by giving them a fresh id and filling either date from the other (or
+    /// the repair time); a missing name is only reported.
+    MissingProperties,
+    /// Position reports whose asset identifier no entity carries. Not
+    /// repaired: the asset may still be extracted, and retention ages
+    /// positions out.
+    OrphanedPositions,
+    /// Archived documents listing entity ids no longer in the graph.
+    /// Repaired by pointing merged ids at the entity they were merged into
+    /// and dropping the rest.
+    OrphanedDocuments,
+}
+
+impl IntegrityCheck {
+    pub const ALL: [IntegrityCheck; 5] = [
+        IntegrityCheck::DanglingRelationships,
+        IntegrityCheck::InvalidEntityIds,
+        IntegrityCheck::MissingProperties,
+        IntegrityCheck::OrphanedPositions,
+        IntegrityCheck::OrphanedDocuments,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IntegrityCheck::DanglingRelationships => "dangling_relationships",
+            IntegrityCheck::InvalidEntityIds => "invalid_entity_ids",
+            IntegrityCheck::MissingProperties => "missing_properties",
+            IntegrityCheck::OrphanedPositions => "orphaned_positions",
+            IntegrityCheck::OrphanedDocuments => "orphaned_documents",
+        }
+    }
+
+    /// Whether the check has a fix that is safe to apply unattended.
+    pub fn repairable(self) -> bool {
+        !matches!(
+            self,
+            IntegrityCheck::InvalidEntityIds | IntegrityCheck::OrphanedPositions
+        )
+    }
+}
+
+/// What one check found, and with repair enabled, fixed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityFinding {
+    pub check: IntegrityCheck,
+    /// Violations found before any repair.
+    pub violations: u64,
+    /// Ids (or other handles) of up to [`MAX_SAMPLES`] violations.
+    pub samples: Vec<String>,
+    /// Violations fixed by this pass.
+    pub repaired: u64,
+    /// Why the check did not complete.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl IntegrityFinding {
+    pub fn new(check: IntegrityCheck) -> Self {
+        Self {
+            check,
+            violations: 0,
+            samples: Vec::new(),
+            repaired: 0,
+            error: None,
+        }
+    }
+}
+
+/// Position reports per asset that no entity identifier matches, as the
+/// number of such reports and the assets, sorted.
+pub fn orphaned_assets(
+    reports_per_asset: &[(String, u64)],
+    identifiers: &HashSet<String>,
+) -> (u64, Vec<String>) {
+    let mut reports = 0;
+    let mut assets = Vec::new();
+    for (asset, count) in reports_per_asset {
+        if !identifiers.contains(asset) {
+            reports += count;
+            assets.push(asset.clone());
+        }
+    }
+    assets.sort();
+    (reports, assets)
+}
+
+/// A document's entity ids with `missing` ones replaced by the entity they
+/// were merged into, if any, or dropped. Order is kept and duplicates the
+/// replacement creates are removed.
+pub fn repoint_entity_ids(
+    entity_ids: &[String],
+    missing: &HashSet<String>,
+    merged_into: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut seen = HashSet::new();
+    entity_ids
+        .iter()
+        .filter_map(|id| match missing.contains(id) {
+            true => merged_into.get(id).filter(|kept| !missing.contains(*kept)),
+            false => Some(id),
+        })
+        .filter(|id| seen.insert(id.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_references_follow_merges() {
+        let ids = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let missing: HashSet<String> = ids(&["merged", "purged", "merged-into-purged"])
+            .into_iter()
+            .collect();
+        let merged_into = HashMap::from([
+            ("merged".to_string(), "kept".to_string()),
+            ("merged-into-purged".to_string(), "purged".to_string()),
+        ]);
+
+        let repointed = repoint_entity_ids(
+            &ids(&["a", "merged", "purged", "kept", "merged-into-purged"]),
+            &missing,
+            &merged_into,
+        );
+        assert_eq!(repointed, ids(&["a", "kept"]));
+    }
+
+    #[test]
+    fn positions_without_an_entity_are_orphaned() {
+        let reports = vec![
+            ("mmsi:244660000".to_string(), 12),
+            ("icao24:3c6444".to_string(), 3),
+            ("mmsi:211000000".to_string(), 5),
+        ];
+        let identifiers = HashSet::from(["mmsi:244660000".to_string()]);
+
+        let (orphaned, assets) = orphaned_assets(&reports, &identifiers);
+        assert_eq!(orphaned, 8);
+        assert_eq!(assets, vec!["icao24:3c6444", "mmsi:211000000"]);
+        assert!(!IntegrityCheck::OrphanedPositions.repairable());
+    }
+}
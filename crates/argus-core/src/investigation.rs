@@ -0,0 +1,266 @@
+//! Investigations: named sets of entities an analyst is working on, with
+//! their notes, and the case timeline merging everything that happened to
+//! those entities in time order.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::alerts::Alert;
+use crate::entity::{Entity, Relationship};
+use crate::tracks::PositionReport;
+
+/// Most entities one investigation may hold; the timeline traverses each.
+pub const MAX_INVESTIGATION_ENTITIES: usize = 100;
+
+/// A case: the entities under investigation and what analysts wrote about
+/// them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Investigation {
+    pub id: Uuid,
+    pub name: String,
+    pub entity_ids: Vec<Uuid>,
+    /// Oldest first.
+    #[serde(default)]
+    pub notes: Vec<InvestigationNote>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An analyst's annotation on a case, optionally about one of its entities.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct InvestigationNote {
+    pub id: Uuid,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// When what the note describes happened; when it was written unless
+    /// the analyst said otherwise.
+    pub at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a timeline entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum CaseEventKind {
+    Relationship,
+    Position,
+    Alert,
+    Note,
+}
+
+impl fmt::Display for CaseEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CaseEventKind::Relationship => "relationship",
+            CaseEventKind::Position => "position",
+            CaseEventKind::Alert => "alert",
+            CaseEventKind::Note => "note",
+        })
+    }
+}
+
+/// One dated entry on a case timeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct CaseEvent {
+    pub at: DateTime<Utc>,
+    pub kind: CaseEventKind,
+    /// The case's entities the entry concerns.
+    pub entity_ids: Vec<Uuid>,
+    pub summary: String,
+    /// Data source, or the note's author.
+    pub source: String,
+    /// The relationship, alert or note id; the asset for positions.
+    pub reference: String,
+}
+
+impl CaseEvent {
+    /// A relationship touching the case, placed at its timestamp. `None`
+    /// for relationships without one.
+    pub fn relationship(
+        rel: &Relationship,
+        names: &HashMap<Uuid, String>,
+        case: &HashSet<Uuid>,
+    ) -> Option<Self> {
+        let name = |id: &Uuid| names.get(id).cloned().unwrap_or_else(|| id.to_string());
+        Some(Self {
+            at: rel.timestamp?,
+            kind: CaseEventKind::Relationship,
+            entity_ids: [rel.source_entity_id, rel.target_entity_id]
+                .into_iter()
+                .filter(|id| case.contains(id))
+                .collect(),
+            summary: format!(
+                "{} {} {}",
+                name(&rel.source_entity_id),
+                rel.relation_type,
+                name(&rel.target_entity_id)
+            ),
+            source: rel.source.clone(),
+            reference: rel.id.to_string(),
+        })
+    }
+
+    pub fn position(report: &PositionReport, entity: &Entity) -> Self {
+        let mut summary = format!(
+            "{} at {:.4}, {:.4}",
+            entity.name, report.latitude, report.longitude
+        );
+        if let Some(speed) = report.speed_mps {
+            summary.push_str(&format!(", {speed:.1} m/s"));
+        }
+        if let Some(destination) = &report.destination {
+            summary.push_str(&format!(", bound for {destination}"));
+        }
+        Self {
+            at: report.observed_at,
+            kind: CaseEventKind::Position,
+            entity_ids: vec![entity.id],
+            summary,
+            source: report.source.clone(),
+            reference: report.asset.clone(),
+        }
+    }
+
+    /// An alert naming any of the case's entities.
+    pub fn alert(alert: &Alert, case: &HashSet<Uuid>) -> Option<Self> {
+        let entity_ids: Vec<Uuid> = alert
+            .entities
+            .iter()
+            .map(|e| e.id)
+            .filter(|id| case.contains(id))
+            .collect();
+        (!entity_ids.is_empty()).then(|| Self {
+            at: alert.created_at,
+            kind: CaseEventKind::Alert,
+            entity_ids,
+            summary: format!("{}: {}", alert.rule_name, alert.message),
+            source: alert.source.clone(),
+            reference: alert.id.to_string(),
+        })
+    }
+
+    pub fn note(note: &InvestigationNote) -> Self {
+        Self {
+            at: note.at,
+            kind: CaseEventKind::Note,
+            entity_ids: note.entity_id.into_iter().collect(),
+            summary: note.text.clone(),
+            source: note.author.clone().unwrap_or_else(|| "analyst".to_string()),
+            reference: note.id.to_string(),
+        }
+    }
+}
+
+/// Oldest first; entries at the same instant in [`CaseEventKind`] order.
+pub fn sort_timeline(events: &mut [CaseEvent]) {
+    events.sort_by(|a, b| {
+        a.at.cmp(&b.at)
+            .then(a.kind.cmp(&b.kind))
+            .then_with(|| a.reference.cmp(&b.reference))
+    });
+}
+
+/// The timeline as CSV with a header row.
+pub fn timeline_csv(events: &[CaseEvent]) -> String {
+    let mut csv = String::from("at,kind,entity_ids,summary,source,reference\n");
+    for event in events {
+        let entity_ids: Vec<String> = event.entity_ids.iter().map(Uuid::to_string).collect();
+        let fields = [
+            event.at.to_rfc3339(),
+            event.kind.to_string(),
+            entity_ids.join(";"),
+            event.summary.clone(),
+            event.source.clone(),
+            event.reference.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityType, RelationType};
+
+    #[test]
+    fn timeline_merges_sources_in_time_order() {
+        let acme = Entity::new(
+            EntityType::Organization,
+            "Acme Ltd".to_string(),
+            "opencorporates".to_string(),
+        );
+        let holdco = Uuid::new_v4();
+        let case = HashSet::from([acme.id]);
+        let names = HashMap::from([
+            (acme.id, acme.name.clone()),
+            (holdco, "Holdco, SA".to_string()),
+        ]);
+        let at = |hour: u32| {
+            DateTime::parse_from_rfc3339(&format!("2026-03-01T{hour:02}:00:00Z"))
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        let owns = Relationship {
+            id: Uuid::new_v4(),
+            source_entity_id: acme.id,
+            target_entity_id: holdco,
+            relation_type: RelationType::OwnerOf,
+            properties: serde_json::json!({}),
+            confidence: 0.9,
+            source: "opencorporates".to_string(),
+            timestamp: Some(at(9)),
+            strength: None,
+        };
+        let undated = Relationship {
+            timestamp: None,
+            ..owns.clone()
+        };
+        let note = InvestigationNote {
+            id: Uuid::new_v4(),
+            text: "Director also \"resigned\" from Holdco".to_string(),
+            entity_id: Some(acme.id),
+            author: Some("analyst-7".to_string()),
+            at: at(8),
+            created_at: at(12),
+        };
+
+        assert!(CaseEvent::relationship(&undated, &names, &case).is_none());
+        let mut events = vec![
+            CaseEvent::relationship(&owns, &names, &case).unwrap(),
+            CaseEvent::note(&note),
+        ];
+        sort_timeline(&mut events);
+
+        assert_eq!(events[0].kind, CaseEventKind::Note);
+        assert_eq!(events[1].summary, "Acme Ltd owner_of Holdco, SA");
+        assert_eq!(events[1].entity_ids, vec![acme.id]);
+
+        let csv = timeline_csv(&events);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains(",\"Director also \"\"resigned\"\" from Holdco\",analyst-7,"));
+        assert!(lines[2].contains(",\"Acme Ltd owner_of Holdco, SA\",opencorporates,"));
+    }
+}
@@ -1,16 +1,78 @@
 pub mod agent;
+pub mod alerts;
 pub mod api_types;
+pub mod backup;
+pub mod briefing;
+pub mod calibration;
+pub mod cold_storage;
 pub mod config;
+pub mod density;
+pub mod deny_list;
+pub mod document;
+pub mod duplicates;
 pub mod entity;
 pub mod error;
+pub mod export;
 pub mod extraction;
 pub mod graph;
+pub mod history;
+pub mod inference;
+pub mod integrity;
+pub mod investigation;
+pub mod llm;
+pub mod natural_search;
+pub mod ontology;
+pub mod places;
+pub mod presentation;
+pub mod prompts;
+pub mod quota;
 pub mod reasoning;
+pub mod retention;
+pub mod secrets;
+pub mod shared;
+pub mod subscriptions;
+pub mod tracks;
+pub mod usage;
+pub mod vector;
 
-pub use agent::{Agent, AgentLookup, AgentStatus, RawDocument};
+pub use agent::{Agent, AgentCapabilities, AgentLookup, AgentStatus, RawDocument, SchemaDrift};
+pub use alerts::{
+    Alert, AlertEntity, AlertRule, AlertSeverity, AlertStatus, ChannelTarget, Delivery,
+    DeliveryStatus, NotificationChannel,
+};
+pub use calibration::{FeedbackLabel, FeedbackTarget};
+pub use cold_storage::{ColdPartition, ColdTable, SqlColumn};
 pub use config::{AppConfig, SourceConfig};
-pub use entity::{Entity, EntityType, ExtractionResult, RelationType, Relationship};
+pub use deny_list::{DenyEntry, DenyMatch};
+pub use document::{
+    ArchivedDocument, DocumentSearchHit, DocumentSearchQuery, RunDocumentSample,
+    RunExtractionStats, DOCUMENT_LABEL,
+};
+pub use duplicates::{DuplicateCandidate, DuplicateMember, DuplicateReason};
+pub use entity::{Direction, Entity, EntityType, ExtractionResult, RelationType, Relationship};
 pub use error::{ArgusError, Result};
-pub use extraction::ExtractionPipeline;
-pub use graph::{GraphNeighbors, GraphQuery, GraphStore};
+pub use export::{ExportFormat, Subgraph};
+pub use extraction::{
+    BatchExtraction, CacheStats, ExtractionCache, ExtractionFailure, ExtractionPipeline,
+    FailedExtraction, GeocodedPlace, Geocoder, PipelineKind, ValidationReport,
+};
+pub use graph::{
+    EntityBatchItem, EntitySearchFilter, EntitySort, GraphNeighbors, GraphPath, GraphQuery,
+    GraphStore, GraphWriteStats, NeighborFilter, RelationIdentity, RelationshipFilter,
+    RelationshipSearchHit, RelationshipSummary, RowStream,
+};
+pub use history::{ChangeKind, EntityChange, Tombstone};
+pub use investigation::{CaseEvent, CaseEventKind, Investigation, InvestigationNote};
+pub use ontology::Ontology;
 pub use reasoning::{ReasoningEngine, ReasoningQuery, ReasoningResponse};
+pub use retention::{RetentionAction, RetentionPolicy};
+pub use shared::{
+    AgentPauseStore, AlertRuleStore, AlertStore, ClaimedJob, DedupStore, DenyListStore,
+    DuplicateReviewQueue, ExtractionFailureStore, ExtractionJob, ExtractionQueue, FailedDocument,
+    FeedbackStore, IdempotencyStore, InvestigationStore, LockManager, NotificationStore,
+    PromptBundleStore, QuotaStore, RateLimiter, RetryQueue, RunStore, SpilloverQueue,
+    SubscriptionStore, UsageStore,
+};
+pub use subscriptions::{Notification, Subscription, SubscriptionEvent};
+pub use tracks::PositionReport;
+pub use vector::{EmbeddingProvider, VectorPoint, VectorStore};
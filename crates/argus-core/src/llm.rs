@@ -0,0 +1,297 @@
+//! Failures of the Anthropic Messages API, classified so the extraction and
+//! reasoning clients can tell a busy API, which is worth waiting for, from
+//! a request that will never succeed as sent; and the optional log of every
+//! call those clients make.
+
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{ArgusError, Result};
+
+/// Wait before the first retry; each later one doubles it.
+pub const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+/// Longest wait between attempts, whatever `retry-after` asks for.
+pub const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Why an LLM call failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmErrorKind {
+    /// 529 `overloaded_error`: the API is at capacity.
+    Overloaded,
+    /// 429 `rate_limit_error`: this key's limits are used up.
+    RateLimited,
+    /// The prompt does not fit the model's context window.
+    ContextLength,
+    /// Any other 5xx.
+    Server,
+    /// The request never got an answer: connection failure or timeout.
+    Transport,
+    /// A request the API refused and will keep refusing, e.g. a bad key.
+    Rejected,
+}
+
+impl LlmErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Overloaded => "overloaded",
+            Self::RateLimited => "rate_limited",
+            Self::ContextLength => "context_length",
+            Self::Server => "server",
+            Self::Transport => "transport",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    /// Whether the same request may succeed if sent again later.
+    pub fn is_transient(self) -> bool {
+        matches!(
+            self,
+            Self::Overloaded | Self::RateLimited | Self::Server | Self::Transport
+        )
+    }
+}
+
+impl fmt::Display for LlmErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A failed LLM call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmError {
+    pub kind: LlmErrorKind,
+    /// HTTP status, when the API answered.
+    pub status: Option<u16>,
+    /// The API's error body, or the transport error.
+    pub message: String,
+    /// How long the API asked callers to wait, from `retry-after`.
+    pub retry_after: Option<Duration>,
+}
+
+impl LlmError {
+    /// Classify an error response from its status and body.
+    pub fn from_status(status: u16, body: &str, retry_after: Option<Duration>) -> Self {
+        Self {
+            kind: classify(status, body),
+            status: Some(status),
+            message: body.to_string(),
+            retry_after,
+        }
+    }
+
+    /// Read an unsuccessful response.
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "<failed to read body>".to_string());
+        Self::from_status(status, &body, retry_after)
+    }
+
+    pub fn transport(error: impl fmt::Display) -> Self {
+        Self {
+            kind: LlmErrorKind::Transport,
+            status: None,
+            message: error.to_string(),
+            retry_after: None,
+        }
+    }
+}
+
+impl fmt::Display for LlmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(
+                f,
+                "Anthropic API returned status {status} ({}): {}",
+                self.kind, self.message
+            ),
+            None => write!(f, "HTTP request to Anthropic failed: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+/// What an LLM call was made for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmPurpose {
+    Extraction,
+    Reasoning,
+}
+
+impl LlmPurpose {
+    pub const ALL: [LlmPurpose; 2] = [LlmPurpose::Extraction, LlmPurpose::Reasoning];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Extraction => "extraction",
+            Self::Reasoning => "reasoning",
+        }
+    }
+
+    /// Source logged calls are archived under, e.g. `llm-extraction`.
+    pub fn archive_source(self) -> String {
+        format!("llm-{}", self.as_str())
+    }
+}
+
+/// One LLM call as sent and as answered, for the call log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmCall {
+    pub id: Uuid,
+    pub purpose: LlmPurpose,
+    pub model: String,
+    /// What the call was about: `source/source_id` of the document being
+    /// extracted, or the start of the question being reasoned about.
+    pub subject: String,
+    pub system: String,
+    /// The messages sent, one `role: content` block each.
+    pub prompt: String,
+    /// The model's text, when the call succeeded.
+    pub response: Option<String>,
+    /// Why the call failed, after any retries.
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+}
+
+/// Receives every LLM call the extraction and reasoning clients make while
+/// `LLM_LOG_ENABLED` is set. Recording must not hold up the caller.
+pub trait LlmCallLog: Send + Sync {
+    fn record(&self, call: LlmCall);
+}
+
+/// The kind of an error response. The API names its error `type` in the
+/// body; the status decides when the body is not the API's own (a proxy's
+/// error page, say).
+pub fn classify(status: u16, body: &str) -> LlmErrorKind {
+    let error_type = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/error/type")
+                .and_then(|t| t.as_str())
+                .map(str::to_string)
+        });
+    let lower = body.to_lowercase();
+    let too_long = lower.contains("prompt is too long")
+        || lower.contains("context length")
+        || lower.contains("context window")
+        || lower.contains("too many tokens");
+
+    match (status, error_type.as_deref()) {
+        (529, _) | (_, Some("overloaded_error")) => LlmErrorKind::Overloaded,
+        (429, _) | (_, Some("rate_limit_error")) => LlmErrorKind::RateLimited,
+        (413, _) | (_, Some("request_too_large")) => LlmErrorKind::ContextLength,
+        (400, _) if too_long => LlmErrorKind::ContextLength,
+        (500..=599, _) | (_, Some("api_error")) => LlmErrorKind::Server,
+        _ => LlmErrorKind::Rejected,
+    }
+}
+
+/// Wait before retry number `attempt` (from 0): what the API asked for,
+/// else exponential backoff, at most [`RETRY_MAX_DELAY`].
+pub fn retry_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after
+        .unwrap_or_else(|| RETRY_BASE_DELAY.saturating_mul(1 << attempt.min(16)))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// Run `call`, retrying transient LLM failures up to `max_retries` times.
+pub async fn with_retries<T, F, Fut>(max_retries: u32, mut call: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match call().await {
+            Err(ArgusError::Llm(e)) if e.kind.is_transient() && attempt < max_retries => {
+                let delay = retry_delay(attempt, e.retry_after);
+                tracing::warn!(
+                    kind = %e.kind,
+                    status = ?e.status,
+                    attempt = attempt + 1,
+                    delay_ms = delay.as_millis() as u64,
+                    "LLM call failed, retrying"
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            outcome => return outcome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_responses_are_classified() {
+        let overloaded =
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        assert_eq!(classify(529, overloaded), LlmErrorKind::Overloaded);
+        assert_eq!(classify(429, "slow down"), LlmErrorKind::RateLimited);
+        let too_long = r#"{"type":"error","error":{"type":"invalid_request_error","message":"prompt is too long: 210000 tokens > 200000 maximum"}}"#;
+        assert_eq!(classify(400, too_long), LlmErrorKind::ContextLength);
+        assert_eq!(
+            classify(502, "<html>Bad Gateway</html>"),
+            LlmErrorKind::Server
+        );
+        assert_eq!(
+            classify(401, r#"{"error":{"type":"authentication_error"}}"#),
+            LlmErrorKind::Rejected
+        );
+
+        assert!(LlmErrorKind::Overloaded.is_transient());
+        assert!(!LlmErrorKind::ContextLength.is_transient());
+    }
+
+    #[test]
+    fn retries_back_off_unless_told_how_long_to_wait() {
+        assert_eq!(retry_delay(0, None), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(2, None), RETRY_BASE_DELAY * 4);
+        assert_eq!(retry_delay(30, None), RETRY_MAX_DELAY);
+        assert_eq!(retry_delay(3, Some(Duration::ZERO)), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn only_transient_failures_are_retried() {
+        let mut calls = 0;
+        let outcome = with_retries(3, || {
+            calls += 1;
+            let kind = if calls < 3 {
+                LlmErrorKind::Overloaded
+            } else {
+                LlmErrorKind::Rejected
+            };
+            let error = LlmError {
+                kind,
+                status: Some(529),
+                message: String::new(),
+                retry_after: None,
+            };
+            async move { Err::<(), _>(ArgusError::Llm(error)) }
+        })
+        .await;
+
+        assert_eq!(calls, 3);
+        assert!(matches!(outcome, Err(ArgusError::Llm(e)) if e.kind == LlmErrorKind::Rejected));
+    }
+}
@@ -0,0 +1,271 @@
+//! Plain-language searches: a question such as "Russian-flagged tankers
+//! seen near Ceuta in January" is translated by a small LLM call into a
+//! [`SearchInterpretation`], which is then run without further reasoning.
+//!
+//! The interpretation is returned with the results so analysts can see how
+//! the question was read.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{Entity, EntityType};
+use crate::graph::EntitySearchFilter;
+use crate::tracks::distance_m;
+
+/// Radius used when a place is named without a distance.
+pub const DEFAULT_RADIUS_KM: f64 = 50.0;
+/// Largest radius honoured around a place.
+pub const MAX_RADIUS_KM: f64 = 1000.0;
+
+/// A structured search read from a question.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct SearchInterpretation {
+    /// Text the entity name must contain; empty matches any name.
+    #[serde(default)]
+    pub query: String,
+    #[serde(default)]
+    pub entity_type: Option<EntityType>,
+    /// Property values the entity must carry, e.g. `{"vessel_type": "tanker"}`,
+    /// matched case-insensitively as substrings.
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+    #[serde(default)]
+    pub min_sanctions_exposure: Option<f64>,
+    /// Only assets with a position report around this place.
+    #[serde(default)]
+    pub near: Option<NearFilter>,
+    /// Start of the time window, inclusive.
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// End of the time window, inclusive.
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// A circle around a named place.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct NearFilter {
+    /// The place as named in the question.
+    pub place: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+}
+
+impl NearFilter {
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        distance_m((self.latitude, self.longitude), (latitude, longitude))
+            <= self.radius_km * 1000.0
+    }
+}
+
+impl SearchInterpretation {
+    /// The part of the search the graph can answer by itself.
+    pub fn filter(&self) -> EntitySearchFilter {
+        EntitySearchFilter {
+            entity_type: self.entity_type.clone(),
+            min_sanctions_exposure: self.min_sanctions_exposure,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the entity passes the name, type, exposure and property
+    /// conditions. Time and place are checked separately, since for tracked
+    /// assets they apply to position reports rather than the entity.
+    pub fn matches(&self, entity: &Entity) -> bool {
+        let query = self.query.trim().to_lowercase();
+        (query.is_empty() || entity.name.to_lowercase().contains(&query))
+            && self
+                .entity_type
+                .as_ref()
+                .is_none_or(|t| *t == entity.entity_type)
+            && self
+                .min_sanctions_exposure
+                .is_none_or(|min| entity.sanctions_exposure.is_some_and(|e| e >= min))
+            && self.properties.iter().all(|(key, wanted)| {
+                entity
+                    .properties
+                    .get(key)
+                    .is_some_and(|value| value_matches(value, wanted))
+            })
+    }
+
+    /// Whether the entity was seen during the time window: its `observed_at`
+    /// if set, else the span between first and last collection.
+    pub fn seen_in_window(&self, entity: &Entity) -> bool {
+        let (start, end) = match entity.observed_at {
+            Some(at) => (at, at),
+            None => (entity.first_seen, entity.last_seen),
+        };
+        self.from.is_none_or(|from| end >= from) && self.to.is_none_or(|to| start <= to)
+    }
+}
+
+fn value_matches(value: &serde_json::Value, wanted: &str) -> bool {
+    let wanted = wanted.trim().to_lowercase();
+    match value {
+        serde_json::Value::String(s) => s.to_lowercase().contains(&wanted),
+        serde_json::Value::Array(items) => items.iter().any(|item| value_matches(item, &wanted)),
+        serde_json::Value::Null | serde_json::Value::Object(_) => false,
+        other => other.to_string().to_lowercase() == wanted,
+    }
+}
+
+/// The interpretation as the model writes it: dates may come without a
+/// time, and the radius may be missing.
+#[derive(Debug, Deserialize)]
+struct ModelInterpretation {
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    entity_type: Option<EntityType>,
+    #[serde(default)]
+    properties: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    min_sanctions_exposure: Option<f64>,
+    #[serde(default)]
+    near: Option<ModelNear>,
+    #[serde(default)]
+    from: Option<String>,
+    #[serde(default)]
+    to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelNear {
+    place: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    radius_km: Option<f64>,
+}
+
+/// Read the model's reply: the first JSON object in it, fenced or not.
+/// Out-of-range values are dropped or clamped rather than rejected.
+pub fn parse_interpretation(reply: &str) -> Result<SearchInterpretation, String> {
+    let (Some(start), Some(end)) = (reply.find('{'), reply.rfind('}')) else {
+        return Err("no JSON object in the reply".to_string());
+    };
+    if end < start {
+        return Err("no JSON object in the reply".to_string());
+    }
+    let raw: ModelInterpretation = serde_json::from_str(&reply[start..=end])
+        .map_err(|e| format!("unreadable interpretation: {e}"))?;
+
+    let properties = raw
+        .properties
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s,
+                serde_json::Value::Null => return None,
+                other => other.to_string(),
+            };
+            (!value.trim().is_empty()).then_some((key, value))
+        })
+        .collect();
+    let near = raw.near.filter(|near| {
+        (-90.0..=90.0).contains(&near.latitude) && (-180.0..=180.0).contains(&near.longitude)
+    });
+
+    Ok(SearchInterpretation {
+        query: raw.query.unwrap_or_default().trim().to_string(),
+        entity_type: raw.entity_type,
+        properties,
+        min_sanctions_exposure: raw.min_sanctions_exposure.map(|e| e.clamp(0.0, 1.0)),
+        near: near.map(|near| NearFilter {
+            place: near.place,
+            latitude: near.latitude,
+            longitude: near.longitude,
+            radius_km: near
+                .radius_km
+                .filter(|r| *r > 0.0)
+                .unwrap_or(DEFAULT_RADIUS_KM)
+                .min(MAX_RADIUS_KM),
+        }),
+        from: raw.from.as_deref().and_then(|s| parse_instant(s, false)),
+        to: raw.to.as_deref().and_then(|s| parse_instant(s, true)),
+    })
+}
+
+/// RFC 3339, or a bare date taken as the start (or, for `end_of_day`, the
+/// last second) of that UTC day.
+fn parse_instant(value: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    let day = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let time = if end_of_day {
+        day.and_hms_opt(23, 59, 59)
+    } else {
+        day.and_hms_opt(0, 0, 0)
+    };
+    time.map(|t| t.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_replies_are_read_leniently() {
+        let reply = r#"Here is the search:
+```json
+{"query": "", "entity_type": "vessel", "properties": {"vessel_type": "tanker", "flag": "Russia", "imo": null},
+ "near": {"place": "Ceuta", "latitude": 35.89, "longitude": -5.32},
+ "from": "2026-01-01", "to": "2026-01-31", "min_sanctions_exposure": 3}
+```"#;
+        let interpretation = parse_interpretation(reply).unwrap();
+
+        assert_eq!(interpretation.entity_type, Some(EntityType::Vessel));
+        assert_eq!(interpretation.properties.len(), 2);
+        assert_eq!(
+            interpretation.near.as_ref().unwrap().radius_km,
+            DEFAULT_RADIUS_KM
+        );
+        assert_eq!(
+            interpretation.from.unwrap().to_rfc3339(),
+            "2026-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            interpretation.to.unwrap().to_rfc3339(),
+            "2026-01-31T23:59:59+00:00"
+        );
+        assert_eq!(interpretation.min_sanctions_exposure, Some(1.0));
+
+        assert!(parse_interpretation("I can't help with that").is_err());
+    }
+
+    #[test]
+    fn entities_match_on_type_and_properties() {
+        let interpretation = SearchInterpretation {
+            entity_type: Some(EntityType::Vessel),
+            properties: BTreeMap::from([("vessel_type".to_string(), "tanker".to_string())]),
+            ..Default::default()
+        };
+        let mut vessel = Entity::new(
+            EntityType::Vessel,
+            "Volga Star".to_string(),
+            "ais".to_string(),
+        );
+        vessel.properties = serde_json::json!({ "vessel_type": "Crude Oil Tanker" });
+        assert!(interpretation.matches(&vessel));
+
+        vessel.properties = serde_json::json!({ "vessel_type": "Cargo" });
+        assert!(!interpretation.matches(&vessel));
+
+        let ceuta = NearFilter {
+            place: "Ceuta".to_string(),
+            latitude: 35.89,
+            longitude: -5.32,
+            radius_km: 30.0,
+        };
+        assert!(ceuta.contains(36.0, -5.4));
+        assert!(!ceuta.contains(36.5, -4.0));
+    }
+}
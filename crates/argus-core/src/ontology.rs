@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{normalize_type_name, EntityType, RelationType};
+
+/// Deployment-specific entity and relationship types layered on top of the
+/// built-in enums. Names are stored normalized (snake_case).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ontology {
+    #[serde(default)]
+    pub entity_types: Vec<String>,
+    #[serde(default)]
+    pub relation_types: Vec<String>,
+}
+
+impl Ontology {
+    pub fn new<E, R>(entity_types: E, relation_types: R) -> Self
+    where
+        E: IntoIterator,
+        E::Item: AsRef<str>,
+        R: IntoIterator,
+        R::Item: AsRef<str>,
+    {
+        Self {
+            entity_types: normalize_all(entity_types, |n| EntityType::from_name(n).is_custom()),
+            relation_types: normalize_all(relation_types, |n| {
+                RelationType::from_name(n).is_custom()
+            }),
+        }
+    }
+
+    /// Read comma-separated custom types from `ARGUS_CUSTOM_ENTITY_TYPES`
+    /// and `ARGUS_CUSTOM_RELATION_TYPES`.
+    pub fn from_env() -> Self {
+        let read = |key: &str| std::env::var(key).unwrap_or_default();
+        Self::new(
+            read("ARGUS_CUSTOM_ENTITY_TYPES").split(','),
+            read("ARGUS_CUSTOM_RELATION_TYPES").split(','),
+        )
+    }
+
+    /// The custom entity type for `name`, if it is registered.
+    pub fn custom_entity_type(&self, name: &str) -> Option<EntityType> {
+        let normalized = normalize_type_name(name);
+        self.entity_types
+            .contains(&normalized)
+            .then_some(EntityType::Custom(normalized))
+    }
+
+    /// The custom relation type for `name`, if it is registered.
+    pub fn custom_relation_type(&self, name: &str) -> Option<RelationType> {
+        let normalized = normalize_type_name(name);
+        self.relation_types
+            .contains(&normalized)
+            .then_some(RelationType::Custom(normalized))
+    }
+
+    /// Accept an entity type an API caller filters on only if it is built
+    /// in or registered. Extraction and storage take any name, but a filter
+    /// on an unregistered one is a typo that would silently match nothing.
+    pub fn check_entity_type(&self, entity_type: &EntityType) -> Result<(), String> {
+        match entity_type {
+            EntityType::Custom(name) if self.custom_entity_type(name).is_none() => Err(format!(
+                "unknown entity type `{name}`; custom types must be listed in ARGUS_CUSTOM_ENTITY_TYPES"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// As [`Ontology::check_entity_type`], for relationship types.
+    pub fn check_relation_type(&self, relation_type: &RelationType) -> Result<(), String> {
+        match relation_type {
+            RelationType::Custom(name) if self.custom_relation_type(name).is_none() => Err(format!(
+                "unknown relationship type `{name}`; custom types must be listed in ARGUS_CUSTOM_RELATION_TYPES"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Normalize and dedup names, dropping any that `is_custom` rejects because
+/// they already resolve to a built-in variant.
+fn normalize_all<I>(names: I, is_custom: impl Fn(&str) -> bool) -> Vec<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut out: Vec<String> = Vec::new();
+    for name in names {
+        let normalized = normalize_type_name(name.as_ref());
+        if !normalized.is_empty() && is_custom(&normalized) && !out.contains(&normalized) {
+            out.push(normalized);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_types_resolve_to_custom() {
+        let ontology = Ontology::new(["Bank Account"], ["FUNDED_BY", "funded by"]);
+        assert_eq!(ontology.entity_types, vec!["bank_account"]);
+        assert_eq!(ontology.relation_types, vec!["funded_by"]);
+        assert_eq!(
+            ontology.custom_entity_type("bank account"),
+            Some(EntityType::Custom("bank_account".into()))
+        );
+        assert_eq!(
+            ontology.custom_relation_type("Funded By"),
+            Some(RelationType::Custom("funded_by".into()))
+        );
+        assert_eq!(ontology.custom_entity_type("crypto_wallet"), None);
+    }
+
+    #[test]
+    fn builtin_and_empty_names_are_ignored() {
+        let ontology = Ontology::new(["person", "", " "], ["owner_of"]);
+        assert!(ontology.entity_types.is_empty());
+        assert!(ontology.relation_types.is_empty());
+    }
+
+    #[test]
+    fn api_filters_accept_builtin_and_registered_types_only() {
+        let ontology = Ontology::new(["bank_account"], ["funded_by"]);
+        assert!(ontology.check_entity_type(&EntityType::Person).is_ok());
+        assert!(ontology
+            .check_entity_type(&EntityType::from_name("bank_account"))
+            .is_ok());
+        assert!(ontology
+            .check_entity_type(&EntityType::from_name("persn"))
+            .is_err());
+        assert!(ontology.check_relation_type(&RelationType::OwnerOf).is_ok());
+        assert!(ontology
+            .check_relation_type(&RelationType::from_name("funded_by"))
+            .is_ok());
+        assert!(ontology
+            .check_relation_type(&RelationType::from_name("owns_of"))
+            .is_err());
+    }
+}
@@ -0,0 +1,613 @@
+//! Bundled reference data for ports and airports, the places position
+//! tracks are matched against.
+//!
+//! Port outlines are coarse polygons around each harbour's terminals and
+//! inner anchorages; airports are matched within [`AIRPORT_RADIUS_M`] of
+//! their reference point. Each place resolves to one Location node whose id
+//! is derived from its code, so every pass links to the same node. The
+//! `argus-refdata` loader writes these nodes, together with the full port
+//! and airport datasets it downloads.
+
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::entity::{Entity, EntityType};
+use crate::tracks::distance_m;
+
+/// Source reference Locations are attributed to.
+pub const SOURCE: &str = "refdata";
+
+/// Namespace for place ids.
+const ID_NAMESPACE: Uuid = Uuid::from_u128(0x8e1f_5a72_c4d3_4b09_a6e8_17f2_2c9d_b0a4);
+
+/// How far from its reference point an airport extends.
+pub const AIRPORT_RADIUS_M: f64 = 4_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceKind {
+    Port,
+    Airport,
+}
+
+impl PlaceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlaceKind::Port => "port",
+            PlaceKind::Airport => "airport",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Area {
+    /// `(latitude, longitude)` vertices, not repeating the first.
+    Outline(&'static [(f64, f64)]),
+    Around {
+        latitude: f64,
+        longitude: f64,
+        radius_m: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Place {
+    pub kind: PlaceKind,
+    /// UN/LOCODE for ports, ICAO location indicator for airports.
+    pub code: &'static str,
+    pub name: &'static str,
+    /// ISO 3166-1 alpha-2.
+    pub country: &'static str,
+    pub area: Area,
+}
+
+const fn port(
+    code: &'static str,
+    name: &'static str,
+    country: &'static str,
+    outline: &'static [(f64, f64)],
+) -> Place {
+    Place {
+        kind: PlaceKind::Port,
+        code,
+        name,
+        country,
+        area: Area::Outline(outline),
+    }
+}
+
+const fn airport(
+    code: &'static str,
+    name: &'static str,
+    country: &'static str,
+    latitude: f64,
+    longitude: f64,
+) -> Place {
+    Place {
+        kind: PlaceKind::Airport,
+        code,
+        name,
+        country,
+        area: Area::Around {
+            latitude,
+            longitude,
+            radius_m: AIRPORT_RADIUS_M,
+        },
+    }
+}
+
+/// Major container, bulk and energy ports.
+pub const PORTS: &[Place] = &[
+    port(
+        "NLRTM",
+        "Port of Rotterdam",
+        "NL",
+        &[(51.99, 3.95), (51.99, 4.50), (51.85, 4.50), (51.85, 3.95)],
+    ),
+    port(
+        "BEANR",
+        "Port of Antwerp-Bruges",
+        "BE",
+        &[(51.38, 4.22), (51.38, 4.45), (51.24, 4.45), (51.24, 4.22)],
+    ),
+    port(
+        "DEHAM",
+        "Port of Hamburg",
+        "DE",
+        &[(53.56, 9.80), (53.56, 10.05), (53.49, 10.05), (53.49, 9.80)],
+    ),
+    port(
+        "GBFXT",
+        "Port of Felixstowe",
+        "GB",
+        &[(51.97, 1.28), (51.97, 1.36), (51.93, 1.36), (51.93, 1.28)],
+    ),
+    port(
+        "ESALG",
+        "Port of Algeciras",
+        "ES",
+        &[
+            (36.18, -5.46),
+            (36.18, -5.38),
+            (36.10, -5.38),
+            (36.10, -5.46),
+        ],
+    ),
+    port(
+        "GRPIR",
+        "Port of Piraeus",
+        "GR",
+        &[
+            (37.96, 23.55),
+            (37.96, 23.66),
+            (37.92, 23.66),
+            (37.92, 23.55),
+        ],
+    ),
+    port(
+        "MTMAR",
+        "Port of Marsaxlokk",
+        "MT",
+        &[
+            (35.85, 14.53),
+            (35.85, 14.57),
+            (35.81, 14.57),
+            (35.81, 14.53),
+        ],
+    ),
+    port(
+        "TRAMR",
+        "Port of Ambarli",
+        "TR",
+        &[
+            (40.99, 28.66),
+            (40.99, 28.71),
+            (40.95, 28.71),
+            (40.95, 28.66),
+        ],
+    ),
+    port(
+        "RUNVS",
+        "Port of Novorossiysk",
+        "RU",
+        &[
+            (44.74, 37.76),
+            (44.74, 37.84),
+            (44.68, 37.84),
+            (44.68, 37.76),
+        ],
+    ),
+    port(
+        "EGPSD",
+        "Port Said",
+        "EG",
+        &[
+            (31.30, 32.28),
+            (31.30, 32.36),
+            (31.22, 32.36),
+            (31.22, 32.28),
+        ],
+    ),
+    port(
+        "AEJEA",
+        "Port of Jebel Ali",
+        "AE",
+        &[
+            (25.06, 55.00),
+            (25.06, 55.10),
+            (24.98, 55.10),
+            (24.98, 55.00),
+        ],
+    ),
+    port(
+        "SGSIN",
+        "Port of Singapore",
+        "SG",
+        &[
+            (1.32, 103.60),
+            (1.32, 104.05),
+            (1.20, 104.05),
+            (1.20, 103.60),
+        ],
+    ),
+    port(
+        "HKHKG",
+        "Port of Hong Kong",
+        "HK",
+        &[
+            (22.37, 114.08),
+            (22.37, 114.20),
+            (22.27, 114.20),
+            (22.27, 114.08),
+        ],
+    ),
+    port(
+        "CNSHA",
+        "Port of Shanghai (Yangshan)",
+        "CN",
+        &[
+            (30.67, 121.98),
+            (30.67, 122.12),
+            (30.58, 122.12),
+            (30.58, 121.98),
+        ],
+    ),
+    port(
+        "CNNGB",
+        "Port of Ningbo-Zhoushan",
+        "CN",
+        &[
+            (30.05, 121.80),
+            (30.05, 122.15),
+            (29.85, 122.15),
+            (29.85, 121.80),
+        ],
+    ),
+    port(
+        "KRPUS",
+        "Port of Busan",
+        "KR",
+        &[
+            (35.14, 128.76),
+            (35.14, 129.10),
+            (35.04, 129.10),
+            (35.04, 128.76),
+        ],
+    ),
+    port(
+        "JPYOK",
+        "Port of Yokohama",
+        "JP",
+        &[
+            (35.48, 139.62),
+            (35.48, 139.70),
+            (35.42, 139.70),
+            (35.42, 139.62),
+        ],
+    ),
+    port(
+        "USLAX",
+        "Port of Los Angeles",
+        "US",
+        &[
+            (33.79, -118.30),
+            (33.79, -118.16),
+            (33.70, -118.16),
+            (33.70, -118.30),
+        ],
+    ),
+    port(
+        "USNYC",
+        "Port of New York and New Jersey",
+        "US",
+        &[
+            (40.72, -74.17),
+            (40.72, -74.02),
+            (40.63, -74.02),
+            (40.63, -74.17),
+        ],
+    ),
+    port(
+        "PABLB",
+        "Port of Balboa",
+        "PA",
+        &[
+            (8.97, -79.58),
+            (8.97, -79.55),
+            (8.94, -79.55),
+            (8.94, -79.58),
+        ],
+    ),
+    port(
+        "BRSSZ",
+        "Port of Santos",
+        "BR",
+        &[
+            (-23.90, -46.38),
+            (-23.90, -46.28),
+            (-24.00, -46.28),
+            (-24.00, -46.38),
+        ],
+    ),
+    port(
+        "ZADUR",
+        "Port of Durban",
+        "ZA",
+        &[
+            (-29.85, 31.00),
+            (-29.85, 31.06),
+            (-29.89, 31.06),
+            (-29.89, 31.00),
+        ],
+    ),
+];
+
+/// Major international airports.
+pub const AIRPORTS: &[Place] = &[
+    airport("EHAM", "Amsterdam Airport Schiphol", "NL", 52.3086, 4.7639),
+    airport("EGLL", "London Heathrow Airport", "GB", 51.4700, -0.4543),
+    airport(
+        "LFPG",
+        "Paris Charles de Gaulle Airport",
+        "FR",
+        49.0097,
+        2.5479,
+    ),
+    airport("EDDF", "Frankfurt Airport", "DE", 50.0379, 8.5622),
+    airport("EDDB", "Berlin Brandenburg Airport", "DE", 52.3667, 13.5033),
+    airport("EBBR", "Brussels Airport", "BE", 50.9014, 4.4844),
+    airport("LSZH", "Zurich Airport", "CH", 47.4582, 8.5555),
+    airport("LSGG", "Geneva Airport", "CH", 46.2381, 6.1090),
+    airport("LIRF", "Rome Fiumicino Airport", "IT", 41.8003, 12.2389),
+    airport(
+        "LEMD",
+        "Adolfo Suárez Madrid–Barajas Airport",
+        "ES",
+        40.4983,
+        -3.5676,
+    ),
+    airport("LTFM", "Istanbul Airport", "TR", 41.2753, 28.7519),
+    airport(
+        "UUEE",
+        "Sheremetyevo International Airport",
+        "RU",
+        55.9726,
+        37.4146,
+    ),
+    airport(
+        "UUDD",
+        "Domodedovo International Airport",
+        "RU",
+        55.4088,
+        37.9063,
+    ),
+    airport(
+        "UKBB",
+        "Boryspil International Airport",
+        "UA",
+        50.3450,
+        30.8947,
+    ),
+    airport("LLBG", "Ben Gurion Airport", "IL", 32.0114, 34.8867),
+    airport(
+        "HECA",
+        "Cairo International Airport",
+        "EG",
+        30.1219,
+        31.4056,
+    ),
+    airport(
+        "OIIE",
+        "Imam Khomeini International Airport",
+        "IR",
+        35.4161,
+        51.1522,
+    ),
+    airport(
+        "OMDB",
+        "Dubai International Airport",
+        "AE",
+        25.2532,
+        55.3657,
+    ),
+    airport(
+        "OTHH",
+        "Hamad International Airport",
+        "QA",
+        25.2731,
+        51.6081,
+    ),
+    airport(
+        "VIDP",
+        "Indira Gandhi International Airport",
+        "IN",
+        28.5562,
+        77.1000,
+    ),
+    airport("WSSS", "Singapore Changi Airport", "SG", 1.3644, 103.9915),
+    airport(
+        "VHHH",
+        "Hong Kong International Airport",
+        "HK",
+        22.3080,
+        113.9185,
+    ),
+    airport(
+        "ZBAA",
+        "Beijing Capital International Airport",
+        "CN",
+        40.0799,
+        116.6031,
+    ),
+    airport("RJTT", "Tokyo Haneda Airport", "JP", 35.5494, 139.7798),
+    airport(
+        "YSSY",
+        "Sydney Kingsford Smith Airport",
+        "AU",
+        -33.9399,
+        151.1753,
+    ),
+    airport(
+        "FAOR",
+        "O. R. Tambo International Airport",
+        "ZA",
+        -26.1337,
+        28.2420,
+    ),
+    airport(
+        "SBGR",
+        "São Paulo/Guarulhos International Airport",
+        "BR",
+        -23.4356,
+        -46.4731,
+    ),
+    airport(
+        "KJFK",
+        "John F. Kennedy International Airport",
+        "US",
+        40.6413,
+        -73.7781,
+    ),
+    airport(
+        "KIAD",
+        "Washington Dulles International Airport",
+        "US",
+        38.9531,
+        -77.4565,
+    ),
+    airport(
+        "KLAX",
+        "Los Angeles International Airport",
+        "US",
+        33.9416,
+        -118.4085,
+    ),
+];
+
+impl Place {
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        match self.area {
+            Area::Outline(vertices) => in_polygon(vertices, latitude, longitude),
+            Area::Around {
+                latitude: lat,
+                longitude: lon,
+                radius_m,
+            } => distance_m((lat, lon), (latitude, longitude)) <= radius_m,
+        }
+    }
+
+    /// Stable id of this place's Location node.
+    pub fn entity_id(&self) -> Uuid {
+        place_id(self.kind, self.code)
+    }
+
+    /// The place's Location entity, with its outline or point as geometry.
+    pub fn to_entity(&self) -> Entity {
+        let mut entity = place_entity(self.kind, self.code, self.name);
+        entity.properties = self.properties();
+        entity.geometry = Some(self.geometry());
+        entity
+    }
+
+    pub fn geometry(&self) -> Value {
+        match self.area {
+            Area::Outline(vertices) => {
+                let mut ring: Vec<[f64; 2]> =
+                    vertices.iter().map(|&(lat, lon)| [lon, lat]).collect();
+                ring.extend(ring.first().copied());
+                json!({"type": "Polygon", "coordinates": [ring]})
+            }
+            Area::Around {
+                latitude,
+                longitude,
+                ..
+            } => json!({"type": "Point", "coordinates": [longitude, latitude]}),
+        }
+    }
+
+    fn properties(&self) -> Value {
+        let (latitude, longitude) = self.center();
+        json!({
+            "location_type": self.kind.as_str(),
+            "code": self.code,
+            "country": self.country,
+            "latitude": latitude,
+            "longitude": longitude,
+        })
+    }
+
+    /// The outline's vertex mean, or the reference point.
+    fn center(&self) -> (f64, f64) {
+        match self.area {
+            Area::Outline(vertices) => {
+                let n = vertices.len() as f64;
+                let (lat, lon) = vertices
+                    .iter()
+                    .fold((0.0, 0.0), |(a, b), (lat, lon)| (a + lat, b + lon));
+                (lat / n, lon / n)
+            }
+            Area::Around {
+                latitude,
+                longitude,
+                ..
+            } => (latitude, longitude),
+        }
+    }
+}
+
+/// Stable id of the Location node for a port's UN/LOCODE or an airport's
+/// ICAO code, shared by bundled and downloaded reference data.
+pub fn place_id(kind: PlaceKind, code: &str) -> Uuid {
+    Uuid::new_v5(
+        &ID_NAMESPACE,
+        format!("{}:{}", kind.as_str(), code.to_uppercase()).as_bytes(),
+    )
+}
+
+/// A bare reference Location for a place, keyed on its kind and code.
+pub fn place_entity(kind: PlaceKind, code: &str, name: &str) -> Entity {
+    let mut entity = Entity::new(EntityType::Location, name.to_string(), SOURCE.to_string());
+    entity.id = place_id(kind, code);
+    entity.source_id = Some(format!("{}:{}", kind.as_str(), code.to_uppercase()));
+    entity
+}
+
+/// Even-odd ray casting; fine at harbour scale, where the outline's edges
+/// are short enough to treat as straight in degrees.
+fn in_polygon(vertices: &[(f64, f64)], latitude: f64, longitude: f64) -> bool {
+    let mut inside = false;
+    let mut j = vertices.len().wrapping_sub(1);
+    for (i, &(lat_i, lon_i)) in vertices.iter().enumerate() {
+        let (lat_j, lon_j) = vertices[j];
+        if (lat_i > latitude) != (lat_j > latitude)
+            && longitude < (lon_j - lon_i) * (latitude - lat_i) / (lat_j - lat_i) + lon_i
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+pub fn by_code(code: &str) -> Option<&'static Place> {
+    let code = code.trim();
+    PORTS
+        .iter()
+        .chain(AIRPORTS)
+        .find(|p| p.code.eq_ignore_ascii_case(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_match_inside_their_area_only() {
+        let rotterdam = by_code("nlrtm").unwrap();
+        assert!(rotterdam.contains(51.95, 4.05));
+        assert!(!rotterdam.contains(52.1, 4.05));
+        assert!(!rotterdam.contains(51.95, 4.6));
+
+        let schiphol = by_code("EHAM").unwrap();
+        assert!(schiphol.contains(52.31, 4.76));
+        assert!(!schiphol.contains(52.37, 4.90));
+    }
+
+    #[test]
+    fn place_entities_are_stable_locations() {
+        let place = by_code("SGSIN").unwrap();
+        let entity = place.to_entity();
+        assert_eq!(entity.id, place.entity_id());
+        assert_eq!(entity.id, place_id(PlaceKind::Port, "sgsin"));
+        assert_eq!(entity.entity_type, EntityType::Location);
+        assert_eq!(entity.properties["location_type"], "port");
+        assert_eq!(
+            entity.geometry.as_ref().unwrap()["coordinates"][0]
+                .as_array()
+                .unwrap()
+                .len(),
+            5
+        );
+        assert!((entity.properties["latitude"].as_f64().unwrap() - 1.26).abs() < 1e-9);
+        assert_ne!(place.entity_id(), by_code("WSSS").unwrap().entity_id());
+    }
+}
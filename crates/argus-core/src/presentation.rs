@@ -0,0 +1,134 @@
+//! How entities are drawn: a category, color and icon per entity, so every
+//! frontend renders the same entity the same way.
+//!
+//! The base look comes from the entity type; a few property combinations
+//! that analysts scan for (sanctioned people and organizations, military
+//! aircraft and vessels) get a category of their own. Icon names are from
+//! the Lucide set.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::{Entity, EntityType};
+
+/// `sanctions_exposure` from which a person or organization is shown as
+/// sanctioned. A direct listing always scores 1.0.
+pub const SANCTIONED_EXPOSURE: f64 = 1.0;
+
+/// Presentation metadata for one entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Presentation {
+    /// The entity type, or a narrower category such as `sanctioned_organization`.
+    pub category: String,
+    /// CSS hex color.
+    pub color: String,
+    /// Lucide icon name, e.g. `ship`.
+    pub icon: String,
+}
+
+impl Presentation {
+    fn new(category: &str, color: &str, icon: &str) -> Self {
+        Self {
+            category: category.to_string(),
+            color: color.to_string(),
+            icon: icon.to_string(),
+        }
+    }
+}
+
+/// The presentation of one entity.
+pub fn presentation(entity: &Entity) -> Presentation {
+    let sanctioned = entity
+        .sanctions_exposure
+        .is_some_and(|exposure| exposure >= SANCTIONED_EXPOSURE);
+    match &entity.entity_type {
+        EntityType::Person if sanctioned => {
+            Presentation::new("sanctioned_person", "#ef4444", "user-x")
+        }
+        EntityType::Organization if sanctioned => {
+            Presentation::new("sanctioned_organization", "#ef4444", "building-2")
+        }
+        EntityType::Aircraft if is_military(entity) => {
+            Presentation::new("military_aircraft", "#84cc16", "shield")
+        }
+        EntityType::Vessel if is_military(entity) => {
+            Presentation::new("military_vessel", "#84cc16", "anchor")
+        }
+        EntityType::Person => Presentation::new("person", "#4a90d9", "user"),
+        EntityType::Organization => Presentation::new("organization", "#50c878", "building-2"),
+        EntityType::Vessel => Presentation::new("vessel", "#00ced1", "ship"),
+        EntityType::Aircraft => Presentation::new("aircraft", "#f0e068", "plane"),
+        EntityType::Location => Presentation::new("location", "#e74c3c", "map-pin"),
+        EntityType::Event => Presentation::new("event", "#f39c12", "calendar"),
+        EntityType::Document => Presentation::new("document", "#a78bfa", "file-text"),
+        EntityType::Transaction => Presentation::new("transaction", "#f472b6", "banknote"),
+        EntityType::Sanction => Presentation::new("sanction", "#ef4444", "gavel"),
+        EntityType::Custom(name) => Presentation::new(name, "#9ca3af", "circle"),
+    }
+}
+
+/// Presentations keyed by entity id, for the entities of one response.
+pub fn presentations<'a>(
+    entities: impl IntoIterator<Item = &'a Entity>,
+) -> BTreeMap<Uuid, Presentation> {
+    entities
+        .into_iter()
+        .map(|entity| (entity.id, presentation(entity)))
+        .collect()
+}
+
+/// Whether a source or extraction marked the entity as military, either
+/// with `"military": true` or a `category`, `operator_type` or `type` of
+/// `military`.
+fn is_military(entity: &Entity) -> bool {
+    let properties = &entity.properties;
+    properties
+        .get("military")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+        || ["category", "operator_type", "type"].iter().any(|key| {
+            properties
+                .get(*key)
+                .and_then(|v| v.as_str())
+                .is_some_and(|value| value.eq_ignore_ascii_case("military"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(entity_type: EntityType) -> Entity {
+        Entity::new(entity_type, "Test".to_string(), "test".to_string())
+    }
+
+    #[test]
+    fn entities_are_drawn_by_type() {
+        let vessel = presentation(&entity(EntityType::Vessel));
+        assert_eq!(
+            (vessel.category.as_str(), vessel.icon.as_str()),
+            ("vessel", "ship")
+        );
+
+        let custom = presentation(&entity(EntityType::Custom("satellite".into())));
+        assert_eq!(custom.category, "satellite");
+    }
+
+    #[test]
+    fn sanctioned_and_military_entities_get_their_own_category() {
+        let mut org = entity(EntityType::Organization);
+        org.sanctions_exposure = Some(0.7);
+        assert_eq!(presentation(&org).category, "organization");
+        org.sanctions_exposure = Some(1.0);
+        assert_eq!(presentation(&org).category, "sanctioned_organization");
+
+        let mut aircraft = entity(EntityType::Aircraft);
+        aircraft.properties = serde_json::json!({ "operator_type": "Military" });
+        assert_eq!(presentation(&aircraft).category, "military_aircraft");
+        aircraft.properties = serde_json::json!({ "military": false });
+        assert_eq!(presentation(&aircraft).category, "aircraft");
+    }
+}
@@ -0,0 +1,458 @@
+//! Versioned extraction prompt bundles: few-shot examples chosen from
+//! analyst feedback, per source, and an A/B deployment that splits
+//! documents between a control and a candidate bundle.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::calibration::{FeedbackLabel, FeedbackTarget};
+
+/// Version meaning "no bundle": the extraction prompt without examples.
+pub const BASELINE_PROMPT_VERSION: u32 = 0;
+
+/// Property on extracted entities and relationships naming the prompt
+/// bundle version they were extracted with, set while a deployment is
+/// active.
+pub const PROMPT_VERSION_PROPERTY: &str = "prompt_version";
+
+/// Examples per source when a build request names no number.
+pub const DEFAULT_EXAMPLES_PER_SOURCE: usize = 6;
+
+/// Most examples per source one bundle may hold.
+pub const MAX_EXAMPLES_PER_SOURCE: usize = 20;
+
+/// Example excerpts are cut to this many bytes.
+pub const MAX_EXAMPLE_TEXT_LEN: usize = 600;
+
+/// Excerpts shorter than this carry too little context to teach anything.
+const MIN_EXAMPLE_TEXT_LEN: usize = 20;
+
+/// A labelled item offered for selection, with the excerpt it came from.
+#[derive(Debug, Clone)]
+pub struct ExampleCandidate {
+    pub label: FeedbackLabel,
+    /// The evidence quote or paragraph; unset for entities.
+    pub text: Option<String>,
+    /// The item as the extraction output schema writes it.
+    pub item: serde_json::Value,
+}
+
+/// One few-shot example shown to the extraction model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FewShotExample {
+    pub label_id: Uuid,
+    pub target: FeedbackTarget,
+    /// Whether the item was right; wrong ones are shown as mistakes.
+    pub correct: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub item: serde_json::Value,
+}
+
+/// An immutable set of few-shot examples keyed by lowercased source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptBundle {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub examples: BTreeMap<String, Vec<FewShotExample>>,
+}
+
+impl PromptBundle {
+    pub fn example_count(&self) -> usize {
+        self.examples.values().map(Vec::len).sum()
+    }
+
+    /// The bundle's examples for `source` as a system prompt section, or
+    /// `None` when it has none.
+    pub fn render(&self, source: &str) -> Option<String> {
+        let examples = self
+            .examples
+            .get(&source.to_lowercase())
+            .filter(|e| !e.is_empty())?;
+        let mut out = String::from(
+            "\n\nExamples from analyst review of earlier extractions from this source. \
+             Follow the correct ones and do not repeat the mistakes:\n",
+        );
+        for example in examples {
+            out.push('\n');
+            if let Some(text) = &example.text {
+                out.push_str(&format!("Text: \"{text}\"\n"));
+            }
+            let verdict = match (example.target, example.correct) {
+                (FeedbackTarget::Relationship, true) => "Correct relationship",
+                (FeedbackTarget::Relationship, false) => "Wrongly extracted relationship",
+                (_, true) => "Correct entity",
+                (_, false) => "Not a valid entity",
+            };
+            out.push_str(&format!("{verdict}: {}\n", example.item));
+        }
+        Some(out)
+    }
+}
+
+/// Which bundles extraction uses: `control` for most documents and
+/// `candidate`, when set, for `candidate_share` of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptDeployment {
+    pub control: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<u32>,
+    /// From 0 to 1.
+    #[serde(default)]
+    pub candidate_share: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for PromptDeployment {
+    fn default() -> Self {
+        Self {
+            control: BASELINE_PROMPT_VERSION,
+            candidate: None,
+            candidate_share: 0.0,
+            updated_at: DateTime::<Utc>::UNIX_EPOCH,
+        }
+    }
+}
+
+impl PromptDeployment {
+    /// Whether extraction runs with anything but the baseline prompt.
+    pub fn is_active(&self) -> bool {
+        self.control != BASELINE_PROMPT_VERSION || self.candidate.is_some()
+    }
+
+    /// The version a document is extracted with. The split hashes the
+    /// document's source and id, so re-extracting it picks the same arm.
+    pub fn version_for(&self, source: &str, source_id: &str) -> u32 {
+        match self.candidate {
+            Some(candidate) if bucket(source, source_id) < self.candidate_share => candidate,
+            _ => self.control,
+        }
+    }
+}
+
+/// A stable position in `[0, 1)` for a document: FNV-1a, then the
+/// murmur3 finalizer, since FNV leaves the high bits of similar short ids
+/// close together.
+fn bucket(source: &str, source_id: &str) -> f64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in source.bytes().chain([0]).chain(source_id.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^= hash >> 33;
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Pick up to `per_source` examples for each source. Mistakes the model
+/// made confidently and right answers it was unsure of teach the most, so
+/// those come first; up to half the slots go to mistakes. Relationships
+/// need an excerpt; entities are only used as mistakes.
+pub fn select_examples(
+    candidates: &[ExampleCandidate],
+    per_source: usize,
+) -> BTreeMap<String, Vec<FewShotExample>> {
+    let per_source = per_source.min(MAX_EXAMPLES_PER_SOURCE);
+    let mut by_source: BTreeMap<String, (Vec<&ExampleCandidate>, Vec<&ExampleCandidate>)> =
+        BTreeMap::new();
+    for candidate in candidates {
+        let usable = match candidate.label.target {
+            FeedbackTarget::Relationship => candidate
+                .text
+                .as_deref()
+                .is_some_and(|t| t.trim().len() >= MIN_EXAMPLE_TEXT_LEN),
+            FeedbackTarget::Entity => !candidate.label.correct,
+            FeedbackTarget::Answer => false,
+        };
+        if !usable {
+            continue;
+        }
+        let (right, wrong) = by_source
+            .entry(candidate.label.source.to_lowercase())
+            .or_default();
+        if candidate.label.correct {
+            right.push(candidate);
+        } else {
+            wrong.push(candidate);
+        }
+    }
+
+    let mut selected = BTreeMap::new();
+    for (source, (mut right, mut wrong)) in by_source {
+        right.sort_by(|a, b| a.label.confidence.total_cmp(&b.label.confidence));
+        wrong.sort_by(|a, b| b.label.confidence.total_cmp(&a.label.confidence));
+
+        let mut seen = HashSet::new();
+        let mut unique = |list: Vec<&ExampleCandidate>| -> Vec<FewShotExample> {
+            list.into_iter()
+                .filter(|c| seen.insert((c.text.clone(), c.item.to_string())))
+                .map(example)
+                .collect()
+        };
+        let wrong = unique(wrong);
+        let right = unique(right);
+
+        let wrong_slots = (per_source / 2).min(wrong.len());
+        let right_slots = (per_source - wrong_slots).min(right.len());
+        let wrong_slots = (per_source - right_slots).min(wrong.len());
+        let mut examples: Vec<FewShotExample> = right.into_iter().take(right_slots).collect();
+        examples.extend(wrong.into_iter().take(wrong_slots));
+        if !examples.is_empty() {
+            selected.insert(source, examples);
+        }
+    }
+    selected
+}
+
+fn example(candidate: &ExampleCandidate) -> FewShotExample {
+    FewShotExample {
+        label_id: candidate.label.id,
+        target: candidate.label.target,
+        correct: candidate.label.correct,
+        text: candidate.text.as_deref().map(|t| excerpt(t.trim())),
+        item: candidate.item.clone(),
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    if text.len() <= MAX_EXAMPLE_TEXT_LEN {
+        return text.to_string();
+    }
+    let mut end = MAX_EXAMPLE_TEXT_LEN;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+/// Feedback on items extracted with one prompt version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantQuality {
+    pub version: u32,
+    pub labels: u64,
+    pub correct: u64,
+    /// Unset without labels.
+    pub accuracy: Option<f64>,
+    pub mean_confidence: Option<f64>,
+}
+
+/// Extraction quality of two prompt versions, judged by analyst feedback.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptComparison {
+    pub control: VariantQuality,
+    pub candidate: VariantQuality,
+    /// Candidate accuracy minus control accuracy.
+    pub accuracy_difference: Option<f64>,
+    /// Two-proportion z statistic for the difference; beyond ±1.96 is
+    /// significant at the 5% level.
+    pub z_score: Option<f64>,
+}
+
+/// Compare the labels on items extracted with `control` and `candidate`.
+/// Answers and items without a recorded prompt version are ignored.
+pub fn compare_versions(
+    labels: &[FeedbackLabel],
+    control: u32,
+    candidate: u32,
+) -> PromptComparison {
+    let quality = |version: u32| {
+        let matching: Vec<&FeedbackLabel> = labels
+            .iter()
+            .filter(|l| l.target != FeedbackTarget::Answer && l.prompt_version == Some(version))
+            .collect();
+        let n = matching.len() as f64;
+        let correct = matching.iter().filter(|l| l.correct).count() as u64;
+        VariantQuality {
+            version,
+            labels: matching.len() as u64,
+            correct,
+            accuracy: (!matching.is_empty()).then(|| correct as f64 / n),
+            mean_confidence: (!matching.is_empty())
+                .then(|| matching.iter().map(|l| l.confidence).sum::<f64>() / n),
+        }
+    };
+    let control = quality(control);
+    let candidate = quality(candidate);
+
+    let accuracy_difference = match (control.accuracy, candidate.accuracy) {
+        (Some(a), Some(b)) => Some(b - a),
+        _ => None,
+    };
+    let z_score = accuracy_difference.and_then(|diff| {
+        let (na, nb) = (control.labels as f64, candidate.labels as f64);
+        let pooled = (control.correct + candidate.correct) as f64 / (na + nb);
+        let se = (pooled * (1.0 - pooled) * (1.0 / na + 1.0 / nb)).sqrt();
+        (se > 0.0).then(|| diff / se)
+    });
+
+    PromptComparison {
+        control,
+        candidate,
+        accuracy_difference,
+        z_score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn candidate(
+        target: FeedbackTarget,
+        source: &str,
+        correct: bool,
+        confidence: f64,
+        text: Option<&str>,
+    ) -> ExampleCandidate {
+        ExampleCandidate {
+            label: FeedbackLabel {
+                id: Uuid::new_v4(),
+                target,
+                target_id: Some(Uuid::new_v4()),
+                correct,
+                confidence,
+                source: source.to_string(),
+                kind: "owner_of".to_string(),
+                note: None,
+                prompt_version: None,
+                created_at: Utc::now(),
+            },
+            text: text.map(str::to_string),
+            item: json!({ "source": "Acme", "target": format!("Sub {confidence}"), "type": "owner_of" }),
+        }
+    }
+
+    const TEXT: &str = "Acme Holdings acquired a controlling stake in the subsidiary last year.";
+
+    #[test]
+    fn selection_prefers_instructive_examples() {
+        let candidates = vec![
+            candidate(
+                FeedbackTarget::Relationship,
+                "GDELT",
+                true,
+                0.95,
+                Some(TEXT),
+            ),
+            candidate(
+                FeedbackTarget::Relationship,
+                "gdelt",
+                true,
+                0.55,
+                Some(TEXT),
+            ),
+            candidate(
+                FeedbackTarget::Relationship,
+                "gdelt",
+                false,
+                0.6,
+                Some(TEXT),
+            ),
+            candidate(
+                FeedbackTarget::Relationship,
+                "gdelt",
+                false,
+                0.9,
+                Some(TEXT),
+            ),
+            candidate(
+                FeedbackTarget::Relationship,
+                "gdelt",
+                true,
+                0.2,
+                Some("too short"),
+            ),
+            candidate(FeedbackTarget::Relationship, "gdelt", true, 0.1, None),
+            candidate(FeedbackTarget::Entity, "gdelt", true, 0.3, None),
+            candidate(FeedbackTarget::Answer, "reasoning", false, 0.9, None),
+        ];
+        let selected = select_examples(&candidates, 2);
+        assert_eq!(selected.keys().collect::<Vec<_>>(), vec!["gdelt"]);
+        let gdelt = &selected["gdelt"];
+        assert_eq!(gdelt.len(), 2);
+        assert_eq!(gdelt[0].label_id, candidates[1].label.id);
+        assert_eq!(gdelt[1].label_id, candidates[3].label.id);
+
+        let only_mistakes = select_examples(&candidates[2..4], 4);
+        assert_eq!(only_mistakes["gdelt"].len(), 2);
+    }
+
+    #[test]
+    fn rendering_marks_mistakes() {
+        let selected = select_examples(
+            &[
+                candidate(FeedbackTarget::Relationship, "gdelt", true, 0.5, Some(TEXT)),
+                candidate(FeedbackTarget::Entity, "gdelt", false, 0.9, None),
+            ],
+            4,
+        );
+        let bundle = PromptBundle {
+            version: 3,
+            created_at: Utc::now(),
+            note: None,
+            examples: selected,
+        };
+        let section = bundle.render("GDELT").unwrap();
+        assert!(section.contains(&format!("Text: \"{TEXT}\"\nCorrect relationship: ")));
+        assert!(section.contains("Not a valid entity: "));
+        assert!(bundle.render("rss").is_none());
+        assert_eq!(bundle.example_count(), 2);
+    }
+
+    #[test]
+    fn deployment_splits_documents_stably() {
+        let deployment = PromptDeployment {
+            control: 1,
+            candidate: Some(2),
+            candidate_share: 0.3,
+            updated_at: Utc::now(),
+        };
+        let versions: Vec<u32> = (0..2000)
+            .map(|i| deployment.version_for("gdelt", &i.to_string()))
+            .collect();
+        let share = versions.iter().filter(|v| **v == 2).count() as f64 / versions.len() as f64;
+        assert!((share - 0.3).abs() < 0.05, "{share}");
+        assert_eq!(deployment.version_for("gdelt", "42"), versions[42]);
+
+        assert!(!PromptDeployment::default().is_active());
+        assert_eq!(
+            PromptDeployment::default().version_for("gdelt", "1"),
+            BASELINE_PROMPT_VERSION
+        );
+    }
+
+    #[test]
+    fn comparison_reports_accuracy_and_significance() {
+        let label = |version, correct| FeedbackLabel {
+            prompt_version: Some(version),
+            ..candidate(FeedbackTarget::Relationship, "gdelt", correct, 0.8, None).label
+        };
+        let mut labels: Vec<FeedbackLabel> = (0..50).map(|i| label(1, i % 2 == 0)).collect();
+        labels.extend((0..50).map(|i| label(2, i % 10 != 0)));
+        labels.push(FeedbackLabel {
+            target: FeedbackTarget::Answer,
+            ..label(2, false)
+        });
+
+        let comparison = compare_versions(&labels, 1, 2);
+        assert_eq!(
+            (comparison.control.labels, comparison.candidate.labels),
+            (50, 50)
+        );
+        assert_eq!(comparison.candidate.accuracy, Some(0.9));
+        assert!((comparison.accuracy_difference.unwrap() - 0.4).abs() < 1e-9);
+        assert!(comparison.z_score.unwrap() > 1.96);
+
+        let empty = compare_versions(&labels, 1, 7);
+        assert!(empty.candidate.accuracy.is_none() && empty.z_score.is_none());
+    }
+}
@@ -0,0 +1,108 @@
+//! Daily caps on how many documents each source may send for LLM
+//! extraction, and what happens to the documents over the cap.
+
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Most documents waiting in one source's spillover queue; the oldest are
+/// dropped beyond this.
+pub const MAX_SPILLOVER_PER_SOURCE: usize = 10_000;
+
+/// Quota counters outlive their day by this much, so yesterday's stays
+/// readable for a while.
+pub const COUNTER_TTL_SECS: u64 = 2 * 24 * 60 * 60;
+
+/// What happens to documents beyond a source's daily quota.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum Spillover {
+    /// Not extracted at all.
+    Drop,
+    /// Held for processing during off-peak hours.
+    #[default]
+    Queue,
+}
+
+impl Spillover {
+    /// `drop` or `queue`; anything else queues.
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "drop" => Spillover::Drop,
+            _ => Spillover::Queue,
+        }
+    }
+}
+
+/// A counter kept per source and UTC day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaCounter {
+    /// Documents sent for extraction within the quota.
+    Used,
+    /// Documents over the quota that were dropped.
+    Dropped,
+    /// Spilled-over documents extracted during off-peak hours.
+    OffPeak,
+}
+
+impl QuotaCounter {
+    pub fn key(&self, source: &str, day: NaiveDate) -> String {
+        let kind = match self {
+            QuotaCounter::Used => "used",
+            QuotaCounter::Dropped => "dropped",
+            QuotaCounter::OffPeak => "off_peak",
+        };
+        format!("quota:{source}:{day}:{kind}")
+    }
+}
+
+/// The UTC day quotas are counted against.
+pub fn quota_day(now: DateTime<Utc>) -> NaiveDate {
+    now.date_naive()
+}
+
+/// UTC hours during which spilled-over documents are processed, written
+/// `start-end` with `end` exclusive; `22-4` wraps past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffPeakWindow {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl OffPeakWindow {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (start, end) = spec.trim().split_once('-')?;
+        let (start, end) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+        (start < 24 && end <= 24 && start != end).then_some(Self { start, end })
+    }
+
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let hour = now.hour();
+        if self.start < self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn off_peak_windows_may_wrap_past_midnight() {
+        let at = |hour| Utc.with_ymd_and_hms(2026, 3, 1, hour, 30, 0).unwrap();
+        let night = OffPeakWindow::parse("22-4").unwrap();
+        assert!(night.contains(at(23)) && night.contains(at(0)) && night.contains(at(3)));
+        assert!(!night.contains(at(4)) && !night.contains(at(12)));
+
+        let early = OffPeakWindow::parse(" 1 - 6 ").unwrap();
+        assert!(early.contains(at(1)) && !early.contains(at(6)));
+
+        for spec in ["", "1", "5-5", "25-3", "a-b"] {
+            assert_eq!(OffPeakWindow::parse(spec), None, "{spec:?}");
+        }
+    }
+}
@@ -3,31 +3,77 @@ use serde::{Deserialize, Serialize};
 
 use crate::entity::Entity;
 use crate::error::Result;
+use crate::graph::GraphQuery;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReasoningQuery {
     pub question: String,
     pub context: Option<String>,
     pub max_hops: Option<u32>,
+    /// Queries run in place of generated ones, e.g. from a template.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prepared: Vec<GraphQuery>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct ReasoningStep {
     pub description: String,
     pub cypher: Option<String>,
     pub result_summary: String,
 }
 
+/// Wall-clock time spent in one stage of a reasoning request.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct StageTiming {
+    pub stage: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub elapsed_ms: u64,
+}
+
+/// What one reasoning request cost.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ReasoningUsage {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub input_tokens: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub output_tokens: u64,
+    pub llm_calls: u32,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub total_ms: u64,
+    pub stages: Vec<StageTiming>,
+    /// Which limit ran out, when the request stopped early with a partial
+    /// answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_exceeded: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct ReasoningResponse {
     pub answer: String,
     pub confidence: f64,
     pub steps: Vec<ReasoningStep>,
     pub entities_referenced: Vec<Entity>,
     pub sources: Vec<String>,
+    #[serde(default)]
+    pub usage: ReasoningUsage,
+}
+
+/// Material the engine summarizes as given, without querying the graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryRequest {
+    /// What to write, e.g. the kind of report and what to lead with.
+    pub instructions: String,
+    pub material: String,
 }
 
 #[async_trait]
 pub trait ReasoningEngine: Send + Sync {
     async fn query(&self, query: &ReasoningQuery) -> Result<ReasoningResponse>;
+    /// Summarize `request.material`; `entities_referenced` holds the graph
+    /// entities the summary names.
+    async fn summarize(&self, request: &SummaryRequest) -> Result<ReasoningResponse>;
 }
@@ -0,0 +1,153 @@
+//! Retention policies: how long nodes of one label, optionally from one
+//! source, are kept before a cleanup pass removes them.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Nodes removed per graph round-trip during a cleanup pass.
+pub const RETENTION_BATCH_SIZE: usize = 1000;
+
+/// What happens to nodes past their retention age.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    /// Deleted with their relationships.
+    #[default]
+    Delete,
+    /// Written to a JSON Lines file under the archive directory, then deleted.
+    Archive,
+}
+
+/// Keep nodes labeled `label` (and from `source`, when set) for
+/// `max_age_days` after they were observed. A node's age runs from its
+/// `observed_at`, or its `last_seen` when it has none; an archived
+/// document's from its `collected_at`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RetentionPolicy {
+    pub label: String,
+    pub source: Option<String>,
+    pub max_age_days: u64,
+    pub action: RetentionAction,
+}
+
+impl RetentionPolicy {
+    /// Nodes observed before this are expired.
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let days = self.max_age_days.min(i32::MAX as u64) as i64;
+        now - Duration::days(days)
+    }
+}
+
+impl std::str::FromStr for RetentionPolicy {
+    type Err = String;
+
+    /// `Label[@source]=<days>d[:archive|:delete]`, e.g. `Position=90d` or
+    /// `Event@gdelt=365d:archive`. The label is a node label as stored
+    /// (`Position`, `Event`, `SourceDocument` ...).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (target, rule) = s
+            .trim()
+            .split_once('=')
+            .ok_or_else(|| format!("Expected label=age in retention policy: {s}"))?;
+        let (label, source) = match target.split_once('@') {
+            Some((label, source)) => (label.trim(), Some(source.trim().to_lowercase())),
+            None => (target.trim(), None),
+        };
+        let label_ok = label.starts_with(|c: char| c.is_ascii_alphabetic())
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !label_ok {
+            return Err(format!("Invalid node label in retention policy: {label}"));
+        }
+        if source.as_deref() == Some("") {
+            return Err(format!("Empty source in retention policy: {s}"));
+        }
+
+        let (age, action) = match rule.split_once(':') {
+            Some((age, action)) => (age.trim(), action.trim().to_lowercase()),
+            None => (rule.trim(), "delete".to_string()),
+        };
+        let action = match action.as_str() {
+            "delete" => RetentionAction::Delete,
+            "archive" => RetentionAction::Archive,
+            _ => return Err(format!("Unknown retention action: {action}")),
+        };
+        let max_age_days = match age.strip_suffix(['d', 'D']).unwrap_or(age).parse::<u64>() {
+            Ok(days) if days > 0 => days,
+            _ => {
+                return Err(format!(
+                    "Retention age must be a positive number of days: {age}"
+                ))
+            }
+        };
+
+        let mut chars = label.chars();
+        let label = chars
+            .next()
+            .map(|c| c.to_ascii_uppercase().to_string())
+            .unwrap_or_default()
+            + chars.as_str();
+        Ok(Self {
+            label,
+            source,
+            max_age_days,
+            action,
+        })
+    }
+}
+
+impl TryFrom<String> for RetentionPolicy {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RetentionPolicy> for String {
+    fn from(policy: RetentionPolicy) -> Self {
+        policy.to_string()
+    }
+}
+
+impl std::fmt::Display for RetentionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)?;
+        if let Some(source) = &self.source {
+            write!(f, "@{source}")?;
+        }
+        write!(f, "={}d", self.max_age_days)?;
+        if self.action == RetentionAction::Archive {
+            f.write_str(":archive")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_parse_and_print_back() {
+        let policy: RetentionPolicy = "event@GDELT = 365d:archive".parse().unwrap();
+        assert_eq!(policy.label, "Event");
+        assert_eq!(policy.source.as_deref(), Some("gdelt"));
+        assert_eq!(policy.max_age_days, 365);
+        assert_eq!(policy.action, RetentionAction::Archive);
+        assert_eq!(policy.to_string(), "Event@gdelt=365d:archive");
+
+        let policy: RetentionPolicy = "Position=90".parse().unwrap();
+        assert_eq!(policy.source, None);
+        assert_eq!(policy.action, RetentionAction::Delete);
+        let now = Utc::now();
+        assert_eq!(now - policy.cutoff(now), Duration::days(90));
+
+        assert!("Position".parse::<RetentionPolicy>().is_err());
+        assert!("Position=0d".parse::<RetentionPolicy>().is_err());
+        assert!("Position=90d:shred".parse::<RetentionPolicy>().is_err());
+        assert!("Pos`ition) DETACH DELETE (n=90d"
+            .parse::<RetentionPolicy>()
+            .is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Where settings come from besides plain environment variables, for
+//! credentials that deployments keep out of the environment.
+//!
+//! Any variable `NAME` can instead be given as `NAME_FILE`, the path of a
+//! file holding the value (a Kubernetes or Docker secret mount). A
+//! [`SecretsProvider`] chosen with `SECRETS_PROVIDER` supplies values
+//! from an external store; they are keyed by variable name, like the
+//! environment. A variable set directly wins over its `_FILE` form, which
+//! wins over the provider.
+
+use std::collections::HashMap;
+use std::env::VarError;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::error::{ArgusError, Result};
+
+/// Suffix of the variable naming a file that holds another's value.
+pub const FILE_SUFFIX: &str = "_FILE";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `key` through `var`, else read from the file `<key>_FILE` names, else
+/// from `secrets`. An unreadable file counts as unset.
+pub fn resolve(
+    var: &impl Fn(&str) -> std::result::Result<String, VarError>,
+    secrets: &HashMap<String, String>,
+    key: &str,
+) -> std::result::Result<String, VarError> {
+    if let Ok(value) = var(key) {
+        return Ok(value);
+    }
+    if let Ok(path) = var(&format!("{key}{FILE_SUFFIX}")) {
+        match read_secret_file(&path) {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                tracing::warn!(var = %format!("{key}{FILE_SUFFIX}"), path, error = %e, "Cannot read secret file")
+            }
+        }
+    }
+    secrets.get(key).cloned().ok_or(VarError::NotPresent)
+}
+
+/// A secret file's contents without the trailing newline editors and
+/// `echo` leave.
+pub fn read_secret_file(path: &str) -> std::io::Result<String> {
+    let contents = std::fs::read_to_string(path.trim())?;
+    Ok(contents.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// An external store of settings, read as a whole.
+#[async_trait]
+pub trait SecretsProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Every value the store holds for this deployment, keyed by the
+    /// environment variable it stands in for.
+    async fn fetch(&self) -> Result<HashMap<String, String>>;
+}
+
+/// The provider `SECRETS_PROVIDER` names (`vault` or
+/// `aws-secrets-manager`), configured from `var`; `None` when unset.
+pub fn provider_from_lookup(
+    var: impl Fn(&str) -> std::result::Result<String, VarError>,
+) -> Result<Option<Box<dyn SecretsProvider>>> {
+    let required = |key: &str| {
+        var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| ArgusError::Config(format!("{key} is required by SECRETS_PROVIDER")))
+    };
+    let kind = var("SECRETS_PROVIDER")
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    let provider: Box<dyn SecretsProvider> = match kind.as_str() {
+        "" | "none" => return Ok(None),
+        "vault" => Box::new(VaultProvider {
+            client: client()?,
+            addr: required("VAULT_ADDR")?.trim_end_matches('/').to_string(),
+            token: required("VAULT_TOKEN")?,
+            path: required("VAULT_SECRET_PATH")?.trim_matches('/').to_string(),
+            namespace: var("VAULT_NAMESPACE")
+                .ok()
+                .filter(|ns| !ns.trim().is_empty()),
+        }),
+        "aws-secrets-manager" | "aws" => Box::new(AwsSecretsManagerProvider {
+            client: client()?,
+            region: required("AWS_REGION").or_else(|_| required("AWS_DEFAULT_REGION"))?,
+            secret_id: required("AWS_SECRETS_MANAGER_SECRET_ID")?,
+            access_key_id: required("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: required("AWS_SECRET_ACCESS_KEY")?,
+            session_token: var("AWS_SESSION_TOKEN")
+                .ok()
+                .filter(|token| !token.trim().is_empty()),
+        }),
+        other => {
+            return Err(ArgusError::Config(format!(
+                "Unknown SECRETS_PROVIDER: {other}"
+            )))
+        }
+    };
+    Ok(Some(provider))
+}
+
+fn client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?)
+}
+
+/// String values of a JSON object; other values in their JSON form.
+fn string_map(object: &serde_json::Map<String, serde_json::Value>) -> HashMap<String, String> {
+    object
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// One secret in HashiCorp Vault's KV engine (version 1 or 2), e.g.
+/// `VAULT_SECRET_PATH=secret/data/argus`, read with a token.
+pub struct VaultProvider {
+    client: reqwest::Client,
+    addr: String,
+    token: String,
+    path: String,
+    namespace: Option<String>,
+}
+
+/// The secret's key/value pairs from a Vault read response: under
+/// `data.data` for KV version 2, under `data` for version 1.
+fn vault_values(body: &serde_json::Value) -> Result<HashMap<String, String>> {
+    let data = &body["data"];
+    let values = match data.get("metadata").is_some() {
+        true => &data["data"],
+        false => data,
+    };
+    values
+        .as_object()
+        .map(string_map)
+        .ok_or_else(|| ArgusError::Config("Vault response holds no secret data".into()))
+}
+
+#[async_trait]
+impl SecretsProvider for VaultProvider {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, String>> {
+        let mut request = self
+            .client
+            .get(format!("{}/v1/{}", self.addr, self.path))
+            .header("X-Vault-Token", &self.token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(ArgusError::Config(format!(
+                "Vault returned {} for {}",
+                response.status(),
+                self.path
+            )));
+        }
+        vault_values(&response.json().await?)
+    }
+}
+
+/// One AWS Secrets Manager secret whose value is a JSON object, read with
+/// the credentials in `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+/// optionally `AWS_SESSION_TOKEN`. Only these static keys are used: there
+/// is no AWS credential chain, so IRSA web identity tokens, instance
+/// profiles and `~/.aws` profiles are not picked up.
+pub struct AwsSecretsManagerProvider {
+    client: reqwest::Client,
+    region: String,
+    secret_id: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+const AWS_SERVICE: &str = "secretsmanager";
+const AWS_TARGET: &str = "secretsmanager.GetSecretValue";
+const AWS_CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    // HMAC takes keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// Signature Version 4 signature of `string_to_sign` for one day, region
+/// and service.
+fn sigv4_signature(
+    secret_access_key: &str,
+    date: &str,
+    region: &str,
+    service: &str,
+    string_to_sign: &str,
+) -> String {
+    let key = hmac_sha256(
+        format!("AWS4{secret_access_key}").as_bytes(),
+        date.as_bytes(),
+    );
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    let key = hmac_sha256(&key, b"aws4_request");
+    hmac_sha256(&key, string_to_sign.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// The Signature Version 4 canonical form of a request to `/`, and its
+/// signed header list. `headers` must have lowercase names, sorted.
+fn canonical_request(
+    method: &str,
+    query: &str,
+    headers: &[(&str, String)],
+    payload: &str,
+) -> (String, String) {
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let request = format!(
+        "{method}\n/\n{query}\n{canonical_headers}\n{signed}\n{}",
+        sha256_hex(payload.as_bytes())
+    );
+    (request, signed)
+}
+
+impl AwsSecretsManagerProvider {
+    fn host(&self) -> String {
+        format!("{AWS_SERVICE}.{}.amazonaws.com", self.region)
+    }
+
+    /// Headers for a signed `GetSecretValue` call with `body` at `now`.
+    fn signed_headers(&self, body: &str, now: DateTime<Utc>) -> Vec<(&'static str, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        // Sorted by name, as the canonical request requires
+        let mut headers = vec![
+            ("content-type", AWS_CONTENT_TYPE.to_string()),
+            ("host", self.host()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        headers.push(("x-amz-target", AWS_TARGET.to_string()));
+
+        let (canonical_request, signed) = canonical_request("POST", "", &headers, body);
+        let scope = format!("{date}/{}/{AWS_SERVICE}/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let signature = sigv4_signature(
+            &self.secret_access_key,
+            &date,
+            &self.region,
+            AWS_SERVICE,
+            &string_to_sign,
+        );
+
+        headers.retain(|(name, _)| *name != "host");
+        headers.push((
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed}, Signature={signature}",
+                self.access_key_id
+            ),
+        ));
+        headers
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    fn name(&self) -> &'static str {
+        "aws-secrets-manager"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, String>> {
+        let body = serde_json::json!({ "SecretId": self.secret_id }).to_string();
+        let mut request = self.client.post(format!("https://{}/", self.host()));
+        for (name, value) in self.signed_headers(&body, Utc::now()) {
+            request = request.header(name, value);
+        }
+        let response = request.body(body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(ArgusError::Config(format!(
+                "Secrets Manager returned {status} for {}: {}",
+                self.secret_id,
+                detail.chars().take(200).collect::<String>()
+            )));
+        }
+        let body: serde_json::Value = response.json().await?;
+        let secret = body["SecretString"]
+            .as_str()
+            .ok_or_else(|| ArgusError::Config(format!("{} has no SecretString", self.secret_id)))?;
+        match serde_json::from_str::<serde_json::Value>(secret)? {
+            serde_json::Value::Object(object) => Ok(string_map(&object)),
+            _ => Err(ArgusError::Config(format!(
+                "{} is not a JSON object",
+                self.secret_id
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables_win_over_files_which_win_over_the_provider() {
+        let path = std::env::temp_dir().join(format!("argus-secret-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "from-file\n").unwrap();
+        let path = path.display().to_string();
+        let secrets = HashMap::from([
+            ("ANTHROPIC_API_KEY".to_string(), "from-provider".to_string()),
+            ("VOYAGE_API_KEY".to_string(), "from-provider".to_string()),
+        ]);
+
+        let env = |key: &str| match key {
+            "NEO4J_PASSWORD" => Ok("from-env".to_string()),
+            "NEO4J_PASSWORD_FILE" | "ANTHROPIC_API_KEY_FILE" => Ok(path.clone()),
+            _ => Err(VarError::NotPresent),
+        };
+        assert_eq!(
+            resolve(&env, &secrets, "NEO4J_PASSWORD").unwrap(),
+            "from-env"
+        );
+        assert_eq!(
+            resolve(&env, &secrets, "ANTHROPIC_API_KEY").unwrap(),
+            "from-file"
+        );
+        assert_eq!(
+            resolve(&env, &secrets, "VOYAGE_API_KEY").unwrap(),
+            "from-provider"
+        );
+        assert!(resolve(&env, &secrets, "SMTP_URL").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn vault_reads_both_kv_versions() {
+        let v2 = serde_json::json!({ "data": { "data": { "ANTHROPIC_API_KEY": "sk", "SERVER_PORT": 8080 }, "metadata": { "version": 3 } } });
+        let values = vault_values(&v2).unwrap();
+        assert_eq!(
+            (
+                values["ANTHROPIC_API_KEY"].as_str(),
+                values["SERVER_PORT"].as_str()
+            ),
+            ("sk", "8080")
+        );
+
+        let v1 = serde_json::json!({ "data": { "NEO4J_PASSWORD": "pw" } });
+        assert_eq!(vault_values(&v1).unwrap()["NEO4J_PASSWORD"], "pw");
+    }
+
+    #[test]
+    fn aws_requests_are_signed_with_sigv4() {
+        // RFC 4231, test case 2
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex: String = mac.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        // The IAM ListUsers example from the AWS Signature Version 4 documentation
+        let string_to_sign =
+            "AWS4-HMAC-SHA256\n20150830T123600Z\n20150830/us-east-1/iam/aws4_request\n\
+                              f536975d06c0309214f805bb90ccff089219ecd68b2577efef23edd43b7e1a59";
+        assert_eq!(
+            sigv4_signature(
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "20150830",
+                "us-east-1",
+                "iam",
+                string_to_sign
+            ),
+            "5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+        // ...whose canonical request hashes to the one signed above
+        let (request, signed) = canonical_request(
+            "GET",
+            "Action=ListUsers&Version=2010-05-08",
+            &[
+                (
+                    "content-type",
+                    "application/x-www-form-urlencoded; charset=utf-8".to_string(),
+                ),
+                ("host", "iam.amazonaws.com".to_string()),
+                ("x-amz-date", "20150830T123600Z".to_string()),
+            ],
+            "",
+        );
+        assert_eq!(signed, "content-type;host;x-amz-date");
+        assert_eq!(
+            sha256_hex(request.as_bytes()),
+            "f536975d06c0309214f805bb90ccff089219ecd68b2577efef23edd43b7e1a59"
+        );
+    }
+
+    #[test]
+    fn get_secret_value_headers_match_botocore() {
+        let mut provider = AwsSecretsManagerProvider {
+            client: reqwest::Client::new(),
+            region: "eu-west-1".to_string(),
+            secret_id: "argus/prod".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        };
+        let body = r#"{"SecretId":"argus/prod"}"#;
+        let now = DateTime::parse_from_rfc3339("2026-03-14T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let authorization = |headers: Vec<(&'static str, String)>| {
+            assert!(headers.iter().all(|(name, _)| *name != "host"));
+            headers
+                .into_iter()
+                .find(|(name, _)| *name == "authorization")
+                .unwrap()
+                .1
+        };
+
+        // Expected values from botocore's SigV4Auth signing the same request
+        assert_eq!(
+            authorization(provider.signed_headers(body, now)),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20260314/eu-west-1/secretsmanager/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-target, \
+             Signature=9b47c0f49eff3811b442207fc1b9f25893723224c78d2a30da1fb9096d7aaf0d"
+        );
+        provider.session_token = Some("FwoGZXIvYXdzEXAMPLETOKEN".to_string());
+        assert_eq!(
+            authorization(provider.signed_headers(body, now)),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20260314/eu-west-1/secretsmanager/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target, \
+             Signature=1188ae2cf9901f617f18bb123c5ac69f2fd90dd6b1546671a4806fdef0047992"
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! State that several server replicas must agree on. Each trait has an
+//! in-process implementation for single-node deployments and a Redis one
+//! for running behind a load balancer.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::agent::{RawDocument, SchemaDrift};
+use crate::alerts::{Alert, AlertRule, AlertStatus};
+use crate::api_types::AgentRunStatus;
+use crate::calibration::FeedbackLabel;
+use crate::deny_list::DenyEntry;
+use crate::duplicates::DuplicateCandidate;
+use crate::error::Result;
+use crate::extraction::ExtractionFailure;
+use crate::investigation::Investigation;
+use crate::prompts::{FewShotExample, PromptBundle, PromptDeployment};
+use crate::subscriptions::{Notification, Subscription};
+use crate::usage::{UsageCounts, UsageRecord};
+
+/// Most recent runs kept in the run history.
+pub const MAX_RUN_HISTORY: usize = 100;
+
+/// Attempts after which a failed document is no longer retried.
+pub const MAX_RETRY_ATTEMPTS: u32 = 5;
+
+const RETRY_BASE_DELAY_SECS: i64 = 60;
+const RETRY_MAX_DELAY_SECS: i64 = 60 * 60;
+
+/// A document whose extraction failed, waiting to be retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedDocument {
+    /// The run that first failed on this document.
+    pub run_id: String,
+    pub agent_name: String,
+    pub document: RawDocument,
+    pub error: String,
+    pub attempts: u32,
+    pub last_failed_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+impl FailedDocument {
+    pub fn new(run_id: &str, agent_name: &str, document: RawDocument, error: String) -> Self {
+        let now = Utc::now();
+        Self {
+            run_id: run_id.to_string(),
+            agent_name: agent_name.to_string(),
+            document,
+            error,
+            attempts: 1,
+            last_failed_at: now,
+            next_attempt_at: now + retry_delay(1),
+        }
+    }
+
+    /// Record another failed attempt and push the next one back.
+    pub fn failed_again(&mut self, error: String) {
+        let now = Utc::now();
+        self.attempts += 1;
+        self.error = error;
+        self.last_failed_at = now;
+        self.next_attempt_at = now + retry_delay(self.attempts);
+    }
+
+    pub fn exhausted(&self) -> bool {
+        self.attempts >= MAX_RETRY_ATTEMPTS
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        !self.exhausted() && self.next_attempt_at <= now
+    }
+}
+
+/// Exponential backoff: 1, 2, 4, … minutes after the nth failure, capped at an hour.
+pub fn retry_delay(attempts: u32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).min(16);
+    let secs = (RETRY_BASE_DELAY_SECS << exponent).min(RETRY_MAX_DELAY_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+/// History of agent and job runs.
+#[async_trait]
+pub trait RunStore: Send + Sync {
+    /// Record a new run, trimming the history to [`MAX_RUN_HISTORY`].
+    async fn insert(&self, run: AgentRunStatus) -> Result<()>;
+    /// Overwrite an existing run. Unknown (already trimmed) runs are ignored.
+    async fn save(&self, run: &AgentRunStatus) -> Result<()>;
+    async fn get(&self, run_id: &str) -> Result<Option<AgentRunStatus>>;
+    /// Runs newest first.
+    async fn list(&self, limit: usize) -> Result<Vec<AgentRunStatus>>;
+}
+
+/// Remembers keys for a while so work is not repeated across replicas.
+#[async_trait]
+pub trait DedupStore: Send + Sync {
+    /// Mark `key` as seen for `ttl`. Returns `true` if it was not already seen.
+    async fn first_seen(&self, key: &str, ttl: Duration) -> Result<bool>;
+}
+
+/// Fixed-window rate limiting shared by all replicas.
+#[async_trait]
+pub trait RateLimiter: Send + Sync {
+    /// Wait until one more request under `key` fits in `per_minute`.
+    async fn acquire(&self, key: &str, per_minute: u32) -> Result<()>;
+}
+
+/// Expiring mutual-exclusion locks, so only one replica does a piece of work.
+#[async_trait]
+pub trait LockManager: Send + Sync {
+    /// Take `key` for `owner` unless another owner holds it. Re-acquiring a
+    /// lock the owner already holds refreshes its expiry.
+    async fn try_acquire(&self, key: &str, owner: &str, ttl: Duration) -> Result<bool>;
+    /// Drop `key` if `owner` still holds it.
+    async fn release(&self, key: &str, owner: &str) -> Result<()>;
+    /// Current owner of `key`, if it is held.
+    async fn holder(&self, key: &str) -> Result<Option<String>>;
+}
+
+/// Maps client-supplied idempotency keys to the run they started.
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Bind `key` to `run_id` for `ttl` unless it is already bound. Returns
+    /// the run the key was already bound to, if any.
+    async fn claim(&self, key: &str, run_id: &str, ttl: Duration) -> Result<Option<String>>;
+    /// Unbind `key`, for a claim whose run never started.
+    async fn forget(&self, key: &str) -> Result<()>;
+}
+
+/// Failed documents per run, kept until they are retried successfully.
+#[async_trait]
+pub trait RetryQueue: Send + Sync {
+    /// Insert or replace the entry for `(run_id, document.source_id)`.
+    async fn record(&self, failed: FailedDocument) -> Result<()>;
+    async fn for_run(&self, run_id: &str) -> Result<Vec<FailedDocument>>;
+    async fn remove(&self, run_id: &str, source_id: &str) -> Result<()>;
+}
+
+/// Agents an operator has paused. The scheduler skips their runs until
+/// they are resumed; pauses are persistent, unlike the rest of this state.
+#[async_trait]
+pub trait AgentPauseStore: Send + Sync {
+    async fn set_paused(&self, agent_name: &str, paused: bool) -> Result<()>;
+    async fn is_paused(&self, agent_name: &str) -> Result<bool>;
+    async fn paused(&self) -> Result<BTreeSet<String>>;
+}
+
+/// Duplicate pairs from the latest scan, awaiting review. Dismissed pairs
+/// are remembered so later scans do not bring them back.
+#[async_trait]
+pub trait DuplicateReviewQueue: Send + Sync {
+    /// Replace the queue with a fresh scan, leaving out dismissed pairs.
+    async fn replace(&self, candidates: Vec<DuplicateCandidate>) -> Result<()>;
+    /// Candidates, highest score first.
+    async fn list(&self) -> Result<Vec<DuplicateCandidate>>;
+    async fn get(&self, id: &str) -> Result<Option<DuplicateCandidate>>;
+    /// Drop every candidate involving one of `entity_ids`, after a merge
+    /// removed them from the graph.
+    async fn remove_involving(&self, entity_ids: &[Uuid]) -> Result<()>;
+    /// Drop a candidate and keep it out of later scans. Returns `false`
+    /// if it was not queued.
+    async fn dismiss(&self, id: &str) -> Result<bool>;
+}
+
+/// User-defined alert rules.
+#[async_trait]
+pub trait AlertRuleStore: Send + Sync {
+    /// Insert or replace a rule.
+    async fn save(&self, rule: &AlertRule) -> Result<()>;
+    /// Rules, oldest first.
+    async fn list(&self) -> Result<Vec<AlertRule>>;
+    async fn get(&self, id: Uuid) -> Result<Option<AlertRule>>;
+    /// Returns `false` if there was no such rule.
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+/// Managed deny-list entries applied by the validation stage.
+#[async_trait]
+pub trait DenyListStore: Send + Sync {
+    /// Insert or replace an entry.
+    async fn save(&self, entry: &DenyEntry) -> Result<()>;
+    /// Entries, oldest first.
+    async fn list(&self) -> Result<Vec<DenyEntry>>;
+    /// Returns `false` if there was no such entry.
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+/// Analysts' correct/incorrect labels, one per item judged.
+#[async_trait]
+pub trait FeedbackStore: Send + Sync {
+    /// Insert a label, replacing any earlier one with the same
+    /// [`FeedbackLabel::item_key`].
+    async fn save(&self, label: &FeedbackLabel) -> Result<()>;
+    /// Labels, newest first.
+    async fn list(&self) -> Result<Vec<FeedbackLabel>>;
+    /// Returns `false` if there was no such label.
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+/// Investigations and their notes.
+#[async_trait]
+pub trait InvestigationStore: Send + Sync {
+    /// Insert or replace an investigation.
+    async fn save(&self, investigation: &Investigation) -> Result<()>;
+    /// Investigations, newest first.
+    async fn list(&self) -> Result<Vec<Investigation>>;
+    async fn get(&self, id: Uuid) -> Result<Option<Investigation>>;
+    /// Returns `false` if there was no such investigation.
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+/// Entity subscriptions.
+#[async_trait]
+pub trait SubscriptionStore: Send + Sync {
+    /// Insert or replace a subscription.
+    async fn save(&self, subscription: &Subscription) -> Result<()>;
+    /// Subscriptions, oldest first.
+    async fn list(&self) -> Result<Vec<Subscription>>;
+    async fn get(&self, id: Uuid) -> Result<Option<Subscription>>;
+    /// Returns `false` if there was no such subscription.
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+/// Notifications for subscriptions, newest first, trimmed to
+/// [`crate::subscriptions::MAX_NOTIFICATIONS`].
+#[async_trait]
+pub trait NotificationStore: Send + Sync {
+    /// Record a notification unless its subscription was already told
+    /// about the same `dedup_key`. Returns whether it was recorded.
+    async fn record(&self, notification: Notification) -> Result<bool>;
+    /// Notifications, newest first.
+    async fn list(&self, limit: usize) -> Result<Vec<Notification>>;
+    /// Store a notification whose delivery changed. Unknown (already
+    /// trimmed) notifications are ignored.
+    async fn save(&self, notification: &Notification) -> Result<()>;
+    /// Forget which keys a subscription was told about, once it is deleted.
+    async fn forget(&self, subscription_id: Uuid) -> Result<()>;
+}
+
+/// Versioned extraction prompt bundles and the deployment choosing
+/// between them.
+#[async_trait]
+pub trait PromptBundleStore: Send + Sync {
+    /// Store `examples` under the next version number and return the bundle.
+    async fn create(
+        &self,
+        note: Option<String>,
+        examples: BTreeMap<String, Vec<FewShotExample>>,
+    ) -> Result<PromptBundle>;
+    /// Bundles, newest first.
+    async fn list(&self) -> Result<Vec<PromptBundle>>;
+    async fn get(&self, version: u32) -> Result<Option<PromptBundle>>;
+    /// The current deployment; the baseline prompt when none was set.
+    async fn deployment(&self) -> Result<PromptDeployment>;
+    async fn set_deployment(&self, deployment: &PromptDeployment) -> Result<()>;
+}
+
+/// Alerts raised by rules, newest first, trimmed to [`crate::alerts::MAX_ALERTS`].
+#[async_trait]
+pub trait AlertStore: Send + Sync {
+    /// Record a new alert unless one with the same `dedup_key` is still
+    /// open. Returns whether it was recorded.
+    async fn raise(&self, alert: Alert) -> Result<bool>;
+    /// Alerts with `status`, newest first.
+    async fn list(&self, status: AlertStatus, limit: usize) -> Result<Vec<Alert>>;
+    async fn get(&self, id: Uuid) -> Result<Option<Alert>>;
+    /// Store an alert changed by acknowledgement, closing its dedup key.
+    /// Unknown (already trimmed) alerts are ignored.
+    async fn save(&self, alert: &Alert) -> Result<()>;
+}
+
+/// Counters behind per-source extraction quotas. Keys come from
+/// [`crate::quota::QuotaCounter::key`] and expire after
+/// [`crate::quota::COUNTER_TTL_SECS`].
+#[async_trait]
+pub trait QuotaStore: Send + Sync {
+    /// Add up to `requested` to `key` without taking it past `limit`, and
+    /// return how much was added. Replicas share one count.
+    async fn reserve(&self, key: &str, requested: u64, limit: u64) -> Result<u64>;
+    async fn add(&self, key: &str, amount: u64) -> Result<()>;
+    /// Zero for keys never counted or expired.
+    async fn get(&self, key: &str) -> Result<u64>;
+}
+
+/// Documents over their source's quota, held for off-peak extraction.
+#[async_trait]
+pub trait SpilloverQueue: Send + Sync {
+    /// Append documents, returning how many old ones were dropped to stay
+    /// within [`crate::quota::MAX_SPILLOVER_PER_SOURCE`].
+    async fn push(&self, source: &str, documents: Vec<RawDocument>) -> Result<usize>;
+    /// Take up to `max` of the oldest documents.
+    async fn pop(&self, source: &str, max: usize) -> Result<Vec<RawDocument>>;
+    /// Queued documents per source, for sources with any.
+    async fn sizes(&self) -> Result<BTreeMap<String, usize>>;
+}
+
+/// Extraction failures whose model output could not be parsed, newest
+/// first, trimmed to [`crate::extraction::MAX_EXTRACTION_FAILURES`].
+#[async_trait]
+pub trait ExtractionFailureStore: Send + Sync {
+    async fn record(&self, failure: ExtractionFailure) -> Result<()>;
+    /// Failures newest first, only those from `agent_name` if given.
+    async fn list(&self, agent_name: Option<&str>, limit: usize) -> Result<Vec<ExtractionFailure>>;
+    async fn get(&self, id: Uuid) -> Result<Option<ExtractionFailure>>;
+    /// Returns `false` if there was no such failure.
+    async fn remove(&self, id: Uuid) -> Result<bool>;
+}
+
+/// API usage per consumer and UTC day, kept for
+/// [`crate::usage::USAGE_RETENTION_DAYS`].
+#[async_trait]
+pub trait UsageStore: Send + Sync {
+    async fn record(&self, consumer: &str, day: NaiveDate, record: &UsageRecord) -> Result<()>;
+    /// Each consumer's usage from `since` through today, days added together.
+    async fn totals(&self, since: NaiveDate) -> Result<BTreeMap<String, UsageCounts>>;
+}
+
+/// Collected documents an API process hands to an extraction worker
+/// (`--role worker`), which extracts and stores them and finishes the run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionJob {
+    pub run_id: String,
+    pub agent_name: String,
+    pub documents: Vec<RawDocument>,
+    /// Documents the agent's quota held back, recorded on the run.
+    pub over_quota: u64,
+    pub schema_drift: Option<SchemaDrift>,
+    /// Look new entities up with the other agents afterwards, as scheduled
+    /// runs do.
+    pub cross_reference: bool,
+    pub enqueued_at: DateTime<Utc>,
+}
+
+/// A job a worker took off the [`ExtractionQueue`]. It stays on the
+/// worker's processing list until acknowledged with its `receipt`.
+#[derive(Debug, Clone)]
+pub struct ClaimedJob {
+    pub job: ExtractionJob,
+    pub receipt: String,
+}
+
+/// Extraction jobs waiting for a worker, oldest first. A taken job is kept
+/// on the taking worker's processing list until it is acknowledged, so
+/// jobs of a worker that dies or is stopped can be handed out again.
+#[async_trait]
+pub trait ExtractionQueue: Send + Sync {
+    async fn push(&self, job: ExtractionJob) -> Result<()>;
+    /// Move the oldest job, if any, onto `worker`'s processing list. A job
+    /// is handed to one worker only.
+    async fn pop(&self, worker: &str) -> Result<Option<ClaimedJob>>;
+    /// Drop a finished job from `worker`'s processing list.
+    async fn ack(&self, worker: &str, receipt: &str) -> Result<()>;
+    /// Mark `worker` alive for `ttl`; its processing list is left alone
+    /// until the mark lapses.
+    async fn heartbeat(&self, worker: &str, ttl: Duration) -> Result<()>;
+    /// Put every job on `worker`'s processing list back at the front of
+    /// the queue. Returns how many.
+    async fn requeue(&self, worker: &str) -> Result<usize>;
+    /// [`requeue`](Self::requeue) the jobs of every worker whose heartbeat
+    /// has lapsed.
+    async fn requeue_stale(&self) -> Result<usize>;
+    /// Jobs waiting.
+    async fn pending(&self) -> Result<usize>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_cap() {
+        assert_eq!(retry_delay(1).num_seconds(), 60);
+        assert_eq!(retry_delay(2).num_seconds(), 120);
+        assert_eq!(retry_delay(3).num_seconds(), 240);
+        assert_eq!(retry_delay(10).num_seconds(), 3600);
+        assert_eq!(retry_delay(u32::MAX).num_seconds(), 3600);
+    }
+
+    #[test]
+    fn failed_document_backs_off_and_exhausts() {
+        let doc = RawDocument {
+            source: "gdelt".to_string(),
+            source_id: "evt-1".to_string(),
+            title: None,
+            content: "text".to_string(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: None,
+            identifiers: Default::default(),
+            metadata: serde_json::Value::Null,
+            structured: None,
+        };
+        let mut failed = FailedDocument::new("run-1", "gdelt", doc, "timeout".to_string());
+        assert!(!failed.is_due(Utc::now()));
+        assert!(failed.is_due(Utc::now() + chrono::Duration::minutes(2)));
+
+        for _ in 1..MAX_RETRY_ATTEMPTS {
+            failed.failed_again("timeout".to_string());
+        }
+        assert!(failed.exhausted());
+        assert!(!failed.is_due(Utc::now() + chrono::Duration::days(1)));
+    }
+}
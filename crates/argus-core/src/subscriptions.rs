@@ -0,0 +1,109 @@
+//! Entity subscriptions ("follow this entity") and the notifications they
+//! collect.
+//!
+//! A subscription is checked against every batch the write buffer stores
+//! (`argus-server/src/subscriptions.rs`): a notification is recorded when
+//! the followed entity gains a relationship, appears in a new document or
+//! gets a `SANCTIONED_BY` edge, and posted to the subscription's webhook
+//! if it has one.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::alerts::Delivery;
+use crate::entity::{Entity, EntityType};
+
+/// Most notifications kept; the oldest are dropped beyond this.
+pub const MAX_NOTIFICATIONS: usize = 10_000;
+
+/// What a subscription is told about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionEvent {
+    /// A relationship to or from the entity, other than a sanction.
+    NewRelationship,
+    /// A document the entity was extracted from.
+    NewDocument,
+    /// A `SANCTIONED_BY` relationship: the entity was listed or matched.
+    SanctionsChange,
+}
+
+impl SubscriptionEvent {
+    pub const ALL: [SubscriptionEvent; 3] = [
+        SubscriptionEvent::NewRelationship,
+        SubscriptionEvent::NewDocument,
+        SubscriptionEvent::SanctionsChange,
+    ];
+}
+
+/// Someone following an entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Subscription {
+    pub id: Uuid,
+    pub entity_id: Uuid,
+    pub entity_name: String,
+    pub entity_type: EntityType,
+    /// The entity's identifiers when it was followed. Extracted entities
+    /// only get the stored node's id once written, so writes are matched
+    /// on these and the name as well as the id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub identifiers: BTreeMap<String, String>,
+    pub events: Vec<SubscriptionEvent>,
+    /// Who follows it, for filtering the feed, e.g. an analyst's name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscriber: Option<String>,
+    /// Each notification is POSTed here as JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Subscription {
+    pub fn wants(&self, event: SubscriptionEvent) -> bool {
+        self.events.contains(&event)
+    }
+
+    /// Whether `entity` in a write is the followed entity: the same id, or
+    /// the same type and name, or a shared identifier.
+    pub fn follows(&self, entity: &Entity) -> bool {
+        entity.id == self.entity_id
+            || (entity.entity_type == self.entity_type
+                && entity.name.eq_ignore_ascii_case(&self.entity_name))
+            || entity
+                .identifiers
+                .iter()
+                .any(|(scheme, value)| self.identifiers.get(scheme) == Some(value))
+    }
+}
+
+/// Something that happened to a followed entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Notification {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscriber: Option<String>,
+    pub entity_id: Uuid,
+    pub entity_name: String,
+    pub event: SubscriptionEvent,
+    pub message: String,
+    /// Source of the write.
+    pub source: String,
+    /// The document the write was extracted from.
+    pub raw_source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationship_id: Option<Uuid>,
+    /// Same subscription and subject; a subscription is told about each
+    /// key once.
+    pub dedup_key: String,
+    pub created_at: DateTime<Utc>,
+    /// Delivery to the subscription's webhook, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<Delivery>,
+}
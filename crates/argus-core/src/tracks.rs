@@ -0,0 +1,934 @@
+//! Position reports of tracked vessels and aircraft, and the encounters
+//! derived from them.
+//!
+//! Reports are taken from the documents the AIS and ADS-B agents collect
+//! and stored as `Position` nodes keyed on the asset's identifier
+//! (`mmsi:…`, `icao24:…`), apart from the entity graph.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::agent::RawDocument;
+use crate::entity::{identifier_scheme, Entity, EntityType};
+use crate::places::Place;
+
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+const METERS_PER_DEGREE_LAT: f64 = 111_195.0;
+const KNOTS_TO_MPS: f64 = 0.514_444;
+
+/// Label of the graph nodes position reports are stored as.
+pub const POSITION_LABEL: &str = "Position";
+
+/// Speed at or below which a vessel counts as stopped (about 1 knot).
+pub const STOP_SPEED_MPS: f64 = 0.5;
+
+/// Identifier schemes that name a tracked asset, with the asset's type.
+const ASSET_SCHEMES: [(&str, EntityType); 2] = [
+    (identifier_scheme::MMSI, EntityType::Vessel),
+    (identifier_scheme::ICAO24, EntityType::Aircraft),
+];
+
+/// Where one asset was at one time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionReport {
+    /// The asset's identifier as `scheme:value`, e.g. `mmsi:244660000`.
+    pub asset: String,
+    pub entity_type: EntityType,
+    pub source: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub observed_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_mps: Option<f64>,
+    /// Course over ground in degrees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub course: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub altitude_m: Option<f64>,
+    /// ADS-B's ground flag; `None` for vessels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_ground: Option<bool>,
+    /// The destination an AIS vessel declares, as typed by its crew.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+}
+
+impl PositionReport {
+    /// The position a document reports for its subject, if it names a
+    /// tracked asset and carries valid coordinates in its metadata. AIS
+    /// speeds are in knots, ADS-B's in m/s.
+    pub fn from_document(doc: &RawDocument) -> Option<Self> {
+        let (asset, entity_type) = ASSET_SCHEMES.iter().find_map(|(scheme, entity_type)| {
+            let value = doc.identifiers.get(*scheme)?.trim();
+            (!value.is_empty()).then(|| (format!("{scheme}:{value}"), entity_type.clone()))
+        })?;
+        let number = |key: &str| doc.metadata.get(key).and_then(Value::as_f64);
+        let (latitude, longitude) = (number("latitude")?, number("longitude")?);
+        // AIS reports 91/181 when the position is unavailable
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return None;
+        }
+        Some(Self {
+            asset,
+            entity_type,
+            source: doc.source.clone(),
+            latitude,
+            longitude,
+            observed_at: doc.observed_at.unwrap_or(doc.collected_at),
+            speed_mps: number("speed_over_ground")
+                .map(|knots| knots * KNOTS_TO_MPS)
+                .or_else(|| number("velocity")),
+            course: number("course_over_ground").or_else(|| number("true_track")),
+            altitude_m: number("geo_altitude").or_else(|| number("baro_altitude")),
+            on_ground: doc.metadata.get("on_ground").and_then(Value::as_bool),
+            destination: doc
+                .metadata
+                .get("destination")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    /// Set `latitude`/`longitude` on the entities this report is about,
+    /// matched on their asset identifiers. Returns how many were placed.
+    pub fn place(&self, entities: &mut [Entity]) -> usize {
+        let mut placed = 0;
+        for entity in entities
+            .iter_mut()
+            .filter(|e| asset_keys(e).contains(&self.asset))
+        {
+            if !entity.properties.is_object() {
+                entity.properties = Value::Object(Default::default());
+            }
+            if let Some(properties) = entity.properties.as_object_mut() {
+                properties.insert("latitude".to_string(), Value::from(self.latitude));
+                properties.insert("longitude".to_string(), Value::from(self.longitude));
+                placed += 1;
+            }
+        }
+        placed
+    }
+}
+
+/// The `scheme:value` keys position reports about this entity are stored under.
+pub fn asset_keys(entity: &Entity) -> Vec<String> {
+    ASSET_SCHEMES
+        .iter()
+        .filter_map(|(scheme, _)| {
+            let value = entity.identifiers.get(*scheme)?.trim();
+            (!value.is_empty()).then(|| format!("{scheme}:{value}"))
+        })
+        .collect()
+}
+
+/// Great-circle distance in meters between two `(latitude, longitude)` points.
+pub fn distance_m(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.1 - a.1).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().asin()
+}
+
+/// Coordinates in an entity's `latitude`/`longitude` (or `lat`/`lon`/`lng`) properties.
+pub fn entity_coordinates(entity: &Entity) -> Option<(f64, f64)> {
+    property_coordinates(&entity.properties)
+}
+
+/// Coordinates in a properties object, as for [`entity_coordinates`].
+pub fn property_coordinates(properties: &Value) -> Option<(f64, f64)> {
+    let number = |keys: &[&str]| {
+        keys.iter().find_map(|k| match properties.get(*k)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        })
+    };
+    Some((
+        number(&["latitude", "lat"])?,
+        number(&["longitude", "lon", "lng"])?,
+    ))
+}
+
+/// Two assets reported within the distance and time window of each other.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Encounter {
+    /// The pair's asset identifiers, in order.
+    pub assets: (String, String),
+    /// First and last report times of the close pairs of reports.
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub closest_at: DateTime<Utc>,
+    pub min_distance_m: f64,
+    /// Midpoint of the closest pair of reports.
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Close pairs of reports seen.
+    pub reports: usize,
+}
+
+/// Every pair of assets with some two reports at most `max_distance_m`
+/// apart and at most `window` apart in time, one [`Encounter`] per pair.
+pub fn detect_encounters(
+    positions: &[PositionReport],
+    max_distance_m: f64,
+    window: Duration,
+) -> Vec<Encounter> {
+    let mut sorted: Vec<&PositionReport> = positions.iter().collect();
+    sorted.sort_by_key(|p| p.observed_at);
+    let max_dlat = max_distance_m / METERS_PER_DEGREE_LAT;
+
+    let mut encounters: BTreeMap<(String, String), Encounter> = BTreeMap::new();
+    for (i, a) in sorted.iter().enumerate() {
+        for b in sorted[i + 1..]
+            .iter()
+            .take_while(|b| b.observed_at - a.observed_at <= window)
+        {
+            if a.asset == b.asset || (a.latitude - b.latitude).abs() > max_dlat {
+                continue;
+            }
+            let distance = distance_m((a.latitude, a.longitude), (b.latitude, b.longitude));
+            if distance > max_distance_m {
+                continue;
+            }
+            let (first, second) = if a.asset < b.asset { (a, b) } else { (b, a) };
+            let closest_at = a.observed_at + (b.observed_at - a.observed_at) / 2;
+            let (latitude, longitude) = (
+                (a.latitude + b.latitude) / 2.0,
+                (a.longitude + b.longitude) / 2.0,
+            );
+            encounters
+                .entry((first.asset.clone(), second.asset.clone()))
+                .and_modify(|e| {
+                    e.started_at = e.started_at.min(a.observed_at);
+                    e.ended_at = e.ended_at.max(b.observed_at);
+                    e.reports += 1;
+                    if distance < e.min_distance_m {
+                        e.min_distance_m = distance;
+                        e.closest_at = closest_at;
+                        e.latitude = latitude;
+                        e.longitude = longitude;
+                    }
+                })
+                .or_insert_with(|| Encounter {
+                    assets: (first.asset.clone(), second.asset.clone()),
+                    started_at: a.observed_at,
+                    ended_at: b.observed_at,
+                    closest_at,
+                    min_distance_m: distance,
+                    latitude,
+                    longitude,
+                    reports: 1,
+                });
+        }
+    }
+    encounters.into_values().collect()
+}
+
+/// A Location whose surroundings are watched for tracked assets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedLocation {
+    pub entity_id: Uuid,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// An asset reported within the distance of a [`WatchedLocation`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationVisit {
+    pub asset: String,
+    pub location_id: Uuid,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub min_distance_m: f64,
+    pub reports: usize,
+}
+
+/// Every asset reported at most `max_distance_m` from a watched location,
+/// one [`LocationVisit`] per asset and location.
+pub fn detect_visits(
+    positions: &[PositionReport],
+    locations: &[WatchedLocation],
+    max_distance_m: f64,
+) -> Vec<LocationVisit> {
+    let mut visits: BTreeMap<(String, Uuid), LocationVisit> = BTreeMap::new();
+    for position in positions {
+        for location in locations {
+            let distance = distance_m(
+                (position.latitude, position.longitude),
+                (location.latitude, location.longitude),
+            );
+            if distance > max_distance_m {
+                continue;
+            }
+            visits
+                .entry((position.asset.clone(), location.entity_id))
+                .and_modify(|v| {
+                    v.first_seen_at = v.first_seen_at.min(position.observed_at);
+                    v.last_seen_at = v.last_seen_at.max(position.observed_at);
+                    v.min_distance_m = v.min_distance_m.min(distance);
+                    v.reports += 1;
+                })
+                .or_insert_with(|| LocationVisit {
+                    asset: position.asset.clone(),
+                    location_id: location.entity_id,
+                    first_seen_at: position.observed_at,
+                    last_seen_at: position.observed_at,
+                    min_distance_m: distance,
+                    reports: 1,
+                });
+        }
+    }
+    visits.into_values().collect()
+}
+
+/// An asset's stay at a port or airport, from its first report inside the
+/// place to its first report outside again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arrival<'a> {
+    pub asset: String,
+    pub place: &'a Place,
+    pub arrived_at: DateTime<Utc>,
+    /// `None` while the asset's latest report is still inside.
+    pub departed_at: Option<DateTime<Utc>>,
+    pub reports: usize,
+}
+
+/// A port stay in progress, with the first and last of its stopped reports.
+type Stay<'a> = (Arrival<'a>, Option<(DateTime<Utc>, DateTime<Utc>)>);
+
+/// Vessel stays in a port with stopped reports spanning at least
+/// `min_stop`. A stay counts only when the arrival itself was seen, i.e.
+/// the vessel was reported elsewhere first; vessels already inside when
+/// their track begins are left out.
+pub fn detect_port_calls<'a>(
+    positions: &[PositionReport],
+    ports: &'a [Place],
+    min_stop: Duration,
+) -> Vec<Arrival<'a>> {
+    tracks_of(positions, EntityType::Vessel)
+        .iter()
+        .flat_map(|track| port_calls_of(track, ports, min_stop))
+        .collect()
+}
+
+/// [`detect_port_calls`] for one vessel's time-ordered track.
+fn port_calls_of<'a>(
+    track: &[&PositionReport],
+    ports: &'a [Place],
+    min_stop: Duration,
+) -> Vec<Arrival<'a>> {
+    let mut calls = Vec::new();
+    let mut current: Option<Stay<'a>> = None;
+    let finish = |current: Option<Stay<'a>>, departed_at, calls: &mut Vec<Arrival<'a>>| {
+        if let Some((mut call, Some((first, last)))) = current {
+            if last - first >= min_stop {
+                call.departed_at = departed_at;
+                calls.push(call);
+            }
+        }
+    };
+    for (i, report) in track.iter().enumerate() {
+        let port = ports
+            .iter()
+            .find(|p| p.contains(report.latitude, report.longitude));
+        let stopped = report
+            .speed_mps
+            .is_some_and(|speed| speed <= STOP_SPEED_MPS);
+        match (&mut current, port) {
+            (Some((call, stops)), Some(port)) if std::ptr::eq(call.place, port) => {
+                call.reports += 1;
+                if stopped {
+                    let first = stops.map_or(report.observed_at, |(first, _)| first);
+                    *stops = Some((first, report.observed_at));
+                }
+            }
+            _ => {
+                finish(current.take(), Some(report.observed_at), &mut calls);
+                if let (Some(place), true) = (port, i > 0) {
+                    let call = Arrival {
+                        asset: report.asset.clone(),
+                        place,
+                        arrived_at: report.observed_at,
+                        departed_at: None,
+                        reports: 1,
+                    };
+                    current = Some((
+                        call,
+                        stopped.then_some((report.observed_at, report.observed_at)),
+                    ));
+                }
+            }
+        }
+    }
+    finish(current, None, &mut calls);
+    calls
+}
+
+/// Aircraft reported on the ground at an airport right after a report in
+/// the air; the stay ends with the next airborne report. Aircraft without
+/// the ground flag are left out.
+pub fn detect_landings<'a>(
+    positions: &[PositionReport],
+    airports: &'a [Place],
+) -> Vec<Arrival<'a>> {
+    let mut landings = Vec::new();
+    for track in tracks_of(positions, EntityType::Aircraft) {
+        let mut current: Option<Arrival<'a>> = None;
+        let mut airborne = false;
+        for report in track {
+            match report.on_ground {
+                Some(true) => {
+                    if let Some(landing) = &mut current {
+                        landing.reports += 1;
+                    } else if airborne {
+                        current = airports
+                            .iter()
+                            .find(|a| a.contains(report.latitude, report.longitude))
+                            .map(|place| Arrival {
+                                asset: report.asset.clone(),
+                                place,
+                                arrived_at: report.observed_at,
+                                departed_at: None,
+                                reports: 1,
+                            });
+                    }
+                    airborne = false;
+                }
+                Some(false) => {
+                    if let Some(mut landing) = current.take() {
+                        landing.departed_at = Some(report.observed_at);
+                        landings.push(landing);
+                    }
+                    airborne = true;
+                }
+                None => {}
+            }
+        }
+        landings.extend(current);
+    }
+    landings
+}
+
+/// Points kept in a flight's track; longer tracks are thinned evenly.
+pub const MAX_TRACK_POINTS: usize = 200;
+
+/// One aircraft's flight, from takeoff to landing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flight<'a> {
+    pub asset: String,
+    /// The first airborne report.
+    pub departed_at: DateTime<Utc>,
+    /// The airport of the ground report before takeoff, when it was seen.
+    pub origin: Option<&'a Place>,
+    /// The first ground report after takeoff; `None` while the aircraft is
+    /// airborne or after it went unreported for too long.
+    pub landed_at: Option<DateTime<Utc>>,
+    pub destination: Option<&'a Place>,
+    pub last_seen_at: DateTime<Utc>,
+    /// `(latitude, longitude)` in time order, at most [`MAX_TRACK_POINTS`].
+    pub track: Vec<(f64, f64)>,
+    pub reports: usize,
+}
+
+impl Flight<'_> {
+    fn extend(&mut self, report: &PositionReport) {
+        self.last_seen_at = report.observed_at;
+        self.track.push((report.latitude, report.longitude));
+        self.reports += 1;
+    }
+}
+
+/// Reconstruct flights from aircraft reports with the ground flag. A flight
+/// starts with an airborne report right after a ground report (a takeoff)
+/// or after `max_gap` without reports, and ends on the next ground report
+/// (a landing) or when the aircraft goes unreported for longer than
+/// `max_gap`. Tracks that begin in the air are left out until the aircraft
+/// next lands, as their takeoff was not seen.
+pub fn detect_flights<'a>(
+    positions: &[PositionReport],
+    airports: &'a [Place],
+    max_gap: Duration,
+) -> Vec<Flight<'a>> {
+    let airport_at = |report: &PositionReport| {
+        airports
+            .iter()
+            .find(|a| a.contains(report.latitude, report.longitude))
+    };
+    let mut flights = Vec::new();
+    for track in tracks_of(positions, EntityType::Aircraft) {
+        let mut current: Option<Flight<'a>> = None;
+        let mut previous: Option<&PositionReport> = None;
+        for report in track {
+            let silent = previous.is_some_and(|p| report.observed_at - p.observed_at > max_gap);
+            if silent {
+                flights.extend(current.take());
+            }
+            match (report.on_ground, &mut current) {
+                (Some(true), Some(flight)) => {
+                    flight.extend(report);
+                    flight.landed_at = Some(report.observed_at);
+                    flight.destination = airport_at(report);
+                    flights.extend(current.take());
+                }
+                (_, Some(flight)) => flight.extend(report),
+                (Some(false), None) => {
+                    let took_off = previous.is_some_and(|p| p.on_ground == Some(true));
+                    if took_off || silent {
+                        current = Some(Flight {
+                            asset: report.asset.clone(),
+                            departed_at: report.observed_at,
+                            origin: previous
+                                .filter(|_| took_off && !silent)
+                                .and_then(airport_at),
+                            landed_at: None,
+                            destination: None,
+                            last_seen_at: report.observed_at,
+                            track: vec![(report.latitude, report.longitude)],
+                            reports: 1,
+                        });
+                    }
+                }
+                _ => {}
+            }
+            previous = Some(report);
+        }
+        flights.extend(current);
+    }
+    for flight in &mut flights {
+        flight.track = thin(std::mem::take(&mut flight.track), MAX_TRACK_POINTS);
+    }
+    flights
+}
+
+/// One vessel's passage from a port call to the next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Voyage<'a> {
+    pub asset: String,
+    pub origin: &'a Place,
+    /// The first report outside the origin port.
+    pub departed_at: DateTime<Utc>,
+    /// The port of the next call; `None` while the vessel is still at sea.
+    pub destination: Option<&'a Place>,
+    pub arrived_at: Option<DateTime<Utc>>,
+    /// The last destination the vessel declared on the way.
+    pub declared_destination: Option<String>,
+    /// `(last report before, first report after)` for every silence longer
+    /// than the gap threshold.
+    pub gaps: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    pub last_seen_at: DateTime<Utc>,
+    /// `(latitude, longitude)` in time order, at most [`MAX_TRACK_POINTS`].
+    pub track: Vec<(f64, f64)>,
+    pub reports: usize,
+}
+
+impl Voyage<'_> {
+    /// Departure to arrival, or to the latest report while at sea.
+    pub fn duration(&self) -> Duration {
+        self.arrived_at.unwrap_or(self.last_seen_at) - self.departed_at
+    }
+
+    /// Whether the vessel arrived somewhere other than it declared. Unknown
+    /// while at sea or when it declared nothing.
+    pub fn destination_mismatch(&self) -> bool {
+        match (self.destination, &self.declared_destination) {
+            (Some(place), Some(declared)) => !declares(declared, place),
+            _ => false,
+        }
+    }
+}
+
+/// Whether a free-text AIS destination names `place`: its UN/LOCODE, with
+/// or without the space (`NLRTM`, `NL RTM`), or a word of its name
+/// (`ROTTERDAM`). Only the part after the last `>` counts, as crews often
+/// enter `origin>destination`.
+pub fn declares(declared: &str, place: &Place) -> bool {
+    let declared = declared
+        .rsplit('>')
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    let compact: String = declared
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    if compact == place.code {
+        return true;
+    }
+    let words: Vec<&str> = declared
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .collect();
+    place
+        .name
+        .to_uppercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| word.len() >= 4 && *word != "PORT")
+        .any(|word| words.contains(&word))
+}
+
+/// Reconstruct voyages between the port calls [`detect_port_calls`] finds.
+/// A voyage runs from the first report outside one call's port to the
+/// arrival of the next call; after the last call it stays open with no
+/// destination. Silences over `max_gap` are recorded as gaps.
+pub fn detect_voyages<'a>(
+    positions: &[PositionReport],
+    ports: &'a [Place],
+    min_stop: Duration,
+    max_gap: Duration,
+) -> Vec<Voyage<'a>> {
+    let mut voyages = Vec::new();
+    for track in tracks_of(positions, EntityType::Vessel) {
+        let calls = port_calls_of(&track, ports, min_stop);
+        for (i, call) in calls.iter().enumerate() {
+            let Some(departed_at) = call.departed_at else {
+                continue;
+            };
+            let next = calls.get(i + 1);
+            let until = next.map(|n| n.arrived_at);
+            let reports: Vec<&PositionReport> = track
+                .iter()
+                .copied()
+                .filter(|r| {
+                    r.observed_at >= departed_at && until.is_none_or(|until| r.observed_at <= until)
+                })
+                .collect();
+            let Some(last) = reports.last() else {
+                continue;
+            };
+            voyages.push(Voyage {
+                asset: call.asset.clone(),
+                origin: call.place,
+                departed_at,
+                destination: next.map(|n| n.place),
+                arrived_at: until,
+                declared_destination: reports.iter().rev().find_map(|r| r.destination.clone()),
+                gaps: reports
+                    .windows(2)
+                    .filter(|pair| pair[1].observed_at - pair[0].observed_at > max_gap)
+                    .map(|pair| (pair[0].observed_at, pair[1].observed_at))
+                    .collect(),
+                last_seen_at: last.observed_at,
+                track: thin(
+                    reports.iter().map(|r| (r.latitude, r.longitude)).collect(),
+                    MAX_TRACK_POINTS,
+                ),
+                reports: reports.len(),
+            });
+        }
+    }
+    voyages
+}
+
+/// At most `max` points, evenly spaced and keeping both ends.
+fn thin(points: Vec<(f64, f64)>, max: usize) -> Vec<(f64, f64)> {
+    if points.len() <= max || max < 2 {
+        return points;
+    }
+    let last = points.len() - 1;
+    (0..max).map(|i| points[i * last / (max - 1)]).collect()
+}
+
+/// Each asset's reports of one type, in time order.
+fn tracks_of(positions: &[PositionReport], entity_type: EntityType) -> Vec<Vec<&PositionReport>> {
+    let mut tracks: BTreeMap<&str, Vec<&PositionReport>> = BTreeMap::new();
+    for position in positions.iter().filter(|p| p.entity_type == entity_type) {
+        tracks.entry(&position.asset).or_default().push(position);
+    }
+    tracks
+        .into_values()
+        .map(|mut track| {
+            track.sort_by_key(|p| p.observed_at);
+            track
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn report(asset: &str, latitude: f64, longitude: f64, minute: u32) -> PositionReport {
+        PositionReport {
+            asset: asset.to_string(),
+            entity_type: EntityType::Vessel,
+            source: "ais".to_string(),
+            latitude,
+            longitude,
+            observed_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, minute, 0).unwrap(),
+            speed_mps: None,
+            course: None,
+            altitude_m: None,
+            on_ground: None,
+            destination: None,
+        }
+    }
+
+    #[test]
+    fn distance_matches_known_points() {
+        // One degree of latitude, and Berlin to Paris
+        assert!((distance_m((0.0, 0.0), (1.0, 0.0)) - 111_195.0).abs() < 10.0);
+        assert!((distance_m((52.52, 13.405), (48.8566, 2.3522)) - 877_500.0).abs() < 2_000.0);
+    }
+
+    #[test]
+    fn encounters_need_both_closeness_and_overlap() {
+        let positions = vec![
+            report("mmsi:1", 36.0, -5.5, 0),
+            report("mmsi:2", 36.001, -5.5, 10),
+            report("mmsi:2", 36.0005, -5.5, 12),
+            // Close, but long after mmsi:1 was there
+            report("mmsi:3", 36.0, -5.5, 59),
+            // Same time, too far
+            report("mmsi:4", 36.1, -5.5, 0),
+            // An asset never meets itself
+            report("mmsi:1", 36.0, -5.5, 5),
+        ];
+
+        let encounters = detect_encounters(&positions, 200.0, Duration::minutes(15));
+        assert_eq!(encounters.len(), 1, "{encounters:?}");
+        let e = &encounters[0];
+        assert_eq!(e.assets, ("mmsi:1".to_string(), "mmsi:2".to_string()));
+        assert_eq!(e.reports, 4);
+        assert_eq!(e.started_at, positions[0].observed_at);
+        assert_eq!(e.ended_at, positions[2].observed_at);
+        assert!(
+            (e.min_distance_m - 55.6).abs() < 1.0,
+            "{}",
+            e.min_distance_m
+        );
+    }
+
+    #[test]
+    fn positions_come_from_asset_documents_with_valid_coordinates() {
+        let doc = |identifiers: &[(&str, &str)], metadata: Value| RawDocument {
+            source: "ais".to_string(),
+            source_id: "244660000".to_string(),
+            title: None,
+            content: String::new(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: None,
+            identifiers: identifiers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            metadata,
+            structured: None,
+        };
+        let vessel = doc(
+            &[("mmsi", "244660000"), ("imo", "9811000")],
+            serde_json::json!({"latitude": 51.9, "longitude": 4.1, "speed_over_ground": 10.0}),
+        );
+        let report = PositionReport::from_document(&vessel).unwrap();
+        assert_eq!(report.asset, "mmsi:244660000");
+        assert_eq!(report.entity_type, EntityType::Vessel);
+        assert!((report.speed_mps.unwrap() - 5.144).abs() < 0.01);
+
+        let unavailable = doc(
+            &[("mmsi", "1")],
+            serde_json::json!({"latitude": 91.0, "longitude": 181.0}),
+        );
+        assert_eq!(PositionReport::from_document(&unavailable), None);
+        let no_asset = doc(
+            &[("lei", "X")],
+            serde_json::json!({"latitude": 1.0, "longitude": 1.0}),
+        );
+        assert_eq!(PositionReport::from_document(&no_asset), None);
+    }
+
+    #[test]
+    fn port_calls_need_a_seen_arrival_and_a_long_enough_stop() {
+        let ports = crate::places::PORTS;
+        let at =
+            |asset: &str, latitude: f64, longitude: f64, minute: u32, speed: f64| PositionReport {
+                speed_mps: Some(speed),
+                ..report(asset, latitude, longitude, minute)
+            };
+        let positions = vec![
+            // Approaches Rotterdam, stops for 40 minutes, leaves
+            at("mmsi:1", 52.05, 3.90, 0, 6.0),
+            at("mmsi:1", 51.95, 4.05, 5, 0.1),
+            at("mmsi:1", 51.95, 4.05, 45, 0.2),
+            at("mmsi:1", 52.05, 3.90, 55, 6.0),
+            // Already in port when its track begins
+            at("mmsi:2", 51.95, 4.05, 0, 0.0),
+            at("mmsi:2", 51.95, 4.05, 50, 0.0),
+            // Passes through without stopping long enough
+            at("mmsi:3", 52.05, 3.90, 0, 6.0),
+            at("mmsi:3", 51.95, 4.05, 10, 0.3),
+            at("mmsi:3", 51.95, 4.10, 20, 5.0),
+        ];
+
+        let calls = detect_port_calls(&positions, ports, Duration::minutes(30));
+        assert_eq!(calls.len(), 1, "{calls:?}");
+        let call = &calls[0];
+        assert_eq!((call.asset.as_str(), call.place.code), ("mmsi:1", "NLRTM"));
+        assert_eq!(call.arrived_at, positions[1].observed_at);
+        assert_eq!(call.departed_at, Some(positions[3].observed_at));
+        assert_eq!(call.reports, 2);
+    }
+
+    #[test]
+    fn landings_follow_an_airborne_report() {
+        let airports = crate::places::AIRPORTS;
+        let at = |asset: &str, latitude: f64, longitude: f64, minute: u32, on_ground: bool| {
+            PositionReport {
+                entity_type: EntityType::Aircraft,
+                on_ground: Some(on_ground),
+                ..report(asset, latitude, longitude, minute)
+            }
+        };
+        let positions = vec![
+            at("icao24:a", 52.40, 4.70, 0, false),
+            at("icao24:a", 52.31, 4.76, 6, true),
+            at("icao24:a", 52.30, 4.77, 20, true),
+            // Parked all along: no landing seen
+            at("icao24:b", 52.31, 4.76, 0, true),
+            at("icao24:b", 52.31, 4.76, 30, true),
+            // Lands away from any known airport
+            at("icao24:c", 48.00, 11.00, 0, false),
+            at("icao24:c", 48.01, 11.01, 5, true),
+        ];
+
+        let landings = detect_landings(&positions, airports);
+        assert_eq!(landings.len(), 1, "{landings:?}");
+        assert_eq!(
+            (landings[0].asset.as_str(), landings[0].place.code),
+            ("icao24:a", "EHAM")
+        );
+        assert_eq!(landings[0].arrived_at, positions[1].observed_at);
+        assert_eq!((landings[0].departed_at, landings[0].reports), (None, 2));
+    }
+
+    #[test]
+    fn flights_run_from_takeoff_to_landing() {
+        let airports = crate::places::AIRPORTS;
+        let at = |asset: &str, latitude: f64, longitude: f64, minute: u32, on_ground: bool| {
+            PositionReport {
+                entity_type: EntityType::Aircraft,
+                on_ground: Some(on_ground),
+                ..report(asset, latitude, longitude, minute)
+            }
+        };
+        let positions = vec![
+            // Schiphol to Frankfurt
+            at("icao24:a", 52.31, 4.76, 0, true),
+            at("icao24:a", 52.20, 5.00, 2, false),
+            at("icao24:a", 51.00, 7.00, 20, false),
+            at("icao24:a", 50.04, 8.56, 45, true),
+            at("icao24:a", 50.04, 8.57, 50, true),
+            // Already airborne when first seen, then lands: takeoff not seen
+            at("icao24:b", 51.00, 3.00, 0, false),
+            at("icao24:b", 50.90, 4.48, 10, true),
+            // Takes off, drops out of coverage, reappears in the air
+            at("icao24:c", 49.01, 2.55, 0, true),
+            at("icao24:c", 49.10, 2.70, 3, false),
+            at("icao24:c", 45.00, 5.00, 50, false),
+            at("icao24:c", 44.00, 6.00, 55, false),
+        ];
+
+        let flights = detect_flights(&positions, airports, Duration::minutes(30));
+        assert_eq!(flights.len(), 3, "{flights:?}");
+
+        let a = &flights[0];
+        assert_eq!(a.asset, "icao24:a");
+        assert_eq!(
+            (a.origin.map(|p| p.code), a.destination.map(|p| p.code)),
+            (Some("EHAM"), Some("EDDF"))
+        );
+        assert_eq!(a.departed_at, positions[1].observed_at);
+        assert_eq!(a.landed_at, Some(positions[3].observed_at));
+        assert_eq!((a.reports, a.track.len()), (3, 3));
+
+        let (lost, resumed) = (&flights[1], &flights[2]);
+        assert_eq!(
+            (lost.origin.map(|p| p.code), lost.landed_at),
+            (Some("LFPG"), None)
+        );
+        assert_eq!(lost.last_seen_at, positions[8].observed_at);
+        assert_eq!(
+            (resumed.origin, resumed.departed_at),
+            (None, positions[9].observed_at)
+        );
+        assert_eq!((resumed.landed_at, resumed.reports), (None, 2));
+    }
+
+    #[test]
+    fn voyages_run_between_port_calls() {
+        let ports = crate::places::PORTS;
+        let at = |latitude: f64, longitude: f64, minute: u32, speed: f64, destination: &str| {
+            PositionReport {
+                speed_mps: Some(speed),
+                destination: (!destination.is_empty()).then(|| destination.to_string()),
+                ..report("mmsi:1", latitude, longitude, minute)
+            }
+        };
+        let positions = vec![
+            // Calls at Rotterdam, crosses to Hamburg with a silence on the way
+            at(52.05, 3.90, 0, 6.0, ""),
+            at(51.95, 4.05, 1, 0.1, "HAMBURG"),
+            at(51.95, 4.05, 40, 0.1, "HAMBURG"),
+            at(52.50, 5.00, 42, 6.0, "HAMBURG"),
+            at(53.60, 8.00, 50, 6.0, "DE HAM"),
+            at(53.52, 9.90, 51, 0.2, "DE HAM"),
+            at(53.52, 9.90, 59, 0.2, "DE HAM"),
+        ];
+
+        let voyages = detect_voyages(
+            &positions,
+            ports,
+            Duration::minutes(5),
+            Duration::minutes(5),
+        );
+        assert_eq!(voyages.len(), 1, "{voyages:?}");
+        let v = &voyages[0];
+        assert_eq!(
+            (v.origin.code, v.destination.map(|p| p.code)),
+            ("NLRTM", Some("DEHAM"))
+        );
+        assert_eq!(
+            (v.departed_at, v.arrived_at),
+            (positions[3].observed_at, Some(positions[5].observed_at))
+        );
+        assert_eq!(v.duration(), Duration::minutes(9));
+        assert_eq!(
+            v.gaps,
+            vec![(positions[3].observed_at, positions[4].observed_at)]
+        );
+        assert_eq!(
+            (v.declared_destination.as_deref(), v.reports),
+            (Some("DE HAM"), 3)
+        );
+        assert!(!v.destination_mismatch());
+    }
+
+    #[test]
+    fn declared_destinations_match_codes_and_names() {
+        let rotterdam = crate::places::by_code("NLRTM").unwrap();
+        assert!(declares("NL RTM", rotterdam));
+        assert!(declares("rotterdam", rotterdam));
+        assert!(declares("BEANR>NLRTM", rotterdam));
+        assert!(!declares("NLRTM>BEANR", rotterdam));
+        assert!(!declares("PORT SAID", rotterdam));
+        assert!(!declares("FOR ORDERS", rotterdam));
+    }
+
+    #[test]
+    fn long_tracks_are_thinned_keeping_both_ends() {
+        let points: Vec<(f64, f64)> = (0..1000).map(|i| (i as f64, 0.0)).collect();
+        let thinned = thin(points, MAX_TRACK_POINTS);
+        assert_eq!(thinned.len(), MAX_TRACK_POINTS);
+        assert_eq!(
+            (thinned[0].0, thinned[MAX_TRACK_POINTS - 1].0),
+            (0.0, 999.0)
+        );
+    }
+}
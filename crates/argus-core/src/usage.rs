@@ -0,0 +1,124 @@
+//! API usage per consumer, counted per UTC day so cost can be attributed
+//! to whoever holds each token.
+//!
+//! Consumers are told apart by a hash of the bearer token they send, never
+//! the token itself; requests without one count as [`ANONYMOUS`].
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Days of usage kept; older days are dropped.
+pub const USAGE_RETENTION_DAYS: u32 = 30;
+
+/// Consumer name for requests without a bearer token.
+pub const ANONYMOUS: &str = "anonymous";
+
+/// Distinct consumers tracked per day. Tokens are not validated, so each
+/// new one would otherwise be stored; once a day is full, further new
+/// consumers are counted together as [`OTHER_CONSUMERS`].
+pub const MAX_CONSUMERS_PER_DAY: usize = 1000;
+
+/// Consumer name for new consumers beyond [`MAX_CONSUMERS_PER_DAY`].
+pub const OTHER_CONSUMERS: &str = "other";
+
+/// What one request adds to its consumer's usage.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageRecord {
+    /// Method and route template, e.g. `GET /api/entities/{id}`.
+    pub endpoint: String,
+    /// Reasoning input plus output tokens spent answering the request.
+    pub reasoning_tokens: u64,
+    /// Entities and relationships the request exported.
+    pub exported_records: u64,
+}
+
+/// A consumer's usage over one or more days.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageCounts {
+    pub requests: u64,
+    /// Requests per endpoint, keyed as in [`UsageRecord::endpoint`].
+    pub endpoints: BTreeMap<String, u64>,
+    pub reasoning_tokens: u64,
+    pub exported_records: u64,
+}
+
+impl UsageCounts {
+    pub fn add(&mut self, record: &UsageRecord) {
+        self.requests += 1;
+        *self.endpoints.entry(record.endpoint.clone()).or_default() += 1;
+        self.reasoning_tokens += record.reasoning_tokens;
+        self.exported_records += record.exported_records;
+    }
+
+    pub fn merge(&mut self, other: &UsageCounts) {
+        self.requests += other.requests;
+        for (endpoint, count) in &other.endpoints {
+            *self.endpoints.entry(endpoint.clone()).or_default() += count;
+        }
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.exported_records += other.exported_records;
+    }
+}
+
+/// The name to count `consumer` under on a day that already tracks
+/// `tracked` consumers, `known` saying whether it is one of them.
+pub fn tracked_consumer(consumer: &str, known: bool, tracked: usize) -> &str {
+    if known || tracked < MAX_CONSUMERS_PER_DAY {
+        consumer
+    } else {
+        OTHER_CONSUMERS
+    }
+}
+
+/// The UTC day usage is counted against.
+pub fn usage_day(now: DateTime<Utc>) -> NaiveDate {
+    now.date_naive()
+}
+
+/// The first day still kept on `today`.
+pub fn oldest_kept_day(today: NaiveDate) -> NaiveDate {
+    today - chrono::Duration::days(USAGE_RETENTION_DAYS as i64 - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_add_up_per_endpoint() {
+        let mut counts = UsageCounts::default();
+        let record = |endpoint: &str, reasoning_tokens, exported_records| UsageRecord {
+            endpoint: endpoint.to_string(),
+            reasoning_tokens,
+            exported_records,
+        };
+        counts.add(&record("POST /api/reasoning/query", 1200, 0));
+        counts.add(&record("POST /api/export", 0, 40));
+
+        let mut other = UsageCounts::default();
+        other.add(&record("POST /api/export", 0, 2));
+        counts.merge(&other);
+
+        assert_eq!(counts.requests, 3);
+        assert_eq!(counts.endpoints["POST /api/export"], 2);
+        assert_eq!(
+            (counts.reasoning_tokens, counts.exported_records),
+            (1200, 42)
+        );
+    }
+
+    #[test]
+    fn new_consumers_beyond_the_daily_cap_share_one_name() {
+        assert_eq!(tracked_consumer("token:a", false, 0), "token:a");
+        assert_eq!(
+            tracked_consumer("token:a", true, MAX_CONSUMERS_PER_DAY),
+            "token:a"
+        );
+        assert_eq!(
+            tracked_consumer("token:b", false, MAX_CONSUMERS_PER_DAY),
+            OTHER_CONSUMERS
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+
+/// A single vector with its payload, keyed by the entity id it embeds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorPoint {
+    pub id: Uuid,
+    pub vector: Vec<f32>,
+    pub payload: serde_json::Value,
+}
+
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    fn model(&self) -> &str;
+    /// Embed each text, returning vectors in input order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// Create the backing collection if it does not exist yet.
+    async fn ensure_collection(&self, dimensions: usize) -> Result<()>;
+    async fn upsert(&self, points: &[VectorPoint]) -> Result<()>;
+}
@@ -0,0 +1,358 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use argus_core::config::AppConfig;
+use argus_core::entity::ExtractionResult;
+use argus_core::error::{ArgusError, Result};
+use argus_core::extraction::{CacheStats, ExtractionCache};
+
+const REDIS_KEY_PREFIX: &str = "argus:extraction:";
+
+/// Hash the exact model input so any change to the model, prompt or
+/// document invalidates the entry.
+pub fn cache_key(model: &str, system_prompt: &str, user_prompt: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [model, system_prompt, user_prompt] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Build the cache selected by `EXTRACTION_CACHE`, or `None` when disabled.
+pub fn from_config(config: &AppConfig) -> Option<Arc<dyn ExtractionCache>> {
+    let ttl = Duration::from_secs(config.extraction_cache_ttl_seconds);
+    match config.extraction_cache.to_lowercase().as_str() {
+        "none" | "off" | "" => None,
+        "redis" => match RedisExtractionCache::new(&config.redis_url, ttl) {
+            Ok(cache) => Some(Arc::new(cache)),
+            Err(e) => {
+                tracing::warn!(error = %e, "Invalid REDIS_URL, extraction cache disabled");
+                None
+            }
+        },
+        "disk" => Some(Arc::new(DiskExtractionCache::new(
+            &config.extraction_cache_dir,
+            ttl,
+        ))),
+        other => {
+            tracing::warn!(
+                backend = other,
+                "Unknown EXTRACTION_CACHE backend, extraction cache disabled"
+            );
+            None
+        }
+    }
+}
+
+/// Lock-free usage counters shared by every backend.
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    writes: AtomicU64,
+    expired: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl Counters {
+    fn bump(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self, backend: &str, ttl: Duration) -> CacheStats {
+        CacheStats {
+            backend: backend.to_string(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            ttl_seconds: ttl.as_secs(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    stored_at: DateTime<Utc>,
+    result: ExtractionResult,
+}
+
+fn is_fresh(stored_at: DateTime<Utc>, ttl: Duration, now: DateTime<Utc>) -> bool {
+    match chrono::Duration::from_std(ttl) {
+        Ok(ttl) => now - stored_at <= ttl,
+        Err(_) => true,
+    }
+}
+
+/// One JSON file per entry under `<dir>/<first two hex chars>/<key>.json`.
+pub struct DiskExtractionCache {
+    dir: PathBuf,
+    ttl: Duration,
+    counters: Counters,
+}
+
+impl DiskExtractionCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+            counters: Counters::default(),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        let shard = key.get(..2).unwrap_or("00");
+        self.dir.join(shard).join(format!("{key}.json"))
+    }
+}
+
+#[async_trait]
+impl ExtractionCache for DiskExtractionCache {
+    async fn get(&self, key: &str) -> Result<Option<ExtractionResult>> {
+        let path = self.path_for(key);
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Counters::bump(&self.counters.misses);
+                return Ok(None);
+            }
+            Err(e) => {
+                Counters::bump(&self.counters.errors);
+                return Err(ArgusError::Cache(format!(
+                    "Failed to read {}: {e}",
+                    path.display()
+                )));
+            }
+        };
+
+        let entry: DiskEntry = match serde_json::from_slice(&bytes) {
+            Ok(entry) => entry,
+            Err(e) => {
+                // A truncated or outdated entry is just a miss; drop it
+                tracing::debug!(error = %e, path = %path.display(), "Discarding unreadable cache entry");
+                let _ = tokio::fs::remove_file(&path).await;
+                Counters::bump(&self.counters.misses);
+                return Ok(None);
+            }
+        };
+
+        if !is_fresh(entry.stored_at, self.ttl, Utc::now()) {
+            let _ = tokio::fs::remove_file(&path).await;
+            Counters::bump(&self.counters.expired);
+            Counters::bump(&self.counters.misses);
+            return Ok(None);
+        }
+
+        Counters::bump(&self.counters.hits);
+        Ok(Some(entry.result))
+    }
+
+    async fn put(&self, key: &str, result: &ExtractionResult) -> Result<()> {
+        let path = self.path_for(key);
+        let entry = DiskEntry {
+            stored_at: Utc::now(),
+            result: result.clone(),
+        };
+        let bytes = serde_json::to_vec(&entry)?;
+
+        let write = async {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            // Write then rename so readers never see a partial file
+            let tmp = path.with_extension("json.tmp");
+            tokio::fs::write(&tmp, &bytes).await?;
+            tokio::fs::rename(&tmp, &path).await
+        };
+
+        match write.await {
+            Ok(()) => {
+                Counters::bump(&self.counters.writes);
+                Ok(())
+            }
+            Err(e) => {
+                Counters::bump(&self.counters.errors);
+                Err(ArgusError::Cache(format!(
+                    "Failed to write {}: {e}",
+                    path.display()
+                )))
+            }
+        }
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.counters.snapshot("disk", self.ttl)
+    }
+}
+
+/// Entries stored as JSON strings with a Redis-side expiry, so several
+/// server instances can share one cache.
+pub struct RedisExtractionCache {
+    client: redis::Client,
+    connection: tokio::sync::OnceCell<redis::aio::ConnectionManager>,
+    ttl: Duration,
+    counters: Counters,
+}
+
+impl RedisExtractionCache {
+    pub fn new(url: &str, ttl: Duration) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| ArgusError::Config(format!("Invalid Redis URL: {e}")))?;
+        Ok(Self {
+            client,
+            connection: tokio::sync::OnceCell::new(),
+            ttl,
+            counters: Counters::default(),
+        })
+    }
+
+    async fn connection(&self) -> Result<redis::aio::ConnectionManager> {
+        self.connection
+            .get_or_try_init(|| redis::aio::ConnectionManager::new(self.client.clone()))
+            .await
+            .cloned()
+            .map_err(|e| ArgusError::Cache(format!("Failed to connect to Redis: {e}")))
+    }
+}
+
+#[async_trait]
+impl ExtractionCache for RedisExtractionCache {
+    async fn get(&self, key: &str) -> Result<Option<ExtractionResult>> {
+        let result: Result<Option<String>> = async {
+            let mut conn = self.connection().await?;
+            redis::AsyncCommands::get(&mut conn, format!("{REDIS_KEY_PREFIX}{key}"))
+                .await
+                .map_err(|e| ArgusError::Cache(format!("Redis GET failed: {e}")))
+        }
+        .await;
+
+        match result {
+            Ok(Some(json)) => match serde_json::from_str(&json) {
+                Ok(result) => {
+                    Counters::bump(&self.counters.hits);
+                    Ok(Some(result))
+                }
+                Err(e) => {
+                    tracing::debug!(error = %e, "Discarding unreadable cache entry");
+                    Counters::bump(&self.counters.misses);
+                    Ok(None)
+                }
+            },
+            Ok(None) => {
+                Counters::bump(&self.counters.misses);
+                Ok(None)
+            }
+            Err(e) => {
+                Counters::bump(&self.counters.errors);
+                Err(e)
+            }
+        }
+    }
+
+    async fn put(&self, key: &str, result: &ExtractionResult) -> Result<()> {
+        let json = serde_json::to_string(result)?;
+        let outcome: Result<()> = async {
+            let mut conn = self.connection().await?;
+            redis::AsyncCommands::set_ex(
+                &mut conn,
+                format!("{REDIS_KEY_PREFIX}{key}"),
+                json,
+                self.ttl.as_secs().max(1),
+            )
+            .await
+            .map_err(|e| ArgusError::Cache(format!("Redis SET failed: {e}")))
+        }
+        .await;
+
+        match outcome {
+            Ok(()) => Counters::bump(&self.counters.writes),
+            Err(_) => Counters::bump(&self.counters.errors),
+        }
+        outcome
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.counters.snapshot("redis", self.ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_result() -> ExtractionResult {
+        ExtractionResult {
+            entities: vec![],
+            relationships: vec![],
+            raw_source: "doc-1".to_string(),
+            extracted_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn cache_key_depends_on_every_input() {
+        let base = cache_key("model", "system", "user");
+        assert_eq!(base.len(), 64);
+        assert_eq!(base, cache_key("model", "system", "user"));
+        assert_ne!(base, cache_key("other", "system", "user"));
+        assert_ne!(base, cache_key("model", "system2", "user"));
+        assert_ne!(base, cache_key("model", "system", "user2"));
+        // Separators keep shifted boundaries from colliding
+        assert_ne!(cache_key("ab", "c", ""), cache_key("a", "bc", ""));
+    }
+
+    #[test]
+    fn freshness_respects_ttl() {
+        let now = Utc::now();
+        let ttl = Duration::from_secs(60);
+        assert!(is_fresh(now - chrono::Duration::seconds(30), ttl, now));
+        assert!(!is_fresh(now - chrono::Duration::seconds(90), ttl, now));
+    }
+
+    #[tokio::test]
+    async fn disk_cache_roundtrip_and_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskExtractionCache::new(dir.path(), Duration::from_secs(3600));
+        let key = cache_key("m", "s", "u");
+
+        assert!(cache.get(&key).await.unwrap().is_none());
+        cache.put(&key, &sample_result()).await.unwrap();
+        let cached = cache
+            .get(&key)
+            .await
+            .unwrap()
+            .expect("entry should be cached");
+        assert_eq!(cached.raw_source, "doc-1");
+
+        let stats = cache.stats();
+        assert_eq!(stats.backend, "disk");
+        assert_eq!((stats.hits, stats.misses, stats.writes), (1, 1, 1));
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn disk_cache_treats_corrupt_entry_as_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskExtractionCache::new(dir.path(), Duration::from_secs(3600));
+        let key = cache_key("m", "s", "u");
+        let path = cache.path_for(&key);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"{not json").unwrap();
+
+        assert!(cache.get(&key).await.unwrap().is_none());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,1129 @@
+//! Country and jurisdiction codes resolved to canonical Location entities.
+//!
+//! Sources name countries in different schemes: GDELT actors carry CAMEO
+//! (ISO 3166 alpha-3) codes and event locations FIPS 10-4 codes, AIS MMSIs
+//! start with an ITU Maritime Identification Digit (MID), and OpenCorporates
+//! jurisdictions are lowercase alpha-2 codes with an optional subdivision
+//! ("us_de"). All of them resolve to one Location per country whose id is
+//! derived from its alpha-2 code, so every source and run links to the same
+//! node.
+
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use argus_core::agent::RawDocument;
+use argus_core::entity::{Entity, EntityType, ExtractionResult, RelationType, Relationship};
+
+use crate::normalize::normalize_name;
+
+/// Namespace for canonical country ids.
+const ID_NAMESPACE: Uuid = Uuid::from_u128(0x3f6c_2b1d_8a4e_4c7f_9d05_e1a2_b3c4_d5e6);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Country {
+    pub iso2: &'static str,
+    pub iso3: &'static str,
+    /// FIPS 10-4 code; empty where FIPS has none.
+    pub fips: &'static str,
+    pub name: &'static str,
+}
+
+const fn c(
+    iso2: &'static str,
+    iso3: &'static str,
+    fips: &'static str,
+    name: &'static str,
+) -> Country {
+    Country {
+        iso2,
+        iso3,
+        fips,
+        name,
+    }
+}
+
+/// ISO 3166-1 countries and territories.
+const COUNTRIES: &[Country] = &[
+    c("AF", "AFG", "AF", "Afghanistan"),
+    c("AX", "ALA", "", "Åland Islands"),
+    c("AL", "ALB", "AL", "Albania"),
+    c("DZ", "DZA", "AG", "Algeria"),
+    c("AS", "ASM", "AQ", "American Samoa"),
+    c("AD", "AND", "AN", "Andorra"),
+    c("AO", "AGO", "AO", "Angola"),
+    c("AI", "AIA", "AV", "Anguilla"),
+    c("AQ", "ATA", "AY", "Antarctica"),
+    c("AG", "ATG", "AC", "Antigua and Barbuda"),
+    c("AR", "ARG", "AR", "Argentina"),
+    c("AM", "ARM", "AM", "Armenia"),
+    c("AW", "ABW", "AA", "Aruba"),
+    c("AU", "AUS", "AS", "Australia"),
+    c("AT", "AUT", "AU", "Austria"),
+    c("AZ", "AZE", "AJ", "Azerbaijan"),
+    c("BS", "BHS", "BF", "Bahamas"),
+    c("BH", "BHR", "BA", "Bahrain"),
+    c("BD", "BGD", "BG", "Bangladesh"),
+    c("BB", "BRB", "BB", "Barbados"),
+    c("BY", "BLR", "BO", "Belarus"),
+    c("BE", "BEL", "BE", "Belgium"),
+    c("BZ", "BLZ", "BH", "Belize"),
+    c("BJ", "BEN", "BN", "Benin"),
+    c("BM", "BMU", "BD", "Bermuda"),
+    c("BT", "BTN", "BT", "Bhutan"),
+    c("BO", "BOL", "BL", "Bolivia"),
+    c("BQ", "BES", "", "Bonaire, Sint Eustatius and Saba"),
+    c("BA", "BIH", "BK", "Bosnia and Herzegovina"),
+    c("BW", "BWA", "BC", "Botswana"),
+    c("BV", "BVT", "BV", "Bouvet Island"),
+    c("BR", "BRA", "BR", "Brazil"),
+    c("IO", "IOT", "IO", "British Indian Ocean Territory"),
+    c("VG", "VGB", "VI", "British Virgin Islands"),
+    c("BN", "BRN", "BX", "Brunei"),
+    c("BG", "BGR", "BU", "Bulgaria"),
+    c("BF", "BFA", "UV", "Burkina Faso"),
+    c("BI", "BDI", "BY", "Burundi"),
+    c("CV", "CPV", "CV", "Cabo Verde"),
+    c("KH", "KHM", "CB", "Cambodia"),
+    c("CM", "CMR", "CM", "Cameroon"),
+    c("CA", "CAN", "CA", "Canada"),
+    c("KY", "CYM", "CJ", "Cayman Islands"),
+    c("CF", "CAF", "CT", "Central African Republic"),
+    c("TD", "TCD", "CD", "Chad"),
+    c("CL", "CHL", "CI", "Chile"),
+    c("CN", "CHN", "CH", "China"),
+    c("CX", "CXR", "KT", "Christmas Island"),
+    c("CC", "CCK", "CK", "Cocos (Keeling) Islands"),
+    c("CO", "COL", "CO", "Colombia"),
+    c("KM", "COM", "CN", "Comoros"),
+    c("CG", "COG", "CF", "Congo"),
+    c("CD", "COD", "CG", "Democratic Republic of the Congo"),
+    c("CK", "COK", "CW", "Cook Islands"),
+    c("CR", "CRI", "CS", "Costa Rica"),
+    c("CI", "CIV", "IV", "Côte d'Ivoire"),
+    c("HR", "HRV", "HR", "Croatia"),
+    c("CU", "CUB", "CU", "Cuba"),
+    c("CW", "CUW", "UC", "Curaçao"),
+    c("CY", "CYP", "CY", "Cyprus"),
+    c("CZ", "CZE", "EZ", "Czechia"),
+    c("DK", "DNK", "DA", "Denmark"),
+    c("DJ", "DJI", "DJ", "Djibouti"),
+    c("DM", "DMA", "DO", "Dominica"),
+    c("DO", "DOM", "DR", "Dominican Republic"),
+    c("EC", "ECU", "EC", "Ecuador"),
+    c("EG", "EGY", "EG", "Egypt"),
+    c("SV", "SLV", "ES", "El Salvador"),
+    c("GQ", "GNQ", "EK", "Equatorial Guinea"),
+    c("ER", "ERI", "ER", "Eritrea"),
+    c("EE", "EST", "EN", "Estonia"),
+    c("SZ", "SWZ", "WZ", "Eswatini"),
+    c("ET", "ETH", "ET", "Ethiopia"),
+    c("FK", "FLK", "FK", "Falkland Islands"),
+    c("FO", "FRO", "FO", "Faroe Islands"),
+    c("FJ", "FJI", "FJ", "Fiji"),
+    c("FI", "FIN", "FI", "Finland"),
+    c("FR", "FRA", "FR", "France"),
+    c("GF", "GUF", "FG", "French Guiana"),
+    c("PF", "PYF", "FP", "French Polynesia"),
+    c("TF", "ATF", "FS", "French Southern Territories"),
+    c("GA", "GAB", "GB", "Gabon"),
+    c("GM", "GMB", "GA", "Gambia"),
+    c("GE", "GEO", "GG", "Georgia"),
+    c("DE", "DEU", "GM", "Germany"),
+    c("GH", "GHA", "GH", "Ghana"),
+    c("GI", "GIB", "GI", "Gibraltar"),
+    c("GR", "GRC", "GR", "Greece"),
+    c("GL", "GRL", "GL", "Greenland"),
+    c("GD", "GRD", "GJ", "Grenada"),
+    c("GP", "GLP", "GP", "Guadeloupe"),
+    c("GU", "GUM", "GQ", "Guam"),
+    c("GT", "GTM", "GT", "Guatemala"),
+    c("GG", "GGY", "GK", "Guernsey"),
+    c("GN", "GIN", "GV", "Guinea"),
+    c("GW", "GNB", "PU", "Guinea-Bissau"),
+    c("GY", "GUY", "GY", "Guyana"),
+    c("HT", "HTI", "HA", "Haiti"),
+    c("HM", "HMD", "HM", "Heard Island and McDonald Islands"),
+    c("VA", "VAT", "VT", "Holy See"),
+    c("HN", "HND", "HO", "Honduras"),
+    c("HK", "HKG", "HK", "Hong Kong"),
+    c("HU", "HUN", "HU", "Hungary"),
+    c("IS", "ISL", "IC", "Iceland"),
+    c("IN", "IND", "IN", "India"),
+    c("ID", "IDN", "ID", "Indonesia"),
+    c("IR", "IRN", "IR", "Iran"),
+    c("IQ", "IRQ", "IZ", "Iraq"),
+    c("IE", "IRL", "EI", "Ireland"),
+    c("IM", "IMN", "IM", "Isle of Man"),
+    c("IL", "ISR", "IS", "Israel"),
+    c("IT", "ITA", "IT", "Italy"),
+    c("JM", "JAM", "JM", "Jamaica"),
+    c("JP", "JPN", "JA", "Japan"),
+    c("JE", "JEY", "JE", "Jersey"),
+    c("JO", "JOR", "JO", "Jordan"),
+    c("KZ", "KAZ", "KZ", "Kazakhstan"),
+    c("KE", "KEN", "KE", "Kenya"),
+    c("KI", "KIR", "KR", "Kiribati"),
+    c("XK", "XKX", "KV", "Kosovo"),
+    c("KW", "KWT", "KU", "Kuwait"),
+    c("KG", "KGZ", "KG", "Kyrgyzstan"),
+    c("LA", "LAO", "LA", "Laos"),
+    c("LV", "LVA", "LG", "Latvia"),
+    c("LB", "LBN", "LE", "Lebanon"),
+    c("LS", "LSO", "LT", "Lesotho"),
+    c("LR", "LBR", "LI", "Liberia"),
+    c("LY", "LBY", "LY", "Libya"),
+    c("LI", "LIE", "LS", "Liechtenstein"),
+    c("LT", "LTU", "LH", "Lithuania"),
+    c("LU", "LUX", "LU", "Luxembourg"),
+    c("MO", "MAC", "MC", "Macao"),
+    c("MG", "MDG", "MA", "Madagascar"),
+    c("MW", "MWI", "MI", "Malawi"),
+    c("MY", "MYS", "MY", "Malaysia"),
+    c("MV", "MDV", "MV", "Maldives"),
+    c("ML", "MLI", "ML", "Mali"),
+    c("MT", "MLT", "MT", "Malta"),
+    c("MH", "MHL", "RM", "Marshall Islands"),
+    c("MQ", "MTQ", "MB", "Martinique"),
+    c("MR", "MRT", "MR", "Mauritania"),
+    c("MU", "MUS", "MP", "Mauritius"),
+    c("YT", "MYT", "MF", "Mayotte"),
+    c("MX", "MEX", "MX", "Mexico"),
+    c("FM", "FSM", "FM", "Micronesia"),
+    c("MD", "MDA", "MD", "Moldova"),
+    c("MC", "MCO", "MN", "Monaco"),
+    c("MN", "MNG", "MG", "Mongolia"),
+    c("ME", "MNE", "MJ", "Montenegro"),
+    c("MS", "MSR", "MH", "Montserrat"),
+    c("MA", "MAR", "MO", "Morocco"),
+    c("MZ", "MOZ", "MZ", "Mozambique"),
+    c("MM", "MMR", "BM", "Myanmar"),
+    c("NA", "NAM", "WA", "Namibia"),
+    c("NR", "NRU", "NR", "Nauru"),
+    c("NP", "NPL", "NP", "Nepal"),
+    c("NL", "NLD", "NL", "Netherlands"),
+    c("NC", "NCL", "NC", "New Caledonia"),
+    c("NZ", "NZL", "NZ", "New Zealand"),
+    c("NI", "NIC", "NU", "Nicaragua"),
+    c("NE", "NER", "NG", "Niger"),
+    c("NG", "NGA", "NI", "Nigeria"),
+    c("NU", "NIU", "NE", "Niue"),
+    c("NF", "NFK", "NF", "Norfolk Island"),
+    c("KP", "PRK", "KN", "North Korea"),
+    c("MK", "MKD", "MK", "North Macedonia"),
+    c("MP", "MNP", "CQ", "Northern Mariana Islands"),
+    c("NO", "NOR", "NO", "Norway"),
+    c("OM", "OMN", "MU", "Oman"),
+    c("PK", "PAK", "PK", "Pakistan"),
+    c("PW", "PLW", "PS", "Palau"),
+    c("PS", "PSE", "WE", "Palestine"),
+    c("PA", "PAN", "PM", "Panama"),
+    c("PG", "PNG", "PP", "Papua New Guinea"),
+    c("PY", "PRY", "PA", "Paraguay"),
+    c("PE", "PER", "PE", "Peru"),
+    c("PH", "PHL", "RP", "Philippines"),
+    c("PN", "PCN", "PC", "Pitcairn"),
+    c("PL", "POL", "PL", "Poland"),
+    c("PT", "PRT", "PO", "Portugal"),
+    c("PR", "PRI", "RQ", "Puerto Rico"),
+    c("QA", "QAT", "QA", "Qatar"),
+    c("RE", "REU", "RE", "Réunion"),
+    c("RO", "ROU", "RO", "Romania"),
+    c("RU", "RUS", "RS", "Russia"),
+    c("RW", "RWA", "RW", "Rwanda"),
+    c("BL", "BLM", "TB", "Saint Barthélemy"),
+    c("SH", "SHN", "SH", "Saint Helena"),
+    c("KN", "KNA", "SC", "Saint Kitts and Nevis"),
+    c("LC", "LCA", "ST", "Saint Lucia"),
+    c("MF", "MAF", "RN", "Saint Martin"),
+    c("PM", "SPM", "SB", "Saint Pierre and Miquelon"),
+    c("VC", "VCT", "VC", "Saint Vincent and the Grenadines"),
+    c("WS", "WSM", "WS", "Samoa"),
+    c("SM", "SMR", "SM", "San Marino"),
+    c("ST", "STP", "TP", "São Tomé and Príncipe"),
+    c("SA", "SAU", "SA", "Saudi Arabia"),
+    c("SN", "SEN", "SG", "Senegal"),
+    c("RS", "SRB", "RI", "Serbia"),
+    c("SC", "SYC", "SE", "Seychelles"),
+    c("SL", "SLE", "SL", "Sierra Leone"),
+    c("SG", "SGP", "SN", "Singapore"),
+    c("SX", "SXM", "NN", "Sint Maarten"),
+    c("SK", "SVK", "LO", "Slovakia"),
+    c("SI", "SVN", "SI", "Slovenia"),
+    c("SB", "SLB", "BP", "Solomon Islands"),
+    c("SO", "SOM", "SO", "Somalia"),
+    c("ZA", "ZAF", "SF", "South Africa"),
+    c(
+        "GS",
+        "SGS",
+        "SX",
+        "South Georgia and the South Sandwich Islands",
+    ),
+    c("KR", "KOR", "KS", "South Korea"),
+    c("SS", "SSD", "OD", "South Sudan"),
+    c("ES", "ESP", "SP", "Spain"),
+    c("LK", "LKA", "CE", "Sri Lanka"),
+    c("SD", "SDN", "SU", "Sudan"),
+    c("SR", "SUR", "NS", "Suriname"),
+    c("SJ", "SJM", "SV", "Svalbard and Jan Mayen"),
+    c("SE", "SWE", "SW", "Sweden"),
+    c("CH", "CHE", "SZ", "Switzerland"),
+    c("SY", "SYR", "SY", "Syria"),
+    c("TW", "TWN", "TW", "Taiwan"),
+    c("TJ", "TJK", "TI", "Tajikistan"),
+    c("TZ", "TZA", "TZ", "Tanzania"),
+    c("TH", "THA", "TH", "Thailand"),
+    c("TL", "TLS", "TT", "Timor-Leste"),
+    c("TG", "TGO", "TO", "Togo"),
+    c("TK", "TKL", "TL", "Tokelau"),
+    c("TO", "TON", "TN", "Tonga"),
+    c("TT", "TTO", "TD", "Trinidad and Tobago"),
+    c("TN", "TUN", "TS", "Tunisia"),
+    c("TR", "TUR", "TU", "Turkey"),
+    c("TM", "TKM", "TX", "Turkmenistan"),
+    c("TC", "TCA", "TK", "Turks and Caicos Islands"),
+    c("TV", "TUV", "TV", "Tuvalu"),
+    c("UG", "UGA", "UG", "Uganda"),
+    c("UA", "UKR", "UP", "Ukraine"),
+    c("AE", "ARE", "AE", "United Arab Emirates"),
+    c("GB", "GBR", "UK", "United Kingdom"),
+    c("US", "USA", "US", "United States"),
+    c("VI", "VIR", "VQ", "United States Virgin Islands"),
+    c("UY", "URY", "UY", "Uruguay"),
+    c("UZ", "UZB", "UZ", "Uzbekistan"),
+    c("VU", "VUT", "NH", "Vanuatu"),
+    c("VE", "VEN", "VE", "Venezuela"),
+    c("VN", "VNM", "VM", "Vietnam"),
+    c("WF", "WLF", "WF", "Wallis and Futuna"),
+    c("EH", "ESH", "WI", "Western Sahara"),
+    c("YE", "YEM", "YM", "Yemen"),
+    c("ZM", "ZMB", "ZA", "Zambia"),
+    c("ZW", "ZWE", "ZI", "Zimbabwe"),
+];
+
+/// Other names a country commonly goes by, matched against extracted
+/// locations so the model's "USA" merges into the canonical node.
+const OTHER_NAMES: &[(&str, &[&str])] = &[
+    ("US", &["United States of America", "America", "US"]),
+    ("GB", &["Great Britain", "Britain", "UK"]),
+    ("RU", &["Russian Federation"]),
+    ("KR", &["Republic of Korea", "Korea, South"]),
+    (
+        "KP",
+        &[
+            "Democratic People's Republic of Korea",
+            "DPRK",
+            "Korea, North",
+        ],
+    ),
+    ("IR", &["Islamic Republic of Iran"]),
+    ("SY", &["Syrian Arab Republic"]),
+    ("CZ", &["Czech Republic"]),
+    ("CI", &["Ivory Coast"]),
+    ("CV", &["Cape Verde"]),
+    ("MM", &["Burma"]),
+    ("SZ", &["Swaziland"]),
+    ("TR", &["Türkiye", "Turkiye"]),
+    ("MK", &["Macedonia"]),
+    ("VN", &["Viet Nam"]),
+    ("LA", &["Lao People's Democratic Republic"]),
+    ("TZ", &["United Republic of Tanzania"]),
+    ("VE", &["Bolivarian Republic of Venezuela"]),
+    ("BO", &["Plurinational State of Bolivia"]),
+    ("MD", &["Republic of Moldova"]),
+    ("CD", &["DR Congo", "DRC", "Congo-Kinshasa"]),
+    ("CG", &["Republic of the Congo", "Congo-Brazzaville"]),
+    ("AE", &["UAE"]),
+    ("NL", &["Holland", "The Netherlands"]),
+    ("VA", &["Vatican City", "Vatican"]),
+    ("PS", &["State of Palestine", "Palestinian Territories"]),
+];
+
+/// CAMEO actor codes that differ from ISO alpha-3.
+const CAMEO_CODES: &[(&str, &str)] = &[("ROM", "RO"), ("KSV", "XK")];
+
+/// FIPS codes covering part of a country listed under another code.
+const EXTRA_FIPS: &[(&str, &str)] = &[("GZ", "PS"), ("JN", "SJ")];
+
+/// ITU Maritime Identification Digits, as (MID, alpha-2). Island groups
+/// with their own MID but no ISO code (Azores, Madeira, Crozet, ...) map to
+/// the state they belong to.
+const MIDS: &[(u16, &str)] = &[
+    (201, "AL"),
+    (202, "AD"),
+    (203, "AT"),
+    (204, "PT"),
+    (205, "BE"),
+    (206, "BY"),
+    (207, "BG"),
+    (208, "VA"),
+    (209, "CY"),
+    (210, "CY"),
+    (211, "DE"),
+    (212, "CY"),
+    (213, "GE"),
+    (214, "MD"),
+    (215, "MT"),
+    (216, "AM"),
+    (218, "DE"),
+    (219, "DK"),
+    (220, "DK"),
+    (224, "ES"),
+    (225, "ES"),
+    (226, "FR"),
+    (227, "FR"),
+    (228, "FR"),
+    (229, "MT"),
+    (230, "FI"),
+    (231, "FO"),
+    (232, "GB"),
+    (233, "GB"),
+    (234, "GB"),
+    (235, "GB"),
+    (236, "GI"),
+    (237, "GR"),
+    (238, "HR"),
+    (239, "GR"),
+    (240, "GR"),
+    (241, "GR"),
+    (242, "MA"),
+    (243, "HU"),
+    (244, "NL"),
+    (245, "NL"),
+    (246, "NL"),
+    (247, "IT"),
+    (248, "MT"),
+    (249, "MT"),
+    (250, "IE"),
+    (251, "IS"),
+    (252, "LI"),
+    (253, "LU"),
+    (254, "MC"),
+    (255, "PT"),
+    (256, "MT"),
+    (257, "NO"),
+    (258, "NO"),
+    (259, "NO"),
+    (261, "PL"),
+    (262, "ME"),
+    (263, "PT"),
+    (264, "RO"),
+    (265, "SE"),
+    (266, "SE"),
+    (267, "SK"),
+    (268, "SM"),
+    (269, "CH"),
+    (270, "CZ"),
+    (271, "TR"),
+    (272, "UA"),
+    (273, "RU"),
+    (274, "MK"),
+    (275, "LV"),
+    (276, "EE"),
+    (277, "LT"),
+    (278, "SI"),
+    (279, "RS"),
+    (301, "AI"),
+    (303, "US"),
+    (304, "AG"),
+    (305, "AG"),
+    (306, "CW"),
+    (307, "AW"),
+    (308, "BS"),
+    (309, "BS"),
+    (310, "BM"),
+    (311, "BS"),
+    (312, "BZ"),
+    (314, "BB"),
+    (316, "CA"),
+    (319, "KY"),
+    (321, "CR"),
+    (323, "CU"),
+    (325, "DM"),
+    (327, "DO"),
+    (329, "GP"),
+    (330, "GD"),
+    (331, "GL"),
+    (332, "GT"),
+    (334, "HN"),
+    (336, "HT"),
+    (338, "US"),
+    (339, "JM"),
+    (341, "KN"),
+    (343, "LC"),
+    (345, "MX"),
+    (347, "MQ"),
+    (348, "MS"),
+    (350, "NI"),
+    (351, "PA"),
+    (352, "PA"),
+    (353, "PA"),
+    (354, "PA"),
+    (355, "PA"),
+    (356, "PA"),
+    (357, "PA"),
+    (358, "PR"),
+    (359, "SV"),
+    (361, "PM"),
+    (362, "TT"),
+    (364, "TC"),
+    (366, "US"),
+    (367, "US"),
+    (368, "US"),
+    (369, "US"),
+    (370, "PA"),
+    (371, "PA"),
+    (372, "PA"),
+    (373, "PA"),
+    (374, "PA"),
+    (375, "VC"),
+    (376, "VC"),
+    (377, "VC"),
+    (378, "VG"),
+    (379, "VI"),
+    (401, "AF"),
+    (403, "SA"),
+    (405, "BD"),
+    (408, "BH"),
+    (410, "BT"),
+    (412, "CN"),
+    (413, "CN"),
+    (414, "CN"),
+    (416, "TW"),
+    (417, "LK"),
+    (419, "IN"),
+    (422, "IR"),
+    (423, "AZ"),
+    (425, "IQ"),
+    (428, "IL"),
+    (431, "JP"),
+    (432, "JP"),
+    (434, "TM"),
+    (436, "KZ"),
+    (437, "UZ"),
+    (438, "JO"),
+    (440, "KR"),
+    (441, "KR"),
+    (443, "PS"),
+    (445, "KP"),
+    (447, "KW"),
+    (450, "LB"),
+    (451, "KG"),
+    (453, "MO"),
+    (455, "MV"),
+    (457, "MN"),
+    (459, "NP"),
+    (461, "OM"),
+    (463, "PK"),
+    (466, "QA"),
+    (468, "SY"),
+    (470, "AE"),
+    (471, "AE"),
+    (472, "TJ"),
+    (473, "YE"),
+    (475, "YE"),
+    (477, "HK"),
+    (478, "BA"),
+    (501, "TF"),
+    (503, "AU"),
+    (506, "MM"),
+    (508, "BN"),
+    (510, "FM"),
+    (511, "PW"),
+    (512, "NZ"),
+    (514, "KH"),
+    (515, "KH"),
+    (516, "CX"),
+    (518, "CK"),
+    (520, "FJ"),
+    (523, "CC"),
+    (525, "ID"),
+    (529, "KI"),
+    (531, "LA"),
+    (533, "MY"),
+    (536, "MP"),
+    (538, "MH"),
+    (540, "NC"),
+    (542, "NU"),
+    (544, "NR"),
+    (546, "PF"),
+    (548, "PH"),
+    (550, "TL"),
+    (553, "PG"),
+    (555, "PN"),
+    (557, "SB"),
+    (559, "AS"),
+    (561, "WS"),
+    (563, "SG"),
+    (564, "SG"),
+    (565, "SG"),
+    (566, "SG"),
+    (567, "TH"),
+    (570, "TO"),
+    (572, "TV"),
+    (574, "VN"),
+    (576, "VU"),
+    (577, "VU"),
+    (578, "WF"),
+    (601, "ZA"),
+    (603, "AO"),
+    (605, "DZ"),
+    (607, "TF"),
+    (608, "SH"),
+    (609, "BI"),
+    (610, "BJ"),
+    (611, "BW"),
+    (612, "CF"),
+    (613, "CM"),
+    (615, "CG"),
+    (616, "KM"),
+    (617, "CV"),
+    (618, "TF"),
+    (619, "CI"),
+    (620, "KM"),
+    (621, "DJ"),
+    (622, "EG"),
+    (624, "ET"),
+    (625, "ER"),
+    (626, "GA"),
+    (627, "GH"),
+    (629, "GM"),
+    (630, "GW"),
+    (631, "GQ"),
+    (632, "GN"),
+    (633, "BF"),
+    (634, "KE"),
+    (635, "TF"),
+    (636, "LR"),
+    (637, "LR"),
+    (638, "SS"),
+    (642, "LY"),
+    (644, "LS"),
+    (645, "MU"),
+    (647, "MG"),
+    (649, "ML"),
+    (650, "MZ"),
+    (654, "MR"),
+    (655, "MW"),
+    (656, "NE"),
+    (657, "NG"),
+    (659, "NA"),
+    (660, "RE"),
+    (661, "RW"),
+    (662, "SD"),
+    (663, "SN"),
+    (664, "SC"),
+    (665, "SH"),
+    (666, "SO"),
+    (667, "SL"),
+    (668, "ST"),
+    (669, "SZ"),
+    (670, "TD"),
+    (671, "TG"),
+    (672, "TN"),
+    (674, "TZ"),
+    (675, "UG"),
+    (676, "CD"),
+    (677, "TZ"),
+    (678, "ZM"),
+    (679, "ZW"),
+    (701, "AR"),
+    (710, "BR"),
+    (720, "BO"),
+    (725, "CL"),
+    (730, "CO"),
+    (735, "EC"),
+    (740, "FK"),
+    (745, "GF"),
+    (750, "GY"),
+    (755, "PY"),
+    (760, "PE"),
+    (765, "SR"),
+    (770, "UY"),
+    (775, "VE"),
+];
+
+impl Country {
+    /// Stable id of this country's Location node.
+    pub fn entity_id(&self) -> Uuid {
+        Uuid::new_v5(&ID_NAMESPACE, format!("country:{}", self.iso2).as_bytes())
+    }
+
+    /// Every name the country is matched by, canonical name first. Bare
+    /// alpha-2 codes are left out; too many collide with abbreviations
+    /// ("CA", "IN").
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        let other = OTHER_NAMES
+            .iter()
+            .find(|(iso2, _)| *iso2 == self.iso2)
+            .map(|(_, names)| *names)
+            .unwrap_or(&[]);
+        [self.name, self.iso3]
+            .into_iter()
+            .chain(other.iter().copied())
+    }
+
+    pub fn matches_name(&self, name: &str) -> bool {
+        let name = name.trim().to_lowercase();
+        self.names().any(|n| n.to_lowercase() == name)
+    }
+
+    /// The canonical Location entity, attributed to `source`.
+    pub fn to_entity(&self, source: &str) -> Entity {
+        let mut entity = Entity::new(
+            EntityType::Location,
+            self.name.to_string(),
+            source.to_string(),
+        );
+        entity.id = self.entity_id();
+        entity.aliases = self.names().skip(1).map(String::from).collect();
+        entity.properties = self.properties();
+        entity
+    }
+
+    fn properties(&self) -> Value {
+        json!({
+            "location_type": "country",
+            "iso2": self.iso2,
+            "iso3": self.iso3,
+            "fips": (!self.fips.is_empty()).then_some(self.fips),
+        })
+    }
+}
+
+/// ISO 3166-1 alpha-2, any case.
+pub fn by_iso2(code: &str) -> Option<&'static Country> {
+    let code = code.trim();
+    COUNTRIES.iter().find(|c| c.iso2.eq_ignore_ascii_case(code))
+}
+
+/// ISO 3166-1 alpha-3, also accepting CAMEO's variants.
+pub fn by_iso3(code: &str) -> Option<&'static Country> {
+    let code = code.trim();
+    if let Some((_, iso2)) = CAMEO_CODES
+        .iter()
+        .find(|(cameo, _)| cameo.eq_ignore_ascii_case(code))
+    {
+        return by_iso2(iso2);
+    }
+    COUNTRIES.iter().find(|c| c.iso3.eq_ignore_ascii_case(code))
+}
+
+/// FIPS 10-4, as used for GDELT event locations.
+pub fn by_fips(code: &str) -> Option<&'static Country> {
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+    if let Some((_, iso2)) = EXTRA_FIPS
+        .iter()
+        .find(|(fips, _)| fips.eq_ignore_ascii_case(code))
+    {
+        return by_iso2(iso2);
+    }
+    COUNTRIES.iter().find(|c| c.fips.eq_ignore_ascii_case(code))
+}
+
+/// Flag state of a 9-digit MMSI, read from its MID. Ship stations start
+/// with the MID; coast stations (00MID), group calls (0MID), SAR aircraft
+/// (111MID), handhelds (8MID) and aids to navigation (99MID) embed it after
+/// their prefix.
+pub fn by_mmsi(mmsi: &str) -> Option<&'static Country> {
+    let mmsi = mmsi.trim();
+    if mmsi.len() != 9 || !mmsi.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mid = if mmsi.starts_with("111") {
+        &mmsi[3..6]
+    } else if mmsi.starts_with("00") || mmsi.starts_with("98") || mmsi.starts_with("99") {
+        &mmsi[2..5]
+    } else if mmsi.starts_with('0') || mmsi.starts_with('8') {
+        &mmsi[1..4]
+    } else {
+        &mmsi[..3]
+    };
+    let mid: u16 = mid.parse().ok()?;
+    MIDS.iter()
+        .find(|(m, _)| *m == mid)
+        .and_then(|(_, iso2)| by_iso2(iso2))
+}
+
+/// OpenCorporates jurisdiction code ("gb", "us_de") to its country.
+pub fn by_jurisdiction(code: &str) -> Option<&'static Country> {
+    let country = code.trim().split('_').next()?;
+    if country.len() == 2 {
+        by_iso2(country)
+    } else {
+        None
+    }
+}
+
+/// Country by canonical or common name ("Russian Federation").
+pub fn by_name(name: &str) -> Option<&'static Country> {
+    COUNTRIES.iter().find(|c| c.matches_name(name))
+}
+
+/// Add canonical locations for the country codes in `doc`'s metadata and
+/// link the document's subjects to them: vessels to their flag state and
+/// companies to their jurisdiction (REGISTERED_IN), GDELT actors to their
+/// country and events to where they happened (LOCATED_AT). Returns the
+/// number of relationships added.
+pub fn link_countries(doc: &RawDocument, result: &mut ExtractionResult) -> usize {
+    let meta = &doc.metadata;
+    let mut links = Vec::new();
+
+    match doc.source.as_str() {
+        "ais" => {
+            let mmsi = match &meta["mmsi"] {
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => s.clone(),
+                _ => String::new(),
+            };
+            if let Some(country) = by_mmsi(&mmsi) {
+                let name = meta["name"].as_str().or(doc.title.as_deref());
+                for subject in subjects(result, Some(EntityType::Vessel), name, country) {
+                    links.push((
+                        subject,
+                        country,
+                        RelationType::RegisteredIn,
+                        json!({ "mmsi": mmsi }),
+                    ));
+                }
+            }
+        }
+        "opencorporates" => {
+            if let Some(code) = meta["jurisdiction_code"].as_str() {
+                if let Some(country) = by_jurisdiction(code) {
+                    let name = doc.title.as_deref();
+                    for subject in subjects(result, Some(EntityType::Organization), name, country) {
+                        let properties = json!({ "jurisdiction_code": code });
+                        links.push((subject, country, RelationType::RegisteredIn, properties));
+                    }
+                }
+            }
+        }
+        "gdelt" => {
+            for actor in ["actor1", "actor2"] {
+                let code = meta[format!("{actor}_country_code")]
+                    .as_str()
+                    .unwrap_or_default();
+                let name = meta[format!("{actor}_name")].as_str();
+                if let (Some(country), Some(name)) = (by_iso3(code), name.filter(|n| !n.is_empty()))
+                {
+                    for subject in subjects(result, None, Some(name), country) {
+                        links.push((
+                            subject,
+                            country,
+                            RelationType::LocatedAt,
+                            json!({ "country_code": code }),
+                        ));
+                    }
+                }
+            }
+            let code = meta["action_geo_country_code"].as_str().unwrap_or_default();
+            if let Some(country) = by_fips(code) {
+                let events: Vec<Uuid> = result
+                    .entities
+                    .iter()
+                    .filter(|e| e.entity_type == EntityType::Event)
+                    .map(|e| e.id)
+                    .collect();
+                for event in events {
+                    links.push((
+                        event,
+                        country,
+                        RelationType::LocatedAt,
+                        json!({ "country_code": code }),
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut added = 0;
+    for (subject, country, relation_type, properties) in links {
+        let location = canonical_location(result, country, &doc.source);
+        let exists = result.relationships.iter().any(|r| {
+            r.source_entity_id == subject
+                && r.target_entity_id == location
+                && r.relation_type == relation_type
+        });
+        if subject != location && !exists {
+            let mut rel = Relationship::new(subject, location, relation_type, doc.source.clone());
+            rel.properties = properties;
+            result.relationships.push(rel);
+            added += 1;
+        }
+    }
+    added
+}
+
+/// Entities `name` refers to. Falls back to the only entity of
+/// `entity_type` when the name matches nothing, and never returns
+/// something that is itself `country`.
+fn subjects(
+    result: &ExtractionResult,
+    entity_type: Option<EntityType>,
+    name: Option<&str>,
+    country: &Country,
+) -> Vec<Uuid> {
+    let of_type = |e: &&Entity| match &entity_type {
+        Some(t) => &e.entity_type == t,
+        None => e.entity_type != EntityType::Location,
+    };
+    let not_country = |e: &&Entity| !country.matches_name(&e.name);
+
+    let named: Vec<Uuid> = match name {
+        Some(name) => result
+            .entities
+            .iter()
+            .filter(of_type)
+            .filter(not_country)
+            .filter(|e| {
+                let normalized = normalize_name(name, &e.entity_type);
+                e.name.to_lowercase() == normalized.to_lowercase()
+                    || e.aliases
+                        .iter()
+                        .any(|a| a.trim().eq_ignore_ascii_case(name.trim()))
+            })
+            .map(|e| e.id)
+            .collect(),
+        None => Vec::new(),
+    };
+    if !named.is_empty() || entity_type.is_none() {
+        return named;
+    }
+
+    let candidates: Vec<Uuid> = result
+        .entities
+        .iter()
+        .filter(of_type)
+        .filter(not_country)
+        .map(|e| e.id)
+        .collect();
+    if candidates.len() == 1 {
+        candidates
+    } else {
+        Vec::new()
+    }
+}
+
+/// Id of `country`'s Location in `result`. An extracted location naming the
+/// country is taken over (re-keyed to the canonical id, original name kept
+/// as an alias); otherwise the canonical entity is added.
+fn canonical_location(result: &mut ExtractionResult, country: &Country, source: &str) -> Uuid {
+    let canonical_id = country.entity_id();
+    if result.entities.iter().any(|e| e.id == canonical_id) {
+        return canonical_id;
+    }
+
+    let extracted = result
+        .entities
+        .iter_mut()
+        .find(|e| e.entity_type == EntityType::Location && country.matches_name(&e.name));
+    match extracted {
+        Some(entity) => {
+            let old_id = std::mem::replace(&mut entity.id, canonical_id);
+            if entity.name != country.name {
+                let original = std::mem::replace(&mut entity.name, country.name.to_string());
+                if !entity.aliases.contains(&original) {
+                    entity.aliases.push(original);
+                }
+            }
+            for name in country.names().skip(1) {
+                if !entity.aliases.iter().any(|a| a == name) {
+                    entity.aliases.push(name.to_string());
+                }
+            }
+            if let Value::Object(props) = &mut entity.properties {
+                if let Value::Object(canonical) = country.properties() {
+                    props.extend(canonical);
+                }
+            } else {
+                entity.properties = country.properties();
+            }
+            for rel in &mut result.relationships {
+                if rel.source_entity_id == old_id {
+                    rel.source_entity_id = canonical_id;
+                }
+                if rel.target_entity_id == old_id {
+                    rel.target_entity_id = canonical_id;
+                }
+            }
+        }
+        None => result.entities.push(country.to_entity(source)),
+    }
+    canonical_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(source: &str, title: Option<&str>, metadata: Value) -> RawDocument {
+        RawDocument {
+            source: source.to_string(),
+            source_id: "doc-1".to_string(),
+            title: title.map(String::from),
+            content: String::new(),
+            url: None,
+            collected_at: chrono::Utc::now(),
+            observed_at: None,
+            identifiers: Default::default(),
+            metadata,
+            structured: None,
+        }
+    }
+
+    fn result(entities: Vec<Entity>) -> ExtractionResult {
+        ExtractionResult {
+            entities,
+            relationships: vec![],
+            raw_source: "doc-1".to_string(),
+            extracted_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn every_scheme_resolves_to_the_same_country() {
+        let russia = by_iso2("ru").unwrap();
+        assert_eq!(by_iso3("RUS"), Some(russia));
+        assert_eq!(by_fips("RS"), Some(russia));
+        assert_eq!(by_mmsi("273123456"), Some(russia));
+        assert_eq!(by_jurisdiction("ru"), Some(russia));
+        assert_eq!(by_name("Russian Federation"), Some(russia));
+
+        // FIPS and ISO disagree on many two-letter codes
+        assert_eq!(by_fips("GM").unwrap().name, "Germany");
+        assert_eq!(by_iso2("GM").unwrap().name, "Gambia");
+        assert_eq!(by_jurisdiction("us_de").unwrap().iso2, "US");
+        assert_eq!(by_iso3("ROM").unwrap().iso2, "RO");
+        assert_eq!(by_fips(""), None);
+
+        // Ids depend only on the country, whichever code found it
+        assert_eq!(russia.entity_id(), by_iso3("RUS").unwrap().entity_id());
+        assert_ne!(russia.entity_id(), by_iso2("GB").unwrap().entity_id());
+        assert_eq!(russia.to_entity("gdelt").id, russia.entity_id());
+    }
+
+    #[test]
+    fn mmsi_prefixes() {
+        assert_eq!(by_mmsi("366999000").unwrap().iso2, "US");
+        assert_eq!(by_mmsi("636012345").unwrap().iso2, "LR");
+        assert_eq!(by_mmsi("002320001").unwrap().iso2, "GB");
+        assert_eq!(by_mmsi("992111234").unwrap().iso2, "DE");
+        assert_eq!(by_mmsi("999999999"), None);
+        assert_eq!(by_mmsi("12345"), None);
+    }
+
+    #[test]
+    fn links_vessels_to_their_flag_state() {
+        let mut vessel = Entity::new(
+            EntityType::Vessel,
+            "Ever Given".to_string(),
+            "ais".to_string(),
+        );
+        vessel.aliases.push("EVER GIVEN".to_string());
+        let vessel_id = vessel.id;
+        let mut extraction = result(vec![vessel]);
+        let doc = doc(
+            "ais",
+            Some("EVER GIVEN"),
+            json!({ "mmsi": 353136000, "name": "EVER GIVEN" }),
+        );
+
+        assert_eq!(link_countries(&doc, &mut extraction), 1);
+        // Linking again adds nothing
+        assert_eq!(link_countries(&doc, &mut extraction), 0);
+
+        let panama = by_iso2("PA").unwrap();
+        assert_eq!(extraction.entities.len(), 2);
+        assert_eq!(extraction.entities[1].id, panama.entity_id());
+        let rel = &extraction.relationships[0];
+        assert_eq!(
+            (rel.source_entity_id, rel.target_entity_id),
+            (vessel_id, panama.entity_id())
+        );
+        assert_eq!(rel.relation_type, RelationType::RegisteredIn);
+    }
+
+    #[test]
+    fn extracted_country_locations_take_the_canonical_id() {
+        let company = Entity::new(
+            EntityType::Organization,
+            "Acme Ltd".to_string(),
+            "opencorporates".to_string(),
+        );
+        let uk = Entity::new(
+            EntityType::Location,
+            "UK".to_string(),
+            "opencorporates".to_string(),
+        );
+        let mut located = Relationship::new(
+            company.id,
+            uk.id,
+            RelationType::LocatedAt,
+            "opencorporates".to_string(),
+        );
+        located.confidence = 0.8;
+        let mut extraction = result(vec![company, uk]);
+        extraction.relationships.push(located);
+        let doc = doc(
+            "opencorporates",
+            Some("ACME LIMITED"),
+            json!({ "jurisdiction_code": "gb" }),
+        );
+
+        assert_eq!(link_countries(&doc, &mut extraction), 1);
+
+        let gb = by_iso2("GB").unwrap();
+        assert_eq!(extraction.entities.len(), 2);
+        assert_eq!(extraction.entities[1].id, gb.entity_id());
+        assert_eq!(extraction.entities[1].name, "United Kingdom");
+        assert!(extraction.entities[1].aliases.contains(&"UK".to_string()));
+        assert!(extraction
+            .relationships
+            .iter()
+            .all(|r| r.target_entity_id == gb.entity_id()));
+    }
+
+    #[test]
+    fn links_gdelt_actors_and_events() {
+        let actor = Entity::new(
+            EntityType::Person,
+            "Vladimir Putin".to_string(),
+            "gdelt".to_string(),
+        );
+        let country_actor = Entity::new(
+            EntityType::Organization,
+            "Russia".to_string(),
+            "gdelt".to_string(),
+        );
+        let event = Entity::new(EntityType::Event, "Summit".to_string(), "gdelt".to_string());
+        let mut extraction = result(vec![actor, country_actor, event]);
+        let doc = doc(
+            "gdelt",
+            None,
+            json!({
+                "actor1_name": "VLADIMIR PUTIN", "actor1_country_code": "RUS",
+                "actor2_name": "RUSSIA", "actor2_country_code": "RUS",
+                "action_geo_country_code": "SZ",
+            }),
+        );
+
+        assert_eq!(link_countries(&doc, &mut extraction), 2);
+
+        let targets: Vec<Uuid> = extraction
+            .relationships
+            .iter()
+            .map(|r| r.target_entity_id)
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                by_iso2("RU").unwrap().entity_id(),
+                by_iso2("CH").unwrap().entity_id()
+            ]
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! Conversion of uploaded files (PDF, HTML, plain text) to the clean text
+//! handed to LLM extraction.
+//!
+//! Converted text is a sequence of paragraphs separated by blank lines.
+//! Paragraph and page boundaries are kept as character offsets into that
+//! text, stored on the document's metadata under `text_offsets`, so a
+//! relationship's evidence quote can later be traced back to the page and
+//! paragraph it came from.
+
+use ego_tree::iter::Edge;
+use scraper::{Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+
+use argus_core::{ArgusError, Result};
+
+/// Metadata key holding a document's [`TextOffsets`].
+pub const TEXT_OFFSETS_KEY: &str = "text_offsets";
+
+/// Elements whose content is never document text.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "head", "nav", "iframe",
+];
+
+/// Elements that start and end a paragraph.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "aside",
+    "header",
+    "footer",
+    "blockquote",
+    "pre",
+    "address",
+    "figure",
+    "figcaption",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "table",
+    "tr",
+    "hr",
+    "form",
+    "fieldset",
+    "caption",
+];
+
+/// File formats text can be extracted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    PlainText,
+    Html,
+    Pdf,
+}
+
+impl DocumentFormat {
+    /// Format for a MIME type, or `None` if its text cannot be extracted.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type {
+            "application/pdf" => Some(Self::Pdf),
+            "text/html" | "application/xhtml+xml" => Some(Self::Html),
+            "message/rfc822" | "application/json" => Some(Self::PlainText),
+            t if t.starts_with("text/") => Some(Self::PlainText),
+            _ => None,
+        }
+    }
+}
+
+/// Half-open range of character (not byte) offsets into converted text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl TextSpan {
+    fn shifted(self, by: usize) -> Self {
+        Self {
+            start: self.start + by,
+            end: self.end + by,
+        }
+    }
+
+    fn contains(&self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+}
+
+/// Paragraph and page boundaries of converted text. Pages are only known
+/// for PDFs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextOffsets {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pages: Vec<TextSpan>,
+    #[serde(default)]
+    pub paragraphs: Vec<TextSpan>,
+}
+
+/// Where a quote was found in a document. Page and paragraph numbers
+/// start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLocation {
+    pub span: TextSpan,
+    pub page: Option<usize>,
+    pub paragraph: Option<usize>,
+}
+
+impl TextOffsets {
+    /// Offsets stored on a document's metadata, if any.
+    pub fn from_metadata(metadata: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(metadata.get(TEXT_OFFSETS_KEY)?.clone()).ok()
+    }
+
+    /// Find `quote` in `text`, ignoring ASCII case and differences in
+    /// whitespace, since quotes returned by the LLM rarely keep the
+    /// original line breaks.
+    pub fn locate(&self, text: &str, quote: &str) -> Option<TextLocation> {
+        let (haystack, positions) = folded(text);
+        let (needle, _) = folded(quote.trim());
+        if needle.is_empty() {
+            return None;
+        }
+        let found = find_chars(&haystack, &needle)?;
+        let start = positions[found];
+        let end = positions[found + needle.len() - 1] + 1;
+        let number =
+            |spans: &[TextSpan]| spans.iter().position(|s| s.contains(start)).map(|i| i + 1);
+        Some(TextLocation {
+            span: TextSpan { start, end },
+            page: number(&self.pages),
+            paragraph: number(&self.paragraphs),
+        })
+    }
+}
+
+/// Text converted from a file, ready for extraction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConvertedText {
+    pub text: String,
+    /// Title declared by the file itself (an HTML `<title>`).
+    pub title: Option<String>,
+    pub offsets: TextOffsets,
+    /// Length of `text` in characters.
+    chars: usize,
+}
+
+impl ConvertedText {
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// Length of the text in characters.
+    pub fn chars(&self) -> usize {
+        self.chars
+    }
+
+    /// Add a paragraph, collapsing runs of spaces within each line and
+    /// dropping blank lines.
+    fn push_paragraph(&mut self, paragraph: &str) {
+        let lines: Vec<String> = paragraph
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+        let paragraph = lines.join("\n");
+        if !self.text.is_empty() {
+            self.text.push_str("\n\n");
+            self.chars += 2;
+        }
+        let start = self.chars;
+        self.chars += paragraph.chars().count();
+        self.text.push_str(&paragraph);
+        self.offsets.paragraphs.push(TextSpan {
+            start,
+            end: self.chars,
+        });
+    }
+
+    /// Add text whose paragraphs are separated by blank lines.
+    fn push_text(&mut self, text: &str) {
+        let mut paragraph = String::new();
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                self.push_paragraph(&paragraph);
+                paragraph.clear();
+            } else {
+                paragraph.push_str(line);
+                paragraph.push('\n');
+            }
+        }
+        self.push_paragraph(&paragraph);
+    }
+
+    /// Append another converted text as the following paragraphs.
+    pub fn append(&mut self, other: ConvertedText) {
+        if other.is_empty() {
+            return;
+        }
+        let shift = if self.text.is_empty() {
+            0
+        } else {
+            self.chars + 2
+        };
+        if !self.text.is_empty() {
+            self.text.push_str("\n\n");
+        }
+        self.text.push_str(&other.text);
+        self.chars = shift + other.chars;
+        self.offsets
+            .pages
+            .extend(other.offsets.pages.into_iter().map(|s| s.shifted(shift)));
+        self.offsets.paragraphs.extend(
+            other
+                .offsets
+                .paragraphs
+                .into_iter()
+                .map(|s| s.shifted(shift)),
+        );
+        self.title = self.title.take().or(other.title);
+    }
+}
+
+/// Text already in plain form, split into paragraphs at blank lines.
+pub fn plain_text(text: &str) -> ConvertedText {
+    let mut converted = ConvertedText::default();
+    converted.push_text(text);
+    converted
+}
+
+/// Extract the text of a file in `format`.
+pub fn convert(bytes: &[u8], format: DocumentFormat) -> Result<ConvertedText> {
+    match format {
+        DocumentFormat::PlainText => Ok(plain_text(utf8(bytes)?)),
+        DocumentFormat::Html => Ok(html_text(utf8(bytes)?)),
+        DocumentFormat::Pdf => pdf_text(bytes),
+    }
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str> {
+    std::str::from_utf8(bytes)
+        .map_err(|_| ArgusError::Extraction("File is not UTF-8 text".to_string()))
+}
+
+/// Visible text of an HTML page: scripts, styles and navigation dropped,
+/// block elements as paragraphs, table cells separated by spaces.
+pub fn html_text(html: &str) -> ConvertedText {
+    let document = Html::parse_document(html);
+    let mut converted = ConvertedText::default();
+    let mut paragraph = String::new();
+    // Depth inside a skipped element; text is ignored while above zero.
+    let mut skipping = 0usize;
+
+    for edge in document.tree.root().traverse() {
+        match edge {
+            Edge::Open(node) => match node.value() {
+                Node::Element(element) => {
+                    let name = element.name();
+                    if skipping > 0 || SKIPPED_ELEMENTS.contains(&name) {
+                        skipping += 1;
+                    } else if name == "br" {
+                        paragraph.push('\n');
+                    } else if name == "td" || name == "th" {
+                        paragraph.push(' ');
+                    } else if BLOCK_ELEMENTS.contains(&name) {
+                        converted.push_paragraph(&paragraph);
+                        paragraph.clear();
+                    }
+                }
+                Node::Text(text) if skipping == 0 => paragraph.push_str(text),
+                _ => {}
+            },
+            Edge::Close(node) => {
+                if let Node::Element(element) = node.value() {
+                    if skipping > 0 {
+                        skipping -= 1;
+                    } else if BLOCK_ELEMENTS.contains(&element.name()) {
+                        converted.push_paragraph(&paragraph);
+                        paragraph.clear();
+                    }
+                }
+            }
+        }
+    }
+    converted.push_paragraph(&paragraph);
+
+    let title_selector = Selector::parse("title").expect("valid selector");
+    converted.title = document
+        .select(&title_selector)
+        .next()
+        .map(|title| {
+            title
+                .text()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|title| !title.is_empty());
+    converted
+}
+
+/// Text of each page of a PDF, with page boundaries.
+pub fn pdf_text(bytes: &[u8]) -> Result<ConvertedText> {
+    // pdf-extract panics on some malformed files rather than erroring.
+    let pages = std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem_by_pages(bytes))
+        .map_err(|_| ArgusError::Extraction("PDF could not be parsed".to_string()))?
+        .map_err(|e| ArgusError::Extraction(format!("PDF could not be parsed: {e}")))?;
+
+    let mut converted = ConvertedText::default();
+    for page in &pages {
+        let first = converted.offsets.paragraphs.len();
+        converted.push_text(page);
+        let added = &converted.offsets.paragraphs[first..];
+        let span = match (added.first(), added.last()) {
+            (Some(head), Some(tail)) => TextSpan {
+                start: head.start,
+                end: tail.end,
+            },
+            // A page without text, e.g. a scanned image; kept so later
+            // page numbers stay right.
+            _ => TextSpan {
+                start: converted.chars,
+                end: converted.chars,
+            },
+        };
+        converted.offsets.pages.push(span);
+    }
+    if converted.is_empty() {
+        return Err(ArgusError::Extraction(
+            "PDF contains no extractable text; scanned pages need OCR first".to_string(),
+        ));
+    }
+    Ok(converted)
+}
+
+/// Lowercased characters of `text` with whitespace runs collapsed to one
+/// space, and the character offset in `text` of each.
+fn folded(text: &str) -> (Vec<char>, Vec<usize>) {
+    let mut chars = Vec::new();
+    let mut positions = Vec::new();
+    for (offset, c) in text.chars().enumerate() {
+        if c.is_whitespace() {
+            if chars.last().is_none_or(|last| *last == ' ') {
+                continue;
+            }
+            chars.push(' ');
+        } else {
+            chars.push(c.to_ascii_lowercase());
+        }
+        positions.push(offset);
+    }
+    (chars, positions)
+}
+
+fn find_chars(haystack: &[char], needle: &[char]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraph_texts(converted: &ConvertedText) -> Vec<String> {
+        converted
+            .offsets
+            .paragraphs
+            .iter()
+            .map(|s| {
+                converted
+                    .text
+                    .chars()
+                    .skip(s.start)
+                    .take(s.end - s.start)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn html_keeps_visible_text_as_paragraphs() {
+        let html = r#"<html><head><title> Annual   Report </title><style>p { color: red }</style></head>
+            <body><nav><a href="/">Home</a></nav>
+            <h1>Acme Holdings plc</h1>
+            <p>Acme is   owned by<br>Holdco SA.</p>
+            <table><tr><td>Director</td><td>Jane Roe</td></tr></table>
+            <script>track();</script></body></html>"#;
+        let converted = html_text(html);
+        assert_eq!(converted.title.as_deref(), Some("Annual Report"));
+        assert_eq!(
+            paragraph_texts(&converted),
+            vec![
+                "Acme Holdings plc",
+                "Acme is owned by\nHoldco SA.",
+                "Director Jane Roe"
+            ]
+        );
+        assert_eq!(converted.chars(), converted.text.chars().count());
+    }
+
+    #[test]
+    fn appended_text_shifts_offsets_and_quotes_are_located() {
+        let mut converted = plain_text("Cover note.");
+        converted.append(plain_text(
+            "Ünïcode first.\n\nAcme Ltd paid\n   Holdco SA in 2021.\n",
+        ));
+        assert_eq!(
+            converted.text,
+            "Cover note.\n\nÜnïcode first.\n\nAcme Ltd paid\nHoldco SA in 2021."
+        );
+        assert_eq!(
+            paragraph_texts(&converted)[2],
+            "Acme Ltd paid\nHoldco SA in 2021."
+        );
+
+        let location = converted
+            .offsets
+            .locate(&converted.text, "acme ltd paid Holdco  SA")
+            .unwrap();
+        assert_eq!(location.paragraph, Some(3));
+        assert_eq!(location.page, None);
+        let quoted: String = converted
+            .text
+            .chars()
+            .skip(location.span.start)
+            .take(location.span.end - location.span.start)
+            .collect();
+        assert_eq!(quoted, "Acme Ltd paid\nHoldco SA");
+        assert!(converted
+            .offsets
+            .locate(&converted.text, "not in the text")
+            .is_none());
+    }
+
+    #[test]
+    fn formats_follow_content_types_and_bad_pdfs_are_errors() {
+        assert_eq!(
+            DocumentFormat::from_content_type("application/pdf"),
+            Some(DocumentFormat::Pdf)
+        );
+        assert_eq!(
+            DocumentFormat::from_content_type("text/html"),
+            Some(DocumentFormat::Html)
+        );
+        assert_eq!(
+            DocumentFormat::from_content_type("text/csv"),
+            Some(DocumentFormat::PlainText)
+        );
+        assert_eq!(DocumentFormat::from_content_type("image/png"), None);
+        assert!(convert(b"%PDF-1.4 truncated", DocumentFormat::Pdf).is_err());
+        assert!(convert(&[0xff, 0xfe], DocumentFormat::PlainText).is_err());
+    }
+}
@@ -0,0 +1,427 @@
+//! Coordinates for extracted Location entities.
+//!
+//! The LLM names places but cannot place them. After validation the
+//! pipeline hands each result to [`LocationGeocoder`], which looks the
+//! Location names up with a [`Geocoder`] (Nominatim or Photon) and records
+//! `latitude`, `longitude` and `country` on the entities, as reference
+//! places carry them. Answers, misses included, are cached per name.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use argus_core::config::AppConfig;
+use argus_core::entity::{EntityType, ExtractionResult};
+use argus_core::error::{ArgusError, Result};
+use argus_core::extraction::{GeocodedPlace, Geocoder};
+use argus_core::shared::RateLimiter;
+
+use crate::normalize::normalize_name;
+
+const NOMINATIM_URL: &str = "https://nominatim.openstreetmap.org";
+const PHOTON_URL: &str = "https://photon.komoot.io";
+/// Nominatim's usage policy asks for an identifying user agent.
+const USER_AGENT: &str = "argus-geocoder/0.1 (+https://github.com/Sportinger/argus)";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Key the shared rate limiter counts geocoder requests under.
+const RATE_LIMIT_KEY: &str = "geocoder";
+/// Names remembered, and for how long.
+const CACHE_CAPACITY: usize = 10_000;
+const CACHE_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Build the geocoder selected by `GEOCODER`, or `None` when disabled.
+pub fn from_config(config: &AppConfig) -> Option<Arc<dyn Geocoder>> {
+    let url = |default: &str| {
+        let url = config.geocoder_url.trim().trim_end_matches('/');
+        if url.is_empty() {
+            default.to_string()
+        } else {
+            url.to_string()
+        }
+    };
+    match config.geocoder.trim().to_lowercase().as_str() {
+        "none" | "off" | "" => None,
+        "nominatim" => Some(Arc::new(NominatimGeocoder::new(&url(NOMINATIM_URL)))),
+        "photon" => Some(Arc::new(PhotonGeocoder::new(&url(PHOTON_URL)))),
+        other => {
+            tracing::warn!(geocoder = %other, "Unknown GEOCODER, geocoding disabled");
+            None
+        }
+    }
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("failed to build reqwest client")
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    geocoder: &str,
+) -> Result<T> {
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ArgusError::Extraction(format!(
+            "{geocoder} returned status {status}"
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| ArgusError::Extraction(format!("Failed to parse {geocoder} response: {e}")))
+}
+
+fn country_code(code: Option<&str>) -> Option<String> {
+    code.map(str::trim)
+        .filter(|c| c.len() == 2)
+        .map(str::to_uppercase)
+}
+
+// ── Nominatim ──────────────────────────────────────────────────────────────
+
+pub struct NominatimGeocoder {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct NominatimPlace {
+    lat: String,
+    lon: String,
+    display_name: Option<String>,
+    #[serde(default)]
+    address: HashMap<String, Value>,
+}
+
+impl NominatimGeocoder {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: client(),
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Geocoder for NominatimGeocoder {
+    fn name(&self) -> &str {
+        "nominatim"
+    }
+
+    async fn geocode(&self, query: &str) -> Result<Option<GeocodedPlace>> {
+        let request = self.client.get(format!("{}/search", self.url)).query(&[
+            ("q", query),
+            ("format", "jsonv2"),
+            ("limit", "1"),
+            ("addressdetails", "1"),
+        ]);
+        let places: Vec<NominatimPlace> = get_json(request, "Nominatim").await?;
+        Ok(places.into_iter().next().and_then(|place| {
+            Some(GeocodedPlace {
+                latitude: place.lat.parse().ok()?,
+                longitude: place.lon.parse().ok()?,
+                country: country_code(place.address.get("country_code").and_then(Value::as_str)),
+                display_name: place.display_name,
+            })
+        }))
+    }
+}
+
+// ── Photon ─────────────────────────────────────────────────────────────────
+
+pub struct PhotonGeocoder {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct PhotonResponse {
+    #[serde(default)]
+    features: Vec<PhotonFeature>,
+}
+
+#[derive(Deserialize)]
+struct PhotonFeature {
+    geometry: PhotonGeometry,
+    #[serde(default)]
+    properties: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct PhotonGeometry {
+    /// `[longitude, latitude]`
+    coordinates: [f64; 2],
+}
+
+impl PhotonGeocoder {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: client(),
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Geocoder for PhotonGeocoder {
+    fn name(&self) -> &str {
+        "photon"
+    }
+
+    async fn geocode(&self, query: &str) -> Result<Option<GeocodedPlace>> {
+        let request = self
+            .client
+            .get(format!("{}/api", self.url))
+            .query(&[("q", query), ("limit", "1")]);
+        let response: PhotonResponse = get_json(request, "Photon").await?;
+        Ok(response.features.into_iter().next().map(|feature| {
+            let text = |key: &str| feature.properties.get(key).and_then(Value::as_str);
+            let display_name = [text("name"), text("city"), text("state"), text("country")]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", ");
+            GeocodedPlace {
+                latitude: feature.geometry.coordinates[1],
+                longitude: feature.geometry.coordinates[0],
+                country: country_code(text("countrycode")),
+                display_name: Some(display_name).filter(|n| !n.is_empty()),
+            }
+        }))
+    }
+}
+
+// ── Pipeline stage ─────────────────────────────────────────────────────────
+
+/// Geocodes the Location entities of extraction results, caching answers
+/// and keeping to the configured request rate.
+pub struct LocationGeocoder {
+    geocoder: Arc<dyn Geocoder>,
+    cache: Mutex<HashMap<String, (Option<GeocodedPlace>, Instant)>>,
+}
+
+impl LocationGeocoder {
+    pub fn new(geocoder: Arc<dyn Geocoder>) -> Self {
+        Self {
+            geocoder,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record coordinates on the Location entities in `result` that have
+    /// none. Country Locations keep their reference outline instead. A
+    /// lookup that fails leaves its entity as it was. Returns how many
+    /// entities were geocoded.
+    pub async fn geocode_locations(
+        &self,
+        result: &mut ExtractionResult,
+        limiter: &dyn RateLimiter,
+        requests_per_minute: u32,
+    ) -> usize {
+        let mut geocoded = 0;
+        for entity in result
+            .entities
+            .iter_mut()
+            .filter(|e| needs_geocoding(&e.entity_type, &e.properties, e.geometry.is_some()))
+        {
+            let key = normalize_name(&entity.name, &entity.entity_type).to_lowercase();
+            if key.is_empty() {
+                continue;
+            }
+            let place = match self.cached(&key) {
+                Some(place) => place,
+                None => {
+                    if let Err(e) = limiter.acquire(RATE_LIMIT_KEY, requests_per_minute).await {
+                        tracing::warn!(error = %e, "Geocoder rate limiter failed");
+                        continue;
+                    }
+                    match self.geocoder.geocode(&entity.name).await {
+                        Ok(place) => {
+                            self.remember(key, place.clone());
+                            place
+                        }
+                        Err(e) => {
+                            tracing::warn!(name = %entity.name, error = %e, "Geocoding failed");
+                            continue;
+                        }
+                    }
+                }
+            };
+            let Some(place) = place else {
+                tracing::debug!(name = %entity.name, "No geocoder match");
+                continue;
+            };
+            apply(&mut entity.properties, &place, self.geocoder.name());
+            geocoded += 1;
+        }
+        geocoded
+    }
+
+    fn cached(&self, key: &str) -> Option<Option<GeocodedPlace>> {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .get(key)
+            .filter(|(_, at)| at.elapsed() < CACHE_TTL)
+            .map(|(place, _)| place.clone())
+    }
+
+    fn remember(&self, key: String, place: Option<GeocodedPlace>) {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if cache.len() >= CACHE_CAPACITY {
+            cache.retain(|_, (_, at)| at.elapsed() < CACHE_TTL);
+            if cache.len() >= CACHE_CAPACITY {
+                cache.clear();
+            }
+        }
+        cache.insert(key, (place, Instant::now()));
+    }
+}
+
+fn needs_geocoding(entity_type: &EntityType, properties: &Value, has_geometry: bool) -> bool {
+    *entity_type == EntityType::Location
+        && !has_geometry
+        && properties.get("latitude").is_none()
+        && properties.get("location_type").and_then(Value::as_str) != Some("country")
+}
+
+/// Write `place` into an entity's properties. A country the source stated
+/// is kept.
+fn apply(properties: &mut Value, place: &GeocodedPlace, geocoder: &str) {
+    if !properties.is_object() {
+        *properties = Value::Object(Default::default());
+    }
+    let Some(map) = properties.as_object_mut() else {
+        return;
+    };
+    map.insert("latitude".to_string(), place.latitude.into());
+    map.insert("longitude".to_string(), place.longitude.into());
+    if let Some(country) = &place.country {
+        map.entry("country")
+            .or_insert_with(|| country.clone().into());
+    }
+    if let Some(name) = &place.display_name {
+        map.insert("geocoded_name".to_string(), name.clone().into());
+    }
+    map.insert("geocoder".to_string(), geocoder.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use argus_core::entity::Entity;
+    use serde_json::json;
+
+    use super::*;
+
+    struct Gazetteer {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Geocoder for Gazetteer {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn geocode(&self, query: &str) -> Result<Option<GeocodedPlace>> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Ok((query == "Limassol").then(|| GeocodedPlace {
+                latitude: 34.68,
+                longitude: 33.04,
+                country: Some("CY".to_string()),
+                display_name: Some("Limassol, Cyprus".to_string()),
+            }))
+        }
+    }
+
+    struct Unlimited;
+
+    #[async_trait]
+    impl RateLimiter for Unlimited {
+        async fn acquire(&self, _key: &str, _per_minute: u32) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn location(name: &str, properties: Value) -> Entity {
+        let mut entity = Entity::new(EntityType::Location, name.to_string(), "gdelt".to_string());
+        entity.properties = properties;
+        entity
+    }
+
+    fn result(entities: Vec<Entity>) -> ExtractionResult {
+        ExtractionResult {
+            entities,
+            relationships: Vec::new(),
+            raw_source: "doc".to_string(),
+            extracted_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn locations_get_coordinates_once_per_name() {
+        let gazetteer = Arc::new(Gazetteer {
+            calls: AtomicUsize::new(0),
+        });
+        let geocoder = LocationGeocoder::new(gazetteer.clone());
+        let mut first = result(vec![
+            location("Limassol", json!({})),
+            location("Atlantis", json!({})),
+            location("Cyprus", json!({"location_type": "country", "iso2": "CY"})),
+            location("Riga", json!({"latitude": 56.95, "longitude": 24.1})),
+            Entity::new(
+                EntityType::Person,
+                "Limassol".to_string(),
+                "gdelt".to_string(),
+            ),
+        ]);
+
+        assert_eq!(
+            geocoder.geocode_locations(&mut first, &Unlimited, 60).await,
+            1
+        );
+        let limassol = &first.entities[0].properties;
+        assert_eq!(limassol["latitude"], 34.68);
+        assert_eq!(limassol["longitude"], 33.04);
+        assert_eq!(limassol["country"], "CY");
+        assert_eq!(limassol["geocoder"], "test");
+        assert!(first.entities[1].properties.get("latitude").is_none());
+        assert!(first.entities[2].properties.get("latitude").is_none());
+        assert!(first.entities[4].properties.get("latitude").is_none());
+
+        // Hits and misses are both remembered
+        let mut second = result(vec![
+            location("limassol", json!({"country": "XX"})),
+            location("Atlantis", json!({})),
+        ]);
+        assert_eq!(
+            geocoder
+                .geocode_locations(&mut second, &Unlimited, 60)
+                .await,
+            1
+        );
+        assert_eq!(second.entities[0].properties["country"], "XX");
+        assert_eq!(gazetteer.calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn geocoder_is_chosen_by_config() {
+        let mut config = AppConfig::from_lookup(|_| Err(std::env::VarError::NotPresent));
+        assert!(from_config(&config).is_none());
+        config.geocoder = "Photon".to_string();
+        assert_eq!(from_config(&config).unwrap().name(), "photon");
+        config.geocoder = "nominatim".to_string();
+        config.geocoder_url = "http://localhost:8088/".to_string();
+        assert_eq!(from_config(&config).unwrap().name(), "nominatim");
+    }
+}
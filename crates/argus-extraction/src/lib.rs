@@ -1,3 +1,16 @@
+pub mod cache;
+pub mod countries;
+pub mod formats;
+pub mod geocode;
+pub mod normalize;
 mod pipeline;
+pub mod prompts;
+mod router;
+pub mod validation;
 
+pub use cache::{DiskExtractionCache, RedisExtractionCache};
+pub use geocode::{LocationGeocoder, NominatimGeocoder, PhotonGeocoder};
 pub use pipeline::LlmExtractionPipeline;
+pub use prompts::ActivePrompts;
+pub use router::{HybridExtractionPipeline, PipelineRouter, StructuredExtractionPipeline};
+pub use validation::{DenyList, ValidationRule, Validator, Verdict};
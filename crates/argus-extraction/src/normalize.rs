@@ -0,0 +1,235 @@
+//! Canonical forms for entity names, applied before entity resolution so
+//! that "ACME HOLDINGS LIMITED" and "Acme Holdings Ltd." land on the same
+//! graph node.
+//!
+//! Steps run in order: transliterate non-Latin scripts, collapse
+//! whitespace, fold all-caps names to title case and, for organizations,
+//! canonicalize trailing legal-form suffixes.
+
+use argus_core::entity::EntityType;
+
+/// Words kept lowercase when folding an all-caps name, unless first.
+const LOWERCASE_PARTICLES: &[&str] = &[
+    "of", "the", "and", "for", "in", "on", "at", "de", "der", "van", "von", "da", "del", "du",
+    "la", "le",
+];
+
+/// Legal-form suffixes, as lowercase tokens with dots and commas removed,
+/// and the form they are rewritten to. Longer suffixes come first.
+const LEGAL_SUFFIXES: &[(&[&str], &str)] = &[
+    (&["public", "limited", "company"], "PLC"),
+    (&["limited", "liability", "company"], "LLC"),
+    (&["limited", "liability", "partnership"], "LLP"),
+    (&["limited", "partnership"], "LP"),
+    (&["limited"], "Ltd"),
+    (&["ltd"], "Ltd"),
+    (&["incorporated"], "Inc"),
+    (&["inc"], "Inc"),
+    (&["corporation"], "Corp"),
+    (&["corp"], "Corp"),
+    (&["company"], "Co"),
+    (&["co"], "Co"),
+    (&["plc"], "PLC"),
+    (&["llc"], "LLC"),
+    (&["llp"], "LLP"),
+    (&["lp"], "LP"),
+    (&["gmbh"], "GmbH"),
+    (&["ag"], "AG"),
+    (&["sa"], "SA"),
+    (&["sarl"], "SARL"),
+    (&["srl"], "SRL"),
+    (&["spa"], "SpA"),
+    (&["bv"], "BV"),
+    (&["nv"], "NV"),
+    (&["ab"], "AB"),
+    (&["asa"], "ASA"),
+    (&["pte"], "Pte"),
+    (&["pty"], "Pty"),
+    (&["ooo"], "OOO"),
+    (&["oao"], "OAO"),
+    (&["zao"], "ZAO"),
+    (&["pao"], "PAO"),
+];
+
+/// The canonical form of `name` for an entity of `entity_type`.
+pub fn normalize_name(name: &str, entity_type: &EntityType) -> String {
+    let name = transliterate(name);
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = fold_case(&name);
+    match entity_type {
+        EntityType::Organization => normalize_legal_suffix(&name),
+        _ => name,
+    }
+}
+
+/// Latin letters (with or without diacritics) and anything that is not a
+/// letter are kept; letters from other scripts are romanized.
+fn is_kept(c: char) -> bool {
+    c.is_ascii()
+        || !c.is_alphabetic()
+        || matches!(c, '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}')
+}
+
+/// Romanize Cyrillic, Greek, Arabic, CJK and other non-Latin letters.
+/// "Müller" is left alone; "Владимир Путин" becomes "Vladimir Putin".
+pub fn transliterate(name: &str) -> String {
+    if name.chars().all(is_kept) {
+        return name.to_string();
+    }
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        match deunicode::deunicode_char(c) {
+            Some(latin) if !is_kept(c) => out.push_str(latin),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Title-case names written entirely in capitals ("VLADIMIR PUTIN" →
+/// "Vladimir Putin"). Single words are left alone since they are as
+/// likely to be acronyms ("NATO") as names.
+pub fn fold_case(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let all_caps = name.chars().any(char::is_uppercase) && !name.chars().any(char::is_lowercase);
+    if words.len() < 2 || !all_caps {
+        return name.to_string();
+    }
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            if i > 0 && LOWERCASE_PARTICLES.contains(&lower.as_str()) {
+                lower
+            } else {
+                title_case_word(&lower)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Capitalize the first letter and any letter after a hyphen, apostrophe
+/// or dot, so "o'brien" and "jean-pierre" come out right.
+fn title_case_word(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut capitalize = true;
+    for c in word.chars() {
+        if capitalize && c.is_alphabetic() {
+            out.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            out.push(c);
+        }
+        if matches!(c, '-' | '\'' | '’' | '.') {
+            capitalize = true;
+        }
+    }
+    out
+}
+
+fn suffix_key(token: &str) -> String {
+    token
+        .chars()
+        .filter(|c| !matches!(c, '.' | ','))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Rewrite trailing legal forms to one spelling: "Acme, Limited" and
+/// "ACME Ltd." both become "Acme Ltd". A name that is only a suffix is
+/// left alone.
+pub fn normalize_legal_suffix(name: &str) -> String {
+    let tokens: Vec<&str> = name.split_whitespace().collect();
+    let keys: Vec<String> = tokens.iter().map(|t| suffix_key(t)).collect();
+
+    let mut end = tokens.len();
+    let mut suffixes = Vec::new();
+    'outer: while end > 1 {
+        for (pattern, canonical) in LEGAL_SUFFIXES {
+            let len = pattern.len();
+            if len < end
+                && keys[end - len..end]
+                    .iter()
+                    .zip(pattern.iter())
+                    .all(|(k, p)| k == p)
+            {
+                suffixes.push(*canonical);
+                end -= len;
+                continue 'outer;
+            }
+        }
+        break;
+    }
+    if suffixes.is_empty() {
+        return name.to_string();
+    }
+
+    let mut words: Vec<String> = tokens[..end].iter().map(|t| t.to_string()).collect();
+    if let Some(last) = words.last_mut() {
+        let trimmed = last.trim_end_matches(',').len();
+        last.truncate(trimmed);
+    }
+    words.extend(suffixes.iter().rev().map(|s| s.to_string()));
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_all_caps_names_but_not_acronyms() {
+        assert_eq!(fold_case("VLADIMIR PUTIN"), "Vladimir Putin");
+        assert_eq!(fold_case("BANK OF AMERICA"), "Bank of America");
+        assert_eq!(fold_case("JEAN-PIERRE O'BRIEN"), "Jean-Pierre O'Brien");
+        assert_eq!(fold_case("NATO"), "NATO");
+        assert_eq!(fold_case("Deutsche Bank AG"), "Deutsche Bank AG");
+    }
+
+    #[test]
+    fn canonicalizes_legal_suffixes() {
+        assert_eq!(
+            normalize_legal_suffix("Acme Holdings Limited"),
+            "Acme Holdings Ltd"
+        );
+        assert_eq!(
+            normalize_legal_suffix("Acme Holdings Ltd."),
+            "Acme Holdings Ltd"
+        );
+        assert_eq!(normalize_legal_suffix("Acme, Inc."), "Acme Inc");
+        assert_eq!(normalize_legal_suffix("Acme Co., Ltd."), "Acme Co Ltd");
+        assert_eq!(
+            normalize_legal_suffix("Widget Limited Liability Company"),
+            "Widget LLC"
+        );
+        assert_eq!(normalize_legal_suffix("Siemens G.m.b.H."), "Siemens GmbH");
+        assert_eq!(normalize_legal_suffix("Limited"), "Limited");
+        assert_eq!(normalize_legal_suffix("Acme Trading"), "Acme Trading");
+    }
+
+    #[test]
+    fn transliterates_non_latin_scripts_only() {
+        assert_eq!(transliterate("Владимир Путин"), "Vladimir Putin");
+        assert_eq!(transliterate("Αθήνα"), "Athena");
+        assert_eq!(transliterate("Müller «Gazprom»"), "Müller «Gazprom»");
+    }
+
+    #[test]
+    fn normalization_applies_suffixes_to_organizations_only() {
+        assert_eq!(
+            normalize_name("  ACME   HOLDINGS LIMITED ", &EntityType::Organization),
+            "Acme Holdings Ltd"
+        );
+        assert_eq!(
+            normalize_name("ООО Ромашка", &EntityType::Organization),
+            "OOO Romashka"
+        );
+        assert_eq!(
+            normalize_name("JACK LIMITED", &EntityType::Person),
+            "Jack Limited"
+        );
+    }
+}
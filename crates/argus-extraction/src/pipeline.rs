@@ -1,25 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use argus_core::agent::RawDocument;
 use argus_core::config::AppConfig;
-use argus_core::entity::{Entity, EntityType, ExtractionResult, RelationType, Relationship};
+use argus_core::entity::{
+    normalize_identifier_scheme, Entity, EntityType, ExtractionResult, RelationType, Relationship,
+    EVENT_KEY_PREFIX,
+};
 use argus_core::error::{ArgusError, Result};
-use argus_core::extraction::ExtractionPipeline;
+use argus_core::extraction::{
+    BatchExtraction, CacheStats, ExtractionCache, ExtractionPipeline, FailedExtraction,
+};
+use argus_core::llm::{with_retries, LlmCall, LlmCallLog, LlmError, LlmErrorKind, LlmPurpose};
+use argus_core::ontology::Ontology;
+
+use crate::formats::TextOffsets;
+use crate::prompts::{apply_prompt_version, ActivePrompts, PickedPrompt};
 
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const MODEL: &str = "claude-haiku-4-5-20251001";
 const MAX_TOKENS: u32 = 4096;
+/// Times a document the model finds too long is halved before giving up,
+/// so it is extracted in at most 16 pieces.
+const MAX_SPLIT_DEPTH: u32 = 4;
 
 /// LLM-based entity and relationship extraction pipeline using the Anthropic Messages API.
 pub struct LlmExtractionPipeline {
     client: reqwest::Client,
     api_key: String,
+    api_url: String,
     model: String,
+    ontology: Ontology,
+    cache: Option<Arc<dyn ExtractionCache>>,
+    prompts: Arc<ActivePrompts>,
+    /// Retries of overloaded, rate-limited and failed calls.
+    max_retries: u32,
+    /// Where every call is recorded when `LLM_LOG_ENABLED` is set.
+    call_log: Option<Arc<dyn LlmCallLog>>,
 }
 
 // ── Anthropic Messages API request/response types ──────────────────────────
@@ -71,6 +92,9 @@ struct LlmEntity {
     aliases: Vec<String>,
     #[serde(default)]
     properties: serde_json::Value,
+    /// External identifiers stated in the text, by scheme.
+    #[serde(default)]
+    identifiers: BTreeMap<String, serde_json::Value>,
     #[serde(default = "default_confidence")]
     confidence: f64,
 }
@@ -83,6 +107,9 @@ struct LlmRelationship {
     relation_type: String,
     #[serde(default)]
     properties: serde_json::Value,
+    /// Supporting quote; stored as the `evidence` property.
+    #[serde(default)]
+    evidence: Option<String>,
     #[serde(default = "default_confidence")]
     confidence: f64,
 }
@@ -91,6 +118,253 @@ fn default_confidence() -> f64 {
     1.0
 }
 
+/// Date extracted items by when the source says the fact happened rather
+/// than when it was collected: entities get `observed_at` and relationships
+/// their `timestamp`.
+fn apply_observed_at(result: &mut ExtractionResult, observed_at: Option<DateTime<Utc>>) {
+    let Some(observed_at) = observed_at else {
+        return;
+    };
+    for entity in &mut result.entities {
+        entity.observed_at = Some(
+            entity
+                .observed_at
+                .map_or(observed_at, |at| at.min(observed_at)),
+        );
+    }
+    for relationship in &mut result.relationships {
+        relationship.timestamp = Some(observed_at);
+    }
+}
+
+/// Make a cached result a fresh extraction: new entity and relationship
+/// ids (relationship endpoints follow their entities), seen and extracted
+/// `now`, and no times from the document it was first extracted from.
+/// Without this, two documents hitting one cache entry would write the same
+/// ids and the second would inherit the first's dates.
+fn refresh_cached(result: &mut ExtractionResult, now: DateTime<Utc>) {
+    let mut ids = HashMap::new();
+    for entity in &mut result.entities {
+        let id = Uuid::new_v4();
+        ids.insert(entity.id, id);
+        entity.id = id;
+        entity.first_seen = now;
+        entity.last_seen = now;
+        entity.observed_at = None;
+    }
+    for relationship in &mut result.relationships {
+        relationship.id = Uuid::new_v4();
+        relationship.source_entity_id = ids
+            .get(&relationship.source_entity_id)
+            .copied()
+            .unwrap_or(relationship.source_entity_id);
+        relationship.target_entity_id = ids
+            .get(&relationship.target_entity_id)
+            .copied()
+            .unwrap_or(relationship.target_entity_id);
+        relationship.timestamp = None;
+    }
+    result.extracted_at = now;
+}
+
+/// Apply what comes from the document rather than the model: its source
+/// record id, dates, subject identifiers, GDELT event key, evidence
+/// locations and the prompt version that extracted it.
+fn apply_document(
+    result: &mut ExtractionResult,
+    document: &RawDocument,
+    picked: Option<&PickedPrompt>,
+) {
+    result.raw_source = document.source_id.clone();
+    apply_observed_at(result, document.observed_at);
+    apply_subject_identifiers(result, document);
+    apply_event_key(result, document);
+    apply_evidence_locations(result, document);
+    apply_prompt_version(result, picked);
+}
+
+/// Names a document title may give its subject: the whole title and, for
+/// titles like "UAL123 (a1b2c3)", the parts outside and inside the
+/// parentheses. Lowercased.
+fn subject_names(title: &str) -> Vec<String> {
+    let title = title.trim().to_lowercase();
+    let mut names = vec![title.clone()];
+    if let Some((outside, rest)) = title.split_once(" (") {
+        names.push(outside.trim().to_string());
+        if let Some(inside) = rest.strip_suffix(')') {
+            names.push(inside.trim().to_string());
+        }
+    }
+    names.retain(|n| !n.is_empty());
+    names
+}
+
+/// Attach the document's subject identifiers to the entity its title
+/// names, or to the only entity when the title matches none. The agent's
+/// identifiers win over any the LLM read from the text.
+fn apply_subject_identifiers(result: &mut ExtractionResult, document: &RawDocument) {
+    if document.identifiers.is_empty() {
+        return;
+    }
+    let names = document
+        .title
+        .as_deref()
+        .map(subject_names)
+        .unwrap_or_default();
+    let is_subject = |entity: &Entity| {
+        std::iter::once(&entity.name)
+            .chain(&entity.aliases)
+            .any(|n| names.contains(&n.to_lowercase()))
+    };
+
+    let mut matched = false;
+    for entity in result.entities.iter_mut().filter(|e| is_subject(e)) {
+        entity.identifiers.extend(document.identifiers.clone());
+        matched = true;
+    }
+    if !matched {
+        if let [only] = result.entities.as_mut_slice() {
+            only.identifiers.extend(document.identifiers.clone());
+        }
+    }
+}
+
+/// Key the event a GDELT record's prose describes on the record's
+/// `event_key` (day, CAMEO code, actors and place), so later reports of
+/// the same event land on one `Event` and raise its mention count, as
+/// structured events do. Only a result with a single event is keyed.
+fn apply_event_key(result: &mut ExtractionResult, document: &RawDocument) {
+    let Some(key) = document
+        .metadata
+        .get("event_key")
+        .and_then(serde_json::Value::as_str)
+    else {
+        return;
+    };
+    if !key.starts_with(EVENT_KEY_PREFIX) {
+        return;
+    }
+    let mut events = result
+        .entities
+        .iter_mut()
+        .filter(|e| e.entity_type == EntityType::Event);
+    if let (Some(event), None) = (events.next(), events.next()) {
+        event.source_id = Some(key.to_string());
+    }
+}
+
+/// Record where each relationship's evidence quote sits in a converted
+/// file: `evidence_offset` (characters into the document text) and, when
+/// known, `evidence_page` and `evidence_paragraph`.
+fn apply_evidence_locations(result: &mut ExtractionResult, document: &RawDocument) {
+    let Some(offsets) = TextOffsets::from_metadata(&document.metadata) else {
+        return;
+    };
+    for relationship in &mut result.relationships {
+        let Some(map) = relationship.properties.as_object_mut() else {
+            continue;
+        };
+        let Some(location) = map
+            .get("evidence")
+            .and_then(|e| e.as_str())
+            .and_then(|quote| offsets.locate(&document.content, quote))
+        else {
+            continue;
+        };
+        map.insert("evidence_offset".to_string(), location.span.start.into());
+        if let Some(page) = location.page {
+            map.insert("evidence_page".to_string(), page.into());
+        }
+        if let Some(paragraph) = location.paragraph {
+            map.insert("evidence_paragraph".to_string(), paragraph.into());
+        }
+    }
+}
+
+/// `content` cut in two near its middle, at a paragraph break if there is
+/// one, else a line break, else a space. `None` when it cannot be cut.
+fn split_content(content: &str) -> Option<(&str, &str)> {
+    let mut middle = content.len() / 2;
+    while !content.is_char_boundary(middle) {
+        middle -= 1;
+    }
+    let cut = ["\n\n", "\n", " "].iter().find_map(|separator| {
+        let before = content[..middle]
+            .rfind(separator)
+            .map(|i| i + separator.len());
+        let after = content[middle..]
+            .find(separator)
+            .map(|i| middle + i + separator.len());
+        match (before, after) {
+            (Some(b), Some(a)) => Some(if middle - b <= a - middle { b } else { a }),
+            (b, a) => b.or(a),
+        }
+    })?;
+    let (first, second) = content.split_at(cut);
+    (!first.trim().is_empty() && !second.trim().is_empty()).then_some((first, second))
+}
+
+/// One extraction from the pieces of a split document. An entity named in
+/// several pieces (same type and name) is kept once, with the others'
+/// aliases, identifiers and properties added and the highest confidence;
+/// relationships are pointed at the kept entity.
+fn merge_chunks(parts: Vec<(Vec<Entity>, Vec<Relationship>)>) -> (Vec<Entity>, Vec<Relationship>) {
+    let mut entities: Vec<Entity> = Vec::new();
+    let mut relationships: Vec<Relationship> = Vec::new();
+    let mut by_key: HashMap<(EntityType, String), usize> = HashMap::new();
+    let mut merged_ids: HashMap<Uuid, Uuid> = HashMap::new();
+
+    for (part_entities, part_relationships) in parts {
+        for entity in part_entities {
+            let key = (entity.entity_type.clone(), entity.name.to_lowercase());
+            let Some(&i) = by_key.get(&key) else {
+                by_key.insert(key, entities.len());
+                entities.push(entity);
+                continue;
+            };
+            let kept = &mut entities[i];
+            merged_ids.insert(entity.id, kept.id);
+            for alias in entity.aliases {
+                if !kept.aliases.contains(&alias) {
+                    kept.aliases.push(alias);
+                }
+            }
+            for (scheme, value) in entity.identifiers {
+                kept.identifiers.entry(scheme).or_insert(value);
+            }
+            if let (Some(kept_props), serde_json::Value::Object(props)) =
+                (kept.properties.as_object_mut(), entity.properties)
+            {
+                for (key, value) in props {
+                    kept_props.entry(key).or_insert(value);
+                }
+            }
+            kept.confidence = kept.confidence.max(entity.confidence);
+        }
+        for mut relationship in part_relationships {
+            for id in [
+                &mut relationship.source_entity_id,
+                &mut relationship.target_entity_id,
+            ] {
+                if let Some(kept) = merged_ids.get(id) {
+                    *id = *kept;
+                }
+            }
+            let duplicate = relationships.iter_mut().find(|r| {
+                r.source_entity_id == relationship.source_entity_id
+                    && r.target_entity_id == relationship.target_entity_id
+                    && r.relation_type == relationship.relation_type
+            });
+            match duplicate {
+                Some(kept) => kept.confidence = kept.confidence.max(relationship.confidence),
+                None => relationships.push(relationship),
+            }
+        }
+    }
+
+    (entities, relationships)
+}
+
 // ── Implementation ─────────────────────────────────────────────────────────
 
 impl LlmExtractionPipeline {
@@ -99,11 +373,67 @@ impl LlmExtractionPipeline {
         Self {
             client,
             api_key: config.anthropic_api_key.clone(),
+            api_url: config.anthropic_api_url.clone(),
             model: MODEL.to_string(),
+            ontology: config.ontology.clone(),
+            cache: crate::cache::from_config(config),
+            prompts: Arc::new(ActivePrompts::default()),
+            max_retries: config.llm_max_retries,
+            call_log: None,
         }
     }
 
-    fn build_system_prompt() -> String {
+    /// Record every call made from now on to `log`.
+    pub fn with_call_log(mut self, log: Option<Arc<dyn LlmCallLog>>) -> Self {
+        self.call_log = log;
+        self
+    }
+
+    /// Replace the configured cache (or disable caching with `None`).
+    pub fn with_cache(mut self, cache: Option<Arc<dyn ExtractionCache>>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The deployed prompt bundles, for loading and replacing them.
+    pub fn prompts(&self) -> &Arc<ActivePrompts> {
+        &self.prompts
+    }
+
+    /// Usage counters for the extraction cache, if one is enabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.stats())
+    }
+
+    fn build_system_prompt(ontology: &Ontology) -> String {
+        let mut entity_types = vec![
+            "person",
+            "organization",
+            "vessel",
+            "aircraft",
+            "location",
+            "event",
+            "document",
+            "transaction",
+            "sanction",
+        ];
+        entity_types.extend(ontology.entity_types.iter().map(String::as_str));
+        let mut relation_types = vec![
+            "owner_of",
+            "director_of",
+            "employee_of",
+            "related_to",
+            "located_at",
+            "transacted_with",
+            "sanctioned_by",
+            "registered_in",
+            "flagged_as",
+            "meeting_with",
+            "traveled_to",
+            "part_of",
+        ];
+        relation_types.extend(ontology.relation_types.iter().map(String::as_str));
+
         r#"You are an entity and relationship extraction system for an intelligence analysis platform.
 
 Given a document, extract all notable entities and the relationships between them.
@@ -114,9 +444,10 @@ Return ONLY valid JSON (no markdown fences, no commentary) matching this exact s
   "entities": [
     {
       "name": "Entity Name",
-      "type": "person | organization | vessel | aircraft | location | event | document | transaction | sanction",
+      "type": "{entity_types}",
       "aliases": ["optional alternate names"],
       "properties": { "arbitrary": "key-value pairs with extra info" },
+      "identifiers": { "wikidata | lei | imo | mmsi | other scheme": "identifier value" },
       "confidence": 0.0 to 1.0
     }
   ],
@@ -124,8 +455,9 @@ Return ONLY valid JSON (no markdown fences, no commentary) matching this exact s
     {
       "source": "Source Entity Name",
       "target": "Target Entity Name",
-      "type": "owner_of | director_of | employee_of | related_to | located_at | transacted_with | sanctioned_by | registered_in | flagged_as | meeting_with | traveled_to | part_of",
+      "type": "{relation_types}",
       "properties": { "arbitrary": "key-value pairs" },
+      "evidence": "short verbatim quote from the document supporting the relationship",
       "confidence": 0.0 to 1.0
     }
   ]
@@ -135,12 +467,19 @@ Rules:
 - Entity names in relationships MUST exactly match an entity in the entities list.
 - Choose the most specific entity type and relationship type that applies.
 - Only extract entities and relationships that are clearly supported by the text.
+- Include "identifiers" only for identifiers the text states verbatim (Wikidata QIDs, LEI codes, IMO or MMSI numbers, registry numbers); never guess. Omit it otherwise.
 - If no entities or relationships can be extracted, return {"entities": [], "relationships": []}.
 - Output ONLY the JSON object. No additional text."#
-            .to_string()
+            .replace("{entity_types}", &entity_types.join(" | "))
+            .replace("{relation_types}", &relation_types.join(" | "))
     }
 
     fn build_user_prompt(document: &RawDocument) -> String {
+        Self::build_chunk_prompt(document, &document.content)
+    }
+
+    /// The user prompt for `content`, the whole of `document` or a piece of it.
+    fn build_chunk_prompt(document: &RawDocument, content: &str) -> String {
         let mut prompt = String::new();
         if let Some(title) = &document.title {
             prompt.push_str(&format!("Title: {}\n", title));
@@ -149,80 +488,156 @@ Rules:
             prompt.push_str(&format!("URL: {}\n", url));
         }
         prompt.push_str(&format!("Source: {}\n", document.source));
-        prompt.push_str(&format!("\nDocument content:\n{}", document.content));
+        prompt.push_str(&format!("\nDocument content:\n{}", content));
         prompt
     }
 
-    async fn call_anthropic(&self, document: &RawDocument) -> Result<String> {
+    #[tracing::instrument(
+        name = "llm_request",
+        skip_all,
+        fields(otel.kind = "client", model = %self.model, source = %document.source, source_id = %document.source_id)
+    )]
+    async fn call_anthropic(
+        &self,
+        document: &RawDocument,
+        system_prompt: &str,
+        user_prompt: String,
+    ) -> Result<String> {
         let request = AnthropicRequest {
             model: self.model.clone(),
             max_tokens: MAX_TOKENS,
-            system: Self::build_system_prompt(),
+            system: system_prompt.to_string(),
             messages: vec![Message {
                 role: "user".to_string(),
-                content: Self::build_user_prompt(document),
+                content: user_prompt,
             }],
         };
 
         tracing::debug!(
             model = %self.model,
             source = %document.source,
-            content_len = document.content.len(),
+            prompt_len = request.messages[0].content.len(),
             "Sending extraction request to Anthropic API"
         );
 
-        let response = self
-            .client
-            .post(ANTHROPIC_API_URL)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| ArgusError::Extraction(format!("HTTP request failed: {e}")))?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let body = response
-                .text()
+        let started_at = Utc::now();
+        let started = std::time::Instant::now();
+        let outcome = with_retries(self.max_retries, || async {
+            let response = self
+                .client
+                .post(&self.api_url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("content-type", "application/json")
+                .json(&request)
+                .send()
                 .await
-                .unwrap_or_else(|_| "<failed to read body>".to_string());
-            return Err(ArgusError::Extraction(format!(
-                "Anthropic API returned status {status}: {body}"
-            )));
+                .map_err(|e| ArgusError::Llm(LlmError::transport(e)))?;
+            if !response.status().is_success() {
+                return Err(ArgusError::Llm(LlmError::from_response(response).await));
+            }
+            response
+                .json()
+                .await
+                .map_err(|e| ArgusError::Extraction(format!("Failed to parse API response: {e}")))
+        })
+        .await
+        .and_then(|api_response: AnthropicResponse| {
+            // Extract the text from the first text content block
+            let text = api_response
+                .content
+                .iter()
+                .find_map(|block| {
+                    if block.block_type == "text" {
+                        block.text.clone()
+                    } else {
+                        None
+                    }
+                })
+                .ok_or_else(|| {
+                    ArgusError::Extraction("No text content block in API response".to_string())
+                })?;
+
+            tracing::debug!(
+                stop_reason = ?api_response.stop_reason,
+                response_len = text.len(),
+                "Received extraction response from Anthropic API"
+            );
+            Ok(text)
+        });
+
+        if let Some(log) = &self.call_log {
+            log.record(LlmCall {
+                id: Uuid::new_v4(),
+                purpose: LlmPurpose::Extraction,
+                model: self.model.clone(),
+                subject: format!("{}/{}", document.source, document.source_id),
+                system: request.system.clone(),
+                prompt: format!("user: {}", request.messages[0].content),
+                response: outcome.as_ref().ok().cloned(),
+                error: outcome.as_ref().err().map(|e| e.to_string()),
+                started_at,
+                duration_ms: started.elapsed().as_millis() as u64,
+            });
         }
 
-        let api_response: AnthropicResponse = response
-            .json()
-            .await
-            .map_err(|e| ArgusError::Extraction(format!("Failed to parse API response: {e}")))?;
+        outcome
+    }
 
-        // Extract the text from the first text content block
-        let text = api_response
-            .content
-            .iter()
-            .find_map(|block| {
-                if block.block_type == "text" {
-                    block.text.clone()
-                } else {
-                    None
+    /// Extract `document`, halving its content and extracting the pieces in
+    /// turn whenever the model says a prompt is too long.
+    async fn extract_chunks(
+        &self,
+        document: &RawDocument,
+        system_prompt: &str,
+        user_prompt: String,
+    ) -> Result<(Vec<Entity>, Vec<Relationship>)> {
+        // Pieces still to extract, the next on top
+        let mut pending = vec![(None::<String>, 0)];
+        let mut parts = Vec::new();
+
+        while let Some((piece, depth)) = pending.pop() {
+            let prompt = match &piece {
+                Some(content) => Self::build_chunk_prompt(document, content),
+                None => user_prompt.clone(),
+            };
+            let raw_json = match self.call_anthropic(document, system_prompt, prompt).await {
+                Ok(raw_json) => raw_json,
+                Err(ArgusError::Llm(e))
+                    if e.kind == LlmErrorKind::ContextLength && depth < MAX_SPLIT_DEPTH =>
+                {
+                    let content = piece.as_deref().unwrap_or(&document.content);
+                    let Some((first, second)) = split_content(content) else {
+                        return Err(ArgusError::Llm(e));
+                    };
+                    tracing::warn!(
+                        source_id = %document.source_id,
+                        content_len = content.len(),
+                        depth = depth + 1,
+                        "Document too long for the model, extracting it in halves"
+                    );
+                    pending.push((Some(second.to_string()), depth + 1));
+                    pending.push((Some(first.to_string()), depth + 1));
+                    continue;
                 }
-            })
-            .ok_or_else(|| {
-                ArgusError::Extraction("No text content block in API response".to_string())
-            })?;
-
-        tracing::debug!(
-            stop_reason = ?api_response.stop_reason,
-            response_len = text.len(),
-            "Received extraction response from Anthropic API"
-        );
+                Err(e) => return Err(e),
+            };
+            let part = Self::parse_llm_response(&raw_json, &document.source, &self.ontology)
+                .map_err(|e| ArgusError::ExtractionOutput {
+                    model: self.model.clone(),
+                    message: match e {
+                        ArgusError::Extraction(message) => message,
+                        other => other.to_string(),
+                    },
+                    raw_output: raw_json.clone(),
+                })?;
+            parts.push(part);
+        }
 
-        Ok(text)
+        Ok(merge_chunks(parts))
     }
 
-    fn parse_entity_type(s: &str) -> EntityType {
+    fn parse_entity_type(s: &str, ontology: &Ontology) -> EntityType {
         match s.to_lowercase().as_str() {
             "person" => EntityType::Person,
             "organization" | "org" | "company" => EntityType::Organization,
@@ -233,14 +648,14 @@ Rules:
             "document" | "report" | "filing" => EntityType::Document,
             "transaction" | "payment" | "transfer" => EntityType::Transaction,
             "sanction" | "sanctions" => EntityType::Sanction,
-            _ => {
+            _ => ontology.custom_entity_type(s).unwrap_or_else(|| {
                 tracing::warn!(entity_type = %s, "Unknown entity type, defaulting to Event");
                 EntityType::Event
-            }
+            }),
         }
     }
 
-    fn parse_relation_type(s: &str) -> RelationType {
+    fn parse_relation_type(s: &str, ontology: &Ontology) -> RelationType {
         match s.to_lowercase().as_str() {
             "owner_of" | "owns" => RelationType::OwnerOf,
             "director_of" | "directs" => RelationType::DirectorOf,
@@ -254,16 +669,17 @@ Rules:
             "meeting_with" | "met_with" => RelationType::MeetingWith,
             "traveled_to" | "visited" => RelationType::TraveledTo,
             "part_of" | "member_of" | "subsidiary_of" => RelationType::PartOf,
-            _ => {
+            _ => ontology.custom_relation_type(s).unwrap_or_else(|| {
                 tracing::warn!(relation_type = %s, "Unknown relation type, defaulting to RelatedTo");
                 RelationType::RelatedTo
-            }
+            }),
         }
     }
 
     fn parse_llm_response(
         raw_json: &str,
         source: &str,
+        ontology: &Ontology,
     ) -> Result<(Vec<Entity>, Vec<Relationship>)> {
         // Strip potential markdown code fences the LLM might include despite instructions
         let cleaned = raw_json.trim();
@@ -288,7 +704,7 @@ Rules:
 
         for llm_entity in &output.entities {
             let id = Uuid::new_v4();
-            let entity_type = Self::parse_entity_type(&llm_entity.entity_type);
+            let entity_type = Self::parse_entity_type(&llm_entity.entity_type, ontology);
 
             let entity = Entity {
                 id,
@@ -305,6 +721,24 @@ Rules:
                 confidence: llm_entity.confidence,
                 first_seen: now,
                 last_seen: now,
+                observed_at: None,
+                sanctions_exposure: None,
+                completeness: None,
+                mention_count: None,
+                geometry: None,
+                identifiers: llm_entity
+                    .identifiers
+                    .iter()
+                    .filter_map(|(scheme, value)| {
+                        let value = match value {
+                            serde_json::Value::String(s) => s.trim().to_string(),
+                            serde_json::Value::Number(n) => n.to_string(),
+                            _ => return None,
+                        };
+                        let scheme = normalize_identifier_scheme(scheme);
+                        (!value.is_empty() && !scheme.is_empty()).then_some((scheme, value))
+                    })
+                    .collect(),
             };
 
             // Store canonical name (lowercased) for lookup
@@ -326,19 +760,34 @@ Rules:
 
             match (source_id, target_id) {
                 (Some(&src), Some(&tgt)) => {
+                    let mut properties = if llm_rel.properties.is_null() {
+                        serde_json::Value::Object(serde_json::Map::new())
+                    } else {
+                        llm_rel.properties.clone()
+                    };
+                    if let (Some(evidence), Some(map)) = (
+                        llm_rel
+                            .evidence
+                            .as_deref()
+                            .map(str::trim)
+                            .filter(|e| !e.is_empty()),
+                        properties.as_object_mut(),
+                    ) {
+                        map.insert(
+                            "evidence".to_string(),
+                            serde_json::Value::String(evidence.to_string()),
+                        );
+                    }
                     let relationship = Relationship {
                         id: Uuid::new_v4(),
                         source_entity_id: src,
                         target_entity_id: tgt,
-                        relation_type: Self::parse_relation_type(&llm_rel.relation_type),
-                        properties: if llm_rel.properties.is_null() {
-                            serde_json::Value::Object(serde_json::Map::new())
-                        } else {
-                            llm_rel.properties.clone()
-                        },
+                        relation_type: Self::parse_relation_type(&llm_rel.relation_type, ontology),
+                        properties,
                         confidence: llm_rel.confidence,
                         source: source.to_string(),
                         timestamp: Some(now),
+                        strength: None,
                     };
                     relationships.push(relationship);
                 }
@@ -374,8 +823,39 @@ impl ExtractionPipeline for LlmExtractionPipeline {
             "Starting entity extraction for document"
         );
 
-        let raw_json = self.call_anthropic(document).await?;
-        let (entities, relationships) = Self::parse_llm_response(&raw_json, &document.source)?;
+        let picked = self.prompts.pick(document);
+        let mut system_prompt = Self::build_system_prompt(&self.ontology);
+        if let Some(bundle) = picked.as_ref().and_then(|p| p.bundle.as_ref()) {
+            if let Some(examples) = bundle.render(&document.source) {
+                system_prompt.push_str(&examples);
+            }
+        }
+        let user_prompt = Self::build_user_prompt(document);
+        let cache_key = self
+            .cache
+            .as_ref()
+            .map(|_| crate::cache::cache_key(&self.model, &system_prompt, &user_prompt));
+
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            match cache.get(key).await {
+                Ok(Some(mut cached)) => {
+                    tracing::info!(
+                        source = %document.source,
+                        source_id = %document.source_id,
+                        "Extraction cache hit, skipping LLM call"
+                    );
+                    refresh_cached(&mut cached, Utc::now());
+                    apply_document(&mut cached, document, picked.as_ref());
+                    return Ok(cached);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(error = %e, "Extraction cache lookup failed"),
+            }
+        }
+
+        let (entities, relationships) = self
+            .extract_chunks(document, &system_prompt, user_prompt)
+            .await?;
 
         tracing::info!(
             source = %document.source,
@@ -384,81 +864,124 @@ impl ExtractionPipeline for LlmExtractionPipeline {
             "Extraction complete"
         );
 
-        Ok(ExtractionResult {
+        let mut result = ExtractionResult {
             entities,
             relationships,
             raw_source: document.source_id.clone(),
             extracted_at: Utc::now(),
-        })
+        };
+
+        // Cached before anything taken from this document is applied, so
+        // a hit on another document with the same text gets its own
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Err(e) = cache.put(key, &result).await {
+                tracing::warn!(error = %e, "Failed to write extraction cache entry");
+            }
+        }
+
+        apply_document(&mut result, document, picked.as_ref());
+        Ok(result)
     }
 
     async fn extract_batch(&self, documents: &[RawDocument]) -> Result<Vec<ExtractionResult>> {
+        let batch = self.extract_batch_detailed(documents).await;
+
+        if batch.results.is_empty() && !batch.failures.is_empty() {
+            let errors: Vec<String> = batch
+                .failures
+                .iter()
+                .map(|f| format!("{}: {}", f.document.source_id, f.error))
+                .collect();
+            return Err(ArgusError::Extraction(format!(
+                "All documents failed extraction: {}",
+                errors.join("; ")
+            )));
+        }
+
+        Ok(batch.results)
+    }
+
+    async fn extract_batch_detailed(&self, documents: &[RawDocument]) -> BatchExtraction {
         tracing::info!(count = documents.len(), "Starting batch extraction");
 
         let mut join_set = tokio::task::JoinSet::new();
+        // Task ids let a panicked task still be traced back to its document
+        let mut task_index = HashMap::with_capacity(documents.len());
 
         for (i, doc) in documents.iter().enumerate() {
             let client = self.client.clone();
             let api_key = self.api_key.clone();
+            let api_url = self.api_url.clone();
             let model = self.model.clone();
+            let ontology = self.ontology.clone();
+            let cache = self.cache.clone();
+            let prompts = self.prompts.clone();
+            let max_retries = self.max_retries;
+            let call_log = self.call_log.clone();
             let doc = doc.clone();
 
-            join_set.spawn(async move {
+            let handle = join_set.spawn(async move {
                 let pipeline = LlmExtractionPipeline {
                     client,
                     api_key,
+                    api_url,
                     model,
+                    ontology,
+                    cache,
+                    prompts,
+                    max_retries,
+                    call_log,
                 };
-                (i, pipeline.extract(&doc).await)
+                pipeline.extract(&doc).await
             });
+            task_index.insert(handle.id(), i);
         }
 
-        let mut extraction_results = Vec::with_capacity(documents.len());
-        let mut errors = Vec::new();
-
-        while let Some(join_result) = join_set.join_next().await {
-            match join_result {
-                Ok((_i, Ok(extraction))) => extraction_results.push(extraction),
-                Ok((i, Err(e))) => {
-                    tracing::error!(
-                        document_index = i,
-                        source = %documents[i].source,
-                        error = %e,
-                        "Extraction failed for document in batch"
-                    );
-                    errors.push(format!(
-                        "Document {} ({}): {}",
-                        i, documents[i].source_id, e
-                    ));
+        let mut batch = BatchExtraction {
+            results: Vec::with_capacity(documents.len()),
+            failures: Vec::new(),
+        };
+
+        while let Some(join_result) = join_set.join_next_with_id().await {
+            let (i, error) = match join_result {
+                Ok((_, Ok(extraction))) => {
+                    batch.results.push(extraction);
+                    continue;
                 }
+                Ok((id, Err(e))) => (task_index[&id], e),
                 Err(join_err) => {
                     tracing::error!(error = %join_err, "Task panicked during batch extraction");
-                    errors.push(format!("Task join error: {join_err}"));
+                    (
+                        task_index[&join_err.id()],
+                        ArgusError::Internal(format!("Task join error: {join_err}")),
+                    )
                 }
-            }
-        }
-
-        if extraction_results.is_empty() && !errors.is_empty() {
-            return Err(ArgusError::Extraction(format!(
-                "All documents failed extraction: {}",
-                errors.join("; ")
-            )));
+            };
+            tracing::error!(
+                document_index = i,
+                source = %documents[i].source,
+                error = %error,
+                "Extraction failed for document in batch"
+            );
+            batch
+                .failures
+                .push(FailedExtraction::new(documents[i].clone(), &error));
         }
 
-        if !errors.is_empty() {
+        if !batch.failures.is_empty() {
             tracing::warn!(
-                succeeded = extraction_results.len(),
-                failed = errors.len(),
+                succeeded = batch.results.len(),
+                failed = batch.failures.len(),
                 "Batch extraction completed with partial failures"
             );
         } else {
             tracing::info!(
-                count = extraction_results.len(),
+                count = batch.results.len(),
                 "Batch extraction completed successfully"
             );
         }
 
-        Ok(extraction_results)
+        batch
     }
 }
 
@@ -469,19 +992,31 @@ mod tests {
     #[test]
     fn test_parse_entity_types() {
         assert_eq!(
-            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type("person")),
+            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type(
+                "person",
+                &Ontology::default()
+            )),
             std::mem::discriminant(&EntityType::Person)
         );
         assert_eq!(
-            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type("Organization")),
+            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type(
+                "Organization",
+                &Ontology::default()
+            )),
             std::mem::discriminant(&EntityType::Organization)
         );
         assert_eq!(
-            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type("VESSEL")),
+            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type(
+                "VESSEL",
+                &Ontology::default()
+            )),
             std::mem::discriminant(&EntityType::Vessel)
         );
         assert_eq!(
-            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type("unknown_thing")),
+            std::mem::discriminant(&LlmExtractionPipeline::parse_entity_type(
+                "unknown_thing",
+                &Ontology::default()
+            )),
             std::mem::discriminant(&EntityType::Event)
         );
     }
@@ -489,15 +1024,24 @@ mod tests {
     #[test]
     fn test_parse_relation_types() {
         assert_eq!(
-            std::mem::discriminant(&LlmExtractionPipeline::parse_relation_type("owner_of")),
+            std::mem::discriminant(&LlmExtractionPipeline::parse_relation_type(
+                "owner_of",
+                &Ontology::default()
+            )),
             std::mem::discriminant(&RelationType::OwnerOf)
         );
         assert_eq!(
-            std::mem::discriminant(&LlmExtractionPipeline::parse_relation_type("located_in")),
+            std::mem::discriminant(&LlmExtractionPipeline::parse_relation_type(
+                "located_in",
+                &Ontology::default()
+            )),
             std::mem::discriminant(&RelationType::LocatedAt)
         );
         assert_eq!(
-            std::mem::discriminant(&LlmExtractionPipeline::parse_relation_type("something_else")),
+            std::mem::discriminant(&LlmExtractionPipeline::parse_relation_type(
+                "something_else",
+                &Ontology::default()
+            )),
             std::mem::discriminant(&RelationType::RelatedTo)
         );
     }
@@ -527,13 +1071,14 @@ mod tests {
                     "target": "Acme Corp",
                     "type": "director_of",
                     "properties": {"since": "2020"},
+                    "evidence": "John Smith, a director of Acme Corp since 2020",
                     "confidence": 0.85
                 }
             ]
         }"#;
 
         let (entities, relationships) =
-            LlmExtractionPipeline::parse_llm_response(json, "test").unwrap();
+            LlmExtractionPipeline::parse_llm_response(json, "test", &Ontology::default()).unwrap();
 
         assert_eq!(entities.len(), 2);
         assert_eq!(relationships.len(), 1);
@@ -550,6 +1095,254 @@ mod tests {
         assert_eq!(relationships[0].target_entity_id, entities[0].id);
         assert_eq!(relationships[0].relation_type, RelationType::DirectorOf);
         assert_eq!(relationships[0].confidence, 0.85);
+        assert_eq!(
+            relationships[0].properties["evidence"],
+            "John Smith, a director of Acme Corp since 2020"
+        );
+        assert_eq!(relationships[0].properties["since"], "2020");
+    }
+
+    #[test]
+    fn observed_at_dates_entities_and_relationships() {
+        let json = r#"{
+            "entities": [
+                {"name": "Acme Corp", "type": "organization", "confidence": 0.9},
+                {"name": "John Smith", "type": "person", "confidence": 0.9}
+            ],
+            "relationships": [
+                {"source": "John Smith", "target": "Acme Corp", "type": "director_of"}
+            ]
+        }"#;
+        let (entities, relationships) =
+            LlmExtractionPipeline::parse_llm_response(json, "test", &Ontology::default()).unwrap();
+        let mut result = ExtractionResult {
+            entities,
+            relationships,
+            raw_source: "doc-1".to_string(),
+            extracted_at: Utc::now(),
+        };
+        let incorporated = DateTime::parse_from_rfc3339("2004-07-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        apply_observed_at(&mut result, Some(incorporated));
+
+        assert!(result
+            .entities
+            .iter()
+            .all(|e| e.observed_at == Some(incorporated)));
+        assert_eq!(result.relationships[0].timestamp, Some(incorporated));
+        assert!(result.entities[0].first_seen > incorporated);
+    }
+
+    #[test]
+    fn subject_identifiers_attach_to_the_titled_entity() {
+        let json = r#"{
+            "entities": [
+                {"name": "UAL123", "type": "aircraft", "identifiers": {"ICAO": "a1b2c3"}},
+                {"name": "United Airlines", "type": "organization", "identifiers": {"LEI code": "LEI-1", "note": null}}
+            ],
+            "relationships": []
+        }"#;
+        let (entities, relationships) =
+            LlmExtractionPipeline::parse_llm_response(json, "adsb", &Ontology::default()).unwrap();
+        assert_eq!(entities[0].identifiers["icao24"], "a1b2c3");
+        assert_eq!(entities[1].identifiers.len(), 1);
+        assert_eq!(entities[1].identifiers["lei"], "LEI-1");
+
+        let mut result = ExtractionResult {
+            entities,
+            relationships,
+            raw_source: "a1b2c3".to_string(),
+            extracted_at: Utc::now(),
+        };
+        let document = RawDocument {
+            source: "adsb".to_string(),
+            source_id: "a1b2c3".to_string(),
+            title: Some("UAL123 (a1b2c3)".to_string()),
+            content: String::new(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: None,
+            identifiers: [("icao24".to_string(), "A1B2C3".to_string())].into(),
+            metadata: serde_json::Value::Null,
+            structured: None,
+        };
+
+        apply_subject_identifiers(&mut result, &document);
+
+        assert_eq!(result.entities[0].identifiers["icao24"], "A1B2C3");
+        assert!(!result.entities[1].identifiers.contains_key("icao24"));
+    }
+
+    #[test]
+    fn cache_hits_are_fresh_extractions_of_the_second_document() {
+        let json = r#"{
+            "entities": [
+                {"name": "Acme Ltd", "type": "organization"},
+                {"name": "Holdco SA", "type": "organization"}
+            ],
+            "relationships": [
+                {"source": "Holdco SA", "target": "Acme Ltd", "type": "owner_of"}
+            ]
+        }"#;
+        let (entities, relationships) =
+            LlmExtractionPipeline::parse_llm_response(json, "manual", &Ontology::default())
+                .unwrap();
+        let document = |source_id: &str, observed_at: &str| RawDocument {
+            source: "manual".to_string(),
+            source_id: source_id.to_string(),
+            title: None,
+            content: "Acme Ltd is owned by Holdco SA".to_string(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: Some(observed_at.parse().unwrap()),
+            identifiers: Default::default(),
+            metadata: serde_json::Value::Null,
+            structured: None,
+        };
+        let first_at = Utc::now() - chrono::Duration::days(2);
+        let mut first = ExtractionResult {
+            entities,
+            relationships,
+            raw_source: String::new(),
+            extracted_at: first_at,
+        };
+        let cached = first.clone();
+        apply_document(
+            &mut first,
+            &document("filing-1", "2024-01-01T00:00:00Z"),
+            None,
+        );
+
+        let mut second = cached;
+        let now = Utc::now();
+        refresh_cached(&mut second, now);
+        apply_document(
+            &mut second,
+            &document("filing-2", "2025-06-30T00:00:00Z"),
+            None,
+        );
+
+        assert_eq!(second.raw_source, "filing-2");
+        assert_eq!(second.extracted_at, now);
+        for (a, b) in first.entities.iter().zip(&second.entities) {
+            assert_ne!(a.id, b.id);
+            assert_eq!(a.name, b.name);
+            assert_eq!(b.first_seen, now);
+            assert_eq!(
+                b.observed_at.map(|t| t.to_rfc3339()).as_deref(),
+                Some("2025-06-30T00:00:00+00:00")
+            );
+        }
+        let (rel, holdco, acme) = (
+            &second.relationships[0],
+            &second.entities[1],
+            &second.entities[0],
+        );
+        assert_ne!(rel.id, first.relationships[0].id);
+        assert_eq!(
+            (rel.source_entity_id, rel.target_entity_id),
+            (holdco.id, acme.id)
+        );
+        assert_eq!(
+            rel.timestamp.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2025-06-30T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn prose_gdelt_events_are_keyed_like_structured_ones() {
+        let json = r#"{
+            "entities": [
+                {"name": "Talks in Beijing", "type": "event"},
+                {"name": "Acme Shipping", "type": "organization"}
+            ],
+            "relationships": []
+        }"#;
+        let extract = || {
+            let (entities, relationships) =
+                LlmExtractionPipeline::parse_llm_response(json, "gdelt", &Ontology::default())
+                    .unwrap();
+            ExtractionResult {
+                entities,
+                relationships,
+                raw_source: "gdelt-event-1".to_string(),
+                extracted_at: Utc::now(),
+            }
+        };
+        let key = format!("{EVENT_KEY_PREFIX}20260226|04|usa|acme|beijing");
+        let document = RawDocument {
+            source: "gdelt".to_string(),
+            source_id: "gdelt-event-1".to_string(),
+            title: None,
+            content: String::new(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: None,
+            identifiers: Default::default(),
+            metadata: serde_json::json!({ "event_key": key }),
+            structured: None,
+        };
+
+        let mut result = extract();
+        apply_event_key(&mut result, &document);
+        assert_eq!(result.entities[0].source_id.as_deref(), Some(key.as_str()));
+        assert_eq!(result.entities[1].source_id, None);
+
+        // Nothing to key without the record's key
+        let mut result = extract();
+        apply_event_key(
+            &mut result,
+            &RawDocument {
+                metadata: serde_json::Value::Null,
+                ..document
+            },
+        );
+        assert_eq!(result.entities[0].source_id, None);
+    }
+
+    #[test]
+    fn evidence_quotes_are_located_in_converted_files() {
+        let json = r#"{
+            "entities": [
+                {"name": "Acme Ltd", "type": "organization"},
+                {"name": "Holdco SA", "type": "organization"}
+            ],
+            "relationships": [
+                {"source": "Holdco SA", "target": "Acme Ltd", "type": "owner_of", "evidence": "Acme Ltd is owned by Holdco SA"}
+            ]
+        }"#;
+        let (entities, relationships) =
+            LlmExtractionPipeline::parse_llm_response(json, "manual", &Ontology::default())
+                .unwrap();
+        let mut result = ExtractionResult {
+            entities,
+            relationships,
+            raw_source: "filing".to_string(),
+            extracted_at: Utc::now(),
+        };
+        let converted =
+            crate::formats::plain_text("Annual report 2023.\n\nAcme Ltd is owned\nby Holdco SA.");
+        let document = RawDocument {
+            source: "manual".to_string(),
+            source_id: "filing".to_string(),
+            title: None,
+            content: converted.text.clone(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: None,
+            identifiers: Default::default(),
+            metadata: serde_json::json!({ crate::formats::TEXT_OFFSETS_KEY: converted.offsets }),
+            structured: None,
+        };
+
+        apply_evidence_locations(&mut result, &document);
+
+        let properties = &result.relationships[0].properties;
+        assert_eq!(properties["evidence_offset"], 21);
+        assert_eq!(properties["evidence_paragraph"], 2);
+        assert!(properties.get("evidence_page").is_none());
     }
 
     #[test]
@@ -564,7 +1357,7 @@ mod tests {
 ```"#;
 
         let (entities, relationships) =
-            LlmExtractionPipeline::parse_llm_response(json, "test").unwrap();
+            LlmExtractionPipeline::parse_llm_response(json, "test", &Ontology::default()).unwrap();
 
         assert_eq!(entities.len(), 1);
         assert_eq!(entities[0].name, "TestEntity");
@@ -576,7 +1369,7 @@ mod tests {
     fn test_parse_llm_response_empty() {
         let json = r#"{"entities": [], "relationships": []}"#;
         let (entities, relationships) =
-            LlmExtractionPipeline::parse_llm_response(json, "test").unwrap();
+            LlmExtractionPipeline::parse_llm_response(json, "test", &Ontology::default()).unwrap();
 
         assert_eq!(entities.len(), 0);
         assert_eq!(relationships.len(), 0);
@@ -600,7 +1393,7 @@ mod tests {
         }"#;
 
         let (entities, relationships) =
-            LlmExtractionPipeline::parse_llm_response(json, "test").unwrap();
+            LlmExtractionPipeline::parse_llm_response(json, "test", &Ontology::default()).unwrap();
 
         assert_eq!(entities.len(), 1);
         // Relationship should be skipped because "NonExistent" is not in entities
@@ -609,10 +1402,56 @@ mod tests {
 
     #[test]
     fn test_parse_llm_response_invalid_json() {
-        let result = LlmExtractionPipeline::parse_llm_response("not json at all", "test");
+        let result = LlmExtractionPipeline::parse_llm_response(
+            "not json at all",
+            "test",
+            &Ontology::default(),
+        );
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_split_content_prefers_paragraph_breaks() {
+        let content =
+            "First paragraph about Atlas.\n\nSecond one, about Nordic Trading and its owners.";
+        let (first, second) = split_content(content).unwrap();
+        assert_eq!(first, "First paragraph about Atlas.\n\n");
+        assert_eq!(second, "Second one, about Nordic Trading and its owners.");
+
+        assert_eq!(split_content("Unsplittable"), None);
+    }
+
+    #[test]
+    fn test_merge_chunks_keeps_shared_entities_once() {
+        let ontology = Ontology::default();
+        let first = LlmExtractionPipeline::parse_llm_response(
+            r#"{"entities": [{"name": "Atlas Capital", "type": "organization", "confidence": 0.7},
+                             {"name": "Oslo", "type": "location"}],
+                "relationships": [{"source": "Atlas Capital", "target": "Oslo", "type": "located_at"}]}"#,
+            "test",
+            &ontology,
+        )
+        .unwrap();
+        let second = LlmExtractionPipeline::parse_llm_response(
+            r#"{"entities": [{"name": "ATLAS CAPITAL", "type": "organization", "aliases": ["Atlas"], "confidence": 0.9},
+                             {"name": "Nordic Trading", "type": "organization"}],
+                "relationships": [{"source": "Nordic Trading", "target": "Atlas Capital", "type": "owner_of"}]}"#,
+            "test",
+            &ontology,
+        )
+        .unwrap();
+
+        let (entities, relationships) = merge_chunks(vec![first, second]);
+        assert_eq!(entities.len(), 3);
+        let atlas = &entities[0];
+        assert_eq!(
+            (atlas.aliases.as_slice(), atlas.confidence),
+            (["Atlas".to_string()].as_slice(), 0.9)
+        );
+        assert_eq!(relationships.len(), 2);
+        assert_eq!(relationships[1].target_entity_id, atlas.id);
+    }
+
     #[test]
     fn test_parse_llm_response_alias_lookup() {
         let json = r#"{
@@ -644,11 +1483,53 @@ mod tests {
         }"#;
 
         let (entities, relationships) =
-            LlmExtractionPipeline::parse_llm_response(json, "test").unwrap();
+            LlmExtractionPipeline::parse_llm_response(json, "test", &Ontology::default()).unwrap();
 
         assert_eq!(entities.len(), 2);
         // Relationship should resolve "USA" alias to the "United States of America" entity
         assert_eq!(relationships.len(), 1);
         assert_eq!(relationships[0].target_entity_id, entities[0].id);
     }
+
+    #[test]
+    fn test_parse_llm_response_custom_ontology() {
+        let ontology = Ontology::new(["bank_account"], ["funded_by"]);
+        let json = r#"{
+            "entities": [
+                {"name": "Acct 123", "type": "Bank Account", "confidence": 0.9},
+                {"name": "Acme Corp", "type": "organization", "confidence": 0.9}
+            ],
+            "relationships": [
+                {"source": "Acme Corp", "target": "Acct 123", "type": "FUNDED_BY"}
+            ]
+        }"#;
+
+        let (entities, relationships) =
+            LlmExtractionPipeline::parse_llm_response(json, "test", &ontology).unwrap();
+        assert_eq!(
+            entities[0].entity_type,
+            EntityType::Custom("bank_account".into())
+        );
+        assert_eq!(
+            relationships[0].relation_type,
+            RelationType::Custom("funded_by".into())
+        );
+
+        // Unregistered types still fall back to the built-in defaults
+        let (entities, relationships) =
+            LlmExtractionPipeline::parse_llm_response(json, "test", &Ontology::default()).unwrap();
+        assert_eq!(entities[0].entity_type, EntityType::Event);
+        assert_eq!(relationships[0].relation_type, RelationType::RelatedTo);
+    }
+
+    #[test]
+    fn test_system_prompt_lists_custom_types() {
+        let prompt = LlmExtractionPipeline::build_system_prompt(&Ontology::new(
+            ["bank_account"],
+            ["funded_by"],
+        ));
+        assert!(prompt.contains("transaction | sanction | bank_account"));
+        assert!(prompt.contains("part_of | funded_by"));
+        assert!(!prompt.contains("{entity_types}"));
+    }
 }
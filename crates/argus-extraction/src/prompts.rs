@@ -0,0 +1,168 @@
+//! The prompt bundles extraction uses right now. The server reloads them
+//! from the shared store; the pipeline reads them for every document.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use argus_core::agent::RawDocument;
+use argus_core::entity::ExtractionResult;
+use argus_core::prompts::{
+    PromptBundle, PromptDeployment, BASELINE_PROMPT_VERSION, PROMPT_VERSION_PROPERTY,
+};
+
+#[derive(Default)]
+pub struct ActivePrompts {
+    inner: RwLock<Active>,
+}
+
+#[derive(Default)]
+struct Active {
+    deployment: PromptDeployment,
+    bundles: HashMap<u32, Arc<PromptBundle>>,
+}
+
+/// The arm of the deployment one document is extracted with.
+#[derive(Debug, Clone)]
+pub struct PickedPrompt {
+    pub version: u32,
+    /// Unset for the baseline prompt.
+    pub bundle: Option<Arc<PromptBundle>>,
+}
+
+impl ActivePrompts {
+    /// Apply `deployment` from now on, with the bundles it names.
+    pub fn replace(&self, deployment: PromptDeployment, bundles: Vec<PromptBundle>) {
+        let bundles = bundles
+            .into_iter()
+            .map(|b| (b.version, Arc::new(b)))
+            .collect();
+        *self.inner.write().unwrap_or_else(|e| e.into_inner()) = Active {
+            deployment,
+            bundles,
+        };
+    }
+
+    pub fn deployment(&self) -> PromptDeployment {
+        self.inner
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .deployment
+            .clone()
+    }
+
+    /// The prompt to extract `document` with, or `None` while no deployment
+    /// is active. An arm whose bundle is not loaded falls back to the
+    /// baseline.
+    pub fn pick(&self, document: &RawDocument) -> Option<PickedPrompt> {
+        let active = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        if !active.deployment.is_active() {
+            return None;
+        }
+        let version = active
+            .deployment
+            .version_for(&document.source, &document.source_id);
+        if version == BASELINE_PROMPT_VERSION {
+            return Some(PickedPrompt {
+                version,
+                bundle: None,
+            });
+        }
+        match active.bundles.get(&version) {
+            Some(bundle) => Some(PickedPrompt {
+                version,
+                bundle: Some(Arc::clone(bundle)),
+            }),
+            None => {
+                tracing::warn!(
+                    version,
+                    "Prompt bundle not loaded, extracting with the baseline prompt"
+                );
+                Some(PickedPrompt {
+                    version: BASELINE_PROMPT_VERSION,
+                    bundle: None,
+                })
+            }
+        }
+    }
+}
+
+/// Record the prompt version on everything extracted, so feedback on the
+/// items can be traced back to it.
+pub(crate) fn apply_prompt_version(result: &mut ExtractionResult, picked: Option<&PickedPrompt>) {
+    let Some(picked) = picked else {
+        return;
+    };
+    let properties = result
+        .entities
+        .iter_mut()
+        .map(|e| &mut e.properties)
+        .chain(result.relationships.iter_mut().map(|r| &mut r.properties));
+    for properties in properties {
+        if let Some(map) = properties.as_object_mut() {
+            map.insert(PROMPT_VERSION_PROPERTY.to_string(), picked.version.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::Utc;
+
+    use super::*;
+
+    fn document(source_id: &str) -> RawDocument {
+        RawDocument {
+            source: "gdelt".to_string(),
+            source_id: source_id.to_string(),
+            title: None,
+            content: "text".to_string(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: None,
+            identifiers: BTreeMap::new(),
+            metadata: serde_json::Value::Null,
+            structured: None,
+        }
+    }
+
+    fn bundle(version: u32) -> PromptBundle {
+        PromptBundle {
+            version,
+            created_at: Utc::now(),
+            note: None,
+            examples: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn picks_follow_the_deployment() {
+        let prompts = ActivePrompts::default();
+        assert!(prompts.pick(&document("1")).is_none());
+
+        prompts.replace(
+            PromptDeployment {
+                control: 1,
+                candidate: Some(2),
+                candidate_share: 1.0,
+                updated_at: Utc::now(),
+            },
+            vec![bundle(1), bundle(2)],
+        );
+        let picked = prompts.pick(&document("1")).unwrap();
+        assert_eq!(picked.version, 2);
+        assert_eq!(picked.bundle.unwrap().version, 2);
+
+        prompts.replace(
+            PromptDeployment {
+                control: 3,
+                ..PromptDeployment::default()
+            },
+            Vec::new(),
+        );
+        let picked = prompts.pick(&document("1")).unwrap();
+        assert_eq!(picked.version, BASELINE_PROMPT_VERSION);
+        assert!(picked.bundle.is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Extraction pipelines chosen per source: an agent's own structured
+//! results, the LLM, or a mix of the two.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use argus_core::agent::RawDocument;
+use argus_core::config::AppConfig;
+use argus_core::entity::ExtractionResult;
+use argus_core::error::{ArgusError, Result};
+use argus_core::extraction::{BatchExtraction, ExtractionPipeline, FailedExtraction, PipelineKind};
+
+use crate::pipeline::LlmExtractionPipeline;
+
+/// Fail only when no document in the batch produced a result.
+fn all_or_error(batch: BatchExtraction) -> Result<Vec<ExtractionResult>> {
+    if batch.results.is_empty() && !batch.failures.is_empty() {
+        let errors: Vec<String> = batch
+            .failures
+            .iter()
+            .map(|f| format!("{}: {}", f.document.source_id, f.error))
+            .collect();
+        return Err(ArgusError::Extraction(format!(
+            "All documents failed extraction: {}",
+            errors.join("; ")
+        )));
+    }
+    Ok(batch.results)
+}
+
+/// The result the agent built itself, keyed to the document.
+fn structured_result(document: &RawDocument) -> Option<ExtractionResult> {
+    let mut result = document.structured.clone()?;
+    result.raw_source = document.source_id.clone();
+    Some(result)
+}
+
+/// Passes through what agents extracted themselves, with no LLM call.
+#[derive(Debug, Default)]
+pub struct StructuredExtractionPipeline;
+
+#[async_trait]
+impl ExtractionPipeline for StructuredExtractionPipeline {
+    async fn extract(&self, document: &RawDocument) -> Result<ExtractionResult> {
+        structured_result(document).ok_or_else(|| {
+            ArgusError::Extraction(format!("{} sent no structured extraction", document.source))
+        })
+    }
+
+    async fn extract_batch(&self, documents: &[RawDocument]) -> Result<Vec<ExtractionResult>> {
+        all_or_error(self.extract_batch_detailed(documents).await)
+    }
+
+    async fn extract_batch_detailed(&self, documents: &[RawDocument]) -> BatchExtraction {
+        let mut batch = BatchExtraction::default();
+        for document in documents {
+            match self.extract(document).await {
+                Ok(result) => batch.results.push(result),
+                Err(e) => batch
+                    .failures
+                    .push(FailedExtraction::new(document.clone(), &e)),
+            }
+        }
+        batch
+    }
+}
+
+/// Structured results where the agent sent one, the LLM for the rest.
+pub struct HybridExtractionPipeline {
+    llm: Arc<LlmExtractionPipeline>,
+}
+
+impl HybridExtractionPipeline {
+    pub fn new(llm: Arc<LlmExtractionPipeline>) -> Self {
+        Self { llm }
+    }
+}
+
+#[async_trait]
+impl ExtractionPipeline for HybridExtractionPipeline {
+    async fn extract(&self, document: &RawDocument) -> Result<ExtractionResult> {
+        match structured_result(document) {
+            Some(result) => Ok(result),
+            None => self.llm.extract(document).await,
+        }
+    }
+
+    async fn extract_batch(&self, documents: &[RawDocument]) -> Result<Vec<ExtractionResult>> {
+        all_or_error(self.extract_batch_detailed(documents).await)
+    }
+
+    async fn extract_batch_detailed(&self, documents: &[RawDocument]) -> BatchExtraction {
+        let (structured, prose): (Vec<&RawDocument>, Vec<&RawDocument>) =
+            documents.iter().partition(|d| d.structured.is_some());
+        let mut batch = if prose.is_empty() {
+            BatchExtraction::default()
+        } else {
+            let prose: Vec<RawDocument> = prose.into_iter().cloned().collect();
+            self.llm.extract_batch_detailed(&prose).await
+        };
+        batch
+            .results
+            .extend(structured.into_iter().filter_map(structured_result));
+        batch
+    }
+}
+
+/// Sends each document to the pipeline configured for its source in
+/// `EXTRACTION_PIPELINES`, looked up by agent name and then by the
+/// agent's source type, falling back to `DEFAULT_EXTRACTION_PIPELINE`.
+pub struct PipelineRouter {
+    llm: Arc<LlmExtractionPipeline>,
+    structured: StructuredExtractionPipeline,
+    hybrid: HybridExtractionPipeline,
+    routes: BTreeMap<String, PipelineKind>,
+    default: PipelineKind,
+    /// Agent name to source type, e.g. `gdelt` to `news_events`.
+    source_types: HashMap<String, String>,
+}
+
+impl PipelineRouter {
+    pub fn new(config: &AppConfig, llm: Arc<LlmExtractionPipeline>) -> Self {
+        Self {
+            structured: StructuredExtractionPipeline,
+            hybrid: HybridExtractionPipeline::new(llm.clone()),
+            llm,
+            routes: config.extraction_pipelines.clone(),
+            default: config.default_extraction_pipeline,
+            source_types: HashMap::new(),
+        }
+    }
+
+    /// Let routes name a source type as well as an agent.
+    pub fn with_source_types(
+        mut self,
+        source_types: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.source_types = source_types.into_iter().collect();
+        self
+    }
+
+    /// The LLM pipeline, for its prompts and cache.
+    pub fn llm(&self) -> &Arc<LlmExtractionPipeline> {
+        &self.llm
+    }
+
+    /// The pipeline kind documents from `source` go through.
+    pub fn kind_for(&self, source: &str) -> PipelineKind {
+        self.routes
+            .get(source)
+            .or_else(|| {
+                self.source_types
+                    .get(source)
+                    .and_then(|t| self.routes.get(t))
+            })
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Whether extracting `document` calls the LLM.
+    pub fn uses_llm(&self, document: &RawDocument) -> bool {
+        match self.kind_for(&document.source) {
+            PipelineKind::Structured => false,
+            PipelineKind::Llm => true,
+            PipelineKind::Hybrid => document.structured.is_none(),
+        }
+    }
+
+    fn pipeline(&self, kind: PipelineKind) -> &dyn ExtractionPipeline {
+        match kind {
+            PipelineKind::Structured => &self.structured,
+            PipelineKind::Llm => self.llm.as_ref(),
+            PipelineKind::Hybrid => &self.hybrid,
+        }
+    }
+}
+
+#[async_trait]
+impl ExtractionPipeline for PipelineRouter {
+    async fn extract(&self, document: &RawDocument) -> Result<ExtractionResult> {
+        self.pipeline(self.kind_for(&document.source))
+            .extract(document)
+            .await
+    }
+
+    async fn extract_batch(&self, documents: &[RawDocument]) -> Result<Vec<ExtractionResult>> {
+        all_or_error(self.extract_batch_detailed(documents).await)
+    }
+
+    async fn extract_batch_detailed(&self, documents: &[RawDocument]) -> BatchExtraction {
+        let mut by_kind: BTreeMap<&'static str, (PipelineKind, Vec<RawDocument>)> = BTreeMap::new();
+        for document in documents {
+            let kind = self.kind_for(&document.source);
+            by_kind
+                .entry(kind.name())
+                .or_insert_with(|| (kind, Vec::new()))
+                .1
+                .push(document.clone());
+        }
+        let mut batch = BatchExtraction::default();
+        for (kind, documents) in by_kind.into_values() {
+            tracing::debug!(
+                pipeline = kind.name(),
+                count = documents.len(),
+                "Routing documents to extraction pipeline"
+            );
+            let part = self.pipeline(kind).extract_batch_detailed(&documents).await;
+            batch.results.extend(part.results);
+            batch.failures.extend(part.failures);
+        }
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn config(routes: &str) -> AppConfig {
+        let routes = routes.to_string();
+        AppConfig::from_lookup(move |key| match key {
+            "EXTRACTION_PIPELINES" => Ok(routes.clone()),
+            _ => Err(std::env::VarError::NotPresent),
+        })
+    }
+
+    fn router(routes: &str) -> PipelineRouter {
+        let config = config(routes);
+        PipelineRouter::new(
+            &config,
+            Arc::new(LlmExtractionPipeline::new(&config).with_cache(None)),
+        )
+        .with_source_types([("gdelt".to_string(), "news_events".to_string())])
+    }
+
+    fn document(source: &str, structured: bool) -> RawDocument {
+        RawDocument {
+            source: source.into(),
+            source_id: format!("{source}-1"),
+            title: None,
+            content: "Some content".into(),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: None,
+            identifiers: Default::default(),
+            metadata: serde_json::Value::Null,
+            structured: structured.then(|| ExtractionResult {
+                entities: vec![],
+                relationships: vec![],
+                raw_source: String::new(),
+                extracted_at: Utc::now(),
+            }),
+        }
+    }
+
+    #[test]
+    fn agent_names_win_over_source_types() {
+        let router = router("news_events=llm, gdelt=structured, ais=structured");
+        assert_eq!(router.kind_for("gdelt"), PipelineKind::Structured);
+        assert_eq!(router.kind_for("ais"), PipelineKind::Structured);
+        assert_eq!(router.kind_for("adsb"), PipelineKind::Hybrid);
+
+        let router = self::router("news_events=llm");
+        assert_eq!(router.kind_for("gdelt"), PipelineKind::Llm);
+    }
+
+    #[test]
+    fn only_llm_bound_documents_use_the_llm() {
+        let router = router("ais=structured, gdelt=llm");
+        assert!(!router.uses_llm(&document("ais", false)));
+        assert!(router.uses_llm(&document("gdelt", true)));
+        assert!(router.uses_llm(&document("opensanctions", false)));
+        assert!(!router.uses_llm(&document("opensanctions", true)));
+    }
+
+    #[tokio::test]
+    async fn structured_routes_skip_the_llm() {
+        let router = router("ais=structured, opensanctions=structured");
+        let batch = router
+            .extract_batch_detailed(&[document("ais", false), document("opensanctions", true)])
+            .await;
+        assert_eq!(batch.results.len(), 1);
+        assert_eq!(batch.results[0].raw_source, "opensanctions-1");
+        assert_eq!(batch.failures.len(), 1);
+        assert_eq!(batch.failures[0].document.source, "ais");
+    }
+}
@@ -0,0 +1,690 @@
+//! Post-extraction validation: rules that normalize or reject extracted
+//! entities and relationships before they reach the graph.
+//!
+//! Rules run in order on every item. Relationships whose endpoint was
+//! rejected, and repeats of an identical edge, are dropped as well.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use regex::{Regex, RegexBuilder};
+
+use argus_core::config::AppConfig;
+use argus_core::deny_list::{DenyEntry, DenyMatch};
+use argus_core::entity::{Entity, EntityType, ExtractionResult, Relationship};
+use argus_core::extraction::ValidationReport;
+
+use crate::normalize::normalize_name;
+
+/// Rule name recorded for relationships dropped with a rejected endpoint.
+const REJECTED_ENDPOINT: &str = "rejected_endpoint";
+/// Rule name recorded for repeats of the same (source, target, type) edge.
+const DUPLICATE_RELATIONSHIP: &str = "duplicate_relationship";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Keep,
+    Reject,
+}
+
+/// One validation or normalization step. Rules may edit the item in place;
+/// returning [`Verdict::Reject`] drops it and counts it under [`name`].
+///
+/// [`name`]: ValidationRule::name
+pub trait ValidationRule: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn check_entity(&self, _entity: &mut Entity) -> Verdict {
+        Verdict::Keep
+    }
+
+    /// `source` and `target` are the already-validated endpoints.
+    fn check_relationship(
+        &self,
+        _rel: &mut Relationship,
+        _source: &Entity,
+        _target: &Entity,
+    ) -> Verdict {
+        Verdict::Keep
+    }
+}
+
+/// Rewrite names to their canonical form (see [`crate::normalize`]) so
+/// entity resolution sees one spelling, keeping the original as an alias.
+pub struct NormalizeNames;
+
+impl ValidationRule for NormalizeNames {
+    fn name(&self) -> &'static str {
+        "normalize_names"
+    }
+
+    fn check_entity(&self, entity: &mut Entity) -> Verdict {
+        let normalized = normalize_name(&entity.name, &entity.entity_type);
+        if normalized != entity.name {
+            let original = std::mem::replace(&mut entity.name, normalized);
+            if !entity.aliases.contains(&original) {
+                entity.aliases.push(original);
+            }
+        }
+        Verdict::Keep
+    }
+}
+
+/// Collapse runs of whitespace and trim; drop empty aliases and aliases
+/// that repeat the name or each other. Aliases differing only in case are
+/// kept, since they may be the original spelling.
+pub struct NormalizeWhitespace;
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl ValidationRule for NormalizeWhitespace {
+    fn name(&self) -> &'static str {
+        "normalize_whitespace"
+    }
+
+    fn check_entity(&self, entity: &mut Entity) -> Verdict {
+        entity.name = collapse_whitespace(&entity.name);
+        let mut seen = HashSet::from([entity.name.clone()]);
+        entity.aliases = entity
+            .aliases
+            .iter()
+            .map(|a| collapse_whitespace(a))
+            .filter(|a| !a.is_empty() && seen.insert(a.clone()))
+            .collect();
+        Verdict::Keep
+    }
+}
+
+/// Reject entities whose name is shorter than `min` characters.
+pub struct MinNameLength {
+    pub min: usize,
+}
+
+impl ValidationRule for MinNameLength {
+    fn name(&self) -> &'static str {
+        "min_name_length"
+    }
+
+    fn check_entity(&self, entity: &mut Entity) -> Verdict {
+        // An empty name is never useful, whatever the configured minimum
+        if entity.name.is_empty() || entity.name.chars().count() < self.min {
+            Verdict::Reject
+        } else {
+            Verdict::Keep
+        }
+    }
+}
+
+/// Reject placeholder names such as "Unknown".
+pub struct BlocklistedNames {
+    names: HashSet<String>,
+}
+
+impl BlocklistedNames {
+    pub fn new<I: IntoIterator<Item = S>, S: AsRef<str>>(names: I) -> Self {
+        Self {
+            names: names
+                .into_iter()
+                .map(|n| n.as_ref().trim().to_lowercase())
+                .collect(),
+        }
+    }
+}
+
+impl ValidationRule for BlocklistedNames {
+    fn name(&self) -> &'static str {
+        "blocklisted_name"
+    }
+
+    fn check_entity(&self, entity: &mut Entity) -> Verdict {
+        if self.names.contains(&entity.name.to_lowercase()) {
+            Verdict::Reject
+        } else {
+            Verdict::Keep
+        }
+    }
+}
+
+/// Reject entities matching a managed deny-list entry. Entries are edited
+/// at runtime through the admin API, so they sit behind a lock and are
+/// swapped wholesale with [`DenyList::replace`].
+#[derive(Default)]
+pub struct DenyList {
+    entries: RwLock<Vec<CompiledDenyEntry>>,
+}
+
+struct CompiledDenyEntry {
+    pattern: DenyPattern,
+    entity_type: Option<EntityType>,
+    source: Option<String>,
+}
+
+enum DenyPattern {
+    /// Lowercased and trimmed.
+    Exact(String),
+    Regex(Regex),
+}
+
+/// Keeps a careless pattern from compiling into a huge automaton.
+const DENY_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+impl CompiledDenyEntry {
+    fn compile(entry: &DenyEntry) -> Result<Self, String> {
+        let pattern = entry.pattern.trim();
+        if pattern.is_empty() {
+            return Err("Deny-list pattern must not be empty".to_string());
+        }
+        let pattern = match entry.match_kind {
+            DenyMatch::Exact => DenyPattern::Exact(pattern.to_lowercase()),
+            DenyMatch::Regex => DenyPattern::Regex(
+                RegexBuilder::new(&format!("^(?:{pattern})$"))
+                    .case_insensitive(true)
+                    .size_limit(DENY_REGEX_SIZE_LIMIT)
+                    .build()
+                    .map_err(|e| format!("Invalid deny-list regex: {e}"))?,
+            ),
+        };
+        Ok(Self {
+            pattern,
+            entity_type: entry.entity_type.clone(),
+            source: entry
+                .source
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_lowercase),
+        })
+    }
+
+    fn matches(&self, entity: &Entity) -> bool {
+        if self
+            .entity_type
+            .as_ref()
+            .is_some_and(|t| *t != entity.entity_type)
+        {
+            return false;
+        }
+        if self
+            .source
+            .as_ref()
+            .is_some_and(|s| !s.eq_ignore_ascii_case(&entity.source))
+        {
+            return false;
+        }
+        match &self.pattern {
+            DenyPattern::Exact(name) => entity.name.trim().to_lowercase() == *name,
+            DenyPattern::Regex(regex) => regex.is_match(entity.name.trim()),
+        }
+    }
+}
+
+impl DenyList {
+    /// Whether `entry` would compile, with the reason when it would not.
+    pub fn check(entry: &DenyEntry) -> Result<(), String> {
+        CompiledDenyEntry::compile(entry).map(|_| ())
+    }
+
+    /// Apply `entries` from now on. Entries that no longer compile are
+    /// skipped with a warning rather than failing the whole list.
+    pub fn replace(&self, entries: &[DenyEntry]) {
+        let compiled = entries
+            .iter()
+            .filter_map(|entry| match CompiledDenyEntry::compile(entry) {
+                Ok(compiled) => Some(compiled),
+                Err(e) => {
+                    tracing::warn!(id = %entry.id, pattern = %entry.pattern, error = %e, "Skipping deny-list entry");
+                    None
+                }
+            })
+            .collect();
+        *self.entries.write().unwrap_or_else(|e| e.into_inner()) = compiled;
+    }
+
+    /// Number of entries being applied.
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ValidationRule for DenyList {
+    fn name(&self) -> &'static str {
+        "deny_list"
+    }
+
+    fn check_entity(&self, entity: &mut Entity) -> Verdict {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        if entries.iter().any(|entry| entry.matches(entity)) {
+            Verdict::Reject
+        } else {
+            Verdict::Keep
+        }
+    }
+}
+
+/// Shared rules, such as the [`DenyList`] its editors also hold.
+impl<R: ValidationRule> ValidationRule for Arc<R> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn check_entity(&self, entity: &mut Entity) -> Verdict {
+        (**self).check_entity(entity)
+    }
+
+    fn check_relationship(
+        &self,
+        rel: &mut Relationship,
+        source: &Entity,
+        target: &Entity,
+    ) -> Verdict {
+        (**self).check_relationship(rel, source, target)
+    }
+}
+
+/// Reject items the model itself was unsure about.
+pub struct MinConfidence {
+    pub min: f64,
+}
+
+impl ValidationRule for MinConfidence {
+    fn name(&self) -> &'static str {
+        "min_confidence"
+    }
+
+    fn check_entity(&self, entity: &mut Entity) -> Verdict {
+        if entity.confidence < self.min {
+            Verdict::Reject
+        } else {
+            Verdict::Keep
+        }
+    }
+
+    fn check_relationship(
+        &self,
+        rel: &mut Relationship,
+        _source: &Entity,
+        _target: &Entity,
+    ) -> Verdict {
+        if rel.confidence < self.min {
+            Verdict::Reject
+        } else {
+            Verdict::Keep
+        }
+    }
+}
+
+/// Reject edges from an entity to itself, including to a same-named
+/// entity of the same type the model listed twice.
+pub struct SelfRelationship;
+
+impl ValidationRule for SelfRelationship {
+    fn name(&self) -> &'static str {
+        "self_relationship"
+    }
+
+    fn check_relationship(
+        &self,
+        rel: &mut Relationship,
+        source: &Entity,
+        target: &Entity,
+    ) -> Verdict {
+        let same_entity = rel.source_entity_id == rel.target_entity_id
+            || (source.entity_type == target.entity_type
+                && source.name.eq_ignore_ascii_case(&target.name));
+        if same_entity {
+            Verdict::Reject
+        } else {
+            Verdict::Keep
+        }
+    }
+}
+
+/// An ordered set of rules applied to every extraction result.
+pub struct Validator {
+    rules: RwLock<Vec<Box<dyn ValidationRule>>>,
+    /// Rules added with [`Validator::with_rule`], run after `rules`.
+    extra: Vec<Box<dyn ValidationRule>>,
+    deny_list: Arc<DenyList>,
+}
+
+impl Validator {
+    /// Run `rules` only. The [`DenyList`] of such a validator is not
+    /// applied unless it is also passed in as a rule.
+    pub fn new(rules: Vec<Box<dyn ValidationRule>>) -> Self {
+        Self {
+            rules: RwLock::new(rules),
+            extra: Vec::new(),
+            deny_list: Arc::default(),
+        }
+    }
+
+    /// The built-in rules, configured by the `VALIDATION_*` settings, plus
+    /// an initially empty deny-list.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let deny_list = Arc::new(DenyList::default());
+        Self {
+            rules: RwLock::new(configured_rules(config, &deny_list)),
+            extra: Vec::new(),
+            deny_list,
+        }
+    }
+
+    /// Rebuild the built-in rules from `config`, keeping the deny-list
+    /// entries and any rules added with [`Validator::with_rule`].
+    pub fn reconfigure(&self, config: &AppConfig) {
+        let rules = configured_rules(config, &self.deny_list);
+        *self.rules.write().unwrap_or_else(|e| e.into_inner()) = rules;
+    }
+
+    /// The deny-list rule, for loading and replacing its entries.
+    pub fn deny_list(&self) -> &Arc<DenyList> {
+        &self.deny_list
+    }
+
+    /// Append a rule after the configured ones.
+    pub fn with_rule(mut self, rule: impl ValidationRule + 'static) -> Self {
+        self.extra.push(Box::new(rule));
+        self
+    }
+
+    /// Normalize `result` in place and drop what fails a rule.
+    pub fn validate(&self, result: &mut ExtractionResult) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let configured = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        let rules = || configured.iter().chain(&self.extra);
+
+        let mut kept = Vec::with_capacity(result.entities.len());
+        for mut entity in std::mem::take(&mut result.entities) {
+            match rules().find(|r| r.check_entity(&mut entity) == Verdict::Reject) {
+                Some(rule) => {
+                    tracing::debug!(name = %entity.name, rule = rule.name(), "Rejected extracted entity");
+                    report.entities_rejected += 1;
+                    *report.by_rule.entry(rule.name().to_string()).or_default() += 1;
+                }
+                None => kept.push(entity),
+            }
+        }
+        result.entities = kept;
+
+        let by_id: HashMap<_, _> = result.entities.iter().map(|e| (e.id, e)).collect();
+        let mut edges = HashSet::new();
+        let mut relationships = Vec::with_capacity(result.relationships.len());
+        for mut rel in std::mem::take(&mut result.relationships) {
+            let rejected_by = match (
+                by_id.get(&rel.source_entity_id),
+                by_id.get(&rel.target_entity_id),
+            ) {
+                (Some(source), Some(target)) => rules()
+                    .find(|r| r.check_relationship(&mut rel, source, target) == Verdict::Reject)
+                    .map(|r| r.name())
+                    .or_else(|| {
+                        let edge = (
+                            rel.source_entity_id,
+                            rel.target_entity_id,
+                            rel.relation_type.clone(),
+                        );
+                        (!edges.insert(edge)).then_some(DUPLICATE_RELATIONSHIP)
+                    }),
+                _ => Some(REJECTED_ENDPOINT),
+            };
+            match rejected_by {
+                Some(rule) => {
+                    report.relationships_rejected += 1;
+                    *report.by_rule.entry(rule.to_string()).or_default() += 1;
+                }
+                None => relationships.push(rel),
+            }
+        }
+        result.relationships = relationships;
+
+        report
+    }
+}
+
+/// The built-in rules in the order they run, with `deny_list` among them.
+fn configured_rules(config: &AppConfig, deny_list: &Arc<DenyList>) -> Vec<Box<dyn ValidationRule>> {
+    let mut rules: Vec<Box<dyn ValidationRule>> = vec![
+        Box::new(NormalizeNames),
+        Box::new(NormalizeWhitespace),
+        Box::new(MinNameLength {
+            min: config.validation_min_name_length,
+        }),
+        Box::new(BlocklistedNames::new(&config.validation_blocklist)),
+        Box::new(deny_list.clone()),
+    ];
+    if config.validation_min_confidence > 0.0 {
+        rules.push(Box::new(MinConfidence {
+            min: config.validation_min_confidence,
+        }));
+    }
+    rules.push(Box::new(SelfRelationship));
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::entity::RelationType;
+
+    fn entity(name: &str) -> Entity {
+        Entity::new(EntityType::Person, name.to_string(), "test".to_string())
+    }
+
+    fn rel(source: &Entity, target: &Entity) -> Relationship {
+        Relationship {
+            id: uuid::Uuid::new_v4(),
+            source_entity_id: source.id,
+            target_entity_id: target.id,
+            relation_type: RelationType::RelatedTo,
+            properties: serde_json::Value::Null,
+            confidence: 0.9,
+            source: "test".to_string(),
+            timestamp: None,
+            strength: None,
+        }
+    }
+
+    fn validator() -> Validator {
+        let mut config = AppConfig::from_env();
+        config.validation_min_name_length = 2;
+        config.validation_blocklist = vec!["Unknown".to_string()];
+        config.validation_min_confidence = 0.5;
+        Validator::from_config(&config)
+    }
+
+    fn result(entities: Vec<Entity>, relationships: Vec<Relationship>) -> ExtractionResult {
+        ExtractionResult {
+            entities,
+            relationships,
+            raw_source: "doc".to_string(),
+            extracted_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn rejects_short_blocklisted_and_unsure_entities() {
+        let mut unsure = entity("Maybe Corp");
+        unsure.confidence = 0.2;
+        let mut extraction = result(
+            vec![
+                entity("  Jane   Doe "),
+                entity("X"),
+                entity("UNKNOWN"),
+                unsure,
+            ],
+            vec![],
+        );
+
+        let report = validator().validate(&mut extraction);
+
+        let names: Vec<&str> = extraction
+            .entities
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, ["Jane Doe"]);
+        assert_eq!(report.entities_rejected, 3);
+        assert_eq!(report.by_rule["min_name_length"], 1);
+        assert_eq!(report.by_rule["blocklisted_name"], 1);
+        assert_eq!(report.by_rule["min_confidence"], 1);
+    }
+
+    #[test]
+    fn drops_self_dangling_and_duplicate_relationships() {
+        let a = entity("Acme");
+        let b = entity("Beta");
+        let twin = entity("acme");
+        let blank = entity("");
+        let relationships = vec![
+            rel(&a, &b),
+            rel(&a, &b),
+            rel(&a, &twin),
+            rel(&a, &blank),
+            rel(&b, &b),
+        ];
+        let mut extraction = result(vec![a, b, twin, blank], relationships);
+
+        let report = validator().validate(&mut extraction);
+
+        assert_eq!(extraction.relationships.len(), 1);
+        assert_eq!(report.relationships_rejected, 4);
+        assert_eq!(report.by_rule[DUPLICATE_RELATIONSHIP], 1);
+        assert_eq!(report.by_rule[REJECTED_ENDPOINT], 1);
+        assert_eq!(report.by_rule["self_relationship"], 2);
+    }
+
+    #[test]
+    fn normalizes_aliases() {
+        let mut e = entity("Acme Ltd");
+        e.aliases = vec![
+            " ".to_string(),
+            "Acme  Ltd".to_string(),
+            "Acme".to_string(),
+            "Acme ".to_string(),
+        ];
+        let mut extraction = result(vec![e], vec![]);
+
+        let report = validator().validate(&mut extraction);
+
+        assert!(report.is_empty());
+        assert_eq!(extraction.entities[0].aliases, ["Acme"]);
+    }
+
+    #[test]
+    fn normalized_names_keep_the_original_as_alias() {
+        let mut org = entity("ACME HOLDINGS LIMITED");
+        org.entity_type = EntityType::Organization;
+        let mut extraction = result(vec![org, entity("Jane Doe")], vec![]);
+
+        validator().validate(&mut extraction);
+
+        assert_eq!(extraction.entities[0].name, "Acme Holdings Ltd");
+        assert_eq!(extraction.entities[0].aliases, ["ACME HOLDINGS LIMITED"]);
+        assert!(extraction.entities[1].aliases.is_empty());
+    }
+
+    #[test]
+    fn deny_list_matches_whole_names_within_scope() {
+        let deny = |pattern: &str, match_kind, source: Option<&str>| DenyEntry {
+            id: uuid::Uuid::new_v4(),
+            pattern: pattern.to_string(),
+            match_kind,
+            entity_type: Some(EntityType::Organization),
+            source: source.map(String::from),
+            note: None,
+            created_at: chrono::Utc::now(),
+        };
+        let validator = validator();
+        validator.deny_list().replace(&[
+            deny("government", DenyMatch::Exact, None),
+            deny("(local |state )?police", DenyMatch::Regex, Some("GDELT")),
+            deny("(unclosed", DenyMatch::Regex, None),
+        ]);
+        assert_eq!(validator.deny_list().len(), 2);
+        assert!(DenyList::check(&deny("(unclosed", DenyMatch::Regex, None)).is_err());
+        assert!(DenyList::check(&deny("  ", DenyMatch::Exact, None)).is_err());
+
+        let org = |name: &str, source: &str| {
+            Entity::new(
+                EntityType::Organization,
+                name.to_string(),
+                source.to_string(),
+            )
+        };
+        let mut extraction = result(
+            vec![
+                org("GOVERNMENT", "gdelt"),
+                org("State Police", "gdelt"),
+                org("Police", "acled"),
+                org("Police Scotland", "gdelt"),
+                entity("Government"),
+            ],
+            vec![],
+        );
+
+        let report = validator.validate(&mut extraction);
+
+        let names: Vec<&str> = extraction
+            .entities
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, ["Police", "Police Scotland", "Government"]);
+        assert_eq!(report.by_rule["deny_list"], 2);
+    }
+
+    #[test]
+    fn custom_rules_run_after_built_ins() {
+        struct NoVessels;
+        impl ValidationRule for NoVessels {
+            fn name(&self) -> &'static str {
+                "no_vessels"
+            }
+            fn check_entity(&self, entity: &mut Entity) -> Verdict {
+                if entity.entity_type == EntityType::Vessel {
+                    Verdict::Reject
+                } else {
+                    Verdict::Keep
+                }
+            }
+        }
+
+        let mut ship = entity("Ever Given");
+        ship.entity_type = EntityType::Vessel;
+        let mut extraction = result(vec![ship, entity("Jane Doe")], vec![]);
+
+        let report = validator().with_rule(NoVessels).validate(&mut extraction);
+
+        assert_eq!(extraction.entities.len(), 1);
+        assert_eq!(report.by_rule["no_vessels"], 1);
+    }
+
+    #[test]
+    fn reconfigure_replaces_built_in_rules_only() {
+        let validator = validator().with_rule(MinNameLength { min: 4 });
+        let mut config = AppConfig::from_env();
+        config.validation_blocklist = vec!["Jane Doe".to_string()];
+        validator.reconfigure(&config);
+
+        let mut extraction = result(
+            vec![entity("Jane Doe"), entity("UNKNOWN"), entity("Abc")],
+            vec![],
+        );
+        let report = validator.validate(&mut extraction);
+
+        let names: Vec<&str> = extraction
+            .entities
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, ["UNKNOWN"]);
+        assert_eq!(report.by_rule["blocklisted_name"], 1);
+        assert_eq!(report.by_rule["min_name_length"], 1);
+    }
+}
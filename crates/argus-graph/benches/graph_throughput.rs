@@ -0,0 +1,163 @@
+//! Graph write/read throughput against a real Neo4j.
+//!
+//! Start a throwaway instance and point the benchmarks at it:
+//!
+//! ```sh
+//! docker compose --profile bench up -d neo4j-bench
+//! BENCH_NEO4J_URI=bolt://localhost:7688 cargo bench -p argus-graph
+//! ```
+//!
+//! Everything is written under a per-run source and deleted afterwards.
+//! Without a reachable Neo4j the benchmarks are skipped.
+
+mod support;
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use argus_core::config::AppConfig;
+use argus_core::graph::{GraphQuery, GraphStore};
+use argus_graph::Neo4jGraphStore;
+
+use support::Generator;
+
+/// Entities seeded before the read benchmarks.
+const SEED_ENTITIES: usize = 2_000;
+const SEED_RESULT_SIZE: usize = 100;
+
+struct Bench {
+    runtime: Runtime,
+    store: Neo4jGraphStore,
+    source: String,
+}
+
+impl Bench {
+    fn connect() -> Option<Self> {
+        let runtime = Runtime::new().expect("failed to start tokio runtime");
+        let mut config = AppConfig::from_env();
+        config.neo4j_uri =
+            std::env::var("BENCH_NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7688".into());
+        if let Ok(password) = std::env::var("BENCH_NEO4J_PASSWORD") {
+            config.neo4j_password = password;
+        }
+
+        let store = runtime.block_on(Neo4jGraphStore::new(&config));
+        if let Err(e) = runtime.block_on(store.entity_count()) {
+            eprintln!(
+                "Skipping graph benchmarks, Neo4j at {} unavailable: {e}",
+                config.neo4j_uri
+            );
+            return None;
+        }
+
+        Some(Self {
+            runtime,
+            store,
+            source: format!("bench-{}", Uuid::new_v4().simple()),
+        })
+    }
+
+    fn cleanup(&self) {
+        let query = GraphQuery {
+            cypher: "MATCH (n) WHERE n.source = $source DETACH DELETE n".to_string(),
+            params: serde_json::json!({ "source": self.source }),
+        };
+        if let Err(e) = self.runtime.block_on(self.store.execute_cypher(&query)) {
+            eprintln!("Failed to clean up benchmark data: {e}");
+        }
+    }
+}
+
+fn writes(c: &mut Criterion, bench: &Bench) {
+    let mut group = c.benchmark_group("store_extraction");
+    group
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(15));
+
+    for size in [10usize, 100] {
+        let mut generator = Generator::new(size as u64, &bench.source);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("single", size), &size, |b, &size| {
+            b.to_async(&bench.runtime).iter_batched(
+                || generator.result(size, 2),
+                |result| async move { bench.store.store_extraction(&result).await.unwrap() },
+                criterion::BatchSize::SmallInput,
+            );
+        });
+    }
+
+    // Same 500 entities per iteration, one transaction vs one per result
+    let mut generator = Generator::new(7, &bench.source);
+    group.throughput(Throughput::Elements(500));
+    group.bench_function("sequential_10x50", |b| {
+        b.to_async(&bench.runtime).iter_batched(
+            || (0..10).map(|_| generator.result(50, 2)).collect::<Vec<_>>(),
+            |results| async move {
+                for result in &results {
+                    bench.store.store_extraction(result).await.unwrap();
+                }
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+    let mut generator = Generator::new(8, &bench.source);
+    group.bench_function("batched_10x50", |b| {
+        b.to_async(&bench.runtime).iter_batched(
+            || (0..10).map(|_| generator.result(50, 2)).collect::<Vec<_>>(),
+            |results| async move { bench.store.store_extractions(&results).await.unwrap() },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    group.finish();
+}
+
+fn reads(c: &mut Criterion, bench: &Bench) {
+    let mut generator = Generator::new(42, &bench.source);
+    let seed: Vec<_> = (0..SEED_ENTITIES / SEED_RESULT_SIZE)
+        .map(|_| generator.result(SEED_RESULT_SIZE, 3))
+        .collect();
+    bench
+        .runtime
+        .block_on(bench.store.store_extractions(&seed))
+        .expect("failed to seed benchmark graph");
+    // The first entities of each result are the generator's hubs
+    let hubs: Vec<Uuid> = seed.iter().map(|r| r.entities[0].id).collect();
+
+    let mut group = c.benchmark_group("search_entities");
+    for query in ["Petrov", "Holdings 1", "nomatch"] {
+        group.bench_with_input(BenchmarkId::from_parameter(query), &query, |b, query| {
+            b.to_async(&bench.runtime)
+                .iter(|| async { bench.store.search_entities(query, 20).await.unwrap() });
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("get_neighbors");
+    for depth in [1u32, 2] {
+        group.bench_with_input(BenchmarkId::new("depth", depth), &depth, |b, &depth| {
+            let mut next = 0;
+            b.to_async(&bench.runtime).iter(|| {
+                let id = hubs[next % hubs.len()];
+                next += 1;
+                async move { bench.store.get_neighbors(id, depth).await.unwrap() }
+            });
+        });
+    }
+    group.finish();
+}
+
+fn graph_throughput(c: &mut Criterion) {
+    let Some(bench) = Bench::connect() else {
+        return;
+    };
+    writes(c, &bench);
+    reads(c, &bench);
+    bench.cleanup();
+}
+
+criterion_group!(benches, graph_throughput);
+criterion_main!(benches);
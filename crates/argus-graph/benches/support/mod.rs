@@ -0,0 +1,144 @@
+//! Synthetic graph data for the benchmarks.
+//!
+//! Generation is seeded so every run writes the same shape of graph:
+//! people and organizations with a handful of properties, linked by
+//! ownership, directorship and employment edges with a few highly
+//! connected hubs, roughly like a corporate registry extract.
+
+use argus_core::entity::{Entity, EntityType, ExtractionResult, RelationType, Relationship};
+use chrono::Utc;
+
+const FIRST: &[&str] = &[
+    "Anna", "Boris", "Chen", "Dara", "Emil", "Farah", "Goran", "Hana", "Ivan", "Jana",
+];
+const LAST: &[&str] = &[
+    "Petrov", "Novak", "Haddad", "Larsen", "Okafor", "Weber", "Santos", "Kim", "Rossi", "Ahmadi",
+];
+const ORG_STEM: &[&str] = &[
+    "Baltic", "Nordic", "Meridian", "Orion", "Atlas", "Cobalt", "Harbor", "Summit", "Vega", "Delta",
+];
+const ORG_KIND: &[&str] = &[
+    "Holdings",
+    "Shipping",
+    "Trading",
+    "Capital",
+    "Logistics",
+    "Energy",
+    "Resources",
+    "Ventures",
+];
+const COUNTRIES: &[&str] = &["CY", "LV", "AE", "GB", "PA", "MT", "NL", "HK"];
+
+/// xorshift64*: deterministic and plenty for test data.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &'a [&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+/// Builds extraction results shaped like what the pipeline produces.
+pub struct Generator {
+    rng: Rng,
+    source: String,
+    serial: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64, source: &str) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            source: source.to_string(),
+            serial: 0,
+        }
+    }
+
+    fn entity(&mut self) -> Entity {
+        self.serial += 1;
+        // A serial suffix keeps names unique so results never collapse
+        // into each other through name-based resolution
+        let (entity_type, name) = if self.rng.below(3) == 0 {
+            let name = format!(
+                "{} {} {}",
+                self.rng.pick(ORG_STEM),
+                self.rng.pick(ORG_KIND),
+                self.serial
+            );
+            (EntityType::Organization, name)
+        } else {
+            let name = format!(
+                "{} {} {}",
+                self.rng.pick(FIRST),
+                self.rng.pick(LAST),
+                self.serial
+            );
+            (EntityType::Person, name)
+        };
+
+        let mut entity = Entity::new(entity_type, name, self.source.clone());
+        entity.source_id = Some(format!("bench-{}", self.serial));
+        entity.confidence = 0.5 + (self.rng.below(50) as f64) / 100.0;
+        entity.properties = serde_json::json!({
+            "country": self.rng.pick(COUNTRIES),
+            "registry_number": format!("{:08}", self.rng.below(100_000_000)),
+        });
+        entity
+    }
+
+    /// One extraction result with `entities` entities and about
+    /// `edges_per_entity` relationships per entity, all internal.
+    pub fn result(&mut self, entities: usize, edges_per_entity: usize) -> ExtractionResult {
+        let entities: Vec<Entity> = (0..entities).map(|_| self.entity()).collect();
+        let mut relationships = Vec::new();
+        if entities.len() > 1 {
+            for (i, entity) in entities.iter().enumerate() {
+                for _ in 0..edges_per_entity {
+                    // Bias targets towards the front of the list to create hubs
+                    let bound = if self.rng.below(4) == 0 {
+                        entities.len().min(5)
+                    } else {
+                        entities.len()
+                    };
+                    let target = self.rng.below(bound);
+                    if target == i {
+                        continue;
+                    }
+                    let relation_type = match self.rng.below(3) {
+                        0 => RelationType::OwnerOf,
+                        1 => RelationType::DirectorOf,
+                        _ => RelationType::EmployeeOf,
+                    };
+                    relationships.push(Relationship::new(
+                        entity.id,
+                        entities[target].id,
+                        relation_type,
+                        self.source.clone(),
+                    ));
+                }
+            }
+        }
+
+        ExtractionResult {
+            entities,
+            relationships,
+            raw_source: format!("bench-doc-{}", self.serial),
+            extracted_at: Utc::now(),
+        }
+    }
+}
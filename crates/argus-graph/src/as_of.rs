@@ -0,0 +1,41 @@
+//! Cypher predicates for reading the graph as it stood at a past instant.
+//!
+//! A node existed at `$as_of` if it was first seen by then; an edge if it was
+//! created by then. Either is hidden from that instant on once it carries an
+//! `invalidated_at` timestamp. Timestamps are compared as the RFC 3339
+//! strings they are stored as.
+
+use chrono::{DateTime, Utc};
+
+/// Predicate on the node bound to `var`.
+pub(crate) fn node_existed(var: &str) -> String {
+    format!("{var}.first_seen <= $as_of AND {}", not_invalidated(var))
+}
+
+/// Predicate on the relationship bound to `var`.
+pub(crate) fn edge_existed(var: &str) -> String {
+    format!("{var}.created_at <= $as_of AND {}", not_invalidated(var))
+}
+
+fn not_invalidated(var: &str) -> String {
+    format!("(coalesce({var}.invalidated_at, '') = '' OR {var}.invalidated_at > $as_of)")
+}
+
+/// Value for the `$as_of` parameter.
+pub(crate) fn param(as_of: DateTime<Utc>) -> String {
+    as_of.to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predicates_check_creation_and_invalidation() {
+        assert_eq!(
+            node_existed("m"),
+            "m.first_seen <= $as_of AND (coalesce(m.invalidated_at, '') = '' OR m.invalidated_at > $as_of)"
+        );
+        assert!(edge_existed("r").starts_with("r.created_at <= $as_of AND "));
+    }
+}
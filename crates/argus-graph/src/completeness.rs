@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph};
+
+use argus_core::error::{ArgusError, Result};
+
+use crate::documents::DOCUMENT_LABEL;
+use crate::history::CHANGE_LABEL;
+use crate::store::{non_entity_filter, timed};
+use crate::tombstones::{DELETED_LABEL, TOMBSTONE_LABEL};
+use crate::tracks::POSITION_LABEL;
+
+/// Share of the score from external identifiers.
+const IDENTIFIER_WEIGHT: f64 = 0.3;
+/// Share of the score from linked entities.
+const RELATIONSHIP_WEIGHT: f64 = 0.3;
+/// Share of the score from having a place on the map.
+const LOCATION_WEIGHT: f64 = 0.2;
+/// Share of the score from how recently the entity was observed.
+const RECENCY_WEIGHT: f64 = 0.2;
+
+/// Identifiers at which that share counts in full.
+const SATURATING_IDENTIFIERS: u64 = 2;
+/// Linked entities at which that share counts in full.
+const SATURATING_RELATIONSHIPS: u64 = 5;
+/// Days after which recency counts half.
+const RECENCY_HALF_LIFE_DAYS: f64 = 90.0;
+
+/// Number of scores written back per UNWIND batch.
+const WRITE_BATCH_SIZE: usize = 1000;
+
+const COMPLETENESS_WRITE_CYPHER: &str = "\
+    UNWIND range(0, size($ids) - 1) AS i \
+    MATCH (n {id: $ids[i]}) \
+    SET n.completeness = $scores[i]";
+
+/// For every entity: its identifiers, the distinct entities it is linked
+/// to, whether it has coordinates itself or is placed at a Location, and
+/// when it was last collected or observed.
+fn completeness_inputs_cypher() -> String {
+    format!(
+        "MATCH (n) WHERE {} \
+         RETURN n.id AS id, size(coalesce(n.identifiers, [])) AS identifiers, \
+           COUNT {{ MATCH (n)--(m) WHERE NOT m:{DOCUMENT_LABEL} AND NOT m:{CHANGE_LABEL} AND NOT m:{POSITION_LABEL} \
+             AND NOT m:{TOMBSTONE_LABEL} AND NOT m:{DELETED_LABEL} RETURN DISTINCT m }} AS linked, \
+           (n.geometry IS NOT NULL OR coalesce(n.properties, '') CONTAINS '\"latitude\"' \
+             OR EXISTS {{ (n)-[:LOCATED_AT|REGISTERED_IN]->(:Location) }}) AS located, \
+           coalesce(n.last_seen, '') AS last_seen",
+        non_entity_filter()
+    )
+}
+
+/// Combine profile signals into a score in [0.0, 1.0].
+///
+/// Identifiers and links count in full at [`SATURATING_IDENTIFIERS`] and
+/// [`SATURATING_RELATIONSHIPS`]; recency halves every
+/// [`RECENCY_HALF_LIFE_DAYS`]. A bare name seen today therefore scores 0.2.
+pub(crate) fn completeness_score(
+    identifiers: u64,
+    linked: u64,
+    located: bool,
+    age_days: f64,
+) -> f64 {
+    let identifiers = (identifiers as f64 / SATURATING_IDENTIFIERS as f64).min(1.0);
+    let linked = (linked as f64 / SATURATING_RELATIONSHIPS as f64).min(1.0);
+    let located = if located { 1.0 } else { 0.0 };
+    let recency = 0.5_f64.powf(age_days.max(0.0) / RECENCY_HALF_LIFE_DAYS);
+    let score = IDENTIFIER_WEIGHT * identifiers
+        + RELATIONSHIP_WEIGHT * linked
+        + LOCATION_WEIGHT * located
+        + RECENCY_WEIGHT * recency;
+    (score * 1000.0).round() / 1000.0
+}
+
+/// Recompute and persist `completeness` for every entity as of `now`.
+pub(crate) async fn update_completeness(graph: &Graph, now: DateTime<Utc>) -> Result<u64> {
+    let mut stream = timed(graph.execute(query(&completeness_inputs_cypher())))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to query completeness inputs: {}", e)))?;
+
+    let mut ids = Vec::new();
+    let mut scores = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let Ok(id) = row.get::<String>("id") else {
+            continue;
+        };
+        let identifiers: i64 = row.get("identifiers").unwrap_or(0);
+        let linked: i64 = row.get("linked").unwrap_or(0);
+        let located: bool = row.get("located").unwrap_or(false);
+        let last_seen: String = row.get("last_seen").unwrap_or_default();
+        // Never seen counts as long ago
+        let age_days = DateTime::parse_from_rfc3339(&last_seen)
+            .map(|at| (now - at.with_timezone(&Utc)).num_seconds() as f64 / 86_400.0)
+            .unwrap_or(f64::INFINITY);
+
+        ids.push(id);
+        scores.push(completeness_score(
+            identifiers.max(0) as u64,
+            linked.max(0) as u64,
+            located,
+            age_days,
+        ));
+    }
+
+    for (id_chunk, score_chunk) in ids
+        .chunks(WRITE_BATCH_SIZE)
+        .zip(scores.chunks(WRITE_BATCH_SIZE))
+    {
+        let q = query(COMPLETENESS_WRITE_CYPHER)
+            .param("ids", id_chunk.to_vec())
+            .param("scores", score_chunk.to_vec());
+        timed(graph.run(q)).await?.map_err(|e| {
+            ArgusError::Graph(format!("Failed to write completeness scores: {}", e))
+        })?;
+    }
+
+    tracing::info!(entities = ids.len(), "Updated entity completeness scores");
+
+    Ok(ids.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_profiles_score_by_recency_alone() {
+        assert_eq!(completeness_score(0, 0, false, 0.0), 0.2);
+        assert_eq!(completeness_score(0, 0, false, 90.0), 0.1);
+        assert_eq!(completeness_score(0, 0, false, f64::INFINITY), 0.0);
+    }
+
+    #[test]
+    fn full_profiles_score_one() {
+        assert_eq!(completeness_score(2, 5, true, 0.0), 1.0);
+        assert_eq!(completeness_score(7, 40, true, 0.0), 1.0);
+        assert_eq!(completeness_score(1, 1, false, 0.0), 0.41);
+    }
+}
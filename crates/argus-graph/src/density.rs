@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph, Query};
+
+use argus_core::density::{geohash_cell_size, DensityCounter, DensityQuery, EventDensity};
+use argus_core::error::{ArgusError, Result};
+use argus_core::tracks::property_coordinates;
+
+use crate::store::timed;
+
+/// Position reports are binned in the database: there are far more of them
+/// than events, and their coordinates are plain properties.
+const POSITION_DENSITY_CYPHER: &str = "\
+    MATCH (p:Position) WHERE p.observed_at >= $since AND p.observed_at < $until \
+      AND p.latitude >= $south AND p.latitude <= $north \
+      AND CASE WHEN $west <= $east THEN p.longitude >= $west AND p.longitude <= $east \
+        ELSE p.longitude >= $west OR p.longitude <= $east END \
+    WITH toInteger(floor((p.longitude + 180.0) / $cell_width)) AS x, \
+      toInteger(floor((p.latitude + 90.0) / $cell_height)) AS y, \
+      datetime(p.observed_at).epochSeconds / $interval AS bucket, \
+      coalesce(p.source, '') AS source \
+    RETURN x, y, bucket, source, count(*) AS count";
+
+/// Event coordinates live in the JSON `properties` string, so events are
+/// binned here; the `CONTAINS` skips the ones without any.
+const EVENT_COORDINATES_CYPHER: &str = "\
+    MATCH (n:Event) WHERE NOT n:Deleted \
+    WITH n, coalesce(n.observed_at, n.first_seen) AS at \
+    WHERE at >= $since AND at < $until AND n.properties CONTAINS 'lat' \
+    RETURN n.properties AS properties, at, n.source AS source";
+
+fn with_window(q: Query, density: &DensityQuery) -> Query {
+    q.param("since", density.since.to_rfc3339())
+        .param("until", density.until.to_rfc3339())
+}
+
+pub(crate) async fn event_density(graph: &Graph, density: &DensityQuery) -> Result<EventDensity> {
+    let mut counter = DensityCounter::new(density.clone());
+    count_positions(graph, &mut counter).await?;
+    count_events(graph, &mut counter).await?;
+    Ok(counter.finish())
+}
+
+async fn count_positions(graph: &Graph, counter: &mut DensityCounter) -> Result<()> {
+    let density = counter.query().clone();
+    let (cell_height, cell_width) = geohash_cell_size(density.precision);
+    let q = with_window(query(POSITION_DENSITY_CYPHER), &density)
+        .param("west", density.bbox.west)
+        .param("south", density.bbox.south)
+        .param("east", density.bbox.east)
+        .param("north", density.bbox.north)
+        .param("cell_width", cell_width)
+        .param("cell_height", cell_height)
+        .param("interval", density.interval.num_seconds());
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to count positions: {}", e)))?;
+
+    while let Ok(Some(row)) = stream.next().await {
+        let (Ok(x), Ok(y), Ok(bucket), Ok(count)) = (
+            row.get::<i64>("x"),
+            row.get::<i64>("y"),
+            row.get::<i64>("bucket"),
+            row.get::<i64>("count"),
+        ) else {
+            continue;
+        };
+        let source: String = row.get("source").unwrap_or_default();
+        counter.add_cell(
+            x.max(0) as u64,
+            y.max(0) as u64,
+            bucket,
+            &source,
+            count.max(0) as u64,
+        );
+    }
+    Ok(())
+}
+
+async fn count_events(graph: &Graph, counter: &mut DensityCounter) -> Result<()> {
+    let q = with_window(query(EVENT_COORDINATES_CYPHER), counter.query());
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to read event coordinates: {}", e)))?;
+
+    while let Ok(Some(row)) = stream.next().await {
+        let (Ok(properties), Ok(at)) = (row.get::<String>("properties"), row.get::<String>("at"))
+        else {
+            continue;
+        };
+        let Some((latitude, longitude)) = serde_json::from_str(&properties)
+            .ok()
+            .as_ref()
+            .and_then(property_coordinates)
+        else {
+            continue;
+        };
+        let Ok(at) = DateTime::parse_from_rfc3339(&at) else {
+            continue;
+        };
+        let source: String = row.get("source").unwrap_or_default();
+        counter.add(latitude, longitude, at.with_timezone(&Utc), &source);
+    }
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph, Node};
+use uuid::Uuid;
+
+use argus_core::document::{
+    ArchivedDocument, DocumentSearchHit, DocumentSearchQuery, RunDocumentSample, RunExtractionStats,
+};
+use argus_core::error::{ArgusError, Result};
+
+use crate::store::{label_to_entity_type, timed};
+
+/// Label for archived source documents. Kept out of entity queries.
+pub(crate) const DOCUMENT_LABEL: &str = argus_core::document::DOCUMENT_LABEL;
+
+const FULLTEXT_INDEX: &str = "source_document_text";
+
+const ARCHIVE_CYPHER: &str = "\
+    MERGE (d:SourceDocument {source: $source, source_id: $source_id}) \
+    ON CREATE SET d.id = $id \
+    SET d.title = $title, d.content = $content, d.url = $url, \
+      d.collected_at = $collected_at, d.run_id = $run_id, d.entity_ids = $entity_ids, \
+      d.extraction = $extraction, d.extraction_error = $extraction_error";
+
+const SAMPLE_CYPHER: &str = "\
+    MATCH (d:SourceDocument {run_id: $run_id}) \
+    WITH collect(d) AS docs \
+    UNWIND docs AS node \
+    WITH node, size(docs) AS total \
+    ORDER BY rand() \
+    LIMIT $sample \
+    RETURN node, total";
+
+const RUN_DOCUMENTS_CYPHER: &str = "\
+    MATCH (d:SourceDocument {run_id: $run_id}) \
+    RETURN count(d) AS documents, \
+      sum(CASE WHEN coalesce(d.extraction_error, '') <> '' THEN 1 ELSE 0 END) AS errors";
+
+/// Entities are counted once per run however many documents mention them.
+const RUN_ENTITIES_CYPHER: &str = "\
+    MATCH (d:SourceDocument {run_id: $run_id}) \
+    UNWIND coalesce(d.entity_ids, []) AS entity_id \
+    WITH DISTINCT entity_id \
+    MATCH (n {id: entity_id}) WHERE NOT n:SourceDocument \
+    RETURN head(labels(n)) AS label, n.first_seen >= $started_at AS new, count(n) AS entities";
+
+const SEARCH_CYPHER: &str = "\
+    CALL db.index.fulltext.queryNodes('source_document_text', $query) YIELD node, score \
+    WHERE ($source = '' OR node.source = $source) \
+      AND ($start = '' OR node.collected_at >= $start) \
+      AND ($end = '' OR node.collected_at <= $end) \
+    RETURN node, score \
+    ORDER BY score DESC \
+    LIMIT $limit";
+
+/// Create the full-text index backing document search if it is missing.
+pub(crate) async fn ensure_schema(graph: &Graph) -> Result<()> {
+    let cypher = format!(
+        "CREATE FULLTEXT INDEX {FULLTEXT_INDEX} IF NOT EXISTS \
+         FOR (d:{DOCUMENT_LABEL}) ON EACH [d.title, d.content]"
+    );
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create document index: {}", e)))?;
+
+    let cypher = format!(
+        "CREATE INDEX source_document_run IF NOT EXISTS FOR (d:{DOCUMENT_LABEL}) ON (d.run_id)"
+    );
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create document run index: {}", e)))
+}
+
+#[tracing::instrument(name = "neo4j_transaction", skip_all, fields(db.system = "neo4j", documents = documents.len()))]
+pub(crate) async fn archive_documents(graph: &Graph, documents: &[ArchivedDocument]) -> Result<()> {
+    let mut txn = timed(graph.start_txn())
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to start transaction: {}", e)))?;
+
+    for doc in documents {
+        let q = query(ARCHIVE_CYPHER)
+            .param("id", doc.id.to_string())
+            .param("source", doc.source.clone())
+            .param("source_id", doc.source_id.clone())
+            .param("title", doc.title.clone().unwrap_or_default())
+            .param("content", doc.content.clone())
+            .param("url", doc.url.clone().unwrap_or_default())
+            .param("collected_at", doc.collected_at.to_rfc3339())
+            .param("run_id", doc.run_id.clone().unwrap_or_default())
+            .param(
+                "entity_ids",
+                doc.entity_ids
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .param(
+                "extraction",
+                doc.extraction
+                    .as_ref()
+                    .and_then(|r| serde_json::to_string(r).ok())
+                    .unwrap_or_default(),
+            )
+            .param(
+                "extraction_error",
+                doc.extraction_error.clone().unwrap_or_default(),
+            );
+        txn.run(q).await.map_err(|e| {
+            ArgusError::Graph(format!(
+                "Failed to archive document {}: {}",
+                doc.source_id, e
+            ))
+        })?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to commit transaction: {}", e)))?;
+
+    tracing::debug!(documents = documents.len(), "Archived source documents");
+    Ok(())
+}
+
+pub(crate) async fn search_documents(
+    graph: &Graph,
+    search: &DocumentSearchQuery,
+) -> Result<Vec<DocumentSearchHit>> {
+    let q = query(SEARCH_CYPHER)
+        .param("query", escape_lucene(&search.query))
+        .param("source", search.source.clone().unwrap_or_default())
+        .param(
+            "start",
+            search.start.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        )
+        .param(
+            "end",
+            search.end.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        )
+        .param("limit", search.limit as i64);
+
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to search documents: {}", e)))?;
+
+    let mut hits = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let node: Node = match row.get("node") {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to parse document node");
+                continue;
+            }
+        };
+        let score: f64 = row.get("score").unwrap_or(0.0);
+        match node_to_document(&node) {
+            Ok(document) => hits.push(DocumentSearchHit { document, score }),
+            Err(e) => tracing::warn!(error = %e, "Skipping malformed document node"),
+        }
+    }
+
+    Ok(hits)
+}
+
+pub(crate) async fn sample_run_documents(
+    graph: &Graph,
+    run_id: &str,
+    sample: usize,
+) -> Result<RunDocumentSample> {
+    let q = query(SAMPLE_CYPHER)
+        .param("run_id", run_id)
+        .param("sample", sample as i64);
+
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to sample run documents: {}", e)))?;
+
+    let mut result = RunDocumentSample::default();
+    while let Ok(Some(row)) = stream.next().await {
+        result.total = row.get::<i64>("total").unwrap_or(0).max(0) as u64;
+        let node: Node = match row.get("node") {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to parse document node");
+                continue;
+            }
+        };
+        match node_to_document(&node) {
+            Ok(mut document) => {
+                let extraction: String = node.get("extraction").unwrap_or_default();
+                document.extraction = serde_json::from_str(&extraction).ok();
+                document.extraction_error = node
+                    .get("extraction_error")
+                    .ok()
+                    .filter(|e: &String| !e.is_empty());
+                result.documents.push(document);
+            }
+            Err(e) => tracing::warn!(error = %e, "Skipping malformed document node"),
+        }
+    }
+
+    Ok(result)
+}
+
+pub(crate) async fn run_extraction_stats(
+    graph: &Graph,
+    run_id: &str,
+    started_at: DateTime<Utc>,
+) -> Result<RunExtractionStats> {
+    let mut stats = RunExtractionStats::default();
+    let failed = |e| ArgusError::Graph(format!("Failed to read run documents: {}", e));
+
+    let mut stream = timed(graph.execute(query(RUN_DOCUMENTS_CYPHER).param("run_id", run_id)))
+        .await?
+        .map_err(failed)?;
+    if let Ok(Some(row)) = stream.next().await {
+        stats.documents = row.get::<i64>("documents").unwrap_or(0).max(0) as u64;
+        stats.extraction_errors = row.get::<i64>("errors").unwrap_or(0).max(0) as u64;
+    }
+
+    let q = query(RUN_ENTITIES_CYPHER)
+        .param("run_id", run_id)
+        .param("started_at", started_at.to_rfc3339());
+    let mut stream = timed(graph.execute(q)).await?.map_err(failed)?;
+    while let Ok(Some(row)) = stream.next().await {
+        let label: String = row.get("label").unwrap_or_default();
+        let count = row.get::<i64>("entities").unwrap_or(0).max(0) as u64;
+        *stats
+            .entities_by_type
+            .entry(label_to_entity_type(&label).to_string())
+            .or_default() += count;
+        if row.get::<bool>("new").unwrap_or(false) {
+            stats.new_entities += count;
+        } else {
+            stats.repeated_entities += count;
+        }
+    }
+
+    Ok(stats)
+}
+
+fn node_to_document(node: &Node) -> Result<ArchivedDocument> {
+    let id_str: String = node
+        .get("id")
+        .map_err(|e| ArgusError::Graph(format!("Missing id on document: {}", e)))?;
+    let id =
+        Uuid::parse_str(&id_str).map_err(|e| ArgusError::Graph(format!("Invalid UUID: {}", e)))?;
+    let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+
+    let collected_at_str: String = node.get("collected_at").unwrap_or_default();
+    let entity_ids: Vec<String> = node.get("entity_ids").unwrap_or_default();
+
+    Ok(ArchivedDocument {
+        id,
+        source: node.get("source").unwrap_or_default(),
+        source_id: node.get("source_id").unwrap_or_default(),
+        title: node.get("title").ok().and_then(non_empty),
+        content: node.get("content").unwrap_or_default(),
+        url: node.get("url").ok().and_then(non_empty),
+        collected_at: chrono::DateTime::parse_from_rfc3339(&collected_at_str)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now()),
+        run_id: node.get("run_id").ok().and_then(non_empty),
+        entity_ids: entity_ids
+            .iter()
+            .filter_map(|s| Uuid::parse_str(s).ok())
+            .collect(),
+        // Potentially large; only run sampling reads it
+        extraction: None,
+        extraction_error: None,
+    })
+}
+
+/// Escape Lucene query syntax so user input is treated as plain terms.
+pub(crate) fn escape_lucene(input: &str) -> String {
+    const SPECIAL: &[char] = &[
+        '+', '-', '&', '|', '!', '(', ')', '{', '}', '[', ']', '^', '"', '~', '*', '?', ':', '\\',
+        '/',
+    ];
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        if SPECIAL.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_lucene_special_characters() {
+        assert_eq!(escape_lucene("acme corp"), "acme corp");
+        assert_eq!(escape_lucene("a+b (c)"), "a\\+b \\(c\\)");
+        assert_eq!(escape_lucene("path/to:x"), "path\\/to\\:x");
+    }
+}
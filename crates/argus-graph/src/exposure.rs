@@ -0,0 +1,159 @@
+use neo4rs::{query, Graph};
+
+use argus_core::error::{ArgusError, Result};
+
+use crate::store::timed;
+
+/// Weight of a direct SANCTIONED_BY link to a Sanction node.
+const DIRECT_WEIGHT: f64 = 1.0;
+/// Weight of each sanctioned owner (OWNER_OF pointing at the entity).
+const OWNED_BY_WEIGHT: f64 = 0.7;
+/// Weight of each sanctioned counterparty (TRANSACTED_WITH in either direction).
+const TRANSACTED_WITH_WEIGHT: f64 = 0.4;
+
+/// Number of scores written back per UNWIND batch.
+const WRITE_BATCH_SIZE: usize = 1000;
+
+/// The labels scored, each with an index on `id` for the write-back.
+const SCORED_LABELS: [&str; 2] = ["Person", "Organization"];
+
+/// Collect, for every person and organization, how many sanctions hit it
+/// directly and how many directly-sanctioned owners / counterparties it has.
+const EXPOSURE_INPUTS_CYPHER: &str = "\
+    MATCH (e) WHERE e:Person OR e:Organization \
+    OPTIONAL MATCH (e)-[:SANCTIONED_BY]->(s:Sanction) \
+    WITH e, count(DISTINCT s) AS direct \
+    OPTIONAL MATCH (o)-[:OWNER_OF]->(e) WHERE (o)-[:SANCTIONED_BY]->(:Sanction) \
+    WITH e, direct, count(DISTINCT o) AS owned_by \
+    OPTIONAL MATCH (e)-[:TRANSACTED_WITH]-(t) WHERE t <> e AND (t)-[:SANCTIONED_BY]->(:Sanction) \
+    RETURN e.id AS id, CASE WHEN e:Person THEN 'Person' ELSE 'Organization' END AS label, \
+           direct, owned_by, count(DISTINCT t) AS transacted_with";
+
+/// Write back one batch of scores for nodes of `label`.
+fn exposure_write_cypher(label: &str) -> String {
+    format!(
+        "UNWIND range(0, size($ids) - 1) AS i \
+         MATCH (n:{label} {{id: $ids[i]}}) \
+         SET n.sanctions_exposure = $scores[i]"
+    )
+}
+
+/// Create the `id` indexes the score write-back looks nodes up by.
+pub(crate) async fn ensure_schema(graph: &Graph) -> Result<()> {
+    for label in SCORED_LABELS {
+        let cypher = format!(
+            "CREATE INDEX {}_id IF NOT EXISTS FOR (n:{label}) ON (n.id)",
+            label.to_lowercase()
+        );
+        timed(graph.run(query(&cypher)))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to create {label} id index: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Combine proximity counts into a score in [0.0, 1.0].
+///
+/// Each sanctioned link is treated as an independent signal and combined
+/// noisy-OR style, so a direct hit always scores 1.0 while several indirect
+/// links approach but never reach it.
+pub(crate) fn exposure_score(direct: u64, owned_by: u64, transacted_with: u64) -> f64 {
+    let clean = (1.0 - DIRECT_WEIGHT).powi(direct.min(i32::MAX as u64) as i32)
+        * (1.0 - OWNED_BY_WEIGHT).powi(owned_by.min(i32::MAX as u64) as i32)
+        * (1.0 - TRANSACTED_WITH_WEIGHT).powi(transacted_with.min(i32::MAX as u64) as i32);
+    ((1.0 - clean) * 1000.0).round() / 1000.0
+}
+
+/// Recompute and persist `sanctions_exposure` for every person and organization.
+pub(crate) async fn update_sanctions_exposure(graph: &Graph) -> Result<u64> {
+    let mut stream = timed(graph.execute(query(EXPOSURE_INPUTS_CYPHER)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to query exposure inputs: {}", e)))?;
+
+    // Ids and scores per label
+    let mut batches: [(Vec<String>, Vec<f64>); 2] = Default::default();
+    while let Some(row) = stream
+        .next()
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to read exposure inputs: {}", e)))?
+    {
+        let id: String = match row.get("id") {
+            Ok(id) => id,
+            Err(_) => continue,
+        };
+        let label: String = row.get("label").unwrap_or_default();
+        let Some(slot) = SCORED_LABELS.iter().position(|l| *l == label) else {
+            continue;
+        };
+        let direct: i64 = row.get("direct").unwrap_or(0);
+        let owned_by: i64 = row.get("owned_by").unwrap_or(0);
+        let transacted_with: i64 = row.get("transacted_with").unwrap_or(0);
+
+        let (ids, scores) = &mut batches[slot];
+        ids.push(id);
+        scores.push(exposure_score(
+            direct.max(0) as u64,
+            owned_by.max(0) as u64,
+            transacted_with.max(0) as u64,
+        ));
+    }
+
+    let mut scored = 0;
+    for (label, (ids, scores)) in SCORED_LABELS.iter().zip(&batches) {
+        let cypher = exposure_write_cypher(label);
+        for (id_chunk, score_chunk) in ids
+            .chunks(WRITE_BATCH_SIZE)
+            .zip(scores.chunks(WRITE_BATCH_SIZE))
+        {
+            let q = query(&cypher)
+                .param("ids", id_chunk.to_vec())
+                .param("scores", score_chunk.to_vec());
+            timed(graph.run(q)).await?.map_err(|e| {
+                ArgusError::Graph(format!("Failed to write exposure scores: {}", e))
+            })?;
+        }
+        scored += ids.len() as u64;
+    }
+
+    tracing::info!(entities = scored, "Updated sanctions exposure scores");
+
+    Ok(scored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_are_written_back_through_the_label_index() {
+        assert!(exposure_write_cypher("Person").contains("MATCH (n:Person {id: $ids[i]})"));
+        assert!(EXPOSURE_INPUTS_CYPHER.contains("AS label"));
+    }
+
+    #[test]
+    fn no_links_scores_zero() {
+        assert_eq!(exposure_score(0, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn direct_hit_scores_one() {
+        assert_eq!(exposure_score(1, 0, 0), 1.0);
+        assert_eq!(exposure_score(2, 3, 4), 1.0);
+    }
+
+    #[test]
+    fn owned_by_outweighs_transacted_with() {
+        assert!(exposure_score(0, 1, 0) > exposure_score(0, 0, 1));
+        assert_eq!(exposure_score(0, 1, 0), 0.7);
+        assert_eq!(exposure_score(0, 0, 1), 0.4);
+    }
+
+    #[test]
+    fn indirect_links_accumulate_below_one() {
+        let one = exposure_score(0, 0, 1);
+        let three = exposure_score(0, 0, 3);
+        assert!(three > one);
+        assert!(three < 1.0);
+        assert_eq!(exposure_score(0, 1, 1), 0.82);
+    }
+}
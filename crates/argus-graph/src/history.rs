@@ -0,0 +1,375 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph, Row, Txn};
+use serde_json::Value;
+use uuid::Uuid;
+
+use argus_core::entity::Entity;
+use argus_core::error::{ArgusError, Result};
+use argus_core::history::{ChangeKind, EntityChange};
+
+use crate::store::timed;
+
+/// Label for change-log entries. They carry `entity_id` rather than an edge
+/// so traversals over the entity graph never pick them up.
+pub(crate) const CHANGE_LABEL: &str = "EntityChange";
+
+const WRITE_CHANGE_CYPHER: &str = "\
+    CREATE (c:EntityChange {id: $id, entity_id: $entity_id, kind: $kind, field: $field, \
+      old_value: $old_value, new_value: $new_value, source: $source, \
+      document_id: $document_id, changed_at: $changed_at})";
+
+const HISTORY_CYPHER: &str = "\
+    MATCH (c:EntityChange {entity_id: $entity_id}) \
+    RETURN c.id AS id, c.kind AS kind, c.field AS field, c.old_value AS old_value, \
+      c.new_value AS new_value, c.source AS source, c.document_id AS document_id, \
+      c.changed_at AS changed_at \
+    ORDER BY c.changed_at DESC \
+    LIMIT $limit";
+
+/// Stored state of a node `store_extraction` is about to overwrite.
+#[derive(Debug, Clone)]
+pub(crate) struct EntitySnapshot {
+    pub id: Uuid,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub properties: Value,
+    pub confidence: f64,
+    pub sources: Vec<String>,
+}
+
+/// Nodes the upsert in `store_extraction` will touch, mirroring its
+/// resolution order: a same-name node from another source wins, otherwise
+/// the node keyed on (source, source_id) or id. The flag reports which.
+pub(crate) fn snapshot_cypher(label: &str, by_source_id: bool) -> String {
+    let own = if by_source_id {
+        format!("(own:{label} {{source: $source, source_id: $source_id}})")
+    } else {
+        format!("(own:{label} {{id: $id}})")
+    };
+    format!(
+        "OPTIONAL MATCH (existing:{label}) \
+           WHERE toLower(existing.name) = toLower($name) AND existing.source <> $source \
+             AND NOT existing:Deleted \
+         WITH collect(existing) AS cross \
+         OPTIONAL MATCH {own} \
+         WITH cross, own, size(cross) > 0 AS is_cross \
+         UNWIND CASE WHEN is_cross THEN cross ELSE [x IN [own] WHERE x IS NOT NULL] END AS n \
+         RETURN n.id AS id, n.name AS name, n.aliases AS aliases, n.properties AS properties, \
+           n.confidence AS confidence, n.sources AS sources, is_cross"
+    )
+}
+
+pub(crate) async fn read_snapshots(
+    txn: &mut Txn,
+    label: &str,
+    entity: &Entity,
+) -> Result<(Vec<EntitySnapshot>, bool)> {
+    let cypher = snapshot_cypher(label, entity.source_id.is_some());
+    let q = query(&cypher)
+        .param("id", entity.id.to_string())
+        .param("name", entity.name.clone())
+        .param("source", entity.source.clone())
+        .param("source_id", entity.source_id.clone().unwrap_or_default());
+
+    let mut stream = txn
+        .execute(q)
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to read entity before update: {}", e)))?;
+
+    let mut snapshots = Vec::new();
+    let mut is_cross = false;
+    while let Ok(Some(row)) = stream.next(txn.handle()).await {
+        is_cross = row.get("is_cross").unwrap_or(false);
+        if let Some(snapshot) = row_to_snapshot(&row) {
+            snapshots.push(snapshot);
+        }
+    }
+    Ok((snapshots, is_cross))
+}
+
+fn row_to_snapshot(row: &Row) -> Option<EntitySnapshot> {
+    let id: String = row.get("id").ok()?;
+    let aliases: String = row.get("aliases").unwrap_or_else(|_| "[]".to_string());
+    let properties: String = row.get("properties").unwrap_or_else(|_| "{}".to_string());
+    Some(EntitySnapshot {
+        id: Uuid::parse_str(&id).ok()?,
+        name: row.get("name").unwrap_or_default(),
+        aliases: serde_json::from_str(&aliases).unwrap_or_default(),
+        properties: serde_json::from_str(&properties).unwrap_or(Value::Null),
+        confidence: row.get("confidence").unwrap_or(0.0),
+        sources: row.get("sources").unwrap_or_default(),
+    })
+}
+
+/// Changes `store_extraction` makes when writing `incoming` over `before`.
+///
+/// Cross-source merges keep the stored name and only raise confidence, so
+/// those cases are not reported as changes.
+pub(crate) fn diff_entity(
+    before: Option<&EntitySnapshot>,
+    incoming: &Entity,
+    is_cross: bool,
+    document_id: &str,
+    now: DateTime<Utc>,
+) -> Vec<EntityChange> {
+    let change =
+        |entity_id: Uuid, kind, field: Option<&str>, old: Option<Value>, new: Option<Value>| {
+            EntityChange {
+                id: Uuid::new_v4(),
+                entity_id,
+                kind,
+                field: field.map(str::to_string),
+                old_value: old,
+                new_value: new,
+                source: incoming.source.clone(),
+                document_id: (!document_id.is_empty()).then(|| document_id.to_string()),
+                changed_at: now,
+            }
+        };
+
+    let Some(before) = before else {
+        return vec![change(
+            incoming.id,
+            ChangeKind::Created,
+            None,
+            None,
+            Some(Value::String(incoming.name.clone())),
+        )];
+    };
+
+    let id = before.id;
+    let mut changes = Vec::new();
+
+    if !is_cross && before.name != incoming.name {
+        changes.push(change(
+            id,
+            ChangeKind::NameChanged,
+            Some("name"),
+            Some(Value::String(before.name.clone())),
+            Some(Value::String(incoming.name.clone())),
+        ));
+    }
+
+    if before.aliases != incoming.aliases {
+        changes.push(change(
+            id,
+            ChangeKind::AliasesChanged,
+            Some("aliases"),
+            Some(serde_json::json!(before.aliases)),
+            Some(serde_json::json!(incoming.aliases)),
+        ));
+    }
+
+    let empty = serde_json::Map::new();
+    let old_props = before.properties.as_object().unwrap_or(&empty);
+    let new_props = incoming.properties.as_object().unwrap_or(&empty);
+    let mut keys: Vec<&String> = old_props.keys().chain(new_props.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let old = old_props.get(key);
+        let new = new_props.get(key);
+        if old != new {
+            changes.push(change(
+                id,
+                ChangeKind::PropertyChanged,
+                Some(key),
+                old.cloned(),
+                new.cloned(),
+            ));
+        }
+    }
+
+    let new_confidence = if is_cross {
+        before.confidence.max(incoming.confidence)
+    } else {
+        incoming.confidence
+    };
+    if (new_confidence - before.confidence).abs() > f64::EPSILON {
+        changes.push(change(
+            id,
+            ChangeKind::ConfidenceChanged,
+            Some("confidence"),
+            Some(serde_json::json!(before.confidence)),
+            Some(serde_json::json!(new_confidence)),
+        ));
+    }
+
+    if !before.sources.contains(&incoming.source) {
+        changes.push(change(
+            id,
+            ChangeKind::SourceAdded,
+            Some("sources"),
+            None,
+            Some(Value::String(incoming.source.clone())),
+        ));
+    }
+
+    changes
+}
+
+fn value_param(value: &Option<Value>) -> String {
+    value.as_ref().map(Value::to_string).unwrap_or_default()
+}
+
+pub(crate) async fn write_changes(txn: &mut Txn, changes: &[EntityChange]) -> Result<()> {
+    for change in changes {
+        let q = query(WRITE_CHANGE_CYPHER)
+            .param("id", change.id.to_string())
+            .param("entity_id", change.entity_id.to_string())
+            .param("kind", change.kind.as_str())
+            .param("field", change.field.clone().unwrap_or_default())
+            .param("old_value", value_param(&change.old_value))
+            .param("new_value", value_param(&change.new_value))
+            .param("source", change.source.clone())
+            .param(
+                "document_id",
+                change.document_id.clone().unwrap_or_default(),
+            )
+            .param("changed_at", change.changed_at.to_rfc3339());
+        txn.run(q)
+            .await
+            .map_err(|e| ArgusError::Graph(format!("Failed to record entity change: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Create the index that keeps history lookups off a label scan.
+pub(crate) async fn ensure_schema(graph: &Graph) -> Result<()> {
+    let cypher = format!(
+        "CREATE INDEX entity_change_entity_id IF NOT EXISTS FOR (c:{CHANGE_LABEL}) ON (c.entity_id)"
+    );
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create entity change index: {}", e)))
+}
+
+pub(crate) async fn entity_history(
+    graph: &Graph,
+    entity_id: Uuid,
+    limit: usize,
+) -> Result<Vec<EntityChange>> {
+    let q = query(HISTORY_CYPHER)
+        .param("entity_id", entity_id.to_string())
+        .param("limit", limit as i64);
+
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to query entity history: {}", e)))?;
+
+    let mut changes = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let id: String = row.get("id").unwrap_or_default();
+        let kind: String = row.get("kind").unwrap_or_default();
+        let Some(kind) = ChangeKind::from_name(&kind) else {
+            tracing::warn!(kind = %kind, "Skipping entity change with unknown kind");
+            continue;
+        };
+        let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+        let parse_value = |s: String| non_empty(s).and_then(|s| serde_json::from_str(&s).ok());
+        let changed_at: String = row.get("changed_at").unwrap_or_default();
+
+        changes.push(EntityChange {
+            id: Uuid::parse_str(&id).unwrap_or_else(|_| Uuid::nil()),
+            entity_id,
+            kind,
+            field: row.get("field").ok().and_then(non_empty),
+            old_value: row.get("old_value").ok().and_then(parse_value),
+            new_value: row.get("new_value").ok().and_then(parse_value),
+            source: row.get("source").unwrap_or_default(),
+            document_id: row.get("document_id").ok().and_then(non_empty),
+            changed_at: DateTime::parse_from_rfc3339(&changed_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_default(),
+        });
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::entity::EntityType;
+
+    fn snapshot() -> EntitySnapshot {
+        EntitySnapshot {
+            id: Uuid::new_v4(),
+            name: "Acme Corp".to_string(),
+            aliases: vec!["Acme".to_string()],
+            properties: serde_json::json!({"country": "DE", "founded": 1999}),
+            confidence: 0.8,
+            sources: vec!["opencorporates".to_string()],
+        }
+    }
+
+    fn incoming(source: &str) -> Entity {
+        let mut entity = Entity::new(
+            EntityType::Organization,
+            "Acme Corp".to_string(),
+            source.to_string(),
+        );
+        entity.aliases = vec!["Acme".to_string()];
+        entity.properties = serde_json::json!({"country": "DE", "founded": 1999});
+        entity.confidence = 0.8;
+        entity
+    }
+
+    #[test]
+    fn new_entity_records_creation() {
+        let entity = incoming("gdelt");
+        let changes = diff_entity(None, &entity, false, "doc-1", Utc::now());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::Created);
+        assert_eq!(changes[0].entity_id, entity.id);
+        assert_eq!(changes[0].document_id.as_deref(), Some("doc-1"));
+    }
+
+    #[test]
+    fn identical_update_records_nothing() {
+        let before = snapshot();
+        let changes = diff_entity(
+            Some(&before),
+            &incoming("opencorporates"),
+            false,
+            "",
+            Utc::now(),
+        );
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn property_changes_are_reported_per_key() {
+        let before = snapshot();
+        let mut entity = incoming("opencorporates");
+        entity.properties = serde_json::json!({"country": "FR", "status": "active"});
+
+        let changes = diff_entity(Some(&before), &entity, false, "", Utc::now());
+        let fields: Vec<&str> = changes.iter().filter_map(|c| c.field.as_deref()).collect();
+        assert_eq!(fields, vec!["country", "founded", "status"]);
+        assert!(changes
+            .iter()
+            .all(|c| c.kind == ChangeKind::PropertyChanged));
+        assert_eq!(changes[1].new_value, None);
+        assert_eq!(changes[2].old_value, None);
+        assert!(changes.iter().all(|c| c.entity_id == before.id));
+    }
+
+    #[test]
+    fn cross_source_merge_keeps_name_and_only_raises_confidence() {
+        let before = snapshot();
+        let mut entity = incoming("gdelt");
+        entity.name = "ACME CORP".to_string();
+        entity.confidence = 0.5;
+
+        let changes = diff_entity(Some(&before), &entity, true, "", Utc::now());
+        let kinds: Vec<ChangeKind> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::SourceAdded]);
+
+        entity.confidence = 0.95;
+        let changes = diff_entity(Some(&before), &entity, true, "", Utc::now());
+        assert!(changes
+            .iter()
+            .any(|c| c.kind == ChangeKind::ConfidenceChanged));
+    }
+}
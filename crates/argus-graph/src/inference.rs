@@ -0,0 +1,219 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph};
+use uuid::Uuid;
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::inference::{
+    self, Inference, InferenceReport, InferenceRule, PremiseEdge, INFERENCE_SOURCE,
+};
+
+use crate::store::{relation_type_to_label, timed};
+
+/// Most premises read per rule and pass.
+const MAX_PREMISES: i64 = 200_000;
+
+/// Number of inferred relationships written per UNWIND batch.
+const WRITE_BATCH_SIZE: usize = 1000;
+
+/// Asserted edges of one type; inferred ones never serve as premises.
+fn premise_edges_cypher(label: &str, pattern: &str) -> String {
+    format!(
+        "MATCH {pattern} WHERE r.id IS NOT NULL AND coalesce(r.inferred, false) = false \
+           AND NOT a:Deleted AND NOT b:Deleted AND a <> b \
+         RETURN a.id AS a, b.id AS b, r.id AS id, coalesce(r.confidence, 1.0) AS confidence \
+         LIMIT $limit",
+        pattern = pattern.replace("{label}", label),
+    )
+}
+
+const ADDRESSES_CYPHER: &str = "\
+    MATCH (n:Organization) WHERE NOT n:Deleted AND n.properties CONTAINS 'address' \
+    RETURN n.id AS id, n.properties AS properties LIMIT $limit";
+
+/// Upsert inferred edges of one type, keyed on rule and endpoints, and
+/// stamp them with the pass that derived them.
+fn write_cypher(label: &str) -> String {
+    format!(
+        "UNWIND range(0, size($ids) - 1) AS i \
+         MATCH (a {{id: $from[i]}}) \
+         MATCH (b {{id: $to[i]}}) \
+         MERGE (a)-[r:{label} {{source: $source, rule: $rule}}]->(b) \
+         ON CREATE SET r.id = $ids[i], r.created_at = $run_at, r.timestamp = $run_at, \
+           r.observations = 1, r.search_text = '' \
+         SET r.inferred = true, r.confidence = $confidence[i], r.properties = $properties[i], \
+           r.last_seen = $run_at, r.inferred_at = $run_at"
+    )
+}
+
+/// Remove the rule's edges that the current pass did not derive again.
+fn retract_cypher(label: &str) -> String {
+    format!(
+        "MATCH ()-[r:{label} {{source: $source, rule: $rule}}]->() \
+         WHERE coalesce(r.inferred_at, '') <> $run_at \
+         DELETE r RETURN count(*) AS retracted"
+    )
+}
+
+/// Derive the rule's relationships from the current graph, store them, and
+/// retract those derived by earlier passes whose premises are gone.
+pub(crate) async fn apply_inference_rule(
+    graph: &Graph,
+    rule: InferenceRule,
+    run_at: DateTime<Utc>,
+) -> Result<InferenceReport> {
+    let inferences = match rule {
+        InferenceRule::OwnershipChain | InferenceRule::PartOfChain => {
+            let label = relation_type_to_label(&rule.relation_type());
+            let edges =
+                premise_edges(graph, &premise_edges_cypher(&label, "(a)-[r:{label}]->(b)")).await?;
+            inference::transitive_closure(rule, &edges)
+        }
+        InferenceRule::SameAddress => inference::same_address(&addresses(graph).await?),
+        InferenceRule::DirectorOverlap => {
+            let cypher =
+                premise_edges_cypher("DIRECTOR_OF", "(a:Person)-[r:{label}]->(b:Organization)");
+            inference::director_overlap(&premise_edges(graph, &cypher).await?)
+        }
+    };
+
+    let label = relation_type_to_label(&rule.relation_type());
+    let run_at = run_at.to_rfc3339();
+    for batch in inferences.chunks(WRITE_BATCH_SIZE) {
+        write(graph, &label, rule, &run_at, batch).await?;
+    }
+
+    let q = query(&retract_cypher(&label))
+        .param("source", INFERENCE_SOURCE)
+        .param("rule", rule.as_str())
+        .param("run_at", run_at.as_str());
+    let mut stream = timed(graph.execute(q)).await?.map_err(|e| {
+        ArgusError::Graph(format!("Failed to retract inferred relationships: {}", e))
+    })?;
+    let retracted = match stream.next().await {
+        Ok(Some(row)) => row.get::<i64>("retracted").unwrap_or(0),
+        _ => 0,
+    };
+
+    Ok(InferenceReport {
+        rule,
+        inferred: inferences.len() as u64,
+        retracted: retracted.max(0) as u64,
+    })
+}
+
+async fn premise_edges(graph: &Graph, cypher: &str) -> Result<Vec<PremiseEdge>> {
+    let mut stream = timed(graph.execute(query(cypher).param("limit", MAX_PREMISES)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to query inference premises: {}", e)))?;
+
+    let mut edges = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let parse = |key: &str| {
+            row.get::<String>(key)
+                .ok()
+                .and_then(|id| Uuid::parse_str(&id).ok())
+        };
+        let (Some(from), Some(to), Some(id)) = (parse("a"), parse("b"), parse("id")) else {
+            continue;
+        };
+        edges.push(PremiseEdge {
+            id,
+            from,
+            to,
+            confidence: row.get("confidence").unwrap_or(1.0),
+        });
+    }
+    Ok(edges)
+}
+
+async fn addresses(graph: &Graph) -> Result<Vec<(Uuid, String)>> {
+    let mut stream = timed(graph.execute(query(ADDRESSES_CYPHER).param("limit", MAX_PREMISES)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to query organization addresses: {}", e)))?;
+
+    let mut addresses = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let Some(id) = row
+            .get::<String>("id")
+            .ok()
+            .and_then(|id| Uuid::parse_str(&id).ok())
+        else {
+            continue;
+        };
+        let properties: serde_json::Value = row
+            .get::<String>("properties")
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        if let Some(address) = inference::address_of(&properties) {
+            addresses.push((id, address.to_string()));
+        }
+    }
+    Ok(addresses)
+}
+
+async fn write(
+    graph: &Graph,
+    label: &str,
+    rule: InferenceRule,
+    run_at: &str,
+    batch: &[Inference],
+) -> Result<()> {
+    let q = query(&write_cypher(label))
+        .param(
+            "ids",
+            batch.iter().map(|i| i.id().to_string()).collect::<Vec<_>>(),
+        )
+        .param(
+            "from",
+            batch
+                .iter()
+                .map(|i| i.source.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .param(
+            "to",
+            batch
+                .iter()
+                .map(|i| i.target.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .param(
+            "confidence",
+            batch.iter().map(|i| i.confidence).collect::<Vec<_>>(),
+        )
+        .param(
+            "properties",
+            batch
+                .iter()
+                .map(|i| i.properties().to_string())
+                .collect::<Vec<_>>(),
+        )
+        .param("source", INFERENCE_SOURCE)
+        .param("rule", rule.as_str())
+        .param("run_at", run_at);
+    timed(graph.run(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to write inferred relationships: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn premises_exclude_inferred_edges() {
+        let cypher = premise_edges_cypher("OWNER_OF", "(a)-[r:{label}]->(b)");
+        assert!(cypher.starts_with("MATCH (a)-[r:OWNER_OF]->(b) WHERE"));
+        assert!(cypher.contains("coalesce(r.inferred, false) = false"));
+    }
+
+    #[test]
+    fn inferred_edges_are_keyed_on_rule_and_retracted_by_pass() {
+        assert!(write_cypher("PART_OF")
+            .contains("MERGE (a)-[r:PART_OF {source: $source, rule: $rule}]->(b)"));
+        assert!(
+            retract_cypher("RELATED_TO").contains("WHERE coalesce(r.inferred_at, '') <> $run_at")
+        );
+    }
+}
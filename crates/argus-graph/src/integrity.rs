@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use neo4rs::{query, BoltType, Graph, Query};
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::history::ChangeKind;
+use argus_core::integrity::{self, IntegrityCheck, IntegrityFinding, MAX_SAMPLES};
+
+use crate::store::{stored_entity_filter, timed};
+
+/// Violations fixed per graph round-trip, so each stays inside the timeout.
+const REPAIR_BATCH_SIZE: i64 = 5000;
+
+/// Ids as the store writes them. `Uuid::parse_str` accepts other forms too,
+/// but nothing writes those.
+const UUID_PATTERN: &str = "(?i)[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}";
+
+/// `stored_entity_filter` for a node bound to `var` instead of `n`.
+fn stored_entity(var: &str) -> String {
+    stored_entity_filter().replace("NOT n:", &format!("NOT {var}:"))
+}
+
+fn dangling_match() -> String {
+    format!(
+        "MATCH (a)-[r]->(b) WHERE a.id IS NULL OR b.id IS NULL OR NOT ({}) OR NOT ({})",
+        stored_entity("a"),
+        stored_entity("b")
+    )
+}
+
+fn invalid_id_match() -> String {
+    format!(
+        "MATCH (n) WHERE {} AND n.id IS NOT NULL AND NOT toString(n.id) =~ $uuid_pattern",
+        stored_entity_filter()
+    )
+}
+
+fn missing_properties_match() -> String {
+    format!(
+        "MATCH (n) WHERE {} AND (n.id IS NULL OR n.name IS NULL OR n.first_seen IS NULL OR n.last_seen IS NULL)",
+        stored_entity_filter()
+    )
+}
+
+/// Count and sample the violations `match_clause` finds.
+fn count_cypher(match_clause: &str, sample: &str) -> String {
+    format!(
+        "{match_clause} RETURN count(*) AS violations, collect({sample})[0..$samples] AS samples"
+    )
+}
+
+fn repair_dangling_cypher() -> String {
+    format!(
+        "{} WITH r LIMIT $batch DELETE r RETURN count(*) AS repaired",
+        dangling_match()
+    )
+}
+
+/// Nodes missing only what can be filled in; a missing name stays.
+fn repair_missing_cypher() -> String {
+    format!(
+        "MATCH (n) WHERE {} AND (n.id IS NULL OR n.first_seen IS NULL OR n.last_seen IS NULL) \
+         WITH n LIMIT $batch \
+         SET n.id = coalesce(n.id, randomUUID()), \
+           n.first_seen = coalesce(n.first_seen, n.last_seen, $now), \
+           n.last_seen = coalesce(n.last_seen, n.first_seen, $now) \
+         RETURN count(n) AS repaired",
+        stored_entity_filter()
+    )
+}
+
+const POSITION_ASSETS_CYPHER: &str = "\
+    MATCH (p:Position) RETURN coalesce(p.asset, '') AS asset, count(*) AS reports";
+
+fn known_assets_cypher() -> String {
+    format!(
+        "MATCH (n) WHERE {} AND n.identifiers IS NOT NULL \
+         UNWIND n.identifiers AS key WITH DISTINCT key WHERE key IN $assets RETURN key",
+        stored_entity_filter()
+    )
+}
+
+fn missing_document_entities_cypher() -> String {
+    format!(
+        "MATCH (d:SourceDocument) UNWIND coalesce(d.entity_ids, []) AS entity_id \
+         WITH DISTINCT entity_id \
+         WHERE NOT EXISTS {{ MATCH (n {{id: entity_id}}) WHERE {} }} \
+         RETURN entity_id",
+        stored_entity_filter()
+    )
+}
+
+const STALE_DOCUMENTS_MATCH: &str = "\
+    MATCH (d:SourceDocument) WHERE any(id IN coalesce(d.entity_ids, []) WHERE id IN $missing)";
+
+const MERGED_INTO_CYPHER: &str = "\
+    MATCH (c:EntityChange {kind: $kind}) WHERE c.new_value IN $values \
+    RETURN c.new_value AS merged, c.entity_id AS kept";
+
+const WRITE_DOCUMENT_ENTITIES_CYPHER: &str = "\
+    UNWIND $documents AS doc \
+    MATCH (d:SourceDocument {id: doc.id}) SET d.entity_ids = doc.entity_ids";
+
+/// Run one check and, with `repair`, its fix if it has one.
+pub(crate) async fn check_integrity(
+    graph: &Graph,
+    check: IntegrityCheck,
+    repair: bool,
+    now: DateTime<Utc>,
+) -> Result<IntegrityFinding> {
+    let repair = repair && check.repairable();
+    let mut finding = IntegrityFinding::new(check);
+    match check {
+        IntegrityCheck::DanglingRelationships => {
+            let sample = "type(r) + ' ' + coalesce(toString(r.id), '(no id)')";
+            count(
+                graph,
+                query(&count_cypher(&dangling_match(), sample)),
+                &mut finding,
+            )
+            .await?;
+            if repair && finding.violations > 0 {
+                finding.repaired = repair_batched(graph, &repair_dangling_cypher(), now).await?;
+            }
+        }
+        IntegrityCheck::InvalidEntityIds => {
+            let q = query(&count_cypher(&invalid_id_match(), "toString(n.id)"))
+                .param("uuid_pattern", UUID_PATTERN);
+            count(graph, q, &mut finding).await?;
+        }
+        IntegrityCheck::MissingProperties => {
+            let sample = "coalesce(toString(n.id), n.name, head(labels(n)), '(unlabeled)')";
+            count(
+                graph,
+                query(&count_cypher(&missing_properties_match(), sample)),
+                &mut finding,
+            )
+            .await?;
+            if repair && finding.violations > 0 {
+                finding.repaired = repair_batched(graph, &repair_missing_cypher(), now).await?;
+            }
+        }
+        IntegrityCheck::OrphanedPositions => {
+            let reports = position_assets(graph).await?;
+            let assets: Vec<String> = reports.iter().map(|(asset, _)| asset.clone()).collect();
+            let q = query(&known_assets_cypher()).param("assets", assets);
+            let known: HashSet<String> = strings(graph, q, "key").await?.into_iter().collect();
+            let (orphaned, assets) = integrity::orphaned_assets(&reports, &known);
+            finding.violations = orphaned;
+            finding.samples = assets.into_iter().take(MAX_SAMPLES).collect();
+        }
+        IntegrityCheck::OrphanedDocuments => {
+            let missing = strings(
+                graph,
+                query(&missing_document_entities_cypher()),
+                "entity_id",
+            )
+            .await?;
+            if missing.is_empty() {
+                return Ok(finding);
+            }
+            let q = query(&count_cypher(STALE_DOCUMENTS_MATCH, "d.id"))
+                .param("missing", missing.clone());
+            count(graph, q, &mut finding).await?;
+            if repair && finding.violations > 0 {
+                finding.repaired = repoint_documents(graph, missing).await?;
+            }
+        }
+    }
+    Ok(finding)
+}
+
+async fn count(graph: &Graph, q: Query, finding: &mut IntegrityFinding) -> Result<()> {
+    let mut stream = timed(graph.execute(q.param("samples", MAX_SAMPLES as i64)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to run integrity check: {}", e)))?;
+    if let Ok(Some(row)) = stream.next().await {
+        finding.violations = row.get::<i64>("violations").unwrap_or(0).max(0) as u64;
+        finding.samples = row.get("samples").unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Apply a `LIMIT $batch` repair until a batch comes back short.
+async fn repair_batched(graph: &Graph, cypher: &str, now: DateTime<Utc>) -> Result<u64> {
+    let mut repaired = 0;
+    loop {
+        let q = query(cypher)
+            .param("batch", REPAIR_BATCH_SIZE)
+            .param("now", now.to_rfc3339());
+        let mut stream = timed(graph.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to repair graph: {}", e)))?;
+        let batch = match stream.next().await {
+            Ok(Some(row)) => row.get::<i64>("repaired").unwrap_or(0).max(0),
+            _ => 0,
+        };
+        repaired += batch as u64;
+        if batch < REPAIR_BATCH_SIZE {
+            return Ok(repaired);
+        }
+    }
+}
+
+async fn position_assets(graph: &Graph) -> Result<Vec<(String, u64)>> {
+    let mut stream = timed(graph.execute(query(POSITION_ASSETS_CYPHER)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to read position assets: {}", e)))?;
+    let mut assets = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let reports = row.get::<i64>("reports").unwrap_or(0).max(0) as u64;
+        assets.push((row.get("asset").unwrap_or_default(), reports));
+    }
+    Ok(assets)
+}
+
+async fn strings(graph: &Graph, q: Query, column: &str) -> Result<Vec<String>> {
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to run integrity check: {}", e)))?;
+    let mut values = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        if let Ok(value) = row.get::<String>(column) {
+            values.push(value);
+        }
+    }
+    Ok(values)
+}
+
+/// Point documents' stale entity ids at the entities they were merged
+/// into, or drop them. Merges record the merged id as the JSON `new_value`
+/// of a change on the kept entity, and later merges carry those changes
+/// along, so one lookup follows a chain of merges.
+async fn repoint_documents(graph: &Graph, missing: Vec<String>) -> Result<u64> {
+    let values: Vec<String> = missing
+        .iter()
+        .map(|id| serde_json::Value::String(id.clone()).to_string())
+        .collect();
+    let q = query(MERGED_INTO_CYPHER)
+        .param("kind", ChangeKind::Merged.as_str())
+        .param("values", values);
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to read merges: {}", e)))?;
+    let mut merged_into = HashMap::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let merged = row
+            .get::<String>("merged")
+            .ok()
+            .and_then(|value| serde_json::from_str::<String>(&value).ok());
+        if let (Some(merged), Ok(kept)) = (merged, row.get::<String>("kept")) {
+            merged_into.insert(merged, kept);
+        }
+    }
+
+    let q = query(&format!(
+        "{STALE_DOCUMENTS_MATCH} RETURN d.id AS id, d.entity_ids AS entity_ids"
+    ))
+    .param("missing", missing.clone());
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to read documents: {}", e)))?;
+    let missing: HashSet<String> = missing.into_iter().collect();
+    let mut documents: Vec<HashMap<String, BoltType>> = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let (Ok(id), Ok(entity_ids)) = (
+            row.get::<String>("id"),
+            row.get::<Vec<String>>("entity_ids"),
+        ) else {
+            continue;
+        };
+        let entity_ids = integrity::repoint_entity_ids(&entity_ids, &missing, &merged_into);
+        documents.push(HashMap::from([
+            ("id".to_string(), BoltType::from(id)),
+            ("entity_ids".to_string(), BoltType::from(entity_ids)),
+        ]));
+    }
+
+    let repaired = documents.len() as u64;
+    for batch in documents.chunks(REPAIR_BATCH_SIZE as usize) {
+        let q = query(WRITE_DOCUMENT_ENTITIES_CYPHER).param("documents", batch.to_vec());
+        timed(graph.run(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to update documents: {}", e)))?;
+    }
+    Ok(repaired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_are_checked_as_entities() {
+        let cypher = dangling_match();
+        assert!(cypher.contains("NOT a:SourceDocument") && cypher.contains("NOT b:Position"));
+        assert!(!cypher.contains("n:"));
+    }
+
+    #[test]
+    fn repairs_stop_when_nothing_is_left() {
+        // Each batch must select only nodes its own SET takes out of the match
+        let cypher = repair_missing_cypher();
+        assert!(cypher.contains("(n.id IS NULL OR n.first_seen IS NULL OR n.last_seen IS NULL)"));
+        assert!(!cypher.contains("n.name IS NULL"));
+    }
+}
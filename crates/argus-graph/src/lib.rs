@@ -1,3 +1,19 @@
+mod as_of;
+mod completeness;
+mod density;
+mod documents;
+mod exposure;
+mod history;
+mod inference;
+mod integrity;
+mod merge;
+mod paths;
+mod retention;
+mod retry;
+mod snapshot;
 mod store;
+mod strength;
+mod tombstones;
+mod tracks;
 
 pub use store::Neo4jGraphStore;
@@ -0,0 +1,368 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph, Node, Txn};
+use serde_json::Value;
+use uuid::Uuid;
+
+use argus_core::entity::Entity;
+use argus_core::error::{ArgusError, Result};
+use argus_core::history::{ChangeKind, EntityChange};
+
+use crate::store::{identifier_keys, node_to_entity, non_entity_filter, timed};
+
+/// Change-log source for merges, which no agent caused.
+const MERGE_SOURCE: &str = "admin";
+
+/// A node about to take part in a merge, with the source list that
+/// `Entity` does not carry.
+#[derive(Debug, Clone)]
+pub(crate) struct MergeNode {
+    pub entity: Entity,
+    pub sources: Vec<String>,
+}
+
+/// Fields written to the surviving node.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MergedFields {
+    pub aliases: Vec<String>,
+    pub properties: Value,
+    pub sources: Vec<String>,
+    pub identifiers: BTreeMap<String, String>,
+    pub confidence: f64,
+    pub first_seen: DateTime<Utc>,
+    pub observed_at: Option<DateTime<Utc>>,
+}
+
+/// Combine `keep` with the nodes folded into it. Where both have a value
+/// (a property, an identifier scheme) `keep`'s wins; the others' names
+/// become aliases, and dates and confidence take the extremes.
+pub(crate) fn merged_fields(keep: &MergeNode, duplicates: &[MergeNode]) -> MergedFields {
+    let keep_name = keep.entity.name.to_lowercase();
+    let mut aliases: Vec<String> = Vec::new();
+    let candidates = keep.entity.aliases.iter().chain(
+        duplicates
+            .iter()
+            .flat_map(|d| std::iter::once(&d.entity.name).chain(d.entity.aliases.iter())),
+    );
+    for alias in candidates {
+        let lower = alias.to_lowercase();
+        if lower != keep_name && !aliases.iter().any(|a| a.to_lowercase() == lower) {
+            aliases.push(alias.clone());
+        }
+    }
+
+    let mut properties = serde_json::Map::new();
+    for node in duplicates.iter().chain(std::iter::once(keep)) {
+        if let Value::Object(map) = &node.entity.properties {
+            properties.extend(map.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+
+    let mut sources: Vec<String> = Vec::new();
+    for node in std::iter::once(keep).chain(duplicates) {
+        let own = (node.sources.is_empty() && !node.entity.source.is_empty())
+            .then(|| node.entity.source.clone());
+        for source in node.sources.iter().cloned().chain(own) {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+    }
+
+    let mut identifiers = keep.entity.identifiers.clone();
+    for node in duplicates {
+        for (scheme, value) in &node.entity.identifiers {
+            identifiers
+                .entry(scheme.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+
+    let all = || std::iter::once(keep).chain(duplicates).map(|n| &n.entity);
+    MergedFields {
+        aliases,
+        properties: Value::Object(properties),
+        sources,
+        identifiers,
+        confidence: all()
+            .map(|e| e.confidence)
+            .fold(keep.entity.confidence, f64::max),
+        first_seen: all()
+            .map(|e| e.first_seen)
+            .min()
+            .unwrap_or(keep.entity.first_seen),
+        observed_at: all().filter_map(|e| e.observed_at).min(),
+    }
+}
+
+async fn read_nodes(txn: &mut Txn, ids: &[Uuid]) -> Result<Vec<MergeNode>> {
+    let cypher = format!(
+        "MATCH (n) WHERE n.id IN $ids AND {} RETURN n",
+        non_entity_filter()
+    );
+    let q = query(&cypher).param("ids", ids.iter().map(Uuid::to_string).collect::<Vec<_>>());
+    let mut stream = txn
+        .execute(q)
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to read entities to merge: {}", e)))?;
+
+    let mut nodes = Vec::new();
+    while let Ok(Some(row)) = stream.next(txn.handle()).await {
+        let node: Node = row
+            .get("n")
+            .map_err(|e| ArgusError::Graph(format!("Failed to deserialize node: {}", e)))?;
+        nodes.push(MergeNode {
+            entity: node_to_entity(&node)?,
+            sources: node.get("sources").unwrap_or_default(),
+        });
+    }
+    Ok(nodes)
+}
+
+/// Relationship types touching any of `ids`.
+async fn relationship_types(txn: &mut Txn, ids: &[String]) -> Result<Vec<String>> {
+    let q = query("MATCH (d)-[r]-() WHERE d.id IN $ids RETURN DISTINCT type(r) AS rel_type")
+        .param("ids", ids.to_vec());
+    let mut stream = txn
+        .execute(q)
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to read relationship types: {}", e)))?;
+    let mut types = Vec::new();
+    while let Ok(Some(row)) = stream.next(txn.handle()).await {
+        if let Ok(rel_type) = row.get::<String>("rel_type") {
+            types.push(rel_type);
+        }
+    }
+    Ok(types)
+}
+
+/// Copy `rel_type` relationships of the merged nodes onto `$keep`, in both
+/// directions. Edges between merged nodes and `keep` would become
+/// self-loops and are dropped with the nodes.
+fn repoint_cypher(rel_type: &str) -> [String; 2] {
+    let label = format!("`{}`", rel_type.replace('`', ""));
+    [
+        format!(
+            "MATCH (d)-[r:{label}]->(m) WHERE d.id IN $merged AND NOT m.id IN $merged AND m.id <> $keep \
+             MATCH (k {{id: $keep}}) \
+             CREATE (k)-[c:{label}]->(m) SET c = properties(r)"
+        ),
+        format!(
+            "MATCH (m)-[r:{label}]->(d) WHERE d.id IN $merged AND NOT m.id IN $merged AND m.id <> $keep \
+             MATCH (k {{id: $keep}}) \
+             CREATE (m)-[c:{label}]->(k) SET c = properties(r)"
+        ),
+    ]
+}
+
+const WRITE_KEEP_CYPHER: &str = "\
+    MATCH (k {id: $keep}) \
+    SET k.aliases = $aliases, k.properties = $properties, k.sources = $sources, \
+      k.identifiers = $identifiers, k.confidence = $confidence, k.first_seen = $first_seen, \
+      k.observed_at = CASE WHEN $observed_at = '' THEN k.observed_at ELSE $observed_at END";
+
+const MOVE_HISTORY_CYPHER: &str = "\
+    MATCH (c:EntityChange) WHERE c.entity_id IN $merged \
+    SET c.entity_id = $keep";
+
+const DELETE_MERGED_CYPHER: &str = "MATCH (d) WHERE d.id IN $merged DETACH DELETE d";
+
+async fn run(txn: &mut Txn, q: neo4rs::Query, what: &str) -> Result<()> {
+    txn.run(q)
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to {what}: {e}")))
+}
+
+/// Fold `duplicates` into `keep` in one transaction.
+pub(crate) async fn merge_entities(
+    graph: &Graph,
+    keep: Uuid,
+    duplicates: &[Uuid],
+) -> Result<Entity> {
+    let duplicates: Vec<Uuid> = {
+        let mut ids: Vec<Uuid> = duplicates
+            .iter()
+            .copied()
+            .filter(|id| *id != keep)
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    };
+    if duplicates.is_empty() {
+        return Err(ArgusError::Graph(
+            "Nothing to merge: no duplicates other than the kept entity".into(),
+        ));
+    }
+
+    let mut txn = timed(graph.start_txn())
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to start transaction: {}", e)))?;
+
+    let mut nodes = read_nodes(&mut txn, &[&[keep][..], &duplicates].concat()).await?;
+    let Some(keep_index) = nodes.iter().position(|n| n.entity.id == keep) else {
+        return Err(ArgusError::NotFound(format!("Entity {keep} not found")));
+    };
+    let keep_node = nodes.swap_remove(keep_index);
+    if let Some(missing) = duplicates
+        .iter()
+        .find(|id| !nodes.iter().any(|n| n.entity.id == **id))
+    {
+        return Err(ArgusError::NotFound(format!("Entity {missing} not found")));
+    }
+
+    let fields = merged_fields(&keep_node, &nodes);
+    let merged: Vec<String> = duplicates.iter().map(Uuid::to_string).collect();
+    let keep_id = keep.to_string();
+
+    for rel_type in relationship_types(&mut txn, &merged).await? {
+        for cypher in repoint_cypher(&rel_type) {
+            let q = query(&cypher)
+                .param("merged", merged.clone())
+                .param("keep", keep_id.clone());
+            run(&mut txn, q, "move relationships").await?;
+        }
+    }
+
+    let q = query(WRITE_KEEP_CYPHER)
+        .param("keep", keep_id.clone())
+        .param("aliases", serde_json::to_string(&fields.aliases)?)
+        .param("properties", serde_json::to_string(&fields.properties)?)
+        .param("sources", fields.sources.clone())
+        .param("identifiers", identifier_keys(&fields.identifiers))
+        .param("confidence", fields.confidence)
+        .param("first_seen", fields.first_seen.to_rfc3339())
+        .param(
+            "observed_at",
+            fields
+                .observed_at
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+        );
+    run(&mut txn, q, "update merged entity").await?;
+
+    let q = query(MOVE_HISTORY_CYPHER)
+        .param("merged", merged.clone())
+        .param("keep", keep_id.clone());
+    run(&mut txn, q, "move entity history").await?;
+
+    let now = Utc::now();
+    let changes: Vec<EntityChange> = nodes
+        .iter()
+        .map(|node| EntityChange {
+            id: Uuid::new_v4(),
+            entity_id: keep,
+            kind: ChangeKind::Merged,
+            field: Some("name".to_string()),
+            old_value: Some(Value::String(node.entity.name.clone())),
+            new_value: Some(Value::String(node.entity.id.to_string())),
+            source: MERGE_SOURCE.to_string(),
+            document_id: None,
+            changed_at: now,
+        })
+        .collect();
+    crate::history::write_changes(&mut txn, &changes).await?;
+
+    let q = query(DELETE_MERGED_CYPHER).param("merged", merged);
+    run(&mut txn, q, "delete merged entities").await?;
+
+    txn.commit()
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to commit transaction: {}", e)))?;
+
+    tracing::info!(keep = %keep, merged = duplicates.len(), "Merged duplicate entities");
+
+    let mut entity = keep_node.entity;
+    entity.aliases = fields.aliases;
+    entity.properties = fields.properties;
+    entity.identifiers = fields.identifiers;
+    entity.confidence = fields.confidence;
+    entity.first_seen = fields.first_seen;
+    entity.observed_at = fields.observed_at.or(entity.observed_at);
+    Ok(entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::entity::EntityType;
+    use serde_json::json;
+
+    fn node(name: &str, source: &str, properties: Value) -> MergeNode {
+        MergeNode {
+            entity: Entity {
+                id: Uuid::new_v4(),
+                entity_type: EntityType::Organization,
+                name: name.to_string(),
+                aliases: vec![],
+                properties,
+                source: source.to_string(),
+                source_id: None,
+                confidence: 0.8,
+                first_seen: Utc::now(),
+                last_seen: Utc::now(),
+                observed_at: None,
+                sanctions_exposure: None,
+                completeness: None,
+                mention_count: None,
+                geometry: None,
+                identifiers: Default::default(),
+            },
+            sources: vec![],
+        }
+    }
+
+    #[test]
+    fn merge_prefers_kept_values_and_unions_the_rest() {
+        let mut keep = node(
+            "Acme Ltd",
+            "opensanctions",
+            json!({"country": "GB", "sector": "mining"}),
+        );
+        keep.entity.aliases = vec!["ACME".to_string()];
+        keep.entity
+            .identifiers
+            .insert("lei".to_string(), "LEI-KEEP".to_string());
+        keep.sources = vec!["opensanctions".to_string(), "gdelt".to_string()];
+
+        let mut dup = node(
+            "Acme Limited",
+            "opencorporates",
+            json!({"country": "UK", "registered": "2001"}),
+        );
+        dup.entity.aliases = vec!["acme".to_string(), "Acme Ltd".to_string()];
+        dup.entity
+            .identifiers
+            .insert("lei".to_string(), "LEI-DUP".to_string());
+        dup.entity
+            .identifiers
+            .insert("opencorporates".to_string(), "gb/123".to_string());
+        dup.entity.confidence = 0.95;
+        dup.entity.first_seen = keep.entity.first_seen - chrono::Duration::days(3);
+        dup.entity.observed_at = Some(dup.entity.first_seen);
+
+        let fields = merged_fields(&keep, &[dup.clone()]);
+        assert_eq!(fields.aliases, vec!["ACME", "Acme Limited"]);
+        assert_eq!(
+            fields.properties,
+            json!({"country": "GB", "sector": "mining", "registered": "2001"})
+        );
+        assert_eq!(
+            fields.sources,
+            vec!["opensanctions", "gdelt", "opencorporates"]
+        );
+        assert_eq!(fields.identifiers["lei"], "LEI-KEEP");
+        assert_eq!(fields.identifiers["opencorporates"], "gb/123");
+        assert_eq!(fields.confidence, 0.95);
+        assert_eq!(fields.first_seen, dup.entity.first_seen);
+        assert_eq!(fields.observed_at, dup.entity.observed_at);
+    }
+
+    #[test]
+    fn repointing_escapes_relationship_types() {
+        let [outgoing, incoming] = repoint_cypher("OWNER_OF`) DETACH DELETE (x");
+        assert!(outgoing.contains("[r:`OWNER_OF) DETACH DELETE (x`]->(m)"));
+        assert!(incoming.contains("CREATE (m)-[c:`OWNER_OF) DETACH DELETE (x`]->(k)"));
+    }
+}
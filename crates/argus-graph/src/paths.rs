@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph, Node, Row};
+use uuid::Uuid;
+
+use argus_core::entity::Relationship;
+use argus_core::error::{ArgusError, Result};
+use argus_core::graph::GraphPath;
+
+use crate::store::{label_to_relation_type, node_to_entity, timed};
+
+/// Upper bound on hops for path queries; longer variable-length patterns get
+/// expensive quickly on dense graphs.
+pub(crate) const MAX_PATH_DEPTH: u32 = 6;
+
+/// All shortest paths between two entities, skipping paths through
+/// archived documents and, when `as_of` is set, through anything that did
+/// not exist at `$as_of`. Edge direction is ignored. The depth is
+/// interpolated because Cypher does not accept a parameter as a
+/// variable-length bound.
+fn paths_cypher(max_depth: u32, as_of: bool) -> String {
+    let depth = max_depth.clamp(1, MAX_PATH_DEPTH);
+    let as_of = if as_of {
+        format!(
+            " AND all(n IN nodes(p) WHERE {}) AND all(r IN relationships(p) WHERE {})",
+            crate::as_of::node_existed("n"),
+            crate::as_of::edge_existed("r")
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "MATCH (a {{id: $from}}), (b {{id: $to}}) \
+         MATCH p = allShortestPaths((a)-[*..{depth}]-(b)) \
+         WHERE none(n IN nodes(p) WHERE n:{document} OR n:{deleted}){as_of} \
+         RETURN nodes(p) AS nodes, \
+           [r IN relationships(p) | r.id] AS rel_ids, \
+           [r IN relationships(p) | type(r)] AS rel_types, \
+           [r IN relationships(p) | startNode(r).id] AS source_ids, \
+           [r IN relationships(p) | endNode(r).id] AS target_ids, \
+           [r IN relationships(p) | coalesce(r.properties, '{{}}')] AS properties, \
+           [r IN relationships(p) | coalesce(r.confidence, 1.0)] AS confidences, \
+           [r IN relationships(p) | coalesce(r.source, '')] AS sources, \
+           [r IN relationships(p) | coalesce(r.timestamp, '')] AS timestamps, \
+           [r IN relationships(p) | coalesce(r.strength, -1.0)] AS strengths \
+         LIMIT $limit",
+        document = crate::documents::DOCUMENT_LABEL,
+        deleted = crate::tombstones::DELETED_LABEL,
+    )
+}
+
+fn row_to_path(row: &Row) -> Result<GraphPath> {
+    let list = |key: &str| -> Result<Vec<String>> {
+        row.get(key)
+            .map_err(|e| ArgusError::Graph(format!("Missing {} on path: {}", key, e)))
+    };
+    let parse_uuid = |s: &str| {
+        Uuid::parse_str(s).map_err(|e| ArgusError::Graph(format!("Invalid UUID on path: {}", e)))
+    };
+
+    let nodes: Vec<Node> = row
+        .get("nodes")
+        .map_err(|e| ArgusError::Graph(format!("Missing nodes on path: {}", e)))?;
+    let entities = nodes
+        .iter()
+        .map(node_to_entity)
+        .collect::<Result<Vec<_>>>()?;
+
+    let ids = list("rel_ids")?;
+    let types = list("rel_types")?;
+    let source_ids = list("source_ids")?;
+    let target_ids = list("target_ids")?;
+    let properties = list("properties")?;
+    let sources = list("sources")?;
+    let timestamps = list("timestamps")?;
+    let confidences: Vec<f64> = row.get("confidences").unwrap_or_default();
+    // Unscored edges come back as -1.0
+    let strengths: Vec<f64> = row.get("strengths").unwrap_or_default();
+
+    let mut relationships = Vec::with_capacity(ids.len());
+    for (i, id) in ids.iter().enumerate() {
+        let at = |values: &[String]| values.get(i).cloned().unwrap_or_default();
+        relationships.push(Relationship {
+            id: parse_uuid(id)?,
+            source_entity_id: parse_uuid(&at(&source_ids))?,
+            target_entity_id: parse_uuid(&at(&target_ids))?,
+            relation_type: label_to_relation_type(&at(&types)),
+            properties: serde_json::from_str(&at(&properties))
+                .unwrap_or(serde_json::Value::Object(Default::default())),
+            confidence: confidences.get(i).copied().unwrap_or(1.0),
+            source: at(&sources),
+            timestamp: chrono::DateTime::parse_from_rfc3339(&at(&timestamps))
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .ok(),
+            strength: strengths.get(i).copied().filter(|s| *s >= 0.0),
+        });
+    }
+
+    Ok(GraphPath {
+        entities,
+        relationships,
+    })
+}
+
+pub(crate) async fn find_paths(
+    graph: &Graph,
+    from: Uuid,
+    to: Uuid,
+    max_depth: u32,
+    limit: usize,
+    as_of: Option<DateTime<Utc>>,
+) -> Result<Vec<GraphPath>> {
+    // allShortestPaths rejects identical endpoints
+    if from == to || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let q = query(&paths_cypher(max_depth, as_of.is_some()))
+        .param("from", from.to_string())
+        .param("to", to.to_string())
+        .param("as_of", as_of.map(crate::as_of::param).unwrap_or_default())
+        .param("limit", limit as i64);
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to find paths: {}", e)))?;
+
+    let mut paths = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        match row_to_path(&row) {
+            Ok(path) => paths.push(path),
+            Err(e) => tracing::warn!(error = %e, "Skipping unreadable path"),
+        }
+    }
+
+    tracing::debug!(%from, %to, paths = paths.len(), "Path search completed");
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_is_clamped_and_interpolated() {
+        assert!(paths_cypher(3, false).contains("[*..3]"));
+        assert!(paths_cypher(0, false).contains("[*..1]"));
+        assert!(paths_cypher(50, false).contains(&format!("[*..{MAX_PATH_DEPTH}]")));
+        assert!(paths_cypher(2, false).contains("coalesce(r.properties, '{}')"));
+    }
+
+    #[test]
+    fn as_of_restricts_every_node_and_edge() {
+        assert!(!paths_cypher(3, false).contains("$as_of"));
+        let cypher = paths_cypher(3, true);
+        assert!(cypher.contains("all(n IN nodes(p) WHERE n.first_seen <= $as_of"));
+        assert!(cypher.contains("all(r IN relationships(p) WHERE r.created_at <= $as_of"));
+    }
+}
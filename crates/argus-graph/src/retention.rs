@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph, Query};
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::retention::RetentionPolicy;
+
+use crate::history::CHANGE_LABEL;
+use crate::store::timed;
+
+const DELETE_HISTORY_CYPHER: &str =
+    "MATCH (c:EntityChange) WHERE c.entity_id IN $ids DETACH DELETE c";
+
+const DELETE_NODES_CYPHER: &str = "\
+    MATCH (n) WHERE n.id IN $ids \
+    WITH n, n.id AS id DETACH DELETE n \
+    RETURN count(id) AS cnt";
+
+/// When a node was observed: entities and positions carry `observed_at` or
+/// `last_seen`, archived documents `collected_at`.
+const AGE_FROM: &str = "coalesce(n.observed_at, n.last_seen, n.collected_at)";
+
+/// Match clause for the nodes `policy` covers that were last observed
+/// before `$before`. Policy labels are validated when parsed; the backticks
+/// keep them inert regardless.
+fn expired_match(policy: &RetentionPolicy) -> String {
+    let label = policy.label.replace('`', "");
+    let source = if policy.source.is_some() {
+        " AND toLower(n.source) = $source"
+    } else {
+        ""
+    };
+    format!("MATCH (n:`{label}`) WHERE {AGE_FROM} < $before{source}")
+}
+
+fn expired_query(cypher: &str, policy: &RetentionPolicy, before: DateTime<Utc>) -> Query {
+    query(cypher)
+        .param("before", before.to_rfc3339())
+        .param("source", policy.source.clone().unwrap_or_default())
+}
+
+pub(crate) async fn count_expired(
+    graph: &Graph,
+    policy: &RetentionPolicy,
+    before: DateTime<Utc>,
+) -> Result<u64> {
+    let cypher = format!("{} RETURN count(n) AS cnt", expired_match(policy));
+    let mut stream = timed(graph.execute(expired_query(&cypher, policy, before)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to count expired nodes: {}", e)))?;
+    Ok(match stream.next().await {
+        Ok(Some(row)) => row.get::<i64>("cnt").unwrap_or(0) as u64,
+        _ => 0,
+    })
+}
+
+pub(crate) async fn expired_nodes(
+    graph: &Graph,
+    policy: &RetentionPolicy,
+    before: DateTime<Utc>,
+    limit: usize,
+) -> Result<Vec<serde_json::Value>> {
+    let cypher = format!(
+        "{} RETURN properties(n) AS props ORDER BY {AGE_FROM} LIMIT $limit",
+        expired_match(policy)
+    );
+    let q = expired_query(&cypher, policy, before).param("limit", limit as i64);
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to read expired nodes: {}", e)))?;
+
+    let mut nodes = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        match row.get::<serde_json::Value>("props") {
+            Ok(props) => nodes.push(props),
+            Err(e) => tracing::warn!(error = %e, "Skipping unreadable expired node"),
+        }
+    }
+    Ok(nodes)
+}
+
+/// Delete nodes by id along with their relationships and, for entities,
+/// their change history.
+pub(crate) async fn delete_nodes(graph: &Graph, ids: &[String]) -> Result<u64> {
+    let mut txn = timed(graph.start_txn())
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to start transaction: {}", e)))?;
+
+    txn.run(query(DELETE_HISTORY_CYPHER).param("ids", ids.to_vec()))
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to delete {CHANGE_LABEL} nodes: {}", e)))?;
+    let mut stream = txn
+        .execute(query(DELETE_NODES_CYPHER).param("ids", ids.to_vec()))
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to delete expired nodes: {}", e)))?;
+    let deleted = match stream.next(txn.handle()).await {
+        Ok(Some(row)) => row.get::<i64>("cnt").unwrap_or(0) as u64,
+        _ => 0,
+    };
+
+    txn.commit()
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to commit deletion: {}", e)))?;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_match_filters_on_source_only_when_set() {
+        let positions: RetentionPolicy = "Position=90d".parse().unwrap();
+        assert_eq!(
+            expired_match(&positions),
+            "MATCH (n:`Position`) WHERE coalesce(n.observed_at, n.last_seen, n.collected_at) < $before"
+        );
+        let events: RetentionPolicy = "Event@gdelt=365d:archive".parse().unwrap();
+        assert!(expired_match(&events).ends_with(" AND toLower(n.source) = $source"));
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::graph::GraphWriteStats;
+
+/// Wait before the first retry; doubled for each one after.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+/// Longest wait between two attempts.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// The class of a Neo4j status code (`Neo.<class>.<category>.<title>`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Neo4jErrorClass {
+    /// Lock conflicts, deadlocks, leader switches: the server rolled the
+    /// transaction back and running it again may succeed.
+    Transient,
+    /// Invalid queries, constraint violations, terminated transactions.
+    Client,
+    Database,
+}
+
+/// The Neo4j status code quoted in an error message, such as
+/// `Neo.TransientError.Transaction.DeadlockDetected`.
+pub(crate) fn status_code(message: &str) -> Option<&str> {
+    let start = message.find("Neo.")?;
+    let code = &message[start..];
+    let end = code
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .unwrap_or(code.len());
+    Some(code[..end].trim_end_matches('.'))
+}
+
+pub(crate) fn classify(code: &str) -> Neo4jErrorClass {
+    match code.split('.').nth(1) {
+        // The server reports these two as transient, but retrying a
+        // transaction someone stopped only repeats the stop
+        Some("TransientError")
+            if !matches!(
+                code,
+                "Neo.TransientError.Transaction.LockClientStopped"
+                    | "Neo.TransientError.Transaction.Terminated"
+            ) =>
+        {
+            Neo4jErrorClass::Transient
+        }
+        Some("TransientError") | Some("ClientError") => Neo4jErrorClass::Client,
+        _ => Neo4jErrorClass::Database,
+    }
+}
+
+/// The transient status code `error` carries, if it is worth retrying.
+fn transient_code(error: &ArgusError) -> Option<String> {
+    let ArgusError::Graph(message) = error else {
+        return None;
+    };
+    status_code(message)
+        .filter(|code| classify(code) == Neo4jErrorClass::Transient)
+        .map(str::to_string)
+}
+
+/// Wait before retry number `attempt` (from 0): exponential backoff up to
+/// [`RETRY_MAX_DELAY`], plus up to one base delay of jitter so writers
+/// that deadlocked each other do not collide again.
+fn retry_delay(attempt: u32) -> Duration {
+    let jitter = std::collections::hash_map::RandomState::new().hash_one(attempt)
+        % RETRY_BASE_DELAY.as_millis() as u64;
+    RETRY_BASE_DELAY
+        .saturating_mul(1 << attempt.min(16))
+        .min(RETRY_MAX_DELAY)
+        .saturating_add(Duration::from_millis(jitter))
+}
+
+/// Retries write transactions Neo4j failed transiently and counts how
+/// that went.
+///
+/// A transient failure means the server rolled the whole transaction back,
+/// so the transaction is run again from the start; the MERGE-based writes
+/// make that the same as running it once.
+pub(crate) struct WriteRetry {
+    max_retries: u32,
+    transactions: AtomicU64,
+    retries: AtomicU64,
+    recovered: AtomicU64,
+    exhausted: AtomicU64,
+    failed: AtomicU64,
+    transient_errors: Mutex<BTreeMap<String, u64>>,
+}
+
+impl WriteRetry {
+    pub(crate) fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            transactions: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            recovered: AtomicU64::new(0),
+            exhausted: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            transient_errors: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Run the transaction `attempt` builds, again after each transient
+    /// failure, up to `max_retries` times.
+    pub(crate) async fn run<T, F, Fut>(&self, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.transactions.fetch_add(1, Ordering::Relaxed);
+        let mut retries = 0;
+        loop {
+            let error = match attempt().await {
+                Ok(value) => {
+                    if retries > 0 {
+                        self.recovered.fetch_add(1, Ordering::Relaxed);
+                    }
+                    return Ok(value);
+                }
+                Err(e) => e,
+            };
+            let Some(code) = transient_code(&error) else {
+                self.failed.fetch_add(1, Ordering::Relaxed);
+                return Err(error);
+            };
+            if let Ok(mut counts) = self.transient_errors.lock() {
+                *counts.entry(code.clone()).or_insert(0) += 1;
+            }
+            if retries >= self.max_retries {
+                self.exhausted.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(code = %code, retries, "Graph write still failing transiently, giving up");
+                return Err(error);
+            }
+
+            let delay = retry_delay(retries);
+            tracing::warn!(
+                code = %code,
+                attempt = retries + 1,
+                delay_ms = delay.as_millis() as u64,
+                "Graph write failed transiently, retrying"
+            );
+            self.retries.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(delay).await;
+            retries += 1;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> GraphWriteStats {
+        GraphWriteStats {
+            transactions: self.transactions.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            recovered: self.recovered.load(Ordering::Relaxed),
+            exhausted: self.exhausted.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            transient_errors: self
+                .transient_errors
+                .lock()
+                .map(|counts| counts.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_codes_are_read_from_messages_and_classified() {
+        let deadlock = "Failed to merge entity: Neo4j error `Neo.TransientError.Transaction.DeadlockDetected`: \
+                        ForsetiClient[1] can't acquire ExclusiveLock";
+        let code = status_code(deadlock).unwrap();
+        assert_eq!(code, "Neo.TransientError.Transaction.DeadlockDetected");
+        assert_eq!(classify(code), Neo4jErrorClass::Transient);
+
+        assert_eq!(
+            classify("Neo.ClientError.Schema.ConstraintValidationFailed"),
+            Neo4jErrorClass::Client
+        );
+        assert_eq!(
+            classify("Neo.TransientError.Transaction.Terminated"),
+            Neo4jErrorClass::Client
+        );
+        assert_eq!(
+            classify("Neo.DatabaseError.General.UnknownError"),
+            Neo4jErrorClass::Database
+        );
+        assert_eq!(status_code("Neo4j operation timed out after 5s"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transient_failures_are_retried_and_counted() {
+        let retry = WriteRetry::new(2);
+        let lock_error = || {
+            ArgusError::Graph(
+                "Neo4j error `Neo.TransientError.Transaction.LockAcquisitionTimeout`: busy".into(),
+            )
+        };
+
+        let mut calls = 0;
+        let stored = retry
+            .run(|| {
+                calls += 1;
+                let outcome = if calls < 3 {
+                    Err(lock_error())
+                } else {
+                    Ok(calls)
+                };
+                async move { outcome }
+            })
+            .await;
+        assert_eq!(stored.unwrap(), 3);
+
+        let exhausted: Result<()> = retry.run(|| async { Err(lock_error()) }).await;
+        assert!(exhausted.is_err());
+        let invalid: Result<()> = retry
+            .run(|| async {
+                Err(ArgusError::Graph(
+                    "Neo4j error `Neo.ClientError.Statement.SyntaxError`: x".into(),
+                ))
+            })
+            .await;
+        assert!(invalid.is_err());
+
+        let stats = retry.stats();
+        assert_eq!(
+            (stats.transactions, stats.retries, stats.recovered),
+            (3, 4, 1)
+        );
+        assert_eq!((stats.exhausted, stats.failed), (1, 1));
+        assert_eq!(
+            stats.transient_errors["Neo.TransientError.Transaction.LockAcquisitionTimeout"],
+            5
+        );
+    }
+}
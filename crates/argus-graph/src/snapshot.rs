@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, HashMap};
+
+use futures::stream::BoxStream;
+use neo4rs::{query, BoltType, Graph, Row};
+
+use argus_core::backup::{is_restorable_label, NodeDump, RelationshipDump};
+use argus_core::error::{ArgusError, Result};
+
+use crate::store::timed;
+
+/// Extra label on nodes written by a restore until it finishes, so
+/// relationships can find their endpoints by snapshot reference.
+const RESTORING_LABEL: &str = "SnapshotRestoring";
+
+/// Property holding a restored node's snapshot reference.
+const REF_PROPERTY: &str = "_snapshot_ref";
+
+/// Nodes deleted or cleaned up per statement, keeping transactions small.
+const CLEANUP_BATCH_SIZE: i64 = 10_000;
+
+const NODES_CYPHER: &str = "\
+    MATCH (n) RETURN elementId(n) AS ref, labels(n) AS labels, properties(n) AS props";
+
+const RELATIONSHIPS_CYPHER: &str = "\
+    MATCH (a)-[r]->(b) \
+    RETURN elementId(r) AS ref, type(r) AS rel_type, elementId(a) AS start, elementId(b) AS end, properties(r) AS props";
+
+const WIPE_CYPHER: &str = "MATCH (n) WITH n LIMIT $batch DETACH DELETE n RETURN count(*) AS cnt";
+
+async fn run_batches(graph: &Graph, cypher: &str, what: &str) -> Result<()> {
+    let failed = |e: &dyn std::fmt::Display| ArgusError::Graph(format!("Failed to {what}: {e}"));
+    loop {
+        let mut stream = timed(graph.execute(query(cypher).param("batch", CLEANUP_BATCH_SIZE)))
+            .await?
+            .map_err(|e| failed(&e))?;
+        let row = timed(stream.next()).await?.map_err(|e| failed(&e))?;
+        let count = match row {
+            Some(row) => row.get::<i64>("cnt").map_err(|e| failed(&e))?,
+            None => 0,
+        };
+        if count == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Every row of `cypher`, read through one server-side cursor that fetches
+/// a batch per round trip. Each fetch is timed on its own, so a large graph
+/// is not cut off by the timeout of a single query; the stream ends after
+/// the first error.
+async fn cursor<T: Send + 'static>(
+    graph: &Graph,
+    cypher: &'static str,
+    what: &'static str,
+    decode: fn(Row) -> Result<T>,
+) -> Result<BoxStream<'static, Result<T>>> {
+    let stream = timed(graph.execute(query(cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to read {what}: {e}")))?;
+
+    let rows = futures::stream::unfold(Some(stream), move |stream| async move {
+        let mut stream = stream?;
+        let row = match timed(stream.next()).await {
+            Ok(Ok(Some(row))) => row,
+            Ok(Ok(None)) => return None,
+            Ok(Err(e)) => {
+                return Some((
+                    Err(ArgusError::Graph(format!("Failed to read {what}: {e}"))),
+                    None,
+                ))
+            }
+            Err(e) => return Some((Err(e), None)),
+        };
+        match decode(row) {
+            Ok(item) => Some((Ok(item), Some(stream))),
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+    Ok(Box::pin(rows))
+}
+
+fn node(row: Row) -> Result<NodeDump> {
+    Ok(NodeDump {
+        node_ref: row
+            .get("ref")
+            .map_err(|e| ArgusError::Graph(format!("Failed to read node reference: {e}")))?,
+        labels: row
+            .get("labels")
+            .map_err(|e| ArgusError::Graph(format!("Failed to read node labels: {e}")))?,
+        properties: row
+            .get("props")
+            .map_err(|e| ArgusError::Graph(format!("Failed to read node properties: {e}")))?,
+    })
+}
+
+fn relationship(row: Row) -> Result<RelationshipDump> {
+    let read = |key: &str| {
+        row.get::<String>(key)
+            .map_err(|e| ArgusError::Graph(format!("Failed to read relationship {key}: {e}")))
+    };
+    Ok(RelationshipDump {
+        rel_ref: read("ref")?,
+        rel_type: read("rel_type")?,
+        start: read("start")?,
+        end: read("end")?,
+        properties: row.get("props").map_err(|e| {
+            ArgusError::Graph(format!("Failed to read relationship properties: {e}"))
+        })?,
+    })
+}
+
+pub(crate) async fn nodes(graph: &Graph) -> Result<BoxStream<'static, Result<NodeDump>>> {
+    cursor(graph, NODES_CYPHER, "nodes", node).await
+}
+
+pub(crate) async fn relationships(
+    graph: &Graph,
+) -> Result<BoxStream<'static, Result<RelationshipDump>>> {
+    cursor(graph, RELATIONSHIPS_CYPHER, "relationships", relationship).await
+}
+
+/// Delete every node, then index the restore bookkeeping.
+pub(crate) async fn begin_restore(graph: &Graph) -> Result<()> {
+    run_batches(graph, WIPE_CYPHER, "clear the graph").await?;
+    let cypher = format!("CREATE INDEX snapshot_restore_ref IF NOT EXISTS FOR (n:{RESTORING_LABEL}) ON (n.{REF_PROPERTY})");
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create restore index: {}", e)))?;
+    // Relationship lookups must not fall back to label scans
+    timed(graph.run(query("CALL db.awaitIndexes(300)")))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed waiting for restore index: {}", e)))
+}
+
+/// Label clause for a node restored with `labels`. Labels are checked so a
+/// tampered snapshot can't inject Cypher.
+fn restore_labels(labels: &[String]) -> Result<String> {
+    let mut clause = String::new();
+    for label in labels {
+        if !is_restorable_label(label) {
+            return Err(ArgusError::Graph(format!(
+                "Refusing to restore node label {label:?}"
+            )));
+        }
+        clause.push_str(&format!(":`{label}`"));
+    }
+    clause.push_str(&format!(":{RESTORING_LABEL}"));
+    Ok(clause)
+}
+
+pub(crate) async fn restore_nodes(graph: &Graph, nodes: &[NodeDump]) -> Result<()> {
+    let mut by_labels: BTreeMap<Vec<String>, Vec<HashMap<String, BoltType>>> = BTreeMap::new();
+    for node in nodes {
+        let mut labels = node.labels.clone();
+        labels.sort();
+        by_labels.entry(labels).or_default().push(HashMap::from([
+            ("ref".to_string(), node.node_ref.clone().into()),
+            ("props".to_string(), json_to_bolt(&node.properties)),
+        ]));
+    }
+
+    for (labels, rows) in by_labels {
+        let cypher = format!(
+            "UNWIND $rows AS row CREATE (n{}) SET n = row.props, n.{REF_PROPERTY} = row.ref",
+            restore_labels(&labels)?
+        );
+        timed(graph.run(query(&cypher).param("rows", rows)))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to restore nodes: {}", e)))?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn restore_relationships(
+    graph: &Graph,
+    relationships: &[RelationshipDump],
+) -> Result<u64> {
+    let mut by_type: BTreeMap<&str, Vec<HashMap<String, BoltType>>> = BTreeMap::new();
+    for rel in relationships {
+        by_type
+            .entry(rel.rel_type.as_str())
+            .or_default()
+            .push(HashMap::from([
+                ("start".to_string(), rel.start.clone().into()),
+                ("end".to_string(), rel.end.clone().into()),
+                ("props".to_string(), json_to_bolt(&rel.properties)),
+            ]));
+    }
+
+    let mut restored = 0;
+    for (rel_type, rows) in by_type {
+        if !is_restorable_label(rel_type) {
+            return Err(ArgusError::Graph(format!(
+                "Refusing to restore relationship type {rel_type:?}"
+            )));
+        }
+        let cypher = format!(
+            "UNWIND $rows AS row \
+             MATCH (a:{RESTORING_LABEL} {{{REF_PROPERTY}: row.start}}) \
+             MATCH (b:{RESTORING_LABEL} {{{REF_PROPERTY}: row.end}}) \
+             CREATE (a)-[r:`{rel_type}`]->(b) SET r = row.props \
+             RETURN count(r) AS cnt"
+        );
+        let mut stream = timed(graph.execute(query(&cypher).param("rows", rows)))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to restore relationships: {}", e)))?;
+        let failed = |e: &dyn std::fmt::Display| {
+            ArgusError::Graph(format!("Failed to restore relationships: {e}"))
+        };
+        if let Some(row) = stream.next().await.map_err(|e| failed(&e))? {
+            restored += row.get::<i64>("cnt").map_err(|e| failed(&e))? as u64;
+        }
+    }
+    Ok(restored)
+}
+
+/// Strip the restore label and references, and drop their index.
+pub(crate) async fn finish_restore(graph: &Graph) -> Result<()> {
+    let cypher = format!(
+        "MATCH (n:{RESTORING_LABEL}) WITH n LIMIT $batch \
+         REMOVE n:{RESTORING_LABEL}, n.{REF_PROPERTY} RETURN count(*) AS cnt"
+    );
+    run_batches(graph, &cypher, "finish restore").await?;
+    timed(graph.run(query("DROP INDEX snapshot_restore_ref IF EXISTS")))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to drop restore index: {}", e)))
+}
+
+/// Property values as read back by `properties(n)`: scalars and lists.
+fn json_to_bolt(value: &serde_json::Value) -> BoltType {
+    use serde_json::Value;
+    match value {
+        Value::Null => None::<String>.into(),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        Value::String(s) => s.clone().into(),
+        Value::Array(items) => items.iter().map(json_to_bolt).collect::<Vec<_>>().into(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| (k.clone(), json_to_bolt(v)))
+            .collect::<HashMap<_, _>>()
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_labels_are_checked_and_tagged() {
+        let labels = vec!["Organization".to_string(), "Deleted".to_string()];
+        assert_eq!(
+            restore_labels(&labels).unwrap(),
+            ":`Organization`:`Deleted`:SnapshotRestoring"
+        );
+        assert_eq!(restore_labels(&[]).unwrap(), ":SnapshotRestoring");
+        assert!(restore_labels(&["A`) DETACH DELETE (x".to_string()]).is_err());
+    }
+
+    #[test]
+    fn snapshots_stream_each_query_once_without_sorting() {
+        for cypher in [NODES_CYPHER, RELATIONSHIPS_CYPHER] {
+            assert!(!cypher.contains("ORDER BY"), "{cypher}");
+            assert!(
+                !cypher.contains("$after") && !cypher.contains("LIMIT"),
+                "{cypher}"
+            );
+        }
+    }
+}
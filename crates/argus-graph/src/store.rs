@@ -1,69 +1,219 @@
 use async_trait::async_trait;
-use neo4rs::{query, Graph, Node};
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use neo4rs::{query, Graph, Node, Query, Row, Txn};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::future::Future;
 use uuid::Uuid;
 
+use argus_core::backup::{NodeDump, RelationshipDump};
 use argus_core::config::AppConfig;
-use argus_core::entity::{Entity, EntityType, ExtractionResult, RelationType, Relationship};
+use argus_core::density::{DensityQuery, EventDensity};
+use argus_core::document::{
+    ArchivedDocument, DocumentSearchHit, DocumentSearchQuery, RunDocumentSample, RunExtractionStats,
+};
+use argus_core::entity::{
+    normalize_identifier_scheme, normalize_type_name, Direction, Entity, EntityType,
+    ExtractionResult, RelationType, Relationship, EVENT_KEY_PREFIX,
+};
 use argus_core::error::{ArgusError, Result};
-use argus_core::graph::{GraphNeighbors, GraphQuery, GraphStore};
+use argus_core::graph::{
+    EntityBatchItem, EntitySearchFilter, EntitySort, GraphNeighbors, GraphPath, GraphQuery,
+    GraphStore, GraphWriteStats, NeighborFilter, RelationIdentity, RelationshipFilter,
+    RelationshipSearchHit, RelationshipSummary, RowStream,
+};
+use argus_core::history::{EntityChange, Tombstone};
+use argus_core::inference::{InferenceReport, InferenceRule};
+use argus_core::integrity::{IntegrityCheck, IntegrityFinding};
+use argus_core::retention::RetentionPolicy;
+use argus_core::tracks::PositionReport;
+
+use crate::retry::WriteRetry;
 
 /// Timeout for all Neo4j operations (seconds).
 const NEO4J_TIMEOUT_SECS: u64 = 5;
+/// Most entities one identifier lookup returns; more than a handful means
+/// the identifier is not unique to begin with.
+const MAX_IDENTIFIER_MATCHES: usize = 100;
 
 pub struct Neo4jGraphStore {
     graph: Option<Graph>,
+    /// Edge identity per relation type name, from `RELATIONSHIP_IDENTITY`.
+    relationship_identity: BTreeMap<String, RelationIdentity>,
+    /// Retries extraction writes Neo4j failed transiently (`NEO4J_MAX_RETRIES`).
+    write_retry: WriteRetry,
 }
 
 impl Neo4jGraphStore {
     pub async fn new(config: &AppConfig) -> Self {
-        match Graph::new(&config.neo4j_uri, &config.neo4j_user, &config.neo4j_password).await {
+        match Graph::new(
+            &config.neo4j_uri,
+            &config.neo4j_user,
+            &config.neo4j_password,
+        )
+        .await
+        {
             Ok(graph) => {
                 tracing::info!(uri = %config.neo4j_uri, "Connected to Neo4j");
-                Self { graph: Some(graph) }
+                if let Err(e) = crate::documents::ensure_schema(&graph).await {
+                    tracing::warn!(error = %e, "Failed to ensure document search index");
+                }
+                if let Err(e) = crate::history::ensure_schema(&graph).await {
+                    tracing::warn!(error = %e, "Failed to ensure entity change index");
+                }
+                if let Err(e) = crate::tracks::ensure_schema(&graph).await {
+                    tracing::warn!(error = %e, "Failed to ensure position indexes");
+                }
+                if let Err(e) = crate::tombstones::ensure_schema(&graph).await {
+                    tracing::warn!(error = %e, "Failed to ensure tombstone indexes");
+                }
+                if let Err(e) = crate::exposure::ensure_schema(&graph).await {
+                    tracing::warn!(error = %e, "Failed to ensure sanctions exposure indexes");
+                }
+                Self {
+                    graph: Some(graph),
+                    relationship_identity: config.relationship_identity.clone(),
+                    write_retry: WriteRetry::new(config.neo4j_max_retries),
+                }
             }
             Err(e) => {
                 tracing::warn!(uri = %config.neo4j_uri, error = %e, "Failed to connect to Neo4j — running in degraded mode");
-                Self { graph: None }
+                Self {
+                    graph: None,
+                    relationship_identity: config.relationship_identity.clone(),
+                    write_retry: WriteRetry::new(config.neo4j_max_retries),
+                }
             }
         }
     }
 
     fn graph(&self) -> Result<&Graph> {
-        self.graph.as_ref().ok_or_else(|| ArgusError::Graph("Neo4j not connected".into()))
+        self.graph
+            .as_ref()
+            .ok_or_else(|| ArgusError::Graph("Neo4j not connected".into()))
     }
 
     pub fn is_connected(&self) -> bool {
         self.graph.is_some()
     }
 
+    /// Extraction write transactions since startup and their retries.
+    pub fn write_stats(&self) -> GraphWriteStats {
+        self.write_retry.stats()
+    }
+
+    /// Write `results` in one transaction, rolled back if any write fails.
+    async fn write_extractions(&self, results: &[ExtractionResult]) -> Result<()> {
+        let mut txn = timed(self.graph()?.start_txn())
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to start transaction: {}", e)))?;
+
+        for result in results {
+            if let Err(e) = write_extraction(&mut txn, result, &self.relationship_identity).await {
+                // Best effort: after a server error the transaction is gone already
+                let _ = txn.rollback().await;
+                return Err(e);
+            }
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| ArgusError::Graph(format!("Failed to commit transaction: {}", e)))
+    }
+
+    /// The entity, if it already existed at `as_of`. Its fields are as they
+    /// are now; only its existence is historical.
+    async fn get_entity_as_of(&self, id: Uuid, as_of: DateTime<Utc>) -> Result<Option<Entity>> {
+        let cypher = format!(
+            "MATCH (n {{id: $id}}) WHERE {} RETURN n",
+            crate::as_of::node_existed("n")
+        );
+        let q = query(&cypher)
+            .param("id", id.to_string())
+            .param("as_of", crate::as_of::param(as_of));
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to query entity: {}", e)))?;
+
+        match stream.next().await {
+            Ok(Some(row)) => {
+                let node: Node = row
+                    .get("n")
+                    .map_err(|e| ArgusError::Graph(format!("Failed to deserialize node: {}", e)))?;
+                Ok(Some(node_to_entity(&node)?))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(ArgusError::Graph(format!("Error fetching entity: {}", e))),
+        }
+    }
 }
 
 /// Wrap any async operation with a timeout, converting timeout to ArgusError::Graph.
-async fn timed<T, F: Future<Output = T>>(op: F) -> std::result::Result<T, ArgusError> {
-    tokio::time::timeout(std::time::Duration::from_secs(NEO4J_TIMEOUT_SECS), op)
-        .await
-        .map_err(|_| {
-            tracing::warn!("Neo4j operation timed out after {}s", NEO4J_TIMEOUT_SECS);
-            ArgusError::Graph(format!("Neo4j operation timed out after {}s", NEO4J_TIMEOUT_SECS))
-        })
+/// The operation runs in a `neo4j` span named after the calling line, so
+/// traces show which query a slow round trip was.
+#[track_caller]
+pub(crate) fn timed<T, F: Future<Output = T>>(
+    op: F,
+) -> impl Future<Output = std::result::Result<T, ArgusError>> {
+    let caller = std::panic::Location::caller();
+    let span = tracing::info_span!(
+        "neo4j",
+        otel.kind = "client",
+        db.system = "neo4j",
+        code.filepath = caller.file(),
+        code.lineno = caller.line(),
+    );
+    tracing::Instrument::instrument(
+        async {
+            tokio::time::timeout(std::time::Duration::from_secs(NEO4J_TIMEOUT_SECS), op)
+                .await
+                .map_err(|_| {
+                    tracing::warn!("Neo4j operation timed out after {}s", NEO4J_TIMEOUT_SECS);
+                    ArgusError::Graph(format!(
+                        "Neo4j operation timed out after {}s",
+                        NEO4J_TIMEOUT_SECS
+                    ))
+                })
+        },
+        span,
+    )
 }
 
-fn entity_type_to_label(et: &EntityType) -> &'static str {
+pub(crate) fn entity_type_to_label(et: &EntityType) -> Cow<'static, str> {
     match et {
-        EntityType::Person => "Person",
-        EntityType::Organization => "Organization",
-        EntityType::Vessel => "Vessel",
-        EntityType::Aircraft => "Aircraft",
-        EntityType::Location => "Location",
-        EntityType::Event => "Event",
-        EntityType::Document => "Document",
-        EntityType::Transaction => "Transaction",
-        EntityType::Sanction => "Sanction",
+        EntityType::Person => "Person".into(),
+        EntityType::Organization => "Organization".into(),
+        EntityType::Vessel => "Vessel".into(),
+        EntityType::Aircraft => "Aircraft".into(),
+        EntityType::Location => "Location".into(),
+        EntityType::Event => "Event".into(),
+        EntityType::Document => "Document".into(),
+        EntityType::Transaction => "Transaction".into(),
+        EntityType::Sanction => "Sanction".into(),
+        // Labels are interpolated into Cypher, so re-normalize custom names
+        // before turning bank_account into BankAccount.
+        EntityType::Custom(name) => {
+            let label: String = normalize_type_name(name)
+                .split('_')
+                .map(|part| {
+                    let mut chars = part.chars();
+                    match chars.next() {
+                        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                        None => String::new(),
+                    }
+                })
+                .collect();
+            if label.is_empty() || label.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("Custom{label}").into()
+            } else {
+                label.into()
+            }
+        }
     }
 }
 
-fn label_to_entity_type(label: &str) -> EntityType {
+pub(crate) fn label_to_entity_type(label: &str) -> EntityType {
     match label {
         "Person" => EntityType::Person,
         "Organization" => EntityType::Organization,
@@ -74,51 +224,48 @@ fn label_to_entity_type(label: &str) -> EntityType {
         "Document" => EntityType::Document,
         "Transaction" => EntityType::Transaction,
         "Sanction" => EntityType::Sanction,
-        _ => EntityType::Event,
+        other => EntityType::from_name(other),
     }
 }
 
-fn relation_type_to_label(rt: &RelationType) -> &'static str {
+pub(crate) fn relation_type_to_label(rt: &RelationType) -> Cow<'static, str> {
     match rt {
-        RelationType::OwnerOf => "OWNER_OF",
-        RelationType::DirectorOf => "DIRECTOR_OF",
-        RelationType::EmployeeOf => "EMPLOYEE_OF",
-        RelationType::RelatedTo => "RELATED_TO",
-        RelationType::LocatedAt => "LOCATED_AT",
-        RelationType::TransactedWith => "TRANSACTED_WITH",
-        RelationType::SanctionedBy => "SANCTIONED_BY",
-        RelationType::RegisteredIn => "REGISTERED_IN",
-        RelationType::FlaggedAs => "FLAGGED_AS",
-        RelationType::MeetingWith => "MEETING_WITH",
-        RelationType::TraveledTo => "TRAVELED_TO",
-        RelationType::PartOf => "PART_OF",
+        RelationType::OwnerOf => "OWNER_OF".into(),
+        RelationType::DirectorOf => "DIRECTOR_OF".into(),
+        RelationType::EmployeeOf => "EMPLOYEE_OF".into(),
+        RelationType::RelatedTo => "RELATED_TO".into(),
+        RelationType::LocatedAt => "LOCATED_AT".into(),
+        RelationType::TransactedWith => "TRANSACTED_WITH".into(),
+        RelationType::SanctionedBy => "SANCTIONED_BY".into(),
+        RelationType::RegisteredIn => "REGISTERED_IN".into(),
+        RelationType::FlaggedAs => "FLAGGED_AS".into(),
+        RelationType::MeetingWith => "MEETING_WITH".into(),
+        RelationType::TraveledTo => "TRAVELED_TO".into(),
+        RelationType::PartOf => "PART_OF".into(),
+        RelationType::Custom(name) => {
+            let label = normalize_type_name(name).to_ascii_uppercase();
+            if label.is_empty() || label.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("CUSTOM_{label}")
+                    .trim_end_matches('_')
+                    .to_string()
+                    .into()
+            } else {
+                label.into()
+            }
+        }
     }
 }
 
-fn label_to_relation_type(label: &str) -> RelationType {
-    match label {
-        "OWNER_OF" => RelationType::OwnerOf,
-        "DIRECTOR_OF" => RelationType::DirectorOf,
-        "EMPLOYEE_OF" => RelationType::EmployeeOf,
-        "RELATED_TO" => RelationType::RelatedTo,
-        "LOCATED_AT" => RelationType::LocatedAt,
-        "TRANSACTED_WITH" => RelationType::TransactedWith,
-        "SANCTIONED_BY" => RelationType::SanctionedBy,
-        "REGISTERED_IN" => RelationType::RegisteredIn,
-        "FLAGGED_AS" => RelationType::FlaggedAs,
-        "MEETING_WITH" => RelationType::MeetingWith,
-        "TRAVELED_TO" => RelationType::TraveledTo,
-        "PART_OF" => RelationType::PartOf,
-        _ => RelationType::RelatedTo,
-    }
+pub(crate) fn label_to_relation_type(label: &str) -> RelationType {
+    RelationType::from_name(label)
 }
 
-fn node_to_entity(node: &Node) -> Result<Entity> {
+pub(crate) fn node_to_entity(node: &Node) -> Result<Entity> {
     let id_str: String = node
         .get("id")
         .map_err(|e| ArgusError::Graph(format!("Missing id on node: {}", e)))?;
-    let id = Uuid::parse_str(&id_str)
-        .map_err(|e| ArgusError::Graph(format!("Invalid UUID: {}", e)))?;
+    let id =
+        Uuid::parse_str(&id_str).map_err(|e| ArgusError::Graph(format!("Invalid UUID: {}", e)))?;
 
     let labels = node.labels();
     let entity_type = labels
@@ -131,12 +278,11 @@ fn node_to_entity(node: &Node) -> Result<Entity> {
         .map_err(|e| ArgusError::Graph(format!("Missing name on node: {}", e)))?;
 
     let aliases_json: String = node.get("aliases").unwrap_or_else(|_| "[]".to_string());
-    let aliases: Vec<String> =
-        serde_json::from_str(&aliases_json).unwrap_or_default();
+    let aliases: Vec<String> = serde_json::from_str(&aliases_json).unwrap_or_default();
 
     let properties_json: String = node.get("properties").unwrap_or_else(|_| "{}".to_string());
-    let properties: serde_json::Value =
-        serde_json::from_str(&properties_json).unwrap_or(serde_json::Value::Object(Default::default()));
+    let properties: serde_json::Value = serde_json::from_str(&properties_json)
+        .unwrap_or(serde_json::Value::Object(Default::default()));
 
     let source: String = node.get("source").unwrap_or_else(|_| String::new());
     let source_id: Option<String> = node.get("source_id").ok();
@@ -156,6 +302,28 @@ fn node_to_entity(node: &Node) -> Result<Entity> {
         .map(|dt| dt.with_timezone(&chrono::Utc))
         .unwrap_or_else(|_| chrono::Utc::now());
 
+    let observed_at = node
+        .get::<String>("observed_at")
+        .ok()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&chrono::Utc));
+
+    let sanctions_exposure: Option<f64> = node.get("sanctions_exposure").ok();
+    let completeness: Option<f64> = node.get("completeness").ok();
+    let mention_count = node
+        .get::<i64>("mention_count")
+        .ok()
+        .map(|count| count.max(0) as u64);
+    let geometry = node
+        .get::<String>("geometry")
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
+
+    let identifiers = node
+        .get::<Vec<String>>("identifiers")
+        .map(|keys| parse_identifier_keys(&keys))
+        .unwrap_or_default();
+
     Ok(Entity {
         id,
         entity_type,
@@ -167,168 +335,590 @@ fn node_to_entity(node: &Node) -> Result<Entity> {
         confidence,
         first_seen,
         last_seen,
+        observed_at,
+        sanctions_exposure,
+        completeness,
+        mention_count,
+        geometry,
+        identifiers,
     })
 }
 
-#[async_trait]
-impl GraphStore for Neo4jGraphStore {
-    async fn store_extraction(&self, result: &ExtractionResult) -> Result<()> {
-        let mut txn = timed(self.graph()?.start_txn())
-            .await?
-            .map_err(|e| ArgusError::Graph(format!("Failed to start transaction: {}", e)))?;
+/// Entity identifiers as stored on nodes: `scheme:value` strings, so one
+/// list membership test finds a node by any of them.
+fn identifier_key(scheme: &str, value: &str) -> String {
+    format!("{scheme}:{value}")
+}
 
-        for entity in &result.entities {
-            let label = entity_type_to_label(&entity.entity_type);
-            let aliases_json = serde_json::to_string(&entity.aliases)
-                .map_err(|e| ArgusError::Graph(format!("Failed to serialize aliases: {}", e)))?;
-            let properties_json = serde_json::to_string(&entity.properties)
-                .map_err(|e| ArgusError::Graph(format!("Failed to serialize properties: {}", e)))?;
-
-            // Cross-source entity resolution: first check if an entity with the
-            // same name (case-insensitive) and type already exists from any source.
-            // If found, merge onto that node and accumulate sources.
-            // Otherwise, MERGE on (source, source_id) or (id) as before.
-            let cypher = if entity.source_id.is_some() {
-                format!(
-                    "OPTIONAL MATCH (existing:{label} \
-                       WHERE toLower(existing.name) = toLower($name) \
-                       AND existing.source <> $source) \
-                     WITH existing \
-                     FOREACH (_ IN CASE WHEN existing IS NOT NULL THEN [1] ELSE [] END | \
-                       SET existing.sources = CASE \
-                         WHEN existing.sources IS NULL THEN [$source] \
-                         WHEN NOT $source IN existing.sources THEN existing.sources + $source \
-                         ELSE existing.sources END, \
-                       existing.aliases = $aliases, \
-                       existing.properties = $properties, \
-                       existing.confidence = CASE WHEN $confidence > existing.confidence THEN $confidence ELSE existing.confidence END, \
-                       existing.last_seen = $last_seen \
-                     ) \
-                     WITH existing \
-                     FOREACH (_ IN CASE WHEN existing IS NULL THEN [1] ELSE [] END | \
-                       MERGE (n:{label} {{source: $source, source_id: $source_id}}) \
-                       ON CREATE SET n.id = $id, n.name = $name, n.aliases = $aliases, \
-                         n.properties = $properties, n.confidence = $confidence, \
-                         n.first_seen = $first_seen, n.last_seen = $last_seen, \
-                         n.sources = [$source] \
-                       ON MATCH SET n.name = $name, n.aliases = $aliases, \
-                         n.properties = $properties, n.confidence = $confidence, \
-                         n.last_seen = $last_seen, \
-                         n.sources = CASE \
-                           WHEN n.sources IS NULL THEN [$source] \
-                           WHEN NOT $source IN n.sources THEN n.sources + $source \
-                           ELSE n.sources END \
-                     )",
-                )
-            } else {
-                format!(
-                    "OPTIONAL MATCH (existing:{label} \
-                       WHERE toLower(existing.name) = toLower($name) \
-                       AND existing.source <> $source) \
-                     WITH existing \
-                     FOREACH (_ IN CASE WHEN existing IS NOT NULL THEN [1] ELSE [] END | \
-                       SET existing.sources = CASE \
-                         WHEN existing.sources IS NULL THEN [$source] \
-                         WHEN NOT $source IN existing.sources THEN existing.sources + $source \
-                         ELSE existing.sources END, \
-                       existing.aliases = $aliases, \
-                       existing.properties = $properties, \
-                       existing.confidence = CASE WHEN $confidence > existing.confidence THEN $confidence ELSE existing.confidence END, \
-                       existing.last_seen = $last_seen \
-                     ) \
-                     WITH existing \
-                     FOREACH (_ IN CASE WHEN existing IS NULL THEN [1] ELSE [] END | \
-                       MERGE (n:{label} {{id: $id}}) \
-                       ON CREATE SET n.name = $name, n.source = $source, n.source_id = $source_id, \
-                         n.aliases = $aliases, n.properties = $properties, \
-                         n.confidence = $confidence, n.first_seen = $first_seen, \
-                         n.last_seen = $last_seen, \
-                         n.sources = [$source] \
-                       ON MATCH SET n.name = $name, n.aliases = $aliases, \
-                         n.properties = $properties, n.confidence = $confidence, \
-                         n.last_seen = $last_seen, \
-                         n.sources = CASE \
-                           WHEN n.sources IS NULL THEN [$source] \
-                           WHEN NOT $source IN n.sources THEN n.sources + $source \
-                           ELSE n.sources END \
-                     )",
-                )
+pub(crate) fn identifier_keys(identifiers: &BTreeMap<String, String>) -> Vec<String> {
+    identifiers
+        .iter()
+        .map(|(scheme, value)| identifier_key(scheme, value))
+        .collect()
+}
+
+fn parse_identifier_keys(keys: &[String]) -> BTreeMap<String, String> {
+    keys.iter()
+        .filter_map(|key| key.split_once(':'))
+        .map(|(scheme, value)| (scheme.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Cypher expression replacing `{var}`'s identifiers for the schemes in
+/// `$identifier_schemes` with `$identifiers`, keeping the rest.
+fn merged_identifiers(var: &str) -> String {
+    format!(
+        "[x IN coalesce({var}.identifiers, []) WHERE NOT split(x, ':')[0] IN $identifier_schemes] + $identifiers"
+    )
+}
+
+const OBSERVED_AT_WRITE_CYPHER: &str = "\
+    UNWIND range(0, size($ids) - 1) AS i \
+    MATCH (n {id: $ids[i]}) \
+    WHERE n.observed_at IS NULL OR $dates[i] < n.observed_at \
+    SET n.observed_at = $dates[i]";
+
+/// Cypher expression keeping the earlier of `{var}.observed_at` and
+/// `$observed_at` (empty when the source gave no date).
+fn earliest_observed_at(var: &str) -> String {
+    format!(
+        "CASE WHEN $observed_at <> '' AND ({var}.observed_at IS NULL OR $observed_at < {var}.observed_at) \
+         THEN $observed_at ELSE {var}.observed_at END"
+    )
+}
+
+/// Excludes nodes that share the graph with entities but are not entities
+/// themselves (archived documents, change-log entries, position reports,
+/// tombstones), and soft-deleted entities.
+pub(crate) fn non_entity_filter() -> String {
+    format!(
+        "{} AND NOT n:{}",
+        stored_entity_filter(),
+        crate::tombstones::DELETED_LABEL
+    )
+}
+
+/// Like `non_entity_filter`, but soft-deleted entities pass.
+pub(crate) fn stored_entity_filter() -> String {
+    format!(
+        "NOT n:{} AND NOT n:{} AND NOT n:{} AND NOT n:{}",
+        crate::documents::DOCUMENT_LABEL,
+        crate::history::CHANGE_LABEL,
+        crate::tracks::POSITION_LABEL,
+        crate::tombstones::TOMBSTONE_LABEL
+    )
+}
+
+/// Build the Cypher for a filtered entity search. Label and ordering are
+/// interpolated from enums; user-supplied values stay in parameters.
+fn build_search_cypher(filter: &EntitySearchFilter) -> String {
+    let pattern = match &filter.entity_type {
+        Some(et) => format!("(n:{})", entity_type_to_label(et)),
+        None => "(n)".to_string(),
+    };
+
+    let mut conditions = vec![non_entity_filter(), "n.name CONTAINS $query".to_string()];
+    if filter.min_sanctions_exposure.is_some() {
+        conditions.push("n.sanctions_exposure >= $min_exposure".to_string());
+    }
+
+    let order = match filter.sort {
+        EntitySort::Relevance => "",
+        EntitySort::Name => " ORDER BY n.name ASC",
+        EntitySort::LastSeen => " ORDER BY n.last_seen DESC",
+        EntitySort::SanctionsExposure => " ORDER BY coalesce(n.sanctions_exposure, 0.0) DESC",
+        EntitySort::Completeness => " ORDER BY coalesce(n.completeness, 0.0) ASC",
+    };
+
+    format!(
+        "MATCH {pattern} WHERE {} RETURN n{order} LIMIT $limit",
+        conditions.join(" AND ")
+    )
+}
+
+/// Most neighbor entities one traversal returns.
+const MAX_NEIGHBOR_NODES: usize = 500;
+/// Most relationships one traversal returns.
+const MAX_NEIGHBOR_RELATIONSHIPS: usize = 2000;
+/// Edges expanded from each hop's frontier, nearest hop first; deeper hops
+/// reuse the last entry.
+const EDGES_PER_HOP: [usize; 3] = [1000, 500, 250];
+
+fn edges_per_hop(hop: u32) -> usize {
+    let i = (hop.max(1) as usize - 1).min(EDGES_PER_HOP.len() - 1);
+    EDGES_PER_HOP[i]
+}
+
+/// Build the single-hop pattern a neighbor traversal expands from each
+/// frontier node `n`.
+fn build_neighbor_pattern(filter: &NeighborFilter) -> String {
+    let types = filter
+        .relation_types
+        .iter()
+        .map(|rt| relation_type_to_label(rt).into_owned())
+        .collect::<Vec<_>>()
+        .join("|");
+    let rel = if types.is_empty() {
+        "[r]".to_string()
+    } else {
+        format!("[r:{types}]")
+    };
+    match filter.direction {
+        Direction::Outgoing => format!("(n)-{rel}->(m)"),
+        Direction::Incoming => format!("(n)<-{rel}-(m)"),
+        Direction::Both => format!("(n)-{rel}-(m)"),
+    }
+}
+
+/// Build a relationship from the `properties(r)` map of a traversed edge.
+fn neighbor_relationship(
+    rel_label: &str,
+    props: &serde_json::Value,
+    source_entity_id: Uuid,
+    target_entity_id: Uuid,
+) -> Relationship {
+    let field = |key: &str| props.as_object().and_then(|m| m.get(key));
+    Relationship {
+        id: field("id")
+            .and_then(|v| v.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .unwrap_or_else(Uuid::new_v4),
+        source_entity_id,
+        target_entity_id,
+        relation_type: label_to_relation_type(rel_label),
+        properties: field("properties")
+            .and_then(|v| serde_json::from_str(v.as_str().unwrap_or("{}")).ok())
+            .unwrap_or(serde_json::Value::Object(Default::default())),
+        confidence: field("confidence").and_then(|v| v.as_f64()).unwrap_or(1.0),
+        source: field("source")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        timestamp: field("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc)),
+        strength: field("strength").and_then(|v| v.as_f64()),
+    }
+}
+
+/// Build the Cypher for `list_relationships`. The relationship type is
+/// interpolated from the enum; everything else is passed as parameters.
+fn build_relationship_list_cypher(filter: &RelationshipFilter) -> String {
+    let pattern = match &filter.relation_type {
+        Some(rt) => format!("(a)-[r:{}]->(b)", relation_type_to_label(rt)),
+        None => "(a)-[r]->(b)".to_string(),
+    };
+
+    let mut conditions = vec!["NOT a:Deleted AND NOT b:Deleted"];
+    if filter.source.is_some() {
+        conditions.push("r.source = $source");
+    }
+    if filter.min_confidence.is_some() {
+        conditions.push("r.confidence >= $min_confidence");
+    }
+    if filter.created_after.is_some() {
+        conditions.push("r.created_at >= $created_after");
+    }
+    if filter.created_before.is_some() {
+        conditions.push("r.created_at <= $created_before");
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
+    format!(
+        "MATCH {pattern}{where_clause} \
+         RETURN r.id AS id, type(r) AS rel_type, a.id AS source_id, b.id AS target_id, \
+           r.properties AS properties, r.confidence AS confidence, r.source AS source, \
+           r.timestamp AS timestamp, r.strength AS strength \
+         ORDER BY r.created_at DESC \
+         SKIP $offset LIMIT $limit"
+    )
+}
+
+/// Matches on the text written by `relationship_search_text`; edges stored
+/// before it existed fall back to their raw properties JSON.
+const RELATIONSHIP_SEARCH_CYPHER: &str = "\
+    MATCH (a)-[r]->(b) \
+    WHERE coalesce(r.search_text, toLower(r.properties)) CONTAINS $query \
+      AND NOT a:Deleted AND NOT b:Deleted \
+    RETURN r.id AS id, type(r) AS rel_type, a.id AS source_id, b.id AS target_id, \
+      r.properties AS properties, r.confidence AS confidence, r.source AS source, \
+      r.timestamp AS timestamp, r.strength AS strength, a.name AS source_name, b.name AS target_name \
+    ORDER BY r.confidence DESC \
+    LIMIT $limit";
+
+const RELATIONSHIP_BY_ID_CYPHER: &str = "\
+    MATCH (a)-[r {id: $id}]->(b) \
+    WHERE NOT a:Deleted AND NOT b:Deleted \
+    RETURN r.id AS id, type(r) AS rel_type, a.id AS source_id, b.id AS target_id, \
+      r.properties AS properties, r.confidence AS confidence, r.source AS source, \
+      r.timestamp AS timestamp, r.strength AS strength \
+    LIMIT 1";
+
+/// Lowercased property values (evidence included) joined by spaces, so
+/// searches match what the edge says rather than its JSON keys.
+pub(crate) fn relationship_search_text(properties: &serde_json::Value) -> String {
+    fn collect(value: &serde_json::Value, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => out.push(s.to_lowercase()),
+            serde_json::Value::Number(n) => out.push(n.to_string()),
+            serde_json::Value::Bool(b) => out.push(b.to_string()),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect(v, out)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| collect(v, out)),
+            serde_json::Value::Null => {}
+        }
+    }
+    let mut parts = Vec::new();
+    collect(properties, &mut parts);
+    parts.join(" ")
+}
+
+/// Convert a row produced by `build_relationship_list_cypher` into a Relationship.
+fn row_to_relationship(row: &Row) -> Result<Relationship> {
+    let parse_uuid = |key: &str| -> Result<Uuid> {
+        let s: String = row
+            .get(key)
+            .map_err(|e| ArgusError::Graph(format!("Missing {} on relationship: {}", key, e)))?;
+        Uuid::parse_str(&s)
+            .map_err(|e| ArgusError::Graph(format!("Invalid UUID for {}: {}", key, e)))
+    };
+
+    let rel_type: String = row
+        .get("rel_type")
+        .map_err(|e| ArgusError::Graph(format!("Missing relationship type: {}", e)))?;
+    let properties_json: String = row.get("properties").unwrap_or_else(|_| "{}".to_string());
+    let timestamp_str: String = row.get("timestamp").unwrap_or_default();
+
+    Ok(Relationship {
+        id: parse_uuid("id")?,
+        source_entity_id: parse_uuid("source_id")?,
+        target_entity_id: parse_uuid("target_id")?,
+        relation_type: label_to_relation_type(&rel_type),
+        properties: serde_json::from_str(&properties_json)
+            .unwrap_or(serde_json::Value::Object(Default::default())),
+        confidence: row.get("confidence").unwrap_or(1.0),
+        source: row.get("source").unwrap_or_default(),
+        timestamp: chrono::DateTime::parse_from_rfc3339(&timestamp_str)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .ok(),
+        strength: row.get("strength").ok(),
+    })
+}
+
+/// Build a query from raw Cypher, binding JSON params by type. Arrays and
+/// objects are passed as their JSON text.
+fn bind_params(graph_query: &GraphQuery) -> Query {
+    let mut q = query(&graph_query.cypher);
+
+    if let Some(obj) = graph_query.params.as_object() {
+        for (key, value) in obj {
+            q = match value {
+                serde_json::Value::String(s) => q.param(&key[..], s.clone()),
+                serde_json::Value::Number(n) => {
+                    if let Some(i) = n.as_i64() {
+                        q.param(&key[..], i)
+                    } else if let Some(f) = n.as_f64() {
+                        q.param(&key[..], f)
+                    } else {
+                        q.param(&key[..], n.to_string())
+                    }
+                }
+                serde_json::Value::Bool(b) => q.param(&key[..], *b),
+                serde_json::Value::Null => q.param(&key[..], ""),
+                _ => q.param(&key[..], value.to_string()),
             };
+        }
+    }
 
-            let q = query(&cypher)
-                .param("id", entity.id.to_string())
-                .param("name", entity.name.clone())
-                .param("source", entity.source.clone())
-                .param(
-                    "source_id",
-                    entity.source_id.clone().unwrap_or_default(),
-                )
-                .param("aliases", aliases_json)
-                .param("properties", properties_json)
-                .param("confidence", entity.confidence)
-                .param("first_seen", entity.first_seen.to_rfc3339())
-                .param("last_seen", entity.last_seen.to_rfc3339());
-
-            txn.run(q)
-                .await
-                .map_err(|e| ArgusError::Graph(format!("Failed to store entity {}: {}", entity.id, e)))?;
+    q
+}
+
+/// Write one extraction result into an open transaction: entity upserts
+/// with their change-log entries, then relationships.
+/// Upsert for one relationship. The MERGE key is (endpoints, type, source),
+/// plus the window start for bucketed types. `timestamp` keeps the earliest
+/// observation and `last_seen` the latest, so repeats of a standing fact
+/// update nothing but the window and `observations`.
+pub(crate) fn relationship_merge_cypher(label: &str, identity: RelationIdentity) -> String {
+    let key = match identity {
+        RelationIdentity::Singleton => "source: $source",
+        RelationIdentity::Bucketed { .. } => "source: $source, bucket: $bucket",
+    };
+    format!(
+        "MATCH (a {{id: $source_id}}) \
+         MATCH (b {{id: $target_id}}) \
+         MERGE (a)-[r:{label} {{{key}}}]->(b) \
+         ON CREATE SET r.id = $rel_id, r.properties = $properties, \
+           r.search_text = $search_text, \
+           r.confidence = $confidence, r.timestamp = $timestamp, \
+           r.last_seen = $seen_at, r.observations = 1, \
+           r.created_at = $created_at \
+         ON MATCH SET r.properties = $properties, r.search_text = $search_text, \
+           r.confidence = CASE WHEN $confidence > r.confidence THEN $confidence ELSE r.confidence END, \
+           r.timestamp = CASE \
+             WHEN $timestamp = '' THEN r.timestamp \
+             WHEN coalesce(r.timestamp, '') = '' OR $timestamp < r.timestamp THEN $timestamp \
+             ELSE r.timestamp END, \
+           r.last_seen = CASE WHEN $seen_at > coalesce(r.last_seen, '') THEN $seen_at ELSE r.last_seen END, \
+           r.observations = coalesce(r.observations, 1) + 1"
+    )
+}
 
-            tracing::debug!(
-                entity_id = %entity.id,
-                entity_name = %entity.name,
-                entity_type = label,
-                "Stored entity"
+/// Source records remembered per event for [`MENTION_CYPHER`]; a record
+/// older than the last this many is counted again if it comes back.
+const MAX_MENTION_REPORTS: usize = 500;
+
+/// Count `$report` (the source record's id) as a mention of the event
+/// unless it was counted before, so re-storing a record changes nothing.
+const MENTION_CYPHER: &str = "\
+    MATCH (n:Event {id: $id}) \
+    WHERE NOT $report IN coalesce(n.report_ids, []) \
+    SET n.report_ids = (coalesce(n.report_ids, []) + $report)[-$max_reports..], \
+      n.mention_count = coalesce(n.mention_count, 0) + 1";
+
+/// Whether `entity` is a keyed event whose reports are counted; other
+/// events have one id per report, or are derived rather than reported.
+fn counts_mentions(entity: &Entity) -> bool {
+    entity.entity_type == EntityType::Event
+        && entity
+            .source_id
+            .as_deref()
+            .is_some_and(|id| id.starts_with(EVENT_KEY_PREFIX))
+}
+
+async fn write_extraction(
+    txn: &mut Txn,
+    result: &ExtractionResult,
+    relationship_identity: &BTreeMap<String, RelationIdentity>,
+) -> Result<()> {
+    let now = chrono::Utc::now();
+    // Node each entity was written to; differs from its own id when it
+    // merged onto an existing node, and relationships must follow it there
+    let mut stored_ids = HashMap::new();
+    // Tombstoned entities are not written, nor are edges touching them
+    let mut tombstoned = HashSet::new();
+    for entity in &result.entities {
+        if crate::tombstones::is_tombstoned(txn, entity).await? {
+            tracing::debug!(entity_id = %entity.id, entity_name = %entity.name, "Skipping tombstoned entity");
+            tombstoned.insert(entity.id);
+            continue;
+        }
+        let label = entity_type_to_label(&entity.entity_type);
+        let (before, is_cross) = crate::history::read_snapshots(txn, &label, entity).await?;
+        stored_ids.insert(entity.id, before.first().map_or(entity.id, |b| b.id));
+        let aliases_json = serde_json::to_string(&entity.aliases)
+            .map_err(|e| ArgusError::Graph(format!("Failed to serialize aliases: {}", e)))?;
+        let properties_json = serde_json::to_string(&entity.properties)
+            .map_err(|e| ArgusError::Graph(format!("Failed to serialize properties: {}", e)))?;
+
+        // Cross-source entity resolution: first check if an entity with the
+        // same name (case-insensitive) and type already exists from any source.
+        // If found, merge onto that node and accumulate sources.
+        // Otherwise, MERGE on (source, source_id) or (id) as before.
+        let existing_observed = earliest_observed_at("existing");
+        let n_observed = earliest_observed_at("n");
+        let existing_identifiers = merged_identifiers("existing");
+        let n_identifiers = merged_identifiers("n");
+        let cypher = if entity.source_id.is_some() {
+            format!(
+                "OPTIONAL MATCH (existing:{label} \
+                   WHERE toLower(existing.name) = toLower($name) \
+                   AND existing.source <> $source AND NOT existing:Deleted) \
+                 WITH existing \
+                 FOREACH (_ IN CASE WHEN existing IS NOT NULL THEN [1] ELSE [] END | \
+                   SET existing.sources = CASE \
+                     WHEN existing.sources IS NULL THEN [$source] \
+                     WHEN NOT $source IN existing.sources THEN existing.sources + $source \
+                     ELSE existing.sources END, \
+                   existing.aliases = $aliases, \
+                   existing.properties = $properties, \
+                   existing.confidence = CASE WHEN $confidence > existing.confidence THEN $confidence ELSE existing.confidence END, \
+                   existing.last_seen = $last_seen, \
+                   existing.observed_at = {existing_observed}, \
+                   existing.identifiers = {existing_identifiers} \
+                 ) \
+                 WITH existing \
+                 FOREACH (_ IN CASE WHEN existing IS NULL THEN [1] ELSE [] END | \
+                   MERGE (n:{label} {{source: $source, source_id: $source_id}}) \
+                   ON CREATE SET n.id = $id, n.name = $name, n.aliases = $aliases, \
+                     n.properties = $properties, n.confidence = $confidence, \
+                     n.first_seen = $first_seen, n.last_seen = $last_seen, \
+                     n.observed_at = {n_observed}, \
+                     n.identifiers = $identifiers, n.sources = [$source] \
+                   ON MATCH SET n.name = $name, n.aliases = $aliases, \
+                     n.properties = $properties, n.confidence = $confidence, \
+                     n.last_seen = $last_seen, n.observed_at = {n_observed}, \
+                     n.identifiers = {n_identifiers}, n.sources = CASE \
+                       WHEN n.sources IS NULL THEN [$source] \
+                       WHEN NOT $source IN n.sources THEN n.sources + $source \
+                       ELSE n.sources END \
+                 )",
+            )
+        } else {
+            format!(
+                "OPTIONAL MATCH (existing:{label} \
+                   WHERE toLower(existing.name) = toLower($name) \
+                   AND existing.source <> $source AND NOT existing:Deleted) \
+                 WITH existing \
+                 FOREACH (_ IN CASE WHEN existing IS NOT NULL THEN [1] ELSE [] END | \
+                   SET existing.sources = CASE \
+                     WHEN existing.sources IS NULL THEN [$source] \
+                     WHEN NOT $source IN existing.sources THEN existing.sources + $source \
+                     ELSE existing.sources END, \
+                   existing.aliases = $aliases, \
+                   existing.properties = $properties, \
+                   existing.confidence = CASE WHEN $confidence > existing.confidence THEN $confidence ELSE existing.confidence END, \
+                   existing.last_seen = $last_seen, \
+                   existing.observed_at = {existing_observed}, \
+                   existing.identifiers = {existing_identifiers} \
+                 ) \
+                 WITH existing \
+                 FOREACH (_ IN CASE WHEN existing IS NULL THEN [1] ELSE [] END | \
+                   MERGE (n:{label} {{id: $id}}) \
+                   ON CREATE SET n.name = $name, n.source = $source, n.source_id = $source_id, \
+                     n.aliases = $aliases, n.properties = $properties, \
+                     n.confidence = $confidence, n.first_seen = $first_seen, \
+                     n.last_seen = $last_seen, n.observed_at = {n_observed}, \
+                     n.identifiers = $identifiers, n.sources = [$source] \
+                   ON MATCH SET n.name = $name, n.aliases = $aliases, \
+                     n.properties = $properties, n.confidence = $confidence, \
+                     n.last_seen = $last_seen, n.observed_at = {n_observed}, \
+                     n.identifiers = {n_identifiers}, n.sources = CASE \
+                       WHEN n.sources IS NULL THEN [$source] \
+                       WHEN NOT $source IN n.sources THEN n.sources + $source \
+                       ELSE n.sources END \
+                 )",
+            )
+        };
+
+        let q = query(&cypher)
+            .param("id", entity.id.to_string())
+            .param("name", entity.name.clone())
+            .param("source", entity.source.clone())
+            .param("source_id", entity.source_id.clone().unwrap_or_default())
+            .param("aliases", aliases_json)
+            .param("properties", properties_json)
+            .param("confidence", entity.confidence)
+            .param("first_seen", entity.first_seen.to_rfc3339())
+            .param("last_seen", entity.last_seen.to_rfc3339())
+            .param(
+                "observed_at",
+                entity
+                    .observed_at
+                    .map(|at| at.to_rfc3339())
+                    .unwrap_or_default(),
+            )
+            .param("identifiers", identifier_keys(&entity.identifiers))
+            .param(
+                "identifier_schemes",
+                entity.identifiers.keys().cloned().collect::<Vec<_>>(),
             );
+
+        txn.run(q).await.map_err(|e| {
+            ArgusError::Graph(format!("Failed to store entity {}: {}", entity.id, e))
+        })?;
+
+        let changes: Vec<_> = if before.is_empty() {
+            crate::history::diff_entity(None, entity, false, &result.raw_source, now)
+        } else {
+            before
+                .iter()
+                .flat_map(|b| {
+                    crate::history::diff_entity(Some(b), entity, is_cross, &result.raw_source, now)
+                })
+                .collect()
+        };
+        crate::history::write_changes(txn, &changes).await?;
+
+        if counts_mentions(entity) && !result.raw_source.is_empty() {
+            let stored_id = stored_ids.get(&entity.id).unwrap_or(&entity.id);
+            let q = query(MENTION_CYPHER)
+                .param("id", stored_id.to_string())
+                .param("report", result.raw_source.clone())
+                .param("max_reports", MAX_MENTION_REPORTS as i64);
+            txn.run(q).await.map_err(|e| {
+                ArgusError::Graph(format!("Failed to count mention of {}: {}", entity.id, e))
+            })?;
         }
 
-        for rel in &result.relationships {
-            let rel_label = relation_type_to_label(&rel.relation_type);
-            let properties_json = serde_json::to_string(&rel.properties)
-                .map_err(|e| ArgusError::Graph(format!("Failed to serialize relationship properties: {}", e)))?;
-
-            let timestamp_str = rel
-                .timestamp
-                .map(|t| t.to_rfc3339())
-                .unwrap_or_default();
-
-            // Use MERGE instead of CREATE to prevent duplicate relationships
-            let cypher = format!(
-                "MATCH (a {{id: $source_id}}) \
-                 MATCH (b {{id: $target_id}}) \
-                 MERGE (a)-[r:{} {{source: $source}}]->(b) \
-                 ON CREATE SET r.id = $rel_id, r.properties = $properties, \
-                   r.confidence = $confidence, r.timestamp = $timestamp \
-                 ON MATCH SET r.properties = $properties, \
-                   r.confidence = CASE WHEN $confidence > r.confidence THEN $confidence ELSE r.confidence END, \
-                   r.timestamp = CASE WHEN $timestamp <> '' THEN $timestamp ELSE r.timestamp END",
-                rel_label
-            );
+        tracing::debug!(
+            entity_id = %entity.id,
+            entity_name = %entity.name,
+            entity_type = %label,
+            "Stored entity"
+        );
+    }
 
-            let q = query(&cypher)
-                .param("source_id", rel.source_entity_id.to_string())
-                .param("target_id", rel.target_entity_id.to_string())
-                .param("rel_id", rel.id.to_string())
-                .param("properties", properties_json)
-                .param("confidence", rel.confidence)
-                .param("source", rel.source.clone())
-                .param("timestamp", timestamp_str);
-
-            txn.run(q)
-                .await
-                .map_err(|e| ArgusError::Graph(format!("Failed to store relationship {}: {}", rel.id, e)))?;
-
-            tracing::debug!(
-                rel_id = %rel.id,
-                source = %rel.source_entity_id,
-                target = %rel.target_entity_id,
-                rel_type = rel_label,
-                "Stored relationship"
-            );
+    for rel in &result.relationships {
+        if tombstoned.contains(&rel.source_entity_id) || tombstoned.contains(&rel.target_entity_id)
+        {
+            continue;
         }
+        let rel_label = relation_type_to_label(&rel.relation_type);
+        let properties_json = serde_json::to_string(&rel.properties).map_err(|e| {
+            ArgusError::Graph(format!(
+                "Failed to serialize relationship properties: {}",
+                e
+            ))
+        })?;
+
+        let timestamp_str = rel.timestamp.map(|t| t.to_rfc3339()).unwrap_or_default();
+        // Undated relationships are observed now
+        let seen_at = rel.timestamp.unwrap_or(now);
+        let identity = relationship_identity
+            .get(rel.relation_type.as_str())
+            .copied()
+            .unwrap_or_default();
+        let bucket = identity
+            .bucket(seen_at)
+            .map(|b| b.to_rfc3339())
+            .unwrap_or_default();
+
+        let cypher = relationship_merge_cypher(&rel_label, identity);
+
+        let endpoint = |id: &Uuid| stored_ids.get(id).unwrap_or(id).to_string();
+        let q = query(&cypher)
+            .param("source_id", endpoint(&rel.source_entity_id))
+            .param("target_id", endpoint(&rel.target_entity_id))
+            .param("rel_id", rel.id.to_string())
+            .param("properties", properties_json)
+            .param("search_text", relationship_search_text(&rel.properties))
+            .param("confidence", rel.confidence)
+            .param("source", rel.source.clone())
+            .param("timestamp", timestamp_str)
+            .param("seen_at", seen_at.to_rfc3339())
+            .param("bucket", bucket)
+            .param("created_at", chrono::Utc::now().to_rfc3339());
+
+        txn.run(q).await.map_err(|e| {
+            ArgusError::Graph(format!("Failed to store relationship {}: {}", rel.id, e))
+        })?;
 
-        txn.commit()
-            .await
-            .map_err(|e| ArgusError::Graph(format!("Failed to commit transaction: {}", e)))?;
+        tracing::debug!(
+            rel_id = %rel.id,
+            source = %rel.source_entity_id,
+            target = %rel.target_entity_id,
+            rel_type = %rel_label,
+            "Stored relationship"
+        );
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl GraphStore for Neo4jGraphStore {
+    #[tracing::instrument(name = "neo4j_transaction", skip_all, fields(db.system = "neo4j", results = 1))]
+    async fn store_extraction(&self, result: &ExtractionResult) -> Result<()> {
+        let results = std::slice::from_ref(result);
+        self.write_retry
+            .run(|| self.write_extractions(results))
+            .await?;
 
         tracing::info!(
             entities = result.entities.len(),
@@ -339,11 +929,30 @@ impl GraphStore for Neo4jGraphStore {
         Ok(())
     }
 
+    #[tracing::instrument(name = "neo4j_transaction", skip_all, fields(db.system = "neo4j", results = results.len()))]
+    async fn store_extractions(&self, results: &[ExtractionResult]) -> Result<()> {
+        self.write_retry
+            .run(|| self.write_extractions(results))
+            .await?;
+
+        tracing::info!(
+            results = results.len(),
+            entities = results.iter().map(|r| r.entities.len()).sum::<usize>(),
+            relationships = results.iter().map(|r| r.relationships.len()).sum::<usize>(),
+            "Stored extraction batch"
+        );
+
+        Ok(())
+    }
+
+    async fn get_entity_history(&self, id: Uuid, limit: usize) -> Result<Vec<EntityChange>> {
+        crate::history::entity_history(self.graph()?, id, limit).await
+    }
+
     async fn get_entity(&self, id: Uuid) -> Result<Option<Entity>> {
-        let mut stream = timed(
-            self.graph()?
-                .execute(query("MATCH (n {id: $id}) RETURN n").param("id", id.to_string())),
-        )
+        let mut stream = timed(self.graph()?.execute(
+            query("MATCH (n {id: $id}) WHERE NOT n:Deleted RETURN n").param("id", id.to_string()),
+        ))
         .await?
         .map_err(|e| ArgusError::Graph(format!("Failed to query entity: {}", e)))?;
 
@@ -360,10 +969,241 @@ impl GraphStore for Neo4jGraphStore {
         }
     }
 
+    async fn find_by_identifier(&self, scheme: &str, value: &str) -> Result<Vec<Entity>> {
+        let key = identifier_key(&normalize_identifier_scheme(scheme), value.trim()).to_lowercase();
+        let cypher = format!(
+            "MATCH (n) WHERE {} \
+             AND any(x IN coalesce(n.identifiers, []) WHERE toLower(x) = $key) \
+             RETURN n LIMIT {MAX_IDENTIFIER_MATCHES}",
+            non_entity_filter()
+        );
+        let mut stream = timed(self.graph()?.execute(query(&cypher).param("key", key)))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to query by identifier: {}", e)))?;
+
+        let mut entities = Vec::new();
+        while let Ok(Some(row)) = stream.next().await {
+            let node: Node = match row.get("n") {
+                Ok(n) => n,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to parse entity node");
+                    continue;
+                }
+            };
+            match node_to_entity(&node) {
+                Ok(entity) => entities.push(entity),
+                Err(e) => tracing::warn!(error = %e, "Skipping malformed entity node"),
+            }
+        }
+        Ok(entities)
+    }
+
+    async fn get_entities_batch(
+        &self,
+        ids: &[Uuid],
+        include_relationships: bool,
+    ) -> Result<Vec<EntityBatchItem>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Relationship counts are grouped per type; the CASE keeps the two
+        // collected lists aligned when an entity has no relationships.
+        let cypher = if include_relationships {
+            "UNWIND $ids AS id \
+             MATCH (n {id: id}) WHERE NOT n:Deleted \
+             OPTIONAL MATCH (n)-[r]-(m) WHERE NOT m:Deleted \
+             WITH n, type(r) AS rel_type, count(r) AS cnt \
+             RETURN n, collect(rel_type) AS rel_types, \
+               collect(CASE WHEN rel_type IS NULL THEN null ELSE cnt END) AS rel_counts"
+        } else {
+            "UNWIND $ids AS id MATCH (n {id: id}) WHERE NOT n:Deleted RETURN n"
+        };
+
+        let id_strings: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        let q = query(cypher).param("ids", id_strings);
+
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to fetch entity batch: {}", e)))?;
+
+        let mut items = Vec::with_capacity(ids.len());
+        while let Ok(Some(row)) = stream.next().await {
+            let node: Node = row
+                .get("n")
+                .map_err(|e| ArgusError::Graph(format!("Failed to deserialize node: {}", e)))?;
+            let entity = match node_to_entity(&node) {
+                Ok(entity) => entity,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Skipping malformed entity node");
+                    continue;
+                }
+            };
+
+            let relationships = if include_relationships {
+                let rel_types: Vec<String> = row.get("rel_types").unwrap_or_default();
+                let rel_counts: Vec<i64> = row.get("rel_counts").unwrap_or_default();
+                let mut summary = RelationshipSummary::default();
+                for (label, count) in rel_types.iter().zip(rel_counts) {
+                    let count = count.max(0) as u64;
+                    summary.total += count;
+                    *summary
+                        .by_type
+                        .entry(label_to_relation_type(label))
+                        .or_default() += count;
+                }
+                Some(summary)
+            } else {
+                None
+            };
+
+            items.push(EntityBatchItem {
+                entity,
+                relationships,
+            });
+        }
+
+        // UNWIND does not guarantee input order once MATCH is involved
+        let position: std::collections::HashMap<Uuid, usize> =
+            ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        items.sort_by_key(|item| position.get(&item.entity.id).copied().unwrap_or(usize::MAX));
+
+        tracing::debug!(
+            requested = ids.len(),
+            found = items.len(),
+            "Fetched entity batch"
+        );
+
+        Ok(items)
+    }
+
+    async fn list_entities_after(&self, after: Option<Uuid>, limit: usize) -> Result<Vec<Entity>> {
+        let cypher = format!(
+            "MATCH (n) WHERE n.id > $after AND {} RETURN n ORDER BY n.id LIMIT $limit",
+            non_entity_filter()
+        );
+        let q = query(&cypher)
+            .param("after", after.map(|id| id.to_string()).unwrap_or_default())
+            .param("limit", limit as i64);
+
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to page entities: {}", e)))?;
+
+        let mut entities = Vec::new();
+        while let Ok(Some(row)) = stream.next().await {
+            let node: Node = row
+                .get("n")
+                .map_err(|e| ArgusError::Graph(format!("Failed to deserialize node: {}", e)))?;
+            match node_to_entity(&node) {
+                Ok(entity) => entities.push(entity),
+                Err(e) => tracing::warn!(error = %e, "Skipping malformed entity node"),
+            }
+        }
+
+        Ok(entities)
+    }
+
+    async fn entities_first_seen_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<Entity>> {
+        let cypher = format!(
+            "MATCH (n) WHERE n.first_seen >= $since AND {} RETURN n ORDER BY n.first_seen DESC LIMIT $limit",
+            non_entity_filter()
+        );
+        let q = query(&cypher)
+            .param("since", since.to_rfc3339())
+            .param("limit", limit as i64);
+
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to list new entities: {}", e)))?;
+
+        let mut entities = Vec::new();
+        while let Ok(Some(row)) = stream.next().await {
+            let node: Node = row
+                .get("n")
+                .map_err(|e| ArgusError::Graph(format!("Failed to deserialize node: {}", e)))?;
+            match node_to_entity(&node) {
+                Ok(entity) => entities.push(entity),
+                Err(e) => tracing::warn!(error = %e, "Skipping malformed entity node"),
+            }
+        }
+
+        Ok(entities)
+    }
+
+    async fn count_new_since(&self, since: chrono::DateTime<chrono::Utc>) -> Result<(u64, u64)> {
+        let cypher = format!(
+            "CALL {{ MATCH (n) WHERE n.first_seen >= $since AND {} RETURN count(n) AS entities }} \
+             CALL {{ MATCH (a)-[r]->(b) WHERE r.created_at >= $since AND NOT a:Deleted AND NOT b:Deleted \
+                     RETURN count(r) AS relationships }} \
+             RETURN entities, relationships",
+            non_entity_filter()
+        );
+        let q = query(&cypher).param("since", since.to_rfc3339());
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to count new entities: {}", e)))?;
+
+        match stream.next().await {
+            Ok(Some(row)) => {
+                let entities: i64 = row.get("entities").unwrap_or(0);
+                let relationships: i64 = row.get("relationships").unwrap_or(0);
+                Ok((entities as u64, relationships as u64))
+            }
+            Ok(None) => Ok((0, 0)),
+            Err(e) => Err(ArgusError::Graph(format!(
+                "Error counting new entities: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn set_observed_at(
+        &self,
+        observed: &[(Uuid, chrono::DateTime<chrono::Utc>)],
+    ) -> Result<()> {
+        if observed.is_empty() {
+            return Ok(());
+        }
+        let q = query(OBSERVED_AT_WRITE_CYPHER)
+            .param(
+                "ids",
+                observed
+                    .iter()
+                    .map(|(id, _)| id.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .param(
+                "dates",
+                observed
+                    .iter()
+                    .map(|(_, at)| at.to_rfc3339())
+                    .collect::<Vec<_>>(),
+            );
+        timed(self.graph()?.run(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to write observed_at: {}", e)))
+    }
+
     async fn search_entities(&self, query_str: &str, limit: usize) -> Result<Vec<Entity>> {
-        let cypher = "MATCH (n) WHERE n.name CONTAINS $query RETURN n LIMIT $limit";
-        let q = query(cypher)
+        self.search_entities_filtered(query_str, &EntitySearchFilter::default(), limit)
+            .await
+    }
+
+    async fn search_entities_filtered(
+        &self,
+        query_str: &str,
+        filter: &EntitySearchFilter,
+        limit: usize,
+    ) -> Result<Vec<Entity>> {
+        let cypher = build_search_cypher(filter);
+        let q = query(&cypher)
             .param("query", query_str.to_string())
+            .param("min_exposure", filter.min_sanctions_exposure.unwrap_or(0.0))
             .param("limit", limit as i64);
 
         let mut stream = timed(self.graph()?.execute(q))
@@ -392,126 +1232,153 @@ impl GraphStore for Neo4jGraphStore {
         Ok(entities)
     }
 
+    async fn merge_entities(&self, keep: Uuid, duplicates: &[Uuid]) -> Result<Entity> {
+        crate::merge::merge_entities(self.graph()?, keep, duplicates).await
+    }
+
+    async fn soft_delete_entity(&self, id: Uuid) -> Result<Tombstone> {
+        crate::tombstones::soft_delete(self.graph()?, id).await
+    }
+
+    async fn restore_entity(&self, id: Uuid) -> Result<Entity> {
+        crate::tombstones::restore(self.graph()?, id).await
+    }
+
+    async fn purge_entity(&self, id: Uuid) -> Result<Tombstone> {
+        crate::tombstones::purge(self.graph()?, id).await
+    }
+
+    async fn list_tombstones(&self, limit: usize) -> Result<Vec<Tombstone>> {
+        crate::tombstones::list(self.graph()?, limit).await
+    }
+
     async fn get_neighbors(&self, entity_id: Uuid, depth: u32) -> Result<GraphNeighbors> {
-        // First get the root entity
-        let root_entity = self
-            .get_entity(entity_id)
-            .await?
-            .ok_or_else(|| ArgusError::NotFound(format!("Entity {} not found", entity_id)))?;
+        self.get_neighbors_filtered(entity_id, depth, &NeighborFilter::default())
+            .await
+    }
 
+    async fn get_neighbors_filtered(
+        &self,
+        entity_id: Uuid,
+        depth: u32,
+        filter: &NeighborFilter,
+    ) -> Result<GraphNeighbors> {
+        // First get the root entity
+        let root_entity = match filter.as_of {
+            Some(as_of) => self.get_entity_as_of(entity_id, as_of).await?,
+            None => self.get_entity(entity_id).await?,
+        }
+        .ok_or_else(|| ArgusError::NotFound(format!("Entity {} not found", entity_id)))?;
+
+        // Expand one hop at a time so a hub node costs at most one capped
+        // query per level instead of every path through it.
+        let as_of_clause = match filter.as_of {
+            Some(_) => format!(
+                " AND {} AND {}",
+                crate::as_of::edge_existed("r"),
+                crate::as_of::node_existed("m")
+            ),
+            None => String::new(),
+        };
+        let strength_clause = match filter.min_strength {
+            Some(_) => " AND coalesce(r.strength, 1.0) >= $min_strength",
+            None => "",
+        };
         let cypher = format!(
-            "MATCH (n {{id: $id}})-[r*1..{}]-(m) \
-             RETURN DISTINCT m, \
-                    [rel IN r | type(rel)] AS rel_types, \
-                    [rel IN r | properties(rel)] AS rel_props, \
-                    [rel IN r | startNode(rel).id] AS rel_sources, \
-                    [rel IN r | endNode(rel).id] AS rel_targets",
-            depth
+            "MATCH {} WHERE n.id IN $frontier AND NOT m:Deleted{as_of_clause}{strength_clause} \
+             RETURN m, type(r) AS rel_type, properties(r) AS rel_props, \
+                    startNode(r).id AS rel_source, endNode(r).id AS rel_target \
+             LIMIT $limit",
+            build_neighbor_pattern(filter)
         );
-
-        let q = query(&cypher).param("id", entity_id.to_string());
-
-        let mut stream = timed(self.graph()?.execute(q))
-            .await?
-            .map_err(|e| ArgusError::Graph(format!("Failed to get neighbors: {}", e)))?;
+        let as_of = filter.as_of.map(crate::as_of::param).unwrap_or_default();
 
         let mut neighbors = Vec::new();
         let mut relationships = Vec::new();
-        let mut seen_neighbor_ids = std::collections::HashSet::new();
+        let mut visited = std::collections::HashSet::from([entity_id]);
         let mut seen_rel_ids = std::collections::HashSet::new();
+        let mut frontier = vec![entity_id.to_string()];
+        let mut truncated = false;
 
-        while let Ok(Some(row)) = stream.next().await {
-            // Parse neighbor node
-            let neighbor_node: Node = match row.get("m") {
-                Ok(n) => n,
-                Err(e) => {
-                    tracing::warn!(error = %e, "Failed to parse neighbor node");
-                    continue;
+        for hop in 1..=depth {
+            if frontier.is_empty() {
+                break;
+            }
+            let hop_limit = edges_per_hop(hop);
+            let q = query(&cypher)
+                .param("frontier", std::mem::take(&mut frontier))
+                .param("as_of", as_of.clone())
+                .param("min_strength", filter.min_strength.unwrap_or(0.0))
+                .param("limit", (hop_limit + 1) as i64);
+
+            let mut stream = timed(self.graph()?.execute(q))
+                .await?
+                .map_err(|e| ArgusError::Graph(format!("Failed to get neighbors: {}", e)))?;
+
+            let mut rows = 0;
+            while let Ok(Some(row)) = stream.next().await {
+                rows += 1;
+                if rows > hop_limit {
+                    truncated = true;
+                    break;
                 }
-            };
 
-            match node_to_entity(&neighbor_node) {
-                Ok(neighbor) => {
-                    if seen_neighbor_ids.insert(neighbor.id) {
-                        neighbors.push(neighbor);
+                let neighbor_node: Node = match row.get("m") {
+                    Ok(n) => n,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to parse neighbor node");
+                        continue;
                     }
-                }
-                Err(e) => {
-                    tracing::warn!(error = %e, "Skipping malformed neighbor node");
-                    continue;
-                }
-            }
+                };
+                let neighbor = match node_to_entity(&neighbor_node) {
+                    Ok(neighbor) => neighbor,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Skipping malformed neighbor node");
+                        continue;
+                    }
+                };
 
-            // Parse relationship chain types
-            let rel_types: Vec<String> = row.get("rel_types").unwrap_or_default();
-            let rel_sources: Vec<String> = row.get("rel_sources").unwrap_or_default();
-            let rel_targets: Vec<String> = row.get("rel_targets").unwrap_or_default();
-            let rel_props: Vec<serde_json::Value> = row.get("rel_props").unwrap_or_default();
-
-            for i in 0..rel_types.len() {
-                let rel_type = label_to_relation_type(&rel_types[i]);
-
-                let source_id = rel_sources
-                    .get(i)
-                    .and_then(|s| Uuid::parse_str(s).ok())
-                    .unwrap_or(entity_id);
-                let target_id = rel_targets
-                    .get(i)
-                    .and_then(|s| Uuid::parse_str(s).ok())
-                    .unwrap_or(entity_id);
-
-                // Extract rel id from properties if available
-                let props = rel_props.get(i).cloned().unwrap_or(serde_json::Value::Object(Default::default()));
-                let rel_id_str = props
-                    .as_object()
-                    .and_then(|m| m.get("id"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("");
-                let rel_id = Uuid::parse_str(rel_id_str).unwrap_or_else(|_| Uuid::new_v4());
-
-                if !seen_rel_ids.insert(rel_id) {
-                    continue;
+                // Past the node cap, only edges among nodes already
+                // returned are kept.
+                if !visited.contains(&neighbor.id) {
+                    if neighbors.len() >= MAX_NEIGHBOR_NODES {
+                        truncated = true;
+                        continue;
+                    }
+                    visited.insert(neighbor.id);
+                    frontier.push(neighbor.id.to_string());
+                    neighbors.push(neighbor);
                 }
 
-                let confidence = props
-                    .as_object()
-                    .and_then(|m| m.get("confidence"))
-                    .and_then(|v| v.as_f64())
-                    .unwrap_or(1.0);
-
-                let source = props
-                    .as_object()
-                    .and_then(|m| m.get("source"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let timestamp_str = props
-                    .as_object()
-                    .and_then(|m| m.get("timestamp"))
-                    .and_then(|v| v.as_str());
-                let timestamp = timestamp_str.and_then(|s| {
-                    chrono::DateTime::parse_from_rfc3339(s)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .ok()
-                });
-
-                let inner_props = props
-                    .as_object()
-                    .and_then(|m| m.get("properties"))
-                    .and_then(|v| serde_json::from_str(v.as_str().unwrap_or("{}")).ok())
+                let rel_type: String = row.get("rel_type").unwrap_or_default();
+                let props: serde_json::Value = row
+                    .get("rel_props")
                     .unwrap_or(serde_json::Value::Object(Default::default()));
+                let endpoint = |key: &str| {
+                    row.get::<String>(key)
+                        .ok()
+                        .and_then(|s| Uuid::parse_str(&s).ok())
+                        .unwrap_or(entity_id)
+                };
+                let rel = neighbor_relationship(
+                    &rel_type,
+                    &props,
+                    endpoint("rel_source"),
+                    endpoint("rel_target"),
+                );
+
+                if !seen_rel_ids.insert(rel.id) {
+                    continue;
+                }
+                if relationships.len() >= MAX_NEIGHBOR_RELATIONSHIPS {
+                    truncated = true;
+                    break;
+                }
+                relationships.push(rel);
+            }
 
-                relationships.push(Relationship {
-                    id: rel_id,
-                    source_entity_id: source_id,
-                    target_entity_id: target_id,
-                    relation_type: rel_type,
-                    properties: inner_props,
-                    confidence,
-                    source,
-                    timestamp,
-                });
+            if relationships.len() >= MAX_NEIGHBOR_RELATIONSHIPS {
+                break;
             }
         }
 
@@ -520,6 +1387,7 @@ impl GraphStore for Neo4jGraphStore {
             depth = depth,
             neighbor_count = neighbors.len(),
             relationship_count = relationships.len(),
+            truncated = truncated,
             "Fetched neighbors"
         );
 
@@ -527,33 +1395,102 @@ impl GraphStore for Neo4jGraphStore {
             entity: root_entity,
             relationships,
             neighbors,
+            truncated,
         })
     }
 
-    async fn execute_cypher(&self, graph_query: &GraphQuery) -> Result<serde_json::Value> {
-        let mut q = query(&graph_query.cypher);
-
-        // Add params from the JSON value
-        if let Some(obj) = graph_query.params.as_object() {
-            for (key, value) in obj {
-                q = match value {
-                    serde_json::Value::String(s) => q.param(&key[..], s.clone()),
-                    serde_json::Value::Number(n) => {
-                        if let Some(i) = n.as_i64() {
-                            q.param(&key[..], i)
-                        } else if let Some(f) = n.as_f64() {
-                            q.param(&key[..], f)
-                        } else {
-                            q.param(&key[..], n.to_string())
-                        }
-                    }
-                    serde_json::Value::Bool(b) => q.param(&key[..], *b),
-                    serde_json::Value::Null => q.param(&key[..], ""),
-                    _ => q.param(&key[..], value.to_string()),
-                };
+    async fn list_relationships(&self, filter: &RelationshipFilter) -> Result<Vec<Relationship>> {
+        let cypher = build_relationship_list_cypher(filter);
+        let q = query(&cypher)
+            .param("source", filter.source.clone().unwrap_or_default())
+            .param("min_confidence", filter.min_confidence.unwrap_or(0.0))
+            .param(
+                "created_after",
+                filter
+                    .created_after
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+            )
+            .param(
+                "created_before",
+                filter
+                    .created_before
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+            )
+            .param("offset", filter.offset as i64)
+            .param("limit", filter.limit as i64);
+
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to list relationships: {}", e)))?;
+
+        let mut relationships = Vec::new();
+        while let Ok(Some(row)) = stream.next().await {
+            match row_to_relationship(&row) {
+                Ok(rel) => relationships.push(rel),
+                Err(e) => tracing::warn!(error = %e, "Skipping malformed relationship"),
             }
         }
 
+        tracing::debug!(results = relationships.len(), "Listed relationships");
+
+        Ok(relationships)
+    }
+
+    async fn search_relationships(
+        &self,
+        query_str: &str,
+        limit: usize,
+    ) -> Result<Vec<RelationshipSearchHit>> {
+        let q = query(RELATIONSHIP_SEARCH_CYPHER)
+            .param("query", query_str.trim().to_lowercase())
+            .param("limit", limit as i64);
+
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to search relationships: {}", e)))?;
+
+        let mut hits = Vec::new();
+        while let Ok(Some(row)) = stream.next().await {
+            match row_to_relationship(&row) {
+                Ok(relationship) => hits.push(RelationshipSearchHit {
+                    relationship,
+                    source_name: row.get("source_name").unwrap_or_default(),
+                    target_name: row.get("target_name").unwrap_or_default(),
+                }),
+                Err(e) => tracing::warn!(error = %e, "Skipping malformed relationship"),
+            }
+        }
+
+        tracing::debug!(
+            query = query_str,
+            results = hits.len(),
+            "Relationship search completed"
+        );
+
+        Ok(hits)
+    }
+
+    async fn get_relationship(&self, id: Uuid) -> Result<Option<Relationship>> {
+        let q = query(RELATIONSHIP_BY_ID_CYPHER).param("id", id.to_string());
+        let mut stream = timed(self.graph()?.execute(q))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to get relationship: {}", e)))?;
+
+        match stream.next().await {
+            Ok(Some(row)) => row_to_relationship(&row).map(Some),
+            Ok(None) => Ok(None),
+            Err(e) => Err(ArgusError::Graph(format!(
+                "Failed to read relationship: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn execute_cypher(&self, graph_query: &GraphQuery) -> Result<serde_json::Value> {
+        let q = bind_params(graph_query);
+
         let mut stream = timed(self.graph()?.execute(q))
             .await?
             .map_err(|e| ArgusError::Graph(format!("Failed to execute cypher: {}", e)))?;
@@ -577,13 +1514,94 @@ impl GraphStore for Neo4jGraphStore {
         Ok(serde_json::Value::Array(rows))
     }
 
+    async fn stream_cypher(&self, graph_query: &GraphQuery) -> Result<RowStream> {
+        let stream = timed(self.graph()?.execute(bind_params(graph_query)))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to execute cypher: {}", e)))?;
+
+        tracing::debug!(cypher = %graph_query.cypher, "Streaming raw Cypher query");
+
+        // The stream ends after the first read error
+        let rows = futures::stream::unfold(Some(stream), |stream| async move {
+            let mut stream = stream?;
+            match stream.next().await {
+                Ok(Some(row)) => {
+                    let row_json = row
+                        .to()
+                        .unwrap_or(serde_json::Value::Object(Default::default()));
+                    Some((Ok(row_json), Some(stream)))
+                }
+                Ok(None) => None,
+                Err(e) => Some((
+                    Err(ArgusError::Graph(format!(
+                        "Failed to read cypher result: {}",
+                        e
+                    ))),
+                    None,
+                )),
+            }
+        });
+        Ok(Box::pin(rows))
+    }
+
+    async fn execute_readonly_cypher(&self, graph_query: &GraphQuery) -> Result<serde_json::Value> {
+        if let Some(clause) = graph_query.write_clause() {
+            return Err(ArgusError::Graph(format!(
+                "Query rejected: {} is not allowed in read-only mode",
+                clause
+            )));
+        }
+
+        // Never committed, so anything the keyword screen missed is discarded
+        let mut txn = timed(self.graph()?.start_txn())
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to start transaction: {}", e)))?;
+
+        let mut stream = txn
+            .execute(bind_params(graph_query))
+            .await
+            .map_err(|e| ArgusError::Graph(format!("Failed to execute cypher: {}", e)))?;
+
+        let mut rows = Vec::new();
+        while let Ok(Some(row)) = stream.next(txn.handle()).await {
+            rows.push(
+                row.to()
+                    .unwrap_or(serde_json::Value::Object(Default::default())),
+            );
+        }
+
+        txn.rollback()
+            .await
+            .map_err(|e| ArgusError::Graph(format!("Failed to roll back transaction: {}", e)))?;
+
+        tracing::debug!(
+            cypher = %graph_query.cypher,
+            rows = rows.len(),
+            "Executed read-only Cypher query"
+        );
+
+        Ok(serde_json::Value::Array(rows))
+    }
+
+    async fn find_paths(
+        &self,
+        from: Uuid,
+        to: Uuid,
+        max_depth: u32,
+        limit: usize,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<GraphPath>> {
+        crate::paths::find_paths(self.graph()?, from, to, max_depth, limit, as_of).await
+    }
+
     async fn entity_count(&self) -> Result<u64> {
-        let mut stream = timed(
-            self.graph()?
-                .execute(query("MATCH (n) RETURN count(n) AS cnt")),
-        )
-        .await?
-        .map_err(|e| ArgusError::Graph(format!("Failed to count entities: {}", e)))?;
+        let cypher = format!(
+            "MATCH (n) WHERE {} RETURN count(n) AS cnt",
+            non_entity_filter()
+        );
+        let mut stream = timed(self.graph()?.execute(query(&cypher)))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to count entities: {}", e)))?;
 
         match stream.next().await {
             Ok(Some(row)) => {
@@ -619,4 +1637,360 @@ impl GraphStore for Neo4jGraphStore {
             ))),
         }
     }
+
+    async fn archive_documents(&self, documents: &[ArchivedDocument]) -> Result<()> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+        crate::documents::archive_documents(self.graph()?, documents).await
+    }
+
+    async fn search_documents(
+        &self,
+        query: &DocumentSearchQuery,
+    ) -> Result<Vec<DocumentSearchHit>> {
+        crate::documents::search_documents(self.graph()?, query).await
+    }
+
+    async fn sample_run_documents(&self, run_id: &str, sample: usize) -> Result<RunDocumentSample> {
+        crate::documents::sample_run_documents(self.graph()?, run_id, sample).await
+    }
+
+    async fn run_extraction_stats(
+        &self,
+        run_id: &str,
+        started_at: DateTime<Utc>,
+    ) -> Result<RunExtractionStats> {
+        crate::documents::run_extraction_stats(self.graph()?, run_id, started_at).await
+    }
+
+    async fn update_sanctions_exposure(&self) -> Result<u64> {
+        crate::exposure::update_sanctions_exposure(self.graph()?).await
+    }
+
+    async fn update_relationship_strength(&self, now: DateTime<Utc>) -> Result<u64> {
+        crate::strength::update_relationship_strength(self.graph()?, now).await
+    }
+
+    async fn update_completeness(&self, now: DateTime<Utc>) -> Result<u64> {
+        crate::completeness::update_completeness(self.graph()?, now).await
+    }
+
+    async fn apply_inference_rule(
+        &self,
+        rule: InferenceRule,
+        run_at: DateTime<Utc>,
+    ) -> Result<InferenceReport> {
+        crate::inference::apply_inference_rule(self.graph()?, rule, run_at).await
+    }
+
+    async fn check_integrity(
+        &self,
+        check: IntegrityCheck,
+        repair: bool,
+        now: DateTime<Utc>,
+    ) -> Result<IntegrityFinding> {
+        crate::integrity::check_integrity(self.graph()?, check, repair, now).await
+    }
+
+    async fn set_geometries(&self, geometries: &[(Uuid, serde_json::Value)]) -> Result<u64> {
+        let graph = self.graph()?;
+        let mut updated = 0;
+        for batch in geometries.chunks(500) {
+            let rows: Vec<HashMap<String, String>> = batch
+                .iter()
+                .map(|(id, geometry)| {
+                    HashMap::from([
+                        ("id".to_string(), id.to_string()),
+                        ("geometry".to_string(), geometry.to_string()),
+                    ])
+                })
+                .collect();
+            let q = query("UNWIND $rows AS x MATCH (n {id: x.id}) SET n.geometry = x.geometry RETURN count(n) AS cnt")
+                .param("rows", rows);
+            let mut stream = timed(graph.execute(q))
+                .await?
+                .map_err(|e| ArgusError::Graph(format!("Failed to set geometries: {}", e)))?;
+            if let Ok(Some(row)) = stream.next().await {
+                updated += row.get::<i64>("cnt").unwrap_or(0) as u64;
+            }
+        }
+        Ok(updated)
+    }
+
+    async fn store_positions(&self, positions: &[PositionReport]) -> Result<()> {
+        if positions.is_empty() {
+            return Ok(());
+        }
+        crate::tracks::store_positions(self.graph()?, positions).await
+    }
+
+    async fn positions_between(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<PositionReport>> {
+        crate::tracks::positions_between(self.graph()?, since, until, limit).await
+    }
+
+    async fn asset_positions(
+        &self,
+        assets: &[String],
+        limit: usize,
+    ) -> Result<Vec<PositionReport>> {
+        if assets.is_empty() {
+            return Ok(Vec::new());
+        }
+        crate::tracks::asset_positions(self.graph()?, assets, limit).await
+    }
+
+    async fn event_density(&self, query: &DensityQuery) -> Result<EventDensity> {
+        crate::density::event_density(self.graph()?, query).await
+    }
+
+    async fn count_expired(&self, policy: &RetentionPolicy, before: DateTime<Utc>) -> Result<u64> {
+        crate::retention::count_expired(self.graph()?, policy, before).await
+    }
+
+    async fn expired_nodes(
+        &self,
+        policy: &RetentionPolicy,
+        before: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<serde_json::Value>> {
+        crate::retention::expired_nodes(self.graph()?, policy, before, limit).await
+    }
+
+    async fn delete_nodes(&self, ids: &[String]) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        crate::retention::delete_nodes(self.graph()?, ids).await
+    }
+
+    async fn snapshot_nodes(&self) -> Result<BoxStream<'static, Result<NodeDump>>> {
+        crate::snapshot::nodes(self.graph()?).await
+    }
+
+    async fn snapshot_relationships(&self) -> Result<BoxStream<'static, Result<RelationshipDump>>> {
+        crate::snapshot::relationships(self.graph()?).await
+    }
+
+    async fn begin_restore(&self) -> Result<()> {
+        crate::snapshot::begin_restore(self.graph()?).await
+    }
+
+    async fn restore_nodes(&self, nodes: &[NodeDump]) -> Result<()> {
+        if nodes.is_empty() {
+            return Ok(());
+        }
+        crate::snapshot::restore_nodes(self.graph()?, nodes).await
+    }
+
+    async fn restore_relationships(&self, relationships: &[RelationshipDump]) -> Result<u64> {
+        if relationships.is_empty() {
+            return Ok(0);
+        }
+        crate::snapshot::restore_relationships(self.graph()?, relationships).await
+    }
+
+    async fn finish_restore(&self) -> Result<()> {
+        crate::snapshot::finish_restore(self.graph()?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifier_keys_round_trip_values_with_colons() {
+        let identifiers = BTreeMap::from([
+            ("lei".to_string(), "5493001KJTIIGC8Y1R12".to_string()),
+            (
+                "opencorporates".to_string(),
+                "https://opencorporates.com/companies/gb/01234567".to_string(),
+            ),
+        ]);
+        let keys = identifier_keys(&identifiers);
+        assert!(keys.contains(&"lei:5493001KJTIIGC8Y1R12".to_string()));
+        assert_eq!(parse_identifier_keys(&keys), identifiers);
+        assert!(merged_identifiers("n").starts_with("[x IN coalesce(n.identifiers, [])"));
+    }
+
+    #[test]
+    fn custom_entity_types_map_to_pascal_case_labels() {
+        let et = EntityType::Custom("bank_account".into());
+        assert_eq!(entity_type_to_label(&et), "BankAccount");
+        assert_eq!(label_to_entity_type("BankAccount"), et);
+        assert_eq!(label_to_entity_type("Person"), EntityType::Person);
+    }
+
+    #[test]
+    fn custom_relation_types_map_to_upper_snake_labels() {
+        let rt = RelationType::Custom("funded_by".into());
+        assert_eq!(relation_type_to_label(&rt), "FUNDED_BY");
+        assert_eq!(label_to_relation_type("FUNDED_BY"), rt);
+        assert_eq!(label_to_relation_type("OWNER_OF"), RelationType::OwnerOf);
+    }
+
+    #[test]
+    fn only_keyed_events_count_mentions() {
+        let mut event = Entity::new(
+            EntityType::Event,
+            "Protest in Beijing".into(),
+            "gdelt".into(),
+        );
+        event.source_id = Some(format!("{EVENT_KEY_PREFIX}20260226|14|chn|chngov|beijing"));
+        assert!(counts_mentions(&event));
+        // LLM-extracted and job-derived events are not re-reports
+        event.source_id = None;
+        assert!(!counts_mentions(&event));
+        event.source_id = Some("flight:3c6444:2026-02-26T08:00:00+00:00".into());
+        assert!(!counts_mentions(&event));
+    }
+
+    #[test]
+    fn relationship_search_text_flattens_values() {
+        let properties = serde_json::json!({
+            "evidence": "Funds were held in ESCROW",
+            "amount": 2500000,
+            "parties": ["Acme Ltd", null],
+            "terms": { "currency": "USD" },
+        });
+        let text = relationship_search_text(&properties);
+        assert!(text.contains("funds were held in escrow"));
+        assert!(text.contains("2500000"));
+        assert!(text.contains("acme ltd"));
+        assert!(text.contains("usd"));
+        assert!(!text.contains("evidence"));
+    }
+
+    #[test]
+    fn bucketed_relationships_merge_per_window() {
+        let singleton = relationship_merge_cypher("LOCATED_AT", RelationIdentity::Singleton);
+        assert!(singleton.contains("MERGE (a)-[r:LOCATED_AT {source: $source}]->(b)"));
+        let daily = relationship_merge_cypher(
+            "MEETING_WITH",
+            RelationIdentity::Bucketed { seconds: 86400 },
+        );
+        assert!(
+            daily.contains("MERGE (a)-[r:MEETING_WITH {source: $source, bucket: $bucket}]->(b)")
+        );
+    }
+
+    #[test]
+    fn custom_labels_are_sanitized() {
+        let et = EntityType::Custom("x`) DETACH DELETE n //".into());
+        assert_eq!(entity_type_to_label(&et), "XDetachDeleteN");
+        let rt = RelationType::Custom("9lives".into());
+        assert_eq!(relation_type_to_label(&rt), "CUSTOM_9LIVES");
+    }
+
+    #[test]
+    fn neighbor_pattern_respects_direction_and_types() {
+        assert_eq!(
+            build_neighbor_pattern(&NeighborFilter::default()),
+            "(n)-[r]-(m)"
+        );
+        let filter = NeighborFilter {
+            direction: Direction::Incoming,
+            relation_types: vec![
+                RelationType::OwnerOf,
+                RelationType::Custom("funded_by".into()),
+            ],
+            as_of: None,
+            min_strength: None,
+        };
+        assert_eq!(
+            build_neighbor_pattern(&filter),
+            "(n)<-[r:OWNER_OF|FUNDED_BY]-(m)"
+        );
+        let filter = NeighborFilter {
+            direction: Direction::Outgoing,
+            relation_types: Vec::new(),
+            as_of: None,
+            min_strength: None,
+        };
+        assert_eq!(build_neighbor_pattern(&filter), "(n)-[r]->(m)");
+    }
+
+    #[test]
+    fn neighbor_hops_shrink_with_depth() {
+        assert_eq!(edges_per_hop(1), 1000);
+        assert_eq!(edges_per_hop(2), 500);
+        assert_eq!(edges_per_hop(3), 250);
+        assert_eq!(edges_per_hop(6), 250);
+    }
+
+    #[test]
+    fn neighbor_relationship_reads_edge_properties() {
+        let id = Uuid::new_v4();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let props = serde_json::json!({
+            "id": id.to_string(),
+            "confidence": 0.7,
+            "source": "opencorporates",
+            "timestamp": "2024-03-01T00:00:00Z",
+            "properties": "{\"share\": 0.5}",
+        });
+        let rel = neighbor_relationship("OWNER_OF", &props, a, b);
+        assert_eq!(rel.id, id);
+        assert_eq!((rel.source_entity_id, rel.target_entity_id), (a, b));
+        assert_eq!(rel.relation_type, RelationType::OwnerOf);
+        assert_eq!(rel.confidence, 0.7);
+        assert_eq!(rel.properties["share"], 0.5);
+        assert!(rel.timestamp.is_some());
+
+        let bare = neighbor_relationship("RELATED_TO", &serde_json::json!({}), a, b);
+        assert_eq!(bare.confidence, 1.0);
+        assert!(bare.timestamp.is_none());
+    }
+
+    #[test]
+    fn relationship_list_cypher_without_filters() {
+        let cypher = build_relationship_list_cypher(&RelationshipFilter::default());
+        assert!(
+            cypher.starts_with("MATCH (a)-[r]->(b) WHERE NOT a:Deleted AND NOT b:Deleted RETURN")
+        );
+        assert!(cypher.contains("SKIP $offset LIMIT $limit"));
+    }
+
+    #[test]
+    fn relationship_list_cypher_with_filters() {
+        let filter = RelationshipFilter {
+            relation_type: Some(RelationType::SanctionedBy),
+            source: Some("opensanctions".into()),
+            min_confidence: Some(0.8),
+            created_after: Some(chrono::Utc::now()),
+            ..Default::default()
+        };
+        let cypher = build_relationship_list_cypher(&filter);
+        assert!(cypher.contains("(a)-[r:SANCTIONED_BY]->(b)"));
+        assert!(cypher.contains(
+            "WHERE NOT a:Deleted AND NOT b:Deleted AND r.source = $source AND r.confidence >= $min_confidence AND r.created_at >= $created_after "
+        ));
+        assert!(!cypher.contains("$created_before"));
+    }
+
+    #[test]
+    fn search_cypher_applies_label_exposure_and_sort() {
+        let filter = EntitySearchFilter {
+            entity_type: Some(EntityType::Organization),
+            min_sanctions_exposure: Some(0.5),
+            sort: EntitySort::SanctionsExposure,
+        };
+        let cypher = build_search_cypher(&filter);
+        assert!(cypher.starts_with(
+            "MATCH (n:Organization) WHERE NOT n:SourceDocument AND NOT n:EntityChange AND NOT n:Position AND NOT n:Tombstone AND NOT n:Deleted AND n.name CONTAINS $query AND n.sanctions_exposure >= $min_exposure"
+        ));
+        assert!(cypher.contains("ORDER BY coalesce(n.sanctions_exposure, 0.0) DESC LIMIT $limit"));
+
+        let weakest_first = build_search_cypher(&EntitySearchFilter {
+            sort: EntitySort::Completeness,
+            ..Default::default()
+        });
+        assert!(weakest_first.ends_with("ORDER BY coalesce(n.completeness, 0.0) ASC LIMIT $limit"));
+    }
 }
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph};
+
+use argus_core::error::{ArgusError, Result};
+
+use crate::store::timed;
+
+/// Share of the score from how often the pair was linked.
+const FREQUENCY_WEIGHT: f64 = 0.5;
+/// Share of the score from how recently the pair was last linked.
+const RECENCY_WEIGHT: f64 = 0.3;
+/// Share of the score from how many sources linked the pair.
+const DIVERSITY_WEIGHT: f64 = 0.2;
+
+/// Observations at which frequency counts in full.
+const SATURATING_OBSERVATIONS: u64 = 10;
+/// Distinct sources at which diversity counts in full.
+const SATURATING_SOURCES: u64 = 3;
+/// Days after which recency counts half.
+const RECENCY_HALF_LIFE_DAYS: f64 = 90.0;
+
+/// Number of pairs written back per UNWIND batch.
+const WRITE_BATCH_SIZE: usize = 1000;
+
+/// Every pair of linked entities once, with all edges between them in
+/// either direction taken together.
+const STRENGTH_INPUTS_CYPHER: &str = "\
+    MATCH (a)-[r]-(b) WHERE a.id < b.id AND NOT a:Deleted AND NOT b:Deleted \
+    RETURN a.id AS a, b.id AS b, sum(coalesce(r.observations, 1)) AS observations, \
+      count(DISTINCT r.source) AS sources, max(coalesce(r.last_seen, r.created_at, '')) AS last_seen";
+
+const STRENGTH_WRITE_CYPHER: &str = "\
+    UNWIND range(0, size($a) - 1) AS i \
+    MATCH (x {id: $a[i]})-[r]-(y {id: $b[i]}) \
+    SET r.strength = $scores[i]";
+
+/// Combine co-occurrence signals into a score in [0.0, 1.0].
+///
+/// Frequency grows with the log of the observations, so a single mention
+/// adds nothing and ten count in full; recency halves every
+/// [`RECENCY_HALF_LIFE_DAYS`]; diversity counts sources beyond the first.
+/// A lone, fresh mention by one source therefore scores 0.3.
+pub(crate) fn strength_score(observations: u64, sources: u64, age_days: f64) -> f64 {
+    let frequency =
+        ((observations.max(1) as f64).ln() / (SATURATING_OBSERVATIONS as f64).ln()).min(1.0);
+    let recency = 0.5_f64.powf(age_days.max(0.0) / RECENCY_HALF_LIFE_DAYS);
+    let diversity = ((sources.max(1) - 1) as f64 / (SATURATING_SOURCES - 1) as f64).min(1.0);
+    let score =
+        FREQUENCY_WEIGHT * frequency + RECENCY_WEIGHT * recency + DIVERSITY_WEIGHT * diversity;
+    (score * 1000.0).round() / 1000.0
+}
+
+/// Recompute and persist `strength` on every relationship, the same for
+/// all edges between a pair.
+pub(crate) async fn update_relationship_strength(graph: &Graph, now: DateTime<Utc>) -> Result<u64> {
+    let mut stream = timed(graph.execute(query(STRENGTH_INPUTS_CYPHER)))
+        .await?
+        .map_err(|e| {
+            ArgusError::Graph(format!(
+                "Failed to query relationship strength inputs: {}",
+                e
+            ))
+        })?;
+
+    let (mut a_ids, mut b_ids, mut scores) = (Vec::new(), Vec::new(), Vec::new());
+    while let Ok(Some(row)) = stream.next().await {
+        let (Ok(a), Ok(b)) = (row.get::<String>("a"), row.get::<String>("b")) else {
+            continue;
+        };
+        let observations: i64 = row.get("observations").unwrap_or(1);
+        let sources: i64 = row.get("sources").unwrap_or(1);
+        let last_seen: String = row.get("last_seen").unwrap_or_default();
+        // Never seen counts as long ago
+        let age_days = DateTime::parse_from_rfc3339(&last_seen)
+            .map(|at| (now - at.with_timezone(&Utc)).num_seconds() as f64 / 86_400.0)
+            .unwrap_or(f64::INFINITY);
+
+        a_ids.push(a);
+        b_ids.push(b);
+        scores.push(strength_score(
+            observations.max(0) as u64,
+            sources.max(0) as u64,
+            age_days,
+        ));
+    }
+
+    for ((a, b), scores) in a_ids
+        .chunks(WRITE_BATCH_SIZE)
+        .zip(b_ids.chunks(WRITE_BATCH_SIZE))
+        .zip(scores.chunks(WRITE_BATCH_SIZE))
+    {
+        let q = query(STRENGTH_WRITE_CYPHER)
+            .param("a", a.to_vec())
+            .param("b", b.to_vec())
+            .param("scores", scores.to_vec());
+        timed(graph.run(q)).await?.map_err(|e| {
+            ArgusError::Graph(format!("Failed to write relationship strength: {}", e))
+        })?;
+    }
+
+    tracing::info!(pairs = a_ids.len(), "Updated relationship strength scores");
+    Ok(a_ids.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_fresh_single_mention_is_weak() {
+        assert_eq!(strength_score(1, 1, 0.0), 0.3);
+        assert_eq!(strength_score(1, 1, f64::INFINITY), 0.0);
+    }
+
+    #[test]
+    fn repeated_diverse_recent_links_score_one() {
+        assert_eq!(strength_score(10, 3, 0.0), 1.0);
+        assert_eq!(strength_score(500, 9, 0.0), 1.0);
+    }
+
+    #[test]
+    fn each_signal_raises_the_score() {
+        let base = strength_score(2, 1, 30.0);
+        assert!(strength_score(5, 1, 30.0) > base);
+        assert!(strength_score(2, 2, 30.0) > base);
+        assert!(strength_score(2, 1, 1.0) > base);
+        assert_eq!(strength_score(1, 1, 90.0), 0.15);
+    }
+}
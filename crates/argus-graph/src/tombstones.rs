@@ -0,0 +1,280 @@
+use chrono::{DateTime, Utc};
+use neo4rs::{query, Graph, Node, Txn};
+use uuid::Uuid;
+
+use argus_core::entity::Entity;
+use argus_core::error::{ArgusError, Result};
+use argus_core::history::{ChangeKind, EntityChange, Tombstone};
+
+use crate::store::{
+    entity_type_to_label, label_to_entity_type, node_to_entity, non_entity_filter,
+    stored_entity_filter, timed,
+};
+
+/// Extra label on soft-deleted entity nodes; reads and traversals skip it.
+pub(crate) const DELETED_LABEL: &str = "Deleted";
+
+/// Label for tombstones. They carry the deleted entity's id and source
+/// record rather than an edge, so they outlive a purge.
+pub(crate) const TOMBSTONE_LABEL: &str = "Tombstone";
+
+/// Change-log source for deletes and restores, which no agent caused.
+const DELETE_SOURCE: &str = "admin";
+
+const TOMBSTONED_CYPHER: &str = "\
+    OPTIONAL MATCH (a:Tombstone {entity_id: $id}) \
+    WITH count(a) AS by_id \
+    OPTIONAL MATCH (b:Tombstone {source: $source, source_id: $source_id}) \
+    RETURN by_id + count(b) > 0 AS tombstoned";
+
+/// Entities without a source record get no `source_id`, so they can only
+/// be matched by id.
+const WRITE_TOMBSTONE_CYPHER: &str = "\
+    MERGE (t:Tombstone {entity_id: $id}) \
+    SET t.name = $name, t.entity_type = $entity_type, t.source = $source, \
+      t.source_id = CASE WHEN $source_id = '' THEN null ELSE $source_id END, \
+      t.deleted_at = coalesce(t.deleted_at, $deleted_at), t.purged = $purged \
+    RETURN t";
+
+const MARK_DELETED_CYPHER: &str = "MATCH (n {id: $id}) SET n:Deleted, n.deleted_at = $deleted_at";
+
+const RESTORE_CYPHER: &str = "\
+    MATCH (n:Deleted {id: $id}) \
+    REMOVE n:Deleted SET n.deleted_at = null \
+    RETURN n";
+
+const LIFT_TOMBSTONE_CYPHER: &str = "MATCH (t:Tombstone {entity_id: $id}) DELETE t";
+
+const PURGE_HISTORY_CYPHER: &str = "MATCH (c:EntityChange {entity_id: $id}) DELETE c";
+
+const PURGE_NODE_CYPHER: &str = "MATCH (n {id: $id}) DETACH DELETE n";
+
+const LIST_CYPHER: &str = "MATCH (t:Tombstone) RETURN t ORDER BY t.deleted_at DESC LIMIT $limit";
+
+/// Create the indexes behind the re-ingestion check.
+pub(crate) async fn ensure_schema(graph: &Graph) -> Result<()> {
+    let cypher = format!(
+        "CREATE INDEX tombstone_entity_id IF NOT EXISTS FOR (t:{TOMBSTONE_LABEL}) ON (t.entity_id)"
+    );
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create tombstone index: {}", e)))?;
+
+    let cypher =
+        format!("CREATE INDEX tombstone_source IF NOT EXISTS FOR (t:{TOMBSTONE_LABEL}) ON (t.source, t.source_id)");
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create tombstone source index: {}", e)))
+}
+
+/// Whether a tombstone covers `entity`'s id or source record.
+pub(crate) async fn is_tombstoned(txn: &mut Txn, entity: &Entity) -> Result<bool> {
+    let q = query(TOMBSTONED_CYPHER)
+        .param("id", entity.id.to_string())
+        .param("source", entity.source.clone())
+        .param("source_id", entity.source_id.clone().unwrap_or_default());
+    let mut stream = txn
+        .execute(q)
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to check tombstones: {}", e)))?;
+    Ok(match stream.next(txn.handle()).await {
+        Ok(Some(row)) => row.get("tombstoned").unwrap_or(false),
+        _ => false,
+    })
+}
+
+async fn run(txn: &mut Txn, q: neo4rs::Query, what: &str) -> Result<()> {
+    txn.run(q)
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to {what}: {e}")))
+}
+
+async fn start(graph: &Graph) -> Result<Txn> {
+    timed(graph.start_txn())
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to start transaction: {}", e)))
+}
+
+async fn commit(txn: Txn) -> Result<()> {
+    txn.commit()
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to commit transaction: {}", e)))
+}
+
+/// The entity node `id`, if `filter` lets it through.
+async fn read_entity(txn: &mut Txn, id: Uuid, filter: &str) -> Result<Option<Entity>> {
+    let cypher = format!("MATCH (n {{id: $id}}) WHERE {filter} RETURN n");
+    let mut stream = txn
+        .execute(query(&cypher).param("id", id.to_string()))
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to read entity: {}", e)))?;
+    match stream.next(txn.handle()).await {
+        Ok(Some(row)) => {
+            let node: Node = row
+                .get("n")
+                .map_err(|e| ArgusError::Graph(format!("Failed to deserialize node: {}", e)))?;
+            Ok(Some(node_to_entity(&node)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn write_tombstone(
+    txn: &mut Txn,
+    entity: &Entity,
+    deleted_at: DateTime<Utc>,
+    purged: bool,
+) -> Result<Tombstone> {
+    let q = query(WRITE_TOMBSTONE_CYPHER)
+        .param("id", entity.id.to_string())
+        .param("name", entity.name.clone())
+        .param(
+            "entity_type",
+            entity_type_to_label(&entity.entity_type).into_owned(),
+        )
+        .param("source", entity.source.clone())
+        .param("source_id", entity.source_id.clone().unwrap_or_default())
+        .param("deleted_at", deleted_at.to_rfc3339())
+        .param("purged", purged);
+    let mut stream = txn
+        .execute(q)
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to write tombstone: {}", e)))?;
+    let node: Node = match stream.next(txn.handle()).await {
+        Ok(Some(row)) => row
+            .get("t")
+            .map_err(|e| ArgusError::Graph(format!("Failed to deserialize tombstone: {}", e)))?,
+        _ => {
+            return Err(ArgusError::Graph(format!(
+                "Tombstone for {} was not written",
+                entity.id
+            )))
+        }
+    };
+    node_to_tombstone(&node)
+        .ok_or_else(|| ArgusError::Graph(format!("Malformed tombstone for {}", entity.id)))
+}
+
+fn lifecycle_change(entity_id: Uuid, kind: ChangeKind, at: DateTime<Utc>) -> EntityChange {
+    EntityChange {
+        id: Uuid::new_v4(),
+        entity_id,
+        kind,
+        field: None,
+        old_value: None,
+        new_value: None,
+        source: DELETE_SOURCE.to_string(),
+        document_id: None,
+        changed_at: at,
+    }
+}
+
+pub(crate) async fn soft_delete(graph: &Graph, id: Uuid) -> Result<Tombstone> {
+    let mut txn = start(graph).await?;
+    let entity = read_entity(&mut txn, id, &non_entity_filter())
+        .await?
+        .ok_or_else(|| ArgusError::NotFound(format!("Entity {id} not found")))?;
+
+    let now = Utc::now();
+    let q = query(MARK_DELETED_CYPHER)
+        .param("id", id.to_string())
+        .param("deleted_at", now.to_rfc3339());
+    run(&mut txn, q, "mark entity deleted").await?;
+    let tombstone = write_tombstone(&mut txn, &entity, now, false).await?;
+    crate::history::write_changes(&mut txn, &[lifecycle_change(id, ChangeKind::Deleted, now)])
+        .await?;
+    commit(txn).await?;
+
+    tracing::info!(entity_id = %id, name = %entity.name, "Soft-deleted entity");
+    Ok(tombstone)
+}
+
+pub(crate) async fn restore(graph: &Graph, id: Uuid) -> Result<Entity> {
+    let mut txn = start(graph).await?;
+    let mut stream = txn
+        .execute(query(RESTORE_CYPHER).param("id", id.to_string()))
+        .await
+        .map_err(|e| ArgusError::Graph(format!("Failed to restore entity: {}", e)))?;
+    let node: Node = match stream.next(txn.handle()).await {
+        Ok(Some(row)) => row
+            .get("n")
+            .map_err(|e| ArgusError::Graph(format!("Failed to deserialize node: {}", e)))?,
+        _ => return Err(ArgusError::NotFound(format!("No soft-deleted entity {id}"))),
+    };
+    let entity = node_to_entity(&node)?;
+
+    run(
+        &mut txn,
+        query(LIFT_TOMBSTONE_CYPHER).param("id", id.to_string()),
+        "lift tombstone",
+    )
+    .await?;
+    crate::history::write_changes(
+        &mut txn,
+        &[lifecycle_change(id, ChangeKind::Restored, Utc::now())],
+    )
+    .await?;
+    commit(txn).await?;
+
+    tracing::info!(entity_id = %id, name = %entity.name, "Restored entity");
+    Ok(entity)
+}
+
+pub(crate) async fn purge(graph: &Graph, id: Uuid) -> Result<Tombstone> {
+    let mut txn = start(graph).await?;
+    let entity = read_entity(&mut txn, id, &stored_entity_filter())
+        .await?
+        .ok_or_else(|| ArgusError::NotFound(format!("Entity {id} not found")))?;
+
+    let tombstone = write_tombstone(&mut txn, &entity, Utc::now(), true).await?;
+    run(
+        &mut txn,
+        query(PURGE_HISTORY_CYPHER).param("id", id.to_string()),
+        "delete entity history",
+    )
+    .await?;
+    run(
+        &mut txn,
+        query(PURGE_NODE_CYPHER).param("id", id.to_string()),
+        "delete entity",
+    )
+    .await?;
+    commit(txn).await?;
+
+    tracing::info!(entity_id = %id, name = %entity.name, "Purged entity");
+    Ok(tombstone)
+}
+
+pub(crate) async fn list(graph: &Graph, limit: usize) -> Result<Vec<Tombstone>> {
+    let mut stream = timed(graph.execute(query(LIST_CYPHER).param("limit", limit as i64)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to list tombstones: {}", e)))?;
+
+    let mut tombstones = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let Ok(node) = row.get::<Node>("t") else {
+            continue;
+        };
+        match node_to_tombstone(&node) {
+            Some(tombstone) => tombstones.push(tombstone),
+            None => tracing::warn!("Skipping malformed tombstone"),
+        }
+    }
+    Ok(tombstones)
+}
+
+fn node_to_tombstone(node: &Node) -> Option<Tombstone> {
+    let entity_id: String = node.get("entity_id").ok()?;
+    let deleted_at: String = node.get("deleted_at").ok()?;
+    Some(Tombstone {
+        entity_id: Uuid::parse_str(&entity_id).ok()?,
+        name: node.get("name").unwrap_or_default(),
+        entity_type: label_to_entity_type(&node.get::<String>("entity_type").unwrap_or_default()),
+        source: node.get("source").unwrap_or_default(),
+        source_id: node.get("source_id").ok(),
+        deleted_at: DateTime::parse_from_rfc3339(&deleted_at)
+            .ok()?
+            .with_timezone(&Utc),
+        purged: node.get("purged").unwrap_or(false),
+    })
+}
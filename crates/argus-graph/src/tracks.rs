@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use neo4rs::{query, BoltType, Graph, Node, Query};
+use uuid::Uuid;
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::tracks::PositionReport;
+
+use crate::store::{entity_type_to_label, label_to_entity_type, timed};
+
+/// Label for position reports. They carry the asset identifier rather than
+/// an edge, like change-log entries, so entity traversals never see them.
+pub(crate) use argus_core::tracks::POSITION_LABEL;
+
+/// Reports written per UNWIND batch.
+const WRITE_BATCH_SIZE: usize = 1000;
+
+const STORE_CYPHER: &str = "\
+    UNWIND $positions AS x \
+    MERGE (p:Position {asset: x.asset, observed_at: x.observed_at}) \
+    ON CREATE SET p.id = x.id, p.entity_type = x.entity_type, p.source = x.source, \
+      p.latitude = x.latitude, p.longitude = x.longitude, p.speed_mps = x.speed_mps, \
+      p.course = x.course, p.altitude_m = x.altitude_m, p.on_ground = x.on_ground, \
+      p.destination = x.destination";
+
+const BETWEEN_CYPHER: &str = "\
+    MATCH (p:Position) WHERE p.observed_at >= $since AND p.observed_at <= $until \
+    RETURN p ORDER BY p.observed_at LIMIT $limit";
+
+const ASSET_CYPHER: &str = "\
+    MATCH (p:Position) WHERE p.asset IN $assets \
+    RETURN p ORDER BY p.observed_at DESC LIMIT $limit";
+
+/// Create the indexes behind report deduplication and time-range reads.
+pub(crate) async fn ensure_schema(graph: &Graph) -> Result<()> {
+    let cypher = format!("CREATE INDEX position_asset IF NOT EXISTS FOR (p:{POSITION_LABEL}) ON (p.asset, p.observed_at)");
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create position index: {}", e)))?;
+
+    let cypher = format!("CREATE INDEX position_observed_at IF NOT EXISTS FOR (p:{POSITION_LABEL}) ON (p.observed_at)");
+    timed(graph.run(query(&cypher)))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to create position time index: {}", e)))
+}
+
+fn position_params(position: &PositionReport) -> HashMap<String, BoltType> {
+    HashMap::from([
+        ("id".to_string(), Uuid::new_v4().to_string().into()),
+        ("asset".to_string(), position.asset.clone().into()),
+        (
+            "entity_type".to_string(),
+            entity_type_to_label(&position.entity_type)
+                .into_owned()
+                .into(),
+        ),
+        ("source".to_string(), position.source.clone().into()),
+        ("latitude".to_string(), position.latitude.into()),
+        ("longitude".to_string(), position.longitude.into()),
+        (
+            "observed_at".to_string(),
+            position.observed_at.to_rfc3339().into(),
+        ),
+        ("speed_mps".to_string(), position.speed_mps.into()),
+        ("course".to_string(), position.course.into()),
+        ("altitude_m".to_string(), position.altitude_m.into()),
+        ("on_ground".to_string(), position.on_ground.into()),
+        (
+            "destination".to_string(),
+            position.destination.clone().into(),
+        ),
+    ])
+}
+
+#[tracing::instrument(name = "neo4j_transaction", skip_all, fields(db.system = "neo4j", positions = positions.len()))]
+pub(crate) async fn store_positions(graph: &Graph, positions: &[PositionReport]) -> Result<()> {
+    for batch in positions.chunks(WRITE_BATCH_SIZE) {
+        let params: Vec<HashMap<String, BoltType>> = batch.iter().map(position_params).collect();
+        timed(graph.run(query(STORE_CYPHER).param("positions", params)))
+            .await?
+            .map_err(|e| ArgusError::Graph(format!("Failed to store positions: {}", e)))?;
+    }
+    tracing::debug!(positions = positions.len(), "Stored position reports");
+    Ok(())
+}
+
+pub(crate) async fn positions_between(
+    graph: &Graph,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    limit: usize,
+) -> Result<Vec<PositionReport>> {
+    let q = query(BETWEEN_CYPHER)
+        .param("since", since.to_rfc3339())
+        .param("until", until.to_rfc3339())
+        .param("limit", limit as i64);
+    read_positions(graph, q).await
+}
+
+/// The latest `limit` reports for any of the assets, oldest first.
+pub(crate) async fn asset_positions(
+    graph: &Graph,
+    assets: &[String],
+    limit: usize,
+) -> Result<Vec<PositionReport>> {
+    let q = query(ASSET_CYPHER)
+        .param("assets", assets.to_vec())
+        .param("limit", limit as i64);
+    let mut positions = read_positions(graph, q).await?;
+    positions.reverse();
+    Ok(positions)
+}
+
+async fn read_positions(graph: &Graph, q: Query) -> Result<Vec<PositionReport>> {
+    let mut stream = timed(graph.execute(q))
+        .await?
+        .map_err(|e| ArgusError::Graph(format!("Failed to read positions: {}", e)))?;
+
+    let mut positions = Vec::new();
+    while let Ok(Some(row)) = stream.next().await {
+        let Ok(node) = row.get::<Node>("p") else {
+            continue;
+        };
+        match node_to_position(&node) {
+            Some(position) => positions.push(position),
+            None => tracing::warn!("Skipping malformed position node"),
+        }
+    }
+    Ok(positions)
+}
+
+fn node_to_position(node: &Node) -> Option<PositionReport> {
+    let observed_at: String = node.get("observed_at").ok()?;
+    Some(PositionReport {
+        asset: node.get("asset").ok()?,
+        entity_type: label_to_entity_type(&node.get::<String>("entity_type").ok()?),
+        source: node.get("source").unwrap_or_default(),
+        latitude: node.get("latitude").ok()?,
+        longitude: node.get("longitude").ok()?,
+        observed_at: DateTime::parse_from_rfc3339(&observed_at)
+            .ok()?
+            .with_timezone(&Utc),
+        speed_mps: node.get("speed_mps").ok(),
+        course: node.get("course").ok(),
+        altitude_m: node.get("altitude_m").ok(),
+        on_ground: node.get("on_ground").ok(),
+        destination: node.get("destination").ok(),
+    })
+}
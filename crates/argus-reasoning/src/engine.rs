@@ -1,16 +1,27 @@
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
 use tracing::{debug, info, instrument, warn};
 
 use argus_core::entity::Entity;
 use argus_core::error::{ArgusError, Result};
 use argus_core::graph::{GraphQuery, GraphStore};
-use argus_core::reasoning::{ReasoningEngine, ReasoningQuery, ReasoningResponse, ReasoningStep};
+use argus_core::llm::{with_retries, LlmCall, LlmCallLog, LlmError, LlmPurpose};
+use argus_core::natural_search::{parse_interpretation, SearchInterpretation};
+use argus_core::reasoning::{
+    ReasoningEngine, ReasoningQuery, ReasoningResponse, ReasoningStep, ReasoningUsage, StageTiming,
+    SummaryRequest,
+};
 use argus_core::AppConfig;
 
+use crate::guardrails::{enforce_limit, render_result, GraphSchema};
+
 // ---------------------------------------------------------------------------
 // Anthropic Messages API types
 // ---------------------------------------------------------------------------
@@ -35,6 +46,16 @@ struct AnthropicResponse {
     content: Vec<ContentBlock>,
     #[serde(default)]
     stop_reason: Option<String>,
+    #[serde(default)]
+    usage: Usage,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,9 +70,22 @@ struct ContentBlock {
 // Constants
 // ---------------------------------------------------------------------------
 
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const MODEL: &str = "claude-sonnet-4-5-20250929";
+/// Smaller model for turning a question into a structured search.
+const SEARCH_TRANSLATION_MODEL: &str = "claude-haiku-4-5-20251001";
 const MAX_REASONING_ITERATIONS: usize = 5;
+/// Smallest output allowance worth spending an LLM call on.
+const MIN_OUTPUT_TOKENS: u32 = 256;
+/// Generated Cypher queries in flight against Neo4j at once.
+const MAX_CONCURRENT_QUERIES: usize = 4;
+/// Longest one batch of generated queries may take, start to finish.
+const QUERY_STAGE_DEADLINE: Duration = Duration::from_secs(8);
+/// Rows a generated query may return; a LIMIT is added when missing.
+const GENERATED_QUERY_ROW_LIMIT: u64 = 100;
+/// Characters of each query result written into a prompt.
+const MAX_RESULT_CHARS: usize = 4000;
+/// How long introspected labels and relationship types are reused.
+const SCHEMA_CACHE_TTL: Duration = Duration::from_secs(300);
 
 // ---------------------------------------------------------------------------
 // Graph schema context used in prompts
@@ -77,6 +111,246 @@ Relationship types:
 All relationships carry: { confidence, source, timestamp, properties }
 "#;
 
+// ---------------------------------------------------------------------------
+// Per-request budget and accounting
+// ---------------------------------------------------------------------------
+
+/// Spending limits for one reasoning request; zero disables a limit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReasoningBudget {
+    pub max_tokens: u64,
+    pub max_llm_calls: u32,
+    pub max_duration_ms: u64,
+}
+
+impl ReasoningBudget {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            max_tokens: config.reasoning_max_tokens,
+            max_llm_calls: config.reasoning_max_llm_calls,
+            max_duration_ms: config.reasoning_max_duration_ms,
+        }
+    }
+}
+
+/// Tracks what one request has spent against its budget.
+struct Accounting {
+    budget: ReasoningBudget,
+    started: Instant,
+    stage_started: Instant,
+    usage: ReasoningUsage,
+}
+
+impl Accounting {
+    fn new(budget: ReasoningBudget) -> Self {
+        let now = Instant::now();
+        Self {
+            budget,
+            started: now,
+            stage_started: now,
+            usage: ReasoningUsage::default(),
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    fn tokens_spent(&self) -> u64 {
+        self.usage.input_tokens + self.usage.output_tokens
+    }
+
+    /// Close the current stage and start timing the next one.
+    fn end_stage(&mut self, stage: &str) {
+        let now = Instant::now();
+        self.usage.stages.push(StageTiming {
+            stage: stage.to_string(),
+            elapsed_ms: now.duration_since(self.stage_started).as_millis() as u64,
+        });
+        self.stage_started = now;
+    }
+
+    fn record_call(&mut self, usage: Usage) {
+        self.usage.llm_calls += 1;
+        self.usage.input_tokens += usage.input_tokens;
+        self.usage.output_tokens += usage.output_tokens;
+    }
+
+    fn time_limit_reason(&self) -> String {
+        format!("time limit of {}ms reached", self.budget.max_duration_ms)
+    }
+
+    /// The limit that rules out another LLM call, if any.
+    fn exhausted(&self) -> Option<String> {
+        let budget = &self.budget;
+        if budget.max_llm_calls > 0 && self.usage.llm_calls >= budget.max_llm_calls {
+            return Some(format!(
+                "LLM call limit of {} reached",
+                budget.max_llm_calls
+            ));
+        }
+        if budget.max_tokens > 0
+            && self.tokens_spent() + u64::from(MIN_OUTPUT_TOKENS) > budget.max_tokens
+        {
+            return Some(format!(
+                "token limit of {} reached ({} spent)",
+                budget.max_tokens,
+                self.tokens_spent()
+            ));
+        }
+        if budget.max_duration_ms > 0 && self.elapsed_ms() >= budget.max_duration_ms {
+            return Some(self.time_limit_reason());
+        }
+        None
+    }
+
+    /// Output tokens the next call may ask for, capped by what is left of
+    /// the token budget.
+    fn output_allowance(&self, wanted: u32) -> u32 {
+        if self.budget.max_tokens == 0 {
+            return wanted;
+        }
+        let left = self.budget.max_tokens.saturating_sub(self.tokens_spent());
+        wanted.min(u32::try_from(left).unwrap_or(u32::MAX))
+    }
+
+    fn remaining_time(&self) -> Option<Duration> {
+        (self.budget.max_duration_ms > 0).then(|| {
+            Duration::from_millis(
+                self.budget
+                    .max_duration_ms
+                    .saturating_sub(self.elapsed_ms()),
+            )
+        })
+    }
+
+    /// Deadline for one batch of graph queries, never past the request's
+    /// own time limit.
+    fn query_deadline(&self) -> Duration {
+        self.remaining_time()
+            .map_or(QUERY_STAGE_DEADLINE, |left| left.min(QUERY_STAGE_DEADLINE))
+    }
+
+    fn finish(mut self) -> ReasoningUsage {
+        self.usage.total_ms = self.elapsed_ms();
+        self.usage
+    }
+}
+
+/// A generated query, which takes no parameters.
+fn unbound(cypher: String) -> GraphQuery {
+    GraphQuery {
+        cypher,
+        params: serde_json::Value::Object(serde_json::Map::new()),
+    }
+}
+
+/// What became of one query.
+#[derive(Debug)]
+enum QueryOutcome {
+    Rows(serde_json::Value),
+    Failed(String),
+    /// Turned away by the guardrails without running.
+    Rejected(String),
+}
+
+impl QueryOutcome {
+    fn is_empty_or_failed(&self) -> bool {
+        match self {
+            QueryOutcome::Rows(val) => val.is_null() || val.as_array().is_some_and(Vec::is_empty),
+            QueryOutcome::Failed(_) | QueryOutcome::Rejected(_) => true,
+        }
+    }
+}
+
+/// Record each query's outcome as a step and append it to the prompt
+/// material in `summary`. `refined` marks queries from the refinement round.
+fn record_outcomes(
+    outcomes: &[(String, QueryOutcome)],
+    refined: bool,
+    steps: &mut Vec<ReasoningStep>,
+    summary: &mut String,
+) {
+    let (heading, kind) = if refined {
+        ("Refined Query", "refined Cypher query")
+    } else {
+        ("Query", "Cypher query")
+    };
+    let label = if refined { "Refined query" } else { "Query" };
+
+    for (i, (cypher, outcome)) in outcomes.iter().enumerate() {
+        let n = i + 1;
+        let (description, result_summary, result_str) = match outcome {
+            QueryOutcome::Rows(val) => {
+                let rows = val.as_array().map_or(1, Vec::len);
+                (
+                    format!("Executed {kind} {n}"),
+                    format!("{label} {n} returned {rows} rows"),
+                    render_result(val, MAX_RESULT_CHARS),
+                )
+            }
+            QueryOutcome::Failed(e) => (
+                format!("Executed {kind} {n}"),
+                format!("{label} {n} failed: {e}"),
+                format!("Error: {e}"),
+            ),
+            QueryOutcome::Rejected(reason) => (
+                format!("Rejected {kind} {n}"),
+                format!("{label} {n} was not run: {reason}"),
+                format!("Rejected before running: {reason}"),
+            ),
+        };
+
+        summary.push_str(&format!(
+            "--- {heading} {n} ---\nCypher: {cypher}\nResult:\n{result_str}\n\n"
+        ));
+        steps.push(ReasoningStep {
+            description,
+            cypher: Some(cypher.clone()),
+            result_summary,
+        });
+    }
+}
+
+/// Run `tasks` with at most `limit` in flight, stopping at `deadline`.
+/// Outputs come back in task order; `None` marks a task that panicked or
+/// had not finished by the deadline (unfinished tasks are aborted).
+async fn join_bounded<T, Fut>(tasks: Vec<Fut>, limit: usize, deadline: Duration) -> Vec<Option<T>>
+where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    let deadline = tokio::time::Instant::now() + deadline;
+    let mut outputs: Vec<Option<T>> = std::iter::repeat_with(|| None).take(tasks.len()).collect();
+    let mut pending = tasks.into_iter().enumerate();
+    let mut set = JoinSet::new();
+
+    for (i, task) in pending.by_ref().take(limit.max(1)) {
+        set.spawn(async move { (i, task.await) });
+    }
+
+    loop {
+        match tokio::time::timeout_at(deadline, set.join_next()).await {
+            Ok(Some(joined)) => {
+                match joined {
+                    Ok((i, output)) => outputs[i] = Some(output),
+                    Err(e) => warn!(error = %e, "query task failed"),
+                }
+                if let Some((i, task)) = pending.next() {
+                    set.spawn(async move { (i, task.await) });
+                }
+            }
+            Ok(None) => break,
+            Err(_) => {
+                set.abort_all();
+                break;
+            }
+        }
+    }
+
+    outputs
+}
+
 // ---------------------------------------------------------------------------
 // LlmReasoningEngine
 // ---------------------------------------------------------------------------
@@ -85,6 +359,14 @@ pub struct LlmReasoningEngine {
     client: Client,
     graph: Arc<dyn GraphStore>,
     api_key: String,
+    api_url: String,
+    budget: RwLock<ReasoningBudget>,
+    /// Retries of overloaded, rate-limited and failed calls.
+    max_retries: u32,
+    /// Where every call is recorded when `LLM_LOG_ENABLED` is set.
+    call_log: Option<Arc<dyn LlmCallLog>>,
+    /// Introspected schema and when it was loaded.
+    schema: Mutex<Option<(Instant, Arc<GraphSchema>)>>,
 }
 
 impl LlmReasoningEngine {
@@ -98,76 +380,222 @@ impl LlmReasoningEngine {
             client,
             graph,
             api_key: config.anthropic_api_key.clone(),
+            api_url: config.anthropic_api_url.clone(),
+            budget: RwLock::new(ReasoningBudget::from_config(config)),
+            max_retries: config.llm_max_retries,
+            call_log: None,
+            schema: Mutex::new(None),
         }
     }
 
+    /// Record every call made from now on to `log`.
+    pub fn with_call_log(mut self, log: Option<Arc<dyn LlmCallLog>>) -> Self {
+        self.call_log = log;
+        self
+    }
+
+    /// Apply `budget` to requests started from now on.
+    pub fn set_budget(&self, budget: ReasoningBudget) {
+        *self.budget.write().expect("budget lock poisoned") = budget;
+    }
+
+    fn budget(&self) -> ReasoningBudget {
+        *self.budget.read().expect("budget lock poisoned")
+    }
+
     // ------------------------------------------------------------------
     // Call the Anthropic Messages API
     // ------------------------------------------------------------------
 
+    #[instrument(name = "llm_request", skip_all, fields(otel.kind = "client", model = model, max_tokens = max_tokens))]
     async fn call_llm(
         &self,
+        model: &str,
         system: &str,
         messages: &[Message],
         max_tokens: u32,
-    ) -> Result<String> {
+    ) -> Result<(String, Usage)> {
         let request = AnthropicRequest {
-            model: MODEL.to_string(),
+            model: model.to_string(),
             max_tokens,
             messages: messages.to_vec(),
             system: Some(system.to_string()),
         };
 
-        debug!(model = MODEL, "sending request to Anthropic API");
+        debug!(model, "sending request to Anthropic API");
+
+        let started_at = Utc::now();
+        let started = Instant::now();
+        // Retries stay within the request's time budget, which `ask_model` enforces
+        let outcome = with_retries(self.max_retries, || async {
+            let resp = self
+                .client
+                .post(&self.api_url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("content-type", "application/json")
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| ArgusError::Llm(LlmError::transport(e)))?;
 
-        let resp = self
-            .client
-            .post(ANTHROPIC_API_URL)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| ArgusError::Reasoning(format!("HTTP request to Anthropic failed: {e}")))?;
+            if !resp.status().is_success() {
+                return Err(ArgusError::Llm(LlmError::from_response(resp).await));
+            }
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp
-                .text()
-                .await
-                .unwrap_or_else(|_| "<unreadable body>".into());
-            return Err(ArgusError::Reasoning(format!(
-                "Anthropic API returned {status}: {body}"
-            )));
+            resp.json().await.map_err(|e| {
+                ArgusError::Reasoning(format!("failed to parse Anthropic response: {e}"))
+            })
+        })
+        .await
+        .and_then(|api_resp: AnthropicResponse| {
+            let text = api_resp
+                .content
+                .into_iter()
+                .filter(|b| b.block_type == "text")
+                .map(|b| b.text)
+                .collect::<Vec<_>>()
+                .join("");
+
+            if text.is_empty() {
+                return Err(ArgusError::Reasoning(
+                    "Anthropic returned an empty response".into(),
+                ));
+            }
+
+            debug!(
+                stop_reason = ?api_resp.stop_reason,
+                response_len = text.len(),
+                input_tokens = api_resp.usage.input_tokens,
+                output_tokens = api_resp.usage.output_tokens,
+                "received Anthropic API response"
+            );
+            Ok((text, api_resp.usage))
+        });
+
+        if let Some(log) = &self.call_log {
+            let subject: String = messages
+                .first()
+                .map(|m| {
+                    m.content
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .chars()
+                        .take(120)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let prompt: Vec<String> = messages
+                .iter()
+                .map(|m| format!("{}: {}", m.role, m.content))
+                .collect();
+            log.record(LlmCall {
+                id: uuid::Uuid::new_v4(),
+                purpose: LlmPurpose::Reasoning,
+                model: model.to_string(),
+                subject,
+                system: system.to_string(),
+                prompt: prompt.join("\n\n"),
+                response: outcome.as_ref().ok().map(|(text, _)| text.clone()),
+                error: outcome.as_ref().err().map(|e| e.to_string()),
+                started_at,
+                duration_ms: started.elapsed().as_millis() as u64,
+            });
         }
 
-        let api_resp: AnthropicResponse = resp
-            .json()
-            .await
-            .map_err(|e| ArgusError::Reasoning(format!("failed to parse Anthropic response: {e}")))?;
+        outcome
+    }
 
-        let text = api_resp
-            .content
-            .into_iter()
-            .filter(|b| b.block_type == "text")
-            .map(|b| b.text)
-            .collect::<Vec<_>>()
-            .join("");
+    /// Call the LLM within what is left of the request's budget. `Ok(None)`
+    /// means the budget ran out; `acct` records which limit.
+    async fn ask(
+        &self,
+        acct: &mut Accounting,
+        system: &str,
+        messages: &[Message],
+        max_tokens: u32,
+    ) -> Result<Option<String>> {
+        self.ask_model(MODEL, acct, system, messages, max_tokens)
+            .await
+    }
 
-        if text.is_empty() {
-            return Err(ArgusError::Reasoning(
-                "Anthropic returned an empty response".into(),
-            ));
+    async fn ask_model(
+        &self,
+        model: &str,
+        acct: &mut Accounting,
+        system: &str,
+        messages: &[Message],
+        max_tokens: u32,
+    ) -> Result<Option<String>> {
+        if acct.usage.budget_exceeded.is_some() {
+            return Ok(None);
         }
+        if let Some(reason) = acct.exhausted() {
+            acct.usage.budget_exceeded = Some(reason);
+            return Ok(None);
+        }
+
+        let call = self.call_llm(model, system, messages, acct.output_allowance(max_tokens));
+        let outcome = match acct.remaining_time() {
+            Some(remaining) => match tokio::time::timeout(remaining, call).await {
+                Ok(outcome) => outcome,
+                Err(_) => {
+                    acct.usage.budget_exceeded = Some(acct.time_limit_reason());
+                    return Ok(None);
+                }
+            },
+            None => call.await,
+        };
 
-        debug!(
-            stop_reason = ?api_resp.stop_reason,
-            response_len = text.len(),
-            "received Anthropic API response"
+        let (text, usage) = outcome?;
+        acct.record_call(usage);
+        Ok(Some(text))
+    }
+
+    /// What is returned when the budget runs out: the raw results of
+    /// whatever queries ran, uninterpreted.
+    fn partial_response(
+        mut steps: Vec<ReasoningStep>,
+        results_summary: &str,
+        acct: Accounting,
+    ) -> ReasoningResponse {
+        let usage = acct.finish();
+        let reason = usage.budget_exceeded.clone().unwrap_or_default();
+
+        warn!(
+            reason = %reason,
+            llm_calls = usage.llm_calls,
+            input_tokens = usage.input_tokens,
+            output_tokens = usage.output_tokens,
+            total_ms = usage.total_ms,
+            "reasoning budget exceeded; returning partial answer"
         );
 
-        Ok(text)
+        let answer = if results_summary.is_empty() {
+            format!("Reasoning stopped before querying the graph: {reason}.")
+        } else {
+            format!(
+                "Reasoning stopped before the results could be interpreted: {reason}. \
+                 Raw query results:\n\n{}",
+                results_summary.trim_end()
+            )
+        };
+
+        steps.push(ReasoningStep {
+            description: "Stopped early: reasoning budget exceeded".to_string(),
+            cypher: None,
+            result_summary: reason,
+        });
+
+        ReasoningResponse {
+            answer,
+            confidence: 0.0,
+            steps,
+            entities_referenced: Vec::new(),
+            sources: Vec::new(),
+            usage,
+        }
     }
 
     // ------------------------------------------------------------------
@@ -195,6 +623,57 @@ impl LlmReasoningEngine {
         prompt
     }
 
+    /// Ask the LLM for Cypher answering the question. `Ok(None)` means the
+    /// budget ran out first.
+    async fn generate_queries(
+        &self,
+        query: &ReasoningQuery,
+        acct: &mut Accounting,
+        steps: &mut Vec<ReasoningStep>,
+    ) -> Result<Option<Vec<GraphQuery>>> {
+        let cypher_prompt =
+            self.build_cypher_generation_prompt(&query.question, query.context.as_deref());
+
+        let system = format!(
+            "You are a Neo4j Cypher expert for the ARGUS intelligence knowledge graph.\n{GRAPH_SCHEMA}"
+        );
+
+        let messages = vec![Message {
+            role: "user".to_string(),
+            content: cypher_prompt,
+        }];
+
+        let Some(cypher_response) = self.ask(acct, &system, &messages, 2048).await? else {
+            return Ok(None);
+        };
+        acct.end_stage("generate_cypher");
+
+        let cypher_queries = Self::extract_cypher_queries(&cypher_response);
+
+        info!(
+            num_queries = cypher_queries.len(),
+            "LLM generated Cypher queries"
+        );
+
+        steps.push(ReasoningStep {
+            description: "Generated Cypher queries from user question".to_string(),
+            cypher: if cypher_queries.is_empty() {
+                None
+            } else {
+                Some(cypher_queries.join(";\n"))
+            },
+            result_summary: format!("Generated {} Cypher queries", cypher_queries.len()),
+        });
+
+        if cypher_queries.is_empty() {
+            return Err(ArgusError::Reasoning(
+                "LLM did not produce any Cypher queries for the given question".into(),
+            ));
+        }
+
+        Ok(Some(cypher_queries.into_iter().map(unbound).collect()))
+    }
+
     fn extract_cypher_queries(response: &str) -> Vec<String> {
         let mut queries = Vec::new();
         let mut in_block = false;
@@ -255,30 +734,112 @@ impl LlmReasoningEngine {
 
     async fn execute_queries(
         &self,
-        queries: &[String],
-    ) -> Vec<(String, std::result::Result<serde_json::Value, String>)> {
-        let mut results = Vec::new();
-
-        for cypher in queries {
-            let graph_query = GraphQuery {
-                cypher: cypher.clone(),
-                params: serde_json::Value::Object(serde_json::Map::new()),
-            };
+        queries: &[GraphQuery],
+        deadline: Duration,
+    ) -> Vec<(String, QueryOutcome)> {
+        let tasks: Vec<_> = queries
+            .iter()
+            .map(|graph_query| {
+                let graph = Arc::clone(&self.graph);
+                let graph_query = graph_query.clone();
+                async move {
+                    debug!(cypher = %graph_query.cypher, "executing Cypher query on graph store");
+                    graph.execute_readonly_cypher(&graph_query).await
+                }
+            })
+            .collect();
 
-            debug!(cypher = %cypher, "executing Cypher query on graph store");
+        let outputs = join_bounded(tasks, MAX_CONCURRENT_QUERIES, deadline).await;
 
-            match self.graph.execute_cypher(&graph_query).await {
-                Ok(val) => {
-                    results.push((cypher.clone(), Ok(val)));
+        queries
+            .iter()
+            .map(|q| &q.cypher)
+            .zip(outputs)
+            .map(|(cypher, output)| {
+                let outcome = match output {
+                    Some(Ok(val)) => QueryOutcome::Rows(val),
+                    Some(Err(e)) => {
+                        warn!(cypher = %cypher, error = %e, "Cypher query execution failed");
+                        QueryOutcome::Failed(e.to_string())
+                    }
+                    None => {
+                        warn!(cypher = %cypher, "Cypher query did not finish before the stage deadline");
+                        QueryOutcome::Failed(format!("did not finish within {}ms", deadline.as_millis()))
+                    }
+                };
+                (cypher.clone(), outcome)
+            })
+            .collect()
+    }
+
+    /// Run LLM-generated queries behind the guardrails: queries naming
+    /// labels or relationship types the graph lacks are rejected unrun, and
+    /// the rest are capped at [`GENERATED_QUERY_ROW_LIMIT`] rows.
+    async fn execute_generated(
+        &self,
+        queries: &[GraphQuery],
+        deadline: Duration,
+    ) -> Vec<(String, QueryOutcome)> {
+        let schema = self.schema().await;
+        let mut outcomes: Vec<Option<(String, QueryOutcome)>> = Vec::with_capacity(queries.len());
+        let mut runnable = Vec::new();
+
+        for graph_query in queries {
+            let checked = schema
+                .as_ref()
+                .map_or(Ok(()), |s| s.check(&graph_query.cypher));
+            match checked {
+                Ok(()) => {
+                    runnable.push(GraphQuery {
+                        cypher: enforce_limit(&graph_query.cypher, GENERATED_QUERY_ROW_LIMIT),
+                        params: graph_query.params.clone(),
+                    });
+                    outcomes.push(None);
                 }
-                Err(e) => {
-                    warn!(cypher = %cypher, error = %e, "Cypher query execution failed");
-                    results.push((cypher.clone(), Err(e.to_string())));
+                Err(reason) => {
+                    info!(cypher = %graph_query.cypher, reason = %reason, "rejected generated Cypher query");
+                    outcomes.push(Some((
+                        graph_query.cypher.clone(),
+                        QueryOutcome::Rejected(reason),
+                    )));
                 }
             }
         }
 
-        results
+        let mut executed = self.execute_queries(&runnable, deadline).await.into_iter();
+        outcomes
+            .into_iter()
+            .filter_map(|outcome| outcome.or_else(|| executed.next()))
+            .collect()
+    }
+
+    /// The graph's labels and relationship types, reloaded after
+    /// [`SCHEMA_CACHE_TTL`]. `None` when introspection fails, in which case
+    /// generated queries run unchecked.
+    async fn schema(&self) -> Option<Arc<GraphSchema>> {
+        if let Some((loaded, schema)) = self
+            .schema
+            .lock()
+            .expect("schema cache lock poisoned")
+            .as_ref()
+        {
+            if loaded.elapsed() < SCHEMA_CACHE_TTL {
+                return Some(Arc::clone(schema));
+            }
+        }
+
+        match GraphSchema::load(self.graph.as_ref()).await {
+            Ok(schema) => {
+                let schema = Arc::new(schema);
+                *self.schema.lock().expect("schema cache lock poisoned") =
+                    Some((Instant::now(), Arc::clone(&schema)));
+                Some(schema)
+            }
+            Err(e) => {
+                warn!(error = %e, "could not introspect the graph schema; generated queries run unchecked");
+                None
+            }
+        }
     }
 
     // ------------------------------------------------------------------
@@ -315,8 +876,27 @@ impl LlmReasoningEngine {
         prompt
     }
 
+    /// Same answer format as the interpretation prompt, so the reply goes
+    /// through `parse_interpretation`.
+    fn build_summary_prompt(request: &SummaryRequest) -> String {
+        format!(
+            "You are an intelligence analyst using the ARGUS knowledge graph.\n\
+             \n\
+             {}\n\
+             \n\
+             Base the summary only on the material below:\n\n\
+             {}\n\n\
+             Your response MUST follow this exact format:\n\
+             \n\
+             ANSWER: <the summary>\n\
+             CONFIDENCE: <a number between 0.0 and 1.0 reflecting how well the material supports the summary>\n\
+             ENTITIES: <comma-separated list of entity names mentioned in the summary, or NONE>\n\
+             SOURCES: <comma-separated list of data source identifiers referenced, or NONE>",
+            request.instructions, request.material
+        )
+    }
+
     fn parse_interpretation(response: &str) -> (String, f64, Vec<String>, Vec<String>) {
-        let mut answer = String::new();
         let mut confidence = 0.5_f64;
         let mut entities = Vec::new();
         let mut sources = Vec::new();
@@ -342,13 +922,21 @@ impl LlmReasoningEngine {
                 current_section = Some("entities");
                 let v = rest.trim();
                 if !v.is_empty() && v.to_uppercase() != "NONE" {
-                    entities = v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+                    entities = v
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
                 }
             } else if let Some(rest) = trimmed.strip_prefix("SOURCES:") {
                 current_section = Some("sources");
                 let v = rest.trim();
                 if !v.is_empty() && v.to_uppercase() != "NONE" {
-                    sources = v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+                    sources = v
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
                 }
             } else if current_section == Some("answer") {
                 // Continuation lines of the answer
@@ -356,7 +944,7 @@ impl LlmReasoningEngine {
             }
         }
 
-        answer = answer_lines.join("\n").trim().to_string();
+        let mut answer = answer_lines.join("\n").trim().to_string();
 
         // Fallback: if parsing failed, use the full response as the answer
         if answer.is_empty() {
@@ -388,6 +976,65 @@ impl LlmReasoningEngine {
 
         resolved
     }
+
+    // ------------------------------------------------------------------
+    // Translate a question into a structured search
+    // ------------------------------------------------------------------
+
+    fn build_search_translation_prompt(question: &str, now: DateTime<Utc>) -> String {
+        format!(
+            "Translate the search below into a JSON object for the ARGUS knowledge graph.\n\
+             Today is {}.\n\
+             \n\
+             Fields (leave out any the search does not ask for):\n\
+             - \"query\": text the entity's name must contain; empty unless a name is given\n\
+             - \"entity_type\": one of person, organization, vessel, aircraft, location, event, \
+             document, transaction, sanction\n\
+             - \"properties\": property values to match, e.g. vessel_type, flag, callsign, \
+             origin_country, country, nationality\n\
+             - \"min_sanctions_exposure\": 0.0-1.0, where 1.0 means directly sanctioned\n\
+             - \"near\": {{\"place\", \"latitude\", \"longitude\", \"radius_km\"}} when the search names \
+             a place an asset was seen at\n\
+             - \"from\", \"to\": the time window as YYYY-MM-DD dates\n\
+             \n\
+             Answer with the JSON object only.\n\
+             \n\
+             Search: {question}",
+            now.format("%Y-%m-%d")
+        )
+    }
+
+    /// Read a plain-language search into a [`SearchInterpretation`] with
+    /// one call to a small model, within the reasoning budget.
+    #[instrument(skip_all, fields(question_len = question.len()))]
+    pub async fn interpret_search(
+        &self,
+        question: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(SearchInterpretation, ReasoningUsage)> {
+        let mut acct = Accounting::new(self.budget());
+        let system = "You translate search requests into structured filters. Reply with JSON only."
+            .to_string();
+        let messages = vec![Message {
+            role: "user".to_string(),
+            content: Self::build_search_translation_prompt(question, now),
+        }];
+
+        let Some(reply) = self
+            .ask_model(SEARCH_TRANSLATION_MODEL, &mut acct, &system, &messages, 512)
+            .await?
+        else {
+            let reason = acct.usage.budget_exceeded.clone().unwrap_or_default();
+            return Err(ArgusError::Reasoning(format!(
+                "search was not translated: {reason}"
+            )));
+        };
+        acct.end_stage("translate_search");
+
+        let interpretation = parse_interpretation(&reply)
+            .map_err(|e| ArgusError::Reasoning(format!("search was not translated: {e}")))?;
+        Ok((interpretation, acct.finish()))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -401,98 +1048,59 @@ impl ReasoningEngine for LlmReasoningEngine {
         info!(question = %query.question, "starting multi-step reasoning");
 
         let mut steps: Vec<ReasoningStep> = Vec::new();
+        let mut acct = Accounting::new(self.budget());
 
         // ------------------------------------------------------------
-        // Step 1: Generate Cypher queries from the user question
+        // Step 1: Generate Cypher queries from the user question, unless
+        //         a template supplied them
         // ------------------------------------------------------------
-        let cypher_prompt = self.build_cypher_generation_prompt(
-            &query.question,
-            query.context.as_deref(),
-        );
-
-        let system = format!(
-            "You are a Neo4j Cypher expert for the ARGUS intelligence knowledge graph.\n{GRAPH_SCHEMA}"
-        );
-
-        let messages = vec![Message {
-            role: "user".to_string(),
-            content: cypher_prompt,
-        }];
-
-        let cypher_response = self.call_llm(&system, &messages, 2048).await?;
-
-        let cypher_queries = Self::extract_cypher_queries(&cypher_response);
-
-        info!(
-            num_queries = cypher_queries.len(),
-            "LLM generated Cypher queries"
-        );
-
-        steps.push(ReasoningStep {
-            description: "Generated Cypher queries from user question".to_string(),
-            cypher: if cypher_queries.is_empty() {
-                None
-            } else {
-                Some(cypher_queries.join(";\n"))
-            },
-            result_summary: format!("Generated {} Cypher queries", cypher_queries.len()),
-        });
-
-        if cypher_queries.is_empty() {
-            return Err(ArgusError::Reasoning(
-                "LLM did not produce any Cypher queries for the given question".into(),
-            ));
-        }
+        let cypher_queries = if query.prepared.is_empty() {
+            let Some(generated) = self.generate_queries(query, &mut acct, &mut steps).await? else {
+                return Ok(Self::partial_response(steps, "", acct));
+            };
+            generated
+        } else {
+            info!(
+                num_queries = query.prepared.len(),
+                "using prepared Cypher queries"
+            );
+            steps.push(ReasoningStep {
+                description: "Used the template's prepared Cypher queries".to_string(),
+                cypher: Some(
+                    query
+                        .prepared
+                        .iter()
+                        .map(|q| q.cypher.as_str())
+                        .collect::<Vec<_>>()
+                        .join(";\n"),
+                ),
+                result_summary: format!("{} prepared queries", query.prepared.len()),
+            });
+            query.prepared.clone()
+        };
 
         // ------------------------------------------------------------
         // Step 2: Execute Cypher queries
         // ------------------------------------------------------------
-        let query_results = self.execute_queries(&cypher_queries).await;
+        let query_results = if query.prepared.is_empty() {
+            self.execute_generated(&cypher_queries, acct.query_deadline())
+                .await
+        } else {
+            self.execute_queries(&cypher_queries, acct.query_deadline())
+                .await
+        };
+        acct.end_stage("execute_queries");
 
         let mut steps_summary = String::new();
-
-        for (i, (cypher, result)) in query_results.iter().enumerate() {
-            let (summary, result_str) = match result {
-                Ok(val) => {
-                    let json_str = serde_json::to_string_pretty(val)
-                        .unwrap_or_else(|_| val.to_string());
-                    // Truncate very large results to avoid exceeding token limits
-                    let truncated = if json_str.len() > 4000 {
-                        format!("{}... [truncated, {} total chars]", &json_str[..4000], json_str.len())
-                    } else {
-                        json_str.clone()
-                    };
-                    (
-                        format!("Query {} returned results ({} chars)", i + 1, json_str.len()),
-                        truncated,
-                    )
-                }
-                Err(e) => (
-                    format!("Query {} failed: {}", i + 1, e),
-                    format!("Error: {e}"),
-                ),
-            };
-
-            steps_summary.push_str(&format!(
-                "--- Query {} ---\nCypher: {cypher}\nResult:\n{result_str}\n\n",
-                i + 1
-            ));
-
-            steps.push(ReasoningStep {
-                description: format!("Executed Cypher query {}", i + 1),
-                cypher: Some(cypher.clone()),
-                result_summary: summary,
-            });
-        }
+        record_outcomes(&query_results, false, &mut steps, &mut steps_summary);
 
         // ------------------------------------------------------------
         // Step 3: Iterative refinement — if all queries failed or
         //         returned empty results, let the LLM try again
         // ------------------------------------------------------------
-        let all_empty_or_failed = query_results.iter().all(|(_, r)| match r {
-            Ok(val) => val.is_null() || val == &serde_json::Value::Array(vec![]),
-            Err(_) => true,
-        });
+        let all_empty_or_failed = query_results
+            .iter()
+            .all(|(_, outcome)| outcome.is_empty_or_failed());
 
         let mut final_steps_summary = steps_summary.clone();
         let mut iteration = 0;
@@ -517,7 +1125,10 @@ impl ReasoningEngine for LlmReasoningEngine {
                 content: refinement_prompt,
             }];
 
-            if let Ok(refinement_resp) = self.call_llm(&refinement_system, &refinement_messages, 2048).await {
+            if let Ok(Some(refinement_resp)) = self
+                .ask(&mut acct, &refinement_system, &refinement_messages, 2048)
+                .await
+            {
                 let refined_queries = Self::extract_cypher_queries(&refinement_resp);
 
                 if !refined_queries.is_empty() {
@@ -527,49 +1138,27 @@ impl ReasoningEngine for LlmReasoningEngine {
                     );
 
                     steps.push(ReasoningStep {
-                        description: "Generated refined Cypher queries after initial results were empty".to_string(),
+                        description:
+                            "Generated refined Cypher queries after initial results were empty"
+                                .to_string(),
                         cypher: Some(refined_queries.join(";\n")),
-                        result_summary: format!("Generated {} refined queries", refined_queries.len()),
+                        result_summary: format!(
+                            "Generated {} refined queries",
+                            refined_queries.len()
+                        ),
                     });
 
-                    let refined_results = self.execute_queries(&refined_queries).await;
-
-                    for (i, (cypher, result)) in refined_results.iter().enumerate() {
-                        let (summary, result_str) = match result {
-                            Ok(val) => {
-                                let json_str = serde_json::to_string_pretty(val)
-                                    .unwrap_or_else(|_| val.to_string());
-                                let truncated = if json_str.len() > 4000 {
-                                    format!("{}... [truncated, {} total chars]", &json_str[..4000], json_str.len())
-                                } else {
-                                    json_str.clone()
-                                };
-                                (
-                                    format!("Refined query {} returned results ({} chars)", i + 1, json_str.len()),
-                                    truncated,
-                                )
-                            }
-                            Err(e) => (
-                                format!("Refined query {} failed: {}", i + 1, e),
-                                format!("Error: {e}"),
-                            ),
-                        };
-
-                        final_steps_summary.push_str(&format!(
-                            "--- Refined Query {} ---\nCypher: {cypher}\nResult:\n{result_str}\n\n",
-                            i + 1
-                        ));
-
-                        steps.push(ReasoningStep {
-                            description: format!("Executed refined Cypher query {}", i + 1),
-                            cypher: Some(cypher.clone()),
-                            result_summary: summary,
-                        });
-                    }
+                    let refined_queries: Vec<GraphQuery> =
+                        refined_queries.into_iter().map(unbound).collect();
+                    let refined_results = self
+                        .execute_generated(&refined_queries, acct.query_deadline())
+                        .await;
+                    record_outcomes(&refined_results, true, &mut steps, &mut final_steps_summary);
 
                     iteration += 1;
                 }
             }
+            acct.end_stage("refine");
         }
         // Suppress the unused assignment warning — `iteration` tracks refinement rounds and
         // would be read if the loop were extended.
@@ -592,7 +1181,13 @@ impl ReasoningEngine for LlmReasoningEngine {
             content: interpretation_prompt,
         }];
 
-        let interpretation = self.call_llm(&interp_system, &interp_messages, 4096).await?;
+        let Some(interpretation) = self
+            .ask(&mut acct, &interp_system, &interp_messages, 4096)
+            .await?
+        else {
+            return Ok(Self::partial_response(steps, &final_steps_summary, acct));
+        };
+        acct.end_stage("interpret");
 
         let (answer, confidence, entity_names, sources) =
             Self::parse_interpretation(&interpretation);
@@ -610,12 +1205,18 @@ impl ReasoningEngine for LlmReasoningEngine {
         // Step 5: Resolve entity names to Entity objects
         // ------------------------------------------------------------
         let entities_referenced = self.resolve_entities(&entity_names).await;
+        acct.end_stage("resolve_entities");
+        let usage = acct.finish();
 
         info!(
             answer_len = answer.len(),
             confidence = confidence,
             steps = steps.len(),
             entities = entities_referenced.len(),
+            llm_calls = usage.llm_calls,
+            input_tokens = usage.input_tokens,
+            output_tokens = usage.output_tokens,
+            total_ms = usage.total_ms,
             "reasoning complete"
         );
 
@@ -625,6 +1226,72 @@ impl ReasoningEngine for LlmReasoningEngine {
             steps,
             entities_referenced,
             sources,
+            usage,
+        })
+    }
+
+    #[instrument(skip_all, fields(material_len = request.material.len()))]
+    async fn summarize(&self, request: &SummaryRequest) -> Result<ReasoningResponse> {
+        info!("starting summary");
+
+        let mut acct = Accounting::new(self.budget());
+        let system = "You are an intelligence analyst. Summarize only what the material supports."
+            .to_string();
+        let messages = vec![Message {
+            role: "user".to_string(),
+            content: Self::build_summary_prompt(request),
+        }];
+
+        let Some(summary) = self.ask(&mut acct, &system, &messages, 4096).await? else {
+            let usage = acct.finish();
+            let reason = usage.budget_exceeded.clone().unwrap_or_default();
+            warn!(reason = %reason, "reasoning budget exceeded before the summary was written");
+            return Ok(ReasoningResponse {
+                answer: format!("The summary could not be written: {reason}."),
+                confidence: 0.0,
+                steps: vec![ReasoningStep {
+                    description: "Stopped early: reasoning budget exceeded".to_string(),
+                    cypher: None,
+                    result_summary: reason,
+                }],
+                entities_referenced: Vec::new(),
+                sources: Vec::new(),
+                usage,
+            });
+        };
+        acct.end_stage("summarize");
+
+        let (answer, confidence, entity_names, sources) = Self::parse_interpretation(&summary);
+        let steps = vec![ReasoningStep {
+            description: "Summarized the provided material".to_string(),
+            cypher: None,
+            result_summary: format!(
+                "Confidence: {confidence:.2}, entities referenced: {}",
+                entity_names.len()
+            ),
+        }];
+
+        let entities_referenced = self.resolve_entities(&entity_names).await;
+        acct.end_stage("resolve_entities");
+        let usage = acct.finish();
+
+        info!(
+            answer_len = answer.len(),
+            entities = entities_referenced.len(),
+            llm_calls = usage.llm_calls,
+            input_tokens = usage.input_tokens,
+            output_tokens = usage.output_tokens,
+            total_ms = usage.total_ms,
+            "summary complete"
+        );
+
+        Ok(ReasoningResponse {
+            answer,
+            confidence,
+            steps,
+            entities_referenced,
+            sources,
+            usage,
         })
     }
 }
@@ -696,6 +1363,31 @@ SOURCES: ofac_sdn, un_sanctions"#;
         assert!(sources.is_empty());
     }
 
+    #[test]
+    fn test_summary_prompt_includes_material_and_answer_format() {
+        let request = SummaryRequest {
+            instructions: "Write a daily briefing.".to_string(),
+            material: "ALERTS (1):\n- [High] sanctions: Acme Holdings listed".to_string(),
+        };
+        let prompt = LlmReasoningEngine::build_summary_prompt(&request);
+        assert!(prompt.contains("Write a daily briefing."));
+        assert!(prompt.contains("Acme Holdings listed"));
+        assert!(prompt.contains("ENTITIES: <comma-separated"));
+    }
+
+    #[test]
+    fn test_search_translation_prompt_dates_the_question() {
+        let now = DateTime::parse_from_rfc3339("2026-02-03T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let prompt = LlmReasoningEngine::build_search_translation_prompt(
+            "tankers near Ceuta in January",
+            now,
+        );
+        assert!(prompt.contains("Today is 2026-02-03."));
+        assert!(prompt.ends_with("Search: tankers near Ceuta in January"));
+    }
+
     #[test]
     fn test_parse_interpretation_clamps_confidence() {
         let response = "ANSWER: test\nCONFIDENCE: 1.5\nENTITIES: NONE\nSOURCES: NONE";
@@ -704,6 +1396,158 @@ SOURCES: ofac_sdn, un_sanctions"#;
         assert!((confidence - 1.0).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn test_budget_limits_calls_tokens_and_output() {
+        let mut acct = Accounting::new(ReasoningBudget {
+            max_tokens: 5000,
+            max_llm_calls: 2,
+            max_duration_ms: 0,
+        });
+        assert!(acct.exhausted().is_none());
+        assert_eq!(acct.output_allowance(4096), 4096);
+        assert!(acct.remaining_time().is_none());
+
+        acct.record_call(Usage {
+            input_tokens: 2500,
+            output_tokens: 500,
+        });
+        assert!(acct.exhausted().is_none());
+        assert_eq!(acct.output_allowance(4096), 2000);
+
+        acct.record_call(Usage {
+            input_tokens: 1000,
+            output_tokens: 100,
+        });
+        assert_eq!(
+            acct.exhausted().as_deref(),
+            Some("LLM call limit of 2 reached")
+        );
+
+        let mut tokens_only = Accounting::new(ReasoningBudget {
+            max_tokens: 1000,
+            ..Default::default()
+        });
+        tokens_only.record_call(Usage {
+            input_tokens: 800,
+            output_tokens: 0,
+        });
+        assert!(tokens_only
+            .exhausted()
+            .unwrap()
+            .starts_with("token limit of 1000"));
+
+        let unlimited = Accounting::new(ReasoningBudget::default());
+        assert!(unlimited.exhausted().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_join_bounded_runs_concurrently_and_keeps_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let task = |ms: u64| {
+            let (running, peak) = (Arc::clone(&running), Arc::clone(&peak));
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(ms)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                ms
+            }
+        };
+
+        // Virtual time: the sleeps overlap, so the batch takes as long as
+        // the slowest task
+        let started = tokio::time::Instant::now();
+        let outputs = join_bounded(
+            [30u64, 10, 20, 10].into_iter().map(task).collect(),
+            4,
+            Duration::from_secs(5),
+        )
+        .await;
+        assert_eq!(outputs, vec![Some(30), Some(10), Some(20), Some(10)]);
+        assert_eq!(started.elapsed(), Duration::from_millis(30));
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+
+        // Never more than the limit at once
+        peak.store(0, Ordering::SeqCst);
+        let outputs = join_bounded(
+            [10u64; 5].into_iter().map(task).collect(),
+            2,
+            Duration::from_secs(5),
+        )
+        .await;
+        assert_eq!(outputs, vec![Some(10); 5]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_join_bounded_abandons_tasks_past_the_deadline() {
+        let tasks: Vec<_> = [5u64, 5_000, 5]
+            .into_iter()
+            .map(|ms| async move {
+                tokio::time::sleep(Duration::from_millis(ms)).await;
+                ms
+            })
+            .collect();
+
+        // One slot: the slow second task holds it until the deadline, so the
+        // third never starts.
+        let outputs = join_bounded(tasks, 1, Duration::from_millis(100)).await;
+        assert_eq!(outputs, vec![Some(5), None, None]);
+    }
+
+    #[test]
+    fn test_partial_response_keeps_raw_results_and_usage() {
+        let mut acct = Accounting::new(ReasoningBudget::default());
+        acct.record_call(Usage {
+            input_tokens: 900,
+            output_tokens: 120,
+        });
+        acct.end_stage("generate_cypher");
+        acct.usage.budget_exceeded = Some("LLM call limit of 1 reached".to_string());
+
+        let response = LlmReasoningEngine::partial_response(
+            Vec::new(),
+            "--- Query 1 ---\nCypher: MATCH (n) RETURN n\nResult:\n[]\n\n",
+            acct,
+        );
+
+        assert!(response.answer.contains("LLM call limit of 1 reached"));
+        assert!(response.answer.contains("MATCH (n) RETURN n"));
+        assert_eq!(response.confidence, 0.0);
+        assert_eq!(response.steps.len(), 1);
+        assert_eq!(response.usage.llm_calls, 1);
+        assert_eq!(response.usage.input_tokens, 900);
+        assert_eq!(response.usage.stages[0].stage, "generate_cypher");
+        assert!(response.usage.budget_exceeded.is_some());
+    }
+
+    #[test]
+    fn test_rejected_queries_are_recorded_as_steps() {
+        let outcomes = vec![
+            (
+                "MATCH (c:Company) RETURN c".to_string(),
+                QueryOutcome::Rejected("unknown label :Company".to_string()),
+            ),
+            (
+                "MATCH (n) RETURN n LIMIT 100".to_string(),
+                QueryOutcome::Rows(serde_json::json!([{ "n": 1 }])),
+            ),
+        ];
+        let mut steps = Vec::new();
+        let mut summary = String::new();
+        record_outcomes(&outcomes, true, &mut steps, &mut summary);
+
+        assert_eq!(steps[0].description, "Rejected refined Cypher query 1");
+        assert!(steps[0].result_summary.contains("unknown label :Company"));
+        assert_eq!(steps[1].description, "Executed refined Cypher query 2");
+        assert_eq!(steps[1].result_summary, "Refined query 2 returned 1 rows");
+        assert!(summary.contains("Rejected before running: unknown label :Company"));
+        assert!(outcomes.iter().any(|(_, o)| !o.is_empty_or_failed()));
+    }
+
     #[test]
     fn test_parse_interpretation_multiline_answer() {
         let response = "ANSWER: Line one.\nLine two continues the answer.\nLine three as well.\nCONFIDENCE: 0.7\nENTITIES: NONE\nSOURCES: NONE";
@@ -0,0 +1,427 @@
+//! Checks applied to LLM-generated Cypher before it runs: labels and
+//! relationship types must exist in the graph, every result is capped with
+//! a LIMIT, and what goes back into the prompt is cut to size.
+
+use std::collections::BTreeSet;
+
+use argus_core::error::Result;
+use argus_core::graph::{GraphQuery, GraphStore};
+
+/// Labels and relationship types present in the graph.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GraphSchema {
+    pub labels: BTreeSet<String>,
+    pub relationship_types: BTreeSet<String>,
+}
+
+impl GraphSchema {
+    /// Introspect the graph's labels and relationship types.
+    pub async fn load(graph: &dyn GraphStore) -> Result<Self> {
+        let labels = column(graph, "CALL db.labels() YIELD label RETURN label", "label").await?;
+        let relationship_types = column(
+            graph,
+            "CALL db.relationshipTypes() YIELD relationshipType RETURN relationshipType",
+            "relationshipType",
+        )
+        .await?;
+        Ok(Self {
+            labels,
+            relationship_types,
+        })
+    }
+
+    /// Why `cypher` cannot match anything in this graph, if it names a
+    /// label or relationship type the graph does not have. A kind the graph
+    /// has none of yet is not checked.
+    pub fn check(&self, cypher: &str) -> std::result::Result<(), String> {
+        let (labels, types) = referenced_names(cypher);
+        let unknown = |names: &[String], known: &BTreeSet<String>| {
+            if known.is_empty() {
+                return None;
+            }
+            names.iter().find(|n| !known.contains(*n)).cloned()
+        };
+        if let Some(label) = unknown(&labels, &self.labels) {
+            return Err(format!(
+                "unknown label :{label} (known labels: {})",
+                join(&self.labels)
+            ));
+        }
+        if let Some(rel_type) = unknown(&types, &self.relationship_types) {
+            return Err(format!(
+                "unknown relationship type :{rel_type} (known types: {})",
+                join(&self.relationship_types)
+            ));
+        }
+        Ok(())
+    }
+}
+
+async fn column(graph: &dyn GraphStore, cypher: &str, key: &str) -> Result<BTreeSet<String>> {
+    let rows = graph
+        .execute_readonly_cypher(&GraphQuery {
+            cypher: cypher.to_string(),
+            params: serde_json::json!({}),
+        })
+        .await?;
+    Ok(rows
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| row.get(key).and_then(|v| v.as_str()).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn join(names: &BTreeSet<String>) -> String {
+    names
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A name, keyword or number; backtick-quoted names without the quotes.
+    Word(String),
+    Punct(char),
+    /// A string literal.
+    Literal,
+}
+
+/// Tokens with their byte ranges. Comments are dropped.
+fn tokenize(cypher: &str) -> Vec<(Token, usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut chars = cypher.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '\'' | '"' | '`' => {
+                let mut escaped = false;
+                let mut name = String::new();
+                let mut end = cypher.len();
+                for (i, inner) in chars.by_ref() {
+                    if escaped {
+                        escaped = false;
+                    } else if inner == '\\' && c != '`' {
+                        escaped = true;
+                    } else if inner == c {
+                        end = i + 1;
+                        break;
+                    }
+                    name.push(inner);
+                }
+                let token = if c == '`' {
+                    Token::Word(name)
+                } else {
+                    Token::Literal
+                };
+                tokens.push((token, start, end));
+            }
+            '/' if matches!(chars.peek(), Some((_, '/'))) => {
+                for (_, inner) in chars.by_ref() {
+                    if inner == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                let mut prev = ' ';
+                for (_, inner) in chars.by_ref() {
+                    if prev == '*' && inner == '/' {
+                        break;
+                    }
+                    prev = inner;
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    end = i + next.len_utf8();
+                    chars.next();
+                }
+                tokens.push((Token::Word(cypher[start..end].to_string()), start, end));
+            }
+            other => tokens.push((Token::Punct(other), start, start + other.len_utf8())),
+        }
+    }
+    tokens
+}
+
+/// Labels and relationship types a query names, in order of appearance.
+fn referenced_names(cypher: &str) -> (Vec<String>, Vec<String>) {
+    let tokens: Vec<Token> = tokenize(cypher).into_iter().map(|(t, _, _)| t).collect();
+    let mut labels = Vec::new();
+    let mut types = Vec::new();
+    // Open brackets; `true` marks a `[` that starts a relationship pattern
+    let mut open: Vec<(char, bool)> = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Punct(c @ ('(' | '{')) => open.push((*c, false)),
+            Token::Punct('[') => {
+                let relationship = i > 0 && tokens[i - 1] == Token::Punct('-');
+                open.push(('[', relationship));
+            }
+            Token::Punct(')' | '}' | ']') => {
+                open.pop();
+            }
+            Token::Punct(':') => {
+                let context = open.last().copied();
+                if context.is_some_and(|(c, _)| c == '{') {
+                    i += 1;
+                    continue;
+                }
+                let relationship = context == Some(('[', true));
+                // `A|B` alternatives are read in patterns only, where `|`
+                // cannot start a list comprehension's projection
+                let alternatives = relationship || context.is_some_and(|(c, _)| c == '(');
+                let out = if relationship {
+                    &mut types
+                } else {
+                    &mut labels
+                };
+                let mut j = i + 1;
+                loop {
+                    if tokens.get(j) == Some(&Token::Punct('!')) {
+                        j += 1;
+                    }
+                    match tokens.get(j) {
+                        Some(Token::Word(name)) => out.push(name.clone()),
+                        _ => break,
+                    }
+                    j += 1;
+                    let separator = matches!(tokens.get(j), Some(Token::Punct('|' | '&')));
+                    if !(alternatives && separator) {
+                        break;
+                    }
+                    j += 1;
+                    if tokens.get(j) == Some(&Token::Punct(':')) {
+                        j += 1;
+                    }
+                }
+                i = j;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    (labels, types)
+}
+
+/// `cypher` with every top-level RETURN capped at `max_rows`: a missing
+/// LIMIT is appended and a larger literal one lowered. Each part of a
+/// UNION is capped on its own; queries without a RETURN are left alone.
+pub(crate) fn enforce_limit(cypher: &str, max_rows: u64) -> String {
+    let cypher = cypher.trim().trim_end_matches(';').trim_end();
+    let tokens = tokenize(cypher);
+    let is_word =
+        |t: &Token, word: &str| matches!(t, Token::Word(w) if w.eq_ignore_ascii_case(word));
+
+    // Split the top-level token indices at UNION
+    let mut parts: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut depth = 0usize;
+    for (i, (token, _, _)) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(' | '[' | '{') => depth += 1,
+            Token::Punct(')' | ']' | '}') => depth = depth.saturating_sub(1),
+            t if depth == 0 && is_word(t, "UNION") => {
+                parts.push(Vec::new());
+                continue;
+            }
+            _ => {}
+        }
+        if depth == 0 {
+            parts.last_mut().expect("parts starts non-empty").push(i);
+        }
+    }
+
+    // (byte range, replacement), in order
+    let mut edits: Vec<(usize, usize, String)> = Vec::new();
+    for part in &parts {
+        let Some(ret) = part.iter().rposition(|&i| is_word(&tokens[i].0, "RETURN")) else {
+            continue;
+        };
+        let limit = part[ret..]
+            .iter()
+            .position(|&i| is_word(&tokens[i].0, "LIMIT"));
+        match limit {
+            Some(at) => {
+                let Some(&value) = part.get(ret + at + 1) else {
+                    continue;
+                };
+                let (Token::Word(n), start, end) = &tokens[value] else {
+                    continue;
+                };
+                if n.parse::<u64>().is_ok_and(|n| n > max_rows) {
+                    edits.push((*start, *end, max_rows.to_string()));
+                }
+            }
+            None => {
+                let end = part.last().map_or(0, |&i| tokens[i].2);
+                edits.push((end, end, format!(" LIMIT {max_rows}")));
+            }
+        }
+    }
+
+    let mut out = cypher.to_string();
+    for (start, end, replacement) in edits.into_iter().rev() {
+        out.replace_range(start..end, &replacement);
+    }
+    out
+}
+
+/// A query result as prompt text of at most about `max_chars`: one compact
+/// JSON row per line, with a note when rows were left out.
+pub(crate) fn render_result(value: &serde_json::Value, max_chars: usize) -> String {
+    let Some(rows) = value.as_array() else {
+        let text = value.to_string();
+        return match cut(&text, max_chars) {
+            Some(head) => format!("{head}... [truncated, {} total chars]", text.len()),
+            None => text,
+        };
+    };
+    if rows.is_empty() {
+        return "[]".to_string();
+    }
+
+    let mut out = String::new();
+    let mut shown = 0;
+    for row in rows {
+        let line = row.to_string();
+        if out.len() + line.len() + 1 > max_chars {
+            if shown == 0 {
+                if let Some(head) = cut(&line, max_chars) {
+                    out.push_str(&format!("{head}... [row truncated]\n"));
+                }
+                shown = 1;
+            }
+            break;
+        }
+        out.push_str(&line);
+        out.push('\n');
+        shown += 1;
+    }
+    if shown < rows.len() {
+        out.push_str(&format!("[{shown} of {} rows shown]\n", rows.len()));
+    }
+    out.trim_end().to_string()
+}
+
+/// The longest prefix of `text` within `max` bytes, if it had to be cut.
+fn cut(text: &str, max: usize) -> Option<&str> {
+    if text.len() <= max {
+        return None;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(&text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> GraphSchema {
+        GraphSchema {
+            labels: ["Person", "Organization", "Deleted"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            relationship_types: ["OWNER_OF", "DIRECTOR_OF"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn names_are_read_from_patterns_and_predicates() {
+        let (labels, types) = referenced_names(
+            "MATCH (p:Person {name: 'A:B'})-[r:OWNER_OF|:DIRECTOR_OF*1..2]->(o:`Organization`) \
+             WHERE NOT o:Deleted AND any(x IN [y IN nodes(p) WHERE y:Vessel | y.name] WHERE x <> '') \
+             // (n:Commented)\n\
+             RETURN p {.name, kind: 'x:y'} LIMIT 5",
+        );
+        assert_eq!(labels, vec!["Person", "Organization", "Deleted", "Vessel"]);
+        assert_eq!(types, vec!["OWNER_OF", "DIRECTOR_OF"]);
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        let schema = schema();
+        assert!(schema
+            .check("MATCH (p:Person)-[:OWNER_OF]->(o) RETURN o")
+            .is_ok());
+        let err = schema.check("MATCH (c:Company) RETURN c").unwrap_err();
+        assert!(
+            err.contains(":Company") && err.contains("Organization"),
+            "{err}"
+        );
+        let err = schema
+            .check("MATCH (p:Person)-[:OWNS]->(o) RETURN o")
+            .unwrap_err();
+        assert!(err.contains("relationship type :OWNS"), "{err}");
+        assert!(GraphSchema::default()
+            .check("MATCH (c:Company) RETURN c")
+            .is_ok());
+    }
+
+    #[test]
+    fn limits_are_appended_or_lowered() {
+        assert_eq!(
+            enforce_limit("MATCH (n) RETURN n;", 100),
+            "MATCH (n) RETURN n LIMIT 100"
+        );
+        assert_eq!(
+            enforce_limit("MATCH (n) RETURN n LIMIT 10", 100),
+            "MATCH (n) RETURN n LIMIT 10"
+        );
+        assert_eq!(
+            enforce_limit("MATCH (n) RETURN n ORDER BY n.name limit 5000", 100),
+            "MATCH (n) RETURN n ORDER BY n.name limit 100"
+        );
+        assert_eq!(
+            enforce_limit(
+                "MATCH (n) CALL { WITH n RETURN n AS m LIMIT 1 } RETURN m",
+                100
+            ),
+            "MATCH (n) CALL { WITH n RETURN n AS m LIMIT 1 } RETURN m LIMIT 100"
+        );
+        assert_eq!(
+            enforce_limit("MATCH (a:Person) RETURN a.name AS name UNION MATCH (b) RETURN b.name AS name", 50),
+            "MATCH (a:Person) RETURN a.name AS name LIMIT 50 UNION MATCH (b) RETURN b.name AS name LIMIT 50"
+        );
+        assert_eq!(enforce_limit("CALL db.labels()", 100), "CALL db.labels()");
+    }
+
+    #[test]
+    fn results_are_capped_by_row() {
+        let rows: Vec<_> = (0..100)
+            .map(|i| json!({ "name": format!("entity {i}") }))
+            .collect();
+        let text = render_result(&json!(rows), 200);
+        assert!(text.len() < 240, "{text}");
+        assert!(text.starts_with(r#"{"name":"entity 0"}"#));
+        assert!(text.ends_with("of 100 rows shown]"));
+
+        let wide = json!([{ "text": "é".repeat(500) }]);
+        let text = render_result(&wide, 101);
+        assert!(text.ends_with("... [row truncated]"));
+
+        assert_eq!(render_result(&json!([]), 100), "[]");
+        assert_eq!(render_result(&json!([{ "n": 1 }]), 100), r#"{"n":1}"#);
+    }
+}
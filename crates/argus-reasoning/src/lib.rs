@@ -1,3 +1,6 @@
 mod engine;
+mod guardrails;
+pub mod provenance;
+pub mod templates;
 
-pub use engine::LlmReasoningEngine;
+pub use engine::{LlmReasoningEngine, ReasoningBudget};
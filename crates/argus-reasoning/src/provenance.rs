@@ -0,0 +1,187 @@
+//! Reasoning answers recorded in the graph, so earlier conclusions can be
+//! searched and traversed like any other document.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use argus_core::entity::{Entity, EntityType, ExtractionResult, RelationType, Relationship};
+use argus_core::reasoning::{ReasoningQuery, ReasoningResponse};
+
+/// Source tag on every node and edge written for a reasoning answer.
+pub const REASONING_SOURCE: &str = "reasoning";
+
+/// Namespace for answer and link ids, which are derived from the question.
+const ID_NAMESPACE: Uuid = Uuid::from_u128(0x9c2d_51a7_3e8b_4f60_b1d4_7a0e_c5f3_2896);
+
+/// Longest question kept as the document's name; the full text stays in
+/// its properties.
+const MAX_NAME_CHARS: usize = 200;
+
+/// Questions asked again map to the same document, whatever the case and
+/// spacing.
+fn question_key(question: &str) -> String {
+    question
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Id of the document recording answers to `question`.
+pub fn answer_document_id(question: &str) -> Uuid {
+    Uuid::new_v5(
+        &ID_NAMESPACE,
+        format!("answer:{}", question_key(question)).as_bytes(),
+    )
+}
+
+/// A reasoning answer as a Document entity, linked RELATED_TO each entity
+/// it referenced. Asking the same question again updates the document with
+/// the newer answer.
+pub fn answer_extraction(
+    query: &ReasoningQuery,
+    response: &ReasoningResponse,
+    asked_at: DateTime<Utc>,
+) -> ExtractionResult {
+    let key = question_key(&query.question);
+    let id = answer_document_id(&query.question);
+    let name: String = query
+        .question
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let name = match name.char_indices().nth(MAX_NAME_CHARS) {
+        Some((cut, _)) => format!("{}…", &name[..cut]),
+        None => name,
+    };
+    let queries: Vec<&str> = response
+        .steps
+        .iter()
+        .filter_map(|s| s.cypher.as_deref())
+        .collect();
+
+    let document = Entity {
+        id,
+        entity_type: EntityType::Document,
+        name,
+        aliases: vec![],
+        properties: json!({
+            "kind": "reasoning_answer",
+            "question": query.question,
+            "context": query.context,
+            "answer": response.answer,
+            "confidence": response.confidence,
+            "cypher_queries": queries,
+            "sources": response.sources,
+            "asked_at": asked_at.to_rfc3339(),
+        }),
+        source: REASONING_SOURCE.to_string(),
+        source_id: Some(key),
+        confidence: response.confidence,
+        first_seen: asked_at,
+        last_seen: asked_at,
+        observed_at: None,
+        sanctions_exposure: None,
+        completeness: None,
+        mention_count: None,
+        geometry: None,
+        identifiers: Default::default(),
+    };
+
+    let relationships = response
+        .entities_referenced
+        .iter()
+        .filter(|entity| entity.id != id)
+        .map(|entity| Relationship {
+            id: Uuid::new_v5(&ID_NAMESPACE, format!("{id}:{}", entity.id).as_bytes()),
+            source_entity_id: id,
+            target_entity_id: entity.id,
+            relation_type: RelationType::RelatedTo,
+            properties: json!({ "source": REASONING_SOURCE }),
+            confidence: response.confidence,
+            source: REASONING_SOURCE.to_string(),
+            timestamp: Some(asked_at),
+            strength: None,
+        })
+        .collect();
+
+    ExtractionResult {
+        entities: vec![document],
+        relationships,
+        raw_source: format!("{REASONING_SOURCE}:{id}"),
+        extracted_at: asked_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::reasoning::{ReasoningStep, ReasoningUsage};
+
+    fn referenced(name: &str) -> Entity {
+        Entity {
+            id: Uuid::new_v4(),
+            entity_type: EntityType::Organization,
+            name: name.to_string(),
+            aliases: vec![],
+            properties: json!({}),
+            source: "opensanctions".to_string(),
+            source_id: None,
+            confidence: 1.0,
+            first_seen: Utc::now(),
+            last_seen: Utc::now(),
+            observed_at: None,
+            sanctions_exposure: None,
+            completeness: None,
+            mention_count: None,
+            geometry: None,
+            identifiers: Default::default(),
+        }
+    }
+
+    #[test]
+    fn answers_become_documents_linked_to_referenced_entities() {
+        let query = ReasoningQuery {
+            question: "Who  owns Acme Ltd?".to_string(),
+            context: None,
+            max_hops: None,
+            prepared: Vec::new(),
+        };
+        let (acme, owner) = (referenced("Acme Ltd"), referenced("Holdco"));
+        let response = ReasoningResponse {
+            answer: "Holdco owns Acme Ltd.".to_string(),
+            confidence: 0.8,
+            steps: vec![ReasoningStep {
+                description: "Find owners".to_string(),
+                cypher: Some("MATCH (o)-[:OWNER_OF]->(a) RETURN o".to_string()),
+                result_summary: "1 row".to_string(),
+            }],
+            entities_referenced: vec![acme.clone(), owner.clone()],
+            sources: vec!["opensanctions".to_string()],
+            usage: ReasoningUsage::default(),
+        };
+
+        let result = answer_extraction(&query, &response, Utc::now());
+        let document = &result.entities[0];
+        assert_eq!(document.entity_type, EntityType::Document);
+        assert_eq!(document.name, "Who owns Acme Ltd?");
+        assert_eq!(document.id, answer_document_id("who owns acme ltd?"));
+        assert_eq!(document.properties["answer"], "Holdco owns Acme Ltd.");
+        assert_eq!(
+            document.properties["cypher_queries"][0],
+            "MATCH (o)-[:OWNER_OF]->(a) RETURN o"
+        );
+
+        let targets: Vec<Uuid> = result
+            .relationships
+            .iter()
+            .map(|r| r.target_entity_id)
+            .collect();
+        assert_eq!(targets, vec![acme.id, owner.id]);
+        assert!(result
+            .relationships
+            .iter()
+            .all(|r| r.relation_type == RelationType::RelatedTo && r.source == REASONING_SOURCE));
+    }
+}
@@ -0,0 +1,366 @@
+//! Named question templates: formulaic questions that expand into a fixed
+//! prompt and pre-built Cypher, so the engine skips query generation.
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use argus_core::api_types::{ReasoningTemplate, ReasoningTemplateParam};
+use argus_core::graph::GraphQuery;
+use argus_core::reasoning::ReasoningQuery;
+
+/// A parameter a template takes.
+#[derive(Debug, Clone, Copy)]
+pub struct TemplateParam {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+/// A named question and the parameters it takes.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: &'static [TemplateParam],
+}
+
+impl QueryTemplate {
+    /// The template as listed by the API.
+    pub fn describe(&self) -> ReasoningTemplate {
+        ReasoningTemplate {
+            name: self.name.to_string(),
+            description: self.description.to_string(),
+            params: self
+                .params
+                .iter()
+                .map(|p| ReasoningTemplateParam {
+                    name: p.name.to_string(),
+                    description: p.description.to_string(),
+                    required: p.required,
+                })
+                .collect(),
+        }
+    }
+}
+
+const ENTITY_ID: TemplateParam = TemplateParam {
+    name: "entity_id",
+    description: "Id of the entity",
+    required: true,
+};
+
+const MAX_HOPS: TemplateParam = TemplateParam {
+    name: "max_hops",
+    description: "Longest path searched, 1-4 (default 3)",
+    required: false,
+};
+
+pub const TEMPLATES: &[QueryTemplate] = &[
+    QueryTemplate {
+        name: "entity_summary",
+        description: "Who or what an entity is: its sources, most important relationships and sanctions exposure",
+        params: &[ENTITY_ID],
+    },
+    QueryTemplate {
+        name: "sanctions_connections",
+        description: "Shortest paths from an entity to sanctioned entities",
+        params: &[ENTITY_ID, MAX_HOPS],
+    },
+    QueryTemplate {
+        name: "connections_between",
+        description: "Shortest paths between two entities",
+        params: &[
+            TemplateParam {
+                name: "from_id",
+                description: "Id of the first entity",
+                required: true,
+            },
+            TemplateParam {
+                name: "to_id",
+                description: "Id of the second entity",
+                required: true,
+            },
+            MAX_HOPS,
+        ],
+    },
+    QueryTemplate {
+        name: "recent_activity",
+        description: "Relationships an entity gained recently",
+        params: &[
+            ENTITY_ID,
+            TemplateParam {
+                name: "days",
+                description: "How many days back, 1-365 (default 30)",
+                required: false,
+            },
+        ],
+    },
+];
+
+const DEFAULT_MAX_HOPS: u64 = 3;
+const MAX_TEMPLATE_HOPS: u64 = 4;
+const DEFAULT_DAYS: u64 = 30;
+const MAX_DAYS: u64 = 365;
+
+const ENTITY_CYPHER: &str = "\
+    MATCH (n {id: $entity_id}) WHERE NOT n:Deleted \
+    RETURN n.name AS name, labels(n) AS labels, n.aliases AS aliases, n.source AS source, \
+      n.sources AS sources, n.confidence AS confidence, n.sanctions_exposure AS sanctions_exposure, \
+      n.first_seen AS first_seen, n.last_seen AS last_seen, n.properties AS properties";
+
+const NEIGHBOR_CYPHER: &str = "\
+    MATCH (n {id: $entity_id})-[r]-(m) WHERE NOT m:Deleted \
+    RETURN type(r) AS relation, startNode(r) = n AS outgoing, m.name AS other, labels(m) AS other_labels, \
+      r.confidence AS confidence, r.source AS source \
+    ORDER BY r.confidence DESC LIMIT 50";
+
+const RECENT_CYPHER: &str = "\
+    MATCH (n {id: $entity_id})-[r]-(m) WHERE r.created_at >= $since AND NOT m:Deleted \
+    RETURN type(r) AS relation, startNode(r) = n AS outgoing, m.name AS other, labels(m) AS other_labels, \
+      r.source AS source, r.created_at AS created_at, r.timestamp AS timestamp \
+    ORDER BY r.created_at DESC LIMIT 50";
+
+const ENDPOINTS_CYPHER: &str = "\
+    MATCH (n) WHERE n.id IN [$from_id, $to_id] \
+    RETURN n.id AS id, n.name AS name, labels(n) AS labels";
+
+fn sanctions_cypher(hops: u64) -> String {
+    format!(
+        "MATCH (n {{id: $entity_id}}) \
+         MATCH (s) WHERE s <> n AND NOT s:Deleted AND (s:Sanction OR (s)-[:SANCTIONED_BY]->()) \
+         MATCH p = shortestPath((n)-[*1..{hops}]-(s)) \
+         WHERE NONE(x IN nodes(p) WHERE x:Deleted) \
+         RETURN s.name AS sanctioned, labels(s) AS labels, length(p) AS hops, \
+           [x IN nodes(p) | x.name] AS path, [r IN relationships(p) | type(r)] AS relations \
+         ORDER BY hops LIMIT 25"
+    )
+}
+
+fn paths_cypher(hops: u64) -> String {
+    format!(
+        "MATCH (a {{id: $from_id}}), (b {{id: $to_id}}) \
+         MATCH p = allShortestPaths((a)-[*1..{hops}]-(b)) \
+         WHERE NONE(x IN nodes(p) WHERE x:Deleted) \
+         RETURN [x IN nodes(p) | x.name] AS path, [r IN relationships(p) | type(r)] AS relations, \
+           length(p) AS hops \
+         LIMIT 20"
+    )
+}
+
+fn id_param(params: &Map<String, Value>, name: &str) -> Result<Uuid, String> {
+    let value = params
+        .get(name)
+        .ok_or_else(|| format!("Missing template parameter '{name}'"))?;
+    value
+        .as_str()
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| format!("Template parameter '{name}' must be an entity id"))
+}
+
+fn count_param(
+    params: &Map<String, Value>,
+    name: &str,
+    default: u64,
+    max: u64,
+) -> Result<u64, String> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(default),
+        Some(value) => value
+            .as_u64()
+            .filter(|n| (1..=max).contains(n))
+            .ok_or_else(|| {
+                format!("Template parameter '{name}' must be a whole number from 1 to {max}")
+            }),
+    }
+}
+
+fn prepared(cypher: impl Into<String>, params: Value) -> GraphQuery {
+    GraphQuery {
+        cypher: cypher.into(),
+        params,
+    }
+}
+
+/// Expand the template `name` with `params` into a query whose Cypher is
+/// already written. Unknown templates, missing or malformed parameters and
+/// parameters the template does not take are errors.
+pub fn expand(
+    name: &str,
+    params: &Map<String, Value>,
+    context: Option<String>,
+    now: DateTime<Utc>,
+) -> Result<ReasoningQuery, String> {
+    let template = TEMPLATES
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| format!("Unknown template '{name}'"))?;
+    if let Some(unknown) = params
+        .keys()
+        .find(|k| !template.params.iter().any(|p| p.name == k.as_str()))
+    {
+        return Err(format!(
+            "Template '{name}' does not take parameter '{unknown}'"
+        ));
+    }
+
+    let (question, max_hops, prepared) = match name {
+        "entity_summary" => {
+            let id = id_param(params, "entity_id")?;
+            let bind = json!({ "entity_id": id.to_string() });
+            (
+                format!(
+                    "Summarize entity {id}: who or what it is, which sources report it, its most \
+                     important relationships, and any sanctions exposure."
+                ),
+                None,
+                vec![
+                    prepared(ENTITY_CYPHER, bind.clone()),
+                    prepared(NEIGHBOR_CYPHER, bind),
+                ],
+            )
+        }
+        "sanctions_connections" => {
+            let id = id_param(params, "entity_id")?;
+            let hops = count_param(params, "max_hops", DEFAULT_MAX_HOPS, MAX_TEMPLATE_HOPS)?;
+            let bind = json!({ "entity_id": id.to_string() });
+            (
+                format!(
+                    "How is entity {id} connected to sanctioned entities within {hops} hops? Name \
+                     each sanctioned entity and the chain of relationships leading to it."
+                ),
+                Some(hops as u32),
+                vec![
+                    prepared(ENTITY_CYPHER, bind.clone()),
+                    prepared(sanctions_cypher(hops), bind),
+                ],
+            )
+        }
+        "connections_between" => {
+            let from = id_param(params, "from_id")?;
+            let to = id_param(params, "to_id")?;
+            let hops = count_param(params, "max_hops", DEFAULT_MAX_HOPS, MAX_TEMPLATE_HOPS)?;
+            let bind = json!({ "from_id": from.to_string(), "to_id": to.to_string() });
+            (
+                format!(
+                    "How are entities {from} and {to} connected within {hops} hops? Describe each \
+                     path and what its relationships imply."
+                ),
+                Some(hops as u32),
+                vec![
+                    prepared(ENDPOINTS_CYPHER, bind.clone()),
+                    prepared(paths_cypher(hops), bind),
+                ],
+            )
+        }
+        "recent_activity" => {
+            let id = id_param(params, "entity_id")?;
+            let days = count_param(params, "days", DEFAULT_DAYS, MAX_DAYS)?;
+            let since = now - Duration::days(days as i64);
+            (
+                format!("What relationships has entity {id} gained in the last {days} days, and what do they indicate?"),
+                None,
+                vec![
+                    prepared(ENTITY_CYPHER, json!({ "entity_id": id.to_string() })),
+                    prepared(
+                        RECENT_CYPHER,
+                        json!({ "entity_id": id.to_string(), "since": since.to_rfc3339() }),
+                    ),
+                ],
+            )
+        }
+        _ => unreachable!("every listed template is expanded"),
+    };
+
+    Ok(ReasoningQuery {
+        question,
+        context,
+        max_hops,
+        prepared,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn every_template_expands_into_prepared_cypher() {
+        let id = Uuid::new_v4().to_string();
+        for template in TEMPLATES {
+            let values: Map<String, Value> = template
+                .params
+                .iter()
+                .filter(|p| p.required)
+                .map(|p| (p.name.to_string(), json!(id)))
+                .collect();
+            let query = expand(template.name, &values, None, Utc::now()).unwrap();
+            assert!(!query.prepared.is_empty(), "{}", template.name);
+            assert!(
+                query.prepared.iter().all(|q| q.write_clause().is_none()),
+                "{}",
+                template.name
+            );
+        }
+    }
+
+    #[test]
+    fn parameters_are_checked() {
+        let now = Utc::now();
+        assert!(expand("entity_summary", &Map::new(), None, now)
+            .unwrap_err()
+            .contains("Missing"));
+        assert!(expand(
+            "entity_summary",
+            &params(json!({ "entity_id": "acme" })),
+            None,
+            now
+        )
+        .is_err());
+        assert!(expand("nope", &Map::new(), None, now)
+            .unwrap_err()
+            .contains("Unknown template"));
+
+        let id = Uuid::new_v4().to_string();
+        let extra = params(json!({ "entity_id": id, "depth": 2 }));
+        assert!(expand("entity_summary", &extra, None, now)
+            .unwrap_err()
+            .contains("'depth'"));
+        let too_far = params(json!({ "entity_id": id, "max_hops": 9 }));
+        assert!(expand("sanctions_connections", &too_far, None, now).is_err());
+
+        let query = expand(
+            "sanctions_connections",
+            &params(json!({ "entity_id": id, "max_hops": 2 })),
+            None,
+            now,
+        )
+        .unwrap();
+        assert_eq!(query.max_hops, Some(2));
+        assert!(query.prepared[1].cypher.contains("[*1..2]"));
+        assert_eq!(query.prepared[1].params["entity_id"], json!(id));
+    }
+
+    #[test]
+    fn recent_activity_binds_the_window_start() {
+        let now = DateTime::parse_from_rfc3339("2026-03-31T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let id = Uuid::new_v4().to_string();
+        let query = expand(
+            "recent_activity",
+            &params(json!({ "entity_id": id, "days": 7 })),
+            None,
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            query.prepared[1].params["since"],
+            "2026-03-24T12:00:00+00:00"
+        );
+    }
+}
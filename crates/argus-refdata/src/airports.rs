@@ -0,0 +1,85 @@
+//! Airports from OurAirports (`airports.csv`).
+
+use serde_json::json;
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::places::{self, PlaceKind};
+use argus_core::Entity;
+
+use crate::csv_table::Table;
+
+/// Airport types kept; small airfields, heliports and closed airports are
+/// far more numerous and rarely matter for tracking.
+const KEPT_TYPES: [&str; 2] = ["large_airport", "medium_airport"];
+
+/// One Location per large or medium airport, keyed on its ident (the ICAO
+/// code where it has one).
+pub fn parse(body: &[u8]) -> Result<Vec<Entity>> {
+    let table = Table::read(body, "OurAirports")?;
+    let ident = table.column(&["ident"])?;
+    let kind = table.column(&["type"])?;
+    let name = table.column(&["name"])?;
+    let latitude = table.column(&["latitude_deg"])?;
+    let longitude = table.column(&["longitude_deg"])?;
+    let elevation = table.optional_column(&["elevation_ft"]);
+    let country = table.optional_column(&["iso_country"]);
+    let municipality = table.optional_column(&["municipality"]);
+    let iata = table.optional_column(&["iata_code"]);
+
+    let mut airports = Vec::new();
+    for row in table.rows() {
+        let Some(airport_type) = row.get(kind).filter(|t| KEPT_TYPES.contains(t)) else {
+            continue;
+        };
+        let (Some(code), Some(airport_name), Some(lat), Some(lon)) = (
+            row.get(ident),
+            row.get(name),
+            row.number(latitude),
+            row.number(longitude),
+        ) else {
+            continue;
+        };
+
+        let mut entity = places::place_entity(PlaceKind::Airport, code, airport_name);
+        entity.properties = json!({
+            "location_type": "airport",
+            "airport_type": airport_type,
+            "code": code.to_uppercase(),
+            "iata": iata.and_then(|i| row.get(i)),
+            "country": country.and_then(|i| row.get(i)),
+            "municipality": municipality.and_then(|i| row.get(i)),
+            "elevation_ft": elevation.and_then(|i| row.number(i)),
+            "latitude": lat,
+            "longitude": lon,
+        });
+        entity.geometry = Some(json!({"type": "Point", "coordinates": [lon, lat]}));
+        airports.push(entity);
+    }
+    if airports.is_empty() {
+        return Err(ArgusError::RefData(
+            "OurAirports has no usable rows".to_string(),
+        ));
+    }
+    Ok(airports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_large_and_medium_airports() {
+        let csv = "\
+\"id\",\"ident\",\"type\",\"name\",\"latitude_deg\",\"longitude_deg\",\"elevation_ft\",\"continent\",\"iso_country\",\"iso_region\",\"municipality\",\"scheduled_service\",\"gps_code\",\"iata_code\"
+2513,\"EHAM\",\"large_airport\",\"Amsterdam Airport Schiphol\",52.308601,4.76389,-11,\"EU\",\"NL\",\"NL-NH\",\"Amsterdam\",\"yes\",\"EHAM\",\"AMS\"
+1,\"00A\",\"heliport\",\"Total RF Heliport\",40.070985,-74.933689,11,\"NA\",\"US\",\"US-PA\",\"Bensalem\",\"no\",\"K00A\",\"\"
+";
+        let airports = parse(csv.as_bytes()).unwrap();
+        assert_eq!(airports.len(), 1);
+        let schiphol = &airports[0];
+        assert_eq!(schiphol.id, places::by_code("EHAM").unwrap().entity_id());
+        assert_eq!(schiphol.properties["iata"], "AMS");
+        assert_eq!(schiphol.properties["elevation_ft"], -11.0);
+        assert_eq!(schiphol.geometry.as_ref().unwrap()["type"], "Point");
+    }
+}
@@ -0,0 +1,74 @@
+//! Country boundaries from Natural Earth (admin-0 countries, GeoJSON),
+//! attached to the canonical country Locations.
+
+use serde_json::Value;
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::places;
+use argus_core::Entity;
+use argus_extraction::countries;
+
+/// One canonical country Location per feature with a known alpha-2 code.
+/// Natural Earth sets `ISO_A2` to `-99` for a few countries (France,
+/// Norway); `ISO_A2_EH` has their codes.
+pub fn parse(body: &[u8]) -> Result<Vec<Entity>> {
+    let collection: Value = serde_json::from_slice(body)?;
+    let features = collection["features"]
+        .as_array()
+        .ok_or_else(|| ArgusError::RefData("Natural Earth: not a FeatureCollection".to_string()))?;
+
+    let mut boundaries = Vec::new();
+    for feature in features {
+        let properties = &feature["properties"];
+        let country = ["ISO_A2", "ISO_A2_EH", "iso_a2"]
+            .iter()
+            .filter_map(|key| properties[*key].as_str())
+            .find_map(countries::by_iso2);
+        let geometry = &feature["geometry"];
+        let (Some(country), true) = (country, geometry.is_object()) else {
+            continue;
+        };
+        let mut entity = country.to_entity(places::SOURCE);
+        entity.geometry = Some(geometry.clone());
+        boundaries.push(entity);
+    }
+    if boundaries.is_empty() {
+        return Err(ArgusError::RefData(
+            "Natural Earth has no usable features".to_string(),
+        ));
+    }
+    Ok(boundaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries_attach_to_canonical_countries() {
+        let geojson = serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": {"ISO_A2": "-99", "ISO_A2_EH": "FR", "NAME": "France"},
+                    "geometry": {"type": "MultiPolygon", "coordinates": [[[[2.0, 51.0], [8.0, 49.0], [3.0, 43.0], [2.0, 51.0]]]]}
+                },
+                {
+                    "type": "Feature",
+                    "properties": {"ISO_A2": "-99", "ISO_A2_EH": "-99", "NAME": "Somaliland"},
+                    "geometry": {"type": "Polygon", "coordinates": []}
+                }
+            ]
+        });
+        let entities = parse(geojson.to_string().as_bytes()).unwrap();
+        assert_eq!(entities.len(), 1);
+        let france = countries::by_iso2("FR").unwrap();
+        assert_eq!(entities[0].id, france.entity_id());
+        assert_eq!(entities[0].name, france.name);
+        assert_eq!(
+            entities[0].geometry.as_ref().unwrap()["type"],
+            "MultiPolygon"
+        );
+    }
+}
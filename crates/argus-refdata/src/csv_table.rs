@@ -0,0 +1,60 @@
+//! CSV datasets read by header name, since column order differs between
+//! dataset releases.
+
+use csv::StringRecord;
+
+use argus_core::error::{ArgusError, Result};
+
+pub(crate) struct Table {
+    dataset: &'static str,
+    headers: StringRecord,
+    rows: Vec<StringRecord>,
+}
+
+impl Table {
+    pub(crate) fn read(body: &[u8], dataset: &'static str) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(body);
+        let headers = reader
+            .headers()
+            .map_err(|e| ArgusError::RefData(format!("{dataset}: unreadable header: {e}")))?
+            .clone();
+        let rows = reader.records().filter_map(|row| row.ok()).collect();
+        Ok(Self {
+            dataset,
+            headers,
+            rows,
+        })
+    }
+
+    /// Index of the first header matching one of `names`, ignoring case.
+    pub(crate) fn optional_column(&self, names: &[&str]) -> Option<usize> {
+        names.iter().find_map(|name| {
+            self.headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+        })
+    }
+
+    pub(crate) fn column(&self, names: &[&str]) -> Result<usize> {
+        self.optional_column(names).ok_or_else(|| {
+            ArgusError::RefData(format!("{}: missing column {}", self.dataset, names[0]))
+        })
+    }
+
+    pub(crate) fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        self.rows.iter().map(Row)
+    }
+}
+
+pub(crate) struct Row<'a>(&'a StringRecord);
+
+impl<'a> Row<'a> {
+    /// The trimmed cell, if not empty.
+    pub(crate) fn get(&self, column: usize) -> Option<&'a str> {
+        self.0.get(column).map(str::trim).filter(|s| !s.is_empty())
+    }
+
+    pub(crate) fn number(&self, column: usize) -> Option<f64> {
+        self.get(column)?.parse().ok()
+    }
+}
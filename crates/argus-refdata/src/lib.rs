@@ -0,0 +1,248 @@
+//! Reference geo data: ports, airports and country boundaries loaded into
+//! the graph as Location entities with a GeoJSON `geometry`.
+//!
+//! The places bundled in [`argus_core::places`] are always loaded. The full
+//! datasets are downloaded when their URL is set:
+//! - ports from the NGA World Port Index (Pub. 150, CSV)
+//! - large and medium airports from OurAirports (`airports.csv`)
+//! - country boundaries from Natural Earth (1:110m admin-0 GeoJSON), set on
+//!   the canonical country Locations of `argus_extraction::countries`
+//!
+//! Ports and airports are keyed on their UN/LOCODE or ICAO code, so a
+//! downloaded place that is also bundled lands on the same node; it keeps
+//! the bundled name and outline and gains the dataset's properties.
+
+mod csv_table;
+
+pub mod airports;
+pub mod countries;
+pub mod ports;
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use argus_core::error::Result;
+use argus_core::places::{self, AIRPORTS, PORTS};
+use argus_core::{Entity, ExtractionResult, GraphStore};
+
+/// Entities written per extraction result.
+const WRITE_BATCH_SIZE: usize = 500;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dataset {
+    Ports,
+    Airports,
+    Countries,
+}
+
+impl Dataset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dataset::Ports => "ports",
+            Dataset::Airports => "airports",
+            Dataset::Countries => "countries",
+        }
+    }
+
+    pub fn parse(&self, body: &[u8]) -> Result<Vec<Entity>> {
+        match self {
+            Dataset::Ports => ports::parse(body),
+            Dataset::Airports => airports::parse(body),
+            Dataset::Countries => countries::parse(body),
+        }
+    }
+}
+
+/// Download URL per dataset; an empty URL skips the dataset.
+#[derive(Debug, Clone, Default)]
+pub struct RefDataSources {
+    pub ports_url: String,
+    pub airports_url: String,
+    pub countries_url: String,
+}
+
+impl RefDataSources {
+    fn urls(&self) -> [(Dataset, &str); 3] {
+        [
+            (Dataset::Ports, self.ports_url.trim()),
+            (Dataset::Airports, self.airports_url.trim()),
+            (Dataset::Countries, self.countries_url.trim()),
+        ]
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RefreshReport {
+    /// Entities written, bundled places included.
+    pub entities: usize,
+    pub geometries: u64,
+    /// Datasets downloaded and loaded.
+    pub loaded: Vec<Dataset>,
+    /// Datasets unchanged since the last load, and so skipped.
+    pub unchanged: Vec<Dataset>,
+    /// Datasets that failed to download or parse; the previous load stays.
+    pub failed: Vec<Dataset>,
+}
+
+/// Loads reference data and reloads a dataset only when its content
+/// changed. Bundled places are written on the first refresh, and again with
+/// any later download that covers them.
+pub struct RefDataLoader {
+    client: reqwest::Client,
+    sources: RefDataSources,
+    /// SHA-256 of each dataset's last loaded body.
+    fingerprints: HashMap<Dataset, String>,
+    bundled_loaded: bool,
+}
+
+impl RefDataLoader {
+    pub fn new(sources: RefDataSources) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DOWNLOAD_TIMEOUT)
+            .user_agent("argus-refdata")
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            sources,
+            fingerprints: HashMap::new(),
+            bundled_loaded: false,
+        }
+    }
+
+    pub async fn refresh(&mut self, graph: &dyn GraphStore) -> Result<RefreshReport> {
+        let mut report = RefreshReport::default();
+        let bundled: HashMap<Uuid, Entity> = PORTS
+            .iter()
+            .chain(AIRPORTS)
+            .map(|place| (place.entity_id(), place.to_entity()))
+            .collect();
+        let mut pending: BTreeMap<Uuid, Entity> = if self.bundled_loaded {
+            BTreeMap::new()
+        } else {
+            bundled.iter().map(|(id, e)| (*id, e.clone())).collect()
+        };
+
+        let mut fingerprints = Vec::new();
+        for (dataset, url) in self.sources.urls() {
+            if url.is_empty() {
+                continue;
+            }
+            let body = match self.download(url).await {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!(dataset = dataset.as_str(), error = %e, "Failed to download reference data");
+                    report.failed.push(dataset);
+                    continue;
+                }
+            };
+            let fingerprint = format!("{:x}", Sha256::digest(&body));
+            if self.fingerprints.get(&dataset) == Some(&fingerprint) {
+                report.unchanged.push(dataset);
+                continue;
+            }
+            let entities = match dataset.parse(&body) {
+                Ok(entities) => entities,
+                Err(e) => {
+                    warn!(dataset = dataset.as_str(), error = %e, "Failed to parse reference data");
+                    report.failed.push(dataset);
+                    continue;
+                }
+            };
+            for entity in entities {
+                let entity = match bundled.get(&entity.id) {
+                    Some(place) => merge_into_bundled(place, entity),
+                    None => entity,
+                };
+                pending.insert(entity.id, entity);
+            }
+            fingerprints.push((dataset, fingerprint));
+            report.loaded.push(dataset);
+        }
+
+        let entities: Vec<Entity> = pending.into_values().collect();
+        let geometries: Vec<(Uuid, serde_json::Value)> = entities
+            .iter()
+            .filter_map(|e| Some((e.id, e.geometry.clone()?)))
+            .collect();
+        let results: Vec<ExtractionResult> = entities
+            .chunks(WRITE_BATCH_SIZE)
+            .map(|batch| ExtractionResult {
+                raw_source: places::SOURCE.to_string(),
+                entities: batch.to_vec(),
+                relationships: Vec::new(),
+                extracted_at: chrono::Utc::now(),
+            })
+            .collect();
+        if !results.is_empty() {
+            graph.store_extractions(&results).await?;
+            report.geometries = graph.set_geometries(&geometries).await?;
+        }
+
+        report.entities = entities.len();
+        self.bundled_loaded = true;
+        self.fingerprints.extend(fingerprints);
+        info!(
+            entities = report.entities,
+            geometries = report.geometries,
+            loaded = ?report.loaded,
+            unchanged = ?report.unchanged,
+            failed = ?report.failed,
+            "Reference data refreshed"
+        );
+        Ok(report)
+    }
+
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// A downloaded place on a bundled one's node: the bundled name and
+/// geometry stay, so the node doesn't flip between the two; properties the
+/// bundle lacks are added.
+fn merge_into_bundled(place: &Entity, downloaded: Entity) -> Entity {
+    let mut merged = place.clone();
+    if let (Some(props), serde_json::Value::Object(extra)) =
+        (merged.properties.as_object_mut(), downloaded.properties)
+    {
+        for (key, value) in extra {
+            if !value.is_null() {
+                props.entry(key).or_insert(value);
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downloaded_places_keep_the_bundled_name_and_outline() {
+        let bundled = places::by_code("NLRTM").unwrap().to_entity();
+        let csv = "World Port Index Number,Main Port Name,UN/LOCODE,Country Code,Harbor Size,Latitude,Longitude\n\
+                   40030,Rotterdam,NL RTM,Netherlands,Large,51.9,4.133333\n";
+        let downloaded = ports::parse(csv.as_bytes()).unwrap().remove(0);
+
+        let merged = merge_into_bundled(&bundled, downloaded);
+        assert_eq!(merged.name, "Port of Rotterdam");
+        assert_eq!(merged.geometry, bundled.geometry);
+        assert_eq!(merged.properties["harbor_size"], "Large");
+        assert_eq!(merged.properties["wpi_number"], "40030");
+        assert_eq!(
+            merged.properties["latitude"],
+            bundled.properties["latitude"]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Ports from the NGA World Port Index (Pub. 150, CSV export).
+
+use serde_json::json;
+
+use argus_core::error::{ArgusError, Result};
+use argus_core::places::{self, PlaceKind};
+use argus_core::Entity;
+use argus_extraction::countries;
+
+use crate::csv_table::Table;
+
+/// One Location per port, keyed on its UN/LOCODE, or its index number for
+/// ports without one.
+pub fn parse(body: &[u8]) -> Result<Vec<Entity>> {
+    let table = Table::read(body, "World Port Index")?;
+    let name = table.column(&["Main Port Name", "PORT_NAME"])?;
+    let latitude = table.column(&["Latitude", "LATITUDE"])?;
+    let longitude = table.column(&["Longitude", "LONGITUDE"])?;
+    let number = table.column(&["World Port Index Number", "INDEX_NO"])?;
+    let locode = table.optional_column(&["UN/LOCODE", "UNLOCODE"]);
+    let country = table.optional_column(&["Country Code", "COUNTRY"]);
+    let harbor_size = table.optional_column(&["Harbor Size", "HARBORSIZE"]);
+    let harbor_type = table.optional_column(&["Harbor Type", "HARBORTYPE"]);
+
+    let mut ports = Vec::new();
+    for row in table.rows() {
+        let (Some(port_name), Some(lat), Some(lon)) =
+            (row.get(name), row.number(latitude), row.number(longitude))
+        else {
+            continue;
+        };
+        let locode = locode
+            .and_then(|i| row.get(i))
+            .map(|code| code.replace(' ', "").to_uppercase());
+        let index_number = row.get(number);
+        let Some(code) = locode
+            .clone()
+            .or_else(|| index_number.map(|n| format!("WPI{n}")))
+        else {
+            continue;
+        };
+        let country_name = country.and_then(|i| row.get(i));
+
+        let name = if port_name.to_lowercase().starts_with("port ") {
+            port_name.to_string()
+        } else {
+            format!("Port of {port_name}")
+        };
+        let mut entity = places::place_entity(PlaceKind::Port, &code, &name);
+        entity.properties = json!({
+            "location_type": "port",
+            "code": code,
+            "unlocode": locode,
+            "wpi_number": index_number,
+            "country": country_name.and_then(countries::by_name).map_or(country_name, |c| Some(c.iso2)),
+            "harbor_size": harbor_size.and_then(|i| row.get(i)),
+            "harbor_type": harbor_type.and_then(|i| row.get(i)),
+            "latitude": lat,
+            "longitude": lon,
+        });
+        entity.geometry = Some(json!({"type": "Point", "coordinates": [lon, lat]}));
+        ports.push(entity);
+    }
+    if ports.is_empty() {
+        return Err(ArgusError::RefData(
+            "World Port Index has no usable rows".to_string(),
+        ));
+    }
+    Ok(ports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ports_keyed_on_locode() {
+        let csv = "\
+World Port Index Number,Region Name,Main Port Name,UN/LOCODE,Country Code,Harbor Size,Latitude,Longitude
+40030,Netherlands,Rotterdam,NL RTM,Netherlands,Large,51.9,4.133333
+99999,Nowhere,\"Bay, North\",,Atlantis,Small,10.5,-20.25
+12345,Broken,No Position,XX XXX,,Small,,
+";
+        let ports = parse(csv.as_bytes()).unwrap();
+        assert_eq!(ports.len(), 2);
+
+        let rotterdam = &ports[0];
+        assert_eq!(rotterdam.id, places::by_code("NLRTM").unwrap().entity_id());
+        assert_eq!(rotterdam.name, "Port of Rotterdam");
+        assert_eq!(rotterdam.properties["country"], "NL");
+        assert_eq!(rotterdam.properties["harbor_size"], "Large");
+        assert_eq!(rotterdam.geometry.as_ref().unwrap()["coordinates"][1], 51.9);
+
+        assert_eq!(ports[1].name, "Port of Bay, North");
+        assert_eq!(ports[1].properties["code"], "WPI99999");
+        assert_eq!(ports[1].properties["country"], "Atlantis");
+    }
+}
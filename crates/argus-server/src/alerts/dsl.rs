@@ -0,0 +1,605 @@
+//! The rule condition language.
+//!
+//! ```text
+//! condition  := or
+//! or         := and ("OR" and)*
+//! and        := unary ("AND" unary)*
+//! unary      := "NOT" unary | "(" condition ")" | comparison | enters
+//! comparison := path op value        op: == != < <= > >= contains
+//! enters     := subject "enters" "bbox" "(" min_lon "," min_lat "," max_lon "," max_lat ")"
+//! ```
+//!
+//! Paths are `entity.*`, `other.*` (the entity at the other end of the
+//! relationship) and `new_relationship.*` (or `relationship.*`). Entity
+//! fields are `type`, `name`, `source`, `confidence`, `id`, `properties.X`
+//! and `identifiers.X`; relationship fields are `type`, `source`,
+//! `confidence` and `properties.X`. Keywords and string comparisons are
+//! case-insensitive, and a comparison on a missing field is false.
+//!
+//! An `enters` subject is an entity type or `entity` for any, matched when
+//! its `latitude`/`longitude` properties fall inside the box and its
+//! previous position, if known, fell outside it. Staying in the box does
+//! not match again.
+
+use argus_core::{Entity, EntityType, RelationType, Relationship};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Compare {
+        path: Path,
+        op: Op,
+        value: Literal,
+    },
+    EntersBbox {
+        subject: Option<EntityType>,
+        bbox: [f64; 4],
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Path {
+    Entity(EntityField),
+    Other(EntityField),
+    Relationship(RelationshipField),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityField {
+    Type,
+    Name,
+    Source,
+    Confidence,
+    Id,
+    Property(String),
+    Identifier(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RelationshipField {
+    Type,
+    Source,
+    Confidence,
+    Property(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+/// A right-hand side, kept as written and as a number when it is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal {
+    text: String,
+    number: Option<f64>,
+}
+
+/// What a condition is checked against: an entity from the write, plus the
+/// relationship being looked at and its other endpoint, if any.
+#[derive(Debug, Clone, Copy)]
+pub struct Subject<'a> {
+    pub entity: &'a Entity,
+    pub relationship: Option<&'a Relationship>,
+    pub other: Option<&'a Entity>,
+    /// Where the entity was reported before this write, as
+    /// `(latitude, longitude)`, if it was.
+    pub previous: Option<(f64, f64)>,
+}
+
+impl Condition {
+    /// Whether the condition looks at a relationship, so has to be checked
+    /// per relationship rather than per entity.
+    pub fn needs_relationship(&self) -> bool {
+        match self {
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                a.needs_relationship() || b.needs_relationship()
+            }
+            Condition::Not(inner) => inner.needs_relationship(),
+            Condition::Compare { path, .. } => !matches!(path, Path::Entity(_)),
+            Condition::EntersBbox { .. } => false,
+        }
+    }
+
+    /// Whether the condition compares positions, so needs each entity's
+    /// previous position looked up.
+    pub fn needs_previous_position(&self) -> bool {
+        match self {
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                a.needs_previous_position() || b.needs_previous_position()
+            }
+            Condition::Not(inner) => inner.needs_previous_position(),
+            Condition::Compare { .. } => false,
+            Condition::EntersBbox { .. } => true,
+        }
+    }
+
+    pub fn matches(&self, subject: &Subject<'_>) -> bool {
+        match self {
+            Condition::And(a, b) => a.matches(subject) && b.matches(subject),
+            Condition::Or(a, b) => a.matches(subject) || b.matches(subject),
+            Condition::Not(inner) => !inner.matches(subject),
+            Condition::Compare { path, op, value } => match path.resolve(subject) {
+                Some(field) => compare(&field, *op, value),
+                None => false,
+            },
+            Condition::EntersBbox {
+                subject: entity_type,
+                bbox,
+            } => {
+                let entity = subject.entity;
+                if entity_type
+                    .as_ref()
+                    .is_some_and(|t| *t != entity.entity_type)
+                {
+                    return false;
+                }
+                let [min_lon, min_lat, max_lon, max_lat] = *bbox;
+                let inside = |(lat, lon): (f64, f64)| {
+                    (min_lat..=max_lat).contains(&lat) && (min_lon..=max_lon).contains(&lon)
+                };
+                coordinates(entity).is_some_and(inside) && !subject.previous.is_some_and(inside)
+            }
+        }
+    }
+}
+
+impl Path {
+    fn resolve(&self, subject: &Subject<'_>) -> Option<Value> {
+        match self {
+            Path::Entity(field) => entity_field(subject.entity, field),
+            Path::Other(field) => entity_field(subject.other?, field),
+            Path::Relationship(field) => {
+                let relationship = subject.relationship?;
+                match field {
+                    RelationshipField::Type => {
+                        Some(Value::from(relationship.relation_type.as_str()))
+                    }
+                    RelationshipField::Source => Some(Value::from(relationship.source.as_str())),
+                    RelationshipField::Confidence => Some(Value::from(relationship.confidence)),
+                    RelationshipField::Property(key) => property(&relationship.properties, key),
+                }
+            }
+        }
+    }
+}
+
+fn entity_field(entity: &Entity, field: &EntityField) -> Option<Value> {
+    match field {
+        EntityField::Type => Some(Value::from(entity.entity_type.as_str())),
+        EntityField::Name => Some(Value::from(entity.name.as_str())),
+        EntityField::Source => Some(Value::from(entity.source.as_str())),
+        EntityField::Confidence => Some(Value::from(entity.confidence)),
+        EntityField::Id => Some(Value::from(entity.id.to_string())),
+        EntityField::Property(key) => property(&entity.properties, key),
+        EntityField::Identifier(scheme) => entity
+            .identifiers
+            .get(scheme)
+            .map(|v| Value::from(v.as_str())),
+    }
+}
+
+fn property(properties: &Value, key: &str) -> Option<Value> {
+    properties.get(key).filter(|v| !v.is_null()).cloned()
+}
+
+fn coordinates(entity: &Entity) -> Option<(f64, f64)> {
+    let number = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| entity.properties.get(*k).and_then(as_number))
+    };
+    Some((
+        number(&["latitude", "lat"])?,
+        number(&["longitude", "lon", "lng"])?,
+    ))
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_lowercase(),
+        other => other.to_string().to_lowercase(),
+    }
+}
+
+fn compare(field: &Value, op: Op, literal: &Literal) -> bool {
+    if let Value::Array(items) = field {
+        // Lists match if any element does, except `!=`, which needs all
+        return match op {
+            Op::Ne => items.iter().all(|item| compare(item, op, literal)),
+            _ => items.iter().any(|item| compare(item, op, literal)),
+        };
+    }
+    let numbers = as_number(field).zip(literal.number);
+    match op {
+        Op::Eq | Op::Ne => {
+            let equal = match numbers {
+                Some((a, b)) => a == b,
+                None => as_text(field) == literal.text.to_lowercase(),
+            };
+            equal == (op == Op::Eq)
+        }
+        Op::Contains => as_text(field).contains(&literal.text.to_lowercase()),
+        Op::Lt | Op::Le | Op::Gt | Op::Ge => match numbers {
+            Some((a, b)) => match op {
+                Op::Lt => a < b,
+                Op::Le => a <= b,
+                Op::Gt => a > b,
+                _ => a >= b,
+            },
+            None => false,
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Comma,
+                });
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                tokens.push(Token::Quoted(text));
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let followed_by_eq = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Op(match (c, followed_by_eq) {
+                    ('=', _) => Op::Eq,
+                    ('!', true) => Op::Ne,
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    ('>', true) => Op::Ge,
+                    _ => return Err("expected `!=`".to_string()),
+                }));
+            }
+            c if c.is_alphanumeric() || "_.-+:".contains(c) => {
+                let mut word = String::new();
+                while let Some(ch) =
+                    chars.next_if(|ch| ch.is_alphanumeric() || "_.-+:".contains(*ch))
+                {
+                    word.push(ch);
+                }
+                tokens.push(Token::Word(word));
+            }
+            other => return Err(format!("unexpected character `{other}`")),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), String> {
+        if self.next() == Some(token) {
+            Ok(())
+        } else {
+            Err(format!("expected {what}"))
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.and()?;
+        while self.keyword("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.unary()?;
+        while self.keyword("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn unary(&mut self) -> Result<Condition, String> {
+        if self.keyword("not") {
+            return Ok(Condition::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.pos += 1;
+            let condition = self.or()?;
+            self.expect(Token::Close, "`)`")?;
+            return Ok(condition);
+        }
+        let Some(Token::Word(word)) = self.next() else {
+            return Err("expected a field or entity type".to_string());
+        };
+        if self.keyword("enters") {
+            return self.bbox(&word);
+        }
+        let path = parse_path(&word)?;
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("contains") => Op::Contains,
+            _ => return Err(format!("expected an operator after `{word}`")),
+        };
+        let text = match self.next() {
+            Some(Token::Word(w) | Token::Quoted(w)) => w,
+            _ => return Err(format!("expected a value after `{word}`")),
+        };
+        let text = match &path {
+            Path::Entity(EntityField::Type) | Path::Other(EntityField::Type) => {
+                EntityType::from_name(&text).to_string()
+            }
+            Path::Relationship(RelationshipField::Type) => {
+                RelationType::from_name(&text).to_string()
+            }
+            _ => text,
+        };
+        let number = text.parse().ok();
+        Ok(Condition::Compare {
+            path,
+            op,
+            value: Literal { text, number },
+        })
+    }
+
+    fn bbox(&mut self, subject: &str) -> Result<Condition, String> {
+        if !self.keyword("bbox") {
+            return Err("expected `bbox` after `enters`".to_string());
+        }
+        self.expect(Token::Open, "`(` after `bbox`")?;
+        let mut bbox = [0.0; 4];
+        for (i, slot) in bbox.iter_mut().enumerate() {
+            if i > 0 {
+                self.expect(Token::Comma, "`,` between bbox coordinates")?;
+            }
+            *slot = match self.next() {
+                Some(Token::Word(w)) => w
+                    .parse()
+                    .map_err(|_| format!("`{w}` is not a coordinate"))?,
+                _ => return Err("bbox takes min_lon, min_lat, max_lon, max_lat".to_string()),
+            };
+        }
+        self.expect(Token::Close, "`)` after bbox coordinates")?;
+        if bbox[0] > bbox[2] || bbox[1] > bbox[3] {
+            return Err("bbox minimums must not exceed maximums".to_string());
+        }
+        let subject = match subject.to_lowercase().as_str() {
+            "entity" | "any" => None,
+            name => Some(EntityType::from_name(name)),
+        };
+        Ok(Condition::EntersBbox { subject, bbox })
+    }
+}
+
+fn parse_path(word: &str) -> Result<Path, String> {
+    let lower = word.to_lowercase();
+    let (scope, field) = lower
+        .split_once('.')
+        .ok_or_else(|| format!("`{word}` is not a field; use e.g. `entity.type`"))?;
+    // Property and identifier keys keep their case
+    let key = |prefix: &str| word[scope.len() + 1 + prefix.len()..].to_string();
+    let entity_field = || -> Result<EntityField, String> {
+        Ok(match field {
+            "type" => EntityField::Type,
+            "name" => EntityField::Name,
+            "source" => EntityField::Source,
+            "confidence" => EntityField::Confidence,
+            "id" => EntityField::Id,
+            f if f.starts_with("properties.") => EntityField::Property(key("properties.")),
+            f if f.starts_with("identifiers.") => EntityField::Identifier(key("identifiers.")),
+            _ => return Err(format!("unknown entity field `{word}`")),
+        })
+    };
+    match scope {
+        "entity" => Ok(Path::Entity(entity_field()?)),
+        "other" => Ok(Path::Other(entity_field()?)),
+        "new_relationship" | "relationship" => Ok(Path::Relationship(match field {
+            "type" => RelationshipField::Type,
+            "source" => RelationshipField::Source,
+            "confidence" => RelationshipField::Confidence,
+            f if f.starts_with("properties.") => RelationshipField::Property(key("properties.")),
+            _ => return Err(format!("unknown relationship field `{word}`")),
+        })),
+        _ => Err(format!(
+            "unknown scope `{scope}`; use entity, other or new_relationship"
+        )),
+    }
+}
+
+/// Parse a rule condition, with a short reason if it is malformed.
+pub fn parse(input: &str) -> Result<Condition, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Err("condition is empty".to_string());
+    }
+    let condition = parser.or()?;
+    match parser.peek() {
+        None => Ok(condition),
+        Some(token) => Err(format!("unexpected {token:?} after the condition")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entity(entity_type: EntityType, name: &str, properties: Value) -> Entity {
+        let mut entity = Entity::new(entity_type, name.to_string(), "opensanctions".to_string());
+        entity.properties = properties;
+        entity
+    }
+
+    #[test]
+    fn sanctioned_organizations_match_on_the_new_relationship() {
+        let condition =
+            parse("entity.type == Organization AND new_relationship.type == SANCTIONED_BY")
+                .unwrap();
+        assert!(condition.needs_relationship());
+
+        let acme = entity(EntityType::Organization, "Acme Ltd", json!({}));
+        let ofac = entity(EntityType::Sanction, "OFAC SDN", json!({}));
+        let check = |relation_type: RelationType| {
+            let rel =
+                Relationship::new(acme.id, ofac.id, relation_type, "opensanctions".to_string());
+            condition.matches(&Subject {
+                entity: &acme,
+                relationship: Some(&rel),
+                other: Some(&ofac),
+                previous: None,
+            })
+        };
+        assert!(check(RelationType::SanctionedBy));
+        assert!(!check(RelationType::OwnerOf));
+    }
+
+    #[test]
+    fn vessels_entering_a_bbox() {
+        let condition = parse("vessel enters bbox(-6.0, 35.5, -5.0, 36.5)").unwrap();
+        assert!(!condition.needs_relationship());
+        assert!(condition.needs_previous_position());
+
+        let inside = entity(
+            EntityType::Vessel,
+            "Ever Given",
+            json!({"latitude": 36.0, "longitude": -5.5}),
+        );
+        let outside = entity(
+            EntityType::Vessel,
+            "Ever Given",
+            json!({"latitude": 40.0, "longitude": -5.5}),
+        );
+        let person = entity(
+            EntityType::Person,
+            "Jane",
+            json!({"latitude": 36.0, "longitude": -5.5}),
+        );
+        let check = |e: &Entity, previous: Option<(f64, f64)>| {
+            condition.matches(&Subject {
+                entity: e,
+                relationship: None,
+                other: None,
+                previous,
+            })
+        };
+        assert!(check(&inside, None));
+        assert!(check(&inside, Some((40.0, -5.5))));
+        // Already inside is not entering
+        assert!(!check(&inside, Some((36.2, -5.6))));
+        assert!(!check(&outside, None));
+        assert!(!check(&person, None));
+    }
+
+    #[test]
+    fn comparisons_precedence_and_missing_fields() {
+        let org = entity(
+            EntityType::Organization,
+            "Acme Shipping",
+            json!({"country": "PA", "risk": 0.9}),
+        );
+        let check = |input: &str| {
+            parse(input).unwrap().matches(&Subject {
+                entity: &org,
+                relationship: None,
+                other: None,
+                previous: None,
+            })
+        };
+        assert!(check("entity.name contains shipping"));
+        assert!(check(
+            "entity.properties.risk >= 0.8 AND entity.properties.country == 'pa'"
+        ));
+        assert!(check(
+            "entity.type == person OR entity.type == organization AND NOT entity.source == gdelt"
+        ));
+        assert!(!check("entity.properties.missing != x"));
+        assert!(check("NOT (entity.properties.risk < 0.5)"));
+    }
+
+    #[test]
+    fn malformed_conditions_are_rejected() {
+        for input in [
+            "",
+            "entity.type ==",
+            "entity.colour == red",
+            "vessel enters bbox(1, 2, 3)",
+            "(entity.type == vessel",
+            "entity.type == vessel extra",
+        ] {
+            assert!(parse(input).is_err(), "{input:?} should not parse");
+        }
+    }
+}
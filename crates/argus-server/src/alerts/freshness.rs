@@ -0,0 +1,129 @@
+//! Alerts for sources whose upstream data has gone stale: checked after
+//! each agent run against `SOURCE_FRESHNESS_MAX_LAG`. They belong to no
+//! rule, so they have a nil `rule_id` and no deliveries.
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use argus_core::{Alert, AlertSeverity, AlertStore, Result};
+
+/// `rule_name` of freshness alerts.
+pub const FRESHNESS_ALERT: &str = "source freshness";
+
+/// Raise an alert if `agent`'s newest upstream data, from `data_at`, trails
+/// `now` by more than `max_lag_seconds`. One stays open per agent until
+/// acknowledged. Returns whether a new alert was raised.
+pub async fn check(
+    alerts: &dyn AlertStore,
+    agent: &str,
+    data_at: Option<DateTime<Utc>>,
+    max_lag_seconds: u64,
+    now: DateTime<Utc>,
+) -> Result<bool> {
+    let Some(data_at) = data_at else {
+        return Ok(false);
+    };
+    let lag = (now - data_at).num_seconds().max(0) as u64;
+    if lag <= max_lag_seconds {
+        return Ok(false);
+    }
+    warn!(
+        agent,
+        lag_secs = lag,
+        max_lag_secs = max_lag_seconds,
+        "Source data is stale"
+    );
+    let raised = alerts
+        .raise(stale_alert(agent, data_at, lag, max_lag_seconds, now))
+        .await?;
+    if raised {
+        info!(agent, "Raised source freshness alert");
+    }
+    Ok(raised)
+}
+
+fn stale_alert(
+    agent: &str,
+    data_at: DateTime<Utc>,
+    lag: u64,
+    max_lag: u64,
+    now: DateTime<Utc>,
+) -> Alert {
+    Alert {
+        id: Uuid::new_v4(),
+        rule_id: Uuid::nil(),
+        rule_name: FRESHNESS_ALERT.to_string(),
+        severity: AlertSeverity::Medium,
+        message: format!(
+            "{agent} data is {} old (newest from {}), over the {} allowed",
+            format_lag(lag),
+            data_at.to_rfc3339(),
+            format_lag(max_lag)
+        ),
+        entities: vec![],
+        relationship_id: None,
+        source: agent.to_string(),
+        dedup_key: format!("freshness:{agent}"),
+        created_at: now,
+        acknowledged_at: None,
+        acknowledged_by: None,
+        note: None,
+        deliveries: vec![],
+    }
+}
+
+/// `3d 4h`, `2h 5m` or `40m`.
+fn format_lag(seconds: u64) -> String {
+    let (days, hours, minutes) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3600,
+        seconds % 3600 / 60,
+    );
+    match (days, hours) {
+        (0, 0) => format!("{minutes}m"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::AlertStatus;
+
+    use crate::shared::MemoryAlertStore;
+
+    #[tokio::test]
+    async fn stale_sources_raise_one_open_alert() {
+        let alerts = MemoryAlertStore::default();
+        let now = Utc::now();
+        let hours_ago = |h| Some(now - chrono::Duration::hours(h));
+
+        assert!(!check(&alerts, "opensanctions", hours_ago(1), 7200, now)
+            .await
+            .unwrap());
+        assert!(!check(&alerts, "opensanctions", None, 7200, now)
+            .await
+            .unwrap());
+        assert!(check(&alerts, "opensanctions", hours_ago(50), 7200, now)
+            .await
+            .unwrap());
+        assert!(!check(&alerts, "opensanctions", hours_ago(51), 7200, now)
+            .await
+            .unwrap());
+
+        let open = alerts.list(AlertStatus::Open, 10).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].rule_name, FRESHNESS_ALERT);
+        assert_eq!(open[0].source, "opensanctions");
+        assert!(
+            open[0]
+                .message
+                .starts_with("opensanctions data is 2d 2h old"),
+            "{}",
+            open[0].message
+        );
+        assert!(open[0].message.ends_with("over the 2h 0m allowed"));
+    }
+}
@@ -0,0 +1,454 @@
+//! Alert rules checked against every batch the write buffer stores.
+//!
+//! Rules whose condition looks at a relationship are checked once per
+//! relationship in a result, with `entity` bound to each endpoint in turn;
+//! the rest are checked once per entity. Writes that bypass the buffer
+//! (imports, persisted reasoning answers, merges) are not checked.
+//! `enters` conditions compare a tracked asset's position with the one
+//! stored before it, so need the graph (`with_positions`).
+//! Stale sources raise rule-less alerts of their own (`freshness`).
+
+pub mod dsl;
+pub mod freshness;
+pub mod notify;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use argus_core::graph::GraphStore;
+use argus_core::tracks::{asset_keys, entity_coordinates};
+use argus_core::{
+    Alert, AlertEntity, AlertRule, AlertRuleStore, AlertStore, Delivery, Entity, ExtractionResult,
+    PositionReport, Result,
+};
+
+use self::dsl::{Condition, Subject};
+use self::notify::Notifier;
+use crate::write_buffer::WriteObserver;
+
+pub struct AlertEngine {
+    rules: Arc<dyn AlertRuleStore>,
+    alerts: Arc<dyn AlertStore>,
+    notifier: Option<Arc<Notifier>>,
+    positions: Option<Arc<dyn GraphStore>>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Arc<dyn AlertRuleStore>, alerts: Arc<dyn AlertStore>) -> Self {
+        Self {
+            rules,
+            alerts,
+            notifier: None,
+            positions: None,
+        }
+    }
+
+    /// Look up where tracked assets were before a write, for `enters`
+    /// conditions. Without it every position inside the box matches.
+    pub fn with_positions(mut self, graph: Arc<dyn GraphStore>) -> Self {
+        self.positions = Some(graph);
+        self
+    }
+
+    /// Send raised alerts to their rule's channels, off the write path.
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Check the enabled rules against `results`, returning how many new
+    /// alerts were raised. Rules that no longer parse are skipped.
+    pub async fn evaluate(&self, results: &[ExtractionResult]) -> Result<usize> {
+        let rules: Vec<(AlertRule, Condition)> = self
+            .rules
+            .list()
+            .await?
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match dsl::parse(&rule.condition) {
+                Ok(condition) => Some((rule, condition)),
+                Err(e) => {
+                    warn!(rule = %rule.name, error = %e, "Skipping alert rule with an invalid condition");
+                    None
+                }
+            })
+            .collect();
+        if rules.is_empty() {
+            return Ok(0);
+        }
+
+        let previous = if rules
+            .iter()
+            .any(|(_, condition)| condition.needs_previous_position())
+        {
+            self.previous_positions(results).await
+        } else {
+            HashMap::new()
+        };
+
+        let mut raised = 0;
+        for (rule, condition) in &rules {
+            let mut new_alerts = Vec::new();
+            for result in results {
+                for alert in matches(rule, condition, result, &previous) {
+                    if self.alerts.raise(alert.clone()).await? {
+                        new_alerts.push(alert);
+                    }
+                }
+            }
+            raised += new_alerts.len();
+            if let Some(notifier) = self
+                .notifier
+                .clone()
+                .filter(|_| !new_alerts.is_empty() && !rule.channels.is_empty())
+            {
+                let rule = rule.clone();
+                tokio::spawn(async move { notifier.deliver_new(&rule, new_alerts).await });
+            }
+        }
+        if raised > 0 {
+            info!(raised, "Raised alerts");
+        }
+        Ok(raised)
+    }
+
+    /// Where each placed entity in `results` was reported before its
+    /// current position, keyed by entity id. The pipeline stores a
+    /// document's position before its results are written, so the latest
+    /// stored report is usually the current one and the one before it is
+    /// the previous. Lookup failures leave the entity out.
+    async fn previous_positions(&self, results: &[ExtractionResult]) -> HashMap<Uuid, (f64, f64)> {
+        let mut previous = HashMap::new();
+        let Some(graph) = &self.positions else {
+            return previous;
+        };
+        for entity in results.iter().flat_map(|r| &r.entities) {
+            let (Some(current), keys) = (entity_coordinates(entity), asset_keys(entity)) else {
+                continue;
+            };
+            if keys.is_empty() {
+                continue;
+            }
+            match graph.asset_positions(&keys, 2).await {
+                Ok(recent) => {
+                    if let Some(position) = previous_position(current, &recent) {
+                        previous.insert(entity.id, position);
+                    }
+                }
+                Err(e) => {
+                    warn!(entity = %entity.name, error = %e, "Failed to look up previous position")
+                }
+            }
+        }
+        previous
+    }
+}
+
+/// The position before `current` among an asset's latest reports (oldest
+/// first): the one before the latest when the latest is `current` itself,
+/// otherwise the latest.
+pub(crate) fn previous_position(
+    current: (f64, f64),
+    recent: &[PositionReport],
+) -> Option<(f64, f64)> {
+    let position = |r: &PositionReport| (r.latitude, r.longitude);
+    match recent.split_last() {
+        Some((latest, before)) if position(latest) == current => before.last().map(position),
+        Some((latest, _)) => Some(position(latest)),
+        None => None,
+    }
+}
+
+#[async_trait]
+impl WriteObserver for AlertEngine {
+    async fn stored(&self, results: &[ExtractionResult]) {
+        if let Err(e) = self.evaluate(results).await {
+            warn!(error = %e, "Failed to evaluate alert rules");
+        }
+    }
+}
+
+/// Entities are keyed by type and name in dedup keys, since a write that
+/// resolves onto an existing node keeps that node's id, not the extracted one.
+pub(crate) fn entity_key(entity: &Entity) -> String {
+    format!("{}:{}", entity.entity_type, entity.name.to_lowercase())
+}
+
+fn alert_entity(entity: &Entity) -> AlertEntity {
+    AlertEntity {
+        id: entity.id,
+        name: entity.name.clone(),
+        entity_type: entity.entity_type.clone(),
+    }
+}
+
+fn alert(
+    rule: &AlertRule,
+    message: String,
+    entities: Vec<AlertEntity>,
+    source: &str,
+    dedup_key: String,
+) -> Alert {
+    Alert {
+        id: Uuid::new_v4(),
+        rule_id: rule.id,
+        rule_name: rule.name.clone(),
+        severity: rule.severity,
+        message,
+        entities,
+        relationship_id: None,
+        source: source.to_string(),
+        dedup_key,
+        created_at: Utc::now(),
+        acknowledged_at: None,
+        acknowledged_by: None,
+        note: None,
+        deliveries: rule
+            .channels
+            .iter()
+            .map(|c| Delivery::pending(c.label()))
+            .collect(),
+    }
+}
+
+/// The alerts `rule` raises for one stored result, before deduplication.
+/// `previous` holds where placed entities were before this write, by id.
+pub fn matches(
+    rule: &AlertRule,
+    condition: &Condition,
+    result: &ExtractionResult,
+    previous: &HashMap<Uuid, (f64, f64)>,
+) -> Vec<Alert> {
+    if !condition.needs_relationship() {
+        return result
+            .entities
+            .iter()
+            .filter(|entity| {
+                condition.matches(&Subject {
+                    entity,
+                    relationship: None,
+                    other: None,
+                    previous: previous.get(&entity.id).copied(),
+                })
+            })
+            .map(|entity| {
+                alert(
+                    rule,
+                    format!(
+                        "{} ({}) matched {}",
+                        entity.name, entity.entity_type, rule.name
+                    ),
+                    vec![alert_entity(entity)],
+                    &entity.source,
+                    format!("rule:{}:{}", rule.id, entity_key(entity)),
+                )
+            })
+            .collect();
+    }
+
+    let find = |id: Uuid| result.entities.iter().find(|e| e.id == id);
+    result
+        .relationships
+        .iter()
+        .filter_map(|rel| {
+            let (source, target) = (find(rel.source_entity_id), find(rel.target_entity_id));
+            let matched =
+                [(source, target), (target, source)]
+                    .into_iter()
+                    .any(|(entity, other)| {
+                        entity.is_some_and(|entity| {
+                            condition.matches(&Subject {
+                                entity,
+                                relationship: Some(rel),
+                                other,
+                                previous: previous.get(&entity.id).copied(),
+                            })
+                        })
+                    });
+            if !matched {
+                return None;
+            }
+            let name = |e: Option<&Entity>, id: Uuid| e.map_or(id.to_string(), |e| e.name.clone());
+            let key = |e: Option<&Entity>, id: Uuid| e.map_or(id.to_string(), entity_key);
+            let mut alert = alert(
+                rule,
+                format!(
+                    "{} {} {} matched {}",
+                    name(source, rel.source_entity_id),
+                    rel.relation_type,
+                    name(target, rel.target_entity_id),
+                    rule.name
+                ),
+                [source, target]
+                    .into_iter()
+                    .flatten()
+                    .map(alert_entity)
+                    .collect(),
+                &rel.source,
+                format!(
+                    "rule:{}:rel:{}:{}:{}",
+                    rule.id,
+                    key(source, rel.source_entity_id),
+                    rel.relation_type,
+                    key(target, rel.target_entity_id)
+                ),
+            );
+            alert.relationship_id = Some(rel.id);
+            Some(alert)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::{
+        AlertSeverity, AlertStatus, EntityType, RawDocument, RelationType, Relationship,
+    };
+    use serde_json::json;
+
+    use crate::shared::{MemoryAlertRuleStore, MemoryAlertStore};
+
+    fn rule(condition: &str) -> AlertRule {
+        AlertRule {
+            id: Uuid::new_v4(),
+            name: "watch".to_string(),
+            condition: condition.to_string(),
+            severity: AlertSeverity::High,
+            enabled: true,
+            created_at: Utc::now(),
+            channels: vec![],
+        }
+    }
+
+    fn sanctioned(org: &str) -> ExtractionResult {
+        let org = Entity::new(
+            EntityType::Organization,
+            org.to_string(),
+            "opensanctions".to_string(),
+        );
+        let list = Entity::new(
+            EntityType::Sanction,
+            "OFAC SDN".to_string(),
+            "opensanctions".to_string(),
+        );
+        let rel = Relationship::new(
+            org.id,
+            list.id,
+            RelationType::SanctionedBy,
+            "opensanctions".to_string(),
+        );
+        ExtractionResult {
+            entities: vec![org, list],
+            relationships: vec![rel],
+            raw_source: "opensanctions:1".to_string(),
+            extracted_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn matching_writes_raise_one_open_alert_per_subject() {
+        let rules = Arc::new(MemoryAlertRuleStore::default());
+        let alerts = Arc::new(MemoryAlertStore::default());
+        rules
+            .save(&rule(
+                "entity.type == organization AND new_relationship.type == sanctioned_by",
+            ))
+            .await
+            .unwrap();
+        let mut disabled = rule("entity.type == sanction");
+        disabled.enabled = false;
+        rules.save(&disabled).await.unwrap();
+        rules.save(&rule("entity.type ==")).await.unwrap();
+        let engine = AlertEngine::new(rules, alerts.clone());
+
+        // The same sanction seen again is the same open alert
+        assert_eq!(engine.evaluate(&[sanctioned("Acme Ltd")]).await.unwrap(), 1);
+        assert_eq!(
+            engine
+                .evaluate(&[sanctioned("ACME LTD"), sanctioned("Borealis")])
+                .await
+                .unwrap(),
+            1
+        );
+
+        let open = alerts.list(AlertStatus::Open, 10).await.unwrap();
+        assert_eq!(open.len(), 2);
+        assert_eq!(
+            open[0].message,
+            "Borealis sanctioned_by OFAC SDN matched watch"
+        );
+        assert_eq!(open[0].severity, AlertSeverity::High);
+        assert_eq!(open[0].entities.len(), 2);
+        assert!(open[0].relationship_id.is_some());
+    }
+
+    /// A vessel as the AIS agent reports it, and the entity extraction
+    /// makes of it: named after the document title, with its MMSI.
+    fn ais_result(
+        latitude: f64,
+        longitude: f64,
+        minute: u32,
+    ) -> (ExtractionResult, PositionReport) {
+        let document = RawDocument {
+            source: "ais".to_string(),
+            source_id: "353136000".to_string(),
+            title: Some("EVER GIVEN".to_string()),
+            content: format!("Vessel EVER GIVEN (MMSI: 353136000) at ({latitude}, {longitude})"),
+            url: None,
+            collected_at: Utc::now(),
+            observed_at: Some(format!("2026-03-01T10:{minute:02}:00Z").parse().unwrap()),
+            identifiers: [("mmsi".to_string(), "353136000".to_string())].into(),
+            metadata: json!({ "mmsi": 353136000, "name": "EVER GIVEN", "latitude": latitude, "longitude": longitude }),
+            structured: None,
+        };
+        let mut vessel = Entity::new(
+            EntityType::Vessel,
+            "EVER GIVEN".to_string(),
+            "ais".to_string(),
+        );
+        vessel.identifiers = document.identifiers.clone();
+        let mut result = ExtractionResult {
+            entities: vec![vessel],
+            relationships: vec![],
+            raw_source: document.source_id.clone(),
+            extracted_at: Utc::now(),
+        };
+        let report = PositionReport::from_document(&document).unwrap();
+        assert_eq!(report.place(&mut result.entities), 1);
+        (result, report)
+    }
+
+    #[test]
+    fn ais_vessels_alert_when_they_cross_into_the_box() {
+        let rule = rule("vessel enters bbox(32.0, 29.5, 33.0, 31.5)");
+        let condition = dsl::parse(&rule.condition).unwrap();
+        let check = |(result, _): &(ExtractionResult, PositionReport),
+                     stored: &[&PositionReport]| {
+            let stored: Vec<PositionReport> = stored.iter().map(|r| (*r).clone()).collect();
+            let current = entity_coordinates(&result.entities[0]).unwrap();
+            let previous = previous_position(current, &stored)
+                .map(|position| HashMap::from([(result.entities[0].id, position)]))
+                .unwrap_or_default();
+            matches(&rule, &condition, result, &previous).len()
+        };
+
+        let approaching = ais_result(29.0, 32.5, 0);
+        let entered = ais_result(30.0, 32.5, 10);
+        let inside = ais_result(30.5, 32.5, 20);
+
+        // First seen outside, then inside: the latest stored report is the
+        // current one, and the one before it was outside
+        assert_eq!(check(&approaching, &[&approaching.1]), 0);
+        assert_eq!(check(&entered, &[&approaching.1, &entered.1]), 1);
+        // Still in the box on the next report
+        assert_eq!(check(&inside, &[&entered.1, &inside.1]), 0);
+        // A first sighting inside counts as entering
+        assert_eq!(check(&inside, &[&inside.1]), 1);
+    }
+}
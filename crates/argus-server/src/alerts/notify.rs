@@ -0,0 +1,401 @@
+//! Alert delivery to the email and Slack channels on a rule.
+//!
+//! Alerts carry one [`Delivery`] per channel of their rule. Immediate
+//! channels are sent as soon as the alert is raised; digest channels, and
+//! immediate sends that failed, wait for [`Notifier::flush`], which the
+//! `alert_digest` job runs every `ALERT_DIGEST_INTERVAL_SECONDS`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use lettre::message::Mailbox;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use argus_core::alerts::MAX_ALERTS;
+use argus_core::{
+    Alert, AlertRule, AlertRuleStore, AlertStatus, AlertStore, AppConfig, ChannelTarget, Delivery,
+    DeliveryStatus, NotificationChannel, Result,
+};
+
+const DEFAULT_TEMPLATE: &str = "[{severity}] {rule}: {message}";
+
+pub struct Notifier {
+    smtp: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: Option<Mailbox>,
+    slack_webhook_url: Option<String>,
+    http: reqwest::Client,
+    rules: Arc<dyn AlertRuleStore>,
+    alerts: Arc<dyn AlertStore>,
+}
+
+impl Notifier {
+    pub fn from_config(
+        config: &AppConfig,
+        rules: Arc<dyn AlertRuleStore>,
+        alerts: Arc<dyn AlertStore>,
+    ) -> Self {
+        let smtp = if config.smtp_url.is_empty() {
+            None
+        } else {
+            match AsyncSmtpTransport::<Tokio1Executor>::from_url(&config.smtp_url) {
+                Ok(builder) => Some(builder.build()),
+                Err(e) => {
+                    warn!(error = %e, "Invalid SMTP_URL, email alert channels are disabled");
+                    None
+                }
+            }
+        };
+        let from = match config.alert_email_from.parse() {
+            Ok(from) => Some(from),
+            Err(e) => {
+                warn!(error = %e, "Invalid ALERT_EMAIL_FROM, email alert channels are disabled");
+                None
+            }
+        };
+        Self {
+            smtp,
+            from,
+            slack_webhook_url: Some(config.slack_webhook_url.clone()).filter(|url| !url.is_empty()),
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+            rules,
+            alerts,
+        }
+    }
+
+    /// Send newly raised alerts to `rule`'s immediate channels. Digest
+    /// channels are left pending for the next [`Notifier::flush`].
+    pub async fn deliver_new(&self, rule: &AlertRule, alerts: Vec<Alert>) {
+        for mut alert in alerts {
+            let mut changed = false;
+            for channel in rule.channels.iter().filter(|c| !c.digest) {
+                let label = channel.label();
+                let outcome = self.send(rule, channel, &[&alert]).await;
+                if let Some(delivery) = alert.deliveries.iter_mut().find(|d| d.channel == label) {
+                    delivery.record(outcome);
+                    changed = true;
+                }
+            }
+            if changed {
+                self.save_deliveries(alert.id, alert.deliveries).await;
+            }
+        }
+    }
+
+    /// Send every pending delivery: one digest per rule and digest channel,
+    /// and a retry of each failed immediate send. Returns the messages sent.
+    pub async fn flush(&self) -> Result<usize> {
+        let alerts: Vec<Alert> = self
+            .alerts
+            .list(AlertStatus::All, MAX_ALERTS)
+            .await?
+            .into_iter()
+            .filter(Alert::has_pending_deliveries)
+            .collect();
+        if alerts.is_empty() {
+            return Ok(0);
+        }
+        let rules: HashMap<Uuid, AlertRule> = self
+            .rules
+            .list()
+            .await?
+            .into_iter()
+            .map(|r| (r.id, r))
+            .collect();
+
+        // Oldest first, so digests read in the order things happened
+        let mut groups: BTreeMap<(Uuid, String), Vec<usize>> = BTreeMap::new();
+        for (i, alert) in alerts.iter().enumerate().rev() {
+            for delivery in alert
+                .deliveries
+                .iter()
+                .filter(|d| d.status == DeliveryStatus::Pending)
+            {
+                groups
+                    .entry((alert.rule_id, delivery.channel.clone()))
+                    .or_default()
+                    .push(i);
+            }
+        }
+
+        let mut outcomes: Vec<(usize, String, std::result::Result<(), String>)> = Vec::new();
+        let mut sent = 0;
+        for ((rule_id, label), members) in groups {
+            let channel = rules.get(&rule_id).and_then(|rule| {
+                rule.channels
+                    .iter()
+                    .find(|c| c.label() == label)
+                    .map(|c| (rule, c))
+            });
+            let Some((rule, channel)) = channel else {
+                for i in members {
+                    outcomes.push((
+                        i,
+                        label.clone(),
+                        Err("channel is no longer on the rule".to_string()),
+                    ));
+                }
+                continue;
+            };
+            let batches: Vec<Vec<usize>> = if channel.digest {
+                vec![members]
+            } else {
+                members.into_iter().map(|i| vec![i]).collect()
+            };
+            for batch in batches {
+                let batch_alerts: Vec<&Alert> = batch.iter().map(|&i| &alerts[i]).collect();
+                let outcome = self.send(rule, channel, &batch_alerts).await;
+                if outcome.is_ok() {
+                    sent += 1;
+                }
+                outcomes.extend(
+                    batch
+                        .into_iter()
+                        .map(|i| (i, label.clone(), outcome.clone())),
+                );
+            }
+        }
+
+        let mut updated: BTreeMap<usize, Vec<Delivery>> = BTreeMap::new();
+        for (i, label, outcome) in outcomes {
+            let deliveries = updated
+                .entry(i)
+                .or_insert_with(|| alerts[i].deliveries.clone());
+            if let Some(delivery) = deliveries.iter_mut().find(|d| d.channel == label) {
+                delivery.record(outcome);
+            }
+        }
+        for (i, deliveries) in updated {
+            self.save_deliveries(alerts[i].id, deliveries).await;
+        }
+        if sent > 0 {
+            info!(messages = sent, "Sent pending alert notifications");
+        }
+        Ok(sent)
+    }
+
+    /// Write back delivery state onto the stored alert, leaving any
+    /// acknowledgement made meanwhile intact.
+    async fn save_deliveries(&self, id: Uuid, deliveries: Vec<Delivery>) {
+        let result = match self.alerts.get(id).await {
+            Ok(Some(mut alert)) => {
+                alert.deliveries = deliveries;
+                self.alerts.save(&alert).await
+            }
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!(alert_id = %id, error = %e, "Failed to record alert delivery");
+        }
+    }
+
+    async fn send(
+        &self,
+        rule: &AlertRule,
+        channel: &NotificationChannel,
+        alerts: &[&Alert],
+    ) -> std::result::Result<(), String> {
+        let body = compose(channel, alerts);
+        match &channel.target {
+            ChannelTarget::Email { to } => {
+                let (Some(smtp), Some(from)) = (&self.smtp, &self.from) else {
+                    return Err("email is not configured (SMTP_URL, ALERT_EMAIL_FROM)".to_string());
+                };
+                let mut message = Message::builder()
+                    .from(from.clone())
+                    .subject(subject(rule, alerts));
+                for recipient in to {
+                    let mailbox: Mailbox = recipient
+                        .parse()
+                        .map_err(|e| format!("invalid recipient {recipient}: {e}"))?;
+                    message = message.to(mailbox);
+                }
+                let message = message.body(body).map_err(|e| e.to_string())?;
+                smtp.send(message)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+            ChannelTarget::Slack { webhook_url } => {
+                let Some(url) = webhook_url.as_ref().or(self.slack_webhook_url.as_ref()) else {
+                    return Err(
+                        "no Slack webhook (channel webhook_url or SLACK_WEBHOOK_URL)".to_string(),
+                    );
+                };
+                let response = self
+                    .http
+                    .post(url)
+                    .json(&serde_json::json!({ "text": body }))
+                    .send()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    Err(format!("Slack webhook returned {}", response.status()))
+                }
+            }
+        }
+    }
+}
+
+/// An alert written out with a channel's template.
+fn render(template: Option<&str>, alert: &Alert) -> String {
+    let entities: Vec<&str> = alert.entities.iter().map(|e| e.name.as_str()).collect();
+    let severity = serde_json::to_value(alert.severity)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_uppercase))
+        .unwrap_or_default();
+    template
+        .unwrap_or(DEFAULT_TEMPLATE)
+        .replace("{rule}", &alert.rule_name)
+        .replace("{severity}", &severity)
+        .replace("{message}", &alert.message)
+        .replace("{entities}", &entities.join(", "))
+        .replace("{source}", &alert.source)
+        .replace("{created_at}", &alert.created_at.to_rfc3339())
+        .replace("{id}", &alert.id.to_string())
+}
+
+fn compose(channel: &NotificationChannel, alerts: &[&Alert]) -> String {
+    let lines: Vec<String> = alerts
+        .iter()
+        .map(|a| render(channel.template.as_deref(), a))
+        .collect();
+    match alerts {
+        [_] => lines.join(""),
+        _ => format!(
+            "{} alerts from {}:\n{}",
+            alerts.len(),
+            alerts[0].rule_name,
+            lines
+                .iter()
+                .map(|l| format!("- {l}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+fn subject(rule: &AlertRule, alerts: &[&Alert]) -> String {
+    match alerts {
+        [alert] => format!("[ARGUS] {:?} alert: {}", alert.severity, rule.name),
+        _ => format!("[ARGUS] {} alerts: {}", alerts.len(), rule.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::{AlertEntity, AlertSeverity, EntityType};
+    use chrono::Utc;
+
+    use crate::shared::{MemoryAlertRuleStore, MemoryAlertStore};
+
+    fn slack(digest: bool, template: Option<&str>) -> NotificationChannel {
+        NotificationChannel {
+            target: ChannelTarget::Slack { webhook_url: None },
+            digest,
+            template: template.map(str::to_string),
+        }
+    }
+
+    fn alert(rule: &AlertRule, message: &str) -> Alert {
+        Alert {
+            id: Uuid::new_v4(),
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            severity: AlertSeverity::High,
+            message: message.to_string(),
+            entities: vec![AlertEntity {
+                id: Uuid::new_v4(),
+                name: "Acme Ltd".to_string(),
+                entity_type: EntityType::Organization,
+            }],
+            relationship_id: None,
+            source: "opensanctions".to_string(),
+            dedup_key: message.to_string(),
+            created_at: Utc::now(),
+            acknowledged_at: None,
+            acknowledged_by: None,
+            note: None,
+            deliveries: rule
+                .channels
+                .iter()
+                .map(|c| Delivery::pending(c.label()))
+                .collect(),
+        }
+    }
+
+    fn rule(channels: Vec<NotificationChannel>) -> AlertRule {
+        AlertRule {
+            id: Uuid::new_v4(),
+            name: "sanctioned orgs".to_string(),
+            condition: "entity.type == organization".to_string(),
+            severity: AlertSeverity::High,
+            enabled: true,
+            created_at: Utc::now(),
+            channels,
+        }
+    }
+
+    #[test]
+    fn messages_follow_the_template_and_digest_several_alerts() {
+        let rule = rule(vec![]);
+        let (first, second) = (
+            alert(&rule, "Acme Ltd sanctioned"),
+            alert(&rule, "Borealis sanctioned"),
+        );
+
+        let templated = slack(false, Some("{severity} {entities} via {source}"));
+        assert_eq!(
+            compose(&templated, &[&first]),
+            "HIGH Acme Ltd via opensanctions"
+        );
+        assert_eq!(
+            compose(&slack(true, None), &[&first, &second]),
+            "2 alerts from sanctioned orgs:\n- [HIGH] sanctioned orgs: Acme Ltd sanctioned\n- [HIGH] sanctioned orgs: Borealis sanctioned"
+        );
+        assert_eq!(
+            subject(&rule, &[&first, &second]),
+            "[ARGUS] 2 alerts: sanctioned orgs"
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_sends_stay_pending_until_attempts_run_out() {
+        let rules = Arc::new(MemoryAlertRuleStore::default());
+        let alerts = Arc::new(MemoryAlertStore::default());
+        let rule = rule(vec![slack(true, None)]);
+        rules.save(&rule).await.unwrap();
+        let raised = alert(&rule, "Acme Ltd sanctioned");
+        alerts.raise(raised.clone()).await.unwrap();
+
+        // No webhook configured anywhere, so every send fails
+        let mut config = AppConfig::from_env();
+        config.slack_webhook_url.clear();
+        let notifier = Notifier::from_config(&config, rules, alerts.clone());
+        assert_eq!(notifier.flush().await.unwrap(), 0);
+        let delivery = &alerts.get(raised.id).await.unwrap().unwrap().deliveries[0];
+        assert_eq!(
+            (delivery.status, delivery.attempts),
+            (DeliveryStatus::Pending, 1)
+        );
+        assert!(delivery
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("SLACK_WEBHOOK_URL"));
+
+        for _ in 1..argus_core::alerts::MAX_DELIVERY_ATTEMPTS {
+            notifier.flush().await.unwrap();
+        }
+        let delivery = &alerts.get(raised.id).await.unwrap().unwrap().deliveries[0];
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+    }
+}
@@ -0,0 +1,38 @@
+//! Wires the Parquet archive (`argus-coldstore`) into the server: position
+//! reports and document metadata are recorded by the agent pipeline, and
+//! Event entities by observing the write buffer.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use argus_coldstore::ColdStorage;
+use argus_core::{AppConfig, ExtractionResult};
+
+use crate::write_buffer::WriteObserver;
+
+/// The archive at `COLD_STORAGE_URL`, or `None` while it is empty or
+/// cannot be opened.
+pub fn from_config(config: &AppConfig) -> Option<Arc<ColdStorage>> {
+    let location = config.cold_storage_url.trim();
+    if location.is_empty() {
+        return None;
+    }
+    match ColdStorage::open(location, config.cold_storage_batch_rows) {
+        Ok(storage) => {
+            tracing::info!(location, "Archiving raw observations to cold storage");
+            Some(Arc::new(storage))
+        }
+        Err(e) => {
+            tracing::error!(location, error = %e, "Failed to open cold storage, raw observations will not be archived");
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl WriteObserver for ColdStorage {
+    async fn stored(&self, results: &[ExtractionResult]) {
+        self.record_events(results).await;
+    }
+}
@@ -0,0 +1,535 @@
+//! GraphQL schema over [`GraphStore`], served at `/graphql`.
+//!
+//! Entities resolve their neighbors, relationships and history lazily, so a
+//! client asks for exactly the projection it needs. Nesting is bounded by
+//! query depth and a complexity budget (see `GRAPHQL_MAX_DEPTH` and
+//! `GRAPHQL_MAX_COMPLEXITY`); list fields cost their `limit` times the cost
+//! of their selection.
+
+use std::sync::Arc;
+
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, Enum, Json, Object, Result, Schema,
+    SchemaBuilder, SimpleObject,
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use argus_core::api_types::TimelineRequest;
+use argus_core::ontology::Ontology;
+use argus_core::{
+    AppConfig, Direction, Entity, EntitySearchFilter, EntitySort, EntityType, GraphStore,
+    NeighborFilter, RelationType, Relationship, RelationshipFilter,
+};
+
+use crate::handlers::graph::MAX_NEIGHBOR_DEPTH;
+
+pub type ArgusSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Largest `limit` accepted by list fields.
+const MAX_LIST_LIMIT: usize = 500;
+
+pub fn build_schema(graph: Arc<dyn GraphStore>, config: &AppConfig) -> ArgusSchema {
+    schema_builder(config).data(graph).finish()
+}
+
+fn schema_builder(
+    config: &AppConfig,
+) -> SchemaBuilder<QueryRoot, EmptyMutation, EmptySubscription> {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(config.graphql_max_depth)
+        .limit_complexity(config.graphql_max_complexity)
+        .data(config.ontology.clone())
+}
+
+fn graph<'a>(ctx: &Context<'a>) -> &'a Arc<dyn GraphStore> {
+    ctx.data_unchecked::<Arc<dyn GraphStore>>()
+}
+
+/// The deployment's types, which type arguments must be drawn from.
+fn ontology<'a>(ctx: &Context<'a>) -> &'a Ontology {
+    ctx.data_unchecked::<Ontology>()
+}
+
+fn entity_type_arg(ctx: &Context<'_>, name: &str) -> Result<EntityType> {
+    let entity_type = EntityType::from_name(name);
+    ontology(ctx).check_entity_type(&entity_type)?;
+    Ok(entity_type)
+}
+
+fn relation_type_arg(ctx: &Context<'_>, name: &str) -> Result<RelationType> {
+    let relation_type = RelationType::from_name(name);
+    ontology(ctx).check_relation_type(&relation_type)?;
+    Ok(relation_type)
+}
+
+fn clamp_limit(limit: i32) -> usize {
+    (limit.max(1) as usize).min(MAX_LIST_LIMIT)
+}
+
+fn clamp_depth(depth: i32) -> u32 {
+    (depth.max(1) as u32).min(MAX_NEIGHBOR_DEPTH)
+}
+
+fn neighbor_filter(
+    ctx: &Context<'_>,
+    direction: EdgeDirection,
+    relation_types: Option<Vec<String>>,
+    min_strength: Option<f64>,
+) -> Result<NeighborFilter> {
+    Ok(NeighborFilter {
+        direction: direction.into(),
+        relation_types: relation_types
+            .unwrap_or_default()
+            .iter()
+            .map(|name| relation_type_arg(ctx, name))
+            .collect::<Result<_>>()?,
+        as_of: None,
+        min_strength,
+    })
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+#[graphql(name = "Direction")]
+pub enum EdgeDirection {
+    Outgoing,
+    Incoming,
+    #[default]
+    Both,
+}
+
+impl From<EdgeDirection> for Direction {
+    fn from(direction: EdgeDirection) -> Self {
+        match direction {
+            EdgeDirection::Outgoing => Direction::Outgoing,
+            EdgeDirection::Incoming => Direction::Incoming,
+            EdgeDirection::Both => Direction::Both,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+#[graphql(name = "EntitySort")]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    Name,
+    LastSeen,
+    SanctionsExposure,
+    Completeness,
+}
+
+impl From<SearchSort> for EntitySort {
+    fn from(sort: SearchSort) -> Self {
+        match sort {
+            SearchSort::Relevance => EntitySort::Relevance,
+            SearchSort::Name => EntitySort::Name,
+            SearchSort::LastSeen => EntitySort::LastSeen,
+            SearchSort::SanctionsExposure => EntitySort::SanctionsExposure,
+            SearchSort::Completeness => EntitySort::Completeness,
+        }
+    }
+}
+
+/// An entity in the knowledge graph.
+pub struct EntityNode(Entity);
+
+#[Object(name = "Entity")]
+impl EntityNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    /// Snake_case type name, e.g. `person` or a custom ontology type.
+    async fn entity_type(&self) -> &str {
+        self.0.entity_type.as_str()
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn aliases(&self) -> &[String] {
+        &self.0.aliases
+    }
+
+    async fn properties(&self) -> Json<&serde_json::Value> {
+        Json(&self.0.properties)
+    }
+
+    async fn source(&self) -> &str {
+        &self.0.source
+    }
+
+    async fn source_id(&self) -> Option<&str> {
+        self.0.source_id.as_deref()
+    }
+
+    async fn confidence(&self) -> f64 {
+        self.0.confidence
+    }
+
+    async fn first_seen(&self) -> DateTime<Utc> {
+        self.0.first_seen
+    }
+
+    async fn last_seen(&self) -> DateTime<Utc> {
+        self.0.last_seen
+    }
+
+    /// When the source data places the entity, as opposed to when it was collected.
+    async fn observed_at(&self) -> Option<DateTime<Utc>> {
+        self.0.observed_at
+    }
+
+    async fn sanctions_exposure(&self) -> Option<f64> {
+        self.0.sanctions_exposure
+    }
+
+    /// How filled-in the profile is, from 0.0 to 1.0.
+    async fn completeness(&self) -> Option<f64> {
+        self.0.completeness
+    }
+
+    /// External knowledge-base identifiers, keyed by scheme.
+    async fn identifiers(&self) -> Json<std::collections::BTreeMap<String, String>> {
+        Json(self.0.identifiers.clone())
+    }
+
+    /// Subgraph around this entity.
+    #[graphql(complexity = "10 * depth.max(1) as usize * child_complexity")]
+    async fn neighbors(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 1)] depth: i32,
+        #[graphql(default)] direction: EdgeDirection,
+        relation_types: Option<Vec<String>>,
+        min_strength: Option<f64>,
+    ) -> Result<Neighbors> {
+        let filter = neighbor_filter(ctx, direction, relation_types, min_strength)?;
+        let neighbors = graph(ctx)
+            .get_neighbors_filtered(self.0.id, clamp_depth(depth), &filter)
+            .await?;
+        Ok(Neighbors {
+            relationships: neighbors
+                .relationships
+                .into_iter()
+                .map(RelationshipNode)
+                .collect(),
+            neighbors: neighbors.neighbors.into_iter().map(EntityNode).collect(),
+            truncated: neighbors.truncated,
+        })
+    }
+
+    /// Recorded changes, newest first.
+    #[graphql(complexity = "limit.max(1) as usize * child_complexity")]
+    async fn history(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20)] limit: i32,
+    ) -> Result<Vec<Change>> {
+        let changes = graph(ctx)
+            .get_entity_history(self.0.id, clamp_limit(limit))
+            .await?;
+        Ok(changes
+            .into_iter()
+            .map(|c| Change {
+                kind: c.kind.as_str().to_string(),
+                field: c.field,
+                old_value: c.old_value.map(Json),
+                new_value: c.new_value.map(Json),
+                source: c.source,
+                document_id: c.document_id,
+                changed_at: c.changed_at,
+            })
+            .collect())
+    }
+}
+
+/// A directed, typed edge between two entities.
+pub struct RelationshipNode(Relationship);
+
+#[Object(name = "Relationship")]
+impl RelationshipNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn source_entity_id(&self) -> Uuid {
+        self.0.source_entity_id
+    }
+
+    async fn target_entity_id(&self) -> Uuid {
+        self.0.target_entity_id
+    }
+
+    async fn relation_type(&self) -> &str {
+        self.0.relation_type.as_str()
+    }
+
+    async fn properties(&self) -> Json<&serde_json::Value> {
+        Json(&self.0.properties)
+    }
+
+    async fn confidence(&self) -> f64 {
+        self.0.confidence
+    }
+
+    async fn source(&self) -> &str {
+        &self.0.source
+    }
+
+    async fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.0.timestamp
+    }
+
+    /// Co-occurrence strength in [0.0, 1.0]; null until first scored.
+    async fn strength(&self) -> Option<f64> {
+        self.0.strength
+    }
+
+    /// Entity the edge starts at.
+    #[graphql(complexity = "5 + child_complexity")]
+    async fn source_entity(&self, ctx: &Context<'_>) -> Result<Option<EntityNode>> {
+        Ok(graph(ctx)
+            .get_entity(self.0.source_entity_id)
+            .await?
+            .map(EntityNode))
+    }
+
+    /// Entity the edge points at.
+    #[graphql(complexity = "5 + child_complexity")]
+    async fn target_entity(&self, ctx: &Context<'_>) -> Result<Option<EntityNode>> {
+        Ok(graph(ctx)
+            .get_entity(self.0.target_entity_id)
+            .await?
+            .map(EntityNode))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Neighbors {
+    relationships: Vec<RelationshipNode>,
+    neighbors: Vec<EntityNode>,
+    /// The neighborhood was capped and is incomplete.
+    truncated: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct Change {
+    kind: String,
+    field: Option<String>,
+    old_value: Option<Json<serde_json::Value>>,
+    new_value: Option<Json<serde_json::Value>>,
+    source: String,
+    document_id: Option<String>,
+    changed_at: DateTime<Utc>,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct TimelineEntry {
+    timestamp: DateTime<Utc>,
+    event_type: String,
+    description: String,
+    source: String,
+    #[graphql(skip)]
+    entity: Entity,
+}
+
+#[ComplexObject]
+impl TimelineEntry {
+    async fn entity(&self) -> EntityNode {
+        EntityNode(self.entity.clone())
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn entity(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<EntityNode>> {
+        Ok(graph(ctx).get_entity(id).await?.map(EntityNode))
+    }
+
+    /// Many entities by id, in request order; unknown ids are omitted.
+    #[graphql(complexity = "ids.len() * child_complexity")]
+    async fn entities(&self, ctx: &Context<'_>, ids: Vec<Uuid>) -> Result<Vec<EntityNode>> {
+        if ids.len() > MAX_LIST_LIMIT {
+            return Err(format!("At most {MAX_LIST_LIMIT} ids per request").into());
+        }
+        let items = graph(ctx).get_entities_batch(&ids, false).await?;
+        Ok(items
+            .into_iter()
+            .map(|item| EntityNode(item.entity))
+            .collect())
+    }
+
+    /// Name search with optional type and exposure filters.
+    #[graphql(complexity = "limit.max(1) as usize * child_complexity")]
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        #[graphql(default = 20)] limit: i32,
+        entity_type: Option<String>,
+        min_sanctions_exposure: Option<f64>,
+        #[graphql(default)] sort: SearchSort,
+    ) -> Result<Vec<EntityNode>> {
+        let filter = EntitySearchFilter {
+            entity_type: entity_type
+                .map(|name| entity_type_arg(ctx, &name))
+                .transpose()?,
+            min_sanctions_exposure,
+            sort: sort.into(),
+        };
+        let entities = graph(ctx)
+            .search_entities_filtered(&query, &filter, clamp_limit(limit))
+            .await?;
+        Ok(entities.into_iter().map(EntityNode).collect())
+    }
+
+    /// Relationships matching the filters, newest first.
+    #[graphql(complexity = "limit.max(1) as usize * child_complexity")]
+    #[allow(clippy::too_many_arguments)]
+    async fn relationships(
+        &self,
+        ctx: &Context<'_>,
+        relation_type: Option<String>,
+        source: Option<String>,
+        min_confidence: Option<f64>,
+        created_after: Option<DateTime<Utc>>,
+        created_before: Option<DateTime<Utc>>,
+        #[graphql(default = 100)] limit: i32,
+        #[graphql(default = 0)] offset: i32,
+    ) -> Result<Vec<RelationshipNode>> {
+        let filter = RelationshipFilter {
+            relation_type: relation_type
+                .map(|name| relation_type_arg(ctx, &name))
+                .transpose()?,
+            source,
+            min_confidence,
+            created_after,
+            created_before,
+            limit: clamp_limit(limit),
+            offset: offset.max(0) as usize,
+        };
+        let relationships = graph(ctx).list_relationships(&filter).await?;
+        Ok(relationships.into_iter().map(RelationshipNode).collect())
+    }
+
+    /// Time-ordered events, optionally around one entity.
+    #[graphql(complexity = "limit.max(1) as usize * child_complexity")]
+    async fn timeline(
+        &self,
+        ctx: &Context<'_>,
+        entity_id: Option<Uuid>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        #[graphql(default = 20)] limit: i32,
+    ) -> Result<Vec<TimelineEntry>> {
+        let request = TimelineRequest {
+            entity_id,
+            start,
+            end,
+            limit: clamp_limit(limit),
+        };
+        let events =
+            crate::handlers::entities::fetch_timeline(graph(ctx).as_ref(), &request).await?;
+        Ok(events
+            .into_iter()
+            .map(|event| TimelineEntry {
+                timestamp: event.timestamp,
+                event_type: event.event_type,
+                description: event.description,
+                source: event.source,
+                entity: event.entity,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_depth: usize, max_complexity: usize) -> AppConfig {
+        let mut config = AppConfig::from_env();
+        config.graphql_max_depth = max_depth;
+        config.graphql_max_complexity = max_complexity;
+        config
+    }
+
+    // Limits are enforced during validation, before any resolver touches
+    // the graph, so these run without a store attached.
+    async fn errors(config: &AppConfig, query: &str) -> Vec<String> {
+        let schema = schema_builder(config).finish();
+        schema
+            .execute(query)
+            .await
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rejects_queries_nested_too_deep() {
+        let query = "{ search(query: \"a\", limit: 1) { neighbors { neighbors { neighbors { neighbors { name } } } } } }";
+        let errors = errors(&config(4, 1_000_000), query).await;
+        assert!(
+            errors.iter().any(|e| e.contains("nested too deep")),
+            "{errors:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn list_limits_count_against_complexity_budget() {
+        let query = "{ search(query: \"a\", limit: 500) { name neighbors(depth: 3) { neighbors { name } } } }";
+        let errors = errors(&config(20, 1000), query).await;
+        assert!(
+            errors.iter().any(|e| e.contains("too complex")),
+            "{errors:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn unregistered_type_arguments_are_rejected() {
+        let search = errors(
+            &config(10, 1000),
+            "{ search(query: \"a\", entityType: \"persn\") { name } }",
+        )
+        .await;
+        assert!(
+            search
+                .iter()
+                .any(|e| e.contains("unknown entity type `persn`")),
+            "{search:?}"
+        );
+        let listing = errors(
+            &config(10, 1000),
+            "{ relationships(relationType: \"owns_of\") { id } }",
+        )
+        .await;
+        assert!(
+            listing
+                .iter()
+                .any(|e| e.contains("unknown relationship type `owns_of`")),
+            "{listing:?}"
+        );
+    }
+
+    #[test]
+    fn sdl_exposes_core_types() {
+        let sdl = schema_builder(&config(10, 1000)).finish().sdl();
+        for needle in [
+            "type Entity",
+            "type Relationship",
+            "type Neighbors",
+            "timeline(",
+            "search(",
+        ] {
+            assert!(sdl.contains(needle), "missing {needle}");
+        }
+    }
+}
@@ -0,0 +1,1043 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use argus_core::api_types::{
+    AgentRunState, AgentRunStatus, AgentTriggerResponse, ConsumerUsage, DenyEntryRequest,
+    DenyListResponse, DuplicateMergeRequest, DuplicateMergeResponse, DuplicateQueueParams,
+    DuplicateQueueResponse, ExtractionCacheStatsResponse, ExtractionFailureEntry,
+    ExtractionFailureListParams, ExtractionFailureListResponse, LlmLogPurgeParams,
+    PromptBundleListResponse, PromptBundleRequest, PromptCompareParams, PromptDeploymentRequest,
+    SnapshotListResponse, SnapshotRestoreRequest, TombstoneListParams, TombstoneListResponse,
+    UsageParams, UsageResponse,
+};
+use argus_core::backup::is_snapshot_name;
+use argus_core::calibration::{FeedbackLabel, FeedbackTarget};
+use argus_core::llm::LlmPurpose;
+use argus_core::prompts::{
+    compare_versions, select_examples, ExampleCandidate, PromptDeployment, BASELINE_PROMPT_VERSION,
+};
+use argus_core::usage::usage_day;
+use argus_core::{
+    ArgusError, DenyEntry, GraphStore, RetentionAction, RetentionPolicy, DOCUMENT_LABEL,
+};
+use argus_extraction::DenyList;
+
+use argus_core::shared::MAX_RUN_HISTORY;
+
+use crate::jobs::{
+    backup, deny_list, duplicate_scan, embedding_backfill, integrity, observed_at_backfill,
+    prompt_bundles, retention,
+};
+use crate::live_config;
+use crate::shared::{insert_run, update_run};
+use crate::state::AppState;
+
+/// POST /api/admin/embeddings/backfill — embed all existing entities into Qdrant.
+/// Returns 202 Accepted with a run_id; progress is visible in /api/agents/runs.
+pub async fn trigger_embedding_backfill(State(state): State<AppState>) -> impl IntoResponse {
+    if !state.embeddings.is_configured() {
+        warn!("Embedding backfill requested but VOYAGE_API_KEY is not set");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Embedding provider not configured (VOYAGE_API_KEY)" })),
+        )
+            .into_response();
+    }
+
+    if let Err(response) =
+        ensure_not_running(&state, embedding_backfill::JOB_NAME, "Embedding backfill").await
+    {
+        return response;
+    }
+
+    let run_id = Uuid::new_v4().to_string();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), embedding_backfill::JOB_NAME),
+    )
+    .await;
+
+    info!(run_id = %run_id, "Triggering embedding backfill");
+
+    tokio::spawn(embedding_backfill::run(
+        run_id.clone(),
+        state.graph.clone(),
+        state.embeddings.clone(),
+        state.vectors.clone(),
+        state.runs.clone(),
+        state.rate_limiter.clone(),
+        state.config().embedding_requests_per_minute,
+    ));
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AgentTriggerResponse {
+            run_id,
+            agent_name: embedding_backfill::JOB_NAME.to_string(),
+            status: "running".to_string(),
+            message: "Embedding backfill started in background".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+/// 409 if a run of `job_name` is still in progress.
+async fn ensure_not_running(state: &AppState, job_name: &str, label: &str) -> Result<(), Response> {
+    let runs = state.runs.list(MAX_RUN_HISTORY).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to read run history: {e}") })),
+        )
+            .into_response()
+    })?;
+    match runs
+        .iter()
+        .find(|r| r.agent_name == job_name && r.status == AgentRunState::Running)
+    {
+        Some(active) => Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!("{label} already running (run {})", active.run_id)
+            })),
+        )
+            .into_response()),
+        None => Ok(()),
+    }
+}
+
+/// POST /api/admin/observed-at/backfill — date entities stored before
+/// `observed_at` existed from the dates in their properties.
+/// Returns 202 Accepted with a run_id; progress is visible in /api/agents/runs.
+pub async fn trigger_observed_at_backfill(State(state): State<AppState>) -> impl IntoResponse {
+    if let Err(response) = ensure_not_running(
+        &state,
+        observed_at_backfill::JOB_NAME,
+        "observed_at backfill",
+    )
+    .await
+    {
+        return response;
+    }
+
+    let run_id = Uuid::new_v4().to_string();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), observed_at_backfill::JOB_NAME),
+    )
+    .await;
+
+    info!(run_id = %run_id, "Triggering observed_at backfill");
+
+    tokio::spawn(observed_at_backfill::run(
+        run_id.clone(),
+        state.graph.clone(),
+        state.runs.clone(),
+    ));
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AgentTriggerResponse {
+            run_id,
+            agent_name: observed_at_backfill::JOB_NAME.to_string(),
+            status: "running".to_string(),
+            message: "observed_at backfill started in background".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+/// POST /api/admin/duplicates/scan — look for probable duplicate entities
+/// and replace the review queue with what is found.
+/// Returns 202 Accepted with a run_id; progress is visible in /api/agents/runs.
+pub async fn trigger_duplicate_scan(State(state): State<AppState>) -> impl IntoResponse {
+    if let Err(response) =
+        ensure_not_running(&state, duplicate_scan::JOB_NAME, "Duplicate scan").await
+    {
+        return response;
+    }
+
+    let run_id = Uuid::new_v4().to_string();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), duplicate_scan::JOB_NAME),
+    )
+    .await;
+
+    info!(run_id = %run_id, "Triggering duplicate scan");
+
+    tokio::spawn(duplicate_scan::run(
+        run_id.clone(),
+        state.graph.clone(),
+        state.duplicates.clone(),
+        state.runs.clone(),
+    ));
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AgentTriggerResponse {
+            run_id,
+            agent_name: duplicate_scan::JOB_NAME.to_string(),
+            status: "running".to_string(),
+            message: "Duplicate scan started in background".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+/// GET /api/admin/duplicates — the duplicate review queue, highest score first.
+pub async fn list_duplicates(
+    State(state): State<AppState>,
+    Query(params): Query<DuplicateQueueParams>,
+) -> impl IntoResponse {
+    match state.duplicates.list().await {
+        Ok(mut candidates) => {
+            let min_score = params.min_score.unwrap_or(0.0);
+            candidates.retain(|c| c.score >= min_score);
+            let total = candidates.len();
+            candidates.truncate(params.limit());
+            (
+                StatusCode::OK,
+                Json(DuplicateQueueResponse { candidates, total }),
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to read duplicate queue: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/admin/duplicates/{id}/merge — fold a candidate pair into one
+/// entity, keeping the suggested survivor unless the body names the other.
+pub async fn merge_duplicate(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    request: Option<Json<DuplicateMergeRequest>>,
+) -> impl IntoResponse {
+    let candidate = match state.duplicates.get(&id).await {
+        Ok(Some(candidate)) => candidate,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Duplicate candidate {id} not found") })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(
+                    serde_json::json!({ "error": format!("Failed to read duplicate queue: {e}") }),
+                ),
+            )
+                .into_response();
+        }
+    };
+
+    let keep = request.and_then(|Json(r)| r.keep).unwrap_or(candidate.keep);
+    if !candidate.involves(keep) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Entity {keep} is not part of candidate {id}") })),
+        )
+            .into_response();
+    }
+    let merged = candidate.merged_away(keep);
+
+    info!(candidate = %id, %keep, ?merged, "Merging duplicate entities");
+
+    match state.graph.merge_entities(keep, &merged).await {
+        Ok(entity) => {
+            if let Err(e) = state.duplicates.remove_involving(&merged).await {
+                warn!(candidate = %id, error = %e, "Failed to drop merged entities from the duplicate queue");
+            }
+            (
+                StatusCode::OK,
+                Json(DuplicateMergeResponse { entity, merged }),
+            )
+                .into_response()
+        }
+        Err(ArgusError::NotFound(message)) => {
+            // One side is already gone, so the pair is stale
+            let ids: Vec<Uuid> = candidate.entities.iter().map(|e| e.id).collect();
+            if let Err(e) = state.duplicates.remove_involving(&ids).await {
+                warn!(candidate = %id, error = %e, "Failed to drop stale duplicate candidate");
+            }
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": message })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!(candidate = %id, error = %e, "Failed to merge duplicate entities");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to merge entities: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /api/admin/duplicates/{id}/dismiss — mark a candidate as not a
+/// duplicate so later scans leave it out.
+pub async fn dismiss_duplicate(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.duplicates.dismiss(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Duplicate candidate {id} not found") })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to dismiss candidate: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/admin/extraction-cache — hit/miss counters for the extraction cache.
+pub async fn extraction_cache_stats(State(state): State<AppState>) -> impl IntoResponse {
+    let stats = state.extraction.llm().cache_stats();
+    let response = ExtractionCacheStatsResponse {
+        enabled: stats.is_some(),
+        hit_rate: stats.as_ref().map(|s| s.hit_rate()).unwrap_or(0.0),
+        stats,
+    };
+    (StatusCode::OK, Json(response))
+}
+
+/// GET /api/admin/graph-writes — extraction write transactions since
+/// startup, how many were retried after a transient Neo4j error, and which
+/// errors those were.
+pub async fn graph_write_stats(State(state): State<AppState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.graph.write_stats()))
+}
+
+/// GET /api/admin/extraction-failures — documents whose model output could
+/// not be parsed, newest first, with the output and the parse error.
+pub async fn list_extraction_failures(
+    State(state): State<AppState>,
+    Query(params): Query<ExtractionFailureListParams>,
+) -> impl IntoResponse {
+    match state
+        .extraction_failures
+        .list(params.source.as_deref(), params.limit())
+        .await
+    {
+        Ok(failures) => {
+            let failures = failures.iter().map(ExtractionFailureEntry::from).collect();
+            (
+                StatusCode::OK,
+                Json(ExtractionFailureListResponse { failures }),
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(
+                serde_json::json!({ "error": format!("Failed to list extraction failures: {e}") }),
+            ),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/admin/usage — requests per endpoint, reasoning tokens and
+/// exported records per API token over the last `days` (default 7).
+pub async fn usage_report(
+    State(state): State<AppState>,
+    Query(params): Query<UsageParams>,
+) -> impl IntoResponse {
+    let until = usage_day(chrono::Utc::now());
+    let since = until - chrono::Duration::days(params.days() as i64 - 1);
+    match state.usage.totals(since).await {
+        Ok(totals) => {
+            let mut consumers: Vec<ConsumerUsage> = totals
+                .into_iter()
+                .map(|(consumer, usage)| ConsumerUsage { consumer, usage })
+                .collect();
+            consumers.sort_by_key(|c| std::cmp::Reverse(c.usage.requests));
+            (
+                StatusCode::OK,
+                Json(UsageResponse {
+                    since,
+                    until,
+                    consumers,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to read usage: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/admin/extraction-failures/{id}/retry — re-extract one
+/// cataloged failure's document. Returns 202 Accepted with the run_id of
+/// the retry; the failure leaves the catalog once it is retried.
+pub async fn retry_extraction_failure(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let failure = match state.extraction_failures.get(id).await {
+        Ok(Some(failure)) => failure,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(
+                    serde_json::json!({ "error": format!("Extraction failure '{id}' not found") }),
+                ),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Failed to load extraction failure: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to load extraction failure: {e}") })),
+            )
+                .into_response();
+        }
+    };
+
+    let run_id = Uuid::new_v4().to_string();
+    let agent_name = failure.agent_name.clone();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), agent_name.clone()),
+    )
+    .await;
+    info!(run_id = %run_id, failure_id = %id, "Retrying cataloged extraction failure");
+
+    let retry_state = state.clone();
+    let retry_id = run_id.clone();
+    tokio::spawn(async move {
+        let outcome =
+            crate::pipeline::retry_cataloged_failure(&retry_state, &retry_id, failure).await;
+        update_run(&retry_state.runs, &retry_id, |run| {
+            run.finished_at = Some(chrono::Utc::now());
+            run.documents_collected = outcome.documents;
+            run.entities_extracted = outcome.entities;
+            run.documents_failed = outcome.failures.len() as u64;
+            run.validation = outcome.validation;
+            match outcome.failures.first() {
+                Some(failure) => {
+                    run.status = AgentRunState::Failed;
+                    run.error = Some(failure.error.clone());
+                }
+                None => run.status = AgentRunState::Completed,
+            }
+        })
+        .await;
+    });
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AgentTriggerResponse {
+            run_id,
+            agent_name,
+            status: "running".to_string(),
+            message: "Retrying extraction in background".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+/// GET /api/admin/retention — what the retention policies would remove now,
+/// per policy, without removing anything.
+pub async fn retention_dry_run(State(state): State<AppState>) -> impl IntoResponse {
+    let config = state.config();
+    let report = retention::enforce(
+        state.graph.as_ref(),
+        &config.effective_retention_policies(),
+        std::path::Path::new(&config.retention_archive_dir),
+        true,
+        chrono::Utc::now(),
+    )
+    .await;
+    (StatusCode::OK, Json(report))
+}
+
+/// POST /api/admin/llm-log/purge — remove logged LLM calls past their
+/// retention now, or past `max_age_days` when given. A configured policy
+/// for an `llm-*` source keeps its action.
+pub async fn purge_llm_log(
+    State(state): State<AppState>,
+    Query(params): Query<LlmLogPurgeParams>,
+) -> Response {
+    let config = state.config();
+    let configured = config.effective_retention_policies();
+    let mut policies = Vec::new();
+    for purpose in LlmPurpose::ALL {
+        let source = purpose.archive_source();
+        let policy = configured
+            .iter()
+            .find(|p| p.label == DOCUMENT_LABEL && p.source.as_deref() == Some(source.as_str()))
+            .cloned()
+            .unwrap_or_else(|| RetentionPolicy {
+                label: DOCUMENT_LABEL.to_string(),
+                source: Some(source),
+                max_age_days: config.llm_log_retention_days,
+                action: RetentionAction::Delete,
+            });
+        match params.max_age_days {
+            Some(max_age_days) => policies.push(RetentionPolicy {
+                max_age_days,
+                ..policy
+            }),
+            None if policy.max_age_days > 0 => policies.push(policy),
+            None => {}
+        }
+    }
+    if policies.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "LLM_LOG_RETENTION_DAYS is 0; pass max_age_days" })),
+        )
+            .into_response();
+    }
+
+    let report = retention::enforce(
+        state.graph.as_ref(),
+        &policies,
+        std::path::Path::new(&config.retention_archive_dir),
+        false,
+        chrono::Utc::now(),
+    )
+    .await;
+    let deleted: u64 = report.policies.iter().map(|p| p.deleted).sum();
+    info!(deleted, max_age_days = ?params.max_age_days, "Purged LLM call log");
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// GET /api/admin/integrity — violations of each graph invariant now, with
+/// a few samples, without changing anything.
+pub async fn integrity_report(State(state): State<AppState>) -> impl IntoResponse {
+    let report = integrity::check(state.graph.as_ref(), false, chrono::Utc::now()).await;
+    (StatusCode::OK, Json(report))
+}
+
+/// POST /api/admin/integrity/repair — check every invariant and apply the
+/// safe fixes; `repaired` counts what each one fixed.
+pub async fn repair_integrity(State(state): State<AppState>) -> impl IntoResponse {
+    let report = integrity::check(state.graph.as_ref(), true, chrono::Utc::now()).await;
+    (StatusCode::OK, Json(report))
+}
+
+/// GET /api/admin/tombstones — deleted entities, most recent first: hidden
+/// ones that can still be restored, and purged ones.
+pub async fn list_tombstones(
+    State(state): State<AppState>,
+    Query(params): Query<TombstoneListParams>,
+) -> impl IntoResponse {
+    match state.graph.list_tombstones(params.limit()).await {
+        Ok(tombstones) => {
+            (StatusCode::OK, Json(TombstoneListResponse { tombstones })).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to list tombstones: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/admin/entities/{id}/restore — undo a soft delete.
+pub async fn restore_entity(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.graph.restore_entity(id).await {
+        Ok(entity) => (StatusCode::OK, Json(entity)).into_response(),
+        Err(ArgusError::NotFound(message)) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": message })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!(entity = %id, error = %e, "Failed to restore entity");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to restore entity: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /api/admin/entities/{id}/purge — delete an entity, its relationships
+/// and its history for good. The tombstone stays, so agents still don't
+/// store it again.
+pub async fn purge_entity(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.graph.purge_entity(id).await {
+        Ok(tombstone) => (StatusCode::OK, Json(tombstone)).into_response(),
+        Err(ArgusError::NotFound(message)) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": message })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!(entity = %id, error = %e, "Failed to purge entity");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to purge entity: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /api/admin/deny-list — names suppressed during validation, oldest first.
+pub async fn list_deny_list(State(state): State<AppState>) -> impl IntoResponse {
+    match state.deny_list.list().await {
+        Ok(entries) => (StatusCode::OK, Json(DenyListResponse { entries })).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to list deny-list: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/admin/deny-list — suppress a name or name pattern from now on;
+/// 400 if the pattern is empty or not a valid regex.
+pub async fn add_deny_entry(
+    State(state): State<AppState>,
+    Json(request): Json<DenyEntryRequest>,
+) -> impl IntoResponse {
+    let entry = DenyEntry {
+        id: Uuid::new_v4(),
+        pattern: request.pattern.trim().to_string(),
+        match_kind: request.match_kind,
+        entity_type: request.entity_type,
+        source: request
+            .source
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty()),
+        note: request.note,
+        created_at: chrono::Utc::now(),
+    };
+    if let Err(e) = DenyList::check(&entry) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response();
+    }
+    if let Err(e) = state.deny_list.save(&entry).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to save deny-list entry: {e}") })),
+        )
+            .into_response();
+    }
+    info!(id = %entry.id, pattern = %entry.pattern, "Added deny-list entry");
+    reload_deny_list(&state).await;
+    (StatusCode::CREATED, Json(entry)).into_response()
+}
+
+/// DELETE /api/admin/deny-list/{id} — stop suppressing an entry's names.
+pub async fn delete_deny_entry(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.deny_list.delete(id).await {
+        Ok(true) => {
+            reload_deny_list(&state).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Deny-list entry {id} not found") })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to delete deny-list entry: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// Apply an edit on this replica right away; the others pick it up on
+/// their next refresh.
+async fn reload_deny_list(state: &AppState) {
+    deny_list::reload(state.deny_list.as_ref(), state.validator.deny_list()).await;
+}
+
+/// Most recent feedback labels a prompt bundle's examples are chosen from.
+const PROMPT_BUNDLE_LABELS: usize = 500;
+
+/// GET /api/admin/prompt-bundles — extraction prompt bundles, newest first,
+/// and the current deployment.
+pub async fn list_prompt_bundles(State(state): State<AppState>) -> impl IntoResponse {
+    let listed = async {
+        Ok::<_, ArgusError>((
+            state.prompts.list().await?,
+            state.prompts.deployment().await?,
+        ))
+    };
+    match listed.await {
+        Ok((bundles, deployment)) => (
+            StatusCode::OK,
+            Json(PromptBundleListResponse {
+                bundles,
+                deployment,
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to list prompt bundles: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/admin/prompt-bundles — build a new bundle version from the
+/// most recent analyst feedback. It is not used until deployed.
+pub async fn create_prompt_bundle(
+    State(state): State<AppState>,
+    Json(request): Json<PromptBundleRequest>,
+) -> impl IntoResponse {
+    let labels = match state.feedback.list().await {
+        Ok(labels) => labels,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to load feedback: {e}") })),
+            )
+                .into_response()
+        }
+    };
+    let candidates = match example_candidates(&state, labels.into_iter().take(PROMPT_BUNDLE_LABELS))
+        .await
+    {
+        Ok(candidates) => candidates,
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to look up labelled items: {e}") })),
+        )
+            .into_response(),
+    };
+    let examples = select_examples(&candidates, request.per_source());
+    if examples.is_empty() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({ "error": "No feedback labels usable as examples yet" })),
+        )
+            .into_response();
+    }
+
+    let note = request
+        .note
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    match state.prompts.create(note, examples).await {
+        Ok(bundle) => {
+            info!(
+                version = bundle.version,
+                examples = bundle.example_count(),
+                "Created prompt bundle"
+            );
+            (StatusCode::CREATED, Json(bundle)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to save prompt bundle: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// The labelled entities and relationships that still exist, written the
+/// way the extraction output schema writes them.
+async fn example_candidates(
+    state: &AppState,
+    labels: impl Iterator<Item = FeedbackLabel>,
+) -> argus_core::Result<Vec<ExampleCandidate>> {
+    let mut names = std::collections::HashMap::new();
+    let mut candidates = Vec::new();
+    for label in labels {
+        let Some(id) = label.target_id else {
+            continue;
+        };
+        let (text, item) = match label.target {
+            FeedbackTarget::Relationship => {
+                let Some(rel) = state.graph.get_relationship(id).await? else {
+                    continue;
+                };
+                let source = entity_name(state, &mut names, rel.source_entity_id).await?;
+                let target = entity_name(state, &mut names, rel.target_entity_id).await?;
+                let (Some(source), Some(target)) = (source, target) else {
+                    continue;
+                };
+                let item = serde_json::json!({ "source": source, "target": target, "type": rel.relation_type });
+                (rel.evidence().map(str::to_string), item)
+            }
+            FeedbackTarget::Entity if !label.correct => {
+                let Some(entity) = state.graph.get_entity(id).await? else {
+                    continue;
+                };
+                (
+                    None,
+                    serde_json::json!({ "name": entity.name, "type": entity.entity_type }),
+                )
+            }
+            _ => continue,
+        };
+        candidates.push(ExampleCandidate { label, text, item });
+    }
+    Ok(candidates)
+}
+
+/// An entity's name, looked up once per bundle build.
+async fn entity_name(
+    state: &AppState,
+    names: &mut std::collections::HashMap<Uuid, Option<String>>,
+    id: Uuid,
+) -> argus_core::Result<Option<String>> {
+    if let Some(name) = names.get(&id) {
+        return Ok(name.clone());
+    }
+    let name = state.graph.get_entity(id).await?.map(|e| e.name);
+    names.insert(id, name.clone());
+    Ok(name)
+}
+
+/// GET /api/admin/prompt-deployment — the bundles extraction uses now.
+pub async fn get_prompt_deployment(State(state): State<AppState>) -> impl IntoResponse {
+    match state.prompts.deployment().await {
+        Ok(deployment) => (StatusCode::OK, Json(deployment)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to load prompt deployment: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/admin/prompt-deployment — extract with `control`, and with
+/// `candidate` for `candidate_share` of documents. 400 if a version does
+/// not exist or the share is outside 0 to 1.
+pub async fn set_prompt_deployment(
+    State(state): State<AppState>,
+    Json(request): Json<PromptDeploymentRequest>,
+) -> impl IntoResponse {
+    let candidate_share = match (request.candidate, request.candidate_share) {
+        (None, _) => 0.0,
+        (Some(_), None) => 0.5,
+        (Some(_), Some(share)) if (0.0..=1.0).contains(&share) => share,
+        (Some(_), Some(share)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("candidate_share must be from 0 to 1, got {share}") })),
+            )
+                .into_response()
+        }
+    };
+    for version in std::iter::once(request.control).chain(request.candidate) {
+        if version == BASELINE_PROMPT_VERSION {
+            continue;
+        }
+        match state.prompts.get(version).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": format!("Prompt bundle {version} does not exist") })),
+                )
+                    .into_response()
+            }
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "error": format!("Failed to load prompt bundle {version}: {e}") })),
+                )
+                    .into_response()
+            }
+        }
+    }
+
+    let deployment = PromptDeployment {
+        control: request.control,
+        candidate: request.candidate,
+        candidate_share,
+        updated_at: chrono::Utc::now(),
+    };
+    if let Err(e) = state.prompts.set_deployment(&deployment).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to save prompt deployment: {e}") })),
+        )
+            .into_response();
+    }
+    info!(
+        control = deployment.control,
+        candidate = ?deployment.candidate,
+        share = deployment.candidate_share,
+        "Deployed prompt bundles"
+    );
+    // Apply on this replica right away; the others pick it up on their next refresh.
+    prompt_bundles::reload(state.prompts.as_ref(), state.extraction.llm().prompts()).await;
+    (StatusCode::OK, Json(deployment)).into_response()
+}
+
+/// GET /api/admin/prompt-bundles/compare — analyst feedback on items
+/// extracted with two prompt versions, by default the deployed pair.
+pub async fn compare_prompt_bundles(
+    State(state): State<AppState>,
+    Query(params): Query<PromptCompareParams>,
+) -> impl IntoResponse {
+    let loaded = async {
+        Ok::<_, ArgusError>((
+            state.prompts.deployment().await?,
+            state.feedback.list().await?,
+        ))
+    };
+    match loaded.await {
+        Ok((deployment, labels)) => {
+            let control = params.control.unwrap_or(deployment.control);
+            let candidate = params.candidate.or(deployment.candidate).unwrap_or(control);
+            (
+                StatusCode::OK,
+                Json(compare_versions(&labels, control, candidate)),
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to load feedback: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// 409 if a backup or restore is still in progress; they must not overlap.
+async fn ensure_no_snapshot_job(state: &AppState) -> Result<(), Response> {
+    ensure_not_running(state, backup::BACKUP_JOB, "Backup").await?;
+    ensure_not_running(state, backup::RESTORE_JOB, "Restore").await
+}
+
+/// POST /api/admin/backup — write a snapshot of the graph, alert rules,
+/// deny-list and paused agents to `BACKUP_DIR`.
+/// Returns 202 Accepted with a run_id; progress is visible in /api/agents/runs.
+pub async fn trigger_backup(State(state): State<AppState>) -> impl IntoResponse {
+    if let Err(response) = ensure_no_snapshot_job(&state).await {
+        return response;
+    }
+
+    let run_id = Uuid::new_v4().to_string();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), backup::BACKUP_JOB),
+    )
+    .await;
+
+    let name = backup::snapshot_name(chrono::Utc::now());
+    info!(run_id = %run_id, file = %name, "Triggering workspace backup");
+    tokio::spawn(backup::backup(run_id.clone(), state.clone(), name.clone()));
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AgentTriggerResponse {
+            run_id,
+            agent_name: backup::BACKUP_JOB.to_string(),
+            status: "running".to_string(),
+            message: format!("Writing snapshot {name} in background"),
+        }),
+    )
+        .into_response()
+}
+
+/// GET /api/admin/backups — finished snapshots in `BACKUP_DIR`, newest first.
+pub async fn list_backups(State(state): State<AppState>) -> impl IntoResponse {
+    match backup::list(std::path::Path::new(&state.config().backup_dir)).await {
+        Ok(snapshots) => (StatusCode::OK, Json(SnapshotListResponse { snapshots })).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("Failed to list snapshots: {e}") })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/admin/restore — replace the graph, alert rules, deny-list and
+/// paused agents with a snapshot from `BACKUP_DIR`. The snapshot is checked
+/// end to end before anything is deleted.
+/// Returns 202 Accepted with a run_id; progress is visible in /api/agents/runs.
+pub async fn trigger_restore(
+    State(state): State<AppState>,
+    Json(request): Json<SnapshotRestoreRequest>,
+) -> impl IntoResponse {
+    if !is_snapshot_name(&request.file) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Not a snapshot file name: {}", request.file) })),
+        )
+            .into_response();
+    }
+    let path = std::path::Path::new(&state.config().backup_dir).join(&request.file);
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Snapshot {} not found", request.file) })),
+        )
+            .into_response();
+    }
+    if let Err(response) = ensure_no_snapshot_job(&state).await {
+        return response;
+    }
+
+    let run_id = Uuid::new_v4().to_string();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), backup::RESTORE_JOB),
+    )
+    .await;
+
+    warn!(run_id = %run_id, file = %request.file, "Triggering workspace restore; the graph will be replaced");
+    tokio::spawn(backup::restore(
+        run_id.clone(),
+        state.clone(),
+        request.file.clone(),
+    ));
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AgentTriggerResponse {
+            run_id,
+            agent_name: backup::RESTORE_JOB.to_string(),
+            status: "running".to_string(),
+            message: format!("Restoring snapshot {} in background", request.file),
+        }),
+    )
+        .into_response()
+}
+
+/// POST /api/admin/reload-config — re-read the environment and `.env` on
+/// this replica (as SIGHUP does) and report which changed settings were
+/// applied and which need a restart. Other replicas reload separately.
+pub async fn reload_config(State(state): State<AppState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(live_config::reload(&state).await))
+}
@@ -1,40 +1,156 @@
+use std::time::Duration;
+
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::Utc;
-use tracing::{info, warn, error};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use argus_core::api_types::{
-    AgentListResponse, AgentRunState, AgentRunStatus, AgentRunsResponse,
-    AgentTriggerRequest, AgentTriggerResponse,
+    AgentListResponse, AgentPauseResponse, AgentRunDetailResponse, AgentRunState, AgentRunStatus,
+    AgentTriggerRequest, AgentTriggerResponse, BudgetStatusResponse, RetryFailuresParams,
+    RunCompareParams, RunComparisonResponse, RunDocumentsParams, RunDocumentsResponse,
+    RunExtractionError, RunListParams, RunLogParams, RunLogsResponse, RunSnapshot, SourceBudget,
 };
-use argus_core::{ExtractionPipeline, GraphStore};
+use argus_core::quota::{quota_day, QuotaCounter, Spillover};
+use argus_core::shared::MAX_RUN_HISTORY;
+use argus_core::{AgentCapabilities, GraphStore};
 
+use crate::scheduler::scheduled_interval;
+use crate::shared::{insert_run, run_lock_key, update_run};
 use crate::state::AppState;
 
-/// GET /api/agents — list all registered agents with their current status.
+/// GET /api/agents — list all registered agents with their current status
+/// and capabilities. Paused agents are reported as disabled.
 pub async fn list_agents(State(state): State<AppState>) -> impl IntoResponse {
     info!("Listing all agents");
 
+    let paused = state.pauses.paused().await.unwrap_or_else(|e| {
+        warn!(error = %e, "Failed to load paused agents");
+        Default::default()
+    });
+
+    let config = state.config();
     let mut statuses = Vec::with_capacity(state.agents.len());
 
-    for (_name, agent) in &state.agents {
-        match agent.status().await {
-            status => statuses.push(status),
-        }
+    for (name, agent) in state.agents.iter() {
+        let mut status = agent.status().await;
+        status.enabled &= !paused.contains(&status.name);
+        let mut capabilities =
+            AgentCapabilities::of(agent.as_ref(), |var| std::env::var(var).is_ok());
+        capabilities.schedule_interval_seconds =
+            scheduled_interval(name, &config).map(|i| i.as_secs());
+        status.capabilities = Some(capabilities);
+        statuses.push(status);
     }
 
     (StatusCode::OK, Json(AgentListResponse { agents: statuses }))
 }
 
+/// POST /api/agents/{name}/pause — stop the scheduler running an agent
+/// until it is resumed. Manual triggers still run.
+pub async fn pause_agent(State(state): State<AppState>, Path(name): Path<String>) -> Response {
+    set_paused(&state, name, true).await
+}
+
+/// POST /api/agents/{name}/resume — let the scheduler run a paused agent
+/// again from its next interval.
+pub async fn resume_agent(State(state): State<AppState>, Path(name): Path<String>) -> Response {
+    set_paused(&state, name, false).await
+}
+
+async fn set_paused(state: &AppState, agent_name: String, paused: bool) -> Response {
+    if !state.agents.contains_key(&agent_name) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Agent '{agent_name}' not found") })),
+        )
+            .into_response();
+    }
+
+    match state.pauses.set_paused(&agent_name, paused).await {
+        Ok(()) => {
+            info!(agent_name = %agent_name, paused, "Agent pause state changed");
+            (
+                StatusCode::OK,
+                Json(AgentPauseResponse {
+                    agent_name,
+                    enabled: !paused,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!(agent_name = %agent_name, "Failed to update pause state: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to update pause state: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Header carrying a client-chosen key that makes trigger retries safe.
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// The trigger's `Idempotency-Key`, if sent. Keys must be 1–255 visible
+/// ASCII characters.
+fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>, String> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let key = value.to_str().unwrap_or_default();
+    let valid = !key.is_empty()
+        && key.len() <= MAX_IDEMPOTENCY_KEY_LEN
+        && key.bytes().all(|b| b.is_ascii_graphic());
+    if valid {
+        Ok(Some(key.to_string()))
+    } else {
+        Err(format!(
+            "Idempotency-Key must be 1-{MAX_IDEMPOTENCY_KEY_LEN} visible ASCII characters"
+        ))
+    }
+}
+
+/// Answer a retried trigger with the run its key already started.
+async fn replay_trigger(state: &AppState, agent_name: String, run_id: String) -> Response {
+    let status = match state.runs.get(&run_id).await {
+        Ok(Some(run)) => run.status.as_str(),
+        // Trimmed from history or not recorded yet; the key still owns it
+        _ => AgentRunState::Running.as_str(),
+    };
+    info!(agent_name = %agent_name, run_id = %run_id, "Replaying trigger for idempotency key");
+
+    let mut response = (
+        StatusCode::OK,
+        Json(AgentTriggerResponse {
+            run_id,
+            agent_name,
+            status: status.to_string(),
+            message: "Run already started for this idempotency key".to_string(),
+        }),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert("idempotent-replayed", HeaderValue::from_static("true"));
+    response
+}
+
 /// POST /api/agents/trigger — trigger a named agent asynchronously.
-/// Returns 202 Accepted immediately with a run_id to track progress.
+/// Returns 202 Accepted immediately with a run_id to track progress, or 409
+/// if a run of the agent is already in progress. Repeating a request with
+/// the same `Idempotency-Key` returns the original run instead of starting
+/// another.
 pub async fn trigger_agent(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<AgentTriggerRequest>,
 ) -> impl IntoResponse {
     let agent_name = req.agent_name.clone();
@@ -55,65 +171,130 @@ pub async fn trigger_agent(
         }
     };
 
-    let run_id = Uuid::new_v4().to_string();
-    let run_status = AgentRunStatus {
-        run_id: run_id.clone(),
-        agent_name: agent_name.clone(),
-        status: AgentRunState::Running,
-        started_at: Utc::now(),
-        finished_at: None,
-        documents_collected: 0,
-        entities_extracted: 0,
-        error: None,
+    let idempotency_key = match idempotency_key(&headers) {
+        Ok(key) => key.map(|key| format!("trigger:{agent_name}:{key}")),
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": message })),
+            )
+                .into_response();
+        }
     };
 
-    // Register the run
-    {
-        let mut runs = state.runs.write().await;
-        runs.push(run_status);
+    let run_id = Uuid::new_v4().to_string();
+
+    if let Some(key) = &idempotency_key {
+        let ttl = Duration::from_secs(state.config().idempotency_ttl_seconds);
+        match state.idempotency.claim(key, &run_id, ttl).await {
+            Ok(Some(existing)) => return replay_trigger(&state, agent_name, existing).await,
+            Ok(None) => {}
+            Err(e) => {
+                error!("Failed to check idempotency key: {e}");
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(serde_json::json!({ "error": format!("Failed to check idempotency key: {e}") })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let lock_key = run_lock_key(&agent_name);
+    let lock_ttl = Duration::from_secs(state.config().agent_run_lock_ttl_seconds);
+    let locked = state.locks.try_acquire(&lock_key, &run_id, lock_ttl).await;
+    if !matches!(locked, Ok(true)) {
+        // No run started, so a retry with this key must be free to start one
+        if let Some(key) = &idempotency_key {
+            if let Err(e) = state.idempotency.forget(key).await {
+                warn!("Failed to release idempotency key: {e}");
+            }
+        }
+        return match locked {
+            Err(e) => {
+                error!("Failed to take run lock: {e}");
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(serde_json::json!({ "error": format!("Failed to take run lock: {e}") })),
+                )
+                    .into_response()
+            }
+            _ => {
+                let active = state.locks.holder(&lock_key).await.ok().flatten();
+                warn!(agent_name = %agent_name, active_run = ?active, "Agent already running");
+                (
+                    StatusCode::CONFLICT,
+                    Json(serde_json::json!({
+                        "error": format!("Agent '{}' is already running", agent_name),
+                        "run_id": active,
+                    })),
+                )
+                    .into_response()
+            }
+        };
     }
 
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), agent_name.clone()),
+    )
+    .await;
+
     // Spawn the pipeline in the background
     let run_id_clone = run_id.clone();
-    let runs = state.runs.clone();
-    let extraction = state.extraction.clone();
-    let graph = state.graph.clone();
+    let pipeline_state = state.clone();
 
     tokio::spawn(async move {
-        let result = run_agent_pipeline(
+        let result = crate::pipeline::run_agent_pipeline(
+            &pipeline_state,
             &agent_name,
             agent,
-            extraction,
-            graph,
+            &run_id_clone,
+            false,
         )
         .await;
+        if matches!(&result, Ok(outcome) if outcome.queued) {
+            // The worker finishes the run and releases its lock
+            return;
+        }
 
-        let mut runs_lock = runs.write().await;
-        if let Some(run) = runs_lock.iter_mut().find(|r| r.run_id == run_id_clone) {
+        update_run(&pipeline_state.runs, &run_id_clone, |run| {
             run.finished_at = Some(Utc::now());
             match result {
-                Ok((docs, entities)) => {
-                    run.status = AgentRunState::Completed;
-                    run.documents_collected = docs;
-                    run.entities_extracted = entities;
+                Ok(outcome) => {
+                    run.status = outcome.run_state();
+                    run.error = outcome.store_error();
+                    run.documents_collected = outcome.documents;
+                    run.entities_extracted = outcome.entities;
+                    run.documents_failed = outcome.failures.len() as u64;
+                    run.validation = outcome.validation;
+                    run.documents_over_quota = outcome.over_quota;
+                    run.schema_drift = outcome.schema_drift;
                     info!(
                         run_id = %run_id_clone,
                         agent_name = %run.agent_name,
-                        documents = docs,
-                        entities = entities,
+                        documents = outcome.documents,
+                        entities = outcome.entities,
                         "Agent run completed"
                     );
                 }
                 Err(e) => {
                     run.status = AgentRunState::Failed;
-                    run.error = Some(e.clone());
+                    run.documents_collected = e.documents;
+                    run.documents_failed = e.failed;
                     error!(
                         run_id = %run_id_clone,
-                        error = %e,
+                        error = %e.message,
                         "Agent run failed"
                     );
+                    run.error = Some(e.message);
                 }
             }
+        })
+        .await;
+
+        if let Err(e) = pipeline_state.locks.release(&lock_key, &run_id_clone).await {
+            warn!(run_id = %run_id_clone, "Failed to release run lock: {e}");
         }
     });
 
@@ -129,52 +310,428 @@ pub async fn trigger_agent(
         .into_response()
 }
 
-/// Run the full agent pipeline: collect → extract → store.
-/// Returns (documents_collected, entities_extracted) on success.
-async fn run_agent_pipeline(
-    agent_name: &str,
-    agent: std::sync::Arc<dyn argus_core::Agent>,
-    extraction: std::sync::Arc<argus_extraction::LlmExtractionPipeline>,
-    graph: std::sync::Arc<argus_graph::Neo4jGraphStore>,
-) -> std::result::Result<(u64, u64), String> {
-    // Collect
-    let documents = agent.collect().await.map_err(|e| {
-        format!("Collection failed: {}", e)
-    })?;
-    let doc_count = documents.len() as u64;
-    info!(agent_name = %agent_name, documents = doc_count, "Collection complete");
-
-    if documents.is_empty() {
-        return Ok((0, 0));
+/// GET /api/agents/budget — today's extraction quota consumption per source,
+/// including sources without a quota and those with queued spillover.
+pub async fn budget_status(State(state): State<AppState>) -> impl IntoResponse {
+    let day = quota_day(Utc::now());
+    let queued = match state.spillover.sizes().await {
+        Ok(sizes) => sizes,
+        Err(e) => {
+            error!("Failed to read spillover queue: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(
+                    serde_json::json!({ "error": format!("Failed to read spillover queue: {e}") }),
+                ),
+            )
+                .into_response();
+        }
+    };
+    let config = state.config();
+    let mut names: Vec<&String> = config
+        .extraction_quotas
+        .keys()
+        .chain(state.agents.keys())
+        .chain(queued.keys())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let mut sources = Vec::with_capacity(names.len());
+    for source in names {
+        let count = |counter: QuotaCounter| {
+            let key = counter.key(source, day);
+            let quotas = state.quotas.clone();
+            async move { quotas.get(&key).await }
+        };
+        let (used, dropped, off_peak) = match tokio::try_join!(
+            count(QuotaCounter::Used),
+            count(QuotaCounter::Dropped),
+            count(QuotaCounter::OffPeak)
+        ) {
+            Ok(counts) => counts,
+            Err(e) => {
+                error!("Failed to read quota counters: {e}");
+                return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({ "error": format!("Failed to read quota counters: {e}") })),
+                    )
+                        .into_response();
+            }
+        };
+        let daily_limit = config.extraction_quotas.get(source).copied();
+        sources.push(SourceBudget {
+            source: source.clone(),
+            daily_limit,
+            used,
+            remaining: daily_limit.map(|limit| limit.saturating_sub(used)),
+            dropped,
+            queued: queued.get(source).copied().unwrap_or(0),
+            off_peak_processed: off_peak,
+        });
+    }
+
+    (
+        StatusCode::OK,
+        Json(BudgetStatusResponse {
+            day,
+            spillover: Spillover::from_name(&config.quota_spillover),
+            off_peak_hours: config.off_peak_hours.clone(),
+            sources,
+        }),
+    )
+        .into_response()
+}
+
+/// GET /api/agents/runs — agent runs newest first, filtered by `agent`,
+/// `status` and a `since`..`until` start time, one page of `limit` at a
+/// time; `next_cursor` continues the listing.
+pub async fn list_runs(
+    State(state): State<AppState>,
+    Query(params): Query<RunListParams>,
+) -> impl IntoResponse {
+    match state.runs.list(MAX_RUN_HISTORY).await {
+        Ok(runs) => match params.page(runs) {
+            Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+            Err(e) => (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e })),
+            )
+                .into_response(),
+        },
+        Err(e) => {
+            error!("Failed to list runs: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to list runs: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /api/agents/runs/{id} — one run with the documents whose extraction
+/// failed and are still queued for retry.
+pub async fn get_run(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+) -> impl IntoResponse {
+    let run = match state.runs.get(&run_id).await {
+        Ok(Some(run)) => run,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Run '{}' not found", run_id) })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Failed to load run: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to load run: {e}") })),
+            )
+                .into_response();
+        }
+    };
+
+    match state.retries.for_run(&run_id).await {
+        Ok(mut failed) => {
+            failed.sort_by_key(|f| f.last_failed_at);
+            let extraction_errors = failed.iter().map(RunExtractionError::from).collect();
+            (
+                StatusCode::OK,
+                Json(AgentRunDetailResponse {
+                    run,
+                    extraction_errors,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("Failed to read retry queue: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to read retry queue: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /api/agents/runs/{id}/retry-failures — re-extract the documents that
+/// failed in a run. Only documents past their backoff are retried unless
+/// `?force=true`. Returns 202 Accepted with the run_id of the retry.
+pub async fn retry_failures(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+    Query(params): Query<RetryFailuresParams>,
+) -> impl IntoResponse {
+    let original = match state.runs.get(&run_id).await {
+        Ok(Some(run)) => run,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Run '{}' not found", run_id) })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Failed to load run: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to load run: {e}") })),
+            )
+                .into_response();
+        }
+    };
+
+    let queued = match state.retries.for_run(&run_id).await {
+        Ok(queued) => queued,
+        Err(e) => {
+            error!("Failed to read retry queue: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to read retry queue: {e}") })),
+            )
+                .into_response();
+        }
+    };
+
+    let now = Utc::now();
+    let (due, waiting): (Vec<_>, Vec<_>) = queued
+        .into_iter()
+        .filter(|f| !f.exhausted())
+        .partition(|f| params.force || f.is_due(now));
+
+    if due.is_empty() {
+        let next_attempt_at = waiting.iter().map(|f| f.next_attempt_at).min();
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "No failed documents are due for retry",
+                "waiting": waiting.len(),
+                "next_attempt_at": next_attempt_at,
+            })),
+        )
+            .into_response();
     }
 
-    // Extract
-    let extraction_results = extraction.extract_batch(&documents).await.map_err(|e| {
-        format!("Extraction failed: {}", e)
-    })?;
-    let entity_count: u64 = extraction_results
-        .iter()
-        .map(|r| r.entities.len() as u64)
-        .sum();
-    info!(agent_name = %agent_name, extractions = extraction_results.len(), entities = entity_count, "Extraction complete");
-
-    // Store
-    for result in &extraction_results {
-        graph.store_extraction(result).await.map_err(|e| {
-            format!("Graph storage failed: {}", e)
-        })?;
+    let retry_run_id = Uuid::new_v4().to_string();
+    let count = due.len();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(retry_run_id.clone(), original.agent_name.clone()),
+    )
+    .await;
+    info!(run_id = %run_id, retry_run_id = %retry_run_id, documents = count, "Retrying failed documents");
+
+    let retry_state = state.clone();
+    let retry_id = retry_run_id.clone();
+    tokio::spawn(async move {
+        let outcome =
+            crate::pipeline::retry_failed_documents(&retry_state, &run_id, &retry_id, due).await;
+
+        update_run(&retry_state.runs, &retry_id, |run| {
+            run.finished_at = Some(Utc::now());
+            run.documents_collected = outcome.documents;
+            run.entities_extracted = outcome.entities;
+            run.documents_failed = outcome.failures.len() as u64;
+            if outcome.results.is_empty() {
+                run.status = AgentRunState::Failed;
+                run.error = Some(format!(
+                    "All {} retried documents failed again",
+                    outcome.failures.len()
+                ));
+            } else {
+                run.status = outcome.run_state();
+                run.error = outcome.store_error();
+            }
+            run.validation = outcome.validation;
+        })
+        .await;
+    });
+
+    (
+        StatusCode::ACCEPTED,
+        Json(AgentTriggerResponse {
+            run_id: retry_run_id,
+            agent_name: original.agent_name,
+            status: "running".to_string(),
+            message: format!("Retrying {} failed documents in background", count),
+        }),
+    )
+        .into_response()
+}
+
+/// GET /api/agents/runs/{id}/documents — a random sample of the source
+/// documents a run archived (`?sample=N`, default 10, max 100), each with the
+/// raw extraction output or error, for debugging odd extractions.
+pub async fn sample_run_documents(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+    Query(params): Query<RunDocumentsParams>,
+) -> impl IntoResponse {
+    let sample = match state
+        .graph
+        .sample_run_documents(&run_id, params.sample())
+        .await
+    {
+        Ok(sample) => sample,
+        Err(e) => {
+            error!("Failed to sample run documents: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(
+                    serde_json::json!({ "error": format!("Failed to sample run documents: {e}") }),
+                ),
+            )
+                .into_response();
+        }
+    };
+
+    // Archived documents outlive the run history, so only 404 when neither knows the run
+    if sample.total == 0 && !matches!(state.runs.get(&run_id).await, Ok(Some(_))) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Run '{}' not found", run_id) })),
+        )
+            .into_response();
     }
-    info!(agent_name = %agent_name, "Stored all extraction results");
 
-    Ok((doc_count, entity_count))
+    (
+        StatusCode::OK,
+        Json(RunDocumentsResponse {
+            run_id,
+            total: sample.total,
+            documents: sample.documents,
+        }),
+    )
+        .into_response()
 }
 
-/// GET /api/agents/runs — list all agent runs (active and completed).
-pub async fn list_runs(State(state): State<AppState>) -> impl IntoResponse {
-    let runs = state.runs.read().await;
-    let runs_vec: Vec<AgentRunStatus> = runs.iter().rev().cloned().collect();
+/// GET /api/agents/runs/{id}/logs — what was logged while a run collected,
+/// extracted and stored, oldest first (`?level=warn` for warnings and errors
+/// only). Lines are kept in memory by the instance that executed the run.
+pub async fn run_logs(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+    Query(params): Query<RunLogParams>,
+) -> impl IntoResponse {
+    let min_level = match params.level.as_deref().map(str::parse::<tracing::Level>) {
+        None => None,
+        Some(Ok(level)) => Some(level),
+        Some(Err(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "level must be one of error, warn, info, debug, trace" })),
+            )
+                .into_response();
+        }
+    };
+
+    // A known run may have logged nothing here yet, or run on another instance
+    let (mut lines, dropped) = match state.run_logs.get(&run_id) {
+        Some(logs) => logs,
+        None if matches!(state.runs.get(&run_id).await, Ok(Some(_))) => (Vec::new(), 0),
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Run '{}' not found", run_id) })),
+            )
+                .into_response();
+        }
+    };
+    if let Some(min_level) = min_level {
+        // More severe levels compare lower
+        lines.retain(|line| {
+            line.level
+                .parse::<tracing::Level>()
+                .is_ok_and(|level| level <= min_level)
+        });
+    }
+
     (
         StatusCode::OK,
-        Json(AgentRunsResponse { runs: runs_vec }),
+        Json(RunLogsResponse {
+            run_id,
+            lines,
+            dropped,
+        }),
     )
+        .into_response()
+}
+
+/// One run of `agent_name` with its archive totals, or the error response.
+async fn run_snapshot(
+    state: &AppState,
+    agent_name: &str,
+    run_id: &str,
+) -> Result<RunSnapshot, Response> {
+    let run = match state.runs.get(run_id).await {
+        Ok(Some(run)) => run,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Run '{}' not found", run_id) })),
+            )
+                .into_response());
+        }
+        Err(e) => {
+            error!("Failed to read run {run_id}: {e}");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to read run: {e}") })),
+            )
+                .into_response());
+        }
+    };
+    if run.agent_name != agent_name {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Run '{}' belongs to agent '{}', not '{}'", run_id, run.agent_name, agent_name)
+            })),
+        )
+            .into_response());
+    }
+    match state
+        .graph
+        .run_extraction_stats(&run.run_id, run.started_at)
+        .await
+    {
+        Ok(stats) => Ok(RunSnapshot::new(run, stats)),
+        Err(e) => {
+            error!("Failed to read documents of run {run_id}: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to read run documents: {e}") })),
+            )
+                .into_response())
+        }
+    }
+}
+
+/// GET /api/agents/{name}/runs/compare?base=&head= — two runs of one agent
+/// side by side: documents, entities per type, new vs repeated entities and
+/// error rates, with large drops from `base` to `head` listed as regressions.
+pub async fn compare_runs(
+    State(state): State<AppState>,
+    Path(agent_name): Path<String>,
+    Query(params): Query<RunCompareParams>,
+) -> impl IntoResponse {
+    let base = match run_snapshot(&state, &agent_name, &params.base).await {
+        Ok(snapshot) => snapshot,
+        Err(response) => return response,
+    };
+    let head = match run_snapshot(&state, &agent_name, &params.head).await {
+        Ok(snapshot) => snapshot,
+        Err(response) => return response,
+    };
+
+    let comparison = RunComparisonResponse::new(agent_name, base, head);
+    if !comparison.regressions.is_empty() {
+        warn!(agent = %comparison.agent_name, regressions = ?comparison.regressions, "Run comparison found regressions");
+    }
+    (StatusCode::OK, Json(comparison)).into_response()
 }
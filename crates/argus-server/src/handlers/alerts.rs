@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use argus_core::alerts::MAX_ALERTS;
+use argus_core::api_types::{
+    AlertAcknowledgeRequest, AlertListParams, AlertListResponse, AlertRuleListResponse,
+    AlertRuleRequest,
+};
+use argus_core::{AlertRule, ChannelTarget, NotificationChannel};
+
+use crate::alerts::dsl;
+use crate::state::AppState;
+
+fn internal_error(context: &str, e: argus_core::ArgusError) -> axum::response::Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({ "error": format!("{context}: {e}") })),
+    )
+        .into_response()
+}
+
+fn not_found(what: &str, id: Uuid) -> axum::response::Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": format!("{what} {id} not found") })),
+    )
+        .into_response()
+}
+
+fn check_channel(channel: &NotificationChannel) -> Result<(), String> {
+    match &channel.target {
+        ChannelTarget::Email { to } if to.is_empty() => {
+            Err("email channel needs at least one recipient".to_string())
+        }
+        ChannelTarget::Email { to } => to.iter().try_for_each(|address| {
+            address
+                .parse::<lettre::message::Mailbox>()
+                .map(|_| ())
+                .map_err(|e| format!("{address}: {e}"))
+        }),
+        ChannelTarget::Slack {
+            webhook_url: Some(url),
+        } if !url.starts_with("https://") => {
+            Err("Slack webhook_url must be an https URL".to_string())
+        }
+        ChannelTarget::Slack { .. } => Ok(()),
+    }
+}
+
+/// GET /api/alerts — alerts newest first, open ones unless `status` says
+/// otherwise, optionally only those at least `min_severity`.
+pub async fn list_alerts(
+    State(state): State<AppState>,
+    Query(params): Query<AlertListParams>,
+) -> impl IntoResponse {
+    match state.alerts.list(params.status, MAX_ALERTS).await {
+        Ok(mut alerts) => {
+            if let Some(min) = params.min_severity {
+                alerts.retain(|a| a.severity >= min);
+            }
+            let total = alerts.len();
+            alerts.truncate(params.limit());
+            (StatusCode::OK, Json(AlertListResponse { alerts, total })).into_response()
+        }
+        Err(e) => internal_error("Failed to list alerts", e),
+    }
+}
+
+/// GET /api/alerts/{id} — a single alert.
+pub async fn get_alert(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    match state.alerts.get(id).await {
+        Ok(Some(alert)) => (StatusCode::OK, Json(alert)).into_response(),
+        Ok(None) => not_found("Alert", id),
+        Err(e) => internal_error("Failed to read alert", e),
+    }
+}
+
+/// POST /api/alerts/{id}/acknowledge — close an open alert, so the next
+/// match for the same rule and subject raises a new one.
+pub async fn acknowledge_alert(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    request: Option<Json<AlertAcknowledgeRequest>>,
+) -> impl IntoResponse {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let mut alert = match state.alerts.get(id).await {
+        Ok(Some(alert)) => alert,
+        Ok(None) => return not_found("Alert", id),
+        Err(e) => return internal_error("Failed to read alert", e),
+    };
+    if !alert.is_open() {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": format!("Alert {id} is already acknowledged") })),
+        )
+            .into_response();
+    }
+    alert.acknowledge(request.by, request.note);
+    match state.alerts.save(&alert).await {
+        Ok(()) => (StatusCode::OK, Json(alert)).into_response(),
+        Err(e) => internal_error("Failed to acknowledge alert", e),
+    }
+}
+
+/// GET /api/alerts/rules — every alert rule, oldest first.
+pub async fn list_alert_rules(State(state): State<AppState>) -> impl IntoResponse {
+    match state.alert_rules.list().await {
+        Ok(rules) => (StatusCode::OK, Json(AlertRuleListResponse { rules })).into_response(),
+        Err(e) => internal_error("Failed to list alert rules", e),
+    }
+}
+
+/// POST /api/alerts/rules — add a rule; 400 if its condition does not parse.
+pub async fn create_alert_rule(
+    State(state): State<AppState>,
+    Json(request): Json<AlertRuleRequest>,
+) -> impl IntoResponse {
+    if request.name.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Rule name must not be empty" })),
+        )
+            .into_response();
+    }
+    if let Err(e) = request.channels.iter().try_for_each(check_channel) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Invalid channel: {e}") })),
+        )
+            .into_response();
+    }
+    if let Err(e) = dsl::parse(&request.condition) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Invalid condition: {e}") })),
+        )
+            .into_response();
+    }
+    let rule = AlertRule {
+        id: Uuid::new_v4(),
+        name: request.name.trim().to_string(),
+        condition: request.condition,
+        severity: request.severity,
+        enabled: request.enabled,
+        created_at: Utc::now(),
+        channels: request.channels,
+    };
+    match state.alert_rules.save(&rule).await {
+        Ok(()) => (StatusCode::CREATED, Json(rule)).into_response(),
+        Err(e) => internal_error("Failed to save alert rule", e),
+    }
+}
+
+/// DELETE /api/alerts/rules/{id} — remove a rule. Alerts it raised are kept.
+pub async fn delete_alert_rule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.alert_rules.delete(id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => not_found("Alert rule", id),
+        Err(e) => internal_error("Failed to delete alert rule", e),
+    }
+}
@@ -0,0 +1,200 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use tracing::{error, info, warn};
+
+use argus_coldstore::SqlError;
+use argus_core::api_types::{
+    AnalyticsSqlRequest, AnalyticsSqlResponse, ArchivedPositionsParams, ArchivedPositionsResponse,
+    ColdStorageResponse, EventDensityParams, EventDensityResponse,
+};
+use argus_core::GraphStore;
+
+use crate::handlers::error;
+use crate::state::AppState;
+
+/// GET /api/analytics/event-density — geolocated events and AIS/ADS-B
+/// position reports counted per geohash cell and time bucket. Accepts
+/// `bbox`, `interval`, `since`, `until` and `precision`.
+pub async fn event_density(
+    State(state): State<AppState>,
+    Query(params): Query<EventDensityParams>,
+) -> impl IntoResponse {
+    let query = match params.query(Utc::now()) {
+        Ok(query) => query,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e })),
+            )
+                .into_response();
+        }
+    };
+    info!(bbox = ?query.bbox, since = %query.since, until = %query.until, precision = query.precision, "Computing event density");
+
+    match state.graph.event_density(&query).await {
+        Ok(density) => {
+            let response = EventDensityResponse {
+                bbox: query.bbox,
+                since: query.since,
+                until: query.until,
+                interval_seconds: query.interval.num_seconds(),
+                precision: query.precision,
+                total: density.total,
+                truncated: density.truncated,
+                cells: density.cells,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to compute event density: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(
+                    serde_json::json!({ "error": format!("Failed to compute event density: {e}") }),
+                ),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn cold_storage_disabled() -> Response {
+    error(
+        StatusCode::NOT_FOUND,
+        "Cold storage is not configured; set COLD_STORAGE_URL".to_string(),
+    )
+}
+
+/// GET /api/analytics/cold-storage — the Parquet partitions written so far
+/// and the rows waiting for the next flush.
+pub async fn cold_storage(State(state): State<AppState>) -> impl IntoResponse {
+    let Some(cold) = &state.cold_storage else {
+        return cold_storage_disabled();
+    };
+    match cold.partitions().await {
+        Ok(partitions) => {
+            let response = ColdStorageResponse {
+                partitions,
+                buffered: cold.buffered(),
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to list cold storage partitions: {e}");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list cold storage partitions: {e}"),
+            )
+        }
+    }
+}
+
+/// GET /api/analytics/positions — an asset's archived position reports,
+/// including those retention has removed from the graph. Accepts `asset`,
+/// `since`, `until` (at most 31 days apart) and `limit`; bounded in time
+/// like SQL queries.
+pub async fn archived_positions(
+    State(state): State<AppState>,
+    Query(params): Query<ArchivedPositionsParams>,
+) -> impl IntoResponse {
+    let Some(cold) = &state.cold_storage else {
+        return cold_storage_disabled();
+    };
+    let asset = params.asset.trim();
+    if asset.is_empty() {
+        return error(StatusCode::BAD_REQUEST, "asset is required".to_string());
+    }
+    let (since, until) = match params.window(Utc::now()) {
+        Ok(window) => window,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e),
+    };
+
+    let timeout = std::time::Duration::from_secs(state.config().analytics_sql_timeout_seconds);
+    match tokio::time::timeout(timeout, cold.positions(asset, since, until, params.limit())).await {
+        Ok(Ok(positions)) => {
+            let response = ArchivedPositionsResponse {
+                asset: asset.to_string(),
+                since,
+                until,
+                positions,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Ok(Err(e)) => {
+            error!(asset, "Failed to read archived positions: {e}");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to read archived positions: {e}"),
+            )
+        }
+        Err(_) => {
+            warn!(
+                asset,
+                timeout_seconds = timeout.as_secs(),
+                "Reading archived positions timed out"
+            );
+            error(
+                StatusCode::GATEWAY_TIMEOUT,
+                format!("Positions were not read within {}s", timeout.as_secs()),
+            )
+        }
+    }
+}
+
+/// POST /api/analytics/sql — one read-only SQL query over the archived
+/// `positions`, `events` and `documents` tables, each with a `date`
+/// partition column. Rows not yet flushed are not included.
+pub async fn analytics_sql(
+    State(state): State<AppState>,
+    Json(request): Json<AnalyticsSqlRequest>,
+) -> impl IntoResponse {
+    let Some(cold) = &state.cold_storage else {
+        return cold_storage_disabled();
+    };
+    if request.sql.trim().is_empty() {
+        return error(StatusCode::BAD_REQUEST, "sql is required".to_string());
+    }
+    let timeout = std::time::Duration::from_secs(state.config().analytics_sql_timeout_seconds);
+    let started = std::time::Instant::now();
+
+    match tokio::time::timeout(timeout, cold.sql(&request.sql, request.limit())).await {
+        Ok(Ok(result)) => {
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            info!(
+                rows = result.rows.len(),
+                truncated = result.truncated,
+                elapsed_ms,
+                "Ran analytics SQL"
+            );
+            let response = AnalyticsSqlResponse {
+                columns: result.columns,
+                rows: result.rows,
+                truncated: result.truncated,
+                elapsed_ms,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Ok(Err(SqlError::Invalid(e))) => error(StatusCode::BAD_REQUEST, e),
+        Ok(Err(SqlError::Failed(e))) => {
+            error!("Analytics SQL failed: {e}");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Query failed: {e}"),
+            )
+        }
+        Err(_) => {
+            warn!(
+                timeout_seconds = timeout.as_secs(),
+                "Analytics SQL timed out"
+            );
+            error(
+                StatusCode::GATEWAY_TIMEOUT,
+                format!("Query did not finish within {}s", timeout.as_secs()),
+            )
+        }
+    }
+}
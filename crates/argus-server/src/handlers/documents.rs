@@ -0,0 +1,381 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::Engine;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use argus_core::api_types::{
+    AgentRunState, AgentRunStatus, DocumentIngestRequest, DocumentIngestResponse,
+    DocumentSearchRequest, DocumentSearchResponse, DocumentSearchResult, IngestFile,
+};
+use argus_core::document::highlight_snippet;
+use argus_core::{DocumentSearchQuery, GraphStore, RawDocument};
+use argus_extraction::formats::{self, ConvertedText, DocumentFormat};
+
+use crate::shared::{insert_run, update_run};
+use crate::state::AppState;
+
+/// Source of documents submitted through the ingestion API; also the agent
+/// name their runs are recorded under.
+pub(crate) const MANUAL_SOURCE: &str = "manual";
+
+/// Largest accepted ingestion body, base64 overhead included.
+pub(crate) const MAX_INGEST_BYTES: usize = 32 * 1024 * 1024;
+
+/// Longest text handed to extraction in one document.
+const MAX_INGEST_CHARS: usize = 200_000;
+
+/// Upper bound on documents returned per search.
+const MAX_RESULTS: usize = 200;
+
+/// Characters of context kept around the first match in each snippet.
+const SNIPPET_CHARS: usize = 240;
+
+/// POST /api/documents/search — full-text search over archived source documents.
+pub async fn search_documents(
+    State(state): State<AppState>,
+    Json(req): Json<DocumentSearchRequest>,
+) -> impl IntoResponse {
+    info!(query = %req.query, source = ?req.source, "Searching documents");
+
+    if req.query.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "query must not be empty" })),
+        )
+            .into_response();
+    }
+
+    let search = DocumentSearchQuery {
+        query: req.query.clone(),
+        source: req.source,
+        start: req.start,
+        end: req.end,
+        limit: req.limit.clamp(1, MAX_RESULTS),
+    };
+
+    match state.graph.search_documents(&search).await {
+        Ok(hits) => {
+            let results: Vec<DocumentSearchResult> = hits
+                .into_iter()
+                .map(|hit| {
+                    let doc = hit.document;
+                    DocumentSearchResult {
+                        snippet: highlight_snippet(&doc.content, &req.query, SNIPPET_CHARS),
+                        id: doc.id,
+                        source: doc.source,
+                        source_id: doc.source_id,
+                        title: doc.title,
+                        url: doc.url,
+                        collected_at: doc.collected_at,
+                        score: hit.score,
+                        entity_ids: doc.entity_ids,
+                    }
+                })
+                .collect();
+            let total = results.len();
+            (
+                StatusCode::OK,
+                Json(DocumentSearchResponse { results, total }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("Document search failed: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Document search failed: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// MIME type for a file, from its declared type or else its extension.
+fn file_content_type(file: &IngestFile) -> String {
+    if let Some(content_type) = file
+        .content_type
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+    {
+        return content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+    }
+    let extension = file
+        .filename
+        .as_deref()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "txt" | "text" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "eml" => "message/rfc822",
+        "json" => "application/json",
+        "html" | "htm" => "text/html",
+        "xhtml" => "application/xhtml+xml",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// The text of an uploaded file, converted from PDF or HTML where needed.
+/// Formats without extractable text are rejected with 415, files that
+/// fail to convert with 422.
+fn file_text(file: &IngestFile) -> Result<ConvertedText, (StatusCode, String)> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(file.data.trim())
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("file.data is not valid base64: {e}"),
+            )
+        })?;
+    let content_type = file_content_type(file);
+    let Some(format) = DocumentFormat::from_content_type(&content_type) else {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!("Cannot extract text from {content_type} files"),
+        ));
+    };
+    formats::convert(&bytes, format).map_err(|e| {
+        let status = match format {
+            DocumentFormat::Pdf => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        };
+        (status, format!("Cannot read {content_type} file: {e}"))
+    })
+}
+
+/// Source id for a document submitted without one: a digest of its text,
+/// so submitting the same text twice updates rather than duplicates.
+fn derived_source_id(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    let hash: String = digest.iter().take(16).map(|b| format!("{b:02x}")).collect();
+    format!("{MANUAL_SOURCE}:{hash}")
+}
+
+/// Turn an ingestion request into the document handed to extraction.
+fn raw_document(req: DocumentIngestRequest) -> Result<RawDocument, (StatusCode, String)> {
+    let mut converted = req
+        .content
+        .as_deref()
+        .map(formats::plain_text)
+        .unwrap_or_default();
+    let mut metadata = match req.metadata {
+        Some(serde_json::Value::Object(map)) => map,
+        Some(other) => serde_json::Map::from_iter([("metadata".to_string(), other)]),
+        None => serde_json::Map::new(),
+    };
+    if let Some(file) = &req.file {
+        converted.append(file_text(file)?);
+        metadata.insert("filename".to_string(), serde_json::json!(file.filename));
+        metadata.insert(
+            "content_type".to_string(),
+            serde_json::json!(file_content_type(file)),
+        );
+    }
+
+    if converted.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "content or file must contain text".to_string(),
+        ));
+    }
+    let characters = converted.chars();
+    if characters > MAX_INGEST_CHARS {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Document has {characters} characters; split it into parts of at most {MAX_INGEST_CHARS}"),
+        ));
+    }
+
+    metadata.insert(
+        formats::TEXT_OFFSETS_KEY.to_string(),
+        serde_json::to_value(&converted.offsets).unwrap_or_default(),
+    );
+
+    let content = converted.text;
+    let source_id = req
+        .source_id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| derived_source_id(&content));
+    let title = req
+        .title
+        .or(converted.title)
+        .or_else(|| req.file.as_ref().and_then(|f| f.filename.clone()));
+    Ok(RawDocument {
+        source: MANUAL_SOURCE.to_string(),
+        source_id,
+        title,
+        content,
+        url: req.url,
+        collected_at: Utc::now(),
+        observed_at: req.observed_at,
+        identifiers: Default::default(),
+        metadata: serde_json::Value::Object(metadata),
+        structured: None,
+    })
+}
+
+/// POST /api/documents/ingest — extract entities from a document supplied
+/// by the caller (raw text and/or a base64 file) and store them.
+/// Returns 202 Accepted with a run_id; progress is visible in /api/agents/runs.
+pub async fn ingest_document(
+    State(state): State<AppState>,
+    Json(req): Json<DocumentIngestRequest>,
+) -> Response {
+    let document = match raw_document(req) {
+        Ok(document) => document,
+        Err((status, message)) => {
+            return (status, Json(serde_json::json!({ "error": message }))).into_response();
+        }
+    };
+
+    let run_id = Uuid::new_v4().to_string();
+    let characters = document.content.chars().count();
+    let source_id = document.source_id.clone();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), MANUAL_SOURCE),
+    )
+    .await;
+
+    info!(run_id = %run_id, source_id = %source_id, characters, "Ingesting document");
+
+    let pipeline_state = state.clone();
+    let pipeline_run_id = run_id.clone();
+    tokio::spawn(async move {
+        let outcome = crate::pipeline::ingest_documents(
+            &pipeline_state,
+            MANUAL_SOURCE,
+            &pipeline_run_id,
+            &[document],
+        )
+        .await;
+        update_run(&pipeline_state.runs, &pipeline_run_id, |run| {
+            run.finished_at = Some(Utc::now());
+            run.documents_collected = outcome.documents;
+            run.entities_extracted = outcome.entities;
+            run.documents_failed = outcome.failures.len() as u64;
+            match outcome.failures.first() {
+                Some(failure) => {
+                    run.status = AgentRunState::Failed;
+                    run.error = Some(format!("Extraction failed: {}", failure.error));
+                }
+                None => {
+                    run.status = outcome.run_state();
+                    run.error = outcome.store_error();
+                }
+            }
+            run.validation = outcome.validation;
+            info!(run_id = %pipeline_run_id, entities = outcome.entities, status = run.status.as_str(), "Ingestion finished");
+        })
+        .await;
+    });
+
+    (
+        StatusCode::ACCEPTED,
+        Json(DocumentIngestResponse {
+            run_id,
+            source: MANUAL_SOURCE.to_string(),
+            source_id,
+            characters,
+            status: "running".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(text: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(text)
+    }
+
+    #[test]
+    fn ingestion_combines_text_and_text_files() {
+        let req = DocumentIngestRequest {
+            content: Some("Cover note.".to_string()),
+            file: Some(IngestFile {
+                filename: Some("memo.txt".to_string()),
+                content_type: None,
+                data: encoded(b"Acme Ltd paid Holdco."),
+            }),
+            metadata: Some(serde_json::json!({ "case": "leak-7" })),
+            ..Default::default()
+        };
+        let doc = raw_document(req).unwrap();
+        assert_eq!(doc.source, MANUAL_SOURCE);
+        assert_eq!(doc.content, "Cover note.\n\nAcme Ltd paid Holdco.");
+        assert_eq!(doc.title.as_deref(), Some("memo.txt"));
+        assert_eq!(doc.metadata["case"], "leak-7");
+        assert_eq!(doc.metadata["content_type"], "text/plain");
+        assert_eq!(doc.source_id, derived_source_id(&doc.content));
+        assert_eq!(doc.metadata["text_offsets"]["paragraphs"][1]["start"], 13);
+    }
+
+    #[test]
+    fn html_files_are_cleaned_and_titled() {
+        let req = DocumentIngestRequest {
+            file: Some(IngestFile {
+                filename: Some("filing.html".to_string()),
+                content_type: None,
+                data: encoded(b"<html><head><title>Form 10-K</title></head><body><p>Acme Ltd</p><script>x()</script></body></html>"),
+            }),
+            ..Default::default()
+        };
+        let doc = raw_document(req).unwrap();
+        assert_eq!(doc.content, "Acme Ltd");
+        assert_eq!(doc.title.as_deref(), Some("Form 10-K"));
+        assert_eq!(doc.metadata["content_type"], "text/html");
+    }
+
+    #[test]
+    fn ingestion_rejects_empty_binary_and_undecodable_input() {
+        let status = |req: DocumentIngestRequest| raw_document(req).unwrap_err().0;
+        assert_eq!(
+            status(DocumentIngestRequest::default()),
+            StatusCode::BAD_REQUEST
+        );
+
+        let file = |content_type: &str, data: String| DocumentIngestRequest {
+            file: Some(IngestFile {
+                filename: None,
+                content_type: Some(content_type.to_string()),
+                data,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            status(file("text/plain", "***".to_string())),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(file("image/png", encoded(b"png"))),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        assert_eq!(
+            status(file("application/pdf", encoded(b"%PDF-1.4 broken"))),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            status(file("text/plain; charset=latin1", encoded(&[0xff, 0xfe]))),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+}
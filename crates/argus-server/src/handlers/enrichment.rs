@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use futures::future::join_all;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use argus_core::api_types::{
+    AgentEnrichment, AgentRunState, AgentRunStatus, EnrichParams, EnrichResponse,
+};
+use argus_core::GraphStore;
+
+use crate::handlers::error;
+use crate::shared::{insert_run, update_run};
+use crate::state::AppState;
+
+/// Agent name enrichment runs are listed under.
+const ENRICH_RUN_AGENT: &str = "enrich";
+
+/// POST /api/entities/{id}/enrich — look the entity up at every agent that
+/// can (or those in `?agents=`), extract and store what they return, and
+/// report what was added. The manual counterpart of the scheduler's
+/// cross-referencing; the returned documents are archived under one run.
+pub async fn enrich_entity(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<EnrichParams>,
+) -> Response {
+    let entity = match state.graph.get_entity(id).await {
+        Ok(Some(entity)) => entity,
+        Ok(None) => return error(StatusCode::NOT_FOUND, format!("Entity {id} not found")),
+        Err(e) => {
+            error!("Failed to fetch entity {id}: {e}");
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch entity: {e}"),
+            );
+        }
+    };
+
+    let wanted = params.agent_names();
+    if let Some(unknown) = wanted
+        .iter()
+        .flatten()
+        .find(|name| !state.agents.contains_key(*name))
+    {
+        return error(StatusCode::BAD_REQUEST, format!("Unknown agent: {unknown}"));
+    }
+    let mut agents: Vec<_> = state
+        .agents
+        .iter()
+        .filter(|(name, _)| wanted.as_ref().is_none_or(|wanted| wanted.contains(name)))
+        .filter_map(|(name, agent)| Some((name.clone(), agent.as_lookup()?)))
+        .filter(|(_, lookup)| lookup.can_lookup(&entity.entity_type))
+        .collect();
+    if agents.is_empty() {
+        return error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("No agent can look up {} entities", entity.entity_type),
+        );
+    }
+    agents.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let run_id = Uuid::new_v4().to_string();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), ENRICH_RUN_AGENT),
+    )
+    .await;
+    info!(%id, run_id = %run_id, agents = agents.len(), "Enriching entity");
+
+    // Upstream lookups are the slow part, so they run side by side
+    let lookups = join_all(
+        agents
+            .iter()
+            .map(|(_, lookup)| lookup.lookup_entity(&entity)),
+    )
+    .await;
+
+    let mut enrichments = Vec::with_capacity(agents.len());
+    for ((name, _), lookup) in agents.iter().zip(lookups) {
+        let mut enrichment = AgentEnrichment {
+            agent: name.clone(),
+            documents: 0,
+            entities: 0,
+            relationships: 0,
+            failed: 0,
+            error: None,
+        };
+        match lookup {
+            Ok(documents) if documents.is_empty() => {}
+            Ok(documents) => {
+                let outcome =
+                    crate::pipeline::ingest_documents(&state, name, &run_id, &documents).await;
+                enrichment.documents = outcome.documents;
+                enrichment.entities = outcome.entities;
+                enrichment.relationships = outcome
+                    .results
+                    .iter()
+                    .map(|r| r.relationships.len() as u64)
+                    .sum();
+                enrichment.failed = outcome.failures.len() as u64;
+            }
+            Err(e) => {
+                warn!(%id, agent = %name, error = %e, "Enrichment lookup failed");
+                enrichment.error = Some(e.to_string());
+            }
+        }
+        enrichments.push(enrichment);
+    }
+
+    let response = EnrichResponse {
+        entity_id: id,
+        run_id: run_id.clone(),
+        documents: enrichments.iter().map(|e| e.documents).sum(),
+        entities: enrichments.iter().map(|e| e.entities).sum(),
+        relationships: enrichments.iter().map(|e| e.relationships).sum(),
+        agents: enrichments,
+    };
+    let lookup_errors: Vec<String> = response
+        .agents
+        .iter()
+        .filter_map(|e| {
+            e.error
+                .as_ref()
+                .map(|error| format!("{}: {error}", e.agent))
+        })
+        .collect();
+    update_run(&state.runs, &run_id, |run| {
+        run.finished_at = Some(Utc::now());
+        run.documents_collected = response.documents;
+        run.entities_extracted = response.entities;
+        run.documents_failed = response.agents.iter().map(|e| e.failed).sum();
+        run.status = if lookup_errors.len() == response.agents.len() {
+            AgentRunState::Failed
+        } else {
+            AgentRunState::Completed
+        };
+        run.error = (!lookup_errors.is_empty()).then(|| lookup_errors.join("; "));
+    })
+    .await;
+    info!(
+        %id,
+        run_id = %run_id,
+        documents = response.documents,
+        entities = response.entities,
+        "Enrichment finished"
+    );
+
+    (StatusCode::OK, Json(response)).into_response()
+}
@@ -1,39 +1,108 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
 use tracing::{error, info};
 use uuid::Uuid;
 
 use argus_core::api_types::{
-    EntityDetailResponse, EntitySearchRequest, EntitySearchResponse, TimelineEvent,
+    EntityBatchRequest, EntityBatchResponse, EntityDetailResponse, EntityHistoryParams,
+    EntityHistoryResponse, EntityIdentifierResponse, EntitySearchRequest, EntitySearchResponse,
+    IncludeParams, NaturalSearchRequest, NaturalSearchResponse, NeighborQueryParams, TimelineEvent,
     TimelineRequest, TimelineResponse,
 };
-use argus_core::{GraphQuery, GraphStore};
+use argus_core::entity::normalize_identifier_scheme;
+use argus_core::natural_search::{NearFilter, SearchInterpretation};
+use argus_core::presentation;
+use argus_core::{ArgusError, Entity, EntitySearchFilter, GraphQuery, GraphStore};
 
+use crate::http_cache;
+use crate::jobs::co_location::AssetResolver;
 use crate::state::AppState;
+use crate::usage::UsageCharge;
 
+/// Upper bound on ids accepted by a single batch lookup.
+const MAX_BATCH_IDS: usize = 500;
+
+/// Most results a natural-language search returns.
+const MAX_NATURAL_SEARCH_RESULTS: usize = 200;
+/// Candidates read from the graph per result wanted, since property and
+/// time conditions are checked afterwards.
+const CANDIDATES_PER_RESULT: usize = 5;
+/// Window searched around a place when the question gives no dates.
+const NEAR_DEFAULT_LOOKBACK: chrono::Duration = chrono::Duration::days(7);
+/// Most position reports read for one search around a place.
+const MAX_NEAR_POSITIONS: usize = 200_000;
+/// Most distinct assets looked up for one search around a place.
+const MAX_NEAR_ASSETS: usize = 2_000;
+
+/// POST /api/entities/search — search entity names, relationship evidence
+/// and properties, or both (`target`). `?include=presentation` adds each
+/// entity's category, color and icon.
 pub async fn search_entities(
     State(state): State<AppState>,
+    Query(include): Query<IncludeParams>,
     Json(request): Json<EntitySearchRequest>,
 ) -> impl IntoResponse {
-    info!(query = %request.query, limit = request.limit, "Searching entities");
+    info!(query = %request.query, limit = request.limit, target = ?request.target, "Searching entities");
 
-    match state
-        .graph
-        .search_entities(&request.query, request.limit)
-        .await
+    if let Some(Err(e)) = request
+        .entity_type
+        .as_ref()
+        .map(|t| state.config().ontology.check_entity_type(t))
     {
-        Ok(mut entities) => {
-            // Filter by entity type if specified
-            if let Some(ref et) = request.entity_type {
-                entities.retain(|e| &e.entity_type == et);
-            }
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response();
+    }
 
-            let total = entities.len();
-            let response = EntitySearchResponse { entities, total };
+    let filter = EntitySearchFilter {
+        entity_type: request.entity_type.clone(),
+        min_sanctions_exposure: request.min_sanctions_exposure,
+        sort: request.sort,
+    };
+
+    let result = async {
+        let entities = if request.target.includes_entities() {
+            state
+                .graph
+                .search_entities_filtered(&request.query, &filter, request.limit)
+                .await?
+        } else {
+            Vec::new()
+        };
+        // An empty query would match every edge; names have no such problem
+        let relationships =
+            if request.target.includes_relationships() && !request.query.trim().is_empty() {
+                state
+                    .graph
+                    .search_relationships(&request.query, request.limit)
+                    .await?
+            } else {
+                Vec::new()
+            };
+        Ok::<_, argus_core::ArgusError>((entities, relationships))
+    }
+    .await;
+
+    match result {
+        Ok((entities, relationships)) => {
+            let total = entities.len() + relationships.len();
+            let presentation = if include.presentation() {
+                presentation::presentations(&entities)
+            } else {
+                Default::default()
+            };
+            let response = EntitySearchResponse {
+                entities,
+                relationships,
+                total,
+                presentation,
+            };
             (StatusCode::OK, Json(response)).into_response()
         }
         Err(e) => {
@@ -47,12 +116,223 @@ pub async fn search_entities(
     }
 }
 
+/// POST /api/search/natural — read a plain-language search into a
+/// structured one with a small LLM call, run it, and return the results
+/// with the interpretation that produced them.
+pub async fn natural_search(
+    State(state): State<AppState>,
+    Json(request): Json<NaturalSearchRequest>,
+) -> impl IntoResponse {
+    if request.question.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "question must not be empty" })),
+        )
+            .into_response();
+    }
+    let limit = request.limit.clamp(1, MAX_NATURAL_SEARCH_RESULTS);
+
+    let (interpretation, usage) = match state
+        .reasoning
+        .interpret_search(&request.question, chrono::Utc::now())
+        .await
+    {
+        Ok(translated) => translated,
+        Err(e) => {
+            error!(error = %e, "Search translation failed");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Search translation failed: {e}") })),
+            )
+                .into_response();
+        }
+    };
+    info!(question = %request.question, interpretation = ?interpretation, "Running natural-language search");
+
+    let result = match &interpretation.near {
+        Some(near) => assets_near(&state, &interpretation, near, limit).await,
+        None => search_interpreted(&state, &interpretation, limit).await,
+    };
+    match result {
+        Ok(entities) => {
+            let charge = UsageCharge {
+                reasoning_tokens: usage.input_tokens + usage.output_tokens,
+                ..UsageCharge::default()
+            };
+            let response = NaturalSearchResponse {
+                interpretation,
+                total: entities.len(),
+                entities,
+                usage,
+            };
+            (StatusCode::OK, Extension(charge), Json(response)).into_response()
+        }
+        Err(e) => {
+            error!("Natural-language search failed: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Search failed: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Entities found by name and type, then held to the interpretation's
+/// property and time conditions.
+async fn search_interpreted(
+    state: &AppState,
+    interpretation: &SearchInterpretation,
+    limit: usize,
+) -> argus_core::Result<Vec<Entity>> {
+    let candidates = state
+        .graph
+        .search_entities_filtered(
+            &interpretation.query,
+            &interpretation.filter(),
+            limit * CANDIDATES_PER_RESULT,
+        )
+        .await?;
+    Ok(candidates
+        .into_iter()
+        .filter(|entity| interpretation.matches(entity) && interpretation.seen_in_window(entity))
+        .take(limit)
+        .collect())
+}
+
+/// Tracked assets with a position report around the place during the time
+/// window (the last week when none was given), held to the remaining
+/// conditions. Assets are returned in order of their first report there.
+async fn assets_near(
+    state: &AppState,
+    interpretation: &SearchInterpretation,
+    near: &NearFilter,
+    limit: usize,
+) -> argus_core::Result<Vec<Entity>> {
+    let until = interpretation.to.unwrap_or_else(chrono::Utc::now);
+    let since = interpretation.from.unwrap_or(until - NEAR_DEFAULT_LOOKBACK);
+    let positions = state
+        .graph
+        .positions_between(since, until, MAX_NEAR_POSITIONS)
+        .await?;
+
+    let mut seen = std::collections::HashSet::new();
+    let assets: Vec<&str> = positions
+        .iter()
+        .filter(|p| {
+            interpretation
+                .entity_type
+                .as_ref()
+                .is_none_or(|t| *t == p.entity_type)
+        })
+        .filter(|p| near.contains(p.latitude, p.longitude))
+        .map(|p| p.asset.as_str())
+        .filter(|asset| seen.insert(*asset))
+        .take(MAX_NEAR_ASSETS)
+        .collect();
+
+    let mut resolver = AssetResolver::default();
+    let mut found = std::collections::HashSet::new();
+    let mut entities = Vec::new();
+    for asset in assets {
+        if entities.len() >= limit {
+            break;
+        }
+        let Some(entity) = resolver.entity(state.graph.as_ref(), asset).await else {
+            continue;
+        };
+        if interpretation.matches(&entity) && found.insert(entity.id) {
+            entities.push(entity);
+        }
+    }
+    Ok(entities)
+}
+
+/// POST /api/entities/batch — hydrate many entities in a single query.
+/// Accepts `?include=presentation`.
+pub async fn get_entities_batch(
+    State(state): State<AppState>,
+    Query(include): Query<IncludeParams>,
+    Json(request): Json<EntityBatchRequest>,
+) -> impl IntoResponse {
+    let mut ids = request.ids;
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+
+    if ids.len() > MAX_BATCH_IDS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Batch size {} exceeds maximum of {MAX_BATCH_IDS}", ids.len())
+            })),
+        )
+            .into_response();
+    }
+
+    info!(
+        count = ids.len(),
+        include_relationships = request.include_relationships,
+        "Fetching entity batch"
+    );
+
+    match state
+        .graph
+        .get_entities_batch(&ids, request.include_relationships)
+        .await
+    {
+        Ok(entities) => {
+            let found: std::collections::HashSet<Uuid> =
+                entities.iter().map(|item| item.entity.id).collect();
+            let missing = ids.into_iter().filter(|id| !found.contains(id)).collect();
+            let presentation = if include.presentation() {
+                presentation::presentations(entities.iter().map(|item| &item.entity))
+            } else {
+                Default::default()
+            };
+            let response = EntityBatchResponse {
+                entities,
+                missing,
+                presentation,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            error!("Entity batch lookup failed: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Batch lookup failed: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /api/entities/{id} — entity with its direct neighbors. Accepts
+/// `direction` and `relation_types` query parameters to narrow the edges,
+/// and `as_of` to list only neighbors and edges that existed then (404 if
+/// the entity itself did not). The entity's fields are always current.
+/// `?include=presentation` adds the entity's and neighbors' category,
+/// color and icon.
 pub async fn get_entity(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(params): Query<NeighborQueryParams>,
+    Query(include): Query<IncludeParams>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     info!(%id, "Fetching entity");
 
+    let filter = match params.filter(&state.config().ontology) {
+        Ok(filter) => filter,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e })),
+            )
+                .into_response()
+        }
+    };
+
     let entity = match state.graph.get_entity(id).await {
         Ok(Some(entity)) => entity,
         Ok(None) => {
@@ -72,25 +352,165 @@ pub async fn get_entity(
         }
     };
 
-    match state.graph.get_neighbors(id, 1).await {
-        Ok(neighbors_result) => {
-            let response = EntityDetailResponse {
-                entity,
-                relationships: neighbors_result.relationships,
-                neighbors: neighbors_result.neighbors,
+    let not_yet = |as_of: chrono::DateTime<chrono::Utc>| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Entity {id} did not exist as of {}", as_of.to_rfc3339()) })),
+        )
+            .into_response()
+    };
+    if let Some(as_of) = params.as_of.filter(|as_of| entity.first_seen > *as_of) {
+        return not_yet(as_of);
+    }
+
+    match (
+        state.graph.get_neighbors_filtered(id, 1, &filter).await,
+        params.as_of,
+    ) {
+        (Ok(neighbors_result), _) => {
+            let mut response = EntityDetailResponse {
+                truncated: neighbors_result.truncated,
+                ..EntityDetailResponse::new(
+                    entity,
+                    neighbors_result.relationships,
+                    neighbors_result.neighbors,
+                )
             };
-            (StatusCode::OK, Json(response)).into_response()
+            if include.presentation() {
+                response.add_presentation();
+            }
+            http_cache::json_with_etag(&headers, &response)
         }
-        Err(e) => {
+        // Invalidated by then
+        (Err(argus_core::ArgusError::NotFound(_)), Some(as_of)) => not_yet(as_of),
+        (Err(e), _) => {
             error!("Failed to fetch neighbors for entity {id}: {e}");
-            // Return the entity even if neighbors fail
-            let response = EntityDetailResponse {
-                entity,
-                relationships: Vec::new(),
-                neighbors: Vec::new(),
+            // Return the entity even if neighbors fail, but don't let it be reused
+            let mut response = EntityDetailResponse::new(entity, Vec::new(), Vec::new());
+            if include.presentation() {
+                response.add_presentation();
+            }
+            (
+                StatusCode::OK,
+                [(header::CACHE_CONTROL, http_cache::NO_STORE)],
+                Json(response),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /api/entities/by-identifier/{scheme}/{value} — entities carrying an
+/// external identifier such as a Wikidata QID or LEI. The value may contain
+/// slashes, so OpenCorporates URLs can be passed as-is. Accepts
+/// `?include=presentation`.
+pub async fn get_entities_by_identifier(
+    State(state): State<AppState>,
+    Path((scheme, value)): Path<(String, String)>,
+    Query(include): Query<IncludeParams>,
+) -> impl IntoResponse {
+    let scheme = normalize_identifier_scheme(&scheme);
+    info!(%scheme, %value, "Looking up entities by identifier");
+
+    match state.graph.find_by_identifier(&scheme, &value).await {
+        Ok(entities) if entities.is_empty() => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("No entity with {scheme} identifier {value}") })),
+        )
+            .into_response(),
+        Ok(entities) => {
+            let presentation = if include.presentation() {
+                presentation::presentations(&entities)
+            } else {
+                Default::default()
+            };
+            let response = EntityIdentifierResponse {
+                scheme,
+                value,
+                entities,
+                presentation,
             };
             (StatusCode::OK, Json(response)).into_response()
         }
+        Err(e) => {
+            error!("Identifier lookup {scheme}:{value} failed: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Identifier lookup failed: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// DELETE /api/entities/{id} — soft-delete an entity: it drops out of search
+/// and traversal, keeps its history, and agents don't store it again.
+pub async fn delete_entity(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.graph.soft_delete_entity(id).await {
+        Ok(tombstone) => (StatusCode::OK, Json(tombstone)).into_response(),
+        Err(ArgusError::NotFound(message)) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": message })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to delete entity {id}: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to delete entity: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /api/entities/{id}/history — recorded changes to an entity, newest first.
+pub async fn get_entity_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<EntityHistoryParams>,
+) -> impl IntoResponse {
+    match state.graph.get_entity(id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": format!("Entity {id} not found") })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Failed to fetch entity {id}: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to fetch entity: {e}") })),
+            )
+                .into_response();
+        }
+    }
+
+    match state.graph.get_entity_history(id, params.limit()).await {
+        Ok(changes) => (
+            StatusCode::OK,
+            Json(EntityHistoryResponse {
+                entity_id: id,
+                changes,
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to fetch history for entity {id}: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(
+                    serde_json::json!({ "error": format!("Failed to fetch entity history: {e}") }),
+                ),
+            )
+                .into_response()
+        }
     }
 }
 
@@ -106,107 +526,87 @@ pub async fn get_timeline(
         "Fetching timeline"
     );
 
-    // Build a Cypher query for time-ordered events.
-    // If an entity_id is provided, filter to events connected to that entity.
-    let (cypher, params) = if let Some(entity_id) = request.entity_id {
-        let mut conditions = vec!["(e)-[]->(ev)".to_string(), format!("e.id = '{entity_id}'")];
-
-        if let Some(ref start) = request.start {
-            conditions.push(format!("ev.timestamp >= datetime('{}')", start.to_rfc3339()));
+    match fetch_timeline(state.graph.as_ref(), &request).await {
+        Ok(events) => {
+            let response = TimelineResponse { events };
+            (StatusCode::OK, Json(response)).into_response()
         }
-        if let Some(ref end) = request.end {
-            conditions.push(format!("ev.timestamp <= datetime('{}')", end.to_rfc3339()));
+        Err(e) => {
+            error!("Timeline query failed: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Timeline query failed: {e}") })),
+            )
+                .into_response()
         }
+    }
+}
 
-        let cypher = format!(
-            "MATCH (e:Entity)-[r]->(ev:Entity) \
-             WHERE e.id = $entity_id \
-             {} \
-             RETURN ev, type(r) as event_type, e \
-             ORDER BY ev.last_seen DESC \
-             LIMIT $limit",
-            if request.start.is_some() || request.end.is_some() {
-                let mut time_filter = String::new();
-                if let Some(ref start) = request.start {
-                    time_filter
-                        .push_str(&format!("AND ev.last_seen >= datetime('{}')", start.to_rfc3339()));
-                }
-                if let Some(ref end) = request.end {
-                    if !time_filter.is_empty() {
-                        time_filter.push(' ');
-                    }
-                    time_filter
-                        .push_str(&format!("AND ev.last_seen <= datetime('{}')", end.to_rfc3339()));
-                }
-                time_filter
-            } else {
-                String::new()
-            }
-        );
+/// Time-ordered events, optionally around one entity. Shared by the REST
+/// and GraphQL timelines.
+pub(crate) async fn fetch_timeline(
+    graph: &dyn GraphStore,
+    request: &TimelineRequest,
+) -> argus_core::Result<Vec<TimelineEvent>> {
+    let result = graph.execute_cypher(&timeline_query(request)).await?;
+    // The result format depends on the Neo4j driver; we do a best-effort
+    // conversion here.
+    Ok(parse_timeline_events(&result))
+}
 
-        let params = serde_json::json!({
-            "entity_id": entity_id.to_string(),
-            "limit": request.limit,
-        });
+fn timeline_query(request: &TimelineRequest) -> GraphQuery {
+    // Events are placed at the time the source reports, falling back to
+    // when they were last collected. If an entity_id is provided, only
+    // events connected to that entity are returned.
+    let var = if request.entity_id.is_some() {
+        "ev"
+    } else {
+        "e"
+    };
+    let time = format!("coalesce({var}.observed_at, {var}.last_seen)");
 
-        (cypher, params)
+    let mut conditions = Vec::new();
+    if request.entity_id.is_some() {
+        conditions.push("e.id = $entity_id".to_string());
+    }
+    if request.start.is_some() {
+        conditions.push(format!("{time} >= $start"));
+    }
+    if request.end.is_some() {
+        conditions.push(format!("{time} <= $end"));
+    }
+    let where_clause = if conditions.is_empty() {
+        String::new()
     } else {
-        let mut time_filter = String::new();
-        if let Some(ref start) = request.start {
-            time_filter.push_str(&format!(
-                "WHERE e.last_seen >= datetime('{}')",
-                start.to_rfc3339()
-            ));
-        }
-        if let Some(ref end) = request.end {
-            if time_filter.is_empty() {
-                time_filter.push_str(&format!(
-                    "WHERE e.last_seen <= datetime('{}')",
-                    end.to_rfc3339()
-                ));
-            } else {
-                time_filter.push_str(&format!(
-                    " AND e.last_seen <= datetime('{}')",
-                    end.to_rfc3339()
-                ));
-            }
-        }
+        format!("WHERE {}", conditions.join(" AND "))
+    };
 
-        let cypher = format!(
+    let cypher = if request.entity_id.is_some() {
+        format!(
+            "MATCH (e:Entity)-[r]->(ev:Entity) \
+             {where_clause} \
+             RETURN ev, type(r) as event_type, e \
+             ORDER BY {time} DESC \
+             LIMIT $limit"
+        )
+    } else {
+        format!(
             "MATCH (e:Entity) \
-             {time_filter} \
+             {where_clause} \
              RETURN e \
-             ORDER BY e.last_seen DESC \
+             ORDER BY {time} DESC \
              LIMIT $limit"
-        );
-
-        let params = serde_json::json!({
-            "limit": request.limit,
-        });
-
-        (cypher, params)
+        )
     };
 
-    let query = GraphQuery { cypher, params };
+    let params = serde_json::json!({
+        "entity_id": request.entity_id.map(|id| id.to_string()),
+        "start": request.start.map(|t| t.to_rfc3339()),
+        "end": request.end.map(|t| t.to_rfc3339()),
+        "limit": request.limit,
+    });
 
-    match state.graph.execute_cypher(&query).await {
-        Ok(result) => {
-            // Parse the Cypher result into TimelineEvent structs.
-            // The result format depends on the Neo4j driver; we do a
-            // best-effort conversion here.
-            let events = parse_timeline_events(&result);
-            let response = TimelineResponse { events };
-            (StatusCode::OK, Json(response)).into_response()
-        }
-        Err(e) => {
-            error!("Timeline query failed: {e}");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Timeline query failed: {e}") })),
-            )
-                .into_response()
-        }
-    }
+    GraphQuery { cypher, params }
 }
 
 /// Best-effort parse of Cypher result JSON into timeline events.
@@ -220,7 +620,8 @@ fn parse_timeline_events(result: &serde_json::Value) -> Vec<TimelineEvent> {
 
     for row in rows {
         // Try to extract entity data from the result row
-        let entity_value = row.get("e").or_else(|| row.get("ev")).unwrap_or(row);
+        // Connected-entity rows carry the event as `ev` and the anchor as `e`
+        let entity_value = row.get("ev").or_else(|| row.get("e")).unwrap_or(row);
 
         let entity: argus_core::Entity = match serde_json::from_value(entity_value.clone()) {
             Ok(e) => e,
@@ -236,7 +637,7 @@ fn parse_timeline_events(result: &serde_json::Value) -> Vec<TimelineEvent> {
         let description = format!("{} — {}", entity.name, entity.source);
 
         let event = TimelineEvent {
-            timestamp: entity.last_seen,
+            timestamp: entity.observed_at.unwrap_or(entity.last_seen),
             entity,
             event_type,
             description,
@@ -0,0 +1,126 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use argus_core::api_types::{ExportParams, InvestigationExportRequest};
+use argus_core::export::{ftm, stix};
+use argus_core::{ArgusError, ExportFormat, GraphStore, NeighborFilter, Subgraph};
+
+use crate::handlers::graph::MAX_NEIGHBOR_DEPTH;
+use crate::ndjson;
+use crate::state::AppState;
+use crate::usage::UsageCharge;
+
+/// Upper bound on seed entities in one investigation export; each one is a
+/// separate traversal.
+const MAX_EXPORT_SEEDS: usize = 100;
+
+fn render(subgraph: &Subgraph, format: ExportFormat) -> Response {
+    let charge = UsageCharge {
+        exported_records: (subgraph.entities.len() + subgraph.relationships.len()) as u64,
+        ..UsageCharge::default()
+    };
+    let response = match format {
+        ExportFormat::Stix => {
+            let bundle = stix::bundle(subgraph, chrono::Utc::now());
+            (
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, stix::MEDIA_TYPE)],
+                Json(bundle),
+            )
+                .into_response()
+        }
+        // Entities are encoded as the body is sent rather than all up front
+        ExportFormat::Ftm => ndjson::response(futures::stream::iter(
+            ftm::export(subgraph).into_iter().map(Ok),
+        )),
+    };
+    (Extension(charge), response).into_response()
+}
+
+/// GET /api/entities/{id}/export — entity and its neighborhood in an
+/// interchange format (`format=stix|ftm`, `depth` 1–3).
+pub async fn export_entity(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ExportParams>,
+) -> impl IntoResponse {
+    let depth = params.depth.unwrap_or(1).clamp(1, MAX_NEIGHBOR_DEPTH);
+    info!(%id, depth, format = ?params.format, "Exporting entity");
+
+    let filter = NeighborFilter {
+        min_strength: params.min_strength,
+        ..NeighborFilter::default()
+    };
+    match state.graph.get_neighbors_filtered(id, depth, &filter).await {
+        Ok(neighbors) => {
+            let mut subgraph = Subgraph::default();
+            subgraph.add_neighbors(neighbors);
+            render(&subgraph, params.format)
+        }
+        Err(ArgusError::NotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Entity {id} not found") })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to export entity {id}: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to export entity: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /api/export — merge the neighborhoods of several seed entities into
+/// one export. Unknown ids are skipped.
+pub async fn export_investigation(
+    State(state): State<AppState>,
+    Json(request): Json<InvestigationExportRequest>,
+) -> impl IntoResponse {
+    let mut ids = request.entity_ids;
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+
+    if ids.is_empty() || ids.len() > MAX_EXPORT_SEEDS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("entity_ids must contain between 1 and {MAX_EXPORT_SEEDS} ids")
+            })),
+        )
+            .into_response();
+    }
+
+    let depth = request.depth.unwrap_or(1).clamp(1, MAX_NEIGHBOR_DEPTH);
+    info!(seeds = ids.len(), depth, format = ?request.format, "Exporting investigation");
+
+    let filter = NeighborFilter {
+        min_strength: request.min_strength,
+        ..NeighborFilter::default()
+    };
+    let mut subgraph = Subgraph::default();
+    for id in ids {
+        match state.graph.get_neighbors_filtered(id, depth, &filter).await {
+            Ok(neighbors) => subgraph.add_neighbors(neighbors),
+            Err(ArgusError::NotFound(_)) => continue,
+            Err(e) => {
+                error!("Failed to export entity {id}: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "error": format!("Failed to export investigation: {e}") })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    render(&subgraph, request.format)
+}
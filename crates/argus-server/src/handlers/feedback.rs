@@ -0,0 +1,204 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::info;
+use uuid::Uuid;
+
+use argus_core::api_types::{
+    CalibrationParams, FeedbackListParams, FeedbackListResponse, FeedbackRequest,
+};
+use argus_core::calibration::{calibration_report, FeedbackLabel, FeedbackTarget};
+use argus_core::prompts::PROMPT_VERSION_PROPERTY;
+use argus_core::GraphStore;
+
+use crate::handlers::error;
+use crate::state::AppState;
+
+/// Source recorded on labels for reasoning answers.
+const ANSWER_SOURCE: &str = "reasoning";
+
+/// POST /api/feedback — record an analyst's verdict on an entity,
+/// relationship or reasoning answer, replacing any earlier verdict on it.
+/// 404 if the entity or relationship does not exist.
+pub async fn submit_feedback(
+    State(state): State<AppState>,
+    Json(request): Json<FeedbackRequest>,
+) -> Response {
+    let (confidence, source, kind, prompt_version) = match describe_target(&state, &request).await {
+        Ok(described) => described,
+        Err(response) => return response,
+    };
+
+    let label = FeedbackLabel {
+        id: Uuid::new_v4(),
+        target: request.target,
+        target_id: request.target_id,
+        correct: request.correct,
+        confidence,
+        source,
+        kind,
+        note: request
+            .note
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty()),
+        prompt_version,
+        created_at: chrono::Utc::now(),
+    };
+    if let Err(e) = state.feedback.save(&label).await {
+        return error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save feedback: {e}"),
+        );
+    }
+    info!(id = %label.id, target = %label.target, correct = label.correct, "Recorded feedback");
+    (StatusCode::CREATED, Json(label)).into_response()
+}
+
+/// The confidence, source, kind and prompt version a label records for the
+/// item judged.
+async fn describe_target(
+    state: &AppState,
+    request: &FeedbackRequest,
+) -> Result<(f64, String, String, Option<u32>), Response> {
+    let missing_id = || {
+        error(
+            StatusCode::BAD_REQUEST,
+            format!("target_id is required for {} feedback", request.target),
+        )
+    };
+    let lookup_failed = |e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to look up {}: {e}", request.target),
+        )
+    };
+    let not_found = |id: Uuid| {
+        error(
+            StatusCode::NOT_FOUND,
+            format!("{} {id} not found", request.target),
+        )
+    };
+
+    match request.target {
+        FeedbackTarget::Entity => {
+            let id = request.target_id.ok_or_else(missing_id)?;
+            let entity = state
+                .graph
+                .get_entity(id)
+                .await
+                .map_err(lookup_failed)?
+                .ok_or_else(|| not_found(id))?;
+            let version = prompt_version(&entity.properties);
+            Ok((
+                entity.confidence,
+                entity.source,
+                entity.entity_type.to_string(),
+                version,
+            ))
+        }
+        FeedbackTarget::Relationship => {
+            let id = request.target_id.ok_or_else(missing_id)?;
+            let rel = state
+                .graph
+                .get_relationship(id)
+                .await
+                .map_err(lookup_failed)?
+                .ok_or_else(|| not_found(id))?;
+            let version = prompt_version(&rel.properties);
+            Ok((
+                rel.confidence,
+                rel.source,
+                rel.relation_type.to_string(),
+                version,
+            ))
+        }
+        FeedbackTarget::Answer => {
+            let confidence = match (request.target_id, request.confidence) {
+                (Some(id), _) => state.graph.get_entity(id).await.map_err(lookup_failed)?.ok_or_else(|| not_found(id))?.confidence,
+                (None, Some(confidence)) if (0.0..=1.0).contains(&confidence) => confidence,
+                (None, _) => {
+                    return Err(error(
+                        StatusCode::BAD_REQUEST,
+                        "Answer feedback needs the persisted answer's target_id or its confidence (0 to 1)".to_string(),
+                    ))
+                }
+            };
+            Ok((
+                confidence,
+                ANSWER_SOURCE.to_string(),
+                "answer".to_string(),
+                None,
+            ))
+        }
+    }
+}
+
+/// The prompt bundle version an extracted item records, if any.
+fn prompt_version(properties: &serde_json::Value) -> Option<u32> {
+    properties
+        .get(PROMPT_VERSION_PROPERTY)?
+        .as_u64()?
+        .try_into()
+        .ok()
+}
+
+/// GET /api/feedback — labels, newest first, optionally for one target kind.
+pub async fn list_feedback(
+    State(state): State<AppState>,
+    Query(params): Query<FeedbackListParams>,
+) -> Response {
+    match state.feedback.list().await {
+        Ok(labels) => {
+            let matching: Vec<FeedbackLabel> = labels
+                .into_iter()
+                .filter(|l| params.target.is_none_or(|t| l.target == t))
+                .collect();
+            let total = matching.len();
+            let labels = matching.into_iter().take(params.limit()).collect();
+            (StatusCode::OK, Json(FeedbackListResponse { labels, total })).into_response()
+        }
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list feedback: {e}"),
+        ),
+    }
+}
+
+/// DELETE /api/feedback/{id} — withdraw a label.
+pub async fn delete_feedback(State(state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    match state.feedback.delete(id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error(
+            StatusCode::NOT_FOUND,
+            format!("Feedback label {id} not found"),
+        ),
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete feedback: {e}"),
+        ),
+    }
+}
+
+/// GET /api/feedback/calibration — reliability diagrams and suggested
+/// confidence thresholds per target and source and/or type.
+pub async fn get_calibration(
+    State(state): State<AppState>,
+    Query(params): Query<CalibrationParams>,
+) -> Response {
+    match state.feedback.list().await {
+        Ok(labels) => {
+            let labels: Vec<FeedbackLabel> = labels
+                .into_iter()
+                .filter(|l| params.target.is_none_or(|t| l.target == t))
+                .collect();
+            let report = calibration_report(&labels, params.by, params.bins(), params.precision());
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load feedback: {e}"),
+        ),
+    }
+}
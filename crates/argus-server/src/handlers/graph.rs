@@ -1,6 +1,6 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
@@ -8,24 +8,46 @@ use tracing::{error, info};
 use uuid::Uuid;
 
 use argus_core::api_types::{
-    EntityDetailResponse, EntityTypeStat, GraphQueryRequest, GraphQueryResponse,
-    GraphStatsResponse,
+    EntityDetailResponse, EntityTypeStat, GraphQueryParams, GraphQueryRequest, GraphQueryResponse,
+    GraphStatsResponse, IncludeParams, NeighborQueryParams, PathQueryParams, PathResponse,
 };
 use argus_core::{EntityType, GraphQuery, GraphStore};
 
+use crate::http_cache;
+use crate::ndjson;
 use crate::state::AppState;
 
+/// Deepest traversal accepted by the neighbors endpoint.
+pub(crate) const MAX_NEIGHBOR_DEPTH: u32 = 3;
+
+/// POST /api/graph/query — run raw Cypher. With `stream=true` the rows
+/// are sent as newline-delimited JSON while they are read.
 pub async fn query_graph(
     State(state): State<AppState>,
+    Query(params): Query<GraphQueryParams>,
     Json(request): Json<GraphQueryRequest>,
 ) -> impl IntoResponse {
-    info!(cypher = %request.cypher, "Executing graph query");
+    info!(cypher = %request.cypher, stream = params.stream, "Executing graph query");
 
     let query = GraphQuery {
         cypher: request.cypher,
         params: request.params,
     };
 
+    if params.stream {
+        return match state.graph.stream_cypher(&query).await {
+            Ok(rows) => ndjson::response(rows),
+            Err(e) => {
+                error!("Graph query failed: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "error": format!("Query failed: {e}") })),
+                )
+                    .into_response()
+            }
+        };
+    }
+
     match state.graph.execute_cypher(&query).await {
         Ok(result) => {
             let response = GraphQueryResponse { result };
@@ -42,7 +64,7 @@ pub async fn query_graph(
     }
 }
 
-pub async fn graph_stats(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn graph_stats(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
     info!("Fetching graph statistics");
 
     let entity_count = match state.graph.entity_count().await {
@@ -78,7 +100,7 @@ pub async fn graph_stats(State(state): State<AppState>) -> impl IntoResponse {
         entity_types,
     };
 
-    (StatusCode::OK, Json(response)).into_response()
+    http_cache::json_with_etag(&headers, &response)
 }
 
 async fn fetch_entity_type_stats(state: &AppState) -> Vec<EntityTypeStat> {
@@ -105,23 +127,9 @@ async fn fetch_entity_type_stats(state: &AppState) -> Vec<EntityTypeStat> {
                 None => continue,
             };
 
-            let count = row
-                .get("count")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-
-            let entity_type = match type_str {
-                "person" => EntityType::Person,
-                "organization" => EntityType::Organization,
-                "vessel" => EntityType::Vessel,
-                "aircraft" => EntityType::Aircraft,
-                "location" => EntityType::Location,
-                "event" => EntityType::Event,
-                "document" => EntityType::Document,
-                "transaction" => EntityType::Transaction,
-                "sanction" => EntityType::Sanction,
-                _ => continue,
-            };
+            let count = row.get("count").and_then(|v| v.as_u64()).unwrap_or(0);
+
+            let entity_type = EntityType::from_name(type_str);
 
             stats.push(EntityTypeStat { entity_type, count });
         }
@@ -130,41 +138,42 @@ async fn fetch_entity_type_stats(state: &AppState) -> Vec<EntityTypeStat> {
     stats
 }
 
+/// GET /api/graph/neighbors/{id} — neighbor subgraph. Accepts `depth`
+/// (1–3), `direction`, `relation_types`, `as_of` and `include=presentation`
+/// query parameters.
 pub async fn get_neighbors(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(params): Query<NeighborQueryParams>,
+    Query(include): Query<IncludeParams>,
 ) -> impl IntoResponse {
-    info!(%id, "Fetching neighbors");
+    let depth = params.depth.unwrap_or(1).clamp(1, MAX_NEIGHBOR_DEPTH);
+    info!(%id, depth, direction = ?params.direction, "Fetching neighbors");
 
-    // First retrieve the entity itself
-    let entity = match state.graph.get_entity(id).await {
-        Ok(Some(entity)) => entity,
-        Ok(None) => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": format!("Entity {id} not found") })),
-            )
-                .into_response();
-        }
+    let filter = match params.filter(&state.config().ontology) {
+        Ok(filter) => filter,
         Err(e) => {
-            error!("Failed to fetch entity {id}: {e}");
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Failed to fetch entity: {e}") })),
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e })),
             )
-                .into_response();
+                .into_response()
         }
     };
 
-    match state.graph.get_neighbors(id, 1).await {
+    match state.graph.get_neighbors_filtered(id, depth, &filter).await {
         Ok(neighbors_result) => {
-            let response = EntityDetailResponse {
-                entity,
-                relationships: neighbors_result.relationships,
-                neighbors: neighbors_result.neighbors,
-            };
+            let mut response = EntityDetailResponse::from(neighbors_result);
+            if include.presentation() {
+                response.add_presentation();
+            }
             (StatusCode::OK, Json(response)).into_response()
         }
+        Err(argus_core::ArgusError::NotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("Entity {id} not found") })),
+        )
+            .into_response(),
         Err(e) => {
             error!("Failed to fetch neighbors for entity {id}: {e}");
             (
@@ -175,3 +184,38 @@ pub async fn get_neighbors(
         }
     }
 }
+
+/// GET /api/graph/paths — shortest paths between `from` and `to`, ignoring
+/// edge direction. Accepts `max_depth` (1–6), `limit` and `as_of`.
+pub async fn find_paths(
+    State(state): State<AppState>,
+    Query(params): Query<PathQueryParams>,
+) -> impl IntoResponse {
+    let max_depth = params.max_depth.unwrap_or(4);
+    info!(from = %params.from, to = %params.to, max_depth, as_of = ?params.as_of, "Finding paths");
+
+    match state
+        .graph
+        .find_paths(
+            params.from,
+            params.to,
+            max_depth,
+            params.limit(),
+            params.as_of,
+        )
+        .await
+    {
+        Ok(paths) => (StatusCode::OK, Json(PathResponse { paths })).into_response(),
+        Err(e) => {
+            error!(
+                "Failed to find paths from {} to {}: {e}",
+                params.from, params.to
+            );
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to find paths: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
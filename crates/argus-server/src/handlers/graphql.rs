@@ -0,0 +1,15 @@
+use async_graphql::http::GraphiQLSource;
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::{extract::State, response::Html};
+
+use crate::state::AppState;
+
+/// POST /graphql — execute a GraphQL query against the knowledge graph.
+pub async fn execute(State(state): State<AppState>, request: GraphQLRequest) -> GraphQLResponse {
+    state.graphql.execute(request.into_inner()).await.into()
+}
+
+/// GET /graphql — GraphiQL explorer for the schema.
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{error, info};
+
+use argus_core::api_types::{FtmImportParams, FtmImportResponse};
+use argus_core::export::ftm;
+use argus_core::{ExtractionResult, GraphStore};
+
+use crate::state::AppState;
+
+/// Largest accepted import body; OpenSanctions slices are tens of megabytes.
+pub(crate) const MAX_IMPORT_BYTES: usize = 256 * 1024 * 1024;
+
+/// Entities or relationships written per transaction.
+const IMPORT_CHUNK_SIZE: usize = 500;
+
+/// POST /api/import/ftm — ingest FollowTheMoney entities (JSON array or
+/// line-delimited) straight into the graph, bypassing LLM extraction.
+pub async fn import_ftm(
+    State(state): State<AppState>,
+    Query(params): Query<FtmImportParams>,
+    body: String,
+) -> impl IntoResponse {
+    let source = params.source.unwrap_or_else(|| "ftm".to_string());
+    let (entities, invalid_lines) = ftm::parse(&body);
+    let (result, skipped) = ftm::import(&entities, &source, chrono::Utc::now());
+    info!(
+        source = %source,
+        entities = result.entities.len(),
+        relationships = result.relationships.len(),
+        skipped = skipped.len(),
+        invalid = invalid_lines.len(),
+        "Importing FtM entities"
+    );
+
+    // Nodes first, so every relationship finds its endpoints regardless of
+    // the order entities appear in the file
+    let chunk = |entities: &[argus_core::Entity], relationships: &[argus_core::Relationship]| {
+        ExtractionResult {
+            entities: entities.to_vec(),
+            relationships: relationships.to_vec(),
+            raw_source: result.raw_source.clone(),
+            extracted_at: result.extracted_at,
+        }
+    };
+    let batches = result
+        .entities
+        .chunks(IMPORT_CHUNK_SIZE)
+        .map(|entities| chunk(entities, &[]))
+        .chain(
+            result
+                .relationships
+                .chunks(IMPORT_CHUNK_SIZE)
+                .map(|relationships| chunk(&[], relationships)),
+        );
+
+    for batch in batches {
+        if let Err(e) = state.graph.store_extraction(&batch).await {
+            error!("FtM import failed: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to store imported entities: {e}") })),
+            )
+                .into_response();
+        }
+    }
+
+    let response = FtmImportResponse {
+        entities: result.entities.len(),
+        relationships: result.relationships.len(),
+        skipped,
+        invalid_lines,
+    };
+    (StatusCode::OK, Json(response)).into_response()
+}
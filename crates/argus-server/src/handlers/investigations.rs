@@ -0,0 +1,286 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::Utc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use argus_core::alerts::MAX_ALERTS;
+use argus_core::api_types::{
+    CaseTimelineParams, CaseTimelineResponse, InvestigationListResponse, InvestigationNoteRequest,
+    InvestigationRequest, TimelineFormat,
+};
+use argus_core::investigation::{sort_timeline, timeline_csv, MAX_INVESTIGATION_ENTITIES};
+use argus_core::tracks::asset_keys;
+use argus_core::{
+    AlertStatus, ArgusError, CaseEvent, GraphStore, Investigation, InvestigationNote,
+};
+
+use crate::handlers::error;
+use crate::state::AppState;
+use crate::usage::UsageCharge;
+
+/// Position reports read per tracked entity in a case.
+const MAX_POSITIONS_PER_ENTITY: usize = 500;
+
+fn not_found(id: Uuid) -> Response {
+    error(
+        StatusCode::NOT_FOUND,
+        format!("Investigation {id} not found"),
+    )
+}
+
+async fn load(state: &AppState, id: Uuid) -> Result<Investigation, Response> {
+    match state.investigations.get(id).await {
+        Ok(Some(investigation)) => Ok(investigation),
+        Ok(None) => Err(not_found(id)),
+        Err(e) => Err(error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read investigation: {e}"),
+        )),
+    }
+}
+
+/// POST /api/investigations — open a case on a set of entities.
+pub async fn create_investigation(
+    State(state): State<AppState>,
+    Json(request): Json<InvestigationRequest>,
+) -> Response {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "name must not be empty".to_string(),
+        );
+    }
+    let mut entity_ids = request.entity_ids;
+    let mut seen = HashSet::new();
+    entity_ids.retain(|id| seen.insert(*id));
+    if entity_ids.is_empty() || entity_ids.len() > MAX_INVESTIGATION_ENTITIES {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!("entity_ids must contain between 1 and {MAX_INVESTIGATION_ENTITIES} ids"),
+        );
+    }
+
+    let investigation = Investigation {
+        id: Uuid::new_v4(),
+        name,
+        entity_ids,
+        notes: Vec::new(),
+        created_at: Utc::now(),
+    };
+    if let Err(e) = state.investigations.save(&investigation).await {
+        return error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save investigation: {e}"),
+        );
+    }
+    info!(id = %investigation.id, entities = investigation.entity_ids.len(), "Opened investigation");
+    (StatusCode::CREATED, Json(investigation)).into_response()
+}
+
+/// GET /api/investigations — all investigations, newest first.
+pub async fn list_investigations(State(state): State<AppState>) -> Response {
+    match state.investigations.list().await {
+        Ok(investigations) => (
+            StatusCode::OK,
+            Json(InvestigationListResponse { investigations }),
+        )
+            .into_response(),
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list investigations: {e}"),
+        ),
+    }
+}
+
+/// GET /api/investigations/{id} — one investigation with its notes.
+pub async fn get_investigation(State(state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    match load(&state, id).await {
+        Ok(investigation) => (StatusCode::OK, Json(investigation)).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// DELETE /api/investigations/{id} — drop a case and its notes. The
+/// entities are left alone.
+pub async fn delete_investigation(State(state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    match state.investigations.delete(id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => not_found(id),
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete investigation: {e}"),
+        ),
+    }
+}
+
+/// POST /api/investigations/{id}/notes — annotate a case, optionally one
+/// of its entities.
+pub async fn add_note(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<InvestigationNoteRequest>,
+) -> Response {
+    let text = request.text.trim().to_string();
+    if text.is_empty() {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "text must not be empty".to_string(),
+        );
+    }
+    let mut investigation = match load(&state, id).await {
+        Ok(investigation) => investigation,
+        Err(response) => return response,
+    };
+    if let Some(entity_id) = request
+        .entity_id
+        .filter(|e| !investigation.entity_ids.contains(e))
+    {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!("Entity {entity_id} is not part of investigation {id}"),
+        );
+    }
+
+    let now = Utc::now();
+    let note = InvestigationNote {
+        id: Uuid::new_v4(),
+        text,
+        entity_id: request.entity_id,
+        author: request
+            .author
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty()),
+        at: request.at.unwrap_or(now),
+        created_at: now,
+    };
+    investigation.notes.push(note.clone());
+    if let Err(e) = state.investigations.save(&investigation).await {
+        return error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save note: {e}"),
+        );
+    }
+    (StatusCode::CREATED, Json(note)).into_response()
+}
+
+/// GET /api/investigations/{id}/timeline — relationship timestamps,
+/// position reports, alerts and notes for the case's entities, oldest
+/// first. `format=csv` returns the same entries as a CSV download.
+pub async fn get_timeline(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<CaseTimelineParams>,
+) -> Response {
+    let investigation = match load(&state, id).await {
+        Ok(investigation) => investigation,
+        Err(response) => return response,
+    };
+    info!(%id, entities = investigation.entity_ids.len(), format = ?params.format, "Building case timeline");
+
+    let (mut events, truncated) = match case_events(&state, &investigation).await {
+        Ok(built) => built,
+        Err(e) => {
+            error!("Failed to build timeline for investigation {id}: {e}");
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to build timeline: {e}"),
+            );
+        }
+    };
+    events.retain(|e| {
+        params.start.is_none_or(|start| e.at >= start) && params.end.is_none_or(|end| e.at <= end)
+    });
+    sort_timeline(&mut events);
+
+    match params.format {
+        TimelineFormat::Json => {
+            let response = CaseTimelineResponse {
+                investigation_id: id,
+                events,
+                truncated,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        TimelineFormat::Csv => {
+            let charge = UsageCharge {
+                exported_records: events.len() as u64,
+                ..UsageCharge::default()
+            };
+            let disposition = format!("attachment; filename=\"investigation-{id}-timeline.csv\"");
+            let headers = [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, disposition),
+            ];
+            (
+                Extension(charge),
+                (StatusCode::OK, headers, timeline_csv(&events)),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Every dated entry about the case's entities, unsorted, and whether any
+/// source was capped. Entities that no longer exist are skipped.
+async fn case_events(
+    state: &AppState,
+    investigation: &Investigation,
+) -> argus_core::Result<(Vec<CaseEvent>, bool)> {
+    let case: HashSet<Uuid> = investigation.entity_ids.iter().copied().collect();
+    let mut names = HashMap::new();
+    let mut relationships = HashMap::new();
+    let mut entities = Vec::new();
+    let mut truncated = false;
+
+    for &id in &investigation.entity_ids {
+        let neighbors = match state.graph.get_neighbors(id, 1).await {
+            Ok(neighbors) => neighbors,
+            Err(ArgusError::NotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        truncated |= neighbors.truncated;
+        for entity in neighbors.neighbors.iter().chain([&neighbors.entity]) {
+            names.insert(entity.id, entity.name.clone());
+        }
+        for rel in neighbors.relationships {
+            relationships.insert(rel.id, rel);
+        }
+        entities.push(neighbors.entity);
+    }
+
+    let mut events: Vec<CaseEvent> = relationships
+        .values()
+        .filter_map(|rel| CaseEvent::relationship(rel, &names, &case))
+        .collect();
+
+    for entity in &entities {
+        let assets = asset_keys(entity);
+        if assets.is_empty() {
+            continue;
+        }
+        let positions = state
+            .graph
+            .asset_positions(&assets, MAX_POSITIONS_PER_ENTITY)
+            .await?;
+        truncated |= positions.len() >= MAX_POSITIONS_PER_ENTITY;
+        events.extend(positions.iter().map(|p| CaseEvent::position(p, entity)));
+    }
+
+    let alerts = state.alerts.list(AlertStatus::All, MAX_ALERTS).await?;
+    events.extend(
+        alerts
+            .iter()
+            .filter_map(|alert| CaseEvent::alert(alert, &case)),
+    );
+    events.extend(investigation.notes.iter().map(CaseEvent::note));
+
+    Ok((events, truncated))
+}
@@ -1,5 +1,28 @@
+pub mod admin;
 pub mod agents;
+pub mod alerts;
+pub mod analytics;
+pub mod documents;
+pub mod enrichment;
 pub mod entities;
+pub mod export;
+pub mod feedback;
 pub mod graph;
+pub mod graphql;
 pub mod health;
+pub mod import;
+pub mod investigations;
 pub mod reasoning;
+pub mod relationships;
+pub mod subscriptions;
+
+use axum::{
+    extract::Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+/// A `{"error": message}` body with `status`.
+pub(crate) fn error(status: StatusCode, message: String) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
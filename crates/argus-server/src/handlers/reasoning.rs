@@ -1,16 +1,31 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Query, State},
     http::StatusCode,
     response::IntoResponse,
+    Extension,
 };
-use tracing::{error, info, instrument};
+use chrono::Utc;
+use tracing::{error, info, instrument, warn};
 
-use argus_core::api_types::{ReasoningApiResponse, ReasoningRequest};
-use argus_core::reasoning::{ReasoningEngine, ReasoningQuery};
+use argus_core::alerts::MAX_ALERTS;
+use argus_core::api_types::{
+    BriefingParams, ReasoningApiResponse, ReasoningRequest, ReasoningTemplateListResponse,
+};
+use argus_core::briefing::{
+    briefing_instructions, briefing_material, Briefing, BriefingAlert, BriefingCounts,
+    BriefingEntity, BriefingRelationship, MAX_BRIEFING_ITEMS,
+};
+use argus_core::reasoning::{ReasoningEngine, ReasoningQuery, ReasoningUsage, SummaryRequest};
+use argus_core::{AlertStatus, ArgusError, GraphStore, RelationshipFilter};
+use argus_reasoning::provenance::answer_extraction;
+use argus_reasoning::templates::{self, TEMPLATES};
 
 use crate::state::AppState;
+use crate::usage::UsageCharge;
 
-#[instrument(skip(state), fields(question = %req.question))]
+#[instrument(skip(state), fields(question = %req.question, template = req.template.as_deref().unwrap_or("none")))]
 pub async fn query_reasoning(
     State(state): State<AppState>,
     Json(req): Json<ReasoningRequest>,
@@ -21,22 +36,69 @@ pub async fn query_reasoning(
         "Received reasoning query"
     );
 
-    let query = ReasoningQuery {
-        question: req.question,
-        context: req.context,
-        max_hops: req.max_hops,
+    let persist = req
+        .persist
+        .unwrap_or(state.config().reasoning_persist_answers);
+    let query = match req.template {
+        Some(template) => templates::expand(&template, &req.params, req.context, Utc::now()),
+        None if req.question.trim().is_empty() => {
+            Err("Either question or template is required".to_string())
+        }
+        None => Ok(ReasoningQuery {
+            question: req.question,
+            context: req.context,
+            max_hops: req.max_hops,
+            prepared: Vec::new(),
+        }),
+    };
+    let query = match query {
+        Ok(query) => query,
+        Err(e) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e })),
+            ))
+        }
     };
 
     match state.reasoning.query(&query).await {
         Ok(response) => {
-            let api_response: ReasoningApiResponse = response.into();
+            // Answers cut short by the budget are not conclusions worth keeping
+            let document_id = if persist && response.usage.budget_exceeded.is_none() {
+                let record = answer_extraction(&query, &response, chrono::Utc::now());
+                match state.graph.store_extraction(&record).await {
+                    Ok(()) => record.entities.first().map(|d| d.id),
+                    Err(e) => {
+                        warn!(error = %e, "Failed to record reasoning answer");
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            let mut api_response: ReasoningApiResponse = response.into();
+            api_response.document_id = document_id;
             info!(
                 confidence = api_response.confidence,
                 steps = api_response.steps.len(),
                 entities = api_response.entities_referenced.len(),
+                llm_calls = api_response.usage.llm_calls,
+                input_tokens = api_response.usage.input_tokens,
+                output_tokens = api_response.usage.output_tokens,
+                total_ms = api_response.usage.total_ms,
+                budget_exceeded = api_response
+                    .usage
+                    .budget_exceeded
+                    .as_deref()
+                    .unwrap_or("no"),
                 "Reasoning query completed successfully"
             );
-            Ok(Json(api_response))
+            let charge = UsageCharge {
+                reasoning_tokens: api_response.usage.input_tokens
+                    + api_response.usage.output_tokens,
+                ..UsageCharge::default()
+            };
+            Ok((Extension(charge), Json(api_response)))
         }
         Err(e) => {
             error!(error = %e, "Reasoning query failed");
@@ -49,3 +111,160 @@ pub async fn query_reasoning(
         }
     }
 }
+
+/// GET /api/reasoning/templates — named questions usable as `template` in
+/// a reasoning query.
+pub async fn list_templates() -> Json<ReasoningTemplateListResponse> {
+    Json(ReasoningTemplateListResponse {
+        templates: TEMPLATES.iter().map(|t| t.describe()).collect(),
+    })
+}
+
+/// GET /api/briefing — entities and relationships new to the graph and
+/// alerts raised over the last `since` (default 24h), summarized by the
+/// reasoning engine, with links to the underlying entities.
+#[instrument(skip(state), fields(since = params.since.as_deref().unwrap_or("default")))]
+pub async fn get_briefing(
+    State(state): State<AppState>,
+    Query(params): Query<BriefingParams>,
+) -> impl IntoResponse {
+    let window = match params.window() {
+        Ok(window) => window,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e })),
+            )
+                .into_response();
+        }
+    };
+    let until = Utc::now();
+    let since = until - window;
+
+    let mut briefing = match gather_briefing(&state, since).await {
+        Ok(briefing) => briefing,
+        Err(e) => {
+            error!(error = %e, "Failed to gather briefing");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to gather briefing: {e}") })),
+            )
+                .into_response();
+        }
+    };
+    briefing.until = until;
+
+    let counts = &briefing.counts;
+    if counts.new_entities == 0 && counts.new_relationships == 0 && counts.alerts == 0 {
+        briefing.summary = format!(
+            "No new entities, relationships or alerts since {}.",
+            since.to_rfc3339()
+        );
+        briefing.confidence = 1.0;
+        return (StatusCode::OK, Json(briefing)).into_response();
+    }
+
+    let request = SummaryRequest {
+        instructions: briefing_instructions(since, until),
+        material: briefing_material(
+            &briefing.new_entities,
+            &briefing.new_relationships,
+            &briefing.alerts,
+            &briefing.counts,
+        ),
+    };
+    match state.reasoning.summarize(&request).await {
+        Ok(response) => {
+            briefing.summary = response.answer;
+            briefing.confidence = response.confidence;
+            briefing.key_entities = response
+                .entities_referenced
+                .iter()
+                .map(BriefingEntity::from)
+                .collect();
+            briefing.usage = response.usage;
+            info!(
+                new_entities = briefing.counts.new_entities,
+                new_relationships = briefing.counts.new_relationships,
+                alerts = briefing.counts.alerts,
+                key_entities = briefing.key_entities.len(),
+                llm_calls = briefing.usage.llm_calls,
+                total_ms = briefing.usage.total_ms,
+                "Briefing completed"
+            );
+            let charge = UsageCharge {
+                reasoning_tokens: briefing.usage.input_tokens + briefing.usage.output_tokens,
+                ..UsageCharge::default()
+            };
+            (StatusCode::OK, Extension(charge), Json(briefing)).into_response()
+        }
+        Err(e) => {
+            error!(error = %e, "Briefing summary failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Briefing summary failed: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Everything new since `since`, without the summary.
+async fn gather_briefing(
+    state: &AppState,
+    since: chrono::DateTime<Utc>,
+) -> Result<Briefing, ArgusError> {
+    let entities = state
+        .graph
+        .entities_first_seen_since(since, MAX_BRIEFING_ITEMS)
+        .await?;
+    let filter = RelationshipFilter {
+        created_after: Some(since),
+        limit: MAX_BRIEFING_ITEMS,
+        ..RelationshipFilter::default()
+    };
+    let relationships = state.graph.list_relationships(&filter).await?;
+    let (new_entities, new_relationships) = state.graph.count_new_since(since).await?;
+    let mut alerts = state.alerts.list(AlertStatus::All, MAX_ALERTS).await?;
+    alerts.retain(|alert| alert.created_at >= since);
+
+    // Name relationship endpoints, looking up those that are not new
+    let mut names: HashMap<_, _> = entities.iter().map(|e| (e.id, e.name.clone())).collect();
+    let mut missing: Vec<_> = relationships
+        .iter()
+        .flat_map(|r| [r.source_entity_id, r.target_entity_id])
+        .filter(|id| !names.contains_key(id))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    if !missing.is_empty() {
+        for item in state.graph.get_entities_batch(&missing, false).await? {
+            names.insert(item.entity.id, item.entity.name);
+        }
+    }
+
+    let counts = BriefingCounts {
+        new_entities,
+        new_relationships,
+        alerts: alerts.len() as u64,
+        truncated: new_entities > entities.len() as u64
+            || new_relationships > relationships.len() as u64
+            || alerts.len() > MAX_BRIEFING_ITEMS,
+    };
+    alerts.truncate(MAX_BRIEFING_ITEMS);
+    Ok(Briefing {
+        since,
+        until: Utc::now(),
+        summary: String::new(),
+        confidence: 0.0,
+        key_entities: Vec::new(),
+        new_entities: entities.iter().map(BriefingEntity::from).collect(),
+        new_relationships: relationships
+            .iter()
+            .map(|r| BriefingRelationship::new(r, &names))
+            .collect(),
+        alerts: alerts.iter().map(BriefingAlert::from).collect(),
+        counts,
+        usage: ReasoningUsage::default(),
+    })
+}
@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{error, info};
+
+use argus_core::api_types::RelationshipListResponse;
+use argus_core::{GraphStore, RelationshipFilter};
+
+use crate::state::AppState;
+
+/// Upper bound on relationships returned per page.
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// GET /api/relationships — list relationships filtered by type, source,
+/// confidence and creation time, newest first.
+pub async fn list_relationships(
+    State(state): State<AppState>,
+    Query(mut filter): Query<RelationshipFilter>,
+) -> impl IntoResponse {
+    filter.limit = filter.limit.clamp(1, MAX_PAGE_SIZE);
+    let limit = filter.limit;
+    let offset = filter.offset;
+
+    info!(
+        relation_type = ?filter.relation_type,
+        source = ?filter.source,
+        limit,
+        offset,
+        "Listing relationships"
+    );
+
+    if let Some(Err(e)) = filter
+        .relation_type
+        .as_ref()
+        .map(|t| state.config().ontology.check_relation_type(t))
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e })),
+        )
+            .into_response();
+    }
+
+    // Fetch one extra row to tell whether another page exists
+    filter.limit += 1;
+
+    match state.graph.list_relationships(&filter).await {
+        Ok(mut relationships) => {
+            let has_more = relationships.len() > limit;
+            relationships.truncate(limit);
+            let response = RelationshipListResponse {
+                relationships,
+                limit,
+                offset,
+                has_more,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            error!("Relationship listing failed: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to list relationships: {e}") })),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use argus_core::api_types::{
+    FollowRequest, NotificationFeedParams, NotificationFeedResponse, SubscriptionListParams,
+    SubscriptionListResponse,
+};
+use argus_core::subscriptions::MAX_NOTIFICATIONS;
+use argus_core::{GraphStore, Subscription, SubscriptionEvent};
+
+use crate::handlers::error;
+use crate::state::AppState;
+
+/// POST /api/entities/{id}/follow — subscribe to what happens to an
+/// entity: new relationships, new documents and sanctions changes, or the
+/// `events` asked for.
+pub async fn follow_entity(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    request: Option<Json<FollowRequest>>,
+) -> Response {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    if let Some(url) = request
+        .webhook_url
+        .as_deref()
+        .filter(|url| !url.starts_with("https://"))
+    {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!("webhook_url must be an https URL, got {url}"),
+        );
+    }
+    let entity = match state.graph.get_entity(id).await {
+        Ok(Some(entity)) => entity,
+        Ok(None) => return error(StatusCode::NOT_FOUND, format!("Entity {id} not found")),
+        Err(e) => {
+            error!("Failed to fetch entity {id}: {e}");
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch entity: {e}"),
+            );
+        }
+    };
+
+    let mut events = request.events;
+    if events.is_empty() {
+        events = SubscriptionEvent::ALL.to_vec();
+    }
+    events.sort();
+    events.dedup();
+    let subscription = Subscription {
+        id: Uuid::new_v4(),
+        entity_id: entity.id,
+        entity_name: entity.name,
+        entity_type: entity.entity_type,
+        identifiers: entity.identifiers,
+        events,
+        subscriber: request
+            .subscriber
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+        webhook_url: request.webhook_url,
+        created_at: Utc::now(),
+    };
+    if let Err(e) = state.subscriptions.save(&subscription).await {
+        return error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save subscription: {e}"),
+        );
+    }
+    info!(%id, subscription_id = %subscription.id, "Following entity");
+    (StatusCode::CREATED, Json(subscription)).into_response()
+}
+
+/// GET /api/subscriptions — subscriptions oldest first, optionally only a
+/// subscriber's or an entity's.
+pub async fn list_subscriptions(
+    State(state): State<AppState>,
+    Query(params): Query<SubscriptionListParams>,
+) -> Response {
+    match state.subscriptions.list().await {
+        Ok(mut subscriptions) => {
+            subscriptions.retain(|s| {
+                params
+                    .subscriber
+                    .as_ref()
+                    .is_none_or(|who| s.subscriber.as_ref() == Some(who))
+                    && params.entity_id.is_none_or(|id| s.entity_id == id)
+            });
+            (
+                StatusCode::OK,
+                Json(SubscriptionListResponse { subscriptions }),
+            )
+                .into_response()
+        }
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list subscriptions: {e}"),
+        ),
+    }
+}
+
+/// DELETE /api/subscriptions/{id} — stop following. Notifications already
+/// in the feed are kept.
+pub async fn delete_subscription(State(state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    match state.subscriptions.delete(id).await {
+        Ok(true) => {
+            if let Err(e) = state.notifications.forget(id).await {
+                error!(subscription_id = %id, error = %e, "Failed to drop notification keys of a deleted subscription");
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => error(
+            StatusCode::NOT_FOUND,
+            format!("Subscription {id} not found"),
+        ),
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete subscription: {e}"),
+        ),
+    }
+}
+
+/// GET /api/notifications — the in-app feed, newest first, optionally only
+/// a subscriber's or a subscription's and only those after `since`.
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    Query(params): Query<NotificationFeedParams>,
+) -> Response {
+    match state.notifications.list(MAX_NOTIFICATIONS).await {
+        Ok(mut notifications) => {
+            notifications.retain(|n| {
+                params
+                    .subscriber
+                    .as_ref()
+                    .is_none_or(|who| n.subscriber.as_ref() == Some(who))
+                    && params
+                        .subscription_id
+                        .is_none_or(|id| n.subscription_id == id)
+                    && params.since.is_none_or(|since| n.created_at > since)
+            });
+            let total = notifications.len();
+            notifications.truncate(params.limit());
+            (
+                StatusCode::OK,
+                Json(NotificationFeedResponse {
+                    notifications,
+                    total,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read notifications: {e}"),
+        ),
+    }
+}
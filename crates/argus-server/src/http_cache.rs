@@ -0,0 +1,133 @@
+//! HTTP caching helpers: strong ETags with `If-None-Match` revalidation for
+//! JSON responses, and per-route `Cache-Control` policies.
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+/// Entity detail changes whenever the graph does; clients must revalidate
+/// but a matching ETag costs no body.
+pub const ENTITY_DETAIL: &str = "private, no-cache";
+/// Stats are aggregate counts; a short staleness window is fine.
+pub const GRAPH_STATS: &str = "private, max-age=30";
+/// Neighborhoods and exports are large and query-specific.
+pub const SUBGRAPH: &str = "private, max-age=10";
+/// Search results and anything else that should never be reused.
+pub const NO_STORE: &str = "no-store";
+
+/// Set `Cache-Control` on a route's responses unless the handler already
+/// did. Error responses are always `no-store`.
+pub fn cache_control<B>(
+    policy: &'static str,
+) -> SetResponseHeaderLayer<impl Fn(&axum::http::Response<B>) -> Option<HeaderValue> + Clone> {
+    SetResponseHeaderLayer::if_not_present(
+        header::CACHE_CONTROL,
+        move |response: &axum::http::Response<B>| {
+            let status = response.status();
+            let policy = if status.is_success() || status == StatusCode::NOT_MODIFIED {
+                policy
+            } else {
+                NO_STORE
+            };
+            Some(HeaderValue::from_static(policy))
+        },
+    )
+}
+
+/// Strong ETag for a response body.
+fn etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hash: String = digest.iter().take(16).map(|b| format!("{b:02x}")).collect();
+    format!("\"{hash}\"")
+}
+
+/// Whether an `If-None-Match` header value matches `etag`. Weak
+/// comparison, as RFC 9110 requires for `If-None-Match`.
+fn matches_if_none_match(header: &str, etag: &str) -> bool {
+    header.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+/// Serialize `value` as a 200 JSON response with an ETag, or answer 304
+/// Not Modified when the request already holds that version.
+pub fn json_with_etag<T: Serialize>(headers: &HeaderMap, value: &T) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Failed to serialize response: {e}") })),
+            )
+                .into_response();
+        }
+    };
+    let tag = etag(&body);
+    let Ok(tag_value) = HeaderValue::from_str(&tag) else {
+        return (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            body,
+        )
+            .into_response();
+    };
+
+    let not_modified = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| matches_if_none_match(v, &tag));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, tag_value)]).into_response();
+    }
+
+    (
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (header::ETAG, tag_value),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_none_match_accepts_lists_weak_tags_and_wildcards() {
+        let tag = etag(b"{\"total\":1}");
+        assert!(tag.starts_with('"') && tag.ends_with('"'));
+        assert_eq!(tag, etag(b"{\"total\":1}"));
+        assert_ne!(tag, etag(b"{\"total\":2}"));
+
+        assert!(matches_if_none_match(&tag, &tag));
+        assert!(matches_if_none_match(&format!("\"other\", W/{tag}"), &tag));
+        assert!(matches_if_none_match("*", &tag));
+        assert!(!matches_if_none_match("\"other\"", &tag));
+    }
+
+    #[test]
+    fn matching_etag_returns_not_modified() {
+        let value = serde_json::json!({ "entities": 3 });
+        let first = json_with_etag(&HeaderMap::new(), &value);
+        assert_eq!(first.status(), StatusCode::OK);
+        let tag = first.headers()[header::ETAG].clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, tag.clone());
+        let second = json_with_etag(&headers, &value);
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers()[header::ETAG], tag);
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, error};
+
+use argus_core::LockManager;
+
+use crate::alerts::notify::Notifier;
+use crate::subscriptions::SubscriptionEngine;
+
+const LOCK_KEY: &str = "jobs:alert_digest";
+
+/// Send digests and retry failed alert notifications and subscription
+/// webhooks on a fixed interval, on one replica at a time.
+pub async fn run(
+    notifier: Arc<Notifier>,
+    subscriptions: Arc<SubscriptionEngine>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    interval: Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Alert digest owned by another replica, skipping");
+            continue;
+        }
+        if let Err(e) = notifier.flush().await {
+            error!(error = %e, "Alert digest failed");
+        }
+        if let Err(e) = subscriptions.retry_webhooks().await {
+            error!(error = %e, "Subscription webhook retry failed");
+        }
+    }
+}
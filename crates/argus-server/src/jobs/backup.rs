@@ -0,0 +1,743 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::write::GzipEncoder;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tracing::{error, info, warn};
+
+use argus_core::api_types::AgentRunState;
+use argus_core::backup::{
+    is_restorable_label, is_snapshot_name, NodeDump, RelationshipDump, SnapshotCounts,
+    SnapshotManifest, SnapshotMeta, SnapshotRecord, SNAPSHOT_BATCH_SIZE, SNAPSHOT_EXTENSION,
+    SNAPSHOT_FORMAT_VERSION,
+};
+use argus_core::GraphStore;
+
+use crate::shared::{run_lock_key, update_run};
+use crate::state::AppState;
+
+/// Name under which snapshot runs appear in the run history.
+pub const BACKUP_JOB: &str = "workspace_backup";
+/// Name under which restore runs appear in the run history.
+pub const RESTORE_JOB: &str = "workspace_restore";
+
+/// How often a restore checks whether agent runs it waits on have finished.
+const RUN_LOCK_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// `argus-20260114T030000Z.jsonl.gz`.
+pub fn snapshot_name(now: DateTime<Utc>) -> String {
+    format!(
+        "argus-{}.{SNAPSHOT_EXTENSION}",
+        now.format("%Y%m%dT%H%M%SZ")
+    )
+}
+
+/// `argus-20260114T030000Z-pre-restore.jsonl.gz`, the workspace as it was
+/// before a restore.
+fn safety_snapshot_name(now: DateTime<Utc>) -> String {
+    format!(
+        "argus-{}-pre-restore.{SNAPSHOT_EXTENSION}",
+        now.format("%Y%m%dT%H%M%SZ")
+    )
+}
+
+/// The manifest written next to a finished snapshot. A snapshot without one
+/// never finished.
+fn manifest_path(snapshot: &Path) -> PathBuf {
+    snapshot.with_extension("gz.json")
+}
+
+/// Finished snapshots in `dir`, newest first.
+pub async fn list(dir: &Path) -> std::io::Result<Vec<SnapshotManifest>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut manifests = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !is_snapshot_name(&name) {
+            continue;
+        }
+        let manifest = tokio::fs::read(manifest_path(&entry.path())).await.ok();
+        match manifest.map(|json| serde_json::from_slice::<SnapshotManifest>(&json)) {
+            Some(Ok(manifest)) => manifests.push(manifest),
+            Some(Err(e)) => {
+                warn!(file = %name, error = %e, "Skipping snapshot with unreadable manifest")
+            }
+            None => {}
+        }
+    }
+    manifests.sort_by_key(|m| std::cmp::Reverse(m.meta.created_at));
+    Ok(manifests)
+}
+
+struct SnapshotWriter {
+    out: GzipEncoder<BufWriter<tokio::fs::File>>,
+    counts: SnapshotCounts,
+}
+
+impl SnapshotWriter {
+    async fn write(&mut self, record: &SnapshotRecord) -> Result<(), String> {
+        let mut line =
+            serde_json::to_vec(record).map_err(|e| format!("Failed to encode record: {e}"))?;
+        line.push(b'\n');
+        self.out
+            .write_all(&line)
+            .await
+            .map_err(|e| format!("Failed to write snapshot: {e}"))
+    }
+}
+
+/// Write a snapshot of the graph and shared state to `name` in the backup
+/// directory. Progress is reported in the run's `snapshot` counts.
+pub async fn backup(run_id: String, state: AppState, name: String) {
+    let dir = PathBuf::from(&state.config().backup_dir);
+    let path = dir.join(&name);
+    info!(run_id = %run_id, file = %path.display(), "Workspace backup starting");
+
+    let outcome = write_snapshot(Some(&run_id), &state, &dir, &path).await;
+    finish_run(
+        &state,
+        &run_id,
+        BACKUP_JOB,
+        outcome.map(|manifest| manifest.counts),
+    )
+    .await;
+}
+
+/// Write a snapshot to `path`, reporting progress on `run_id` if given.
+async fn write_snapshot(
+    run_id: Option<&str>,
+    state: &AppState,
+    dir: &Path,
+    path: &Path,
+) -> Result<SnapshotManifest, String> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let partial = path.with_extension("gz.partial");
+    let file = tokio::fs::File::create(&partial)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", partial.display()))?;
+    let mut writer = SnapshotWriter {
+        out: GzipEncoder::new(BufWriter::new(file)),
+        counts: SnapshotCounts::default(),
+    };
+
+    let meta = SnapshotMeta {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        argus_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        instance_id: state.instance_id.clone(),
+    };
+    writer.write(&SnapshotRecord::Meta(meta.clone())).await?;
+    writer
+        .write(&SnapshotRecord::Config {
+            config: state.config().redacted(),
+        })
+        .await?;
+
+    // Relationships are read after the nodes, so ones to nodes created in
+    // between are left out; a restore fails on any without both endpoints
+    let mut written = HashSet::new();
+    let mut nodes = state
+        .graph
+        .snapshot_nodes()
+        .await
+        .map_err(|e| format!("Failed to read nodes: {e}"))?;
+    while let Some(node) = nodes.next().await {
+        let node = node.map_err(|e| format!("Failed to read nodes: {e}"))?;
+        writer.write(&SnapshotRecord::Node(node.clone())).await?;
+        written.insert(node.node_ref);
+        writer.counts.nodes += 1;
+        if writer
+            .counts
+            .nodes
+            .is_multiple_of(SNAPSHOT_BATCH_SIZE as u64)
+        {
+            if let Some(run_id) = run_id {
+                report_progress(state, run_id, &writer.counts).await;
+            }
+        }
+    }
+
+    let mut skipped = 0u64;
+    let mut relationships = state
+        .graph
+        .snapshot_relationships()
+        .await
+        .map_err(|e| format!("Failed to read relationships: {e}"))?;
+    while let Some(rel) = relationships.next().await {
+        let rel = rel.map_err(|e| format!("Failed to read relationships: {e}"))?;
+        if !written.contains(&rel.start) || !written.contains(&rel.end) {
+            skipped += 1;
+            continue;
+        }
+        writer.write(&SnapshotRecord::Relationship(rel)).await?;
+        writer.counts.relationships += 1;
+        if writer
+            .counts
+            .relationships
+            .is_multiple_of(SNAPSHOT_BATCH_SIZE as u64)
+        {
+            if let Some(run_id) = run_id {
+                report_progress(state, run_id, &writer.counts).await;
+            }
+        }
+    }
+    if skipped > 0 {
+        info!(run_id = ?run_id, skipped, "Left out relationships to nodes created during the backup");
+    }
+    if let Some(run_id) = run_id {
+        report_progress(state, run_id, &writer.counts).await;
+    }
+
+    let rules = state
+        .alert_rules
+        .list()
+        .await
+        .map_err(|e| format!("Failed to read alert rules: {e}"))?;
+    for rule in rules {
+        writer.write(&SnapshotRecord::AlertRule(rule)).await?;
+        writer.counts.alert_rules += 1;
+    }
+    let entries = state
+        .deny_list
+        .list()
+        .await
+        .map_err(|e| format!("Failed to read deny-list: {e}"))?;
+    for entry in entries {
+        writer.write(&SnapshotRecord::DenyEntry(entry)).await?;
+        writer.counts.deny_entries += 1;
+    }
+    let paused = state
+        .pauses
+        .paused()
+        .await
+        .map_err(|e| format!("Failed to read paused agents: {e}"))?;
+    for name in paused {
+        writer.write(&SnapshotRecord::PausedAgent { name }).await?;
+        writer.counts.paused_agents += 1;
+    }
+
+    let counts = writer.counts.clone();
+    writer.write(&SnapshotRecord::End(counts.clone())).await?;
+    writer
+        .out
+        .shutdown()
+        .await
+        .map_err(|e| format!("Failed to finish snapshot: {e}"))?;
+    tokio::fs::rename(&partial, path)
+        .await
+        .map_err(|e| format!("Failed to move snapshot into place: {e}"))?;
+
+    let bytes = tokio::fs::metadata(path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let manifest = SnapshotManifest {
+        file: path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+        meta,
+        counts,
+        bytes,
+    };
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to encode manifest: {e}"))?;
+    tokio::fs::write(manifest_path(path), json)
+        .await
+        .map_err(|e| format!("Failed to write manifest: {e}"))?;
+    Ok(manifest)
+}
+
+type SnapshotLines = tokio::io::Lines<BufReader<GzipDecoder<BufReader<tokio::fs::File>>>>;
+
+async fn open(path: &Path) -> Result<SnapshotLines, String> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    Ok(BufReader::new(GzipDecoder::new(BufReader::new(file))).lines())
+}
+
+async fn next_record(
+    lines: &mut SnapshotLines,
+    line_no: &mut u64,
+) -> Result<Option<SnapshotRecord>, String> {
+    let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| format!("Failed to read snapshot: {e}"))?
+    else {
+        return Ok(None);
+    };
+    *line_no += 1;
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| format!("Malformed snapshot record on line {line_no}: {e}"))
+}
+
+/// Read the whole snapshot once without restoring anything: it must open
+/// with a known format version, hold only labels and relationship types a
+/// restore can write, and close with counts matching its records.
+pub async fn verify(path: &Path) -> Result<(SnapshotMeta, SnapshotCounts), String> {
+    let mut lines = open(path).await?;
+    let mut line_no = 0;
+    let meta = match next_record(&mut lines, &mut line_no).await? {
+        Some(SnapshotRecord::Meta(meta)) => meta,
+        _ => return Err("Snapshot does not start with its metadata".to_string()),
+    };
+    if meta.format_version > SNAPSHOT_FORMAT_VERSION {
+        return Err(format!(
+            "Snapshot format {} is newer than this build supports ({SNAPSHOT_FORMAT_VERSION})",
+            meta.format_version
+        ));
+    }
+
+    let mut counts = SnapshotCounts::default();
+    while let Some(record) = next_record(&mut lines, &mut line_no).await? {
+        match record {
+            SnapshotRecord::Node(node) => {
+                if let Some(label) = node.labels.iter().find(|l| !is_restorable_label(l)) {
+                    return Err(format!(
+                        "Node label {label:?} on line {line_no} cannot be restored"
+                    ));
+                }
+                counts.nodes += 1;
+            }
+            SnapshotRecord::Relationship(rel) => {
+                if !is_restorable_label(&rel.rel_type) {
+                    return Err(format!(
+                        "Relationship type {:?} on line {line_no} cannot be restored",
+                        rel.rel_type
+                    ));
+                }
+                counts.relationships += 1;
+            }
+            SnapshotRecord::AlertRule(_) => counts.alert_rules += 1,
+            SnapshotRecord::DenyEntry(_) => counts.deny_entries += 1,
+            SnapshotRecord::PausedAgent { .. } => counts.paused_agents += 1,
+            SnapshotRecord::Meta(_) | SnapshotRecord::Config { .. } => {}
+            SnapshotRecord::End(expected) if expected == counts => return Ok((meta, counts)),
+            SnapshotRecord::End(expected) => {
+                return Err(format!(
+                    "Snapshot records {counts:?} do not match its trailer {expected:?}"
+                ))
+            }
+        }
+    }
+    Err("Snapshot is truncated: no trailer".to_string())
+}
+
+/// Replace the graph and shared state with the snapshot `name` from the
+/// backup directory, after checking it with [`verify`]. Agent runs and
+/// graph writes are held off until it is done (see [`hold_agent_runs`]),
+/// and the workspace is first saved to a `-pre-restore` snapshot that a
+/// failed restore can be undone from.
+/// Progress is reported in the run's `snapshot` counts.
+pub async fn restore(run_id: String, state: AppState, name: String) {
+    let dir = PathBuf::from(&state.config().backup_dir);
+    let path = dir.join(&name);
+    info!(run_id = %run_id, file = %path.display(), "Workspace restore starting");
+
+    let outcome = async {
+        let (meta, expected) = verify(&path).await?;
+        info!(
+            run_id = %run_id,
+            created_at = %meta.created_at,
+            argus_version = %meta.argus_version,
+            nodes = expected.nodes,
+            relationships = expected.relationships,
+            "Snapshot verified, waiting for agent runs to finish"
+        );
+        hold_agent_runs(&state, &run_id).await?;
+        let writes = state.writes.clone();
+        let paused = writes.pause().await;
+        info!(run_id = %run_id, "Agent runs and graph writes held off, saving the workspace before restoring");
+
+        let safety = safety_snapshot_name(Utc::now());
+        let restored = match write_snapshot(None, &state, &dir, &dir.join(&safety)).await {
+            Ok(_) => read_snapshot(&run_id, &state, &path)
+                .await
+                .map_err(|e| format!("{e}; the workspace before the restore is in snapshot {safety}")),
+            Err(e) => Err(format!("Failed to save the workspace before restoring, nothing was restored: {e}")),
+        };
+        drop(paused);
+        release_agent_runs(&state, &run_id).await;
+
+        let restored = restored?;
+        if restored.relationships < expected.relationships {
+            return Err(format!(
+                "{} of {} relationships lost an endpoint and were not restored; \
+                 the workspace before the restore is in snapshot {safety}",
+                expected.relationships - restored.relationships,
+                expected.relationships
+            ));
+        }
+        Ok(restored)
+    }
+    .await;
+    finish_run(&state, &run_id, RESTORE_JOB, outcome).await;
+}
+
+/// Take every agent's run lock for the restore `run_id`, waiting up to
+/// `AGENT_RUN_LOCK_TTL_SECONDS` for runs in progress (including ones queued
+/// for extraction workers) to finish. While the restore holds them no
+/// replica starts a run, scheduled or triggered. Locks already taken are
+/// given back if the wait times out.
+async fn hold_agent_runs(state: &AppState, run_id: &str) -> Result<(), String> {
+    let ttl = Duration::from_secs(state.config().agent_run_lock_ttl_seconds);
+    let deadline = tokio::time::Instant::now() + ttl;
+    loop {
+        let busy = take_run_locks(state, run_id, ttl).await;
+        if busy.is_empty() {
+            return Ok(());
+        }
+        if tokio::time::Instant::now() >= deadline {
+            release_agent_runs(state, run_id).await;
+            return Err(format!(
+                "Runs of {} still in progress after {}s, nothing was restored",
+                busy.join(", "),
+                ttl.as_secs()
+            ));
+        }
+        tokio::time::sleep(RUN_LOCK_POLL_INTERVAL).await;
+    }
+}
+
+/// Take or refresh the run lock of every agent for `run_id`, returning the
+/// agents whose lock another run holds.
+async fn take_run_locks(state: &AppState, run_id: &str, ttl: Duration) -> Vec<String> {
+    let mut busy = Vec::new();
+    for agent in state.agents.keys() {
+        match state
+            .locks
+            .try_acquire(&run_lock_key(agent), run_id, ttl)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => busy.push(agent.clone()),
+            Err(e) => {
+                warn!(run_id, agent = %agent, error = %e, "Run lock unavailable");
+                busy.push(agent.clone());
+            }
+        }
+    }
+    busy.sort();
+    busy
+}
+
+async fn release_agent_runs(state: &AppState, run_id: &str) {
+    for agent in state.agents.keys() {
+        if let Err(e) = state.locks.release(&run_lock_key(agent), run_id).await {
+            warn!(run_id, agent = %agent, error = %e, "Failed to release run lock");
+        }
+    }
+}
+
+/// Refresh the run locks [`hold_agent_runs`] took, so they do not lapse
+/// while the restore is still going.
+async fn keep_agent_runs(state: &AppState, run_id: &str) {
+    let ttl = Duration::from_secs(state.config().agent_run_lock_ttl_seconds);
+    let busy = take_run_locks(state, run_id, ttl).await;
+    if !busy.is_empty() {
+        warn!(run_id, agents = ?busy, "Lost run locks during restore; their runs may write to the graph");
+    }
+}
+
+async fn report_progress(state: &AppState, run_id: &str, counts: &SnapshotCounts) {
+    let counts = counts.clone();
+    update_run(&state.runs, run_id, |run| run.snapshot = Some(counts)).await;
+}
+
+async fn read_snapshot(
+    run_id: &str,
+    state: &AppState,
+    path: &Path,
+) -> Result<SnapshotCounts, String> {
+    state
+        .graph
+        .begin_restore()
+        .await
+        .map_err(|e| format!("Failed to clear the graph: {e}"))?;
+
+    let mut lines = open(path).await?;
+    let mut line_no = 0;
+    let mut restored = SnapshotCounts::default();
+    let mut nodes: Vec<NodeDump> = Vec::with_capacity(SNAPSHOT_BATCH_SIZE);
+    let mut relationships: Vec<RelationshipDump> = Vec::with_capacity(SNAPSHOT_BATCH_SIZE);
+    let mut rules = Vec::new();
+    let mut entries = Vec::new();
+    let mut paused = std::collections::BTreeSet::new();
+
+    while let Some(record) = next_record(&mut lines, &mut line_no).await? {
+        match record {
+            SnapshotRecord::Node(node) => {
+                nodes.push(node);
+                if nodes.len() == SNAPSHOT_BATCH_SIZE {
+                    flush_nodes(run_id, state, &mut nodes, &mut restored).await?;
+                }
+            }
+            SnapshotRecord::Relationship(rel) => {
+                // Snapshots list every node before any relationship
+                flush_nodes(run_id, state, &mut nodes, &mut restored).await?;
+                relationships.push(rel);
+                if relationships.len() == SNAPSHOT_BATCH_SIZE {
+                    flush_relationships(run_id, state, &mut relationships, &mut restored).await?;
+                }
+            }
+            SnapshotRecord::AlertRule(rule) => rules.push(rule),
+            SnapshotRecord::DenyEntry(entry) => entries.push(entry),
+            SnapshotRecord::PausedAgent { name } => {
+                paused.insert(name);
+            }
+            SnapshotRecord::Meta(_) | SnapshotRecord::Config { .. } => {}
+            SnapshotRecord::End(_) => break,
+        }
+    }
+    flush_nodes(run_id, state, &mut nodes, &mut restored).await?;
+    flush_relationships(run_id, state, &mut relationships, &mut restored).await?;
+    state
+        .graph
+        .finish_restore()
+        .await
+        .map_err(|e| format!("Failed to finish graph restore: {e}"))?;
+
+    restore_shared_state(state, rules, entries, paused, &mut restored).await?;
+    Ok(restored)
+}
+
+async fn flush_nodes(
+    run_id: &str,
+    state: &AppState,
+    nodes: &mut Vec<NodeDump>,
+    restored: &mut SnapshotCounts,
+) -> Result<(), String> {
+    if nodes.is_empty() {
+        return Ok(());
+    }
+    state
+        .graph
+        .restore_nodes(nodes)
+        .await
+        .map_err(|e| format!("Failed to restore nodes: {e}"))?;
+    restored.nodes += nodes.len() as u64;
+    nodes.clear();
+    report_progress(state, run_id, restored).await;
+    keep_agent_runs(state, run_id).await;
+    Ok(())
+}
+
+async fn flush_relationships(
+    run_id: &str,
+    state: &AppState,
+    relationships: &mut Vec<RelationshipDump>,
+    restored: &mut SnapshotCounts,
+) -> Result<(), String> {
+    if relationships.is_empty() {
+        return Ok(());
+    }
+    restored.relationships += state
+        .graph
+        .restore_relationships(relationships)
+        .await
+        .map_err(|e| format!("Failed to restore relationships: {e}"))?;
+    relationships.clear();
+    report_progress(state, run_id, restored).await;
+    keep_agent_runs(state, run_id).await;
+    Ok(())
+}
+
+/// Replace alert rules, deny-list entries and paused agents with the
+/// snapshot's.
+async fn restore_shared_state(
+    state: &AppState,
+    rules: Vec<argus_core::AlertRule>,
+    entries: Vec<argus_core::DenyEntry>,
+    paused: std::collections::BTreeSet<String>,
+    restored: &mut SnapshotCounts,
+) -> Result<(), String> {
+    let failed = |what: &'static str| {
+        move |e: argus_core::ArgusError| format!("Failed to restore {what}: {e}")
+    };
+
+    for rule in state
+        .alert_rules
+        .list()
+        .await
+        .map_err(failed("alert rules"))?
+    {
+        state
+            .alert_rules
+            .delete(rule.id)
+            .await
+            .map_err(failed("alert rules"))?;
+    }
+    for rule in &rules {
+        state
+            .alert_rules
+            .save(rule)
+            .await
+            .map_err(failed("alert rules"))?;
+    }
+    restored.alert_rules = rules.len() as u64;
+
+    for entry in state.deny_list.list().await.map_err(failed("deny-list"))? {
+        state
+            .deny_list
+            .delete(entry.id)
+            .await
+            .map_err(failed("deny-list"))?;
+    }
+    for entry in &entries {
+        state
+            .deny_list
+            .save(entry)
+            .await
+            .map_err(failed("deny-list"))?;
+    }
+    restored.deny_entries = entries.len() as u64;
+    state.validator.deny_list().replace(&entries);
+
+    for name in state
+        .pauses
+        .paused()
+        .await
+        .map_err(failed("paused agents"))?
+    {
+        if !paused.contains(&name) {
+            state
+                .pauses
+                .set_paused(&name, false)
+                .await
+                .map_err(failed("paused agents"))?;
+        }
+    }
+    for name in &paused {
+        state
+            .pauses
+            .set_paused(name, true)
+            .await
+            .map_err(failed("paused agents"))?;
+    }
+    restored.paused_agents = paused.len() as u64;
+    Ok(())
+}
+
+async fn finish_run(
+    state: &AppState,
+    run_id: &str,
+    job: &str,
+    outcome: Result<SnapshotCounts, String>,
+) {
+    update_run(&state.runs, run_id, |run| {
+        run.finished_at = Some(Utc::now());
+        match outcome {
+            Ok(counts) => {
+                run.status = AgentRunState::Completed;
+                info!(run_id = %run_id, job, ?counts, "Workspace snapshot job completed");
+                run.snapshot = Some(counts);
+            }
+            Err(e) => {
+                error!(run_id = %run_id, job, error = %e, "Workspace snapshot job failed");
+                run.status = AgentRunState::Failed;
+                run.error = Some(e);
+            }
+        }
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_names_sort_by_time_and_keep_their_manifest_apart() {
+        let now = DateTime::parse_from_rfc3339("2026-01-14T03:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let name = snapshot_name(now);
+        assert_eq!(name, "argus-20260114T030000Z.jsonl.gz");
+        assert!(is_snapshot_name(&name));
+        assert_eq!(
+            manifest_path(Path::new("data/backups").join(&name).as_path()),
+            Path::new("data/backups/argus-20260114T030000Z.jsonl.gz.json")
+        );
+        assert!(is_snapshot_name(&safety_snapshot_name(now)));
+    }
+
+    async fn write_records(records: &[SnapshotRecord]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("argus-snapshot-{}.jsonl.gz", uuid::Uuid::new_v4()));
+        let mut out = GzipEncoder::new(tokio::fs::File::create(&path).await.unwrap());
+        for record in records {
+            let mut line = serde_json::to_vec(record).unwrap();
+            line.push(b'\n');
+            out.write_all(&line).await.unwrap();
+        }
+        out.shutdown().await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_labels_a_restore_cannot_write() {
+        let meta = SnapshotMeta {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            argus_version: "test".to_string(),
+            created_at: Utc::now(),
+            instance_id: "test".to_string(),
+        };
+        let node = |labels: &[&str]| NodeDump {
+            node_ref: "4:abc:0".to_string(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            properties: serde_json::json!({}),
+        };
+        let relationship = |rel_type: &str| RelationshipDump {
+            rel_ref: "5:abc:0".to_string(),
+            rel_type: rel_type.to_string(),
+            start: "4:abc:0".to_string(),
+            end: "4:abc:0".to_string(),
+            properties: serde_json::json!({}),
+        };
+        let counts = SnapshotCounts {
+            nodes: 1,
+            relationships: 1,
+            ..SnapshotCounts::default()
+        };
+        let snapshot = |node: NodeDump, rel: RelationshipDump| {
+            vec![
+                SnapshotRecord::Meta(meta.clone()),
+                SnapshotRecord::Node(node),
+                SnapshotRecord::Relationship(rel),
+                SnapshotRecord::End(counts.clone()),
+            ]
+        };
+
+        let good = write_records(&snapshot(node(&["Person"]), relationship("OWNS"))).await;
+        assert_eq!(verify(&good).await.unwrap().1, counts);
+        let bad_label = write_records(&snapshot(
+            node(&["Person", "A`) DETACH DELETE (x"]),
+            relationship("OWNS"),
+        ))
+        .await;
+        assert!(verify(&bad_label).await.unwrap_err().contains("line 2"));
+        let bad_type = write_records(&snapshot(
+            node(&["Person"]),
+            relationship("OWNS]->() DELETE x//"),
+        ))
+        .await;
+        assert!(verify(&bad_type).await.unwrap_err().contains("line 3"));
+        for path in [good, bad_label, bad_type] {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+    }
+}
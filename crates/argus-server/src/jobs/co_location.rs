@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+
+use argus_core::tracks::{self, Encounter, LocationVisit, WatchedLocation};
+use argus_core::{Entity, EntityType, ExtractionResult, GraphStore, RelationType, Relationship};
+
+use crate::state::AppState;
+
+/// Delay before the first pass so agents get a chance to report positions.
+const INITIAL_DELAY: Duration = Duration::from_secs(60);
+
+const LOCK_KEY: &str = "jobs:co_location";
+
+/// Most positions read per pass; a busier window is checked in part.
+const MAX_POSITIONS_PER_PASS: usize = 50_000;
+
+/// Relationships this job writes are attributed to this source.
+pub const SOURCE: &str = "co_location";
+
+/// Find tracked assets that were together, or near a watched location,
+/// among recent positions, on one replica at a time. Each pass looks back
+/// one interval plus the time window, so encounters spanning two passes are
+/// seen whole; the `meeting_with` and `located_at` identity rules fold the
+/// repeats into the same edges.
+pub async fn run(state: AppState, interval: Duration) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !state
+            .locks
+            .try_acquire(LOCK_KEY, &state.instance_id, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Co-location pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        pass(&state, interval).await;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn pass(state: &AppState, interval: Duration) {
+    let config = state.config();
+    let window = chrono::Duration::minutes(config.colocation_window_minutes as i64);
+    let until = Utc::now();
+    let since = until - chrono::Duration::from_std(interval).unwrap_or_default() - window;
+    let positions = match state
+        .graph
+        .positions_between(since, until, MAX_POSITIONS_PER_PASS)
+        .await
+    {
+        Ok(positions) => positions,
+        Err(e) => {
+            warn!(error = %e, "Failed to read positions for co-location");
+            return;
+        }
+    };
+    if positions.len() == MAX_POSITIONS_PER_PASS {
+        warn!(
+            limit = MAX_POSITIONS_PER_PASS,
+            "Too many positions for one co-location pass, checking the oldest only"
+        );
+    }
+
+    let encounters =
+        tracks::detect_encounters(&positions, config.colocation_distance_meters, window);
+    let locations =
+        watched_locations(state.graph.as_ref(), &config.colocation_watch_locations).await;
+    let visits = tracks::detect_visits(&positions, &locations, config.colocation_distance_meters);
+
+    let mut assets = AssetResolver::default();
+    let mut written = 0;
+    for encounter in &encounters {
+        let (Some(a), Some(b)) = (
+            assets
+                .entity(state.graph.as_ref(), &encounter.assets.0)
+                .await,
+            assets
+                .entity(state.graph.as_ref(), &encounter.assets.1)
+                .await,
+        ) else {
+            continue;
+        };
+        let rel = meeting(&a, &b, encounter);
+        write(state, vec![a, b], rel).await;
+        written += 1;
+    }
+    for visit in &visits {
+        let Some(asset) = assets.entity(state.graph.as_ref(), &visit.asset).await else {
+            continue;
+        };
+        let Some(location) = locations.iter().find(|l| l.entity_id == visit.location_id) else {
+            continue;
+        };
+        let Ok(Some(place)) = state.graph.get_entity(location.entity_id).await else {
+            continue;
+        };
+        let rel = located_at(&asset, location, visit);
+        write(state, vec![asset, place], rel).await;
+        written += 1;
+    }
+
+    info!(
+        positions = positions.len(),
+        encounters = encounters.len(),
+        visits = visits.len(),
+        relationships = written,
+        unresolved_assets = assets.unresolved(),
+        "Co-location pass complete"
+    );
+}
+
+/// Send one derived relationship, with its endpoints, through the write buffer.
+async fn write(state: &AppState, entities: Vec<Entity>, rel: Relationship) {
+    let result = ExtractionResult {
+        raw_source: format!("{SOURCE}:{}:{}", rel.source_entity_id, rel.target_entity_id),
+        entities,
+        relationships: vec![rel],
+        extracted_at: Utc::now(),
+    };
+    if let Some(report) = state.writes.push(result).await {
+        if report.failed > 0 {
+            warn!(
+                failed = report.failed,
+                "Failed to store co-location relationships"
+            );
+        }
+    }
+}
+
+fn meeting(a: &Entity, b: &Entity, encounter: &Encounter) -> Relationship {
+    let mut rel = Relationship::new(a.id, b.id, RelationType::MeetingWith, SOURCE.to_string());
+    rel.timestamp = Some(encounter.closest_at);
+    rel.properties = serde_json::json!({
+        "started_at": encounter.started_at,
+        "ended_at": encounter.ended_at,
+        "closest_at": encounter.closest_at,
+        "distance_m": encounter.min_distance_m.round(),
+        "latitude": encounter.latitude,
+        "longitude": encounter.longitude,
+        "reports": encounter.reports,
+    });
+    rel
+}
+
+fn located_at(asset: &Entity, location: &WatchedLocation, visit: &LocationVisit) -> Relationship {
+    let mut rel = Relationship::new(
+        asset.id,
+        location.entity_id,
+        RelationType::LocatedAt,
+        SOURCE.to_string(),
+    );
+    rel.timestamp = Some(visit.first_seen_at);
+    rel.properties = serde_json::json!({
+        "first_seen_at": visit.first_seen_at,
+        "last_seen_at": visit.last_seen_at,
+        "distance_m": visit.min_distance_m.round(),
+        "reports": visit.reports,
+    });
+    rel
+}
+
+/// The configured Location entities that have coordinates. Names match
+/// case-insensitively; the first match with coordinates wins.
+async fn watched_locations(graph: &dyn GraphStore, names: &[String]) -> Vec<WatchedLocation> {
+    let mut locations = Vec::new();
+    for name in names {
+        let candidates = match graph.search_entities(name, 20).await {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                warn!(location = %name, error = %e, "Failed to look up watched location");
+                continue;
+            }
+        };
+        let found = candidates.into_iter().find_map(|entity| {
+            let coordinates = tracks::entity_coordinates(&entity)?;
+            (entity.entity_type == EntityType::Location && entity.name.eq_ignore_ascii_case(name))
+                .then_some((entity, coordinates))
+        });
+        match found {
+            Some((entity, (latitude, longitude))) => locations.push(WatchedLocation {
+                entity_id: entity.id,
+                name: entity.name,
+                latitude,
+                longitude,
+            }),
+            None => debug!(location = %name, "Watched location not found or has no coordinates"),
+        }
+    }
+    locations
+}
+
+/// Asset identifiers resolved to entities once per pass. Assets whose
+/// reports were stored before extraction produced an entity for them are
+/// skipped until it does.
+#[derive(Default)]
+pub(crate) struct AssetResolver {
+    entities: HashMap<String, Option<Entity>>,
+}
+
+impl AssetResolver {
+    pub(crate) async fn entity(&mut self, graph: &dyn GraphStore, asset: &str) -> Option<Entity> {
+        if let Some(entity) = self.entities.get(asset) {
+            return entity.clone();
+        }
+        let (scheme, value) = asset.split_once(':')?;
+        let entity = match graph.find_by_identifier(scheme, value).await {
+            Ok(mut matches) => (!matches.is_empty()).then(|| matches.remove(0)),
+            Err(e) => {
+                warn!(asset = %asset, error = %e, "Failed to resolve tracked asset");
+                None
+            }
+        };
+        self.entities.insert(asset.to_string(), entity.clone());
+        entity
+    }
+
+    pub(crate) fn unresolved(&self) -> usize {
+        self.entities.values().filter(|e| e.is_none()).count()
+    }
+}
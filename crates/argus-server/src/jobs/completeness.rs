@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, error, info};
+
+use argus_core::{GraphStore, LockManager};
+use argus_graph::Neo4jGraphStore;
+
+/// Delay before the first pass so agents get a chance to populate the graph.
+const INITIAL_DELAY: Duration = Duration::from_secs(120);
+
+const LOCK_KEY: &str = "jobs:completeness";
+
+/// Rescore entity completeness on a fixed interval, on one replica at a time.
+pub async fn run(
+    graph: Arc<Neo4jGraphStore>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    interval: Duration,
+) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Completeness pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        match graph.update_completeness(Utc::now()).await {
+            Ok(scored) => info!(entities = scored, "Completeness pass complete"),
+            Err(e) => error!(error = %e, "Completeness pass failed"),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
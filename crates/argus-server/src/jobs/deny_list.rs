@@ -0,0 +1,28 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+use argus_core::DenyListStore;
+use argus_extraction::DenyList;
+
+/// How often entries edited on another replica are picked up.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Load the stored deny-list into the validator right away, then reload it
+/// every minute. Every replica validates, so every replica refreshes.
+pub async fn run(store: Arc<dyn DenyListStore>, deny_list: Arc<DenyList>) {
+    loop {
+        reload(store.as_ref(), &deny_list).await;
+        debug!(entries = deny_list.len(), "Refreshed entity deny-list");
+        tokio::time::sleep(REFRESH_INTERVAL).await;
+    }
+}
+
+/// Apply the stored entries now. On failure the current entries stay.
+pub async fn reload(store: &dyn DenyListStore, deny_list: &DenyList) {
+    match store.list().await {
+        Ok(entries) => deny_list.replace(&entries),
+        Err(e) => warn!(error = %e, "Failed to load entity deny-list"),
+    }
+}
@@ -0,0 +1,424 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use argus_core::api_types::AgentRunState;
+use argus_core::duplicates::{
+    candidate_id, sort_candidates, DuplicateCandidate, DuplicateMember, DuplicateReason,
+};
+use argus_core::{DuplicateReviewQueue, Entity, GraphStore, RunStore};
+use argus_extraction::normalize::normalize_name;
+use argus_graph::Neo4jGraphStore;
+
+use crate::shared::update_run;
+
+/// Name under which scan runs appear in the run history.
+pub const JOB_NAME: &str = "duplicate_scan";
+
+const BATCH_SIZE: usize = 500;
+/// Entities one scan holds in memory; the rest of a larger graph is skipped.
+const MAX_SCAN_ENTITIES: usize = 500_000;
+/// Candidates kept per scan, best first.
+const MAX_CANDIDATES: usize = 5_000;
+/// Groups (one name token, one identifier) larger than this are too
+/// ambiguous to pair up: common words, placeholder identifiers.
+const MAX_BLOCK_SIZE: usize = 200;
+/// Bigram similarity from which two names of one type are reported.
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+/// Shorter names are compared by exact key only; "Iran" and "Iraq" are
+/// similar strings and different countries.
+const MIN_SIMILAR_NAME_LEN: usize = 6;
+
+/// What the scan keeps of each entity.
+#[derive(Debug, Clone)]
+struct ScanEntry {
+    member: DuplicateMember,
+    key: String,
+    identifiers: Vec<String>,
+    confidence: f64,
+    first_seen: DateTime<Utc>,
+}
+
+impl ScanEntry {
+    fn new(entity: &Entity) -> Self {
+        Self {
+            member: DuplicateMember {
+                id: entity.id,
+                name: entity.name.clone(),
+                entity_type: entity.entity_type.clone(),
+                source: entity.source.clone(),
+            },
+            key: match_key(&normalize_name(&entity.name, &entity.entity_type)),
+            identifiers: entity
+                .identifiers
+                .iter()
+                .filter(|(_, value)| !value.trim().is_empty())
+                .map(|(scheme, value)| format!("{scheme}:{}", value.trim().to_lowercase()))
+                .collect(),
+            confidence: entity.confidence,
+            first_seen: entity.first_seen,
+        }
+    }
+
+    fn type_name(&self) -> &str {
+        self.member.entity_type.as_str()
+    }
+
+    /// Whether `self` makes a better survivor than `other`: more
+    /// identifiers, then more confident, then seen first.
+    fn outranks(&self, other: &ScanEntry) -> bool {
+        self.identifiers
+            .len()
+            .cmp(&other.identifiers.len())
+            .then_with(|| self.confidence.total_cmp(&other.confidence))
+            .then_with(|| other.first_seen.cmp(&self.first_seen))
+            .then_with(|| other.member.id.cmp(&self.member.id))
+            .is_gt()
+    }
+}
+
+/// Lowercased, punctuation-free form of a normalized name for comparison.
+fn match_key(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Sørensen–Dice coefficient over character bigrams, 0.0–1.0.
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>()
+    };
+    let (left, mut right) = (bigrams(a), bigrams(b));
+    let total = left.len() + right.len();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut shared = 0;
+    for bigram in &left {
+        if let Some(pos) = right.iter().position(|b| b == bigram) {
+            right.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    (2 * shared) as f64 / total as f64
+}
+
+/// Collects reasons per pair, so a pair found several ways is reported once.
+#[derive(Default)]
+struct Candidates {
+    by_pair: HashMap<String, DuplicateCandidate>,
+}
+
+impl Candidates {
+    fn add(&mut self, a: &ScanEntry, b: &ScanEntry, reason: DuplicateReason) {
+        if a.member.id == b.member.id {
+            return;
+        }
+        match self
+            .by_pair
+            .get_mut(&candidate_id(a.member.id, b.member.id))
+        {
+            Some(candidate) => candidate.add_reason(reason),
+            None => {
+                let (keep, other) = if b.outranks(a) { (b, a) } else { (a, b) };
+                let candidate =
+                    DuplicateCandidate::new(keep.member.clone(), other.member.clone(), reason);
+                self.by_pair.insert(candidate.id.clone(), candidate);
+            }
+        }
+    }
+
+    fn into_sorted(self) -> Vec<DuplicateCandidate> {
+        let mut candidates: Vec<_> = self.by_pair.into_values().collect();
+        sort_candidates(&mut candidates);
+        candidates.truncate(MAX_CANDIDATES);
+        candidates
+    }
+}
+
+/// Index of the best survivor among `members`.
+fn best(entries: &[ScanEntry], members: &[usize]) -> usize {
+    members
+        .iter()
+        .copied()
+        .reduce(|best, i| {
+            if entries[i].outranks(&entries[best]) {
+                i
+            } else {
+                best
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Pair up probable duplicates among `entries`.
+fn find_candidates(entries: &[ScanEntry]) -> Vec<DuplicateCandidate> {
+    let mut candidates = Candidates::default();
+
+    // Same type and match key, from different sources
+    let mut by_key: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if !entry.key.is_empty() {
+            by_key
+                .entry((entry.type_name(), &entry.key))
+                .or_default()
+                .push(i);
+        }
+    }
+    for members in by_key
+        .values()
+        .filter(|m| m.len() > 1 && m.len() <= MAX_BLOCK_SIZE)
+    {
+        let keep = best(entries, members);
+        for &i in members {
+            if entries[i].member.source != entries[keep].member.source {
+                candidates.add(&entries[keep], &entries[i], DuplicateReason::SameName);
+            }
+        }
+    }
+
+    // Shared external identifiers, whatever the type
+    let mut by_identifier: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        for identifier in &entry.identifiers {
+            by_identifier.entry(identifier).or_default().push(i);
+        }
+    }
+    for (identifier, members) in by_identifier
+        .iter()
+        .filter(|(_, m)| m.len() > 1 && m.len() <= MAX_BLOCK_SIZE)
+    {
+        let (scheme, value) = identifier.split_once(':').unwrap_or((identifier, ""));
+        let keep = best(entries, members);
+        for &i in members {
+            let reason = DuplicateReason::SharedIdentifier {
+                scheme: scheme.to_string(),
+                value: value.to_string(),
+            };
+            candidates.add(&entries[keep], &entries[i], reason);
+        }
+    }
+
+    // Similar names of one type, compared within blocks sharing a name token
+    let mut by_token: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.key.len() < MIN_SIMILAR_NAME_LEN {
+            continue;
+        }
+        for token in entry.key.split(' ').filter(|t| t.len() >= 3) {
+            by_token
+                .entry((entry.type_name(), token))
+                .or_default()
+                .push(i);
+        }
+    }
+    let mut compared = HashSet::new();
+    for members in by_token
+        .values()
+        .filter(|m| m.len() > 1 && m.len() <= MAX_BLOCK_SIZE)
+    {
+        for (n, &i) in members.iter().enumerate() {
+            for &j in &members[n + 1..] {
+                let (a, b) = (&entries[i], &entries[j]);
+                if a.key == b.key || !compared.insert((i.min(j), i.max(j))) {
+                    continue;
+                }
+                let score = similarity(&a.key, &b.key);
+                if score >= SIMILARITY_THRESHOLD {
+                    let similarity = (score * 1000.0).round() / 1000.0;
+                    candidates.add(a, b, DuplicateReason::SimilarName { similarity });
+                }
+            }
+        }
+    }
+
+    candidates.into_sorted()
+}
+
+/// Scan every entity for probable duplicates and replace the review queue
+/// with what was found.
+///
+/// `documents_collected` counts entities scanned, `entities_extracted`
+/// the candidate pairs queued.
+pub async fn run(
+    run_id: String,
+    graph: Arc<Neo4jGraphStore>,
+    queue: Arc<dyn DuplicateReviewQueue>,
+    runs: Arc<dyn RunStore>,
+) {
+    let mut after: Option<Uuid> = None;
+    let mut entries = Vec::new();
+    let mut found = 0u64;
+
+    info!(run_id = %run_id, "Duplicate scan starting");
+
+    let outcome: std::result::Result<(), String> = async {
+        loop {
+            let entities = graph
+                .list_entities_after(after, BATCH_SIZE)
+                .await
+                .map_err(|e| format!("Failed to read entities: {e}"))?;
+            let Some(last) = entities.last() else {
+                break;
+            };
+            after = Some(last.id);
+            entries.extend(entities.iter().map(ScanEntry::new));
+
+            update_run(&runs, &run_id, |run| run.documents_collected = entries.len() as u64).await;
+
+            if entries.len() >= MAX_SCAN_ENTITIES {
+                warn!(run_id = %run_id, limit = MAX_SCAN_ENTITIES, "Duplicate scan stopped at entity limit");
+                break;
+            }
+            if entities.len() < BATCH_SIZE {
+                break;
+            }
+        }
+
+        let candidates = find_candidates(&entries);
+        found = candidates.len() as u64;
+        queue
+            .replace(candidates)
+            .await
+            .map_err(|e| format!("Failed to store review queue: {e}"))
+    }
+    .await;
+
+    let scanned = entries.len() as u64;
+    update_run(&runs, &run_id, |run| {
+        run.finished_at = Some(Utc::now());
+        run.documents_collected = scanned;
+        run.entities_extracted = found;
+        match outcome {
+            Ok(()) => {
+                run.status = AgentRunState::Completed;
+                info!(run_id = %run_id, scanned, found, "Duplicate scan completed");
+            }
+            Err(e) => {
+                error!(run_id = %run_id, error = %e, "Duplicate scan failed");
+                run.status = AgentRunState::Failed;
+                run.error = Some(e);
+            }
+        }
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::EntityType;
+
+    fn entry(
+        name: &str,
+        entity_type: EntityType,
+        source: &str,
+        identifiers: &[(&str, &str)],
+    ) -> ScanEntry {
+        ScanEntry::new(&Entity {
+            id: Uuid::new_v4(),
+            entity_type,
+            name: name.to_string(),
+            aliases: vec![],
+            properties: serde_json::json!({}),
+            source: source.to_string(),
+            source_id: None,
+            confidence: 0.8,
+            first_seen: Utc::now(),
+            last_seen: Utc::now(),
+            observed_at: None,
+            sanctions_exposure: None,
+            completeness: None,
+            mention_count: None,
+            geometry: None,
+            identifiers: identifiers
+                .iter()
+                .map(|(s, v)| (s.to_string(), v.to_string()))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn similarity_is_symmetric_and_bounded() {
+        assert_eq!(similarity("acme holdings", "acme holdings"), 1.0);
+        assert_eq!(similarity("ab", "cd"), 0.0);
+        let s = similarity("acme holding", "acme holdings");
+        assert!(s > 0.9 && s < 1.0);
+        assert_eq!(s, similarity("acme holdings", "acme holding"));
+        assert!(similarity("iran", "iraq") < SIMILARITY_THRESHOLD);
+    }
+
+    #[test]
+    fn same_key_pairs_only_across_sources() {
+        let entries = vec![
+            entry("Acme, Inc.", EntityType::Organization, "opensanctions", &[]),
+            entry("ACME INC", EntityType::Organization, "opencorporates", &[]),
+            entry("Acme Inc", EntityType::Location, "gdelt", &[]),
+            entry("Beta Corp", EntityType::Organization, "gdelt", &[]),
+            entry("Beta Corp.", EntityType::Organization, "gdelt", &[]),
+        ];
+        let candidates = find_candidates(&entries);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].reasons, vec![DuplicateReason::SameName]);
+        assert!(
+            candidates[0].involves(entries[0].member.id)
+                && candidates[0].involves(entries[1].member.id)
+        );
+    }
+
+    #[test]
+    fn shared_identifiers_outrank_and_pick_the_better_survivor() {
+        let entries = vec![
+            entry(
+                "Gazprom",
+                EntityType::Organization,
+                "gdelt",
+                &[("lei", "ABC123")],
+            ),
+            entry(
+                "PAO Gazprom",
+                EntityType::Organization,
+                "opensanctions",
+                &[("lei", "abc123"), ("wikidata", "Q102673")],
+            ),
+            entry("Jon Smithson", EntityType::Person, "gdelt", &[]),
+            entry("John Smithson", EntityType::Person, "opensanctions", &[]),
+        ];
+        let candidates = find_candidates(&entries);
+        assert_eq!(candidates.len(), 2);
+
+        let shared = &candidates[0];
+        assert_eq!(shared.score, 1.0);
+        assert_eq!(shared.keep, entries[1].member.id);
+        assert_eq!(
+            shared.reasons,
+            vec![DuplicateReason::SharedIdentifier {
+                scheme: "lei".to_string(),
+                value: "abc123".to_string()
+            }]
+        );
+
+        let similar = &candidates[1];
+        assert!(
+            matches!(similar.reasons[0], DuplicateReason::SimilarName { similarity } if similarity >= SIMILARITY_THRESHOLD)
+        );
+        assert!(similar.score < shared.score);
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::{error, info};
+
+use argus_core::api_types::AgentRunState;
+use argus_core::vector::{EmbeddingProvider, VectorPoint, VectorStore};
+use argus_core::{GraphStore, RateLimiter, RunStore};
+use argus_graph::Neo4jGraphStore;
+use argus_vector::{embedding_text, QdrantVectorStore, VoyageEmbeddingProvider};
+
+use crate::shared::update_run;
+
+/// Name under which backfill runs appear in the run history.
+pub const JOB_NAME: &str = "embedding_backfill";
+
+/// Entities embedded per API request.
+const BATCH_SIZE: usize = 64;
+
+/// Rate limiter key shared by every replica calling the embedding API.
+const RATE_LIMIT_KEY: &str = "embeddings";
+
+/// Embed every entity in the graph and write the vectors to Qdrant.
+///
+/// Progress is reported on the run entry as it goes: `documents_collected`
+/// counts entities read from Neo4j, `entities_extracted` counts vectors written.
+pub async fn run(
+    run_id: String,
+    graph: Arc<Neo4jGraphStore>,
+    embeddings: Arc<VoyageEmbeddingProvider>,
+    vectors: Arc<QdrantVectorStore>,
+    runs: Arc<dyn RunStore>,
+    rate_limiter: Arc<dyn RateLimiter>,
+    requests_per_minute: u32,
+) {
+    let mut after = None;
+    let mut scanned = 0u64;
+    let mut embedded = 0u64;
+    let mut collection_ready = false;
+
+    info!(run_id = %run_id, model = embeddings.model(), "Embedding backfill starting");
+
+    let outcome: std::result::Result<(), String> = async {
+        loop {
+            let entities = graph
+                .list_entities_after(after, BATCH_SIZE)
+                .await
+                .map_err(|e| format!("Failed to read entities: {e}"))?;
+            let Some(last) = entities.last() else {
+                return Ok(());
+            };
+            after = Some(last.id);
+            scanned += entities.len() as u64;
+
+            let texts: Vec<String> = entities.iter().map(embedding_text).collect();
+            rate_limiter
+                .acquire(RATE_LIMIT_KEY, requests_per_minute)
+                .await
+                .map_err(|e| format!("Rate limiter failed: {e}"))?;
+            let vectors_out = embeddings
+                .embed(&texts)
+                .await
+                .map_err(|e| format!("Embedding failed: {e}"))?;
+
+            if !collection_ready {
+                let dimensions = vectors_out.first().map(Vec::len).unwrap_or_default();
+                vectors
+                    .ensure_collection(dimensions)
+                    .await
+                    .map_err(|e| format!("Failed to prepare collection: {e}"))?;
+                collection_ready = true;
+            }
+
+            let points: Vec<VectorPoint> = entities
+                .iter()
+                .zip(vectors_out)
+                .map(|(entity, vector)| VectorPoint {
+                    id: entity.id,
+                    vector,
+                    payload: serde_json::json!({
+                        "name": entity.name,
+                        "entity_type": entity.entity_type,
+                        "source": entity.source,
+                    }),
+                })
+                .collect();
+            vectors
+                .upsert(&points)
+                .await
+                .map_err(|e| format!("Failed to write vectors: {e}"))?;
+            embedded += points.len() as u64;
+
+            update_run(&runs, &run_id, |run| {
+                run.documents_collected = scanned;
+                run.entities_extracted = embedded;
+            })
+            .await;
+
+            if entities.len() < BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+    .await;
+
+    update_run(&runs, &run_id, |run| {
+        run.finished_at = Some(Utc::now());
+        run.documents_collected = scanned;
+        run.entities_extracted = embedded;
+        match outcome {
+            Ok(()) => {
+                run.status = AgentRunState::Completed;
+                info!(run_id = %run_id, scanned, embedded, "Embedding backfill completed");
+            }
+            Err(e) => {
+                error!(run_id = %run_id, error = %e, "Embedding backfill failed");
+                run.status = AgentRunState::Failed;
+                run.error = Some(e);
+            }
+        }
+    })
+    .await;
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use argus_core::places::{self, Place};
+use argus_core::tracks::{self, Flight};
+use argus_core::{Entity, EntityType, ExtractionResult, GraphStore, RelationType, Relationship};
+
+use super::co_location::AssetResolver;
+use crate::state::AppState;
+
+/// Delay before the first pass so agents get a chance to report positions.
+const INITIAL_DELAY: Duration = Duration::from_secs(120);
+
+const LOCK_KEY: &str = "jobs:flight_sessions";
+
+/// How far back each pass reads. Long enough for any flight; one whose
+/// takeoff has left the window keeps the Event its last pass wrote.
+const LOOKBACK: chrono::Duration = chrono::Duration::hours(24);
+
+/// Most positions read per pass; a busier window is checked in part.
+const MAX_POSITIONS_PER_PASS: usize = 200_000;
+
+/// Entities and relationships this job writes are attributed to this source.
+pub const SOURCE: &str = "flight_sessions";
+
+/// Namespace for event ids, so repeat passes over one flight write one Event.
+const EVENT_NAMESPACE: Uuid = Uuid::from_u128(0x2f61_c4d8_0b97_4e3a_a5d2_7c19_e84b_36f0);
+
+/// Rebuild aircraft positions into flight Events, on one replica at a time.
+/// Every pass rereads the lookback window; Event ids and (source,
+/// source_id) keys are derived from the aircraft and takeoff time, so a
+/// flight seen again updates its Event (e.g. with the landing) instead of
+/// adding one.
+pub async fn run(state: AppState, interval: Duration) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !state
+            .locks
+            .try_acquire(LOCK_KEY, &state.instance_id, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Flight session pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        pass(&state).await;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn pass(state: &AppState) {
+    let until = Utc::now();
+    let positions = match state
+        .graph
+        .positions_between(until - LOOKBACK, until, MAX_POSITIONS_PER_PASS)
+        .await
+    {
+        Ok(positions) => positions,
+        Err(e) => {
+            warn!(error = %e, "Failed to read positions for flight sessions");
+            return;
+        }
+    };
+    if positions.len() == MAX_POSITIONS_PER_PASS {
+        warn!(
+            limit = MAX_POSITIONS_PER_PASS,
+            "Too many positions for one flight session pass, checking the oldest only"
+        );
+    }
+
+    let max_gap =
+        chrono::Duration::minutes(state.config().flight_session_max_gap_minutes.max(1) as i64);
+    let flights = tracks::detect_flights(&positions, places::AIRPORTS, max_gap);
+
+    let mut assets = AssetResolver::default();
+    let mut written = 0;
+    for flight in &flights {
+        let Some(aircraft) = assets.entity(state.graph.as_ref(), &flight.asset).await else {
+            continue;
+        };
+        write(state, aircraft, flight).await;
+        written += 1;
+    }
+
+    info!(
+        positions = positions.len(),
+        flights = flights.len(),
+        landed = flights.iter().filter(|f| f.landed_at.is_some()).count(),
+        events = written,
+        unresolved_assets = assets.unresolved(),
+        "Flight session pass complete"
+    );
+}
+
+/// Store one flight as `aircraft -TRAVELED_TO-> Event`, with the Event
+/// `LOCATED_AT` its origin and destination airports where they are known.
+/// Airport nodes come from the reference data job.
+async fn write(state: &AppState, aircraft: Entity, flight: &Flight<'_>) {
+    let event = event(&aircraft, flight);
+
+    let mut traveled = Relationship::new(
+        aircraft.id,
+        event.id,
+        RelationType::TraveledTo,
+        SOURCE.to_string(),
+    );
+    traveled.timestamp = Some(flight.departed_at);
+    traveled.properties = serde_json::json!({
+        "origin": flight.origin.map(|p| p.code),
+        "destination": flight.destination.map(|p| p.code),
+        "departed_at": flight.departed_at,
+        "landed_at": flight.landed_at,
+    });
+    let mut relationships = vec![traveled];
+    for (role, place, at) in [
+        ("origin", flight.origin, Some(flight.departed_at)),
+        ("destination", flight.destination, flight.landed_at),
+    ] {
+        let Some(place) = place else {
+            continue;
+        };
+        let mut located = Relationship::new(
+            event.id,
+            place.entity_id(),
+            RelationType::LocatedAt,
+            SOURCE.to_string(),
+        );
+        located.timestamp = at;
+        located.properties = serde_json::json!({ "role": role });
+        relationships.push(located);
+    }
+
+    let result = ExtractionResult {
+        raw_source: format!(
+            "{SOURCE}:{}",
+            event.source_id.as_deref().unwrap_or_default()
+        ),
+        entities: vec![aircraft, event],
+        relationships,
+        extracted_at: Utc::now(),
+    };
+    if let Some(report) = state.writes.push(result).await {
+        if report.failed > 0 {
+            warn!(failed = report.failed, "Failed to store flight events");
+        }
+    }
+}
+
+fn event(aircraft: &Entity, flight: &Flight<'_>) -> Entity {
+    let source_id = format!(
+        "flight:{}:{}",
+        flight.asset,
+        flight.departed_at.to_rfc3339()
+    );
+    let code = |place: Option<&Place>| place.map_or("unknown", |p| p.code);
+    let name = format!(
+        "{} flight {} to {}, {}",
+        aircraft.name,
+        code(flight.origin),
+        code(flight.destination),
+        flight.departed_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    // GeoJSON orders coordinates longitude first
+    let coordinates: Vec<[f64; 2]> = flight.track.iter().map(|&(lat, lon)| [lon, lat]).collect();
+
+    let mut event = Entity::new(EntityType::Event, name, SOURCE.to_string());
+    event.id = Uuid::new_v5(&EVENT_NAMESPACE, source_id.as_bytes());
+    event.source_id = Some(source_id);
+    event.observed_at = Some(flight.departed_at);
+    event.properties = serde_json::json!({
+        "event_type": "flight",
+        "asset": flight.asset,
+        "origin": flight.origin.map(|p| p.code),
+        "origin_country": flight.origin.map(|p| p.country),
+        "destination": flight.destination.map(|p| p.code),
+        "destination_country": flight.destination.map(|p| p.country),
+        "departed_at": flight.departed_at,
+        "landed_at": flight.landed_at,
+        "last_seen_at": flight.last_seen_at,
+        "reports": flight.reports,
+        "track": { "type": "LineString", "coordinates": coordinates },
+    });
+    event
+}
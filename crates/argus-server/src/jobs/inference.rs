@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, error, info};
+
+use argus_core::inference::InferenceRule;
+use argus_core::{GraphStore, LockManager};
+use argus_graph::Neo4jGraphStore;
+
+/// Delay before the first pass so agents get a chance to populate the graph.
+const INITIAL_DELAY: Duration = Duration::from_secs(120);
+
+const LOCK_KEY: &str = "jobs:inference";
+
+/// Rederive inferred relationships on a fixed interval, on one replica at a time.
+pub async fn run(
+    graph: Arc<Neo4jGraphStore>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    interval: Duration,
+) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Inference pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        let run_at = Utc::now();
+        for rule in InferenceRule::ALL {
+            match graph.apply_inference_rule(rule, run_at).await {
+                Ok(report) => info!(
+                    rule = rule.as_str(),
+                    inferred = report.inferred,
+                    retracted = report.retracted,
+                    "Inference rule applied"
+                ),
+                Err(e) => error!(rule = rule.as_str(), error = %e, "Inference rule failed"),
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
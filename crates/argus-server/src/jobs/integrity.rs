@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{debug, info, warn};
+
+use argus_core::api_types::IntegrityReport;
+use argus_core::integrity::{IntegrityCheck, IntegrityFinding};
+use argus_core::{GraphStore, LockManager};
+use argus_graph::Neo4jGraphStore;
+
+const LOCK_KEY: &str = "jobs:integrity";
+
+/// Delay before the first pass; a restore or import right after startup
+/// would otherwise be checked half done.
+const INITIAL_DELAY: Duration = Duration::from_secs(10 * 60);
+
+/// Check the graph invariants every `INTEGRITY_CHECK_INTERVAL_SECONDS`, on
+/// one replica at a time, repairing what is safe when `repair` is set.
+pub async fn run(
+    graph: Arc<Neo4jGraphStore>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    interval: Duration,
+    repair: bool,
+) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Integrity check owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        let report = check(graph.as_ref(), repair, Utc::now()).await;
+        for finding in &report.findings {
+            match &finding.error {
+                Some(e) => {
+                    warn!(check = finding.check.as_str(), error = %e, "Integrity check failed")
+                }
+                None if finding.violations > 0 => warn!(
+                    check = finding.check.as_str(),
+                    violations = finding.violations,
+                    repaired = finding.repaired,
+                    samples = ?finding.samples,
+                    "Graph integrity violations found"
+                ),
+                None => debug!(check = finding.check.as_str(), "Integrity check passed"),
+            }
+        }
+        info!(
+            repair,
+            checks = report.findings.len(),
+            "Integrity check complete"
+        );
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Run every check, applying safe fixes when `repair` is set. A failing
+/// check is reported with its error; the others still run.
+pub async fn check(graph: &dyn GraphStore, repair: bool, now: DateTime<Utc>) -> IntegrityReport {
+    let mut findings = Vec::with_capacity(IntegrityCheck::ALL.len());
+    for check in IntegrityCheck::ALL {
+        let finding = match graph.check_integrity(check, repair, now).await {
+            Ok(finding) => finding,
+            Err(e) => IntegrityFinding {
+                error: Some(e.to_string()),
+                ..IntegrityFinding::new(check)
+            },
+        };
+        findings.push(finding);
+    }
+    IntegrityReport {
+        repair,
+        checked_at: now,
+        findings,
+    }
+}
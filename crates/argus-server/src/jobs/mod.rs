@@ -0,0 +1,220 @@
+//! Periodic analytics jobs that run alongside the agent scheduler.
+
+mod alert_digest;
+pub mod backup;
+pub(crate) mod co_location;
+mod completeness;
+pub mod deny_list;
+pub mod duplicate_scan;
+pub mod embedding_backfill;
+mod flight_sessions;
+mod inference;
+pub mod integrity;
+pub mod observed_at_backfill;
+mod port_calls;
+pub mod prompt_bundles;
+mod quota_spillover;
+mod refdata;
+mod relationship_strength;
+pub mod retention;
+mod sanctions_exposure;
+mod voyages;
+
+use std::time::Duration;
+
+use tracing::info;
+
+use argus_core::AppConfig;
+
+use crate::state::AppState;
+
+/// Spawn every analytics job as its own background task.
+pub fn spawn_jobs(state: &AppState) {
+    let config = state.config();
+    let interval = Duration::from_secs(config.sanctions_exposure_interval_seconds.max(60));
+    let graph = state.graph.clone();
+    let locks = state.locks.clone();
+    let owner = state.instance_id.clone();
+    tokio::spawn(async move {
+        sanctions_exposure::run(graph, locks, owner, interval).await;
+    });
+
+    info!(
+        job = "sanctions_exposure",
+        interval_secs = interval.as_secs(),
+        "Scheduled analytics job"
+    );
+
+    let interval = Duration::from_secs(config.relationship_strength_interval_seconds.max(60));
+    let graph = state.graph.clone();
+    let locks = state.locks.clone();
+    let owner = state.instance_id.clone();
+    tokio::spawn(async move {
+        relationship_strength::run(graph, locks, owner, interval).await;
+    });
+    info!(
+        job = "relationship_strength",
+        interval_secs = interval.as_secs(),
+        "Scheduled analytics job"
+    );
+
+    let interval = Duration::from_secs(config.completeness_interval_seconds.max(60));
+    let graph = state.graph.clone();
+    let locks = state.locks.clone();
+    let owner = state.instance_id.clone();
+    tokio::spawn(async move {
+        completeness::run(graph, locks, owner, interval).await;
+    });
+    info!(
+        job = "completeness",
+        interval_secs = interval.as_secs(),
+        "Scheduled analytics job"
+    );
+
+    if config.inference_interval_seconds > 0 {
+        let interval = Duration::from_secs(config.inference_interval_seconds.max(60));
+        let graph = state.graph.clone();
+        let locks = state.locks.clone();
+        let owner = state.instance_id.clone();
+        tokio::spawn(async move {
+            inference::run(graph, locks, owner, interval).await;
+        });
+        info!(
+            job = "inference",
+            interval_secs = interval.as_secs(),
+            "Scheduled analytics job"
+        );
+    }
+
+    let interval = Duration::from_secs(config.alert_digest_interval_seconds.max(60));
+    let notifier = state.notifier.clone();
+    let subscriptions = state.subscription_engine.clone();
+    let locks = state.locks.clone();
+    let owner = state.instance_id.clone();
+    tokio::spawn(async move {
+        alert_digest::run(notifier, subscriptions, locks, owner, interval).await;
+    });
+
+    let graph = state.graph.clone();
+    let locks = state.locks.clone();
+    let owner = state.instance_id.clone();
+    let job_config = AppConfig::clone(&config);
+    tokio::spawn(async move {
+        refdata::run(graph, locks, owner, job_config).await;
+    });
+    info!(
+        job = "refdata",
+        refresh_hours = config.refdata_refresh_hours,
+        "Scheduled analytics job"
+    );
+
+    if config.colocation_interval_seconds > 0 {
+        let interval = Duration::from_secs(config.colocation_interval_seconds.max(60));
+        let colocation_state = state.clone();
+        tokio::spawn(async move {
+            co_location::run(colocation_state, interval).await;
+        });
+        info!(
+            job = "co_location",
+            interval_secs = interval.as_secs(),
+            "Scheduled analytics job"
+        );
+    }
+
+    if config.port_call_interval_seconds > 0 {
+        let interval = Duration::from_secs(config.port_call_interval_seconds.max(60));
+        let port_call_state = state.clone();
+        tokio::spawn(async move {
+            port_calls::run(port_call_state, interval).await;
+        });
+        info!(
+            job = "port_calls",
+            interval_secs = interval.as_secs(),
+            "Scheduled analytics job"
+        );
+    }
+
+    if config.flight_session_interval_seconds > 0 {
+        let interval = Duration::from_secs(config.flight_session_interval_seconds.max(60));
+        let flight_state = state.clone();
+        tokio::spawn(async move {
+            flight_sessions::run(flight_state, interval).await;
+        });
+        info!(
+            job = "flight_sessions",
+            interval_secs = interval.as_secs(),
+            "Scheduled analytics job"
+        );
+    }
+
+    if config.voyage_interval_seconds > 0 {
+        let interval = Duration::from_secs(config.voyage_interval_seconds.max(60));
+        let voyage_state = state.clone();
+        tokio::spawn(async move {
+            voyages::run(voyage_state, interval).await;
+        });
+        info!(
+            job = "voyages",
+            interval_secs = interval.as_secs(),
+            "Scheduled analytics job"
+        );
+    }
+
+    let retention_policies = config.effective_retention_policies();
+    if config.retention_interval_seconds > 0 && !retention_policies.is_empty() {
+        let graph = state.graph.clone();
+        let locks = state.locks.clone();
+        let owner = state.instance_id.clone();
+        let job_config = AppConfig::clone(&config);
+        tokio::spawn(async move {
+            retention::run(graph, locks, owner, job_config).await;
+        });
+        info!(
+            job = "retention",
+            interval_secs = config.retention_interval_seconds.max(60),
+            policies = retention_policies.len(),
+            dry_run = config.retention_dry_run,
+            "Scheduled analytics job"
+        );
+    }
+
+    if config.integrity_check_interval_seconds > 0 {
+        let interval = Duration::from_secs(config.integrity_check_interval_seconds.max(60));
+        let repair = config.integrity_auto_repair;
+        let graph = state.graph.clone();
+        let locks = state.locks.clone();
+        let owner = state.instance_id.clone();
+        tokio::spawn(async move {
+            integrity::run(graph, locks, owner, interval, repair).await;
+        });
+        info!(
+            job = "integrity",
+            interval_secs = interval.as_secs(),
+            repair,
+            "Scheduled analytics job"
+        );
+    }
+
+    spawn_extraction_refresh(state);
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        quota_spillover::run(state).await;
+    });
+}
+
+/// Keep this process's deny-list and prompt bundles in step with the shared
+/// store. Extraction workers run these without the other jobs.
+pub fn spawn_extraction_refresh(state: &AppState) {
+    let store = state.deny_list.clone();
+    let deny_list = state.validator.deny_list().clone();
+    tokio::spawn(async move {
+        deny_list::run(store, deny_list).await;
+    });
+
+    let store = state.prompts.clone();
+    let prompts = state.extraction.llm().prompts().clone();
+    tokio::spawn(async move {
+        prompt_bundles::run(store, prompts).await;
+    });
+}
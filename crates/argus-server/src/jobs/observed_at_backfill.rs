@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::{error, info};
+
+use argus_core::api_types::AgentRunState;
+use argus_core::entity::observed_at_from_properties;
+use argus_core::{GraphStore, RunStore};
+use argus_graph::Neo4jGraphStore;
+
+use crate::shared::update_run;
+
+/// Name under which backfill runs appear in the run history.
+pub const JOB_NAME: &str = "observed_at_backfill";
+
+const BATCH_SIZE: usize = 500;
+
+/// Give entities stored before `observed_at` existed one from the dates in
+/// their properties (incorporation, registration, listing ...).
+///
+/// `documents_collected` counts entities scanned, `entities_extracted`
+/// those that got a date.
+pub async fn run(run_id: String, graph: Arc<Neo4jGraphStore>, runs: Arc<dyn RunStore>) {
+    let mut after = None;
+    let mut scanned = 0u64;
+    let mut dated = 0u64;
+
+    info!(run_id = %run_id, "observed_at backfill starting");
+
+    let outcome: std::result::Result<(), String> = async {
+        loop {
+            let entities = graph
+                .list_entities_after(after, BATCH_SIZE)
+                .await
+                .map_err(|e| format!("Failed to read entities: {e}"))?;
+            let Some(last) = entities.last() else {
+                return Ok(());
+            };
+            after = Some(last.id);
+            scanned += entities.len() as u64;
+
+            let observed: Vec<_> = entities
+                .iter()
+                .filter(|entity| entity.observed_at.is_none())
+                .filter_map(|entity| {
+                    Some((entity.id, observed_at_from_properties(&entity.properties)?))
+                })
+                .collect();
+            graph
+                .set_observed_at(&observed)
+                .await
+                .map_err(|e| format!("Failed to write dates: {e}"))?;
+            dated += observed.len() as u64;
+
+            update_run(&runs, &run_id, |run| {
+                run.documents_collected = scanned;
+                run.entities_extracted = dated;
+            })
+            .await;
+
+            if entities.len() < BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+    .await;
+
+    update_run(&runs, &run_id, |run| {
+        run.finished_at = Some(Utc::now());
+        run.documents_collected = scanned;
+        run.entities_extracted = dated;
+        match outcome {
+            Ok(()) => {
+                run.status = AgentRunState::Completed;
+                info!(run_id = %run_id, scanned, dated, "observed_at backfill completed");
+            }
+            Err(e) => {
+                error!(run_id = %run_id, error = %e, "observed_at backfill failed");
+                run.status = AgentRunState::Failed;
+                run.error = Some(e);
+            }
+        }
+    })
+    .await;
+}
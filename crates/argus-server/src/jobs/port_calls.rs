@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use argus_core::places::{self, PlaceKind};
+use argus_core::tracks::{self, Arrival};
+use argus_core::{Entity, EntityType, ExtractionResult, GraphStore, RelationType, Relationship};
+
+use super::co_location::AssetResolver;
+use crate::state::AppState;
+
+/// Delay before the first pass so agents get a chance to report positions.
+const INITIAL_DELAY: Duration = Duration::from_secs(90);
+
+const LOCK_KEY: &str = "jobs:port_calls";
+
+/// How far back each pass reads. A stay is only recognised while its
+/// arrival is inside this window, so calls longer than it keep an unset
+/// `departed_at`.
+const LOOKBACK: chrono::Duration = chrono::Duration::hours(48);
+
+/// Most positions read per pass; a busier window is checked in part.
+const MAX_POSITIONS_PER_PASS: usize = 200_000;
+
+/// Entities and relationships this job writes are attributed to this source.
+pub const SOURCE: &str = "port_calls";
+
+/// Namespace for event ids, so repeat passes over one stay write one Event.
+const EVENT_NAMESPACE: Uuid = Uuid::from_u128(0x5b0e_93c1_7d2a_4f86_b41e_c8a3_90d7_6e15);
+
+/// Turn recent positions into port call and landing Events, on one replica
+/// at a time. Every pass rereads the lookback window; the Event ids and
+/// (source, source_id) keys are derived from the arrival, so a stay seen
+/// again updates its Event (e.g. with the departure) instead of adding one.
+pub async fn run(state: AppState, interval: Duration) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !state
+            .locks
+            .try_acquire(LOCK_KEY, &state.instance_id, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Port call pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        pass(&state).await;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn pass(state: &AppState) {
+    let until = Utc::now();
+    let positions = match state
+        .graph
+        .positions_between(until - LOOKBACK, until, MAX_POSITIONS_PER_PASS)
+        .await
+    {
+        Ok(positions) => positions,
+        Err(e) => {
+            warn!(error = %e, "Failed to read positions for port calls");
+            return;
+        }
+    };
+    if positions.len() == MAX_POSITIONS_PER_PASS {
+        warn!(
+            limit = MAX_POSITIONS_PER_PASS,
+            "Too many positions for one port call pass, checking the oldest only"
+        );
+    }
+
+    let min_stop = chrono::Duration::minutes(state.config().port_call_min_stop_minutes as i64);
+    let port_calls = tracks::detect_port_calls(&positions, places::PORTS, min_stop);
+    let landings = tracks::detect_landings(&positions, places::AIRPORTS);
+
+    let mut assets = AssetResolver::default();
+    let mut written = 0;
+    for arrival in port_calls.iter().chain(&landings) {
+        let Some(asset) = assets.entity(state.graph.as_ref(), &arrival.asset).await else {
+            continue;
+        };
+        write(state, asset, arrival).await;
+        written += 1;
+    }
+
+    info!(
+        positions = positions.len(),
+        port_calls = port_calls.len(),
+        landings = landings.len(),
+        events = written,
+        unresolved_assets = assets.unresolved(),
+        "Port call pass complete"
+    );
+}
+
+/// Store one stay as `asset -TRAVELED_TO-> Event -LOCATED_AT-> place`. The
+/// place's node comes from the reference data job, which loads the bundled
+/// places on startup.
+async fn write(state: &AppState, asset: Entity, arrival: &Arrival<'_>) {
+    let event = event(&asset, arrival);
+
+    let mut traveled = Relationship::new(
+        asset.id,
+        event.id,
+        RelationType::TraveledTo,
+        SOURCE.to_string(),
+    );
+    traveled.timestamp = Some(arrival.arrived_at);
+    traveled.properties = serde_json::json!({
+        "place_code": arrival.place.code,
+        "arrived_at": arrival.arrived_at,
+        "departed_at": arrival.departed_at,
+    });
+    let mut located = Relationship::new(
+        event.id,
+        arrival.place.entity_id(),
+        RelationType::LocatedAt,
+        SOURCE.to_string(),
+    );
+    located.timestamp = Some(arrival.arrived_at);
+
+    let result = ExtractionResult {
+        raw_source: format!(
+            "{SOURCE}:{}",
+            event.source_id.as_deref().unwrap_or_default()
+        ),
+        entities: vec![asset, event],
+        relationships: vec![traveled, located],
+        extracted_at: Utc::now(),
+    };
+    if let Some(report) = state.writes.push(result).await {
+        if report.failed > 0 {
+            warn!(failed = report.failed, "Failed to store port call events");
+        }
+    }
+}
+
+fn event(asset: &Entity, arrival: &Arrival<'_>) -> Entity {
+    let (event_type, label) = match arrival.place.kind {
+        PlaceKind::Port => ("port_call", "port call"),
+        PlaceKind::Airport => ("landing", "landing"),
+    };
+    let source_id = format!(
+        "{event_type}:{}:{}:{}",
+        arrival.asset,
+        arrival.place.code,
+        arrival.arrived_at.to_rfc3339()
+    );
+    let name = format!(
+        "{} {label} at {}, {}",
+        asset.name,
+        arrival.place.name,
+        arrival.arrived_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    let mut event = Entity::new(EntityType::Event, name, SOURCE.to_string());
+    event.id = Uuid::new_v5(&EVENT_NAMESPACE, source_id.as_bytes());
+    event.source_id = Some(source_id);
+    event.observed_at = Some(arrival.arrived_at);
+    event.properties = serde_json::json!({
+        "event_type": event_type,
+        "asset": arrival.asset,
+        "place_code": arrival.place.code,
+        "country": arrival.place.country,
+        "arrived_at": arrival.arrived_at,
+        "departed_at": arrival.departed_at,
+        "reports": arrival.reports,
+    });
+    event
+}
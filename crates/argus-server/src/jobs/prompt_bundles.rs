@@ -0,0 +1,49 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+use argus_core::prompts::BASELINE_PROMPT_VERSION;
+use argus_core::PromptBundleStore;
+use argus_extraction::ActivePrompts;
+
+/// How often a deployment changed on another replica is picked up.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Load the stored prompt deployment into the extraction pipeline right
+/// away, then reload it every minute. Every replica extracts, so every
+/// replica refreshes.
+pub async fn run(store: Arc<dyn PromptBundleStore>, prompts: Arc<ActivePrompts>) {
+    loop {
+        reload(store.as_ref(), &prompts).await;
+        debug!(deployment = ?prompts.deployment(), "Refreshed prompt deployment");
+        tokio::time::sleep(REFRESH_INTERVAL).await;
+    }
+}
+
+/// Apply the stored deployment and the bundles it names now. On failure
+/// the current deployment stays.
+pub async fn reload(store: &dyn PromptBundleStore, prompts: &ActivePrompts) {
+    let deployment = match store.deployment().await {
+        Ok(deployment) => deployment,
+        Err(e) => {
+            warn!(error = %e, "Failed to load prompt deployment");
+            return;
+        }
+    };
+    let mut bundles = Vec::new();
+    for version in std::iter::once(deployment.control).chain(deployment.candidate) {
+        if version == BASELINE_PROMPT_VERSION {
+            continue;
+        }
+        match store.get(version).await {
+            Ok(Some(bundle)) => bundles.push(bundle),
+            Ok(None) => warn!(version, "Deployed prompt bundle does not exist"),
+            Err(e) => {
+                warn!(error = %e, version, "Failed to load prompt bundle");
+                return;
+            }
+        }
+    }
+    prompts.replace(deployment, bundles);
+}
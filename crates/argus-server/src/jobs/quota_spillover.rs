@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use argus_core::api_types::AgentRunStatus;
+use argus_core::quota::{quota_day, OffPeakWindow, QuotaCounter};
+
+use crate::shared::{insert_run, update_run};
+use crate::state::AppState;
+
+const LOCK_KEY: &str = "jobs:quota_spillover";
+
+/// How often the job checks for off-peak hours and queued documents.
+const TICK: Duration = Duration::from_secs(10 * 60);
+
+/// Documents taken off one source's queue per tick.
+const BATCH_SIZE: usize = 200;
+
+/// Extract documents held back by extraction quotas during the configured
+/// off-peak hours, on one replica at a time. Each batch is recorded as a
+/// run of its source.
+pub async fn run(state: AppState) {
+    loop {
+        tokio::time::sleep(TICK).await;
+
+        // Read every tick, so a reloaded OFF_PEAK_HOURS applies
+        let spec = state.config().off_peak_hours.clone();
+        let Some(window) = OffPeakWindow::parse(&spec) else {
+            warn!(spec = %spec, "Invalid OFF_PEAK_HOURS, spillover queue will not drain");
+            continue;
+        };
+        if !window.contains(Utc::now()) {
+            continue;
+        }
+        if !state
+            .locks
+            .try_acquire(LOCK_KEY, &state.instance_id, TICK)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Quota spillover owned by another replica, skipping");
+            continue;
+        }
+        let sizes = match state.spillover.sizes().await {
+            Ok(sizes) => sizes,
+            Err(e) => {
+                warn!(error = %e, "Failed to read spillover queue");
+                continue;
+            }
+        };
+        for source in sizes.into_keys() {
+            drain(&state, &source).await;
+        }
+    }
+}
+
+async fn drain(state: &AppState, source: &str) {
+    let documents = match state.spillover.pop(source, BATCH_SIZE).await {
+        Ok(documents) if documents.is_empty() => return,
+        Ok(documents) => documents,
+        Err(e) => {
+            warn!(source = %source, error = %e, "Failed to pop spilled-over documents");
+            return;
+        }
+    };
+
+    let run_id = Uuid::new_v4().to_string();
+    insert_run(
+        &state.runs,
+        AgentRunStatus::started(run_id.clone(), source.to_string()),
+    )
+    .await;
+    let outcome = crate::pipeline::ingest_documents(state, source, &run_id, &documents).await;
+
+    let counter = QuotaCounter::OffPeak.key(source, quota_day(Utc::now()));
+    if let Err(e) = state.quotas.add(&counter, outcome.documents).await {
+        warn!(source = %source, error = %e, "Failed to count off-peak documents");
+    }
+    let (status, error) = (outcome.run_state(), outcome.store_error());
+    update_run(&state.runs, &run_id, |run| {
+        run.finished_at = Some(Utc::now());
+        run.status = status;
+        run.error = error;
+        run.documents_collected = outcome.documents;
+        run.entities_extracted = outcome.entities;
+        run.documents_failed = outcome.failures.len() as u64;
+        run.validation = outcome.validation;
+    })
+    .await;
+    info!(source = %source, run_id = %run_id, documents = outcome.documents, entities = outcome.entities, "Processed spilled-over documents");
+}
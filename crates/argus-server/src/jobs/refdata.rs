@@ -0,0 +1,60 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, error};
+
+use argus_core::{AppConfig, LockManager};
+use argus_graph::Neo4jGraphStore;
+use argus_refdata::{RefDataLoader, RefDataSources};
+
+const LOCK_KEY: &str = "jobs:refdata";
+
+/// Delay before retrying a refresh that could not write to the graph.
+const RETRY_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// Load the bundled places right away, then the downloaded datasets every
+/// `REFDATA_REFRESH_HOURS`, on one replica at a time. With a refresh
+/// interval of 0 only the bundled places are loaded, once.
+pub async fn run(
+    graph: Arc<Neo4jGraphStore>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    config: AppConfig,
+) {
+    let interval = Duration::from_secs(config.refdata_refresh_hours * 3600);
+    let sources = if interval.is_zero() {
+        RefDataSources::default()
+    } else {
+        RefDataSources {
+            ports_url: config.refdata_ports_url.clone(),
+            airports_url: config.refdata_airports_url.clone(),
+            countries_url: config.refdata_countries_url.clone(),
+        }
+    };
+    let mut loader = RefDataLoader::new(sources);
+    let lock_ttl = interval.max(RETRY_DELAY);
+
+    loop {
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, lock_ttl)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Reference data refresh owned by another replica, skipping");
+            if interval.is_zero() {
+                return;
+            }
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        match loader.refresh(graph.as_ref()).await {
+            Ok(_) if interval.is_zero() => return,
+            Ok(_) => tokio::time::sleep(interval).await,
+            Err(e) => {
+                error!(error = %e, "Reference data refresh failed");
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, error, info};
+
+use argus_core::{GraphStore, LockManager};
+use argus_graph::Neo4jGraphStore;
+
+/// Delay before the first pass so agents get a chance to populate the graph.
+const INITIAL_DELAY: Duration = Duration::from_secs(90);
+
+const LOCK_KEY: &str = "jobs:relationship_strength";
+
+/// Rescore relationship strength on a fixed interval, on one replica at a time.
+pub async fn run(
+    graph: Arc<Neo4jGraphStore>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    interval: Duration,
+) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Relationship strength pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        match graph.update_relationship_strength(Utc::now()).await {
+            Ok(pairs) => info!(pairs, "Relationship strength pass complete"),
+            Err(e) => error!(error = %e, "Relationship strength pass failed"),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
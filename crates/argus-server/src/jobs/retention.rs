@@ -0,0 +1,208 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
+
+use argus_core::api_types::{RetentionPolicyReport, RetentionReport};
+use argus_core::retention::{RetentionAction, RetentionPolicy, RETENTION_BATCH_SIZE};
+use argus_core::{AppConfig, GraphStore, LockManager};
+use argus_graph::Neo4jGraphStore;
+
+const LOCK_KEY: &str = "jobs:retention";
+
+/// Delay before the first pass, so a restart doesn't go straight to deleting.
+const INITIAL_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Apply the retention policies every `RETENTION_INTERVAL_SECONDS`, on one
+/// replica at a time. With `RETENTION_DRY_RUN` passes only count.
+pub async fn run(
+    graph: Arc<Neo4jGraphStore>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    config: AppConfig,
+) {
+    let interval = Duration::from_secs(config.retention_interval_seconds.max(60));
+    let archive_dir = PathBuf::from(&config.retention_archive_dir);
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Retention pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        let report = enforce(
+            graph.as_ref(),
+            &config.effective_retention_policies(),
+            &archive_dir,
+            config.retention_dry_run,
+            Utc::now(),
+        )
+        .await;
+        for policy in &report.policies {
+            match &policy.error {
+                Some(e) => {
+                    warn!(policy = %policy.policy, error = %e, deleted = policy.deleted, "Retention policy failed")
+                }
+                None => info!(
+                    policy = %policy.policy,
+                    dry_run = report.dry_run,
+                    expired = policy.expired,
+                    archived = policy.archived,
+                    deleted = policy.deleted,
+                    "Retention policy applied"
+                ),
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Count each policy's expired nodes and, unless `dry_run`, remove them,
+/// archiving first where the policy says so. A failing policy stops on its
+/// own; the others still run.
+pub async fn enforce(
+    graph: &dyn GraphStore,
+    policies: &[RetentionPolicy],
+    archive_dir: &Path,
+    dry_run: bool,
+    now: DateTime<Utc>,
+) -> RetentionReport {
+    let mut reports = Vec::with_capacity(policies.len());
+    for policy in policies {
+        let mut report = RetentionPolicyReport {
+            policy: policy.clone(),
+            cutoff: policy.cutoff(now),
+            expired: 0,
+            archived: 0,
+            deleted: 0,
+            archive_file: None,
+            error: None,
+        };
+        let outcome = match graph.count_expired(policy, report.cutoff).await {
+            Ok(expired) => {
+                report.expired = expired;
+                if dry_run || expired == 0 {
+                    Ok(())
+                } else {
+                    remove_expired(graph, policy, archive_dir, now, &mut report).await
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        report.error = outcome.err();
+        reports.push(report);
+    }
+    RetentionReport {
+        dry_run,
+        ran_at: now,
+        policies: reports,
+    }
+}
+
+async fn remove_expired(
+    graph: &dyn GraphStore,
+    policy: &RetentionPolicy,
+    archive_dir: &Path,
+    now: DateTime<Utc>,
+    report: &mut RetentionPolicyReport,
+) -> Result<(), String> {
+    let archive_file =
+        (policy.action == RetentionAction::Archive).then(|| archive_path(archive_dir, policy, now));
+    if let Some(path) = &archive_file {
+        report.archive_file = Some(path.display().to_string());
+    }
+
+    loop {
+        let nodes = graph
+            .expired_nodes(policy, report.cutoff, RETENTION_BATCH_SIZE)
+            .await
+            .map_err(|e| format!("Failed to read expired nodes: {e}"))?;
+        let ids: Vec<String> = nodes
+            .iter()
+            .filter_map(|node| node["id"].as_str().map(String::from))
+            .collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(path) = &archive_file {
+            append_archive(path, &nodes)
+                .await
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            report.archived += nodes.len() as u64;
+        }
+        let deleted = graph
+            .delete_nodes(&ids)
+            .await
+            .map_err(|e| format!("Failed to delete expired nodes: {e}"))?;
+        report.deleted += deleted;
+
+        // Nodes without an id can't be deleted and would come back forever
+        if nodes.len() < RETENTION_BATCH_SIZE || deleted == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// One archive file per policy and day, e.g. `event-gdelt-20260114.jsonl`.
+fn archive_path(dir: &Path, policy: &RetentionPolicy, now: DateTime<Utc>) -> PathBuf {
+    let mut name = policy.label.to_lowercase();
+    if let Some(source) = &policy.source {
+        let source: String = source
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        name = format!("{name}-{source}");
+    }
+    dir.join(format!("{name}-{}.jsonl", now.format("%Y%m%d")))
+}
+
+async fn append_archive(path: &Path, nodes: &[serde_json::Value]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let mut lines = Vec::new();
+    for node in nodes {
+        serde_json::to_writer(&mut lines, node)?;
+        lines.push(b'\n');
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(&lines).await?;
+    file.sync_data().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archives_are_named_per_policy_and_day() {
+        let now = DateTime::parse_from_rfc3339("2026-01-14T03:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let policy: RetentionPolicy = "Event@gdelt=365d:archive".parse().unwrap();
+        assert_eq!(
+            archive_path(Path::new("data/archive"), &policy, now),
+            Path::new("data/archive/event-gdelt-20260114.jsonl")
+        );
+        let policy: RetentionPolicy = "Position@../x=90d:archive".parse().unwrap();
+        assert_eq!(
+            archive_path(Path::new("/a"), &policy, now),
+            Path::new("/a/position-___x-20260114.jsonl")
+        );
+    }
+}
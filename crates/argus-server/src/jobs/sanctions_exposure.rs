@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, error, info};
+
+use argus_core::{GraphStore, LockManager};
+use argus_graph::Neo4jGraphStore;
+
+/// Delay before the first pass so agents get a chance to populate the graph.
+const INITIAL_DELAY: Duration = Duration::from_secs(60);
+
+const LOCK_KEY: &str = "jobs:sanctions_exposure";
+
+/// Recompute sanctions exposure scores on a fixed interval, on one replica at a time.
+pub async fn run(
+    graph: Arc<Neo4jGraphStore>,
+    locks: Arc<dyn LockManager>,
+    owner: String,
+    interval: Duration,
+) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !locks
+            .try_acquire(LOCK_KEY, &owner, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Sanctions exposure pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        match graph.update_sanctions_exposure().await {
+            Ok(scored) => info!(entities = scored, "Sanctions exposure pass complete"),
+            Err(e) => error!(error = %e, "Sanctions exposure pass failed"),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
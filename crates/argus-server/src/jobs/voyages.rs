@@ -0,0 +1,209 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use argus_core::places;
+use argus_core::tracks::{self, Voyage};
+use argus_core::{Entity, EntityType, ExtractionResult, GraphStore, RelationType, Relationship};
+
+use super::co_location::AssetResolver;
+use crate::state::AppState;
+
+/// Delay before the first pass so agents get a chance to report positions.
+const INITIAL_DELAY: Duration = Duration::from_secs(150);
+
+const LOCK_KEY: &str = "jobs:voyages";
+
+/// How far back each pass reads. A voyage is only rebuilt while the port
+/// call it left from is inside this window; longer passages keep the Event
+/// an earlier pass wrote.
+const LOOKBACK: chrono::Duration = chrono::Duration::days(14);
+
+/// Most positions read per pass; a busier window is checked in part.
+const MAX_POSITIONS_PER_PASS: usize = 500_000;
+
+/// Entities and relationships this job writes are attributed to this source.
+pub const SOURCE: &str = "voyages";
+
+/// Namespace for event ids, so repeat passes over one voyage write one Event.
+const EVENT_NAMESPACE: Uuid = Uuid::from_u128(0x74c2_1e9b_53fa_4d07_9b68_0ad5_e2f3_41c8);
+
+/// Rebuild vessel positions into voyage Events, on one replica at a time.
+/// Event ids are derived from the vessel and its departure, so a voyage
+/// seen again updates its Event (e.g. with the arrival) instead of adding
+/// one.
+pub async fn run(state: AppState, interval: Duration) {
+    tokio::time::sleep(INITIAL_DELAY).await;
+
+    loop {
+        if !state
+            .locks
+            .try_acquire(LOCK_KEY, &state.instance_id, interval)
+            .await
+            .unwrap_or(false)
+        {
+            debug!("Voyage pass owned by another replica, skipping");
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+
+        pass(&state).await;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn pass(state: &AppState) {
+    let until = Utc::now();
+    let positions = match state
+        .graph
+        .positions_between(until - LOOKBACK, until, MAX_POSITIONS_PER_PASS)
+        .await
+    {
+        Ok(positions) => positions,
+        Err(e) => {
+            warn!(error = %e, "Failed to read positions for voyages");
+            return;
+        }
+    };
+    if positions.len() == MAX_POSITIONS_PER_PASS {
+        warn!(
+            limit = MAX_POSITIONS_PER_PASS,
+            "Too many positions for one voyage pass, checking the oldest only"
+        );
+    }
+
+    let config = state.config();
+    let min_stop = chrono::Duration::minutes(config.port_call_min_stop_minutes as i64);
+    let max_gap = chrono::Duration::minutes(config.voyage_gap_minutes.max(1) as i64);
+    let voyages = tracks::detect_voyages(&positions, places::PORTS, min_stop, max_gap);
+
+    let mut assets = AssetResolver::default();
+    let (mut written, mut mismatches) = (0, 0);
+    for voyage in &voyages {
+        let Some(vessel) = assets.entity(state.graph.as_ref(), &voyage.asset).await else {
+            continue;
+        };
+        if voyage.destination_mismatch() {
+            mismatches += 1;
+            info!(
+                vessel = %vessel.name,
+                declared = voyage.declared_destination.as_deref().unwrap_or_default(),
+                arrived = voyage.destination.map(|p| p.code).unwrap_or_default(),
+                "Vessel arrived somewhere other than its declared destination"
+            );
+        }
+        write(state, vessel, voyage).await;
+        written += 1;
+    }
+
+    info!(
+        positions = positions.len(),
+        voyages = voyages.len(),
+        arrived = voyages.iter().filter(|v| v.arrived_at.is_some()).count(),
+        with_gaps = voyages.iter().filter(|v| !v.gaps.is_empty()).count(),
+        destination_mismatches = mismatches,
+        events = written,
+        unresolved_assets = assets.unresolved(),
+        "Voyage pass complete"
+    );
+}
+
+/// Store one voyage as `vessel -TRAVELED_TO-> Event`, with the Event
+/// `LOCATED_AT` its departure and arrival ports.
+async fn write(state: &AppState, vessel: Entity, voyage: &Voyage<'_>) {
+    let event = event(&vessel, voyage);
+
+    let mut traveled = Relationship::new(
+        vessel.id,
+        event.id,
+        RelationType::TraveledTo,
+        SOURCE.to_string(),
+    );
+    traveled.timestamp = Some(voyage.departed_at);
+    traveled.properties = serde_json::json!({
+        "origin": voyage.origin.code,
+        "destination": voyage.destination.map(|p| p.code),
+        "departed_at": voyage.departed_at,
+        "arrived_at": voyage.arrived_at,
+    });
+    let mut relationships = vec![traveled];
+    for (role, place, at) in [
+        ("origin", Some(voyage.origin), Some(voyage.departed_at)),
+        ("destination", voyage.destination, voyage.arrived_at),
+    ] {
+        let Some(place) = place else {
+            continue;
+        };
+        let mut located = Relationship::new(
+            event.id,
+            place.entity_id(),
+            RelationType::LocatedAt,
+            SOURCE.to_string(),
+        );
+        located.timestamp = at;
+        located.properties = serde_json::json!({ "role": role });
+        relationships.push(located);
+    }
+
+    let result = ExtractionResult {
+        raw_source: format!(
+            "{SOURCE}:{}",
+            event.source_id.as_deref().unwrap_or_default()
+        ),
+        entities: vec![vessel, event],
+        relationships,
+        extracted_at: Utc::now(),
+    };
+    if let Some(report) = state.writes.push(result).await {
+        if report.failed > 0 {
+            warn!(failed = report.failed, "Failed to store voyage events");
+        }
+    }
+}
+
+fn event(vessel: &Entity, voyage: &Voyage<'_>) -> Entity {
+    let source_id = format!(
+        "voyage:{}:{}",
+        voyage.asset,
+        voyage.departed_at.to_rfc3339()
+    );
+    let name = format!(
+        "{} voyage {} to {}, {}",
+        vessel.name,
+        voyage.origin.code,
+        voyage.destination.map_or("sea", |p| p.code),
+        voyage.departed_at.format("%Y-%m-%d")
+    );
+    let gaps: Vec<serde_json::Value> = voyage
+        .gaps
+        .iter()
+        .map(|(from, to)| serde_json::json!({ "from": from, "to": to, "minutes": (*to - *from).num_minutes() }))
+        .collect();
+    // GeoJSON orders coordinates longitude first
+    let coordinates: Vec<[f64; 2]> = voyage.track.iter().map(|&(lat, lon)| [lon, lat]).collect();
+
+    let mut event = Entity::new(EntityType::Event, name, SOURCE.to_string());
+    event.id = Uuid::new_v5(&EVENT_NAMESPACE, source_id.as_bytes());
+    event.source_id = Some(source_id);
+    event.observed_at = Some(voyage.departed_at);
+    event.properties = serde_json::json!({
+        "event_type": "voyage",
+        "asset": voyage.asset,
+        "origin": voyage.origin.code,
+        "origin_country": voyage.origin.country,
+        "destination": voyage.destination.map(|p| p.code),
+        "destination_country": voyage.destination.map(|p| p.country),
+        "departed_at": voyage.departed_at,
+        "arrived_at": voyage.arrived_at,
+        "last_seen_at": voyage.last_seen_at,
+        "duration_hours": (voyage.duration().num_minutes() as f64 / 60.0 * 10.0).round() / 10.0,
+        "declared_destination": voyage.declared_destination,
+        "destination_mismatch": voyage.destination_mismatch(),
+        "ais_gaps": gaps,
+        "reports": voyage.reports,
+        "track": { "type": "LineString", "coordinates": coordinates },
+    });
+    event
+}
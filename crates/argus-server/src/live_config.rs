@@ -0,0 +1,240 @@
+//! The running configuration. It is read from the environment, `.env`,
+//! `<NAME>_FILE` files and the secrets provider at startup and re-read on
+//! SIGHUP or `POST /api/admin/reload-config`; only [`RELOADABLE_FIELDS`]
+//! take effect without a restart.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use tracing::{info, warn};
+
+use argus_core::api_types::ConfigReloadResponse;
+use argus_core::config::RELOADABLE_FIELDS;
+use argus_core::secrets::{self, SecretsProvider, FILE_SUFFIX};
+use argus_core::AppConfig;
+use argus_reasoning::ReasoningBudget;
+
+use crate::jobs::{deny_list, prompt_bundles};
+use crate::state::AppState;
+
+pub struct LiveConfig {
+    current: RwLock<Arc<AppConfig>>,
+    env_file: PathBuf,
+    /// Variables set from `env_file` at startup. They are read from the
+    /// file again on reload; everything else set in the environment wins.
+    from_file: HashSet<String>,
+    /// Variables set from `<NAME>_FILE` files or the secrets provider at
+    /// startup, for code that reads the environment itself. They are
+    /// resolved from their source again on reload.
+    from_secrets: HashSet<String>,
+    provider: Option<Box<dyn SecretsProvider>>,
+    /// What the provider returned last.
+    secrets: RwLock<HashMap<String, String>>,
+    /// Held for the whole of a reload, so two never interleave.
+    reloading: tokio::sync::Mutex<()>,
+}
+
+impl LiveConfig {
+    /// Set the variables in `env_file` that the environment does not, then
+    /// those given as `<NAME>_FILE` or by the secrets provider, and read
+    /// the configuration. Fails when the provider is misconfigured or
+    /// cannot be read, rather than start without its credentials.
+    pub async fn load(env_file: &Path) -> argus_core::Result<Self> {
+        let mut from_file = HashSet::new();
+        for (key, value) in read_env_file(env_file) {
+            if std::env::var(&key).is_err() {
+                std::env::set_var(&key, value);
+                from_file.insert(key);
+            }
+        }
+
+        let provider =
+            secrets::provider_from_lookup(|key| secrets::resolve(&env, &HashMap::new(), key))?;
+        let fetched = match &provider {
+            Some(provider) => provider.fetch().await?,
+            None => HashMap::new(),
+        };
+
+        let mut from_secrets = HashSet::new();
+        let named_by_files = std::env::vars()
+            .filter_map(|(key, _)| key.strip_suffix(FILE_SUFFIX).map(str::to_string))
+            .filter(|key| !key.is_empty());
+        let keys: Vec<String> = named_by_files.chain(fetched.keys().cloned()).collect();
+        for key in keys {
+            if std::env::var(&key).is_err() {
+                if let Ok(value) = secrets::resolve(&env, &fetched, &key) {
+                    std::env::set_var(&key, value);
+                    from_secrets.insert(key);
+                }
+            }
+        }
+
+        Ok(Self {
+            current: RwLock::new(Arc::new(AppConfig::from_env())),
+            env_file: env_file.to_path_buf(),
+            from_file,
+            from_secrets,
+            provider,
+            secrets: RwLock::new(fetched),
+            reloading: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// The configuration in effect now. Hold on to it for one operation at
+    /// most, so reloads reach the next one.
+    pub fn current(&self) -> Arc<AppConfig> {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Name of the secrets provider in use, if any.
+    pub fn secrets_provider(&self) -> Option<&'static str> {
+        self.provider.as_ref().map(|provider| provider.name())
+    }
+
+    /// Fetch the provider's values again; on failure the last ones stay.
+    async fn refresh_secrets(&self) {
+        let Some(provider) = &self.provider else {
+            return;
+        };
+        match provider.fetch().await {
+            Ok(fetched) => *self.secrets.write().unwrap_or_else(|e| e.into_inner()) = fetched,
+            Err(e) => {
+                warn!(provider = provider.name(), error = %e, "Failed to refresh secrets, keeping the previous values")
+            }
+        }
+    }
+
+    /// The configuration as the environment, `env_file`, secret files and
+    /// the last fetched secrets describe it now.
+    fn read(&self) -> AppConfig {
+        let file: HashMap<String, String> = read_env_file(&self.env_file).into_iter().collect();
+        let secrets = self
+            .secrets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        AppConfig::with_secrets(
+            |key| {
+                if !self.from_file.contains(key) && !self.from_secrets.contains(key) {
+                    if let Ok(value) = std::env::var(key) {
+                        return Ok(value);
+                    }
+                }
+                file.get(key).cloned().ok_or(std::env::VarError::NotPresent)
+            },
+            &secrets,
+        )
+    }
+}
+
+fn env(key: &str) -> Result<String, std::env::VarError> {
+    std::env::var(key)
+}
+
+/// `KEY=value` lines of a `.env` file; empty if there is none.
+fn read_env_file(path: &Path) -> Vec<(String, String)> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Re-read the configuration and apply the reloadable changes: the new
+/// values are in effect for the next operation that reads them, the
+/// validator and reasoning budgets are rebuilt, and the deny-list and
+/// prompt bundles are reloaded from the shared store.
+pub async fn reload(state: &AppState) -> ConfigReloadResponse {
+    let live = &state.live_config;
+    let _reloading = live.reloading.lock().await;
+
+    live.refresh_secrets().await;
+    let current = live.current();
+    let fresh = live.read();
+    let (applied, requires_restart): (Vec<String>, Vec<String>) = current
+        .changed_fields(&fresh)
+        .into_iter()
+        .partition(|field| RELOADABLE_FIELDS.contains(&field.as_str()));
+    let next = Arc::new(current.reloaded(&fresh));
+    *live.current.write().unwrap_or_else(|e| e.into_inner()) = next.clone();
+
+    if applied.iter().any(|field| field.starts_with("validation_")) {
+        state.validator.reconfigure(&next);
+    }
+    if applied
+        .iter()
+        .any(|field| field.starts_with("reasoning_max_"))
+    {
+        state
+            .reasoning
+            .set_budget(ReasoningBudget::from_config(&next));
+    }
+    deny_list::reload(state.deny_list.as_ref(), state.validator.deny_list()).await;
+    prompt_bundles::reload(state.prompts.as_ref(), state.extraction.llm().prompts()).await;
+
+    info!(applied = ?applied, "Reloaded configuration");
+    if !requires_restart.is_empty() {
+        warn!(settings = ?requires_restart, "Changed settings take effect after a restart");
+    }
+    ConfigReloadResponse {
+        applied,
+        requires_restart,
+        reloaded_at: Utc::now(),
+    }
+}
+
+/// Reload the configuration whenever the process receives SIGHUP.
+#[cfg(unix)]
+pub async fn watch_sighup(state: AppState) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            warn!(error = %e, "Cannot listen for SIGHUP, reload the configuration through the API instead");
+            return;
+        }
+    };
+    while hangups.recv().await.is_some() {
+        info!("SIGHUP received, reloading configuration");
+        reload(&state).await;
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn watch_sighup(_state: AppState) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_file_lines_are_parsed() {
+        let path = std::env::temp_dir().join(format!("argus-env-{}", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "# comment\n\nDEDUP_TTL_SECONDS = 60\nBROKEN\nOFF_PEAK_HOURS=2-4\n",
+        )
+        .unwrap();
+
+        let pairs = read_env_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            pairs,
+            [
+                ("DEDUP_TTL_SECONDS".to_string(), "60".to_string()),
+                ("OFF_PEAK_HOURS".to_string(), "2-4".to_string()),
+            ]
+        );
+        assert!(read_env_file(Path::new("/nonexistent/.env")).is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! The LLM call log: with `LLM_LOG_ENABLED`, every prompt and response the
+//! extraction and reasoning clients exchange is masked for credentials and
+//! personal data and archived as a document from `llm-extraction` or
+//! `llm-reasoning`, where document search finds it and retention policies
+//! expire it.
+//!
+//! Calls are queued and written in batches in the background; when the
+//! queue is full, calls are dropped rather than slowing extraction down.
+
+use std::sync::Arc;
+
+use regex::Regex;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use argus_core::llm::{LlmCall, LlmCallLog};
+use argus_core::{AppConfig, ArchivedDocument, GraphStore};
+
+/// Calls waiting to be archived before new ones are dropped.
+const QUEUE_CAPACITY: usize = 1000;
+
+/// Calls archived per graph transaction.
+const ARCHIVE_BATCH_SIZE: usize = 50;
+
+/// Credentials shaped like the ones Argus and its sources use.
+const API_KEY_PATTERNS: &[&str] = &[
+    r"sk-ant-[A-Za-z0-9_\-]{10,}",
+    r"\bsk-[A-Za-z0-9]{20,}",
+    r"\bAKIA[0-9A-Z]{16}\b",
+    r"(?i)\bbearer\s+[A-Za-z0-9._\-]{16,}",
+    r#"(?i)\b(?:api[_-]?key|access[_-]?token|secret|password)["']?\s*[:=]\s*["']?[^\s"',;()\[\]{}]{8,}"#,
+];
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9\-]+(?:\.[A-Za-z0-9\-]+)*\.[A-Za-z]{2,}";
+/// International numbers only: bare digit runs are too often MMSI, IMO or
+/// registry numbers the log is meant to show.
+const PHONE_PATTERN: &str =
+    r"\+\d{1,3}[\s.\-]?(?:\(\d{1,4}\)[\s.\-]?)?\d{2,4}(?:[\s.\-]?\d{2,4}){1,4}";
+const IBAN_PATTERN: &str = r"\b[A-Z]{2}\d{2}(?:\s?[A-Z0-9]{4}){2,7}(?:\s?[A-Z0-9]{1,3})?\b";
+
+/// Masks sensitive text before a call is archived.
+pub struct Redactor {
+    /// Configured credentials, masked verbatim wherever they appear.
+    secrets: Vec<String>,
+    rules: Vec<(Regex, &'static str)>,
+}
+
+impl Redactor {
+    /// The kinds in `LLM_LOG_REDACT` plus `LLM_LOG_REDACT_PATTERNS`.
+    /// Unknown kinds and invalid patterns are skipped with a warning.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mut rules = Vec::new();
+        let mut add = |pattern: &str, mask: &'static str| match Regex::new(pattern) {
+            Ok(regex) => rules.push((regex, mask)),
+            Err(e) => warn!(pattern, error = %e, "Ignoring invalid LLM log redaction pattern"),
+        };
+        for kind in &config.llm_log_redact {
+            match kind.as_str() {
+                "api_keys" => API_KEY_PATTERNS.iter().for_each(|p| add(p, "[redacted]")),
+                "emails" => add(EMAIL_PATTERN, "[email]"),
+                "phones" => add(PHONE_PATTERN, "[phone]"),
+                "ibans" => add(IBAN_PATTERN, "[iban]"),
+                other => warn!(kind = other, "Ignoring unknown LLM_LOG_REDACT kind"),
+            }
+        }
+        for pattern in &config.llm_log_redact_patterns {
+            add(pattern, "[redacted]");
+        }
+
+        let secrets = if config.llm_log_redact.iter().any(|kind| kind == "api_keys") {
+            config.secret_values()
+        } else {
+            Vec::new()
+        };
+        Self { secrets, rules }
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for secret in &self.secrets {
+            text = text.replace(secret.as_str(), "[redacted]");
+        }
+        for (regex, mask) in &self.rules {
+            if let std::borrow::Cow::Owned(masked) = regex.replace_all(&text, *mask) {
+                text = masked;
+            }
+        }
+        text
+    }
+}
+
+/// Queues calls for the background writer started by [`spawn`].
+pub struct LlmCallArchiver {
+    calls: mpsc::Sender<LlmCall>,
+}
+
+impl LlmCallLog for LlmCallArchiver {
+    fn record(&self, call: LlmCall) {
+        if let Err(e) = self.calls.try_send(call) {
+            warn!(error = %e, "LLM call log queue full, dropping call");
+        }
+    }
+}
+
+/// Start archiving logged calls into `graph`.
+pub fn spawn(graph: Arc<dyn GraphStore>, redactor: Redactor) -> Arc<LlmCallArchiver> {
+    let (calls, mut queue) = mpsc::channel(QUEUE_CAPACITY);
+    tokio::spawn(async move {
+        while let Some(call) = queue.recv().await {
+            let mut batch = vec![to_document(&call, &redactor)];
+            while batch.len() < ARCHIVE_BATCH_SIZE {
+                match queue.try_recv() {
+                    Ok(call) => batch.push(to_document(&call, &redactor)),
+                    Err(_) => break,
+                }
+            }
+            match graph.archive_documents(&batch).await {
+                Ok(()) => debug!(calls = batch.len(), "Archived LLM calls"),
+                Err(e) => warn!(calls = batch.len(), error = %e, "Failed to archive LLM calls"),
+            }
+        }
+    });
+    Arc::new(LlmCallArchiver { calls })
+}
+
+/// The call as an archived document: the subject as title, the masked
+/// system prompt, messages and response as content.
+fn to_document(call: &LlmCall, redactor: &Redactor) -> ArchivedDocument {
+    let mut content = format!(
+        "model: {}\nduration_ms: {}\n\nsystem:\n{}\n\n{}\n\n",
+        call.model, call.duration_ms, call.system, call.prompt
+    );
+    match &call.response {
+        Some(response) => content.push_str(&format!("response:\n{response}")),
+        None => content.push_str("response: none"),
+    }
+    ArchivedDocument {
+        id: Uuid::new_v4(),
+        source: call.purpose.archive_source(),
+        source_id: call.id.to_string(),
+        title: Some(redactor.redact(&format!("{} call: {}", call.purpose.as_str(), call.subject))),
+        content: redactor.redact(&content),
+        url: None,
+        collected_at: call.started_at,
+        run_id: None,
+        entity_ids: Vec::new(),
+        extraction: None,
+        extraction_error: call.error.as_deref().map(|e| redactor.redact(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use argus_core::llm::LlmPurpose;
+
+    use super::*;
+
+    fn redactor(kinds: &[&str], secret: &str) -> Redactor {
+        let mut config = AppConfig::from_lookup(|_| Err(std::env::VarError::NotPresent));
+        config.anthropic_api_key = secret.to_string();
+        config.llm_log_redact = kinds.iter().map(|k| k.to_string()).collect();
+        config.llm_log_redact_patterns =
+            vec![r"\bPASSPORT-\d+".to_string(), "(unclosed".to_string()];
+        Redactor::from_config(&config)
+    }
+
+    #[test]
+    fn credentials_and_personal_data_are_masked() {
+        let masking = redactor(
+            &["api_keys", "emails", "phones", "ibans", "faces"],
+            "internal-key-123",
+        );
+        let text = "Contact j.doe@example.co.uk or +44 20 7946 0958 (key internal-key-123, \
+                    api_key=abcdefgh12345678). Pay GB82 WEST 1234 5698 7654 32. \
+                    Vessel MMSI 273123456, IMO 9876543, holder PASSPORT-991.";
+        let masked = masking.redact(text);
+
+        assert_eq!(
+            masked,
+            "Contact [email] or [phone] (key [redacted], [redacted]). Pay [iban]. \
+             Vessel MMSI 273123456, IMO 9876543, holder [redacted]."
+        );
+        assert_eq!(
+            redactor(&[], "internal-key-123").redact("internal-key-123"),
+            "internal-key-123"
+        );
+    }
+
+    #[test]
+    fn calls_are_archived_under_their_purpose() {
+        let call = LlmCall {
+            id: Uuid::new_v4(),
+            purpose: LlmPurpose::Extraction,
+            model: "claude-haiku-4-5-20251001".to_string(),
+            subject: "gdelt/123".to_string(),
+            system: "Extract entities.".to_string(),
+            prompt: "user: Write to press@acme.example".to_string(),
+            response: None,
+            error: Some("Anthropic API returned status 529 (overloaded): {}".to_string()),
+            started_at: chrono::Utc::now(),
+            duration_ms: 1200,
+        };
+        let document = to_document(&call, &redactor(&["emails"], ""));
+
+        assert_eq!(document.source, "llm-extraction");
+        assert_eq!(document.source_id, call.id.to_string());
+        assert_eq!(
+            document.title.as_deref(),
+            Some("extraction call: gdelt/123")
+        );
+        assert!(document.content.contains("user: Write to [email]"));
+        assert!(document.content.ends_with("response: none"));
+        assert!(document.extraction_error.is_some());
+    }
+}
@@ -1,84 +1,273 @@
 use std::sync::Arc;
 
-use tokio::sync::RwLock;
-use tower_http::cors::CorsLayer;
+use tower_http::compression::CompressionLayer;
 use tower_http::trace::TraceLayer;
-use tracing_subscriber::EnvFilter;
 
+mod alerts;
+mod cold_storage;
+mod graphql;
 mod handlers;
+mod http_cache;
+mod jobs;
+mod live_config;
+mod llm_log;
+mod mcp;
+mod ndjson;
+mod pipeline;
 mod routes;
+mod run_logs;
 mod scheduler;
+mod security;
+mod shared;
 mod state;
+mod subscriptions;
+mod telemetry;
+mod usage;
+mod worker;
+mod write_buffer;
 
 use state::AppState;
 
 #[tokio::main]
 async fn main() {
     // Load .env file if present
-    if let Ok(env_path) = std::fs::canonicalize(".env") {
-        if env_path.exists() {
-            for line in std::fs::read_to_string(&env_path).unwrap_or_default().lines() {
-                let line = line.trim();
-                if line.is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                if let Some((key, value)) = line.split_once('=') {
-                    let key = key.trim();
-                    let value = value.trim();
-                    if std::env::var(key).is_err() {
-                        std::env::set_var(key, value);
-                    }
-                }
-            }
+    let live_config = match live_config::LiveConfig::load(std::path::Path::new(".env")).await {
+        Ok(live_config) => live_config,
+        Err(e) => {
+            eprintln!("argus-server: {e}");
+            std::process::exit(2);
         }
+    };
+
+    let args: Vec<String> = std::env::args().collect();
+    let mcp_mode = args.iter().skip(1).any(|arg| arg == "--mcp");
+    let role = match worker::ServerRole::from_args(&args) {
+        Ok(role) => role,
+        Err(e) => {
+            eprintln!("argus-server: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let telemetry = telemetry::init(mcp_mode);
+
+    let config = live_config.current();
+
+    if mcp_mode {
+        let graph = Arc::new(argus_graph::Neo4jGraphStore::new(&config).await);
+        tracing::info!("ARGUS MCP server running on stdio");
+        if let Err(e) = mcp::run_stdio(graph).await {
+            tracing::error!(error = %e, "MCP server stopped");
+        }
+        telemetry.shutdown();
+        return;
     }
 
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive("argus=info".parse().unwrap()))
-        .init();
+    if role != worker::ServerRole::All && !config.state_backend.eq_ignore_ascii_case("redis") {
+        tracing::error!(
+            role = role.name(),
+            "Separate api and worker roles share jobs through Redis; set STATE_BACKEND=redis"
+        );
+        telemetry.shutdown();
+        std::process::exit(2);
+    }
 
-    let config = argus_core::AppConfig::from_env();
     let host = config.server_host.clone();
     let port = config.server_port;
 
+    if let Some(provider) = live_config.secrets_provider() {
+        tracing::info!(provider, "Loaded settings from secrets provider");
+    }
     if config.anthropic_api_key.is_empty() {
         tracing::warn!("ANTHROPIC_API_KEY not set — extraction and reasoning will fail");
     } else {
-        tracing::info!("ANTHROPIC_API_KEY loaded ({} chars)", config.anthropic_api_key.len());
+        tracing::info!(
+            "ANTHROPIC_API_KEY loaded ({} chars)",
+            config.anthropic_api_key.len()
+        );
     }
 
     let graph = Arc::new(argus_graph::Neo4jGraphStore::new(&config).await);
-    let extraction = Arc::new(argus_extraction::LlmExtractionPipeline::new(&config));
-    let reasoning = Arc::new(argus_reasoning::LlmReasoningEngine::new(
+    let validator = Arc::new(argus_extraction::Validator::from_config(&config));
+    let geocoder = argus_extraction::geocode::from_config(&config).map(|geocoder| {
+        tracing::info!(geocoder = geocoder.name(), "Geocoding extracted locations");
+        Arc::new(argus_extraction::LocationGeocoder::new(geocoder))
+    });
+    let llm_log = config.llm_log_enabled.then(|| {
+        tracing::info!(
+            redact = ?config.llm_log_redact,
+            retention_days = config.llm_log_retention_days,
+            "Logging LLM calls to the document archive"
+        );
+        llm_log::spawn(
+            graph.clone() as Arc<dyn argus_core::graph::GraphStore>,
+            llm_log::Redactor::from_config(&config),
+        ) as Arc<dyn argus_core::llm::LlmCallLog>
+    });
+    let reasoning = Arc::new(
+        argus_reasoning::LlmReasoningEngine::new(
+            graph.clone() as Arc<dyn argus_core::graph::GraphStore>,
+            &config,
+        )
+        .with_call_log(llm_log.clone()),
+    );
+    let embeddings = Arc::new(argus_vector::VoyageEmbeddingProvider::new(&config));
+    let vectors = Arc::new(argus_vector::QdrantVectorStore::new(&config));
+    let agents = argus_agents::agent_registry();
+    let extraction = Arc::new(
+        argus_extraction::PipelineRouter::new(
+            &config,
+            Arc::new(argus_extraction::LlmExtractionPipeline::new(&config).with_call_log(llm_log)),
+        )
+        .with_source_types(
+            agents
+                .iter()
+                .map(|(name, agent)| (name.clone(), agent.source_type().to_string())),
+        ),
+    );
+    let shared = shared::SharedState::from_config(&config);
+    let graphql = graphql::build_schema(
         graph.clone() as Arc<dyn argus_core::graph::GraphStore>,
         &config,
+    );
+    let notifier = Arc::new(alerts::notify::Notifier::from_config(
+        &config,
+        shared.alert_rules.clone(),
+        shared.alerts.clone(),
     ));
-    let agents = argus_agents::agent_registry();
-    let runs = Arc::new(RwLock::new(Vec::new()));
+    let alert_engine = Arc::new(
+        alerts::AlertEngine::new(shared.alert_rules.clone(), shared.alerts.clone())
+            .with_notifier(notifier.clone())
+            .with_positions(graph.clone() as Arc<dyn argus_core::graph::GraphStore>),
+    );
+    let subscription_engine = Arc::new(subscriptions::SubscriptionEngine::new(
+        shared.subscriptions.clone(),
+        shared.notifications.clone(),
+    ));
+    let cold_storage = cold_storage::from_config(&config);
+    let mut writes = write_buffer::GraphWriteBuffer::new(
+        graph.clone() as Arc<dyn argus_core::graph::GraphStore>,
+        config.graph_write_batch_size,
+        std::time::Duration::from_millis(config.graph_write_flush_ms),
+    )
+    .with_observer(alert_engine)
+    .with_observer(subscription_engine.clone());
+    if let Some(cold) = &cold_storage {
+        writes = writes.with_observer(cold.clone());
+    }
+    let writes = Arc::new(writes);
 
     let state = AppState {
-        config,
+        live_config: Arc::new(live_config),
         agents,
         graph,
         extraction,
+        validator,
+        geocoder,
         reasoning,
-        runs,
+        embeddings,
+        vectors,
+        writes,
+        cold_storage,
+        graphql,
+        runs: shared.runs,
+        dedup: shared.dedup,
+        rate_limiter: shared.rate_limiter,
+        locks: shared.locks,
+        retries: shared.retries,
+        idempotency: shared.idempotency,
+        pauses: shared.pauses,
+        duplicates: shared.duplicates,
+        alert_rules: shared.alert_rules,
+        alerts: shared.alerts,
+        deny_list: shared.deny_list,
+        feedback: shared.feedback,
+        investigations: shared.investigations,
+        subscriptions: shared.subscriptions,
+        notifications: shared.notifications,
+        subscription_engine,
+        prompts: shared.prompts,
+        notifier,
+        quotas: shared.quotas,
+        spillover: shared.spillover,
+        extraction_jobs: shared.extraction_jobs,
+        extraction_failures: shared.extraction_failures,
+        usage: shared.usage,
+        role,
+        run_logs: telemetry.run_logs.clone(),
+        instance_id: shared.instance_id,
     };
 
+    if role == worker::ServerRole::Worker {
+        tracing::info!(instance = %state.instance_id, "ARGUS extraction worker running");
+        tokio::spawn(write_buffer::run_flusher(state.writes.clone()));
+        spawn_cold_storage_flusher(&state, &config);
+        jobs::spawn_extraction_refresh(&state);
+        tokio::spawn(live_config::watch_sighup(state.clone()));
+        worker::run(state.clone(), async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await;
+        // Write what the last jobs left in the buffer
+        state.writes.flush().await;
+        if let Some(cold) = &state.cold_storage {
+            cold.flush().await;
+        }
+        telemetry.shutdown();
+        return;
+    }
+
     // Start background scheduler
     let scheduler_state = state.clone();
     tokio::spawn(async move {
         scheduler::run_scheduler(scheduler_state).await;
     });
 
+    // Start periodic analytics jobs
+    jobs::spawn_jobs(&state);
+    spawn_cold_storage_flusher(&state, &config);
+
+    tokio::spawn(live_config::watch_sighup(state.clone()));
+
+    let security_headers = Arc::new(security::security_headers(&config));
+    let usage = state.usage.clone();
+    let writes = state.writes.clone();
+    let cold_storage = state.cold_storage.clone();
     let app = routes::create_router()
         .with_state(state)
-        .layer(CorsLayer::permissive())
+        .layer(axum::middleware::from_fn_with_state(
+            usage,
+            usage::track_usage,
+        ))
+        .layer(CompressionLayer::new())
+        .layer(axum::middleware::from_fn_with_state(
+            security_headers,
+            security::add_security_headers,
+        ))
+        .layer(security::cors_layer(&config))
         .layer(TraceLayer::new_for_http());
 
     let addr = format!("{host}:{port}");
     tracing::info!("ARGUS server listening on {addr}");
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .unwrap();
+    // Write what requests and jobs left in the buffer
+    writes.flush().await;
+    if let Some(cold) = cold_storage {
+        cold.flush().await;
+    }
+    telemetry.shutdown();
+}
+
+fn spawn_cold_storage_flusher(state: &AppState, config: &argus_core::AppConfig) {
+    if let Some(cold) = &state.cold_storage {
+        let every = std::time::Duration::from_secs(config.cold_storage_flush_seconds);
+        tokio::spawn(argus_coldstore::run_flusher(cold.clone(), every));
+    }
 }
@@ -0,0 +1,351 @@
+//! Model Context Protocol server over stdio, so assistants like Claude
+//! Desktop can query the knowledge graph directly.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0 on stdin/stdout. Only the
+//! tools capability is implemented; every tool is read-only.
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+use argus_core::graph::{GraphQuery, GraphStore};
+
+/// Protocol revision offered when the client does not name one.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const MAX_SEARCH_LIMIT: usize = 50;
+const MAX_PATHS: usize = 20;
+const MAX_CYPHER_ROWS: usize = 200;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A `tools/call` request, decoded from its `name` and `arguments`.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(tag = "name", content = "arguments", rename_all = "snake_case")]
+enum Tool {
+    SearchEntities {
+        query: String,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    GetEntity {
+        id: Uuid,
+    },
+    FindPaths {
+        from: Uuid,
+        to: Uuid,
+        #[serde(default)]
+        max_depth: Option<u32>,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    RunReadonlyCypher {
+        cypher: String,
+        #[serde(default)]
+        params: Option<Value>,
+    },
+}
+
+/// What to do with one incoming message.
+#[derive(Debug)]
+enum Dispatch {
+    Reply(Value),
+    Call(Value, Tool),
+    /// Notifications get no response.
+    Ignore,
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "search_entities",
+            "description": "Full-text search over entity names and aliases. Returns matching entities with their type, properties and sources.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Search text" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_SEARCH_LIMIT, "default": 10 }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "get_entity",
+            "description": "Fetch one entity by id together with its direct neighbors and the relationships to them.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "format": "uuid" }
+                },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "find_paths",
+            "description": "Shortest paths between two entities, ignoring edge direction. Useful for explaining how two people or organizations are connected.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "from": { "type": "string", "format": "uuid" },
+                    "to": { "type": "string", "format": "uuid" },
+                    "max_depth": { "type": "integer", "minimum": 1, "maximum": 6, "default": 4 },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_PATHS, "default": 5 }
+                },
+                "required": ["from", "to"]
+            }
+        },
+        {
+            "name": "run_readonly_cypher",
+            "description": "Run a read-only Cypher query against the Neo4j graph. Write clauses are rejected and the transaction is always rolled back.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "cypher": { "type": "string" },
+                    "params": { "type": "object", "description": "Query parameters referenced as $name" }
+                },
+                "required": ["cypher"]
+            }
+        }
+    ])
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+fn tool_result(text: String, is_error: bool) -> Value {
+    json!({ "content": [{ "type": "text", "text": text }], "isError": is_error })
+}
+
+/// Route a decoded message to a protocol reply or a tool call.
+fn dispatch(message: Value) -> Dispatch {
+    let method = message
+        .get("method")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let Some(id) = message.get("id").cloned() else {
+        // Notifications (initialized, cancelled) need no answer
+        return Dispatch::Ignore;
+    };
+    let Some(method) = method else {
+        return Dispatch::Reply(error_response(id, INVALID_REQUEST, "Missing method"));
+    };
+
+    match method.as_str() {
+        "initialize" => {
+            let version = params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(PROTOCOL_VERSION);
+            Dispatch::Reply(response(
+                id,
+                json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": { "name": "argus", "version": env!("CARGO_PKG_VERSION") }
+                }),
+            ))
+        }
+        "ping" => Dispatch::Reply(response(id, json!({}))),
+        "tools/list" => Dispatch::Reply(response(id, json!({ "tools": tool_definitions() }))),
+        "tools/call" => match serde_json::from_value::<Tool>(params) {
+            Ok(tool) => Dispatch::Call(id, tool),
+            Err(e) => Dispatch::Reply(error_response(
+                id,
+                INVALID_PARAMS,
+                format!("Invalid tool call: {e}"),
+            )),
+        },
+        other => Dispatch::Reply(error_response(
+            id,
+            METHOD_NOT_FOUND,
+            format!("Unknown method: {other}"),
+        )),
+    }
+}
+
+/// Run a tool against the graph. Errors become tool results with
+/// `isError` set, so the model sees them rather than the client.
+async fn call_tool(graph: &dyn GraphStore, tool: Tool) -> Value {
+    let outcome: Result<Value, String> = async {
+        match tool {
+            Tool::SearchEntities { query, limit } => {
+                let limit = limit.unwrap_or(10).clamp(1, MAX_SEARCH_LIMIT);
+                let entities = graph.search_entities(&query, limit).await.map_err(|e| e.to_string())?;
+                Ok(json!({ "entities": entities }))
+            }
+            Tool::GetEntity { id } => {
+                let entity = graph
+                    .get_entity(id)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Entity {id} not found"))?;
+                let neighbors = graph.get_neighbors(id, 1).await.map_err(|e| e.to_string())?;
+                Ok(json!({
+                    "entity": entity,
+                    "neighbors": neighbors.neighbors,
+                    "relationships": neighbors.relationships,
+                    "truncated": neighbors.truncated,
+                }))
+            }
+            Tool::FindPaths {
+                from,
+                to,
+                max_depth,
+                limit,
+            } => {
+                let limit = limit.unwrap_or(5).clamp(1, MAX_PATHS);
+                let paths = graph
+                    .find_paths(from, to, max_depth.unwrap_or(4), limit, None)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(json!({ "paths": paths }))
+            }
+            Tool::RunReadonlyCypher { cypher, params } => {
+                let query = GraphQuery {
+                    cypher,
+                    params: params.unwrap_or_else(|| json!({})),
+                };
+                let rows = graph.execute_readonly_cypher(&query).await.map_err(|e| e.to_string())?;
+                let mut rows = match rows {
+                    Value::Array(rows) => rows,
+                    other => vec![other],
+                };
+                let total = rows.len();
+                rows.truncate(MAX_CYPHER_ROWS);
+                Ok(json!({ "rows": rows, "total_rows": total, "truncated": total > MAX_CYPHER_ROWS }))
+            }
+        }
+    }
+    .await;
+
+    match outcome {
+        Ok(value) => tool_result(
+            serde_json::to_string_pretty(&value).unwrap_or_default(),
+            false,
+        ),
+        Err(message) => tool_result(message, true),
+    }
+}
+
+/// Serve requests until the reader closes. Each line is one message.
+pub async fn serve<R, W>(
+    graph: Arc<dyn GraphStore>,
+    reader: R,
+    mut writer: W,
+) -> std::io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => match dispatch(message) {
+                Dispatch::Reply(reply) => reply,
+                Dispatch::Call(id, tool) => {
+                    tracing::debug!(?tool, "MCP tool call");
+                    response(id, call_tool(graph.as_ref(), tool).await)
+                }
+                Dispatch::Ignore => continue,
+            },
+            Err(e) => error_response(Value::Null, PARSE_ERROR, format!("Parse error: {e}")),
+        };
+
+        let mut bytes = serde_json::to_vec(&reply)?;
+        bytes.push(b'\n');
+        writer.write_all(&bytes).await?;
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+/// Run the MCP server on stdin/stdout.
+pub async fn run_stdio(graph: Arc<dyn GraphStore>) -> std::io::Result<()> {
+    let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+    serve(graph, stdin, tokio::io::stdout()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(message: Value) -> Value {
+        match dispatch(message) {
+            Dispatch::Reply(reply) => reply,
+            other => panic!("expected a reply, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn initialize_echoes_protocol_version_and_lists_tools() {
+        let init = reply(json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": "2025-03-26", "capabilities": {} }
+        }));
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert!(init["result"]["capabilities"]["tools"].is_object());
+
+        let list = reply(json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }));
+        let names: Vec<&str> = list["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "search_entities",
+                "get_entity",
+                "find_paths",
+                "run_readonly_cypher"
+            ]
+        );
+    }
+
+    #[test]
+    fn notifications_and_unknown_methods() {
+        assert!(matches!(
+            dispatch(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })),
+            Dispatch::Ignore
+        ));
+        let unknown = reply(json!({ "jsonrpc": "2.0", "id": "a", "method": "resources/list" }));
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn tool_calls_decode_arguments() {
+        let id = Uuid::new_v4();
+        let call = dispatch(json!({
+            "jsonrpc": "2.0", "id": 3, "method": "tools/call",
+            "params": { "name": "get_entity", "arguments": { "id": id.to_string() } }
+        }));
+        match call {
+            Dispatch::Call(_, tool) => assert_eq!(tool, Tool::GetEntity { id }),
+            other => panic!("expected a call, got {other:?}"),
+        }
+
+        let bad = reply(json!({
+            "jsonrpc": "2.0", "id": 4, "method": "tools/call",
+            "params": { "name": "drop_everything", "arguments": {} }
+        }));
+        assert_eq!(bad["error"]["code"], INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,73 @@
+//! Newline-delimited JSON responses written item by item as they are
+//! produced, so large results are never rendered into one body.
+
+use std::convert::Infallible;
+
+use axum::{
+    body::Body,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures::{future, Stream, StreamExt};
+use serde::Serialize;
+
+use argus_core::Result;
+
+pub const MEDIA_TYPE: &str = "application/x-ndjson";
+
+/// One JSON line per item. The status is sent before the first item, so a
+/// failure partway through ends the body with an `{"error": ...}` line.
+pub fn response<T, S>(items: S) -> Response
+where
+    T: Serialize,
+    S: Stream<Item = Result<T>> + Send + 'static,
+{
+    let lines = items.scan(false, |failed, item| {
+        if *failed {
+            return future::ready(None);
+        }
+        let line = item
+            .and_then(|item| serde_json::to_string(&item).map_err(Into::into))
+            .unwrap_or_else(|e| {
+                *failed = true;
+                serde_json::json!({ "error": e.to_string() }).to_string()
+            });
+        future::ready(Some(Ok::<_, Infallible>(line + "\n")))
+    });
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, MEDIA_TYPE)],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::ArgusError;
+
+    #[tokio::test]
+    async fn a_failure_ends_the_stream_with_an_error_line() {
+        let items = futures::stream::iter(vec![
+            Ok(serde_json::json!({"n": 1})),
+            Ok(serde_json::json!({"n": 2})),
+            Err(ArgusError::Graph("connection reset".into())),
+            Ok(serde_json::json!({"n": 3})),
+        ]);
+        let response = response(items);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], MEDIA_TYPE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let lines: Vec<serde_json::Value> = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1]["n"], 2);
+        assert_eq!(lines[2]["error"], "Graph database error: connection reset");
+    }
+}
@@ -0,0 +1,621 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, info_span, instrument, warn, Instrument};
+
+use argus_core::api_types::AgentRunState;
+use argus_core::document::ArchivedDocument;
+use argus_core::quota::{quota_day, QuotaCounter, Spillover};
+use argus_core::{
+    Agent, ExtractionFailure, ExtractionJob, ExtractionPipeline, ExtractionResult, FailedDocument,
+    FailedExtraction, GraphStore, PositionReport, RawDocument, SchemaDrift, ValidationReport,
+};
+
+use crate::alerts::freshness;
+use crate::state::AppState;
+use crate::worker::ServerRole;
+
+/// Result of one collect → extract → store pass.
+pub struct PipelineOutcome {
+    pub documents: u64,
+    pub entities: u64,
+    pub store_errors: u64,
+    pub results: Vec<ExtractionResult>,
+    pub failures: Vec<FailedExtraction>,
+    /// What validation dropped before storage.
+    pub validation: ValidationReport,
+    /// Documents held back by the agent's extraction quota.
+    pub over_quota: u64,
+    /// Upstream schema changes the agent reported for this collection.
+    pub schema_drift: Option<SchemaDrift>,
+    /// The documents were handed to an extraction worker, which finishes
+    /// the run and releases its run lock.
+    pub queued: bool,
+}
+
+impl PipelineOutcome {
+    fn empty() -> Self {
+        Self {
+            documents: 0,
+            entities: 0,
+            store_errors: 0,
+            results: Vec::new(),
+            failures: Vec::new(),
+            validation: ValidationReport::default(),
+            over_quota: 0,
+            schema_drift: None,
+            queued: false,
+        }
+    }
+
+    /// How a run that got this far ended. Documents whose results could not
+    /// be stored are queued for retry, but the run still counts as failed,
+    /// so a storage outage shows in the run list and run status filters
+    /// instead of passing as a completed run with an error note.
+    pub fn run_state(&self) -> AgentRunState {
+        if self.store_errors > 0 {
+            AgentRunState::Failed
+        } else {
+            AgentRunState::Completed
+        }
+    }
+
+    /// The run's error when storage lost documents.
+    pub fn store_error(&self) -> Option<String> {
+        (self.store_errors > 0).then(|| format!("{} storage errors", self.store_errors))
+    }
+}
+
+/// A pipeline pass that stopped before storing anything.
+pub struct PipelineError {
+    pub message: String,
+    /// Documents collected before the failure.
+    pub documents: u64,
+    /// Documents queued for retry.
+    pub failed: u64,
+}
+
+/// Key identifying a document's exact content from a given source.
+fn dedup_key(doc: &RawDocument) -> String {
+    let digest = Sha256::digest(doc.content.as_bytes());
+    let hash: String = digest.iter().take(16).map(|b| format!("{b:02x}")).collect();
+    format!("doc:{}:{}:{}", doc.source, doc.source_id, hash)
+}
+
+/// Drop documents that any replica already processed within `ttl`.
+/// Dedup store failures keep the document, erring on reprocessing.
+async fn drop_seen(
+    state: &AppState,
+    documents: Vec<RawDocument>,
+    ttl: Duration,
+) -> Vec<RawDocument> {
+    let mut fresh = Vec::with_capacity(documents.len());
+    for doc in documents {
+        match state.dedup.first_seen(&dedup_key(&doc), ttl).await {
+            Ok(true) => fresh.push(doc),
+            Ok(false) => debug!(source_id = %doc.source_id, "Skipping recently processed document"),
+            Err(e) => {
+                warn!(error = %e, "Dedup check failed, processing document anyway");
+                fresh.push(doc);
+            }
+        }
+    }
+    fresh
+}
+
+/// Keep the positions collected documents report for tracked assets. They
+/// need no extraction, so are stored before quotas can hold documents back.
+/// With cold storage, the positions and the documents' metadata are
+/// archived as well, and the graph's copies of the positions expire after
+/// `COLD_STORAGE_GRAPH_POSITION_DAYS`.
+async fn store_positions(state: &AppState, agent_name: &str, documents: &[RawDocument]) {
+    let positions: Vec<PositionReport> = documents
+        .iter()
+        .filter_map(PositionReport::from_document)
+        .collect();
+    if let Err(e) = state.graph.store_positions(&positions).await {
+        warn!(agent = %agent_name, error = %e, "Failed to store position reports");
+    }
+    if let Some(cold) = &state.cold_storage {
+        cold.record_positions(&positions).await;
+        cold.record_documents(documents).await;
+    }
+}
+
+/// Count documents bound for LLM extraction against the agent's daily
+/// quota, setting aside those over it per `QUOTA_SPILLOVER`. Documents
+/// their pipeline extracts without the LLM are never held back. Returns the documents
+/// to process now and how many were held back.
+async fn apply_quota(
+    state: &AppState,
+    agent_name: &str,
+    documents: Vec<RawDocument>,
+) -> (Vec<RawDocument>, u64) {
+    let (structured, prose): (Vec<RawDocument>, Vec<RawDocument>) = documents
+        .into_iter()
+        .partition(|d| !state.extraction.uses_llm(d));
+    if prose.is_empty() {
+        return (structured, 0);
+    }
+    let day = quota_day(chrono::Utc::now());
+    let used_key = QuotaCounter::Used.key(agent_name, day);
+    let requested = prose.len() as u64;
+    let Some(&limit) = state.config().extraction_quotas.get(agent_name) else {
+        if let Err(e) = state.quotas.add(&used_key, requested).await {
+            warn!(agent = %agent_name, error = %e, "Failed to count extraction usage");
+        }
+        return (structured.into_iter().chain(prose).collect(), 0);
+    };
+    let granted = match state.quotas.reserve(&used_key, requested, limit).await {
+        Ok(granted) => granted as usize,
+        Err(e) => {
+            // Like dedup, a quota store failure errs on processing
+            warn!(agent = %agent_name, error = %e, "Quota check failed, processing documents anyway");
+            prose.len()
+        }
+    };
+
+    let mut prose = prose;
+    let over = prose.split_off(granted.min(prose.len()));
+    let held = over.len() as u64;
+    if held > 0 {
+        match Spillover::from_name(&state.config().quota_spillover) {
+            Spillover::Drop => {
+                if let Err(e) = state
+                    .quotas
+                    .add(&QuotaCounter::Dropped.key(agent_name, day), held)
+                    .await
+                {
+                    warn!(agent = %agent_name, error = %e, "Failed to count dropped documents");
+                }
+            }
+            Spillover::Queue => match state.spillover.push(agent_name, over).await {
+                Ok(0) => {}
+                Ok(evicted) => {
+                    warn!(agent = %agent_name, evicted, "Spillover queue full, dropped its oldest documents")
+                }
+                Err(e) => {
+                    warn!(agent = %agent_name, error = %e, "Failed to queue documents over quota")
+                }
+            },
+        }
+        info!(agent = %agent_name, limit, over_quota = held, "Daily extraction quota reached");
+    }
+    (structured.into_iter().chain(prose).collect(), held)
+}
+
+/// Run the full agent pipeline: collect → dedup → extract → store → archive.
+///
+/// Documents that fail extraction are queued for retry under `run_id`; the
+/// run only fails outright if collection fails or every document failed.
+/// Everything logged along the way is kept under `run_id` for the run's logs.
+///
+/// With `--role api` the collected documents are queued for an extraction
+/// worker instead, which also cross-references the results if
+/// `cross_reference` is set.
+#[instrument(name = "agent_run", skip_all, fields(run_id = %run_id, agent = %agent_name))]
+pub async fn run_agent_pipeline(
+    state: &AppState,
+    agent_name: &str,
+    agent: Arc<dyn Agent>,
+    run_id: &str,
+    cross_reference: bool,
+) -> Result<PipelineOutcome, PipelineError> {
+    // Collect
+    let started = chrono::Utc::now();
+    let collected = agent
+        .collect()
+        .instrument(info_span!("collect"))
+        .await
+        .map_err(|e| PipelineError {
+            message: format!("Collection failed: {}", e),
+            documents: 0,
+            failed: 0,
+        })?;
+    let status = agent.status().await;
+    let schema_drift = status.schema_drift.filter(|d| d.detected_at >= started);
+    if let Some(&max_lag) = state.config().source_freshness_max_lag.get(agent_name) {
+        let data_at = status.source_data_timestamp;
+        if let Err(e) = freshness::check(
+            state.alerts.as_ref(),
+            agent_name,
+            data_at,
+            max_lag,
+            chrono::Utc::now(),
+        )
+        .await
+        {
+            warn!(agent = %agent_name, error = %e, "Failed to check source freshness");
+        }
+    }
+    let collected_count = collected.len();
+    let ttl = Duration::from_secs(state.config().dedup_ttl_seconds);
+    let documents = drop_seen(state, collected, ttl).await;
+    info!(
+        agent = %agent_name,
+        documents = documents.len(),
+        duplicates = collected_count - documents.len(),
+        "Collection complete"
+    );
+    store_positions(state, agent_name, &documents).await;
+    let (documents, over_quota) = apply_quota(state, agent_name, documents).await;
+
+    if documents.is_empty() {
+        return Ok(PipelineOutcome {
+            over_quota,
+            schema_drift,
+            ..PipelineOutcome::empty()
+        });
+    }
+
+    if state.role == ServerRole::Api {
+        let count = documents.len() as u64;
+        let job = ExtractionJob {
+            run_id: run_id.to_string(),
+            agent_name: agent_name.to_string(),
+            documents,
+            over_quota,
+            schema_drift: schema_drift.clone(),
+            cross_reference,
+            enqueued_at: chrono::Utc::now(),
+        };
+        state
+            .extraction_jobs
+            .push(job)
+            .await
+            .map_err(|e| PipelineError {
+                message: format!("Failed to queue extraction job: {e}"),
+                documents: count,
+                failed: 0,
+            })?;
+        info!(agent = %agent_name, documents = count, "Queued documents for an extraction worker");
+        return Ok(PipelineOutcome {
+            documents: count,
+            over_quota,
+            schema_drift,
+            queued: true,
+            ..PipelineOutcome::empty()
+        });
+    }
+
+    extract_and_store(
+        state,
+        agent_name,
+        run_id,
+        &documents,
+        over_quota,
+        schema_drift,
+    )
+    .await
+}
+
+/// Extract and store the documents of a queued agent run, on a worker.
+#[instrument(name = "extraction_job", skip_all, fields(run_id = %job.run_id, agent = %job.agent_name))]
+pub async fn run_extraction_job(
+    state: &AppState,
+    job: &ExtractionJob,
+) -> Result<PipelineOutcome, PipelineError> {
+    let schema_drift = job.schema_drift.clone();
+    extract_and_store(
+        state,
+        &job.agent_name,
+        &job.run_id,
+        &job.documents,
+        job.over_quota,
+        schema_drift,
+    )
+    .await
+}
+
+/// The part of an agent run after collection. Fails only if every
+/// document failed extraction.
+async fn extract_and_store(
+    state: &AppState,
+    agent_name: &str,
+    run_id: &str,
+    documents: &[RawDocument],
+    over_quota: u64,
+    schema_drift: Option<SchemaDrift>,
+) -> Result<PipelineOutcome, PipelineError> {
+    let mut outcome = process_documents(state, agent_name, run_id, documents).await;
+    outcome.over_quota = over_quota;
+    outcome.schema_drift = schema_drift;
+    queue_failures(state, agent_name, run_id, &outcome).await;
+
+    if outcome.results.is_empty() && !outcome.failures.is_empty() {
+        return Err(PipelineError {
+            message: format!(
+                "Extraction failed: all {} documents failed",
+                outcome.failures.len()
+            ),
+            documents: outcome.documents,
+            failed: outcome.failures.len() as u64,
+        });
+    }
+
+    Ok(outcome)
+}
+
+/// Extract and store documents handed in directly rather than collected by
+/// an agent, or taken off the quota spillover queue. They skip dedup and
+/// quotas, since they are processed on purpose; failures are queued for
+/// retry like an agent's.
+#[instrument(name = "ingest", skip_all, fields(run_id = %run_id, source = %source))]
+pub async fn ingest_documents(
+    state: &AppState,
+    source: &str,
+    run_id: &str,
+    documents: &[RawDocument],
+) -> PipelineOutcome {
+    let outcome = process_documents(state, source, run_id, documents).await;
+    queue_failures(state, source, run_id, &outcome).await;
+    outcome
+}
+
+async fn queue_failures(
+    state: &AppState,
+    agent_name: &str,
+    run_id: &str,
+    outcome: &PipelineOutcome,
+) {
+    for failure in &outcome.failures {
+        let queued = FailedDocument::new(
+            run_id,
+            agent_name,
+            failure.document.clone(),
+            failure.error.clone(),
+        );
+        if let Err(e) = state.retries.record(queued).await {
+            warn!(agent = %agent_name, error = %e, "Failed to queue document for retry");
+        }
+    }
+}
+
+/// Re-extract queued failures of `original_run_id`. Successes leave the
+/// queue; failures stay with their attempt count bumped and backoff extended.
+#[instrument(name = "retry", skip_all, fields(run_id = %retry_run_id, original_run_id = %original_run_id))]
+pub async fn retry_failed_documents(
+    state: &AppState,
+    original_run_id: &str,
+    retry_run_id: &str,
+    entries: Vec<FailedDocument>,
+) -> PipelineOutcome {
+    let agent_name = entries
+        .first()
+        .map(|f| f.agent_name.clone())
+        .unwrap_or_default();
+    let documents: Vec<RawDocument> = entries.iter().map(|f| f.document.clone()).collect();
+    let outcome = process_documents(state, &agent_name, retry_run_id, &documents).await;
+
+    for mut entry in entries {
+        let failure = outcome
+            .failures
+            .iter()
+            .find(|f| f.document.source_id == entry.document.source_id);
+        let result = match failure {
+            Some(failure) => {
+                entry.failed_again(failure.error.clone());
+                state.retries.record(entry).await
+            }
+            None => {
+                state
+                    .retries
+                    .remove(original_run_id, &entry.document.source_id)
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            warn!(run_id = %original_run_id, error = %e, "Failed to update retry queue");
+        }
+    }
+
+    outcome
+}
+
+/// Keep failures with unparseable model output for prompt debugging.
+async fn catalog_failures(
+    state: &AppState,
+    agent_name: &str,
+    run_id: &str,
+    failures: &[FailedExtraction],
+) {
+    for failure in failures
+        .iter()
+        .filter_map(|f| ExtractionFailure::from_failed(run_id, agent_name, f))
+    {
+        if let Err(e) = state.extraction_failures.record(failure).await {
+            warn!(agent = %agent_name, error = %e, "Failed to catalog extraction failure");
+        }
+    }
+}
+
+/// Re-extract the document of a cataloged failure. The entry leaves the
+/// catalog unless the document failed again before the model answered;
+/// an unparseable answer is cataloged afresh.
+#[instrument(name = "retry_cataloged", skip_all, fields(run_id = %retry_run_id, failure_id = %failure.id))]
+pub async fn retry_cataloged_failure(
+    state: &AppState,
+    retry_run_id: &str,
+    failure: ExtractionFailure,
+) -> PipelineOutcome {
+    let documents = [failure.document.clone()];
+    let outcome = process_documents(state, &failure.agent_name, retry_run_id, &documents).await;
+
+    let failed_again = outcome.failures.first();
+    if failed_again.is_none_or(|f| f.raw_output.is_some()) {
+        if let Err(e) = state.extraction_failures.remove(failure.id).await {
+            warn!(failure_id = %failure.id, error = %e, "Failed to remove cataloged extraction failure");
+        }
+    }
+    if failed_again.is_none() {
+        if let Err(e) = state
+            .retries
+            .remove(&failure.run_id, &failure.document.source_id)
+            .await
+        {
+            warn!(run_id = %failure.run_id, error = %e, "Failed to update retry queue");
+        }
+    }
+    outcome
+}
+
+/// Extract, validate, link countries, store and archive already-collected documents.
+///
+/// Results go through the shared write buffer, which is flushed before the
+/// run reports. Documents whose results could not be stored are counted as
+/// store errors and returned with the failures, so they are queued for
+/// retry; archiving failures are only logged.
+async fn process_documents(
+    state: &AppState,
+    agent_name: &str,
+    run_id: &str,
+    documents: &[RawDocument],
+) -> PipelineOutcome {
+    let graph = &state.graph;
+
+    // Extract
+    let batch = state
+        .extraction
+        .extract_batch_detailed(documents)
+        .instrument(info_span!("extract"))
+        .await;
+    catalog_failures(state, agent_name, run_id, &batch.failures).await;
+    // The archive keeps what the model returned, rejects included
+    let extracted = batch.results;
+    let mut results = extracted.clone();
+
+    // Validate
+    let mut validation = ValidationReport::default();
+    for result in &mut results {
+        validation.merge(&state.validator.validate(result));
+    }
+
+    // Link country codes in the source metadata to canonical locations
+    for result in &mut results {
+        if let Some(doc) = documents.iter().find(|d| d.source_id == result.raw_source) {
+            argus_extraction::countries::link_countries(doc, result);
+        }
+    }
+
+    // Place tracked vessels and aircraft where their document reports them,
+    // which the model is not asked to copy from the metadata
+    for result in &mut results {
+        let report = documents
+            .iter()
+            .find(|d| d.source_id == result.raw_source)
+            .and_then(PositionReport::from_document);
+        if let Some(report) = report {
+            report.place(&mut result.entities);
+        }
+    }
+
+    // Place extracted locations
+    let mut geocoded = 0;
+    if let Some(geocoder) = &state.geocoder {
+        let per_minute = state.config().geocoder_requests_per_minute;
+        for result in &mut results {
+            geocoded += geocoder
+                .geocode_locations(result, state.rate_limiter.as_ref(), per_minute)
+                .await;
+        }
+    }
+
+    let entity_count: u64 = results.iter().map(|r| r.entities.len() as u64).sum();
+    info!(
+        agent = %agent_name,
+        extractions = results.len(),
+        failed = batch.failures.len(),
+        entities = entity_count,
+        entities_rejected = validation.entities_rejected,
+        relationships_rejected = validation.relationships_rejected,
+        geocoded,
+        "Extraction complete"
+    );
+
+    let store_failures = async {
+        // Store through the write buffer, batched with whatever else is pending
+        let store_failures: Vec<FailedExtraction> = state
+            .writes
+            .store(results.clone())
+            .await
+            .into_iter()
+            .filter_map(|(raw_source, error)| {
+                let document = documents.iter().find(|d| d.source_id == raw_source)?;
+                Some(FailedExtraction {
+                    document: document.clone(),
+                    error: format!("Failed to store extraction result: {error}"),
+                    raw_output: None,
+                    model: None,
+                })
+            })
+            .collect();
+
+        // Archive the source documents so they can be searched later
+        let archived: Vec<ArchivedDocument> = documents
+            .iter()
+            .map(|doc| {
+                let entity_ids = results
+                    .iter()
+                    .filter(|r| r.raw_source == doc.source_id)
+                    .flat_map(|r| r.entities.iter().map(|e| e.id))
+                    .collect();
+                let extraction = extracted.iter().find(|r| r.raw_source == doc.source_id);
+                let mut archived =
+                    ArchivedDocument::from_raw(doc, Some(run_id.to_string()), entity_ids);
+                // Kept so a run's documents can be inspected next to what was extracted
+                archived.extraction = extraction.cloned();
+                archived.extraction_error = batch
+                    .failures
+                    .iter()
+                    .chain(&store_failures)
+                    .find(|f| f.document.source_id == doc.source_id)
+                    .map(|f| f.error.clone());
+                archived
+            })
+            .collect();
+        if let Err(e) = graph.archive_documents(&archived).await {
+            warn!(agent = %agent_name, error = %e, "Failed to archive source documents");
+        }
+        store_failures
+    }
+    .instrument(info_span!("store"))
+    .await;
+
+    let store_errors = store_failures.len() as u64;
+    let mut failures = batch.failures;
+    failures.extend(store_failures);
+    PipelineOutcome {
+        documents: documents.len() as u64,
+        entities: entity_count,
+        store_errors,
+        results,
+        failures,
+        validation,
+        over_quota: 0,
+        schema_drift: None,
+        queued: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_that_lost_documents_in_storage_fail() {
+        let stored = PipelineOutcome {
+            documents: 3,
+            ..PipelineOutcome::empty()
+        };
+        assert_eq!(stored.run_state(), AgentRunState::Completed);
+        assert_eq!(stored.store_error(), None);
+
+        let lost = PipelineOutcome {
+            store_errors: 2,
+            ..stored
+        };
+        assert_eq!(lost.run_state(), AgentRunState::Failed);
+        assert_eq!(lost.store_error().as_deref(), Some("2 storage errors"));
+    }
+}
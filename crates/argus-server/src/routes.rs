@@ -1,28 +1,332 @@
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post},
     Router,
 };
 
 use crate::handlers;
+use crate::http_cache::{self, cache_control};
 use crate::state::AppState;
 
 pub fn create_router() -> Router<AppState> {
     Router::new()
+        // GraphQL
+        .route(
+            "/graphql",
+            get(handlers::graphql::graphiql).post(handlers::graphql::execute),
+        )
         // Health
         .route("/api/health", get(handlers::health::health_check))
+        // Admin
+        .route(
+            "/api/admin/embeddings/backfill",
+            post(handlers::admin::trigger_embedding_backfill),
+        )
+        .route(
+            "/api/admin/observed-at/backfill",
+            post(handlers::admin::trigger_observed_at_backfill),
+        )
+        .route(
+            "/api/admin/duplicates/scan",
+            post(handlers::admin::trigger_duplicate_scan),
+        )
+        .route("/api/admin/backup", post(handlers::admin::trigger_backup))
+        .route("/api/admin/backups", get(handlers::admin::list_backups))
+        .route(
+            "/api/admin/deny-list",
+            get(handlers::admin::list_deny_list).post(handlers::admin::add_deny_entry),
+        )
+        .route(
+            "/api/admin/deny-list/{id}",
+            delete(handlers::admin::delete_deny_entry),
+        )
+        .route(
+            "/api/admin/duplicates",
+            get(handlers::admin::list_duplicates),
+        )
+        .route(
+            "/api/admin/duplicates/{id}/merge",
+            post(handlers::admin::merge_duplicate),
+        )
+        .route(
+            "/api/admin/duplicates/{id}/dismiss",
+            post(handlers::admin::dismiss_duplicate),
+        )
+        .route(
+            "/api/admin/integrity",
+            get(handlers::admin::integrity_report),
+        )
+        .route(
+            "/api/admin/integrity/repair",
+            post(handlers::admin::repair_integrity),
+        )
+        .route("/api/admin/restore", post(handlers::admin::trigger_restore))
+        .route(
+            "/api/admin/retention",
+            get(handlers::admin::retention_dry_run),
+        )
+        .route(
+            "/api/admin/llm-log/purge",
+            post(handlers::admin::purge_llm_log),
+        )
+        .route(
+            "/api/admin/tombstones",
+            get(handlers::admin::list_tombstones),
+        )
+        .route(
+            "/api/admin/entities/{id}/restore",
+            post(handlers::admin::restore_entity),
+        )
+        .route(
+            "/api/admin/entities/{id}/purge",
+            post(handlers::admin::purge_entity),
+        )
+        .route(
+            "/api/admin/extraction-cache",
+            get(handlers::admin::extraction_cache_stats),
+        )
+        .route(
+            "/api/admin/graph-writes",
+            get(handlers::admin::graph_write_stats),
+        )
+        .route(
+            "/api/admin/extraction-failures",
+            get(handlers::admin::list_extraction_failures),
+        )
+        .route(
+            "/api/admin/usage",
+            get(handlers::admin::usage_report).layer(cache_control(http_cache::NO_STORE)),
+        )
+        .route(
+            "/api/admin/extraction-failures/{id}/retry",
+            post(handlers::admin::retry_extraction_failure),
+        )
+        .route(
+            "/api/admin/prompt-bundles",
+            get(handlers::admin::list_prompt_bundles).post(handlers::admin::create_prompt_bundle),
+        )
+        .route(
+            "/api/admin/prompt-bundles/compare",
+            get(handlers::admin::compare_prompt_bundles),
+        )
+        .route(
+            "/api/admin/prompt-deployment",
+            get(handlers::admin::get_prompt_deployment)
+                .post(handlers::admin::set_prompt_deployment),
+        )
+        .route(
+            "/api/admin/reload-config",
+            post(handlers::admin::reload_config),
+        )
+        // Alerts
+        .route("/api/alerts", get(handlers::alerts::list_alerts))
+        .route(
+            "/api/alerts/rules",
+            get(handlers::alerts::list_alert_rules).post(handlers::alerts::create_alert_rule),
+        )
+        .route(
+            "/api/alerts/rules/{id}",
+            delete(handlers::alerts::delete_alert_rule),
+        )
+        .route("/api/alerts/{id}", get(handlers::alerts::get_alert))
+        .route(
+            "/api/alerts/{id}/acknowledge",
+            post(handlers::alerts::acknowledge_alert),
+        )
         // Agents
         .route("/api/agents", get(handlers::agents::list_agents))
         .route("/api/agents/trigger", post(handlers::agents::trigger_agent))
+        .route("/api/agents/budget", get(handlers::agents::budget_status))
+        .route(
+            "/api/agents/{name}/pause",
+            post(handlers::agents::pause_agent),
+        )
+        .route(
+            "/api/agents/{name}/resume",
+            post(handlers::agents::resume_agent),
+        )
+        .route(
+            "/api/agents/{name}/runs/compare",
+            get(handlers::agents::compare_runs),
+        )
         .route("/api/agents/runs", get(handlers::agents::list_runs))
+        .route("/api/agents/runs/{id}", get(handlers::agents::get_run))
+        .route(
+            "/api/agents/runs/{id}/retry-failures",
+            post(handlers::agents::retry_failures),
+        )
+        .route(
+            "/api/agents/runs/{id}/documents",
+            get(handlers::agents::sample_run_documents),
+        )
+        .route(
+            "/api/agents/runs/{id}/logs",
+            get(handlers::agents::run_logs).layer(cache_control(http_cache::NO_STORE)),
+        )
+        // Analytics
+        .route(
+            "/api/analytics/event-density",
+            get(handlers::analytics::event_density).layer(cache_control(http_cache::GRAPH_STATS)),
+        )
+        .route(
+            "/api/analytics/cold-storage",
+            get(handlers::analytics::cold_storage).layer(cache_control(http_cache::NO_STORE)),
+        )
+        .route(
+            "/api/analytics/positions",
+            get(handlers::analytics::archived_positions),
+        )
+        .route(
+            "/api/analytics/sql",
+            post(handlers::analytics::analytics_sql).layer(cache_control(http_cache::NO_STORE)),
+        )
+        // Documents
+        .route(
+            "/api/documents/ingest",
+            post(handlers::documents::ingest_document)
+                .layer(DefaultBodyLimit::max(handlers::documents::MAX_INGEST_BYTES)),
+        )
+        .route(
+            "/api/documents/search",
+            post(handlers::documents::search_documents).layer(cache_control(http_cache::NO_STORE)),
+        )
         // Entities
-        .route("/api/entities/search", post(handlers::entities::search_entities))
-        .route("/api/entities/{id}", get(handlers::entities::get_entity))
+        .route(
+            "/api/search/natural",
+            post(handlers::entities::natural_search).layer(cache_control(http_cache::NO_STORE)),
+        )
+        .route(
+            "/api/entities/search",
+            post(handlers::entities::search_entities).layer(cache_control(http_cache::NO_STORE)),
+        )
+        .route(
+            "/api/entities/batch",
+            post(handlers::entities::get_entities_batch),
+        )
+        .route(
+            "/api/entities/{id}",
+            get(handlers::entities::get_entity)
+                .layer(cache_control(http_cache::ENTITY_DETAIL))
+                .delete(handlers::entities::delete_entity),
+        )
+        .route(
+            "/api/entities/{id}/history",
+            get(handlers::entities::get_entity_history),
+        )
+        .route(
+            "/api/entities/{id}/enrich",
+            post(handlers::enrichment::enrich_entity),
+        )
+        .route(
+            "/api/entities/{id}/follow",
+            post(handlers::subscriptions::follow_entity),
+        )
+        .route(
+            "/api/entities/by-identifier/{scheme}/{*value}",
+            get(handlers::entities::get_entities_by_identifier),
+        )
+        // Export
+        .route(
+            "/api/entities/{id}/export",
+            get(handlers::export::export_entity).layer(cache_control(http_cache::SUBGRAPH)),
+        )
+        .route(
+            "/api/export",
+            post(handlers::export::export_investigation).layer(cache_control(http_cache::NO_STORE)),
+        )
+        // Import
+        .route(
+            "/api/import/ftm",
+            post(handlers::import::import_ftm)
+                .layer(DefaultBodyLimit::max(handlers::import::MAX_IMPORT_BYTES)),
+        )
+        // Investigations
+        .route(
+            "/api/investigations",
+            get(handlers::investigations::list_investigations)
+                .post(handlers::investigations::create_investigation),
+        )
+        .route(
+            "/api/investigations/{id}",
+            get(handlers::investigations::get_investigation)
+                .delete(handlers::investigations::delete_investigation),
+        )
+        .route(
+            "/api/investigations/{id}/notes",
+            post(handlers::investigations::add_note),
+        )
+        .route(
+            "/api/investigations/{id}/timeline",
+            get(handlers::investigations::get_timeline).layer(cache_control(http_cache::NO_STORE)),
+        )
+        // Subscriptions
+        .route(
+            "/api/subscriptions",
+            get(handlers::subscriptions::list_subscriptions),
+        )
+        .route(
+            "/api/subscriptions/{id}",
+            delete(handlers::subscriptions::delete_subscription),
+        )
+        .route(
+            "/api/notifications",
+            get(handlers::subscriptions::list_notifications)
+                .layer(cache_control(http_cache::NO_STORE)),
+        )
+        // Feedback
+        .route(
+            "/api/feedback",
+            get(handlers::feedback::list_feedback).post(handlers::feedback::submit_feedback),
+        )
+        .route(
+            "/api/feedback/calibration",
+            get(handlers::feedback::get_calibration),
+        )
+        .route(
+            "/api/feedback/{id}",
+            delete(handlers::feedback::delete_feedback),
+        )
         // Graph
         .route("/api/graph/query", post(handlers::graph::query_graph))
-        .route("/api/graph/stats", get(handlers::graph::graph_stats))
-        .route("/api/graph/neighbors/{id}", get(handlers::graph::get_neighbors))
+        .route(
+            "/api/graph/stats",
+            get(handlers::graph::graph_stats).layer(cache_control(http_cache::GRAPH_STATS)),
+        )
+        .route(
+            "/api/graph/neighbors/{id}",
+            get(handlers::graph::get_neighbors).layer(cache_control(http_cache::SUBGRAPH)),
+        )
+        .route(
+            "/api/graph/paths",
+            get(handlers::graph::find_paths).layer(cache_control(http_cache::SUBGRAPH)),
+        )
+        // Relationships
+        .route(
+            "/api/relationships",
+            get(handlers::relationships::list_relationships),
+        )
         // Reasoning
-        .route("/api/reasoning/query", post(handlers::reasoning::query_reasoning))
+        .route(
+            "/api/reasoning/query",
+            post(handlers::reasoning::query_reasoning),
+        )
+        .route(
+            "/api/reasoning/templates",
+            get(handlers::reasoning::list_templates),
+        )
+        .route(
+            "/api/briefing",
+            get(handlers::reasoning::get_briefing).layer(cache_control(http_cache::NO_STORE)),
+        )
         // Timeline
         .route("/api/timeline", post(handlers::entities::get_timeline))
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn routes_do_not_conflict() {
+        // axum panics on overlapping paths when the router is built
+        let _ = super::create_router();
+    }
+}
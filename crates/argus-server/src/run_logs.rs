@@ -0,0 +1,245 @@
+//! Log lines kept per agent run, for `GET /api/agents/runs/{id}/logs`.
+//!
+//! [`RunLogLayer`] records every event inside a span with a `run_id` field,
+//! or carrying a `run_id` field itself, into a [`RunLogBuffer`]. The buffer
+//! is in memory and per instance: a run's lines are on the replica that
+//! executed it, and only the most recent runs are kept.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use argus_core::api_types::RunLogLine;
+use argus_core::shared::MAX_RUN_HISTORY;
+
+/// Lines kept per run; older ones are dropped first.
+pub const MAX_LINES_PER_RUN: usize = 2_000;
+
+#[derive(Default)]
+struct RunLog {
+    lines: VecDeque<RunLogLine>,
+    dropped: u64,
+}
+
+#[derive(Default)]
+struct Runs {
+    logs: HashMap<String, RunLog>,
+    /// Run ids oldest first, for evicting whole runs.
+    order: VecDeque<String>,
+}
+
+/// The most recent [`MAX_RUN_HISTORY`] runs' log lines.
+#[derive(Default)]
+pub struct RunLogBuffer {
+    runs: Mutex<Runs>,
+}
+
+impl RunLogBuffer {
+    pub fn record(&self, run_id: &str, line: RunLogLine) {
+        let mut runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+        if !runs.logs.contains_key(run_id) {
+            if runs.order.len() >= MAX_RUN_HISTORY {
+                if let Some(oldest) = runs.order.pop_front() {
+                    runs.logs.remove(&oldest);
+                }
+            }
+            runs.order.push_back(run_id.to_string());
+        }
+        let log = runs.logs.entry(run_id.to_string()).or_default();
+        if log.lines.len() >= MAX_LINES_PER_RUN {
+            log.lines.pop_front();
+            log.dropped += 1;
+        }
+        log.lines.push_back(line);
+    }
+
+    /// A run's lines oldest first, and how many earlier ones were dropped.
+    pub fn get(&self, run_id: &str) -> Option<(Vec<RunLogLine>, u64)> {
+        let runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+        runs.logs
+            .get(run_id)
+            .map(|log| (log.lines.iter().cloned().collect(), log.dropped))
+    }
+}
+
+/// The run a span belongs to, stored in its extensions.
+struct RunId(String);
+
+/// Copies events that belong to a run into a [`RunLogBuffer`].
+pub struct RunLogLayer {
+    buffer: Arc<RunLogBuffer>,
+}
+
+impl RunLogLayer {
+    pub fn new(buffer: Arc<RunLogBuffer>) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S> Layer<S> for RunLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = FieldVisitor::default();
+        attrs.record(&mut fields);
+        if let (Some(serde_json::Value::String(run_id)), Some(span)) =
+            (fields.fields.remove("run_id"), ctx.span(id))
+        {
+            span.extensions_mut().insert(RunId(run_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let spans: Vec<_> = ctx
+            .event_scope(event)
+            .map(|scope| scope.from_root().collect())
+            .unwrap_or_default();
+        let mut fields = FieldVisitor::default();
+        event.record(&mut fields);
+
+        // The innermost run span wins, so a retry's lines go to the retry
+        // even when they mention the run being retried
+        let run_id = spans
+            .iter()
+            .rev()
+            .find_map(|span| span.extensions().get::<RunId>().map(|r| r.0.clone()))
+            .or_else(|| match fields.fields.get("run_id") {
+                Some(serde_json::Value::String(run_id)) => Some(run_id.clone()),
+                _ => None,
+            });
+        let Some(run_id) = run_id else {
+            return;
+        };
+
+        let metadata = event.metadata();
+        self.buffer.record(
+            &run_id,
+            RunLogLine {
+                timestamp: Utc::now(),
+                level: metadata.level().to_string(),
+                target: metadata.target().to_string(),
+                message: fields.message.unwrap_or_default(),
+                spans: spans.iter().map(|span| span.name().to_string()).collect(),
+                fields: fields.fields,
+            },
+        );
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: BTreeMap<String, serde_json::Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        if field.name() == "message" {
+            self.message = Some(match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            });
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{value:?}").into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::{info, info_span, warn};
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn events_are_kept_under_their_run() {
+        let buffer = Arc::new(RunLogBuffer::default());
+        let subscriber = tracing_subscriber::registry().with(RunLogLayer::new(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let run = info_span!("agent_run", run_id = "run-1", agent = "gdelt");
+            let _run = run.enter();
+            let extract = info_span!("extract");
+            let _extract = extract.enter();
+            warn!(documents = 3, "Extraction failed");
+            drop(_extract);
+            {
+                let retry = info_span!("retry", run_id = "run-2");
+                let _retry = retry.enter();
+                info!(run_id = "run-1", "Retrying");
+            }
+            drop(_run);
+            info!(run_id = "run-3", "Outside any span");
+            info!("Not part of a run");
+        });
+
+        let (lines, dropped) = buffer.get("run-1").unwrap();
+        assert_eq!(dropped, 0);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].level, "WARN");
+        assert_eq!(lines[0].message, "Extraction failed");
+        assert_eq!(lines[0].spans, ["agent_run", "extract"]);
+        assert_eq!(lines[0].fields["documents"], 3);
+        assert_eq!(buffer.get("run-2").unwrap().0[0].message, "Retrying");
+        assert_eq!(buffer.get("run-3").unwrap().0.len(), 1);
+    }
+
+    #[test]
+    fn old_lines_and_runs_are_evicted() {
+        let buffer = RunLogBuffer::default();
+        let line = |message: &str| RunLogLine {
+            timestamp: Utc::now(),
+            level: "INFO".to_string(),
+            target: "argus".to_string(),
+            message: message.to_string(),
+            spans: vec![],
+            fields: BTreeMap::new(),
+        };
+        for i in 0..MAX_LINES_PER_RUN + 5 {
+            buffer.record("busy", line(&i.to_string()));
+        }
+        let (lines, dropped) = buffer.get("busy").unwrap();
+        assert_eq!((lines.len(), dropped), (MAX_LINES_PER_RUN, 5));
+        assert_eq!(lines[0].message, "5");
+
+        for i in 0..MAX_RUN_HISTORY {
+            buffer.record(&format!("run-{i}"), line("started"));
+        }
+        assert!(buffer.get("busy").is_none());
+        assert!(buffer.get("run-0").is_some());
+    }
+}
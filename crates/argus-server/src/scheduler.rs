@@ -1,242 +1,325 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
 use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::Utc;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 use uuid::Uuid;
 
 use argus_core::api_types::{AgentRunState, AgentRunStatus};
-use argus_core::{Agent, ExtractionPipeline, GraphStore};
+use argus_core::{
+    Agent, AppConfig, ExtractionPipeline, GraphStore, RawDocument, SchemaDrift, ValidationReport,
+};
 
+use crate::pipeline::{PipelineError, PipelineOutcome};
+use crate::shared::{insert_run, run_lock_key, update_run};
 use crate::state::AppState;
 
 /// Schedule configuration for each agent.
 struct AgentSchedule {
     name: &'static str,
     interval: Duration,
-    requires_env: Option<&'static str>,
 }
 
 const SCHEDULES: &[AgentSchedule] = &[
     AgentSchedule {
         name: "gdelt",
         interval: Duration::from_secs(15 * 60), // 15 min
-        requires_env: None,
     },
     AgentSchedule {
         name: "adsb",
         interval: Duration::from_secs(5 * 60), // 5 min
-        requires_env: None,
     },
     AgentSchedule {
         name: "opencorporates",
         interval: Duration::from_secs(60 * 60), // 1 hour
-        requires_env: None,
     },
     AgentSchedule {
         name: "opensanctions",
         interval: Duration::from_secs(6 * 60 * 60), // 6 hours
-        requires_env: None,
     },
     AgentSchedule {
         name: "eu_transparency",
         interval: Duration::from_secs(24 * 60 * 60), // 24 hours
-        requires_env: None,
     },
     AgentSchedule {
         name: "ais",
         interval: Duration::from_secs(5 * 60), // 5 min
-        requires_env: Some("AISHUB_API_KEY"),
+    },
+    AgentSchedule {
+        name: "synthetic",
+        interval: Duration::from_secs(60), // 1 min
     },
 ];
 
+/// Minimum wait before any agent's first collection, so the server is up.
+const STARTUP_DELAY: Duration = Duration::from_secs(10);
+
+/// A random duration below `max`, different per process and per call.
+fn jitter(max: Duration) -> Duration {
+    let max_ms = max.as_millis() as u64;
+    if max_ms == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(RandomState::new().hash_one(Utc::now()) % max_ms)
+}
+
+/// How long `agent` waits before its first run: the startup delay, its
+/// configured phase offset and a random start jitter.
+fn start_delay(agent: &str, config: &AppConfig) -> Duration {
+    let offset = config.agent_phase_offsets.get(agent).copied().unwrap_or(0);
+    STARTUP_DELAY
+        + Duration::from_secs(offset)
+        + jitter(Duration::from_secs(config.scheduler_start_jitter_seconds))
+}
+
 /// Main scheduler loop. Spawns one task per agent, each running on its own interval.
 pub async fn run_scheduler(state: AppState) {
     info!("Starting background scheduler");
 
-    // Give the server a moment to start up before first collection
-    tokio::time::sleep(Duration::from_secs(10)).await;
+    // Flush buffered graph writes that never fill a whole batch
+    tokio::spawn(crate::write_buffer::run_flusher(state.writes.clone()));
 
     for schedule in SCHEDULES {
-        // Skip agents that require an env var that isn't set
-        if let Some(env_var) = schedule.requires_env {
-            if std::env::var(env_var).is_err() {
-                info!(
-                    agent = schedule.name,
-                    env_var = env_var,
-                    "Skipping scheduled agent (env var not set)"
-                );
-                continue;
-            }
-        }
-
         let agent = match state.agents.get(schedule.name) {
             Some(a) => a.clone(),
             None => {
-                warn!(agent = schedule.name, "Scheduled agent not found in registry");
+                warn!(
+                    agent = schedule.name,
+                    "Scheduled agent not found in registry"
+                );
                 continue;
             }
         };
 
-        let interval = schedule.interval;
-        let agent_name = schedule.name.to_string();
-        let extraction = state.extraction.clone();
-        let graph = state.graph.clone();
-        let runs = state.runs.clone();
-        let all_agents: Vec<(String, Arc<dyn Agent>)> = state
-            .agents
+        // Skip agents that require an env var that isn't set
+        if let Some(env_var) = agent
+            .required_env()
             .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
+            .find(|v| std::env::var(v).is_err())
+        {
+            info!(
+                agent = schedule.name,
+                env_var = env_var,
+                "Skipping scheduled agent (env var not set)"
+            );
+            continue;
+        }
+
+        let default_interval = schedule.interval;
+        let agent_name = schedule.name.to_string();
+        let loop_state = state.clone();
+        let config = state.config();
+        let delay = start_delay(schedule.name, &config);
 
         tokio::spawn(async move {
-            agent_loop(
-                agent_name,
-                agent,
-                interval,
-                extraction,
-                graph,
-                runs,
-                all_agents,
-            )
-            .await;
+            // Staggered so agents sharing an interval don't collect,
+            // extract and write to Neo4j at the same moment
+            tokio::time::sleep(delay).await;
+            agent_loop(loop_state, agent_name, agent, default_interval).await;
         });
 
         info!(
             agent = schedule.name,
-            interval_secs = schedule.interval.as_secs(),
+            interval_secs = run_interval(schedule.name, default_interval, &config).as_secs(),
+            start_delay_secs = delay.as_secs(),
             "Scheduled agent"
         );
     }
 }
 
-/// Run a single agent in a loop at the given interval.
+/// How long the scheduler waits between runs of `agent`; `None` for agents
+/// it does not run.
+pub fn scheduled_interval(agent: &str, config: &AppConfig) -> Option<Duration> {
+    SCHEDULES
+        .iter()
+        .find(|s| s.name == agent)
+        .map(|s| run_interval(agent, s.interval, config))
+}
+
+/// How long `agent` waits between runs: its `AGENT_INTERVALS` entry, or
+/// `default` from its schedule.
+fn run_interval(agent: &str, default: Duration, config: &AppConfig) -> Duration {
+    config
+        .agent_intervals
+        .get(agent)
+        .map_or(default, |&secs| Duration::from_secs(secs.max(1)))
+}
+
+/// Run a single agent in a loop, re-reading its interval and run lock TTL
+/// before every run so reloaded settings apply.
 async fn agent_loop(
+    state: AppState,
     agent_name: String,
     agent: Arc<dyn Agent>,
-    interval: Duration,
-    extraction: Arc<argus_extraction::LlmExtractionPipeline>,
-    graph: Arc<argus_graph::Neo4jGraphStore>,
-    runs: Arc<tokio::sync::RwLock<Vec<AgentRunStatus>>>,
-    all_agents: Vec<(String, Arc<dyn Agent>)>,
+    default_interval: Duration,
 ) {
-    loop {
-        let run_id = Uuid::new_v4().to_string();
+    let lock_key = format!("scheduler:{agent_name}");
+    let run_lock = run_lock_key(&agent_name);
 
-        let run_status = AgentRunStatus {
-            run_id: run_id.clone(),
-            agent_name: agent_name.clone(),
-            status: AgentRunState::Running,
-            started_at: Utc::now(),
-            finished_at: None,
-            documents_collected: 0,
-            entities_extracted: 0,
-            error: None,
-        };
+    loop {
+        let config = state.config();
+        let interval = run_interval(&agent_name, default_interval, &config);
+        let run_lock_ttl = Duration::from_secs(config.agent_run_lock_ttl_seconds);
 
-        {
-            let mut runs_lock = runs.write().await;
-            runs_lock.push(run_status);
-            // Keep only the last 100 runs to avoid unbounded growth
-            if runs_lock.len() > 100 {
-                let drain_count = runs_lock.len() - 100;
-                runs_lock.drain(0..drain_count);
+        match state.pauses.is_paused(&agent_name).await {
+            Ok(true) => {
+                debug!(agent = %agent_name, "Agent paused, skipping interval");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!(agent = %agent_name, error = %e, "Pause state unavailable, running anyway")
             }
         }
 
-        info!(agent = %agent_name, run_id = %run_id, "Scheduled collection starting");
-
-        // Step 1: Collect
-        let documents = match agent.collect().await {
-            Ok(docs) => {
-                info!(agent = %agent_name, count = docs.len(), "Collection complete");
-                docs
+        // Holding the lock for a full interval means only one replica
+        // collects per interval; it lapses on its own if the holder dies.
+        match state
+            .locks
+            .try_acquire(&lock_key, &state.instance_id, interval)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!(agent = %agent_name, "Another replica owns this collection interval, skipping");
+                tokio::time::sleep(interval).await;
+                continue;
             }
             Err(e) => {
-                error!(agent = %agent_name, error = %e, "Collection failed");
-                update_run(&runs, &run_id, AgentRunState::Failed, 0, 0, Some(e.to_string())).await;
+                warn!(agent = %agent_name, error = %e, "Scheduler lock unavailable, skipping run");
                 tokio::time::sleep(interval).await;
                 continue;
             }
-        };
-
-        let doc_count = documents.len() as u64;
-
-        if documents.is_empty() {
-            update_run(&runs, &run_id, AgentRunState::Completed, 0, 0, None).await;
-            tokio::time::sleep(interval).await;
-            continue;
         }
 
-        // Step 2: Extract
-        let extraction_results = match extraction.extract_batch(&documents).await {
-            Ok(results) => {
-                info!(agent = %agent_name, results = results.len(), "Extraction complete");
-                results
+        let run_id = Uuid::new_v4().to_string();
+
+        // A run triggered through the API may still be in progress
+        match state
+            .locks
+            .try_acquire(&run_lock, &run_id, run_lock_ttl)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                info!(agent = %agent_name, "Previous run still in progress, skipping interval");
+                tokio::time::sleep(interval).await;
+                continue;
             }
             Err(e) => {
-                error!(agent = %agent_name, error = %e, "Extraction failed");
-                update_run(&runs, &run_id, AgentRunState::Failed, doc_count, 0, Some(e.to_string())).await;
+                warn!(agent = %agent_name, error = %e, "Run lock unavailable, skipping run");
                 tokio::time::sleep(interval).await;
                 continue;
             }
-        };
-
-        let entity_count: u64 = extraction_results
-            .iter()
-            .map(|r| r.entities.len() as u64)
-            .sum();
-
-        // Step 3: Store
-        let mut store_errors = 0;
-        for result in &extraction_results {
-            if let Err(e) = graph.store_extraction(result).await {
-                error!(agent = %agent_name, error = %e, "Failed to store extraction result");
-                store_errors += 1;
-            }
         }
 
-        // Step 4: Cross-reference new entities against other agents
-        cross_reference(
-            &agent_name,
-            &extraction_results,
-            &all_agents,
-            &extraction,
-            &graph,
+        insert_run(
+            &state.runs,
+            AgentRunStatus::started(run_id.clone(), agent_name.clone()),
         )
         .await;
 
-        if store_errors > 0 {
-            update_run(
-                &runs, &run_id, AgentRunState::Completed, doc_count, entity_count,
-                Some(format!("{} storage errors", store_errors)),
-            ).await;
+        info!(agent = %agent_name, run_id = %run_id, "Scheduled collection starting");
+
+        let result =
+            crate::pipeline::run_agent_pipeline(&state, &agent_name, agent.clone(), &run_id, true)
+                .await;
+        if matches!(&result, Ok(outcome) if outcome.queued) {
+            // The worker finishes the run and releases its lock
+            info!(agent = %agent_name, run_id = %run_id, "Scheduled run handed to an extraction worker");
         } else {
-            update_run(&runs, &run_id, AgentRunState::Completed, doc_count, entity_count, None).await;
+            conclude_run(&state, &agent_name, &run_id, result, true).await;
+            release_run_lock(&state, &run_lock, &run_id).await;
         }
 
-        info!(
-            agent = %agent_name,
-            documents = doc_count,
-            entities = entity_count,
-            "Scheduled run complete, sleeping for {}s",
-            interval.as_secs()
-        );
-
+        debug!(agent = %agent_name, "Sleeping for {}s", interval.as_secs());
         tokio::time::sleep(interval).await;
     }
 }
 
+/// Record how a run that was collected ended, cross-referencing its new
+/// entities against the other agents first if `cross_reference` is set.
+/// Shared by scheduled runs and the runs extraction workers finish.
+pub(crate) async fn conclude_run(
+    state: &AppState,
+    agent_name: &str,
+    run_id: &str,
+    result: Result<PipelineOutcome, PipelineError>,
+    cross_reference: bool,
+) {
+    let outcome = match result {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            error!(agent = %agent_name, run_id = %run_id, error = %e.message, "Run failed");
+            let counts = RunCounts {
+                documents: e.documents,
+                entities: 0,
+                failed: e.failed,
+                validation: ValidationReport::default(),
+                over_quota: 0,
+                schema_drift: None,
+            };
+            finish_run(
+                state,
+                run_id,
+                AgentRunState::Failed,
+                counts,
+                Some(e.message),
+            )
+            .await;
+            return;
+        }
+    };
+
+    if cross_reference {
+        let all_agents: Vec<(String, Arc<dyn Agent>)> = state
+            .agents
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        self::cross_reference(
+            agent_name,
+            &outcome.results,
+            &all_agents,
+            &state.extraction,
+            &state.validator,
+            &state.graph,
+        )
+        .instrument(info_span!("cross_reference", run_id = %run_id))
+        .await;
+    }
+
+    let (run_state, error) = (outcome.run_state(), outcome.store_error());
+    let counts = RunCounts {
+        documents: outcome.documents,
+        entities: outcome.entities,
+        failed: outcome.failures.len() as u64,
+        validation: outcome.validation,
+        over_quota: outcome.over_quota,
+        schema_drift: outcome.schema_drift,
+    };
+    finish_run(state, run_id, run_state, counts, error).await;
+    info!(
+        agent = %agent_name,
+        run_id = %run_id,
+        documents = outcome.documents,
+        entities = outcome.entities,
+        "Run complete"
+    );
+}
+
 /// Cross-reference newly extracted entities against other agents' lookup capabilities.
 async fn cross_reference(
     source_agent: &str,
     extraction_results: &[argus_core::ExtractionResult],
     all_agents: &[(String, Arc<dyn Agent>)],
-    extraction: &Arc<argus_extraction::LlmExtractionPipeline>,
+    extraction: &Arc<argus_extraction::PipelineRouter>,
+    validator: &argus_extraction::Validator,
     graph: &Arc<argus_graph::Neo4jGraphStore>,
 ) {
-    use argus_core::agent::AgentLookup;
-
     for result in extraction_results {
         for entity in &result.entities {
             for (name, agent) in all_agents {
@@ -246,24 +329,8 @@ async fn cross_reference(
                 }
 
                 // Check if this agent supports lookup for this entity type
-                let lookup: &dyn AgentLookup = match agent.as_any().downcast_ref::<argus_agents::GdeltAgent>() {
-                    Some(a) => a as &dyn AgentLookup,
-                    None => match agent.as_any().downcast_ref::<argus_agents::OpenSanctionsAgent>() {
-                        Some(a) => a as &dyn AgentLookup,
-                        None => match agent.as_any().downcast_ref::<argus_agents::OpenCorporatesAgent>() {
-                            Some(a) => a as &dyn AgentLookup,
-                            None => match agent.as_any().downcast_ref::<argus_agents::AdsbAgent>() {
-                                Some(a) => a as &dyn AgentLookup,
-                                None => match agent.as_any().downcast_ref::<argus_agents::AisAgent>() {
-                                    Some(a) => a as &dyn AgentLookup,
-                                    None => match agent.as_any().downcast_ref::<argus_agents::EuTransparencyAgent>() {
-                                        Some(a) => a as &dyn AgentLookup,
-                                        None => continue,
-                                    },
-                                },
-                            },
-                        },
-                    },
+                let Some(lookup) = agent.as_lookup() else {
+                    continue;
                 };
 
                 if !lookup.can_lookup(&entity.entity_type) {
@@ -277,7 +344,7 @@ async fn cross_reference(
                     "Cross-referencing entity"
                 );
 
-                match lookup.lookup(&entity.name, &entity.entity_type).await {
+                match lookup.lookup_entity(entity).await {
                     Ok(docs) if !docs.is_empty() => {
                         info!(
                             entity = %entity.name,
@@ -286,10 +353,24 @@ async fn cross_reference(
                             "Cross-reference found documents"
                         );
 
+                        // Structured lookups (sanctions matches) are stored as they are
+                        let (structured, docs): (Vec<RawDocument>, Vec<RawDocument>) =
+                            docs.into_iter().partition(|d| d.structured.is_some());
+                        for mut r in structured.into_iter().filter_map(|d| d.structured) {
+                            validator.validate(&mut r);
+                            if let Err(e) = graph.store_extraction(&r).await {
+                                warn!(error = %e, "Failed to store cross-reference match");
+                            }
+                        }
+                        if docs.is_empty() {
+                            continue;
+                        }
+
                         match extraction.extract_batch(&docs).await {
                             Ok(results) => {
-                                for r in &results {
-                                    if let Err(e) = graph.store_extraction(r).await {
+                                for mut r in results {
+                                    validator.validate(&mut r);
+                                    if let Err(e) = graph.store_extraction(&r).await {
                                         warn!(
                                             error = %e,
                                             "Failed to store cross-reference extraction"
@@ -320,20 +401,75 @@ async fn cross_reference(
     }
 }
 
-async fn update_run(
-    runs: &Arc<tokio::sync::RwLock<Vec<AgentRunStatus>>>,
+struct RunCounts {
+    documents: u64,
+    entities: u64,
+    failed: u64,
+    validation: ValidationReport,
+    over_quota: u64,
+    schema_drift: Option<SchemaDrift>,
+}
+
+async fn finish_run(
+    state: &AppState,
     run_id: &str,
     status: AgentRunState,
-    docs: u64,
-    entities: u64,
+    counts: RunCounts,
     error: Option<String>,
 ) {
-    let mut runs_lock = runs.write().await;
-    if let Some(run) = runs_lock.iter_mut().find(|r| r.run_id == run_id) {
+    update_run(&state.runs, run_id, |run| {
         run.status = status;
         run.finished_at = Some(Utc::now());
-        run.documents_collected = docs;
-        run.entities_extracted = entities;
+        run.documents_collected = counts.documents;
+        run.entities_extracted = counts.entities;
+        run.documents_failed = counts.failed;
+        run.validation = counts.validation;
+        run.documents_over_quota = counts.over_quota;
+        run.schema_drift = counts.schema_drift;
         run.error = error;
+    })
+    .await;
+}
+
+async fn release_run_lock(state: &AppState, key: &str, run_id: &str) {
+    if let Err(e) = state.locks.release(key, run_id).await {
+        warn!(run_id, error = %e, "Failed to release run lock");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_delay_adds_phase_offset_and_bounded_jitter() {
+        let mut config = AppConfig::from_env();
+        config.scheduler_start_jitter_seconds = 30;
+        config.agent_phase_offsets.insert("ais".to_string(), 120);
+
+        for _ in 0..20 {
+            let ais = start_delay("ais", &config);
+            assert!(ais >= STARTUP_DELAY + Duration::from_secs(120));
+            assert!(ais < STARTUP_DELAY + Duration::from_secs(150));
+
+            let gdelt = start_delay("gdelt", &config);
+            assert!(gdelt >= STARTUP_DELAY && gdelt < STARTUP_DELAY + Duration::from_secs(30));
+        }
+
+        config.scheduler_start_jitter_seconds = 0;
+        assert_eq!(start_delay("gdelt", &config), STARTUP_DELAY);
+    }
+
+    #[test]
+    fn configured_intervals_override_the_schedule() {
+        let mut config = AppConfig::from_env();
+        config.agent_intervals.insert("gdelt".to_string(), 600);
+        let default = Duration::from_secs(900);
+
+        assert_eq!(
+            run_interval("gdelt", default, &config),
+            Duration::from_secs(600)
+        );
+        assert_eq!(run_interval("adsb", default, &config), default);
     }
 }
@@ -0,0 +1,162 @@
+//! Browser-facing response policy: CORS from configuration and standard
+//! security headers on every response.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+use tracing::warn;
+
+use argus_core::AppConfig;
+
+/// Parse each configured value, skipping (with a warning) any that do not
+/// parse. `None` when the list is `*`.
+fn parse_list<T>(
+    setting: &str,
+    values: &[String],
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<Vec<T>> {
+    if values.iter().any(|v| v == "*") {
+        return None;
+    }
+    Some(
+        values
+            .iter()
+            .filter_map(|value| {
+                let parsed = parse(value);
+                if parsed.is_none() {
+                    warn!(setting, value = %value, "Ignoring invalid CORS setting");
+                }
+                parsed
+            })
+            .collect(),
+    )
+}
+
+fn allowed_origins(config: &AppConfig) -> Option<Vec<HeaderValue>> {
+    parse_list(
+        "CORS_ALLOWED_ORIGINS",
+        &config.cors_allowed_origins,
+        |origin| HeaderValue::from_str(origin.trim_end_matches('/')).ok(),
+    )
+}
+
+/// The CORS policy in `CORS_ALLOWED_ORIGINS`, `CORS_ALLOWED_METHODS` and
+/// `CORS_ALLOWED_HEADERS`. An empty origin list allows no cross-origin calls.
+pub fn cors_layer(config: &AppConfig) -> CorsLayer {
+    let origins = match allowed_origins(config) {
+        Some(origins) => AllowOrigin::list(origins),
+        None => AllowOrigin::any(),
+    };
+    let methods = match parse_list("CORS_ALLOWED_METHODS", &config.cors_allowed_methods, |m| {
+        Method::from_bytes(m.to_ascii_uppercase().as_bytes()).ok()
+    }) {
+        Some(methods) => AllowMethods::list(methods),
+        None => AllowMethods::any(),
+    };
+    let headers = match parse_list("CORS_ALLOWED_HEADERS", &config.cors_allowed_headers, |h| {
+        HeaderName::from_bytes(h.to_ascii_lowercase().as_bytes()).ok()
+    }) {
+        Some(headers) => AllowHeaders::list(headers),
+        None => AllowHeaders::any(),
+    };
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(headers)
+}
+
+/// Headers added to every response that does not set them itself.
+pub fn security_headers(config: &AppConfig) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if config.hsts_max_age_seconds > 0 {
+        let hsts = format!("max-age={}", config.hsts_max_age_seconds);
+        headers.insert(
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(&hsts).expect("valid header"),
+        );
+    }
+    if config.security_headers {
+        headers.insert(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        );
+        headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+        headers.insert(
+            header::REFERRER_POLICY,
+            HeaderValue::from_static("no-referrer"),
+        );
+        // Only framing: a full policy would block GraphiQL's CDN assets
+        headers.insert(
+            header::CONTENT_SECURITY_POLICY,
+            HeaderValue::from_static("frame-ancestors 'none'"),
+        );
+    }
+    headers
+}
+
+/// Middleware adding [`security_headers`], installed with
+/// `axum::middleware::from_fn_with_state`.
+pub async fn add_security_headers(
+    State(headers): State<Arc<HeaderMap>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    for (name, value) in headers.iter() {
+        response
+            .headers_mut()
+            .entry(name)
+            .or_insert_with(|| value.clone());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AppConfig {
+        AppConfig::from_lookup(|_| Err(std::env::VarError::NotPresent))
+    }
+
+    #[test]
+    fn origins_are_listed_unless_wildcarded() {
+        let mut config = config();
+        assert_eq!(
+            allowed_origins(&config),
+            Some(vec![HeaderValue::from_static("http://localhost:3000")])
+        );
+
+        config.cors_allowed_origins =
+            vec!["https://argus.example.org/".into(), "bad\norigin".into()];
+        assert_eq!(
+            allowed_origins(&config),
+            Some(vec![HeaderValue::from_static("https://argus.example.org")])
+        );
+
+        config.cors_allowed_origins = vec!["*".into()];
+        assert_eq!(allowed_origins(&config), None);
+    }
+
+    #[test]
+    fn security_headers_follow_the_config() {
+        let mut config = config();
+        let headers = security_headers(&config);
+        assert_eq!(
+            headers[header::STRICT_TRANSPORT_SECURITY],
+            "max-age=31536000"
+        );
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+
+        config.hsts_max_age_seconds = 0;
+        config.security_headers = false;
+        assert!(security_headers(&config).is_empty());
+    }
+}
@@ -0,0 +1,1432 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::Instant;
+
+use argus_core::alerts::{Alert, AlertRule, AlertStatus, MAX_ALERTS};
+use argus_core::api_types::AgentRunStatus;
+use argus_core::duplicates::{sort_candidates, DuplicateCandidate};
+use argus_core::extraction::{ExtractionFailure, MAX_EXTRACTION_FAILURES};
+use argus_core::prompts::{FewShotExample, PromptBundle, PromptDeployment};
+use argus_core::quota::{COUNTER_TTL_SECS, MAX_SPILLOVER_PER_SOURCE};
+use argus_core::shared::{
+    AgentPauseStore, AlertRuleStore, AlertStore, ClaimedJob, DedupStore, DenyListStore,
+    DuplicateReviewQueue, ExtractionFailureStore, ExtractionJob, ExtractionQueue, FailedDocument,
+    FeedbackStore, IdempotencyStore, InvestigationStore, LockManager, NotificationStore,
+    PromptBundleStore, QuotaStore, RateLimiter, RetryQueue, RunStore, SpilloverQueue,
+    SubscriptionStore, UsageStore, MAX_RUN_HISTORY,
+};
+use argus_core::subscriptions::{Notification, Subscription, MAX_NOTIFICATIONS};
+use argus_core::usage::{oldest_kept_day, tracked_consumer, UsageCounts, UsageRecord};
+use argus_core::{ArgusError, DenyEntry, FeedbackLabel, Investigation, RawDocument, Result};
+use uuid::Uuid;
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Run history in a `Vec`, oldest first.
+#[derive(Default)]
+pub struct MemoryRunStore {
+    runs: RwLock<Vec<AgentRunStatus>>,
+}
+
+#[async_trait]
+impl RunStore for MemoryRunStore {
+    async fn insert(&self, run: AgentRunStatus) -> Result<()> {
+        let mut runs = self.runs.write().await;
+        runs.push(run);
+        if runs.len() > MAX_RUN_HISTORY {
+            let drain_count = runs.len() - MAX_RUN_HISTORY;
+            runs.drain(0..drain_count);
+        }
+        Ok(())
+    }
+
+    async fn save(&self, run: &AgentRunStatus) -> Result<()> {
+        let mut runs = self.runs.write().await;
+        if let Some(existing) = runs.iter_mut().find(|r| r.run_id == run.run_id) {
+            *existing = run.clone();
+        }
+        Ok(())
+    }
+
+    async fn get(&self, run_id: &str) -> Result<Option<AgentRunStatus>> {
+        let runs = self.runs.read().await;
+        Ok(runs.iter().find(|r| r.run_id == run_id).cloned())
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<AgentRunStatus>> {
+        let runs = self.runs.read().await;
+        Ok(runs.iter().rev().take(limit).cloned().collect())
+    }
+}
+
+/// Seen keys with their expiry; expired keys are swept on insert.
+#[derive(Default)]
+pub struct MemoryDedupStore {
+    seen: Mutex<HashMap<String, Instant>>,
+}
+
+#[async_trait]
+impl DedupStore for MemoryDedupStore {
+    async fn first_seen(&self, key: &str, ttl: Duration) -> Result<bool> {
+        let now = Instant::now();
+        let mut seen = self.seen.lock().await;
+        seen.retain(|_, expires| *expires > now);
+        if seen.contains_key(key) {
+            return Ok(false);
+        }
+        seen.insert(key.to_string(), now + ttl);
+        Ok(true)
+    }
+}
+
+/// Per-key request counts for the current one-minute window.
+#[derive(Default)]
+pub struct MemoryRateLimiter {
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+#[async_trait]
+impl RateLimiter for MemoryRateLimiter {
+    async fn acquire(&self, key: &str, per_minute: u32) -> Result<()> {
+        let per_minute = per_minute.max(1);
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut windows = self.windows.lock().await;
+                let (start, count) = windows.entry(key.to_string()).or_insert((now, 0));
+                if now.duration_since(*start) >= WINDOW {
+                    *start = now;
+                    *count = 0;
+                }
+                if *count < per_minute {
+                    *count += 1;
+                    return Ok(());
+                }
+                WINDOW.saturating_sub(now.duration_since(*start))
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Lock owners with their expiry.
+#[derive(Default)]
+pub struct MemoryLockManager {
+    locks: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+#[async_trait]
+impl LockManager for MemoryLockManager {
+    async fn try_acquire(&self, key: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        let now = Instant::now();
+        let mut locks = self.locks.lock().await;
+        match locks.get(key) {
+            Some((holder, expires)) if holder != owner && *expires > now => Ok(false),
+            _ => {
+                locks.insert(key.to_string(), (owner.to_string(), now + ttl));
+                Ok(true)
+            }
+        }
+    }
+
+    async fn release(&self, key: &str, owner: &str) -> Result<()> {
+        let mut locks = self.locks.lock().await;
+        if locks.get(key).is_some_and(|(holder, _)| holder == owner) {
+            locks.remove(key);
+        }
+        Ok(())
+    }
+
+    async fn holder(&self, key: &str) -> Result<Option<String>> {
+        let locks = self.locks.lock().await;
+        Ok(locks
+            .get(key)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(holder, _)| holder.clone()))
+    }
+}
+
+/// Idempotency keys with the run they map to and their expiry.
+#[derive(Default)]
+pub struct MemoryIdempotencyStore {
+    keys: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn claim(&self, key: &str, run_id: &str, ttl: Duration) -> Result<Option<String>> {
+        let now = Instant::now();
+        let mut keys = self.keys.lock().await;
+        keys.retain(|_, (_, expires)| *expires > now);
+        if let Some((existing, _)) = keys.get(key) {
+            return Ok(Some(existing.clone()));
+        }
+        keys.insert(key.to_string(), (run_id.to_string(), now + ttl));
+        Ok(None)
+    }
+
+    async fn forget(&self, key: &str) -> Result<()> {
+        self.keys.lock().await.remove(key);
+        Ok(())
+    }
+}
+
+/// Failed documents grouped by run. Only the newest [`MAX_RUN_HISTORY`]
+/// runs keep their queue, matching the run history.
+#[derive(Default)]
+pub struct MemoryRetryQueue {
+    inner: Mutex<RetryQueueInner>,
+}
+
+#[derive(Default)]
+struct RetryQueueInner {
+    order: VecDeque<String>,
+    runs: HashMap<String, Vec<FailedDocument>>,
+}
+
+#[async_trait]
+impl RetryQueue for MemoryRetryQueue {
+    async fn record(&self, failed: FailedDocument) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        if !inner.runs.contains_key(&failed.run_id) {
+            inner.order.push_back(failed.run_id.clone());
+            while inner.order.len() > MAX_RUN_HISTORY {
+                if let Some(oldest) = inner.order.pop_front() {
+                    inner.runs.remove(&oldest);
+                }
+            }
+        }
+        let entries = inner.runs.entry(failed.run_id.clone()).or_default();
+        entries.retain(|f| f.document.source_id != failed.document.source_id);
+        entries.push(failed);
+        Ok(())
+    }
+
+    async fn for_run(&self, run_id: &str) -> Result<Vec<FailedDocument>> {
+        let inner = self.inner.lock().await;
+        Ok(inner.runs.get(run_id).cloned().unwrap_or_default())
+    }
+
+    async fn remove(&self, run_id: &str, source_id: &str) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        if let Some(entries) = inner.runs.get_mut(run_id) {
+            entries.retain(|f| f.document.source_id != source_id);
+        }
+        Ok(())
+    }
+}
+
+/// Paused agent names, mirrored to a JSON file (when given a path) so a
+/// restart picks them back up.
+#[derive(Default)]
+pub struct MemoryAgentPauseStore {
+    paused: RwLock<BTreeSet<String>>,
+    path: Option<PathBuf>,
+}
+
+impl MemoryAgentPauseStore {
+    /// Load previously paused agents from `path`. A missing or unreadable
+    /// file starts with nothing paused.
+    pub fn persistent(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let paused = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "Ignoring unreadable paused agents file");
+                BTreeSet::new()
+            }),
+            Err(_) => BTreeSet::new(),
+        };
+        Self {
+            paused: RwLock::new(paused),
+            path: Some(path),
+        }
+    }
+
+    async fn persist(&self, paused: &BTreeSet<String>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await.map_err(|e| {
+                ArgusError::Internal(format!("Failed to create {}: {e}", dir.display()))
+            })?;
+        }
+        tokio::fs::write(path, serde_json::to_vec(paused)?)
+            .await
+            .map_err(|e| ArgusError::Internal(format!("Failed to write {}: {e}", path.display())))
+    }
+}
+
+#[async_trait]
+impl AgentPauseStore for MemoryAgentPauseStore {
+    async fn set_paused(&self, agent_name: &str, paused: bool) -> Result<()> {
+        let mut names = self.paused.write().await;
+        let changed = if paused {
+            names.insert(agent_name.to_string())
+        } else {
+            names.remove(agent_name)
+        };
+        if changed {
+            self.persist(&names).await?;
+        }
+        Ok(())
+    }
+
+    async fn is_paused(&self, agent_name: &str) -> Result<bool> {
+        Ok(self.paused.read().await.contains(agent_name))
+    }
+
+    async fn paused(&self) -> Result<BTreeSet<String>> {
+        Ok(self.paused.read().await.clone())
+    }
+}
+
+/// The duplicate review queue, sorted by score, and dismissed pair ids.
+#[derive(Default)]
+pub struct MemoryDuplicateReviewQueue {
+    inner: RwLock<DuplicateQueueInner>,
+}
+
+#[derive(Default)]
+struct DuplicateQueueInner {
+    candidates: Vec<DuplicateCandidate>,
+    dismissed: HashSet<String>,
+}
+
+#[async_trait]
+impl DuplicateReviewQueue for MemoryDuplicateReviewQueue {
+    async fn replace(&self, mut candidates: Vec<DuplicateCandidate>) -> Result<()> {
+        let mut inner = self.inner.write().await;
+        candidates.retain(|c| !inner.dismissed.contains(&c.id));
+        sort_candidates(&mut candidates);
+        inner.candidates = candidates;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<DuplicateCandidate>> {
+        Ok(self.inner.read().await.candidates.clone())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<DuplicateCandidate>> {
+        Ok(self
+            .inner
+            .read()
+            .await
+            .candidates
+            .iter()
+            .find(|c| c.id == id)
+            .cloned())
+    }
+
+    async fn remove_involving(&self, entity_ids: &[Uuid]) -> Result<()> {
+        self.inner
+            .write()
+            .await
+            .candidates
+            .retain(|c| !entity_ids.iter().any(|id| c.involves(*id)));
+        Ok(())
+    }
+
+    async fn dismiss(&self, id: &str) -> Result<bool> {
+        let mut inner = self.inner.write().await;
+        let before = inner.candidates.len();
+        inner.candidates.retain(|c| c.id != id);
+        let removed = inner.candidates.len() < before;
+        if removed {
+            inner.dismissed.insert(id.to_string());
+        }
+        Ok(removed)
+    }
+}
+
+/// Alert rules in a `Vec`, oldest first.
+#[derive(Default)]
+pub struct MemoryAlertRuleStore {
+    rules: RwLock<Vec<AlertRule>>,
+}
+
+#[async_trait]
+impl AlertRuleStore for MemoryAlertRuleStore {
+    async fn save(&self, rule: &AlertRule) -> Result<()> {
+        let mut rules = self.rules.write().await;
+        match rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule.clone(),
+            None => rules.push(rule.clone()),
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<AlertRule>> {
+        Ok(self.rules.read().await.clone())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<AlertRule>> {
+        Ok(self.rules.read().await.iter().find(|r| r.id == id).cloned())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut rules = self.rules.write().await;
+        let before = rules.len();
+        rules.retain(|r| r.id != id);
+        Ok(rules.len() < before)
+    }
+}
+
+/// Deny-list entries in a `Vec`, oldest first.
+#[derive(Default)]
+pub struct MemoryDenyListStore {
+    entries: RwLock<Vec<DenyEntry>>,
+}
+
+#[async_trait]
+impl DenyListStore for MemoryDenyListStore {
+    async fn save(&self, entry: &DenyEntry) -> Result<()> {
+        let mut entries = self.entries.write().await;
+        match entries.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry.clone(),
+            None => entries.push(entry.clone()),
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<DenyEntry>> {
+        Ok(self.entries.read().await.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut entries = self.entries.write().await;
+        let before = entries.len();
+        entries.retain(|e| e.id != id);
+        Ok(entries.len() < before)
+    }
+}
+
+/// Feedback labels in a `Vec`, oldest first.
+#[derive(Default)]
+pub struct MemoryFeedbackStore {
+    labels: RwLock<Vec<FeedbackLabel>>,
+}
+
+#[async_trait]
+impl FeedbackStore for MemoryFeedbackStore {
+    async fn save(&self, label: &FeedbackLabel) -> Result<()> {
+        let mut labels = self.labels.write().await;
+        let key = label.item_key();
+        labels.retain(|l| l.item_key() != key);
+        labels.push(label.clone());
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<FeedbackLabel>> {
+        Ok(self.labels.read().await.iter().rev().cloned().collect())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut labels = self.labels.write().await;
+        let before = labels.len();
+        labels.retain(|l| l.id != id);
+        Ok(labels.len() < before)
+    }
+}
+
+/// Investigations in a `Vec`, oldest first.
+#[derive(Default)]
+pub struct MemoryInvestigationStore {
+    investigations: RwLock<Vec<Investigation>>,
+}
+
+#[async_trait]
+impl InvestigationStore for MemoryInvestigationStore {
+    async fn save(&self, investigation: &Investigation) -> Result<()> {
+        let mut investigations = self.investigations.write().await;
+        match investigations.iter_mut().find(|i| i.id == investigation.id) {
+            Some(existing) => *existing = investigation.clone(),
+            None => investigations.push(investigation.clone()),
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<Investigation>> {
+        Ok(self
+            .investigations
+            .read()
+            .await
+            .iter()
+            .rev()
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Investigation>> {
+        Ok(self
+            .investigations
+            .read()
+            .await
+            .iter()
+            .find(|i| i.id == id)
+            .cloned())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut investigations = self.investigations.write().await;
+        let before = investigations.len();
+        investigations.retain(|i| i.id != id);
+        Ok(investigations.len() < before)
+    }
+}
+
+/// Subscriptions in a `Vec`, oldest first.
+#[derive(Default)]
+pub struct MemorySubscriptionStore {
+    subscriptions: RwLock<Vec<Subscription>>,
+}
+
+#[async_trait]
+impl SubscriptionStore for MemorySubscriptionStore {
+    async fn save(&self, subscription: &Subscription) -> Result<()> {
+        let mut subscriptions = self.subscriptions.write().await;
+        match subscriptions.iter_mut().find(|s| s.id == subscription.id) {
+            Some(existing) => *existing = subscription.clone(),
+            None => subscriptions.push(subscription.clone()),
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<Subscription>> {
+        Ok(self.subscriptions.read().await.clone())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Subscription>> {
+        Ok(self
+            .subscriptions
+            .read()
+            .await
+            .iter()
+            .find(|s| s.id == id)
+            .cloned())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut subscriptions = self.subscriptions.write().await;
+        let before = subscriptions.len();
+        subscriptions.retain(|s| s.id != id);
+        Ok(subscriptions.len() < before)
+    }
+}
+
+/// Notifications oldest first, and the keys each subscription was told
+/// about.
+#[derive(Default)]
+pub struct MemoryNotificationStore {
+    inner: RwLock<NotificationInner>,
+}
+
+#[derive(Default)]
+struct NotificationInner {
+    notifications: VecDeque<Notification>,
+    seen: HashSet<(Uuid, String)>,
+}
+
+#[async_trait]
+impl NotificationStore for MemoryNotificationStore {
+    async fn record(&self, notification: Notification) -> Result<bool> {
+        let mut inner = self.inner.write().await;
+        if !inner
+            .seen
+            .insert((notification.subscription_id, notification.dedup_key.clone()))
+        {
+            return Ok(false);
+        }
+        inner.notifications.push_back(notification);
+        while inner.notifications.len() > MAX_NOTIFICATIONS {
+            inner.notifications.pop_front();
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<Notification>> {
+        Ok(self
+            .inner
+            .read()
+            .await
+            .notifications
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn save(&self, notification: &Notification) -> Result<()> {
+        let mut inner = self.inner.write().await;
+        if let Some(existing) = inner
+            .notifications
+            .iter_mut()
+            .find(|n| n.id == notification.id)
+        {
+            *existing = notification.clone();
+        }
+        Ok(())
+    }
+
+    async fn forget(&self, subscription_id: Uuid) -> Result<()> {
+        self.inner
+            .write()
+            .await
+            .seen
+            .retain(|(id, _)| *id != subscription_id);
+        Ok(())
+    }
+}
+
+/// Prompt bundles oldest first, and the deployment.
+#[derive(Default)]
+pub struct MemoryPromptBundleStore {
+    inner: RwLock<(Vec<PromptBundle>, PromptDeployment)>,
+}
+
+#[async_trait]
+impl PromptBundleStore for MemoryPromptBundleStore {
+    async fn create(
+        &self,
+        note: Option<String>,
+        examples: BTreeMap<String, Vec<FewShotExample>>,
+    ) -> Result<PromptBundle> {
+        let mut inner = self.inner.write().await;
+        let bundle = PromptBundle {
+            version: inner.0.last().map_or(1, |b| b.version + 1),
+            created_at: chrono::Utc::now(),
+            note,
+            examples,
+        };
+        inner.0.push(bundle.clone());
+        Ok(bundle)
+    }
+
+    async fn list(&self) -> Result<Vec<PromptBundle>> {
+        Ok(self.inner.read().await.0.iter().rev().cloned().collect())
+    }
+
+    async fn get(&self, version: u32) -> Result<Option<PromptBundle>> {
+        Ok(self
+            .inner
+            .read()
+            .await
+            .0
+            .iter()
+            .find(|b| b.version == version)
+            .cloned())
+    }
+
+    async fn deployment(&self) -> Result<PromptDeployment> {
+        Ok(self.inner.read().await.1.clone())
+    }
+
+    async fn set_deployment(&self, deployment: &PromptDeployment) -> Result<()> {
+        self.inner.write().await.1 = deployment.clone();
+        Ok(())
+    }
+}
+
+/// Alerts oldest first, with the dedup keys of open ones.
+#[derive(Default)]
+pub struct MemoryAlertStore {
+    inner: RwLock<AlertInner>,
+}
+
+#[derive(Default)]
+struct AlertInner {
+    alerts: VecDeque<Alert>,
+    open: HashMap<String, Uuid>,
+}
+
+#[async_trait]
+impl AlertStore for MemoryAlertStore {
+    async fn raise(&self, alert: Alert) -> Result<bool> {
+        let mut inner = self.inner.write().await;
+        if inner.open.contains_key(&alert.dedup_key) {
+            return Ok(false);
+        }
+        inner.open.insert(alert.dedup_key.clone(), alert.id);
+        inner.alerts.push_back(alert);
+        while inner.alerts.len() > MAX_ALERTS {
+            if let Some(dropped) = inner.alerts.pop_front() {
+                if inner.open.get(&dropped.dedup_key) == Some(&dropped.id) {
+                    inner.open.remove(&dropped.dedup_key);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, status: AlertStatus, limit: usize) -> Result<Vec<Alert>> {
+        let inner = self.inner.read().await;
+        Ok(inner
+            .alerts
+            .iter()
+            .rev()
+            .filter(|a| status.matches(a))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Alert>> {
+        Ok(self
+            .inner
+            .read()
+            .await
+            .alerts
+            .iter()
+            .find(|a| a.id == id)
+            .cloned())
+    }
+
+    async fn save(&self, alert: &Alert) -> Result<()> {
+        let mut inner = self.inner.write().await;
+        let Some(existing) = inner.alerts.iter_mut().find(|a| a.id == alert.id) else {
+            return Ok(());
+        };
+        *existing = alert.clone();
+        if !alert.is_open() && inner.open.get(&alert.dedup_key) == Some(&alert.id) {
+            inner.open.remove(&alert.dedup_key);
+        }
+        Ok(())
+    }
+}
+
+/// Quota counts with their expiry; expired counters are swept on write.
+#[derive(Default)]
+pub struct MemoryQuotaStore {
+    counters: Mutex<HashMap<String, (u64, Instant)>>,
+}
+
+impl MemoryQuotaStore {
+    async fn update(&self, key: &str, change: impl FnOnce(u64) -> u64) -> u64 {
+        let now = Instant::now();
+        let mut counters = self.counters.lock().await;
+        counters.retain(|_, (_, expires)| *expires > now);
+        let entry = counters
+            .entry(key.to_string())
+            .or_insert((0, now + Duration::from_secs(COUNTER_TTL_SECS)));
+        let added = change(entry.0);
+        entry.0 += added;
+        added
+    }
+}
+
+#[async_trait]
+impl QuotaStore for MemoryQuotaStore {
+    async fn reserve(&self, key: &str, requested: u64, limit: u64) -> Result<u64> {
+        Ok(self
+            .update(key, |used| requested.min(limit.saturating_sub(used)))
+            .await)
+    }
+
+    async fn add(&self, key: &str, amount: u64) -> Result<()> {
+        self.update(key, |_| amount).await;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<u64> {
+        let now = Instant::now();
+        Ok(match self.counters.lock().await.get(key) {
+            Some((count, expires)) if *expires > now => *count,
+            _ => 0,
+        })
+    }
+}
+
+/// Spilled-over documents per source, oldest first.
+#[derive(Default)]
+pub struct MemorySpilloverQueue {
+    queues: Mutex<HashMap<String, VecDeque<RawDocument>>>,
+}
+
+#[async_trait]
+impl SpilloverQueue for MemorySpilloverQueue {
+    async fn push(&self, source: &str, documents: Vec<RawDocument>) -> Result<usize> {
+        let mut queues = self.queues.lock().await;
+        let queue = queues.entry(source.to_string()).or_default();
+        queue.extend(documents);
+        let dropped = queue.len().saturating_sub(MAX_SPILLOVER_PER_SOURCE);
+        queue.drain(..dropped);
+        Ok(dropped)
+    }
+
+    async fn pop(&self, source: &str, max: usize) -> Result<Vec<RawDocument>> {
+        let mut queues = self.queues.lock().await;
+        let Some(queue) = queues.get_mut(source) else {
+            return Ok(Vec::new());
+        };
+        let taken = queue.drain(..max.min(queue.len())).collect();
+        if queue.is_empty() {
+            queues.remove(source);
+        }
+        Ok(taken)
+    }
+
+    async fn sizes(&self) -> Result<BTreeMap<String, usize>> {
+        let queues = self.queues.lock().await;
+        Ok(queues
+            .iter()
+            .map(|(source, queue)| (source.clone(), queue.len()))
+            .collect())
+    }
+}
+
+#[derive(Default)]
+struct ExtractionJobs {
+    waiting: VecDeque<ExtractionJob>,
+    /// Jobs each worker took, by receipt, oldest first.
+    processing: HashMap<String, Vec<(String, ExtractionJob)>>,
+    heartbeats: HashMap<String, Instant>,
+}
+
+#[derive(Default)]
+pub struct MemoryExtractionQueue {
+    jobs: Mutex<ExtractionJobs>,
+}
+
+impl ExtractionJobs {
+    fn requeue(&mut self, worker: &str) -> usize {
+        let taken = self.processing.remove(worker).unwrap_or_default();
+        let count = taken.len();
+        for (_, job) in taken.into_iter().rev() {
+            self.waiting.push_front(job);
+        }
+        count
+    }
+}
+
+#[async_trait]
+impl ExtractionQueue for MemoryExtractionQueue {
+    async fn push(&self, job: ExtractionJob) -> Result<()> {
+        self.jobs.lock().await.waiting.push_back(job);
+        Ok(())
+    }
+
+    async fn pop(&self, worker: &str) -> Result<Option<ClaimedJob>> {
+        let mut jobs = self.jobs.lock().await;
+        let Some(job) = jobs.waiting.pop_front() else {
+            return Ok(None);
+        };
+        let receipt = Uuid::new_v4().to_string();
+        jobs.processing
+            .entry(worker.to_string())
+            .or_default()
+            .push((receipt.clone(), job.clone()));
+        Ok(Some(ClaimedJob { job, receipt }))
+    }
+
+    async fn ack(&self, worker: &str, receipt: &str) -> Result<()> {
+        let mut jobs = self.jobs.lock().await;
+        if let Some(taken) = jobs.processing.get_mut(worker) {
+            taken.retain(|(r, _)| r != receipt);
+            if taken.is_empty() {
+                jobs.processing.remove(worker);
+            }
+        }
+        Ok(())
+    }
+
+    async fn heartbeat(&self, worker: &str, ttl: Duration) -> Result<()> {
+        self.jobs
+            .lock()
+            .await
+            .heartbeats
+            .insert(worker.to_string(), Instant::now() + ttl);
+        Ok(())
+    }
+
+    async fn requeue(&self, worker: &str) -> Result<usize> {
+        Ok(self.jobs.lock().await.requeue(worker))
+    }
+
+    async fn requeue_stale(&self) -> Result<usize> {
+        let mut jobs = self.jobs.lock().await;
+        let now = Instant::now();
+        jobs.heartbeats.retain(|_, expires| *expires > now);
+        let stale: Vec<String> = jobs
+            .processing
+            .keys()
+            .filter(|worker| !jobs.heartbeats.contains_key(*worker))
+            .cloned()
+            .collect();
+        Ok(stale.iter().map(|worker| jobs.requeue(worker)).sum())
+    }
+
+    async fn pending(&self) -> Result<usize> {
+        Ok(self.jobs.lock().await.waiting.len())
+    }
+}
+
+/// Extraction failures oldest first.
+#[derive(Default)]
+pub struct MemoryExtractionFailureStore {
+    failures: RwLock<VecDeque<ExtractionFailure>>,
+}
+
+#[async_trait]
+impl ExtractionFailureStore for MemoryExtractionFailureStore {
+    async fn record(&self, failure: ExtractionFailure) -> Result<()> {
+        let mut failures = self.failures.write().await;
+        failures.push_back(failure);
+        while failures.len() > MAX_EXTRACTION_FAILURES {
+            failures.pop_front();
+        }
+        Ok(())
+    }
+
+    async fn list(&self, agent_name: Option<&str>, limit: usize) -> Result<Vec<ExtractionFailure>> {
+        let failures = self.failures.read().await;
+        Ok(failures
+            .iter()
+            .rev()
+            .filter(|f| agent_name.is_none_or(|name| f.agent_name == name))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<ExtractionFailure>> {
+        Ok(self
+            .failures
+            .read()
+            .await
+            .iter()
+            .find(|f| f.id == id)
+            .cloned())
+    }
+
+    async fn remove(&self, id: Uuid) -> Result<bool> {
+        let mut failures = self.failures.write().await;
+        let before = failures.len();
+        failures.retain(|f| f.id != id);
+        Ok(failures.len() < before)
+    }
+}
+
+/// Usage per day and consumer; days past retention are dropped on write.
+#[derive(Default)]
+pub struct MemoryUsageStore {
+    days: Mutex<BTreeMap<NaiveDate, HashMap<String, UsageCounts>>>,
+}
+
+#[async_trait]
+impl UsageStore for MemoryUsageStore {
+    async fn record(&self, consumer: &str, day: NaiveDate, record: &UsageRecord) -> Result<()> {
+        let mut days = self.days.lock().await;
+        let consumers = days.entry(day).or_default();
+        let consumer =
+            tracked_consumer(consumer, consumers.contains_key(consumer), consumers.len());
+        consumers
+            .entry(consumer.to_string())
+            .or_default()
+            .add(record);
+        let oldest = oldest_kept_day(day);
+        days.retain(|kept, _| *kept >= oldest);
+        Ok(())
+    }
+
+    async fn totals(&self, since: NaiveDate) -> Result<BTreeMap<String, UsageCounts>> {
+        let days = self.days.lock().await;
+        let mut totals: BTreeMap<String, UsageCounts> = BTreeMap::new();
+        for consumers in days.range(since..).map(|(_, consumers)| consumers) {
+            for (consumer, counts) in consumers {
+                totals.entry(consumer.clone()).or_default().merge(counts);
+            }
+        }
+        Ok(totals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::usage::{MAX_CONSUMERS_PER_DAY, OTHER_CONSUMERS};
+
+    #[tokio::test]
+    async fn run_store_lists_newest_first_and_trims() {
+        let store = MemoryRunStore::default();
+        for i in 0..MAX_RUN_HISTORY + 5 {
+            store
+                .insert(AgentRunStatus::started(i.to_string(), "gdelt"))
+                .await
+                .unwrap();
+        }
+
+        let runs = store.list(3).await.unwrap();
+        let ids: Vec<&str> = runs.iter().map(|r| r.run_id.as_str()).collect();
+        assert_eq!(ids, vec!["104", "103", "102"]);
+        assert!(store.get("0").await.unwrap().is_none());
+        assert_eq!(store.list(usize::MAX).await.unwrap().len(), MAX_RUN_HISTORY);
+    }
+
+    #[tokio::test]
+    async fn run_store_save_overwrites() {
+        let store = MemoryRunStore::default();
+        store
+            .insert(AgentRunStatus::started("r1", "gdelt"))
+            .await
+            .unwrap();
+
+        let mut run = store.get("r1").await.unwrap().unwrap();
+        run.documents_collected = 7;
+        store.save(&run).await.unwrap();
+
+        assert_eq!(
+            store.get("r1").await.unwrap().unwrap().documents_collected,
+            7
+        );
+    }
+
+    #[tokio::test]
+    async fn feedback_store_keeps_one_label_per_item() {
+        let store = MemoryFeedbackStore::default();
+        let first = FeedbackLabel {
+            id: Uuid::new_v4(),
+            target: argus_core::FeedbackTarget::Entity,
+            target_id: Some(Uuid::new_v4()),
+            correct: true,
+            confidence: 0.8,
+            source: "gdelt".to_string(),
+            kind: "person".to_string(),
+            note: None,
+            prompt_version: None,
+            created_at: chrono::Utc::now(),
+        };
+        let relabel = FeedbackLabel {
+            id: Uuid::new_v4(),
+            correct: false,
+            ..first.clone()
+        };
+        store.save(&first).await.unwrap();
+        store.save(&relabel).await.unwrap();
+
+        let labels = store.list().await.unwrap();
+        assert_eq!(labels, vec![relabel.clone()]);
+        assert!(!store.delete(first.id).await.unwrap());
+        assert!(store.delete(relabel.id).await.unwrap());
+        assert!(store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dedup_reports_first_sighting_only() {
+        let dedup = MemoryDedupStore::default();
+        let ttl = Duration::from_secs(60);
+        assert!(dedup.first_seen("a", ttl).await.unwrap());
+        assert!(!dedup.first_seen("a", ttl).await.unwrap());
+        assert!(dedup.first_seen("b", ttl).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn dedup_forgets_after_ttl() {
+        let dedup = MemoryDedupStore::default();
+        assert!(dedup.first_seen("a", Duration::ZERO).await.unwrap());
+        assert!(dedup.first_seen("a", Duration::ZERO).await.unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limiter_waits_for_next_window() {
+        let limiter = MemoryRateLimiter::default();
+        limiter.acquire("voyage", 2).await.unwrap();
+        limiter.acquire("voyage", 2).await.unwrap();
+
+        let started = tokio::time::Instant::now();
+        limiter.acquire("voyage", 2).await.unwrap();
+        assert!(started.elapsed() >= WINDOW - Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn lock_excludes_other_owners_until_released() {
+        let locks = MemoryLockManager::default();
+        let ttl = Duration::from_secs(60);
+        assert!(locks.try_acquire("gdelt", "a", ttl).await.unwrap());
+        assert!(!locks.try_acquire("gdelt", "b", ttl).await.unwrap());
+        assert!(locks.try_acquire("gdelt", "a", ttl).await.unwrap());
+
+        locks.release("gdelt", "b").await.unwrap();
+        assert!(!locks.try_acquire("gdelt", "b", ttl).await.unwrap());
+
+        locks.release("gdelt", "a").await.unwrap();
+        assert!(locks.try_acquire("gdelt", "b", ttl).await.unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lock_expires_after_ttl() {
+        let locks = MemoryLockManager::default();
+        assert!(locks
+            .try_acquire("gdelt", "a", Duration::from_secs(5))
+            .await
+            .unwrap());
+        tokio::time::advance(Duration::from_secs(6)).await;
+        assert!(locks
+            .try_acquire("gdelt", "b", Duration::from_secs(5))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lock_holder_is_reported_until_expiry() {
+        let locks = MemoryLockManager::default();
+        assert_eq!(locks.holder("run:gdelt").await.unwrap(), None);
+        locks
+            .try_acquire("run:gdelt", "r1", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            locks.holder("run:gdelt").await.unwrap().as_deref(),
+            Some("r1")
+        );
+        tokio::time::advance(Duration::from_secs(6)).await;
+        assert_eq!(locks.holder("run:gdelt").await.unwrap(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idempotency_claim_returns_first_run_until_expiry() {
+        let keys = MemoryIdempotencyStore::default();
+        let ttl = Duration::from_secs(60);
+        assert_eq!(keys.claim("k", "r1", ttl).await.unwrap(), None);
+        assert_eq!(
+            keys.claim("k", "r2", ttl).await.unwrap().as_deref(),
+            Some("r1")
+        );
+
+        keys.forget("k").await.unwrap();
+        assert_eq!(keys.claim("k", "r3", ttl).await.unwrap(), None);
+        tokio::time::advance(Duration::from_secs(61)).await;
+        assert_eq!(keys.claim("k", "r4", ttl).await.unwrap(), None);
+    }
+
+    fn failed(run_id: &str, source_id: &str) -> FailedDocument {
+        let doc = argus_core::RawDocument {
+            source: "gdelt".to_string(),
+            source_id: source_id.to_string(),
+            title: None,
+            content: "text".to_string(),
+            url: None,
+            collected_at: chrono::Utc::now(),
+            observed_at: None,
+            identifiers: Default::default(),
+            metadata: serde_json::Value::Null,
+            structured: None,
+        };
+        FailedDocument::new(run_id, "gdelt", doc, "timeout".to_string())
+    }
+
+    #[tokio::test]
+    async fn retry_queue_replaces_and_removes_entries() {
+        let queue = MemoryRetryQueue::default();
+        queue.record(failed("r1", "a")).await.unwrap();
+        queue.record(failed("r1", "b")).await.unwrap();
+
+        let mut again = failed("r1", "a");
+        again.failed_again("still failing".to_string());
+        queue.record(again).await.unwrap();
+
+        let entries = queue.for_run("r1").await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries
+                .iter()
+                .find(|f| f.document.source_id == "a")
+                .unwrap()
+                .attempts,
+            2
+        );
+
+        queue.remove("r1", "b").await.unwrap();
+        assert_eq!(queue.for_run("r1").await.unwrap().len(), 1);
+        assert!(queue.for_run("r2").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn agent_pauses_survive_a_reload() {
+        let path = std::env::temp_dir().join(format!("argus-paused-{}.json", uuid::Uuid::new_v4()));
+        let pauses = MemoryAgentPauseStore::persistent(&path);
+        pauses.set_paused("gdelt", true).await.unwrap();
+        pauses.set_paused("adsb", true).await.unwrap();
+        pauses.set_paused("adsb", false).await.unwrap();
+        assert!(pauses.is_paused("gdelt").await.unwrap());
+
+        let reloaded = MemoryAgentPauseStore::persistent(&path);
+        assert_eq!(
+            reloaded.paused().await.unwrap(),
+            BTreeSet::from(["gdelt".to_string()])
+        );
+        assert!(!reloaded.is_paused("adsb").await.unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    fn candidate(score: f64) -> DuplicateCandidate {
+        use argus_core::duplicates::{DuplicateMember, DuplicateReason};
+        let member = |name: &str| DuplicateMember {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            entity_type: argus_core::EntityType::Person,
+            source: "gdelt".to_string(),
+        };
+        let mut candidate = DuplicateCandidate::new(
+            member("Jon Smith"),
+            member("John Smith"),
+            DuplicateReason::SameName,
+        );
+        candidate.score = score;
+        candidate
+    }
+
+    #[tokio::test]
+    async fn duplicate_queue_sorts_and_keeps_dismissed_pairs_out() {
+        let queue = MemoryDuplicateReviewQueue::default();
+        let (low, high, other) = (candidate(0.5), candidate(0.9), candidate(0.7));
+        queue
+            .replace(vec![low.clone(), high.clone(), other.clone()])
+            .await
+            .unwrap();
+        let scores: Vec<f64> = queue
+            .list()
+            .await
+            .unwrap()
+            .iter()
+            .map(|c| c.score)
+            .collect();
+        assert_eq!(scores, vec![0.9, 0.7, 0.5]);
+
+        assert!(queue.dismiss(&low.id).await.unwrap());
+        assert!(!queue.dismiss(&low.id).await.unwrap());
+        queue
+            .remove_involving(&[other.entities[1].id])
+            .await
+            .unwrap();
+        assert_eq!(queue.list().await.unwrap(), vec![high.clone()]);
+
+        queue
+            .replace(vec![low.clone(), high.clone()])
+            .await
+            .unwrap();
+        assert!(queue.get(&low.id).await.unwrap().is_none());
+        assert_eq!(queue.get(&high.id).await.unwrap(), Some(high));
+    }
+
+    fn alert(dedup_key: &str) -> Alert {
+        Alert {
+            id: Uuid::new_v4(),
+            rule_id: Uuid::nil(),
+            rule_name: "sanctioned orgs".to_string(),
+            severity: argus_core::AlertSeverity::High,
+            message: "Acme Ltd sanctioned_by OFAC".to_string(),
+            entities: vec![],
+            relationship_id: None,
+            source: "opensanctions".to_string(),
+            dedup_key: dedup_key.to_string(),
+            created_at: chrono::Utc::now(),
+            acknowledged_at: None,
+            acknowledged_by: None,
+            note: None,
+            deliveries: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn open_alerts_suppress_repeats_until_acknowledged() {
+        let alerts = MemoryAlertStore::default();
+        let first = alert("rule:acme");
+        assert!(alerts.raise(first.clone()).await.unwrap());
+        assert!(!alerts.raise(alert("rule:acme")).await.unwrap());
+        assert!(alerts.raise(alert("rule:other")).await.unwrap());
+        assert_eq!(
+            alerts.list(AlertStatus::Open, 10).await.unwrap()[1].id,
+            first.id
+        );
+
+        let mut acknowledged = alerts.get(first.id).await.unwrap().unwrap();
+        acknowledged.acknowledge(Some("analyst".to_string()), None);
+        alerts.save(&acknowledged).await.unwrap();
+        assert_eq!(
+            alerts.list(AlertStatus::Acknowledged, 10).await.unwrap(),
+            vec![acknowledged]
+        );
+        assert!(alerts.raise(alert("rule:acme")).await.unwrap());
+        assert_eq!(alerts.list(AlertStatus::All, 10).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn quota_reservations_stop_at_the_limit() {
+        let quotas = MemoryQuotaStore::default();
+        assert_eq!(quotas.reserve("quota:gdelt", 30, 50).await.unwrap(), 30);
+        assert_eq!(quotas.reserve("quota:gdelt", 30, 50).await.unwrap(), 20);
+        assert_eq!(quotas.reserve("quota:gdelt", 30, 50).await.unwrap(), 0);
+        quotas.add("quota:gdelt", 5).await.unwrap();
+        assert_eq!(quotas.get("quota:gdelt").await.unwrap(), 55);
+        assert_eq!(quotas.get("quota:rss").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn spillover_queue_pops_oldest_first() {
+        let queue = MemorySpilloverQueue::default();
+        let docs: Vec<RawDocument> = (0..3)
+            .map(|i| failed("run", &i.to_string()).document)
+            .collect();
+        assert_eq!(queue.push("gdelt", docs).await.unwrap(), 0);
+
+        let taken = queue.pop("gdelt", 2).await.unwrap();
+        let ids: Vec<&str> = taken.iter().map(|d| d.source_id.as_str()).collect();
+        assert_eq!(ids, vec!["0", "1"]);
+        assert_eq!(
+            queue.sizes().await.unwrap(),
+            BTreeMap::from([("gdelt".to_string(), 1)])
+        );
+        queue.pop("gdelt", 5).await.unwrap();
+        assert!(queue.sizes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn extraction_queue_hands_out_jobs_in_order() {
+        let queue = MemoryExtractionQueue::default();
+        for run_id in ["a", "b"] {
+            let job = ExtractionJob {
+                run_id: run_id.to_string(),
+                agent_name: "gdelt".to_string(),
+                documents: vec![failed(run_id, "1").document],
+                over_quota: 0,
+                schema_drift: None,
+                cross_reference: true,
+                enqueued_at: chrono::Utc::now(),
+            };
+            queue.push(job).await.unwrap();
+        }
+
+        assert_eq!(queue.pending().await.unwrap(), 2);
+        let a = queue.pop("w1").await.unwrap().unwrap();
+        assert_eq!(a.job.run_id, "a");
+        assert_eq!(queue.pop("w1").await.unwrap().unwrap().job.run_id, "b");
+        assert!(queue.pop("w1").await.unwrap().is_none());
+
+        // Acknowledged jobs are gone; the rest go back to the front
+        queue.ack("w1", &a.receipt).await.unwrap();
+        assert_eq!(queue.requeue("w1").await.unwrap(), 1);
+        assert_eq!(queue.pending().await.unwrap(), 1);
+        assert_eq!(queue.pop("w2").await.unwrap().unwrap().job.run_id, "b");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn extraction_jobs_of_lapsed_workers_are_requeued() {
+        let queue = MemoryExtractionQueue::default();
+        for run_id in ["a", "b"] {
+            let job = ExtractionJob {
+                run_id: run_id.to_string(),
+                agent_name: "gdelt".to_string(),
+                documents: vec![failed(run_id, "1").document],
+                over_quota: 0,
+                schema_drift: None,
+                cross_reference: true,
+                enqueued_at: chrono::Utc::now(),
+            };
+            queue.push(job).await.unwrap();
+        }
+        queue
+            .heartbeat("alive", Duration::from_secs(60))
+            .await
+            .unwrap();
+        queue
+            .heartbeat("dead", Duration::from_secs(10))
+            .await
+            .unwrap();
+        queue.pop("alive").await.unwrap().unwrap();
+        queue.pop("dead").await.unwrap().unwrap();
+        assert_eq!(queue.requeue_stale().await.unwrap(), 0);
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(queue.requeue_stale().await.unwrap(), 1);
+        assert_eq!(queue.pop("alive").await.unwrap().unwrap().job.run_id, "b");
+    }
+
+    #[tokio::test]
+    async fn extraction_failures_list_newest_first_by_agent() {
+        let store = MemoryExtractionFailureStore::default();
+        let mut ids = Vec::new();
+        for (agent, source_id) in [("gdelt", "1"), ("ais", "2"), ("gdelt", "3")] {
+            let failure = argus_core::FailedExtraction {
+                document: failed("r1", source_id).document,
+                error: "Extraction error: Failed to parse LLM JSON output".to_string(),
+                raw_output: Some("{\"entities\": [".to_string()),
+                model: Some("claude-haiku-4-5".to_string()),
+            };
+            let failure = ExtractionFailure::from_failed("r1", agent, &failure).unwrap();
+            ids.push(failure.id);
+            store.record(failure).await.unwrap();
+        }
+
+        let gdelt = store.list(Some("gdelt"), 10).await.unwrap();
+        let sources: Vec<&str> = gdelt
+            .iter()
+            .map(|f| f.document.source_id.as_str())
+            .collect();
+        assert_eq!(sources, vec!["3", "1"]);
+        assert_eq!(store.list(None, 1).await.unwrap()[0].id, ids[2]);
+
+        assert!(store.remove(ids[0]).await.unwrap());
+        assert!(!store.remove(ids[0]).await.unwrap());
+        assert!(store.get(ids[0]).await.unwrap().is_none());
+        assert_eq!(store.list(None, 10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn usage_totals_add_days_and_drop_old_ones() {
+        let store = MemoryUsageStore::default();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let record = |endpoint: &str, reasoning_tokens| UsageRecord {
+            endpoint: endpoint.to_string(),
+            reasoning_tokens,
+            exported_records: 0,
+        };
+        store
+            .record("token:a", day(1), &record("GET /api/health", 0))
+            .await
+            .unwrap();
+        store
+            .record("token:a", day(2), &record("POST /api/reasoning/query", 900))
+            .await
+            .unwrap();
+        store
+            .record("anonymous", day(2), &record("GET /api/health", 0))
+            .await
+            .unwrap();
+
+        let totals = store.totals(day(1)).await.unwrap();
+        assert_eq!(
+            (
+                totals["token:a"].requests,
+                totals["token:a"].reasoning_tokens
+            ),
+            (2, 900)
+        );
+        assert_eq!(totals["anonymous"].endpoints["GET /api/health"], 1);
+        assert_eq!(store.totals(day(2)).await.unwrap()["token:a"].requests, 1);
+
+        // Day 1 falls out of retention
+        store
+            .record("token:a", day(31), &record("GET /api/health", 0))
+            .await
+            .unwrap();
+        assert_eq!(store.totals(day(1)).await.unwrap()["token:a"].requests, 2);
+
+        // Random tokens stop adding consumers once the day is full
+        for i in 0..MAX_CONSUMERS_PER_DAY + 5 {
+            store
+                .record(&format!("token:{i}"), day(4), &record("GET /api/health", 0))
+                .await
+                .unwrap();
+        }
+        let totals = store.totals(day(4)).await.unwrap();
+        assert!(totals.contains_key(&format!("token:{}", MAX_CONSUMERS_PER_DAY - 1)));
+        assert!(!totals.contains_key(&format!("token:{MAX_CONSUMERS_PER_DAY}")));
+        assert_eq!(totals[OTHER_CONSUMERS].requests, 5);
+    }
+}
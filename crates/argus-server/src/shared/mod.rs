@@ -0,0 +1,195 @@
+//! Backends for the shared-state traits in `argus_core::shared`.
+//!
+//! `STATE_BACKEND=memory` (default) keeps everything in process;
+//! `STATE_BACKEND=redis` stores it under `REDIS_URL` so replicas share it.
+//! Covers run history, document dedup, rate limits, locks, the retry queue,
+//! trigger idempotency keys, paused agents, the duplicate review queue, alert
+//! rules, alerts, deny-list entries, analyst feedback labels, investigations, entity subscriptions and their notifications, extraction prompt bundles, extraction quota counters, the quota spillover queue, the queue of
+//! extraction jobs for `--role worker` processes, the extraction failure catalog and per-token API usage.
+
+mod memory;
+mod redis;
+
+use std::sync::Arc;
+
+use tracing::{info, warn};
+
+use argus_core::api_types::AgentRunStatus;
+use argus_core::shared::{
+    AgentPauseStore, AlertRuleStore, AlertStore, DedupStore, DenyListStore, DuplicateReviewQueue,
+    ExtractionFailureStore, ExtractionQueue, FeedbackStore, IdempotencyStore, InvestigationStore,
+    LockManager, NotificationStore, PromptBundleStore, QuotaStore, RateLimiter, RetryQueue,
+    RunStore, SpilloverQueue, SubscriptionStore, UsageStore,
+};
+use argus_core::AppConfig;
+
+pub use self::memory::{
+    MemoryAgentPauseStore, MemoryAlertRuleStore, MemoryAlertStore, MemoryDedupStore,
+    MemoryDenyListStore, MemoryDuplicateReviewQueue, MemoryExtractionFailureStore,
+    MemoryExtractionQueue, MemoryFeedbackStore, MemoryIdempotencyStore, MemoryInvestigationStore,
+    MemoryLockManager, MemoryNotificationStore, MemoryPromptBundleStore, MemoryQuotaStore,
+    MemoryRateLimiter, MemoryRetryQueue, MemoryRunStore, MemorySpilloverQueue,
+    MemorySubscriptionStore, MemoryUsageStore,
+};
+pub use self::redis::{
+    RedisAgentPauseStore, RedisAlertRuleStore, RedisAlertStore, RedisDedupStore,
+    RedisDenyListStore, RedisDuplicateReviewQueue, RedisExtractionFailureStore,
+    RedisExtractionQueue, RedisFeedbackStore, RedisIdempotencyStore, RedisInvestigationStore,
+    RedisLockManager, RedisNotificationStore, RedisPromptBundleStore, RedisQuotaStore,
+    RedisRateLimiter, RedisRetryQueue, RedisRunStore, RedisSpilloverQueue, RedisSubscriptionStore,
+    RedisUsageStore,
+};
+
+/// The shared-state handles held by `AppState`.
+#[derive(Clone)]
+pub struct SharedState {
+    pub runs: Arc<dyn RunStore>,
+    pub dedup: Arc<dyn DedupStore>,
+    pub rate_limiter: Arc<dyn RateLimiter>,
+    pub locks: Arc<dyn LockManager>,
+    pub retries: Arc<dyn RetryQueue>,
+    pub idempotency: Arc<dyn IdempotencyStore>,
+    pub pauses: Arc<dyn AgentPauseStore>,
+    pub duplicates: Arc<dyn DuplicateReviewQueue>,
+    pub alert_rules: Arc<dyn AlertRuleStore>,
+    pub alerts: Arc<dyn AlertStore>,
+    pub deny_list: Arc<dyn DenyListStore>,
+    pub feedback: Arc<dyn FeedbackStore>,
+    pub investigations: Arc<dyn InvestigationStore>,
+    pub subscriptions: Arc<dyn SubscriptionStore>,
+    pub notifications: Arc<dyn NotificationStore>,
+    pub prompts: Arc<dyn PromptBundleStore>,
+    pub quotas: Arc<dyn QuotaStore>,
+    pub spillover: Arc<dyn SpilloverQueue>,
+    pub extraction_jobs: Arc<dyn ExtractionQueue>,
+    pub extraction_failures: Arc<dyn ExtractionFailureStore>,
+    pub usage: Arc<dyn UsageStore>,
+    /// Identifies this replica as a lock owner.
+    pub instance_id: String,
+}
+
+impl SharedState {
+    /// In-process state. Pauses are persisted to `pause_file` unless it is empty.
+    pub fn in_memory(pause_file: &str) -> Self {
+        let pauses = if pause_file.is_empty() {
+            MemoryAgentPauseStore::default()
+        } else {
+            MemoryAgentPauseStore::persistent(pause_file)
+        };
+        Self {
+            runs: Arc::new(MemoryRunStore::default()),
+            dedup: Arc::new(MemoryDedupStore::default()),
+            rate_limiter: Arc::new(MemoryRateLimiter::default()),
+            locks: Arc::new(MemoryLockManager::default()),
+            retries: Arc::new(MemoryRetryQueue::default()),
+            idempotency: Arc::new(MemoryIdempotencyStore::default()),
+            pauses: Arc::new(pauses),
+            duplicates: Arc::new(MemoryDuplicateReviewQueue::default()),
+            alert_rules: Arc::new(MemoryAlertRuleStore::default()),
+            alerts: Arc::new(MemoryAlertStore::default()),
+            deny_list: Arc::new(MemoryDenyListStore::default()),
+            feedback: Arc::new(MemoryFeedbackStore::default()),
+            investigations: Arc::new(MemoryInvestigationStore::default()),
+            subscriptions: Arc::new(MemorySubscriptionStore::default()),
+            notifications: Arc::new(MemoryNotificationStore::default()),
+            prompts: Arc::new(MemoryPromptBundleStore::default()),
+            quotas: Arc::new(MemoryQuotaStore::default()),
+            spillover: Arc::new(MemorySpilloverQueue::default()),
+            extraction_jobs: Arc::new(MemoryExtractionQueue::default()),
+            extraction_failures: Arc::new(MemoryExtractionFailureStore::default()),
+            usage: Arc::new(MemoryUsageStore::default()),
+            instance_id: instance_id(),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        match config.state_backend.to_lowercase().as_str() {
+            "redis" => match self::redis::connect(&config.redis_url) {
+                Ok(conn) => {
+                    info!(url = %config.redis_url, "Using Redis for shared state");
+                    Self {
+                        runs: Arc::new(RedisRunStore::new(conn.clone())),
+                        dedup: Arc::new(RedisDedupStore::new(conn.clone())),
+                        rate_limiter: Arc::new(RedisRateLimiter::new(conn.clone())),
+                        locks: Arc::new(RedisLockManager::new(conn.clone())),
+                        retries: Arc::new(RedisRetryQueue::new(conn.clone())),
+                        idempotency: Arc::new(RedisIdempotencyStore::new(conn.clone())),
+                        pauses: Arc::new(RedisAgentPauseStore::new(conn.clone())),
+                        duplicates: Arc::new(RedisDuplicateReviewQueue::new(conn.clone())),
+                        alert_rules: Arc::new(RedisAlertRuleStore::new(conn.clone())),
+                        alerts: Arc::new(RedisAlertStore::new(conn.clone())),
+                        deny_list: Arc::new(RedisDenyListStore::new(conn.clone())),
+                        feedback: Arc::new(RedisFeedbackStore::new(conn.clone())),
+                        investigations: Arc::new(RedisInvestigationStore::new(conn.clone())),
+                        subscriptions: Arc::new(RedisSubscriptionStore::new(conn.clone())),
+                        notifications: Arc::new(RedisNotificationStore::new(conn.clone())),
+                        prompts: Arc::new(RedisPromptBundleStore::new(conn.clone())),
+                        quotas: Arc::new(RedisQuotaStore::new(conn.clone())),
+                        spillover: Arc::new(RedisSpilloverQueue::new(conn.clone())),
+                        extraction_jobs: Arc::new(RedisExtractionQueue::new(conn.clone())),
+                        extraction_failures: Arc::new(RedisExtractionFailureStore::new(
+                            conn.clone(),
+                        )),
+                        usage: Arc::new(RedisUsageStore::new(conn)),
+                        instance_id: instance_id(),
+                    }
+                }
+                Err(e) => {
+                    warn!(error = %e, "Invalid REDIS_URL, falling back to in-memory state");
+                    Self::in_memory(&config.agent_pause_file)
+                }
+            },
+            "memory" | "" => Self::in_memory(&config.agent_pause_file),
+            other => {
+                warn!(
+                    backend = other,
+                    "Unknown STATE_BACKEND, using in-memory state"
+                );
+                Self::in_memory(&config.agent_pause_file)
+            }
+        }
+    }
+}
+
+/// `INSTANCE_ID` if set, otherwise the host name plus a random suffix so
+/// restarted containers never inherit a stale lock.
+fn instance_id() -> String {
+    std::env::var("INSTANCE_ID").unwrap_or_else(|_| {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "argus".to_string());
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        format!("{host}-{}", &suffix[..8])
+    })
+}
+
+/// Lock held for the duration of one run of `agent_name`, whether
+/// triggered by the scheduler or the API, so runs of an agent never overlap.
+pub fn run_lock_key(agent_name: &str) -> String {
+    format!("run:{agent_name}")
+}
+
+/// Apply `update` to a stored run and write it back. Failures are logged,
+/// since run bookkeeping must never abort the work it describes.
+pub async fn update_run(
+    runs: &Arc<dyn RunStore>,
+    run_id: &str,
+    update: impl FnOnce(&mut AgentRunStatus),
+) {
+    match runs.get(run_id).await {
+        Ok(Some(mut run)) => {
+            update(&mut run);
+            if let Err(e) = runs.save(&run).await {
+                warn!(run_id, error = %e, "Failed to save run status");
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!(run_id, error = %e, "Failed to load run status"),
+    }
+}
+
+/// Record a new run; failures are logged for the same reason as [`update_run`].
+pub async fn insert_run(runs: &Arc<dyn RunStore>, run: AgentRunStatus) {
+    let run_id = run.run_id.clone();
+    if let Err(e) = runs.insert(run).await {
+        warn!(run_id = %run_id, error = %e, "Failed to record run");
+    }
+}
@@ -0,0 +1,1602 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use tokio::sync::OnceCell;
+
+use argus_core::alerts::{Alert, AlertRule, AlertStatus, MAX_ALERTS};
+use argus_core::api_types::AgentRunStatus;
+use argus_core::duplicates::{sort_candidates, DuplicateCandidate};
+use argus_core::extraction::{ExtractionFailure, MAX_EXTRACTION_FAILURES};
+use argus_core::prompts::{FewShotExample, PromptBundle, PromptDeployment};
+use argus_core::quota::{COUNTER_TTL_SECS, MAX_SPILLOVER_PER_SOURCE};
+use argus_core::shared::{
+    AgentPauseStore, AlertRuleStore, AlertStore, ClaimedJob, DedupStore, DenyListStore,
+    DuplicateReviewQueue, ExtractionFailureStore, ExtractionJob, ExtractionQueue, FailedDocument,
+    FeedbackStore, IdempotencyStore, InvestigationStore, LockManager, NotificationStore,
+    PromptBundleStore, QuotaStore, RateLimiter, RetryQueue, RunStore, SpilloverQueue,
+    SubscriptionStore, UsageStore, MAX_RUN_HISTORY,
+};
+use argus_core::subscriptions::{Notification, Subscription, MAX_NOTIFICATIONS};
+use argus_core::usage::{
+    UsageCounts, UsageRecord, MAX_CONSUMERS_PER_DAY, OTHER_CONSUMERS, USAGE_RETENTION_DAYS,
+};
+use argus_core::{ArgusError, DenyEntry, FeedbackLabel, Investigation, RawDocument, Result};
+use uuid::Uuid;
+
+const RUNS_KEY: &str = "argus:runs";
+const RUNS_INDEX_KEY: &str = "argus:runs:index";
+const DEDUP_PREFIX: &str = "argus:dedup:";
+const RATE_PREFIX: &str = "argus:rate:";
+const LOCK_PREFIX: &str = "argus:lock:";
+const RETRY_PREFIX: &str = "argus:retry:";
+const IDEMPOTENCY_PREFIX: &str = "argus:idempotency:";
+const PAUSED_AGENTS_KEY: &str = "argus:agents:paused";
+const DUPLICATES_KEY: &str = "argus:duplicates";
+const DISMISSED_DUPLICATES_KEY: &str = "argus:duplicates:dismissed";
+const ALERT_RULES_KEY: &str = "argus:alerts:rules";
+const DENY_LIST_KEY: &str = "argus:deny_list";
+const FEEDBACK_KEY: &str = "argus:feedback";
+const INVESTIGATIONS_KEY: &str = "argus:investigations";
+const SUBSCRIPTIONS_KEY: &str = "argus:subscriptions";
+const NOTIFICATIONS_KEY: &str = "argus:notifications";
+const NOTIFICATIONS_INDEX_KEY: &str = "argus:notifications:index";
+const NOTIFIED_PREFIX: &str = "argus:notifications:seen:";
+const PROMPT_BUNDLES_KEY: &str = "argus:prompts:bundles";
+const PROMPT_VERSION_KEY: &str = "argus:prompts:version";
+const PROMPT_DEPLOYMENT_KEY: &str = "argus:prompts:deployment";
+const ALERTS_KEY: &str = "argus:alerts";
+const ALERTS_INDEX_KEY: &str = "argus:alerts:index";
+const OPEN_ALERTS_KEY: &str = "argus:alerts:open";
+const QUOTA_PREFIX: &str = "argus:";
+const SPILLOVER_PREFIX: &str = "argus:spillover:";
+const SPILLOVER_SOURCES_KEY: &str = "argus:spillover:sources";
+const EXTRACTION_JOBS_KEY: &str = "argus:extraction:jobs";
+const EXTRACTION_PROCESSING_PREFIX: &str = "argus:extraction:processing:";
+const EXTRACTION_WORKERS_KEY: &str = "argus:extraction:workers";
+const EXTRACTION_HEARTBEAT_PREFIX: &str = "argus:extraction:heartbeat:";
+const EXTRACTION_FAILURES_KEY: &str = "argus:extraction:failures";
+const EXTRACTION_FAILURES_INDEX_KEY: &str = "argus:extraction:failures:index";
+const USAGE_PREFIX: &str = "argus:usage:";
+
+/// Retry queues outlive their run by a week, then Redis drops them.
+const RETRY_QUEUE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Usage keys expire a day after their day leaves retention.
+const USAGE_TTL_SECS: i64 = (USAGE_RETENTION_DAYS as i64 + 1) * 24 * 60 * 60;
+
+/// Hash field prefix for per-endpoint request counts.
+const USAGE_ENDPOINT_FIELD: &str = "endpoint:";
+
+/// Add a consumer to the day's set unless it is new and the set holds
+/// `ARGV[2]` already, then return the name to count it under: itself or
+/// `ARGV[3]` (see `argus_core::usage::tracked_consumer`).
+const USAGE_CONSUMER_SCRIPT: &str = r#"
+local consumer = ARGV[1]
+if redis.call("SISMEMBER", KEYS[1], consumer) == 0 and redis.call("SCARD", KEYS[1]) >= tonumber(ARGV[2]) then
+    consumer = ARGV[3]
+end
+redis.call("SADD", KEYS[1], consumer)
+redis.call("EXPIRE", KEYS[1], ARGV[4])
+return consumer
+"#;
+
+/// Move the oldest job onto the worker's processing list, and remember the
+/// worker has one.
+const EXTRACTION_POP_SCRIPT: &str = r#"
+local json = redis.call("LMOVE", KEYS[1], KEYS[2], "LEFT", "RIGHT")
+if json then
+    redis.call("SADD", KEYS[3], ARGV[1])
+end
+return json
+"#;
+
+/// Move the worker's processing list back to the front of the queue in
+/// its order.
+const EXTRACTION_REQUEUE_SCRIPT: &str = r#"
+local count = 0
+while redis.call("LMOVE", KEYS[1], KEYS[2], "RIGHT", "LEFT") do
+    count = count + 1
+end
+redis.call("SREM", KEYS[3], ARGV[1])
+return count
+"#;
+
+/// Refresh the lock if `owner` holds it, otherwise take it only if free.
+const ACQUIRE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+end
+if redis.call("SET", KEYS[1], ARGV[1], "NX", "PX", ARGV[2]) then
+    return 1
+end
+return 0
+"#;
+
+/// Delete the lock only if `owner` still holds it.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// Return the run bound to the key, or bind the new one and return nil.
+const CLAIM_SCRIPT: &str = r#"
+local existing = redis.call("GET", KEYS[1])
+if existing then
+    return existing
+end
+redis.call("SET", KEYS[1], ARGV[1], "PX", ARGV[2])
+return false
+"#;
+
+/// Record the alert unless its dedup key already maps to an open one.
+const RAISE_SCRIPT: &str = r#"
+if redis.call("HSETNX", KEYS[1], ARGV[1], ARGV[2]) == 0 then
+    return 0
+end
+redis.call("HSET", KEYS[2], ARGV[2], ARGV[3])
+redis.call("ZADD", KEYS[3], ARGV[4], ARGV[2])
+return 1
+"#;
+
+/// Clear the dedup key only if it still maps to this alert.
+const CLOSE_SCRIPT: &str = r#"
+if redis.call("HGET", KEYS[1], ARGV[1]) == ARGV[2] then
+    return redis.call("HDEL", KEYS[1], ARGV[1])
+end
+return 0
+"#;
+
+/// Add up to ARGV[1] to the counter without passing the ARGV[2] limit.
+const RESERVE_SCRIPT: &str = r#"
+local used = tonumber(redis.call("GET", KEYS[1]) or "0")
+local grant = math.min(tonumber(ARGV[1]), math.max(tonumber(ARGV[2]) - used, 0))
+if grant > 0 then
+    redis.call("INCRBY", KEYS[1], grant)
+    redis.call("EXPIRE", KEYS[1], ARGV[3])
+end
+return grant
+"#;
+
+/// A lazily-established connection shared by all Redis-backed stores.
+/// Connecting on first use keeps startup independent of Redis availability.
+#[derive(Clone)]
+pub struct RedisConnection {
+    client: redis::Client,
+    manager: std::sync::Arc<OnceCell<ConnectionManager>>,
+}
+
+impl RedisConnection {
+    async fn get(&self) -> Result<ConnectionManager> {
+        self.manager
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await
+            .cloned()
+            .map_err(|e| ArgusError::Internal(format!("Failed to connect to Redis: {e}")))
+    }
+}
+
+pub fn connect(url: &str) -> Result<RedisConnection> {
+    let client = redis::Client::open(url)
+        .map_err(|e| ArgusError::Config(format!("Invalid Redis URL: {e}")))?;
+    Ok(RedisConnection {
+        client,
+        manager: Default::default(),
+    })
+}
+
+fn redis_err(op: &str) -> impl Fn(redis::RedisError) -> ArgusError + '_ {
+    move |e| ArgusError::Internal(format!("Redis {op} failed: {e}"))
+}
+
+/// Runs as JSON in a hash, ordered by a sorted set scored on start time.
+pub struct RedisRunStore {
+    conn: RedisConnection,
+}
+
+impl RedisRunStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl RunStore for RedisRunStore {
+    async fn insert(&self, run: AgentRunStatus) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(&run)?;
+        let score = run.started_at.timestamp_millis();
+
+        redis::pipe()
+            .atomic()
+            .hset(RUNS_KEY, &run.run_id, json)
+            .zadd(RUNS_INDEX_KEY, &run.run_id, score)
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(redis_err("run insert"))?;
+
+        // Trim everything older than the newest MAX_RUN_HISTORY runs
+        let stale: Vec<String> = conn
+            .zrange(RUNS_INDEX_KEY, 0, -(MAX_RUN_HISTORY as isize) - 1)
+            .await
+            .map_err(redis_err("run trim"))?;
+        if !stale.is_empty() {
+            redis::pipe()
+                .atomic()
+                .hdel(RUNS_KEY, &stale)
+                .zrem(RUNS_INDEX_KEY, &stale)
+                .query_async::<()>(&mut conn)
+                .await
+                .map_err(redis_err("run trim"))?;
+        }
+        Ok(())
+    }
+
+    async fn save(&self, run: &AgentRunStatus) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let exists: bool = conn
+            .hexists(RUNS_KEY, &run.run_id)
+            .await
+            .map_err(redis_err("run lookup"))?;
+        if exists {
+            let json = serde_json::to_string(run)?;
+            conn.hset::<_, _, _, ()>(RUNS_KEY, &run.run_id, json)
+                .await
+                .map_err(redis_err("run save"))?;
+        }
+        Ok(())
+    }
+
+    async fn get(&self, run_id: &str) -> Result<Option<AgentRunStatus>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(RUNS_KEY, run_id)
+            .await
+            .map_err(redis_err("run get"))?;
+        json.map(|j| serde_json::from_str(&j).map_err(ArgusError::from))
+            .transpose()
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<AgentRunStatus>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn.get().await?;
+        let stop = isize::try_from(limit).unwrap_or(isize::MAX) - 1;
+        let ids: Vec<String> = conn
+            .zrevrange(RUNS_INDEX_KEY, 0, stop)
+            .await
+            .map_err(redis_err("run list"))?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let values: Vec<Option<String>> = redis::cmd("HMGET")
+            .arg(RUNS_KEY)
+            .arg(&ids)
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_err("run list"))?;
+
+        Ok(values
+            .into_iter()
+            .flatten()
+            .filter_map(|json| match serde_json::from_str(&json) {
+                Ok(run) => Some(run),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Skipping unreadable run entry");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+/// `SET NX EX` markers; the first replica to set a key wins.
+pub struct RedisDedupStore {
+    conn: RedisConnection,
+}
+
+impl RedisDedupStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl DedupStore for RedisDedupStore {
+    async fn first_seen(&self, key: &str, ttl: Duration) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let set: Option<String> = redis::cmd("SET")
+            .arg(format!("{DEDUP_PREFIX}{key}"))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl.as_secs().max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_err("dedup SET"))?;
+        Ok(set.is_some())
+    }
+}
+
+/// Per-minute counters keyed on the current epoch minute.
+pub struct RedisRateLimiter {
+    conn: RedisConnection,
+}
+
+impl RedisRateLimiter {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl RateLimiter for RedisRateLimiter {
+    async fn acquire(&self, key: &str, per_minute: u32) -> Result<()> {
+        let per_minute = per_minute.max(1);
+        loop {
+            let now = chrono::Utc::now();
+            let minute = now.timestamp() / 60;
+            let window_key = format!("{RATE_PREFIX}{key}:{minute}");
+
+            let mut conn = self.conn.get().await?;
+            let (count,): (u32,) = redis::pipe()
+                .atomic()
+                .incr(&window_key, 1)
+                .expire(&window_key, 120)
+                .ignore()
+                .query_async(&mut conn)
+                .await
+                .map_err(redis_err("rate limit INCR"))?;
+
+            if count <= per_minute {
+                return Ok(());
+            }
+            let into_window = (now.timestamp_millis() % 60_000) as u64;
+            tokio::time::sleep(Duration::from_millis(60_000 - into_window)).await;
+        }
+    }
+}
+
+/// Owner-tagged keys with a millisecond expiry, checked and changed atomically
+/// in Lua so one replica can never release another's lock.
+pub struct RedisLockManager {
+    conn: RedisConnection,
+}
+
+impl RedisLockManager {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl LockManager for RedisLockManager {
+    async fn try_acquire(&self, key: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let acquired: i64 = redis::Script::new(ACQUIRE_SCRIPT)
+            .key(format!("{LOCK_PREFIX}{key}"))
+            .arg(owner)
+            .arg(ttl.as_millis().max(1) as u64)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_err("lock acquire"))?;
+        Ok(acquired == 1)
+    }
+
+    async fn release(&self, key: &str, owner: &str) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        redis::Script::new(RELEASE_SCRIPT)
+            .key(format!("{LOCK_PREFIX}{key}"))
+            .arg(owner)
+            .invoke_async::<i64>(&mut conn)
+            .await
+            .map_err(redis_err("lock release"))?;
+        Ok(())
+    }
+
+    async fn holder(&self, key: &str) -> Result<Option<String>> {
+        let mut conn = self.conn.get().await?;
+        conn.get(format!("{LOCK_PREFIX}{key}"))
+            .await
+            .map_err(redis_err("lock GET"))
+    }
+}
+
+/// One string key per idempotency key, holding the run id.
+pub struct RedisIdempotencyStore {
+    conn: RedisConnection,
+}
+
+impl RedisIdempotencyStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for RedisIdempotencyStore {
+    async fn claim(&self, key: &str, run_id: &str, ttl: Duration) -> Result<Option<String>> {
+        let mut conn = self.conn.get().await?;
+        redis::Script::new(CLAIM_SCRIPT)
+            .key(format!("{IDEMPOTENCY_PREFIX}{key}"))
+            .arg(run_id)
+            .arg(ttl.as_millis().max(1) as u64)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_err("idempotency claim"))
+    }
+
+    async fn forget(&self, key: &str) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        conn.del::<_, ()>(format!("{IDEMPOTENCY_PREFIX}{key}"))
+            .await
+            .map_err(redis_err("idempotency DEL"))
+    }
+}
+
+/// One hash per run, keyed by document source id.
+pub struct RedisRetryQueue {
+    conn: RedisConnection,
+}
+
+impl RedisRetryQueue {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl RetryQueue for RedisRetryQueue {
+    async fn record(&self, failed: FailedDocument) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let key = format!("{RETRY_PREFIX}{}", failed.run_id);
+        let json = serde_json::to_string(&failed)?;
+        redis::pipe()
+            .atomic()
+            .hset(&key, &failed.document.source_id, json)
+            .expire(&key, RETRY_QUEUE_TTL_SECS)
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(redis_err("retry record"))
+    }
+
+    async fn for_run(&self, run_id: &str) -> Result<Vec<FailedDocument>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(format!("{RETRY_PREFIX}{run_id}"))
+            .await
+            .map_err(redis_err("retry list"))?;
+        Ok(values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    async fn remove(&self, run_id: &str, source_id: &str) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        conn.hdel::<_, _, ()>(format!("{RETRY_PREFIX}{run_id}"), source_id)
+            .await
+            .map_err(redis_err("retry remove"))
+    }
+}
+
+/// A set of paused agent names, kept without expiry.
+pub struct RedisAgentPauseStore {
+    conn: RedisConnection,
+}
+
+impl RedisAgentPauseStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl AgentPauseStore for RedisAgentPauseStore {
+    async fn set_paused(&self, agent_name: &str, paused: bool) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        if paused {
+            conn.sadd::<_, _, ()>(PAUSED_AGENTS_KEY, agent_name)
+                .await
+                .map_err(redis_err("pause SADD"))
+        } else {
+            conn.srem::<_, _, ()>(PAUSED_AGENTS_KEY, agent_name)
+                .await
+                .map_err(redis_err("pause SREM"))
+        }
+    }
+
+    async fn is_paused(&self, agent_name: &str) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        conn.sismember(PAUSED_AGENTS_KEY, agent_name)
+            .await
+            .map_err(redis_err("pause SISMEMBER"))
+    }
+
+    async fn paused(&self) -> Result<BTreeSet<String>> {
+        let mut conn = self.conn.get().await?;
+        conn.smembers(PAUSED_AGENTS_KEY)
+            .await
+            .map_err(redis_err("pause SMEMBERS"))
+    }
+}
+
+/// Candidates as JSON in a hash keyed by pair id; dismissed ids in a set.
+/// Both are kept without expiry.
+pub struct RedisDuplicateReviewQueue {
+    conn: RedisConnection,
+}
+
+impl RedisDuplicateReviewQueue {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl DuplicateReviewQueue for RedisDuplicateReviewQueue {
+    async fn replace(&self, candidates: Vec<DuplicateCandidate>) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let dismissed: BTreeSet<String> = conn
+            .smembers(DISMISSED_DUPLICATES_KEY)
+            .await
+            .map_err(redis_err("duplicates SMEMBERS"))?;
+        let mut entries = Vec::with_capacity(candidates.len());
+        for candidate in candidates.iter().filter(|c| !dismissed.contains(&c.id)) {
+            entries.push((candidate.id.clone(), serde_json::to_string(candidate)?));
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().del(DUPLICATES_KEY);
+        if !entries.is_empty() {
+            pipe.hset_multiple(DUPLICATES_KEY, &entries);
+        }
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(redis_err("duplicates replace"))
+    }
+
+    async fn list(&self) -> Result<Vec<DuplicateCandidate>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(DUPLICATES_KEY)
+            .await
+            .map_err(redis_err("duplicates list"))?;
+        let mut candidates: Vec<DuplicateCandidate> = values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        sort_candidates(&mut candidates);
+        Ok(candidates)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<DuplicateCandidate>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(DUPLICATES_KEY, id)
+            .await
+            .map_err(redis_err("duplicates get"))?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    async fn remove_involving(&self, entity_ids: &[Uuid]) -> Result<()> {
+        let stale: Vec<String> = self
+            .list()
+            .await?
+            .into_iter()
+            .filter(|c| entity_ids.iter().any(|id| c.involves(*id)))
+            .map(|c| c.id)
+            .collect();
+        if stale.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.get().await?;
+        conn.hdel::<_, _, ()>(DUPLICATES_KEY, stale)
+            .await
+            .map_err(redis_err("duplicates remove"))
+    }
+
+    async fn dismiss(&self, id: &str) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let (removed, _): (u64, u64) = redis::pipe()
+            .atomic()
+            .hdel(DUPLICATES_KEY, id)
+            .sadd(DISMISSED_DUPLICATES_KEY, id)
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_err("duplicates dismiss"))?;
+        Ok(removed > 0)
+    }
+}
+
+/// Rules as JSON in a hash keyed by id.
+pub struct RedisAlertRuleStore {
+    conn: RedisConnection,
+}
+
+impl RedisAlertRuleStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl AlertRuleStore for RedisAlertRuleStore {
+    async fn save(&self, rule: &AlertRule) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(rule)?;
+        conn.hset::<_, _, _, ()>(ALERT_RULES_KEY, rule.id.to_string(), json)
+            .await
+            .map_err(redis_err("alert rule save"))
+    }
+
+    async fn list(&self) -> Result<Vec<AlertRule>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(ALERT_RULES_KEY)
+            .await
+            .map_err(redis_err("alert rule list"))?;
+        let mut rules: Vec<AlertRule> = values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        rules.sort_by_key(|r| r.created_at);
+        Ok(rules)
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<AlertRule>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(ALERT_RULES_KEY, id.to_string())
+            .await
+            .map_err(redis_err("alert rule get"))?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let removed: u64 = conn
+            .hdel(ALERT_RULES_KEY, id.to_string())
+            .await
+            .map_err(redis_err("alert rule delete"))?;
+        Ok(removed > 0)
+    }
+}
+
+/// Deny-list entries as JSON in a hash keyed by id.
+pub struct RedisDenyListStore {
+    conn: RedisConnection,
+}
+
+impl RedisDenyListStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl DenyListStore for RedisDenyListStore {
+    async fn save(&self, entry: &DenyEntry) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(entry)?;
+        conn.hset::<_, _, _, ()>(DENY_LIST_KEY, entry.id.to_string(), json)
+            .await
+            .map_err(redis_err("deny list save"))
+    }
+
+    async fn list(&self) -> Result<Vec<DenyEntry>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(DENY_LIST_KEY)
+            .await
+            .map_err(redis_err("deny list list"))?;
+        let mut entries: Vec<DenyEntry> = values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        entries.sort_by_key(|e| e.created_at);
+        Ok(entries)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let removed: u64 = conn
+            .hdel(DENY_LIST_KEY, id.to_string())
+            .await
+            .map_err(redis_err("deny list delete"))?;
+        Ok(removed > 0)
+    }
+}
+
+/// Investigations as JSON in a hash keyed by id.
+pub struct RedisInvestigationStore {
+    conn: RedisConnection,
+}
+
+impl RedisInvestigationStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl InvestigationStore for RedisInvestigationStore {
+    async fn save(&self, investigation: &Investigation) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(investigation)?;
+        conn.hset::<_, _, _, ()>(INVESTIGATIONS_KEY, investigation.id.to_string(), json)
+            .await
+            .map_err(redis_err("investigation save"))
+    }
+
+    async fn list(&self) -> Result<Vec<Investigation>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(INVESTIGATIONS_KEY)
+            .await
+            .map_err(redis_err("investigation list"))?;
+        let mut investigations: Vec<Investigation> = values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        investigations.sort_by_key(|i| std::cmp::Reverse(i.created_at));
+        Ok(investigations)
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Investigation>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(INVESTIGATIONS_KEY, id.to_string())
+            .await
+            .map_err(redis_err("investigation get"))?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let removed: u64 = conn
+            .hdel(INVESTIGATIONS_KEY, id.to_string())
+            .await
+            .map_err(redis_err("investigation delete"))?;
+        Ok(removed > 0)
+    }
+}
+
+/// Subscriptions as JSON in a hash keyed by id.
+pub struct RedisSubscriptionStore {
+    conn: RedisConnection,
+}
+
+impl RedisSubscriptionStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl SubscriptionStore for RedisSubscriptionStore {
+    async fn save(&self, subscription: &Subscription) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(subscription)?;
+        conn.hset::<_, _, _, ()>(SUBSCRIPTIONS_KEY, subscription.id.to_string(), json)
+            .await
+            .map_err(redis_err("subscription save"))
+    }
+
+    async fn list(&self) -> Result<Vec<Subscription>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(SUBSCRIPTIONS_KEY)
+            .await
+            .map_err(redis_err("subscription list"))?;
+        let mut subscriptions: Vec<Subscription> = values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        subscriptions.sort_by_key(|s| s.created_at);
+        Ok(subscriptions)
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Subscription>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(SUBSCRIPTIONS_KEY, id.to_string())
+            .await
+            .map_err(redis_err("subscription get"))?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let removed: u64 = conn
+            .hdel(SUBSCRIPTIONS_KEY, id.to_string())
+            .await
+            .map_err(redis_err("subscription delete"))?;
+        Ok(removed > 0)
+    }
+}
+
+/// Notifications as JSON in a hash, ordered by a sorted set scored on
+/// creation time, with a set per subscription of the keys it was told
+/// about.
+pub struct RedisNotificationStore {
+    conn: RedisConnection,
+}
+
+impl RedisNotificationStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl NotificationStore for RedisNotificationStore {
+    async fn record(&self, notification: Notification) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let added: u64 = conn
+            .sadd(
+                format!("{NOTIFIED_PREFIX}{}", notification.subscription_id),
+                &notification.dedup_key,
+            )
+            .await
+            .map_err(redis_err("notification record"))?;
+        if added == 0 {
+            return Ok(false);
+        }
+
+        let id = notification.id.to_string();
+        let json = serde_json::to_string(&notification)?;
+        redis::pipe()
+            .atomic()
+            .hset(NOTIFICATIONS_KEY, &id, json)
+            .zadd(
+                NOTIFICATIONS_INDEX_KEY,
+                &id,
+                notification.created_at.timestamp_millis(),
+            )
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(redis_err("notification record"))?;
+
+        let stale: Vec<String> = conn
+            .zrange(
+                NOTIFICATIONS_INDEX_KEY,
+                0,
+                -(MAX_NOTIFICATIONS as isize) - 1,
+            )
+            .await
+            .map_err(redis_err("notification trim"))?;
+        if !stale.is_empty() {
+            redis::pipe()
+                .atomic()
+                .hdel(NOTIFICATIONS_KEY, &stale)
+                .zrem(NOTIFICATIONS_INDEX_KEY, &stale)
+                .query_async::<()>(&mut conn)
+                .await
+                .map_err(redis_err("notification trim"))?;
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<Notification>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn.get().await?;
+        let stop = isize::try_from(limit).unwrap_or(isize::MAX) - 1;
+        let ids: Vec<String> = conn
+            .zrevrange(NOTIFICATIONS_INDEX_KEY, 0, stop)
+            .await
+            .map_err(redis_err("notification list"))?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let values: Vec<Option<String>> = redis::cmd("HMGET")
+            .arg(NOTIFICATIONS_KEY)
+            .arg(&ids)
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_err("notification list"))?;
+        Ok(values
+            .into_iter()
+            .flatten()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    async fn save(&self, notification: &Notification) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let exists: bool = conn
+            .hexists(NOTIFICATIONS_KEY, notification.id.to_string())
+            .await
+            .map_err(redis_err("notification lookup"))?;
+        if !exists {
+            return Ok(());
+        }
+        let json = serde_json::to_string(notification)?;
+        conn.hset::<_, _, _, ()>(NOTIFICATIONS_KEY, notification.id.to_string(), json)
+            .await
+            .map_err(redis_err("notification save"))
+    }
+
+    async fn forget(&self, subscription_id: Uuid) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        conn.del::<_, ()>(format!("{NOTIFIED_PREFIX}{subscription_id}"))
+            .await
+            .map_err(redis_err("notification forget"))
+    }
+}
+
+/// Feedback labels as JSON in a hash keyed by [`FeedbackLabel::item_key`],
+/// so relabelling an item overwrites its label.
+pub struct RedisFeedbackStore {
+    conn: RedisConnection,
+}
+
+impl RedisFeedbackStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl FeedbackStore for RedisFeedbackStore {
+    async fn save(&self, label: &FeedbackLabel) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(label)?;
+        conn.hset::<_, _, _, ()>(FEEDBACK_KEY, label.item_key(), json)
+            .await
+            .map_err(redis_err("feedback save"))
+    }
+
+    async fn list(&self) -> Result<Vec<FeedbackLabel>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(FEEDBACK_KEY)
+            .await
+            .map_err(redis_err("feedback list"))?;
+        let mut labels: Vec<FeedbackLabel> = values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        labels.sort_by_key(|l| std::cmp::Reverse(l.created_at));
+        Ok(labels)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let Some(label) = self.list().await?.into_iter().find(|l| l.id == id) else {
+            return Ok(false);
+        };
+        let mut conn = self.conn.get().await?;
+        let removed: u64 = conn
+            .hdel(FEEDBACK_KEY, label.item_key())
+            .await
+            .map_err(redis_err("feedback delete"))?;
+        Ok(removed > 0)
+    }
+}
+
+/// Prompt bundles as JSON in a hash keyed by version, numbered by a
+/// counter so replicas never reuse one; the deployment as a JSON string.
+pub struct RedisPromptBundleStore {
+    conn: RedisConnection,
+}
+
+impl RedisPromptBundleStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl PromptBundleStore for RedisPromptBundleStore {
+    async fn create(
+        &self,
+        note: Option<String>,
+        examples: BTreeMap<String, Vec<FewShotExample>>,
+    ) -> Result<PromptBundle> {
+        let mut conn = self.conn.get().await?;
+        let version: u32 = conn
+            .incr(PROMPT_VERSION_KEY, 1)
+            .await
+            .map_err(redis_err("prompt bundle version"))?;
+        let bundle = PromptBundle {
+            version,
+            created_at: chrono::Utc::now(),
+            note,
+            examples,
+        };
+        let json = serde_json::to_string(&bundle)?;
+        conn.hset::<_, _, _, ()>(PROMPT_BUNDLES_KEY, version, json)
+            .await
+            .map_err(redis_err("prompt bundle save"))?;
+        Ok(bundle)
+    }
+
+    async fn list(&self) -> Result<Vec<PromptBundle>> {
+        let mut conn = self.conn.get().await?;
+        let values: Vec<String> = conn
+            .hvals(PROMPT_BUNDLES_KEY)
+            .await
+            .map_err(redis_err("prompt bundle list"))?;
+        let mut bundles: Vec<PromptBundle> = values
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        bundles.sort_by_key(|b| std::cmp::Reverse(b.version));
+        Ok(bundles)
+    }
+
+    async fn get(&self, version: u32) -> Result<Option<PromptBundle>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(PROMPT_BUNDLES_KEY, version)
+            .await
+            .map_err(redis_err("prompt bundle get"))?;
+        Ok(json.and_then(|j| serde_json::from_str(&j).ok()))
+    }
+
+    async fn deployment(&self) -> Result<PromptDeployment> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .get(PROMPT_DEPLOYMENT_KEY)
+            .await
+            .map_err(redis_err("prompt deployment get"))?;
+        Ok(json
+            .and_then(|j| serde_json::from_str(&j).ok())
+            .unwrap_or_default())
+    }
+
+    async fn set_deployment(&self, deployment: &PromptDeployment) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(deployment)?;
+        conn.set::<_, _, ()>(PROMPT_DEPLOYMENT_KEY, json)
+            .await
+            .map_err(redis_err("prompt deployment save"))
+    }
+}
+
+/// Alerts as JSON in a hash, ordered by a sorted set scored on creation
+/// time, with a hash from dedup key to the open alert holding it.
+pub struct RedisAlertStore {
+    conn: RedisConnection,
+}
+
+impl RedisAlertStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+
+    async fn close(&self, conn: &mut ConnectionManager, alert: &Alert) -> Result<()> {
+        redis::Script::new(CLOSE_SCRIPT)
+            .key(OPEN_ALERTS_KEY)
+            .arg(&alert.dedup_key)
+            .arg(alert.id.to_string())
+            .invoke_async::<i64>(conn)
+            .await
+            .map_err(redis_err("alert close"))?;
+        Ok(())
+    }
+
+    async fn fetch(&self, conn: &mut ConnectionManager, ids: &[String]) -> Result<Vec<Alert>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let values: Vec<Option<String>> = redis::cmd("HMGET")
+            .arg(ALERTS_KEY)
+            .arg(ids)
+            .query_async(conn)
+            .await
+            .map_err(redis_err("alert list"))?;
+        Ok(values
+            .into_iter()
+            .flatten()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl AlertStore for RedisAlertStore {
+    async fn raise(&self, alert: Alert) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let json = serde_json::to_string(&alert)?;
+        let raised: i64 = redis::Script::new(RAISE_SCRIPT)
+            .key(OPEN_ALERTS_KEY)
+            .key(ALERTS_KEY)
+            .key(ALERTS_INDEX_KEY)
+            .arg(&alert.dedup_key)
+            .arg(alert.id.to_string())
+            .arg(json)
+            .arg(alert.created_at.timestamp_millis())
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_err("alert raise"))?;
+        if raised == 0 {
+            return Ok(false);
+        }
+
+        let stale: Vec<String> = conn
+            .zrange(ALERTS_INDEX_KEY, 0, -(MAX_ALERTS as isize) - 1)
+            .await
+            .map_err(redis_err("alert trim"))?;
+        if !stale.is_empty() {
+            for dropped in self.fetch(&mut conn, &stale).await? {
+                self.close(&mut conn, &dropped).await?;
+            }
+            redis::pipe()
+                .atomic()
+                .hdel(ALERTS_KEY, &stale)
+                .zrem(ALERTS_INDEX_KEY, &stale)
+                .query_async::<()>(&mut conn)
+                .await
+                .map_err(redis_err("alert trim"))?;
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, status: AlertStatus, limit: usize) -> Result<Vec<Alert>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn.get().await?;
+        // Only the unfiltered listing can stop at `limit` ids up front
+        let stop = match status {
+            AlertStatus::All => isize::try_from(limit).unwrap_or(isize::MAX) - 1,
+            _ => -1,
+        };
+        let ids: Vec<String> = conn
+            .zrevrange(ALERTS_INDEX_KEY, 0, stop)
+            .await
+            .map_err(redis_err("alert list"))?;
+        let alerts = self.fetch(&mut conn, &ids).await?;
+        Ok(alerts
+            .into_iter()
+            .filter(|a| status.matches(a))
+            .take(limit)
+            .collect())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Alert>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(ALERTS_KEY, id.to_string())
+            .await
+            .map_err(redis_err("alert get"))?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    async fn save(&self, alert: &Alert) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let exists: bool = conn
+            .hexists(ALERTS_KEY, alert.id.to_string())
+            .await
+            .map_err(redis_err("alert lookup"))?;
+        if !exists {
+            return Ok(());
+        }
+        let json = serde_json::to_string(alert)?;
+        conn.hset::<_, _, _, ()>(ALERTS_KEY, alert.id.to_string(), json)
+            .await
+            .map_err(redis_err("alert save"))?;
+        if !alert.is_open() {
+            self.close(&mut conn, alert).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Quota counters as plain integers under `argus:quota:...`.
+pub struct RedisQuotaStore {
+    conn: RedisConnection,
+}
+
+impl RedisQuotaStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl QuotaStore for RedisQuotaStore {
+    async fn reserve(&self, key: &str, requested: u64, limit: u64) -> Result<u64> {
+        let mut conn = self.conn.get().await?;
+        redis::Script::new(RESERVE_SCRIPT)
+            .key(format!("{QUOTA_PREFIX}{key}"))
+            .arg(requested)
+            .arg(limit)
+            .arg(COUNTER_TTL_SECS)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_err("quota reserve"))
+    }
+
+    async fn add(&self, key: &str, amount: u64) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let key = format!("{QUOTA_PREFIX}{key}");
+        redis::pipe()
+            .atomic()
+            .incr(&key, amount)
+            .expire(&key, COUNTER_TTL_SECS as i64)
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(redis_err("quota INCRBY"))
+    }
+
+    async fn get(&self, key: &str) -> Result<u64> {
+        let mut conn = self.conn.get().await?;
+        let count: Option<u64> = conn
+            .get(format!("{QUOTA_PREFIX}{key}"))
+            .await
+            .map_err(redis_err("quota GET"))?;
+        Ok(count.unwrap_or(0))
+    }
+}
+
+/// Documents as JSON in a list per source, with the sources in a set.
+pub struct RedisSpilloverQueue {
+    conn: RedisConnection,
+}
+
+impl RedisSpilloverQueue {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl SpilloverQueue for RedisSpilloverQueue {
+    async fn push(&self, source: &str, documents: Vec<RawDocument>) -> Result<usize> {
+        if documents.is_empty() {
+            return Ok(0);
+        }
+        let mut conn = self.conn.get().await?;
+        let mut entries = Vec::with_capacity(documents.len());
+        for doc in &documents {
+            entries.push(serde_json::to_string(doc)?);
+        }
+        let key = format!("{SPILLOVER_PREFIX}{source}");
+        let (len, _, _): (usize, (), u64) = redis::pipe()
+            .atomic()
+            .rpush(&key, entries)
+            .ltrim(&key, -(MAX_SPILLOVER_PER_SOURCE as isize), -1)
+            .sadd(SPILLOVER_SOURCES_KEY, source)
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_err("spillover push"))?;
+        Ok(len.saturating_sub(MAX_SPILLOVER_PER_SOURCE))
+    }
+
+    async fn pop(&self, source: &str, max: usize) -> Result<Vec<RawDocument>> {
+        let Some(count) = NonZeroUsize::new(max) else {
+            return Ok(Vec::new());
+        };
+        let mut conn = self.conn.get().await?;
+        let values: Option<Vec<String>> = conn
+            .lpop(format!("{SPILLOVER_PREFIX}{source}"), Some(count))
+            .await
+            .map_err(redis_err("spillover pop"))?;
+        Ok(values
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    async fn sizes(&self) -> Result<BTreeMap<String, usize>> {
+        let mut conn = self.conn.get().await?;
+        let sources: BTreeSet<String> = conn
+            .smembers(SPILLOVER_SOURCES_KEY)
+            .await
+            .map_err(redis_err("spillover SMEMBERS"))?;
+        let mut sizes = BTreeMap::new();
+        for source in sources {
+            let len: usize = conn
+                .llen(format!("{SPILLOVER_PREFIX}{source}"))
+                .await
+                .map_err(redis_err("spillover LLEN"))?;
+            if len > 0 {
+                sizes.insert(source, len);
+            }
+        }
+        Ok(sizes)
+    }
+}
+
+pub struct RedisExtractionQueue {
+    conn: RedisConnection,
+}
+
+impl RedisExtractionQueue {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl ExtractionQueue for RedisExtractionQueue {
+    async fn push(&self, job: ExtractionJob) -> Result<()> {
+        let json = serde_json::to_string(&job)?;
+        let mut conn = self.conn.get().await?;
+        let _: () = conn
+            .rpush(EXTRACTION_JOBS_KEY, json)
+            .await
+            .map_err(redis_err("extraction job push"))?;
+        Ok(())
+    }
+
+    async fn pop(&self, worker: &str) -> Result<Option<ClaimedJob>> {
+        let mut conn = self.conn.get().await?;
+        // LMOVE rather than a blocking BLMOVE, which would stall every other
+        // command sharing the multiplexed connection
+        let json: Option<String> = redis::Script::new(EXTRACTION_POP_SCRIPT)
+            .key(EXTRACTION_JOBS_KEY)
+            .key(format!("{EXTRACTION_PROCESSING_PREFIX}{worker}"))
+            .key(EXTRACTION_WORKERS_KEY)
+            .arg(worker)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_err("extraction job pop"))?;
+        match json {
+            Some(json) => Ok(Some(ClaimedJob {
+                job: serde_json::from_str(&json)?,
+                receipt: json,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    async fn ack(&self, worker: &str, receipt: &str) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let _: () = conn
+            .lrem(
+                format!("{EXTRACTION_PROCESSING_PREFIX}{worker}"),
+                1,
+                receipt,
+            )
+            .await
+            .map_err(redis_err("extraction job LREM"))?;
+        Ok(())
+    }
+
+    async fn heartbeat(&self, worker: &str, ttl: Duration) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let _: () = conn
+            .set_ex(
+                format!("{EXTRACTION_HEARTBEAT_PREFIX}{worker}"),
+                1,
+                ttl.as_secs().max(1),
+            )
+            .await
+            .map_err(redis_err("extraction worker heartbeat"))?;
+        Ok(())
+    }
+
+    async fn requeue(&self, worker: &str) -> Result<usize> {
+        let mut conn = self.conn.get().await?;
+        redis::Script::new(EXTRACTION_REQUEUE_SCRIPT)
+            .key(format!("{EXTRACTION_PROCESSING_PREFIX}{worker}"))
+            .key(EXTRACTION_JOBS_KEY)
+            .key(EXTRACTION_WORKERS_KEY)
+            .arg(worker)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_err("extraction job requeue"))
+    }
+
+    async fn requeue_stale(&self) -> Result<usize> {
+        let mut conn = self.conn.get().await?;
+        let workers: Vec<String> = conn
+            .smembers(EXTRACTION_WORKERS_KEY)
+            .await
+            .map_err(redis_err("extraction workers SMEMBERS"))?;
+        let mut requeued = 0;
+        for worker in workers {
+            let alive: bool = conn
+                .exists(format!("{EXTRACTION_HEARTBEAT_PREFIX}{worker}"))
+                .await
+                .map_err(redis_err("extraction worker heartbeat"))?;
+            if !alive {
+                requeued += self.requeue(&worker).await?;
+            }
+        }
+        Ok(requeued)
+    }
+
+    async fn pending(&self) -> Result<usize> {
+        let mut conn = self.conn.get().await?;
+        conn.llen(EXTRACTION_JOBS_KEY)
+            .await
+            .map_err(redis_err("extraction job LLEN"))
+    }
+}
+
+/// Extraction failures as JSON in a hash, ordered by a sorted set scored
+/// on failure time.
+pub struct RedisExtractionFailureStore {
+    conn: RedisConnection,
+}
+
+impl RedisExtractionFailureStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl ExtractionFailureStore for RedisExtractionFailureStore {
+    async fn record(&self, failure: ExtractionFailure) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let id = failure.id.to_string();
+        let json = serde_json::to_string(&failure)?;
+        redis::pipe()
+            .atomic()
+            .hset(EXTRACTION_FAILURES_KEY, &id, json)
+            .zadd(
+                EXTRACTION_FAILURES_INDEX_KEY,
+                &id,
+                failure.failed_at.timestamp_millis(),
+            )
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(redis_err("extraction failure record"))?;
+
+        let stale: Vec<String> = conn
+            .zrange(
+                EXTRACTION_FAILURES_INDEX_KEY,
+                0,
+                -(MAX_EXTRACTION_FAILURES as isize) - 1,
+            )
+            .await
+            .map_err(redis_err("extraction failure trim"))?;
+        if !stale.is_empty() {
+            redis::pipe()
+                .atomic()
+                .hdel(EXTRACTION_FAILURES_KEY, &stale)
+                .zrem(EXTRACTION_FAILURES_INDEX_KEY, &stale)
+                .query_async::<()>(&mut conn)
+                .await
+                .map_err(redis_err("extraction failure trim"))?;
+        }
+        Ok(())
+    }
+
+    async fn list(&self, agent_name: Option<&str>, limit: usize) -> Result<Vec<ExtractionFailure>> {
+        let mut conn = self.conn.get().await?;
+        let ids: Vec<String> = conn
+            .zrevrange(EXTRACTION_FAILURES_INDEX_KEY, 0, -1)
+            .await
+            .map_err(redis_err("extraction failure list"))?;
+        if ids.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let values: Vec<Option<String>> = redis::cmd("HMGET")
+            .arg(EXTRACTION_FAILURES_KEY)
+            .arg(&ids)
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_err("extraction failure list"))?;
+
+        Ok(values
+            .into_iter()
+            .flatten()
+            .filter_map(|json| serde_json::from_str::<ExtractionFailure>(&json).ok())
+            .filter(|f| agent_name.is_none_or(|name| f.agent_name == name))
+            .take(limit)
+            .collect())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<ExtractionFailure>> {
+        let mut conn = self.conn.get().await?;
+        let json: Option<String> = conn
+            .hget(EXTRACTION_FAILURES_KEY, id.to_string())
+            .await
+            .map_err(redis_err("extraction failure get"))?;
+        json.map(|j| serde_json::from_str(&j).map_err(ArgusError::from))
+            .transpose()
+    }
+
+    async fn remove(&self, id: Uuid) -> Result<bool> {
+        let mut conn = self.conn.get().await?;
+        let id = id.to_string();
+        let (removed, _): (u64, u64) = redis::pipe()
+            .atomic()
+            .hdel(EXTRACTION_FAILURES_KEY, &id)
+            .zrem(EXTRACTION_FAILURES_INDEX_KEY, &id)
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_err("extraction failure remove"))?;
+        Ok(removed > 0)
+    }
+}
+
+/// Usage as one hash per day and consumer under `argus:usage:{day}:{consumer}`,
+/// with the day's consumers in a set beside it.
+pub struct RedisUsageStore {
+    conn: RedisConnection,
+}
+
+impl RedisUsageStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self { conn }
+    }
+}
+
+fn usage_consumers_key(day: NaiveDate) -> String {
+    format!("{USAGE_PREFIX}{day}:consumers")
+}
+
+fn usage_key(day: NaiveDate, consumer: &str) -> String {
+    format!("{USAGE_PREFIX}{day}:{consumer}")
+}
+
+#[async_trait]
+impl UsageStore for RedisUsageStore {
+    async fn record(&self, consumer: &str, day: NaiveDate, record: &UsageRecord) -> Result<()> {
+        let mut conn = self.conn.get().await?;
+        let consumer: String = redis::Script::new(USAGE_CONSUMER_SCRIPT)
+            .key(usage_consumers_key(day))
+            .arg(consumer)
+            .arg(MAX_CONSUMERS_PER_DAY)
+            .arg(OTHER_CONSUMERS)
+            .arg(USAGE_TTL_SECS)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(redis_err("usage consumer"))?;
+        let key = usage_key(day, &consumer);
+        redis::pipe()
+            .atomic()
+            .hincr(&key, "requests", 1)
+            .hincr(
+                &key,
+                format!("{USAGE_ENDPOINT_FIELD}{}", record.endpoint),
+                1,
+            )
+            .hincr(&key, "reasoning_tokens", record.reasoning_tokens)
+            .hincr(&key, "exported_records", record.exported_records)
+            .expire(&key, USAGE_TTL_SECS)
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(redis_err("usage record"))
+    }
+
+    async fn totals(&self, since: NaiveDate) -> Result<BTreeMap<String, UsageCounts>> {
+        let mut conn = self.conn.get().await?;
+        let today = chrono::Utc::now().date_naive();
+        let mut totals: BTreeMap<String, UsageCounts> = BTreeMap::new();
+        for day in since.iter_days().take_while(|day| *day <= today) {
+            let consumers: Vec<String> = conn
+                .smembers(usage_consumers_key(day))
+                .await
+                .map_err(redis_err("usage consumers"))?;
+            for consumer in consumers {
+                let fields: BTreeMap<String, u64> = conn
+                    .hgetall(usage_key(day, &consumer))
+                    .await
+                    .map_err(redis_err("usage HGETALL"))?;
+                let mut counts = UsageCounts::default();
+                for (field, value) in fields {
+                    match field.as_str() {
+                        "requests" => counts.requests = value,
+                        "reasoning_tokens" => counts.reasoning_tokens = value,
+                        "exported_records" => counts.exported_records = value,
+                        _ => {
+                            if let Some(endpoint) = field.strip_prefix(USAGE_ENDPOINT_FIELD) {
+                                counts.endpoints.insert(endpoint.to_string(), value);
+                            }
+                        }
+                    }
+                }
+                totals.entry(consumer).or_default().merge(&counts);
+            }
+        }
+        Ok(totals)
+    }
+}
@@ -1,19 +1,79 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
-use argus_core::api_types::AgentRunStatus;
-use argus_core::{Agent, AppConfig};
-use argus_extraction::LlmExtractionPipeline;
+use argus_coldstore::ColdStorage;
+use argus_core::{
+    Agent, AgentPauseStore, AlertRuleStore, AlertStore, AppConfig, DedupStore, DenyListStore,
+    DuplicateReviewQueue, ExtractionFailureStore, ExtractionQueue, FeedbackStore, IdempotencyStore,
+    InvestigationStore, LockManager, NotificationStore, PromptBundleStore, QuotaStore, RateLimiter,
+    RetryQueue, RunStore, SpilloverQueue, SubscriptionStore, UsageStore,
+};
+use argus_extraction::{LocationGeocoder, PipelineRouter, Validator};
 use argus_graph::Neo4jGraphStore;
 use argus_reasoning::LlmReasoningEngine;
+use argus_vector::{QdrantVectorStore, VoyageEmbeddingProvider};
+
+use crate::alerts::notify::Notifier;
+use crate::graphql::ArgusSchema;
+use crate::live_config::LiveConfig;
+use crate::run_logs::RunLogBuffer;
+use crate::subscriptions::SubscriptionEngine;
+use crate::worker::ServerRole;
+use crate::write_buffer::GraphWriteBuffer;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub config: AppConfig,
+    pub live_config: Arc<LiveConfig>,
     pub agents: HashMap<String, Arc<dyn Agent>>,
     pub graph: Arc<Neo4jGraphStore>,
-    pub extraction: Arc<LlmExtractionPipeline>,
+    /// Picks each document's pipeline from `EXTRACTION_PIPELINES`.
+    pub extraction: Arc<PipelineRouter>,
+    pub validator: Arc<Validator>,
+    /// Unset while `GEOCODER` is `none`.
+    pub geocoder: Option<Arc<LocationGeocoder>>,
     pub reasoning: Arc<LlmReasoningEngine>,
-    pub runs: Arc<RwLock<Vec<AgentRunStatus>>>,
+    pub embeddings: Arc<VoyageEmbeddingProvider>,
+    pub vectors: Arc<QdrantVectorStore>,
+    pub writes: Arc<GraphWriteBuffer>,
+    /// The Parquet archive of raw observations. Unset while
+    /// `COLD_STORAGE_URL` is empty.
+    pub cold_storage: Option<Arc<ColdStorage>>,
+    pub graphql: ArgusSchema,
+    pub runs: Arc<dyn RunStore>,
+    pub dedup: Arc<dyn DedupStore>,
+    pub rate_limiter: Arc<dyn RateLimiter>,
+    pub locks: Arc<dyn LockManager>,
+    pub retries: Arc<dyn RetryQueue>,
+    pub idempotency: Arc<dyn IdempotencyStore>,
+    pub pauses: Arc<dyn AgentPauseStore>,
+    pub duplicates: Arc<dyn DuplicateReviewQueue>,
+    pub alert_rules: Arc<dyn AlertRuleStore>,
+    pub alerts: Arc<dyn AlertStore>,
+    pub deny_list: Arc<dyn DenyListStore>,
+    pub feedback: Arc<dyn FeedbackStore>,
+    pub investigations: Arc<dyn InvestigationStore>,
+    pub subscriptions: Arc<dyn SubscriptionStore>,
+    /// The feed behind `GET /api/notifications`.
+    pub notifications: Arc<dyn NotificationStore>,
+    /// Retries failed subscription webhooks from the `alert_digest` job.
+    pub subscription_engine: Arc<SubscriptionEngine>,
+    pub prompts: Arc<dyn PromptBundleStore>,
+    pub notifier: Arc<Notifier>,
+    pub quotas: Arc<dyn QuotaStore>,
+    pub spillover: Arc<dyn SpilloverQueue>,
+    pub extraction_jobs: Arc<dyn ExtractionQueue>,
+    /// Unparseable model output, for `/api/admin/extraction-failures`.
+    pub extraction_failures: Arc<dyn ExtractionFailureStore>,
+    /// Requests per API token, for `/api/admin/usage`.
+    pub usage: Arc<dyn UsageStore>,
+    pub role: ServerRole,
+    pub run_logs: Arc<RunLogBuffer>,
+    pub instance_id: String,
+}
+
+impl AppState {
+    /// The configuration in effect now; see [`LiveConfig::current`].
+    pub fn config(&self) -> Arc<AppConfig> {
+        self.live_config.current()
+    }
 }
@@ -0,0 +1,444 @@
+//! Entity subscriptions checked against every batch the write buffer
+//! stores, next to the alert rules.
+//!
+//! A subscription is told about each subject once: a relationship (by
+//! type and the entity at the other end), a sanction listing, or a
+//! document the entity was extracted from. Notifications go to the feed
+//! at `GET /api/notifications` and, for subscriptions with a webhook, are
+//! POSTed to it off the write path; failed posts are retried by the
+//! `alert_digest` job. Writes that bypass the buffer are not checked.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use argus_core::subscriptions::MAX_NOTIFICATIONS;
+use argus_core::{
+    Delivery, DeliveryStatus, Entity, ExtractionResult, Notification, NotificationStore,
+    RelationType, Result, Subscription, SubscriptionEvent, SubscriptionStore,
+};
+
+use crate::alerts::entity_key;
+use crate::write_buffer::WriteObserver;
+
+/// [`Delivery::channel`] of webhook posts.
+const WEBHOOK_CHANNEL: &str = "webhook";
+const WEBHOOK_TIMEOUT_SECONDS: u64 = 15;
+/// Pending posts attempted more recently than this may still be in flight
+/// and are left alone by [`SubscriptionEngine::retry_webhooks`].
+const WEBHOOK_RETRY_AFTER_SECONDS: i64 = 2 * WEBHOOK_TIMEOUT_SECONDS as i64;
+
+pub struct SubscriptionEngine {
+    subscriptions: Arc<dyn SubscriptionStore>,
+    notifications: Arc<dyn NotificationStore>,
+    http: reqwest::Client,
+}
+
+impl SubscriptionEngine {
+    pub fn new(
+        subscriptions: Arc<dyn SubscriptionStore>,
+        notifications: Arc<dyn NotificationStore>,
+    ) -> Self {
+        Self {
+            subscriptions,
+            notifications,
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(WEBHOOK_TIMEOUT_SECONDS))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Record what `results` mean for each subscription, returning how
+    /// many new notifications there were.
+    pub async fn evaluate(&self, results: &[ExtractionResult]) -> Result<usize> {
+        let subscriptions = self.subscriptions.list().await?;
+        if subscriptions.is_empty() {
+            return Ok(0);
+        }
+
+        let mut recorded = 0;
+        let mut posts = Vec::new();
+        for subscription in &subscriptions {
+            for result in results {
+                for mut notification in notifications_for(subscription, result) {
+                    // Stamped before it is stored so a retry does not race the post below
+                    if let Some(delivery) = notification.delivery.as_mut() {
+                        delivery.attempted_at = Some(Utc::now());
+                    }
+                    if !self.notifications.record(notification.clone()).await? {
+                        continue;
+                    }
+                    recorded += 1;
+                    if let Some(url) = &subscription.webhook_url {
+                        posts.push((url.clone(), notification));
+                    }
+                }
+            }
+        }
+        if !posts.is_empty() {
+            let (http, notifications) = (self.http.clone(), self.notifications.clone());
+            tokio::spawn(async move {
+                for (url, notification) in posts {
+                    let outcome = post(&http, &url, &notification).await;
+                    save_delivery(notifications.as_ref(), notification, outcome).await;
+                }
+            });
+        }
+        if recorded > 0 {
+            info!(recorded, "Recorded subscription notifications");
+        }
+        Ok(recorded)
+    }
+
+    /// Post every notification whose webhook delivery is still pending and
+    /// not possibly in flight, oldest first. Returns how many went through.
+    pub async fn retry_webhooks(&self) -> Result<usize> {
+        let in_flight_since = Utc::now() - Duration::seconds(WEBHOOK_RETRY_AFTER_SECONDS);
+        let pending: Vec<Notification> = self
+            .notifications
+            .list(MAX_NOTIFICATIONS)
+            .await?
+            .into_iter()
+            .filter(|n| {
+                n.delivery.as_ref().is_some_and(|d| {
+                    d.status == DeliveryStatus::Pending
+                        && d.attempted_at.is_none_or(|at| at < in_flight_since)
+                })
+            })
+            .collect();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        let urls: HashMap<Uuid, String> = self
+            .subscriptions
+            .list()
+            .await?
+            .into_iter()
+            .filter_map(|s| Some((s.id, s.webhook_url?)))
+            .collect();
+
+        let mut sent = 0;
+        for mut notification in pending.into_iter().rev() {
+            if let Some(delivery) = notification.delivery.as_mut() {
+                delivery.attempted_at = Some(Utc::now());
+            }
+            if let Err(e) = self.notifications.save(&notification).await {
+                warn!(notification_id = %notification.id, error = %e, "Failed to record webhook attempt");
+                continue;
+            }
+            let outcome = match urls.get(&notification.subscription_id) {
+                Some(url) => post(&self.http, url, &notification).await,
+                None => Err("subscription no longer has a webhook".to_string()),
+            };
+            if outcome.is_ok() {
+                sent += 1;
+            }
+            save_delivery(self.notifications.as_ref(), notification, outcome).await;
+        }
+        if sent > 0 {
+            info!(sent, "Sent pending subscription webhooks");
+        }
+        Ok(sent)
+    }
+}
+
+#[async_trait]
+impl WriteObserver for SubscriptionEngine {
+    async fn stored(&self, results: &[ExtractionResult]) {
+        if let Err(e) = self.evaluate(results).await {
+            warn!(error = %e, "Failed to evaluate entity subscriptions");
+        }
+    }
+}
+
+async fn post(
+    http: &reqwest::Client,
+    url: &str,
+    notification: &Notification,
+) -> std::result::Result<(), String> {
+    let body = Notification {
+        delivery: None,
+        ..notification.clone()
+    };
+    let response = http
+        .post(url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("webhook returned {}", response.status()))
+    }
+}
+
+async fn save_delivery(
+    store: &dyn NotificationStore,
+    mut notification: Notification,
+    outcome: std::result::Result<(), String>,
+) {
+    if let Some(delivery) = notification.delivery.as_mut() {
+        delivery.record(outcome);
+    }
+    if let Err(e) = store.save(&notification).await {
+        warn!(notification_id = %notification.id, error = %e, "Failed to record webhook delivery");
+    }
+}
+
+/// What one stored result tells `subscription`, before deduplication.
+pub fn notifications_for(
+    subscription: &Subscription,
+    result: &ExtractionResult,
+) -> Vec<Notification> {
+    let followed: Vec<&Entity> = result
+        .entities
+        .iter()
+        .filter(|e| subscription.follows(e))
+        .collect();
+    let is_followed =
+        |id: Uuid| id == subscription.entity_id || followed.iter().any(|e| e.id == id);
+    let find = |id: Uuid| result.entities.iter().find(|e| e.id == id);
+    let name = |id: Uuid| {
+        if is_followed(id) {
+            subscription.entity_name.clone()
+        } else {
+            find(id).map_or(id.to_string(), |e| e.name.clone())
+        }
+    };
+    let key = |id: Uuid| {
+        if is_followed(id) {
+            "followed".to_string()
+        } else {
+            find(id).map_or(id.to_string(), entity_key)
+        }
+    };
+    let notification =
+        |event: SubscriptionEvent, message: String, source: &str, dedup_key: String| Notification {
+            id: Uuid::new_v4(),
+            subscription_id: subscription.id,
+            subscriber: subscription.subscriber.clone(),
+            entity_id: subscription.entity_id,
+            entity_name: subscription.entity_name.clone(),
+            event,
+            message,
+            source: source.to_string(),
+            raw_source: result.raw_source.clone(),
+            relationship_id: None,
+            dedup_key,
+            created_at: Utc::now(),
+            delivery: subscription
+                .webhook_url
+                .as_ref()
+                .map(|_| Delivery::pending(WEBHOOK_CHANNEL.to_string())),
+        };
+
+    let mut notifications = Vec::new();
+    if let Some(entity) = followed.first() {
+        if subscription.wants(SubscriptionEvent::NewDocument) && !result.raw_source.is_empty() {
+            notifications.push(notification(
+                SubscriptionEvent::NewDocument,
+                format!(
+                    "{} appears in {}",
+                    subscription.entity_name, result.raw_source
+                ),
+                &entity.source,
+                format!("doc:{}", result.raw_source),
+            ));
+        }
+    }
+    for rel in &result.relationships {
+        let (source, target) = (rel.source_entity_id, rel.target_entity_id);
+        if is_followed(source) == is_followed(target) {
+            continue;
+        }
+        let event = if rel.relation_type == RelationType::SanctionedBy {
+            SubscriptionEvent::SanctionsChange
+        } else {
+            SubscriptionEvent::NewRelationship
+        };
+        if !subscription.wants(event) {
+            continue;
+        }
+        let mut notification = notification(
+            event,
+            format!("{} {} {}", name(source), rel.relation_type, name(target)),
+            &rel.source,
+            format!("rel:{}:{}:{}", key(source), rel.relation_type, key(target)),
+        );
+        notification.relationship_id = Some(rel.id);
+        notifications.push(notification);
+    }
+    notifications
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::{EntityType, Relationship};
+
+    use crate::shared::{MemoryNotificationStore, MemorySubscriptionStore};
+
+    fn subscription(entity: &Entity, events: &[SubscriptionEvent]) -> Subscription {
+        Subscription {
+            id: Uuid::new_v4(),
+            entity_id: entity.id,
+            entity_name: entity.name.clone(),
+            entity_type: entity.entity_type.clone(),
+            identifiers: entity.identifiers.clone(),
+            events: events.to_vec(),
+            subscriber: Some("analyst-7".to_string()),
+            webhook_url: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// `name` listed by OFAC and owning a shipping company, as extracted
+    /// from `raw_source` (so under fresh ids).
+    fn listing(name: &str, raw_source: &str) -> ExtractionResult {
+        let org = Entity::new(
+            EntityType::Organization,
+            name.to_string(),
+            "opensanctions".to_string(),
+        );
+        let list = Entity::new(
+            EntityType::Sanction,
+            "OFAC SDN".to_string(),
+            "opensanctions".to_string(),
+        );
+        let ship = Entity::new(
+            EntityType::Organization,
+            "Borealis Shipping".to_string(),
+            "opensanctions".to_string(),
+        );
+        let sanctioned = Relationship::new(
+            org.id,
+            list.id,
+            RelationType::SanctionedBy,
+            "opensanctions".to_string(),
+        );
+        let owns = Relationship::new(
+            org.id,
+            ship.id,
+            RelationType::OwnerOf,
+            "opensanctions".to_string(),
+        );
+        ExtractionResult {
+            entities: vec![org, list, ship],
+            relationships: vec![sanctioned, owns],
+            raw_source: raw_source.to_string(),
+            extracted_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn followed_entities_are_notified_once_per_subject() {
+        let subscriptions = Arc::new(MemorySubscriptionStore::default());
+        let notifications = Arc::new(MemoryNotificationStore::default());
+        let mut acme = Entity::new(
+            EntityType::Organization,
+            "Acme Ltd".to_string(),
+            "opencorporates".to_string(),
+        );
+        acme.identifiers
+            .insert("lei".to_string(), "5493001KJTIIGC8Y1R12".to_string());
+        let everything = subscription(&acme, &SubscriptionEvent::ALL);
+        subscriptions.save(&everything).await.unwrap();
+        subscriptions
+            .save(&subscription(&acme, &[SubscriptionEvent::SanctionsChange]))
+            .await
+            .unwrap();
+        let engine = SubscriptionEngine::new(subscriptions, notifications.clone());
+
+        // Found by name despite the new id: document, sanction, ownership,
+        // and the sanction again for the narrower subscription
+        assert_eq!(
+            engine
+                .evaluate(&[listing("ACME LTD", "opensanctions:1")])
+                .await
+                .unwrap(),
+            4
+        );
+        // The same listing again is old news; a new document is not
+        assert_eq!(
+            engine
+                .evaluate(&[listing("Acme Ltd", "opensanctions:1")])
+                .await
+                .unwrap(),
+            0
+        );
+        let mut renamed = listing("Acme Limited", "opensanctions:2");
+        renamed.entities[0].identifiers = acme.identifiers.clone();
+        assert_eq!(engine.evaluate(&[renamed]).await.unwrap(), 1);
+        assert_eq!(
+            engine
+                .evaluate(&[listing("Zenith AG", "opensanctions:3")])
+                .await
+                .unwrap(),
+            0
+        );
+
+        let feed = notifications.list(10).await.unwrap();
+        assert_eq!(feed.len(), 5);
+        assert_eq!(feed[0].event, SubscriptionEvent::NewDocument);
+        assert_eq!(feed[0].raw_source, "opensanctions:2");
+        let sanctions: Vec<&Notification> = feed
+            .iter()
+            .filter(|n| {
+                n.subscription_id == everything.id && n.event == SubscriptionEvent::SanctionsChange
+            })
+            .collect();
+        assert_eq!(sanctions.len(), 1);
+        assert_eq!(sanctions[0].message, "Acme Ltd sanctioned_by OFAC SDN");
+        assert_eq!(sanctions[0].entity_id, acme.id);
+        assert!(feed.iter().all(|n| n.delivery.is_none()));
+    }
+
+    #[tokio::test]
+    async fn webhook_retries_skip_posts_that_may_be_in_flight() {
+        let subscriptions = Arc::new(MemorySubscriptionStore::default());
+        let notifications = Arc::new(MemoryNotificationStore::default());
+        let acme = Entity::new(
+            EntityType::Organization,
+            "Acme Ltd".to_string(),
+            "opencorporates".to_string(),
+        );
+        let mut followed = subscription(&acme, &[SubscriptionEvent::SanctionsChange]);
+        // Nothing listens here, so every post fails
+        followed.webhook_url = Some("http://127.0.0.1:9/hook".to_string());
+        subscriptions.save(&followed).await.unwrap();
+        let engine = SubscriptionEngine::new(subscriptions, notifications.clone());
+
+        let mut notification =
+            notifications_for(&followed, &listing("Acme Ltd", "opensanctions:1")).remove(0);
+        notification.delivery.as_mut().unwrap().attempted_at = Some(Utc::now());
+        notifications.record(notification.clone()).await.unwrap();
+        assert_eq!(engine.retry_webhooks().await.unwrap(), 0);
+        assert_eq!(
+            notifications.list(10).await.unwrap()[0]
+                .delivery
+                .as_ref()
+                .unwrap()
+                .attempts,
+            0
+        );
+
+        notification.delivery.as_mut().unwrap().attempted_at =
+            Some(Utc::now() - Duration::seconds(WEBHOOK_RETRY_AFTER_SECONDS + 1));
+        notifications.save(&notification).await.unwrap();
+        assert_eq!(engine.retry_webhooks().await.unwrap(), 0);
+        let delivery = notifications.list(10).await.unwrap()[0]
+            .delivery
+            .clone()
+            .unwrap();
+        assert_eq!(
+            (delivery.status, delivery.attempts),
+            (DeliveryStatus::Pending, 1)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Log output, per-run log capture and OpenTelemetry trace export.
+//!
+//! Traces go out over OTLP/HTTP when `OTEL_EXPORTER_OTLP_ENDPOINT` is set
+//! (e.g. `http://localhost:4318` for Jaeger or Tempo). A scheduled run is
+//! one trace: `agent_run`, with `collect` (`http_request` per upstream call),
+//! `extract` (`llm_request` per document) and `store` (`neo4j` and
+//! `neo4j_transaction`) beneath it. The other standard `OTEL_*` variables,
+//! such as `OTEL_SERVICE_NAME` and `OTEL_TRACES_SAMPLER`, apply as usual.
+
+use std::sync::Arc;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+use crate::run_logs::{RunLogBuffer, RunLogLayer};
+
+pub struct Telemetry {
+    pub run_logs: Arc<RunLogBuffer>,
+    tracer_provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    /// Export spans still buffered; finished spans otherwise go out in
+    /// batches every few seconds.
+    pub fn shutdown(&self) {
+        if let Some(provider) = &self.tracer_provider {
+            if let Err(e) = provider.shutdown() {
+                tracing::warn!(error = %e, "Failed to flush traces");
+            }
+        }
+    }
+}
+
+/// Install the global subscriber. In MCP mode stdout carries the protocol,
+/// so logs go to stderr. Read from the environment rather than
+/// `AppConfig`, so the config's own parse warnings are already captured.
+pub fn init(mcp_mode: bool) -> Telemetry {
+    let filter = EnvFilter::from_default_env().add_directive("argus=info".parse().unwrap());
+    let writer = if mcp_mode {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    let json_logs =
+        std::env::var("LOG_FORMAT").is_ok_and(|format| format.trim().eq_ignore_ascii_case("json"));
+
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok();
+    let (tracer_provider, export_error) = match tracer_provider(endpoint.as_deref()) {
+        Ok(provider) => (provider, None),
+        Err(e) => (None, Some(e)),
+    };
+    let otel = tracer_provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer("argus")));
+
+    let run_logs = Arc::new(RunLogBuffer::default());
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(RunLogLayer::new(run_logs.clone()))
+        .with(otel);
+    if json_logs {
+        registry
+            .with(tracing_subscriber::fmt::layer().json().with_writer(writer))
+            .init();
+    } else {
+        registry
+            .with(tracing_subscriber::fmt::layer().with_writer(writer))
+            .init();
+    }
+
+    if let Some(e) = export_error {
+        tracing::warn!(error = %e, "Trace export disabled: failed to set up the OTLP exporter");
+    } else if tracer_provider.is_some() {
+        tracing::info!("Exporting traces over OTLP");
+    }
+
+    Telemetry {
+        run_logs,
+        tracer_provider,
+    }
+}
+
+/// A batching OTLP/HTTP provider, if `endpoint` (`OTEL_EXPORTER_OTLP_ENDPOINT`)
+/// is set and not blank.
+fn tracer_provider(
+    endpoint: Option<&str>,
+) -> Result<Option<SdkTracerProvider>, opentelemetry_otlp::ExporterBuildError> {
+    if endpoint.is_none_or(|endpoint| endpoint.trim().is_empty()) {
+        return Ok(None);
+    }
+    // The exporter reads the endpoint itself and appends `/v1/traces`
+    let exporter = SpanExporter::builder().with_http().build()?;
+    let mut resource = Resource::builder();
+    if std::env::var("OTEL_SERVICE_NAME").is_err() {
+        resource = resource.with_service_name("argus");
+    }
+    Ok(Some(
+        SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(resource.build())
+            .build(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traces_stay_local_without_an_endpoint() {
+        assert!(tracer_provider(None).unwrap().is_none());
+        assert!(tracer_provider(Some("  ")).unwrap().is_none());
+    }
+
+    #[test]
+    fn an_endpoint_sets_up_the_exporter() {
+        let provider = tracer_provider(Some("http://localhost:4318"))
+            .unwrap()
+            .expect("a provider");
+        provider.shutdown().ok();
+    }
+}
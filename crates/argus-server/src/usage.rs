@@ -0,0 +1,154 @@
+//! Per-token usage accounting: every routed request is counted against the
+//! bearer token it carried, with the reasoning tokens and export volume
+//! handlers report through [`UsageCharge`].
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use argus_core::usage::{usage_day, UsageRecord, ANONYMOUS};
+use argus_core::UsageStore;
+
+/// Hex characters of the token hash that name a consumer.
+const CONSUMER_ID_CHARS: usize = 12;
+
+/// Endpoint requests matching no route are counted under, whatever their
+/// method and path, so probing random paths adds no endpoints.
+const UNMATCHED_ENDPOINT: &str = "<unmatched>";
+
+/// Cost beyond the request itself, attached to a response as an
+/// `axum::Extension` by the handlers that incur it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageCharge {
+    pub reasoning_tokens: u64,
+    pub exported_records: u64,
+}
+
+/// `token:` plus the start of the bearer token's SHA-256, or [`ANONYMOUS`].
+/// Tokens are secrets, so only the hash is ever stored or shown.
+pub fn consumer(headers: &HeaderMap) -> String {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty());
+    match token {
+        Some(token) => {
+            let hash = format!("{:x}", Sha256::digest(token.as_bytes()));
+            format!("token:{}", &hash[..CONSUMER_ID_CHARS])
+        }
+        None => ANONYMOUS.to_string(),
+    }
+}
+
+/// Middleware counting each request, installed with
+/// `axum::middleware::from_fn_with_state` on the router so the matched
+/// route is known. The count is written in the background.
+pub async fn track_usage(
+    State(store): State<Arc<dyn UsageStore>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let consumer = consumer(request.headers());
+    let endpoint = match request.extensions().get::<MatchedPath>() {
+        Some(route) => format!("{} {}", request.method(), route.as_str()),
+        None => UNMATCHED_ENDPOINT.to_string(),
+    };
+
+    let response = next.run(request).await;
+
+    let charge = response
+        .extensions()
+        .get::<UsageCharge>()
+        .copied()
+        .unwrap_or_default();
+    let record = UsageRecord {
+        endpoint,
+        reasoning_tokens: charge.reasoning_tokens,
+        exported_records: charge.exported_records,
+    };
+    tokio::spawn(async move {
+        if let Err(e) = store
+            .record(&consumer, usage_day(chrono::Utc::now()), &record)
+            .await
+        {
+            warn!(error = %e, consumer, "Failed to record API usage");
+        }
+    });
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    use crate::shared::MemoryUsageStore;
+
+    #[test]
+    fn consumers_are_named_by_token_hash() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(consumer(&headers), ANONYMOUS);
+
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        let id = consumer(&headers);
+        assert!(
+            id.starts_with("token:") && id.len() == 6 + CONSUMER_ID_CHARS,
+            "{id}"
+        );
+        assert!(!id.contains("s3cret"));
+
+        headers.insert(header::AUTHORIZATION, "Basic dXNlcjpwYXNz".parse().unwrap());
+        assert_eq!(consumer(&headers), ANONYMOUS);
+    }
+
+    #[tokio::test]
+    async fn requests_are_counted_by_route_with_their_charge() {
+        let store: Arc<dyn UsageStore> = Arc::new(MemoryUsageStore::default());
+        let app = Router::new()
+            .route(
+                "/api/entities/{id}",
+                get(|| async {
+                    (
+                        Extension(UsageCharge {
+                            reasoning_tokens: 0,
+                            exported_records: 7,
+                        }),
+                        "ok",
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                store.clone(),
+                track_usage,
+            ));
+
+        for uri in ["/api/entities/42", "/wp-admin/x1", "/wp-admin/x2"] {
+            let request = Request::builder()
+                .uri(uri)
+                .header(header::AUTHORIZATION, "Bearer abc")
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+        // Recorded by spawned tasks
+        for _ in 0..3 {
+            tokio::task::yield_now().await;
+        }
+
+        let totals = store.totals(usage_day(chrono::Utc::now())).await.unwrap();
+        let usage = totals.values().next().expect("usage recorded");
+        assert_eq!(usage.endpoints["GET /api/entities/{id}"], 1);
+        assert_eq!(usage.endpoints[UNMATCHED_ENDPOINT], 2);
+        assert_eq!(usage.exported_records, 7);
+    }
+}
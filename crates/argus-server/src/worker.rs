@@ -0,0 +1,267 @@
+//! Process roles, and the extraction worker loop behind `--role worker`.
+//!
+//! By default one process collects, extracts and serves the API. With
+//! `--role api` agent runs stop after collection and queue their documents
+//! in the shared `ExtractionQueue`; `--role worker` processes take them
+//! off it, so LLM extraction scales apart from the API.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use argus_core::ClaimedJob;
+
+use crate::shared::run_lock_key;
+use crate::state::AppState;
+
+/// How long an idle worker waits before checking the queue again.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How often a worker renews its heartbeat and looks for jobs left by
+/// workers whose heartbeat lapsed.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a heartbeat lasts; a worker missing three renewals is taken
+/// for dead and its jobs are handed out again.
+const HEARTBEAT_TTL: Duration = Duration::from_secs(30);
+/// How long jobs in progress get to finish after a shutdown signal before
+/// they are put back on the queue.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ServerRole {
+    /// Collect, extract and serve the API in one process.
+    #[default]
+    All,
+    /// Serve the API and collect; extraction is queued for workers.
+    Api,
+    /// Only extract queued jobs.
+    Worker,
+}
+
+impl ServerRole {
+    /// The role named by `--role <name>` or `--role=<name>` among `args`.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut name = None;
+        for (i, arg) in args.iter().enumerate() {
+            if arg == "--role" {
+                name = Some(args.get(i + 1).map(String::as_str).unwrap_or_default());
+            } else if let Some(value) = arg.strip_prefix("--role=") {
+                name = Some(value);
+            }
+        }
+        match name {
+            None | Some("all") => Ok(Self::All),
+            Some("api") => Ok(Self::Api),
+            Some("worker") => Ok(Self::Worker),
+            Some(other) => Err(format!(
+                "unknown role '{other}', expected all, api or worker"
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Api => "api",
+            Self::Worker => "worker",
+        }
+    }
+}
+
+/// Take jobs off the extraction queue, `EXTRACTION_WORKER_CONCURRENCY` at
+/// a time, until `shutdown` completes. Jobs in progress then get
+/// [`SHUTDOWN_GRACE`] to finish; any left are put back on the queue.
+pub async fn run(state: AppState, shutdown: impl Future<Output = ()>) {
+    let concurrency = state.config().extraction_worker_concurrency.max(1);
+    info!(concurrency, worker = %state.instance_id, "Extraction worker started");
+    keep_alive(&state).await;
+
+    let (stop, stopped) = watch::channel(false);
+    let mut tasks = tokio::task::JoinSet::new();
+    for _ in 0..concurrency {
+        let state = state.clone();
+        let mut stopped = stopped.clone();
+        tasks.spawn(async move {
+            while !*stopped.borrow() {
+                match state.extraction_jobs.pop(&state.instance_id).await {
+                    Ok(Some(claimed)) => process(&state, claimed).await,
+                    Ok(None) => idle(&mut stopped).await,
+                    Err(e) => {
+                        warn!(error = %e, "Extraction queue unavailable");
+                        idle(&mut stopped).await;
+                    }
+                }
+            }
+        });
+    }
+
+    let heartbeat = async {
+        loop {
+            tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+            keep_alive(&state).await;
+        }
+    };
+    tokio::pin!(heartbeat);
+    tokio::select! {
+        _ = &mut heartbeat => {}
+        _ = shutdown => {}
+    }
+
+    info!("Extraction worker stopping, finishing jobs in progress");
+    let _ = stop.send(true);
+    let drained = tokio::select! {
+        drained = tokio::time::timeout(SHUTDOWN_GRACE, async { while tasks.join_next().await.is_some() {} }) => drained.is_ok(),
+        _ = &mut heartbeat => false,
+    };
+    if !drained {
+        tasks.shutdown().await;
+        match state.extraction_jobs.requeue(&state.instance_id).await {
+            Ok(requeued) => info!(requeued, "Put unfinished extraction jobs back on the queue"),
+            Err(e) => {
+                warn!(error = %e, "Failed to put unfinished extraction jobs back; another worker will once this one's heartbeat lapses")
+            }
+        }
+    }
+}
+
+/// Renew this worker's heartbeat and hand out again the jobs of workers
+/// whose heartbeat lapsed.
+async fn keep_alive(state: &AppState) {
+    if let Err(e) = state
+        .extraction_jobs
+        .heartbeat(&state.instance_id, HEARTBEAT_TTL)
+        .await
+    {
+        warn!(error = %e, "Failed to renew extraction worker heartbeat");
+    }
+    match state.extraction_jobs.requeue_stale().await {
+        Ok(0) => {}
+        Ok(requeued) => info!(requeued, "Requeued extraction jobs of stopped workers"),
+        Err(e) => warn!(error = %e, "Failed to requeue extraction jobs of stopped workers"),
+    }
+}
+
+/// Wait for the next poll, or until the worker is stopping.
+async fn idle(stopped: &mut watch::Receiver<bool>) {
+    tokio::select! {
+        _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        _ = stopped.changed() => {}
+    }
+}
+
+/// Extract and store one job, then finish its run, release the run lock
+/// the API process left held and acknowledge the job. The lock is taken
+/// again first, in case it lapsed while the job waited, and extended while
+/// the job runs; `AGENT_RUN_LOCK_TTL_SECONDS` only has to cover the wait.
+async fn process(state: &AppState, claimed: ClaimedJob) {
+    let job = &claimed.job;
+    let run_lock = run_lock_key(&job.agent_name);
+    let ttl = Duration::from_secs(state.config().agent_run_lock_ttl_seconds);
+    match state.locks.try_acquire(&run_lock, &job.run_id, ttl).await {
+        Ok(true) => {}
+        Ok(false) => {
+            // Its lock lapsed and another run or a restore took it
+            let holder = state.locks.holder(&run_lock).await.ok().flatten();
+            warn!(run_id = %job.run_id, agent = %job.agent_name, holder = ?holder, "Run lock held elsewhere, requeueing extraction job");
+            requeue(state, claimed).await;
+            return;
+        }
+        Err(e) => {
+            warn!(run_id = %job.run_id, error = %e, "Run lock unavailable, requeueing extraction job");
+            requeue(state, claimed).await;
+            return;
+        }
+    }
+
+    let waited = (chrono::Utc::now() - job.enqueued_at).num_seconds();
+    info!(run_id = %job.run_id, agent = %job.agent_name, documents = job.documents.len(), waited_secs = waited, "Extraction job started");
+
+    let work = async {
+        let result = crate::pipeline::run_extraction_job(state, job).await;
+        crate::scheduler::conclude_run(
+            state,
+            &job.agent_name,
+            &job.run_id,
+            result,
+            job.cross_reference,
+        )
+        .await;
+    };
+    let extend = async {
+        loop {
+            tokio::time::sleep((ttl / 3).max(Duration::from_secs(1))).await;
+            if !matches!(
+                state.locks.try_acquire(&run_lock, &job.run_id, ttl).await,
+                Ok(true)
+            ) {
+                warn!(run_id = %job.run_id, "Failed to extend run lock");
+            }
+        }
+    };
+    tokio::select! {
+        _ = work => {}
+        _ = extend => {}
+    }
+
+    if let Err(e) = state.locks.release(&run_lock, &job.run_id).await {
+        warn!(run_id = %job.run_id, error = %e, "Failed to release run lock");
+    }
+    if let Err(e) = state
+        .extraction_jobs
+        .ack(&state.instance_id, &claimed.receipt)
+        .await
+    {
+        warn!(run_id = %job.run_id, error = %e, "Failed to acknowledge extraction job");
+    }
+    if let Ok(pending) = state.extraction_jobs.pending().await {
+        info!(run_id = %job.run_id, pending, "Extraction job finished");
+    }
+}
+
+/// Put a job this worker could not start at the back of the queue, and
+/// wait a poll before taking another.
+async fn requeue(state: &AppState, claimed: ClaimedJob) {
+    match state.extraction_jobs.push(claimed.job).await {
+        Ok(()) => {
+            if let Err(e) = state
+                .extraction_jobs
+                .ack(&state.instance_id, &claimed.receipt)
+                .await
+            {
+                warn!(error = %e, "Failed to acknowledge requeued extraction job");
+            }
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to requeue extraction job; it is handed out again once this worker stops")
+        }
+    }
+    tokio::time::sleep(POLL_INTERVAL).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn role_comes_from_the_role_flag() {
+        assert_eq!(
+            ServerRole::from_args(&args(&["argus-server"])),
+            Ok(ServerRole::All)
+        );
+        assert_eq!(
+            ServerRole::from_args(&args(&["argus-server", "--role", "worker"])),
+            Ok(ServerRole::Worker)
+        );
+        assert_eq!(
+            ServerRole::from_args(&args(&["argus-server", "--role=api"])),
+            Ok(ServerRole::Api)
+        );
+        assert!(ServerRole::from_args(&args(&["argus-server", "--role", "both"])).is_err());
+        assert!(ServerRole::from_args(&args(&["argus-server", "--role"])).is_err());
+    }
+}
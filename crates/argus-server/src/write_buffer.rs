@@ -0,0 +1,267 @@
+//! Coalesces extraction results into larger graph transactions.
+//!
+//! The pipeline pushes one result per document; writing each on its own
+//! means many small transactions contending for the same nodes. Results
+//! are buffered and flushed together once enough entities and relationships
+//! are pending, or once the oldest has waited `max_age` (see [`run_flusher`]).
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::{Mutex, MutexGuard};
+use tokio::time::Instant;
+use tracing::{debug, error, warn};
+
+use argus_core::{ExtractionResult, GraphStore};
+
+/// Results waiting to be written.
+#[derive(Default)]
+struct Pending {
+    results: Vec<ExtractionResult>,
+    items: usize,
+    oldest: Option<Instant>,
+}
+
+impl Pending {
+    /// Buffer a result; returns whether the size threshold is reached.
+    fn push(&mut self, result: ExtractionResult, max_items: usize) -> bool {
+        self.items += result.entities.len() + result.relationships.len();
+        self.oldest.get_or_insert_with(Instant::now);
+        self.results.push(result);
+        self.items >= max_items
+    }
+
+    fn is_stale(&self, max_age: Duration) -> bool {
+        self.oldest
+            .is_some_and(|oldest| oldest.elapsed() >= max_age)
+    }
+
+    fn take(&mut self) -> Vec<ExtractionResult> {
+        self.items = 0;
+        self.oldest = None;
+        std::mem::take(&mut self.results)
+    }
+}
+
+/// What a flush wrote.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FlushReport {
+    pub stored: u64,
+    /// Results that could not be stored even on their own.
+    pub failed: u64,
+}
+
+/// Told about results once they are in the graph.
+#[async_trait]
+pub trait WriteObserver: Send + Sync {
+    async fn stored(&self, results: &[ExtractionResult]);
+}
+
+pub struct GraphWriteBuffer {
+    graph: Arc<dyn GraphStore>,
+    observers: Vec<Arc<dyn WriteObserver>>,
+    pending: Mutex<Pending>,
+    /// Serializes flushes so batches commit in the order they were buffered.
+    flushing: Mutex<()>,
+    /// Raw sources [`store`](Self::store) is waiting on, and the errors of
+    /// those that failed, whichever flush wrote them.
+    awaited: Mutex<HashSet<String>>,
+    failed: Mutex<HashMap<String, String>>,
+    max_items: usize,
+    max_age: Duration,
+}
+
+impl GraphWriteBuffer {
+    pub fn new(graph: Arc<dyn GraphStore>, max_items: usize, max_age: Duration) -> Self {
+        Self {
+            graph,
+            observers: Vec::new(),
+            pending: Mutex::new(Pending::default()),
+            flushing: Mutex::new(()),
+            awaited: Mutex::new(HashSet::new()),
+            failed: Mutex::new(HashMap::new()),
+            max_items: max_items.max(1),
+            max_age,
+        }
+    }
+
+    /// Also tell `observer` about stored results, after those added before.
+    pub fn with_observer(mut self, observer: Arc<dyn WriteObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// Buffer a result, flushing if the size threshold is reached. The
+    /// report covers every result in that flush, not just this one.
+    pub async fn push(&self, result: ExtractionResult) -> Option<FlushReport> {
+        let full = self.pending.lock().await.push(result, self.max_items);
+        if full {
+            Some(self.flush().await)
+        } else {
+            None
+        }
+    }
+
+    /// Buffer results and wait until they are written, returning the raw
+    /// sources that could not be stored with why. Flushes are serialized,
+    /// so once the one started here is done every result pushed before it
+    /// has been written or has failed, even if another flush took it.
+    pub async fn store(&self, results: Vec<ExtractionResult>) -> Vec<(String, String)> {
+        let sources: Vec<String> = results.iter().map(|r| r.raw_source.clone()).collect();
+        self.awaited.lock().await.extend(sources.iter().cloned());
+        for result in results {
+            self.push(result).await;
+        }
+        self.flush().await;
+
+        let mut awaited = self.awaited.lock().await;
+        let mut failed = self.failed.lock().await;
+        sources
+            .into_iter()
+            .filter_map(|source| {
+                awaited.remove(&source);
+                failed.remove_entry(&source)
+            })
+            .collect()
+    }
+
+    /// Flush if the oldest buffered result has waited `max_age`.
+    pub async fn flush_if_stale(&self) -> Option<FlushReport> {
+        let stale = self.pending.lock().await.is_stale(self.max_age);
+        if stale {
+            Some(self.flush().await)
+        } else {
+            None
+        }
+    }
+
+    /// Write everything buffered. A failed batch is retried one result at a
+    /// time so a single bad result does not sink the rest.
+    pub async fn flush(&self) -> FlushReport {
+        let _guard = self.flushing.lock().await;
+        self.write_pending().await
+    }
+
+    /// Write everything buffered, then hold off every flush until the guard
+    /// is dropped. Results pushed meanwhile stay buffered, and callers of
+    /// [`store`](Self::store) wait.
+    pub async fn pause(&self) -> MutexGuard<'_, ()> {
+        let guard = self.flushing.lock().await;
+        self.write_pending().await;
+        guard
+    }
+
+    async fn write_pending(&self) -> FlushReport {
+        let results = self.pending.lock().await.take();
+        if results.is_empty() {
+            return FlushReport::default();
+        }
+
+        match self.graph.store_extractions(&results).await {
+            Ok(()) => {
+                debug!(results = results.len(), "Flushed graph write buffer");
+                self.notify(&results).await;
+                FlushReport {
+                    stored: results.len() as u64,
+                    failed: 0,
+                }
+            }
+            Err(e) => {
+                warn!(results = results.len(), error = %e, "Batched graph write failed, storing results individually");
+                let mut report = FlushReport::default();
+                let mut stored = Vec::with_capacity(results.len());
+                for result in results {
+                    match self.graph.store_extraction(&result).await {
+                        Ok(()) => {
+                            report.stored += 1;
+                            stored.push(result);
+                        }
+                        Err(e) => {
+                            error!(raw_source = %result.raw_source, error = %e, "Failed to store extraction result");
+                            report.failed += 1;
+                            if self.awaited.lock().await.contains(&result.raw_source) {
+                                self.failed
+                                    .lock()
+                                    .await
+                                    .insert(result.raw_source, e.to_string());
+                            }
+                        }
+                    }
+                }
+                self.notify(&stored).await;
+                report
+            }
+        }
+    }
+
+    async fn notify(&self, results: &[ExtractionResult]) {
+        if results.is_empty() {
+            return;
+        }
+        for observer in &self.observers {
+            observer.stored(results).await;
+        }
+    }
+}
+
+/// Periodically flush writes that have waited too long for a full batch.
+pub async fn run_flusher(buffer: Arc<GraphWriteBuffer>) {
+    let mut ticker = tokio::time::interval((buffer.max_age() / 2).max(Duration::from_millis(100)));
+    loop {
+        ticker.tick().await;
+        buffer.flush_if_stale().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::{Entity, EntityType};
+
+    fn result_with(entities: usize) -> ExtractionResult {
+        ExtractionResult {
+            entities: (0..entities)
+                .map(|i| Entity::new(EntityType::Person, format!("p{i}"), "test".to_string()))
+                .collect(),
+            relationships: Vec::new(),
+            raw_source: "doc".to_string(),
+            extracted_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn pending_signals_when_size_threshold_reached() {
+        let mut pending = Pending::default();
+        assert!(!pending.push(result_with(3), 5));
+        assert!(pending.push(result_with(2), 5));
+
+        let taken = pending.take();
+        assert_eq!(taken.len(), 2);
+        assert_eq!(pending.items, 0);
+        assert!(pending.results.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pending_goes_stale_after_max_age() {
+        let mut pending = Pending::default();
+        let max_age = Duration::from_secs(2);
+        assert!(!pending.is_stale(max_age));
+
+        pending.push(result_with(1), 100);
+        assert!(!pending.is_stale(max_age));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        // Later pushes don't reset the clock
+        pending.push(result_with(1), 100);
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(pending.is_stale(max_age));
+
+        pending.take();
+        assert!(!pending.is_stale(max_age));
+    }
+}
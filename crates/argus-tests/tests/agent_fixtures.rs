@@ -0,0 +1,177 @@
+//! Agents against recorded upstream responses: the golden fixtures under
+//! `fixtures/agents`, and a record-then-replay round trip.
+
+mod support;
+
+use std::path::PathBuf;
+
+use argus_agents::fixtures::{self, FixtureMode, Fixtures};
+use argus_agents::{GdeltAgent, GdeltMode, OpenSanctionsAgent, OpenSanctionsMode};
+use argus_core::agent::Agent;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use support::{sanctioned_organization, stub_opensanctions};
+
+/// Fixture settings are process-wide, so the tests take turns.
+static FIXTURES: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/agents")
+}
+
+fn replay_from(dir: PathBuf) {
+    fixtures::configure(Fixtures {
+        mode: FixtureMode::Replay,
+        dir,
+    });
+}
+
+#[tokio::test]
+async fn gdelt_parses_the_golden_export() {
+    let _turn = FIXTURES.lock().await;
+    replay_from(golden_dir());
+
+    let agent = GdeltAgent::with_mode(GdeltMode::Prose, 0);
+    let documents = agent.collect().await.expect("replayed collection failed");
+
+    let ids: Vec<&str> = documents.iter().map(|d| d.source_id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "gdelt-event-1153301467",
+            "gdelt-event-1153301468",
+            "gdelt-event-1153301469"
+        ]
+    );
+    let first = &documents[0];
+    assert!(first.content.contains("RUSSIA"));
+    assert!(first.content.contains("Kyiv"));
+    assert_eq!(
+        first.url.as_deref(),
+        Some("https://www.example-news.com/world/2024/01/15/talks-kyiv")
+    );
+    assert_eq!(first.metadata["action_geo_lat"], 50.4333);
+    assert_eq!(
+        agent
+            .status()
+            .await
+            .source_data_timestamp
+            .map(|t| t.to_rfc3339()),
+        Some("2024-01-15T12:00:00+00:00".to_string())
+    );
+}
+
+#[tokio::test]
+async fn opensanctions_parses_the_golden_page() {
+    let _turn = FIXTURES.lock().await;
+    replay_from(golden_dir());
+
+    let agent = OpenSanctionsAgent::with_mode(OpenSanctionsMode::Api, String::new(), 0);
+    let documents = agent.collect().await.expect("replayed collection failed");
+
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0].title.as_deref(), Some("Sovcomflot"));
+    assert_eq!(
+        documents[0].identifiers.get("lei").map(String::as_str),
+        Some("253400GLQHOLHTMSXE19")
+    );
+    assert_eq!(
+        documents[1].identifiers.get("wikidata").map(String::as_str),
+        Some("Q7747")
+    );
+    assert!(agent.status().await.schema_drift.is_none());
+}
+
+#[tokio::test]
+async fn recorded_runs_replay_without_the_network() {
+    let _turn = FIXTURES.lock().await;
+    let dir = std::env::temp_dir().join(format!("argus-fixtures-{}", uuid::Uuid::new_v4()));
+
+    let upstream = MockServer::start().await;
+    let uri = upstream.uri();
+    stub_opensanctions(
+        &upstream,
+        vec![sanctioned_organization(
+            "NK-rec",
+            "Cobalt Resources",
+            "LEI000REC",
+        )],
+    )
+    .await;
+    fixtures::configure(Fixtures {
+        mode: FixtureMode::Record,
+        dir: dir.clone(),
+    });
+    let agent = || {
+        OpenSanctionsAgent::with_mode(OpenSanctionsMode::Api, String::new(), 0).with_api_url(&uri)
+    };
+    let recorded = agent().collect().await.expect("recorded collection failed");
+    drop(upstream);
+
+    replay_from(dir.clone());
+    let replayed = agent().collect().await.expect("replayed collection failed");
+
+    // Nothing is listening any more, so a miss fails instead of reaching out
+    replay_from(std::env::temp_dir().join("argus-fixtures-empty"));
+    let missing = agent().collect().await;
+    fixtures::configure(Fixtures::default());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(
+        replayed
+            .iter()
+            .map(|d| (&d.source_id, &d.content))
+            .collect::<Vec<_>>(),
+        recorded
+            .iter()
+            .map(|d| (&d.source_id, &d.content))
+            .collect::<Vec<_>>()
+    );
+    let error = missing
+        .expect_err("replay without a fixture should fail")
+        .to_string();
+    assert!(error.contains("no fixture"), "unexpected error: {error}");
+}
+
+#[tokio::test]
+async fn opensanctions_pages_are_fetched_concurrently_in_order() {
+    let _turn = FIXTURES.lock().await;
+    fixtures::configure(Fixtures::default());
+
+    let upstream = MockServer::start().await;
+    // The second page answers last, so it only comes second if pages are reassembled in order
+    for (offset, count, delay_ms) in [(0, 100, 0), (100, 100, 300), (200, 50, 0)] {
+        let results: Vec<_> = (offset..offset + count)
+            .map(|n| {
+                sanctioned_organization(
+                    &format!("NK-{n:03}"),
+                    &format!("Company {n}"),
+                    &format!("LEI{n:03}"),
+                )
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/entities"))
+            .and(query_param("offset", offset.to_string()))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "results": results, "total": 250 }))
+                    .set_delay(std::time::Duration::from_millis(delay_ms)),
+            )
+            .expect(1)
+            .mount(&upstream)
+            .await;
+    }
+
+    let agent = OpenSanctionsAgent::with_mode(OpenSanctionsMode::Api, String::new(), 0)
+        .with_api_url(&upstream.uri())
+        .with_page_concurrency(2, std::time::Duration::ZERO);
+    let documents = agent.collect().await.expect("paged collection failed");
+
+    let ids: Vec<_> = documents.iter().map(|d| d.source_id.clone()).collect();
+    let expected: Vec<_> = (0..250).map(|n| format!("NK-{n:03}")).collect();
+    assert_eq!(ids, expected);
+}
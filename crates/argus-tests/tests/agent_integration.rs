@@ -1,7 +1,6 @@
 use argus_agents::agent_registry;
-use argus_core::agent::{AgentStatus, RawDocument};
+use argus_core::agent::{AgentCapabilities, AgentStatus, RawDocument, SchemaDrift};
 use argus_core::entity::{Entity, EntityType, ExtractionResult, RelationType, Relationship};
-use argus_core::Agent;
 use chrono::Utc;
 use uuid::Uuid;
 
@@ -10,15 +9,23 @@ use uuid::Uuid;
 // ---------------------------------------------------------------------------
 
 #[test]
-fn agent_registry_returns_all_six_agents() {
+fn agent_registry_returns_all_seven_agents() {
     let registry = agent_registry();
-    assert_eq!(registry.len(), 6);
+    assert_eq!(registry.len(), 7);
 }
 
 #[test]
 fn agent_registry_contains_expected_keys() {
     let registry = agent_registry();
-    let expected_keys = ["gdelt", "opencorporates", "ais", "adsb", "opensanctions", "eu_transparency"];
+    let expected_keys = [
+        "gdelt",
+        "opencorporates",
+        "ais",
+        "adsb",
+        "opensanctions",
+        "eu_transparency",
+        "synthetic",
+    ];
     for key in &expected_keys {
         assert!(
             registry.contains_key(*key),
@@ -42,7 +49,9 @@ fn gdelt_agent_name_and_source_type() {
 #[test]
 fn opencorporates_agent_name_and_source_type() {
     let registry = agent_registry();
-    let agent = registry.get("opencorporates").expect("opencorporates agent not found");
+    let agent = registry
+        .get("opencorporates")
+        .expect("opencorporates agent not found");
     assert_eq!(agent.name(), "opencorporates");
     assert_eq!(agent.source_type(), "corporate_registry");
 }
@@ -66,7 +75,9 @@ fn adsb_agent_name_and_source_type() {
 #[test]
 fn opensanctions_agent_name_and_source_type() {
     let registry = agent_registry();
-    let agent = registry.get("opensanctions").expect("opensanctions agent not found");
+    let agent = registry
+        .get("opensanctions")
+        .expect("opensanctions agent not found");
     assert_eq!(agent.name(), "opensanctions");
     assert_eq!(agent.source_type(), "sanctions");
 }
@@ -74,11 +85,24 @@ fn opensanctions_agent_name_and_source_type() {
 #[test]
 fn eu_transparency_agent_name_and_source_type() {
     let registry = agent_registry();
-    let agent = registry.get("eu_transparency").expect("eu_transparency agent not found");
+    let agent = registry
+        .get("eu_transparency")
+        .expect("eu_transparency agent not found");
     assert_eq!(agent.name(), "eu_transparency");
     assert_eq!(agent.source_type(), "lobby_register");
 }
 
+#[tokio::test]
+async fn synthetic_agent_is_disabled_by_default() {
+    let registry = agent_registry();
+    let agent = registry
+        .get("synthetic")
+        .expect("synthetic agent not found");
+    assert_eq!(agent.name(), "synthetic");
+    assert_eq!(agent.source_type(), "synthetic");
+    assert!(!agent.status().await.enabled);
+}
+
 // ---------------------------------------------------------------------------
 // Agent names from registry match their keys
 // ---------------------------------------------------------------------------
@@ -96,6 +120,38 @@ fn agent_names_match_registry_keys() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Capabilities derived from the registry
+// ---------------------------------------------------------------------------
+
+#[test]
+fn agent_capabilities_list_lookup_types_and_missing_env() {
+    let registry = agent_registry();
+    let capabilities = |name: &str| AgentCapabilities::of(registry[name].as_ref(), |_| false);
+
+    let sanctions = capabilities("opensanctions");
+    assert_eq!(sanctions.source_type, "sanctions");
+    assert_eq!(
+        sanctions.lookup_entity_types,
+        vec![
+            EntityType::Person,
+            EntityType::Organization,
+            EntityType::Vessel
+        ]
+    );
+    assert!(sanctions.required_env.is_empty());
+
+    let ais = capabilities("ais");
+    assert_eq!(ais.lookup_entity_types, vec![EntityType::Vessel]);
+    assert_eq!(ais.missing_env, vec!["AISHUB_API_KEY".to_string()]);
+    assert!(AgentCapabilities::of(registry["ais"].as_ref(), |_| true)
+        .missing_env
+        .is_empty());
+
+    assert!(capabilities("gdelt").lookup_entity_types.is_empty());
+    assert!(capabilities("synthetic").lookup_entity_types.is_empty());
+}
+
 // ---------------------------------------------------------------------------
 // AgentStatus fields
 // ---------------------------------------------------------------------------
@@ -108,6 +164,9 @@ fn agent_status_fields_properly_initialized() {
         last_run: None,
         documents_collected: 0,
         error: None,
+        source_data_timestamp: None,
+        schema_drift: None,
+        capabilities: None,
     };
 
     assert_eq!(status.name, "test_agent");
@@ -126,6 +185,9 @@ fn agent_status_with_last_run_and_error() {
         last_run: Some(now),
         documents_collected: 42,
         error: Some("connection timeout".to_string()),
+        source_data_timestamp: None,
+        schema_drift: None,
+        capabilities: None,
     };
 
     assert_eq!(status.name, "failing_agent");
@@ -144,6 +206,9 @@ fn agent_status_serialization_roundtrip() {
         last_run: Some(now),
         documents_collected: 100,
         error: None,
+        source_data_timestamp: None,
+        schema_drift: None,
+        capabilities: None,
     };
 
     let json = serde_json::to_string(&status).expect("failed to serialize AgentStatus");
@@ -154,6 +219,35 @@ fn agent_status_serialization_roundtrip() {
     assert_eq!(deserialized.enabled, status.enabled);
     assert_eq!(deserialized.documents_collected, status.documents_collected);
     assert_eq!(deserialized.error, status.error);
+    assert!(!json.contains("schema_drift"));
+}
+
+#[test]
+fn agent_status_reports_schema_drift() {
+    let drift = SchemaDrift {
+        unknown_fields: vec!["results.*.company.country_code".to_string()],
+        missing_fields: vec!["results.*.company.jurisdiction_code".to_string()],
+        detected_at: Utc::now(),
+    };
+    let status = AgentStatus {
+        name: "opencorporates".to_string(),
+        enabled: true,
+        last_run: Some(drift.detected_at),
+        documents_collected: 100,
+        error: None,
+        source_data_timestamp: None,
+        schema_drift: Some(drift.clone()),
+        capabilities: None,
+    };
+
+    let json = serde_json::to_value(&status).expect("failed to serialize AgentStatus");
+    assert_eq!(
+        json["schema_drift"]["missing_fields"][0],
+        "results.*.company.jurisdiction_code"
+    );
+    let deserialized: AgentStatus =
+        serde_json::from_value(json).expect("failed to deserialize AgentStatus");
+    assert_eq!(deserialized.schema_drift, Some(drift));
 }
 
 // ---------------------------------------------------------------------------
@@ -169,9 +263,11 @@ async fn all_agents_initial_status_is_clean() {
             status.name, *key,
             "agent '{key}' status.name does not match"
         );
-        assert!(
+        // The load-test agent is off until configured
+        assert_eq!(
             status.enabled,
-            "agent '{key}' should be enabled by default"
+            key != "synthetic",
+            "agent '{key}' has the wrong default enabled state"
         );
         assert!(
             status.last_run.is_none(),
@@ -202,7 +298,10 @@ fn raw_document_creation() {
         content: "This is the content of the test document.".to_string(),
         url: Some("https://example.com/doc/001".to_string()),
         collected_at: now,
+        observed_at: None,
+        identifiers: Default::default(),
         metadata: serde_json::json!({"key": "value", "count": 42}),
+        structured: None,
     };
 
     assert_eq!(doc.source, "test_source");
@@ -224,7 +323,10 @@ fn raw_document_with_none_fields() {
         content: "content".to_string(),
         url: None,
         collected_at: Utc::now(),
+        observed_at: None,
+        identifiers: Default::default(),
         metadata: serde_json::json!({}),
+        structured: None,
     };
 
     assert!(doc.title.is_none());
@@ -241,7 +343,10 @@ fn raw_document_serialization_roundtrip() {
         content: "Some content here.".to_string(),
         url: Some("https://example.com".to_string()),
         collected_at: now,
+        observed_at: None,
+        identifiers: Default::default(),
         metadata: serde_json::json!({"nested": {"a": 1}}),
+        structured: None,
     };
 
     let json = serde_json::to_string(&doc).expect("failed to serialize RawDocument");
@@ -321,8 +426,7 @@ fn entity_serialization_roundtrip() {
     );
 
     let json = serde_json::to_string(&entity).expect("failed to serialize Entity");
-    let deserialized: Entity =
-        serde_json::from_str(&json).expect("failed to deserialize Entity");
+    let deserialized: Entity = serde_json::from_str(&json).expect("failed to deserialize Entity");
 
     assert_eq!(deserialized.id, entity.id);
     assert_eq!(deserialized.entity_type, entity.entity_type);
@@ -440,11 +544,7 @@ fn extraction_result_empty_roundtrip() {
 
 #[test]
 fn extraction_result_with_data_roundtrip() {
-    let entity1 = Entity::new(
-        EntityType::Person,
-        "Alice".to_string(),
-        "gdelt".to_string(),
-    );
+    let entity1 = Entity::new(EntityType::Person, "Alice".to_string(), "gdelt".to_string());
     let entity2 = Entity::new(
         EntityType::Organization,
         "ACME".to_string(),
@@ -478,14 +578,8 @@ fn extraction_result_with_data_roundtrip() {
         deserialized.relationships[0].relation_type,
         RelationType::EmployeeOf
     );
-    assert_eq!(
-        deserialized.relationships[0].source_entity_id,
-        entity1.id
-    );
-    assert_eq!(
-        deserialized.relationships[0].target_entity_id,
-        entity2.id
-    );
+    assert_eq!(deserialized.relationships[0].source_entity_id, entity1.id);
+    assert_eq!(deserialized.relationships[0].target_entity_id, entity2.id);
 }
 
 #[test]
@@ -525,8 +619,7 @@ fn entity_type_serialization() {
     ];
 
     for (entity_type, expected_json) in cases {
-        let json = serde_json::to_string(&entity_type)
-            .expect("failed to serialize EntityType");
+        let json = serde_json::to_string(&entity_type).expect("failed to serialize EntityType");
         assert_eq!(
             json, expected_json,
             "EntityType::{:?} serialized to '{json}' but expected '{expected_json}'",
@@ -578,8 +671,7 @@ fn relation_type_serialization() {
     ];
 
     for (relation_type, expected_json) in cases {
-        let json = serde_json::to_string(&relation_type)
-            .expect("failed to serialize RelationType");
+        let json = serde_json::to_string(&relation_type).expect("failed to serialize RelationType");
         assert_eq!(
             json, expected_json,
             "RelationType::{:?} serialized to '{json}' but expected '{expected_json}'",
@@ -1,13 +1,43 @@
+use argus_core::agent::{AgentStatus, RawDocument};
 use argus_core::api_types::{
-    AgentListResponse, AgentTriggerRequest, AgentTriggerResponse, EntityDetailResponse,
-    EntitySearchRequest, EntitySearchResponse, EntityTypeStat, GraphQueryRequest,
-    GraphQueryResponse, GraphStatsResponse, HealthResponse, ReasoningApiResponse,
-    ReasoningRequest, TimelineEvent, TimelineRequest, TimelineResponse,
+    AgentEnrichment, AgentListResponse, AgentRunState, AgentRunStatus, AgentTriggerRequest,
+    AgentTriggerResponse, AnalyticsSqlRequest, AnalyticsSqlResponse, ArchivedPositionsParams,
+    BriefingParams, BudgetStatusResponse, CalibrationParams, CaseTimelineParams,
+    CaseTimelineResponse, ColdStorageResponse, DenyEntryRequest, DenyListResponse,
+    DocumentSearchRequest, DocumentSearchResponse, DocumentSearchResult, EnrichParams,
+    EnrichResponse, EntityBatchRequest, EntityBatchResponse, EntityDetailResponse,
+    EntityHistoryParams, EntityHistoryResponse, EntitySearchRequest, EntitySearchResponse,
+    EntityTypeStat, EventDensityParams, ExportParams, FeedbackListResponse, FeedbackRequest,
+    FollowRequest, GraphQueryParams, GraphQueryRequest, GraphQueryResponse, GraphStatsResponse,
+    HealthResponse, IntegrityReport, InvestigationExportRequest, InvestigationNoteRequest,
+    NaturalSearchRequest, NaturalSearchResponse, NeighborQueryParams, NotificationFeedParams,
+    PathQueryParams, PromptBundleListResponse, PromptBundleRequest, PromptDeploymentRequest,
+    ReasoningApiResponse, ReasoningRequest, RetryFailuresParams, RunComparisonResponse,
+    RunDocumentsParams, RunListParams, RunLogLine, RunLogsResponse, RunSnapshot, SearchTarget,
+    SnapshotListResponse, SnapshotRestoreRequest, TimelineEvent, TimelineFormat, TimelineRequest,
+    TimelineResponse, TombstoneListParams, TombstoneListResponse, UsageParams, MAX_PATHS,
+    MAX_RUN_DOCUMENT_SAMPLE,
+};
+use argus_core::backup::{SnapshotCounts, SnapshotManifest, SnapshotMeta, SNAPSHOT_FORMAT_VERSION};
+use argus_core::briefing::{Briefing, BriefingCounts, BriefingEntity};
+use argus_core::calibration::{
+    calibration_report, CalibrationGrouping, FeedbackLabel, FeedbackTarget,
 };
-use argus_core::agent::AgentStatus;
 use argus_core::config::AppConfig;
-use argus_core::entity::{Entity, EntityType, Relationship, RelationType};
-use argus_core::reasoning::{ReasoningResponse, ReasoningStep};
+use argus_core::deny_list::{DenyEntry, DenyMatch};
+use argus_core::document::{ArchivedDocument, RunExtractionStats};
+use argus_core::entity::{Direction, Entity, EntityType, RelationType, Relationship};
+use argus_core::export::ExportFormat;
+use argus_core::extraction::{PipelineKind, ValidationReport};
+use argus_core::graph::{EntityBatchItem, EntitySort, RelationIdentity, RelationshipSummary};
+use argus_core::history::{ChangeKind, EntityChange, Tombstone};
+use argus_core::integrity::{IntegrityCheck, IntegrityFinding};
+use argus_core::investigation::{CaseEvent, CaseEventKind};
+use argus_core::ontology::Ontology;
+use argus_core::prompts::{FewShotExample, PromptBundle, PromptDeployment};
+use argus_core::quota::Spillover;
+use argus_core::reasoning::{ReasoningResponse, ReasoningStep, ReasoningUsage, StageTiming};
+use argus_core::subscriptions::SubscriptionEvent;
 use chrono::Utc;
 use uuid::Uuid;
 
@@ -52,6 +82,9 @@ fn agent_list_response_roundtrip() {
                 last_run: None,
                 documents_collected: 0,
                 error: None,
+                source_data_timestamp: None,
+                schema_drift: None,
+                capabilities: None,
             },
             AgentStatus {
                 name: "adsb".to_string(),
@@ -59,6 +92,9 @@ fn agent_list_response_roundtrip() {
                 last_run: Some(Utc::now()),
                 documents_collected: 42,
                 error: Some("timeout".to_string()),
+                source_data_timestamp: None,
+                schema_drift: None,
+                capabilities: None,
             },
         ],
     };
@@ -110,18 +146,20 @@ fn agent_trigger_request_roundtrip() {
 #[test]
 fn agent_trigger_response_roundtrip() {
     let resp = AgentTriggerResponse {
+        run_id: "run-123".to_string(),
         agent_name: "opensanctions".to_string(),
-        documents_collected: 150,
-        message: "Collection completed successfully".to_string(),
+        status: "accepted".to_string(),
+        message: "Collection started".to_string(),
     };
 
     let json = serde_json::to_string(&resp).expect("failed to serialize AgentTriggerResponse");
     let deserialized: AgentTriggerResponse =
         serde_json::from_str(&json).expect("failed to deserialize AgentTriggerResponse");
 
+    assert_eq!(deserialized.run_id, "run-123");
     assert_eq!(deserialized.agent_name, "opensanctions");
-    assert_eq!(deserialized.documents_collected, 150);
-    assert_eq!(deserialized.message, "Collection completed successfully");
+    assert_eq!(deserialized.status, "accepted");
+    assert_eq!(deserialized.message, "Collection started");
 }
 
 // ---------------------------------------------------------------------------
@@ -134,6 +172,9 @@ fn entity_search_request_roundtrip() {
         query: "John Doe".to_string(),
         limit: 10,
         entity_type: Some(EntityType::Person),
+        min_sanctions_exposure: Some(0.5),
+        sort: EntitySort::SanctionsExposure,
+        target: SearchTarget::All,
     };
 
     let json = serde_json::to_string(&req).expect("failed to serialize EntitySearchRequest");
@@ -143,6 +184,9 @@ fn entity_search_request_roundtrip() {
     assert_eq!(deserialized.query, "John Doe");
     assert_eq!(deserialized.limit, 10);
     assert_eq!(deserialized.entity_type, Some(EntityType::Person));
+    assert_eq!(deserialized.min_sanctions_exposure, Some(0.5));
+    assert_eq!(deserialized.sort, EntitySort::SanctionsExposure);
+    assert_eq!(deserialized.target, SearchTarget::All);
 }
 
 #[test]
@@ -155,6 +199,9 @@ fn entity_search_request_default_limit() {
     assert_eq!(deserialized.query, "test");
     assert_eq!(deserialized.limit, 20);
     assert!(deserialized.entity_type.is_none());
+    assert!(deserialized.min_sanctions_exposure.is_none());
+    assert_eq!(deserialized.sort, EntitySort::Relevance);
+    assert_eq!(deserialized.target, SearchTarget::Entities);
 }
 
 #[test]
@@ -163,6 +210,9 @@ fn entity_search_request_without_entity_type() {
         query: "search term".to_string(),
         limit: 50,
         entity_type: None,
+        min_sanctions_exposure: None,
+        sort: EntitySort::Name,
+        target: SearchTarget::Relationships,
     };
 
     let json = serde_json::to_string(&req).unwrap();
@@ -185,7 +235,9 @@ fn entity_search_response_roundtrip() {
     );
 
     let resp = EntitySearchResponse {
+        presentation: argus_core::presentation::presentations([&entity]),
         entities: vec![entity],
+        relationships: vec![],
         total: 1,
     };
 
@@ -196,22 +248,62 @@ fn entity_search_response_roundtrip() {
     assert_eq!(deserialized.entities.len(), 1);
     assert_eq!(deserialized.entities[0].name, "Jane Smith");
     assert_eq!(deserialized.total, 1);
+    let presentation = &deserialized.presentation[&deserialized.entities[0].id];
+    assert_eq!(
+        (presentation.category.as_str(), presentation.icon.as_str()),
+        ("person", "user")
+    );
 }
 
 #[test]
 fn entity_search_response_empty() {
     let resp = EntitySearchResponse {
         entities: vec![],
+        relationships: vec![],
         total: 0,
+        presentation: Default::default(),
     };
 
     let json = serde_json::to_string(&resp).unwrap();
+    assert!(
+        !json.contains("presentation"),
+        "only sent when asked for: {json}"
+    );
     let deserialized: EntitySearchResponse = serde_json::from_str(&json).unwrap();
 
     assert!(deserialized.entities.is_empty());
     assert_eq!(deserialized.total, 0);
 }
 
+#[test]
+fn natural_search_request_defaults_limit() {
+    let req: NaturalSearchRequest = serde_json::from_str(
+        r#"{"question": "Russian-flagged tankers seen near Ceuta in January"}"#,
+    )
+    .unwrap();
+    assert_eq!(req.limit, 20);
+}
+
+#[test]
+fn natural_search_response_reports_its_interpretation() {
+    let resp = NaturalSearchResponse {
+        interpretation: argus_core::natural_search::parse_interpretation(
+            r#"{"entity_type": "vessel", "properties": {"vessel_type": "tanker"},
+                "near": {"place": "Ceuta", "latitude": 35.89, "longitude": -5.32, "radius_km": 40}}"#,
+        )
+        .unwrap(),
+        entities: vec![Entity::new(EntityType::Vessel, "Volga Star".to_string(), "ais".to_string())],
+        total: 1,
+        usage: ReasoningUsage::default(),
+    };
+
+    let json = serde_json::to_value(&resp).unwrap();
+    assert_eq!(json["interpretation"]["entity_type"], "vessel");
+    assert_eq!(json["interpretation"]["near"]["place"], "Ceuta");
+    let deserialized: NaturalSearchResponse = serde_json::from_value(json).unwrap();
+    assert_eq!(deserialized.interpretation, resp.interpretation);
+}
+
 // ---------------------------------------------------------------------------
 // GraphQueryRequest serialization/deserialization
 // ---------------------------------------------------------------------------
@@ -235,14 +327,24 @@ fn graph_query_request_roundtrip() {
 fn graph_query_request_default_params() {
     // When params is not provided, it should default to null (serde default)
     let json = r#"{"cypher": "MATCH (n) RETURN count(n)"}"#;
-    let deserialized: GraphQueryRequest =
-        serde_json::from_str(json).expect("failed to deserialize GraphQueryRequest with default params");
+    let deserialized: GraphQueryRequest = serde_json::from_str(json)
+        .expect("failed to deserialize GraphQueryRequest with default params");
 
     assert_eq!(deserialized.cypher, "MATCH (n) RETURN count(n)");
     // serde(default) on serde_json::Value yields Value::Null
     assert!(deserialized.params.is_null());
 }
 
+#[test]
+fn graph_query_params_stream_only_when_asked() {
+    let params: GraphQueryParams =
+        serde_json::from_str("{}").expect("failed to deserialize GraphQueryParams");
+    assert!(!params.stream);
+    let params: GraphQueryParams = serde_json::from_value(serde_json::json!({"stream": true}))
+        .expect("failed to deserialize GraphQueryParams");
+    assert!(params.stream);
+}
+
 // ---------------------------------------------------------------------------
 // GraphQueryResponse serialization/deserialization
 // ---------------------------------------------------------------------------
@@ -327,9 +429,13 @@ fn reasoning_request_roundtrip() {
         question: "Who owns ACME Corp?".to_string(),
         context: Some("Corporate ownership analysis".to_string()),
         max_hops: Some(3),
+        persist: Some(true),
+        template: None,
+        params: serde_json::Map::new(),
     };
 
     let json = serde_json::to_string(&req).expect("failed to serialize ReasoningRequest");
+    assert!(!json.contains("template") && !json.contains("params"));
     let deserialized: ReasoningRequest =
         serde_json::from_str(&json).expect("failed to deserialize ReasoningRequest");
 
@@ -339,6 +445,22 @@ fn reasoning_request_roundtrip() {
         Some("Corporate ownership analysis")
     );
     assert_eq!(deserialized.max_hops, Some(3));
+    assert_eq!(deserialized.persist, Some(true));
+}
+
+#[test]
+fn reasoning_request_accepts_a_template_without_question() {
+    let req: ReasoningRequest = serde_json::from_value(serde_json::json!({
+        "template": "entity_summary",
+        "params": { "entity_id": "6f1c2f0e-8d9a-4b7e-9a51-3c0d7e2b1a44" },
+    }))
+    .expect("failed to deserialize ReasoningRequest");
+    assert!(req.question.is_empty());
+    assert_eq!(req.template.as_deref(), Some("entity_summary"));
+    assert_eq!(
+        req.params["entity_id"],
+        "6f1c2f0e-8d9a-4b7e-9a51-3c0d7e2b1a44"
+    );
 }
 
 #[test]
@@ -350,6 +472,7 @@ fn reasoning_request_minimal() {
     assert_eq!(deserialized.question, "What is going on?");
     assert!(deserialized.context.is_none());
     assert!(deserialized.max_hops.is_none());
+    assert!(deserialized.persist.is_none());
 }
 
 // ---------------------------------------------------------------------------
@@ -370,7 +493,9 @@ fn reasoning_api_response_roundtrip() {
         steps: vec![
             ReasoningStep {
                 description: "Search for ACME Corp ownership".to_string(),
-                cypher: Some("MATCH (p)-[:OWNER_OF]->(o) WHERE o.name = 'ACME' RETURN p".to_string()),
+                cypher: Some(
+                    "MATCH (p)-[:OWNER_OF]->(o) WHERE o.name = 'ACME' RETURN p".to_string(),
+                ),
                 result_summary: "Found 1 owner".to_string(),
             },
             ReasoningStep {
@@ -380,14 +505,22 @@ fn reasoning_api_response_roundtrip() {
             },
         ],
         entities_referenced: vec![entity],
-        sources: vec![
-            "opencorporates".to_string(),
-            "opensanctions".to_string(),
-        ],
+        sources: vec!["opencorporates".to_string(), "opensanctions".to_string()],
+        usage: ReasoningUsage {
+            input_tokens: 1200,
+            output_tokens: 300,
+            llm_calls: 2,
+            total_ms: 4200,
+            stages: vec![StageTiming {
+                stage: "interpret".to_string(),
+                elapsed_ms: 2100,
+            }],
+            budget_exceeded: None,
+        },
+        document_id: None,
     };
 
-    let json =
-        serde_json::to_string(&resp).expect("failed to serialize ReasoningApiResponse");
+    let json = serde_json::to_string(&resp).expect("failed to serialize ReasoningApiResponse");
     let deserialized: ReasoningApiResponse =
         serde_json::from_str(&json).expect("failed to deserialize ReasoningApiResponse");
 
@@ -406,6 +539,9 @@ fn reasoning_api_response_roundtrip() {
     assert_eq!(deserialized.entities_referenced.len(), 1);
     assert_eq!(deserialized.entities_referenced[0].name, "Bob");
     assert_eq!(deserialized.sources.len(), 2);
+    assert_eq!(deserialized.usage.llm_calls, 2);
+    assert_eq!(deserialized.usage.stages[0].stage, "interpret");
+    assert!(!json.contains("budget_exceeded"));
 }
 
 // ---------------------------------------------------------------------------
@@ -431,6 +567,7 @@ fn reasoning_api_response_from_reasoning_response() {
         }],
         entities_referenced: vec![entity],
         sources: vec!["opensanctions".to_string()],
+        usage: Default::default(),
     };
 
     let api_response: ReasoningApiResponse = reasoning_response.into();
@@ -456,6 +593,7 @@ fn reasoning_api_response_from_empty_reasoning_response() {
         steps: vec![],
         entities_referenced: vec![],
         sources: vec![],
+        usage: Default::default(),
     };
 
     let api_response: ReasoningApiResponse = reasoning_response.into();
@@ -551,16 +689,981 @@ fn timeline_response_empty() {
 }
 
 // ---------------------------------------------------------------------------
-// EntityDetailResponse serialization/deserialization
+// EntityBatchRequest / EntityBatchResponse serialization/deserialization
 // ---------------------------------------------------------------------------
 
 #[test]
-fn entity_detail_response_roundtrip() {
+fn entity_batch_request_defaults_to_no_relationships() {
+    let id = Uuid::new_v4();
+    let json = format!(r#"{{"ids": ["{id}"]}}"#);
+    let req: EntityBatchRequest =
+        serde_json::from_str(&json).expect("failed to deserialize EntityBatchRequest");
+
+    assert_eq!(req.ids, vec![id]);
+    assert!(!req.include_relationships);
+}
+
+#[test]
+fn entity_batch_response_roundtrip() {
     let entity = Entity::new(
-        EntityType::Person,
-        "Alice".to_string(),
+        EntityType::Organization,
+        "Acme".to_string(),
         "test".to_string(),
     );
+    let mut summary = RelationshipSummary {
+        total: 3,
+        ..Default::default()
+    };
+    summary.by_type.insert(RelationType::OwnerOf, 2);
+    summary.by_type.insert(RelationType::SanctionedBy, 1);
+    let missing_id = Uuid::new_v4();
+
+    let resp = EntityBatchResponse {
+        entities: vec![EntityBatchItem {
+            entity: entity.clone(),
+            relationships: Some(summary),
+        }],
+        missing: vec![missing_id],
+        presentation: Default::default(),
+    };
+
+    let json = serde_json::to_string(&resp).expect("failed to serialize EntityBatchResponse");
+    let deserialized: EntityBatchResponse =
+        serde_json::from_str(&json).expect("failed to deserialize EntityBatchResponse");
+
+    assert_eq!(deserialized.entities.len(), 1);
+    assert_eq!(deserialized.entities[0].entity.id, entity.id);
+    let summary = deserialized.entities[0].relationships.as_ref().unwrap();
+    assert_eq!(summary.total, 3);
+    assert_eq!(summary.by_type.get(&RelationType::OwnerOf), Some(&2));
+    assert_eq!(deserialized.missing, vec![missing_id]);
+}
+
+// ---------------------------------------------------------------------------
+// AgentRunStatus / ValidationReport / RetryFailuresParams / RunDocumentsParams
+// ---------------------------------------------------------------------------
+
+#[test]
+fn agent_run_status_without_failed_count_defaults_to_zero() {
+    let json = r#"{
+        "run_id": "r1",
+        "agent_name": "gdelt",
+        "status": "completed",
+        "started_at": "2026-01-01T00:00:00Z",
+        "finished_at": null,
+        "documents_collected": 10,
+        "entities_extracted": 4,
+        "error": null
+    }"#;
+    let run: AgentRunStatus =
+        serde_json::from_str(json).expect("failed to deserialize AgentRunStatus");
+
+    assert_eq!(run.status, AgentRunState::Completed);
+    assert_eq!(run.documents_failed, 0);
+    assert_eq!(run.documents_over_quota, 0);
+    assert!(run.validation.is_empty());
+}
+
+#[test]
+fn run_list_filters_and_pages_newest_first() {
+    let start = Utc::now() - chrono::Duration::hours(10);
+    let runs: Vec<AgentRunStatus> = (0..6)
+        .map(|i| {
+            let mut run = AgentRunStatus::started(
+                format!("run-{i}"),
+                if i % 3 == 0 { "ais" } else { "gdelt" },
+            );
+            run.started_at = start + chrono::Duration::hours(i);
+            run.status = if i == 4 {
+                AgentRunState::Failed
+            } else {
+                AgentRunState::Completed
+            };
+            run
+        })
+        .collect();
+    let ids = |page: &[AgentRunStatus]| page.iter().map(|r| r.run_id.clone()).collect::<Vec<_>>();
+
+    let params = RunListParams {
+        agent: Some("gdelt".into()),
+        limit: Some(2),
+        ..Default::default()
+    };
+    let first = params.page(runs.clone()).unwrap();
+    assert_eq!(ids(&first.runs), vec!["run-5", "run-4"]);
+    let params = RunListParams {
+        cursor: first.next_cursor,
+        ..params
+    };
+    let second = params.page(runs.clone()).unwrap();
+    assert_eq!(ids(&second.runs), vec!["run-2", "run-1"]);
+    assert!(second.next_cursor.is_none());
+
+    let params = RunListParams {
+        status: Some(AgentRunState::Completed),
+        since: Some(start + chrono::Duration::hours(1)),
+        until: Some(start + chrono::Duration::hours(5)),
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(&params.page(runs.clone()).unwrap().runs),
+        vec!["run-3", "run-2", "run-1"]
+    );
+
+    let params = RunListParams {
+        cursor: Some("not-a-cursor".into()),
+        ..Default::default()
+    };
+    assert!(params.page(runs).is_err());
+}
+
+#[test]
+fn run_comparison_flags_large_drops() {
+    let snapshot = |collected: u64, failed: u64, documents: u64, people: u64, orgs: u64| {
+        let mut run = AgentRunStatus::started(Uuid::new_v4().to_string(), "gdelt");
+        run.documents_collected = collected;
+        run.documents_failed = failed;
+        let stats = RunExtractionStats {
+            documents,
+            extraction_errors: failed,
+            entities_by_type: [
+                ("person".to_string(), people),
+                ("organization".to_string(), orgs),
+            ]
+            .into(),
+            new_entities: people,
+            repeated_entities: orgs,
+        };
+        RunSnapshot::new(run, stats)
+    };
+
+    let steady = RunComparisonResponse::new(
+        "gdelt".to_string(),
+        snapshot(100, 2, 98, 40, 60),
+        snapshot(90, 3, 87, 38, 55),
+    );
+    assert!(steady.regressions.is_empty(), "{:?}", steady.regressions);
+    assert_eq!(steady.entity_types.len(), 2);
+
+    let broken = RunComparisonResponse::new(
+        "gdelt".to_string(),
+        snapshot(100, 2, 98, 40, 60),
+        snapshot(100, 30, 70, 2, 20),
+    );
+    assert_eq!(broken.head.error_rate, 30.0);
+    assert_eq!(broken.regressions.len(), 4, "{:?}", broken.regressions);
+    assert!(broken
+        .regressions
+        .iter()
+        .any(|r| r.starts_with("person entities fell from 40 to 2")));
+    assert!(broken
+        .regressions
+        .iter()
+        .any(|r| r.starts_with("extraction error rate rose")));
+
+    let json = serde_json::to_value(&broken).unwrap();
+    assert_eq!(json["base"]["entities_by_type"]["person"], 40);
+    assert_eq!(json["head"]["run"]["documents_failed"], 30);
+}
+
+#[test]
+fn budget_status_response_roundtrip() {
+    let json = r#"{
+        "day": "2026-03-01",
+        "spillover": "drop",
+        "off_peak_hours": "1-6",
+        "sources": [{
+            "source": "gdelt",
+            "daily_limit": 2000,
+            "used": 2000,
+            "remaining": 0,
+            "dropped": 35,
+            "queued": 0,
+            "off_peak_processed": 0
+        }]
+    }"#;
+    let budget: BudgetStatusResponse =
+        serde_json::from_str(json).expect("failed to deserialize BudgetStatusResponse");
+
+    assert_eq!(budget.spillover, Spillover::Drop);
+    assert_eq!(budget.sources[0].daily_limit, Some(2000));
+    assert_eq!(budget.sources[0].dropped, 35);
+    let back = serde_json::to_value(&budget).unwrap();
+    assert_eq!(back["day"], "2026-03-01");
+}
+
+#[test]
+fn validation_reports_merge_per_rule() {
+    let mut total = ValidationReport::default();
+    let mut one = ValidationReport {
+        entities_rejected: 2,
+        ..Default::default()
+    };
+    one.by_rule.insert("min_name_length".to_string(), 2);
+    let mut two = ValidationReport {
+        relationships_rejected: 1,
+        ..Default::default()
+    };
+    two.by_rule.insert("min_name_length".to_string(), 1);
+    two.by_rule.insert("self_relationship".to_string(), 1);
+
+    total.merge(&one);
+    total.merge(&two);
+
+    assert_eq!(
+        (total.entities_rejected, total.relationships_rejected),
+        (2, 1)
+    );
+    assert_eq!(total.by_rule["min_name_length"], 3);
+    assert_eq!(total.by_rule["self_relationship"], 1);
+}
+
+#[test]
+fn agent_run_state_str_matches_serialized_form() {
+    for state in [
+        AgentRunState::Running,
+        AgentRunState::Completed,
+        AgentRunState::Failed,
+    ] {
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json, serde_json::Value::String(state.as_str().to_string()));
+    }
+}
+
+#[test]
+fn run_documents_sample_defaults_and_clamps() {
+    assert_eq!(RunDocumentsParams::default().sample(), 10);
+    assert_eq!(RunDocumentsParams { sample: Some(0) }.sample(), 1);
+    assert_eq!(
+        RunDocumentsParams { sample: Some(5000) }.sample(),
+        MAX_RUN_DOCUMENT_SAMPLE
+    );
+}
+
+#[test]
+fn run_logs_response_roundtrip() {
+    let json = r#"{
+        "run_id": "r1",
+        "lines": [
+            {
+                "timestamp": "2026-03-01T12:00:00Z",
+                "level": "WARN",
+                "target": "argus_server::pipeline",
+                "message": "Failed to archive source documents",
+                "spans": ["agent_run", "store"],
+                "fields": { "agent": "gdelt", "error": "connection refused" }
+            },
+            {
+                "timestamp": "2026-03-01T12:00:01Z",
+                "level": "INFO",
+                "target": "argus_server::handlers::agents",
+                "message": "Agent run completed"
+            }
+        ],
+        "dropped": 0
+    }"#;
+    let logs: RunLogsResponse =
+        serde_json::from_str(json).expect("failed to deserialize RunLogsResponse");
+    assert_eq!(logs.lines[0].spans, ["agent_run", "store"]);
+    assert_eq!(logs.lines[0].fields["agent"], "gdelt");
+    assert!(logs.lines[1].spans.is_empty());
+
+    let line: &RunLogLine = &logs.lines[1];
+    let json = serde_json::to_value(line).unwrap();
+    assert!(json.get("fields").is_none());
+}
+
+#[test]
+fn archived_document_omits_missing_extraction() {
+    let raw = RawDocument {
+        source: "gdelt".to_string(),
+        source_id: "evt-1".to_string(),
+        title: None,
+        content: "text".to_string(),
+        url: None,
+        collected_at: Utc::now(),
+        observed_at: None,
+        identifiers: Default::default(),
+        metadata: serde_json::Value::Null,
+        structured: None,
+    };
+    let mut doc = ArchivedDocument::from_raw(&raw, Some("r1".to_string()), vec![]);
+    let json = serde_json::to_value(&doc).unwrap();
+    assert!(json.get("extraction").is_none());
+    assert!(json.get("extraction_error").is_none());
+
+    doc.extraction_error = Some("invalid JSON from model".to_string());
+    let json = serde_json::to_string(&doc).unwrap();
+    let back: ArchivedDocument = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        back.extraction_error.as_deref(),
+        Some("invalid JSON from model")
+    );
+    assert!(back.extraction.is_none());
+}
+
+#[test]
+fn retry_failures_params_default_to_due_only() {
+    let params: RetryFailuresParams = serde_json::from_str("{}").unwrap();
+    assert!(!params.force);
+    let params: RetryFailuresParams = serde_json::from_str(r#"{"force": true}"#).unwrap();
+    assert!(params.force);
+}
+
+// ---------------------------------------------------------------------------
+// DocumentSearchRequest / DocumentSearchResponse serialization/deserialization
+// ---------------------------------------------------------------------------
+
+#[test]
+fn document_search_request_defaults() {
+    let req: DocumentSearchRequest = serde_json::from_str(r#"{"query": "shell company"}"#)
+        .expect("failed to deserialize DocumentSearchRequest");
+
+    assert_eq!(req.query, "shell company");
+    assert!(req.source.is_none());
+    assert!(req.start.is_none());
+    assert!(req.end.is_none());
+    assert_eq!(req.limit, 20);
+}
+
+#[test]
+fn document_search_response_roundtrip() {
+    let entity_id = Uuid::new_v4();
+    let resp = DocumentSearchResponse {
+        results: vec![DocumentSearchResult {
+            id: Uuid::new_v4(),
+            source: "gdelt".to_string(),
+            source_id: "evt-1".to_string(),
+            title: Some("Shell company exposed".to_string()),
+            url: None,
+            collected_at: Utc::now(),
+            score: 2.5,
+            snippet: "<mark>Shell</mark> company exposed".to_string(),
+            entity_ids: vec![entity_id],
+        }],
+        total: 1,
+    };
+
+    let json = serde_json::to_string(&resp).expect("failed to serialize DocumentSearchResponse");
+    let deserialized: DocumentSearchResponse =
+        serde_json::from_str(&json).expect("failed to deserialize DocumentSearchResponse");
+
+    assert_eq!(deserialized.total, 1);
+    assert_eq!(deserialized.results[0].source_id, "evt-1");
+    assert_eq!(deserialized.results[0].entity_ids, vec![entity_id]);
+}
+
+#[test]
+fn neighbor_query_params_parse_relation_types() {
+    let ontology = Ontology::new(Vec::<String>::new(), ["funded_by"]);
+    let params: NeighborQueryParams = serde_json::from_str(
+        r#"{"direction": "incoming", "relation_types": "owner_of, FUNDED_BY,"}"#,
+    )
+    .expect("failed to deserialize NeighborQueryParams");
+    let filter = params.filter(&ontology).expect("registered relation types");
+
+    assert_eq!(filter.direction, Direction::Incoming);
+    assert_eq!(
+        filter.relation_types,
+        vec![
+            RelationType::OwnerOf,
+            RelationType::Custom("funded_by".to_string())
+        ]
+    );
+    assert_eq!(
+        NeighborQueryParams::default()
+            .filter(&ontology)
+            .unwrap()
+            .direction,
+        Direction::Both
+    );
+    assert_eq!(filter.as_of, None);
+    assert_eq!(filter.min_strength, None);
+
+    let params: NeighborQueryParams = serde_json::from_str(r#"{"min_strength": 0.4}"#).unwrap();
+    assert_eq!(params.filter(&ontology).unwrap().min_strength, Some(0.4));
+
+    // Unregistered custom types are rejected rather than matching nothing
+    let params: NeighborQueryParams =
+        serde_json::from_str(r#"{"relation_types": "owner_of,owns_of"}"#).unwrap();
+    let error = params.filter(&ontology).unwrap_err();
+    assert!(error.contains("owns_of"), "{error}");
+}
+
+#[test]
+fn path_query_params_carry_as_of_and_clamp_limit() {
+    let from = Uuid::new_v4();
+    let to = Uuid::new_v4();
+    let json = format!(
+        r#"{{"from": "{from}", "to": "{to}", "limit": 500, "as_of": "2025-06-30T00:00:00Z"}}"#
+    );
+    let params: PathQueryParams =
+        serde_json::from_str(&json).expect("failed to deserialize PathQueryParams");
+
+    assert_eq!(params.limit(), MAX_PATHS);
+    assert_eq!(
+        params.as_of.map(|t| t.to_rfc3339()).as_deref(),
+        Some("2025-06-30T00:00:00+00:00")
+    );
+
+    let params: NeighborQueryParams = serde_json::from_str(r#"{"as_of": "2025-06-30T00:00:00Z"}"#)
+        .expect("failed to deserialize NeighborQueryParams");
+    assert!(params.filter(&Ontology::default()).unwrap().as_of.is_some());
+}
+
+// ---------------------------------------------------------------------------
+// EntityHistoryResponse serialization/deserialization
+// ---------------------------------------------------------------------------
+
+#[test]
+fn entity_history_response_roundtrip() {
+    let entity_id = Uuid::new_v4();
+    let response = EntityHistoryResponse {
+        entity_id,
+        changes: vec![EntityChange {
+            id: Uuid::new_v4(),
+            entity_id,
+            kind: ChangeKind::PropertyChanged,
+            field: Some("country".to_string()),
+            old_value: Some(serde_json::json!("DE")),
+            new_value: None,
+            source: "opencorporates".to_string(),
+            document_id: Some("doc-1".to_string()),
+            changed_at: Utc::now(),
+        }],
+    };
+
+    let json = serde_json::to_value(&response).expect("failed to serialize EntityHistoryResponse");
+    assert_eq!(json["changes"][0]["kind"], "property_changed");
+
+    let back: EntityHistoryResponse =
+        serde_json::from_value(json).expect("failed to deserialize EntityHistoryResponse");
+    assert_eq!(back.entity_id, entity_id);
+    assert_eq!(back.changes[0].kind, ChangeKind::PropertyChanged);
+    assert_eq!(back.changes[0].old_value, Some(serde_json::json!("DE")));
+    assert!(back.changes[0].new_value.is_none());
+}
+
+#[test]
+fn tombstone_list_response_roundtrip() {
+    assert_eq!(TombstoneListParams::default().limit(), 100);
+    assert_eq!(TombstoneListParams { limit: Some(0) }.limit(), 1);
+
+    let response = TombstoneListResponse {
+        tombstones: vec![Tombstone {
+            entity_id: Uuid::new_v4(),
+            name: "Acme Shipping Ltd".to_string(),
+            entity_type: EntityType::Organization,
+            source: "opencorporates".to_string(),
+            source_id: None,
+            deleted_at: Utc::now(),
+            purged: true,
+        }],
+    };
+    let json = serde_json::to_value(&response).expect("failed to serialize TombstoneListResponse");
+    assert_eq!(json["tombstones"][0]["entity_type"], "organization");
+    assert!(json["tombstones"][0]["source_id"].is_null());
+
+    let back: TombstoneListResponse =
+        serde_json::from_value(json).expect("failed to deserialize TombstoneListResponse");
+    assert!(back.tombstones[0].purged);
+    assert_eq!(
+        ChangeKind::from_name(ChangeKind::Deleted.as_str()),
+        Some(ChangeKind::Deleted)
+    );
+}
+
+#[test]
+fn deny_list_request_defaults_to_exact_match() {
+    let request: DenyEntryRequest = serde_json::from_str(r#"{"pattern": "GOVERNMENT"}"#)
+        .expect("failed to deserialize DenyEntryRequest");
+    assert_eq!(request.match_kind, DenyMatch::Exact);
+    assert!(request.entity_type.is_none() && request.source.is_none());
+
+    let response = DenyListResponse {
+        entries: vec![DenyEntry {
+            id: Uuid::new_v4(),
+            pattern: "(local |state )?police".to_string(),
+            match_kind: DenyMatch::Regex,
+            entity_type: Some(EntityType::Organization),
+            source: Some("gdelt".to_string()),
+            note: None,
+            created_at: Utc::now(),
+        }],
+    };
+    let json = serde_json::to_value(&response).expect("failed to serialize DenyListResponse");
+    assert_eq!(json["entries"][0]["match"], "regex");
+    assert!(json["entries"][0].get("note").is_none());
+
+    let back: DenyListResponse =
+        serde_json::from_value(json).expect("failed to deserialize DenyListResponse");
+    assert_eq!(back.entries[0], response.entries[0]);
+}
+
+#[test]
+fn feedback_request_and_label_roundtrip() {
+    let request: FeedbackRequest =
+        serde_json::from_str(r#"{"target": "answer", "correct": true, "confidence": 0.7}"#)
+            .expect("failed to deserialize FeedbackRequest");
+    assert_eq!(request.target, FeedbackTarget::Answer);
+    assert!(request.target_id.is_none() && request.note.is_none());
+
+    let response = FeedbackListResponse {
+        labels: vec![FeedbackLabel {
+            id: Uuid::new_v4(),
+            target: FeedbackTarget::Relationship,
+            target_id: Some(Uuid::new_v4()),
+            correct: false,
+            confidence: 0.85,
+            source: "gdelt".to_string(),
+            kind: "owner_of".to_string(),
+            note: None,
+            prompt_version: Some(2),
+            created_at: Utc::now(),
+        }],
+        total: 1,
+    };
+    let json = serde_json::to_value(&response).expect("failed to serialize FeedbackListResponse");
+    assert_eq!(json["labels"][0]["target"], "relationship");
+    assert!(json["labels"][0].get("note").is_none());
+
+    let back: FeedbackListResponse =
+        serde_json::from_value(json).expect("failed to deserialize FeedbackListResponse");
+    assert_eq!(back.labels[0], response.labels[0]);
+}
+
+#[test]
+fn calibration_params_default_and_report_serializes() {
+    let params: CalibrationParams =
+        serde_json::from_str(r#"{"by": "type", "bins": 500, "precision": 2.0}"#)
+            .expect("failed to deserialize CalibrationParams");
+    assert_eq!(params.by, CalibrationGrouping::Type);
+    assert_eq!(params.bins(), 50);
+    assert_eq!(params.precision(), 1.0);
+    let defaults = CalibrationParams::default();
+    assert_eq!(
+        (defaults.by, defaults.bins(), defaults.precision()),
+        (CalibrationGrouping::SourceType, 10, 0.9)
+    );
+
+    let report = calibration_report(&[], defaults.by, defaults.bins(), defaults.precision());
+    let json = serde_json::to_value(&report).expect("failed to serialize CalibrationReport");
+    assert_eq!(json["grouping"], "source_type");
+    assert_eq!(json["overall"]["labels"], 0);
+    assert!(json["overall"]["bins"][0]["accuracy"].is_null());
+}
+
+#[test]
+fn prompt_bundle_requests_and_listing_roundtrip() {
+    let request: PromptBundleRequest = serde_json::from_str(r#"{"per_source": 100}"#)
+        .expect("failed to deserialize PromptBundleRequest");
+    assert_eq!(request.per_source(), 20);
+    assert_eq!(PromptBundleRequest::default().per_source(), 6);
+
+    let deploy: PromptDeploymentRequest = serde_json::from_str(r#"{"control": 0, "candidate": 2}"#)
+        .expect("failed to deserialize PromptDeploymentRequest");
+    assert_eq!(
+        (deploy.control, deploy.candidate, deploy.candidate_share),
+        (0, Some(2), None)
+    );
+
+    let response = PromptBundleListResponse {
+        bundles: vec![PromptBundle {
+            version: 2,
+            created_at: Utc::now(),
+            note: Some("after sanctions review".to_string()),
+            examples: [(
+                "opensanctions".to_string(),
+                vec![FewShotExample {
+                    label_id: Uuid::new_v4(),
+                    target: FeedbackTarget::Relationship,
+                    correct: false,
+                    text: Some("The vessel was previously managed by Acme Shipping".to_string()),
+                    item: serde_json::json!({"source": "Acme Shipping", "target": "Sea Star", "type": "owner_of"}),
+                }],
+            )]
+            .into(),
+        }],
+        deployment: PromptDeployment {
+            control: 0,
+            candidate: Some(2),
+            candidate_share: 0.5,
+            updated_at: Utc::now(),
+        },
+    };
+    let json =
+        serde_json::to_value(&response).expect("failed to serialize PromptBundleListResponse");
+    assert_eq!(
+        json["bundles"][0]["examples"]["opensanctions"][0]["target"],
+        "relationship"
+    );
+    assert!(json["bundles"][0]["examples"]["opensanctions"][0]["item"]["type"].is_string());
+    let back: PromptBundleListResponse =
+        serde_json::from_value(json).expect("failed to deserialize PromptBundleListResponse");
+    assert_eq!(back.bundles, response.bundles);
+    assert_eq!(back.deployment, response.deployment);
+}
+
+#[test]
+fn snapshot_list_response_roundtrip() {
+    let response = SnapshotListResponse {
+        snapshots: vec![SnapshotManifest {
+            file: "argus-20260114T030000Z.jsonl.gz".to_string(),
+            meta: SnapshotMeta {
+                format_version: SNAPSHOT_FORMAT_VERSION,
+                argus_version: "0.1.0".to_string(),
+                created_at: Utc::now(),
+                instance_id: "replica-a".to_string(),
+            },
+            counts: SnapshotCounts {
+                nodes: 120,
+                relationships: 340,
+                alert_rules: 2,
+                deny_entries: 1,
+                paused_agents: 0,
+            },
+            bytes: 48_213,
+        }],
+    };
+    let json = serde_json::to_value(&response).expect("failed to serialize SnapshotListResponse");
+    assert_eq!(json["snapshots"][0]["counts"]["relationships"], 340);
+    let back: SnapshotListResponse =
+        serde_json::from_value(json).expect("failed to deserialize SnapshotListResponse");
+    assert_eq!(back.snapshots, response.snapshots);
+
+    let request: SnapshotRestoreRequest =
+        serde_json::from_str(r#"{"file": "argus-20260114T030000Z.jsonl.gz"}"#)
+            .expect("failed to deserialize SnapshotRestoreRequest");
+    assert_eq!(request.file, response.snapshots[0].file);
+}
+
+#[test]
+fn redacted_config_hides_credentials() {
+    let mut config = AppConfig::from_env();
+    config.anthropic_api_key = "sk-ant-secret".to_string();
+    config.neo4j_password = String::new();
+    let json = config.redacted();
+    assert_eq!(json["anthropic_api_key"], "[redacted]");
+    assert_eq!(json["neo4j_password"], "");
+    assert_eq!(json["backup_dir"], serde_json::json!(config.backup_dir));
+    assert!(!json.to_string().contains("sk-ant-secret"));
+}
+
+#[test]
+fn briefing_params_default_to_a_day() {
+    assert_eq!(
+        BriefingParams::default().window().unwrap(),
+        chrono::Duration::hours(24)
+    );
+    let params: BriefingParams =
+        serde_json::from_value(serde_json::json!({ "since": "7d" })).unwrap();
+    assert_eq!(params.window().unwrap(), chrono::Duration::days(7));
+    let params: BriefingParams =
+        serde_json::from_value(serde_json::json!({ "since": "30d" })).unwrap();
+    assert!(params.window().is_err());
+}
+
+#[test]
+fn event_density_params_default_to_a_day_of_hours() {
+    let now = Utc::now();
+    let query = EventDensityParams::default().query(now).unwrap();
+    assert_eq!(
+        (query.since, query.until),
+        (now - chrono::Duration::hours(24), now)
+    );
+    assert_eq!(query.interval, chrono::Duration::hours(1));
+    assert_eq!(query.bbox, argus_core::density::BoundingBox::WORLD);
+    assert_eq!(query.precision, 2);
+
+    let params: EventDensityParams = serde_json::from_value(
+        serde_json::json!({ "bbox": "29,49,32,52", "interval": "15m", "precision": 6 }),
+    )
+    .unwrap();
+    let query = params.query(now).unwrap();
+    assert_eq!(
+        (query.interval, query.precision),
+        (chrono::Duration::minutes(15), 6)
+    );
+    assert!(query.bbox.contains(50.45, 30.52));
+    assert_eq!(
+        EventDensityParams {
+            bbox: params.bbox,
+            ..Default::default()
+        }
+        .query(now)
+        .unwrap()
+        .precision,
+        4
+    );
+
+    for invalid in [
+        serde_json::json!({ "interval": "1m" }),
+        serde_json::json!({ "precision": 8 }),
+        serde_json::json!({ "bbox": "29,49,32" }),
+        serde_json::json!({ "since": "2024-01-02T00:00:00Z", "until": "2024-01-01T00:00:00Z" }),
+    ] {
+        let params: EventDensityParams = serde_json::from_value(invalid).unwrap();
+        assert!(params.query(now).is_err());
+    }
+}
+
+#[test]
+fn archived_positions_params_default_to_a_week() {
+    let now = Utc::now();
+    let params: ArchivedPositionsParams =
+        serde_json::from_value(serde_json::json!({ "asset": "mmsi:244660000", "limit": 50_000 }))
+            .unwrap();
+    assert_eq!(
+        params.window(now).unwrap(),
+        (now - chrono::Duration::days(7), now)
+    );
+    assert_eq!(
+        params.limit(),
+        argus_core::api_types::MAX_ARCHIVED_POSITIONS
+    );
+
+    let params: ArchivedPositionsParams = serde_json::from_value(serde_json::json!({
+        "asset": "mmsi:244660000",
+        "since": "2026-03-15T00:00:00Z",
+        "until": "2026-03-14T00:00:00Z",
+    }))
+    .unwrap();
+    assert!(params.window(now).is_err());
+
+    // The whole archive is not one request
+    let params: ArchivedPositionsParams = serde_json::from_value(serde_json::json!({
+        "asset": "mmsi:244660000",
+        "since": "2000-01-01T00:00:00Z",
+    }))
+    .unwrap();
+    assert!(params.window(now).is_err());
+    let params: ArchivedPositionsParams = serde_json::from_value(serde_json::json!({
+        "asset": "mmsi:244660000",
+        "since": "2026-02-11T00:00:00Z",
+        "until": "2026-03-14T00:00:00Z",
+    }))
+    .unwrap();
+    assert!(params.window(now).is_ok());
+
+    let response: ColdStorageResponse = serde_json::from_value(serde_json::json!({
+        "partitions": [{ "table": "positions", "date": "2026-03-14", "files": 2, "bytes": 18_432 }],
+        "buffered": 120,
+    }))
+    .unwrap();
+    assert_eq!(
+        response.partitions[0].table,
+        argus_core::ColdTable::Positions
+    );
+    assert_eq!(
+        serde_json::to_value(&response).unwrap()["partitions"][0]["date"],
+        "2026-03-14"
+    );
+}
+
+#[test]
+fn analytics_sql_rows_are_column_keyed_objects() {
+    let request: AnalyticsSqlRequest = serde_json::from_value(
+        serde_json::json!({ "sql": "SELECT count(*) AS reports FROM positions" }),
+    )
+    .unwrap();
+    assert_eq!(request.limit(), 1000);
+    let request: AnalyticsSqlRequest =
+        serde_json::from_value(serde_json::json!({ "sql": "SELECT 1", "limit": 0 })).unwrap();
+    assert_eq!(request.limit(), 1);
+
+    let response: AnalyticsSqlResponse = serde_json::from_value(serde_json::json!({
+        "columns": [{ "name": "asset", "data_type": "Utf8" }, { "name": "reports", "data_type": "Int64" }],
+        "rows": [{ "asset": "mmsi:244660000", "reports": 42 }],
+        "truncated": false,
+        "elapsed_ms": 18,
+    }))
+    .unwrap();
+    assert_eq!(response.columns[1].name, "reports");
+    assert_eq!(response.rows[0]["reports"], 42);
+    assert_eq!(
+        serde_json::to_value(&response).unwrap()["columns"][0]["data_type"],
+        "Utf8"
+    );
+}
+
+#[test]
+fn briefing_links_entities() {
+    let entity = Entity::new(
+        EntityType::Organization,
+        "Acme Holdings".to_string(),
+        "opencorporates".to_string(),
+    );
+    let briefing = Briefing {
+        since: Utc::now() - chrono::Duration::hours(24),
+        until: Utc::now(),
+        summary: "Acme Holdings was registered.".to_string(),
+        confidence: 0.8,
+        key_entities: vec![BriefingEntity::from(&entity)],
+        new_entities: vec![BriefingEntity::from(&entity)],
+        new_relationships: Vec::new(),
+        alerts: Vec::new(),
+        counts: BriefingCounts {
+            new_entities: 1,
+            ..BriefingCounts::default()
+        },
+        usage: ReasoningUsage::default(),
+    };
+    let json = serde_json::to_value(&briefing).expect("failed to serialize Briefing");
+    assert_eq!(
+        json["key_entities"][0]["link"],
+        format!("/api/entities/{}", entity.id)
+    );
+    assert_eq!(json["new_entities"][0]["entity_type"], "organization");
+    assert_eq!(json["counts"]["truncated"], false);
+
+    let back: Briefing = serde_json::from_value(json).expect("failed to deserialize Briefing");
+    assert_eq!(back.new_entities, briefing.new_entities);
+}
+
+#[test]
+fn entity_history_params_clamp_limit() {
+    assert_eq!(EntityHistoryParams::default().limit(), 100);
+    assert_eq!(EntityHistoryParams { limit: Some(0) }.limit(), 1);
+    assert_eq!(
+        EntityHistoryParams {
+            limit: Some(50_000)
+        }
+        .limit(),
+        1000
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Export request types
+// ---------------------------------------------------------------------------
+
+#[test]
+fn export_params_default_to_stix() {
+    let params: ExportParams =
+        serde_json::from_str("{}").expect("failed to deserialize ExportParams");
+    assert_eq!(params.format, ExportFormat::Stix);
+    assert!(params.depth.is_none());
+}
+
+#[test]
+fn investigation_export_request_roundtrip() {
+    let ids = vec![Uuid::new_v4(), Uuid::new_v4()];
+    let json = serde_json::json!({ "entity_ids": ids, "format": "stix", "depth": 2 });
+    let request: InvestigationExportRequest =
+        serde_json::from_value(json).expect("failed to deserialize InvestigationExportRequest");
+    assert_eq!(request.entity_ids, ids);
+    assert_eq!(request.format, ExportFormat::Stix);
+    assert_eq!(request.depth, Some(2));
+
+    let ftm: ExportParams =
+        serde_json::from_str(r#"{"format": "ftm"}"#).expect("failed to deserialize ExportParams");
+    assert_eq!(ftm.format, ExportFormat::Ftm);
+    assert!(serde_json::from_str::<InvestigationExportRequest>(
+        r#"{"entity_ids": [], "format": "csv"}"#
+    )
+    .is_err());
+}
+
+#[test]
+fn case_timeline_roundtrip() {
+    let note: InvestigationNoteRequest = serde_json::from_str(r#"{"text": "Called the registry"}"#)
+        .expect("failed to deserialize InvestigationNoteRequest");
+    assert!(note.entity_id.is_none() && note.author.is_none() && note.at.is_none());
+
+    let params: CaseTimelineParams = serde_json::from_str(r#"{"format": "csv"}"#)
+        .expect("failed to deserialize CaseTimelineParams");
+    assert_eq!(params.format, TimelineFormat::Csv);
+    assert_eq!(CaseTimelineParams::default().format, TimelineFormat::Json);
+
+    let entity_id = Uuid::new_v4();
+    let response = CaseTimelineResponse {
+        investigation_id: Uuid::new_v4(),
+        events: vec![CaseEvent {
+            at: Utc::now(),
+            kind: CaseEventKind::Position,
+            entity_ids: vec![entity_id],
+            summary: "Volga Star at 35.8900, -5.3200".to_string(),
+            source: "ais".to_string(),
+            reference: "mmsi:273123456".to_string(),
+        }],
+        truncated: false,
+    };
+    let json = serde_json::to_value(&response).expect("failed to serialize CaseTimelineResponse");
+    assert_eq!(json["events"][0]["kind"], "position");
+
+    let back: CaseTimelineResponse =
+        serde_json::from_value(json).expect("failed to deserialize CaseTimelineResponse");
+    assert_eq!(back.events, response.events);
+}
+
+#[test]
+fn enrich_roundtrip() {
+    let params: EnrichParams =
+        serde_json::from_str(r#"{"agents": " OpenSanctions, ,opencorporates"}"#)
+            .expect("failed to deserialize EnrichParams");
+    assert_eq!(
+        params.agent_names(),
+        Some(vec![
+            "opensanctions".to_string(),
+            "opencorporates".to_string()
+        ])
+    );
+    assert_eq!(
+        EnrichParams {
+            agents: Some(" ,".to_string())
+        }
+        .agent_names(),
+        None
+    );
+
+    let response = EnrichResponse {
+        entity_id: Uuid::new_v4(),
+        run_id: "run-1".to_string(),
+        agents: vec![AgentEnrichment {
+            agent: "opencorporates".to_string(),
+            documents: 0,
+            entities: 0,
+            relationships: 0,
+            failed: 0,
+            error: Some("OpenCorporates API returned 401".to_string()),
+        }],
+        documents: 0,
+        entities: 0,
+        relationships: 0,
+    };
+    let json = serde_json::to_value(&response).expect("failed to serialize EnrichResponse");
+    assert_eq!(
+        json["agents"][0]["error"],
+        "OpenCorporates API returned 401"
+    );
+
+    let back: EnrichResponse =
+        serde_json::from_value(json).expect("failed to deserialize EnrichResponse");
+    assert_eq!(back.agents[0].agent, "opencorporates");
+}
+
+#[test]
+fn follow_request_and_feed_params_defaults() {
+    let request: FollowRequest =
+        serde_json::from_str(r#"{"events": ["sanctions_change"], "subscriber": "analyst-7"}"#)
+            .expect("failed to deserialize FollowRequest");
+    assert_eq!(request.events, vec![SubscriptionEvent::SanctionsChange]);
+    assert!(request.webhook_url.is_none());
+    assert!(serde_json::from_str::<FollowRequest>(r#"{"events": ["renamed"]}"#).is_err());
+
+    let params: NotificationFeedParams = serde_json::from_str(r#"{"limit": 5000}"#)
+        .expect("failed to deserialize NotificationFeedParams");
+    assert_eq!(params.limit(), 1000);
+    assert_eq!(NotificationFeedParams::default().limit(), 100);
+}
+
+// ---------------------------------------------------------------------------
+// EntityDetailResponse serialization/deserialization
+// ---------------------------------------------------------------------------
+
+#[test]
+fn entity_detail_response_roundtrip() {
+    let entity = Entity::new(EntityType::Person, "Alice".to_string(), "test".to_string());
     let neighbor = Entity::new(
         EntityType::Organization,
         "Corp Inc".to_string(),
@@ -573,11 +1676,7 @@ fn entity_detail_response_roundtrip() {
         "test".to_string(),
     );
 
-    let resp = EntityDetailResponse {
-        entity: entity.clone(),
-        relationships: vec![rel],
-        neighbors: vec![neighbor],
-    };
+    let resp = EntityDetailResponse::new(entity.clone(), vec![rel], vec![neighbor]);
 
     let json = serde_json::to_string(&resp).expect("failed to serialize EntityDetailResponse");
     let deserialized: EntityDetailResponse =
@@ -592,6 +1691,54 @@ fn entity_detail_response_roundtrip() {
     );
     assert_eq!(deserialized.neighbors.len(), 1);
     assert_eq!(deserialized.neighbors[0].name, "Corp Inc");
+    assert_eq!(deserialized.outgoing.len(), 1);
+    assert!(deserialized.incoming.is_empty());
+    assert!(deserialized.evidence.is_empty());
+}
+
+#[test]
+fn entity_detail_response_lists_relationship_evidence() {
+    let entity = Entity::new(
+        EntityType::Organization,
+        "Acme Ltd".to_string(),
+        "manual".to_string(),
+    );
+    let owner = Entity::new(
+        EntityType::Organization,
+        "Holdco SA".to_string(),
+        "manual".to_string(),
+    );
+    let mut owned_by = Relationship::new(
+        owner.id,
+        entity.id,
+        RelationType::OwnerOf,
+        "manual".to_string(),
+    );
+    owned_by.properties = serde_json::json!({
+        "evidence": " Acme Ltd is wholly owned by Holdco SA. ",
+        "evidence_page": 4,
+        "evidence_paragraph": 12,
+    });
+    let unquoted = Relationship::new(
+        entity.id,
+        owner.id,
+        RelationType::RelatedTo,
+        "gdelt".to_string(),
+    );
+
+    let resp = EntityDetailResponse::new(
+        entity.clone(),
+        vec![owned_by.clone(), unquoted],
+        vec![owner.clone()],
+    );
+
+    assert_eq!(resp.evidence.len(), 1);
+    let evidence = &resp.evidence[0];
+    assert_eq!(evidence.relationship_id, owned_by.id);
+    assert_eq!(evidence.direction, Direction::Incoming);
+    assert_eq!(evidence.entity_id, owner.id);
+    assert_eq!(evidence.quote, "Acme Ltd is wholly owned by Holdco SA.");
+    assert_eq!((evidence.page, evidence.paragraph), (Some(4), Some(12)));
 }
 
 // ---------------------------------------------------------------------------
@@ -617,8 +1764,12 @@ fn entity_type_stat_roundtrip() {
 // AppConfig::from_env() with default values
 // ---------------------------------------------------------------------------
 
+/// Serializes tests that mutate process-wide environment variables.
+static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
 #[test]
 fn app_config_from_env_defaults() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     // Clear any existing env vars that could interfere
     std::env::remove_var("NEO4J_URI");
     std::env::remove_var("NEO4J_USER");
@@ -632,7 +1783,7 @@ fn app_config_from_env_defaults() {
 
     assert_eq!(config.neo4j_uri, "bolt://localhost:7687");
     assert_eq!(config.neo4j_user, "neo4j");
-    assert_eq!(config.neo4j_password, "argus");
+    assert_eq!(config.neo4j_password, "argus2026");
     assert_eq!(config.qdrant_url, "http://localhost:6333");
     assert_eq!(config.anthropic_api_key, ""); // unwrap_or_default
     assert_eq!(config.server_host, "0.0.0.0");
@@ -642,6 +1793,7 @@ fn app_config_from_env_defaults() {
 
 #[test]
 fn app_config_from_env_custom_values() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     // Set custom env vars
     std::env::set_var("NEO4J_URI", "bolt://custom:7688");
     std::env::set_var("NEO4J_USER", "admin");
@@ -673,6 +1825,7 @@ fn app_config_from_env_custom_values() {
 
 #[test]
 fn app_config_from_env_invalid_port_falls_back_to_default() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     std::env::set_var("SERVER_PORT", "not_a_number");
 
     let config = AppConfig::from_env();
@@ -681,17 +1834,222 @@ fn app_config_from_env_invalid_port_falls_back_to_default() {
     std::env::remove_var("SERVER_PORT");
 }
 
+#[test]
+fn app_config_relationship_identity_overrides_defaults() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    std::env::set_var(
+        "RELATIONSHIP_IDENTITY",
+        "LOCATED_AT=1h, meeting_with=singleton, bogus, owner_of=weekly",
+    );
+
+    let config = AppConfig::from_env();
+    let identity = |name: &str| config.relationship_identity.get(name).copied();
+    assert_eq!(
+        identity("located_at"),
+        Some(RelationIdentity::Bucketed { seconds: 3600 })
+    );
+    assert_eq!(identity("meeting_with"), Some(RelationIdentity::Singleton));
+    assert_eq!(
+        identity("transacted_with"),
+        Some(RelationIdentity::Bucketed { seconds: 86400 })
+    );
+    assert_eq!(identity("owner_of"), None);
+
+    std::env::remove_var("RELATIONSHIP_IDENTITY");
+}
+
+#[test]
+fn app_config_reload_takes_only_reloadable_fields() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let running = AppConfig::from_env();
+    let mut fresh = running.clone();
+    fresh.extraction_quotas.insert("gdelt".to_string(), 500);
+    fresh.validation_blocklist.push("redacted".to_string());
+    fresh.server_port = running.server_port + 1;
+
+    assert_eq!(
+        running.changed_fields(&fresh),
+        ["extraction_quotas", "server_port", "validation_blocklist"]
+    );
+    let reloaded = running.reloaded(&fresh);
+    assert_eq!(reloaded.extraction_quotas.get("gdelt"), Some(&500));
+    assert!(reloaded
+        .validation_blocklist
+        .contains(&"redacted".to_string()));
+    assert_eq!(reloaded.server_port, running.server_port);
+    assert_eq!(reloaded.changed_fields(&fresh), ["server_port"]);
+}
+
+#[test]
+fn app_config_from_lookup_ignores_the_environment() {
+    let config = AppConfig::from_lookup(|key| match key {
+        "AGENT_INTERVALS" => Ok("gdelt=600, adsb=oops".to_string()),
+        "DEDUP_TTL_SECONDS" => Ok("60".to_string()),
+        _ => Err(std::env::VarError::NotPresent),
+    });
+    assert_eq!(config.agent_intervals.get("gdelt"), Some(&600));
+    assert_eq!(config.agent_intervals.len(), 1);
+    assert_eq!(config.dedup_ttl_seconds, 60);
+    assert_eq!(config.server_port, 8080);
+}
+
+#[test]
+fn app_config_extraction_pipelines_parse_per_source() {
+    let config = AppConfig::from_lookup(|key| match key {
+        "EXTRACTION_PIPELINES" => {
+            Ok("ais=structured, news_events=LLM, gdelt=magic, =llm".to_string())
+        }
+        "DEFAULT_EXTRACTION_PIPELINE" => Ok("llm".to_string()),
+        _ => Err(std::env::VarError::NotPresent),
+    });
+    assert_eq!(
+        config.extraction_pipelines.get("ais"),
+        Some(&PipelineKind::Structured)
+    );
+    assert_eq!(
+        config.extraction_pipelines.get("news_events"),
+        Some(&PipelineKind::Llm)
+    );
+    assert_eq!(config.extraction_pipelines.len(), 2);
+    assert_eq!(config.default_extraction_pipeline, PipelineKind::Llm);
+
+    let defaults = AppConfig::from_lookup(|_| Err(std::env::VarError::NotPresent));
+    assert!(defaults.extraction_pipelines.is_empty());
+    assert_eq!(defaults.default_extraction_pipeline, PipelineKind::Hybrid);
+}
+
+#[test]
+fn app_config_cold_storage_expires_positions_from_the_graph() {
+    let position_policies = |config: &AppConfig| -> Vec<u64> {
+        config
+            .effective_retention_policies()
+            .iter()
+            .filter(|p| p.label == "Position")
+            .map(|p| p.max_age_days)
+            .collect()
+    };
+    let lookup = |vars: &'static [(&'static str, &'static str)]| {
+        AppConfig::from_lookup(move |key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+                .ok_or(std::env::VarError::NotPresent)
+        })
+    };
+
+    assert!(position_policies(&lookup(&[])).is_empty());
+    assert_eq!(
+        position_policies(&lookup(&[("COLD_STORAGE_URL", "/var/lib/argus/cold")])),
+        [7]
+    );
+    let configured = lookup(&[
+        ("COLD_STORAGE_URL", "/var/lib/argus/cold"),
+        ("RETENTION_POLICIES", "Position@ais=2d"),
+    ]);
+    assert_eq!(position_policies(&configured), [2]);
+    let kept = lookup(&[
+        ("COLD_STORAGE_URL", "/var/lib/argus/cold"),
+        ("COLD_STORAGE_GRAPH_POSITION_DAYS", "0"),
+    ]);
+    assert!(position_policies(&kept).is_empty());
+}
+
 #[test]
 fn app_config_serialization_roundtrip() {
     let config = AppConfig {
         neo4j_uri: "bolt://localhost:7687".to_string(),
         neo4j_user: "neo4j".to_string(),
         neo4j_password: "pass".to_string(),
+        neo4j_max_retries: 3,
         qdrant_url: "http://localhost:6333".to_string(),
         anthropic_api_key: "key".to_string(),
+        anthropic_api_url: "https://api.anthropic.com/v1/messages".to_string(),
+        llm_max_retries: 3,
+        llm_log_enabled: false,
+        llm_log_redact: vec!["api_keys".to_string(), "emails".to_string()],
+        llm_log_redact_patterns: Vec::new(),
+        llm_log_retention_days: 30,
         server_host: "0.0.0.0".to_string(),
         server_port: 8080,
+        cors_allowed_origins: vec!["https://argus.example.org".to_string()],
+        cors_allowed_methods: vec!["GET".to_string()],
+        cors_allowed_headers: vec!["content-type".to_string()],
+        hsts_max_age_seconds: 0,
+        security_headers: false,
         sources: vec![],
+        sanctions_exposure_interval_seconds: 3600,
+        relationship_strength_interval_seconds: 3600,
+        completeness_interval_seconds: 3600,
+        inference_interval_seconds: 3600,
+        qdrant_collection: "argus_entities".to_string(),
+        embedding_api_key: String::new(),
+        embedding_model: "voyage-3".to_string(),
+        embedding_requests_per_minute: 60,
+        ontology: Default::default(),
+        extraction_cache: "none".to_string(),
+        extraction_cache_dir: "data/extraction_cache".to_string(),
+        extraction_cache_ttl_seconds: 604800,
+        extraction_worker_concurrency: 2,
+        geocoder: "none".to_string(),
+        geocoder_url: String::new(),
+        geocoder_requests_per_minute: 60,
+        redis_url: "redis://localhost:6379".to_string(),
+        state_backend: "memory".to_string(),
+        dedup_ttl_seconds: 3600,
+        graph_write_batch_size: 1000,
+        graph_write_flush_ms: 2000,
+        graphql_max_depth: 10,
+        graphql_max_complexity: 5000,
+        agent_run_lock_ttl_seconds: 7200,
+        idempotency_ttl_seconds: 86400,
+        agent_pause_file: String::new(),
+        validation_min_name_length: 2,
+        validation_blocklist: vec!["unknown".to_string()],
+        validation_min_confidence: 0.0,
+        relationship_identity: Default::default(),
+        reasoning_max_tokens: 0,
+        reasoning_max_llm_calls: 0,
+        reasoning_max_duration_ms: 0,
+        reasoning_persist_answers: false,
+        scheduler_start_jitter_seconds: 0,
+        agent_phase_offsets: Default::default(),
+        agent_intervals: Default::default(),
+        smtp_url: String::new(),
+        alert_email_from: "argus@localhost".to_string(),
+        slack_webhook_url: String::new(),
+        alert_digest_interval_seconds: 3600,
+        extraction_quotas: [("gdelt".to_string(), 2000)].into(),
+        extraction_pipelines: [("ais".to_string(), PipelineKind::Structured)].into(),
+        default_extraction_pipeline: PipelineKind::Hybrid,
+        source_freshness_max_lag: [("opensanctions".to_string(), 172_800)].into(),
+        quota_spillover: "queue".to_string(),
+        off_peak_hours: "1-6".to_string(),
+        colocation_interval_seconds: 900,
+        colocation_distance_meters: 500.0,
+        colocation_window_minutes: 30,
+        colocation_watch_locations: vec!["Port of Rotterdam".to_string()],
+        port_call_interval_seconds: 1800,
+        port_call_min_stop_minutes: 30,
+        flight_session_interval_seconds: 900,
+        flight_session_max_gap_minutes: 30,
+        voyage_interval_seconds: 3600,
+        voyage_gap_minutes: 180,
+        refdata_refresh_hours: 0,
+        refdata_ports_url: String::new(),
+        refdata_airports_url: String::new(),
+        refdata_countries_url: String::new(),
+        retention_policies: vec!["Position=90d".parse().unwrap()],
+        retention_interval_seconds: 86400,
+        retention_dry_run: true,
+        retention_archive_dir: "data/archive".to_string(),
+        integrity_check_interval_seconds: 86400,
+        integrity_auto_repair: false,
+        backup_dir: "data/backups".to_string(),
+        cold_storage_url: "s3://argus-cold/observations".to_string(),
+        cold_storage_flush_seconds: 300,
+        cold_storage_batch_rows: 50_000,
+        cold_storage_graph_position_days: 7,
+        analytics_sql_timeout_seconds: 30,
     };
 
     let json = serde_json::to_string(&config).expect("failed to serialize AppConfig");
@@ -811,3 +2169,51 @@ fn full_graph_stats_with_all_entity_types() {
     assert_eq!(deserialized.entity_types[0].count, 100);
     assert_eq!(deserialized.entity_types[8].count, 900);
 }
+
+// ---------------------------------------------------------------------------
+// Usage report
+// ---------------------------------------------------------------------------
+
+#[test]
+fn usage_params_default_to_a_week_within_retention() {
+    let params: UsageParams =
+        serde_json::from_str("{}").expect("failed to deserialize UsageParams");
+    assert_eq!(params.days(), 7);
+    assert_eq!(
+        UsageParams { days: Some(365) }.days(),
+        argus_core::usage::USAGE_RETENTION_DAYS
+    );
+    assert_eq!(UsageParams { days: Some(0) }.days(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// Integrity report
+// ---------------------------------------------------------------------------
+
+#[test]
+fn integrity_report_names_checks_and_omits_missing_errors() {
+    let mut failed = IntegrityFinding::new(IntegrityCheck::OrphanedPositions);
+    failed.error = Some("timed out".to_string());
+    let report = IntegrityReport {
+        repair: true,
+        checked_at: Utc::now(),
+        findings: vec![
+            IntegrityFinding {
+                violations: 2,
+                samples: vec!["OWNER_OF (no id)".to_string()],
+                repaired: 2,
+                ..IntegrityFinding::new(IntegrityCheck::DanglingRelationships)
+            },
+            failed,
+        ],
+    };
+
+    let json = serde_json::to_value(&report).expect("failed to serialize IntegrityReport");
+    assert_eq!(json["findings"][0]["check"], "dangling_relationships");
+    assert!(json["findings"][0].get("error").is_none());
+    assert_eq!(json["findings"][1]["error"], "timed out");
+
+    let back: IntegrityReport =
+        serde_json::from_value(json).expect("failed to deserialize IntegrityReport");
+    assert_eq!(back.findings[0].repaired, 2);
+}
@@ -0,0 +1,468 @@
+//! End-to-end pipeline tests: collect from stubbed upstream APIs, extract
+//! through a stubbed Anthropic API, validate, store in Neo4j and query
+//! the graph back. See `support` for where Neo4j comes from.
+
+mod support;
+
+use argus_agents::{GdeltAgent, GdeltMode, OpenSanctionsAgent, OpenSanctionsMode};
+use argus_core::agent::Agent;
+use argus_core::entity::{EntityType, RelationType};
+use argus_core::extraction::{ExtractionFailure, ExtractionPipeline};
+use argus_core::graph::GraphStore;
+use argus_core::shared::RateLimiter;
+use argus_extraction::{
+    LlmExtractionPipeline, LocationGeocoder, NominatimGeocoder, PhotonGeocoder, Validator,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use support::{
+    gdelt_event, neo4j, sanctioned_organization, stub_anthropic, stub_gdelt, stub_opensanctions,
+    test_config,
+};
+
+/// A suffix that keeps names from one run apart from earlier runs against
+/// a shared database.
+fn run_tag() -> String {
+    Uuid::new_v4().simple().to_string()[..8].to_string()
+}
+
+fn extraction(person: &str, company: &str) -> Value {
+    json!({
+        "entities": [
+            { "name": person, "type": "person", "confidence": 0.9 },
+            { "name": company, "type": "organization", "aliases": [], "properties": { "country": "CY" }, "confidence": 0.85 },
+            // Rejected by validation: too short to be a name
+            { "name": "X", "type": "person", "confidence": 0.9 },
+        ],
+        "relationships": [
+            { "source": person, "target": company, "type": "director_of", "evidence": "appointed director", "confidence": 0.8 },
+        ],
+    })
+}
+
+#[tokio::test]
+async fn gdelt_events_are_extracted_stored_and_queried() {
+    let upstream = MockServer::start().await;
+    let config = test_config(&upstream);
+    let tag = run_tag();
+    let (person, company) = (
+        format!("Anna Petrov {tag}"),
+        format!("Orion Shipping {tag}"),
+    );
+
+    stub_gdelt(
+        &upstream,
+        &[
+            gdelt_event("1100000001", &person, &company, "Limassol, Cyprus"),
+            gdelt_event("1100000002", &company, "", "Riga, Latvia"),
+        ],
+    )
+    .await;
+    stub_anthropic(&upstream, extraction(&person, &company)).await;
+
+    // Collect
+    let agent = GdeltAgent::with_mode(GdeltMode::Prose, 0).with_base_url(&upstream.uri());
+    let documents = agent.collect().await.expect("collection failed");
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0].source_id, "gdelt-event-1100000001");
+    assert!(documents[0].content.contains(&person));
+    assert!(agent.status().await.source_data_timestamp.is_some());
+
+    // Extract
+    let pipeline = LlmExtractionPipeline::new(&config);
+    let batch = pipeline.extract_batch_detailed(&documents).await;
+    assert!(
+        batch.failures.is_empty(),
+        "extraction failed: {:?}",
+        batch.failures
+    );
+    assert_eq!(batch.results.len(), 2);
+    let requests = upstream.received_requests().await.unwrap_or_default();
+    let prompts: Vec<&wiremock::Request> = requests
+        .iter()
+        .filter(|r| r.url.path() == support::ANTHROPIC_PATH)
+        .collect();
+    assert_eq!(prompts.len(), 2);
+    assert!(prompts
+        .iter()
+        .any(|r| String::from_utf8_lossy(&r.body).contains("Limassol, Cyprus")));
+
+    // Validate
+    let validator = Validator::from_config(&config);
+    let mut results = batch.results;
+    let report = results.iter_mut().fold(0, |rejected, result| {
+        rejected + validator.validate(result).entities_rejected
+    });
+    assert_eq!(report, 2);
+    assert!(results
+        .iter()
+        .all(|r| r.entities.len() == 2 && r.relationships.len() == 1));
+
+    // Store and query
+    let Some(graph) = neo4j(&config).await else {
+        return;
+    };
+    graph
+        .store
+        .store_extractions(&results)
+        .await
+        .expect("store failed");
+
+    let found = graph
+        .store
+        .search_entities(&person, 10)
+        .await
+        .expect("search failed");
+    let stored = found
+        .iter()
+        .find(|e| e.name == person)
+        .expect("person not stored");
+    assert_eq!(stored.entity_type, EntityType::Person);
+    assert_eq!(stored.source, "gdelt");
+
+    let neighbors = graph
+        .store
+        .get_neighbors(stored.id, 1)
+        .await
+        .expect("neighbors failed");
+    assert!(neighbors
+        .neighbors
+        .iter()
+        .any(|e| e.name == company && e.entity_type == EntityType::Organization));
+    assert!(neighbors
+        .relationships
+        .iter()
+        .any(|r| r.relation_type == RelationType::DirectorOf));
+}
+
+#[tokio::test]
+async fn opensanctions_entities_are_extracted_stored_and_queried() {
+    let upstream = MockServer::start().await;
+    let config = test_config(&upstream);
+    let tag = run_tag();
+    let company = format!("Baltic Holdings {tag}");
+    let lei = format!("LEI{}", tag.to_uppercase());
+
+    stub_opensanctions(
+        &upstream,
+        vec![sanctioned_organization(
+            &format!("NK-{tag}"),
+            &company,
+            &lei,
+        )],
+    )
+    .await;
+    stub_anthropic(
+        &upstream,
+        json!({
+            "entities": [{ "name": company, "type": "organization", "identifiers": { "lei": lei }, "confidence": 0.95 }],
+            "relationships": [],
+        }),
+    )
+    .await;
+
+    let agent = OpenSanctionsAgent::with_mode(OpenSanctionsMode::Api, String::new(), 0)
+        .with_api_url(&upstream.uri());
+    let documents = agent.collect().await.expect("collection failed");
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].title.as_deref(), Some(company.as_str()));
+    assert_eq!(documents[0].identifiers.get("lei"), Some(&lei));
+
+    let pipeline = LlmExtractionPipeline::new(&config);
+    let mut results = pipeline
+        .extract_batch(&documents)
+        .await
+        .expect("extraction failed");
+    for result in &mut results {
+        Validator::from_config(&config).validate(result);
+    }
+    assert_eq!(results[0].entities.len(), 1);
+
+    let Some(graph) = neo4j(&config).await else {
+        return;
+    };
+    graph
+        .store
+        .store_extractions(&results)
+        .await
+        .expect("store failed");
+
+    let by_lei = graph
+        .store
+        .find_by_identifier("lei", &lei)
+        .await
+        .expect("lookup failed");
+    assert_eq!(by_lei.len(), 1);
+    assert_eq!(by_lei[0].name, company);
+    let fetched = graph
+        .store
+        .get_entity(by_lei[0].id)
+        .await
+        .expect("get failed")
+        .expect("entity missing");
+    assert_eq!(fetched.source, "opensanctions");
+}
+
+#[tokio::test]
+async fn anthropic_errors_are_reported_per_document() {
+    let upstream = MockServer::start().await;
+    let config = test_config(&upstream);
+    stub_gdelt(
+        &upstream,
+        &[gdelt_event(
+            "1100000003",
+            "Nordic Trading",
+            "Atlas Capital",
+            "Oslo, Norway",
+        )],
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path(support::ANTHROPIC_PATH))
+        .respond_with(
+            ResponseTemplate::new(529)
+                .insert_header("retry-after", "0")
+                .set_body_json(json!({ "type": "error", "error": { "type": "overloaded_error" } })),
+        )
+        // The first attempt and every retry
+        .expect(u64::from(config.llm_max_retries) + 1)
+        .mount(&upstream)
+        .await;
+
+    let documents = GdeltAgent::with_mode(GdeltMode::Prose, 0)
+        .with_base_url(&upstream.uri())
+        .collect()
+        .await
+        .expect("collection failed");
+    let batch = LlmExtractionPipeline::new(&config)
+        .extract_batch_detailed(&documents)
+        .await;
+
+    assert!(batch.results.is_empty());
+    assert_eq!(batch.failures.len(), 1);
+    assert_eq!(
+        batch.failures[0].document.source_id,
+        "gdelt-event-1100000003"
+    );
+    assert!(
+        batch.failures[0].error.contains("529"),
+        "unexpected error: {}",
+        batch.failures[0].error
+    );
+}
+
+#[tokio::test]
+async fn documents_too_long_for_the_model_are_extracted_in_halves() {
+    let upstream = MockServer::start().await;
+    let config = test_config(&upstream);
+    stub_gdelt(
+        &upstream,
+        &[gdelt_event(
+            "1100000006",
+            "Nordic Trading",
+            "Atlas Capital",
+            "Oslo, Norway",
+        )],
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path(support::ANTHROPIC_PATH))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "type": "error",
+            "error": { "type": "invalid_request_error", "message": "prompt is too long: 212000 tokens > 200000 maximum" },
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&upstream)
+        .await;
+    stub_anthropic(
+        &upstream,
+        json!({
+            "entities": [{ "name": "Nordic Trading", "type": "organization", "confidence": 0.9 }],
+            "relationships": [],
+        }),
+    )
+    .await;
+
+    let documents = GdeltAgent::with_mode(GdeltMode::Prose, 0)
+        .with_base_url(&upstream.uri())
+        .collect()
+        .await
+        .expect("collection failed");
+    let batch = LlmExtractionPipeline::new(&config)
+        .extract_batch_detailed(&documents)
+        .await;
+
+    assert!(
+        batch.failures.is_empty(),
+        "unexpected failure: {}",
+        batch.failures[0].error
+    );
+    // Each half names the company; it is kept once
+    assert_eq!(batch.results[0].entities.len(), 1);
+    let requests = upstream.received_requests().await.unwrap();
+    assert_eq!(
+        requests
+            .iter()
+            .filter(|r| r.url.path() == support::ANTHROPIC_PATH)
+            .count(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn unparseable_model_output_is_kept_for_the_catalog() {
+    let upstream = MockServer::start().await;
+    let config = test_config(&upstream);
+    stub_gdelt(
+        &upstream,
+        &[gdelt_event(
+            "1100000005",
+            "Nordic Trading",
+            "Atlas Capital",
+            "Oslo, Norway",
+        )],
+    )
+    .await;
+    stub_anthropic(
+        &upstream,
+        json!("I could not find any entities in this text."),
+    )
+    .await;
+
+    let documents = GdeltAgent::with_mode(GdeltMode::Prose, 0)
+        .with_base_url(&upstream.uri())
+        .collect()
+        .await
+        .expect("collection failed");
+    let batch = LlmExtractionPipeline::new(&config)
+        .extract_batch_detailed(&documents)
+        .await;
+
+    assert_eq!(batch.failures.len(), 1);
+    let failed = &batch.failures[0];
+    assert!(
+        failed.error.contains("Failed to parse LLM JSON output"),
+        "unexpected error: {}",
+        failed.error
+    );
+    assert!(failed
+        .raw_output
+        .as_deref()
+        .unwrap()
+        .contains("could not find any entities"));
+    assert!(failed.model.is_some());
+
+    let cataloged =
+        ExtractionFailure::from_failed("run-1", "gdelt", failed).expect("not cataloged");
+    assert_eq!(cataloged.document.source_id, "gdelt-event-1100000005");
+    assert_eq!(cataloged.raw_output, failed.raw_output.clone().unwrap());
+}
+
+#[tokio::test]
+async fn a_missing_gdelt_manifest_fails_the_run() {
+    let upstream = MockServer::start().await;
+    let agent = GdeltAgent::with_mode(GdeltMode::Prose, 0).with_base_url(&upstream.uri());
+
+    assert!(agent.collect().await.is_err());
+    assert!(agent.status().await.error.is_some());
+}
+
+struct Unlimited;
+
+#[async_trait::async_trait]
+impl RateLimiter for Unlimited {
+    async fn acquire(&self, _key: &str, _per_minute: u32) -> argus_core::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn extracted_locations_are_geocoded() {
+    let upstream = MockServer::start().await;
+    let config = test_config(&upstream);
+    stub_gdelt(
+        &upstream,
+        &[gdelt_event(
+            "1100000004",
+            "Harbor Logistics",
+            "",
+            "Limassol, Cyprus",
+        )],
+    )
+    .await;
+    stub_anthropic(
+        &upstream,
+        json!({
+            "entities": [
+                { "name": "Harbor Logistics", "type": "organization", "confidence": 0.9 },
+                { "name": "Limassol", "type": "location", "confidence": 0.9 },
+            ],
+            "relationships": [{ "source": "Harbor Logistics", "target": "Limassol", "type": "based_in", "confidence": 0.8 }],
+        }),
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("q", "Limassol"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "lat": "34.6786322",
+            "lon": "33.0413055",
+            "display_name": "Limassol, Limassol District, Cyprus",
+            "address": { "city": "Limassol", "country": "Cyprus", "country_code": "cy" },
+        }])))
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [33.0413055, 34.6786322] },
+                "properties": { "name": "Limassol", "country": "Cyprus", "countrycode": "CY" },
+            }],
+        })))
+        .mount(&upstream)
+        .await;
+
+    let documents = GdeltAgent::with_mode(GdeltMode::Prose, 0)
+        .with_base_url(&upstream.uri())
+        .collect()
+        .await
+        .expect("collection failed");
+    let extracted = LlmExtractionPipeline::new(&config)
+        .extract_batch(&documents)
+        .await
+        .expect("extraction failed");
+
+    for geocoder in [
+        LocationGeocoder::new(std::sync::Arc::new(NominatimGeocoder::new(&upstream.uri()))),
+        LocationGeocoder::new(std::sync::Arc::new(PhotonGeocoder::new(&upstream.uri()))),
+    ] {
+        let mut result = extracted[0].clone();
+        assert_eq!(
+            geocoder
+                .geocode_locations(&mut result, &Unlimited, 60)
+                .await,
+            1
+        );
+        let limassol = result
+            .entities
+            .iter()
+            .find(|e| e.name == "Limassol")
+            .expect("location missing");
+        assert!((limassol.properties["latitude"].as_f64().unwrap() - 34.6786).abs() < 1e-3);
+        assert!((limassol.properties["longitude"].as_f64().unwrap() - 33.0413).abs() < 1e-3);
+        assert_eq!(limassol.properties["country"], "CY");
+        let company = result
+            .entities
+            .iter()
+            .find(|e| e.name == "Harbor Logistics")
+            .unwrap();
+        assert!(company.properties.get("latitude").is_none());
+    }
+}
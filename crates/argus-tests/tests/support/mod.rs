@@ -0,0 +1,243 @@
+//! Harness for the end-to-end tests: stubbed upstream APIs on a local
+//! wiremock server and a real Neo4j.
+//!
+//! Neo4j comes from `E2E_NEO4J_URI` (with `E2E_NEO4J_USER` and
+//! `E2E_NEO4J_PASSWORD`) when set, otherwise from a testcontainers-run
+//! `neo4j:5`. Without either the graph steps are skipped, so the tests
+//! still cover collection and extraction on machines without Docker.
+
+#![allow(dead_code)]
+
+use argus_core::config::AppConfig;
+use argus_core::graph::GraphStore;
+use argus_graph::Neo4jGraphStore;
+use serde_json::{json, Value};
+use testcontainers_modules::neo4j::{Neo4j, Neo4jImage};
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::ContainerAsync;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+pub const ANTHROPIC_PATH: &str = "/v1/messages";
+pub const TEST_API_KEY: &str = "test-key";
+
+/// Defaults only, with extraction pointed at `upstream` and caching off.
+pub fn test_config(upstream: &MockServer) -> AppConfig {
+    let mut config = AppConfig::from_lookup(|_| Err(std::env::VarError::NotPresent));
+    config.anthropic_api_key = TEST_API_KEY.to_string();
+    config.anthropic_api_url = format!("{}{ANTHROPIC_PATH}", upstream.uri());
+    config.extraction_cache = "none".to_string();
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Neo4j
+// ---------------------------------------------------------------------------
+
+pub struct TestGraph {
+    pub store: Neo4jGraphStore,
+    /// Dropping it stops the container.
+    _container: Option<ContainerAsync<Neo4jImage>>,
+}
+
+/// A connected graph store, or `None` (after saying why) when there is no
+/// Neo4j to test against.
+pub async fn neo4j(config: &AppConfig) -> Option<TestGraph> {
+    let mut config = config.clone();
+    let container = match std::env::var("E2E_NEO4J_URI") {
+        Ok(uri) => {
+            config.neo4j_uri = uri;
+            config.neo4j_user = std::env::var("E2E_NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+            config.neo4j_password =
+                std::env::var("E2E_NEO4J_PASSWORD").unwrap_or_else(|_| "password".into());
+            None
+        }
+        Err(_) => match start_container(&mut config).await {
+            Ok(container) => Some(container),
+            Err(e) => {
+                eprintln!("Skipping graph steps, no Neo4j container: {e}");
+                return None;
+            }
+        },
+    };
+
+    let store = Neo4jGraphStore::new(&config).await;
+    if let Err(e) = store.entity_count().await {
+        eprintln!(
+            "Skipping graph steps, Neo4j at {} unavailable: {e}",
+            config.neo4j_uri
+        );
+        return None;
+    }
+    Some(TestGraph {
+        store,
+        _container: container,
+    })
+}
+
+async fn start_container(
+    config: &mut AppConfig,
+) -> Result<ContainerAsync<Neo4jImage>, Box<dyn std::error::Error>> {
+    let container = Neo4j::default().start().await?;
+    let image = container.image();
+    config.neo4j_uri = format!(
+        "bolt://{}:{}",
+        container.get_host().await?,
+        image.bolt_port_ipv4()?
+    );
+    config.neo4j_user = image.user().unwrap_or("neo4j").to_string();
+    config.neo4j_password = image.password().unwrap_or_default().to_string();
+    Ok(container)
+}
+
+// ---------------------------------------------------------------------------
+// Anthropic
+// ---------------------------------------------------------------------------
+
+/// A Messages API response whose text is `extraction` as JSON.
+pub fn anthropic_response(extraction: &Value) -> Value {
+    json!({
+        "id": "msg_test",
+        "type": "message",
+        "role": "assistant",
+        "model": "claude-test",
+        "content": [{ "type": "text", "text": extraction.to_string() }],
+        "stop_reason": "end_turn",
+    })
+}
+
+/// Answer every authenticated extraction request with `extraction`.
+pub async fn stub_anthropic(server: &MockServer, extraction: Value) {
+    Mock::given(method("POST"))
+        .and(path(ANTHROPIC_PATH))
+        .and(header("x-api-key", TEST_API_KEY))
+        .and(header("anthropic-version", "2023-06-01"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(anthropic_response(&extraction)))
+        .mount(server)
+        .await;
+}
+
+// ---------------------------------------------------------------------------
+// GDELT
+// ---------------------------------------------------------------------------
+
+pub const GDELT_EXPORT_FILE: &str = "20240115120000.export.CSV.zip";
+
+/// Serve a last-update manifest pointing at an export of `rows`.
+pub async fn stub_gdelt(server: &MockServer, rows: &[Vec<String>]) {
+    let export_url = format!("{}/{GDELT_EXPORT_FILE}", server.uri());
+    let manifest = format!(
+        "1000 0123456789abcdef {export_url}\n\
+         1000 0123456789abcdef {}/20240115120000.mentions.CSV.zip\n\
+         1000 0123456789abcdef {}/20240115120000.gkg.csv.zip\n",
+        server.uri(),
+        server.uri()
+    );
+    let csv: String = rows
+        .iter()
+        .map(|row| format!("{}\n", row.join("\t")))
+        .collect();
+
+    Mock::given(method("GET"))
+        .and(path("/lastupdate.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(manifest))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/{GDELT_EXPORT_FILE}")))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(stored_zip("20240115120000.export.CSV", csv.as_bytes())),
+        )
+        .mount(server)
+        .await;
+}
+
+/// One row of a GDELT 2.0 events export, blank but for what the agent
+/// reads into prose.
+pub fn gdelt_event(id: &str, actor1: &str, actor2: &str, place: &str) -> Vec<String> {
+    let mut row = vec![String::new(); 58];
+    row[0] = id.to_string();
+    row[1] = "20240115".to_string();
+    row[6] = actor1.to_string();
+    row[16] = actor2.to_string();
+    row[26] = "036".to_string();
+    row[28] = "03".to_string();
+    row[29] = "1".to_string();
+    row[30] = "4.0".to_string();
+    row[31] = "10".to_string();
+    row[34] = "1.5".to_string();
+    row[50] = place.to_string();
+    row[57] = format!("https://news.example.com/{id}");
+    row
+}
+
+/// A ZIP archive holding `data` uncompressed as `name`.
+fn stored_zip(name: &str, data: &[u8]) -> Vec<u8> {
+    let name = name.as_bytes();
+    let entry_header = |zip: &mut Vec<u8>| {
+        zip.extend_from_slice(&[0x14, 0x00]); // version needed
+        zip.extend_from_slice(&[0x00, 0x00]); // flags
+        zip.extend_from_slice(&[0x00, 0x00]); // compression method: stored
+        zip.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // mod time and date
+        zip.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // crc32, not checked
+        zip.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        zip.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        zip.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        zip.extend_from_slice(&[0x00, 0x00]); // extra field length
+    };
+
+    let mut zip = vec![0x50, 0x4b, 0x03, 0x04];
+    entry_header(&mut zip);
+    zip.extend_from_slice(name);
+    zip.extend_from_slice(data);
+
+    let cd_offset = zip.len();
+    zip.extend_from_slice(&[0x50, 0x4b, 0x01, 0x02, 0x14, 0x00]); // signature, version made by
+    entry_header(&mut zip);
+    zip.extend_from_slice(&[0x00; 10]); // comment length, disk, attributes
+    zip.extend_from_slice(&0u32.to_le_bytes()); // local header offset
+    zip.extend_from_slice(name);
+    let cd_size = zip.len() - cd_offset;
+
+    zip.extend_from_slice(&[0x50, 0x4b, 0x05, 0x06, 0x00, 0x00, 0x00, 0x00]);
+    zip.extend_from_slice(&1u16.to_le_bytes());
+    zip.extend_from_slice(&1u16.to_le_bytes());
+    zip.extend_from_slice(&(cd_size as u32).to_le_bytes());
+    zip.extend_from_slice(&(cd_offset as u32).to_le_bytes());
+    zip.extend_from_slice(&[0x00, 0x00]); // comment length
+    zip
+}
+
+// ---------------------------------------------------------------------------
+// OpenSanctions
+// ---------------------------------------------------------------------------
+
+/// Serve one page of `entities` from the entities API.
+pub async fn stub_opensanctions(server: &MockServer, entities: Vec<Value>) {
+    let total = entities.len();
+    Mock::given(method("GET"))
+        .and(path("/entities"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "results": entities, "total": total })),
+        )
+        .mount(server)
+        .await;
+}
+
+/// A sanctioned organization as the entities API returns it.
+pub fn sanctioned_organization(id: &str, name: &str, lei: &str) -> Value {
+    json!({
+        "id": id,
+        "caption": name,
+        "schema": "Organization",
+        "properties": { "name": [name], "leiCode": [lei], "country": ["ru"] },
+        "datasets": ["us_ofac_sdn"],
+        "referents": [],
+        "first_seen": "2022-03-01T00:00:00",
+        "last_seen": "2024-01-15T00:00:00",
+        "last_change": "2024-01-10T00:00:00",
+        "target": true,
+    })
+}
@@ -0,0 +1,5 @@
+mod qdrant;
+mod voyage;
+
+pub use qdrant::QdrantVectorStore;
+pub use voyage::{embedding_text, VoyageEmbeddingProvider};
@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use serde_json::json;
+
+use argus_core::config::AppConfig;
+use argus_core::error::{ArgusError, Result};
+use argus_core::vector::{VectorPoint, VectorStore};
+
+/// Qdrant vector store accessed through its REST API.
+pub struct QdrantVectorStore {
+    client: reqwest::Client,
+    base_url: String,
+    collection: String,
+}
+
+impl QdrantVectorStore {
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: config.qdrant_url.trim_end_matches('/').to_string(),
+            collection: config.qdrant_collection.clone(),
+        }
+    }
+
+    fn collection_url(&self) -> String {
+        format!("{}/collections/{}", self.base_url, self.collection)
+    }
+
+    async fn check(response: reqwest::Response, action: &str) -> Result<()> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(ArgusError::Vector(format!(
+            "Failed to {action}: {status}: {body}"
+        )))
+    }
+}
+
+#[async_trait]
+impl VectorStore for QdrantVectorStore {
+    async fn ensure_collection(&self, dimensions: usize) -> Result<()> {
+        let existing = self.client.get(self.collection_url()).send().await?;
+        if existing.status().is_success() {
+            return Ok(());
+        }
+
+        let response = self
+            .client
+            .put(self.collection_url())
+            .json(&json!({ "vectors": { "size": dimensions, "distance": "Cosine" } }))
+            .send()
+            .await?;
+        Self::check(response, "create collection").await?;
+
+        tracing::info!(collection = %self.collection, dimensions, "Created Qdrant collection");
+        Ok(())
+    }
+
+    async fn upsert(&self, points: &[VectorPoint]) -> Result<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+
+        let body = json!({
+            "points": points
+                .iter()
+                .map(|p| json!({ "id": p.id.to_string(), "vector": p.vector, "payload": p.payload }))
+                .collect::<Vec<_>>(),
+        });
+
+        let response = self
+            .client
+            .put(format!("{}/points?wait=true", self.collection_url()))
+            .json(&body)
+            .send()
+            .await?;
+        Self::check(response, "upsert points").await
+    }
+}
@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use argus_core::config::AppConfig;
+use argus_core::entity::Entity;
+use argus_core::error::{ArgusError, Result};
+use argus_core::vector::EmbeddingProvider;
+
+const VOYAGE_API_URL: &str = "https://api.voyageai.com/v1/embeddings";
+
+/// Embedding provider backed by the Voyage AI embeddings API.
+pub struct VoyageEmbeddingProvider {
+    client: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+// ── Voyage embeddings API request/response types ───────────────────────────
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    input: &'a [String],
+    model: &'a str,
+    input_type: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    index: usize,
+}
+
+// ── Implementation ─────────────────────────────────────────────────────────
+
+impl VoyageEmbeddingProvider {
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: config.embedding_api_key.clone(),
+            model: config.embedding_model.clone(),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for VoyageEmbeddingProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        if !self.is_configured() {
+            return Err(ArgusError::Config("VOYAGE_API_KEY not set".into()));
+        }
+
+        let request = EmbeddingRequest {
+            input: texts,
+            model: &self.model,
+            input_type: "document",
+        };
+
+        let response = self
+            .client
+            .post(VOYAGE_API_URL)
+            .bearer_auth(&self.api_key)
+            .json(&request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ArgusError::Vector(format!(
+                "Embedding API returned {status}: {body}"
+            )));
+        }
+
+        let mut parsed: EmbeddingResponse = response.json().await?;
+        if parsed.data.len() != texts.len() {
+            return Err(ArgusError::Vector(format!(
+                "Embedding API returned {} vectors for {} inputs",
+                parsed.data.len(),
+                texts.len()
+            )));
+        }
+
+        parsed.data.sort_by_key(|d| d.index);
+        Ok(parsed.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// Text representation of an entity used as embedding input.
+pub fn embedding_text(entity: &Entity) -> String {
+    let entity_type = serde_json::to_value(&entity.entity_type)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default();
+
+    let mut text = format!("{entity_type}: {}", entity.name);
+    if !entity.aliases.is_empty() {
+        text.push_str(&format!("\nAliases: {}", entity.aliases.join(", ")));
+    }
+    if let Some(props) = entity.properties.as_object() {
+        for (key, value) in props {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            text.push_str(&format!("\n{key}: {value}"));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argus_core::entity::EntityType;
+
+    #[test]
+    fn embedding_text_includes_type_name_and_aliases() {
+        let mut entity = Entity::new(EntityType::Organization, "Acme Corp".into(), "test".into());
+        entity.aliases = vec!["ACME".into(), "Acme Ltd".into()];
+        entity.properties = serde_json::json!({ "country": "CY", "employees": 12 });
+
+        let text = embedding_text(&entity);
+        assert!(text.starts_with("organization: Acme Corp"));
+        assert!(text.contains("Aliases: ACME, Acme Ltd"));
+        assert!(text.contains("country: CY"));
+        assert!(text.contains("employees: 12"));
+    }
+
+    #[test]
+    fn embedding_text_without_extras_is_single_line() {
+        let entity = Entity::new(EntityType::Vessel, "Ever Given".into(), "test".into());
+        assert_eq!(embedding_text(&entity), "vessel: Ever Given");
+    }
+}
@@ -6,6 +6,7 @@ import type {
   EntityDetailResponse,
   EntityType,
   Relationship,
+  RelationshipEvidence,
 } from "@/types/argus";
 
 const ENTITY_TYPE_COLORS: Record<EntityType, string> = {
@@ -114,14 +115,24 @@ function resolveEntityName(
   return neighbor?.name ?? entityId.slice(0, 12) + "...";
 }
 
+function evidenceLocation(evidence: RelationshipEvidence): string | null {
+  const parts = [
+    evidence.page != null ? `p. ${evidence.page}` : null,
+    evidence.paragraph != null ? `¶ ${evidence.paragraph}` : null,
+  ].filter(Boolean);
+  return parts.length > 0 ? parts.join(", ") : null;
+}
+
 function RelationshipCard({
   rel,
   currentEntity,
   neighbors,
+  evidence,
 }: {
   rel: Relationship;
   currentEntity: Entity;
   neighbors: Entity[];
+  evidence?: RelationshipEvidence;
 }) {
   const isSource = rel.source_entity_id === currentEntity.id;
   const linkedEntityId = isSource
@@ -159,12 +170,23 @@ function RelationshipCard({
       {rel.timestamp && (
         <p className="text-xs text-neutral-500 mt-1">{formatDate(rel.timestamp)}</p>
       )}
+      {evidence && (
+        <blockquote className="mt-2 pl-3 border-l-2 border-white/[0.12] text-xs text-neutral-400 italic">
+          &ldquo;{evidence.quote}&rdquo;
+          {evidenceLocation(evidence) && (
+            <span className="not-italic text-neutral-600 ml-2">
+              {evidenceLocation(evidence)}
+            </span>
+          )}
+        </blockquote>
+      )}
     </Link>
   );
 }
 
 export default function EntityDetail({ data }: { data: EntityDetailResponse }) {
   const { entity, relationships, neighbors } = data;
+  const evidence = data.evidence ?? [];
   const properties = Object.entries(entity.properties);
 
   return (
@@ -286,6 +308,7 @@ export default function EntityDetail({ data }: { data: EntityDetailResponse }) {
                 rel={rel}
                 currentEntity={entity}
                 neighbors={neighbors}
+                evidence={evidence.find((e) => e.relationship_id === rel.id)}
               />
             ))}
           </div>
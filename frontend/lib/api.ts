@@ -1,16 +1,45 @@
 import type {
   AgentListResponse,
+  AgentPauseResponse,
+  AgentRunDetailResponse,
+  AgentRunsResponse,
   AgentTriggerRequest,
   AgentTriggerResponse,
+  Alert,
+  AlertAcknowledgeRequest,
+  AlertListParams,
+  AlertListResponse,
+  AlertRule,
+  AlertRuleListResponse,
+  AlertRuleRequest,
+  Briefing,
+  BriefingParams,
+  BudgetStatusResponse,
+  CalibrationParams,
+  CalibrationReport,
+  DocumentIngestRequest,
+  DocumentIngestResponse,
   EntityDetailResponse,
+  EntityIdentifierResponse,
   EntitySearchRequest,
   EntitySearchResponse,
+  FeedbackLabel,
+  FeedbackListParams,
+  FeedbackListResponse,
+  FeedbackRequest,
   GraphQueryRequest,
   GraphQueryResponse,
   GraphStatsResponse,
   HealthResponse,
+  PathQueryParams,
+  PathResponse,
+  RunComparisonResponse,
+  RunListParams,
+  Tombstone,
+  RunLogsResponse,
   ReasoningRequest,
   ReasoningResponse,
+  ReasoningTemplateListResponse,
   TimelineRequest,
   TimelineResponse,
 } from "@/types/argus";
@@ -46,6 +75,84 @@ export function triggerAgent(req: AgentTriggerRequest): Promise<AgentTriggerResp
   });
 }
 
+export function pauseAgent(name: string): Promise<AgentPauseResponse> {
+  return fetchApi(`/api/agents/${encodeURIComponent(name)}/pause`, { method: "POST" });
+}
+
+export function resumeAgent(name: string): Promise<AgentPauseResponse> {
+  return fetchApi(`/api/agents/${encodeURIComponent(name)}/resume`, { method: "POST" });
+}
+
+export function compareRuns(name: string, base: string, head: string): Promise<RunComparisonResponse> {
+  const query = new URLSearchParams({ base, head });
+  return fetchApi(`/api/agents/${encodeURIComponent(name)}/runs/compare?${query}`);
+}
+
+export function listRuns(params: RunListParams = {}): Promise<AgentRunsResponse> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined) query.set(key, String(value));
+  }
+  const qs = query.toString();
+  return fetchApi(`/api/agents/runs${qs ? `?${qs}` : ""}`);
+}
+
+export function getRun(runId: string): Promise<AgentRunDetailResponse> {
+  return fetchApi(`/api/agents/runs/${encodeURIComponent(runId)}`);
+}
+
+export function getRunLogs(runId: string, level?: "error" | "warn" | "info" | "debug" | "trace"): Promise<RunLogsResponse> {
+  const query = level ? `?${new URLSearchParams({ level })}` : "";
+  return fetchApi(`/api/agents/runs/${encodeURIComponent(runId)}/logs${query}`);
+}
+
+export function getBudget(): Promise<BudgetStatusResponse> {
+  return fetchApi("/api/agents/budget");
+}
+
+// Alerts
+export function listAlerts(params: AlertListParams = {}): Promise<AlertListResponse> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined) query.set(key, String(value));
+  }
+  const qs = query.toString();
+  return fetchApi(`/api/alerts${qs ? `?${qs}` : ""}`);
+}
+
+export function acknowledgeAlert(id: string, req: AlertAcknowledgeRequest = {}): Promise<Alert> {
+  return fetchApi(`/api/alerts/${id}/acknowledge`, {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
+export function listAlertRules(): Promise<AlertRuleListResponse> {
+  return fetchApi("/api/alerts/rules");
+}
+
+export function createAlertRule(req: AlertRuleRequest): Promise<AlertRule> {
+  return fetchApi("/api/alerts/rules", {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
+export async function deleteAlertRule(id: string): Promise<void> {
+  const res = await fetch(`${API_BASE}/api/alerts/rules/${id}`, { method: "DELETE" });
+  if (!res.ok) {
+    throw new Error(`API error ${res.status}: ${await res.text()}`);
+  }
+}
+
+// Documents
+export function ingestDocument(req: DocumentIngestRequest): Promise<DocumentIngestResponse> {
+  return fetchApi("/api/documents/ingest", {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
 // Entities
 export function searchEntities(req: EntitySearchRequest): Promise<EntitySearchResponse> {
   return fetchApi("/api/entities/search", {
@@ -54,8 +161,17 @@ export function searchEntities(req: EntitySearchRequest): Promise<EntitySearchRe
   });
 }
 
-export function getEntity(id: string): Promise<EntityDetailResponse> {
-  return fetchApi(`/api/entities/${id}`);
+export function getEntity(id: string, asOf?: string): Promise<EntityDetailResponse> {
+  const query = asOf ? `?as_of=${encodeURIComponent(asOf)}` : "";
+  return fetchApi(`/api/entities/${id}${query}`);
+}
+
+export function deleteEntity(id: string): Promise<Tombstone> {
+  return fetchApi(`/api/entities/${id}`, { method: "DELETE" });
+}
+
+export function getEntitiesByIdentifier(scheme: string, value: string): Promise<EntityIdentifierResponse> {
+  return fetchApi(`/api/entities/by-identifier/${encodeURIComponent(scheme)}/${encodeURIComponent(value)}`);
 }
 
 // Graph
@@ -70,8 +186,17 @@ export function getGraphStats(): Promise<GraphStatsResponse> {
   return fetchApi("/api/graph/stats");
 }
 
-export function getNeighbors(entityId: string): Promise<EntityDetailResponse> {
-  return fetchApi(`/api/graph/neighbors/${entityId}`);
+export function getNeighbors(entityId: string, asOf?: string): Promise<EntityDetailResponse> {
+  const query = asOf ? `?as_of=${encodeURIComponent(asOf)}` : "";
+  return fetchApi(`/api/graph/neighbors/${entityId}${query}`);
+}
+
+export function findPaths(params: PathQueryParams): Promise<PathResponse> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined) query.set(key, String(value));
+  }
+  return fetchApi(`/api/graph/paths?${query}`);
 }
 
 // Reasoning
@@ -82,6 +207,49 @@ export function queryReasoning(req: ReasoningRequest): Promise<ReasoningResponse
   });
 }
 
+export function listReasoningTemplates(): Promise<ReasoningTemplateListResponse> {
+  return fetchApi("/api/reasoning/templates");
+}
+
+// Briefing
+export function getBriefing(params: BriefingParams = {}): Promise<Briefing> {
+  const query = params.since ? `?${new URLSearchParams({ since: params.since })}` : "";
+  return fetchApi(`/api/briefing${query}`);
+}
+
+// Feedback
+export function submitFeedback(req: FeedbackRequest): Promise<FeedbackLabel> {
+  return fetchApi("/api/feedback", {
+    method: "POST",
+    body: JSON.stringify(req),
+  });
+}
+
+export function listFeedback(params: FeedbackListParams = {}): Promise<FeedbackListResponse> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined) query.set(key, String(value));
+  }
+  const qs = query.toString();
+  return fetchApi(`/api/feedback${qs ? `?${qs}` : ""}`);
+}
+
+export async function deleteFeedback(id: string): Promise<void> {
+  const res = await fetch(`${API_BASE}/api/feedback/${id}`, { method: "DELETE" });
+  if (!res.ok) {
+    throw new Error(`API error ${res.status}: ${await res.text()}`);
+  }
+}
+
+export function getCalibration(params: CalibrationParams = {}): Promise<CalibrationReport> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined) query.set(key, String(value));
+  }
+  const qs = query.toString();
+  return fetchApi(`/api/feedback/calibration${qs ? `?${qs}` : ""}`);
+}
+
 // Timeline
 export function getTimeline(req: TimelineRequest): Promise<TimelineResponse> {
   return fetchApi("/api/timeline", {
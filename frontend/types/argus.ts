@@ -1,4 +1,6 @@
-// ARGUS TypeScript API Types — mirrors Rust API types
+// ARGUS TypeScript API Types — mirrors Rust API types.
+// Types generated from argus-core (see README, "Frontend") are in ./generated;
+// prefer those over adding hand-written copies here.
 
 export type EntityType =
   | "person"
@@ -36,6 +38,19 @@ export interface Entity {
   confidence: number;
   first_seen: string;
   last_seen: string;
+  observed_at?: string;
+  sanctions_exposure?: number;
+  completeness?: number;
+  mention_count?: number;
+  /** GeoJSON geometry of reference Locations (countries, ports, airports). */
+  geometry?: { type: string; coordinates: unknown };
+  identifiers?: Record<string, string>;
+}
+
+export interface EntityIdentifierResponse {
+  scheme: string;
+  value: string;
+  entities: Entity[];
 }
 
 export interface Relationship {
@@ -60,6 +75,152 @@ export interface HealthResponse {
   relationship_count: number;
 }
 
+// --- Alerts ---
+
+export type AlertSeverity = "info" | "low" | "medium" | "high" | "critical";
+
+export type AlertStatus = "open" | "acknowledged" | "all";
+
+export type ChannelTarget =
+  | { type: "email"; to: string[] }
+  | { type: "slack"; webhook_url?: string };
+
+export type NotificationChannel = ChannelTarget & {
+  digest?: boolean;
+  template?: string;
+};
+
+export type DeliveryStatus = "pending" | "sent" | "failed";
+
+export interface Delivery {
+  channel: string;
+  status: DeliveryStatus;
+  attempts: number;
+  error?: string;
+  sent_at?: string;
+  attempted_at?: string;
+}
+
+export interface AlertEntity {
+  id: string;
+  name: string;
+  entity_type: EntityType;
+}
+
+export interface Alert {
+  id: string;
+  rule_id: string;
+  rule_name: string;
+  severity: AlertSeverity;
+  message: string;
+  entities: AlertEntity[];
+  relationship_id?: string;
+  source: string;
+  dedup_key: string;
+  created_at: string;
+  acknowledged_at?: string;
+  acknowledged_by?: string;
+  note?: string;
+  deliveries?: Delivery[];
+}
+
+export interface AlertListParams {
+  status?: AlertStatus;
+  min_severity?: AlertSeverity;
+  limit?: number;
+}
+
+export interface AlertListResponse {
+  alerts: Alert[];
+  total: number;
+}
+
+export interface AlertAcknowledgeRequest {
+  by?: string;
+  note?: string;
+}
+
+export interface AlertRule {
+  id: string;
+  name: string;
+  condition: string;
+  severity: AlertSeverity;
+  enabled: boolean;
+  created_at: string;
+  channels?: NotificationChannel[];
+}
+
+export interface AlertRuleRequest {
+  name: string;
+  condition: string;
+  severity?: AlertSeverity;
+  enabled?: boolean;
+  channels?: NotificationChannel[];
+}
+
+export interface AlertRuleListResponse {
+  rules: AlertRule[];
+}
+
+// --- Subscriptions ---
+
+export type SubscriptionEvent = "new_relationship" | "new_document" | "sanctions_change";
+
+export interface Subscription {
+  id: string;
+  entity_id: string;
+  entity_name: string;
+  entity_type: EntityType;
+  identifiers?: Record<string, string>;
+  events: SubscriptionEvent[];
+  subscriber?: string;
+  webhook_url?: string;
+  created_at: string;
+}
+
+export interface FollowRequest {
+  events?: SubscriptionEvent[];
+  subscriber?: string;
+  webhook_url?: string;
+}
+
+export interface SubscriptionListParams {
+  subscriber?: string;
+  entity_id?: string;
+}
+
+export interface SubscriptionListResponse {
+  subscriptions: Subscription[];
+}
+
+export interface Notification {
+  id: string;
+  subscription_id: string;
+  subscriber?: string;
+  entity_id: string;
+  entity_name: string;
+  event: SubscriptionEvent;
+  message: string;
+  source: string;
+  raw_source: string;
+  relationship_id?: string;
+  dedup_key: string;
+  created_at: string;
+  delivery?: Delivery;
+}
+
+export interface NotificationFeedParams {
+  subscriber?: string;
+  subscription_id?: string;
+  since?: string;
+  limit?: number;
+}
+
+export interface NotificationFeedResponse {
+  notifications: Notification[];
+  total: number;
+}
+
 // --- Agents ---
 
 export interface AgentStatus {
@@ -68,6 +229,27 @@ export interface AgentStatus {
   last_run: string | null;
   documents_collected: number;
   error: string | null;
+  /** Newest time the upstream put on collected data. */
+  source_data_timestamp?: string;
+  schema_drift?: SchemaDrift;
+  capabilities?: AgentCapabilities;
+}
+
+export interface AgentCapabilities {
+  source_type: string;
+  /** Entity types the agent can look up, for "enrich with" actions. */
+  lookup_entity_types: EntityType[];
+  /** Unset for agents that only run when triggered. */
+  schedule_interval_seconds?: number;
+  required_env: string[];
+  /** Required variables unset on the server; the agent is not scheduled. */
+  missing_env: string[];
+}
+
+export interface SchemaDrift {
+  unknown_fields: string[];
+  missing_fields: string[];
+  detected_at: string;
 }
 
 export interface AgentListResponse {
@@ -84,16 +266,54 @@ export interface AgentTriggerResponse {
   message: string;
 }
 
+export interface AgentPauseResponse {
+  agent_name: string;
+  enabled: boolean;
+}
+
+export type Spillover = "drop" | "queue";
+
+export interface SourceBudget {
+  source: string;
+  daily_limit: number | null;
+  used: number;
+  remaining: number | null;
+  dropped: number;
+  queued: number;
+  off_peak_processed: number;
+}
+
+export interface BudgetStatusResponse {
+  day: string;
+  spillover: Spillover;
+  off_peak_hours: string;
+  sources: SourceBudget[];
+}
+
 // --- Entities ---
 
 export interface EntitySearchRequest {
   query: string;
   limit?: number;
   entity_type?: EntityType;
+  min_sanctions_exposure?: number;
+  sort?: EntitySort;
+  target?: SearchTarget;
+}
+
+export type EntitySort = "relevance" | "name" | "last_seen" | "sanctions_exposure" | "completeness";
+
+export type SearchTarget = "entities" | "relationships" | "all";
+
+export interface RelationshipSearchHit {
+  relationship: Relationship;
+  source_name: string;
+  target_name: string;
 }
 
 export interface EntitySearchResponse {
   entities: Entity[];
+  relationships?: RelationshipSearchHit[];
   total: number;
 }
 
@@ -101,6 +321,279 @@ export interface EntityDetailResponse {
   entity: Entity;
   relationships: Relationship[];
   neighbors: Entity[];
+  incoming: Relationship[];
+  outgoing: Relationship[];
+  truncated?: boolean;
+  evidence: RelationshipEvidence[];
+}
+
+export interface RelationshipEvidence {
+  relationship_id: string;
+  relation_type: RelationType;
+  direction: "outgoing" | "incoming";
+  /** The entity at the other end. */
+  entity_id: string;
+  quote: string;
+  source: string;
+  confidence: number;
+  page?: number;
+  paragraph?: number;
+}
+
+export type Direction = "outgoing" | "incoming" | "both";
+
+export type ChangeKind =
+  | "created"
+  | "name_changed"
+  | "aliases_changed"
+  | "property_changed"
+  | "confidence_changed"
+  | "source_added"
+  | "merged"
+  | "deleted"
+  | "restored";
+
+export interface EntityChange {
+  id: string;
+  entity_id: string;
+  kind: ChangeKind;
+  field?: string;
+  old_value?: unknown;
+  new_value?: unknown;
+  source: string;
+  document_id?: string;
+  changed_at: string;
+}
+
+export interface Tombstone {
+  entity_id: string;
+  name: string;
+  entity_type: EntityType;
+  source: string;
+  source_id: string | null;
+  deleted_at: string;
+  /** Node deleted for good rather than hidden */
+  purged: boolean;
+}
+
+export interface EntityHistoryResponse {
+  entity_id: string;
+  changes: EntityChange[];
+}
+
+export interface AgentEnrichment {
+  agent: string;
+  documents: number;
+  entities: number;
+  relationships: number;
+  /** Documents whose extraction failed; queued for retry. */
+  failed: number;
+  /** Why the lookup itself failed. */
+  error?: string;
+}
+
+/** Response of POST /api/entities/{id}/enrich. */
+export interface EnrichResponse {
+  entity_id: string;
+  run_id: string;
+  agents: AgentEnrichment[];
+  documents: number;
+  entities: number;
+  relationships: number;
+}
+
+// --- Export ---
+
+export type ExportFormat = "stix" | "ftm";
+
+export interface InvestigationExportRequest {
+  entity_ids: string[];
+  format?: ExportFormat;
+  depth?: number;
+}
+
+export interface FtmImportResponse {
+  entities: number;
+  relationships: number;
+  skipped: string[];
+  invalid_lines: number[];
+}
+
+// --- Documents ---
+
+export interface IngestFile {
+  filename?: string;
+  content_type?: string;
+  /** Base64 of the file's bytes: PDF, HTML or text. */
+  data: string;
+}
+
+export interface DocumentIngestRequest {
+  title?: string;
+  content?: string;
+  file?: IngestFile;
+  url?: string;
+  source_id?: string;
+  observed_at?: string;
+  metadata?: Record<string, unknown>;
+}
+
+export interface DocumentIngestResponse {
+  run_id: string;
+  source: string;
+  source_id: string;
+  characters: number;
+  status: string;
+}
+
+export interface DocumentSearchRequest {
+  query: string;
+  source?: string;
+  start?: string;
+  end?: string;
+  limit?: number;
+}
+
+export interface DocumentSearchResult {
+  id: string;
+  source: string;
+  source_id: string;
+  title: string | null;
+  url: string | null;
+  collected_at: string;
+  score: number;
+  /** Excerpt with matches wrapped in <mark>; other text is HTML-escaped */
+  snippet: string;
+  entity_ids: string[];
+}
+
+export interface DocumentSearchResponse {
+  results: DocumentSearchResult[];
+  total: number;
+}
+
+export interface ExtractionResult {
+  entities: Entity[];
+  relationships: Relationship[];
+  raw_source: string;
+  extracted_at: string;
+}
+
+export interface ArchivedDocument {
+  id: string;
+  source: string;
+  source_id: string;
+  title: string | null;
+  content: string;
+  url: string | null;
+  collected_at: string;
+  run_id: string | null;
+  entity_ids: string[];
+  /** Raw extraction output, before entities were merged into the graph */
+  extraction?: ExtractionResult;
+  extraction_error?: string;
+}
+
+export interface RunDocumentsResponse {
+  run_id: string;
+  total: number;
+  documents: ArchivedDocument[];
+}
+
+export interface RunLogLine {
+  timestamp: string;
+  level: "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE";
+  target: string;
+  message: string;
+  /** Spans the event happened in, outermost first */
+  spans: string[];
+  fields?: Record<string, unknown>;
+}
+
+export interface RunLogsResponse {
+  run_id: string;
+  lines: RunLogLine[];
+  /** Earlier lines that were no longer kept */
+  dropped: number;
+}
+
+export interface AgentRunStatus {
+  run_id: string;
+  agent_name: string;
+  status: "running" | "completed" | "failed";
+  started_at: string;
+  finished_at: string | null;
+  documents_collected: number;
+  entities_extracted: number;
+  documents_failed: number;
+  documents_over_quota: number;
+  error: string | null;
+  schema_drift?: SchemaDrift;
+  /** Records written or restored so far, on backup and restore runs */
+  snapshot?: SnapshotCounts;
+}
+
+export interface SnapshotCounts {
+  nodes: number;
+  relationships: number;
+  alert_rules: number;
+  deny_entries: number;
+  paused_agents: number;
+}
+
+export interface RunListParams {
+  agent?: string;
+  status?: AgentRunStatus["status"];
+  /** Start-time bounds, RFC 3339 */
+  since?: string;
+  until?: string;
+  limit?: number;
+  cursor?: string;
+}
+
+export interface AgentRunsResponse {
+  runs: AgentRunStatus[];
+  /** Pass as `cursor` for the next page; absent on the last one */
+  next_cursor?: string;
+}
+
+export interface RunExtractionError {
+  source_id: string;
+  title: string | null;
+  error: string;
+  attempts: number;
+  last_failed_at: string;
+  next_attempt_at: string;
+  exhausted: boolean;
+}
+
+export interface AgentRunDetailResponse extends AgentRunStatus {
+  extraction_errors: RunExtractionError[];
+}
+
+export interface RunSnapshot {
+  run: AgentRunStatus;
+  documents: number;
+  extraction_errors: number;
+  entities_by_type: Record<string, number>;
+  new_entities: number;
+  repeated_entities: number;
+  error_rate: number;
+  entities_per_document: number;
+}
+
+export interface EntityTypeDelta {
+  entity_type: string;
+  base: number;
+  head: number;
+}
+
+export interface RunComparisonResponse {
+  agent_name: string;
+  base: RunSnapshot;
+  head: RunSnapshot;
+  entity_types: EntityTypeDelta[];
+  regressions: string[];
 }
 
 // --- Graph ---
@@ -125,12 +618,48 @@ export interface GraphStatsResponse {
   entity_types: EntityTypeStat[];
 }
 
+export interface GraphPath {
+  entities: Entity[];
+  relationships: Relationship[];
+}
+
+export interface PathQueryParams {
+  from: string;
+  to: string;
+  max_depth?: number;
+  limit?: number;
+  as_of?: string;
+}
+
+export interface PathResponse {
+  paths: GraphPath[];
+}
+
 // --- Reasoning ---
 
 export interface ReasoningRequest {
-  question: string;
+  question?: string;
   context?: string;
   max_hops?: number;
+  persist?: boolean;
+  template?: string;
+  params?: Record<string, string | number>;
+}
+
+export interface ReasoningTemplateParam {
+  name: string;
+  description: string;
+  required: boolean;
+}
+
+export interface ReasoningTemplate {
+  name: string;
+  description: string;
+  params: ReasoningTemplateParam[];
+}
+
+export interface ReasoningTemplateListResponse {
+  templates: ReasoningTemplate[];
 }
 
 export interface ReasoningStep {
@@ -139,12 +668,192 @@ export interface ReasoningStep {
   result_summary: string;
 }
 
+export interface StageTiming {
+  stage: string;
+  elapsed_ms: number;
+}
+
+export interface ReasoningUsage {
+  input_tokens: number;
+  output_tokens: number;
+  llm_calls: number;
+  total_ms: number;
+  stages: StageTiming[];
+  budget_exceeded?: string;
+}
+
 export interface ReasoningResponse {
   answer: string;
   confidence: number;
   steps: ReasoningStep[];
   entities_referenced: Entity[];
   sources: string[];
+  usage: ReasoningUsage;
+  document_id?: string;
+}
+
+// --- Briefing ---
+
+export interface BriefingParams {
+  /** e.g. "90m", "24h" or "7d"; defaults to 24 hours. */
+  since?: string;
+}
+
+export interface BriefingEntity {
+  id: string;
+  name: string;
+  entity_type: EntityType;
+  source: string;
+  link: string;
+}
+
+export interface BriefingRelationship {
+  id: string;
+  relation_type: RelationType;
+  source_entity_id: string;
+  source_name: string;
+  target_entity_id: string;
+  target_name: string;
+  source: string;
+  confidence: number;
+}
+
+export interface BriefingAlert {
+  id: string;
+  rule_name: string;
+  severity: AlertSeverity;
+  message: string;
+  created_at: string;
+  entities: BriefingEntity[];
+}
+
+export interface BriefingCounts {
+  new_entities: number;
+  new_relationships: number;
+  alerts: number;
+  truncated: boolean;
+}
+
+export interface Briefing {
+  since: string;
+  until: string;
+  summary: string;
+  confidence: number;
+  key_entities: BriefingEntity[];
+  new_entities: BriefingEntity[];
+  new_relationships: BriefingRelationship[];
+  alerts: BriefingAlert[];
+  counts: BriefingCounts;
+  usage: ReasoningUsage;
+}
+
+// --- Cold storage ---
+// Partition listings and SQL queries are generated (ColdStorageResponse,
+// ColdPartition, AnalyticsSqlRequest, AnalyticsSqlResponse).
+
+export interface PositionReport {
+  /** `scheme:value`, e.g. `mmsi:244660000`. */
+  asset: string;
+  entity_type: EntityType;
+  source: string;
+  latitude: number;
+  longitude: number;
+  observed_at: string;
+  speed_mps?: number;
+  course?: number;
+  altitude_m?: number;
+  on_ground?: boolean;
+  destination?: string;
+}
+
+export interface ArchivedPositionsParams {
+  asset: string;
+  since?: string;
+  until?: string;
+  limit?: number;
+}
+
+export interface ArchivedPositionsResponse {
+  asset: string;
+  since: string;
+  until: string;
+  positions: PositionReport[];
+}
+
+// --- Feedback ---
+
+export type FeedbackTarget = "entity" | "relationship" | "answer";
+
+export interface FeedbackRequest {
+  target: FeedbackTarget;
+  /** Required for entities and relationships; a persisted answer's document_id. */
+  target_id?: string;
+  correct: boolean;
+  /** The answer's confidence, for answers that were not persisted. */
+  confidence?: number;
+  note?: string;
+}
+
+export interface FeedbackLabel {
+  id: string;
+  target: FeedbackTarget;
+  target_id?: string;
+  correct: boolean;
+  confidence: number;
+  source: string;
+  kind: string;
+  note?: string;
+  /** Prompt bundle version the item was extracted with, if recorded. */
+  prompt_version?: number;
+  created_at: string;
+}
+
+export interface FeedbackListParams {
+  target?: FeedbackTarget;
+  limit?: number;
+}
+
+export interface FeedbackListResponse {
+  labels: FeedbackLabel[];
+  total: number;
+}
+
+export type CalibrationGrouping = "source" | "type" | "source_type";
+
+export interface CalibrationParams {
+  target?: FeedbackTarget;
+  by?: CalibrationGrouping;
+  bins?: number;
+  precision?: number;
+}
+
+export interface ReliabilityBin {
+  lower: number;
+  upper: number;
+  count: number;
+  mean_confidence: number | null;
+  accuracy: number | null;
+}
+
+export interface CalibrationGroup {
+  target: FeedbackTarget | null;
+  source: string | null;
+  kind: string | null;
+  labels: number;
+  correct: number;
+  accuracy: number;
+  mean_confidence: number;
+  expected_calibration_error: number;
+  brier_score: number;
+  suggested_threshold: number | null;
+  bins: ReliabilityBin[];
+}
+
+export interface CalibrationReport {
+  grouping: CalibrationGrouping;
+  target_precision: number;
+  overall: CalibrationGroup;
+  groups: CalibrationGroup[];
 }
 
 // --- Timeline ---
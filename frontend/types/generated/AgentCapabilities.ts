@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+
+/**
+ * What an agent offers beyond collecting, so clients know which agents
+ * can enrich which entities.
+ */
+export type AgentCapabilities = { source_type: string, 
+/**
+ * Built-in entity types the agent's [`AgentLookup`] accepts; empty for
+ * agents without one.
+ */
+lookup_entity_types: Array<EntityType>, 
+/**
+ * Seconds between scheduled runs; unset for agents that only run when
+ * triggered.
+ */
+schedule_interval_seconds?: number | null, 
+/**
+ * Environment variables the agent needs to collect.
+ */
+required_env: Array<string>, 
+/**
+ * Those of `required_env` unset on this instance; the scheduler skips
+ * the agent while any are.
+ */
+missing_env: Array<string>, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One agent's part of an enrichment.
+ */
+export type AgentEnrichment = { agent: string, 
+/**
+ * Documents the lookup returned.
+ */
+documents: number, 
+/**
+ * Entities and relationships stored from them, after validation.
+ */
+entities: number, relationships: number, 
+/**
+ * Documents whose extraction failed; they are queued for retry.
+ */
+failed: number, 
+/**
+ * Why the lookup itself failed.
+ */
+error?: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of pausing or resuming an agent.
+ */
+export type AgentPauseResponse = { agent_name: string, 
+/**
+ * Whether the scheduler will run the agent.
+ */
+enabled: boolean, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentRunState } from "./AgentRunState";
+import type { SchemaDrift } from "./SchemaDrift";
+import type { SnapshotCounts } from "./SnapshotCounts";
+import type { ValidationReport } from "./ValidationReport";
+
+export type AgentRunStatus = { run_id: string, agent_name: string, status: AgentRunState, started_at: string, finished_at: string | null, documents_collected: number, entities_extracted: number, 
+/**
+ * Documents whose extraction failed and were queued for retry.
+ */
+documents_failed: number, 
+/**
+ * Extracted items dropped by validation before storage.
+ */
+validation: ValidationReport, 
+/**
+ * Documents beyond the agent's daily extraction quota, dropped or
+ * queued for off-peak extraction.
+ */
+documents_over_quota: number, 
+/**
+ * Upstream schema changes the agent noticed while collecting.
+ */
+schema_drift?: SchemaDrift | null, 
+/**
+ * Records written so far by a workspace backup run, or restored by a
+ * restore run.
+ */
+snapshot?: SnapshotCounts | null, error: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentRunStatus } from "./AgentRunStatus";
+
+export type AgentRunsResponse = { runs: Array<AgentRunStatus>, 
+/**
+ * Pass as `cursor` for the next page; absent on the last one.
+ */
+next_cursor?: string | null, };
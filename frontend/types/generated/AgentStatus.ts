@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentCapabilities } from "./AgentCapabilities";
+import type { SchemaDrift } from "./SchemaDrift";
+
+export type AgentStatus = { name: string, enabled: boolean, last_run: string | null, documents_collected: number, error: string | null, 
+/**
+ * Newest time the upstream put on the data collected, such as a
+ * GDELT export's timestamp or an OpenSanctions `last_change`; how far
+ * it trails now is how stale the source is. Unset for agents whose
+ * source carries no such time, or before a run returned data.
+ */
+source_data_timestamp?: string | null, 
+/**
+ * Set when the latest run's responses no longer matched what the agent
+ * decodes.
+ */
+schema_drift?: SchemaDrift | null, 
+/**
+ * What the agent can do here. Filled in by `GET /api/agents`, not by
+ * the agent.
+ */
+capabilities?: AgentCapabilities | null, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertEntity } from "./AlertEntity";
+import type { AlertSeverity } from "./AlertSeverity";
+import type { Delivery } from "./Delivery";
+
+/**
+ * A rule match on a graph write, open until acknowledged.
+ */
+export type Alert = { id: string, rule_id: string, rule_name: string, severity: AlertSeverity, message: string, entities: Array<AlertEntity>, relationship_id?: string | null, 
+/**
+ * Source of the write that matched.
+ */
+source: string, 
+/**
+ * Same rule and subject; while one alert with a key is open, later
+ * matches for it are not raised again.
+ */
+dedup_key: string, created_at: string, acknowledged_at?: string | null, acknowledged_by?: string | null, note?: string | null, 
+/**
+ * One per channel of the rule at the time it was raised.
+ */
+deliveries?: Array<Delivery>, };
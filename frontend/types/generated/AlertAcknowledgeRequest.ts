@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Body of `POST /api/alerts/{id}/acknowledge`.
+ */
+export type AlertAcknowledgeRequest = { 
+/**
+ * Who acknowledged it, e.g. an analyst's name.
+ */
+by: string | null, note: string | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+
+/**
+ * An entity an alert is about.
+ */
+export type AlertEntity = { 
+/**
+ * As extracted; writes resolving onto an existing node keep that
+ * node's id instead.
+ */
+id: string, name: string, entity_type: EntityType, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertSeverity } from "./AlertSeverity";
+import type { AlertStatus } from "./AlertStatus";
+
+/**
+ * Query-string options for `GET /api/alerts`.
+ */
+export type AlertListParams = { status: AlertStatus, 
+/**
+ * Only alerts at least this severe.
+ */
+min_severity: AlertSeverity | null, limit: number | null, };
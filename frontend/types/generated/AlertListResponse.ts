@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Alert } from "./Alert";
+
+export type AlertListResponse = { 
+/**
+ * Newest first.
+ */
+alerts: Array<Alert>, total: number, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertSeverity } from "./AlertSeverity";
+import type { NotificationChannel } from "./NotificationChannel";
+
+/**
+ * A user-defined condition checked against every stored extraction.
+ */
+export type AlertRule = { id: string, name: string, 
+/**
+ * e.g. `entity.type == organization AND new_relationship.type == sanctioned_by`
+ * or `vessel enters bbox(-6.0, 35.5, -5.0, 36.5)`.
+ */
+condition: string, severity: AlertSeverity, enabled: boolean, created_at: string, 
+/**
+ * Where its alerts are sent; none keeps them in the API only.
+ */
+channels?: Array<NotificationChannel>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertSeverity } from "./AlertSeverity";
+import type { NotificationChannel } from "./NotificationChannel";
+
+/**
+ * Body of `POST /api/alerts/rules`.
+ */
+export type AlertRuleRequest = { name: string, condition: string, severity: AlertSeverity, enabled: boolean, channels: Array<NotificationChannel>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Body of `POST /api/analytics/sql`.
+ */
+export type AnalyticsSqlRequest = { 
+/**
+ * One read-only query over `positions`, `events` and `documents`.
+ */
+sql: string, 
+/**
+ * Rows returned; defaults to 1000.
+ */
+limit: number | null, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SqlColumn } from "./SqlColumn";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type AnalyticsSqlResponse = { columns: Array<SqlColumn>, 
+/**
+ * One object per row, keyed by column name.
+ */
+rows: Array<JsonValue>, 
+/**
+ * Whether the query returned more rows than `limit`.
+ */
+truncated: boolean, elapsed_ms: number, };
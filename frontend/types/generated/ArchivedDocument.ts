@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExtractionResult } from "./ExtractionResult";
+
+/**
+ * A collected document kept alongside the graph so it can be searched and
+ * traced back to the entities extracted from it.
+ */
+export type ArchivedDocument = { id: string, source: string, source_id: string, title: string | null, content: string, url: string | null, collected_at: string, run_id: string | null, entity_ids: Array<string>, 
+/**
+ * What extraction returned for this document, before entity merging.
+ * Only loaded when sampling a run's documents, not by search.
+ */
+extraction?: ExtractionResult | null, 
+/**
+ * Why extraction failed, if it did.
+ */
+extraction_error?: string | null, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BriefingAlert } from "./BriefingAlert";
+import type { BriefingCounts } from "./BriefingCounts";
+import type { BriefingEntity } from "./BriefingEntity";
+import type { BriefingRelationship } from "./BriefingRelationship";
+import type { ReasoningUsage } from "./ReasoningUsage";
+
+/**
+ * The answer to `GET /api/briefing`.
+ */
+export type Briefing = { since: string, until: string, 
+/**
+ * The engine's summary of the window; a fixed note when nothing changed.
+ */
+summary: string, confidence: number, 
+/**
+ * Entities the summary names.
+ */
+key_entities: Array<BriefingEntity>, new_entities: Array<BriefingEntity>, new_relationships: Array<BriefingRelationship>, alerts: Array<BriefingAlert>, counts: BriefingCounts, usage: ReasoningUsage, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertSeverity } from "./AlertSeverity";
+import type { BriefingEntity } from "./BriefingEntity";
+
+/**
+ * An alert raised in the window.
+ */
+export type BriefingAlert = { id: string, rule_name: string, severity: AlertSeverity, message: string, created_at: string, entities: Array<BriefingEntity>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How much changed in the window, including what was not listed.
+ */
+export type BriefingCounts = { new_entities: number, new_relationships: number, alerts: number, 
+/**
+ * Some items were left out of the lists.
+ */
+truncated: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+
+/**
+ * An entity a briefing links to.
+ */
+export type BriefingEntity = { id: string, name: string, entity_type: EntityType, source: string, 
+/**
+ * API path of the entity's detail.
+ */
+link: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query-string options for `GET /api/briefing`.
+ */
+export type BriefingParams = { 
+/**
+ * How far back to look, e.g. `90m`, `24h` or `7d`; defaults to 24 hours.
+ */
+since: string | null, };
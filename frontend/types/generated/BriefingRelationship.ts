@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RelationType } from "./RelationType";
+
+/**
+ * A new relationship, with the names of the entities it joins.
+ */
+export type BriefingRelationship = { id: string, relation_type: RelationType, source_entity_id: string, source_name: string, target_entity_id: string, target_name: string, source: string, confidence: number, };
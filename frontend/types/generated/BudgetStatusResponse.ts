@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SourceBudget } from "./SourceBudget";
+import type { Spillover } from "./Spillover";
+
+/**
+ * Response of `GET /api/agents/budget`.
+ */
+export type BudgetStatusResponse = { 
+/**
+ * UTC day the counts are for.
+ */
+day: string, spillover: Spillover, off_peak_hours: string, sources: Array<SourceBudget>, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackTarget } from "./FeedbackTarget";
+import type { ReliabilityBin } from "./ReliabilityBin";
+
+/**
+ * Calibration of one group of labels. `target`, `source` and `kind` are
+ * unset where the group spans several.
+ */
+export type CalibrationGroup = { target: FeedbackTarget | null, source: string | null, kind: string | null, labels: number, correct: number, accuracy: number, mean_confidence: number, 
+/**
+ * Label-weighted mean gap between each bin's confidence and accuracy.
+ */
+expected_calibration_error: number, 
+/**
+ * Mean squared gap between confidence and outcome (1 correct, 0 not).
+ */
+brier_score: number, 
+/**
+ * Lowest confidence at which items at or above it were correct at
+ * least the report's `target_precision` of the time, given at least
+ * [`MIN_LABELS_FOR_THRESHOLD`] labels; unset when none qualifies.
+ */
+suggested_threshold: number | null, bins: Array<ReliabilityBin>, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CalibrationGrouping } from "./CalibrationGrouping";
+import type { FeedbackTarget } from "./FeedbackTarget";
+
+export type CalibrationParams = { 
+/**
+ * Only labels on this kind of item.
+ */
+target: FeedbackTarget | null, by: CalibrationGrouping, bins: number | null, 
+/**
+ * Precision the suggested thresholds must reach, from 0 to 1.
+ */
+precision: number | null, };
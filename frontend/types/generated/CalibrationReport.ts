@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CalibrationGroup } from "./CalibrationGroup";
+import type { CalibrationGrouping } from "./CalibrationGrouping";
+
+/**
+ * The answer to `GET /api/feedback/calibration`.
+ */
+export type CalibrationReport = { grouping: CalibrationGrouping, target_precision: number, 
+/**
+ * Every label considered, in one group.
+ */
+overall: CalibrationGroup, 
+/**
+ * Most labelled first.
+ */
+groups: Array<CalibrationGroup>, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CaseEventKind } from "./CaseEventKind";
+
+/**
+ * One dated entry on a case timeline.
+ */
+export type CaseEvent = { at: string, kind: CaseEventKind, 
+/**
+ * The case's entities the entry concerns.
+ */
+entity_ids: Array<string>, summary: string, 
+/**
+ * Data source, or the note's author.
+ */
+source: string, 
+/**
+ * The relationship, alert or note id; the asset for positions.
+ */
+reference: string, };
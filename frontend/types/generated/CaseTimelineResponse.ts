@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CaseEvent } from "./CaseEvent";
+
+export type CaseTimelineResponse = { investigation_id: string, 
+/**
+ * Oldest first.
+ */
+events: Array<CaseEvent>, 
+/**
+ * Some entity neighborhoods or position histories were capped, so
+ * older entries may be missing.
+ */
+truncated: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What changed on an entity.
+ */
+export type ChangeKind = "created" | "name_changed" | "aliases_changed" | "property_changed" | "confidence_changed" | "source_added" | "merged" | "deleted" | "restored";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a notification goes.
+ */
+export type ChannelTarget = { "type": "email", to: Array<string>, } | { "type": "slack", webhook_url?: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ColdTable } from "./ColdTable";
+
+/**
+ * One day of one table: the files under `{table}/date=YYYY-MM-DD/`.
+ */
+export type ColdPartition = { table: ColdTable, date: string, files: number, bytes: number, };
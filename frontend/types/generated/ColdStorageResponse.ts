@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ColdPartition } from "./ColdPartition";
+
+/**
+ * `GET /api/analytics/cold-storage`.
+ */
+export type ColdStorageResponse = { 
+/**
+ * By table, then day.
+ */
+partitions: Array<ColdPartition>, 
+/**
+ * Rows waiting for the next flush.
+ */
+buffered: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One archived record type; each is a directory of partitions.
+ */
+export type ColdTable = "positions" | "events" | "documents";
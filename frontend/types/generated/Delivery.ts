@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeliveryStatus } from "./DeliveryStatus";
+
+/**
+ * Delivery of an alert to one of its rule's channels.
+ */
+export type Delivery = { 
+/**
+ * [`NotificationChannel::label`] of the channel.
+ */
+channel: string, status: DeliveryStatus, attempts: number, error?: string | null, sent_at?: string | null, 
+/**
+ * When the latest attempt started, so one still in flight is not
+ * retried alongside it.
+ */
+attempted_at?: string | null, };
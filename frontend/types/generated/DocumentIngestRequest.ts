@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IngestFile } from "./IngestFile";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Body of `POST /api/documents/ingest`: raw text, a file, or both (the
+ * file's text follows `content`).
+ */
+export type DocumentIngestRequest = { title: string | null, content: string | null, file: IngestFile | null, url: string | null, 
+/**
+ * Stable id for the document; re-ingesting under the same id updates
+ * what it produced. Derived from the text when absent.
+ */
+source_id: string | null, 
+/**
+ * When the document was written, if known.
+ */
+observed_at: string | null, 
+/**
+ * Free-form metadata kept with the archived document.
+ */
+metadata: JsonValue | null, };
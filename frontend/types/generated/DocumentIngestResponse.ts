@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DocumentIngestResponse = { run_id: string, source: string, source_id: string, 
+/**
+ * Characters of text handed to extraction.
+ */
+characters: number, status: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DocumentSearchResult = { id: string, source: string, source_id: string, title: string | null, url: string | null, collected_at: string, score: number, 
+/**
+ * Excerpt with matches wrapped in `<mark>` tags; other text is HTML-escaped.
+ */
+snippet: string, 
+/**
+ * Entities extracted from this document (hydrate via /api/entities/batch).
+ */
+entity_ids: Array<string>, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentEnrichment } from "./AgentEnrichment";
+
+/**
+ * What one on-demand enrichment added to the graph.
+ */
+export type EnrichResponse = { entity_id: string, 
+/**
+ * Run the looked-up documents were processed under; its documents and
+ * extractions are at `/api/agents/runs/{run_id}/documents`.
+ */
+run_id: string, 
+/**
+ * One per agent asked, in name order.
+ */
+agents: Array<AgentEnrichment>, documents: number, entities: number, relationships: number, };
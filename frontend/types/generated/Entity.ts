@@ -0,0 +1,46 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type Entity = { id: string, entity_type: EntityType, name: string, aliases: Array<string>, properties: JsonValue, source: string, source_id: string | null, confidence: number, 
+/**
+ * When Argus first collected the entity.
+ */
+first_seen: string, 
+/**
+ * When Argus last collected the entity.
+ */
+last_seen: string, 
+/**
+ * Earliest time the source data itself places the entity (event day,
+ * incorporation or listing date), as opposed to when it was collected.
+ */
+observed_at?: string | null, 
+/**
+ * Proximity to sanctioned entities in [0.0, 1.0], computed periodically
+ * for people and organizations. `None` until the first scoring pass.
+ */
+sanctions_exposure?: number | null, 
+/**
+ * How filled-in the profile is in [0.0, 1.0]: identifiers, links,
+ * a place on the map and recent observations. `None` until the first
+ * scoring pass.
+ */
+completeness?: number | null, 
+/**
+ * Distinct source records describing this entity, for events a source
+ * reports again under new ids (GDELT re-exports, source ids starting
+ * with [`EVENT_KEY_PREFIX`]). `None` for entities not counted this way.
+ */
+mention_count?: number | null, 
+/**
+ * GeoJSON geometry of a reference Location (country boundary, port
+ * outline, airport point). Stored apart from `properties`, which every
+ * source write replaces.
+ */
+geometry?: JsonValue | null, 
+/**
+ * Identifiers in external knowledge bases, keyed by scheme (see
+ * [`identifier_scheme`]), e.g. `{"lei": "5493001KJTIIGC8Y1R12"}`.
+ */
+identifiers?: { [key in string]?: string }, };
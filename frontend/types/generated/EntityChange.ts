@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeKind } from "./ChangeKind";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * One entry in an entity's append-only change log.
+ */
+export type EntityChange = { id: string, entity_id: string, kind: ChangeKind, 
+/**
+ * Property key for `property_changed`, otherwise the changed field.
+ */
+field: string | null, old_value: JsonValue | null, new_value: JsonValue | null, 
+/**
+ * Agent source whose extraction caused the change.
+ */
+source: string, 
+/**
+ * Source id of the document the extraction came from.
+ */
+document_id: string | null, changed_at: string, };
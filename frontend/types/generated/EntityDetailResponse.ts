@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+import type { Presentation } from "./Presentation";
+import type { Relationship } from "./Relationship";
+import type { RelationshipEvidence } from "./RelationshipEvidence";
+
+export type EntityDetailResponse = { entity: Entity, relationships: Array<Relationship>, neighbors: Array<Entity>, 
+/**
+ * Edges pointing at the entity (e.g. "owned by").
+ */
+incoming: Array<Relationship>, 
+/**
+ * Edges starting at the entity (e.g. "owns").
+ */
+outgoing: Array<Relationship>, 
+/**
+ * Set when the neighborhood was capped; see `GraphNeighbors::truncated`.
+ */
+truncated: boolean, 
+/**
+ * Supporting quotes for the entity's relationships, for checking what
+ * extraction concluded against the source text.
+ */
+evidence: Array<RelationshipEvidence>, 
+/**
+ * Per-entity category, color and icon, with `?include=presentation`.
+ */
+presentation?: { [key in string]?: Presentation }, };
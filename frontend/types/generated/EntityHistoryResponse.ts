@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityChange } from "./EntityChange";
+
+export type EntityHistoryResponse = { entity_id: string, 
+/**
+ * Newest first.
+ */
+changes: Array<EntityChange>, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+import type { Presentation } from "./Presentation";
+
+/**
+ * Entities found by an external identifier lookup.
+ */
+export type EntityIdentifierResponse = { 
+/**
+ * Canonical scheme the lookup used (e.g. `qid` is reported as `wikidata`).
+ */
+scheme: string, value: string, entities: Array<Entity>, 
+/**
+ * Per-entity category, color and icon, with `?include=presentation`.
+ */
+presentation?: { [key in string]?: Presentation }, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntitySort } from "./EntitySort";
+import type { EntityType } from "./EntityType";
+import type { SearchTarget } from "./SearchTarget";
+
+export type EntitySearchRequest = { query: string, limit: number, entity_type: EntityType | null, min_sanctions_exposure: number | null, sort: EntitySort, 
+/**
+ * What to search; `limit` applies to entities and relationships each.
+ */
+target: SearchTarget, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+import type { Presentation } from "./Presentation";
+import type { RelationshipSearchHit } from "./RelationshipSearchHit";
+
+export type EntitySearchResponse = { entities: Array<Entity>, 
+/**
+ * Present when the search targeted relationships.
+ */
+relationships?: Array<RelationshipSearchHit>, 
+/**
+ * Entities plus relationships returned.
+ */
+total: number, 
+/**
+ * Per-entity category, color and icon, with `?include=presentation`.
+ */
+presentation?: { [key in string]?: Presentation }, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Ordering applied to entity search results.
+ */
+export type EntitySort = "relevance" | "name" | "last_seen" | "sanctions_exposure" | "completeness";
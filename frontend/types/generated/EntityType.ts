@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Entity types. The built-in variants cover the core ontology; `Custom`
+ * carries deployment-specific types (see [`crate::ontology::Ontology`]).
+ *
+ * Serialized as a plain snake_case string, e.g. `"person"` or `"bank_account"`.
+ */
+export type EntityType = "Person" | "Organization" | "Vessel" | "Aircraft" | "Location" | "Event" | "Document" | "Transaction" | "Sanction" | { "Custom": string };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Entity counts for one type in both runs.
+ */
+export type EntityTypeDelta = { entity_type: string, base: number, head: number, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+import type { Relationship } from "./Relationship";
+
+export type ExtractionResult = { entities: Array<Entity>, relationships: Array<Relationship>, raw_source: string, extracted_at: string, };
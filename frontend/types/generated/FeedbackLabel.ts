@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackTarget } from "./FeedbackTarget";
+
+/**
+ * An analyst's verdict on one item, with the confidence the system gave it
+ * at the time.
+ */
+export type FeedbackLabel = { id: string, target: FeedbackTarget, 
+/**
+ * The entity, relationship or persisted answer judged; unset for
+ * answers that were not persisted.
+ */
+target_id?: string | null, correct: boolean, confidence: number, 
+/**
+ * Data source that produced the item; `reasoning` for answers.
+ */
+source: string, 
+/**
+ * Entity type or relationship type; `answer` for answers.
+ */
+kind: string, note?: string | null, 
+/**
+ * Extraction prompt bundle the item was extracted with, when one was
+ * deployed; see [`crate::prompts`].
+ */
+prompt_version?: number | null, created_at: string, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackTarget } from "./FeedbackTarget";
+
+export type FeedbackListParams = { target: FeedbackTarget | null, limit: number | null, };
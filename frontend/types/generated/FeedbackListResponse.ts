@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackLabel } from "./FeedbackLabel";
+
+export type FeedbackListResponse = { 
+/**
+ * Newest first.
+ */
+labels: Array<FeedbackLabel>, 
+/**
+ * Labels matching `target`, before `limit`.
+ */
+total: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackTarget } from "./FeedbackTarget";
+
+/**
+ * Body of `POST /api/feedback`. Entities and relationships are looked up
+ * by `target_id` for their confidence, source and type; answers take
+ * `target_id` when persisted (its `document_id`) and otherwise need the
+ * `confidence` the answer was given.
+ */
+export type FeedbackRequest = { target: FeedbackTarget, target_id: string | null, correct: boolean, confidence: number | null, note: string | null, };
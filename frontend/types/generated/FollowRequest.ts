@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SubscriptionEvent } from "./SubscriptionEvent";
+
+/**
+ * Body of `POST /api/entities/{id}/follow`.
+ */
+export type FollowRequest = { 
+/**
+ * What to be told about (default everything).
+ */
+events: Array<SubscriptionEvent>, subscriber: string | null, 
+/**
+ * An https URL each notification is POSTed to.
+ */
+webhook_url: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FtmImportResponse = { entities: number, relationships: number, 
+/**
+ * FtM ids of link entities missing an endpoint.
+ */
+skipped: Array<string>, 
+/**
+ * 1-based line numbers that were not valid FtM JSON.
+ */
+invalid_lines: Array<number>, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+import type { Relationship } from "./Relationship";
+
+/**
+ * One path between two entities, as alternating entities and edges.
+ */
+export type GraphPath = { 
+/**
+ * Entities along the path, starting at the source.
+ */
+entities: Array<Entity>, 
+/**
+ * Edges between consecutive entities; `relationships[i]` joins
+ * `entities[i]` and `entities[i + 1]` in either direction.
+ */
+relationships: Array<Relationship>, };
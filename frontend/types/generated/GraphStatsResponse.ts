@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityTypeStat } from "./EntityTypeStat";
+
+export type GraphStatsResponse = { entity_count: number, relationship_count: number, entity_types: Array<EntityTypeStat>, };
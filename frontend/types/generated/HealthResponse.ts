@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HealthResponse = { status: string, version: string, neo4j_connected: boolean, qdrant_connected: boolean, entity_count: number, relationship_count: number, };
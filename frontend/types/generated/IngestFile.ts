@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A file attached to an ingestion request, base64-encoded.
+ */
+export type IngestFile = { filename: string | null, 
+/**
+ * MIME type; guessed from `filename` when absent.
+ */
+content_type: string | null, 
+/**
+ * Standard base64 of the file's bytes.
+ */
+data: string, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InvestigationNote } from "./InvestigationNote";
+
+/**
+ * A case: the entities under investigation and what analysts wrote about
+ * them.
+ */
+export type Investigation = { id: string, name: string, entity_ids: Array<string>, 
+/**
+ * Oldest first.
+ */
+notes: Array<InvestigationNote>, created_at: string, };
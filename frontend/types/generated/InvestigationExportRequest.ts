@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportFormat } from "./ExportFormat";
+
+/**
+ * Export of an investigation: a set of seed entities and their surroundings.
+ */
+export type InvestigationExportRequest = { entity_ids: Array<string>, format: ExportFormat, 
+/**
+ * Neighbor depth to include around each seed (default 1).
+ */
+depth: number | null, 
+/**
+ * Leave out relationships scored weaker than this (0.0–1.0).
+ */
+min_strength: number | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An analyst's annotation on a case, optionally about one of its entities.
+ */
+export type InvestigationNote = { id: string, text: string, entity_id?: string | null, author?: string | null, 
+/**
+ * When what the note describes happened; when it was written unless
+ * the analyst said otherwise.
+ */
+at: string, created_at: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Body of `POST /api/investigations/{id}/notes`.
+ */
+export type InvestigationNoteRequest = { text: string, 
+/**
+ * One of the investigation's entities the note is about.
+ */
+entity_id: string | null, author: string | null, 
+/**
+ * When what the note describes happened (default now).
+ */
+at: string | null, };
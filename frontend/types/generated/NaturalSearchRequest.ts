@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A search in plain language, e.g. "Russian-flagged tankers seen near
+ * Ceuta in January".
+ */
+export type NaturalSearchRequest = { question: string, limit: number, };
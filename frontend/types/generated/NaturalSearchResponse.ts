@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+import type { ReasoningUsage } from "./ReasoningUsage";
+import type { SearchInterpretation } from "./SearchInterpretation";
+
+export type NaturalSearchResponse = { 
+/**
+ * How the question was read; this is the search that ran.
+ */
+interpretation: SearchInterpretation, entities: Array<Entity>, total: number, 
+/**
+ * Cost of translating the question.
+ */
+usage: ReasoningUsage, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A circle around a named place.
+ */
+export type NearFilter = { 
+/**
+ * The place as named in the question.
+ */
+place: string, latitude: number, longitude: number, radius_km: number, };
@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Delivery } from "./Delivery";
+import type { SubscriptionEvent } from "./SubscriptionEvent";
+
+/**
+ * Something that happened to a followed entity.
+ */
+export type Notification = { id: string, subscription_id: string, subscriber?: string | null, entity_id: string, entity_name: string, event: SubscriptionEvent, message: string, 
+/**
+ * Source of the write.
+ */
+source: string, 
+/**
+ * The document the write was extracted from.
+ */
+raw_source: string, relationship_id?: string | null, 
+/**
+ * Same subscription and subject; a subscription is told about each
+ * key once.
+ */
+dedup_key: string, created_at: string, 
+/**
+ * Delivery to the subscription's webhook, if it has one.
+ */
+delivery?: Delivery | null, };
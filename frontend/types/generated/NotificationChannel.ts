@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A delivery channel on a rule.
+ */
+export type NotificationChannel = { 
+/**
+ * Collect alerts and send one message per digest interval instead of
+ * one per alert.
+ */
+digest: boolean, 
+/**
+ * Message per alert, with `{rule}`, `{severity}`, `{message}`,
+ * `{entities}`, `{source}`, `{created_at}` and `{id}` filled in.
+ */
+template?: string | null, } & ({ "type": "email", to: Array<string>, } | { "type": "slack", webhook_url?: string | null, });
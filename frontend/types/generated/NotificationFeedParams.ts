@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query-string options for `GET /api/notifications`.
+ */
+export type NotificationFeedParams = { subscriber: string | null, subscription_id: string | null, 
+/**
+ * Only notifications created after this.
+ */
+since: string | null, limit: number | null, };
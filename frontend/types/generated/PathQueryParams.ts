@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query-string options for `GET /api/graph/paths`.
+ */
+export type PathQueryParams = { from: string, to: string, 
+/**
+ * Hops, 1–6 (default 4).
+ */
+max_depth: number | null, limit: number | null, 
+/**
+ * Only paths through entities and relationships that existed at this
+ * instant (RFC 3339).
+ */
+as_of: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Presentation metadata for one entity.
+ */
+export type Presentation = { 
+/**
+ * The entity type, or a narrower category such as `sanctioned_organization`.
+ */
+category: string, 
+/**
+ * CSS hex color.
+ */
+color: string, 
+/**
+ * Lucide icon name, e.g. `ship`.
+ */
+icon: string, };
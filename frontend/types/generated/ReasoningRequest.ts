@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type ReasoningRequest = { 
+/**
+ * Free-form question; may be left out when `template` is set.
+ */
+question: string, context: string | null, max_hops: number | null, 
+/**
+ * Record the answer in the graph; defaults to `REASONING_PERSIST_ANSWERS`.
+ */
+persist?: boolean | null, 
+/**
+ * Named question template, as listed by `GET /api/reasoning/templates`;
+ * replaces `question` and `max_hops`.
+ */
+template?: string | null, 
+/**
+ * The template's parameters.
+ */
+params?: { [key in string]?: JsonValue }, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+import type { ReasoningStep } from "./ReasoningStep";
+import type { ReasoningUsage } from "./ReasoningUsage";
+
+export type ReasoningResponse = { answer: string, confidence: number, steps: Array<ReasoningStep>, entities_referenced: Array<Entity>, sources: Array<string>, usage: ReasoningUsage, };
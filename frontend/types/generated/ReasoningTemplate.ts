@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReasoningTemplateParam } from "./ReasoningTemplateParam";
+
+/**
+ * A named question usable as `template` in a reasoning request.
+ */
+export type ReasoningTemplate = { name: string, description: string, params: Array<ReasoningTemplateParam>, };
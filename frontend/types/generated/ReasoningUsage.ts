@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StageTiming } from "./StageTiming";
+
+/**
+ * What one reasoning request cost.
+ */
+export type ReasoningUsage = { input_tokens: number, output_tokens: number, llm_calls: number, total_ms: number, stages: Array<StageTiming>, 
+/**
+ * Which limit ran out, when the request stopped early with a partial
+ * answer.
+ */
+budget_exceeded?: string | null, };
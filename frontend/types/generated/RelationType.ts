@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Relationship types. Like [`EntityType`], `Custom` carries
+ * deployment-specific types and everything serializes as a snake_case string.
+ */
+export type RelationType = "OwnerOf" | "DirectorOf" | "EmployeeOf" | "RelatedTo" | "LocatedAt" | "TransactedWith" | "SanctionedBy" | "RegisteredIn" | "FlaggedAs" | "MeetingWith" | "TraveledTo" | "PartOf" | { "Custom": string };
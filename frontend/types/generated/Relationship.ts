@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RelationType } from "./RelationType";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type Relationship = { id: string, source_entity_id: string, target_entity_id: string, relation_type: RelationType, properties: JsonValue, confidence: number, source: string, timestamp: string | null, 
+/**
+ * How strongly the endpoints are tied in [0.0, 1.0], from how often,
+ * how recently and by how many sources they were linked. Computed
+ * periodically; `None` until the first scoring pass.
+ */
+strength?: number | null, };
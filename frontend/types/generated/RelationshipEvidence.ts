@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Direction } from "./Direction";
+import type { RelationType } from "./RelationType";
+
+/**
+ * The source sentence a relationship was extracted from.
+ */
+export type RelationshipEvidence = { relationship_id: string, relation_type: RelationType, 
+/**
+ * As seen from the entity the detail is for.
+ */
+direction: Direction, 
+/**
+ * The entity at the other end.
+ */
+entity_id: string, quote: string, source: string, confidence: number, 
+/**
+ * Where the quote sits in an ingested file, when known (1-based).
+ */
+page?: number | null, paragraph?: number | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Relationship } from "./Relationship";
+
+/**
+ * A relationship whose evidence or properties matched a search, with the
+ * names of the entities it joins.
+ */
+export type RelationshipSearchHit = { relationship: Relationship, source_name: string, target_name: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One bar of a reliability diagram: labels whose confidence fell in
+ * `[lower, upper)` (the last bin includes 1.0).
+ */
+export type ReliabilityBin = { lower: number, upper: number, count: number, 
+/**
+ * Unset for empty bins.
+ */
+mean_confidence: number | null, 
+/**
+ * Share of the bin's labels that were correct; unset for empty bins.
+ */
+accuracy: number | null, };
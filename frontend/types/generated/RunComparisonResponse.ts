@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityTypeDelta } from "./EntityTypeDelta";
+import type { RunSnapshot } from "./RunSnapshot";
+
+/**
+ * Response of `GET /api/agents/{name}/runs/compare`.
+ */
+export type RunComparisonResponse = { agent_name: string, base: RunSnapshot, head: RunSnapshot, 
+/**
+ * Every entity type seen in either run.
+ */
+entity_types: Array<EntityTypeDelta>, 
+/**
+ * Drops large enough to suggest the source changed underneath the
+ * agent; empty when `head` looks like `base`.
+ */
+regressions: Array<string>, };
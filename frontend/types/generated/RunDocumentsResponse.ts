@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArchivedDocument } from "./ArchivedDocument";
+
+export type RunDocumentsResponse = { run_id: string, 
+/**
+ * Documents the run archived; `documents` is a random sample of them.
+ */
+total: number, documents: Array<ArchivedDocument>, };
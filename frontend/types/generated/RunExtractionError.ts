@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A document whose extraction failed in a run.
+ */
+export type RunExtractionError = { source_id: string, title: string | null, error: string, attempts: number, last_failed_at: string, 
+/**
+ * When a retry may run; `exhausted` documents are not retried.
+ */
+next_attempt_at: string, exhausted: boolean, };
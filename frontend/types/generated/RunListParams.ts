@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentRunState } from "./AgentRunState";
+
+/**
+ * Query-string options for `GET /api/agents/runs`.
+ */
+export type RunListParams = { 
+/**
+ * Only runs of this agent.
+ */
+agent: string | null, status: AgentRunState | null, 
+/**
+ * Only runs started at or after this time.
+ */
+since: string | null, 
+/**
+ * Only runs started before this time.
+ */
+until: string | null, limit: number | null, 
+/**
+ * `next_cursor` of the previous page.
+ */
+cursor: string | null, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * One log event recorded while a run was in progress.
+ */
+export type RunLogLine = { timestamp: string, 
+/**
+ * `ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`.
+ */
+level: string, target: string, message: string, 
+/**
+ * Spans the event happened in, outermost first, e.g. `["agent_run", "extract"]`.
+ */
+spans: Array<string>, 
+/**
+ * The event's structured fields other than the message.
+ */
+fields?: { [key in string]?: JsonValue }, };
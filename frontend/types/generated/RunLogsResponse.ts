@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RunLogLine } from "./RunLogLine";
+
+export type RunLogsResponse = { run_id: string, 
+/**
+ * Oldest first.
+ */
+lines: Array<RunLogLine>, 
+/**
+ * Earlier lines of the run that were no longer kept.
+ */
+dropped: number, };
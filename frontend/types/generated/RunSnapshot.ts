@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentRunStatus } from "./AgentRunStatus";
+
+/**
+ * One side of a run comparison.
+ */
+export type RunSnapshot = { run: AgentRunStatus, 
+/**
+ * Percentage of collected documents whose extraction failed.
+ */
+error_rate: number, 
+/**
+ * Distinct entities per archived document.
+ */
+entities_per_document: number, documents: number, 
+/**
+ * Documents archived with an extraction error.
+ */
+extraction_errors: number, 
+/**
+ * Distinct stored entities the documents resolved to, by type.
+ */
+entities_by_type: Record<string, number>, 
+/**
+ * Of those, entities first seen since the run started.
+ */
+new_entities: number, 
+/**
+ * Of those, entities already in the graph before the run.
+ */
+repeated_entities: number, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How an upstream API's responses differed from what an agent decodes in
+ * one run. Paths are dotted, with `*` for array positions and map keys
+ * that are numbers.
+ */
+export type SchemaDrift = { 
+/**
+ * Fields the source sent that the agent ignores.
+ */
+unknown_fields: Array<string>, 
+/**
+ * Fields earlier runs received that no response carried this run.
+ */
+missing_fields: Array<string>, detected_at: string, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+import type { NearFilter } from "./NearFilter";
+
+/**
+ * A structured search read from a question.
+ */
+export type SearchInterpretation = { 
+/**
+ * Text the entity name must contain; empty matches any name.
+ */
+query: string, entity_type: EntityType | null, 
+/**
+ * Property values the entity must carry, e.g. `{"vessel_type": "tanker"}`,
+ * matched case-insensitively as substrings.
+ */
+properties: { [key in string]?: string }, min_sanctions_exposure: number | null, 
+/**
+ * Only assets with a position report around this place.
+ */
+near: NearFilter | null, 
+/**
+ * Start of the time window, inclusive.
+ */
+from: string | null, 
+/**
+ * End of the time window, inclusive.
+ */
+to: string | null, };
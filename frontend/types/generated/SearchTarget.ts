@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What an entity search looks through. Entity filters and sorting only
+ * apply to entities.
+ */
+export type SearchTarget = "entities" | "relationships" | "all";
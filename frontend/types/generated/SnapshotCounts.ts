@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Records in a snapshot, for checking it is complete.
+ */
+export type SnapshotCounts = { nodes: number, relationships: number, alert_rules: number, deny_entries: number, paused_agents: number, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Today's extraction consumption for one source.
+ */
+export type SourceBudget = { source: string, 
+/**
+ * `None` when the source has no quota.
+ */
+daily_limit: number | null, 
+/**
+ * Documents sent for extraction today within the quota.
+ */
+used: number, remaining: number | null, 
+/**
+ * Documents over quota dropped today.
+ */
+dropped: number, 
+/**
+ * Documents waiting for off-peak extraction.
+ */
+queued: number, 
+/**
+ * Queued documents extracted off-peak today, outside the quota.
+ */
+off_peak_processed: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What happens to documents beyond a source's daily quota.
+ */
+export type Spillover = "drop" | "queue";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A column of an analytical query's result.
+ */
+export type SqlColumn = { name: string, 
+/**
+ * Arrow type name, e.g. `Utf8`, `Float64` or `Timestamp(ms, "UTC")`.
+ */
+data_type: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Wall-clock time spent in one stage of a reasoning request.
+ */
+export type StageTiming = { stage: string, elapsed_ms: number, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+import type { SubscriptionEvent } from "./SubscriptionEvent";
+
+/**
+ * Someone following an entity.
+ */
+export type Subscription = { id: string, entity_id: string, entity_name: string, entity_type: EntityType, 
+/**
+ * The entity's identifiers when it was followed. Extracted entities
+ * only get the stored node's id once written, so writes are matched
+ * on these and the name as well as the id.
+ */
+identifiers?: { [key in string]?: string }, events: Array<SubscriptionEvent>, 
+/**
+ * Who follows it, for filtering the feed, e.g. an analyst's name.
+ */
+subscriber?: string | null, 
+/**
+ * Each notification is POSTed here as JSON.
+ */
+webhook_url?: string | null, created_at: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a subscription is told about.
+ */
+export type SubscriptionEvent = "new_relationship" | "new_document" | "sanctions_change";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query-string options for `GET /api/subscriptions`.
+ */
+export type SubscriptionListParams = { subscriber: string | null, entity_id: string | null, };
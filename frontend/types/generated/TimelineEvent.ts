@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Entity } from "./Entity";
+
+export type TimelineEvent = { timestamp: string, entity: Entity, event_type: string, description: string, source: string, };
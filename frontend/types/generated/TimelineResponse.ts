@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimelineEvent } from "./TimelineEvent";
+
+export type TimelineResponse = { events: Array<TimelineEvent>, };
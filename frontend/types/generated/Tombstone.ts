@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityType } from "./EntityType";
+
+/**
+ * Left behind by a deleted entity. Agents reporting the same id, or the
+ * same source record, are not stored again while it exists.
+ */
+export type Tombstone = { entity_id: string, name: string, entity_type: EntityType, source: string, source_id: string | null, deleted_at: string, 
+/**
+ * The node is gone for good, rather than hidden.
+ */
+purged: boolean, };
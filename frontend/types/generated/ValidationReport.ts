@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Items dropped by post-extraction validation, counted per rule.
+ */
+export type ValidationReport = { entities_rejected: number, relationships_rejected: number, 
+/**
+ * Rejections keyed by rule name, entities and relationships together.
+ */
+by_rule: Record<string, number>, };